@@ -0,0 +1,70 @@
+//! Proof envelope sealing and verification throughput
+
+use chrono::Duration;
+use compliance_backend::compliance::claims::{Claim, ClaimKind, ClaimSet, JurisdictionClass, PepStatus};
+use compliance_backend::compliance::proof_envelope::{EnvelopeParams, ProofCompression, ProofEnvelope};
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::simulation;
+use compliance_backend::types::ComplianceAttestation;
+use compliance_backend::verifier::{verify_proof_envelope, VerificationPolicy};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+const PROOF_SIZE: usize = 64 * 1024;
+
+fn envelopes(c: &mut Criterion) {
+    let signer = AttestationSigner::generate("bench");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert(signer.key_id(), signer.verifying_key());
+    let attestations: Vec<_> = simulation::identities(100, &[], 0.0, 4)
+        .iter()
+        .map(simulation::attestation)
+        .collect();
+    
+    let seal = |attestation: &ComplianceAttestation| {
+        let claims = ClaimSet::new(vec![
+            Claim::KycTier(None),
+            Claim::RiskBand(attestation.aml_risk_level.clone()),
+            Claim::PepStatus(PepStatus::Clear),
+            Claim::JurisdictionClass(JurisdictionClass::Standard),
+            Claim::AttestationAge(0),
+        ])
+        .expect("claim set builds");
+        ProofEnvelope::seal(
+            EnvelopeParams {
+                attestation,
+                claims: &claims,
+                disclose: &[ClaimKind::KycTier, ClaimKind::RiskBand],
+                audience: "bench.example",
+                nonce: "00",
+                scope: None,
+                proof: vec![0u8; PROOF_SIZE],
+                compression: ProofCompression::None,
+                validity: Duration::hours(1),
+            },
+            &signer,
+        )
+        .and_then(|envelope| envelope.encode())
+        .expect("envelope seals")
+    };
+    
+    let mut group = c.benchmark_group("attestation");
+    group.throughput(Throughput::Elements(attestations.len() as u64));
+    group.bench_function("seal_and_encode", |b| {
+        b.iter(|| attestations.iter().map(&seal).count())
+    });
+    
+    let encoded: Vec<_> = attestations.iter().map(&seal).collect();
+    let policy = VerificationPolicy::new("bench.example");
+    group.bench_function("verify", |b| {
+        b.iter(|| {
+            encoded
+                .iter()
+                .filter(|envelope| verify_proof_envelope(envelope, &trusted, &policy).is_ok())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, envelopes);
+criterion_main!(benches);
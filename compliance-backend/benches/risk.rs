@@ -0,0 +1,28 @@
+//! Geographic risk scoring throughput
+
+use compliance_backend::compliance::country_risk::CountryRiskService;
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::simulation;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::sync::Arc;
+
+fn country_risk(c: &mut Criterion) {
+    let mut config = ComplianceConfig::default();
+    config.aml.country_risk.enabled = true;
+    let service = CountryRiskService::new(Arc::new(LiveConfig::new(config))).expect("built-in dataset");
+    let profiles: Vec<_> = simulation::identities(10_000, &[], 0.0, 3)
+        .iter()
+        .map(|identity| identity.geographic_profile())
+        .collect();
+    
+    let mut group = c.benchmark_group("risk");
+    group.throughput(Throughput::Elements(profiles.len() as u64));
+    group.bench_function("country_risk_assess", |b| {
+        b.iter(|| profiles.iter().filter_map(|profile| service.assess(profile)).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, country_risk);
+criterion_main!(benches);
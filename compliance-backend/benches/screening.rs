@@ -0,0 +1,65 @@
+//! Name matcher and list search throughput across list sizes
+
+use compliance_backend::compliance::screening::matcher::NameMatcher;
+use compliance_backend::compliance::screening::search::{self, SearchRequest};
+use compliance_backend::compliance::screening::ScreeningListStore;
+use compliance_backend::simulation;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const LIST_SIZES: &[usize] = &[1_000, 10_000, 100_000];
+
+fn matcher(c: &mut Criterion) {
+    let list = simulation::sanctions_list("sim", 1_000, 1);
+    let identities = simulation::identities(1_000, &list, 0.1, 2);
+    let matcher = NameMatcher::new(0.85);
+    
+    let mut group = c.benchmark_group("matcher");
+    group.throughput(Throughput::Elements(identities.len() as u64));
+    group.bench_function("score_pairs", |b| {
+        b.iter(|| {
+            identities
+                .iter()
+                .zip(&list)
+                .filter(|(identity, entity)| matcher.matches(&identity.name, &entity.name).is_some())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn search(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("tokio runtime");
+    let matcher = NameMatcher::new(0.85);
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+    
+    for &size in LIST_SIZES {
+        let list = simulation::sanctions_list("sim", size, 1);
+        let identities = simulation::identities(20, &list, 0.5, 2);
+        let store = ScreeningListStore::new();
+        runtime.block_on(store.ingest("sim", "v1", list)).expect("list ingests");
+        
+        group.throughput(Throughput::Elements(identities.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &identities, |b, identities| {
+            b.to_async(&runtime).iter(|| async {
+                for identity in identities {
+                    let request = SearchRequest {
+                        name: identity.name.clone(),
+                        language: None,
+                        attributes: Default::default(),
+                        lists: None,
+                        entity_type: None,
+                        min_score: None,
+                        limit: Some(10),
+                        client_id: None,
+                    };
+                    search::search(&store, &matcher, &request, vec![]).await;
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, matcher, search);
+criterion_main!(benches);
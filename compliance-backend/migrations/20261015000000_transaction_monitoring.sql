@@ -0,0 +1,23 @@
+-- Executed transactions reported by business clients for monitoring
+CREATE TABLE IF NOT EXISTS monitored_transactions (
+    client_id UUID NOT NULL,
+    external_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    asset TEXT,
+    counterparty TEXT,
+    transaction_type TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (client_id, external_id)
+);
+
+CREATE INDEX IF NOT EXISTS monitored_transactions_account_received
+    ON monitored_transactions (account_id, received_at);
+
+-- Per-account aggregates, checkpointed from memory
+CREATE TABLE IF NOT EXISTS monitoring_account_aggregates (
+    account_id TEXT PRIMARY KEY,
+    aggregate JSONB NOT NULL,
+    checkpointed_at TIMESTAMPTZ NOT NULL
+);
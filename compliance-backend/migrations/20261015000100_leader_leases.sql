@@ -0,0 +1,6 @@
+-- Leases electing the one instance that runs each job or worker
+CREATE TABLE IF NOT EXISTS leader_leases (
+    lock_name TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
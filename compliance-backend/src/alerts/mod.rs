@@ -0,0 +1,251 @@
+//! Internal alerting with severity routing and deduplication
+
+pub mod sinks;
+
+use crate::config::AlertingConfig;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sinks::AlertSink;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Alert severity, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Kind of condition that raised an alert
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    SanctionsHit,
+    CriticalRisk,
+    OutcomeAnomaly,
+    DuplicateIdentity,
+    Custom(String),
+}
+
+/// An internal alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub id: Uuid,
+    pub kind: AlertKind,
+    pub severity: AlertSeverity,
+    /// Entity the alert is about (account id, endpoint id, ...), used for deduplication
+    pub subject: String,
+    pub message: String,
+    pub details: serde_json::Value,
+    pub raised_at: DateTime<Utc>,
+    /// Last time a duplicate of this alert was raised
+    pub last_seen_at: DateTime<Utc>,
+    /// Number of duplicates suppressed within the dedup window
+    pub suppressed_count: u32,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+impl Alert {
+    /// Create a new alert
+    pub fn new(
+        kind: AlertKind,
+        severity: AlertSeverity,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            severity,
+            subject: subject.into(),
+            message: message.into(),
+            details: serde_json::Value::Null,
+            raised_at: now,
+            last_seen_at: now,
+            suppressed_count: 0,
+            acknowledged_by: None,
+            acknowledged_at: None,
+        }
+    }
+    
+    /// Attach structured details
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
+    
+    /// Sanctions screening produced a hit for an account
+    pub fn sanctions_hit(account_id: &str) -> Self {
+        Self::new(
+            AlertKind::SanctionsHit,
+            AlertSeverity::Critical,
+            account_id,
+            format!("Sanctions screening hit for account {}", account_id),
+        )
+    }
+    
+    /// An account was assessed at critical AML risk
+    pub fn critical_risk(account_id: &str) -> Self {
+        Self::new(
+            AlertKind::CriticalRisk,
+            AlertSeverity::Critical,
+            account_id,
+            format!("Account {} assessed at critical AML risk", account_id),
+        )
+    }
+    
+    /// A client's screening or verification outcomes deviated from their baseline
+    pub fn outcome_anomaly(client_id: Uuid, metric: &str, description: &str) -> Self {
+        Self::new(
+            AlertKind::OutcomeAnomaly,
+            AlertSeverity::Warning,
+            format!("{}/{}", client_id, metric),
+            format!("Anomalous {} for client {}: {}", metric, client_id, description),
+        )
+    }
+    
+    /// Several accounts appear to belong to the same person
+    pub fn duplicate_identity(case_id: Uuid, accounts: usize) -> Self {
+        Self::new(
+            AlertKind::DuplicateIdentity,
+            AlertSeverity::Warning,
+            case_id.to_string(),
+            format!("Fraud case {}: {} accounts appear to belong to one person", case_id, accounts),
+        )
+    }
+    
+    fn dedup_key(&self) -> (AlertKind, String) {
+        (self.kind.clone(), self.subject.clone())
+    }
+}
+
+/// Alert manager that deduplicates, retains, and routes alerts to sinks
+pub struct AlertManager {
+    config: AlertingConfig,
+    sinks: Vec<Arc<dyn AlertSink>>,
+    alerts: RwLock<VecDeque<Alert>>,
+    /// Most recent alert id per dedup key
+    recent: RwLock<HashMap<(AlertKind, String), Uuid>>,
+}
+
+impl AlertManager {
+    /// Create an alert manager with sinks built from configuration
+    pub fn new(config: AlertingConfig) -> Self {
+        let sinks = config.sinks.iter().map(sinks::from_config).collect();
+        Self::with_sinks(config, sinks)
+    }
+    
+    /// Create an alert manager with explicit sinks
+    pub fn with_sinks(config: AlertingConfig, sinks: Vec<Arc<dyn AlertSink>>) -> Self {
+        Self {
+            config,
+            sinks,
+            alerts: RwLock::new(VecDeque::new()),
+            recent: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Raise an alert, suppressing duplicates within the dedup window
+    ///
+    /// Returns the id of the stored alert, which is the existing alert's id
+    /// when the new one was deduplicated.
+    pub async fn raise(&self, alert: Alert) -> Uuid {
+        if !self.config.enabled {
+            return alert.id;
+        }
+        
+        let window = Duration::seconds(self.config.dedup_window_secs as i64);
+        let key = alert.dedup_key();
+        let mut alerts = self.alerts.write().await;
+        let mut recent = self.recent.write().await;
+        
+        if let Some(existing_id) = recent.get(&key) {
+            if let Some(existing) = alerts.iter_mut().find(|a| a.id == *existing_id) {
+                if existing.acknowledged_at.is_none() && alert.raised_at - existing.last_seen_at < window {
+                    existing.last_seen_at = alert.raised_at;
+                    existing.suppressed_count += 1;
+                    if alert.severity > existing.severity {
+                        existing.severity = alert.severity;
+                    }
+                    return existing.id;
+                }
+            }
+        }
+        
+        recent.insert(key, alert.id);
+        alerts.push_back(alert.clone());
+        while alerts.len() > self.config.max_retained {
+            if let Some(evicted) = alerts.pop_front() {
+                recent.retain(|_, id| *id != evicted.id);
+            }
+        }
+        drop(recent);
+        drop(alerts);
+        
+        self.route(&alert).await;
+        alert.id
+    }
+    
+    /// Raise alerts warranted by a freshly issued attestation
+    pub async fn observe_attestation(&self, attestation: &ComplianceAttestation) {
+        if !attestation.sanctions_cleared {
+            self.raise(Alert::sanctions_hit(&attestation.account_id)).await;
+        }
+        if attestation.aml_risk_level == AmlRiskLevel::Critical {
+            self.raise(Alert::critical_risk(&attestation.account_id)).await;
+        }
+    }
+    
+    /// Acknowledge an alert
+    pub async fn acknowledge(&self, alert_id: Uuid, operator: &str) -> Result<Alert> {
+        let mut alerts = self.alerts.write().await;
+        let alert = alerts
+            .iter_mut()
+            .find(|a| a.id == alert_id)
+            .ok_or_else(|| ComplianceError::AlertNotFound {
+                alert_id: alert_id.to_string(),
+            })?;
+        
+        if alert.acknowledged_at.is_none() {
+            alert.acknowledged_by = Some(operator.to_string());
+            alert.acknowledged_at = Some(Utc::now());
+        }
+        
+        Ok(alert.clone())
+    }
+    
+    /// List alerts, most recent first
+    pub async fn list(&self, include_acknowledged: bool) -> Vec<Alert> {
+        self.alerts
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|a| include_acknowledged || a.acknowledged_at.is_none())
+            .cloned()
+            .collect()
+    }
+    
+    /// Deliver an alert to every sink accepting its severity
+    async fn route(&self, alert: &Alert) {
+        let deliveries = self
+            .sinks
+            .iter()
+            .filter(|sink| alert.severity >= sink.min_severity())
+            .map(|sink| async move { (sink.name(), sink.deliver(alert).await) });
+        
+        for (name, result) in futures::future::join_all(deliveries).await {
+            if let Err(e) = result {
+                tracing::warn!(sink = name, alert_id = %alert.id, error = %e, "alert delivery failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Alert delivery sinks
+
+use super::{Alert, AlertSeverity};
+use crate::config::AlertSinkConfig;
+use crate::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Destination alerts are routed to
+pub trait AlertSink: Send + Sync {
+    /// Sink name used in logs
+    fn name(&self) -> &'static str;
+    
+    /// Minimum severity delivered to this sink
+    fn min_severity(&self) -> AlertSeverity;
+    
+    /// Deliver an alert
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Build a sink from configuration
+pub fn from_config(config: &AlertSinkConfig) -> Arc<dyn AlertSink> {
+    let http = reqwest::Client::new();
+    match config.clone() {
+        AlertSinkConfig::Email { relay_endpoint, api_key, recipients, min_severity } => Arc::new(EmailSink {
+            http,
+            relay_endpoint,
+            api_key,
+            recipients,
+            min_severity,
+        }),
+        AlertSinkConfig::Slack { webhook_url, min_severity } => Arc::new(SlackSink {
+            http,
+            webhook_url,
+            min_severity,
+        }),
+        AlertSinkConfig::PagerDuty { routing_key, min_severity } => Arc::new(PagerDutySink {
+            http,
+            routing_key,
+            min_severity,
+        }),
+    }
+}
+
+async fn post_json(
+    sink: &str,
+    request: reqwest::RequestBuilder,
+    body: serde_json::Value,
+) -> Result<()> {
+    let response = request.json(&body).send().await?;
+    if !response.status().is_success() {
+        return Err(ComplianceError::AlertDeliveryFailed {
+            sink: sink.to_string(),
+            reason: format!("unexpected status {}", response.status()),
+        });
+    }
+    Ok(())
+}
+
+/// Email delivered through an HTTP mail relay
+pub struct EmailSink {
+    http: reqwest::Client,
+    relay_endpoint: String,
+    api_key: Option<String>,
+    recipients: Vec<String>,
+    min_severity: AlertSeverity,
+}
+
+impl AlertSink for EmailSink {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+    
+    fn min_severity(&self) -> AlertSeverity {
+        self.min_severity
+    }
+    
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut request = self.http.post(&self.relay_endpoint);
+            if let Some(api_key) = &self.api_key {
+                request = request.bearer_auth(api_key);
+            }
+            let body = json!({
+                "to": self.recipients,
+                "subject": format!("[{:?}] {}", alert.severity, alert.message),
+                "text": serde_json::to_string_pretty(alert)?,
+            });
+            post_json(self.name(), request, body).await
+        })
+    }
+}
+
+/// Slack incoming webhook
+pub struct SlackSink {
+    http: reqwest::Client,
+    webhook_url: String,
+    min_severity: AlertSeverity,
+}
+
+impl AlertSink for SlackSink {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+    
+    fn min_severity(&self) -> AlertSeverity {
+        self.min_severity
+    }
+    
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body = json!({
+                "text": format!("*{:?}* {} (alert `{}`)", alert.severity, alert.message, alert.id),
+            });
+            post_json(self.name(), self.http.post(&self.webhook_url), body).await
+        })
+    }
+}
+
+/// PagerDuty Events API v2
+pub struct PagerDutySink {
+    http: reqwest::Client,
+    routing_key: String,
+    min_severity: AlertSeverity,
+}
+
+impl PagerDutySink {
+    const EVENTS_ENDPOINT: &'static str = "https://events.pagerduty.com/v2/enqueue";
+}
+
+impl AlertSink for PagerDutySink {
+    fn name(&self) -> &'static str {
+        "pagerduty"
+    }
+    
+    fn min_severity(&self) -> AlertSeverity {
+        self.min_severity
+    }
+    
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let severity = match alert.severity {
+                AlertSeverity::Info => "info",
+                AlertSeverity::Warning => "warning",
+                AlertSeverity::Critical => "critical",
+            };
+            let body = json!({
+                "routing_key": self.routing_key,
+                "event_action": "trigger",
+                "dedup_key": format!("{:?}:{}", alert.kind, alert.subject),
+                "payload": {
+                    "summary": alert.message,
+                    "source": "compliance-backend",
+                    "severity": severity,
+                    "custom_details": alert.details,
+                },
+            });
+            post_json(self.name(), self.http.post(Self::EVENTS_ENDPOINT), body).await
+        })
+    }
+}
@@ -0,0 +1,63 @@
+//! Account watch handlers
+
+use super::auth::ClientAuth;
+use super::tags::TagQuery;
+use super::validation::{Valid, Validate};
+use super::AppState;
+use crate::compliance::account_watch::{AccountWatch, AccountWatchInput};
+use crate::compliance::tags::TaggedKind;
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use uuid::Uuid;
+
+/// `GET /v1/account-watches`
+///
+/// Filtered by `tag` on the watched accounts' tags.
+pub async fn list_watches(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Query(query): Query<TagQuery>,
+) -> Result<Json<Vec<AccountWatch>>> {
+    let filter = query.filter()?;
+    let mut watches = state.account_watches.list(client.id).await;
+    state
+        .tags
+        .retain(client.id, TaggedKind::Account, filter.as_ref(), &mut watches, |watch| {
+            watch.account_id.to_string()
+        })
+        .await;
+    Ok(Json(watches))
+}
+
+impl Validate for AccountWatchInput {}
+
+/// `POST /v1/account-watches`
+///
+/// Changes to the account's on-chain storage are sent to the client's
+/// webhook as `onchain.compliance_changed` events from the next sync on.
+pub async fn create_watch(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(input): Valid<AccountWatchInput>,
+) -> Result<Json<AccountWatch>> {
+    Ok(Json(state.account_watches.watch(client.id, input).await?))
+}
+
+/// `GET /v1/account-watches/{watch_id}`
+pub async fn get_watch(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(watch_id): Path<Uuid>,
+) -> Result<Json<AccountWatch>> {
+    Ok(Json(state.account_watches.get(client.id, watch_id).await?))
+}
+
+/// `DELETE /v1/account-watches/{watch_id}`
+pub async fn delete_watch(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(watch_id): Path<Uuid>,
+) -> Result<Json<AccountWatch>> {
+    Ok(Json(state.account_watches.unwatch(client.id, watch_id).await?))
+}
@@ -0,0 +1,647 @@
+//! Account-scoped API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::attestation_events::AttestationState;
+use crate::compliance::country_risk::{normalize_country, GeographicProfile, GeographicRiskAssessment};
+use crate::compliance::device_risk::{DeviceObservation, DeviceRiskAssessment, DeviceSignals, SignalContext};
+use crate::compliance::event_feed::FeedEvent;
+use crate::compliance::localization::{attestation_messages, LocalizedMessage, Message};
+use crate::compliance::rejection::KycRejection;
+use crate::compliance::risk_factors::{validate_attributes, CustomRiskAssessment};
+use crate::compliance::velocity::{AuthorizationDecision, AuthorizationOutcome, TransactionRequest};
+use crate::crypto::canonical_json;
+use crate::types::{AccountId, ComplianceAttestation, ComplianceLevel, KycStatus};
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+/// Query parameters for the compliance snapshot
+#[derive(Debug, Deserialize)]
+pub struct ComplianceQuery {
+    /// Point in time to reconstruct (defaults to now)
+    pub as_of: Option<DateTime<Utc>>,
+    
+    /// Seconds to wait for the snapshot to differ from `If-None-Match`,
+    /// capped at `server.max_long_poll_secs`
+    pub wait: Option<u64>,
+}
+
+/// Compliance state of an account at a point in time
+#[derive(Debug, Serialize)]
+pub struct ComplianceSnapshot {
+    pub account_id: AccountId,
+    pub as_of: DateTime<Utc>,
+    /// Attestation state rebuilt from lifecycle events
+    pub state: Option<AttestationState>,
+    /// Highest compliance level satisfied at `as_of`
+    pub compliance_level: Option<ComplianceLevel>,
+    /// Screening list versions in force at `as_of`
+    pub sanctions_list_versions: HashMap<String, String>,
+}
+
+/// `GET /v1/accounts/{id}/compliance`
+///
+/// Returns the account's attestation and risk state as it existed at `as_of`,
+/// together with the screening list versions then in force.
+///
+/// The response carries a weak `ETag` over everything but `as_of`. A request
+/// whose `If-None-Match` still matches gets `304 Not Modified`; with `wait`
+/// it is held until the snapshot changes, answering as soon as it does, and
+/// gets `304` only once the wait is over. Waiting is woken by the account's
+/// attestation events and by its attestation expiring, and is not allowed
+/// with `as_of`, since past snapshots never change.
+pub async fn get_compliance(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Query(query): Query<ComplianceQuery>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    if query.as_of.is_some_and(|as_of| as_of > state.compliance.clock.now()) {
+        return Err(ComplianceError::validation("as_of", "must not be in the future"));
+    }
+    if query.as_of.is_some() && query.wait.is_some() {
+        return Err(ComplianceError::validation("wait", "cannot be combined with as_of"));
+    }
+    let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    let wait = std::time::Duration::from_secs(query.wait.unwrap_or(0).min(state.config.server.max_long_poll_secs));
+    let deadline = tokio::time::Instant::now() + wait;
+    
+    // Subscribed before the first snapshot, so a change made while it is
+    // taken still wakes the wait
+    let mut feed = match if_none_match {
+        Some(_) if !wait.is_zero() => Some(state.compliance.events.feed().subscribe(None).await?.live),
+        _ => None,
+    };
+    loop {
+        let snapshot = compliance_snapshot(&state, &account_id, query.as_of).await?;
+        let etag = snapshot_etag(&snapshot)?;
+        if !if_none_match.is_some_and(|tags| etag_matches(tags, &etag)) {
+            return Ok(([(header::ETAG, etag)], Json(snapshot)).into_response());
+        }
+        let Some(live) = feed.as_mut() else {
+            return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+        };
+        
+        // The attestation expiring changes the snapshot without an event
+        let expiry = snapshot
+            .state
+            .as_ref()
+            .filter(|s| s.attestation.expires_at > snapshot.as_of)
+            .and_then(|s| (s.attestation.expires_at - snapshot.as_of).to_std().ok())
+            .map(|until_expiry| tokio::time::Instant::now() + until_expiry);
+        let wake_at = expiry.map_or(deadline, |expiry| expiry.min(deadline));
+        match tokio::time::timeout_at(wake_at, next_account_event(live, &account_id)).await {
+            Ok(true) => continue,
+            Ok(false) => feed = None,
+            Err(_) if wake_at < deadline => continue,
+            Err(_) => return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response()),
+        }
+    }
+}
+
+/// Compliance snapshot of an account at `as_of`, or now
+async fn compliance_snapshot(
+    state: &AppState,
+    account_id: &AccountId,
+    as_of: Option<DateTime<Utc>>,
+) -> Result<ComplianceSnapshot> {
+    let as_of = as_of.unwrap_or_else(|| state.compliance.clock.now());
+    let attestation_state = state.compliance.get_compliance_state_at(account_id, as_of).await?;
+    let compliance_level = match attestation_state.as_ref().filter(|s| s.is_valid_at(as_of)) {
+        Some(s) => state.compliance.highest_compliance_level_at(&s.attestation, as_of).await,
+        None => None,
+    };
+    
+    Ok(ComplianceSnapshot {
+        account_id: account_id.clone(),
+        as_of,
+        state: attestation_state,
+        compliance_level,
+        sanctions_list_versions: state.screening_lists.versions_at(as_of).await,
+    })
+}
+
+/// Weak entity tag of a snapshot's content, `as_of` aside
+pub fn snapshot_etag(snapshot: &ComplianceSnapshot) -> Result<String> {
+    let content = canonical_json::to_vec(&serde_json::json!({
+        "account_id": snapshot.account_id,
+        "state": snapshot.state,
+        "compliance_level": snapshot.compliance_level,
+        "sanctions_list_versions": snapshot.sanctions_list_versions,
+    }))?;
+    Ok(format!("W/\"{}\"", &blake3::hash(&content).to_hex()[..32]))
+}
+
+/// Whether an `If-None-Match` header value matches an entity tag, compared weakly
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// Wait for an attestation event of the account; `false` once the feed has closed
+async fn next_account_event(live: &mut broadcast::Receiver<FeedEvent>, account_id: &AccountId) -> bool {
+    loop {
+        match live.recv().await {
+            Ok(event) if event.event.account_id == *account_id => return true,
+            Ok(_) => continue,
+            // The missed events may include the account's
+            Err(RecvError::Lagged(_)) => return true,
+            Err(RecvError::Closed) => return false,
+        }
+    }
+}
+
+/// Request body for running a compliance check
+#[derive(Debug, Deserialize)]
+pub struct CheckRequest {
+    /// Preview the outcome without persisting anything or emitting events
+    #[serde(default)]
+    pub dry_run: bool,
+    
+    /// Compliance level the result is evaluated against (defaults to `Basic`)
+    pub required_level: Option<ComplianceLevel>,
+}
+
+impl Validate for CheckRequest {}
+
+/// Policy evaluation of a check result
+#[derive(Debug, Serialize)]
+pub struct PolicyResult {
+    pub required_level: ComplianceLevel,
+    pub meets_required_level: bool,
+    /// Highest compliance level the attestation satisfies
+    pub compliance_level: Option<ComplianceLevel>,
+    pub reasons: Vec<String>,
+    /// Why KYC was rejected, with retry eligibility, when it was
+    pub kyc_rejection: Option<KycRejection>,
+    
+    /// The reasons as display text, in the caller's `Accept-Language` or
+    /// else the client's configured locales
+    pub display: Vec<LocalizedMessage>,
+}
+
+/// Result of a compliance check
+#[derive(Debug, Serialize)]
+pub struct CheckResponse {
+    pub dry_run: bool,
+    /// Issued attestation, or the would-be attestation for a dry run
+    pub attestation: ComplianceAttestation,
+    pub policy: PolicyResult,
+    
+    /// Workflow run that issued the attestation, for clients with a workflow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_run_id: Option<Uuid>,
+}
+
+/// `POST /v1/accounts/{id}/check`
+///
+/// Runs KYC, AML, and sanctions checks, through the client's workflow when
+/// it has one. A dry run returns the would-be attestation of the built-in
+/// check and its policy result without storing it, recording lifecycle
+/// events, raising alerts, or writing to the audit log. Offboarded accounts
+/// cannot be checked.
+pub async fn run_check(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    headers: HeaderMap,
+    Valid(request): Valid<CheckRequest>,
+) -> Result<Json<CheckResponse>> {
+    state.offboarding.ensure_active(&account_id).await?;
+    let compliance = state.live_config.compliance();
+    let mut workflow_run_id = None;
+    let attestation = if request.dry_run {
+        state.compliance.comprehensive_check(&account_id, true).await?
+    } else {
+        let attestation = match compliance.workflows.for_client(client.id) {
+            Some((name, workflow)) => {
+                let required = workflow.required_level.clone().unwrap_or_else(|| client.compliance_level.clone());
+                let outcome = state
+                    .compliance
+                    .run_workflow(name, workflow, &account_id, required, &state.workflows)
+                    .await?;
+                workflow_run_id = Some(outcome.run.id);
+                outcome.into_attestation()?
+            }
+            None => state.compliance.update_compliance_status(&account_id).await?,
+        };
+        state.alerts.observe_attestation(&attestation).await;
+        attestation
+    };
+    
+    let required_level = request.required_level.unwrap_or(ComplianceLevel::Basic);
+    let compliance_level = state.compliance.highest_compliance_level(&attestation).await;
+    let kyc_rejection = if attestation.kyc_status != KycStatus::Verified {
+        state
+            .compliance
+            .events
+            .project(&account_id, None)
+            .await?
+            .and_then(|s| s.kyc_rejection)
+    } else {
+        None
+    };
+    let mut reasons = Vec::new();
+    match &kyc_rejection {
+        Some(rejection) => reasons.extend(
+            rejection
+                .reasons
+                .iter()
+                .map(|reason| format!("KYC rejected: {}", reason.description())),
+        ),
+        None if attestation.kyc_status != KycStatus::Verified => {
+            reasons.push(format!("KYC status is {:?}", attestation.kyc_status));
+        }
+        None => {}
+    }
+    if !attestation.sanctions_cleared {
+        reasons.push("sanctions screening not cleared".to_string());
+    }
+    let mut messages = attestation_messages(&attestation, kyc_rejection.as_ref());
+    if compliance_level.as_ref().map_or(true, |level| *level < required_level) {
+        reasons.push(format!(
+            "{:?} compliance required, attestation satisfies {:?}",
+            required_level, compliance_level
+        ));
+        messages.push(Message::new("policy.level_not_met").with("required", format!("{:?}", required_level)));
+    }
+    let mut locales = super::preferred_locales(&headers);
+    if locales.is_empty() {
+        locales = client.locales.clone();
+    }
+    let display = state.catalog.render_all(&messages, &locales);
+    
+    Ok(Json(CheckResponse {
+        dry_run: request.dry_run,
+        attestation,
+        policy: PolicyResult {
+            meets_required_level: compliance_level.as_ref().is_some_and(|level| *level >= required_level),
+            required_level,
+            compliance_level,
+            reasons,
+            kyc_rejection,
+            display,
+        },
+        workflow_run_id,
+    }))
+}
+
+/// Request body for authorizing a transaction
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeTransactionRequest {
+    #[serde(flatten)]
+    pub transaction: TransactionRequest,
+    
+    /// Device and IP signals of the session the transaction was made in
+    #[serde(default)]
+    pub device: Option<DeviceSignals>,
+}
+
+impl Validate for AuthorizeTransactionRequest {
+    fn validate(&self, v: &mut Violations) {
+        let transaction = &self.transaction;
+        v.ensure(transaction.amount != 0, "amount", "must be greater than zero");
+        v.ensure(
+            transaction
+                .counterparty_country
+                .as_ref()
+                .is_none_or(|c| c.len() == 2 && c.bytes().all(|b| b.is_ascii_alphabetic())),
+            "counterparty_country",
+            "must be an ISO 3166-1 alpha-2 country code",
+        );
+        v.at("device", |v| self.device.validate(v));
+    }
+}
+
+/// `POST /v1/accounts/{id}/authorize-transaction`
+///
+/// Checks a proposed transaction against the account's compliance level,
+/// AML risk, velocity limits, and the client's counterparty watchlists before
+/// it is executed. Device signals sent along are recorded for the account's
+/// device risk, which feeds its next compliance check, when device risk is
+/// enabled.
+pub async fn authorize_transaction(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(AuthorizeTransactionRequest { transaction: request, device }): Valid<AuthorizeTransactionRequest>,
+) -> Result<Json<AuthorizationDecision>> {
+    if let Some(signals) = device {
+        state
+            .compliance
+            .device_risk
+            .record(&account_id, SignalContext::Transaction, signals)
+            .await?;
+    }
+    
+    let attestation = state.compliance.get_compliance_status(&account_id).await?;
+    let compliance_level = match attestation.as_ref() {
+        Some(att) => state.compliance.highest_compliance_level(att).await,
+        None => None,
+    };
+    
+    let mut decision = state
+        .velocity
+        .authorize(client.id, &account_id, attestation.as_ref(), compliance_level.clone(), &request)
+        .await;
+    
+    if decision.outcome == AuthorizationOutcome::StepUp {
+        let session = state
+            .step_up
+            .create_session(
+                client.id,
+                &account_id,
+                compliance_level,
+                decision.required_level.clone(),
+                decision.reasons.clone(),
+            )
+            .await?;
+        state.velocity.attach_step_up_session(decision.id, session.id).await;
+        decision.step_up_session = Some(session.id);
+    }
+    
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "transaction.authorized",
+            Some(&account_id),
+            serde_json::to_value(&decision)?,
+        )
+        .await;
+    
+    Ok(Json(decision))
+}
+
+/// Geographic profile of an account and the risk it carries
+#[derive(Debug, Serialize)]
+pub struct GeographyResponse {
+    pub account_id: AccountId,
+    pub profile: GeographicProfile,
+    /// `None` when geographic risk scoring is disabled
+    pub assessment: Option<GeographicRiskAssessment>,
+}
+
+/// `GET /v1/accounts/{id}/geography`
+pub async fn get_geography(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<GeographyResponse>> {
+    let profile = state
+        .country_risk
+        .profile(&account_id)
+        .await
+        .ok_or_else(|| ComplianceError::AccountNotFound {
+            account_id: account_id.to_string(),
+        })?;
+    
+    Ok(Json(GeographyResponse {
+        assessment: state.country_risk.assess(&profile),
+        account_id,
+        profile,
+    }))
+}
+
+impl Validate for GeographicProfile {
+    fn validate(&self, v: &mut Violations) {
+        let countries = [("residence", &self.residence), ("document_issuing_country", &self.document_issuing_country)];
+        for (field, country) in countries {
+            if let Some(country) = country {
+                v.absorb(normalize_country(field, country).map(drop));
+            }
+        }
+        for (i, country) in self.counterparty_countries.iter().enumerate() {
+            v.absorb(normalize_country(&format!("counterparty_countries[{}]", i), country).map(drop));
+        }
+    }
+}
+
+/// `PUT /v1/accounts/{id}/geography`
+///
+/// Records the account's country of residence, document issuing country, and
+/// counterparty countries. They feed the AML risk level of the next compliance
+/// check.
+pub async fn set_geography(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(profile): Valid<GeographicProfile>,
+) -> Result<Json<GeographyResponse>> {
+    let profile = state.country_risk.set_profile(&account_id, profile).await?;
+    let assessment = state.country_risk.assess(&profile);
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "account.geography_updated",
+            Some(&account_id),
+            serde_json::json!({
+                "score": assessment.as_ref().map(|a| a.score),
+                "dataset_version": state.country_risk.dataset_version(),
+            }),
+        )
+        .await;
+    
+    Ok(Json(GeographyResponse {
+        account_id,
+        profile,
+        assessment,
+    }))
+}
+
+/// Request body for revoking an attestation
+#[derive(Debug, Deserialize)]
+pub struct RevokeRequest {
+    pub reason: String,
+}
+
+impl Validate for RevokeRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.reason.trim().is_empty(), "reason", "must not be empty");
+    }
+}
+
+/// `POST /v1/admin/accounts/{id}/revoke`
+pub async fn revoke_attestation(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<RevokeRequest>,
+) -> Result<Json<ComplianceSnapshot>> {
+    auth.require(Permission::RevokeAttestation)?;
+    
+    state
+        .compliance
+        .revoke_attestation(&account_id, &request.reason, Some(&auth.operator.username))
+        .await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "attestation.revoked",
+            Some(&account_id),
+            serde_json::json!({ "reason": request.reason }),
+        )
+        .await;
+    
+    compliance_snapshot(&state, &account_id, None).await.map(Json)
+}
+
+/// Device and IP signals recorded for an account and the risk they carry
+#[derive(Debug, Serialize)]
+pub struct DeviceRiskResponse {
+    pub account_id: AccountId,
+    /// Most recent first
+    pub observations: Vec<DeviceObservation>,
+    /// `None` when nothing was recorded
+    pub assessment: Option<DeviceRiskAssessment>,
+}
+
+/// `GET /v1/accounts/{id}/device-risk`
+pub async fn get_device_risk(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<DeviceRiskResponse>> {
+    device_risk_response(&state, account_id).await.map(Json)
+}
+
+impl Validate for DeviceSignals {
+    fn validate(&self, v: &mut Violations) {
+        let fingerprint_only = DeviceSignals {
+            ip_country: None,
+            ..self.clone()
+        };
+        v.absorb(fingerprint_only.normalized().map(drop));
+        if let Some(country) = &self.ip_country {
+            v.absorb(normalize_country("ip_country", country).map(drop));
+        }
+    }
+}
+
+/// `POST /v1/accounts/{id}/device-signals`
+///
+/// Records device and IP signals collected while onboarding the account.
+/// They feed the AML risk level of the next compliance check.
+pub async fn record_device_signals(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(signals): Valid<DeviceSignals>,
+) -> Result<Json<DeviceRiskResponse>> {
+    state
+        .compliance
+        .device_risk
+        .record(&account_id, SignalContext::Onboarding, signals)
+        .await?;
+    let response = device_risk_response(&state, account_id).await?;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "account.device_signals_recorded",
+            Some(&response.account_id),
+            serde_json::json!({ "score": response.assessment.as_ref().map(|a| a.score) }),
+        )
+        .await;
+    
+    Ok(Json(response))
+}
+
+async fn device_risk_response(state: &AppState, account_id: AccountId) -> Result<DeviceRiskResponse> {
+    let device_risk = &state.compliance.device_risk;
+    if !device_risk.enabled() {
+        return Err(ComplianceError::validation("device_risk", "device and IP signals are not enabled"));
+    }
+    Ok(DeviceRiskResponse {
+        observations: device_risk.observations(&account_id).await,
+        assessment: device_risk.assess_account(&account_id).await,
+        account_id,
+    })
+}
+
+/// Attributes recorded for an account and the custom risk they carry
+#[derive(Debug, Serialize)]
+pub struct RiskFactorsResponse {
+    pub account_id: AccountId,
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+    /// `None` when no custom risk factor is configured
+    pub assessment: Option<CustomRiskAssessment>,
+}
+
+/// Request body for recording an account's attributes
+#[derive(Debug, Deserialize)]
+pub struct RiskAttributesRequest {
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Validate for RiskAttributesRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.absorb(validate_attributes(&self.attributes));
+    }
+}
+
+/// `GET /v1/accounts/{id}/risk-factors`
+pub async fn get_risk_factors(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<RiskFactorsResponse>> {
+    Ok(Json(risk_factors_response(&state, account_id).await))
+}
+
+/// `PUT /v1/accounts/{id}/risk-factors`
+///
+/// Replaces the attributes custom risk factors read for the account. They
+/// feed the AML risk level of the next compliance check.
+pub async fn set_risk_attributes(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<RiskAttributesRequest>,
+) -> Result<Json<RiskFactorsResponse>> {
+    let names: Vec<String> = request.attributes.keys().cloned().collect();
+    state
+        .compliance
+        .risk_factors
+        .set_attributes(&account_id, request.attributes)
+        .await?;
+    let response = risk_factors_response(&state, account_id).await;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "account.risk_attributes_updated",
+            Some(&response.account_id),
+            serde_json::json!({
+                "attributes": names,
+                "score": response.assessment.as_ref().map(|a| a.score),
+            }),
+        )
+        .await;
+    
+    Ok(Json(response))
+}
+
+async fn risk_factors_response(state: &AppState, account_id: AccountId) -> RiskFactorsResponse {
+    let risk_factors = &state.compliance.risk_factors;
+    RiskFactorsResponse {
+        attributes: risk_factors.attributes(&account_id).await,
+        assessment: risk_factors.assess(&account_id, None).await,
+        account_id,
+    }
+}
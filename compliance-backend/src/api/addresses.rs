@@ -0,0 +1,72 @@
+//! Blockchain address linking and on-chain risk API handlers
+
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate};
+use super::AppState;
+use crate::compliance::chain_analytics::{ChainRiskProfile, LinkedAddress};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::Json;
+
+/// `GET /v1/accounts/{id}/addresses`
+pub async fn list_addresses(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Json<Vec<LinkedAddress>> {
+    Json(state.compliance.chain_analytics.addresses(&account_id).await)
+}
+
+impl Validate for LinkedAddress {}
+
+/// `POST /v1/accounts/{id}/addresses`
+///
+/// Links a blockchain address to the account. Linked addresses are scored by
+/// the on-chain analytics provider during the next compliance check, and
+/// transfers to them count as transfers to the account in the counterparty
+/// graph.
+pub async fn link_address(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(address): Valid<LinkedAddress>,
+) -> Result<Json<Vec<LinkedAddress>>> {
+    let addresses = state.compliance.chain_analytics.link_address(&account_id, address.clone()).await?;
+    for linked in &addresses {
+        state.compliance.counterparties.link_address(&account_id, linked).await;
+    }
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "account.address_linked",
+            Some(&account_id),
+            serde_json::json!({ "chain": address.chain, "address": address.address }),
+        )
+        .await;
+    
+    Ok(Json(addresses))
+}
+
+/// `GET /v1/accounts/{id}/chain-risk`
+///
+/// Scores every linked address and returns the account's on-chain exposure
+/// broken down by category. Scores are served from cache while fresh.
+pub async fn chain_risk(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<ChainRiskProfile>> {
+    let analytics = &state.compliance.chain_analytics;
+    let profile = state
+        .compliance
+        .breakers
+        .chain_analytics
+        .call(analytics.account_profile(&account_id))
+        .await?;
+    
+    profile.map(Json).ok_or_else(|| {
+        ComplianceError::validation("account_id", "chain analytics is disabled or no address is linked")
+    })
+}
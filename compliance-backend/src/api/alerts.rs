@@ -0,0 +1,47 @@
+//! Alert listing and acknowledgment API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::AppState;
+use crate::alerts::Alert;
+use crate::compliance::anomaly::BaselineStatus;
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Query parameters for listing alerts
+#[derive(Debug, Deserialize)]
+pub struct ListAlertsQuery {
+    #[serde(default)]
+    pub include_acknowledged: bool,
+}
+
+/// `GET /v1/admin/alerts`
+pub async fn list_alerts(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<ListAlertsQuery>,
+) -> Result<Json<Vec<Alert>>> {
+    auth.require(Permission::ViewAlerts)?;
+    Ok(Json(state.alerts.list(query.include_acknowledged).await))
+}
+
+/// `POST /v1/admin/alerts/{alert_id}/acknowledge`
+pub async fn acknowledge_alert(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(alert_id): Path<Uuid>,
+) -> Result<Json<Alert>> {
+    auth.require(Permission::AcknowledgeAlerts)?;
+    Ok(Json(state.alerts.acknowledge(alert_id, &auth.operator.username).await?))
+}
+
+/// `GET /v1/admin/anomalies/baselines`
+///
+/// Outcome baselines of every client, with the current window of each, as
+/// anomaly alerts are judged against them.
+pub async fn anomaly_baselines(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<Vec<BaselineStatus>>> {
+    auth.require(Permission::ViewAlerts)?;
+    Ok(Json(state.compliance.anomalies.baselines()))
+}
@@ -0,0 +1,225 @@
+//! Four-eyes override approval API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::tags::client_filter;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::approvals::{ApprovalRequest, ApprovalStatus, OverrideAction};
+use crate::compliance::tags::TaggedKind;
+use crate::types::AmlRiskLevel;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Request body for proposing an override
+#[derive(Debug, Deserialize)]
+pub struct ProposeRequest {
+    pub action: OverrideAction,
+    pub justification: String,
+    
+    /// When the override lapses; required for sanctions clearances and risk downgrades
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Validate for ProposeRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.justification.trim().is_empty(), "justification", "must not be empty");
+        match (self.action.is_time_bounded(), self.expires_at) {
+            (true, None) => v.push("expires_at", "is required for this override"),
+            (false, Some(_)) => v.push("expires_at", "is not supported for this override"),
+            _ => {}
+        }
+    }
+}
+
+/// Query parameters for listing approval requests
+#[derive(Debug, Deserialize)]
+pub struct ListApprovalsQuery {
+    /// Status filter (defaults to pending)
+    pub status: Option<ApprovalStatus>,
+    /// Client whose tags `tag` filters by
+    pub client_id: Option<Uuid>,
+    pub tag: Option<String>,
+}
+
+/// Request body for approving or rejecting a request
+#[derive(Debug, Default, Deserialize)]
+pub struct DecisionRequest {
+    pub note: Option<String>,
+}
+
+impl Validate for DecisionRequest {}
+
+/// Permission needed to propose or approve an override
+fn required_permission(action: &OverrideAction) -> Permission {
+    match action {
+        OverrideAction::SanctionsClearance { .. } | OverrideAction::RiskDowngrade { .. } => Permission::ManualOverride,
+        OverrideAction::RevocationReversal { .. } => Permission::RevokeAttestation,
+    }
+}
+
+fn risk_rank(level: &AmlRiskLevel) -> u8 {
+    match level {
+        AmlRiskLevel::Low => 0,
+        AmlRiskLevel::Medium => 1,
+        AmlRiskLevel::High => 2,
+        AmlRiskLevel::Critical => 3,
+    }
+}
+
+/// `POST /v1/admin/approvals`
+pub async fn propose(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Valid(request): Valid<ProposeRequest>,
+) -> Result<Json<ApprovalRequest>> {
+    auth.require(required_permission(&request.action))?;
+    
+    let account_id = request.action.account_id();
+    let current = state
+        .compliance
+        .events
+        .project(account_id, None)
+        .await?
+        .ok_or_else(|| ComplianceError::AccountNotFound {
+            account_id: account_id.to_string(),
+        })?;
+    if let OverrideAction::RiskDowngrade { aml_risk_level, .. } = &request.action {
+        if risk_rank(aml_risk_level) >= risk_rank(&current.attestation.aml_risk_level) {
+            return Err(ComplianceError::validation(
+                "aml_risk_level",
+                "must be lower than the account's current risk level",
+            ));
+        }
+    }
+    
+    let prior_risk_level = matches!(request.action, OverrideAction::RiskDowngrade { .. })
+        .then(|| current.attestation.aml_risk_level.clone());
+    let max_duration = Duration::days(state.live_config.compliance().overrides.max_duration_days as i64);
+    
+    let approval = state
+        .approvals
+        .propose(
+            request.action,
+            &request.justification,
+            &auth.operator.username,
+            request.expires_at,
+            prior_risk_level,
+            max_duration,
+        )
+        .await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            &format!("{}.proposed", approval.action.audit_action()),
+            Some(approval.action.account_id()),
+            serde_json::json!({
+                "approval_id": approval.id,
+                "action": approval.action,
+                "justification": approval.justification,
+                "expires_at": approval.expires_at,
+            }),
+        )
+        .await;
+    
+    Ok(Json(approval))
+}
+
+/// `GET /v1/admin/approvals`
+pub async fn list_approvals(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<ListApprovalsQuery>,
+) -> Result<Json<Vec<ApprovalRequest>>> {
+    auth.require(Permission::ViewCases)?;
+    let mut approvals = state.approvals.list(Some(query.status.unwrap_or(ApprovalStatus::Pending))).await?;
+    if let Some((client_id, filter)) = client_filter(query.client_id, query.tag.as_deref())? {
+        state
+            .tags
+            .retain(client_id, TaggedKind::Case, Some(&filter), &mut approvals, |approval| {
+                approval.id.to_string()
+            })
+            .await;
+    }
+    Ok(Json(approvals))
+}
+
+/// `GET /v1/admin/approvals/{approval_id}`
+pub async fn get_approval(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(approval_id): Path<Uuid>,
+) -> Result<Json<ApprovalRequest>> {
+    auth.require(Permission::ViewCases)?;
+    Ok(Json(state.approvals.get(approval_id).await?))
+}
+
+/// `POST /v1/admin/approvals/{approval_id}/approve`
+///
+/// Approves and applies the override. The approver must hold the same
+/// permission as the proposer and be a different operator.
+pub async fn approve(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(approval_id): Path<Uuid>,
+    Valid(decision): Valid<DecisionRequest>,
+) -> Result<Json<ApprovalRequest>> {
+    let pending = state.approvals.get(approval_id).await?;
+    auth.require(required_permission(&pending.action))?;
+    
+    let approval = state
+        .approvals
+        .approve(approval_id, &auth.operator.username, decision.note)
+        .await?;
+    let approved_by = vec![approval.proposed_by.clone(), auth.operator.username.clone()];
+    
+    if let Err(e) = state.compliance.apply_override(&approval.action, approved_by).await {
+        if let Err(mark) = state.approvals.mark_failed(approval_id, &e.to_string()).await {
+            tracing::error!(approval_id = %approval_id, error = %mark, "failed to mark override failed");
+        }
+        return Err(e);
+    }
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            &format!("{}.approved", approval.action.audit_action()),
+            Some(approval.action.account_id()),
+            serde_json::json!({ "approval_id": approval.id, "proposed_by": approval.proposed_by, "action": approval.action }),
+        )
+        .await;
+    
+    Ok(Json(approval))
+}
+
+/// `POST /v1/admin/approvals/{approval_id}/reject`
+pub async fn reject(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(approval_id): Path<Uuid>,
+    Valid(decision): Valid<DecisionRequest>,
+) -> Result<Json<ApprovalRequest>> {
+    let pending = state.approvals.get(approval_id).await?;
+    auth.require(required_permission(&pending.action))?;
+    
+    let approval = state
+        .approvals
+        .reject(approval_id, &auth.operator.username, decision.note)
+        .await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            &format!("{}.rejected", approval.action.audit_action()),
+            Some(approval.action.account_id()),
+            serde_json::json!({ "approval_id": approval.id, "note": approval.decision_note }),
+        )
+        .await;
+    
+    Ok(Json(approval))
+}
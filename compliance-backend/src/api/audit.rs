@@ -0,0 +1,155 @@
+//! Audit log API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::AppState;
+use crate::audit::{decode_cursor, AuditEntry, AuditPage, AuditQuery, ChainVerification, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::compliance::velocity::DecisionReplay;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for `GET /v1/audit`
+#[derive(Debug, Deserialize)]
+pub struct AuditListQuery {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub account_id: Option<AccountId>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    
+    /// `json` (default, paginated) or `ndjson` (streams every matching entry)
+    pub format: Option<String>,
+}
+
+/// A page of audit entries with the integrity of the chain they belong to
+#[derive(Debug, Serialize)]
+pub struct AuditListResponse {
+    #[serde(flatten)]
+    pub page: AuditPage,
+    pub integrity: ChainVerification,
+}
+
+/// Trailer line closing an NDJSON export
+#[derive(Debug, Serialize)]
+struct ExportTrailer<'a> {
+    exported: usize,
+    integrity: &'a ChainVerification,
+}
+
+/// `GET /v1/audit`
+///
+/// Returns matching entries oldest first. In `ndjson` mode every matching
+/// entry is streamed one per line, followed by a trailer line carrying the
+/// chain verification result, so exports can be checked end to end.
+pub async fn list_audit(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(params): Query<AuditListQuery>,
+) -> Result<Response> {
+    auth.require(Permission::ViewAudit)?;
+    
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from >= to {
+            return Err(ComplianceError::validation("from", "must be before to"));
+        }
+    }
+    
+    let query = AuditQuery {
+        actor: params.actor,
+        action: params.action,
+        account_id: params.account_id,
+        from: params.from,
+        to: params.to,
+    };
+    let integrity = state.audit.verify_chain().await?;
+    if !integrity.valid {
+        tracing::error!(first_invalid_sequence = ?integrity.first_invalid_sequence, "audit chain verification failed");
+    }
+    
+    match params.format.as_deref() {
+        None | Some("json") => {
+            let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+            if limit == 0 || limit > MAX_PAGE_SIZE {
+                return Err(ComplianceError::validation(
+                    "limit",
+                    format!("must be between 1 and {}", MAX_PAGE_SIZE),
+                ));
+            }
+            let cursor = params.cursor.as_deref().map(decode_cursor).transpose()?;
+            let page = state.audit.query(&query, cursor, limit).await?;
+            Ok(Json(AuditListResponse { page, integrity }).into_response())
+        }
+        Some("ndjson") => {
+            let entries = state.audit.export(&query).await?;
+            Ok(ndjson_response(entries, integrity))
+        }
+        Some(other) => Err(ComplianceError::validation(
+            "format",
+            format!("unsupported format {}, expected json or ndjson", other),
+        )),
+    }
+}
+
+fn ndjson_response(entries: Vec<AuditEntry>, integrity: ChainVerification) -> Response {
+    let exported = entries.len();
+    let lines = entries
+        .into_iter()
+        .map(|entry| serde_json::to_vec(&entry))
+        .chain(std::iter::once_with(move || {
+            serde_json::to_vec(&ExportTrailer {
+                exported,
+                integrity: &integrity,
+            })
+        }))
+        .map(|line| {
+            line.map(|mut bytes| {
+                bytes.push(b'\n');
+                bytes
+            })
+        });
+    
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(futures::stream::iter(lines)),
+    )
+        .into_response()
+}
+
+/// `POST /v1/admin/decisions/{decision_id}/replay`
+///
+/// Re-runs a transaction authorization decision against the inputs recorded
+/// with it, showing the outcome follows from them alone. The replay is
+/// recorded in the audit log.
+pub async fn replay_decision(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(decision_id): Path<Uuid>,
+) -> Result<Json<DecisionReplay>> {
+    auth.require(Permission::ViewAudit)?;
+    let replay = state.velocity.replay_decision(decision_id).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "decision.replayed",
+            Some(&replay.inputs.account_id),
+            serde_json::json!({
+                "decision_id": decision_id,
+                "deterministic": replay.deterministic,
+                "rules_version": replay.inputs.rules_version,
+            }),
+        )
+        .await;
+    
+    Ok(Json(replay))
+}
@@ -0,0 +1,88 @@
+//! API key authentication for business clients, and session token
+//! authentication for their end users
+
+pub mod rbac;
+
+use super::AppState;
+use crate::compliance::metering::BillableOperation;
+use crate::compliance::provider_credentials;
+use crate::compliance::verification_sessions::VerificationSession;
+use crate::types::BusinessClient;
+use crate::ComplianceError;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+/// Header carrying the business client API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Business client authenticated by its API key
+pub struct ClientAuth(pub BusinessClient);
+
+impl FromRequestParts<AppState> for ClientAuth {
+    type Rejection = ComplianceError;
+    
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let api_key = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(ComplianceError::InvalidApiKey)?;
+        
+        let client = state
+            .clients
+            .find_by_api_key(api_key)
+            .await?
+            .ok_or(ComplianceError::InvalidApiKey)?;
+        
+        Ok(Self(client))
+    }
+}
+
+/// End user authenticated by a verification session token
+pub struct SessionAuth(pub VerificationSession);
+
+impl FromRequestParts<AppState> for SessionAuth {
+    type Rejection = ComplianceError;
+    
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ComplianceError::InvalidSessionToken {
+                reason: "missing bearer token".to_string(),
+            })?;
+        
+        Ok(Self(state.verification_sessions.authenticate(token).await?))
+    }
+}
+
+/// Run a business client's request on its behalf, so provider calls made
+/// while serving it use the client's own provider credentials and stay in
+/// its data residency region
+///
+/// The request is billed to the client as an API call, and rejected when
+/// the client's plan allows no more.
+pub async fn scope_client(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    // Serving the request unscoped would send a client's PII to global endpoints
+    let client = match request.headers().get(API_KEY_HEADER).and_then(|value| value.to_str().ok()) {
+        Some(api_key) => match state.clients.find_by_api_key(api_key).await {
+            Ok(client) => client,
+            Err(e) => return e.into_response(),
+        },
+        None => None,
+    };
+    match client {
+        Some(client) => {
+            if let Err(e) = state.meter.charge(client.id, BillableOperation::ApiCall).await {
+                return e.into_response();
+            }
+            provider_credentials::with_client(client.id, client.region, next.run(request)).await
+        }
+        None => next.run(request).await,
+    }
+}
@@ -0,0 +1,366 @@
+//! Role-based access control for human operators
+//!
+//! Operators authenticate with a username and password and receive a
+//! bearer session token. Sessions are kept separate from the machine API
+//! keys used by business clients.
+
+use crate::api::AppState;
+use crate::{ComplianceError, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Minimum operator password length
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+
+/// Operator role, ordered from least to most privileged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Viewer,
+    Analyst,
+    ComplianceOfficer,
+    Admin,
+}
+
+/// Action an operator may be permitted to perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ViewAlerts,
+    ViewAudit,
+    ViewCases,
+    AcknowledgeAlerts,
+    ManageCases,
+    ManualOverride,
+    RevokeAttestation,
+    GenerateReports,
+    ManageOperators,
+    ManageWebhooks,
+    ReloadConfig,
+    MigrateAttestations,
+    ManageScreeningLists,
+    ManageClients,
+    ManageComponents,
+    AdjudicateMatches,
+    TuneScreening,
+    CosignAttestations,
+    ManageJobs,
+}
+
+impl Role {
+    /// Permissions granted to this role
+    pub fn permissions(self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Role::Viewer => &[ViewAlerts, ViewAudit, ViewCases],
+            Role::Analyst => &[
+                ViewAlerts,
+                ViewAudit,
+                ViewCases,
+                AcknowledgeAlerts,
+                ManageCases,
+                AdjudicateMatches,
+            ],
+            Role::ComplianceOfficer => &[
+                ViewAlerts,
+                ViewAudit,
+                ViewCases,
+                AcknowledgeAlerts,
+                ManageCases,
+                ManualOverride,
+                RevokeAttestation,
+                GenerateReports,
+                AdjudicateMatches,
+                TuneScreening,
+                CosignAttestations,
+            ],
+            Role::Admin => &[
+                ViewAlerts,
+                ViewAudit,
+                ViewCases,
+                AcknowledgeAlerts,
+                ManageCases,
+                ManualOverride,
+                RevokeAttestation,
+                GenerateReports,
+                ManageOperators,
+                ManageWebhooks,
+                ReloadConfig,
+                MigrateAttestations,
+                ManageScreeningLists,
+                ManageClients,
+                ManageComponents,
+                AdjudicateMatches,
+                TuneScreening,
+                CosignAttestations,
+                ManageJobs,
+            ],
+        }
+    }
+    
+    /// Check if this role grants a permission
+    pub fn grants(self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// A human operator account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operator {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: String,
+    pub role: Role,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An authenticated operator session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorSession {
+    pub id: Uuid,
+    pub operator_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct OperatorRecord {
+    operator: Operator,
+    password_hash: String,
+}
+
+/// Operator accounts and their sessions
+pub struct RbacService {
+    session_ttl: Duration,
+    operators: RwLock<HashMap<Uuid, OperatorRecord>>,
+    /// Sessions keyed by the hash of their bearer token
+    sessions: RwLock<HashMap<String, OperatorSession>>,
+}
+
+impl RbacService {
+    /// Create an RBAC service with the given session lifetime
+    pub fn new(session_ttl_secs: u64) -> Self {
+        Self {
+            session_ttl: Duration::seconds(session_ttl_secs as i64),
+            operators: RwLock::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Create an operator account
+    pub async fn create_operator(
+        &self,
+        username: &str,
+        display_name: &str,
+        role: Role,
+        password: &str,
+    ) -> Result<Operator> {
+        if username.trim().is_empty() {
+            return Err(ComplianceError::validation("username", "must not be empty"));
+        }
+        if password.len() < MIN_PASSWORD_LENGTH {
+            return Err(ComplianceError::validation(
+                "password",
+                format!("must be at least {} characters", MIN_PASSWORD_LENGTH),
+            ));
+        }
+        
+        let mut operators = self.operators.write().await;
+        if operators.values().any(|r| r.operator.username == username) {
+            return Err(ComplianceError::validation("username", "already in use"));
+        }
+        
+        let operator = Operator {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            display_name: display_name.to_string(),
+            role,
+            active: true,
+            created_at: Utc::now(),
+        };
+        operators.insert(
+            operator.id,
+            OperatorRecord {
+                operator: operator.clone(),
+                password_hash: hash_password(password)?,
+            },
+        );
+        
+        Ok(operator)
+    }
+    
+    /// Get an operator
+    pub async fn get_operator(&self, operator_id: Uuid) -> Result<Operator> {
+        self.operators
+            .read()
+            .await
+            .get(&operator_id)
+            .map(|r| r.operator.clone())
+            .ok_or_else(|| ComplianceError::OperatorNotFound {
+                operator_id: operator_id.to_string(),
+            })
+    }
+    
+    /// List operators
+    pub async fn list_operators(&self) -> Vec<Operator> {
+        let mut operators: Vec<Operator> = self.operators.read().await.values().map(|r| r.operator.clone()).collect();
+        operators.sort_by(|a, b| a.username.cmp(&b.username));
+        operators
+    }
+    
+    /// Change an operator's role or active flag
+    ///
+    /// Deactivating an operator or changing their role ends their sessions.
+    pub async fn update_operator(&self, operator_id: Uuid, role: Option<Role>, active: Option<bool>) -> Result<Operator> {
+        let mut operators = self.operators.write().await;
+        let record = operators
+            .get_mut(&operator_id)
+            .ok_or_else(|| ComplianceError::OperatorNotFound {
+                operator_id: operator_id.to_string(),
+            })?;
+        
+        if let Some(role) = role {
+            record.operator.role = role;
+        }
+        if let Some(active) = active {
+            record.operator.active = active;
+        }
+        let operator = record.operator.clone();
+        drop(operators);
+        
+        self.sessions.write().await.retain(|_, s| s.operator_id != operator_id);
+        Ok(operator)
+    }
+    
+    /// Authenticate with a username and password, returning a bearer token
+    ///
+    /// Unknown and inactive usernames are checked against a dummy hash, so
+    /// they take as long to reject as a wrong password.
+    pub async fn login(&self, username: &str, password: &str) -> Result<(String, OperatorSession)> {
+        let operator = self
+            .operators
+            .read()
+            .await
+            .values()
+            .find(|r| r.operator.username == username && r.operator.active)
+            .map(|r| (r.operator.id, r.password_hash.clone()));
+        
+        // Argon2 takes long enough to stall a runtime worker
+        let password = password.to_string();
+        let hash = operator.as_ref().map_or_else(|| dummy_hash().to_string(), |(_, hash)| hash.clone());
+        let verified = tokio::task::spawn_blocking(move || verify_password(&password, &hash))
+            .await
+            .map_err(|e| ComplianceError::internal(format!("password verification worker failed: {}", e)))?;
+        let operator_id = match operator {
+            Some((operator_id, _)) if verified => operator_id,
+            _ => return Err(ComplianceError::InvalidCredentials),
+        };
+        
+        let now = Utc::now();
+        let token = hex::encode(rand::random::<[u8; 32]>());
+        let session = OperatorSession {
+            id: Uuid::new_v4(),
+            operator_id,
+            created_at: now,
+            expires_at: now + self.session_ttl,
+        };
+        
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, s| s.expires_at > now);
+        sessions.insert(token_hash(&token), session.clone());
+        
+        Ok((token, session))
+    }
+    
+    /// Resolve a bearer token to its active operator
+    pub async fn authenticate(&self, token: &str) -> Result<Operator> {
+        let operator_id = self
+            .sessions
+            .read()
+            .await
+            .get(&token_hash(token))
+            .filter(|s| s.expires_at > Utc::now())
+            .map(|s| s.operator_id)
+            .ok_or(ComplianceError::InvalidCredentials)?;
+        
+        let operator = self.get_operator(operator_id).await.map_err(|_| ComplianceError::InvalidCredentials)?;
+        if !operator.active {
+            return Err(ComplianceError::InvalidCredentials);
+        }
+        Ok(operator)
+    }
+    
+    /// End the session identified by a bearer token
+    pub async fn logout(&self, token: &str) {
+        self.sessions.write().await.remove(&token_hash(token));
+    }
+}
+
+fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>()).map_err(|e| ComplianceError::crypto(e.to_string()))?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| ComplianceError::crypto(e.to_string()))
+}
+
+/// Hash unknown usernames are verified against
+fn dummy_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_HASH.get_or_init(|| hash_password("dummy operator password").expect("hashing a fixed password"))
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+fn token_hash(token: &str) -> String {
+    blake3::hash(token.as_bytes()).to_hex().to_string()
+}
+
+/// Operator authenticated by a session bearer token
+pub struct OperatorAuth {
+    pub operator: Operator,
+    pub token: String,
+}
+
+impl OperatorAuth {
+    /// Fail unless the operator's role grants a permission
+    pub fn require(&self, permission: Permission) -> Result<()> {
+        if self.operator.role.grants(permission) {
+            Ok(())
+        } else {
+            Err(ComplianceError::PermissionDenied {
+                permission: format!("{:?}", permission),
+            })
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for OperatorAuth {
+    type Rejection = ComplianceError;
+    
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(ComplianceError::InvalidCredentials)?;
+        
+        let operator = state.operators.authenticate(token).await?;
+        Ok(Self {
+            operator,
+            token: token.to_string(),
+        })
+    }
+}
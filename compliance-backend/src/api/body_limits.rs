@@ -0,0 +1,114 @@
+//! Per-route request body limits
+//!
+//! Each request is held to the body size and media types its route accepts
+//! in `server.body_limits`, looked up by method and matched path. A declared
+//! `Content-Length` over the limit is rejected with 413 before the body is
+//! read, and a body without one is cut off as soon as it passes the limit. A
+//! body of a media type the route does not accept is rejected with 415. Both
+//! responses name the limit or the accepted types.
+
+use super::AppState;
+use crate::config::BodyLimitsConfig;
+use crate::ComplianceError;
+use axum::body::Body;
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, HeaderMap};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http_body_util::Limited;
+
+/// Body limit of the route a request matched, available as an extension
+#[derive(Debug, Clone)]
+pub struct RouteLimit {
+    /// Method and path of the route, e.g. `POST /v1/accounts`
+    pub endpoint: String,
+    pub max_bytes: usize,
+    pub content_types: Vec<String>,
+}
+
+impl RouteLimit {
+    /// Limit of the route `request` matched
+    ///
+    /// A request matching no route is held to the defaults for its method.
+    pub fn of(config: &BodyLimitsConfig, request: &Request) -> Self {
+        let path = match request.extensions().get::<MatchedPath>() {
+            Some(matched) => matched.as_str(),
+            None => request.uri().path(),
+        };
+        Self::new(config, request.method().as_str(), path)
+    }
+    
+    /// Limit of the route with `method` and `path`
+    pub fn new(config: &BodyLimitsConfig, method: &str, path: &str) -> Self {
+        let (max_bytes, content_types) = config.resolve(method, path);
+        Self {
+            endpoint: format!("{} {}", method, path),
+            max_bytes,
+            content_types: content_types.to_vec(),
+        }
+    }
+    
+    /// Error for a body over the limit
+    pub fn too_large(&self) -> ComplianceError {
+        ComplianceError::PayloadTooLarge {
+            endpoint: self.endpoint.clone(),
+            limit: self.max_bytes,
+        }
+    }
+    
+    /// Whether the route accepts bodies of `media_type`
+    pub fn accepts(&self, media_type: &str) -> bool {
+        self.content_types.iter().any(|accepted| {
+            accepted == media_type
+                || (accepted == "application/json"
+                    && media_type.starts_with("application/")
+                    && media_type.ends_with("+json"))
+        })
+    }
+}
+
+/// Media type of the body, without parameters and lowercased
+pub fn media_type(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase())
+        .filter(|mime| !mime.is_empty())
+}
+
+/// Declared length of the body, if any
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Reject bodies over the route's limit or of a media type it does not accept
+pub async fn enforce_body_limits(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let limit = RouteLimit::of(&state.config.server.body_limits, &request);
+    let headers = request.headers();
+    let declared = content_length(headers);
+    if declared.is_some_and(|length| length > limit.max_bytes as u64) {
+        return limit.too_large().into_response();
+    }
+    
+    let has_body = declared.is_some_and(|length| length > 0) || headers.contains_key(header::TRANSFER_ENCODING);
+    if has_body {
+        let content_type = media_type(headers);
+        if !content_type.as_deref().is_some_and(|mime| limit.accepts(mime)) {
+            return ComplianceError::UnsupportedMediaType {
+                endpoint: limit.endpoint,
+                content_type: content_type.unwrap_or_else(|| "unspecified".to_string()),
+                accepted: limit.content_types,
+            }
+            .into_response();
+        }
+    }
+    
+    let max_bytes = limit.max_bytes;
+    let (mut parts, body) = request.into_parts();
+    parts.extensions.insert(limit);
+    next.run(Request::from_parts(parts, Body::new(Limited::new(body, max_bytes)))).await
+}
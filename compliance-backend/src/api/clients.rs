@@ -0,0 +1,260 @@
+//! Business client administration handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::clients::check_webhook_url;
+use crate::compliance::localization::normalize_locale;
+use crate::compliance::residency;
+use crate::types::{BusinessClient, ClientEnvironment, ComplianceLevel, DataRegion, WebhookEncryptionKey};
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Request body for creating a business client
+#[derive(Debug, Deserialize)]
+pub struct CreateClientRequest {
+    pub name: String,
+    pub webhook_url: Option<String>,
+    pub compliance_level: ComplianceLevel,
+    /// Region the client's PII must stay in
+    #[serde(default)]
+    pub region: Option<DataRegion>,
+}
+
+impl Validate for CreateClientRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.name.trim().is_empty(), "name", "must not be empty");
+        v.absorb(check_webhook_url(self.webhook_url.as_deref()));
+    }
+}
+
+/// `POST /v1/admin/clients`
+///
+/// The response is the only time the generated API keys are returned. The
+/// client gets a sandbox key when the sandbox is enabled.
+pub async fn create_client(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Valid(request): Valid<CreateClientRequest>,
+) -> Result<Json<BusinessClient>> {
+    auth.require(Permission::ManageClients)?;
+    if let Some(region) = request.region {
+        residency::ensure_supported(region, &state.config.database, &state.live_config.compliance().residency)?;
+    }
+    let client = state
+        .clients
+        .create(
+            &request.name,
+            request.webhook_url,
+            request.compliance_level,
+            request.region,
+            state.config.sandbox.enabled,
+        )
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "client.created",
+            None,
+            serde_json::json!({ "client_id": client.id, "name": client.name, "region": client.region }),
+        )
+        .await;
+    
+    Ok(Json(client))
+}
+
+/// Query parameters for rotating an API key
+#[derive(Debug, Deserialize)]
+pub struct RotateKeyQuery {
+    /// Environment whose key is rotated; production when absent
+    pub environment: Option<ClientEnvironment>,
+}
+
+/// `POST /v1/admin/clients/{client_id}/rotate-key`
+pub async fn rotate_api_key(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<Uuid>,
+    Query(query): Query<RotateKeyQuery>,
+) -> Result<Json<BusinessClient>> {
+    auth.require(Permission::ManageClients)?;
+    let environment = query.environment.unwrap_or(ClientEnvironment::Production);
+    let client = state.clients.rotate_api_key(client_id, environment).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "client.api_key_rotated",
+            None,
+            serde_json::json!({ "client_id": client.id, "environment": environment }),
+        )
+        .await;
+    
+    Ok(Json(client))
+}
+
+/// Request body for updating a client's sandbox settings
+#[derive(Debug, Deserialize)]
+pub struct UpdateSandboxRequest {
+    pub webhook_url: Option<String>,
+    pub compliance_level: ComplianceLevel,
+}
+
+impl Validate for UpdateSandboxRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.absorb(check_webhook_url(self.webhook_url.as_deref()));
+    }
+}
+
+/// `PUT /v1/admin/clients/{client_id}/sandbox`
+pub async fn update_sandbox(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<Uuid>,
+    Valid(request): Valid<UpdateSandboxRequest>,
+) -> Result<Json<BusinessClient>> {
+    auth.require(Permission::ManageClients)?;
+    let client = state
+        .clients
+        .update_sandbox(client_id, request.webhook_url, request.compliance_level)
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "client.sandbox_updated",
+            None,
+            serde_json::json!({
+                "client_id": client.id,
+                "webhook_url": client.sandbox.as_ref().and_then(|sandbox| sandbox.webhook_url.as_ref()),
+                "compliance_level": client.sandbox.as_ref().map(|sandbox| &sandbox.compliance_level),
+            }),
+        )
+        .await;
+    
+    Ok(Json(client))
+}
+
+/// `POST /v1/admin/clients/{client_id}/promote`
+///
+/// Copies the client's sandbox settings to production, once they have been
+/// tried against sandbox traffic.
+pub async fn promote_sandbox(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<Uuid>,
+) -> Result<Json<BusinessClient>> {
+    auth.require(Permission::ManageClients)?;
+    let previous = state.clients.get(client_id).await?;
+    let client = state.clients.promote_sandbox(client_id).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "client.sandbox_promoted",
+            None,
+            serde_json::json!({
+                "client_id": client.id,
+                "previous": { "webhook_url": previous.webhook_url, "compliance_level": previous.compliance_level },
+                "promoted": { "webhook_url": client.webhook_url, "compliance_level": client.compliance_level },
+            }),
+        )
+        .await;
+    
+    Ok(Json(client))
+}
+
+/// Request body for setting a webhook endpoint's encryption key
+#[derive(Debug, Deserialize)]
+pub struct SetWebhookEncryptionRequest {
+    /// Environment whose webhook endpoint is configured; production when absent
+    pub environment: Option<ClientEnvironment>,
+    
+    /// X25519 public key to encrypt deliveries to, or `null` to send them unencrypted
+    pub key: Option<WebhookEncryptionKey>,
+}
+
+impl Validate for SetWebhookEncryptionRequest {}
+
+/// `PUT /v1/admin/clients/{client_id}/webhook-encryption`
+///
+/// Deliveries to the endpoint are sent as a JWE encrypted to the key, still
+/// signed with the webhook secret.
+pub async fn set_webhook_encryption(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<Uuid>,
+    Valid(request): Valid<SetWebhookEncryptionRequest>,
+) -> Result<Json<BusinessClient>> {
+    auth.require(Permission::ManageClients)?;
+    let environment = request.environment.unwrap_or(ClientEnvironment::Production);
+    let kid = request.key.as_ref().map(|key| key.kid.clone());
+    let client = state
+        .clients
+        .set_webhook_encryption(client_id, environment, request.key)
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "client.webhook_encryption_updated",
+            None,
+            serde_json::json!({ "client_id": client.id, "environment": environment, "kid": kid }),
+        )
+        .await;
+    
+    Ok(Json(client))
+}
+
+/// Request body for setting a client's display locales
+#[derive(Debug, Deserialize)]
+pub struct SetLocalesRequest {
+    /// Locale tags, most preferred first; empty for the deployment default
+    pub locales: Vec<String>,
+}
+
+impl Validate for SetLocalesRequest {
+    fn validate(&self, v: &mut Violations) {
+        for (i, locale) in self.locales.iter().enumerate() {
+            v.ensure(
+                normalize_locale(locale).is_ok(),
+                &format!("locales[{}]", i),
+                format!("{} is not a locale tag", locale),
+            );
+        }
+    }
+}
+
+/// `PUT /v1/admin/clients/{client_id}/locales`
+///
+/// Webhooks to the client carry display text in each of these locales.
+pub async fn set_locales(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<Uuid>,
+    Valid(request): Valid<SetLocalesRequest>,
+) -> Result<Json<BusinessClient>> {
+    auth.require(Permission::ManageClients)?;
+    let client = state.clients.set_locales(client_id, request.locales).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "client.locales_updated",
+            None,
+            serde_json::json!({ "client_id": client.id, "locales": client.locales }),
+        )
+        .await;
+    
+    Ok(Json(client))
+}
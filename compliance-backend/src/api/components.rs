@@ -0,0 +1,168 @@
+//! Account component migration handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::tags::client_filter;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::account_components::foreign::{self, KycGate, GATE_PROCEDURE, KYC_STATUS_PROCEDURE};
+use crate::compliance::account_components::migration::{
+    AccountComponentState, ComponentKind, ComponentSummary, MigrationPlan, MigrationStatus,
+};
+use crate::compliance::tags::TaggedKind;
+use crate::types::{AccountId, ComplianceLevel};
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for listing accounts' components
+#[derive(Debug, Deserialize)]
+pub struct ListComponentsQuery {
+    pub kind: Option<ComponentKind>,
+    pub status: Option<MigrationStatus>,
+    /// Client whose account tags `tag` filters by
+    pub client_id: Option<Uuid>,
+    pub tag: Option<String>,
+}
+
+/// Query parameters for generating a KYC gate
+#[derive(Debug, Deserialize)]
+pub struct KycGateQuery {
+    /// Minimum compliance level a counterparty must hold; `Basic` when omitted
+    pub min_level: Option<ComplianceLevel>,
+}
+
+/// Generated KYC gate component
+#[derive(Debug, Serialize)]
+pub struct KycGateCode {
+    pub min_level: ComplianceLevel,
+    /// Component assembly to add to the dApp account
+    pub masm: String,
+    /// Procedure the dApp executes with the counterparty's account id
+    pub procedure: &'static str,
+    /// MAST root of the KYC status procedure the gate reads
+    pub kyc_status_root: String,
+}
+
+/// Request body reporting a failed migration
+#[derive(Debug, Deserialize)]
+pub struct MigrationFailureRequest {
+    pub error: String,
+}
+
+impl Validate for MigrationFailureRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.error.trim().is_empty(), "error", "must not be empty");
+    }
+}
+
+/// `GET /v1/components/kyc-gate`
+///
+/// Account component a dApp adds to its account to check a counterparty's
+/// on-chain KYC itself through foreign procedure invocation, matching the
+/// KYC component this deployment issues.
+pub async fn kyc_gate(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Query(query): Query<KycGateQuery>,
+) -> Result<Json<KycGateCode>> {
+    let gate = KycGate {
+        min_level: query.min_level.unwrap_or(ComplianceLevel::Basic),
+    };
+    let templates = state.component_migrations.templates();
+    let root = foreign::procedure_root(ComponentKind::Kyc, templates, KYC_STATUS_PROCEDURE)?;
+    
+    Ok(Json(KycGateCode {
+        masm: gate.render(root),
+        procedure: GATE_PROCEDURE,
+        kyc_status_root: root.to_hex(),
+        min_level: gate.min_level,
+    }))
+}
+
+/// `GET /v1/admin/components`
+///
+/// Migration dashboard: per component, the current release and how many
+/// synced accounts run each release and are in each migration status.
+pub async fn summary(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<Vec<ComponentSummary>>> {
+    auth.require(Permission::ManageComponents)?;
+    Ok(Json(state.component_migrations.summary().await))
+}
+
+/// `GET /v1/admin/components/accounts`
+pub async fn list_accounts(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<ListComponentsQuery>,
+) -> Result<Json<Vec<AccountComponentState>>> {
+    auth.require(Permission::ManageComponents)?;
+    let mut accounts = state.component_migrations.list(query.kind, query.status).await;
+    if let Some((client_id, filter)) = client_filter(query.client_id, query.tag.as_deref())? {
+        state
+            .tags
+            .retain(client_id, TaggedKind::Account, Some(&filter), &mut accounts, |account| {
+                account.account_id.to_string()
+            })
+            .await;
+    }
+    Ok(Json(accounts))
+}
+
+/// `POST /v1/admin/components/accounts/{id}/{kind}/migration`
+///
+/// Generates the transaction script migrating the account's component
+/// storage to the current release. The migration is confirmed when the next
+/// sync sees the account on the current release.
+pub async fn plan_migration(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path((account_id, kind)): Path<(AccountId, ComponentKind)>,
+) -> Result<Json<MigrationPlan>> {
+    auth.require(Permission::ManageComponents)?;
+    let plan = state.component_migrations.plan(&account_id, kind).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "component.migration_planned",
+            Some(&account_id),
+            serde_json::json!({
+                "component": kind,
+                "from_version": plan.from_version,
+                "to_version": plan.to_version,
+            }),
+        )
+        .await;
+    
+    Ok(Json(plan))
+}
+
+/// `POST /v1/admin/components/accounts/{id}/{kind}/migration/failure`
+pub async fn report_failure(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path((account_id, kind)): Path<(AccountId, ComponentKind)>,
+    Valid(request): Valid<MigrationFailureRequest>,
+) -> Result<StatusCode> {
+    auth.require(Permission::ManageComponents)?;
+    state
+        .component_migrations
+        .mark_failed(&account_id, kind, request.error.clone())
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "component.migration_failed",
+            Some(&account_id),
+            serde_json::json!({ "component": kind, "error": request.error }),
+        )
+        .await;
+    
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,78 @@
+//! Response compression and streaming
+//!
+//! Responses are compressed in the encoding negotiated from the client's
+//! `Accept-Encoding` header, unless they are small or already compressed,
+//! such as images and custody archives. Server-sent events are never
+//! compressed, so each event reaches the client as it is written.
+//!
+//! Large bodies, such as proof envelopes carrying multi-megabyte STARK proofs
+//! and exports, are sent as a stream of chunks rather than one write. The
+//! client starts receiving, and its read timeout restarts, with the first
+//! chunk instead of after the whole body.
+
+use crate::compliance::custody::CUSTODY_CONTENT_TYPE;
+use crate::config::ResponseConfig;
+use crate::Result;
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use std::convert::Infallible;
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+
+/// Marks a response streamed in chunks, available as a response extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streamed {
+    pub chunk_bytes: usize,
+}
+
+impl Streamed {
+    /// Body sending `bytes` in chunks of at most `chunk_bytes`
+    pub fn body(self, bytes: Bytes) -> Body {
+        let chunk_bytes = self.chunk_bytes;
+        let chunks = (0..bytes.len())
+            .step_by(chunk_bytes)
+            .map(move |start| Ok::<_, Infallible>(bytes.slice(start..bytes.len().min(start + chunk_bytes))));
+        Body::from_stream(futures::stream::iter(chunks))
+    }
+}
+
+/// Compression of responses as configured
+///
+/// With compression disabled no encoding is offered, so every response is
+/// sent as is.
+pub fn compression_layer(config: &ResponseConfig) -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(config.min_compress_bytes)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE)
+        .and(NotForContentType::const_new(CUSTODY_CONTENT_TYPE));
+    CompressionLayer::new()
+        .gzip(config.compression && config.gzip)
+        .br(config.compression && config.br)
+        .compress_when(predicate)
+}
+
+/// Response of `content_type` carrying `bytes`, streamed in chunks when
+/// larger than `stream_threshold_bytes`
+pub fn sized(config: &ResponseConfig, content_type: &'static str, bytes: Vec<u8>) -> Response {
+    let bytes = Bytes::from(bytes);
+    let mut response = if bytes.len() > config.stream_threshold_bytes {
+        let streamed = Streamed {
+            chunk_bytes: config.stream_chunk_bytes,
+        };
+        let mut response = streamed.body(bytes).into_response();
+        response.extensions_mut().insert(streamed);
+        response
+    } else {
+        bytes.into_response()
+    };
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+/// JSON response of `value`, streamed in chunks when large
+pub fn json<T: Serialize>(config: &ResponseConfig, value: &T) -> Result<Response> {
+    Ok(sized(config, "application/json", serde_json::to_vec(value)?))
+}
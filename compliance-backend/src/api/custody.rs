@@ -0,0 +1,49 @@
+//! Chain-of-custody export handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::compression;
+use super::AppState;
+use crate::compliance::custody::{self, CustodySources, CUSTODY_CONTENT_TYPE};
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+
+/// `POST /v1/admin/accounts/{id}/custody-export`
+///
+/// Builds the account's signed chain-of-custody archive for handing to a
+/// regulator. The export itself is audited, so the next archive of the
+/// account records who exported it before. Large archives are streamed in
+/// chunks; being compressed already, they are sent without content encoding.
+pub async fn export_custody(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Response> {
+    auth.require(Permission::GenerateReports)?;
+    let sources = CustodySources {
+        compliance: &state.compliance,
+        velocity: &state.velocity,
+        outbox: state.outbox.as_ref(),
+    };
+    let archive = custody::export(sources, &account_id, &state.config.security.deployment_id, &state.signer).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "custody.exported",
+            Some(&account_id),
+            serde_json::json!({
+                "export_id": archive.manifest.export_id,
+                "files": archive.manifest.files.len(),
+                "audit_head_hash": archive.manifest.audit_head_hash,
+            }),
+        )
+        .await;
+    
+    let filename = format!("attachment; filename=\"custody-{}.zip\"", archive.manifest.export_id);
+    let response = compression::sized(&state.config.server.responses, CUSTODY_CONTENT_TYPE, archive.bytes);
+    Ok(([(header::CONTENT_DISPOSITION, filename)], response).into_response())
+}
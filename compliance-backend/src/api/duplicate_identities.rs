@@ -0,0 +1,151 @@
+//! Duplicate identity and fraud case handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::tags::client_filter;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::duplicate_identities::{
+    DuplicateCheck, FraudCase, FraudCaseResolution, FraudCaseStatus, IdentitySubmission,
+};
+use crate::compliance::tags::TaggedKind;
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+impl Validate for IdentitySubmission {
+    fn validate(&self, v: &mut Violations) {
+        v.absorb(IdentitySubmission::validate(self));
+    }
+}
+
+/// `PUT /v1/accounts/{id}/identity`
+///
+/// Fingerprints the identity attributes the account was verified with and
+/// reports other accounts that appear to be the same person. Accounts of
+/// other clients are only counted, never identified.
+pub async fn submit_identity(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(submission): Valid<IdentitySubmission>,
+) -> Result<Json<DuplicateCheck>> {
+    let check = state.duplicate_identities.submit(client.id, &account_id, &submission).await?;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "identity.fingerprinted",
+            Some(&account_id),
+            serde_json::json!({
+                "cross_client_consent": submission.cross_client_consent,
+                "duplicates": check.duplicates.len(),
+                "other_client_matches": check.other_client_matches,
+                "case_id": check.case_id,
+            }),
+        )
+        .await;
+    Ok(Json(check))
+}
+
+/// `DELETE /v1/accounts/{id}/identity`
+///
+/// Drops the account's fingerprints, as when its end user withdraws consent.
+pub async fn forget_identity(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> StatusCode {
+    if !state.duplicate_identities.forget(client.id, &account_id).await {
+        return StatusCode::NOT_FOUND;
+    }
+    state
+        .audit
+        .record(&client.id.to_string(), "identity.forgotten", Some(&account_id), serde_json::Value::Null)
+        .await;
+    StatusCode::NO_CONTENT
+}
+
+/// Query parameters for listing fraud cases
+#[derive(Debug, Deserialize)]
+pub struct FraudCaseQuery {
+    /// Cases in this status (defaults to `open`)
+    pub status: Option<FraudCaseStatus>,
+    /// Client whose tags `tag` filters by
+    pub client_id: Option<Uuid>,
+    pub tag: Option<String>,
+}
+
+/// `GET /v1/admin/fraud-cases`
+pub async fn list_cases(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<FraudCaseQuery>,
+) -> Result<Json<Vec<FraudCase>>> {
+    auth.require(Permission::ViewCases)?;
+    let status = query.status.unwrap_or(FraudCaseStatus::Open);
+    let mut cases = state.duplicate_identities.cases(Some(status)).await;
+    if let Some((client_id, filter)) = client_filter(query.client_id, query.tag.as_deref())? {
+        state
+            .tags
+            .retain(client_id, TaggedKind::Case, Some(&filter), &mut cases, |case| case.id.to_string())
+            .await;
+    }
+    Ok(Json(cases))
+}
+
+/// `GET /v1/admin/fraud-cases/{case_id}`
+pub async fn get_case(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(case_id): Path<Uuid>,
+) -> Result<Json<FraudCase>> {
+    auth.require(Permission::ViewCases)?;
+    Ok(Json(state.duplicate_identities.case(case_id).await?))
+}
+
+/// Request body for resolving a fraud case
+#[derive(Debug, Deserialize)]
+pub struct ResolveCaseRequest {
+    pub resolution: FraudCaseResolution,
+    pub notes: Option<String>,
+}
+
+impl Validate for ResolveCaseRequest {}
+
+/// `POST /v1/admin/fraud-cases/{case_id}/resolve`
+///
+/// Confirms or dismisses a case. A dismissed case is not reopened when the
+/// same accounts match again.
+pub async fn resolve_case(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(case_id): Path<Uuid>,
+    Valid(request): Valid<ResolveCaseRequest>,
+) -> Result<Json<FraudCase>> {
+    auth.require(Permission::ManageCases)?;
+    let case = state
+        .duplicate_identities
+        .resolve(case_id, request.resolution, &auth.operator.username, request.notes)
+        .await?;
+    for subject in &case.subjects {
+        state
+            .audit
+            .record(
+                &auth.operator.username,
+                "fraud_case.resolved",
+                Some(&subject.account_id),
+                serde_json::json!({
+                    "case_id": case.id,
+                    "client_id": subject.client_id,
+                    "resolution": request.resolution,
+                }),
+            )
+            .await;
+    }
+    Ok(Json(case))
+}
@@ -0,0 +1,78 @@
+//! Attestation epoch handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::AppState;
+use crate::compliance::epochs::{AnchoredEpoch, EpochInclusionProof};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::Json;
+use uuid::Uuid;
+
+/// `GET /v1/epochs`
+pub async fn list_epochs(State(state): State<AppState>, ClientAuth(_client): ClientAuth) -> Result<Json<Vec<AnchoredEpoch>>> {
+    Ok(Json(state.compliance.epochs.list().await))
+}
+
+/// `GET /v1/epochs/{epoch}`
+pub async fn get_epoch(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(epoch): Path<u64>,
+) -> Result<Json<AnchoredEpoch>> {
+    Ok(Json(state.compliance.epochs.get(epoch).await?))
+}
+
+/// `GET /v1/attestations/{attestation_id}/inclusion-proof`
+///
+/// Merkle path proving the attestation is in the epoch root anchored
+/// on-chain. Attestations issued in the open epoch have no proof until it
+/// closes.
+pub async fn inclusion_proof(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(attestation_id): Path<Uuid>,
+) -> Result<Json<EpochInclusionProof>> {
+    Ok(Json(state.compliance.epochs.inclusion_proof(attestation_id).await?))
+}
+
+/// `POST /v1/admin/epochs/close`
+///
+/// Anchors the open epoch now instead of when it ends.
+pub async fn close_epoch(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<Option<AnchoredEpoch>>> {
+    auth.require(Permission::ManageComponents)?;
+    let account = state
+        .live_config
+        .compliance()
+        .attestation
+        .epochs
+        .anchor_account_id
+        .clone()
+        .ok_or_else(|| ComplianceError::validation("epochs.anchor_account_id", "no anchor account is configured"))?;
+    let anchor_account = AccountId::parse(&account)?;
+    let closed = state
+        .compliance
+        .epochs
+        .close_epoch(&state.compliance, &anchor_account, state.compliance.clock.now())
+        .await?;
+    
+    if let Some(epoch) = &closed {
+        state
+            .audit
+            .record(
+                &auth.operator.username,
+                "epoch.anchored",
+                None,
+                serde_json::json!({
+                    "epoch": epoch.epoch,
+                    "root": epoch.root,
+                    "attestations": epoch.attestations,
+                    "transaction_id": epoch.transaction_id,
+                }),
+            )
+            .await;
+    }
+    
+    Ok(Json(closed))
+}
@@ -0,0 +1,197 @@
+//! Streaming compliance event handlers (SSE and WebSocket)
+
+use super::auth::ClientAuth;
+use super::AppState;
+use crate::audit::AuditLog;
+use crate::compliance::event_feed::{decode_resume_token, FeedEvent, FeedSubscription};
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Header browsers send when reconnecting an event source
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Minimum interval between refreshes of a client's account scope
+const SCOPE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Query parameters for event streams
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    /// Resume after the event carrying this token
+    pub resume_token: Option<String>,
+}
+
+/// Message sent over the WebSocket feed
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamMessage<'a> {
+    Event { resume_token: String, event: &'a FeedEvent },
+    /// The subscriber fell behind; reconnect with the last resume token
+    Lagged,
+}
+
+enum FeedItem {
+    Event(FeedEvent),
+    Lagged,
+}
+
+/// Accounts a client receives events for: those it has checked or
+/// authorized transactions for
+///
+/// The event for a client's first check of an account may precede the audit
+/// entry that brings the account into scope; the client already holds that
+/// result from the check response.
+struct ClientScope {
+    actor: String,
+    audit: Arc<AuditLog>,
+    accounts: HashSet<AccountId>,
+    refreshed_at: Instant,
+}
+
+impl ClientScope {
+    async fn new(actor: String, audit: Arc<AuditLog>) -> Self {
+        let mut scope = Self {
+            actor,
+            audit,
+            accounts: HashSet::new(),
+            refreshed_at: Instant::now(),
+        };
+        scope.refresh().await;
+        scope
+    }
+    
+    async fn allows(&mut self, account_id: &AccountId) -> bool {
+        if !self.accounts.contains(account_id) && self.refreshed_at.elapsed() >= SCOPE_REFRESH_INTERVAL {
+            self.refresh().await;
+        }
+        self.accounts.contains(account_id)
+    }
+    
+    async fn refresh(&mut self) {
+        match self.audit.accounts_touched_by(&self.actor, DateTime::<Utc>::MIN_UTC, Utc::now()).await {
+            Ok(accounts) => self.accounts = accounts.into_iter().collect(),
+            Err(e) => tracing::warn!(actor = %self.actor, error = %e, "failed to refresh event stream scope"),
+        }
+        self.refreshed_at = Instant::now();
+    }
+}
+
+/// Resume position from `Last-Event-ID` or the `resume_token` parameter
+fn resume_after(headers: &HeaderMap, query: &StreamQuery) -> Result<Option<u64>> {
+    let token = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or(query.resume_token.as_deref());
+    token.map(decode_resume_token).transpose()
+}
+
+/// Events visible to the client, backlog first, ending after a lag notice
+fn client_events(subscription: FeedSubscription, scope: ClientScope) -> impl Stream<Item = FeedItem> {
+    let FeedSubscription { backlog, live } = subscription;
+    futures::stream::unfold(
+        (backlog.into_iter(), live, scope, false),
+        |(mut backlog, mut live, mut scope, lagged)| async move {
+            if lagged {
+                return None;
+            }
+            loop {
+                let event = match backlog.next() {
+                    Some(event) => event,
+                    None => match live.recv().await {
+                        Ok(event) => event,
+                        Err(RecvError::Lagged(_)) => return Some((FeedItem::Lagged, (backlog, live, scope, true))),
+                        Err(RecvError::Closed) => return None,
+                    },
+                };
+                if scope.allows(&event.event.account_id).await {
+                    return Some((FeedItem::Event(event), (backlog, live, scope, false)));
+                }
+            }
+        },
+    )
+}
+
+async fn subscribe(
+    state: &AppState,
+    client_id: String,
+    headers: &HeaderMap,
+    query: &StreamQuery,
+) -> Result<impl Stream<Item = FeedItem>> {
+    let after = resume_after(headers, query)?;
+    let subscription = state.compliance.events.feed().subscribe(after).await?;
+    let scope = ClientScope::new(client_id, state.audit.clone()).await;
+    Ok(client_events(subscription, scope))
+}
+
+/// `GET /v1/events/stream`
+///
+/// Server-sent events carrying attestation lifecycle events. Each event's id
+/// is its resume token, so reconnecting clients resume via `Last-Event-ID`.
+pub async fn stream_sse(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    headers: HeaderMap,
+    Query(query): Query<StreamQuery>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>> {
+    let events = subscribe(&state, client.id.to_string(), &headers, &query).await?;
+    let stream = events.map(|item| {
+        Ok(match item {
+            FeedItem::Event(event) => Event::default()
+                .id(event.resume_token())
+                .event("compliance_event")
+                .data(serde_json::to_string(&event).unwrap_or_default()),
+            FeedItem::Lagged => Event::default().event("lagged").data("reconnect with the last event id"),
+        })
+    });
+    
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// `GET /v1/events/ws`
+///
+/// WebSocket carrying the same events as the SSE stream, one JSON message each.
+pub async fn stream_ws(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    headers: HeaderMap,
+    Query(query): Query<StreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response> {
+    let events = subscribe(&state, client.id.to_string(), &headers, &query).await?;
+    Ok(ws.on_upgrade(move |socket| forward(socket, events)).into_response())
+}
+
+async fn forward(mut socket: WebSocket, events: impl Stream<Item = FeedItem>) {
+    let mut events = std::pin::pin!(events);
+    loop {
+        tokio::select! {
+            item = events.next() => {
+                let Some(item) = item else { break };
+                let message = match &item {
+                    FeedItem::Event(event) => StreamMessage::Event { resume_token: event.resume_token(), event },
+                    FeedItem::Lagged => StreamMessage::Lagged,
+                };
+                let Ok(text) = serde_json::to_string(&message) else { continue };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
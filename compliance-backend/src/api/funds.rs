@@ -0,0 +1,131 @@
+//! Source-of-funds and source-of-wealth declaration API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::source_of_funds::{DeclarationStatus, DeclarationSubmission, FundsDeclaration, ReviewDecision};
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+impl Validate for DeclarationSubmission {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.sources.is_empty(), "sources", "at least one source is required");
+        for (i, source) in self.sources.iter().enumerate() {
+            v.ensure(
+                !source.description.trim().is_empty(),
+                &format!("sources[{}].description", i),
+                "must not be empty",
+            );
+        }
+        let itemized: u64 = self.sources.iter().filter_map(|s| s.amount).sum();
+        v.ensure(itemized <= self.total_amount, "sources", "itemized amounts exceed the declared total");
+        v.ensure(
+            self.currency.len() == 3 && self.currency.bytes().all(|b| b.is_ascii_alphabetic()),
+            "currency",
+            "must be an ISO 4217 currency code",
+        );
+    }
+}
+
+/// `POST /v1/accounts/{id}/funds-declarations`
+///
+/// Submits a declaration with references to supporting documents already
+/// uploaded to the encrypted document vault. Any open declaration of the same
+/// kind is superseded.
+pub async fn submit_declaration(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(submission): Valid<DeclarationSubmission>,
+) -> Result<Json<FundsDeclaration>> {
+    let declaration = state.compliance.funds.submit(client.id, &account_id, submission).await?;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "funds_declaration.submitted",
+            Some(&account_id),
+            serde_json::json!({
+                "declaration_id": declaration.id,
+                "kind": declaration.kind,
+                "documents": declaration.supporting_documents.len(),
+            }),
+        )
+        .await;
+    
+    Ok(Json(declaration))
+}
+
+/// `GET /v1/accounts/{id}/funds-declarations`
+pub async fn list_declarations(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Json<Vec<FundsDeclaration>> {
+    Json(state.compliance.funds.for_account(&account_id).await)
+}
+
+/// Query parameters for the review queue
+#[derive(Debug, Deserialize)]
+pub struct ReviewQueueQuery {
+    /// Declarations in this status (defaults to `submitted`)
+    pub status: Option<DeclarationStatus>,
+}
+
+/// `GET /v1/admin/funds-declarations`
+pub async fn review_queue(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<ReviewQueueQuery>,
+) -> Result<Json<Vec<FundsDeclaration>>> {
+    auth.require(Permission::ViewCases)?;
+    let status = query.status.unwrap_or(DeclarationStatus::Submitted);
+    Ok(Json(state.compliance.funds.with_status(status).await))
+}
+
+/// Request body for reviewing a declaration
+#[derive(Debug, Deserialize)]
+pub struct ReviewRequest {
+    pub decision: ReviewDecision,
+    pub notes: Option<String>,
+}
+
+impl Validate for ReviewRequest {}
+
+/// `POST /v1/admin/funds-declarations/{declaration_id}/review`
+///
+/// Moves a declaration through review. A verified declaration counts towards
+/// `Enhanced` and `InstitutionalGrade` evaluation until it lapses.
+pub async fn review_declaration(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(declaration_id): Path<Uuid>,
+    Valid(request): Valid<ReviewRequest>,
+) -> Result<Json<FundsDeclaration>> {
+    auth.require(Permission::ManageCases)?;
+    let declaration = state
+        .compliance
+        .funds
+        .review(declaration_id, request.decision, &auth.operator.username, request.notes)
+        .await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "funds_declaration.reviewed",
+            Some(&declaration.account_id),
+            serde_json::json!({
+                "declaration_id": declaration.id,
+                "decision": request.decision,
+                "status": declaration.status,
+            }),
+        )
+        .await;
+    
+    Ok(Json(declaration))
+}
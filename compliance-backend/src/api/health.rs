@@ -0,0 +1,170 @@
+//! Provider and leader health and metrics handlers
+
+use super::response_sizes::RouteSizeStats;
+use super::AppState;
+use crate::compliance::breaker::{BreakerState, BreakerStatus};
+use crate::leader::LockHealth;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Health of the external providers
+#[derive(Debug, Serialize)]
+pub struct ProviderHealth {
+    /// `ok` when every breaker is closed, `degraded` otherwise
+    pub status: &'static str,
+    pub providers: Vec<BreakerStatus>,
+    /// Accounts waiting for a provider to recover
+    pub retry_queue_depth: usize,
+}
+
+/// `GET /v1/health/providers`
+pub async fn provider_health(State(state): State<AppState>) -> Json<ProviderHealth> {
+    let breakers = &state.compliance.breakers;
+    let providers = breakers.statuses();
+    let degraded = providers.iter().any(|p| p.state != BreakerState::Closed);
+    
+    Json(ProviderHealth {
+        status: if degraded { "degraded" } else { "ok" },
+        providers,
+        retry_queue_depth: breakers.retry_queue_depth(),
+    })
+}
+
+/// Leader leases held or sought by this instance
+#[derive(Debug, Serialize)]
+pub struct LeaderHealth {
+    /// Whether leader election is enabled; every instance runs all the work
+    /// when it is not
+    pub enabled: bool,
+    pub instance_id: Option<String>,
+    pub locks: Vec<LockHealth>,
+}
+
+/// `GET /v1/health/leader`
+pub async fn leader_health(State(state): State<AppState>) -> Json<LeaderHealth> {
+    Json(match &state.leader {
+        Some(leader) => LeaderHealth {
+            enabled: true,
+            instance_id: Some(leader.instance_id().to_string()),
+            locks: leader.health(),
+        },
+        None => LeaderHealth {
+            enabled: false,
+            instance_id: None,
+            locks: vec![],
+        },
+    })
+}
+
+/// `GET /metrics`
+///
+/// Exposes breaker, proving queue, verification pool, leader lease, and
+/// response size state in the Prometheus text format.
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let breakers = &state.compliance.breakers;
+    let statuses = breakers.statuses();
+    let mut out = String::new();
+    
+    out.push_str("# HELP provider_breaker_state Circuit breaker state (0 closed, 1 half-open, 2 open)\n");
+    out.push_str("# TYPE provider_breaker_state gauge\n");
+    for status in &statuses {
+        let value = match status.state {
+            BreakerState::Closed => 0,
+            BreakerState::HalfOpen => 1,
+            BreakerState::Open => 2,
+        };
+        let _ = writeln!(out, "provider_breaker_state{{provider=\"{}\"}} {}", status.provider.name(), value);
+    }
+    
+    let counters: [(&str, &str, fn(&BreakerStatus) -> u64); 3] = [
+        ("provider_calls_total", "Provider calls attempted", |s| s.total_calls),
+        ("provider_failures_total", "Provider calls that failed", |s| s.total_failures),
+        ("provider_rejected_calls_total", "Provider calls rejected by an open breaker", |s| s.rejected_calls),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", name, help, name);
+        for status in &statuses {
+            let _ = writeln!(out, "{}{{provider=\"{}\"}} {}", name, status.provider.name(), value(status));
+        }
+    }
+    
+    let proving = state.compliance.proving.status();
+    out.push_str("# HELP proving_queue_depth Proof requests waiting for a slot\n");
+    out.push_str("# TYPE proving_queue_depth gauge\n");
+    let _ = writeln!(out, "proving_queue_depth{{priority=\"interactive\"}} {}", proving.queued_interactive);
+    let _ = writeln!(out, "proving_queue_depth{{priority=\"batch\"}} {}", proving.queued_batch);
+    out.push_str("# HELP proving_running Proofs currently being generated\n");
+    out.push_str("# TYPE proving_running gauge\n");
+    let _ = writeln!(out, "proving_running {}", proving.running);
+    out.push_str("# HELP proving_rejected_total Proof requests rejected because the queue was full\n");
+    out.push_str("# TYPE proving_rejected_total counter\n");
+    let _ = writeln!(out, "proving_rejected_total {}", proving.rejected_total);
+    
+    let verifying = state.compliance.verifying.status();
+    let gauges: [(&str, &str, u64); 5] = [
+        ("verification_pool_running", "Proofs currently being verified", verifying.running as u64),
+        ("verification_pool_queued", "Verifications waiting for a worker", verifying.queued as u64),
+        ("verification_pool_workers", "Proofs verified concurrently at most", verifying.max_concurrent as u64),
+        ("verification_pool_queue_limit", "Verifications allowed to wait for a worker", verifying.max_queued as u64),
+        ("verification_pool_avg_verify_ms", "Moving average of verification time", verifying.avg_verify_ms),
+    ];
+    for (name, help, value) in gauges {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge\n{} {}", name, help, name, name, value);
+    }
+    let counters: [(&str, &str, u64); 2] = [
+        ("verification_pool_rejected_total", "Verifications rejected with the queue full", verifying.rejected_total),
+        ("verification_pool_timed_out_total", "Verifications that missed their deadline", verifying.timed_out_total),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter\n{} {}", name, help, name, name, value);
+    }
+    
+    out.push_str("# HELP provider_retry_queue_depth Accounts waiting for a provider to recover\n");
+    out.push_str("# TYPE provider_retry_queue_depth gauge\n");
+    let _ = writeln!(out, "provider_retry_queue_depth {}", breakers.retry_queue_depth());
+    
+    if let Some(leader) = &state.leader {
+        let locks = leader.health();
+        out.push_str("# HELP leader_lock_held Whether this instance holds the lease (0 or 1)\n");
+        out.push_str("# TYPE leader_lock_held gauge\n");
+        for lock in &locks {
+            let _ = writeln!(out, "leader_lock_held{{lock=\"{}\"}} {}", lock.name, lock.held as u8);
+        }
+        let counters: [(&str, &str, fn(&LockHealth) -> u64); 3] = [
+            ("leader_lock_acquisitions_total", "Times this instance took the lease", |l| l.acquisitions),
+            ("leader_lock_losses_total", "Times another instance took over the lease", |l| l.losses),
+            ("leader_lock_errors_total", "Lease attempts failed for want of the lease store", |l| l.errors),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", name, help, name);
+            for lock in &locks {
+                let _ = writeln!(out, "{}{{lock=\"{}\"}} {}", name, lock.name, value(lock));
+            }
+        }
+    }
+    
+    let sizes = state.response_sizes.stats();
+    let counters: [(&str, &str, fn(&RouteSizeStats) -> u64); 2] = [
+        ("http_responses_total", "Responses sent, by route and content encoding", |s| s.responses),
+        ("http_response_bytes_total", "Response body bytes sent after compression", |s| s.bytes),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", name, help, name);
+        for size in &sizes {
+            let _ = writeln!(
+                out,
+                "{}{{route=\"{}\",encoding=\"{}\"}} {}",
+                name,
+                size.route,
+                size.encoding,
+                value(size)
+            );
+        }
+    }
+    
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
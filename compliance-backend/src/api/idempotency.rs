@@ -0,0 +1,154 @@
+//! Replay of responses to retried requests carrying an idempotency key
+
+use super::auth::API_KEY_HEADER;
+use super::body_limits::RouteLimit;
+use super::compression::Streamed;
+use super::AppState;
+use crate::ComplianceError;
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Header carrying the client-chosen idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header set on responses replayed from an earlier request
+pub const IDEMPOTENT_REPLAY_HEADER: &str = "idempotent-replayed";
+
+/// Maximum length of an idempotency key
+const MAX_KEY_LEN: usize = 255;
+
+/// A response recorded for replay
+struct StoredResponse {
+    /// Hash of the request body, to reject a key reused for a different request
+    request_hash: blake3::Hash,
+    status: StatusCode,
+    content_type: Option<HeaderValue>,
+    body: axum::body::Bytes,
+    /// Set when the response was streamed, so replays are streamed too
+    streamed: Option<Streamed>,
+    stored_at: DateTime<Utc>,
+}
+
+/// Recorded responses keyed by caller, method, path, and idempotency key
+pub struct IdempotencyStore {
+    ttl: Duration,
+    responses: RwLock<HashMap<String, StoredResponse>>,
+}
+
+impl IdempotencyStore {
+    /// Create a store replaying responses for the given lifetime
+    pub fn new(ttl_secs: u64) -> Self {
+        Self {
+            ttl: Duration::seconds(ttl_secs as i64),
+            responses: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+/// Replay the recorded response when a POST is retried with the same key
+///
+/// Only completed, non-5xx responses are recorded, so a retry after a server
+/// error or a dropped connection mid-request runs the handler again.
+pub async fn replay_idempotent(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+    else {
+        return next.run(request).await;
+    };
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return ComplianceError::validation("Idempotency-Key", format!("must be 1-{} characters", MAX_KEY_LEN))
+            .into_response();
+    }
+    
+    let scope = format!(
+        "{}:{}:{}",
+        caller_fingerprint(request.headers()),
+        request.uri().path(),
+        key
+    );
+    let limit = RouteLimit::of(&state.config.server.body_limits, &request);
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = to_bytes(body, limit.max_bytes).await else {
+        return limit.too_large().into_response();
+    };
+    let request_hash = blake3::hash(&bytes);
+    
+    let store = &state.idempotency;
+    let now = Utc::now();
+    if let Some(stored) = store.responses.read().await.get(&scope) {
+        if now - stored.stored_at < store.ttl {
+            if stored.request_hash != request_hash {
+                return ComplianceError::validation(
+                    "Idempotency-Key",
+                    "was already used for a request with a different body",
+                )
+                .into_response();
+            }
+            return replay(stored);
+        }
+    }
+    
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    if response.status().is_server_error() {
+        return response;
+    }
+    
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX).await.unwrap_or_default();
+    let mut responses = store.responses.write().await;
+    responses.retain(|_, stored| now - stored.stored_at < store.ttl);
+    responses.insert(
+        scope,
+        StoredResponse {
+            request_hash,
+            status: parts.status,
+            content_type: parts.headers.get(header::CONTENT_TYPE).cloned(),
+            body: body.clone(),
+            streamed: parts.extensions.get::<Streamed>().copied(),
+            stored_at: now,
+        },
+    );
+    let body = match parts.extensions.get::<Streamed>() {
+        Some(streamed) => streamed.body(body),
+        None => Body::from(body),
+    };
+    Response::from_parts(parts, body)
+}
+
+fn replay(stored: &StoredResponse) -> Response {
+    let body = match stored.streamed {
+        Some(streamed) => streamed.body(stored.body.clone()),
+        None => Body::from(stored.body.clone()),
+    };
+    let mut response = (stored.status, body).into_response();
+    if let Some(streamed) = stored.streamed {
+        response.extensions_mut().insert(streamed);
+    }
+    if let Some(content_type) = &stored.content_type {
+        response.headers_mut().insert(header::CONTENT_TYPE, content_type.clone());
+    }
+    response.headers_mut().insert(IDEMPOTENT_REPLAY_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Hash of the caller's credentials, so keys are scoped per caller
+fn caller_fingerprint(headers: &HeaderMap) -> String {
+    let credential = headers
+        .get(API_KEY_HEADER)
+        .or_else(|| headers.get(header::AUTHORIZATION))
+        .map(|value| value.as_bytes())
+        .unwrap_or_default();
+    blake3::hash(credential).to_hex().to_string()
+}
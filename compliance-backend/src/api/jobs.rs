@@ -0,0 +1,49 @@
+//! Background job handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::AppState;
+use crate::scheduler::JobStatus;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::Json;
+
+/// `GET /v1/admin/jobs`
+///
+/// Every registered job with its schedule, last run, and next run.
+pub async fn list_jobs(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<Vec<JobStatus>>> {
+    auth.require(Permission::ManageJobs)?;
+    Ok(Json(state.scheduler.statuses()))
+}
+
+/// `GET /v1/admin/jobs/{name}`
+pub async fn get_job(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(name): Path<String>,
+) -> Result<Json<JobStatus>> {
+    auth.require(Permission::ManageJobs)?;
+    Ok(Json(state.scheduler.status(&name)?))
+}
+
+/// `POST /v1/admin/jobs/{name}/run`
+///
+/// Makes the job due now instead of at its next scheduled run. The run
+/// starts on the scheduler's next tick; its result shows in the job's status.
+pub async fn run_job(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(name): Path<String>,
+) -> Result<Json<JobStatus>> {
+    auth.require(Permission::ManageJobs)?;
+    let status = state.scheduler.trigger(&name)?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "job.triggered",
+            None,
+            serde_json::json!({ "job": name }),
+        )
+        .await;
+    Ok(Json(status))
+}
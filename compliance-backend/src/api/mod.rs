@@ -0,0 +1,525 @@
+//! REST API for business clients
+
+pub mod account_watches;
+pub mod accounts;
+pub mod addresses;
+pub mod alerts;
+pub mod approvals;
+pub mod audit;
+pub mod auth;
+pub mod body_limits;
+pub mod clients;
+pub mod components;
+pub mod compression;
+pub mod custody;
+pub mod duplicate_identities;
+pub mod epochs;
+pub mod events;
+pub mod funds;
+pub mod health;
+pub mod idempotency;
+pub mod jobs;
+pub mod monitoring;
+pub mod offboarding;
+pub mod operators;
+pub mod oracle;
+pub mod portability;
+pub mod presentations;
+pub mod proofs;
+pub mod provider_callbacks;
+pub mod provider_credentials;
+pub mod registry;
+pub mod reports;
+pub mod request_log;
+pub mod response_sizes;
+pub mod sandbox;
+pub mod schema_versions;
+pub mod screening;
+pub mod signing_requests;
+pub mod status;
+pub mod step_up;
+pub mod tags;
+pub mod usage;
+pub mod validation;
+pub mod verification_sessions;
+pub mod watchlists;
+pub mod webhook_tls;
+pub mod workflows;
+
+use crate::alerts::AlertManager;
+use crate::audit::AuditLog;
+use auth::rbac::RbacService;
+use crate::compliance::account_components::migration::MigrationTracker;
+use crate::compliance::account_watch::AccountWatchService;
+use crate::compliance::approvals::ApprovalService;
+use crate::compliance::attestation_registry::AttestationRegistry;
+use crate::compliance::challenges::ChallengeService;
+use crate::compliance::clients::ClientRegistry;
+use crate::compliance::localization::{accept_language, MessageCatalog};
+use crate::compliance::metering::UsageMeter;
+use crate::compliance::country_risk::CountryRiskService;
+use crate::compliance::duplicate_identities::DuplicateIdentityService;
+use crate::compliance::presentation_store::PresentationStore;
+use crate::compliance::threshold_signing::ThresholdSigningService;
+use crate::compliance::tags::TagService;
+use crate::compliance::screening::results::ScreeningResultStore;
+use crate::compliance::screening::ScreeningListStore;
+use crate::compliance::step_up::StepUpService;
+use crate::compliance::monitoring::TransactionMonitor;
+use crate::compliance::offboarding::OffboardingService;
+use crate::compliance::oracle::ComplianceOracle;
+use crate::compliance::provider_callbacks::CallbackStore;
+use crate::compliance::provider_credentials::ProviderCredentialStore;
+use crate::compliance::status_page::StatusPage;
+use crate::compliance::workflows::WorkflowStore;
+use crate::compliance::velocity::VelocityService;
+use crate::compliance::verification_cache::VerificationCache;
+use crate::compliance::verification_sessions::VerificationSessionService;
+use crate::compliance::watchlists::WatchlistService;
+use crate::compliance::ComplianceService;
+use crate::crypto::tls::WebhookTlsStore;
+use crate::crypto::{canonical_json, AttestationSigner, TrustedKeys};
+use crate::leader::LeaderElection;
+use crate::logging::RedactionRegistry;
+use crate::reporting::ReportService;
+use crate::reload::LiveConfig;
+use crate::scheduler::Scheduler;
+use crate::storage::OutboxRepo;
+use crate::{ComplianceError, Config};
+use axum::extract::DefaultBodyLimit;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post, put};
+use axum::{Json, Router};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Shared state available to all API handlers
+#[derive(Clone)]
+pub struct AppState {
+    /// Application configuration
+    pub config: Arc<Config>,
+    
+    /// Hot-reloadable compliance configuration
+    pub live_config: Arc<LiveConfig>,
+    
+    /// Compliance service
+    pub compliance: Arc<ComplianceService>,
+    
+    /// Transaction authorization service
+    pub velocity: Arc<VelocityService>,
+    
+    /// Reported transactions and per-account aggregates
+    pub monitor: Arc<TransactionMonitor>,
+    
+    /// Step-up verification service
+    pub step_up: Arc<StepUpService>,
+    
+    /// Alert manager
+    pub alerts: Arc<AlertManager>,
+    
+    /// Ingested sanctions and PEP lists
+    pub screening_lists: Arc<ScreeningListStore>,
+    
+    /// Latest name screening result of each account
+    pub screening_results: Arc<ScreeningResultStore>,
+    
+    /// Country-risk dataset and account geographic profiles
+    pub country_risk: Arc<CountryRiskService>,
+    
+    /// Business client registry
+    pub clients: Arc<ClientRegistry>,
+    
+    /// Client watchlists
+    pub watchlists: Arc<WatchlistService>,
+    
+    /// Clients' watches on Miden accounts' on-chain state
+    pub account_watches: Arc<AccountWatchService>,
+    
+    /// Proof challenges
+    pub challenges: Arc<ChallengeService>,
+    
+    /// Proof envelope signer
+    pub signer: Arc<AttestationSigner>,
+    
+    /// Keys trusted to sign proof envelopes
+    pub trusted_keys: Arc<TrustedKeys>,
+    
+    /// Audit log
+    pub audit: Arc<AuditLog>,
+    
+    /// Fields redacted from request logs
+    pub redactor: Arc<RedactionRegistry>,
+    
+    /// Operator accounts and sessions
+    pub operators: Arc<RbacService>,
+    
+    /// Override proposals awaiting a second operator
+    pub approvals: Arc<ApprovalService>,
+    
+    /// Per-endpoint webhook TLS configuration
+    pub webhook_tls: Arc<WebhookTlsStore>,
+    
+    /// Management information reports
+    pub reports: Arc<ReportService>,
+    
+    /// Responses recorded for idempotent retries
+    pub idempotency: Arc<idempotency::IdempotencyStore>,
+    
+    /// Responses sent and their sizes, by route
+    pub response_sizes: Arc<response_sizes::ResponseSizes>,
+    
+    /// Inbound provider result callbacks
+    pub provider_callbacks: Arc<CallbackStore>,
+    
+    /// Business clients' own provider credentials
+    pub provider_credentials: Arc<ProviderCredentialStore>,
+    
+    /// Component versions of synced accounts and their migrations
+    pub component_migrations: Arc<MigrationTracker>,
+    
+    /// Published roots of the compliant account set
+    pub oracle: Arc<ComplianceOracle>,
+    
+    /// Published roots of the attestation registry
+    pub registry: Arc<AttestationRegistry>,
+    
+    /// Aggregate statistics for the public status page
+    pub status_page: Arc<StatusPage>,
+    
+    /// Onboarding workflow runs per account
+    pub workflows: Arc<WorkflowStore>,
+    
+    /// Billable usage of business clients
+    pub meter: Arc<UsageMeter>,
+    
+    /// Proof verification outcomes by envelope hash
+    pub verification_cache: Arc<VerificationCache>,
+    
+    /// Translations of display text, loaded from [`Config::localization`]
+    pub catalog: Arc<MessageCatalog>,
+    
+    /// Sessions in which end users complete KYC directly
+    pub verification_sessions: Arc<VerificationSessionService>,
+    
+    /// Offboarding jobs and tombstones of offboarded accounts
+    pub offboarding: Arc<OffboardingService>,
+    
+    /// Identity fingerprints and the fraud cases raised from them
+    pub duplicate_identities: Arc<DuplicateIdentityService>,
+    
+    /// QR code and deep link presentations of proofs
+    pub presentations: Arc<PresentationStore>,
+    
+    /// Partial signature collection for attestations needing several signers
+    pub threshold_signing: Arc<ThresholdSigningService>,
+    
+    /// Clients' tags on attestations, cases, and accounts
+    pub tags: Arc<TagService>,
+    
+    /// Background jobs and their run status
+    pub scheduler: Arc<Scheduler>,
+    
+    /// Leases deciding which instance runs jobs and webhook delivery; `None`
+    /// when every instance runs them
+    pub leader: Option<Arc<LeaderElection>>,
+    
+    /// Webhook outbox, read for the webhooks sent about an account
+    pub outbox: Arc<dyn OutboxRepo>,
+    
+    /// Services serving sandbox traffic, built from [`Config::sandbox`] and
+    /// sharing this state's client registry; `None` when the sandbox is
+    /// disabled, and in the sandbox's own state
+    pub sandbox: Option<Arc<AppState>>,
+}
+
+/// Build the API router
+pub fn router(state: AppState) -> Router {
+    let sandbox = state.sandbox.as_ref().map(|sandbox| router(AppState::clone(sandbox)));
+    let mut public = Router::new();
+    if state.config.status_page.enabled {
+        public = public.route("/v1/status", get(status::service_status));
+    }
+    
+    Router::new()
+        .route(
+            "/v1/accounts/{id}/authorize-transaction",
+            post(accounts::authorize_transaction),
+        )
+        .route("/v1/monitoring/transactions", post(monitoring::report_transactions))
+        .route("/v1/accounts/{id}/monitoring", get(monitoring::get_aggregate))
+        .route("/v1/accounts/{id}/risk-neighborhood", get(monitoring::risk_neighborhood))
+        .route("/v1/accounts/{id}/compliance", get(accounts::get_compliance))
+        .route("/v1/accounts/{id}/check", post(accounts::run_check))
+        .route("/v1/accounts/{id}/workflows", get(workflows::list_runs))
+        .route("/v1/accounts/{id}/workflows/{run_id}", get(workflows::get_run))
+        .route(
+            "/v1/accounts/{id}/geography",
+            get(accounts::get_geography).put(accounts::set_geography),
+        )
+        .route("/v1/accounts/{id}/device-signals", post(accounts::record_device_signals))
+        .route("/v1/accounts/{id}/device-risk", get(accounts::get_device_risk))
+        .route(
+            "/v1/accounts/{id}/risk-factors",
+            get(accounts::get_risk_factors).put(accounts::set_risk_attributes),
+        )
+        .route("/v1/accounts/{id}/proofs", post(proofs::generate_proof))
+        .route("/v1/proofs/challenges", post(proofs::issue_challenge))
+        .route("/v1/proofs/verify", post(proofs::verify_proof))
+        .route("/v1/accounts/{id}/presentations", post(presentations::present_proof))
+        .route("/v1/presentations/redeem", post(presentations::redeem_presentation))
+        .route("/v1/presentations/{presentation_id}", post(presentations::retrieve_presentation))
+        .route(
+            "/v1/accounts/{id}/addresses",
+            get(addresses::list_addresses).post(addresses::link_address),
+        )
+        .route("/v1/accounts/{id}/chain-risk", get(addresses::chain_risk))
+        .route(
+            "/v1/accounts/{id}/funds-declarations",
+            get(funds::list_declarations).post(funds::submit_declaration),
+        )
+        .route("/v1/admin/funds-declarations", get(funds::review_queue))
+        .route(
+            "/v1/admin/funds-declarations/{declaration_id}/review",
+            post(funds::review_declaration),
+        )
+        .route("/v1/accounts/{id}/step-up", post(step_up::create_session))
+        .route("/v1/accounts/{id}/upgrade-compliance-level", post(step_up::upgrade_compliance_level))
+        .route("/v1/step-up/{session_id}", get(step_up::get_session))
+        .route("/v1/step-up/{session_id}/evidence", post(step_up::submit_evidence))
+        .route("/v1/step-up/{session_id}/cancel", post(step_up::cancel_session))
+        .route(
+            "/v1/accounts/{id}/verification-sessions",
+            get(verification_sessions::list_sessions).post(verification_sessions::create_session),
+        )
+        .route("/v1/verification-sessions/{session_id}", get(verification_sessions::get_session))
+        .route(
+            "/v1/verification-sessions/{session_id}/cancel",
+            post(verification_sessions::cancel_session),
+        )
+        .route("/v1/end-user/session", get(verification_sessions::end_user_session))
+        .route("/v1/end-user/session/documents", post(verification_sessions::upload_document))
+        .route("/v1/end-user/session/submit", post(verification_sessions::submit_session))
+        .route(
+            "/v1/accounts/{id}/offboarding",
+            get(offboarding::list_jobs).post(offboarding::offboard_account),
+        )
+        .route("/v1/offboarding/{job_id}", get(offboarding::get_job))
+        .route("/v1/offboarding/{job_id}/retry", post(offboarding::retry_job))
+        .route(
+            "/v1/accounts/{id}/identity",
+            put(duplicate_identities::submit_identity).delete(duplicate_identities::forget_identity),
+        )
+        .route("/v1/admin/fraud-cases", get(duplicate_identities::list_cases))
+        .route("/v1/admin/fraud-cases/{case_id}", get(duplicate_identities::get_case))
+        .route("/v1/admin/fraud-cases/{case_id}/resolve", post(duplicate_identities::resolve_case))
+        .route("/v1/admin/accounts/{id}/signing-requests", post(signing_requests::open_request))
+        .route("/v1/admin/signing-requests", get(signing_requests::list_requests))
+        .route("/v1/admin/signing-requests/{request_id}", get(signing_requests::get_request))
+        .route(
+            "/v1/admin/signing-requests/{request_id}/signatures",
+            post(signing_requests::submit_signature),
+        )
+        .route("/v1/screening/search", post(screening::search))
+        .route("/v1/admin/screening/search", post(screening::admin_search))
+        .route("/v1/accounts/{id}/screening", post(screening::screen_account))
+        .route("/v1/admin/screening/lists/{name}", put(screening::ingest_list))
+        .route(
+            "/v1/admin/screening/results/{id}/adjudications",
+            get(screening::list_adjudications).post(screening::adjudicate_match),
+        )
+        .route("/v1/admin/screening/threshold-suggestions", get(screening::threshold_suggestions))
+        .route("/v1/admin/clients/{client_id}/screening-threshold", put(screening::set_client_threshold))
+        .route("/v1/usage", get(usage::client_usage))
+        .route(
+            "/v1/schema-versions",
+            get(schema_versions::get_schema_versions).put(schema_versions::set_schema_pins),
+        )
+        .route("/v1/admin/usage", get(usage::all_usage))
+        .route("/v1/admin/usage/costs", get(usage::all_costs))
+        .route(
+            "/v1/watchlists",
+            get(watchlists::list_entries).post(watchlists::create_entry),
+        )
+        .route(
+            "/v1/watchlists/{entry_id}",
+            get(watchlists::get_entry)
+                .put(watchlists::update_entry)
+                .delete(watchlists::delete_entry),
+        )
+        .route(
+            "/v1/account-watches",
+            get(account_watches::list_watches).post(account_watches::create_watch),
+        )
+        .route(
+            "/v1/account-watches/{watch_id}",
+            get(account_watches::get_watch).delete(account_watches::delete_watch),
+        )
+        .route("/v1/tags/{kind}", get(tags::list_tagged))
+        .route("/v1/tags/{kind}/{id}", get(tags::get_tags).patch(tags::update_tags))
+        .route("/v1/admin/alerts", get(alerts::list_alerts))
+        .route("/v1/admin/alerts/{alert_id}/acknowledge", post(alerts::acknowledge_alert))
+        .route("/v1/admin/anomalies/baselines", get(alerts::anomaly_baselines))
+        .route("/v1/audit", get(audit::list_audit))
+        .route("/v1/admin/decisions/{decision_id}/replay", post(audit::replay_decision))
+        .route("/v1/admin/accounts/{id}/revoke", post(accounts::revoke_attestation))
+        .route("/v1/admin/accounts/{id}/custody-export", post(custody::export_custody))
+        .route("/v1/operators/sessions", post(operators::login))
+        .route("/v1/operators/sessions/current", delete(operators::logout))
+        .route(
+            "/v1/admin/operators",
+            get(operators::list_operators).post(operators::create_operator),
+        )
+        .route("/v1/admin/operators/{operator_id}", patch(operators::update_operator))
+        .route("/v1/admin/clients", post(clients::create_client))
+        .route("/v1/admin/clients/{client_id}/rotate-key", post(clients::rotate_api_key))
+        .route("/v1/admin/clients/{client_id}/sandbox", put(clients::update_sandbox))
+        .route("/v1/admin/clients/{client_id}/promote", post(clients::promote_sandbox))
+        .route("/v1/admin/clients/{client_id}/locales", put(clients::set_locales))
+        .route(
+            "/v1/admin/clients/{client_id}/webhook-encryption",
+            put(clients::set_webhook_encryption),
+        )
+        .route(
+            "/v1/admin/clients/{client_id}/provider-credentials",
+            get(provider_credentials::list_client_credentials),
+        )
+        .route("/v1/provider-credentials", get(provider_credentials::list_credentials))
+        .route("/v1/components/kyc-gate", get(components::kyc_gate))
+        .route("/v1/oracle/root", get(oracle::latest_root))
+        .route("/v1/oracle/roots", get(oracle::list_roots))
+        .route("/v1/oracle/proofs/{id}", get(oracle::inclusion_proof))
+        .route("/v1/admin/oracle/publish", post(oracle::publish))
+        .route("/v1/registry/root", get(registry::latest_root))
+        .route("/v1/registry/roots", get(registry::list_roots))
+        .route("/v1/registry/proofs/{id}", get(registry::proof))
+        .route("/v1/admin/registry/publish", post(registry::publish))
+        .route("/v1/epochs", get(epochs::list_epochs))
+        .route("/v1/epochs/{epoch}", get(epochs::get_epoch))
+        .route("/v1/attestations/{attestation_id}/inclusion-proof", get(epochs::inclusion_proof))
+        .route("/v1/admin/epochs/close", post(epochs::close_epoch))
+        .route("/v1/admin/components", get(components::summary))
+        .route("/v1/admin/components/accounts", get(components::list_accounts))
+        .route(
+            "/v1/admin/components/accounts/{id}/{kind}/migration",
+            post(components::plan_migration),
+        )
+        .route(
+            "/v1/admin/components/accounts/{id}/{kind}/migration/failure",
+            post(components::report_failure),
+        )
+        .route(
+            "/v1/provider-credentials/{provider}",
+            put(provider_credentials::set_credentials).delete(provider_credentials::remove_credentials),
+        )
+        .route(
+            "/v1/admin/approvals",
+            get(approvals::list_approvals).post(approvals::propose),
+        )
+        .route("/v1/admin/approvals/{approval_id}", get(approvals::get_approval))
+        .route("/v1/admin/approvals/{approval_id}/approve", post(approvals::approve))
+        .route("/v1/admin/approvals/{approval_id}/reject", post(approvals::reject))
+        .route("/v1/admin/jobs", get(jobs::list_jobs))
+        .route("/v1/admin/jobs/{name}", get(jobs::get_job))
+        .route("/v1/admin/jobs/{name}/run", post(jobs::run_job))
+        .route(
+            "/v1/admin/webhook-endpoints/{endpoint_id}/tls",
+            get(webhook_tls::get_tls)
+                .put(webhook_tls::set_tls)
+                .delete(webhook_tls::delete_tls),
+        )
+        .route(
+            "/v1/admin/webhook-endpoints/{endpoint_id}/tls/rotate",
+            post(webhook_tls::rotate_identity),
+        )
+        .route("/v1/webhooks/source-ips", get(webhook_tls::source_ips))
+        .route("/v1/reports", get(reports::list_reports))
+        .route("/v1/reports/{report_id}", get(reports::get_report))
+        .route("/v1/reports/{report_id}/download", get(reports::download_report))
+        .route("/v1/admin/reports", post(reports::generate_report))
+        .route("/v1/admin/attestations/export", post(portability::export_attestations))
+        .route("/v1/admin/attestations/import", post(portability::import_attestations))
+        .route("/v1/events/stream", get(events::stream_sse))
+        .route("/v1/events/ws", get(events::stream_ws))
+        .route("/v1/providers/{provider}/callback", post(provider_callbacks::receive_callback))
+        .route("/v1/admin/provider-callbacks", get(provider_callbacks::list_callbacks))
+        .route("/v1/admin/provider-callbacks/{callback_id}", get(provider_callbacks::get_callback))
+        .route(
+            "/v1/admin/provider-callbacks/{callback_id}/replay",
+            post(provider_callbacks::replay_callback),
+        )
+        .route("/v1/health/providers", get(health::provider_health))
+        .route("/v1/health/leader", get(health::leader_health))
+        .route("/metrics", get(health::metrics))
+        .merge(public)
+        .layer(middleware::from_fn_with_state(state.clone(), schema_versions::version_responses))
+        .layer(middleware::from_fn_with_state(state.clone(), auth::scope_client))
+        .layer(middleware::from_fn_with_state(state.clone(), idempotency::replay_idempotent))
+        // Body limits are enforced per route, replacing the extractors' default
+        .layer(DefaultBodyLimit::disable())
+        .layer(middleware::from_fn_with_state(state.clone(), body_limits::enforce_body_limits))
+        .layer(middleware::from_fn_with_state(state.clone(), request_log::log_requests))
+        .layer(compression::compression_layer(&state.config.server.responses))
+        .layer(middleware::from_fn_with_state(state.clone(), response_sizes::record_response_sizes))
+        .layer(middleware::from_fn_with_state(sandbox, sandbox::route_sandbox))
+        .with_state(state)
+}
+
+/// JSON response serialized canonically (RFC 8785)
+///
+/// Used for responses carrying signed payloads, so the bytes a client
+/// receives are the bytes that were signed.
+pub struct CanonicalJson<T>(pub T);
+
+impl<T: serde::Serialize> IntoResponse for CanonicalJson<T> {
+    fn into_response(self) -> Response {
+        match canonical_json::to_vec(&self.0) {
+            Ok(body) => ([(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))], body).into_response(),
+            Err(e) => e.into_response(),
+        }
+    }
+}
+
+/// Locales the caller prefers, from its `Accept-Language` header, most preferred first
+pub(crate) fn preferred_locales(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(accept_language)
+        .unwrap_or_default()
+}
+
+impl IntoResponse for ComplianceError {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        
+        if self.is_server_error() {
+            tracing::error!(error = %self, "request failed");
+        }
+        
+        let mut body = json!({ "error": self.to_string(), "code": self.code() });
+        let violations = self.violations();
+        if !violations.is_empty() {
+            body["errors"] = json!(violations);
+        }
+        if let ComplianceError::DocumentQualityInsufficient { score, feedback, .. } = &self {
+            body["quality_score"] = json!(score);
+            body["feedback"] = json!(feedback);
+        }
+        if let ComplianceError::PayloadTooLarge { limit, .. } = &self {
+            body["max_body_bytes"] = json!(limit);
+        }
+        if let ComplianceError::UnsupportedMediaType { accepted, .. } = &self {
+            body["accepted_content_types"] = json!(accepted);
+        }
+        let mut response = (status, Json(body)).into_response();
+        if let ComplianceError::ProverSaturated { retry_after_secs }
+        | ComplianceError::MonitoringBackpressure { retry_after_secs }
+        | ComplianceError::VerifierSaturated { retry_after_secs } = self
+        {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
+    }
+}
@@ -0,0 +1,117 @@
+//! Transaction monitoring ingestion API handlers
+
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::counterparty_graph::RiskNeighborhood;
+use crate::compliance::monitoring::{AccountAggregate, MonitoredTransaction, Totals};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Maximum transactions in one report
+const MAX_REPORT_SIZE: usize = 10_000;
+
+/// Batch of executed transactions
+#[derive(Debug, Deserialize)]
+pub struct ReportTransactionsRequest {
+    pub transactions: Vec<MonitoredTransaction>,
+}
+
+impl Validate for ReportTransactionsRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.transactions.is_empty(), "transactions", "must not be empty");
+        v.ensure(
+            self.transactions.len() <= MAX_REPORT_SIZE,
+            "transactions",
+            format!("at most {} transactions per report", MAX_REPORT_SIZE),
+        );
+        for (i, transaction) in self.transactions.iter().enumerate() {
+            v.at(&format!("transactions[{}]", i), |v| {
+                v.ensure(!transaction.external_id.trim().is_empty(), "external_id", "must not be empty");
+                v.ensure(transaction.amount != 0, "amount", "must be greater than zero");
+            });
+        }
+    }
+}
+
+/// Transactions accepted for monitoring
+#[derive(Debug, Serialize)]
+pub struct ReportTransactionsResponse {
+    pub accepted: usize,
+}
+
+/// `POST /v1/monitoring/transactions`
+///
+/// Accepts executed transactions for monitoring. They are written in batches
+/// shortly after the response; re-reporting a transaction with the same
+/// `external_id` is ignored. Reports are not audited individually given
+/// their volume. Transactions with a counterparty add to the counterparty
+/// graph right away.
+pub async fn report_transactions(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(request): Valid<ReportTransactionsRequest>,
+) -> Result<(StatusCode, Json<ReportTransactionsResponse>)> {
+    let accepted = state.monitor.ingest(client.id, request.transactions.clone()).await?;
+    state.compliance.counterparties.record_transactions(&request.transactions).await;
+    Ok((StatusCode::ACCEPTED, Json(ReportTransactionsResponse { accepted })))
+}
+
+/// Monitoring totals of an account
+#[derive(Debug, Serialize)]
+pub struct AggregateResponse {
+    pub account_id: AccountId,
+    pub as_of: DateTime<Utc>,
+    /// Totals for the current UTC day
+    pub today: Totals,
+    /// Totals for the 30 UTC days ending today
+    pub last_30_days: Totals,
+    pub aggregate: AccountAggregate,
+}
+
+/// `GET /v1/accounts/{id}/monitoring`
+pub async fn get_aggregate(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<AggregateResponse>> {
+    let aggregate = state
+        .monitor
+        .aggregate(&account_id)
+        .await
+        .ok_or_else(|| ComplianceError::AccountNotFound {
+            account_id: account_id.to_string(),
+        })?;
+    let now = Utc::now();
+    Ok(Json(AggregateResponse {
+        today: aggregate.window(1, now),
+        last_30_days: aggregate.window(30, now),
+        account_id,
+        as_of: now,
+        aggregate,
+    }))
+}
+
+/// `GET /v1/accounts/{id}/risk-neighborhood`
+///
+/// Returns the counterparties within `max_hops` of the account and the risk
+/// each propagates to it, as of the latest compliance checks of the accounts
+/// involved.
+pub async fn risk_neighborhood(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<RiskNeighborhood>> {
+    state
+        .compliance
+        .counterparties
+        .neighborhood(&account_id)
+        .await
+        .map(Json)
+        .ok_or_else(|| ComplianceError::validation("account_id", "counterparty risk propagation is disabled"))
+}
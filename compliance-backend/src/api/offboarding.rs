@@ -0,0 +1,87 @@
+//! Account offboarding handlers
+
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::offboarding::OffboardingJob;
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Request body for offboarding an account
+#[derive(Debug, Deserialize)]
+pub struct OffboardRequest {
+    pub reason: String,
+}
+
+impl Validate for OffboardRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.reason.trim().is_empty(), "reason", "must not be empty");
+    }
+}
+
+/// `POST /v1/accounts/{id}/offboarding`
+///
+/// Starts offboarding the account and returns the job at once; its steps run
+/// in the background. The client receives an `account.offboarded` webhook
+/// when the job completes, and `account.pii_erased` once the retention
+/// period ends.
+pub async fn offboard_account(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<OffboardRequest>,
+) -> Result<(StatusCode, Json<OffboardingJob>)> {
+    let job = state
+        .offboarding
+        .start(client.id, &account_id, &request.reason, &client.id.to_string())
+        .await?;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "account.offboarding_requested",
+            Some(&account_id),
+            serde_json::json!({ "job_id": job.id, "reason": job.reason }),
+        )
+        .await;
+    state.offboarding.spawn(job.id);
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// `GET /v1/accounts/{id}/offboarding`
+///
+/// The client's offboarding jobs for the account, newest first.
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Json<Vec<OffboardingJob>> {
+    Json(state.offboarding.for_account(client.id, &account_id).await)
+}
+
+/// `GET /v1/offboarding/{job_id}`
+pub async fn get_job(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<OffboardingJob>> {
+    Ok(Json(state.offboarding.get(client.id, job_id).await?))
+}
+
+/// `POST /v1/offboarding/{job_id}/retry`
+///
+/// Resumes a failed job from the step that failed.
+pub async fn retry_job(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(job_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<OffboardingJob>)> {
+    let job = state.offboarding.retry(client.id, job_id).await?;
+    state.offboarding.spawn(job.id);
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
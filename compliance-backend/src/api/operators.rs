@@ -0,0 +1,138 @@
+//! Operator session and account management API handlers
+
+use super::auth::rbac::{Operator, OperatorAuth, OperatorSession, Permission, Role, MIN_PASSWORD_LENGTH};
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request body for opening an operator session
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+impl Validate for LoginRequest {}
+
+/// Newly opened operator session
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    /// Bearer token for the `Authorization` header
+    pub token: String,
+    pub session: OperatorSession,
+    pub operator: Operator,
+}
+
+/// Request body for creating an operator
+#[derive(Debug, Deserialize)]
+pub struct CreateOperatorRequest {
+    pub username: String,
+    pub display_name: String,
+    pub role: Role,
+    pub password: String,
+}
+
+impl Validate for CreateOperatorRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.username.trim().is_empty(), "username", "must not be empty");
+        v.ensure(
+            self.password.len() >= MIN_PASSWORD_LENGTH,
+            "password",
+            format!("must be at least {} characters", MIN_PASSWORD_LENGTH),
+        );
+    }
+}
+
+/// Request body for updating an operator
+#[derive(Debug, Deserialize)]
+pub struct UpdateOperatorRequest {
+    pub role: Option<Role>,
+    pub active: Option<bool>,
+}
+
+impl Validate for UpdateOperatorRequest {}
+
+/// `POST /v1/operators/sessions`
+pub async fn login(State(state): State<AppState>, Valid(request): Valid<LoginRequest>) -> Result<Json<LoginResponse>> {
+    let (token, session) = state.operators.login(&request.username, &request.password).await?;
+    let operator = state.operators.get_operator(session.operator_id).await?;
+    
+    state
+        .audit
+        .record(&operator.username, "operator.session_opened", None, serde_json::json!({ "session_id": session.id }))
+        .await;
+    
+    Ok(Json(LoginResponse {
+        token,
+        session,
+        operator,
+    }))
+}
+
+/// `DELETE /v1/operators/sessions/current`
+pub async fn logout(State(state): State<AppState>, auth: OperatorAuth) -> StatusCode {
+    state.operators.logout(&auth.token).await;
+    StatusCode::NO_CONTENT
+}
+
+/// `GET /v1/admin/operators`
+pub async fn list_operators(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<Vec<Operator>>> {
+    auth.require(Permission::ManageOperators)?;
+    Ok(Json(state.operators.list_operators().await))
+}
+
+/// `POST /v1/admin/operators`
+pub async fn create_operator(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Valid(request): Valid<CreateOperatorRequest>,
+) -> Result<Json<Operator>> {
+    auth.require(Permission::ManageOperators)?;
+    let operator = state
+        .operators
+        .create_operator(&request.username, &request.display_name, request.role, &request.password)
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "operator.created",
+            None,
+            serde_json::json!({ "operator_id": operator.id, "username": operator.username, "role": operator.role }),
+        )
+        .await;
+    
+    Ok(Json(operator))
+}
+
+/// `PATCH /v1/admin/operators/{operator_id}`
+pub async fn update_operator(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(operator_id): Path<Uuid>,
+    Valid(request): Valid<UpdateOperatorRequest>,
+) -> Result<Json<Operator>> {
+    auth.require(Permission::ManageOperators)?;
+    let operator = state
+        .operators
+        .update_operator(operator_id, request.role, request.active)
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "operator.updated",
+            None,
+            serde_json::json!({ "operator_id": operator.id, "role": operator.role, "active": operator.active }),
+        )
+        .await;
+    
+    Ok(Json(operator))
+}
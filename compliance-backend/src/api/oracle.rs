@@ -0,0 +1,69 @@
+//! Compliance oracle handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::AppState;
+use crate::compliance::oracle::{InclusionProof, OracleRoot};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::Json;
+
+/// `GET /v1/oracle/root`
+pub async fn latest_root(State(state): State<AppState>, ClientAuth(_client): ClientAuth) -> Result<Json<OracleRoot>> {
+    Ok(Json(state.oracle.latest().await?))
+}
+
+/// `GET /v1/oracle/roots`
+pub async fn list_roots(State(state): State<AppState>, ClientAuth(_client): ClientAuth) -> Result<Json<Vec<OracleRoot>>> {
+    Ok(Json(state.oracle.history().await))
+}
+
+/// `GET /v1/oracle/proofs/{id}`
+///
+/// Merkle path proving the account is in the latest published root, for
+/// presenting to a contract that reads the root from the oracle account.
+pub async fn inclusion_proof(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<InclusionProof>> {
+    Ok(Json(state.oracle.inclusion_proof(&account_id).await?))
+}
+
+/// `POST /v1/admin/oracle/publish`
+///
+/// Publishes the compliant account set now instead of at the next scheduled pass.
+pub async fn publish(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<OracleRoot>> {
+    auth.require(Permission::ManageComponents)?;
+    let account = state
+        .live_config
+        .compliance()
+        .attestation
+        .oracle
+        .account_id
+        .clone()
+        .ok_or_else(|| ComplianceError::validation("oracle.account_id", "no oracle account is configured"))?;
+    let oracle_account = AccountId::parse(&account)?;
+    let root = state
+        .oracle
+        .publish(&state.compliance, &state.signer, &oracle_account, state.compliance.clock.now())
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "oracle.root_published",
+            None,
+            serde_json::json!({
+                "epoch": root.epoch,
+                "root": root.root,
+                "accounts": root.accounts,
+                "transaction_id": root.transaction_id,
+            }),
+        )
+        .await;
+    
+    Ok(Json(root))
+}
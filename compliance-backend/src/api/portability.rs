@@ -0,0 +1,89 @@
+//! Attestation export and import handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::validation::{Valid, Validate};
+use super::compression;
+use super::AppState;
+use crate::crypto::canonical_json;
+use crate::compliance::portability::{ExportBundle, ImportReport};
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Json;
+use serde::Deserialize;
+
+/// Request body for exporting attestations
+#[derive(Debug, Deserialize)]
+pub struct ExportRequest {
+    /// Accounts to export (defaults to every account)
+    pub account_ids: Option<Vec<AccountId>>,
+}
+
+impl Validate for ExportRequest {}
+
+/// `POST /v1/admin/attestations/export`
+///
+/// The bundle is serialized canonically (RFC 8785), as it is signed, and
+/// streamed in chunks when large.
+pub async fn export_attestations(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Valid(request): Valid<ExportRequest>,
+) -> Result<Response> {
+    auth.require(Permission::MigrateAttestations)?;
+    let bundle = state
+        .compliance
+        .export_attestations(
+            request.account_ids.as_deref(),
+            &state.config.security.deployment_id,
+            &state.signer,
+        )
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "attestations.exported",
+            None,
+            serde_json::json!({ "bundle_id": bundle.bundle_id, "records": bundle.records.len() }),
+        )
+        .await;
+    
+    let bytes = canonical_json::to_vec(&bundle)?;
+    Ok(compression::sized(&state.config.server.responses, "application/json", bytes))
+}
+
+impl Validate for ExportBundle {}
+
+/// `POST /v1/admin/attestations/import`
+///
+/// Accepts bundles signed by a key in the trusted key set.
+pub async fn import_attestations(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Valid(bundle): Valid<ExportBundle>,
+) -> Result<Json<ImportReport>> {
+    auth.require(Permission::MigrateAttestations)?;
+    let report = state.compliance.import_attestations(&bundle, &state.trusted_keys).await?;
+    
+    for record in report.records.iter().filter(|r| r.imported) {
+        state
+            .audit
+            .record(
+                &auth.operator.username,
+                "attestation.imported",
+                Some(&record.account_id),
+                serde_json::json!({
+                    "attestation_id": record.attestation_id,
+                    "bundle_id": report.bundle_id,
+                    "source_deployment": report.source_deployment,
+                    "key_id": bundle.key_id,
+                }),
+            )
+            .await;
+    }
+    
+    Ok(Json(report))
+}
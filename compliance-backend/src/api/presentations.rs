@@ -0,0 +1,125 @@
+//! QR code and deep link proof presentation handlers
+
+use super::auth::ClientAuth;
+use super::proofs::{create_envelope, verifier_audience, verify_encoded, GenerateProofRequest, VerifyProofResponse};
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::claims::ClaimKind;
+use crate::compliance::presentation::{Presentation, PresentationMode};
+use crate::compliance::presentation_store::IssuedPresentation;
+use crate::compliance::scope::ScopeUsage;
+use crate::types::AccountId;
+use crate::verifier::VerificationPolicy;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request body for presenting a proof
+#[derive(Debug, Deserialize)]
+pub struct PresentProofRequest {
+    #[serde(flatten)]
+    pub proof: GenerateProofRequest,
+    
+    /// Inline, by reference, or inline when it fits (the default)
+    #[serde(default)]
+    pub mode: PresentationMode,
+}
+
+impl Validate for PresentProofRequest {
+    fn validate(&self, v: &mut Violations) {
+        self.proof.validate(v);
+    }
+}
+
+/// Request body for retrieving an envelope presented by reference
+#[derive(Debug, Deserialize)]
+pub struct RetrieveRequest {
+    pub token: String,
+}
+
+impl Validate for RetrieveRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.token.is_empty(), "token", "must not be empty");
+    }
+}
+
+/// Envelope retrieved by reference
+#[derive(Debug, Serialize)]
+pub struct RetrievedEnvelope {
+    /// Canonically encoded envelope
+    pub envelope: String,
+}
+
+/// Request body for redeeming a scanned presentation
+#[derive(Debug, Deserialize)]
+pub struct RedeemRequest {
+    /// Scanned or followed presentation URI
+    pub uri: String,
+    /// How the verifier intends to rely on the proof, checked against its scope
+    #[serde(default)]
+    pub usage: ScopeUsage,
+    /// Claims the proof must disclose
+    #[serde(default)]
+    pub required_claims: Vec<ClaimKind>,
+}
+
+impl Validate for RedeemRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.uri.trim().is_empty(), "uri", "must not be empty");
+    }
+}
+
+/// `POST /v1/accounts/{id}/presentations`
+///
+/// Answers a challenge like `POST /v1/accounts/{id}/proofs` and returns the
+/// proof as a `zerotrust:present` URI to show as a QR code or open as a deep
+/// link. The envelope is carried in the URI when it fits
+/// `presentations.max_inline_bytes`; otherwise the URI refers to it, with a
+/// one-time token, for `presentations.reference_ttl_secs`.
+pub async fn present_proof(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<PresentProofRequest>,
+) -> Result<Json<IssuedPresentation>> {
+    let (envelope, _) = create_envelope(&state, &account_id, request.proof).await?;
+    let presentation = state
+        .presentations
+        .present(&account_id, envelope.encode()?, envelope.expires_at(), request.mode)
+        .await?;
+    Ok(Json(presentation))
+}
+
+/// `POST /v1/presentations/{presentation_id}`
+///
+/// Retrieval URL of reference presentations. Returns the envelope for
+/// verification through `POST /v1/proofs/verify` or offline, and spends the
+/// token.
+pub async fn retrieve_presentation(
+    State(state): State<AppState>,
+    Path(presentation_id): Path<Uuid>,
+    Valid(request): Valid<RetrieveRequest>,
+) -> Result<Json<RetrievedEnvelope>> {
+    let (_, envelope) = state.presentations.retrieve(presentation_id, &request.token).await?;
+    Ok(Json(RetrievedEnvelope { envelope }))
+}
+
+/// `POST /v1/presentations/redeem`
+///
+/// Resolves a scanned presentation to its envelope, retrieving it when it is
+/// presented by reference, and verifies it as `POST /v1/proofs/verify` does.
+pub async fn redeem_presentation(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(request): Valid<RedeemRequest>,
+) -> Result<Json<VerifyProofResponse>> {
+    let envelope = state.presentations.resolve(Presentation::parse(&request.uri)?).await?;
+    let policy = VerificationPolicy {
+        usage: request.usage,
+        required_claims: request.required_claims,
+        ..VerificationPolicy::new(verifier_audience(&client))
+    };
+    verify_encoded(&state, &envelope, policy).await.map(Json)
+}
@@ -0,0 +1,323 @@
+//! Proof challenge, generation, and verification handlers
+
+use super::auth::ClientAuth;
+use super::compression;
+use super::screening::name_matcher;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::challenges::ProofChallenge;
+use crate::compliance::claims::{Claim, ClaimKind, PepStatus};
+use crate::compliance::freshness::FreshnessRequirement;
+use crate::compliance::proof_envelope::{ProofEnvelope, ProofOptions, ProofReport};
+use crate::compliance::scope::{ProofScope, ScopeUsage};
+use crate::compliance::screening::results::ScreeningResult;
+use crate::compliance::verification_cache::envelope_hash;
+use crate::types::{AccountId, BusinessClient};
+use crate::verifier::{verify_proof_envelope, VerificationPolicy};
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::response::Response;
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Request body for issuing a challenge
+#[derive(Debug, Deserialize)]
+pub struct IssueChallengeRequest {
+    pub account_id: AccountId,
+    /// Scope the proof must be restricted to
+    #[serde(default)]
+    pub scope: Option<ProofScope>,
+    /// Claims the proof must disclose
+    #[serde(default)]
+    pub claims: Vec<ClaimKind>,
+    /// Freshness required of the attestation and screening behind the proof
+    #[serde(default)]
+    pub freshness: Option<FreshnessRequirement>,
+}
+
+impl Validate for IssueChallengeRequest {
+    fn validate(&self, v: &mut Violations) {
+        if let Some(scope) = &self.scope {
+            v.absorb(scope.validate());
+        }
+        if let Some(freshness) = &self.freshness {
+            v.absorb(freshness.validate());
+        }
+    }
+}
+
+/// Request body for generating a proof
+#[derive(Debug, Deserialize)]
+pub struct GenerateProofRequest {
+    pub nonce: String,
+    /// Scope to restrict the proof to; must match the challenge's scope if it set one
+    #[serde(default)]
+    pub scope: Option<ProofScope>,
+    /// Compression, disclosed claims, and size budget of the proof
+    #[serde(default)]
+    pub options: ProofOptions,
+}
+
+impl Validate for GenerateProofRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.nonce.trim().is_empty(), "nonce", "must not be empty");
+        if let Some(scope) = &self.scope {
+            v.absorb(scope.validate());
+        }
+        v.ensure(self.options.max_proof_size != Some(0), "options.max_proof_size", "must be greater than 0");
+    }
+}
+
+/// Generated proof envelope
+#[derive(Debug, Serialize)]
+pub struct ProofEnvelopeResponse {
+    /// Canonically encoded envelope
+    pub envelope: String,
+    pub audience: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scope: Option<ProofScope>,
+    /// Size of `envelope`, in bytes
+    pub envelope_size: usize,
+    /// Size and timing of the embedded proof
+    pub proof: ProofReport,
+}
+
+/// Request body for verifying a proof envelope
+#[derive(Debug, Deserialize)]
+pub struct VerifyProofRequest {
+    /// Canonically encoded envelope
+    pub envelope: String,
+    /// How the verifier intends to rely on the proof, checked against its scope
+    #[serde(default)]
+    pub usage: ScopeUsage,
+    /// Claims the proof must disclose
+    #[serde(default)]
+    pub required_claims: Vec<ClaimKind>,
+}
+
+impl Validate for VerifyProofRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.envelope.is_empty(), "envelope", "must not be empty");
+    }
+}
+
+/// Verification result
+#[derive(Debug, Serialize)]
+pub struct VerifyProofResponse {
+    pub valid: bool,
+    pub account_id: AccountId,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scope: Option<ProofScope>,
+    /// Claims the proof disclosed
+    pub claims: Vec<Claim>,
+}
+
+/// Audience of the challenges a verifying client issues and the proofs it verifies
+///
+/// Verifiers don't choose their audience, so a proof answering one client's
+/// challenge cannot be verified by another.
+pub(super) fn verifier_audience(client: &BusinessClient) -> String {
+    client.id.to_string()
+}
+
+/// `POST /v1/proofs/challenges`
+///
+/// The challenge's audience is the calling client (see [`verifier_audience`]).
+/// Required list versions must name versions that have been ingested.
+pub async fn issue_challenge(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(request): Valid<IssueChallengeRequest>,
+) -> Result<Json<ProofChallenge>> {
+    for (list, version) in request.freshness.iter().flat_map(|f| &f.min_list_versions) {
+        if !state.screening_lists.has_version(list, version).await {
+            return Err(ComplianceError::validation(
+                "freshness.min_list_versions",
+                format!("version {:?} of list {:?} has not been ingested", version, list),
+            ));
+        }
+    }
+    Ok(Json(
+        state
+            .challenges
+            .issue(&verifier_audience(&client), &request.account_id, request.scope, request.claims, request.freshness)
+            .await?,
+    ))
+}
+
+/// `POST /v1/accounts/{id}/proofs`
+///
+/// The proof must fit the smaller of the request's `max_proof_size` and the
+/// configured `attestation.max_proof_size`; a larger one fails with
+/// `proof_too_large` instead of being rejected later by the verifier.
+///
+/// When the challenge requires attestation freshness, a prepared renewal
+/// older than it allows is passed over for a fresh check. An account last
+/// screened against list versions older than required is re-screened if the
+/// challenge allows it, and otherwise fails with `attestation_not_fresh`.
+///
+/// A large envelope is streamed in chunks, compressed when the client
+/// accepts it (see [`compression`]).
+pub async fn generate_proof(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<GenerateProofRequest>,
+) -> Result<Response> {
+    let (envelope, report) = create_envelope(&state, &account_id, request).await?;
+    let encoded = envelope.encode()?;
+    let response = ProofEnvelopeResponse {
+        envelope_size: encoded.len(),
+        envelope: encoded,
+        audience: envelope.audience.clone(),
+        expires_at: envelope.expires_at(),
+        scope: envelope.scope.clone(),
+        proof: report,
+    };
+    compression::json(&state.config.server.responses, &response)
+}
+
+/// Generate a proof envelope answering an open challenge
+pub(super) async fn create_envelope(
+    state: &AppState,
+    account_id: &AccountId,
+    request: GenerateProofRequest,
+) -> Result<(ProofEnvelope, ProofReport)> {
+    let challenge = state.challenges.get_open(&request.nonce, account_id).await?;
+    let compliance = state.live_config.compliance();
+    let validity = Duration::seconds(compliance.attestation.proof_validity_secs as i64);
+    let options = request.options;
+    let max_proof_size = options
+        .max_proof_size
+        .map_or(compliance.attestation.max_proof_size, |size| size.min(compliance.attestation.max_proof_size));
+    if let Some(claims) = &options.claims {
+        if let Some(missing) = challenge.claims.iter().find(|kind| !claims.contains(kind)) {
+            return Err(ComplianceError::validation(
+                "options.claims",
+                format!("the challenge requires the {:?} claim", missing),
+            ));
+        }
+    }
+    
+    let scope = match (challenge.scope.clone(), request.scope) {
+        (Some(required), Some(requested)) if required != requested => {
+            return Err(ComplianceError::validation("scope", "does not match the scope the challenge requires"));
+        }
+        (required, requested) => required.or(requested),
+    };
+    let scope = match scope {
+        Some(mut scope) => {
+            scope.validate()?;
+            scope.min_level = Some(scope.required_level(&compliance.attestation.asset_class_levels, |amount| {
+                state.velocity.required_level(amount)
+            }));
+            Some(scope)
+        }
+        None => None,
+    };
+    
+    let mut screening = state.screening_results.get(account_id).await;
+    if let Some(freshness) = challenge.freshness.as_ref().filter(|f| !f.min_list_versions.is_empty()) {
+        let mut stale = stale_lists(state, screening.as_ref(), freshness).await;
+        if !stale.is_empty() && freshness.rescreen {
+            screening = state
+                .screening_results
+                .rescreen_account(&state.screening_lists, &name_matcher(state, None), account_id)
+                .await;
+            stale = stale_lists(state, screening.as_ref(), freshness).await;
+        }
+        if !stale.is_empty() {
+            return Err(ComplianceError::AttestationNotFresh {
+                reason: format!("account was not screened against the required versions of {}", stale.join(", ")),
+            });
+        }
+    }
+    let pep_status = screening.map_or(PepStatus::Unscreened, |result| result.pep_status());
+    state
+        .compliance
+        .create_proof_envelope(&challenge, scope, pep_status, &state.signer, validity, &options, max_proof_size)
+        .await
+}
+
+/// Lists the account's screening is older than the freshness requirement allows
+async fn stale_lists(
+    state: &AppState,
+    screening: Option<&ScreeningResult>,
+    freshness: &FreshnessRequirement,
+) -> Vec<String> {
+    let screened = screening.map(|result| result.list_versions.clone()).unwrap_or_default();
+    state.screening_lists.stale_lists(&screened, &freshness.min_list_versions).await
+}
+
+/// `POST /v1/proofs/verify`
+///
+/// Validates the envelope and consumes its challenge, so a proof can be
+/// verified at most once and only by the client whose challenge it answers;
+/// an envelope for any other audience is rejected. A scoped proof is
+/// rejected unless the verifier's stated usage is in scope.
+///
+/// The embedded proof is checked before the challenge is consumed, through
+/// the verification cache, so replayed and repeatedly submitted envelopes do
+/// not run the Miden verifier again.
+pub async fn verify_proof(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(request): Valid<VerifyProofRequest>,
+) -> Result<Json<VerifyProofResponse>> {
+    let policy = VerificationPolicy {
+        usage: request.usage,
+        required_claims: request.required_claims,
+        ..VerificationPolicy::new(verifier_audience(&client))
+    };
+    verify_encoded(&state, &request.envelope, policy).await.map(Json)
+}
+
+/// Verify an encoded envelope and consume its challenge
+///
+/// The configured `attestation.max_proof_size` replaces the policy's.
+pub(super) async fn verify_encoded(
+    state: &AppState,
+    encoded: &str,
+    policy: VerificationPolicy,
+) -> Result<VerifyProofResponse> {
+    let started = Instant::now();
+    let compliance = state.live_config.compliance();
+    let policy = VerificationPolicy {
+        max_proof_size: compliance.attestation.max_proof_size,
+        ..policy
+    };
+    let envelope = verify_proof_envelope(encoded, &state.trusted_keys, &policy)?;
+    
+    let valid = state
+        .verification_cache
+        .verify(envelope_hash(encoded), &envelope.account_id, envelope.expires_at, async {
+            let proof = String::from_utf8(envelope.proof_bytes.clone()).map_err(|_| ComplianceError::InvalidProof {
+                reason: "proof bytes are not a valid Miden proof encoding".to_string(),
+            })?;
+            state
+                .compliance
+                .verify_compliance_proof(
+                    &proof,
+                    &envelope.account_id,
+                    std::time::Duration::from_secs(compliance.attestation.proof_verification_timeout),
+                )
+                .await
+        })
+        .await?;
+    
+    state
+        .challenges
+        .consume(&envelope.nonce, &policy.audience, &envelope.account_id)
+        .await?;
+    state.status_page.record_verification(started.elapsed()).await;
+    
+    Ok(VerifyProofResponse {
+        valid,
+        account_id: envelope.account_id,
+        expires_at: envelope.expires_at,
+        scope: envelope.scope,
+        claims: envelope.claims,
+    })
+}
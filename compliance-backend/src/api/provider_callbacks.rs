@@ -0,0 +1,143 @@
+//! Provider callback API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::AppState;
+use crate::compliance::breaker::Provider;
+use crate::compliance::provider_callbacks::{self, CallbackProcessing, CallbackStatus, ProviderCallback};
+use crate::{ComplianceError, Result};
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Default number of callbacks listed
+const DEFAULT_LIST_LIMIT: usize = 100;
+
+/// Acknowledgement returned to the provider
+#[derive(Debug, Serialize)]
+pub struct CallbackReceipt {
+    pub callback_id: Uuid,
+    pub status: CallbackStatus,
+}
+
+/// Query parameters for listing callbacks
+#[derive(Debug, Deserialize)]
+pub struct ListCallbacksQuery {
+    pub status: Option<CallbackStatus>,
+    pub limit: Option<usize>,
+}
+
+/// `POST /v1/providers/{provider}/callback`
+///
+/// Verifies the provider's signature over the raw body, stores the callback,
+/// then processes it. A stored callback is acknowledged with 202 even when
+/// processing fails, so the provider does not redeliver it; failures are
+/// replayed by an operator instead.
+pub async fn receive_callback(
+    State(state): State<AppState>,
+    Path(provider): Path<Provider>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<CallbackReceipt>)> {
+    let compliance = state.live_config.compliance();
+    let verification = compliance
+        .callbacks
+        .providers
+        .get(&provider)
+        .ok_or_else(|| ComplianceError::InvalidCallbackSignature {
+            reason: format!("no callback secret configured for {}", provider.name()),
+        })?;
+    let signature = headers
+        .get(verification.signature_header.as_str())
+        .and_then(|value| value.to_str().ok());
+    provider_callbacks::verify_signature(verification, signature, &body)?;
+    
+    let body = String::from_utf8(body.to_vec())
+        .map_err(|_| ComplianceError::validation("body", "callback body must be UTF-8"))?;
+    let callback = state.provider_callbacks.record(provider, body).await;
+    let processing = state.provider_callbacks.process(callback.id, &state.compliance).await?;
+    observe(&state, &processing).await;
+    
+    state
+        .audit
+        .record(
+            &format!("provider:{}", provider.name()),
+            "provider_callback.received",
+            processing.callback.account_id.as_ref(),
+            serde_json::json!({
+                "callback_id": processing.callback.id,
+                "status": processing.callback.status,
+                "last_error": processing.callback.last_error,
+            }),
+        )
+        .await;
+    
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CallbackReceipt {
+            callback_id: processing.callback.id,
+            status: processing.callback.status,
+        }),
+    ))
+}
+
+/// `GET /v1/admin/provider-callbacks`
+pub async fn list_callbacks(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<ListCallbacksQuery>,
+) -> Result<Json<Vec<ProviderCallback>>> {
+    auth.require(Permission::ManageWebhooks)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    Ok(Json(state.provider_callbacks.list(query.status, limit).await))
+}
+
+/// `GET /v1/admin/provider-callbacks/{callback_id}`
+pub async fn get_callback(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(callback_id): Path<Uuid>,
+) -> Result<Json<ProviderCallback>> {
+    auth.require(Permission::ManageWebhooks)?;
+    Ok(Json(state.provider_callbacks.get(callback_id).await?))
+}
+
+/// `POST /v1/admin/provider-callbacks/{callback_id}/replay`
+///
+/// Processes a stored callback again from its raw body.
+pub async fn replay_callback(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(callback_id): Path<Uuid>,
+) -> Result<Json<ProviderCallback>> {
+    auth.require(Permission::ManageWebhooks)?;
+    let previous = state.provider_callbacks.get(callback_id).await?;
+    let processing = state.provider_callbacks.process(callback_id, &state.compliance).await?;
+    observe(&state, &processing).await;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "provider_callback.replayed",
+            processing.callback.account_id.as_ref(),
+            serde_json::json!({
+                "callback_id": callback_id,
+                "provider": processing.callback.provider,
+                "previous_status": previous.status,
+                "status": processing.callback.status,
+            }),
+        )
+        .await;
+    
+    Ok(Json(processing.callback))
+}
+
+/// Raise alerts for an attestation re-issued from a callback
+async fn observe(state: &AppState, processing: &CallbackProcessing) {
+    if let Some(attestation) = &processing.attestation {
+        state.alerts.observe_attestation(attestation).await;
+    }
+}
@@ -0,0 +1,104 @@
+//! Client provider credential handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::breaker::Provider;
+use crate::compliance::provider_credentials::CredentialSummary;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Request body for registering provider credentials
+#[derive(Deserialize)]
+pub struct SetCredentialsRequest {
+    /// Vendor endpoint; the globally configured one when omitted
+    pub endpoint: Option<String>,
+    pub api_key: String,
+}
+
+impl Validate for SetCredentialsRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.api_key.trim().is_empty(), "api_key", "must not be empty");
+        v.ensure(
+            self.endpoint.as_ref().is_none_or(|endpoint| endpoint.starts_with("https://")),
+            "endpoint",
+            "must be an https URL",
+        );
+    }
+}
+
+/// `GET /v1/provider-credentials`
+pub async fn list_credentials(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+) -> Result<Json<Vec<CredentialSummary>>> {
+    Ok(Json(state.provider_credentials.list(client.id).await))
+}
+
+/// `PUT /v1/provider-credentials/{provider}`
+///
+/// Registers the client's own vendor account for a provider. Checks run for
+/// the client's requests then call the provider with these credentials. The
+/// API key is never returned.
+pub async fn set_credentials(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(provider): Path<Provider>,
+    Valid(request): Valid<SetCredentialsRequest>,
+) -> Result<Json<CredentialSummary>> {
+    let summary = state
+        .provider_credentials
+        .set(client.id, provider, request.endpoint, &request.api_key)
+        .await?;
+    
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "provider_credentials.set",
+            None,
+            serde_json::json!({ "provider": provider, "endpoint": summary.endpoint }),
+        )
+        .await;
+    
+    Ok(Json(summary))
+}
+
+/// `DELETE /v1/provider-credentials/{provider}`
+///
+/// Reverts the client to the globally configured provider account.
+pub async fn remove_credentials(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(provider): Path<Provider>,
+) -> Result<StatusCode> {
+    state.provider_credentials.remove(client.id, provider).await?;
+    
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "provider_credentials.removed",
+            None,
+            serde_json::json!({ "provider": provider }),
+        )
+        .await;
+    
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /v1/admin/clients/{client_id}/provider-credentials`
+pub async fn list_client_credentials(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<Uuid>,
+) -> Result<Json<Vec<CredentialSummary>>> {
+    auth.require(Permission::ManageClients)?;
+    state.clients.get(client_id).await?;
+    Ok(Json(state.provider_credentials.list(client_id).await))
+}
@@ -0,0 +1,58 @@
+//! Attestation registry handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::AppState;
+use crate::compliance::attestation_registry::{RegistryProof, RegistryRoot};
+use crate::types::AccountId;
+use crate::Result;
+use axum::extract::{Path, State};
+use axum::Json;
+
+/// `GET /v1/registry/root`
+pub async fn latest_root(State(state): State<AppState>, ClientAuth(_client): ClientAuth) -> Result<Json<RegistryRoot>> {
+    Ok(Json(state.registry.latest().await?))
+}
+
+/// `GET /v1/registry/roots`
+pub async fn list_roots(State(state): State<AppState>, ClientAuth(_client): ClientAuth) -> Result<Json<Vec<RegistryRoot>>> {
+    Ok(Json(state.registry.history().await))
+}
+
+/// `GET /v1/registry/proofs/{id}`
+///
+/// Opening of the latest root for the account: a membership proof if it
+/// held a valid attestation when the root was published, otherwise a
+/// non-membership proof.
+pub async fn proof(
+    State(state): State<AppState>,
+    ClientAuth(_client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<RegistryProof>> {
+    Ok(Json(state.registry.proof(&state.compliance, &account_id).await?))
+}
+
+/// `POST /v1/admin/registry/publish`
+///
+/// Publishes the registry now, so a revocation is provable without waiting
+/// for the next scheduled pass.
+pub async fn publish(State(state): State<AppState>, auth: OperatorAuth) -> Result<Json<RegistryRoot>> {
+    auth.require(Permission::RevokeAttestation)?;
+    let root = state.registry.publish(&state.compliance, &state.signer, state.compliance.clock.now()).await?;
+    
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "registry.root_published",
+            None,
+            serde_json::json!({
+                "epoch": root.epoch,
+                "root": root.root,
+                "attested": root.attested,
+            }),
+        )
+        .await;
+    
+    Ok(Json(root))
+}
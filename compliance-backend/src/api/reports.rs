@@ -0,0 +1,113 @@
+//! Management information report API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::reporting::{render, Report, ReportFormat};
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Query parameters for downloading a report
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    pub format: ReportFormat,
+}
+
+/// Request body for generating a report on demand
+#[derive(Debug, Deserialize)]
+pub struct GenerateReportRequest {
+    pub client_id: Uuid,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl Validate for GenerateReportRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(self.from < self.to, "from", "must be before to");
+    }
+}
+
+/// Get a report owned by the authenticated client
+async fn owned_report(state: &AppState, client_id: Uuid, report_id: Uuid) -> Result<Report> {
+    let report = state.reports.get(report_id).await?;
+    if report.client_id != client_id {
+        // Do not reveal reports belonging to other clients
+        return Err(ComplianceError::ReportNotFound {
+            report_id: report_id.to_string(),
+        });
+    }
+    Ok(report)
+}
+
+/// `GET /v1/reports`
+pub async fn list_reports(State(state): State<AppState>, ClientAuth(client): ClientAuth) -> Json<Vec<Report>> {
+    Json(state.reports.list(client.id).await)
+}
+
+/// `GET /v1/reports/{report_id}`
+pub async fn get_report(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<Report>> {
+    Ok(Json(owned_report(&state, client.id, report_id).await?))
+}
+
+/// `GET /v1/reports/{report_id}/download?format=csv|pdf`
+pub async fn download_report(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(report_id): Path<Uuid>,
+    Query(query): Query<DownloadQuery>,
+) -> Result<Response> {
+    let report = owned_report(&state, client.id, report_id).await?;
+    let (content_type, extension, body) = match query.format {
+        ReportFormat::Csv => ("text/csv", "csv", render::to_csv(&report).into_bytes()),
+        ReportFormat::Pdf => ("application/pdf", "pdf", render::to_pdf(&report)),
+    };
+    
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"report-{}.{}\"", report.id, extension),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// `POST /v1/admin/reports`
+pub async fn generate_report(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Valid(request): Valid<GenerateReportRequest>,
+) -> Result<Json<Report>> {
+    auth.require(Permission::GenerateReports)?;
+    state.clients.get(request.client_id).await?;
+    
+    let report = state
+        .reports
+        .generate(request.client_id, request.from, request.to, None)
+        .await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "report.generated",
+            None,
+            serde_json::json!({ "report_id": report.id, "client_id": report.client_id }),
+        )
+        .await;
+    
+    Ok(Json(report))
+}
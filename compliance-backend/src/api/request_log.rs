@@ -0,0 +1,100 @@
+//! Per-request logging middleware
+
+use super::auth::API_KEY_HEADER;
+use super::body_limits::RouteLimit;
+use super::compression::Streamed;
+use super::AppState;
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::time::Instant;
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request id, echoed on every response
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Request id assigned to the current request, available as an extension
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Assign a request id and log method, path, status, latency, and client id
+///
+/// Bodies are only logged when enabled in `LoggingConfig`, and always pass
+/// through the redaction registry first.
+pub async fn log_requests(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let logging = &state.config.logging;
+    let started = Instant::now();
+    
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+    
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let client_id = match request.headers().get(API_KEY_HEADER).and_then(|value| value.to_str().ok()) {
+        Some(api_key) => state.clients.find_by_api_key(api_key).await.ok().flatten().map(|client| client.id),
+        None => None,
+    };
+    
+    let span = tracing::info_span!("request", request_id = %request_id, %method, %path);
+    
+    // Multipart uploads are streamed to their handler, never buffered here
+    let multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/"));
+    if logging.log_requests && !multipart {
+        let limit = RouteLimit::of(&state.config.server.body_limits, &request);
+        let (parts, body) = request.into_parts();
+        let bytes = match to_bytes(body, limit.max_bytes).await {
+            Ok(bytes) => bytes,
+            Err(_) => return limit.too_large().into_response(),
+        };
+        span.in_scope(|| tracing::info!(body = %state.redactor.render_body(&bytes), "request body"));
+        request = Request::from_parts(parts, Body::from(bytes));
+    }
+    
+    let mut response = next.run(request).instrument(span.clone()).await;
+    
+    if logging.log_responses && !is_streaming(&response) {
+        let (parts, body) = response.into_parts();
+        let bytes = to_bytes(body, usize::MAX).await.unwrap_or_default();
+        span.in_scope(|| tracing::info!(body = %state.redactor.render_body(&bytes), "response body"));
+        response = Response::from_parts(parts, Body::from(bytes));
+    }
+    
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            client_id = client_id.map(|id| id.to_string()),
+            "request completed"
+        )
+    });
+    
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Streaming responses are never buffered for logging
+fn is_streaming(response: &Response) -> bool {
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS || response.extensions().get::<Streamed>().is_some() {
+        return true;
+    }
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream") || value.starts_with("application/x-ndjson"))
+}
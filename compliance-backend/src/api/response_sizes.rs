@@ -0,0 +1,105 @@
+//! Response size metrics per route
+//!
+//! Counts the responses of each route and the body bytes they put on the
+//! wire, after compression, by content encoding. Streamed bodies are counted
+//! chunk by chunk as they are sent, so a response cut short counts only what
+//! reached the client.
+
+use super::AppState;
+use axum::body::Body;
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::Response;
+use http_body_util::BodyExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Route label of requests matching no route
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Counters of one route and encoding
+#[derive(Default)]
+struct Counters {
+    responses: AtomicU64,
+    bytes: AtomicU64,
+}
+
+/// Responses sent by one route in one encoding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteSizeStats {
+    /// Method and path of the route, e.g. `POST /v1/accounts/{id}/proofs`
+    pub route: String,
+    /// Content encoding, `identity` when uncompressed
+    pub encoding: String,
+    pub responses: u64,
+    pub bytes: u64,
+}
+
+/// Response counters by route and encoding
+#[derive(Default)]
+pub struct ResponseSizes {
+    counters: Mutex<HashMap<(String, String), Arc<Counters>>>,
+}
+
+impl ResponseSizes {
+    /// Create empty counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Count a response of `route`, and its body bytes as they are sent
+    pub fn track(&self, route: &str, response: Response) -> Response {
+        let encoding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("identity")
+            .to_string();
+        let counters = self
+            .counters
+            .lock()
+            .expect("response size lock poisoned")
+            .entry((route.to_string(), encoding))
+            .or_default()
+            .clone();
+        counters.responses.fetch_add(1, Ordering::Relaxed);
+        
+        response.map(|body| {
+            Body::new(body.map_frame(move |frame| {
+                if let Some(data) = frame.data_ref() {
+                    counters.bytes.fetch_add(data.len() as u64, Ordering::Relaxed);
+                }
+                frame
+            }))
+        })
+    }
+    
+    /// Counters of every route and encoding seen, ordered by route
+    pub fn stats(&self) -> Vec<RouteSizeStats> {
+        let counters = self.counters.lock().expect("response size lock poisoned");
+        let mut stats: Vec<RouteSizeStats> = counters
+            .iter()
+            .map(|((route, encoding), counters)| RouteSizeStats {
+                route: route.clone(),
+                encoding: encoding.clone(),
+                responses: counters.responses.load(Ordering::Relaxed),
+                bytes: counters.bytes.load(Ordering::Relaxed),
+            })
+            .collect();
+        stats.sort_by(|a, b| (&a.route, &a.encoding).cmp(&(&b.route, &b.encoding)));
+        stats
+    }
+}
+
+/// Count every response and its size under the route it matched
+pub async fn record_response_sizes(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(matched) => format!("{} {}", request.method(), matched.as_str()),
+        None => UNMATCHED_ROUTE.to_string(),
+    };
+    let response = next.run(request).await;
+    state.response_sizes.track(&route, response)
+}
@@ -0,0 +1,36 @@
+//! Routing of sandbox traffic
+//!
+//! Requests made with a sandbox API key are served by a router over the
+//! sandbox services, with their own data, the test Miden network, and mock
+//! providers, so test traffic never reaches production state. The key's
+//! prefix decides the route, before any lookup.
+
+use super::auth::API_KEY_HEADER;
+use crate::compliance::clients::SANDBOX_API_KEY_PREFIX;
+use crate::ComplianceError;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use tower::ServiceExt;
+
+/// Hand requests bearing a sandbox API key to the sandbox router
+///
+/// Sandbox keys are rejected while the sandbox is disabled.
+pub async fn route_sandbox(State(sandbox): State<Option<Router>>, request: Request, next: Next) -> Response {
+    let is_sandbox = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|api_key| api_key.starts_with(SANDBOX_API_KEY_PREFIX));
+    if !is_sandbox {
+        return next.run(request).await;
+    }
+    match sandbox {
+        Some(router) => match router.oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        },
+        None => ComplianceError::InvalidApiKey.into_response(),
+    }
+}
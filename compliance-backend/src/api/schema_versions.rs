@@ -0,0 +1,129 @@
+//! Payload schema version negotiation and pinning
+
+use super::auth::{ClientAuth, API_KEY_HEADER};
+use super::validation::{Valid, Validate};
+use super::AppState;
+use crate::compliance::schema_versions::{
+    check_version, current_version, downgrade_response, negotiate, versions, SchemaVersionInfo, SCHEMA_VERSION_HEADER,
+};
+use crate::types::SchemaPins;
+use crate::{ComplianceError, Result};
+use axum::body::{to_bytes, Body};
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+
+/// Serve each response in the schema version negotiated for it
+///
+/// The version is the one requested in the `X-ZeroTrust-Schema-Version`
+/// header, else the one the calling client pinned for the endpoint, else
+/// the current version. JSON responses are downgraded to it and every
+/// response carries it in the same header.
+pub async fn version_responses(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let requested = match request.headers().get(SCHEMA_VERSION_HEADER) {
+        Some(value) => match value.to_str().ok().and_then(|value| value.trim().parse().ok()) {
+            Some(version) => match check_version(SCHEMA_VERSION_HEADER, version) {
+                Ok(version) => Some(version),
+                Err(e) => return e.into_response(),
+            },
+            None => {
+                return ComplianceError::validation(SCHEMA_VERSION_HEADER, "must be a schema version number")
+                    .into_response()
+            }
+        },
+        None => None,
+    };
+    let client = match request.headers().get(API_KEY_HEADER).and_then(|value| value.to_str().ok()) {
+        Some(api_key) => state.clients.find_by_api_key(api_key).await.ok().flatten(),
+        None => None,
+    };
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path().to_string(), |path| path.as_str().to_string());
+    let endpoint = format!("{} {}", request.method(), route);
+    let version = negotiate(requested, client.as_ref().map(|client| &client.schema), &endpoint);
+    
+    let mut response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if version < current_version() && is_json {
+        response = downgrade(response, &endpoint, version).await;
+    }
+    response.headers_mut().insert(SCHEMA_VERSION_HEADER, HeaderValue::from(version));
+    response
+}
+
+/// Downgrade a JSON response body, leaving the bytes untouched when no adapter applies
+async fn downgrade(response: Response, endpoint: &str, version: u32) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return ComplianceError::internal("failed to read response body").into_response();
+    };
+    let Ok(mut value) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    if !downgrade_response(endpoint, &mut value, version) {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    match serde_json::to_vec(&value) {
+        Ok(body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(body))
+        }
+        Err(e) => ComplianceError::from(e).into_response(),
+    }
+}
+
+/// Available schema versions and the client's pins
+#[derive(Debug, Serialize)]
+pub struct SchemaVersionsResponse {
+    pub current: u32,
+    pub versions: Vec<SchemaVersionInfo>,
+    pub pins: SchemaPins,
+}
+
+/// `GET /v1/schema-versions`
+pub async fn get_schema_versions(ClientAuth(client): ClientAuth) -> Json<SchemaVersionsResponse> {
+    Json(SchemaVersionsResponse {
+        current: current_version(),
+        versions: versions(),
+        pins: client.schema,
+    })
+}
+
+impl Validate for SchemaPins {}
+
+/// `PUT /v1/schema-versions`
+///
+/// Pins the client's webhooks and API responses to schema versions, so
+/// fields added by later versions are only sent once the client opts in.
+pub async fn set_schema_pins(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(pins): Valid<SchemaPins>,
+) -> Result<Json<SchemaVersionsResponse>> {
+    let client = state.clients.set_schema_pins(client.id, pins).await?;
+    
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "client.schema_pinned",
+            None,
+            serde_json::json!({ "pins": client.schema }),
+        )
+        .await;
+    
+    Ok(Json(SchemaVersionsResponse {
+        current: current_version(),
+        versions: versions(),
+        pins: client.schema,
+    }))
+}
@@ -0,0 +1,409 @@
+//! Screening API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::metering::BillableOperation;
+use crate::compliance::screening::adjudications::{Adjudication, AdjudicationInput, ThresholdSuggestion};
+use crate::compliance::screening::attributes::SubjectAttributes;
+use crate::compliance::screening::delta::DeltaSummary;
+use crate::compliance::screening::matcher::NameMatcher;
+use crate::compliance::screening::normalize::is_language_tag;
+use crate::compliance::screening::results::ScreeningResult;
+use crate::compliance::screening::search::{self as name_search, SearchRequest, SearchResponse};
+use crate::compliance::screening::ScreenedEntity;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum number of results a single search may request
+const MAX_SEARCH_LIMIT: usize = 500;
+
+impl Validate for SearchRequest {
+    fn validate(&self, v: &mut Violations) {
+        check_subject(v, &self.name, self.language.as_deref(), &self.attributes);
+        v.ensure(
+            self.min_score.is_none_or(|score| (0.0..=1.0).contains(&score)),
+            "min_score",
+            "must be between 0 and 1",
+        );
+        v.ensure(
+            self.limit.is_none_or(|limit| limit > 0 && limit <= MAX_SEARCH_LIMIT),
+            "limit",
+            format!("must be between 1 and {}", MAX_SEARCH_LIMIT),
+        );
+    }
+}
+
+/// `POST /v1/screening/search`
+///
+/// Ad-hoc fuzzy and phonetic name search over ingested sanctions and PEP lists
+/// and the calling client's own watchlist.
+pub async fn search(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(request): Valid<SearchRequest>,
+) -> Result<Json<SearchResponse>> {
+    Ok(Json(run_search(&state, &request, Some(client.id)).await))
+}
+
+/// Query parameters for `POST /v1/admin/screening/search`
+#[derive(Debug, Deserialize)]
+pub struct AdminSearchQuery {
+    /// Also search this business client's watchlist
+    pub client_id: Option<Uuid>,
+}
+
+/// `POST /v1/admin/screening/search`
+///
+/// Search for compliance officers running manual checks, over the ingested
+/// lists and optionally any one client's watchlist.
+pub async fn admin_search(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<AdminSearchQuery>,
+    Valid(request): Valid<SearchRequest>,
+) -> Result<Json<SearchResponse>> {
+    auth.require(Permission::AdjudicateMatches)?;
+    if let Some(client_id) = query.client_id {
+        state.clients.get(client_id).await?;
+    }
+    Ok(Json(run_search(&state, &request, query.client_id).await))
+}
+
+/// Search the ingested lists, and the watchlist of `client_id` when given
+async fn run_search(state: &AppState, request: &SearchRequest, client_id: Option<Uuid>) -> SearchResponse {
+    let matcher = name_matcher(state, request.min_score);
+    let watchlist_hits = match client_id {
+        Some(client_id) => {
+            state
+                .watchlists
+                .check_identity(client_id, &request.name, request.language.as_deref(), &matcher)
+                .await
+        }
+        None => vec![],
+    };
+    name_search::search(&state.screening_lists, &matcher, request, watchlist_hits).await
+}
+
+/// Request body for screening an account holder's name
+#[derive(Debug, Deserialize)]
+pub struct ScreenAccountRequest {
+    pub name: String,
+    
+    /// BCP 47 language of the name as recorded in the holder's KYC data
+    pub language: Option<String>,
+    
+    /// Date of birth, nationalities and other attributes from the holder's KYC data
+    #[serde(default)]
+    pub attributes: SubjectAttributes,
+}
+
+impl Validate for ScreenAccountRequest {
+    fn validate(&self, v: &mut Violations) {
+        check_subject(v, &self.name, self.language.as_deref(), &self.attributes);
+    }
+}
+
+/// Matcher with the configured attribute weights and threshold, unless overridden
+pub(super) fn name_matcher(state: &AppState, threshold: Option<f64>) -> NameMatcher {
+    let sanctions = &state.live_config.compliance().sanctions;
+    NameMatcher::new(threshold.unwrap_or(sanctions.fuzzy_match_threshold))
+        .with_attribute_weights(sanctions.attribute_weights.clone())
+}
+
+/// Check the name, language hint and attributes of a screened subject
+fn check_subject(v: &mut Violations, name: &str, language: Option<&str>, attributes: &SubjectAttributes) {
+    v.ensure(!name.trim().is_empty(), "name", "must not be empty");
+    v.ensure(
+        language.is_none_or(is_language_tag),
+        "language",
+        "must be a BCP 47 language tag",
+    );
+    v.absorb(attributes.validate());
+}
+
+/// `POST /v1/accounts/{id}/screening`
+///
+/// Screens the account holder's name against every ingested list and records
+/// the result, including the list versions it was screened against, so later
+/// list updates re-screen the account only when they could affect it.
+pub async fn screen_account(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<ScreenAccountRequest>,
+) -> Result<Json<ScreeningResult>> {
+    state.meter.charge(client.id, BillableOperation::Screening).await?;
+    
+    let matcher = name_matcher(&state, None);
+    let result = state
+        .screening_results
+        .screen(
+            &state.screening_lists,
+            &matcher,
+            &account_id,
+            Some(client.id),
+            &request.name,
+            request.language.as_deref(),
+            &request.attributes,
+        )
+        .await;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "account.screened",
+            Some(&account_id),
+            serde_json::json!({
+                "matches": result.matches.len(),
+                "suppressed": result.suppressed.len(),
+                "language": result.language,
+                "list_versions": result.list_versions,
+            }),
+        )
+        .await;
+    
+    Ok(Json(result))
+}
+
+/// Request body for ingesting a screening list version
+#[derive(Debug, Deserialize)]
+pub struct IngestListRequest {
+    pub version: String,
+    pub entities: Vec<ScreenedEntity>,
+}
+
+impl Validate for IngestListRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.version.trim().is_empty(), "version", "must not be empty");
+    }
+}
+
+/// An account whose screening outcome changed after a list update
+#[derive(Debug, Serialize)]
+pub struct ChangedOutcome {
+    pub account_id: AccountId,
+    pub previous_matches: usize,
+    pub current_matches: usize,
+    /// Attestation reissued for the account, if the compliance check succeeded
+    pub attestation_id: Option<uuid::Uuid>,
+}
+
+/// Result of ingesting a list version
+#[derive(Debug, Serialize)]
+pub struct IngestListResponse {
+    pub delta: DeltaSummary,
+    /// Number of accounts re-screened because they intersect the delta
+    pub rescreened: usize,
+    pub changed: Vec<ChangedOutcome>,
+}
+
+/// `PUT /v1/admin/screening/lists/{name}`
+///
+/// Ingests a new version of a sanctions or PEP list, computes the entries
+/// added, removed, and changed since the previous version, and re-screens
+/// only the accounts whose prior matches or name keys intersect that delta.
+/// Accounts whose outcome changed get a fresh compliance check.
+pub async fn ingest_list(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(name): Path<String>,
+    Valid(request): Valid<IngestListRequest>,
+) -> Result<Json<IngestListResponse>> {
+    auth.require(Permission::ManageScreeningLists)?;
+    if let Some(entity) = request.entities.iter().find(|entity| entity.list != name) {
+        return Err(ComplianceError::validation(
+            "entities",
+            format!("entity {} belongs to list {}, not {}", entity.id, entity.list, name),
+        ));
+    }
+    if state.screening_lists.get(&name).await.is_some_and(|list| list.version == request.version) {
+        return Err(ComplianceError::validation(
+            "version",
+            format!("version {} of {} is already ingested", request.version, name),
+        ));
+    }
+    
+    let delta = state.screening_lists.ingest(&name, &request.version, request.entities).await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "screening_list.ingested",
+            None,
+            serde_json::to_value(delta.summary())?,
+        )
+        .await;
+    
+    let matcher = name_matcher(&state, None);
+    let rescreenings = state
+        .screening_results
+        .rescreen(&state.screening_lists, &matcher, &delta)
+        .await;
+    
+    let mut changed = Vec::new();
+    for rescreening in rescreenings.iter().filter(|r| r.outcome_changed) {
+        let attestation = match state.compliance.update_compliance_status(&rescreening.account_id).await {
+            Ok(attestation) => {
+                state.alerts.observe_attestation(&attestation).await;
+                Some(attestation.id)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    account_id = %rescreening.account_id,
+                    error = %e,
+                    "compliance check after list update failed"
+                );
+                None
+            }
+        };
+        state
+            .audit
+            .record(
+                &auth.operator.username,
+                "account.rescreened",
+                Some(&rescreening.account_id),
+                serde_json::json!({
+                    "list": delta.list,
+                    "from_version": delta.from_version,
+                    "to_version": delta.to_version,
+                    "previous_matches": rescreening.previous.matches,
+                    "current_matches": rescreening.current.matches,
+                    "attestation_id": attestation,
+                }),
+            )
+            .await;
+        changed.push(ChangedOutcome {
+            account_id: rescreening.account_id.clone(),
+            previous_matches: rescreening.previous.matches.len(),
+            current_matches: rescreening.current.matches.len(),
+            attestation_id: attestation,
+        });
+    }
+    
+    Ok(Json(IngestListResponse {
+        delta: delta.summary(),
+        rescreened: rescreenings.len(),
+        changed,
+    }))
+}
+
+impl Validate for AdjudicationInput {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.list.trim().is_empty(), "list", "must not be empty");
+        v.ensure(!self.entity_id.trim().is_empty(), "entity_id", "must not be empty");
+    }
+}
+
+/// `POST /v1/admin/screening/results/{id}/adjudications`
+///
+/// Records an analyst's verdict on one of the account's screening matches.
+/// False positives are suppressed on later screens until the list entry
+/// changes.
+pub async fn adjudicate_match(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(input): Valid<AdjudicationInput>,
+) -> Result<Json<Adjudication>> {
+    auth.require(Permission::AdjudicateMatches)?;
+    
+    let adjudication = state
+        .screening_results
+        .adjudicate(&state.screening_lists, &account_id, &auth.operator.username, input)
+        .await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "screening_match.adjudicated",
+            Some(&account_id),
+            serde_json::json!({
+                "adjudication_id": adjudication.id,
+                "list": adjudication.list,
+                "entity_id": adjudication.entity.id,
+                "verdict": adjudication.verdict,
+                "score": adjudication.score,
+            }),
+        )
+        .await;
+    
+    Ok(Json(adjudication))
+}
+
+/// `GET /v1/admin/screening/results/{id}/adjudications`
+pub async fn list_adjudications(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<Vec<Adjudication>>> {
+    auth.require(Permission::ViewCases)?;
+    Ok(Json(state.screening_results.adjudications().for_account(&account_id).await))
+}
+
+/// `GET /v1/admin/screening/threshold-suggestions`
+///
+/// Per-client fuzzy match thresholds that adjudicated false positives suggest.
+pub async fn threshold_suggestions(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+) -> Result<Json<Vec<ThresholdSuggestion>>> {
+    auth.require(Permission::TuneScreening)?;
+    let configured = state.live_config.compliance().sanctions.fuzzy_match_threshold;
+    Ok(Json(state.screening_results.adjudications().suggestions(configured).await))
+}
+
+/// Request body for adopting a client's screening threshold
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetThresholdRequest {
+    /// Threshold for the client's accounts; `null` reverts to the configured one
+    pub threshold: Option<f64>,
+}
+
+impl Validate for SetThresholdRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(
+            self.threshold.is_none_or(|threshold| (0.0..=1.0).contains(&threshold)),
+            "threshold",
+            "must be between 0 and 1",
+        );
+    }
+}
+
+/// `PUT /v1/admin/clients/{client_id}/screening-threshold`
+///
+/// Applies to the client's accounts from their next screen, including
+/// re-screens after list updates.
+pub async fn set_client_threshold(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(client_id): Path<uuid::Uuid>,
+    Valid(request): Valid<SetThresholdRequest>,
+) -> Result<Json<SetThresholdRequest>> {
+    auth.require(Permission::TuneScreening)?;
+    state.clients.get(client_id).await?;
+    
+    let adjudications = state.screening_results.adjudications();
+    let previous = adjudications.threshold(client_id).await;
+    adjudications.set_threshold(client_id, request.threshold).await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "screening_threshold.set",
+            None,
+            serde_json::json!({
+                "client_id": client_id,
+                "previous": previous,
+                "threshold": request.threshold,
+            }),
+        )
+        .await;
+    
+    Ok(Json(request))
+}
@@ -0,0 +1,128 @@
+//! Threshold signing request handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::threshold_signing::{SigningRequest, SigningRequestStatus};
+use crate::crypto::signing::SIGNATURE_LENGTH;
+use crate::crypto::PartialSignature;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+impl Validate for PartialSignature {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.key_id.trim().is_empty(), "key_id", "must not be empty");
+        v.ensure(
+            self.signature.len() == SIGNATURE_LENGTH * 2 && self.signature.bytes().all(|b| b.is_ascii_hexdigit()),
+            "signature",
+            format!("must be a hex-encoded {} byte signature", SIGNATURE_LENGTH),
+        );
+    }
+}
+
+/// `POST /v1/admin/accounts/{id}/signing-requests`
+///
+/// Opens a signing request for the account's attestation at the highest
+/// level it holds, under the threshold policy for that level. The automated
+/// signer signs at once when the policy lists it.
+pub async fn open_request(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<SigningRequest>> {
+    auth.require(Permission::CosignAttestations)?;
+    let now = state.compliance.clock.now();
+    let attestation = state
+        .compliance
+        .events
+        .project(&account_id, None)
+        .await?
+        .filter(|current| current.is_valid_at(now))
+        .ok_or_else(|| ComplianceError::AccountNotFound {
+            account_id: account_id.to_string(),
+        })?
+        .attestation;
+    let level = state
+        .compliance
+        .highest_compliance_level(&attestation)
+        .await
+        .ok_or_else(|| ComplianceError::validation("account_id", "account holds no compliance level to sign for"))?;
+    let request = state.threshold_signing.open(&attestation, level, &state.signer).await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "signing_request.opened",
+            Some(&account_id),
+            serde_json::json!({
+                "request_id": request.id,
+                "attestation_id": request.attestation_id,
+                "level": request.level,
+                "threshold": request.policy.threshold,
+            }),
+        )
+        .await;
+    Ok(Json(request))
+}
+
+/// Query parameters for listing signing requests
+#[derive(Debug, Deserialize)]
+pub struct SigningRequestQuery {
+    /// Requests in this status (defaults to `pending`)
+    pub status: Option<SigningRequestStatus>,
+}
+
+/// `GET /v1/admin/signing-requests`
+pub async fn list_requests(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<SigningRequestQuery>,
+) -> Result<Json<Vec<SigningRequest>>> {
+    auth.require(Permission::CosignAttestations)?;
+    let status = query.status.unwrap_or(SigningRequestStatus::Pending);
+    Ok(Json(state.threshold_signing.list(Some(status)).await))
+}
+
+/// `GET /v1/admin/signing-requests/{request_id}`
+pub async fn get_request(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(request_id): Path<Uuid>,
+) -> Result<Json<SigningRequest>> {
+    auth.require(Permission::CosignAttestations)?;
+    Ok(Json(state.threshold_signing.get(request_id).await?))
+}
+
+/// `POST /v1/admin/signing-requests/{request_id}/signatures`
+///
+/// Adds a partial signature made over the request's message, such as one
+/// produced by a compliance officer's HSM key. The response carries the
+/// combined signature once the threshold is met.
+pub async fn submit_signature(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(request_id): Path<Uuid>,
+    Valid(partial): Valid<PartialSignature>,
+) -> Result<Json<SigningRequest>> {
+    auth.require(Permission::CosignAttestations)?;
+    let key_id = partial.key_id.clone();
+    let request = state.threshold_signing.submit(request_id, partial).await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "signing_request.signed",
+            Some(&request.account_id),
+            serde_json::json!({
+                "request_id": request.id,
+                "key_id": key_id,
+                "status": request.status,
+            }),
+        )
+        .await;
+    Ok(Json(request))
+}
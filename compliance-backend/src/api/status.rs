@@ -0,0 +1,32 @@
+//! Public status page handler
+
+use super::AppState;
+use crate::Result;
+use axum::extract::connect_info::ConnectInfo;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::{Extension, Json};
+use std::net::SocketAddr;
+
+/// `GET /v1/status`
+///
+/// Unauthenticated and rate limited per caller address; only routed when
+/// the status page is enabled. The response may be cached by intermediaries
+/// for the refresh period, since it does not change any sooner.
+pub async fn service_status(
+    State(state): State<AppState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+) -> Result<impl IntoResponse> {
+    state
+        .status_page
+        .admit(connect_info.map(|Extension(ConnectInfo(addr))| addr.ip()))
+        .await?;
+    
+    let status = state
+        .status_page
+        .status(&state.screening_lists, &state.compliance.breakers)
+        .await;
+    let cache_control = format!("public, max-age={}", state.config.status_page.refresh_secs);
+    Ok(([(header::CACHE_CONTROL, cache_control)], Json(status)))
+}
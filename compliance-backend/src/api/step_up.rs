@@ -0,0 +1,290 @@
+//! Step-up verification API handlers
+
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::{preferred_locales, AppState};
+use crate::compliance::localization::{LocalizedMessage, Message};
+use crate::compliance::step_up::{StepUpRequirement, StepUpSession, StepUpStatus};
+use crate::compliance::upgrades::UpgradePlan;
+use crate::types::{AccountId, ComplianceAttestation, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Step-up session with display text for its requirements
+#[derive(Debug, Serialize)]
+pub struct StepUpSessionResponse {
+    #[serde(flatten)]
+    pub session: StepUpSession,
+    
+    /// Names of the requirements, in the caller's `Accept-Language`
+    pub display: Vec<LocalizedMessage>,
+}
+
+/// Wrap a session with its requirements' display text
+fn respond(state: &AppState, headers: &HeaderMap, session: StepUpSession) -> Json<StepUpSessionResponse> {
+    let messages: Vec<Message> = session
+        .requirements
+        .iter()
+        .map(|progress| Message::requirement(progress.requirement))
+        .collect();
+    let display = state.catalog.render_all(&messages, &preferred_locales(headers));
+    Json(StepUpSessionResponse { session, display })
+}
+
+/// Request body for opening a step-up session
+#[derive(Debug, Deserialize)]
+pub struct CreateStepUpRequest {
+    pub target_level: ComplianceLevel,
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+impl Validate for CreateStepUpRequest {}
+
+/// Request body for upgrading an account's compliance level
+#[derive(Debug, Deserialize)]
+pub struct UpgradeRequest {
+    pub target_level: ComplianceLevel,
+}
+
+impl Validate for UpgradeRequest {}
+
+/// Where an upgrade stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeStatus {
+    /// Waiting for evidence through the step-up session
+    PendingEvidence,
+    /// Attestation in force holds the target level
+    Upgraded,
+    /// Attestation in force, re-issued when reassessed, did not reach the target level
+    Failed,
+}
+
+/// Outcome of requesting a compliance level upgrade
+#[derive(Debug, Serialize)]
+pub struct UpgradeResponse {
+    pub status: UpgradeStatus,
+    pub plan: UpgradePlan,
+    
+    /// Session collecting the outstanding evidence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<StepUpSessionResponse>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<ComplianceAttestation>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub achieved_level: Option<ComplianceLevel>,
+}
+
+/// Request body for submitting step-up evidence
+#[derive(Debug, Deserialize)]
+pub struct SubmitEvidenceRequest {
+    pub requirement: StepUpRequirement,
+    pub evidence_ref: String,
+}
+
+impl Validate for SubmitEvidenceRequest {
+    fn validate(&self, v: &mut Violations) {
+        let expected = match self.requirement {
+            StepUpRequirement::SourceOfFundsDeclaration => "must be a funds declaration id",
+            StepUpRequirement::AdditionalDocument | StepUpRequirement::ReLiveness => "must be a document id",
+        };
+        v.ensure(self.evidence_ref.parse::<Uuid>().is_ok(), "evidence_ref", expected);
+    }
+}
+
+/// Check that evidence references something stored for the session's account
+///
+/// Source-of-funds evidence must be one of the account's funds declarations;
+/// document and re-liveness evidence must be a document the client uploaded
+/// to one of the account's verification sessions.
+async fn check_evidence(
+    state: &AppState,
+    client_id: Uuid,
+    account_id: &AccountId,
+    request: &SubmitEvidenceRequest,
+) -> Result<()> {
+    let evidence_id = request
+        .evidence_ref
+        .parse::<Uuid>()
+        .map_err(|_| ComplianceError::validation("evidence_ref", "must be a document or funds declaration id"))?;
+    
+    match request.requirement {
+        StepUpRequirement::SourceOfFundsDeclaration => {
+            if state.compliance.funds.get(evidence_id).await?.account_id != *account_id {
+                return Err(ComplianceError::validation(
+                    "evidence_ref",
+                    "declaration belongs to a different account",
+                ));
+            }
+        }
+        StepUpRequirement::AdditionalDocument | StepUpRequirement::ReLiveness => {
+            let uploaded = state
+                .verification_sessions
+                .for_account(client_id, account_id)
+                .await
+                .iter()
+                .flat_map(|session| &session.documents)
+                .any(|document| document.id == evidence_id);
+            if !uploaded {
+                return Err(ComplianceError::validation(
+                    "evidence_ref",
+                    "must be a document uploaded for the account",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `POST /v1/accounts/{id}/step-up`
+pub async fn create_session(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    headers: HeaderMap,
+    Valid(request): Valid<CreateStepUpRequest>,
+) -> Result<Json<StepUpSessionResponse>> {
+    let current_level = match state.compliance.get_compliance_status(&account_id).await? {
+        Some(att) => state.compliance.highest_compliance_level(&att).await,
+        None => None,
+    };
+    
+    let session = state
+        .step_up
+        .create_session(client.id, &account_id, current_level, request.target_level, request.reasons)
+        .await?;
+    
+    Ok(respond(&state, &headers, session))
+}
+
+/// `POST /v1/accounts/{id}/upgrade-compliance-level`
+///
+/// Works out which checks moving the account to the requested level still
+/// needs and runs only those. Outstanding evidence opens a step-up session,
+/// whose completion re-runs the checks an AML reassessment needs; with none
+/// outstanding they are re-run right away. An account missing verified
+/// declarations is told so and nothing is issued.
+pub async fn upgrade_compliance_level(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    headers: HeaderMap,
+    Valid(request): Valid<UpgradeRequest>,
+) -> Result<Json<UpgradeResponse>> {
+    let plan = state.compliance.upgrade_plan(&account_id, request.target_level).await?;
+    if plan.current_level >= plan.target_level {
+        return Err(ComplianceError::validation(
+            "target_level",
+            format!("account already holds {:?}", plan.current_level),
+        ));
+    }
+    
+    if !plan.requirements.is_empty() {
+        let session = state
+            .step_up
+            .create_upgrade_session(
+                client.id,
+                &account_id,
+                plan.current_level.clone(),
+                plan.target_level.clone(),
+                plan.requirements.clone(),
+            )
+            .await?;
+        return Ok(Json(UpgradeResponse {
+            status: UpgradeStatus::PendingEvidence,
+            plan,
+            session: Some(respond(&state, &headers, session).0),
+            attestation: None,
+            achieved_level: None,
+        }));
+    }
+    if let Some(missing) = state.compliance.upgrade_shortfall(&plan).await {
+        return Err(ComplianceError::validation("target_level", missing));
+    }
+    
+    let attestation = state.compliance.upgrade_attestation(&account_id, plan.target_level.clone()).await?;
+    state.alerts.observe_attestation(&attestation).await;
+    let achieved_level = state.compliance.highest_compliance_level(&attestation).await;
+    let status = if achieved_level.as_ref().is_some_and(|level| *level >= plan.target_level) {
+        UpgradeStatus::Upgraded
+    } else {
+        UpgradeStatus::Failed
+    };
+    Ok(Json(UpgradeResponse {
+        status,
+        plan,
+        session: None,
+        attestation: Some(attestation),
+        achieved_level,
+    }))
+}
+
+/// `GET /v1/step-up/{session_id}`
+pub async fn get_session(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(session_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<StepUpSessionResponse>> {
+    let session = state.step_up.get_session(client.id, session_id).await?;
+    Ok(respond(&state, &headers, session))
+}
+
+/// `POST /v1/step-up/{session_id}/evidence`
+///
+/// Once the last requirement is satisfied the account's checks are re-run and
+/// the attestation is re-issued, moving the session to `upgraded` or `failed`.
+/// Sessions opened by the upgrade path only run the checks still needed.
+/// Evidence must reference a funds declaration or uploaded document for the
+/// account (see [`check_evidence`]).
+pub async fn submit_evidence(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(session_id): Path<Uuid>,
+    headers: HeaderMap,
+    Valid(request): Valid<SubmitEvidenceRequest>,
+) -> Result<Json<StepUpSessionResponse>> {
+    let account_id = state.step_up.get_session(client.id, session_id).await?.account_id;
+    check_evidence(&state, client.id, &account_id, &request).await?;
+    
+    let session = state
+        .step_up
+        .submit_evidence(client.id, session_id, request.requirement, request.evidence_ref)
+        .await?;
+    
+    if session.status != StepUpStatus::Completed {
+        return Ok(respond(&state, &headers, session));
+    }
+    
+    let attestation = if session.upgrade {
+        state
+            .compliance
+            .upgrade_attestation(&session.account_id, session.target_level.clone())
+            .await?
+    } else {
+        state.compliance.update_compliance_status(&session.account_id).await?
+    };
+    state.alerts.observe_attestation(&attestation).await;
+    let achieved_level = state.compliance.highest_compliance_level(&attestation).await;
+    let session = state.step_up.record_reissue(session_id, achieved_level).await?;
+    
+    Ok(respond(&state, &headers, session))
+}
+
+/// `POST /v1/step-up/{session_id}/cancel`
+pub async fn cancel_session(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(session_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<StepUpSessionResponse>> {
+    let session = state.step_up.cancel(client.id, session_id).await?;
+    Ok(respond(&state, &headers, session))
+}
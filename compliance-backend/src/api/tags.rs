@@ -0,0 +1,100 @@
+//! Client tag handlers
+
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::tags::{TagFilter, TagUpdate, TaggedKind, TaggedRecord, Tags};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Query parameters filtering a list by tags
+#[derive(Debug, Default, Deserialize)]
+pub struct TagQuery {
+    /// Comma-separated `key:value` or `key` conditions, all of which must hold
+    pub tag: Option<String>,
+}
+
+impl TagQuery {
+    /// The parsed filter, if one was given
+    pub fn filter(&self) -> Result<Option<TagFilter>> {
+        TagFilter::from_query(self.tag.as_deref())
+    }
+}
+
+/// Tag filter of an operator list endpoint
+///
+/// Tags belong to a business client, so filtering by them needs the client
+/// whose tags are meant.
+pub(crate) fn client_filter(client_id: Option<Uuid>, tag: Option<&str>) -> Result<Option<(Uuid, TagFilter)>> {
+    match (client_id, TagFilter::from_query(tag)?) {
+        (_, None) => Ok(None),
+        (Some(client_id), Some(filter)) => Ok(Some((client_id, filter))),
+        (None, Some(_)) => Err(ComplianceError::validation("client_id", "is required to filter by tag")),
+    }
+}
+
+impl Validate for TagUpdate {
+    fn validate(&self, v: &mut Violations) {
+        v.absorb(TagUpdate::validate(self));
+    }
+}
+
+/// `GET /v1/tags/{kind}`
+///
+/// The client's tagged records of a kind, optionally filtered by tag.
+pub async fn list_tagged(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(kind): Path<TaggedKind>,
+    Query(query): Query<TagQuery>,
+) -> Result<Json<Vec<TaggedRecord>>> {
+    let filter = query.filter()?.unwrap_or_default();
+    Ok(Json(state.tags.list(client.id, kind, &filter).await))
+}
+
+/// `GET /v1/tags/{kind}/{id}`
+pub async fn get_tags(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path((kind, id)): Path<(TaggedKind, String)>,
+) -> Result<Json<Tags>> {
+    Ok(Json(state.tags.get(client.id, kind, &id).await?))
+}
+
+/// `PATCH /v1/tags/{kind}/{id}`
+///
+/// Sets and removes tags on a record. A change is sent to the client's
+/// webhook as a `tags.changed` event.
+pub async fn update_tags(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path((kind, id)): Path<(TaggedKind, String)>,
+    Valid(update): Valid<TagUpdate>,
+) -> Result<Json<TaggedRecord>> {
+    let set: Vec<String> = update.set.keys().cloned().collect();
+    let removed = update.remove.clone();
+    let record = state.tags.update(client.id, kind, &id, update).await?;
+    let account_id = match kind {
+        TaggedKind::Account => Some(AccountId::parse(&record.id)?),
+        TaggedKind::Attestation | TaggedKind::Case => None,
+    };
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "tags.updated",
+            account_id.as_ref(),
+            serde_json::json!({
+                "kind": kind,
+                "id": record.id,
+                "set": set,
+                "removed": removed,
+            }),
+        )
+        .await;
+    Ok(Json(record))
+}
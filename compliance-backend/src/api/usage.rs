@@ -0,0 +1,68 @@
+//! Billable usage handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::auth::ClientAuth;
+use super::AppState;
+use crate::compliance::metering::{CostReport, UsageSummary};
+use crate::{ComplianceError, Result};
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+
+/// Query parameters selecting a billing period
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Month of the billing period, as `YYYY-MM`; the current period when absent
+    pub period: Option<String>,
+}
+
+impl UsageQuery {
+    /// A time within the selected billing period
+    fn at(&self, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let Some(period) = &self.period else {
+            return Ok(now);
+        };
+        NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d")
+            .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+            .map_err(|_| ComplianceError::validation("period", "must be a month, as YYYY-MM"))
+    }
+}
+
+/// `GET /v1/usage`
+///
+/// The client's billable usage in a billing period, against its plan's quotas.
+pub async fn client_usage(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageSummary>> {
+    let at = query.at(state.compliance.clock.now())?;
+    Ok(Json(state.meter.usage(client.id, at).await?))
+}
+
+/// `GET /v1/admin/usage`
+///
+/// Billable usage of every client with usage in a billing period.
+pub async fn all_usage(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<Vec<UsageSummary>>> {
+    auth.require(Permission::ManageClients)?;
+    let at = query.at(state.compliance.clock.now())?;
+    Ok(Json(state.meter.period_usage(at).await?))
+}
+
+/// `GET /v1/admin/usage/costs`
+///
+/// What provider calls made for each client with usage cost in a billing period.
+pub async fn all_costs(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<Vec<CostReport>>> {
+    auth.require(Permission::ManageClients)?;
+    let at = query.at(state.compliance.clock.now())?;
+    Ok(Json(state.meter.period_costs(at).await?))
+}
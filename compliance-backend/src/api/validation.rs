@@ -0,0 +1,177 @@
+//! Request body validation
+//!
+//! Handlers take their JSON bodies through [`Valid`], which deserializes the
+//! body and then runs the type's [`Validate`] checks before the handler sees
+//! it. Every problem a type's checks find is reported at once, each under the
+//! JSON pointer of the offending field, in a single `validation_error`
+//! response. A body that does not deserialize is reported under the pointer
+//! of the field serde stopped at.
+//!
+//! Checks that need state, such as whether a referenced list version was
+//! ingested, stay in the handlers.
+
+use super::body_limits::RouteLimit;
+use crate::error::{json_pointer, FieldViolation, FieldViolations};
+use crate::{ComplianceError, Result};
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::{header, HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_path_to_error::Segment;
+
+/// Field-level checks of a request body
+pub trait Validate {
+    /// Record every problem with the value
+    fn validate(&self, _violations: &mut Violations) {}
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self, violations: &mut Violations) {
+        for (i, item) in self.iter().enumerate() {
+            violations.at(&i.to_string(), |violations| item.validate(violations));
+        }
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self, violations: &mut Violations) {
+        if let Some(value) = self {
+            value.validate(violations);
+        }
+    }
+}
+
+/// Problems found while validating a request body
+#[derive(Debug, Default)]
+pub struct Violations {
+    /// Pointer to the value being validated
+    prefix: String,
+    found: Vec<FieldViolation>,
+}
+
+impl Violations {
+    /// Create an empty set of violations for a whole body
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record a problem with a field of the value being validated
+    ///
+    /// Fields are named with dots and indexes (`transactions[3].amount`) or
+    /// as JSON pointers relative to the value.
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.found.push(FieldViolation {
+            pointer: format!("{}{}", self.prefix, json_pointer(field)),
+            message: message.into(),
+        });
+    }
+    
+    /// Record a problem with a field unless `ok` holds
+    pub fn ensure(&mut self, ok: bool, field: &str, message: impl Into<String>) {
+        if !ok {
+            self.push(field, message);
+        }
+    }
+    
+    /// Validate a nested value, recording its problems under `field`
+    pub fn at(&mut self, field: &str, f: impl FnOnce(&mut Self)) {
+        let len = self.prefix.len();
+        self.prefix.push_str(&json_pointer(field));
+        f(self);
+        self.prefix.truncate(len);
+    }
+    
+    /// Record the problems behind an error returned by a domain type's own checks
+    ///
+    /// Errors other than validation errors are recorded against the value itself.
+    pub fn absorb(&mut self, result: Result<()>) {
+        let Err(e) = result else {
+            return;
+        };
+        match e.violations() {
+            violations if violations.is_empty() => self.push("", e.to_string()),
+            violations => {
+                for violation in violations {
+                    self.push(&violation.pointer, violation.message);
+                }
+            }
+        }
+    }
+    
+    /// Whether no problem was recorded
+    pub fn is_empty(&self) -> bool {
+        self.found.is_empty()
+    }
+    
+    /// Fail with every recorded problem, if there is any
+    pub fn into_result(self) -> Result<()> {
+        if self.found.is_empty() {
+            Ok(())
+        } else {
+            Err(ComplianceError::InvalidRequest(FieldViolations(self.found)))
+        }
+    }
+}
+
+/// Deserialize and validate a JSON request body
+pub fn from_slice<T: DeserializeOwned + Validate>(bytes: &[u8]) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value: T = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        let pointer = e.path().iter().fold(String::new(), |mut pointer, segment| {
+            let token = match segment {
+                Segment::Seq { index } => index.to_string(),
+                Segment::Map { key } => key.clone(),
+                Segment::Enum { variant } => variant.clone(),
+                Segment::Unknown => return pointer,
+            };
+            pointer.push('/');
+            pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+            pointer
+        });
+        ComplianceError::InvalidRequest(FieldViolations(vec![FieldViolation {
+            pointer,
+            message: e.into_inner().to_string(),
+        }]))
+    })?;
+    deserializer
+        .end()
+        .map_err(|e| ComplianceError::validation("", e.to_string()))?;
+    
+    let mut violations = Violations::new();
+    value.validate(&mut violations);
+    violations.into_result()?;
+    Ok(value)
+}
+
+/// JSON request body that passed its [`Validate`] checks
+pub struct Valid<T>(pub T);
+
+impl<T, S> FromRequest<S> for Valid<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ComplianceError;
+    
+    async fn from_request(req: Request, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        if !is_json(req.headers()) {
+            return Err(ComplianceError::validation("", "request body must be application/json"));
+        }
+        let limit = req.extensions().get::<RouteLimit>().cloned();
+        let bytes = Bytes::from_request(req, state).await.map_err(|e| match limit {
+            Some(limit) if e.status() == StatusCode::PAYLOAD_TOO_LARGE => limit.too_large(),
+            _ => ComplianceError::validation("", e.body_text()),
+        })?;
+        Ok(Self(from_slice(&bytes)?))
+    }
+}
+
+/// Whether the request declares a JSON body
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim)
+        .is_some_and(|mime| mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json")))
+}
@@ -0,0 +1,218 @@
+//! End-user verification session API handlers
+//!
+//! Clients open and manage sessions with their API key; end users act on
+//! their own session with its bearer token under `/v1/end-user/session`.
+
+use super::auth::{ClientAuth, SessionAuth};
+use super::validation::{Valid, Validate, Violations};
+use super::{preferred_locales, AppState};
+use crate::compliance::localization::LocalizedMessage;
+use crate::compliance::provider_credentials;
+use crate::compliance::verification_sessions::{
+    CreateSessionRequest, EndUserSessionView, IssuedSession, VerificationSession,
+};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::multipart::{Field, MultipartError};
+use axum::extract::{Multipart, Path, State};
+use axum::http::HeaderMap;
+use axum::Json;
+use serde::Serialize;
+use uuid::Uuid;
+
+impl Validate for CreateSessionRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(self.ttl_secs != Some(0), "ttl_secs", "must be greater than 0");
+        v.ensure(
+            self.redirect_url.as_ref().is_none_or(|url| url.starts_with("https://")),
+            "redirect_url",
+            "must be an https URL",
+        );
+    }
+}
+
+/// `POST /v1/accounts/{id}/verification-sessions`
+///
+/// Opens a session and returns the token to hand to the end user, along
+/// with a hosted page link when one is configured.
+pub async fn create_session(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+    Valid(request): Valid<CreateSessionRequest>,
+) -> Result<Json<IssuedSession>> {
+    let issued = state.verification_sessions.create(client.id, &account_id, request).await?;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "verification_session.created",
+            Some(&account_id),
+            serde_json::json!({
+                "session_id": issued.session.id,
+                "expires_at": issued.session.expires_at,
+            }),
+        )
+        .await;
+    
+    Ok(Json(issued))
+}
+
+/// `GET /v1/accounts/{id}/verification-sessions`
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Json<Vec<VerificationSession>> {
+    Json(state.verification_sessions.for_account(client.id, &account_id).await)
+}
+
+/// `GET /v1/verification-sessions/{session_id}`
+pub async fn get_session(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<VerificationSession>> {
+    Ok(Json(state.verification_sessions.get(client.id, session_id).await?))
+}
+
+/// `POST /v1/verification-sessions/{session_id}/cancel`
+///
+/// Cancels a session the end user has not submitted; its token stops working.
+pub async fn cancel_session(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<VerificationSession>> {
+    let session = state.verification_sessions.cancel(client.id, session_id).await?;
+    state
+        .audit
+        .record(
+            &client.id.to_string(),
+            "verification_session.cancelled",
+            Some(&session.account_id),
+            serde_json::json!({ "session_id": session.id }),
+        )
+        .await;
+    
+    Ok(Json(session))
+}
+
+/// A session as its end user sees it, with display text for the outcome
+#[derive(Debug, Serialize)]
+pub struct EndUserSessionResponse {
+    #[serde(flatten)]
+    pub session: EndUserSessionView,
+    
+    /// Why verification fell short, in the caller's `Accept-Language`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub display: Vec<LocalizedMessage>,
+}
+
+fn respond(state: &AppState, headers: &HeaderMap, session: &VerificationSession) -> Json<EndUserSessionResponse> {
+    Json(EndUserSessionResponse {
+        session: state.verification_sessions.view(session),
+        display: state.catalog.render_all(&session.messages, &preferred_locales(headers)),
+    })
+}
+
+/// `GET /v1/end-user/session`
+///
+/// Polled by the end user for the session's progress and outcome.
+pub async fn end_user_session(
+    State(state): State<AppState>,
+    SessionAuth(session): SessionAuth,
+    headers: HeaderMap,
+) -> Json<EndUserSessionResponse> {
+    respond(&state, &headers, &session)
+}
+
+/// `POST /v1/end-user/session/documents`
+///
+/// Takes a `multipart/form-data` body: a `document_type` field, one of
+/// `kyc.supported_documents`, followed by a `document` part typed by its
+/// `Content-Type`. The document is read as it streams in and rejected as
+/// soon as it exceeds `kyc.documents.max_document_bytes`; it is then
+/// scanned and stored without passing through the client.
+pub async fn upload_document(
+    State(state): State<AppState>,
+    SessionAuth(session): SessionAuth,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Json<EndUserSessionResponse>> {
+    let max_bytes = state.live_config.compliance().kyc.documents.max_document_bytes;
+    let mut document_type = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().map(str::to_string);
+        match name.as_deref() {
+            Some("document_type") => document_type = Some(field.text().await.map_err(multipart_error)?),
+            Some("document") => {
+                let document_type = document_type
+                    .ok_or_else(|| ComplianceError::validation("document_type", "must precede the document part"))?;
+                let content_type = field
+                    .content_type()
+                    .map(str::to_string)
+                    .ok_or_else(|| ComplianceError::validation("content_type", "must be set on the document part"))?;
+                let bytes = read_document(&mut field, max_bytes).await?;
+                let session = state
+                    .verification_sessions
+                    .upload(session.id, &document_type, &content_type, bytes)
+                    .await?;
+                return Ok(respond(&state, &headers, &session));
+            }
+            _ => {}
+        }
+    }
+    Err(ComplianceError::validation("document", "must be sent as a `document` part"))
+}
+
+/// Read a document part chunk by chunk, failing once it exceeds `max_bytes`
+async fn read_document(field: &mut Field<'_>, max_bytes: usize) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err(ComplianceError::validation(
+                "document",
+                format!("must be at most {} bytes", max_bytes),
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn multipart_error(e: MultipartError) -> ComplianceError {
+    ComplianceError::validation("body", e.body_text())
+}
+
+/// `POST /v1/end-user/session/submit`
+///
+/// Runs the account's compliance checks on behalf of the client that opened
+/// the session, which is billed for them and notified of the attestation.
+/// When the checks cannot run, the session is returned to the end user to
+/// submit again.
+pub async fn submit_session(
+    State(state): State<AppState>,
+    SessionAuth(session): SessionAuth,
+    headers: HeaderMap,
+) -> Result<Json<EndUserSessionResponse>> {
+    let client = state.clients.get(session.client_id).await?;
+    let session = state.verification_sessions.submit(session.id).await?;
+    let checked = provider_credentials::with_client(
+        client.id,
+        client.region,
+        state.compliance.update_compliance_status(&session.account_id),
+    )
+    .await;
+    let attestation = match checked {
+        Ok(attestation) => attestation,
+        Err(e) => {
+            state.verification_sessions.reopen(session.id).await?;
+            return Err(e);
+        }
+    };
+    state.alerts.observe_attestation(&attestation).await;
+    let session = state.verification_sessions.complete(session.id, &attestation).await?;
+    
+    Ok(respond(&state, &headers, &session))
+}
@@ -0,0 +1,69 @@
+//! Client watchlist CRUD handlers
+
+use super::auth::ClientAuth;
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::compliance::watchlists::{WatchlistEntry, WatchlistEntryInput, WatchlistKind};
+use crate::Result;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Query parameters for listing watchlist entries
+#[derive(Debug, Deserialize)]
+pub struct ListEntriesQuery {
+    pub kind: Option<WatchlistKind>,
+}
+
+/// `GET /v1/watchlists`
+pub async fn list_entries(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Query(query): Query<ListEntriesQuery>,
+) -> Json<Vec<WatchlistEntry>> {
+    Json(state.watchlists.list(client.id, query.kind).await)
+}
+
+impl Validate for WatchlistEntryInput {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.value.trim().is_empty(), "value", "must not be empty");
+    }
+}
+
+/// `POST /v1/watchlists`
+pub async fn create_entry(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Valid(input): Valid<WatchlistEntryInput>,
+) -> Result<Json<WatchlistEntry>> {
+    Ok(Json(state.watchlists.create(client.id, input).await?))
+}
+
+/// `GET /v1/watchlists/{entry_id}`
+pub async fn get_entry(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<WatchlistEntry>> {
+    Ok(Json(state.watchlists.get(client.id, entry_id).await?))
+}
+
+/// `PUT /v1/watchlists/{entry_id}`
+pub async fn update_entry(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(entry_id): Path<Uuid>,
+    Valid(input): Valid<WatchlistEntryInput>,
+) -> Result<Json<WatchlistEntry>> {
+    Ok(Json(state.watchlists.update(client.id, entry_id, input).await?))
+}
+
+/// `DELETE /v1/watchlists/{entry_id}`
+pub async fn delete_entry(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<WatchlistEntry>> {
+    Ok(Json(state.watchlists.delete(client.id, entry_id).await?))
+}
@@ -0,0 +1,157 @@
+//! Webhook endpoint TLS and egress API handlers
+
+use super::auth::rbac::{OperatorAuth, Permission};
+use super::validation::{Valid, Validate, Violations};
+use super::AppState;
+use crate::crypto::tls::{ClientIdentity, EndpointTlsConfig, MinTlsVersion};
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Client certificate and key supplied by an operator
+#[derive(Debug, Deserialize)]
+pub struct IdentityInput {
+    pub certificate_pem: String,
+    pub private_key_pem: String,
+    /// Time from which to present the identity (defaults to now)
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+impl Validate for IdentityInput {
+    fn validate(&self, v: &mut Violations) {
+        v.ensure(!self.certificate_pem.trim().is_empty(), "certificate_pem", "must not be empty");
+        v.ensure(!self.private_key_pem.trim().is_empty(), "private_key_pem", "must not be empty");
+    }
+}
+
+impl From<IdentityInput> for ClientIdentity {
+    fn from(input: IdentityInput) -> Self {
+        Self {
+            certificate_pem: input.certificate_pem,
+            private_key_pem: input.private_key_pem,
+            not_before: input.not_before.unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+/// Request body for setting an endpoint's TLS configuration
+#[derive(Debug, Deserialize)]
+pub struct SetTlsRequest {
+    pub identity: Option<IdentityInput>,
+    pub ca_bundle_pem: Option<String>,
+    pub min_tls_version: Option<MinTlsVersion>,
+}
+
+impl Validate for SetTlsRequest {
+    fn validate(&self, v: &mut Violations) {
+        v.at("identity", |v| self.identity.validate(v));
+    }
+}
+
+/// Addresses webhook deliveries originate from
+#[derive(Debug, Serialize)]
+pub struct SourceIpsResponse {
+    pub source_ips: Vec<String>,
+}
+
+/// `GET /v1/admin/webhook-endpoints/{endpoint_id}/tls`
+pub async fn get_tls(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(endpoint_id): Path<Uuid>,
+) -> Result<Json<EndpointTlsConfig>> {
+    auth.require(Permission::ManageWebhooks)?;
+    state
+        .webhook_tls
+        .get(endpoint_id)
+        .await
+        .map(Json)
+        .ok_or_else(|| ComplianceError::validation("endpoint_id", "endpoint has no TLS configuration"))
+}
+
+/// `PUT /v1/admin/webhook-endpoints/{endpoint_id}/tls`
+pub async fn set_tls(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(endpoint_id): Path<Uuid>,
+    Valid(request): Valid<SetTlsRequest>,
+) -> Result<Json<EndpointTlsConfig>> {
+    auth.require(Permission::ManageWebhooks)?;
+    
+    let config = EndpointTlsConfig {
+        identity: request.identity.map(ClientIdentity::from),
+        next_identity: None,
+        ca_bundle_pem: request.ca_bundle_pem,
+        min_tls_version: request.min_tls_version.unwrap_or(MinTlsVersion::Tls12),
+        updated_at: Utc::now(),
+    };
+    state.webhook_tls.set(endpoint_id, config.clone()).await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "webhook.tls_configured",
+            None,
+            serde_json::json!({ "endpoint_id": endpoint_id, "mtls": config.identity.is_some() }),
+        )
+        .await;
+    
+    Ok(Json(config))
+}
+
+/// `DELETE /v1/admin/webhook-endpoints/{endpoint_id}/tls`
+pub async fn delete_tls(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(endpoint_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    auth.require(Permission::ManageWebhooks)?;
+    state.webhook_tls.remove(endpoint_id).await;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "webhook.tls_removed",
+            None,
+            serde_json::json!({ "endpoint_id": endpoint_id }),
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /v1/admin/webhook-endpoints/{endpoint_id}/tls/rotate`
+///
+/// Stages a new client certificate, presented from its `not_before` onwards.
+pub async fn rotate_identity(
+    State(state): State<AppState>,
+    auth: OperatorAuth,
+    Path(endpoint_id): Path<Uuid>,
+    Valid(identity): Valid<IdentityInput>,
+) -> Result<Json<EndpointTlsConfig>> {
+    auth.require(Permission::ManageWebhooks)?;
+    let config = state.webhook_tls.stage_rotation(endpoint_id, identity.into()).await?;
+    state
+        .audit
+        .record(
+            &auth.operator.username,
+            "webhook.tls_rotation_staged",
+            None,
+            serde_json::json!({
+                "endpoint_id": endpoint_id,
+                "not_before": config.next_identity.as_ref().map(|i| i.not_before),
+            }),
+        )
+        .await;
+    Ok(Json(config))
+}
+
+/// `GET /v1/webhooks/source-ips`
+pub async fn source_ips(State(state): State<AppState>) -> Json<SourceIpsResponse> {
+    Json(SourceIpsResponse {
+        source_ips: state.config.webhooks.egress.source_ips.clone(),
+    })
+}
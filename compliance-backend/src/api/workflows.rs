@@ -0,0 +1,39 @@
+//! Onboarding workflow run handlers
+
+use super::auth::ClientAuth;
+use super::AppState;
+use crate::compliance::workflows::WorkflowRun;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use axum::extract::{Path, State};
+use axum::Json;
+use uuid::Uuid;
+
+/// `GET /v1/accounts/{id}/workflows`
+///
+/// The client's workflow runs for the account, newest first.
+pub async fn list_runs(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path(account_id): Path<AccountId>,
+) -> Result<Json<Vec<WorkflowRun>>> {
+    let runs = state.workflows.runs(&account_id).await;
+    Ok(Json(runs.into_iter().filter(|run| run.client_id == Some(client.id)).collect()))
+}
+
+/// `GET /v1/accounts/{id}/workflows/{run_id}`
+pub async fn get_run(
+    State(state): State<AppState>,
+    ClientAuth(client): ClientAuth,
+    Path((account_id, run_id)): Path<(AccountId, Uuid)>,
+) -> Result<Json<WorkflowRun>> {
+    state
+        .workflows
+        .get(&account_id, run_id)
+        .await
+        .filter(|run| run.client_id == Some(client.id))
+        .map(Json)
+        .ok_or_else(|| ComplianceError::WorkflowRunNotFound {
+            run_id: run_id.to_string(),
+        })
+}
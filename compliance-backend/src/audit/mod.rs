@@ -0,0 +1,360 @@
+//! Tamper-evident audit log
+//!
+//! Every entry carries the hash of its predecessor, so altering or removing
+//! an entry breaks the chain from that point on.
+
+use crate::clock::{system_clock, SharedClock};
+use crate::storage::memory::MemoryAuditRepo;
+use crate::storage::{AuditRepo, WriteBatch};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Previous-hash value of the first entry in the chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Default number of entries per page
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Maximum number of entries per page
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// A single audit log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the chain, starting at 1
+    pub sequence: u64,
+    pub id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    
+    /// Operator, client, or system component that performed the action
+    pub actor: String,
+    
+    /// Dotted action name (e.g. "config.reloaded")
+    pub action: String,
+    
+    /// Account the action concerns, if any
+    pub account_id: Option<AccountId>,
+    
+    pub details: serde_json::Value,
+    
+    /// Hex-encoded hash of the previous entry
+    pub prev_hash: String,
+    
+    /// Hex-encoded hash of this entry
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Compute the hash of this entry's contents and previous hash
+    pub fn compute_hash(&self) -> String {
+        let content = serde_json::json!({
+            "sequence": self.sequence,
+            "id": self.id,
+            "recorded_at": self.recorded_at,
+            "actor": self.actor,
+            "action": self.action,
+            "account_id": self.account_id,
+            "details": self.details,
+            "prev_hash": self.prev_hash,
+        });
+        blake3::hash(content.to_string().as_bytes()).to_hex().to_string()
+    }
+}
+
+/// Filters for querying the audit log
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub account_id: Option<AccountId>,
+    
+    /// Inclusive lower bound on `recorded_at`
+    pub from: Option<DateTime<Utc>>,
+    
+    /// Exclusive upper bound on `recorded_at`
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl AuditQuery {
+    /// Check if an entry matches every filter
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.actor.as_ref().map_or(true, |actor| entry.actor == *actor)
+            && self.action.as_ref().map_or(true, |action| entry.action == *action)
+            && self.account_id.as_ref().map_or(true, |account| entry.account_id.as_ref() == Some(account))
+            && self.from.map_or(true, |from| entry.recorded_at >= from)
+            && self.to.map_or(true, |to| entry.recorded_at < to)
+    }
+}
+
+/// A page of audit entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    
+    /// Cursor to pass to fetch the next page, absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Result of verifying the hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainVerification {
+    pub valid: bool,
+    pub entries_checked: u64,
+    
+    /// Sequence of the first entry whose hash or link does not match
+    pub first_invalid_sequence: Option<u64>,
+    
+    /// Hash of the last entry in the chain
+    pub head_hash: String,
+}
+
+/// Verify that entries form an unbroken chain starting after `prev_hash`
+pub fn verify_entries(entries: &[AuditEntry], prev_hash: &str) -> ChainVerification {
+    let mut expected_prev = prev_hash.to_string();
+    for (checked, entry) in entries.iter().enumerate() {
+        if entry.prev_hash != expected_prev || entry.hash != entry.compute_hash() {
+            return ChainVerification {
+                valid: false,
+                entries_checked: checked as u64,
+                first_invalid_sequence: Some(entry.sequence),
+                head_hash: expected_prev,
+            };
+        }
+        expected_prev = entry.hash.clone();
+    }
+    
+    ChainVerification {
+        valid: true,
+        entries_checked: entries.len() as u64,
+        first_invalid_sequence: None,
+        head_hash: expected_prev,
+    }
+}
+
+/// Encode a sequence number as an opaque page cursor
+pub fn encode_cursor(sequence: u64) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(sequence.to_be_bytes())
+}
+
+/// Decode a page cursor into the sequence number it resumes after
+pub fn decode_cursor(cursor: &str) -> Result<u64> {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_be_bytes)
+        .ok_or_else(|| ComplianceError::validation("cursor", "malformed cursor"))
+}
+
+/// Position of the last persisted entry
+struct ChainHead {
+    sequence: u64,
+    hash: String,
+}
+
+/// Append-only, hash-chained audit log
+pub struct AuditLog {
+    repo: Arc<dyn AuditRepo>,
+    
+    /// Held while appending, so entries link in sequence
+    head: Mutex<ChainHead>,
+    
+    clock: SharedClock,
+}
+
+impl AuditLog {
+    /// Create an empty audit log kept in memory
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+    
+    /// Create an empty audit log kept in memory, timestamping entries with `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
+        Self {
+            repo: Arc::new(MemoryAuditRepo::default()),
+            head: Mutex::new(ChainHead {
+                sequence: 0,
+                hash: GENESIS_HASH.to_string(),
+            }),
+            clock,
+        }
+    }
+    
+    /// Open the audit log stored in `repo`, continuing its chain
+    pub async fn open(repo: Arc<dyn AuditRepo>, clock: SharedClock) -> Result<Self> {
+        let head = match repo.head().await? {
+            Some(entry) => ChainHead {
+                sequence: entry.sequence,
+                hash: entry.hash,
+            },
+            None => ChainHead {
+                sequence: 0,
+                hash: GENESIS_HASH.to_string(),
+            },
+        };
+        Ok(Self {
+            repo,
+            head: Mutex::new(head),
+            clock,
+        })
+    }
+    
+    /// Append an entry to the chain
+    ///
+    /// Callers have no way to undo the action they are recording, so a
+    /// failure to persist the entry is logged rather than returned. The chain
+    /// stays intact: the next entry links to the last persisted one.
+    pub async fn record(
+        &self,
+        actor: &str,
+        action: &str,
+        account_id: Option<&AccountId>,
+        details: serde_json::Value,
+    ) -> AuditEntry {
+        let mut head = self.head.lock().await;
+        let entry = self.link(&head, actor, action, account_id, details);
+        match self.repo.append(&entry).await {
+            Ok(()) => {
+                tracing::info!(sequence = entry.sequence, actor, action, "audit entry recorded");
+                head.sequence = entry.sequence;
+                head.hash = entry.hash.clone();
+            }
+            Err(e) => {
+                tracing::error!(sequence = entry.sequence, actor, action, error = %e, "audit entry not persisted")
+            }
+        }
+        entry
+    }
+    
+    /// Hold the chain to stage entries for a [`WriteBatch`]
+    ///
+    /// Other entries wait until the staged ones are committed or dropped.
+    pub async fn stage(&self) -> StagedAudit<'_> {
+        let head = self.head.lock().await;
+        let next = ChainHead {
+            sequence: head.sequence,
+            hash: head.hash.clone(),
+        };
+        StagedAudit { log: self, head, next }
+    }
+    
+    /// Build the entry following `head`
+    fn link(
+        &self,
+        head: &ChainHead,
+        actor: &str,
+        action: &str,
+        account_id: Option<&AccountId>,
+        details: serde_json::Value,
+    ) -> AuditEntry {
+        let mut entry = AuditEntry {
+            sequence: head.sequence + 1,
+            id: Uuid::new_v4(),
+            recorded_at: self.clock.now(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            account_id: account_id.cloned(),
+            details,
+            prev_hash: head.hash.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        entry
+    }
+    
+    /// Get every entry, oldest first
+    pub async fn entries(&self) -> Result<Vec<AuditEntry>> {
+        self.repo.find(&AuditQuery::default(), 0, None).await
+    }
+    
+    /// Get a page of matching entries, oldest first, after an optional cursor
+    pub async fn query(&self, query: &AuditQuery, cursor: Option<u64>, limit: usize) -> Result<AuditPage> {
+        // One entry past the page tells whether another page follows
+        let mut entries = self.repo.find(query, cursor.unwrap_or(0), Some(limit + 1)).await?;
+        let more = entries.len() > limit;
+        entries.truncate(limit);
+        let next_cursor = match entries.last() {
+            Some(last) if more => Some(encode_cursor(last.sequence)),
+            _ => None,
+        };
+        
+        Ok(AuditPage { entries, next_cursor })
+    }
+    
+    /// Get every matching entry, oldest first
+    pub async fn export(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        self.repo.find(query, 0, None).await
+    }
+    
+    /// Accounts an actor acted on within `[from, to)`
+    pub async fn accounts_touched_by(
+        &self,
+        actor: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AccountId>> {
+        let query = AuditQuery {
+            actor: Some(actor.to_string()),
+            from: Some(from),
+            to: Some(to),
+            ..AuditQuery::default()
+        };
+        let entries = self.repo.find(&query, 0, None).await?;
+        let mut accounts: Vec<AccountId> = entries.into_iter().filter_map(|e| e.account_id).collect();
+        accounts.sort();
+        accounts.dedup();
+        Ok(accounts)
+    }
+    
+    /// Verify the integrity of the whole chain
+    pub async fn verify_chain(&self) -> Result<ChainVerification> {
+        Ok(verify_entries(&self.entries().await?, GENESIS_HASH))
+    }
+}
+
+/// Audit entries staged for a batch, holding the chain until committed or dropped
+pub struct StagedAudit<'a> {
+    log: &'a AuditLog,
+    head: MutexGuard<'a, ChainHead>,
+    
+    /// Last staged entry
+    next: ChainHead,
+}
+
+impl StagedAudit<'_> {
+    /// Add the next entry of the chain to `batch`
+    pub fn push(
+        &mut self,
+        batch: &mut WriteBatch,
+        actor: &str,
+        action: &str,
+        account_id: Option<&AccountId>,
+        details: serde_json::Value,
+    ) -> AuditEntry {
+        let entry = self.log.link(&self.next, actor, action, account_id, details);
+        self.next = ChainHead {
+            sequence: entry.sequence,
+            hash: entry.hash.clone(),
+        };
+        batch.audit.push(entry.clone());
+        entry
+    }
+    
+    /// Continue the chain after the staged entries, once their batch is committed
+    pub fn committed(self) {
+        let Self { mut head, next, .. } = self;
+        *head = next;
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,358 @@
+//! Operator command-line tool for the compliance backend
+//!
+//! Local commands (migrations, signing key generation, offline proof
+//! verification) read the deployment configuration directly. Commands that act
+//! on server state call the admin API with an operator session token.
+
+use anyhow::{bail, Context};
+use clap::{Args, Parser, Subcommand};
+use compliance_backend::api::auth::API_KEY_HEADER;
+use compliance_backend::compliance::scope::{AssetClass, ScopeUsage};
+use compliance_backend::config::SigningBackendConfig;
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::ComplianceLevel;
+use compliance_backend::verifier::{verify_proof_envelope, VerificationPolicy};
+use compliance_backend::Config;
+use serde_json::{json, Value};
+use sqlx::migrate::Migrator;
+use sqlx::postgres::PgPoolOptions;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Parser)]
+#[command(name = "compliance-cli", about = "Operational tasks for the ZeroTrust compliance backend")]
+struct Cli {
+    /// Configuration directory
+    #[arg(long, env = "COMPLIANCE_CONFIG_PATH", default_value = "config", global = true)]
+    config: String,
+    
+    #[command(flatten)]
+    api: ApiArgs,
+    
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Connection to a running compliance backend
+#[derive(Debug, Args)]
+struct ApiArgs {
+    /// Base URL of the compliance API
+    #[arg(long, env = "COMPLIANCE_API_URL", default_value = "http://localhost:8080", global = true)]
+    api_url: String,
+    
+    /// Operator session token for admin endpoints
+    #[arg(long, env = "COMPLIANCE_OPERATOR_TOKEN", global = true, hide_env_values = true)]
+    token: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Apply pending database migrations
+    Migrate {
+        /// Directory containing the migration files
+        #[arg(long, default_value = "migrations")]
+        dir: PathBuf,
+    },
+    
+    /// Manage business clients
+    #[command(subcommand)]
+    Clients(ClientsCommand),
+    
+    /// Rotate credentials
+    #[command(subcommand)]
+    Keys(KeysCommand),
+    
+    /// Run a comprehensive compliance check for an account
+    Check {
+        account_id: String,
+        
+        /// API key of the business client requesting the check
+        #[arg(long, env = "COMPLIANCE_API_KEY", hide_env_values = true)]
+        api_key: String,
+        
+        /// Return the would-be attestation without storing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    
+    /// Manage sanctions and PEP lists
+    #[command(subcommand)]
+    Screening(ScreeningCommand),
+    
+    /// Work with proof envelopes
+    #[command(subcommand)]
+    Proofs(ProofsCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum ClientsCommand {
+    /// Create a business client and print its API key
+    Create {
+        #[arg(long)]
+        name: String,
+        
+        #[arg(long)]
+        webhook_url: Option<String>,
+        
+        #[arg(long, value_parser = parse_level, default_value = "basic")]
+        compliance_level: ComplianceLevel,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum KeysCommand {
+    /// Replace a business client's API key
+    RotateClient { client_id: uuid::Uuid },
+    
+    /// Generate a new proof envelope signing key
+    ///
+    /// Set the printed seed as `security.signing_key_seed` (ideally through a
+    /// secret reference) and the key id as `security.signing_key_id`, keeping
+    /// the previous public key trusted until its envelopes have expired.
+    GenerateSigning {
+        #[arg(long)]
+        key_id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ScreeningCommand {
+    /// Ingest a new version of a list from a JSON file of screened entities
+    Ingest {
+        /// List name (e.g. "ofac_sdn")
+        list: String,
+        
+        /// Version identifier of the list publication
+        #[arg(long)]
+        version: String,
+        
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ProofsCommand {
+    /// Verify an envelope's encoding, signature, lifetime, audience, and scope offline
+    ///
+    /// Does not consume the envelope's challenge or check the proof itself.
+    Verify {
+        /// Encoded envelope, or `@path` to read it from a file
+        envelope: String,
+        
+        #[arg(long)]
+        audience: String,
+        
+        /// Additional trusted key as `key_id=hex_public_key`; repeatable
+        #[arg(long = "trusted-key")]
+        trusted_keys: Vec<String>,
+        
+        #[arg(long)]
+        application: Option<String>,
+        
+        #[arg(long, value_parser = parse_asset_class)]
+        asset_class: Option<AssetClass>,
+        
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let api = AdminApi::new(&cli.api);
+    match cli.command {
+        Command::Migrate { dir } => migrate(&cli.config, &dir).await,
+        Command::Clients(ClientsCommand::Create {
+            name,
+            webhook_url,
+            compliance_level,
+        }) => {
+            let body = json!({ "name": name, "webhook_url": webhook_url, "compliance_level": compliance_level });
+            print_json(&api.send(api.post("/v1/admin/clients")?.json(&body)).await?)
+        }
+        Command::Keys(KeysCommand::RotateClient { client_id }) => {
+            let path = format!("/v1/admin/clients/{}/rotate-key", client_id);
+            print_json(&api.send(api.post(&path)?).await?)
+        }
+        Command::Keys(KeysCommand::GenerateSigning { key_id }) => {
+            let seed: [u8; 32] = rand::random();
+            let signer = AttestationSigner::from_hex_seed(key_id.clone(), &hex::encode(seed))?;
+            print_json(&json!({
+                "key_id": key_id,
+                "seed": hex::encode(seed),
+                "public_key": hex::encode(signer.verifying_key().to_bytes()),
+            }))
+        }
+        Command::Check {
+            account_id,
+            api_key,
+            dry_run,
+        } => {
+            let request = api
+                .http
+                .post(api.url(&format!("/v1/accounts/{}/check", account_id)))
+                .header(API_KEY_HEADER, api_key)
+                .json(&json!({ "dry_run": dry_run }));
+            print_json(&api.send(request).await?)
+        }
+        Command::Screening(ScreeningCommand::Ingest { list, version, file }) => {
+            let entities: Value = serde_json::from_slice(
+                &std::fs::read(&file).with_context(|| format!("reading {}", file.display()))?,
+            )
+            .with_context(|| format!("parsing {}", file.display()))?;
+            let body = json!({ "version": version, "entities": entities });
+            let path = format!("/v1/admin/screening/lists/{}", list);
+            print_json(&api.send(api.put(&path)?.json(&body)).await?)
+        }
+        Command::Proofs(ProofsCommand::Verify {
+            envelope,
+            audience,
+            trusted_keys,
+            application,
+            asset_class,
+            amount,
+        }) => {
+            let usage = ScopeUsage {
+                application,
+                asset_class,
+                amount,
+            };
+            verify_offline(&cli.config, &envelope, &audience, &trusted_keys, &usage).await
+        }
+    }
+}
+
+async fn migrate(config_path: &str, dir: &Path) -> anyhow::Result<()> {
+    let (config, _secrets) = Config::load_with_secrets(config_path).await?;
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(config.database.connection_timeout))
+        .connect(&config.database.connection_url())
+        .await
+        .context("connecting to the database")?;
+    
+    let migrator = Migrator::new(dir)
+        .await
+        .with_context(|| format!("loading migrations from {}", dir.display()))?;
+    migrator.run(&pool).await.context("applying migrations")?;
+    println!("Applied migrations from {} ({} known)", dir.display(), migrator.iter().count());
+    Ok(())
+}
+
+async fn verify_offline(
+    config_path: &str,
+    envelope: &str,
+    audience: &str,
+    extra_keys: &[String],
+    usage: &ScopeUsage,
+) -> anyhow::Result<()> {
+    let (config, _secrets) = Config::load_with_secrets(config_path).await?;
+    let encoded = match envelope.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).with_context(|| format!("reading {}", path))?,
+        None => envelope.to_string(),
+    };
+    
+    let mut trusted = TrustedKeys::new();
+    // An ephemeral key signed nothing worth trusting
+    let ephemeral = matches!(config.security.signing_backend, SigningBackendConfig::Software)
+        && config.security.signing_key_seed.is_none();
+    if !ephemeral {
+        let signer = AttestationSigner::from_config(&config.security)?;
+        trusted.insert(signer.key_id(), signer.verifying_key());
+    }
+    for entry in extra_keys {
+        let Some((key_id, key)) = entry.split_once('=') else {
+            bail!("trusted key {:?} must be key_id=hex_public_key", entry);
+        };
+        trusted.insert_hex(key_id, key)?;
+    }
+    
+    let policy = VerificationPolicy {
+        usage: usage.clone(),
+        max_proof_size: config.compliance.attestation.max_proof_size,
+        ..VerificationPolicy::new(audience)
+    };
+    let verified = verify_proof_envelope(&encoded, &trusted, &policy)?;
+    
+    print_json(&json!({
+        "valid": true,
+        "account_id": verified.account_id,
+        "audience": verified.audience,
+        "key_id": verified.key_id,
+        "expires_at": verified.expires_at,
+        "scope": verified.scope,
+        "claims": verified.claims,
+    }))
+}
+
+/// Client for the admin API
+struct AdminApi {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl AdminApi {
+    fn new(args: &ApiArgs) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: args.api_url.trim_end_matches('/').to_string(),
+            token: args.token.clone(),
+        }
+    }
+    
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+    
+    fn post(&self, path: &str) -> anyhow::Result<reqwest::RequestBuilder> {
+        Ok(self.http.post(self.url(path)).bearer_auth(self.operator_token()?))
+    }
+    
+    fn put(&self, path: &str) -> anyhow::Result<reqwest::RequestBuilder> {
+        Ok(self.http.put(self.url(path)).bearer_auth(self.operator_token()?))
+    }
+    
+    fn operator_token(&self) -> anyhow::Result<&str> {
+        self.token
+            .as_deref()
+            .context("an operator token is required (--token or COMPLIANCE_OPERATOR_TOKEN)")
+    }
+    
+    async fn send(&self, request: reqwest::RequestBuilder) -> anyhow::Result<Value> {
+        let response = request.send().await.context("calling the compliance API")?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            bail!("API returned {}: {}", status, body);
+        }
+        Ok(body)
+    }
+}
+
+fn print_json(value: &Value) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn parse_level(value: &str) -> Result<ComplianceLevel, String> {
+    match value {
+        "basic" => Ok(ComplianceLevel::Basic),
+        "standard" => Ok(ComplianceLevel::Standard),
+        "enhanced" => Ok(ComplianceLevel::Enhanced),
+        "institutional" | "institutional_grade" => Ok(ComplianceLevel::InstitutionalGrade),
+        _ => Err(format!("unknown compliance level {:?}", value)),
+    }
+}
+
+fn parse_asset_class(value: &str) -> Result<AssetClass, String> {
+    serde_json::from_value(Value::String(value.to_string())).map_err(|_| format!("unknown asset class {:?}", value))
+}
@@ -0,0 +1,158 @@
+//! Typed errors returned by the API client
+
+use crate::error::FieldViolation;
+use reqwest::StatusCode;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Machine-readable error code returned in API error bodies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorCode {
+    AccountNotFound,
+    InsufficientPrivileges,
+    InvalidProof,
+    RateLimitExceeded,
+    InvalidApiKey,
+    ValidationError,
+    BusinessClientNotFound,
+    CompliancePolicyViolation,
+    StepUpSessionNotFound,
+    AlertNotFound,
+    WatchlistEntryNotFound,
+    ChallengeRejected,
+    InvalidCredentials,
+    PermissionDenied,
+    OperatorNotFound,
+    ApprovalNotFound,
+    ReportNotFound,
+    ProverSaturated,
+    FundsDeclarationNotFound,
+    ProviderUnavailable,
+    MonitoringBackpressure,
+    ProviderCallbackNotFound,
+    InvalidCallbackSignature,
+    ProviderCredentialNotFound,
+    OracleNotPublished,
+    NotInComplianceSet,
+    RegistryNotPublished,
+    EpochNotFound,
+    AttestationNotAnchored,
+    ScreeningResultNotFound,
+    ScreeningMatchNotFound,
+    ResidencyUnavailable,
+    WorkflowStepTimedOut,
+    WorkflowFailed,
+    WorkflowRunNotFound,
+    QuotaExceeded,
+    ClientSandboxNotFound,
+    VerifierSaturated,
+    ProofVerificationTimedOut,
+    InternalError,
+    /// A code this client version does not know
+    Unknown(String),
+}
+
+impl ErrorCode {
+    /// Parse the `code` field of an error body
+    pub fn parse(code: &str) -> Self {
+        match code {
+            "account_not_found" => Self::AccountNotFound,
+            "insufficient_privileges" => Self::InsufficientPrivileges,
+            "invalid_proof" => Self::InvalidProof,
+            "rate_limit_exceeded" => Self::RateLimitExceeded,
+            "invalid_api_key" => Self::InvalidApiKey,
+            "validation_error" => Self::ValidationError,
+            "business_client_not_found" => Self::BusinessClientNotFound,
+            "compliance_policy_violation" => Self::CompliancePolicyViolation,
+            "step_up_session_not_found" => Self::StepUpSessionNotFound,
+            "alert_not_found" => Self::AlertNotFound,
+            "watchlist_entry_not_found" => Self::WatchlistEntryNotFound,
+            "challenge_rejected" => Self::ChallengeRejected,
+            "invalid_credentials" => Self::InvalidCredentials,
+            "permission_denied" => Self::PermissionDenied,
+            "operator_not_found" => Self::OperatorNotFound,
+            "approval_not_found" => Self::ApprovalNotFound,
+            "report_not_found" => Self::ReportNotFound,
+            "prover_saturated" => Self::ProverSaturated,
+            "funds_declaration_not_found" => Self::FundsDeclarationNotFound,
+            "provider_unavailable" => Self::ProviderUnavailable,
+            "monitoring_backpressure" => Self::MonitoringBackpressure,
+            "provider_callback_not_found" => Self::ProviderCallbackNotFound,
+            "invalid_callback_signature" => Self::InvalidCallbackSignature,
+            "provider_credential_not_found" => Self::ProviderCredentialNotFound,
+            "oracle_not_published" => Self::OracleNotPublished,
+            "not_in_compliance_set" => Self::NotInComplianceSet,
+            "registry_not_published" => Self::RegistryNotPublished,
+            "epoch_not_found" => Self::EpochNotFound,
+            "attestation_not_anchored" => Self::AttestationNotAnchored,
+            "screening_result_not_found" => Self::ScreeningResultNotFound,
+            "screening_match_not_found" => Self::ScreeningMatchNotFound,
+            "residency_unavailable" => Self::ResidencyUnavailable,
+            "workflow_step_timed_out" => Self::WorkflowStepTimedOut,
+            "workflow_failed" => Self::WorkflowFailed,
+            "workflow_run_not_found" => Self::WorkflowRunNotFound,
+            "quota_exceeded" => Self::QuotaExceeded,
+            "client_sandbox_not_found" => Self::ClientSandboxNotFound,
+            "verifier_saturated" => Self::VerifierSaturated,
+            "proof_verification_timed_out" => Self::ProofVerificationTimedOut,
+            "internal_error" => Self::InternalError,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+/// Error returned by the API client
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("API error {status}: {message}")]
+    Api {
+        status: StatusCode,
+        code: ErrorCode,
+        message: String,
+        /// Field-level problems, for validation errors
+        violations: Vec<FieldViolation>,
+        /// Delay the server asked for before retrying
+        retry_after: Option<Duration>,
+    },
+    
+    #[error("Transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+    
+    #[error("Invalid response body: {0}")]
+    Decode(#[from] serde_json::Error),
+    
+    #[error("Invalid client configuration: {0}")]
+    Configuration(String),
+}
+
+impl ClientError {
+    /// Error code, for API errors
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self {
+            Self::Api { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+    
+    /// Field-level problems the server found with the request body
+    pub fn violations(&self) -> &[FieldViolation] {
+        match self {
+            Self::Api { violations, .. } => violations,
+            _ => &[],
+        }
+    }
+    
+    /// Whether retrying the same request may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Api { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::BAD_GATEWAY
+                    || *status == StatusCode::SERVICE_UNAVAILABLE
+                    || *status == StatusCode::GATEWAY_TIMEOUT
+            }
+            Self::Transport(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            Self::Decode(_) | Self::Configuration(_) => false,
+        }
+    }
+}
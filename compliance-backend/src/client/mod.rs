@@ -0,0 +1,285 @@
+//! Typed async client for the compliance REST API
+//!
+//! Enabled with the `client` feature. Requests authenticate with a business
+//! client API key, transient failures are retried with jittered exponential
+//! backoff, and every POST carries an idempotency key that is reused across
+//! its retries so the server never applies it twice.
+
+pub mod error;
+pub mod types;
+
+pub use crate::crypto::webhook_encryption;
+pub use crate::crypto::webhook_signature;
+pub use error::{ClientError, ErrorCode};
+pub use types::*;
+
+use crate::compliance::claims::ClaimKind;
+use crate::compliance::freshness::FreshnessRequirement;
+use crate::compliance::scope::{ProofScope, ScopeUsage};
+use crate::error::FieldViolation;
+use crate::types::AccountId;
+use reqwest::header::{HeaderValue, RETRY_AFTER};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Header carrying the business client API key
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying the idempotency key of a POST
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Result type of the API client
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Retry behavior for transient failures
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    
+    /// Backoff cap for the first retry; doubles on each retry
+    pub base_delay: Duration,
+    
+    /// Upper bound on any single backoff
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before a retry, with full jitter
+    fn backoff(&self, retry: u32) -> Duration {
+        let cap = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        cap.mul_f64(rand::random::<f64>())
+    }
+}
+
+/// Client for the compliance REST API
+#[derive(Debug, Clone)]
+pub struct ComplianceClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    retry: RetryPolicy,
+}
+
+impl ComplianceClient {
+    /// Create a client for a deployment's base URL and a business client API key
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        Self::with_http_client(http, base_url, api_key)
+    }
+    
+    /// Create a client on a preconfigured HTTP client (proxies, TLS roots, timeouts)
+    pub fn with_http_client(
+        http: reqwest::Client,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Result<Self> {
+        let api_key = api_key.into();
+        if api_key.is_empty() || HeaderValue::from_str(&api_key).is_err() {
+            return Err(ClientError::Configuration("API key is not a valid header value".to_string()));
+        }
+        Ok(Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key,
+            retry: RetryPolicy::default(),
+        })
+    }
+    
+    /// Replace the retry policy
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+    
+    /// `POST /v1/accounts/{id}/check`
+    pub async fn run_check(&self, account_id: &AccountId, options: &CheckOptions) -> Result<CheckResult> {
+        self.post(&format!("/v1/accounts/{}/check", account_id), options, None).await
+    }
+    
+    /// `GET /v1/accounts/{id}/compliance`
+    pub async fn compliance(&self, account_id: &AccountId) -> Result<ComplianceSnapshot> {
+        self.get(&format!("/v1/accounts/{}/compliance", account_id)).await
+    }
+    
+    /// `POST /v1/accounts/{id}/authorize-transaction`
+    ///
+    /// Pass the integrator's own transaction id as `idempotency_key` so a
+    /// retried authorization is never recorded twice.
+    pub async fn authorize_transaction(
+        &self,
+        account_id: &AccountId,
+        transaction: &TransactionRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<AuthorizationDecision> {
+        let path = format!("/v1/accounts/{}/authorize-transaction", account_id);
+        self.post(&path, transaction, idempotency_key).await
+    }
+    
+    /// `POST /v1/proofs/challenges`
+    pub async fn issue_challenge(
+        &self,
+        audience: &str,
+        account_id: &AccountId,
+        scope: Option<&ProofScope>,
+        claims: &[ClaimKind],
+        freshness: Option<&FreshnessRequirement>,
+    ) -> Result<ProofChallenge> {
+        let body = types::IssueChallengeBody {
+            audience,
+            account_id,
+            scope,
+            claims,
+            freshness,
+        };
+        self.post("/v1/proofs/challenges", &body, None).await
+    }
+    
+    /// `POST /v1/accounts/{id}/proofs`
+    pub async fn generate_proof(
+        &self,
+        account_id: &AccountId,
+        nonce: &str,
+        scope: Option<&ProofScope>,
+    ) -> Result<GeneratedProof> {
+        let body = types::GenerateProofBody { nonce, scope };
+        self.post(&format!("/v1/accounts/{}/proofs", account_id), &body, None).await
+    }
+    
+    /// `POST /v1/proofs/verify`
+    pub async fn verify_proof(
+        &self,
+        envelope: &str,
+        audience: &str,
+        usage: &ScopeUsage,
+        required_claims: &[ClaimKind],
+    ) -> Result<ProofVerification> {
+        let body = types::VerifyProofBody {
+            envelope,
+            audience,
+            usage,
+            required_claims,
+        };
+        self.post("/v1/proofs/verify", &body, None).await
+    }
+    
+    /// GET any endpoint and decode its JSON response
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(Method::GET, path, None::<&()>, None).await
+    }
+    
+    /// POST a JSON body to any endpoint and decode its JSON response
+    ///
+    /// A random idempotency key is generated when none is given.
+    pub async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+        idempotency_key: Option<&str>,
+    ) -> Result<T> {
+        let key = idempotency_key
+            .map(str::to_string)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        self.send(Method::POST, path, Some(body), Some(&key)).await
+    }
+    
+    async fn send<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        idempotency_key: Option<&str>,
+    ) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut retry = 0;
+        loop {
+            let mut request: RequestBuilder = self
+                .http
+                .request(method.clone(), &url)
+                .header(API_KEY_HEADER, &self.api_key);
+            if let Some(key) = idempotency_key {
+                request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            
+            let result = match request.send().await {
+                Ok(response) => decode(response).await,
+                Err(e) => Err(ClientError::Transport(e)),
+            };
+            match result {
+                Err(e) if e.is_retryable() && retry < self.retry.max_retries => {
+                    let delay = match &e {
+                        ClientError::Api {
+                            retry_after: Some(after), ..
+                        } => (*after).min(self.retry.max_delay),
+                        _ => self.retry.backoff(retry),
+                    };
+                    tracing::debug!(%url, retry = retry + 1, delay_ms = delay.as_millis() as u64, error = %e, "retrying request");
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Error body returned by the API
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: String,
+    code: Option<String>,
+    #[serde(default)]
+    errors: Vec<FieldViolation>,
+}
+
+async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let status = response.status();
+    if status.is_success() {
+        let bytes = response.bytes().await?;
+        return Ok(serde_json::from_slice(&bytes)?);
+    }
+    
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let bytes = response.bytes().await?;
+    let (code, message, violations) = match serde_json::from_slice::<ErrorBody>(&bytes) {
+        Ok(body) => (
+            body.code.map_or(ErrorCode::InternalError, |code| ErrorCode::parse(&code)),
+            body.error,
+            body.errors,
+        ),
+        Err(_) => (ErrorCode::InternalError, String::from_utf8_lossy(&bytes).into_owned(), vec![]),
+    };
+    Err(ClientError::Api {
+        status,
+        code,
+        message,
+        violations,
+        retry_after,
+    })
+}
@@ -0,0 +1,153 @@
+//! Request and response bodies of the REST API as seen by integrators
+
+use crate::compliance::claims::{Claim, ClaimKind};
+use crate::compliance::freshness::FreshnessRequirement;
+use crate::compliance::rejection::KycRejection;
+use crate::compliance::scope::{ProofScope, ScopeUsage};
+use crate::types::{AccountId, ComplianceAttestation, ComplianceLevel};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Options for a compliance check
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CheckOptions {
+    /// Preview the outcome without persisting anything or emitting events
+    pub dry_run: bool,
+    
+    /// Compliance level the result is evaluated against (defaults to `Basic`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_level: Option<ComplianceLevel>,
+}
+
+/// Policy evaluation of a check result
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyResult {
+    pub required_level: ComplianceLevel,
+    pub meets_required_level: bool,
+    pub compliance_level: Option<ComplianceLevel>,
+    pub reasons: Vec<String>,
+    /// Why KYC was rejected, with retry eligibility, when it was
+    #[serde(default)]
+    pub kyc_rejection: Option<KycRejection>,
+}
+
+/// Result of a compliance check
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckResult {
+    pub dry_run: bool,
+    pub attestation: ComplianceAttestation,
+    pub policy: PolicyResult,
+    /// Workflow run that issued the attestation, for clients with a workflow
+    #[serde(default)]
+    pub workflow_run_id: Option<Uuid>,
+}
+
+/// Compliance state of an account at a point in time
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComplianceSnapshot {
+    pub account_id: AccountId,
+    pub as_of: DateTime<Utc>,
+    /// Attestation lifecycle state, if the account has one
+    pub state: Option<serde_json::Value>,
+    pub compliance_level: Option<ComplianceLevel>,
+    pub sanctions_list_versions: HashMap<String, String>,
+}
+
+/// A proposed transaction to authorize
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionRequest {
+    /// Amount in the asset's base unit
+    pub amount: u64,
+    pub asset: Option<String>,
+    pub counterparty: Option<String>,
+    /// ISO 3166-1 alpha-2 code of the counterparty's country, when known
+    pub counterparty_country: Option<String>,
+    /// Transaction type (e.g. "transfer", "withdrawal")
+    pub transaction_type: String,
+}
+
+/// Outcome of a transaction authorization
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorizationOutcome {
+    Allow,
+    Deny,
+    StepUp,
+}
+
+/// Authorization decision for a transaction
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthorizationDecision {
+    pub id: Uuid,
+    pub account_id: AccountId,
+    pub outcome: AuthorizationOutcome,
+    pub reasons: Vec<String>,
+    pub compliance_level: Option<ComplianceLevel>,
+    pub required_level: ComplianceLevel,
+    /// Step-up session opened when the outcome is `StepUp`
+    pub step_up_session: Option<Uuid>,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// A single-use proof challenge
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProofChallenge {
+    pub nonce: String,
+    pub audience: String,
+    pub account_id: AccountId,
+    pub scope: Option<ProofScope>,
+    #[serde(default)]
+    pub claims: Vec<ClaimKind>,
+    #[serde(default)]
+    pub freshness: Option<FreshnessRequirement>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A generated proof envelope
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeneratedProof {
+    /// Canonically encoded envelope
+    pub envelope: String,
+    pub audience: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scope: Option<ProofScope>,
+}
+
+/// Result of verifying a proof envelope
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProofVerification {
+    pub valid: bool,
+    pub account_id: AccountId,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scope: Option<ProofScope>,
+    /// Claims the proof disclosed
+    #[serde(default)]
+    pub claims: Vec<Claim>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct IssueChallengeBody<'a> {
+    pub audience: &'a str,
+    pub account_id: &'a AccountId,
+    pub scope: Option<&'a ProofScope>,
+    pub claims: &'a [ClaimKind],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<&'a FreshnessRequirement>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct GenerateProofBody<'a> {
+    pub nonce: &'a str,
+    pub scope: Option<&'a ProofScope>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct VerifyProofBody<'a> {
+    pub envelope: &'a str,
+    pub audience: &'a str,
+    pub usage: &'a ScopeUsage,
+    pub required_claims: &'a [ClaimKind],
+}
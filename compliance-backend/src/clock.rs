@@ -0,0 +1,61 @@
+//! Time source
+//!
+//! Services read the current time through a [`Clock`] instead of calling
+//! `Utc::now()` directly, so expiry checks, velocity windows and schedulers
+//! can be driven deterministically by a [`MockClock`] in tests.
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, Mutex};
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock shared between services
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The system clock, shared
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Wall-clock time of the host
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when told to
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    /// Clock stopped at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+    
+    /// Move the clock to `at`, backwards or forwards
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().expect("mock clock lock poisoned") = at;
+    }
+    
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().expect("mock clock lock poisoned") += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().expect("mock clock lock poisoned")
+    }
+}
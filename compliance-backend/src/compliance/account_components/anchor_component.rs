@@ -0,0 +1,137 @@
+//! Attestation epoch anchor account component
+//!
+//! Attestation commitments are batched into epochs and only each epoch's
+//! Merkle root goes on-chain, in one transaction per epoch. The backend
+//! writes roots with `anchor_epoch`; contracts read an epoch's root with
+//! `get_epoch_root` and check an attestation's inclusion with
+//! `mtree_verify`, using a proof fetched from the API.
+
+use super::compile_library;
+use super::template::{MasmBuilder, Procedure, SlotLayout};
+use crate::{ComplianceError, Result};
+use miden_client::account::AccountComponent;
+use miden_objects::account::{StorageMap, StorageSlot};
+use miden_objects::transaction::TransactionScript;
+use miden_objects::{Digest, TransactionKernel, EMPTY_WORD};
+
+/// Library path the anchor component is compiled under
+pub const ANCHOR_PATH: &str = "compliance::anchor";
+
+/// Procedure writing an epoch's root
+pub const ANCHOR_PROCEDURE: &str = "anchor_epoch";
+
+/// Procedure other contracts call to read an epoch's root
+pub const GET_EPOCH_ROOT_PROCEDURE: &str = "get_epoch_root";
+
+/// Storage slots of the anchor component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnchorSlot {
+    Roots,
+    Latest,
+}
+
+impl SlotLayout for AnchorSlot {
+    const SLOTS: &'static [Self] = &[Self::Roots, Self::Latest];
+    
+    fn index(self) -> u8 {
+        self as u8
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            Self::Roots => "roots",
+            Self::Latest => "latest",
+        }
+    }
+    
+    fn description(self) -> String {
+        match self {
+            Self::Roots => "Map from [epoch, 0, 0, 0] to the epoch's Merkle root".to_string(),
+            Self::Latest => "[epoch, attestation count, 0, 0] of the latest anchored epoch".to_string(),
+        }
+    }
+}
+
+/// Generate the anchor component's assembly
+pub fn render() -> String {
+    let mut masm = MasmBuilder::new();
+    masm.layout::<AnchorSlot>(
+        "Attestation Epoch Anchor Component",
+        "This component anchors one Merkle root of attestation commitments per epoch",
+    )
+    .import("miden::account")
+    .blank();
+    
+    masm.export(
+        &Procedure {
+            name: ANCHOR_PROCEDURE,
+            doc: "Store an epoch's root and make it the latest epoch",
+            inputs: &["ROOT", "epoch", "attestations"],
+            outputs: &[],
+        },
+        |m| {
+            m.op_with_comment("push.0.0.0 dup.7", "=> [epoch, 0, 0, 0, ROOT, epoch, attestations]");
+            m.op(format_args!("push.{} exec.account::set_map_item dropw dropw", AnchorSlot::Roots.index()));
+            m.op_with_comment("push.0.0 movup.3 movup.3", "=> [epoch, attestations, 0, 0]");
+            m.op(format_args!("push.{} exec.account::set_item dropw dropw", AnchorSlot::Latest.index()));
+        },
+    );
+    
+    masm.export(
+        &Procedure {
+            name: GET_EPOCH_ROOT_PROCEDURE,
+            doc: "Read an epoch's root; empty for epochs not anchored",
+            inputs: &["epoch"],
+            outputs: &["ROOT"],
+        },
+        |m| {
+            m.op_with_comment("push.0.0.0 movup.3", "=> [epoch, 0, 0, 0]");
+            m.op(format_args!("push.{} exec.account::get_map_item", AnchorSlot::Roots.index()));
+        },
+    );
+    
+    masm.export(
+        &Procedure {
+            name: "get_latest_epoch",
+            doc: "Read the latest anchored epoch",
+            inputs: &[],
+            outputs: &["epoch", "attestations", "0", "0"],
+        },
+        |m| {
+            m.op(format_args!("push.{} exec.account::get_item", AnchorSlot::Latest.index()));
+        },
+    );
+    
+    masm.finish()
+}
+
+/// Compile the anchor component with no epochs anchored
+pub fn compile_anchor_component() -> Result<AccountComponent> {
+    let slots = vec![StorageSlot::Map(StorageMap::new()), StorageSlot::Value(EMPTY_WORD)];
+    AccountComponent::compile(render(), TransactionKernel::assembler(), slots).map_err(|e| {
+        ComplianceError::AccountComponentCompilationFailed {
+            reason: format!("Failed to compile anchor component: {}", e),
+        }
+    })
+}
+
+/// Transaction script anchoring `root` as `epoch` on the anchor account
+pub fn anchor_script(root: Digest, epoch: u64, attestations: u64) -> Result<TransactionScript> {
+    let library = compile_library(ANCHOR_PATH, render())?;
+    let assembler = TransactionKernel::assembler()
+        .with_library(&library)
+        .map_err(|e| ComplianceError::AccountComponentCompilationFailed {
+            reason: format!("{}: {}", ANCHOR_PATH, e),
+        })?;
+    
+    let mut masm = MasmBuilder::new();
+    masm.import(ANCHOR_PATH).blank();
+    masm.begin(|m| {
+        m.op(format_args!("push.{} push.{} push.{}", attestations, epoch, root.to_hex()));
+        m.op(format_args!("call.anchor::{}", ANCHOR_PROCEDURE));
+    });
+    
+    TransactionScript::compile(masm.finish(), [], assembler).map_err(|e| ComplianceError::TransactionExecutionFailed {
+        reason: format!("failed to compile epoch anchor script: {}", e),
+    })
+}
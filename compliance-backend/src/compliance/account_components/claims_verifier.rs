@@ -0,0 +1,57 @@
+//! Verification of disclosed claims in Miden programs
+//!
+//! A proof envelope commits to each claim as a leaf of its claim tree (see
+//! [`crate::compliance::claims`]). Scripts and contracts that receive a
+//! disclosed claim check it against the envelope's claim root with
+//! `verify_claim`, which recomputes the leaf from the claim and its salt and
+//! verifies its Merkle path. The path must be loaded into the advice
+//! provider's Merkle store beforehand.
+
+use super::compile_library;
+use super::template::{MasmBuilder, Procedure};
+use crate::compliance::claims::CLAIM_TREE_DEPTH;
+use crate::crypto::commitment::{CommitmentDomain, COMMITMENT_VERSION};
+use crate::Result;
+use miden_objects::assembly::Library;
+
+/// Library path the claim verifier is compiled under
+pub const CLAIMS_PATH: &str = "compliance::claims";
+
+/// Procedure verifying a disclosed claim
+pub const VERIFY_CLAIM_PROCEDURE: &str = "verify_claim";
+
+/// Generate the claim verifier's assembly
+pub fn render() -> String {
+    let mut masm = MasmBuilder::new();
+    masm.comment("Compliance Claim Verifier")
+        .comment("Checks claims disclosed by proof envelopes against the envelope's claim root")
+        .blank();
+    
+    masm.export(
+        &Procedure {
+            name: VERIFY_CLAIM_PROCEDURE,
+            doc: "Verify a salted claim of a kind against a claim root; fails if it is not committed to",
+            inputs: &["SALT", "value", "kind", "CLAIMS_ROOT"],
+            outputs: &[],
+        },
+        |m| {
+            m.op_with_comment("dup.5 movdn.6", "=> [SALT, value, kind, kind, CLAIMS_ROOT]");
+            m.op(format_args!("push.{} movdn.6", COMMITMENT_VERSION));
+            m.op_with_comment(
+                format_args!("push.{} movdn.6", CommitmentDomain::Claim.code()),
+                "=> [SALT, value, kind, domain, version, kind, CLAIMS_ROOT]",
+            );
+            m.op_with_comment("hmerge", "=> [LEAF, kind, CLAIMS_ROOT]");
+            m.op_with_comment(format_args!("push.{} movdn.4", CLAIM_TREE_DEPTH), "=> [LEAF, depth, kind, CLAIMS_ROOT]");
+            m.op("mtree_verify");
+            m.op("dropw drop drop dropw");
+        },
+    );
+    
+    masm.finish()
+}
+
+/// Compile the claim verifier as a library scripts can link
+pub fn compile_claims_library() -> Result<Library> {
+    compile_library(CLAIMS_PATH, render())
+}
@@ -0,0 +1,195 @@
+//! Cross-account reads of compliance components
+//!
+//! A transaction executing against one account can call procedures of
+//! another account's components through foreign procedure invocation. The
+//! kernel runs the foreign procedure in the foreign account's context, so a
+//! dApp account can check a counterparty's on-chain KYC state itself instead
+//! of trusting the backend. Foreign procedures are addressed by MAST root,
+//! which depends on the deployment's template parameters.
+
+use super::compile_library;
+use super::migration::ComponentKind;
+use super::template::{compliance_level_code, kyc_status_code, ComponentTemplates, MasmBuilder, Procedure};
+use crate::types::{AccountId, AccountIdKind, ComplianceLevel, KycStatus};
+use crate::{ComplianceError, Result};
+use miden_client::account::AccountComponent;
+use miden_client::rpc::domain::account::AccountStorageRequirements;
+use miden_client::transaction::{ForeignAccount, TransactionRequest, TransactionRequestBuilder};
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::assembly::Library;
+use miden_objects::transaction::TransactionScript;
+use miden_objects::{Digest, TransactionKernel};
+use std::fmt::Display;
+
+/// Procedure of the KYC component read by gates
+pub const KYC_STATUS_PROCEDURE: &str = "get_kyc_status";
+
+/// Procedure exported by the gate component
+pub const GATE_PROCEDURE: &str = "require_verified_counterparty";
+
+/// Library path a component is compiled under
+pub fn component_path(kind: ComponentKind) -> String {
+    format!("compliance::{}", kind.name())
+}
+
+/// Compile a component as a library whose procedures can be looked up by name
+pub fn component_library(kind: ComponentKind, templates: &ComponentTemplates) -> Result<Library> {
+    compile_library(&component_path(kind), templates.render(kind))
+}
+
+/// MAST root of a component procedure
+///
+/// Looked up among the library's exports, whose MAST nodes carry the roots.
+pub fn procedure_root(kind: ComponentKind, templates: &ComponentTemplates, procedure: &str) -> Result<Digest> {
+    let library = component_library(kind, templates)?;
+    let export = library
+        .exports()
+        .find(|export| export.name.as_str() == procedure)
+        .ok_or_else(|| {
+            ComplianceError::validation("procedure", format!("{} component has no procedure {}", kind.name(), procedure))
+        })?;
+    let node_id = library.get_export_node_id(export);
+    Ok(library.mast_forest()[node_id].digest())
+}
+
+/// Miden account id of a validated account identifier
+pub fn miden_account_id(account_id: &AccountId) -> Result<MidenAccountId> {
+    let parsed = match account_id.kind() {
+        AccountIdKind::Evm => {
+            return Err(ComplianceError::validation(
+                "account_id",
+                "foreign procedure invocation needs a Miden account",
+            ));
+        }
+        AccountIdKind::Miden => MidenAccountId::from_hex(account_id.as_str()),
+    };
+    parsed.map_err(|e| ComplianceError::validation("account_id", format!("invalid Miden account id: {}", e)))
+}
+
+/// Call a foreign procedure that takes no inputs
+///
+/// `push_account` must push `[account_id_prefix, account_id_suffix]`. The
+/// procedure's outputs are left on the stack, padded to 16 elements.
+pub(crate) fn call_foreign(m: &mut MasmBuilder, root: Digest, push_account: impl FnOnce(&mut MasmBuilder)) {
+    m.op_with_comment("padw padw padw padw", "Foreign procedure inputs");
+    m.op_with_comment(format_args!("push.{}", root.to_hex()), "Foreign procedure root");
+    push_account(m);
+    m.op("exec.tx::execute_foreign_procedure");
+}
+
+/// Assert the outputs of `get_kyc_status` describe a usable verification
+///
+/// Consumes the procedure's 16 output elements.
+pub(crate) fn require_verified(m: &mut MasmBuilder, min_level: &ComplianceLevel) {
+    m.comment("=> [compliance_level, expiry_time, verification_time, status, pad(12)]");
+    m.op_with_comment(
+        format_args!("push.{} gte assert", compliance_level_code(min_level)),
+        "Compliance level meets the minimum",
+    );
+    m.op_with_comment("exec.tx::get_block_timestamp gt assert", "Verification has not expired");
+    m.op_with_comment("drop", "Verification time");
+    m.op_with_comment(
+        format_args!("push.{} eq assert", kyc_status_code(&KycStatus::Verified)),
+        "KYC is verified",
+    );
+    m.op("dropw dropw dropw");
+}
+
+/// Account component letting a dApp account gate its procedures on a
+/// counterparty's KYC
+///
+/// The dApp calls `exec.gate::require_verified_counterparty` with the
+/// counterparty's account id; the transaction fails unless the counterparty's
+/// KYC component reports a verified, unexpired status at `min_level` or above.
+/// The counterparty must be a public account so the kernel can load it.
+#[derive(Debug, Clone)]
+pub struct KycGate {
+    pub min_level: ComplianceLevel,
+}
+
+impl KycGate {
+    /// Generate the gate's assembly, calling the KYC status procedure at `kyc_status_root`
+    pub fn render(&self, kyc_status_root: Digest) -> String {
+        let mut masm = MasmBuilder::new();
+        masm.comment("Counterparty KYC Gate")
+            .comment("Reads a counterparty's KYC component through foreign procedure invocation")
+            .blank()
+            .import("miden::tx")
+            .blank();
+        
+        masm.export_with_locals(
+            &Procedure {
+                name: GATE_PROCEDURE,
+                doc: "Fail unless the counterparty's KYC is verified, unexpired and at the minimum level",
+                inputs: &["counterparty_id_prefix", "counterparty_id_suffix"],
+                outputs: &[],
+            },
+            2,
+            |m| {
+                m.op("loc_store.0 loc_store.1");
+                call_foreign(m, kyc_status_root, |m| {
+                    m.op("loc_load.1 loc_load.0");
+                });
+                require_verified(m, &self.min_level);
+            },
+        );
+        
+        masm.finish()
+    }
+    
+    /// Compile the gate against the deployment's KYC component
+    pub fn component(&self, templates: &ComponentTemplates) -> Result<AccountComponent> {
+        let root = procedure_root(ComponentKind::Kyc, templates, KYC_STATUS_PROCEDURE)?;
+        AccountComponent::compile(self.render(root), TransactionKernel::assembler(), vec![])
+            .map_err(|e| compilation_failed("kyc gate", e))
+    }
+}
+
+/// Transaction script failing unless `counterparty` passes the KYC gate
+pub fn kyc_check_script(
+    counterparty: MidenAccountId,
+    min_level: &ComplianceLevel,
+    templates: &ComponentTemplates,
+) -> Result<TransactionScript> {
+    let root = procedure_root(ComponentKind::Kyc, templates, KYC_STATUS_PROCEDURE)?;
+    let mut masm = MasmBuilder::new();
+    masm.import("miden::tx").blank();
+    masm.begin(|m| {
+        call_foreign(m, root, |m| {
+            m.op(format_args!("push.{} push.{}", counterparty.suffix(), counterparty.prefix().as_felt()));
+        });
+        require_verified(m, min_level);
+    });
+    
+    TransactionScript::compile(masm.finish(), [], TransactionKernel::assembler()).map_err(|e| {
+        ComplianceError::TransactionExecutionFailed {
+            reason: format!("failed to compile KYC check script: {}", e),
+        }
+    })
+}
+
+/// Transaction request running `script` with `counterparty` available for foreign calls
+///
+/// The counterparty is fetched from the node during execution, so it must be
+/// a public account.
+pub fn foreign_read_request(counterparty: MidenAccountId, script: TransactionScript) -> Result<TransactionRequest> {
+    let foreign = ForeignAccount::public(counterparty, AccountStorageRequirements::default())
+        .map_err(|e| request_failed(counterparty, e))?;
+    TransactionRequestBuilder::new()
+        .with_custom_script(script)
+        .with_foreign_accounts([foreign])
+        .build()
+        .map_err(|e| request_failed(counterparty, e))
+}
+
+fn compilation_failed(component: &str, e: impl Display) -> ComplianceError {
+    ComplianceError::AccountComponentCompilationFailed {
+        reason: format!("{}: {}", component, e),
+    }
+}
+
+fn request_failed(counterparty: MidenAccountId, e: impl Display) -> ComplianceError {
+    ComplianceError::TransactionExecutionFailed {
+        reason: format!("failed to build foreign read of {}: {}", counterparty, e),
+    }
+}
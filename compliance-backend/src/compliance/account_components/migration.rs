@@ -0,0 +1,439 @@
+//! Versioning and storage migration of deployed compliance components
+//!
+//! Every released revision of a component is listed in [`RELEASES`]. Accounts
+//! are matched to a release by the procedure roots in their account code, so
+//! the version of a deployed component is known without trusting anything it
+//! stores. When a release changes a component's storage layout, a
+//! [`StorageMigration`] from the previous release describes how to carry the
+//! existing values over; migration transaction scripts are generated from it.
+//!
+//! Release code is generated with the deployment's template parameters, so
+//! detection matches accounts deployed by this backend's configuration.
+//!
+//! To ship a fix, add a release with the next version rendering the new code
+//! and, if its slots moved, the migration from the previous version.
+
+use super::template::{layout, AmlSlot, ComponentTemplates, KycSlot, SanctionsSlot};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use miden_client::account::{AccountCode, AccountComponent};
+use miden_objects::transaction::TransactionScript;
+use miden_objects::{Digest, TransactionKernel};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::sync::OnceLock;
+use tokio::sync::RwLock;
+
+/// Compliance component deployed to accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Kyc,
+    Aml,
+    Sanctions,
+}
+
+impl ComponentKind {
+    /// Every component kind
+    pub const ALL: [Self; 3] = [Self::Kyc, Self::Aml, Self::Sanctions];
+    
+    /// Component name used in errors and logs
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Kyc => "kyc",
+            Self::Aml => "aml",
+            Self::Sanctions => "sanctions",
+        }
+    }
+    
+    /// Latest release of the component
+    pub fn current(self) -> &'static ComponentRelease {
+        RELEASES
+            .iter()
+            .filter(|release| release.kind == self)
+            .max_by_key(|release| release.version)
+            .expect("every component kind has a release")
+    }
+}
+
+/// A released revision of a component
+#[derive(Debug)]
+pub struct ComponentRelease {
+    pub kind: ComponentKind,
+    pub version: u32,
+    /// Generates the release's code with a deployment's parameters
+    ///
+    /// Once a release ships, its generator must keep emitting the same
+    /// instructions; changes to the code are new releases.
+    pub render: fn(&ComponentTemplates) -> String,
+    /// Storage slot of each field
+    pub slots: fn() -> Vec<(&'static str, u8)>,
+}
+
+/// Every released component revision, oldest first within a kind
+pub static RELEASES: &[ComponentRelease] = &[
+    ComponentRelease {
+        kind: ComponentKind::Kyc,
+        version: 1,
+        render: |templates| templates.kyc.render(),
+        slots: layout::<KycSlot>,
+    },
+    ComponentRelease {
+        kind: ComponentKind::Aml,
+        version: 1,
+        render: |templates| templates.aml.render(),
+        slots: layout::<AmlSlot>,
+    },
+    ComponentRelease {
+        kind: ComponentKind::Sanctions,
+        version: 1,
+        render: |templates| templates.sanctions.render(),
+        slots: layout::<SanctionsSlot>,
+    },
+];
+
+/// Change applied to an account's storage when migrating between releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MigrationStep {
+    /// Carry a value over to the slot it moved to
+    MoveSlot { from: u8, to: u8 },
+    /// Set a slot introduced by the new release
+    InitSlot { slot: u8, value: [u64; 4] },
+    /// Zero a slot the new release no longer uses
+    ClearSlot { slot: u8 },
+}
+
+/// Storage changes from one release of a component to the next
+#[derive(Debug)]
+pub struct StorageMigration {
+    pub kind: ComponentKind,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub steps: &'static [MigrationStep],
+}
+
+/// Storage migrations between consecutive releases
+///
+/// A release whose layout is unchanged needs no entry.
+pub static MIGRATIONS: &[StorageMigration] = &[];
+
+/// Storage steps migrating a component from `from_version` to the current release
+pub fn migration_steps(kind: ComponentKind, from_version: u32) -> Result<Vec<MigrationStep>> {
+    let target = kind.current().version;
+    if from_version > target {
+        return Err(ComplianceError::validation(
+            "from_version",
+            format!("{} has no release v{}", kind.name(), from_version),
+        ));
+    }
+    
+    let mut steps = Vec::new();
+    for version in from_version..target {
+        if let Some(migration) = MIGRATIONS
+            .iter()
+            .find(|m| m.kind == kind && m.from_version == version && m.to_version == version + 1)
+        {
+            steps.extend_from_slice(migration.steps);
+        }
+    }
+    Ok(steps)
+}
+
+/// Transaction script applying migration steps to the executing account
+pub fn migration_script(steps: &[MigrationStep]) -> String {
+    let mut script = String::from("use.miden::account\n\nbegin\n");
+    for step in steps {
+        match *step {
+            MigrationStep::MoveSlot { from, to } => {
+                let _ = writeln!(script, "    # move slot {} to slot {}", from, to);
+                let _ = writeln!(script, "    push.{} exec.account::get_item", from);
+                let _ = writeln!(script, "    push.{} exec.account::set_item dropw dropw", to);
+                let _ = writeln!(script, "    padw push.{} exec.account::set_item dropw dropw", from);
+            }
+            MigrationStep::InitSlot { slot, value } => {
+                let _ = writeln!(script, "    # initialize slot {}", slot);
+                let _ = writeln!(
+                    script,
+                    "    push.{}.{}.{}.{} push.{} exec.account::set_item dropw dropw",
+                    value[3], value[2], value[1], value[0], slot
+                );
+            }
+            MigrationStep::ClearSlot { slot } => {
+                let _ = writeln!(script, "    # clear slot {}", slot);
+                let _ = writeln!(script, "    padw push.{} exec.account::set_item dropw dropw", slot);
+            }
+        }
+    }
+    script.push_str("end\n");
+    script
+}
+
+/// Compile a migration script for submission
+pub fn compile_migration_script(script: &str) -> Result<TransactionScript> {
+    TransactionScript::compile(script, [], TransactionKernel::assembler()).map_err(|e| {
+        ComplianceError::TransactionExecutionFailed {
+            reason: format!("failed to compile migration script: {}", e),
+        }
+    })
+}
+
+/// Where an account's component stands relative to the current release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    /// Runs the current release
+    Current,
+    /// Runs an older release
+    Outdated,
+    /// A migration was generated and awaits the next sync to confirm it
+    InProgress,
+    /// The last migration attempt failed
+    Failed,
+    /// Runs code matching no release
+    Unrecognized,
+}
+
+/// An account's component as last seen during sync
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountComponentState {
+    pub account_id: AccountId,
+    pub kind: ComponentKind,
+    /// Detected release, `None` when unrecognized
+    pub version: Option<u32>,
+    pub target_version: u32,
+    pub status: MigrationStatus,
+    pub last_synced_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+/// Component versions across accounts
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentSummary {
+    pub kind: ComponentKind,
+    pub current_version: u32,
+    /// Accounts per detected release
+    pub accounts_by_version: BTreeMap<u32, usize>,
+    /// Accounts per migration status
+    pub accounts_by_status: HashMap<MigrationStatus, usize>,
+}
+
+/// A generated migration for one account
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPlan {
+    pub account_id: AccountId,
+    pub kind: ComponentKind,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub steps: Vec<MigrationStep>,
+    /// Transaction script applying `steps`
+    pub script: String,
+}
+
+/// Component versions of synced accounts and their migration progress
+#[derive(Default)]
+pub struct MigrationTracker {
+    templates: ComponentTemplates,
+    /// Procedure roots of every release, compiled on first detection
+    roots: OnceLock<HashMap<(ComponentKind, u32), BTreeSet<Digest>>>,
+    states: RwLock<HashMap<(AccountId, ComponentKind), AccountComponentState>>,
+}
+
+impl MigrationTracker {
+    /// Create an empty tracker detecting releases rendered with `templates`
+    pub fn new(templates: ComponentTemplates) -> Self {
+        Self {
+            templates,
+            roots: OnceLock::new(),
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Templates releases are rendered with
+    pub fn templates(&self) -> &ComponentTemplates {
+        &self.templates
+    }
+    
+    /// Procedure roots of a release
+    fn release_roots(&self, release: &ComponentRelease) -> Result<&BTreeSet<Digest>> {
+        let roots = match self.roots.get() {
+            Some(roots) => roots,
+            None => {
+                let mut compiled = HashMap::new();
+                for release in RELEASES {
+                    let code = (release.render)(&self.templates);
+                    let component = AccountComponent::compile(code, TransactionKernel::assembler(), vec![]).map_err(|e| {
+                        ComplianceError::AccountComponentCompilationFailed {
+                            reason: format!("{} v{}: {}", release.kind.name(), release.version, e),
+                        }
+                    })?;
+                    let roots = component.get_procedures().into_iter().map(|(root, _)| root).collect();
+                    compiled.insert((release.kind, release.version), roots);
+                }
+                self.roots.get_or_init(|| compiled)
+            }
+        };
+        roots
+            .get(&(release.kind, release.version))
+            .ok_or_else(|| ComplianceError::internal("component release missing from compiled roots"))
+    }
+    
+    /// Release of a component an account runs, if it runs a known one
+    ///
+    /// `None` means the account does not carry the component, or carries code
+    /// that matches no release.
+    pub fn detect_version(&self, kind: ComponentKind, code: &AccountCode) -> Result<Option<u32>> {
+        let account_roots: BTreeSet<Digest> = code.procedure_roots().collect();
+        let mut detected = None;
+        for release in RELEASES.iter().filter(|release| release.kind == kind) {
+            if self.release_roots(release)?.is_subset(&account_roots) {
+                detected = Some(release.version);
+            }
+        }
+        Ok(detected)
+    }
+    
+    /// Record the component releases an account runs, as seen during sync
+    ///
+    /// Components the account does not carry are skipped.
+    pub async fn observe(&self, account_id: &AccountId, code: &AccountCode) -> Result<Vec<AccountComponentState>> {
+        let mut observed = Vec::new();
+        for kind in ComponentKind::ALL {
+            let version = self.detect_version(kind, code)?;
+            if version.is_none() && !self.states.read().await.contains_key(&(account_id.clone(), kind)) {
+                continue;
+            }
+            observed.push(self.record(account_id, kind, version).await);
+        }
+        Ok(observed)
+    }
+    
+    /// Record the detected release of one component
+    pub async fn record(&self, account_id: &AccountId, kind: ComponentKind, version: Option<u32>) -> AccountComponentState {
+        let target_version = kind.current().version;
+        let mut states = self.states.write().await;
+        let previous = states.get(&(account_id.clone(), kind));
+        let status = match version {
+            Some(v) if v >= target_version => MigrationStatus::Current,
+            Some(_) => match previous.map(|state| state.status) {
+                Some(status @ (MigrationStatus::InProgress | MigrationStatus::Failed)) => status,
+                _ => MigrationStatus::Outdated,
+            },
+            None => MigrationStatus::Unrecognized,
+        };
+        let confirmed = previous.is_some_and(|state| state.status == MigrationStatus::InProgress)
+            && status == MigrationStatus::Current;
+        if confirmed {
+            tracing::info!(
+                account_id = %account_id,
+                component = kind.name(),
+                version = target_version,
+                "component migration confirmed"
+            );
+        }
+        
+        let state = AccountComponentState {
+            account_id: account_id.clone(),
+            kind,
+            version,
+            target_version,
+            status,
+            last_synced_at: Utc::now(),
+            last_error: previous
+                .and_then(|state| state.last_error.clone())
+                .filter(|_| status == MigrationStatus::Failed),
+        };
+        states.insert((account_id.clone(), kind), state.clone());
+        state
+    }
+    
+    /// Generate the migration bringing an account's component to the current release
+    pub async fn plan(&self, account_id: &AccountId, kind: ComponentKind) -> Result<MigrationPlan> {
+        let mut states = self.states.write().await;
+        let state = states
+            .get_mut(&(account_id.clone(), kind))
+            .ok_or_else(|| ComplianceError::AccountNotFound {
+                account_id: account_id.to_string(),
+            })?;
+        let from_version = match (state.status, state.version) {
+            (MigrationStatus::Current, _) => {
+                return Err(ComplianceError::validation("kind", format!("{} is already current", kind.name())));
+            }
+            (_, Some(version)) => version,
+            (_, None) => {
+                return Err(ComplianceError::validation(
+                    "kind",
+                    format!("{} runs unrecognized code and cannot be migrated", kind.name()),
+                ));
+            }
+        };
+        
+        let steps = migration_steps(kind, from_version)?;
+        let script = migration_script(&steps);
+        compile_migration_script(&script)?;
+        state.status = MigrationStatus::InProgress;
+        state.last_error = None;
+        
+        Ok(MigrationPlan {
+            account_id: account_id.clone(),
+            kind,
+            from_version,
+            to_version: state.target_version,
+            steps,
+            script,
+        })
+    }
+    
+    /// Record that a migration transaction failed
+    pub async fn mark_failed(&self, account_id: &AccountId, kind: ComponentKind, error: impl Into<String>) -> Result<()> {
+        let mut states = self.states.write().await;
+        let state = states
+            .get_mut(&(account_id.clone(), kind))
+            .ok_or_else(|| ComplianceError::AccountNotFound {
+                account_id: account_id.to_string(),
+            })?;
+        state.status = MigrationStatus::Failed;
+        state.last_error = Some(error.into());
+        Ok(())
+    }
+    
+    /// Accounts' component states, optionally filtered
+    pub async fn list(&self, kind: Option<ComponentKind>, status: Option<MigrationStatus>) -> Vec<AccountComponentState> {
+        let mut states: Vec<_> = self
+            .states
+            .read()
+            .await
+            .values()
+            .filter(|state| kind.map_or(true, |k| state.kind == k))
+            .filter(|state| status.map_or(true, |s| state.status == s))
+            .cloned()
+            .collect();
+        states.sort_by(|a, b| (a.kind, &a.account_id).cmp(&(b.kind, &b.account_id)));
+        states
+    }
+    
+    /// Component versions across all synced accounts
+    pub async fn summary(&self) -> Vec<ComponentSummary> {
+        let states = self.states.read().await;
+        ComponentKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let mut summary = ComponentSummary {
+                    kind,
+                    current_version: kind.current().version,
+                    accounts_by_version: BTreeMap::new(),
+                    accounts_by_status: HashMap::new(),
+                };
+                for state in states.values().filter(|state| state.kind == kind) {
+                    if let Some(version) = state.version {
+                        *summary.accounts_by_version.entry(version).or_default() += 1;
+                    }
+                    *summary.accounts_by_status.entry(state.status).or_default() += 1;
+                }
+                summary
+            })
+            .collect()
+    }
+}
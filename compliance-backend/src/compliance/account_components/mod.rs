@@ -1,405 +1,30 @@
 //! Account components for privacy-preserving compliance operations
+//!
+//! Component code is generated by [`template`] from typed slot layouts and
+//! deployment parameters.
 
+pub mod anchor_component;
+pub mod claims_verifier;
 pub mod kyc_component;
 pub mod compliance_component;
+pub mod foreign;
+pub mod migration;
+pub mod oracle_component;
+pub mod template;
 
 use crate::{Result, ComplianceError};
 use miden_client::account::AccountComponent;
+use miden_objects::assembly::{DefaultSourceManager, Library, LibraryPath, Module, ModuleKind};
 use miden_objects::TransactionKernel;
-
-/// KYC Account Component Code in Miden Assembly
-pub const KYC_ACCOUNT_COMPONENT_CODE: &str = r#"
-# KYC Account Component
-# This component handles privacy-preserving KYC verification
-
-# Storage slots:
-# - slot 0: KYC status (0=pending, 1=verified, 2=rejected, 3=expired)
-# - slot 1: KYC hash (hash of encrypted KYC data)
-# - slot 2: Verification timestamp
-# - slot 3: Expiry timestamp
-# - slot 4: Verifier ID (hash of verifier public key)
-# - slot 5: Compliance level (0=basic, 1=standard, 2=enhanced, 3=institutional)
-
-use.std::sys
-
-# Export KYC verification interface
-export.verify_kyc_data
-export.get_kyc_status
-export.update_kyc_status
-export.verify_kyc_proof
-export.get_compliance_level
-export.update_compliance_level
-
-# Verify KYC data with zero-knowledge proof
-# Input: [kyc_data_hash, verifier_id, compliance_level, proof_data]
-# Output: [success_flag]
-proc.verify_kyc_data
-    # Load current KYC status
-    push.0 mem_load
-    
-    # Check if already verified (status == 1)
-    push.1 eq
-    if.true
-        # Already verified, return success
-        push.1
-        return
-    end
-    
-    # Verify the proof data
-    # This would involve verifying the ZK proof of KYC data
-    # For now, we'll simulate by checking the hash
-    mem_load.1 # Load stored KYC hash
-    dup.3 # Duplicate provided hash
-    eq
-    if.true
-        # Hash matches, update status to verified
-        push.1 push.0 mem_store # Set status to verified
-        
-        # Store verifier ID
-        dup.2 push.4 mem_store
-        
-        # Store compliance level
-        dup.1 push.5 mem_store
-        
-        # Store verification timestamp
-        sys.time_now push.2 mem_store
-        
-        # Calculate expiry (1 year from now)
-        sys.time_now push.31536000 add push.3 mem_store
-        
-        push.1 # Success
-    else
-        push.0 # Failure
-    end
-end
-
-# Get KYC status
-# Output: [status, verification_time, expiry_time, compliance_level]
-proc.get_kyc_status
-    push.0 mem_load # KYC status
-    push.2 mem_load # Verification timestamp
-    push.3 mem_load # Expiry timestamp
-    push.5 mem_load # Compliance level
-end
-
-# Update KYC status (only by verifier)
-# Input: [new_status, verifier_id]
-# Output: [success_flag]
-proc.update_kyc_status
-    # Check if caller is authorized verifier
-    push.4 mem_load # Stored verifier ID
-    dup.1 # Duplicate provided verifier ID
-    eq
-    if.true
-        # Authorized, update status
-        dup.1 push.0 mem_store
-        push.1 # Success
-    else
-        push.0 # Failure - unauthorized
-    end
-end
-
-# Verify KYC proof without revealing data
-# Input: [proof_commitment, challenge]
-# Output: [verification_result]
-proc.verify_kyc_proof
-    # Load stored KYC hash
-    push.1 mem_load
-    
-    # Verify proof commitment against stored hash
-    # This would involve actual ZK proof verification
-    # For now, we simulate by checking commitment
-    dup.1 # Duplicate proof commitment
-    eq
-    if.true
-        # Proof valid, return success
-        push.1
-    else
-        # Proof invalid
-        push.0
-    end
-end
-
-# Get compliance level
-# Output: [compliance_level]
-proc.get_compliance_level
-    push.5 mem_load
-end
-
-# Update compliance level (only by authorized verifier)
-# Input: [new_level, verifier_id]
-# Output: [success_flag]
-proc.update_compliance_level
-    # Check if caller is authorized verifier
-    push.4 mem_load # Stored verifier ID
-    dup.1 # Duplicate provided verifier ID
-    eq
-    if.true
-        # Authorized, update compliance level
-        dup.1 push.5 mem_store
-        push.1 # Success
-    else
-        push.0 # Failure - unauthorized
-    end
-end
-"#;
-
-/// AML Account Component Code in Miden Assembly
-pub const AML_ACCOUNT_COMPONENT_CODE: &str = r#"
-# AML Account Component
-# This component handles privacy-preserving AML risk assessment
-
-# Storage slots:
-# - slot 0: AML risk level (0=low, 1=medium, 2=high, 3=critical)
-# - slot 1: Risk score (0-1000)
-# - slot 2: Last assessment timestamp
-# - slot 3: Transaction count
-# - slot 4: Total transaction volume
-# - slot 5: Suspicious activity flags
-
-use.std::sys
-
-export.assess_aml_risk
-export.get_aml_status
-export.update_risk_score
-export.record_transaction
-export.get_transaction_stats
-export.check_suspicious_patterns
-
-# Assess AML risk based on transaction patterns
-# Input: [transaction_amount, transaction_type, counterparty_risk]
-# Output: [risk_level, risk_score]
-proc.assess_aml_risk
-    # Load current risk score
-    push.1 mem_load
-    
-    # Calculate risk based on transaction amount
-    dup.3 # Duplicate transaction amount
-    push.10000 # Large transaction threshold
-    gte
-    if.true
-        # Large transaction, increase risk
-        push.100 add
-    end
-    
-    # Factor in counterparty risk
-    dup.1 # Duplicate counterparty risk
-    push.10 mul add
-    
-    # Update risk score
-    dup.0 push.1 mem_store
-    
-    # Determine risk level
-    dup.0 push.300 gte
-    if.true
-        push.2 # High risk
-    else
-        dup.0 push.150 gte
-        if.true
-            push.1 # Medium risk
-        else
-            push.0 # Low risk
-        end
-    end
-    
-    # Store risk level
-    dup.0 push.0 mem_store
-    
-    # Update timestamp
-    sys.time_now push.2 mem_store
-end
-
-# Get AML status
-# Output: [risk_level, risk_score, last_assessment]
-proc.get_aml_status
-    push.0 mem_load # Risk level
-    push.1 mem_load # Risk score
-    push.2 mem_load # Last assessment
-end
-
-# Update risk score (manual override)
-# Input: [new_score, new_level]
-# Output: [success_flag]
-proc.update_risk_score
-    # Store new score
-    dup.1 push.1 mem_store
-    
-    # Store new level
-    dup.0 push.0 mem_store
-    
-    # Update timestamp
-    sys.time_now push.2 mem_store
-    
-    push.1 # Success
-end
-
-# Record transaction for AML monitoring
-# Input: [amount, transaction_type, counterparty_hash]
-# Output: [success_flag]
-proc.record_transaction
-    # Increment transaction count
-    push.3 mem_load
-    push.1 add
-    push.3 mem_store
-    
-    # Add to total volume
-    push.4 mem_load
-    dup.3 add
-    push.4 mem_store
-    
-    # Check for suspicious patterns
-    exec.check_suspicious_patterns
-    
-    push.1 # Success
-end
-
-# Get transaction statistics
-# Output: [transaction_count, total_volume]
-proc.get_transaction_stats
-    push.3 mem_load # Transaction count
-    push.4 mem_load # Total volume
-end
-
-# Check for suspicious transaction patterns
-# Input: [amount, transaction_type, counterparty_hash]
-# Output: [suspicious_flag]
-proc.check_suspicious_patterns
-    push.0 # Default: not suspicious
-    
-    # Check for round amounts (potential structuring)
-    dup.3 push.10000 mod
-    push.0 eq
-    if.true
-        # Round amount, potentially suspicious
-        push.1 or
-    end
-    
-    # Check for rapid transactions (velocity)
-    # This would require more complex logic
-    
-    # Update suspicious activity flags if needed
-    dup.0 push.0 neq
-    if.true
-        push.5 mem_load
-        push.1 or
-        push.5 mem_store
-    end
-end
-"#;
-
-/// Sanctions Screening Component Code in Miden Assembly
-pub const SANCTIONS_SCREENING_COMPONENT_CODE: &str = r#"
-# Sanctions Screening Component
-# This component handles privacy-preserving sanctions screening
-
-# Storage slots:
-# - slot 0: Sanctions status (0=clear, 1=flagged, 2=blocked)
-# - slot 1: Last screening timestamp
-# - slot 2: Screening hash (hash of screening data)
-# - slot 3: Sanctions list version
-# - slot 4: False positive flag
-# - slot 5: Manual override flag
-
-use.std::sys
-
-export.screen_sanctions
-export.get_sanctions_status
-export.update_sanctions_status
-export.verify_screening_proof
-export.manual_override
-
-# Screen for sanctions matches
-# Input: [identity_hash, sanctions_list_hash, screening_proof]
-# Output: [sanctions_status, confidence_score]
-proc.screen_sanctions
-    # Store screening data hash
-    dup.2 push.2 mem_store
-    
-    # Update screening timestamp
-    sys.time_now push.1 mem_store
-    
-    # Verify screening proof
-    exec.verify_screening_proof
-    
-    # If proof is valid, trust the result
-    if.true
-        # Extract status from proof (simplified)
-        dup.0 push.1000 mod # Extract status
-        push.0 mem_store # Store sanctions status
-        
-        push.1 # High confidence
-    else
-        # Proof invalid, flag for manual review
-        push.1 push.0 mem_store # Flag as suspicious
-        push.0 # Low confidence
-    end
-end
-
-# Get sanctions screening status
-# Output: [status, last_screening, confidence]
-proc.get_sanctions_status
-    push.0 mem_load # Sanctions status
-    push.1 mem_load # Last screening
-    push.2 mem_load # Screening hash (as confidence indicator)
-end
-
-# Update sanctions status (manual override)
-# Input: [new_status, override_reason]
-# Output: [success_flag]
-proc.update_sanctions_status
-    # Store new status
-    dup.1 push.0 mem_store
-    
-    # Set manual override flag
-    push.1 push.5 mem_store
-    
-    # Update timestamp
-    sys.time_now push.1 mem_store
-    
-    push.1 # Success
-end
-
-# Verify sanctions screening proof
-# Input: [screening_proof]
-# Output: [verification_result]
-proc.verify_screening_proof
-    # Load stored screening hash
-    push.2 mem_load
-    
-    # Verify proof against stored hash
-    # This would involve actual ZK proof verification
-    dup.1 # Duplicate proof
-    eq
-    if.true
-        push.1 # Proof valid
-    else
-        push.0 # Proof invalid
-    end
-end
-
-# Manual override for sanctions status
-# Input: [override_status, authorization_hash]
-# Output: [success_flag]
-proc.manual_override
-    # Verify authorization (simplified)
-    dup.1 push.0 neq
-    if.true
-        # Authorized, apply override
-        dup.1 push.0 mem_store
-        push.1 push.5 mem_store
-        push.1 # Success
-    else
-        push.0 # Unauthorized
-    end
-end
-"#;
+use std::sync::Arc;
+use template::{AmlTemplate, KycTemplate, SanctionsTemplate};
 
 /// Compile KYC account component
-pub fn compile_kyc_component() -> Result<AccountComponent> {
+pub fn compile_kyc_component(template: &KycTemplate) -> Result<AccountComponent> {
     let assembler = TransactionKernel::assembler();
     
     AccountComponent::compile(
-        KYC_ACCOUNT_COMPONENT_CODE,
+        template.render(),
         assembler,
         vec![], // No additional storage slots needed
     )
@@ -409,11 +34,11 @@ pub fn compile_kyc_component() -> Result<AccountComponent> {
 }
 
 /// Compile AML account component
-pub fn compile_aml_component() -> Result<AccountComponent> {
+pub fn compile_aml_component(template: &AmlTemplate) -> Result<AccountComponent> {
     let assembler = TransactionKernel::assembler();
     
     AccountComponent::compile(
-        AML_ACCOUNT_COMPONENT_CODE,
+        template.render(),
         assembler,
         vec![], // No additional storage slots needed
     )
@@ -423,15 +48,33 @@ pub fn compile_aml_component() -> Result<AccountComponent> {
 }
 
 /// Compile sanctions screening component
-pub fn compile_sanctions_component() -> Result<AccountComponent> {
+pub fn compile_sanctions_component(template: &SanctionsTemplate) -> Result<AccountComponent> {
     let assembler = TransactionKernel::assembler();
     
     AccountComponent::compile(
-        SANCTIONS_SCREENING_COMPONENT_CODE,
+        template.render(),
         assembler,
         vec![], // No additional storage slots needed
     )
     .map_err(|e| ComplianceError::AccountComponentCompilationFailed {
         reason: format!("Failed to compile sanctions component: {}", e),
     })
-} 
\ No newline at end of file
+} 
+/// Compile component code as a library under `path`
+///
+/// Scripts link the library to call the component's procedures by name.
+/// Procedure roots do not depend on the path, so they match those of the
+/// component deployed to accounts.
+pub fn compile_library(path: &str, source: String) -> Result<Library> {
+    let failed = |e: &dyn std::fmt::Display| ComplianceError::AccountComponentCompilationFailed {
+        reason: format!("{}: {}", path, e),
+    };
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let library_path = LibraryPath::new(path).map_err(|e| failed(&e))?;
+    let module = Module::parser(ModuleKind::Library)
+        .parse_str(library_path, source, &source_manager)
+        .map_err(|e| failed(&e))?;
+    TransactionKernel::assembler()
+        .assemble_library([module])
+        .map_err(|e| failed(&e))
+}
@@ -0,0 +1,123 @@
+//! Compliance oracle account component
+//!
+//! Holds the published root of the compliant account set. The backend writes
+//! it with `publish_root`; other contracts read it with `get_root` through
+//! foreign procedure invocation and check an account's membership with
+//! `mtree_verify`, using an inclusion proof fetched from the API.
+
+use super::compile_library;
+use super::template::{MasmBuilder, Procedure, SlotLayout};
+use crate::{ComplianceError, Result};
+use miden_client::account::AccountComponent;
+use miden_objects::account::StorageSlot;
+use miden_objects::transaction::TransactionScript;
+use miden_objects::{Digest, TransactionKernel, EMPTY_WORD};
+
+/// Library path the oracle component is compiled under
+pub const ORACLE_PATH: &str = "compliance::oracle";
+
+/// Procedure writing a new root
+pub const PUBLISH_PROCEDURE: &str = "publish_root";
+
+/// Procedure other contracts call to read the root
+pub const GET_ROOT_PROCEDURE: &str = "get_root";
+
+/// Storage slots of the oracle component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleSlot {
+    Root,
+    Epoch,
+}
+
+impl SlotLayout for OracleSlot {
+    const SLOTS: &'static [Self] = &[Self::Root, Self::Epoch];
+    
+    fn index(self) -> u8 {
+        self as u8
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            Self::Root => "root",
+            Self::Epoch => "epoch",
+        }
+    }
+    
+    fn description(self) -> String {
+        match self {
+            Self::Root => "Merkle root of the compliant account set".to_string(),
+            Self::Epoch => "[epoch, account count, 0, 0] of the published root".to_string(),
+        }
+    }
+}
+
+/// Generate the oracle component's assembly
+pub fn render() -> String {
+    let mut masm = MasmBuilder::new();
+    masm.layout::<OracleSlot>(
+        "Compliance Oracle Component",
+        "This component publishes a commitment to the set of compliant accounts",
+    )
+    .import("miden::account")
+    .blank();
+    
+    masm.export(
+        &Procedure {
+            name: PUBLISH_PROCEDURE,
+            doc: "Store a new root of the compliant account set",
+            inputs: &["ROOT", "epoch", "accounts"],
+            outputs: &[],
+        },
+        |m| {
+            m.op(format_args!("push.{} exec.account::set_item dropw dropw", OracleSlot::Root.index()));
+            m.op_with_comment("push.0.0 movup.3 movup.3", "=> [epoch, accounts, 0, 0]");
+            m.op(format_args!("push.{} exec.account::set_item dropw dropw", OracleSlot::Epoch.index()));
+        },
+    );
+    
+    masm.export(
+        &Procedure {
+            name: GET_ROOT_PROCEDURE,
+            doc: "Read the published root",
+            inputs: &[],
+            outputs: &["ROOT", "epoch", "accounts"],
+        },
+        |m| {
+            m.op(format_args!("push.{} exec.account::get_item", OracleSlot::Epoch.index()));
+            m.op(format_args!("push.{} exec.account::get_item", OracleSlot::Root.index()));
+        },
+    );
+    
+    masm.finish()
+}
+
+/// Compile the oracle component with empty storage
+pub fn compile_oracle_component() -> Result<AccountComponent> {
+    let slots = OracleSlot::SLOTS.iter().map(|_| StorageSlot::Value(EMPTY_WORD)).collect();
+    AccountComponent::compile(render(), TransactionKernel::assembler(), slots).map_err(|e| {
+        ComplianceError::AccountComponentCompilationFailed {
+            reason: format!("Failed to compile oracle component: {}", e),
+        }
+    })
+}
+
+/// Transaction script publishing `root` on the oracle account
+pub fn publish_script(root: Digest, epoch: u64, accounts: u64) -> Result<TransactionScript> {
+    let library = compile_library(ORACLE_PATH, render())?;
+    let assembler = TransactionKernel::assembler()
+        .with_library(&library)
+        .map_err(|e| ComplianceError::AccountComponentCompilationFailed {
+            reason: format!("{}: {}", ORACLE_PATH, e),
+        })?;
+    
+    let mut masm = MasmBuilder::new();
+    masm.import(ORACLE_PATH).blank();
+    masm.begin(|m| {
+        m.op(format_args!("push.{} push.{} push.{}", accounts, epoch, root.to_hex()));
+        m.op(format_args!("call.oracle::{}", PUBLISH_PROCEDURE));
+    });
+    
+    TransactionScript::compile(masm.finish(), [], assembler).map_err(|e| ComplianceError::TransactionExecutionFailed {
+        reason: format!("failed to compile oracle publish script: {}", e),
+    })
+}
@@ -0,0 +1,846 @@
+//! Generation of component assembly from typed definitions
+//!
+//! Slot indices, status encodings and thresholds used by the assembly come
+//! from the Rust definitions below instead of literals in hand-written code,
+//! so the backend and the deployed components agree on what every slot and
+//! value means. Deployment parameters such as verification validity, risk
+//! thresholds and authorized keys are baked into the generated code.
+
+use super::migration::ComponentKind;
+use crate::config::ComponentTemplateConfig;
+pub use crate::crypto::commitment::{aml_risk_code, compliance_level_code, kyc_status_code};
+use crate::types::{AmlRiskLevel, ComplianceLevel, KycStatus};
+use std::fmt::{Display, Write};
+
+/// Storage layout of a component
+pub trait SlotLayout: Copy + 'static {
+    /// Every slot, in index order
+    const SLOTS: &'static [Self];
+    
+    /// Storage slot index
+    fn index(self) -> u8;
+    
+    /// Field name, as used in release layouts and migrations
+    fn name(self) -> &'static str;
+    
+    /// Description written into the generated code
+    fn description(self) -> String;
+}
+
+/// Field name and slot index of every slot in a layout
+pub fn layout<S: SlotLayout>() -> Vec<(&'static str, u8)> {
+    S::SLOTS.iter().map(|slot| (slot.name(), slot.index())).collect()
+}
+
+/// Storage slots of the KYC component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KycSlot {
+    Status,
+    Hash,
+    VerifiedAt,
+    ExpiresAt,
+    VerifierId,
+    ComplianceLevel,
+}
+
+impl SlotLayout for KycSlot {
+    const SLOTS: &'static [Self] = &[
+        Self::Status,
+        Self::Hash,
+        Self::VerifiedAt,
+        Self::ExpiresAt,
+        Self::VerifierId,
+        Self::ComplianceLevel,
+    ];
+    
+    fn index(self) -> u8 {
+        self as u8
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            Self::Status => "kyc_status",
+            Self::Hash => "kyc_hash",
+            Self::VerifiedAt => "verified_at",
+            Self::ExpiresAt => "expires_at",
+            Self::VerifierId => "verifier_id",
+            Self::ComplianceLevel => "compliance_level",
+        }
+    }
+    
+    fn description(self) -> String {
+        match self {
+            Self::Status => format!(
+                "KYC status ({})",
+                encoding(&[
+                    (kyc_status_code(&KycStatus::Pending), "pending"),
+                    (kyc_status_code(&KycStatus::Verified), "verified"),
+                    (kyc_status_code(&KycStatus::Rejected), "rejected"),
+                    (kyc_status_code(&KycStatus::Expired), "expired"),
+                ])
+            ),
+            Self::Hash => "KYC hash (hash of encrypted KYC data)".to_string(),
+            Self::VerifiedAt => "Verification timestamp".to_string(),
+            Self::ExpiresAt => "Expiry timestamp".to_string(),
+            Self::VerifierId => "Verifier ID (hash of verifier public key)".to_string(),
+            Self::ComplianceLevel => format!(
+                "Compliance level ({})",
+                encoding(&[
+                    (compliance_level_code(&ComplianceLevel::Basic), "basic"),
+                    (compliance_level_code(&ComplianceLevel::Standard), "standard"),
+                    (compliance_level_code(&ComplianceLevel::Enhanced), "enhanced"),
+                    (compliance_level_code(&ComplianceLevel::InstitutionalGrade), "institutional"),
+                ])
+            ),
+        }
+    }
+}
+
+/// Storage slots of the AML component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmlSlot {
+    RiskLevel,
+    RiskScore,
+    LastAssessment,
+    TransactionCount,
+    TransactionVolume,
+    SuspiciousFlags,
+}
+
+impl SlotLayout for AmlSlot {
+    const SLOTS: &'static [Self] = &[
+        Self::RiskLevel,
+        Self::RiskScore,
+        Self::LastAssessment,
+        Self::TransactionCount,
+        Self::TransactionVolume,
+        Self::SuspiciousFlags,
+    ];
+    
+    fn index(self) -> u8 {
+        self as u8
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            Self::RiskLevel => "risk_level",
+            Self::RiskScore => "risk_score",
+            Self::LastAssessment => "last_assessment",
+            Self::TransactionCount => "transaction_count",
+            Self::TransactionVolume => "transaction_volume",
+            Self::SuspiciousFlags => "suspicious_flags",
+        }
+    }
+    
+    fn description(self) -> String {
+        match self {
+            Self::RiskLevel => format!(
+                "AML risk level ({})",
+                encoding(&[
+                    (aml_risk_code(&AmlRiskLevel::Low), "low"),
+                    (aml_risk_code(&AmlRiskLevel::Medium), "medium"),
+                    (aml_risk_code(&AmlRiskLevel::High), "high"),
+                    (aml_risk_code(&AmlRiskLevel::Critical), "critical"),
+                ])
+            ),
+            Self::RiskScore => "Risk score (0-1000)".to_string(),
+            Self::LastAssessment => "Last assessment timestamp".to_string(),
+            Self::TransactionCount => "Transaction count".to_string(),
+            Self::TransactionVolume => "Total transaction volume".to_string(),
+            Self::SuspiciousFlags => "Suspicious activity flags".to_string(),
+        }
+    }
+}
+
+/// Storage slots of the sanctions screening component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanctionsSlot {
+    Status,
+    LastScreening,
+    ScreeningHash,
+    ListVersion,
+    FalsePositive,
+    ManualOverride,
+}
+
+impl SlotLayout for SanctionsSlot {
+    const SLOTS: &'static [Self] = &[
+        Self::Status,
+        Self::LastScreening,
+        Self::ScreeningHash,
+        Self::ListVersion,
+        Self::FalsePositive,
+        Self::ManualOverride,
+    ];
+    
+    fn index(self) -> u8 {
+        self as u8
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            Self::Status => "sanctions_status",
+            Self::LastScreening => "last_screening",
+            Self::ScreeningHash => "screening_hash",
+            Self::ListVersion => "list_version",
+            Self::FalsePositive => "false_positive",
+            Self::ManualOverride => "manual_override",
+        }
+    }
+    
+    fn description(self) -> String {
+        match self {
+            Self::Status => format!(
+                "Sanctions status ({})",
+                encoding(&[
+                    (ScreeningStatus::Clear.code(), "clear"),
+                    (ScreeningStatus::Flagged.code(), "flagged"),
+                    (ScreeningStatus::Blocked.code(), "blocked"),
+                ])
+            ),
+            Self::LastScreening => "Last screening timestamp".to_string(),
+            Self::ScreeningHash => "Screening hash (hash of screening data)".to_string(),
+            Self::ListVersion => "Sanctions list version".to_string(),
+            Self::FalsePositive => "False positive flag".to_string(),
+            Self::ManualOverride => "Manual override flag".to_string(),
+        }
+    }
+}
+
+/// Sanctions status held by the screening component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreeningStatus {
+    Clear,
+    Flagged,
+    Blocked,
+}
+
+impl ScreeningStatus {
+    /// Value stored in the sanctions status slot
+    pub fn code(self) -> u64 {
+        self as u64
+    }
+}
+
+fn encoding(values: &[(u64, &str)]) -> String {
+    values
+        .iter()
+        .map(|(code, name)| format!("{}={}", code, name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Comment lines heading an exported procedure
+pub struct Procedure<'a> {
+    pub name: &'a str,
+    pub doc: &'a str,
+    pub inputs: &'a [&'a str],
+    pub outputs: &'a [&'a str],
+}
+
+/// Miden assembly written instruction by instruction with managed indentation
+#[derive(Default)]
+pub struct MasmBuilder {
+    code: String,
+    depth: usize,
+}
+
+impl MasmBuilder {
+    /// Start an empty module
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Comment line
+    pub fn comment(&mut self, text: impl Display) -> &mut Self {
+        self.line(format_args!("# {}", text))
+    }
+    
+    /// Empty line
+    pub fn blank(&mut self) -> &mut Self {
+        self.code.push('\n');
+        self
+    }
+    
+    /// Instruction or instruction sequence
+    pub fn op(&mut self, instruction: impl Display) -> &mut Self {
+        self.line(instruction)
+    }
+    
+    /// Instruction followed by a trailing comment
+    pub fn op_with_comment(&mut self, instruction: impl Display, comment: impl Display) -> &mut Self {
+        self.line(format_args!("{} # {}", instruction, comment))
+    }
+    
+    /// Module import
+    pub fn import(&mut self, path: &str) -> &mut Self {
+        self.line(format_args!("use.{}", path))
+    }
+    
+    /// Header comment listing a component's storage layout
+    pub fn layout<S: SlotLayout>(&mut self, title: &str, summary: &str) -> &mut Self {
+        self.comment(title).comment(summary).blank().comment("Storage slots:");
+        for slot in S::SLOTS {
+            self.comment(format_args!("- slot {}: {}", slot.index(), slot.description()));
+        }
+        self.blank()
+    }
+    
+    /// Push a slot's value
+    pub fn load<S: SlotLayout>(&mut self, slot: S) -> &mut Self {
+        self.op(format_args!("push.{} mem_load", slot.index()))
+    }
+    
+    /// Pop the top of the stack into a slot
+    pub fn store<S: SlotLayout>(&mut self, slot: S) -> &mut Self {
+        self.op(format_args!("push.{} mem_store", slot.index()))
+    }
+    
+    /// Exported procedure
+    pub fn export(&mut self, procedure: &Procedure<'_>, body: impl FnOnce(&mut Self)) -> &mut Self {
+        self.export_with_locals(procedure, 0, body)
+    }
+    
+    /// Exported procedure with `locals` local memory elements
+    pub fn export_with_locals(&mut self, procedure: &Procedure<'_>, locals: u16, body: impl FnOnce(&mut Self)) -> &mut Self {
+        self.comment(procedure.doc);
+        if !procedure.inputs.is_empty() {
+            self.comment(format_args!("Input: [{}]", procedure.inputs.join(", ")));
+        }
+        if !procedure.outputs.is_empty() {
+            self.comment(format_args!("Output: [{}]", procedure.outputs.join(", ")));
+        }
+        if locals == 0 {
+            self.line(format_args!("export.{}", procedure.name));
+        } else {
+            self.line(format_args!("export.{}.{}", procedure.name, locals));
+        }
+        self.nested(body);
+        self.line("end").blank()
+    }
+    
+    /// Script or program entry point
+    pub fn begin(&mut self, body: impl FnOnce(&mut Self)) -> &mut Self {
+        self.line("begin");
+        self.nested(body);
+        self.line("end")
+    }
+    
+    /// Branch on the top of the stack
+    pub fn if_else(&mut self, then: impl FnOnce(&mut Self), otherwise: impl FnOnce(&mut Self)) -> &mut Self {
+        self.line("if.true");
+        self.nested(then);
+        self.line("else");
+        self.nested(otherwise);
+        self.line("end")
+    }
+    
+    /// Run a block when the top of the stack is true
+    pub fn if_true(&mut self, then: impl FnOnce(&mut Self)) -> &mut Self {
+        self.line("if.true");
+        self.nested(then);
+        self.line("end")
+    }
+    
+    /// Replace the value `depth` elements down with whether it is one of `keys`
+    ///
+    /// Pushes the flag on top of the stack; the stack below it is unchanged.
+    pub fn any_of(&mut self, depth: usize, keys: &[u64]) -> &mut Self {
+        for (i, key) in keys.iter().enumerate() {
+            if i == 0 {
+                self.op(format_args!("dup.{} push.{} eq", depth, key));
+            } else {
+                self.op(format_args!("dup.{} push.{} eq or", depth + 1, key));
+            }
+        }
+        self
+    }
+    
+    /// Generated module source
+    pub fn finish(self) -> String {
+        self.code
+    }
+    
+    fn nested(&mut self, body: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        body(self);
+        self.depth -= 1;
+    }
+    
+    fn line(&mut self, text: impl Display) -> &mut Self {
+        for _ in 0..self.depth {
+            self.code.push_str("    ");
+        }
+        let _ = writeln!(self.code, "{}", text);
+        self
+    }
+}
+
+/// Parameters of the generated KYC component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KycTemplate {
+    /// Seconds a verification stays valid
+    pub validity_secs: u64,
+    /// Verifier IDs accepted by `verify_kyc_data`; any verifier when empty
+    pub authorized_verifiers: Vec<u64>,
+}
+
+impl Default for KycTemplate {
+    fn default() -> Self {
+        Self {
+            validity_secs: 31_536_000,
+            authorized_verifiers: Vec::new(),
+        }
+    }
+}
+
+impl KycTemplate {
+    /// Generate the component's assembly
+    pub fn render(&self) -> String {
+        let verified = kyc_status_code(&KycStatus::Verified);
+        let mut masm = MasmBuilder::new();
+        masm.layout::<KycSlot>("KYC Account Component", "This component handles privacy-preserving KYC verification")
+            .import("std::sys")
+            .blank();
+        
+        masm.export(
+            &Procedure {
+                name: "verify_kyc_data",
+                doc: "Verify KYC data with zero-knowledge proof",
+                inputs: &["kyc_data_hash", "verifier_id", "compliance_level", "proof_data"],
+                outputs: &["success_flag"],
+            },
+            |m| {
+                m.load(KycSlot::Status).op(format_args!("push.{} eq", verified)).if_else(
+                    |m| {
+                        m.op_with_comment("push.1", "Already verified");
+                    },
+                    |m| {
+                        // The proof is simulated by comparing the provided hash with the stored one
+                        m.load(KycSlot::Hash).op_with_comment("dup.1 eq", "Provided hash matches stored hash");
+                        if !self.authorized_verifiers.is_empty() {
+                            m.any_of(2, &self.authorized_verifiers)
+                                .op_with_comment("and", "Verifier is authorized");
+                        }
+                        m.if_else(
+                            |m| {
+                                m.op(format_args!("push.{}", verified)).store(KycSlot::Status);
+                                m.op("dup.1").store(KycSlot::VerifierId);
+                                m.op("dup.2").store(KycSlot::ComplianceLevel);
+                                m.op("sys.time_now").store(KycSlot::VerifiedAt);
+                                m.op(format_args!("sys.time_now push.{} add", self.validity_secs))
+                                    .store(KycSlot::ExpiresAt);
+                                m.op_with_comment("push.1", "Success");
+                            },
+                            |m| {
+                                m.op_with_comment("push.0", "Failure");
+                            },
+                        );
+                    },
+                );
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "get_kyc_status",
+                doc: "Get KYC status",
+                inputs: &[],
+                outputs: &["status", "verification_time", "expiry_time", "compliance_level"],
+            },
+            |m| {
+                m.load(KycSlot::Status)
+                    .load(KycSlot::VerifiedAt)
+                    .load(KycSlot::ExpiresAt)
+                    .load(KycSlot::ComplianceLevel);
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "update_kyc_status",
+                doc: "Update KYC status (only by verifier)",
+                inputs: &["new_status", "verifier_id"],
+                outputs: &["success_flag"],
+            },
+            |m| verifier_update(m, KycSlot::Status),
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "verify_kyc_proof",
+                doc: "Verify KYC proof without revealing data",
+                inputs: &["proof_commitment", "challenge"],
+                outputs: &["verification_result"],
+            },
+            |m| {
+                m.load(KycSlot::Hash).op_with_comment("dup.1 eq", "Commitment matches stored hash");
+                m.if_else(
+                    |m| {
+                        m.op("push.1");
+                    },
+                    |m| {
+                        m.op("push.0");
+                    },
+                );
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "get_compliance_level",
+                doc: "Get compliance level",
+                inputs: &[],
+                outputs: &["compliance_level"],
+            },
+            |m| {
+                m.load(KycSlot::ComplianceLevel);
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "update_compliance_level",
+                doc: "Update compliance level (only by authorized verifier)",
+                inputs: &["new_level", "verifier_id"],
+                outputs: &["success_flag"],
+            },
+            |m| verifier_update(m, KycSlot::ComplianceLevel),
+        );
+        
+        masm.finish()
+    }
+}
+
+/// Store the top of the stack into `slot` when the caller is the stored verifier
+fn verifier_update(m: &mut MasmBuilder, slot: KycSlot) {
+    m.load(KycSlot::VerifierId).op_with_comment("dup.2 eq", "Caller is the stored verifier");
+    m.if_else(
+        |m| {
+            m.op("dup.0").store(slot);
+            m.op_with_comment("push.1", "Success");
+        },
+        |m| {
+            m.op_with_comment("push.0", "Unauthorized");
+        },
+    );
+}
+
+/// Parameters of the generated AML component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmlTemplate {
+    /// Amount from which a transaction counts as large
+    pub large_transaction_threshold: u64,
+    /// Score added for a large transaction
+    pub large_transaction_score: u64,
+    /// Score added per point of counterparty risk
+    pub counterparty_weight: u64,
+    /// Score from which the risk level is medium
+    pub medium_risk_score: u64,
+    /// Score from which the risk level is high
+    pub high_risk_score: u64,
+    /// Amounts divisible by this are flagged as possible structuring
+    pub round_amount_unit: u64,
+}
+
+impl Default for AmlTemplate {
+    fn default() -> Self {
+        Self {
+            large_transaction_threshold: 10_000,
+            large_transaction_score: 100,
+            counterparty_weight: 10,
+            medium_risk_score: 150,
+            high_risk_score: 300,
+            round_amount_unit: 10_000,
+        }
+    }
+}
+
+impl AmlTemplate {
+    /// Generate the component's assembly
+    pub fn render(&self) -> String {
+        let mut masm = MasmBuilder::new();
+        masm.layout::<AmlSlot>("AML Account Component", "This component handles privacy-preserving AML risk assessment")
+            .import("std::sys")
+            .blank();
+        
+        masm.export(
+            &Procedure {
+                name: "assess_aml_risk",
+                doc: "Assess AML risk based on transaction patterns",
+                inputs: &["transaction_amount", "transaction_type", "counterparty_risk"],
+                outputs: &["risk_level", "risk_score"],
+            },
+            |m| {
+                m.load(AmlSlot::RiskScore);
+                m.op_with_comment(
+                    format_args!("dup.1 push.{} gte", self.large_transaction_threshold),
+                    "Large transaction",
+                )
+                .if_true(|m| {
+                    m.op(format_args!("push.{} add", self.large_transaction_score));
+                });
+                m.op_with_comment(
+                    format_args!("dup.3 push.{} mul add", self.counterparty_weight),
+                    "Counterparty risk",
+                );
+                m.op("dup.0").store(AmlSlot::RiskScore);
+                m.op(format_args!("dup.0 push.{} gte", self.high_risk_score)).if_else(
+                    |m| {
+                        m.op(format_args!("push.{}", aml_risk_code(&AmlRiskLevel::High)));
+                    },
+                    |m| {
+                        m.op(format_args!("dup.0 push.{} gte", self.medium_risk_score)).if_else(
+                            |m| {
+                                m.op(format_args!("push.{}", aml_risk_code(&AmlRiskLevel::Medium)));
+                            },
+                            |m| {
+                                m.op(format_args!("push.{}", aml_risk_code(&AmlRiskLevel::Low)));
+                            },
+                        );
+                    },
+                );
+                m.op("dup.0").store(AmlSlot::RiskLevel);
+                m.op("sys.time_now").store(AmlSlot::LastAssessment);
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "get_aml_status",
+                doc: "Get AML status",
+                inputs: &[],
+                outputs: &["risk_level", "risk_score", "last_assessment"],
+            },
+            |m| {
+                m.load(AmlSlot::RiskLevel).load(AmlSlot::RiskScore).load(AmlSlot::LastAssessment);
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "update_risk_score",
+                doc: "Update risk score (manual override)",
+                inputs: &["new_score", "new_level"],
+                outputs: &["success_flag"],
+            },
+            |m| {
+                m.op("dup.0").store(AmlSlot::RiskScore);
+                m.op("dup.1").store(AmlSlot::RiskLevel);
+                m.op("sys.time_now").store(AmlSlot::LastAssessment);
+                m.op_with_comment("push.1", "Success");
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "record_transaction",
+                doc: "Record transaction for AML monitoring",
+                inputs: &["amount", "transaction_type", "counterparty_hash"],
+                outputs: &["success_flag"],
+            },
+            |m| {
+                m.load(AmlSlot::TransactionCount).op("push.1 add").store(AmlSlot::TransactionCount);
+                m.load(AmlSlot::TransactionVolume).op("dup.1 add").store(AmlSlot::TransactionVolume);
+                m.op("exec.check_suspicious_patterns");
+                m.op_with_comment("push.1", "Success");
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "get_transaction_stats",
+                doc: "Get transaction statistics",
+                inputs: &[],
+                outputs: &["transaction_count", "total_volume"],
+            },
+            |m| {
+                m.load(AmlSlot::TransactionCount).load(AmlSlot::TransactionVolume);
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "check_suspicious_patterns",
+                doc: "Check for suspicious transaction patterns",
+                inputs: &["amount", "transaction_type", "counterparty_hash"],
+                outputs: &["suspicious_flag"],
+            },
+            |m| {
+                m.op_with_comment("push.0", "Not suspicious");
+                m.op_with_comment(
+                    format_args!("dup.1 push.{} u32mod push.0 eq", self.round_amount_unit),
+                    "Round amount (potential structuring)",
+                )
+                .if_true(|m| {
+                    m.op("push.1 or");
+                });
+                m.op("dup.0 push.0 neq").if_true(|m| {
+                    m.load(AmlSlot::SuspiciousFlags).op("push.1 or").store(AmlSlot::SuspiciousFlags);
+                });
+            },
+        );
+        
+        masm.finish()
+    }
+}
+
+/// Parameters of the generated sanctions screening component
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SanctionsTemplate {
+    /// Authorization keys accepted by `manual_override`; any non-zero key when empty
+    pub override_keys: Vec<u64>,
+}
+
+impl SanctionsTemplate {
+    /// Generate the component's assembly
+    pub fn render(&self) -> String {
+        let mut masm = MasmBuilder::new();
+        masm.layout::<SanctionsSlot>(
+            "Sanctions Screening Component",
+            "This component handles privacy-preserving sanctions screening",
+        )
+        .import("std::sys")
+        .blank();
+        
+        masm.export(
+            &Procedure {
+                name: "screen_sanctions",
+                doc: "Screen for sanctions matches",
+                inputs: &["identity_hash", "sanctions_list_hash", "screening_proof"],
+                outputs: &["sanctions_status", "confidence_score"],
+            },
+            |m| {
+                m.op("dup.2").store(SanctionsSlot::ScreeningHash);
+                m.op("sys.time_now").store(SanctionsSlot::LastScreening);
+                m.op("exec.verify_screening_proof").if_else(
+                    |m| {
+                        m.op_with_comment("dup.0 push.1000 u32mod", "Status carried by the proof")
+                            .store(SanctionsSlot::Status);
+                        m.op_with_comment("push.1", "High confidence");
+                    },
+                    |m| {
+                        m.op(format_args!("push.{}", ScreeningStatus::Flagged.code()))
+                            .store(SanctionsSlot::Status);
+                        m.op_with_comment("push.0", "Low confidence");
+                    },
+                );
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "get_sanctions_status",
+                doc: "Get sanctions screening status",
+                inputs: &[],
+                outputs: &["status", "last_screening", "confidence"],
+            },
+            |m| {
+                m.load(SanctionsSlot::Status)
+                    .load(SanctionsSlot::LastScreening)
+                    .load(SanctionsSlot::ScreeningHash);
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "update_sanctions_status",
+                doc: "Update sanctions status (manual override)",
+                inputs: &["new_status", "override_reason"],
+                outputs: &["success_flag"],
+            },
+            |m| {
+                m.op("dup.0").store(SanctionsSlot::Status);
+                m.op("push.1").store(SanctionsSlot::ManualOverride);
+                m.op("sys.time_now").store(SanctionsSlot::LastScreening);
+                m.op_with_comment("push.1", "Success");
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "verify_screening_proof",
+                doc: "Verify sanctions screening proof",
+                inputs: &["screening_proof"],
+                outputs: &["verification_result"],
+            },
+            |m| {
+                m.load(SanctionsSlot::ScreeningHash).op_with_comment("dup.1 eq", "Proof matches stored hash");
+                m.if_else(
+                    |m| {
+                        m.op("push.1");
+                    },
+                    |m| {
+                        m.op("push.0");
+                    },
+                );
+            },
+        );
+        
+        masm.export(
+            &Procedure {
+                name: "manual_override",
+                doc: "Manual override for sanctions status",
+                inputs: &["override_status", "authorization_hash"],
+                outputs: &["success_flag"],
+            },
+            |m| {
+                if self.override_keys.is_empty() {
+                    m.op_with_comment("dup.1 push.0 neq", "Authorization present");
+                } else {
+                    m.any_of(1, &self.override_keys);
+                }
+                m.if_else(
+                    |m| {
+                        m.op("dup.0").store(SanctionsSlot::Status);
+                        m.op("push.1").store(SanctionsSlot::ManualOverride);
+                        m.op_with_comment("push.1", "Success");
+                    },
+                    |m| {
+                        m.op_with_comment("push.0", "Unauthorized");
+                    },
+                );
+            },
+        );
+        
+        masm.finish()
+    }
+}
+
+/// Templates of every component for one deployment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentTemplates {
+    pub kyc: KycTemplate,
+    pub aml: AmlTemplate,
+    pub sanctions: SanctionsTemplate,
+}
+
+impl ComponentTemplates {
+    /// Templates with the deployment's configured parameters
+    pub fn from_config(config: &ComponentTemplateConfig) -> Self {
+        Self {
+            kyc: KycTemplate {
+                validity_secs: config.kyc_validity_secs,
+                authorized_verifiers: config.authorized_verifiers.clone(),
+            },
+            aml: AmlTemplate {
+                large_transaction_threshold: config.large_transaction_threshold,
+                medium_risk_score: config.medium_risk_score,
+                high_risk_score: config.high_risk_score,
+                ..AmlTemplate::default()
+            },
+            sanctions: SanctionsTemplate {
+                override_keys: config.sanctions_override_keys.clone(),
+            },
+        }
+    }
+    
+    /// Generate a component's assembly
+    pub fn render(&self, kind: ComponentKind) -> String {
+        match kind {
+            ComponentKind::Kyc => self.kyc.render(),
+            ComponentKind::Aml => self.aml.render(),
+            ComponentKind::Sanctions => self.sanctions.render(),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! Client watches on Miden accounts' on-chain compliance state
+//!
+//! Business clients register the Miden accounts they want to follow. After
+//! every sync with the node the storage of each watched account is compared
+//! with what was seen last, and slots whose values changed are recorded as
+//! an [`AttestationEvent::OnChainComplianceChanged`] event, with a webhook to
+//! every client watching the account. Changes the backend did not initiate,
+//! such as component updates submitted by the account owner, are reported
+//! the same way as its own.
+//!
+//! Only watched accounts are read after a sync. The first sync after an
+//! account is watched records its storage as the baseline without reporting
+//! anything.
+
+use super::account_components::foreign::miden_account_id;
+use super::attestation_events::{AttestationEvent, RecordedEvent};
+use super::ComplianceService;
+use crate::scheduler::Job;
+use crate::storage::{OutboxMessage, WriteBatch};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use miden_client::Client;
+use miden_objects::account::StorageSlot;
+use miden_objects::{Digest, Word, EMPTY_WORD};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Webhook event sent to watching clients when a watched account's storage changes
+pub const ONCHAIN_CHANGE_EVENT: &str = "onchain.compliance_changed";
+
+/// Name of the job syncing watched accounts
+pub const SYNC_JOB: &str = "account_watch_sync";
+
+/// A client's watch on a Miden account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountWatch {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub account_id: AccountId,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields accepted when watching an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountWatchInput {
+    pub account_id: AccountId,
+    pub label: Option<String>,
+}
+
+/// A storage slot of a watched account whose value changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotChange {
+    pub slot: u8,
+    /// Previous word, as hex
+    pub previous: String,
+    /// New word, as hex
+    pub current: String,
+}
+
+/// Service managing account watches and tracking watched accounts' storage
+pub struct AccountWatchService {
+    watches: RwLock<HashMap<Uuid, AccountWatch>>,
+    
+    /// Storage of each watched account as of the last sync
+    snapshots: RwLock<HashMap<AccountId, Vec<Word>>>,
+    
+    /// Distinct accounts that may be watched across all clients
+    max_accounts: usize,
+}
+
+impl AccountWatchService {
+    /// Create a service watching at most `max_accounts` distinct accounts
+    pub fn new(max_accounts: usize) -> Self {
+        Self {
+            watches: RwLock::new(HashMap::new()),
+            snapshots: RwLock::new(HashMap::new()),
+            max_accounts,
+        }
+    }
+    
+    /// Start watching an account for a client
+    pub async fn watch(&self, client_id: Uuid, input: AccountWatchInput) -> Result<AccountWatch> {
+        miden_account_id(&input.account_id)?;
+        let mut watches = self.watches.write().await;
+        if watches
+            .values()
+            .any(|watch| watch.client_id == client_id && watch.account_id == input.account_id)
+        {
+            return Err(ComplianceError::validation("account_id", "account is already watched"));
+        }
+        let accounts: BTreeSet<&AccountId> = watches.values().map(|watch| &watch.account_id).collect();
+        if !accounts.contains(&input.account_id) && accounts.len() >= self.max_accounts {
+            return Err(ComplianceError::validation(
+                "account_id",
+                format!("at most {} accounts can be watched", self.max_accounts),
+            ));
+        }
+        
+        let watch = AccountWatch {
+            id: Uuid::new_v4(),
+            client_id,
+            account_id: input.account_id,
+            label: input.label.map(|label| label.trim().to_string()).filter(|label| !label.is_empty()),
+            created_at: Utc::now(),
+        };
+        watches.insert(watch.id, watch.clone());
+        Ok(watch)
+    }
+    
+    /// Get one of a client's watches
+    pub async fn get(&self, client_id: Uuid, watch_id: Uuid) -> Result<AccountWatch> {
+        self.watches
+            .read()
+            .await
+            .get(&watch_id)
+            .filter(|watch| watch.client_id == client_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::AccountWatchNotFound {
+                watch_id: watch_id.to_string(),
+            })
+    }
+    
+    /// List a client's watches
+    pub async fn list(&self, client_id: Uuid) -> Vec<AccountWatch> {
+        let mut watches: Vec<AccountWatch> = self
+            .watches
+            .read()
+            .await
+            .values()
+            .filter(|watch| watch.client_id == client_id)
+            .cloned()
+            .collect();
+        watches.sort_by_key(|watch| watch.created_at);
+        watches
+    }
+    
+    /// Stop watching an account
+    ///
+    /// The account's storage stops being tracked once no client watches it.
+    pub async fn unwatch(&self, client_id: Uuid, watch_id: Uuid) -> Result<AccountWatch> {
+        let mut watches = self.watches.write().await;
+        let watch = match watches.get(&watch_id) {
+            Some(watch) if watch.client_id == client_id => watches.remove(&watch_id).expect("watch exists"),
+            _ => {
+                return Err(ComplianceError::AccountWatchNotFound {
+                    watch_id: watch_id.to_string(),
+                })
+            }
+        };
+        if !watches.values().any(|other| other.account_id == watch.account_id) {
+            self.snapshots.write().await.remove(&watch.account_id);
+        }
+        Ok(watch)
+    }
+    
+    /// Accounts watched by at least one client
+    pub async fn watched_accounts(&self) -> Vec<AccountId> {
+        let accounts: BTreeSet<AccountId> =
+            self.watches.read().await.values().map(|watch| watch.account_id.clone()).collect();
+        accounts.into_iter().collect()
+    }
+    
+    /// Clients watching an account
+    pub async fn watchers(&self, account_id: &AccountId) -> Vec<Uuid> {
+        let clients: BTreeSet<Uuid> = self
+            .watches
+            .read()
+            .await
+            .values()
+            .filter(|watch| watch.account_id == *account_id)
+            .map(|watch| watch.client_id)
+            .collect();
+        clients.into_iter().collect()
+    }
+    
+    /// Sync with the node and report changes to watched accounts' storage
+    ///
+    /// Accounts the client does not track yet are imported from the node, so
+    /// they must be public. An account that cannot be read is skipped until
+    /// the next sync. Returns the events recorded for changed accounts.
+    pub async fn sync(&self, compliance: &ComplianceService) -> Result<Vec<RecordedEvent>> {
+        let accounts = self.watched_accounts().await;
+        if accounts.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let (block_num, storage) = {
+            let mut client = compliance.miden_client.write().await;
+            let block_num = client.sync_state().await?.block_num.as_u32();
+            let mut storage = Vec::with_capacity(accounts.len());
+            for account_id in accounts {
+                match read_storage(&mut client, &account_id).await {
+                    Ok(slots) => storage.push((account_id, slots)),
+                    Err(e) => tracing::warn!(account_id = %account_id, error = %e, "failed to read watched account"),
+                }
+            }
+            (block_num, storage)
+        };
+        
+        let mut recorded = Vec::new();
+        for (account_id, slots) in storage {
+            let previous = self.snapshots.read().await.get(&account_id).cloned();
+            let changes = match &previous {
+                Some(previous) => slot_changes(previous, &slots),
+                None => Vec::new(),
+            };
+            if !changes.is_empty() {
+                recorded.push(self.record_changes(compliance, &account_id, block_num, changes).await?);
+            }
+            
+            // A watch removed during the sync must not leave a snapshot behind
+            if !self.watchers(&account_id).await.is_empty() {
+                self.snapshots.write().await.insert(account_id, slots);
+            }
+        }
+        Ok(recorded)
+    }
+    
+    /// Record a change event and notify the account's watchers in one batch
+    async fn record_changes(
+        &self,
+        compliance: &ComplianceService,
+        account_id: &AccountId,
+        block_num: u32,
+        changes: Vec<SlotChange>,
+    ) -> Result<RecordedEvent> {
+        let payload = serde_json::json!({
+            "account_id": account_id,
+            "block_num": block_num,
+            "changes": changes,
+        });
+        let mut batch = WriteBatch::default();
+        let mut events = compliance.events.stage().await;
+        let recorded = events
+            .push(
+                &mut batch,
+                account_id,
+                AttestationEvent::OnChainComplianceChanged { block_num, changes },
+            )
+            .await?;
+        for client_id in self.watchers(account_id).await {
+            batch.outbox.push(OutboxMessage::new(
+                client_id,
+                ONCHAIN_CHANGE_EVENT,
+                payload.clone(),
+                compliance.clock.now(),
+            ));
+        }
+        compliance.storage.commit(&batch).await?;
+        events.committed(&batch).await;
+        Ok(recorded)
+    }
+}
+
+/// Current storage of a watched account, importing it on first use
+async fn read_storage(client: &mut Client, account_id: &AccountId) -> Result<Vec<Word>> {
+    let miden_id = miden_account_id(account_id)?;
+    if client.get_account(miden_id).await?.is_none() {
+        client.import_account_by_id(miden_id).await?;
+    }
+    let record = client.get_account(miden_id).await?.ok_or_else(|| ComplianceError::AccountNotFound {
+        account_id: account_id.to_string(),
+    })?;
+    Ok(record.account().storage().slots().iter().map(StorageSlot::value).collect())
+}
+
+/// Slots whose values differ between two snapshots
+///
+/// Slots present in only one snapshot compare against the empty word.
+pub fn slot_changes(previous: &[Word], current: &[Word]) -> Vec<SlotChange> {
+    (0..previous.len().max(current.len()))
+        .filter_map(|index| {
+            let before = previous.get(index).copied().unwrap_or(EMPTY_WORD);
+            let after = current.get(index).copied().unwrap_or(EMPTY_WORD);
+            (before != after).then(|| SlotChange {
+                slot: index as u8,
+                previous: Digest::from(before).to_hex(),
+                current: Digest::from(after).to_hex(),
+            })
+        })
+        .collect()
+}
+
+/// Job syncing watched accounts
+pub fn sync_job(watches: Arc<AccountWatchService>, compliance: Arc<ComplianceService>, interval: Duration) -> Job {
+    Job::every(SYNC_JOB, interval, move || {
+        let (watches, compliance) = (watches.clone(), compliance.clone());
+        async move {
+            let recorded = watches.sync(&compliance).await?;
+            if !recorded.is_empty() {
+                tracing::info!(changed_accounts = recorded.len(), "watched accounts changed on-chain");
+            }
+            Ok(())
+        }
+    })
+}
@@ -0,0 +1,297 @@
+//! Detection of anomalous screening and verification outcomes per client
+//!
+//! Each client's outcomes are counted in fixed windows of `window_secs`: the
+//! share of KYC verifications rejected, the share of sanctions screenings
+//! that hit, and the latency of each provider's calls. Closed windows are
+//! folded into an exponentially weighted baseline per client and metric.
+//!
+//! Once a window holds `min_samples` outcomes, a value `z_threshold`
+//! standard errors above the baseline raises an alert naming the window and
+//! the accounts involved, so a wave of rejections from a fraud ring or a
+//! degrading provider is reported while it happens. Only rises are flagged,
+//! and flagged windows are kept out of the baseline so that a sustained wave
+//! does not become the new normal.
+
+use super::breaker::Provider;
+use crate::alerts::{Alert, AlertManager, AlertSeverity};
+use crate::clock::SharedClock;
+use crate::config::AnomalyDetectionConfig;
+use crate::reload::LiveConfig;
+use crate::types::AccountId;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Outcome tracked per client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Share of KYC verifications rejected
+    KycRejectionRate,
+    
+    /// Share of sanctions screenings with a hit
+    ScreeningHitRate,
+    
+    /// Latency of calls to a provider, in milliseconds
+    ProviderLatency(Provider),
+}
+
+impl Metric {
+    /// Metric name used in alerts
+    pub fn name(self) -> String {
+        match self {
+            Self::KycRejectionRate => "kyc_rejection_rate".to_string(),
+            Self::ScreeningHitRate => "screening_hit_rate".to_string(),
+            Self::ProviderLatency(provider) => format!("{}_latency", provider.name()),
+        }
+    }
+    
+    fn is_rate(self) -> bool {
+        !matches!(self, Self::ProviderLatency(_))
+    }
+    
+    fn describe(self, value: f64) -> String {
+        if self.is_rate() {
+            format!("{:.1}%", value * 100.0)
+        } else {
+            format!("{:.0} ms", value)
+        }
+    }
+}
+
+/// Outcomes of one window
+#[derive(Debug, Clone)]
+struct Window {
+    start: DateTime<Utc>,
+    samples: u64,
+    sum: f64,
+    sum_sq: f64,
+    
+    /// Accounts with outcomes above the baseline, such as rejections or slow calls
+    accounts: Vec<AccountId>,
+    
+    flagged: bool,
+}
+
+impl Window {
+    fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            samples: 0,
+            sum: 0.0,
+            sum_sq: 0.0,
+            accounts: Vec::new(),
+            flagged: false,
+        }
+    }
+    
+    fn mean(&self) -> f64 {
+        self.sum / self.samples.max(1) as f64
+    }
+    
+    fn variance(&self) -> f64 {
+        (self.sum_sq / self.samples.max(1) as f64 - self.mean().powi(2)).max(0.0)
+    }
+}
+
+/// Exponentially weighted per-sample mean and variance of past windows
+#[derive(Debug, Clone, Copy, Default)]
+struct Baseline {
+    windows: u32,
+    mean: f64,
+    variance: f64,
+}
+
+impl Baseline {
+    fn fold(&mut self, window: &Window, weight: f64) {
+        if self.windows == 0 {
+            self.mean = window.mean();
+            self.variance = window.variance();
+        } else {
+            self.mean += weight * (window.mean() - self.mean);
+            self.variance += weight * (window.variance() - self.variance);
+        }
+        self.windows += 1;
+    }
+    
+    /// Standard deviation of a single outcome, floored so that a spotless
+    /// baseline does not flag the first deviation from it
+    fn std_dev(&self, metric: Metric) -> f64 {
+        if metric.is_rate() {
+            let rate = self.mean.clamp(0.01, 0.99);
+            (rate * (1.0 - rate)).sqrt()
+        } else {
+            self.variance.sqrt().max(self.mean * 0.05).max(1.0)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Series {
+    window: Window,
+    baseline: Baseline,
+}
+
+/// Baseline of one client's metric, for inspection
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineStatus {
+    pub client_id: Uuid,
+    pub metric: String,
+    pub baseline: f64,
+    pub baseline_windows: u32,
+    pub window_start: DateTime<Utc>,
+    pub window_value: f64,
+    pub window_samples: u64,
+}
+
+/// Tracks outcome baselines per client and alerts on deviations
+pub struct AnomalyDetector {
+    /// Live configuration holding the detection thresholds
+    config: Arc<LiveConfig>,
+    
+    alerts: Arc<AlertManager>,
+    clock: SharedClock,
+    series: Mutex<HashMap<(Uuid, Metric), Series>>,
+}
+
+impl AnomalyDetector {
+    /// Create a detector without baselines
+    pub fn new(config: Arc<LiveConfig>, alerts: Arc<AlertManager>, clock: SharedClock) -> Self {
+        Self {
+            config,
+            alerts,
+            clock,
+            series: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Record whether a KYC verification for a client was rejected
+    pub async fn record_kyc(&self, client_id: Uuid, account_id: &AccountId, rejected: bool) {
+        self.record(client_id, Metric::KycRejectionRate, account_id, if rejected { 1.0 } else { 0.0 }).await;
+    }
+    
+    /// Record whether a sanctions screening for a client hit
+    pub async fn record_screening(&self, client_id: Uuid, account_id: &AccountId, hit: bool) {
+        self.record(client_id, Metric::ScreeningHitRate, account_id, if hit { 1.0 } else { 0.0 }).await;
+    }
+    
+    /// Record how long a provider call made for a client took
+    pub async fn record_latency(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        provider: Provider,
+        latency: std::time::Duration,
+    ) {
+        let millis = latency.as_secs_f64() * 1000.0;
+        self.record(client_id, Metric::ProviderLatency(provider), account_id, millis).await;
+    }
+    
+    /// Record one outcome, raising an alert if it makes its window anomalous
+    pub async fn record(&self, client_id: Uuid, metric: Metric, account_id: &AccountId, value: f64) {
+        let config = self.config.compliance();
+        let settings = &config.anomaly_detection;
+        if !settings.enabled {
+            return;
+        }
+        
+        let alert = self.observe(settings, client_id, metric, account_id, value);
+        if let Some(alert) = alert {
+            tracing::warn!(client_id = %client_id, metric = %metric.name(), "anomalous outcomes detected");
+            self.alerts.raise(alert).await;
+        }
+    }
+    
+    fn observe(
+        &self,
+        settings: &AnomalyDetectionConfig,
+        client_id: Uuid,
+        metric: Metric,
+        account_id: &AccountId,
+        value: f64,
+    ) -> Option<Alert> {
+        let now = self.clock.now();
+        let window_secs = settings.window_secs.max(1) as i64;
+        let window_start = DateTime::from_timestamp(now.timestamp().div_euclid(window_secs) * window_secs, 0)?;
+        let weight = 2.0 / (settings.baseline_windows.max(1) as f64 + 1.0);
+        
+        let mut all = self.series.lock().expect("anomaly detector lock poisoned");
+        let series = all.entry((client_id, metric)).or_insert_with(|| Series {
+            window: Window::new(window_start),
+            baseline: Baseline::default(),
+        });
+        if series.window.start < window_start {
+            let closed = std::mem::replace(&mut series.window, Window::new(window_start));
+            if !closed.flagged && closed.samples > 0 {
+                series.baseline.fold(&closed, weight);
+            }
+        }
+        
+        let window = &mut series.window;
+        let baseline = series.baseline;
+        window.samples += 1;
+        window.sum += value;
+        window.sum_sq += value * value;
+        if value > baseline.mean
+            && window.accounts.len() < settings.max_affected_accounts
+            && !window.accounts.contains(account_id)
+        {
+            window.accounts.push(account_id.clone());
+        }
+        
+        if window.flagged || window.samples < settings.min_samples || baseline.windows < settings.min_baseline_windows {
+            return None;
+        }
+        let standard_error = baseline.std_dev(metric) / (window.samples as f64).sqrt();
+        let z_score = (window.mean() - baseline.mean) / standard_error;
+        if z_score < settings.z_threshold {
+            return None;
+        }
+        window.flagged = true;
+        
+        let window_end = window.start + Duration::seconds(window_secs);
+        let description = format!(
+            "{} over {} outcomes since {}, against a baseline of {}",
+            metric.describe(window.mean()),
+            window.samples,
+            window.start.format("%Y-%m-%d %H:%M UTC"),
+            metric.describe(baseline.mean),
+        );
+        let details = serde_json::json!({
+            "client_id": client_id,
+            "metric": metric.name(),
+            "window_start": window.start,
+            "window_end": window_end,
+            "observed": window.mean(),
+            "baseline": baseline.mean,
+            "z_score": z_score,
+            "samples": window.samples,
+            "affected_accounts": window.accounts,
+        });
+        let mut alert = Alert::outcome_anomaly(client_id, &metric.name(), &description).with_details(details);
+        if z_score >= settings.z_threshold * 2.0 {
+            alert.severity = AlertSeverity::Critical;
+        }
+        Some(alert)
+    }
+    
+    /// Baselines and current windows of every client's metrics
+    pub fn baselines(&self) -> Vec<BaselineStatus> {
+        let series = self.series.lock().expect("anomaly detector lock poisoned");
+        let mut statuses: Vec<BaselineStatus> = series
+            .iter()
+            .map(|((client_id, metric), series)| BaselineStatus {
+                client_id: *client_id,
+                metric: metric.name(),
+                baseline: series.baseline.mean,
+                baseline_windows: series.baseline.windows,
+                window_start: series.window.start,
+                window_value: series.window.mean(),
+                window_samples: series.window.samples,
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.client_id.cmp(&b.client_id).then_with(|| a.metric.cmp(&b.metric)));
+        statuses
+    }
+}
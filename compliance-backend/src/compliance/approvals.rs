@@ -0,0 +1,347 @@
+//! Four-eyes approval of sensitive manual overrides
+//!
+//! An override is proposed by one operator and only takes effect once a
+//! different operator approves it. Sanctions clearances and risk downgrades
+//! carry a mandatory expiry, after which they are reverted and the account
+//! is re-screened.
+
+use super::ComplianceService;
+use crate::audit::AuditLog;
+use crate::scheduler::Job;
+use crate::storage::memory::MemoryCaseRepo;
+use crate::storage::CaseRepo;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Actor recorded for reverts performed by the expiry worker
+pub const EXPIRY_ACTOR: &str = "system:override-expiry";
+
+/// Name of the job reverting expired overrides
+pub const EXPIRY_JOB: &str = "override_expiry";
+
+/// A manual override requiring dual approval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OverrideAction {
+    /// Clear a sanctions hit judged to be a false positive
+    SanctionsClearance { account_id: AccountId },
+    
+    /// Reinstate a revoked attestation
+    RevocationReversal { account_id: AccountId },
+    
+    /// Lower an account's AML risk level
+    RiskDowngrade {
+        account_id: AccountId,
+        aml_risk_level: AmlRiskLevel,
+    },
+}
+
+impl OverrideAction {
+    /// Account the override applies to
+    pub fn account_id(&self) -> &AccountId {
+        match self {
+            Self::SanctionsClearance { account_id }
+            | Self::RevocationReversal { account_id }
+            | Self::RiskDowngrade { account_id, .. } => account_id,
+        }
+    }
+    
+    /// Check if the override must carry an expiry
+    pub fn is_time_bounded(&self) -> bool {
+        !matches!(self, Self::RevocationReversal { .. })
+    }
+    
+    /// Action name used in the audit log
+    pub fn audit_action(&self) -> &'static str {
+        match self {
+            Self::SanctionsClearance { .. } => "override.sanctions_clearance",
+            Self::RevocationReversal { .. } => "override.revocation_reversal",
+            Self::RiskDowngrade { .. } => "override.risk_downgrade",
+        }
+    }
+}
+
+/// Approval request status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    /// Approved, but applying the override failed
+    Failed,
+}
+
+/// A proposed override awaiting a second operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: Uuid,
+    pub action: OverrideAction,
+    pub justification: String,
+    pub proposed_by: String,
+    pub proposed_at: DateTime<Utc>,
+    pub status: ApprovalStatus,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decision_note: Option<String>,
+    
+    /// When an applied override lapses (time-bounded overrides only)
+    pub expires_at: Option<DateTime<Utc>>,
+    
+    /// Risk level in force before a risk downgrade, restored on expiry
+    pub prior_risk_level: Option<AmlRiskLevel>,
+    
+    /// When the expired override was reverted
+    pub reverted_at: Option<DateTime<Utc>>,
+}
+
+/// Service tracking override proposals and their approval
+pub struct ApprovalService {
+    repo: Arc<dyn CaseRepo>,
+    
+    /// Held while requests are read and written back, so checks and updates are atomic
+    updating: Mutex<()>,
+}
+
+impl Default for ApprovalService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApprovalService {
+    /// Create an empty approval service kept in memory
+    pub fn new() -> Self {
+        Self::with_repo(Arc::new(MemoryCaseRepo::default()))
+    }
+    
+    /// Create an approval service storing requests in `repo`
+    pub fn with_repo(repo: Arc<dyn CaseRepo>) -> Self {
+        Self {
+            repo,
+            updating: Mutex::new(()),
+        }
+    }
+    
+    /// Propose an override
+    ///
+    /// Only one pending proposal may exist per account and override type.
+    /// Time-bounded overrides must expire in the future and no later than
+    /// `max_duration` from now.
+    pub async fn propose(
+        &self,
+        action: OverrideAction,
+        justification: &str,
+        proposed_by: &str,
+        expires_at: Option<DateTime<Utc>>,
+        prior_risk_level: Option<AmlRiskLevel>,
+        max_duration: chrono::Duration,
+    ) -> Result<ApprovalRequest> {
+        if justification.trim().is_empty() {
+            return Err(ComplianceError::validation("justification", "must not be empty"));
+        }
+        
+        let now = Utc::now();
+        match (action.is_time_bounded(), expires_at) {
+            (true, None) => return Err(ComplianceError::validation("expires_at", "is required for this override")),
+            (true, Some(at)) if at <= now => return Err(ComplianceError::validation("expires_at", "must be in the future")),
+            (true, Some(at)) if at > now + max_duration => {
+                return Err(ComplianceError::validation(
+                    "expires_at",
+                    format!("must be within {} days", max_duration.num_days()),
+                ))
+            }
+            (false, Some(_)) => {
+                return Err(ComplianceError::validation("expires_at", "is not supported for this override"))
+            }
+            _ => {}
+        }
+        
+        let _updating = self.updating.lock().await;
+        if self.repo.list().await?.iter().any(|r| {
+            r.status == ApprovalStatus::Pending
+                && r.action.account_id() == action.account_id()
+                && r.action.audit_action() == action.audit_action()
+        }) {
+            return Err(ComplianceError::validation(
+                "action",
+                "an override of this type is already pending for the account",
+            ));
+        }
+        
+        let request = ApprovalRequest {
+            id: Uuid::new_v4(),
+            action,
+            justification: justification.to_string(),
+            proposed_by: proposed_by.to_string(),
+            proposed_at: now,
+            status: ApprovalStatus::Pending,
+            decided_by: None,
+            decided_at: None,
+            decision_note: None,
+            expires_at,
+            prior_risk_level,
+            reverted_at: None,
+        };
+        self.repo.save(&request).await?;
+        Ok(request)
+    }
+    
+    /// Get an approval request
+    pub async fn get(&self, approval_id: Uuid) -> Result<ApprovalRequest> {
+        self.repo
+            .get(approval_id)
+            .await?
+            .ok_or_else(|| ComplianceError::ApprovalNotFound {
+                approval_id: approval_id.to_string(),
+            })
+    }
+    
+    /// List requests, optionally filtered by status, oldest first
+    pub async fn list(&self, status: Option<ApprovalStatus>) -> Result<Vec<ApprovalRequest>> {
+        let mut requests = self.repo.list().await?;
+        requests.retain(|r| status.as_ref().map_or(true, |s| r.status == *s));
+        requests.sort_by_key(|r| r.proposed_at);
+        Ok(requests)
+    }
+    
+    /// Approve a pending request; the approver must differ from the proposer
+    pub async fn approve(&self, approval_id: Uuid, approver: &str, note: Option<String>) -> Result<ApprovalRequest> {
+        self.decide(approval_id, approver, note, ApprovalStatus::Approved).await
+    }
+    
+    /// Reject a pending request
+    pub async fn reject(&self, approval_id: Uuid, approver: &str, note: Option<String>) -> Result<ApprovalRequest> {
+        self.decide(approval_id, approver, note, ApprovalStatus::Rejected).await
+    }
+    
+    /// Record that applying an approved override failed
+    pub async fn mark_failed(&self, approval_id: Uuid, reason: &str) -> Result<()> {
+        let _updating = self.updating.lock().await;
+        let mut request = self.get(approval_id).await?;
+        request.status = ApprovalStatus::Failed;
+        request.decision_note = Some(reason.to_string());
+        self.repo.save(&request).await
+    }
+    
+    /// Approved overrides whose expiry has passed and that are not yet reverted
+    pub async fn due_for_revert(&self, now: DateTime<Utc>) -> Result<Vec<ApprovalRequest>> {
+        let mut requests = self.repo.list().await?;
+        requests.retain(|r| {
+            r.status == ApprovalStatus::Approved && r.reverted_at.is_none() && r.expires_at.is_some_and(|at| at <= now)
+        });
+        Ok(requests)
+    }
+    
+    /// Record that an expired override was reverted
+    pub async fn mark_reverted(&self, approval_id: Uuid) -> Result<()> {
+        let _updating = self.updating.lock().await;
+        let mut request = self.get(approval_id).await?;
+        request.reverted_at = Some(Utc::now());
+        self.repo.save(&request).await
+    }
+    
+    async fn decide(
+        &self,
+        approval_id: Uuid,
+        approver: &str,
+        note: Option<String>,
+        status: ApprovalStatus,
+    ) -> Result<ApprovalRequest> {
+        let _updating = self.updating.lock().await;
+        let mut request = self.get(approval_id).await?;
+        
+        if request.status != ApprovalStatus::Pending {
+            return Err(ComplianceError::validation(
+                "approval_id",
+                format!("request is already {:?}", request.status),
+            ));
+        }
+        if request.proposed_by == approver {
+            return Err(ComplianceError::CompliancePolicyViolation {
+                policy: "an override must be decided by a different operator than its proposer".to_string(),
+            });
+        }
+        
+        request.status = status;
+        request.decided_by = Some(approver.to_string());
+        request.decided_at = Some(Utc::now());
+        request.decision_note = note;
+        self.repo.save(&request).await?;
+        Ok(request)
+    }
+}
+
+/// Revert every expired override and re-screen the affected accounts
+///
+/// Returns the number of overrides reverted.
+pub async fn revert_expired(approvals: &ApprovalService, compliance: &ComplianceService, audit: &AuditLog) -> usize {
+    let due = match approvals.due_for_revert(Utc::now()).await {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load overrides due for revert");
+            return 0;
+        }
+    };
+    
+    for request in &due {
+        let account_id = request.action.account_id();
+        if let Err(e) = compliance.revert_override(request).await {
+            tracing::error!(approval_id = %request.id, account_id = %account_id, error = %e, "failed to revert expired override");
+            continue;
+        }
+        // An unmarked override is reverted again on the next pass, which is harmless
+        if let Err(e) = approvals.mark_reverted(request.id).await {
+            tracing::error!(approval_id = %request.id, error = %e, "failed to mark override reverted");
+        }
+        
+        // Re-screening replaces the reverted state with a fresh attestation when it succeeds
+        let rescreened = match compliance.update_compliance_status(account_id).await {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!(approval_id = %request.id, account_id = %account_id, error = %e, "re-screening after override expiry failed");
+                false
+            }
+        };
+        
+        audit
+            .record(
+                EXPIRY_ACTOR,
+                &format!("{}.expired", request.action.audit_action()),
+                Some(account_id),
+                serde_json::json!({
+                    "approval_id": request.id,
+                    "expires_at": request.expires_at,
+                    "rescreened": rescreened,
+                }),
+            )
+            .await;
+    }
+    
+    due.len()
+}
+
+/// Job periodically reverting expired overrides
+pub fn expiry_job(
+    approvals: Arc<ApprovalService>,
+    compliance: Arc<ComplianceService>,
+    audit: Arc<AuditLog>,
+    interval: Duration,
+) -> Job {
+    Job::every(EXPIRY_JOB, interval, move || {
+        let (approvals, compliance, audit) = (approvals.clone(), compliance.clone(), audit.clone());
+        async move {
+            let reverted = revert_expired(&approvals, &compliance, &audit).await;
+            if reverted > 0 {
+                tracing::info!(reverted, "expired overrides reverted");
+            }
+            Ok(())
+        }
+    })
+}
@@ -0,0 +1,339 @@
+//! Event-sourced attestation lifecycle with point-in-time projections
+
+use super::account_watch::SlotChange;
+use super::event_feed::EventFeed;
+use super::notarization::Notarization;
+use super::rejection::KycRejection;
+use crate::clock::{system_clock, SharedClock};
+use crate::crypto::ProofHash;
+use crate::storage::memory::MemoryAttestationRepo;
+use crate::storage::{AttestationRepo, WriteBatch};
+use crate::types::*;
+use crate::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// A change in an account's attestation lifecycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AttestationEvent {
+    /// A new attestation was issued, superseding any previous one
+    AttestationIssued { attestation: ComplianceAttestation },
+    
+    /// An attestation issued by another deployment was imported, superseding any previous one
+    Imported {
+        attestation: ComplianceAttestation,
+        bundle_id: Uuid,
+        source_deployment: String,
+    },
+    
+    /// AML risk was re-assessed without re-issuing the attestation
+    RiskUpdated { aml_risk_level: AmlRiskLevel },
+    
+    /// The attestation validity was extended
+    Renewed { expires_at: DateTime<Utc>, proof_hash: ProofHash },
+    
+    /// The attestation was revoked
+    Revoked { reason: String, revoked_by: Option<String> },
+    
+    /// A revocation was reversed
+    Reinstated { approved_by: Vec<String> },
+    
+    /// Sanctions screening outcome was manually overridden
+    SanctionsOverridden { sanctions_cleared: bool, approved_by: Vec<String> },
+    
+    /// A provider was unavailable and the existing attestation was kept in force
+    ProviderFallback { provider: String, reason: String },
+    
+    /// The KYC provider rejected the account's verification
+    KycRejected { rejection: KycRejection },
+    
+    /// Independent evidence of an attestation's issuance time was obtained
+    Notarized { attestation_id: Uuid, notarization: Notarization },
+    
+    /// The attestation reached its expiry
+    Expired,
+    
+    /// Values in the account's on-chain storage changed, as seen by a watch
+    OnChainComplianceChanged { block_num: u32, changes: Vec<SlotChange> },
+}
+
+/// A recorded event with its position in the account's stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Position in the account's event stream, starting at 1
+    pub sequence: u64,
+    pub account_id: AccountId,
+    pub recorded_at: DateTime<Utc>,
+    pub event: AttestationEvent,
+}
+
+/// Lifecycle status of a projected attestation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttestationStatus {
+    Active,
+    Revoked,
+    Expired,
+}
+
+/// Attestation state rebuilt from events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationState {
+    pub attestation: ComplianceAttestation,
+    pub status: AttestationStatus,
+    /// Sequence of the last applied event
+    pub version: u64,
+    pub updated_at: DateTime<Utc>,
+    pub revocation_reason: Option<String>,
+    /// Why KYC was last rejected; stands until an attestation with verified KYC is issued
+    #[serde(default)]
+    pub kyc_rejection: Option<KycRejection>,
+    /// Issuance timestamp evidence for the current attestation
+    #[serde(default)]
+    pub notarization: Option<Notarization>,
+}
+
+impl AttestationState {
+    /// Apply an event to the projection
+    ///
+    /// Events other than `AttestationIssued` and `Imported` are ignored until an attestation exists.
+    pub fn apply(state: Option<Self>, recorded: &RecordedEvent) -> Option<Self> {
+        let mut state = match (&recorded.event, state) {
+            (AttestationEvent::AttestationIssued { attestation }, previous)
+            | (AttestationEvent::Imported { attestation, .. }, previous) => {
+                return Some(Self {
+                    attestation: attestation.clone(),
+                    status: AttestationStatus::Active,
+                    version: recorded.sequence,
+                    updated_at: recorded.recorded_at,
+                    revocation_reason: None,
+                    kyc_rejection: previous
+                        .and_then(|state| state.kyc_rejection)
+                        .filter(|_| attestation.kyc_status != KycStatus::Verified),
+                    notarization: None,
+                });
+            }
+            (_, None) => return None,
+            (_, Some(state)) => state,
+        };
+        
+        match &recorded.event {
+            AttestationEvent::AttestationIssued { .. } | AttestationEvent::Imported { .. } => {
+                unreachable!("handled above")
+            }
+            AttestationEvent::RiskUpdated { aml_risk_level } => {
+                state.attestation.aml_risk_level = aml_risk_level.clone();
+            }
+            AttestationEvent::Renewed { expires_at, proof_hash } => {
+                state.attestation.expires_at = *expires_at;
+                state.attestation.proof_hash = *proof_hash;
+                if state.status == AttestationStatus::Expired {
+                    state.status = AttestationStatus::Active;
+                }
+            }
+            AttestationEvent::Revoked { reason, .. } => {
+                state.status = AttestationStatus::Revoked;
+                state.revocation_reason = Some(reason.clone());
+            }
+            AttestationEvent::Reinstated { .. } => {
+                if state.status == AttestationStatus::Revoked {
+                    state.status = AttestationStatus::Active;
+                    state.revocation_reason = None;
+                }
+            }
+            AttestationEvent::SanctionsOverridden { sanctions_cleared, .. } => {
+                state.attestation.sanctions_cleared = *sanctions_cleared;
+            }
+            AttestationEvent::ProviderFallback { .. } | AttestationEvent::OnChainComplianceChanged { .. } => {}
+            AttestationEvent::KycRejected { rejection } => {
+                state.kyc_rejection = Some(rejection.clone());
+            }
+            AttestationEvent::Notarized { attestation_id, notarization } => {
+                // Evidence for a superseded attestation does not carry over
+                if *attestation_id == state.attestation.id {
+                    state.notarization = Some(notarization.clone());
+                }
+            }
+            AttestationEvent::Expired => {
+                if state.status == AttestationStatus::Active {
+                    state.status = AttestationStatus::Expired;
+                }
+            }
+        }
+        
+        state.version = recorded.sequence;
+        state.updated_at = recorded.recorded_at;
+        Some(state)
+    }
+    
+    /// Check if the attestation is usable at the given time
+    pub fn is_valid_at(&self, at: DateTime<Utc>) -> bool {
+        self.status == AttestationStatus::Active && self.attestation.expires_at > at
+    }
+}
+
+/// Append-only store of attestation events per account
+pub struct AttestationEventStore {
+    repo: Arc<dyn AttestationRepo>,
+    
+    /// Held while appending, so sequences are assigned and published in order
+    appending: Mutex<()>,
+    
+    feed: EventFeed,
+    clock: SharedClock,
+}
+
+impl Default for AttestationEventStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AttestationEventStore {
+    /// Create an empty event store kept in memory
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+    
+    /// Create an empty event store kept in memory, timestamping events with `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
+        Self::with_repo(Arc::new(MemoryAttestationRepo::default()), clock)
+    }
+    
+    /// Create an event store persisting to `repo`, timestamping events with `clock`
+    pub fn with_repo(repo: Arc<dyn AttestationRepo>, clock: SharedClock) -> Self {
+        Self {
+            repo,
+            appending: Mutex::new(()),
+            feed: EventFeed::default(),
+            clock,
+        }
+    }
+    
+    /// Append an event to an account's stream
+    pub async fn append(&self, account_id: &AccountId, event: AttestationEvent) -> Result<RecordedEvent> {
+        let _appending = self.appending.lock().await;
+        let recorded = RecordedEvent {
+            sequence: self.repo.last_sequence(account_id).await? + 1,
+            account_id: account_id.clone(),
+            recorded_at: self.clock.now(),
+            event,
+        };
+        self.repo.append(&recorded).await?;
+        
+        // Published under the append lock so feed order matches append order
+        self.feed.publish(recorded.clone()).await;
+        Ok(recorded)
+    }
+    
+    /// Hold the append lock to stage events for a [`WriteBatch`]
+    ///
+    /// Other appends wait until the staged events are committed or dropped.
+    pub async fn stage(&self) -> StagedEvents<'_> {
+        StagedEvents {
+            store: self,
+            _appending: self.appending.lock().await,
+            next: HashMap::new(),
+        }
+    }
+    
+    /// Deployment-wide feed of appended events
+    pub fn feed(&self) -> &EventFeed {
+        &self.feed
+    }
+    
+    /// Get an account's events, optionally only those recorded at or before `as_of`
+    pub async fn events(&self, account_id: &AccountId, as_of: Option<DateTime<Utc>>) -> Result<Vec<RecordedEvent>> {
+        let mut stream = self.repo.stream(account_id).await?;
+        if let Some(at) = as_of {
+            let recorded = stream.iter().take_while(|e| e.recorded_at <= at).count();
+            stream.truncate(recorded);
+        }
+        Ok(stream)
+    }
+    
+    /// Accounts with at least one recorded event
+    pub async fn accounts(&self) -> Result<Vec<AccountId>> {
+        self.repo.accounts().await
+    }
+    
+    /// Rebuild an account's attestation state, as of a point in time when given
+    pub async fn project(
+        &self,
+        account_id: &AccountId,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Option<AttestationState>> {
+        Ok(self.events(account_id, as_of).await?.iter().fold(None, AttestationState::apply))
+    }
+    
+    /// Current attestation state of every account holding one
+    pub async fn current_states(&self) -> Result<Vec<(AccountId, AttestationState)>> {
+        let mut states = Vec::new();
+        for account_id in self.accounts().await? {
+            if let Some(state) = self.project(&account_id, None).await? {
+                states.push((account_id, state));
+            }
+        }
+        Ok(states)
+    }
+    
+    /// Append `Expired` events for active attestations past their expiry
+    ///
+    /// Returns the accounts that were expired.
+    pub async fn expire_due(&self, now: DateTime<Utc>) -> Result<Vec<AccountId>> {
+        let mut expired = Vec::new();
+        for (account_id, state) in self.current_states().await? {
+            if state.status == AttestationStatus::Active && state.attestation.expires_at <= now {
+                self.append(&account_id, AttestationEvent::Expired).await?;
+                expired.push(account_id);
+            }
+        }
+        Ok(expired)
+    }
+}
+
+/// Events staged for a batch, holding the store's append lock until committed or dropped
+pub struct StagedEvents<'a> {
+    store: &'a AttestationEventStore,
+    _appending: MutexGuard<'a, ()>,
+    
+    /// Sequence of the next event staged per account
+    next: HashMap<AccountId, u64>,
+}
+
+impl StagedEvents<'_> {
+    /// Add an event to `batch`, after any already staged for the account
+    pub async fn push(
+        &mut self,
+        batch: &mut WriteBatch,
+        account_id: &AccountId,
+        event: AttestationEvent,
+    ) -> Result<RecordedEvent> {
+        let sequence = match self.next.get(account_id) {
+            Some(sequence) => *sequence,
+            None => self.store.repo.last_sequence(account_id).await? + 1,
+        };
+        self.next.insert(account_id.clone(), sequence + 1);
+        
+        let recorded = RecordedEvent {
+            sequence,
+            account_id: account_id.clone(),
+            recorded_at: self.store.clock.now(),
+            event,
+        };
+        batch.events.push(recorded.clone());
+        Ok(recorded)
+    }
+    
+    /// Publish the events of `batch` once it is committed
+    pub async fn committed(self, batch: &WriteBatch) {
+        for recorded in &batch.events {
+            self.store.feed.publish(recorded.clone()).await;
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Sparse Merkle registry of valid attestations
+//!
+//! Issuance proofs show that an attestation was signed, not that it still
+//! stands. The registry maps a commitment to each account id to the
+//! commitment of the account's valid attestation in a sparse Merkle tree.
+//! Revoked and expired attestations are left out, so an opening of the tree
+//! proves either that an account is attested (its leaf holds the
+//! attestation commitment) or that it is not (its leaf is empty). Roots are
+//! signed and published periodically; verifiers check an opening against a
+//! root they trust.
+
+use super::attestation_events::AttestationStatus;
+use super::ComplianceService;
+use crate::audit::AuditLog;
+use crate::crypto::{attestation_commitment, AttestationSigner, CommitmentDomain, FieldEncoder};
+use crate::reload::LiveConfig;
+use crate::scheduler::Job;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use miden_objects::crypto::merkle::{Smt, SmtProof};
+use miden_objects::utils::{Deserializable, Serializable};
+use miden_objects::{Digest, EMPTY_WORD};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Actor recorded for roots published by the background worker
+pub const REGISTRY_ACTOR: &str = "system:registry";
+
+/// Name of the job publishing the registry
+pub const PUBLISH_JOB: &str = "registry_publish";
+
+/// Domain separator of signed registry roots
+const SIGNATURE_DOMAIN: &[u8] = b"zerotrust-compliance-registry-v1";
+
+/// Key of an account in the registry
+///
+/// The canonical commitment to the account id in the registry key domain.
+pub fn registry_key(account_id: &AccountId) -> Digest {
+    let mut encoder = FieldEncoder::new(CommitmentDomain::RegistryKey);
+    encoder.text(account_id.as_str());
+    encoder.commit().digest()
+}
+
+/// Message signed for a published root
+fn signed_message(epoch: u64, root: &Digest, attested: usize, published_at: DateTime<Utc>) -> Vec<u8> {
+    let mut message = SIGNATURE_DOMAIN.to_vec();
+    message.extend_from_slice(&epoch.to_be_bytes());
+    message.extend_from_slice(&root.as_bytes());
+    message.extend_from_slice(&(attested as u64).to_be_bytes());
+    message.extend_from_slice(&published_at.timestamp().to_be_bytes());
+    message
+}
+
+/// A published root of the registry
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryRoot {
+    pub epoch: u64,
+    /// Hex-encoded sparse Merkle root
+    pub root: String,
+    /// Accounts with a valid attestation
+    pub attested: usize,
+    pub published_at: DateTime<Utc>,
+    /// Key that signed the root
+    pub key_id: String,
+    /// Hex-encoded Ed25519 signature over the domain separator, epoch, root,
+    /// attested count and publication time
+    pub signature: String,
+}
+
+/// What an opening proves about an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Membership {
+    /// The account held a valid attestation when the root was published
+    Attested,
+    /// The account held no valid attestation when the root was published
+    NotAttested,
+}
+
+/// Opening of the registry for one account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryProof {
+    pub account_id: AccountId,
+    pub epoch: u64,
+    pub root: String,
+    pub membership: Membership,
+    /// Hex-encoded registry key of the account
+    pub key: String,
+    /// Hex-encoded attestation commitment, for attested accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_commitment: Option<String>,
+    /// Why the account is not attested, when the backend knows of an attestation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_status: Option<AttestationStatus>,
+    /// Hex-encoded sparse Merkle opening
+    pub proof: String,
+}
+
+impl RegistryProof {
+    /// Check that the opening proves the claimed membership under `root`
+    pub fn verify(&self) -> Result<()> {
+        let root = Digest::try_from(self.root.as_str()).map_err(|e| invalid(format!("invalid root: {}", e)))?;
+        let bytes = hex::decode(&self.proof).map_err(|e| invalid(format!("invalid proof encoding: {}", e)))?;
+        let opening = SmtProof::read_from_bytes(&bytes).map_err(|e| invalid(format!("invalid opening: {}", e)))?;
+        let key = registry_key(&self.account_id);
+        
+        let value = match (self.membership, &self.attestation_commitment) {
+            (Membership::Attested, Some(commitment)) => Digest::try_from(commitment.as_str())
+                .map_err(|e| invalid(format!("invalid attestation commitment: {}", e)))?
+                .into(),
+            (Membership::Attested, None) => return Err(invalid("attested proof carries no attestation commitment")),
+            (Membership::NotAttested, _) => EMPTY_WORD,
+        };
+        if !opening.verify_membership(&key, &value, &root) {
+            return Err(invalid("opening does not match the root"));
+        }
+        Ok(())
+    }
+}
+
+/// The latest root with the tree openings are generated from
+struct Snapshot {
+    root: RegistryRoot,
+    tree: Smt,
+}
+
+/// Published roots of the attestation registry
+#[derive(Default)]
+pub struct AttestationRegistry {
+    latest: RwLock<Option<Snapshot>>,
+    history: RwLock<Vec<RegistryRoot>>,
+    /// Serializes publications so epochs are assigned in order
+    publishing: Mutex<()>,
+}
+
+impl AttestationRegistry {
+    /// Create a registry with nothing published
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Latest published root
+    pub async fn latest(&self) -> Result<RegistryRoot> {
+        self.latest
+            .read()
+            .await
+            .as_ref()
+            .map(|snapshot| snapshot.root.clone())
+            .ok_or(ComplianceError::RegistryNotPublished)
+    }
+    
+    /// Every root published since startup, newest first
+    pub async fn history(&self) -> Vec<RegistryRoot> {
+        self.history.read().await.iter().rev().cloned().collect()
+    }
+    
+    /// Membership or non-membership proof for an account under the latest root
+    ///
+    /// Accounts the backend has never seen get a non-membership proof too.
+    pub async fn proof(&self, compliance: &ComplianceService, account_id: &AccountId) -> Result<RegistryProof> {
+        let latest = self.latest.read().await;
+        let snapshot = latest.as_ref().ok_or(ComplianceError::RegistryNotPublished)?;
+        let key = registry_key(account_id);
+        let value = snapshot.tree.get_value(&key);
+        let opening = snapshot.tree.open(&key);
+        
+        let (membership, commitment, status) = if value == EMPTY_WORD {
+            let status = compliance
+                .events
+                .project(account_id, Some(snapshot.root.published_at))
+                .await?
+                .map(|state| match state.status {
+                    AttestationStatus::Active => AttestationStatus::Expired,
+                    status => status,
+                });
+            (Membership::NotAttested, None, status)
+        } else {
+            (Membership::Attested, Some(Digest::from(value).to_hex()), Some(AttestationStatus::Active))
+        };
+        
+        Ok(RegistryProof {
+            account_id: account_id.clone(),
+            epoch: snapshot.root.epoch,
+            root: snapshot.root.root.clone(),
+            membership,
+            key: key.to_hex(),
+            attestation_commitment: commitment,
+            attestation_status: status,
+            proof: hex::encode(opening.to_bytes()),
+        })
+    }
+    
+    /// Commit to the attestations valid at `now` and sign the root
+    pub async fn publish(
+        &self,
+        compliance: &ComplianceService,
+        signer: &AttestationSigner,
+        now: DateTime<Utc>,
+    ) -> Result<RegistryRoot> {
+        let _publishing = self.publishing.lock().await;
+        let epoch = self.latest.read().await.as_ref().map_or(1, |snapshot| snapshot.root.epoch + 1);
+        
+        let mut entries = Vec::new();
+        for (account_id, state) in compliance.events.current_states().await? {
+            if state.is_valid_at(now) {
+                entries.push((registry_key(&account_id), attestation_commitment(&state.attestation).to_word()));
+            }
+        }
+        let attested = entries.len();
+        let tree = Smt::with_entries(entries).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let root = tree.root();
+        
+        let signature = signer.sign(&signed_message(epoch, &root, attested, now))?;
+        let published = RegistryRoot {
+            epoch,
+            root: root.to_hex(),
+            attested,
+            published_at: now,
+            key_id: signer.key_id().to_string(),
+            signature: hex::encode(signature),
+        };
+        
+        *self.latest.write().await = Some(Snapshot {
+            root: published.clone(),
+            tree,
+        });
+        self.history.write().await.push(published.clone());
+        Ok(published)
+    }
+}
+
+/// Job periodically publishing the registry
+///
+/// Whether publishing is enabled is read from the live configuration on
+/// every run.
+pub fn publish_job(
+    registry: Arc<AttestationRegistry>,
+    compliance: Arc<ComplianceService>,
+    signer: Arc<AttestationSigner>,
+    audit: Arc<AuditLog>,
+    config: Arc<LiveConfig>,
+    interval: std::time::Duration,
+) -> Job {
+    Job::every(PUBLISH_JOB, interval, move || {
+        let (registry, compliance, signer) = (registry.clone(), compliance.clone(), signer.clone());
+        let (audit, config) = (audit.clone(), config.clone());
+        async move {
+            if !config.compliance().attestation.registry.enabled {
+                return Ok(());
+            }
+            
+            let root = registry.publish(&compliance, &signer, compliance.clock.now()).await?;
+            audit
+                .record(
+                    REGISTRY_ACTOR,
+                    "registry.root_published",
+                    None,
+                    serde_json::json!({
+                        "epoch": root.epoch,
+                        "root": root.root,
+                        "attested": root.attested,
+                    }),
+                )
+                .await;
+            tracing::info!(epoch = root.epoch, attested = root.attested, "attestation registry root published");
+            Ok(())
+        }
+    })
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::validation("proof", reason)
+}
@@ -0,0 +1,385 @@
+//! Circuit breakers and fallback policies for external compliance providers
+
+use super::{provider_credentials, ComplianceService};
+use crate::config::{BreakerConfig, CheckCriticality, FallbackPolicy, ProviderResilienceConfig};
+use crate::scheduler::Job;
+use crate::types::{AccountId, DataRegion};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Name of the job re-running queued compliance checks
+pub const RETRY_JOB: &str = "provider_retry";
+
+/// External provider guarded by a breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    Kyc,
+    Aml,
+    Sanctions,
+    AdverseMedia,
+    ChainAnalytics,
+}
+
+impl Provider {
+    /// Provider name used in errors and metrics
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Kyc => "kyc",
+            Self::Aml => "aml",
+            Self::Sanctions => "sanctions",
+            Self::AdverseMedia => "adverse_media",
+            Self::ChainAnalytics => "chain_analytics",
+        }
+    }
+}
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls flow normally
+    Closed,
+    /// Calls are rejected without reaching the provider
+    Open,
+    /// A limited number of probe calls test whether the provider recovered
+    HalfOpen,
+}
+
+/// Point-in-time view of a breaker for health and metrics endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerStatus {
+    pub provider: Provider,
+    pub state: BreakerState,
+    pub fallback: FallbackPolicy,
+    pub consecutive_failures: u32,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub total_calls: u64,
+    pub total_failures: u64,
+    /// Calls rejected while the breaker was open
+    pub rejected_calls: u64,
+}
+
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<(Instant, DateTime<Utc>)>,
+    probes_in_flight: u32,
+    total_calls: u64,
+    total_failures: u64,
+    rejected_calls: u64,
+}
+
+/// Circuit breaker around a single provider
+pub struct CircuitBreaker {
+    provider: Provider,
+    config: BreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker
+    pub fn new(provider: Provider, config: BreakerConfig) -> Self {
+        Self {
+            provider,
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probes_in_flight: 0,
+                total_calls: 0,
+                total_failures: 0,
+                rejected_calls: 0,
+            }),
+        }
+    }
+    
+    /// Fallback policy applied when this provider is unavailable
+    pub fn fallback(&self) -> FallbackPolicy {
+        self.config.fallback
+    }
+    
+    /// Whether this provider's check must succeed for a compliance check to pass
+    pub fn criticality(&self) -> CheckCriticality {
+        self.config.criticality
+    }
+    
+    /// Run a provider call through the breaker
+    ///
+    /// Calls are rejected while the breaker is open. Timeouts and transport
+    /// errors count as failures; other errors are treated as provider answers.
+    pub async fn call<T>(&self, call: impl Future<Output = Result<T>>) -> Result<T> {
+        let admission = self.acquire()?;
+        
+        let timeout = Duration::from_secs(self.config.call_timeout_secs);
+        let result = match tokio::time::timeout(timeout, call).await {
+            Ok(result) => result,
+            Err(_) => Err(self.unavailable(format!("call timed out after {}s", self.config.call_timeout_secs))),
+        };
+        
+        let failed = result.as_ref().err().is_some_and(is_provider_failure);
+        admission.record(!failed);
+        result
+    }
+    
+    /// Check if the breaker currently lets calls through
+    pub fn is_closed(&self) -> bool {
+        self.lock().state == BreakerState::Closed
+    }
+    
+    /// Current breaker status
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.lock();
+        BreakerStatus {
+            provider: self.provider,
+            state: inner.state,
+            fallback: self.config.fallback,
+            consecutive_failures: inner.consecutive_failures,
+            opened_at: inner.opened_at.map(|(_, at)| at),
+            total_calls: inner.total_calls,
+            total_failures: inner.total_failures,
+            rejected_calls: inner.rejected_calls,
+        }
+    }
+    
+    fn acquire(&self) -> Result<Admission<'_>> {
+        let mut inner = self.lock();
+        if inner.state == BreakerState::Open {
+            let cooled_down = inner
+                .opened_at
+                .is_some_and(|(at, _)| at.elapsed() >= Duration::from_secs(self.config.open_duration_secs));
+            if !cooled_down {
+                inner.rejected_calls += 1;
+                return Err(self.unavailable("circuit open".to_string()));
+            }
+            inner.state = BreakerState::HalfOpen;
+            inner.probes_in_flight = 0;
+        }
+        if inner.state == BreakerState::HalfOpen {
+            if inner.probes_in_flight >= self.config.half_open_max_probes {
+                inner.rejected_calls += 1;
+                return Err(self.unavailable("circuit half-open, probe in progress".to_string()));
+            }
+            inner.probes_in_flight += 1;
+        }
+        inner.total_calls += 1;
+        Ok(Admission {
+            breaker: self,
+            probe: inner.state == BreakerState::HalfOpen,
+            recorded: false,
+        })
+    }
+    
+    fn record(&self, success: bool) {
+        let mut inner = self.lock();
+        let probing = inner.state == BreakerState::HalfOpen;
+        if probing {
+            inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+        }
+        
+        if success {
+            inner.consecutive_failures = 0;
+            if probing {
+                inner.state = BreakerState::Closed;
+                inner.opened_at = None;
+                tracing::info!(provider = self.provider.name(), "circuit closed");
+            }
+            return;
+        }
+        
+        inner.total_failures += 1;
+        inner.consecutive_failures += 1;
+        if probing || inner.consecutive_failures >= self.config.failure_threshold {
+            if inner.state != BreakerState::Open {
+                tracing::warn!(
+                    provider = self.provider.name(),
+                    consecutive_failures = inner.consecutive_failures,
+                    "circuit opened"
+                );
+            }
+            inner.state = BreakerState::Open;
+            inner.opened_at = Some((Instant::now(), Utc::now()));
+        }
+    }
+    
+    fn unavailable(&self, reason: String) -> ComplianceError {
+        ComplianceError::ProviderUnavailable {
+            provider: self.provider.name().to_string(),
+            reason,
+        }
+    }
+    
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner.lock().expect("breaker lock poisoned")
+    }
+}
+
+/// A call let through by a breaker
+///
+/// A probe dropped before its outcome is recorded, as when a sibling check in
+/// a `try_join!` fails first, gives its half-open slot back so the breaker
+/// does not stay half-open with no probe running.
+struct Admission<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl Admission<'_> {
+    fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.breaker.record(success);
+    }
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            let mut inner = self.breaker.lock();
+            if inner.state == BreakerState::HalfOpen {
+                inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Errors that indicate the provider itself is failing
+pub(crate) fn is_provider_failure(error: &ComplianceError) -> bool {
+    matches!(
+        error,
+        ComplianceError::Http(_) | ComplianceError::Io(_) | ComplianceError::ProviderUnavailable { .. }
+    )
+}
+
+/// An account waiting for a provider to recover
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedRetry {
+    pub account_id: AccountId,
+    pub provider: Provider,
+    
+    /// Client the check ran on behalf of, and its data residency region
+    pub client: Option<(Uuid, Option<DataRegion>)>,
+}
+
+/// Breakers for every external provider plus the retry queue
+pub struct ProviderBreakers {
+    pub kyc: CircuitBreaker,
+    pub aml: CircuitBreaker,
+    pub sanctions: CircuitBreaker,
+    pub adverse_media: CircuitBreaker,
+    pub chain_analytics: CircuitBreaker,
+    /// Accounts whose checks are waiting for a provider to recover
+    retry_queue: Mutex<VecDeque<QueuedRetry>>,
+}
+
+impl ProviderBreakers {
+    /// Create breakers from configuration
+    pub fn new(config: &ProviderResilienceConfig) -> Self {
+        Self {
+            kyc: CircuitBreaker::new(Provider::Kyc, config.kyc.clone()),
+            aml: CircuitBreaker::new(Provider::Aml, config.aml.clone()),
+            sanctions: CircuitBreaker::new(Provider::Sanctions, config.sanctions.clone()),
+            adverse_media: CircuitBreaker::new(Provider::AdverseMedia, config.adverse_media.clone()),
+            chain_analytics: CircuitBreaker::new(Provider::ChainAnalytics, config.chain_analytics.clone()),
+            retry_queue: Mutex::new(VecDeque::new()),
+        }
+    }
+    
+    /// Get the breaker for a provider
+    pub fn get(&self, provider: Provider) -> &CircuitBreaker {
+        match provider {
+            Provider::Kyc => &self.kyc,
+            Provider::Aml => &self.aml,
+            Provider::Sanctions => &self.sanctions,
+            Provider::AdverseMedia => &self.adverse_media,
+            Provider::ChainAnalytics => &self.chain_analytics,
+        }
+    }
+    
+    /// Identify the provider an unavailability error came from
+    pub fn provider_of(error: &ComplianceError) -> Option<Provider> {
+        let ComplianceError::ProviderUnavailable { provider, .. } = error else {
+            return None;
+        };
+        [
+            Provider::Kyc,
+            Provider::Aml,
+            Provider::Sanctions,
+            Provider::AdverseMedia,
+            Provider::ChainAnalytics,
+        ]
+        .into_iter()
+        .find(|p| p.name() == provider)
+    }
+    
+    /// Status of every breaker
+    pub fn statuses(&self) -> Vec<BreakerStatus> {
+        [&self.kyc, &self.aml, &self.sanctions, &self.adverse_media, &self.chain_analytics]
+            .into_iter()
+            .map(CircuitBreaker::status)
+            .collect()
+    }
+    
+    /// Queue an account for re-checking once a provider recovers
+    ///
+    /// The retry runs on behalf of the client the current task runs for. An
+    /// account waiting on several providers is queued once for each, so a
+    /// provider that recovers first is not held up behind another.
+    pub fn queue_retry(&self, account_id: &AccountId, provider: Provider) {
+        let mut queue = self.retry_queue.lock().expect("retry queue lock poisoned");
+        if !queue.iter().any(|queued| queued.account_id == *account_id && queued.provider == provider) {
+            queue.push_back(QueuedRetry {
+                account_id: account_id.clone(),
+                provider,
+                client: provider_credentials::current_client()
+                    .map(|client_id| (client_id, provider_credentials::current_region())),
+            });
+        }
+    }
+    
+    /// Take queued accounts whose provider breaker has closed again
+    pub fn take_ready_retries(&self) -> Vec<QueuedRetry> {
+        let mut queue = self.retry_queue.lock().expect("retry queue lock poisoned");
+        let (ready, waiting): (VecDeque<_>, VecDeque<_>) =
+            queue.drain(..).partition(|queued| self.get(queued.provider).is_closed());
+        *queue = waiting;
+        ready.into()
+    }
+    
+    /// Number of retries waiting for their provider
+    pub fn retry_queue_depth(&self) -> usize {
+        self.retry_queue.lock().expect("retry queue lock poisoned").len()
+    }
+}
+
+/// Job re-running queued compliance checks once their provider recovers
+///
+/// Each retry runs on behalf of the client the original check ran for.
+pub fn retry_job(compliance: Arc<ComplianceService>, interval: Duration) -> Job {
+    Job::every(RETRY_JOB, interval, move || {
+        let compliance = compliance.clone();
+        async move {
+            for retry in compliance.breakers.take_ready_retries() {
+                let account_id = &retry.account_id;
+                let result = match retry.client {
+                    Some((client_id, region)) => {
+                        provider_credentials::with_client(client_id, region, compliance.retry_checks(account_id)).await
+                    }
+                    None => compliance.retry_checks(account_id).await,
+                };
+                match result {
+                    Ok(_) => tracing::info!(account_id = %account_id, "queued compliance check completed"),
+                    Err(e) => tracing::warn!(account_id = %account_id, error = %e, "queued compliance check failed"),
+                }
+            }
+            Ok(())
+        }
+    })
+}
@@ -0,0 +1,95 @@
+//! Chainalysis address screening provider
+
+use super::{label_score, provider_error, AddressRisk, CategoryExposure, ChainAnalyticsProvider, ExposureCategory};
+use crate::Result;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+
+/// Scores addresses with the Chainalysis address screening API
+pub struct ChainalysisProvider {
+    http: reqwest::Client,
+    api_url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EntityResponse {
+    risk: Option<String>,
+    #[serde(default)]
+    exposures: Vec<Exposure>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Exposure {
+    category: String,
+    value: f64,
+    exposure_type: String,
+}
+
+impl ChainalysisProvider {
+    /// Create a Chainalysis provider
+    pub fn new(http: reqwest::Client, api_url: String, api_key: String) -> Self {
+        Self { http, api_url, api_key }
+    }
+    
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/risk/v2/entities{}", self.api_url.trim_end_matches('/'), path)
+    }
+}
+
+impl ChainAnalyticsProvider for ChainalysisProvider {
+    fn name(&self) -> &'static str {
+        "chainalysis"
+    }
+    
+    /// Registers the address, then fetches its risk assessment
+    fn score_address<'a>(&'a self, chain: &'a str, address: &'a str) -> BoxFuture<'a, Result<AddressRisk>> {
+        Box::pin(async move {
+            let response = self
+                .http
+                .post(self.url(""))
+                .header("Token", &self.api_key)
+                .json(&serde_json::json!({ "address": address }))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(provider_error(self.name(), format!("register returned {}", response.status())));
+            }
+            
+            let response = self
+                .http
+                .get(self.url(&format!("/{}", address)))
+                .header("Token", &self.api_key)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(provider_error(self.name(), format!("lookup returned {}", response.status())));
+            }
+            let entity: EntityResponse = response.json().await?;
+            
+            let total: f64 = entity.exposures.iter().map(|e| e.value).sum();
+            let exposures = entity
+                .exposures
+                .iter()
+                .map(|e| CategoryExposure {
+                    category: ExposureCategory::from_label(&e.category),
+                    direct: e.exposure_type.eq_ignore_ascii_case("direct"),
+                    share: if total > 0.0 { e.value / total } else { 0.0 },
+                    volume_usd: Some(e.value),
+                })
+                .collect();
+            
+            Ok(AddressRisk {
+                chain: chain.to_string(),
+                address: address.to_string(),
+                provider: self.name().to_string(),
+                score: entity.risk.as_deref().map_or(0.0, label_score),
+                exposures,
+                assessed_at: Utc::now(),
+            })
+        })
+    }
+}
@@ -0,0 +1,390 @@
+//! On-chain analytics providers scoring blockchain addresses for illicit exposure
+//!
+//! Addresses linked to an account are scored by an external provider for
+//! exposure to mixers, darknet markets, hacks, and other illicit categories.
+//! The worst address sets the account's on-chain risk, which can raise the AML
+//! risk level of its attestation.
+
+pub mod chainalysis;
+pub mod trm;
+
+use super::breaker::Provider;
+use super::country_risk::risk_level;
+use super::provider_credentials::{CredentialSource, ProviderCredentialStore};
+use crate::config::{ChainAnalyticsBackend, ChainAnalyticsConfig};
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Maximum length of a blockchain address
+const MAX_ADDRESS_LEN: usize = 128;
+
+/// Illicit or risky activity an address can be exposed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExposureCategory {
+    Mixer,
+    DarknetMarket,
+    StolenFunds,
+    Ransomware,
+    Sanctions,
+    TerroristFinancing,
+    ChildAbuse,
+    Scam,
+    Gambling,
+    Other,
+}
+
+impl ExposureCategory {
+    /// Map a provider's category label to a category
+    pub fn from_label(label: &str) -> Self {
+        let label = label.to_ascii_lowercase();
+        let has = |needle: &str| label.contains(needle);
+        if has("sanction") {
+            Self::Sanctions
+        } else if has("terror") {
+            Self::TerroristFinancing
+        } else if has("child") || has("csam") {
+            Self::ChildAbuse
+        } else if has("mix") || has("tumbler") {
+            Self::Mixer
+        } else if has("darknet") {
+            Self::DarknetMarket
+        } else if has("hack") || has("stolen") || has("exploit") {
+            Self::StolenFunds
+        } else if has("ransom") {
+            Self::Ransomware
+        } else if has("scam") || has("fraud") {
+            Self::Scam
+        } else if has("gambling") {
+            Self::Gambling
+        } else {
+            Self::Other
+        }
+    }
+    
+    /// Categories where any direct exposure is critical
+    pub fn is_prohibited(self) -> bool {
+        matches!(self, Self::Sanctions | Self::TerroristFinancing | Self::ChildAbuse)
+    }
+    
+    /// Categories where material direct exposure is high risk
+    pub fn is_severe(self) -> bool {
+        matches!(self, Self::Mixer | Self::DarknetMarket | Self::StolenFunds | Self::Ransomware)
+    }
+}
+
+/// Exposure of an address to one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryExposure {
+    pub category: ExposureCategory,
+    
+    /// Funds moved directly to or from the category rather than via intermediaries
+    pub direct: bool,
+    
+    /// Share of the address's scored volume attributed to the category
+    pub share: f64,
+    
+    /// Exposed volume in USD, when the provider reports it
+    pub volume_usd: Option<f64>,
+}
+
+/// A provider's assessment of one address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressRisk {
+    pub chain: String,
+    pub address: String,
+    pub provider: String,
+    
+    /// Provider risk normalized to `[0, 1]`
+    pub score: f64,
+    
+    pub exposures: Vec<CategoryExposure>,
+    pub assessed_at: DateTime<Utc>,
+}
+
+/// A blockchain address linked to an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedAddress {
+    /// Chain identifier as understood by the provider (e.g. "ethereum", "bitcoin")
+    pub chain: String,
+    pub address: String,
+}
+
+/// On-chain risk of an account across its linked addresses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainRiskProfile {
+    /// Highest address score
+    pub score: f64,
+    pub risk_level: AmlRiskLevel,
+    
+    /// Largest exposure per category and directness across all addresses
+    pub exposures: Vec<CategoryExposure>,
+    
+    pub addresses: Vec<AddressRisk>,
+}
+
+/// A provider that scores blockchain addresses
+pub trait ChainAnalyticsProvider: Send + Sync {
+    /// Provider name used in logs and assessments
+    fn name(&self) -> &'static str;
+    
+    /// Score an address on a chain
+    fn score_address<'a>(&'a self, chain: &'a str, address: &'a str) -> BoxFuture<'a, Result<AddressRisk>>;
+}
+
+/// Build a provider from configuration
+pub fn from_config(backend: &ChainAnalyticsBackend) -> Arc<dyn ChainAnalyticsProvider> {
+    build(reqwest::Client::new(), backend)
+}
+
+fn build(http: reqwest::Client, backend: &ChainAnalyticsBackend) -> Arc<dyn ChainAnalyticsProvider> {
+    match backend.clone() {
+        ChainAnalyticsBackend::Chainalysis { api_url, api_key } => {
+            Arc::new(chainalysis::ChainalysisProvider::new(http, api_url, api_key))
+        }
+        ChainAnalyticsBackend::Trm { api_url, api_key } => Arc::new(trm::TrmProvider::new(http, api_url, api_key)),
+    }
+}
+
+/// Service linking addresses to accounts and scoring them with caching
+pub struct ChainAnalyticsService {
+    config: Arc<LiveConfig>,
+    provider: Option<Arc<dyn ChainAnalyticsProvider>>,
+    
+    /// Business clients' own provider credentials
+    credentials: Arc<ProviderCredentialStore>,
+    
+    /// Shared by providers built from client credentials
+    http: reqwest::Client,
+    
+    /// Latest assessment per credential owner and (chain, address); a client
+    /// using its own vendor account never sees scores from another account
+    cache: RwLock<HashMap<(Option<Uuid>, String, String), AddressRisk>>,
+    
+    /// Addresses linked to each account
+    addresses: RwLock<HashMap<AccountId, Vec<LinkedAddress>>>,
+}
+
+impl ChainAnalyticsService {
+    /// Create the service with the configured provider
+    pub fn new(config: Arc<LiveConfig>, credentials: Arc<ProviderCredentialStore>) -> Self {
+        let provider = config.compliance().aml.chain_analytics.backend.as_ref().map(from_config);
+        Self::with_provider(config, credentials, provider)
+    }
+    
+    /// Create the service with an explicit provider
+    pub fn with_provider(
+        config: Arc<LiveConfig>,
+        credentials: Arc<ProviderCredentialStore>,
+        provider: Option<Arc<dyn ChainAnalyticsProvider>>,
+    ) -> Self {
+        Self {
+            config,
+            provider,
+            credentials,
+            http: reqwest::Client::new(),
+            cache: RwLock::new(HashMap::new()),
+            addresses: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Link an address to an account
+    pub async fn link_address(&self, account_id: &AccountId, address: LinkedAddress) -> Result<Vec<LinkedAddress>> {
+        let max = self.config.compliance().aml.chain_analytics.max_addresses_per_account;
+        let address = normalize_address(address)?;
+        
+        let mut addresses = self.addresses.write().await;
+        let linked = addresses.entry(account_id.clone()).or_default();
+        if !linked.contains(&address) {
+            if linked.len() >= max {
+                return Err(ComplianceError::validation(
+                    "address",
+                    format!("at most {} addresses may be linked to an account", max),
+                ));
+            }
+            linked.push(address);
+        }
+        Ok(linked.clone())
+    }
+    
+    /// Addresses linked to an account
+    pub async fn addresses(&self, account_id: &AccountId) -> Vec<LinkedAddress> {
+        self.addresses.read().await.get(account_id).cloned().unwrap_or_default()
+    }
+    
+    /// Score an address, reusing a cached assessment while it is fresh
+    ///
+    /// Within a business client's request, the client's own credentials are
+    /// used when it has registered them, or else its residency region's.
+    pub async fn address_risk(&self, chain: &str, address: &str) -> Result<AddressRisk> {
+        let (owner, provider) = self.provider_for_request().await?;
+        let ttl = Duration::seconds(self.config.compliance().aml.chain_analytics.cache_ttl_secs as i64);
+        let key = (owner, chain.to_string(), address.to_string());
+        
+        if let Some(cached) = self.cache.read().await.get(&key) {
+            if Utc::now() - cached.assessed_at < ttl {
+                return Ok(cached.clone());
+            }
+        }
+        
+        let risk = provider.score_address(chain, address).await?;
+        self.cache.write().await.insert(key, risk.clone());
+        Ok(risk)
+    }
+    
+    /// Provider to score with for the current request, and the client owning its credentials
+    async fn provider_for_request(&self) -> Result<(Option<Uuid>, Arc<dyn ChainAnalyticsProvider>)> {
+        let unavailable = |reason: &str| ComplianceError::ProviderUnavailable {
+            provider: Provider::ChainAnalytics.name().to_string(),
+            reason: reason.to_string(),
+        };
+        let compliance = self.config.compliance();
+        let resolved = self.credentials.resolve_current(Provider::ChainAnalytics, &compliance).await?;
+        match resolved.source {
+            CredentialSource::Global => {
+                let provider = self
+                    .provider
+                    .clone()
+                    .ok_or_else(|| unavailable("no chain analytics provider is configured"))?;
+                Ok((None, provider))
+            }
+            source @ (CredentialSource::Client(_) | CredentialSource::Regional(_) | CredentialSource::Vendor(_)) => {
+                // Client, regional, and routed credentials are for the configured backend; only the account differs
+                let mut backend = compliance
+                    .aml
+                    .chain_analytics
+                    .backend
+                    .clone()
+                    .ok_or_else(|| unavailable("client and regional credentials require a chain analytics backend"))?;
+                match &mut backend {
+                    ChainAnalyticsBackend::Chainalysis { api_url, api_key }
+                    | ChainAnalyticsBackend::Trm { api_url, api_key } => {
+                        if let Some(endpoint) = resolved.credentials.endpoint {
+                            *api_url = endpoint;
+                        }
+                        if let Some(key) = resolved.credentials.api_key {
+                            *api_key = key;
+                        }
+                    }
+                }
+                let owner = match source {
+                    CredentialSource::Client(client_id) => Some(client_id),
+                    _ => None,
+                };
+                Ok((owner, build(self.http.clone(), &backend)))
+            }
+        }
+    }
+    
+    /// On-chain risk of an account, or `None` when disabled or no address is linked
+    pub async fn account_profile(&self, account_id: &AccountId) -> Result<Option<ChainRiskProfile>> {
+        let compliance = self.config.compliance();
+        let config = &compliance.aml.chain_analytics;
+        if !config.enabled {
+            return Ok(None);
+        }
+        let addresses = self.addresses(account_id).await;
+        if addresses.is_empty() {
+            return Ok(None);
+        }
+        
+        let assessments = futures::future::try_join_all(
+            addresses.iter().map(|linked| self.address_risk(&linked.chain, &linked.address)),
+        )
+        .await?;
+        
+        let score = assessments.iter().map(|a| a.score).fold(0.0, f64::max);
+        let mut worst: HashMap<(ExposureCategory, bool), CategoryExposure> = HashMap::new();
+        for exposure in assessments.iter().flat_map(|a| &a.exposures) {
+            let entry = worst.entry((exposure.category, exposure.direct)).or_insert_with(|| exposure.clone());
+            if exposure.share > entry.share {
+                *entry = exposure.clone();
+            }
+        }
+        let mut exposures: Vec<_> = worst.into_values().collect();
+        exposures.sort_by(|a, b| (a.category, !a.direct).cmp(&(b.category, !b.direct)));
+        
+        Ok(Some(ChainRiskProfile {
+            risk_level: profile_risk_level(score, &exposures, config, &compliance.aml.risk_thresholds),
+            score,
+            exposures,
+            addresses: assessments,
+        }))
+    }
+}
+
+/// Raise an attestation's AML risk level to the account's on-chain risk
+///
+/// On-chain risk never lowers the level reported by the AML provider.
+pub fn apply_profile(attestation: &mut ComplianceAttestation, profile: &ChainRiskProfile) {
+    if profile.risk_level > attestation.aml_risk_level {
+        tracing::debug!(
+            account_id = %attestation.account_id,
+            score = profile.score,
+            level = ?profile.risk_level,
+            "on-chain exposure raised AML risk level"
+        );
+        attestation.aml_risk_level = profile.risk_level.clone();
+    }
+}
+
+/// Risk level from the address score, floored by direct exposure
+fn profile_risk_level(
+    score: f64,
+    exposures: &[CategoryExposure],
+    config: &ChainAnalyticsConfig,
+    thresholds: &crate::config::RiskThresholds,
+) -> AmlRiskLevel {
+    let direct = || exposures.iter().filter(|e| e.direct && e.share > 0.0);
+    let level = risk_level(score, thresholds);
+    
+    if direct().any(|e| e.category.is_prohibited()) {
+        AmlRiskLevel::Critical
+    } else if direct().any(|e| e.category.is_severe() && e.share > config.max_direct_exposure_share) {
+        level.max(AmlRiskLevel::High)
+    } else {
+        level
+    }
+}
+
+fn normalize_address(address: LinkedAddress) -> Result<LinkedAddress> {
+    let chain = address.chain.trim().to_ascii_lowercase();
+    let value = address.address.trim().to_string();
+    if chain.is_empty() || !chain.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-') {
+        return Err(ComplianceError::validation("chain", "must be a chain identifier such as \"ethereum\""));
+    }
+    if value.is_empty() || value.len() > MAX_ADDRESS_LEN || !value.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return Err(ComplianceError::validation(
+            "address",
+            format!("must be 1-{} alphanumeric characters", MAX_ADDRESS_LEN),
+        ));
+    }
+    Ok(LinkedAddress { chain, address: value })
+}
+
+/// Normalize a provider's categorical risk label to a score
+pub(crate) fn label_score(label: &str) -> f64 {
+    match label.to_ascii_lowercase().as_str() {
+        "severe" => 1.0,
+        "high" => 0.8,
+        "medium" => 0.5,
+        "low" => 0.1,
+        _ => 0.0,
+    }
+}
+
+/// Error for a provider response that could not be used
+pub(crate) fn provider_error(provider: &str, reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::ProviderUnavailable {
+        provider: "chain_analytics".to_string(),
+        reason: format!("{}: {}", provider, reason.into()),
+    }
+}
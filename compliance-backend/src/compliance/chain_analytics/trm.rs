@@ -0,0 +1,99 @@
+//! TRM Labs address screening provider
+
+use super::{label_score, provider_error, AddressRisk, CategoryExposure, ChainAnalyticsProvider, ExposureCategory};
+use crate::Result;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+
+/// Scores addresses with the TRM Labs address screening API
+pub struct TrmProvider {
+    http: reqwest::Client,
+    api_url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScreeningResult {
+    #[serde(default)]
+    address_risk_indicators: Vec<RiskIndicator>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RiskIndicator {
+    category: String,
+    category_risk_score_level_label: String,
+    /// "OWNERSHIP", "COUNTERPARTY", or "INDIRECT"
+    risk_type: String,
+    /// USD volume, serialized as a decimal string
+    total_volume_usd: String,
+}
+
+impl TrmProvider {
+    /// Create a TRM provider
+    pub fn new(http: reqwest::Client, api_url: String, api_key: String) -> Self {
+        Self { http, api_url, api_key }
+    }
+}
+
+impl ChainAnalyticsProvider for TrmProvider {
+    fn name(&self) -> &'static str {
+        "trm"
+    }
+    
+    fn score_address<'a>(&'a self, chain: &'a str, address: &'a str) -> BoxFuture<'a, Result<AddressRisk>> {
+        Box::pin(async move {
+            let url = format!("{}/public/v2/screening/addresses", self.api_url.trim_end_matches('/'));
+            let response = self
+                .http
+                .post(url)
+                .basic_auth(&self.api_key, Some(""))
+                .json(&serde_json::json!([{ "address": address, "chain": chain }]))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(provider_error(self.name(), format!("screening returned {}", response.status())));
+            }
+            
+            let results: Vec<ScreeningResult> = response.json().await?;
+            let result = results
+                .into_iter()
+                .next()
+                .ok_or_else(|| provider_error(self.name(), "empty screening response"))?;
+            
+            let volumes: Vec<f64> = result
+                .address_risk_indicators
+                .iter()
+                .map(|i| i.total_volume_usd.parse().unwrap_or(0.0))
+                .collect();
+            let total: f64 = volumes.iter().sum();
+            let exposures = result
+                .address_risk_indicators
+                .iter()
+                .zip(&volumes)
+                .map(|(indicator, volume)| CategoryExposure {
+                    category: ExposureCategory::from_label(&indicator.category),
+                    direct: !indicator.risk_type.eq_ignore_ascii_case("indirect"),
+                    share: if total > 0.0 { volume / total } else { 0.0 },
+                    volume_usd: Some(*volume),
+                })
+                .collect();
+            let score = result
+                .address_risk_indicators
+                .iter()
+                .map(|i| label_score(&i.category_risk_score_level_label))
+                .fold(0.0, f64::max);
+            
+            Ok(AddressRisk {
+                chain: chain.to_string(),
+                address: address.to_string(),
+                provider: self.name().to_string(),
+                score,
+                exposures,
+                assessed_at: Utc::now(),
+            })
+        })
+    }
+}
@@ -0,0 +1,161 @@
+//! Verifier-issued challenges binding proofs to a nonce and audience
+
+use super::claims::ClaimKind;
+use super::freshness::FreshnessRequirement;
+use super::scope::ProofScope;
+use crate::clock::{system_clock, SharedClock};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// A single-use challenge issued to a verifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofChallenge {
+    /// Random hex-encoded nonce
+    pub nonce: String,
+    
+    /// Verifier the proof is intended for (e.g. "exchange-a.example")
+    pub audience: String,
+    
+    /// Account the proof must be generated for
+    pub account_id: AccountId,
+    
+    /// Scope the verifier requires the proof to carry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<ProofScope>,
+    
+    /// Claims the verifier requires the proof to disclose
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claims: Vec<ClaimKind>,
+    
+    /// Freshness the verifier requires of the attestation behind the proof
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<FreshnessRequirement>,
+    
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    
+    /// Set once a proof bound to this challenge has been verified
+    pub consumed_at: Option<DateTime<Utc>>,
+}
+
+/// Service issuing and tracking proof challenges
+pub struct ChallengeService {
+    ttl: Duration,
+    challenges: RwLock<HashMap<String, ProofChallenge>>,
+    clock: SharedClock,
+}
+
+impl ChallengeService {
+    /// Create a challenge service with the given challenge lifetime
+    pub fn new(ttl_secs: u64) -> Self {
+        Self {
+            ttl: Duration::seconds(ttl_secs as i64),
+            challenges: RwLock::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Issue a new challenge for an audience and account, optionally requiring
+    /// a scope, the disclosure of claims, and attestation freshness
+    pub async fn issue(
+        &self,
+        audience: &str,
+        account_id: &AccountId,
+        scope: Option<ProofScope>,
+        mut claims: Vec<ClaimKind>,
+        freshness: Option<FreshnessRequirement>,
+    ) -> Result<ProofChallenge> {
+        if audience.trim().is_empty() {
+            return Err(ComplianceError::validation("audience", "must not be empty"));
+        }
+        if let Some(scope) = &scope {
+            scope.validate()?;
+        }
+        if let Some(freshness) = &freshness {
+            freshness.validate()?;
+        }
+        
+        claims.sort();
+        claims.dedup();
+        
+        let now = self.clock.now();
+        let nonce_bytes: [u8; 32] = rand::random();
+        let challenge = ProofChallenge {
+            nonce: hex::encode(nonce_bytes),
+            audience: audience.to_string(),
+            account_id: account_id.clone(),
+            scope,
+            claims,
+            freshness,
+            issued_at: now,
+            expires_at: now + self.ttl,
+            consumed_at: None,
+        };
+        
+        let mut challenges = self.challenges.write().await;
+        challenges.retain(|_, c| c.expires_at > now);
+        challenges.insert(challenge.nonce.clone(), challenge.clone());
+        
+        Ok(challenge)
+    }
+    
+    /// Get an outstanding challenge for proof generation
+    pub async fn get_open(&self, nonce: &str, account_id: &AccountId) -> Result<ProofChallenge> {
+        let challenges = self.challenges.read().await;
+        let challenge = challenges.get(nonce).ok_or_else(|| ComplianceError::ChallengeRejected {
+            reason: "unknown nonce".to_string(),
+        })?;
+        Self::check_open(challenge, account_id, self.clock.now())?;
+        Ok(challenge.clone())
+    }
+    
+    /// Consume the challenge a proof was generated for
+    ///
+    /// Fails if the challenge is unknown, expired, already consumed, or was
+    /// issued for a different audience or account.
+    pub async fn consume(&self, nonce: &str, audience: &str, account_id: &AccountId) -> Result<ProofChallenge> {
+        let now = self.clock.now();
+        let mut challenges = self.challenges.write().await;
+        let challenge = challenges.get_mut(nonce).ok_or_else(|| ComplianceError::ChallengeRejected {
+            reason: "unknown nonce".to_string(),
+        })?;
+        if challenge.audience != audience {
+            return Err(ComplianceError::ChallengeRejected {
+                reason: "nonce was issued for a different audience".to_string(),
+            });
+        }
+        Self::check_open(challenge, account_id, now)?;
+        
+        challenge.consumed_at = Some(now);
+        Ok(challenge.clone())
+    }
+    
+    fn check_open(challenge: &ProofChallenge, account_id: &AccountId, now: DateTime<Utc>) -> Result<()> {
+        if challenge.account_id != *account_id {
+            return Err(ComplianceError::ChallengeRejected {
+                reason: "nonce was issued for a different account".to_string(),
+            });
+        }
+        if challenge.consumed_at.is_some() {
+            return Err(ComplianceError::ChallengeRejected {
+                reason: "nonce has already been used".to_string(),
+            });
+        }
+        if challenge.expires_at <= now {
+            return Err(ComplianceError::ChallengeRejected {
+                reason: "nonce has expired".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
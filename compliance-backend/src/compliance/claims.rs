@@ -0,0 +1,233 @@
+//! Claim-level commitments in proof envelopes
+//!
+//! Rather than one commitment to the whole attestation, an envelope commits
+//! to each claim separately, as a leaf of a depth-3 Merkle tree in which every
+//! claim kind has a fixed position:
+//!
+//! ```text
+//! leaf = RPO(version, domain, kind, value, salt_0, salt_1, salt_2, salt_3)
+//! ```
+//!
+//! The envelope carries the root and opens only the claims the verifier
+//! asked for, each with its salt and Merkle path. Claim values have a handful
+//! of possibilities, so leaves are salted with fresh randomness per envelope
+//! and the salts of undisclosed claims never leave the backend.
+//!
+//! A leaf is eight elements, one RPO permutation, so a Miden program
+//! recomputes it with `hmerge` and checks it against the root with
+//! `mtree_verify`.
+
+use crate::crypto::commitment::{aml_risk_code, compliance_level_code, CommitmentDomain, FieldEncoder};
+use crate::types::{AmlRiskLevel, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use miden_crypto::hash::rpo::RpoDigest;
+use miden_crypto::merkle::{MerklePath, MerkleTree, NodeIndex};
+use miden_crypto::{Felt, Word, EMPTY_WORD};
+use serde::{Deserialize, Serialize};
+
+/// Depth of the claim tree
+pub const CLAIM_TREE_DEPTH: u8 = 3;
+
+/// Kind of claim an envelope can disclose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimKind {
+    KycTier,
+    RiskBand,
+    PepStatus,
+    JurisdictionClass,
+    AttestationAge,
+}
+
+impl ClaimKind {
+    /// Every claim kind, in tree order
+    pub const ALL: [Self; 5] = [
+        Self::KycTier,
+        Self::RiskBand,
+        Self::PepStatus,
+        Self::JurisdictionClass,
+        Self::AttestationAge,
+    ];
+    
+    /// Position of the claim in the tree, also hashed into its leaf
+    pub fn index(self) -> u64 {
+        self as u64
+    }
+}
+
+/// Outcome of PEP screening
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PepStatus {
+    /// The account has not been screened
+    Unscreened,
+    Clear,
+    /// The account matched a PEP list entry
+    Match,
+}
+
+/// FATF classification of the account's country of residence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JurisdictionClass {
+    /// No country of residence is known
+    Unassessed,
+    Standard,
+    IncreasedMonitoring,
+    CallForAction,
+}
+
+/// A single fact about an account's compliance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "claim", content = "value", rename_all = "snake_case")]
+pub enum Claim {
+    /// Highest compliance level the attestation meets, if any
+    KycTier(Option<ComplianceLevel>),
+    RiskBand(AmlRiskLevel),
+    PepStatus(PepStatus),
+    JurisdictionClass(JurisdictionClass),
+    /// Seconds from attestation issuance to envelope issuance
+    AttestationAge(u64),
+}
+
+impl Claim {
+    /// Kind of the claim
+    pub fn kind(&self) -> ClaimKind {
+        match self {
+            Self::KycTier(_) => ClaimKind::KycTier,
+            Self::RiskBand(_) => ClaimKind::RiskBand,
+            Self::PepStatus(_) => ClaimKind::PepStatus,
+            Self::JurisdictionClass(_) => ClaimKind::JurisdictionClass,
+            Self::AttestationAge(_) => ClaimKind::AttestationAge,
+        }
+    }
+    
+    /// Element encoding the claim's value
+    ///
+    /// A KYC tier is its compliance level code plus one, zero meaning no
+    /// level is met; statuses and classes are their position in declaration
+    /// order.
+    pub fn value_code(&self) -> u64 {
+        match self {
+            Self::KycTier(level) => level.as_ref().map_or(0, |level| compliance_level_code(level) + 1),
+            Self::RiskBand(level) => aml_risk_code(level),
+            Self::PepStatus(status) => *status as u64,
+            Self::JurisdictionClass(class) => *class as u64,
+            Self::AttestationAge(secs) => *secs,
+        }
+    }
+    
+    /// Leaf committing to the claim under `salt`
+    pub fn leaf(&self, salt: Word) -> RpoDigest {
+        let mut encoder = FieldEncoder::new(CommitmentDomain::Claim);
+        encoder.value(self.kind().index()).value(self.value_code());
+        for element in salt {
+            encoder.felt(element);
+        }
+        encoder.commit().digest()
+    }
+}
+
+/// A claim opened against an envelope's claim root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DisclosedClaim {
+    pub claim: Claim,
+    #[serde(with = "super::proof_envelope::serde_bytes_array")]
+    pub salt: [u8; 32],
+    /// Sibling nodes from the leaf up to the root, concatenated
+    #[serde(with = "super::proof_envelope::serde_bytes_vec")]
+    pub path: Vec<u8>,
+}
+
+impl DisclosedClaim {
+    /// Check the claim is committed to under `root`
+    pub fn verify(&self, root: &[u8; 32]) -> Result<()> {
+        let invalid = |reason: &str| ComplianceError::InvalidProof {
+            reason: format!("{:?} claim {}", self.claim.kind(), reason),
+        };
+        let salt = digest(&self.salt).ok_or_else(|| invalid("has a malformed salt"))?;
+        if self.path.len() != CLAIM_TREE_DEPTH as usize * 32 {
+            return Err(invalid("has a Merkle path of the wrong length"));
+        }
+        let path = self
+            .path
+            .chunks(32)
+            .map(|node| <[u8; 32]>::try_from(node).ok().and_then(|node| digest(&node)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("has a malformed Merkle path"))?;
+        
+        let leaf = self.claim.leaf(salt.into());
+        let computed = MerklePath::new(path)
+            .compute_root(self.claim.kind().index(), leaf)
+            .map_err(|e| invalid(&format!("has an invalid Merkle path: {}", e)))?;
+        if computed.as_bytes() != *root {
+            return Err(invalid("is not committed to by the envelope"));
+        }
+        Ok(())
+    }
+}
+
+/// Every claim about an attestation, salted for one envelope
+#[derive(Debug, Clone)]
+pub struct ClaimSet {
+    claims: Vec<Claim>,
+    salts: Vec<Word>,
+    tree: MerkleTree,
+}
+
+impl ClaimSet {
+    /// Salt one claim of every kind and build the claim tree
+    pub fn new(mut claims: Vec<Claim>) -> Result<Self> {
+        claims.sort_by_key(Claim::kind);
+        let kinds: Vec<_> = claims.iter().map(Claim::kind).collect();
+        if kinds != ClaimKind::ALL {
+            return Err(ComplianceError::internal("a claim set needs exactly one claim of every kind"));
+        }
+        
+        let salts: Vec<Word> = claims
+            .iter()
+            .map(|_| std::array::from_fn(|_| Felt::new(rand::random::<u64>())))
+            .collect();
+        let mut leaves: Vec<Word> = claims.iter().zip(&salts).map(|(claim, salt)| claim.leaf(*salt).into()).collect();
+        leaves.resize(1 << CLAIM_TREE_DEPTH, EMPTY_WORD);
+        let tree = MerkleTree::new(leaves).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        
+        Ok(Self { claims, salts, tree })
+    }
+    
+    /// Root of the claim tree, as embedded in envelopes
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.root().as_bytes()
+    }
+    
+    /// The claim of a kind
+    pub fn get(&self, kind: ClaimKind) -> &Claim {
+        &self.claims[kind.index() as usize]
+    }
+    
+    /// Open the claims of the given kinds, in tree order
+    pub fn disclose(&self, kinds: &[ClaimKind]) -> Result<Vec<DisclosedClaim>> {
+        let mut kinds = kinds.to_vec();
+        kinds.sort();
+        kinds.dedup();
+        kinds
+            .into_iter()
+            .map(|kind| {
+                let index = kind.index();
+                let node = NodeIndex::new(CLAIM_TREE_DEPTH, index).map_err(|e| ComplianceError::internal(e.to_string()))?;
+                let path = self.tree.get_path(node).map_err(|e| ComplianceError::internal(e.to_string()))?;
+                Ok(DisclosedClaim {
+                    claim: self.claims[index as usize].clone(),
+                    salt: RpoDigest::from(self.salts[index as usize]).as_bytes(),
+                    path: path.iter().flat_map(|node| node.as_bytes()).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Digest of 32 bytes holding four canonical field elements
+fn digest(bytes: &[u8; 32]) -> Option<RpoDigest> {
+    RpoDigest::try_from(*bytes).ok()
+}
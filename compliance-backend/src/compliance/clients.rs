@@ -0,0 +1,277 @@
+//! Business client registry
+//!
+//! A client with a sandbox has a second API key, for test traffic, and its
+//! own sandbox settings. Sandbox keys carry a distinct prefix so requests
+//! can be routed to the sandbox before the key is looked up.
+
+use super::localization::normalize_locale;
+use super::schema_versions::{check_pins, current_version};
+use crate::crypto::webhook_encryption;
+use crate::storage::memory::MemoryClientRepo;
+use crate::storage::ClientRepo;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Prefix of generated API keys, to make leaked keys recognizable
+const API_KEY_PREFIX: &str = "ztc_";
+
+/// Prefix of generated sandbox API keys
+pub const SANDBOX_API_KEY_PREFIX: &str = "ztc_test_";
+
+impl BusinessClient {
+    /// The client as seen by requests in `environment`, with that
+    /// environment's API key and settings; `None` for a sandbox the client
+    /// does not have
+    pub fn in_environment(&self, environment: ClientEnvironment) -> Option<BusinessClient> {
+        match environment {
+            ClientEnvironment::Production => Some(self.clone()),
+            ClientEnvironment::Sandbox => {
+                let sandbox = self.sandbox.as_ref()?;
+                Some(BusinessClient {
+                    api_key: sandbox.api_key.clone(),
+                    webhook_url: sandbox.webhook_url.clone(),
+                    compliance_level: sandbox.compliance_level.clone(),
+                    webhook_encryption_key: sandbox.webhook_encryption_key.clone(),
+                    ..self.clone()
+                })
+            }
+        }
+    }
+}
+
+/// Registry of business clients and their API keys
+pub struct ClientRegistry {
+    repo: Arc<dyn ClientRepo>,
+    
+    /// Held while a client is read and written back
+    updating: Mutex<()>,
+}
+
+impl Default for ClientRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientRegistry {
+    /// Create an empty registry kept in memory
+    pub fn new() -> Self {
+        Self::with_repo(Arc::new(MemoryClientRepo::default()))
+    }
+    
+    /// Create a registry stored in `repo`
+    pub fn with_repo(repo: Arc<dyn ClientRepo>) -> Self {
+        Self {
+            repo,
+            updating: Mutex::new(()),
+        }
+    }
+    
+    /// Register or replace a business client
+    pub async fn register(&self, client: BusinessClient) -> Result<()> {
+        self.repo.save(&client).await
+    }
+    
+    /// Get a business client by id
+    pub async fn get(&self, client_id: Uuid) -> Result<BusinessClient> {
+        self.repo
+            .get(client_id)
+            .await?
+            .ok_or_else(|| ComplianceError::BusinessClientNotFound {
+                client_id: client_id.to_string(),
+            })
+    }
+    
+    /// List every registered client
+    pub async fn list(&self) -> Result<Vec<BusinessClient>> {
+        self.repo.list().await
+    }
+    
+    /// Find the business client owning an API key, as seen in the key's environment
+    pub async fn find_by_api_key(&self, api_key: &str) -> Result<Option<BusinessClient>> {
+        let Some(client) = self.repo.find_by_api_key(api_key).await? else {
+            return Ok(None);
+        };
+        let environment = match &client.sandbox {
+            Some(sandbox) if sandbox.api_key == api_key => ClientEnvironment::Sandbox,
+            _ => ClientEnvironment::Production,
+        };
+        Ok(client.in_environment(environment))
+    }
+    
+    /// Create a business client with a freshly generated API key
+    ///
+    /// The data residency region is fixed at creation, since PII already
+    /// stored for the client would otherwise be left in the wrong region.
+    /// With `sandbox` set the client also gets a sandbox key, and sandbox
+    /// settings starting as a copy of its production settings. New clients
+    /// are pinned to the current payload schema version.
+    pub async fn create(
+        &self,
+        name: &str,
+        webhook_url: Option<String>,
+        compliance_level: ComplianceLevel,
+        region: Option<DataRegion>,
+        sandbox: bool,
+    ) -> Result<BusinessClient> {
+        if name.trim().is_empty() {
+            return Err(ComplianceError::validation("name", "must not be empty"));
+        }
+        check_webhook_url(webhook_url.as_deref())?;
+        
+        let sandbox = sandbox.then(|| SandboxProfile {
+            api_key: generate_api_key(ClientEnvironment::Sandbox),
+            webhook_url: webhook_url.clone(),
+            compliance_level: compliance_level.clone(),
+            webhook_encryption_key: None,
+        });
+        let client = BusinessClient {
+            id: Uuid::new_v4(),
+            name: name.trim().to_string(),
+            api_key: generate_api_key(ClientEnvironment::Production),
+            webhook_url,
+            compliance_level,
+            created_at: Utc::now(),
+            webhook_encryption_key: None,
+            region,
+            sandbox,
+            locales: Vec::new(),
+            schema: SchemaPins::pinned(current_version()),
+        };
+        self.register(client.clone()).await?;
+        Ok(client)
+    }
+    
+    /// Replace a client's API key for one environment, invalidating the previous one immediately
+    pub async fn rotate_api_key(&self, client_id: Uuid, environment: ClientEnvironment) -> Result<BusinessClient> {
+        self.update(client_id, |client| {
+            let api_key = generate_api_key(environment);
+            match environment {
+                ClientEnvironment::Production => client.api_key = api_key,
+                ClientEnvironment::Sandbox => sandbox_of(client)?.api_key = api_key,
+            }
+            Ok(())
+        })
+        .await
+    }
+    
+    /// Replace a client's sandbox settings
+    pub async fn update_sandbox(
+        &self,
+        client_id: Uuid,
+        webhook_url: Option<String>,
+        compliance_level: ComplianceLevel,
+    ) -> Result<BusinessClient> {
+        check_webhook_url(webhook_url.as_deref())?;
+        self.update(client_id, |client| {
+            let sandbox = sandbox_of(client)?;
+            sandbox.webhook_url = webhook_url;
+            sandbox.compliance_level = compliance_level;
+            Ok(())
+        })
+        .await
+    }
+    
+    /// Replace the locales a client's webhooks carry display text in
+    pub async fn set_locales(&self, client_id: Uuid, locales: Vec<String>) -> Result<BusinessClient> {
+        let mut normalized: Vec<String> = Vec::new();
+        for locale in &locales {
+            let locale = normalize_locale(locale)
+                .map_err(|_| ComplianceError::validation("locales", format!("{} is not a locale tag", locale)))?;
+            if !normalized.contains(&locale) {
+                normalized.push(locale);
+            }
+        }
+        self.update(client_id, |client| {
+            client.locales = normalized;
+            Ok(())
+        })
+        .await
+    }
+    
+    /// Replace the payload schema versions a client is pinned to
+    pub async fn set_schema_pins(&self, client_id: Uuid, pins: SchemaPins) -> Result<BusinessClient> {
+        check_pins(&pins)?;
+        self.update(client_id, |client| {
+            client.schema = pins;
+            Ok(())
+        })
+        .await
+    }
+    
+    /// Set or clear the key a client's webhook deliveries in one environment are encrypted to
+    pub async fn set_webhook_encryption(
+        &self,
+        client_id: Uuid,
+        environment: ClientEnvironment,
+        key: Option<WebhookEncryptionKey>,
+    ) -> Result<BusinessClient> {
+        if let Some(key) = &key {
+            webhook_encryption::check_key(key)?;
+        }
+        self.update(client_id, |client| {
+            match environment {
+                ClientEnvironment::Production => client.webhook_encryption_key = key,
+                ClientEnvironment::Sandbox => sandbox_of(client)?.webhook_encryption_key = key,
+            }
+            Ok(())
+        })
+        .await
+    }
+    
+    /// Copy a client's sandbox settings to production
+    ///
+    /// API keys are not copied; each environment keeps its own. The webhook
+    /// encryption key goes with the webhook URL it belongs to.
+    pub async fn promote_sandbox(&self, client_id: Uuid) -> Result<BusinessClient> {
+        self.update(client_id, |client| {
+            let sandbox = sandbox_of(client)?.clone();
+            client.webhook_url = sandbox.webhook_url;
+            client.compliance_level = sandbox.compliance_level;
+            client.webhook_encryption_key = sandbox.webhook_encryption_key;
+            Ok(())
+        })
+        .await
+    }
+    
+    async fn update(
+        &self,
+        client_id: Uuid,
+        f: impl FnOnce(&mut BusinessClient) -> Result<()>,
+    ) -> Result<BusinessClient> {
+        let _updating = self.updating.lock().await;
+        let mut client = self.get(client_id).await?;
+        f(&mut client)?;
+        self.repo.save(&client).await?;
+        Ok(client)
+    }
+}
+
+fn sandbox_of(client: &mut BusinessClient) -> Result<&mut SandboxProfile> {
+    let client_id = client.id;
+    client.sandbox.as_mut().ok_or_else(|| ComplianceError::ClientSandboxNotFound {
+        client_id: client_id.to_string(),
+    })
+}
+
+/// Reject webhook URLs that are not https
+pub(crate) fn check_webhook_url(webhook_url: Option<&str>) -> Result<()> {
+    match webhook_url {
+        Some(url) if !url.starts_with("https://") => {
+            Err(ComplianceError::validation("webhook_url", "must be an https URL"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn generate_api_key(environment: ClientEnvironment) -> String {
+    let prefix = match environment {
+        ClientEnvironment::Production => API_KEY_PREFIX,
+        ClientEnvironment::Sandbox => SANDBOX_API_KEY_PREFIX,
+    };
+    format!("{}{}", prefix, hex::encode(rand::random::<[u8; 32]>()))
+}
@@ -0,0 +1,224 @@
+//! Notes whose spend conditions encode compliance constraints
+//!
+//! A conditional note carries assets that the consuming account receives only
+//! when the note script's checks pass: the note may have to be consumed
+//! before an attestation expires, only after a timelock, or only by an
+//! account whose own KYC component reports a verified status at a minimum
+//! compliance level. The conditions are compiled into the note script, so
+//! they hold on-chain regardless of who relays the note.
+
+use super::account_components::foreign::{procedure_root, require_verified, KYC_STATUS_PROCEDURE};
+use super::account_components::migration::ComponentKind;
+use super::account_components::template::{ComponentTemplates, MasmBuilder};
+use crate::types::{ComplianceAttestation, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::asset::Asset;
+use miden_objects::note::{
+    Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+    NoteTag, NoteType,
+};
+use miden_objects::{Felt, TransactionKernel, Word};
+use std::fmt::Display;
+
+/// Procedure moving every asset of the note into the consuming account, as in P2ID
+const ADD_NOTE_ASSETS: &str = "\
+# Add every asset of the note to the consuming account
+proc.add_note_assets_to_account
+    push.0 exec.note::get_assets
+    # => [num_assets, ptr]
+    mul.4 dup.1 add
+    padw movup.5
+    dup dup.6 neq
+    while.true
+        mem_loadw
+        padw swapw padw padw swapdw
+        call.wallet::receive_asset
+        dropw dropw dropw
+        movup.4 add.4
+        dup dup.6 neq
+    end
+    drop dropw drop
+end
+";
+
+/// Spend condition of a conditional note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteCondition {
+    /// Consumable only in blocks timestamped before this time
+    ConsumeBefore(DateTime<Utc>),
+    /// Consumable only in blocks timestamped at or after this time
+    ConsumeAfter(DateTime<Utc>),
+    /// Consumable only by accounts whose KYC is verified, unexpired and at this level or above
+    MinimumLevel(ComplianceLevel),
+}
+
+/// Builder of notes with compliance spend conditions
+#[derive(Debug, Clone)]
+pub struct ConditionalNoteBuilder {
+    sender: MidenAccountId,
+    assets: Vec<Asset>,
+    note_type: NoteType,
+    conditions: Vec<NoteCondition>,
+}
+
+impl ConditionalNoteBuilder {
+    /// Note from `sender` carrying `assets`, with no conditions yet
+    pub fn new(sender: MidenAccountId, assets: Vec<Asset>) -> Self {
+        Self {
+            sender,
+            assets,
+            note_type: NoteType::Public,
+            conditions: Vec::new(),
+        }
+    }
+    
+    /// Note that must be consumed while `attestation` is still in force
+    pub fn before_attestation_expiry(
+        sender: MidenAccountId,
+        assets: Vec<Asset>,
+        attestation: &ComplianceAttestation,
+    ) -> Self {
+        Self::new(sender, assets).consume_before(attestation.expires_at)
+    }
+    
+    /// Note that only accounts at `level` or above can consume
+    pub fn for_level(sender: MidenAccountId, assets: Vec<Asset>, level: ComplianceLevel) -> Self {
+        Self::new(sender, assets).min_level(level)
+    }
+    
+    /// Note that cannot be consumed before `unlocks_at`
+    pub fn timelocked(sender: MidenAccountId, assets: Vec<Asset>, unlocks_at: DateTime<Utc>) -> Self {
+        Self::new(sender, assets).consume_after(unlocks_at)
+    }
+    
+    /// Require consumption before `deadline`
+    pub fn consume_before(mut self, deadline: DateTime<Utc>) -> Self {
+        self.conditions.push(NoteCondition::ConsumeBefore(deadline));
+        self
+    }
+    
+    /// Require consumption at or after `unlocks_at`
+    pub fn consume_after(mut self, unlocks_at: DateTime<Utc>) -> Self {
+        self.conditions.push(NoteCondition::ConsumeAfter(unlocks_at));
+        self
+    }
+    
+    /// Require the consuming account to hold `level` or above
+    pub fn min_level(mut self, level: ComplianceLevel) -> Self {
+        self.conditions.push(NoteCondition::MinimumLevel(level));
+        self
+    }
+    
+    /// Keep the note's details off-chain
+    pub fn private(mut self) -> Self {
+        self.note_type = NoteType::Private;
+        self
+    }
+    
+    /// Conditions the note will enforce
+    pub fn conditions(&self) -> &[NoteCondition] {
+        &self.conditions
+    }
+    
+    /// Generate the note script enforcing the conditions
+    ///
+    /// Level conditions call the consuming account's KYC component, which
+    /// must have been generated from `templates`.
+    pub fn script_source(&self, templates: &ComponentTemplates) -> Result<String> {
+        self.validate()?;
+        let kyc_status_root = if self.conditions.iter().any(|c| matches!(c, NoteCondition::MinimumLevel(_))) {
+            Some(procedure_root(ComponentKind::Kyc, templates, KYC_STATUS_PROCEDURE)?)
+        } else {
+            None
+        };
+        
+        let mut masm = MasmBuilder::new();
+        masm.comment("Conditional Compliance Note")
+            .comment("Transfers the note's assets to the consuming account once every condition holds")
+            .blank()
+            .import("miden::note")
+            .import("miden::tx")
+            .import("miden::contracts::wallets::basic->wallet")
+            .blank();
+        
+        masm.op(ADD_NOTE_ASSETS).blank();
+        
+        masm.begin(|m| {
+            m.op_with_comment("dropw", "Note args");
+            for condition in &self.conditions {
+                match condition {
+                    NoteCondition::ConsumeBefore(deadline) => {
+                        m.op_with_comment(
+                            format_args!("exec.tx::get_block_timestamp push.{} lt assert", unix_secs(deadline)),
+                            "Consumed before the deadline",
+                        );
+                    }
+                    NoteCondition::ConsumeAfter(unlocks_at) => {
+                        m.op_with_comment(
+                            format_args!("exec.tx::get_block_timestamp push.{} gte assert", unix_secs(unlocks_at)),
+                            "Timelock has passed",
+                        );
+                    }
+                    NoteCondition::MinimumLevel(level) => {
+                        let root = kyc_status_root.expect("resolved for level conditions");
+                        m.op_with_comment("padw padw padw padw", "Call inputs");
+                        m.op_with_comment(format_args!("call.{}", root.to_hex()), "Consuming account's KYC status");
+                        require_verified(m, level);
+                    }
+                }
+            }
+            m.op("exec.add_note_assets_to_account");
+        });
+        
+        Ok(masm.finish())
+    }
+    
+    /// Build the note with the recipient's `serial_num`
+    pub fn build(&self, templates: &ComponentTemplates, serial_num: Word) -> Result<Note> {
+        let script = NoteScript::compile(self.script_source(templates)?, TransactionKernel::assembler())
+            .map_err(|e| note_error("failed to compile note script", e))?;
+        let inputs = NoteInputs::new(Vec::new()).map_err(|e| note_error("invalid note inputs", e))?;
+        let recipient = NoteRecipient::new(serial_num, script, inputs);
+        
+        let tag = NoteTag::from_account_id(self.sender, NoteExecutionMode::Local)
+            .map_err(|e| note_error("invalid note tag", e))?;
+        let metadata = NoteMetadata::new(self.sender, self.note_type, tag, NoteExecutionHint::always(), Felt::new(0))
+            .map_err(|e| note_error("invalid note metadata", e))?;
+        let assets = NoteAssets::new(self.assets.clone()).map_err(|e| note_error("invalid note assets", e))?;
+        
+        Ok(Note::new(assets, metadata, recipient))
+    }
+    
+    /// Reject conditions that no block could satisfy
+    fn validate(&self) -> Result<()> {
+        let deadline = self.conditions.iter().filter_map(|c| match c {
+            NoteCondition::ConsumeBefore(at) => Some(*at),
+            _ => None,
+        });
+        let unlock = self.conditions.iter().filter_map(|c| match c {
+            NoteCondition::ConsumeAfter(at) => Some(*at),
+            _ => None,
+        });
+        if let (Some(deadline), Some(unlock)) = (deadline.min(), unlock.max()) {
+            if unlock >= deadline {
+                return Err(ComplianceError::validation(
+                    "conditions",
+                    "timelock must end before the consumption deadline",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unix_secs(at: &DateTime<Utc>) -> u64 {
+    at.timestamp().max(0) as u64
+}
+
+fn note_error(context: &str, e: impl Display) -> ComplianceError {
+    ComplianceError::TransactionExecutionFailed {
+        reason: format!("{}: {}", context, e),
+    }
+}
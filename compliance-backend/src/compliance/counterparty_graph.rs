@@ -0,0 +1,333 @@
+//! Risk propagated to accounts from the counterparties they transact with
+//!
+//! Monitored transactions connect each account to its counterparties, with
+//! edges weighted by the volume exchanged. An address linked to an account
+//! is merged into the account's node, so transfers to the address count as
+//! transfers to the account.
+//!
+//! Every compliance check records the account's own risk: the provider's AML
+//! level as raised by geographic and on-chain risk. Risk then flows along
+//! edges for up to `max_hops`, multiplied at each hop by `decay` and by the
+//! share of the receiving node's volume the edge carries, so an account sees
+//! its score rise when a large part of its volume goes to a high-risk
+//! counterparty, but not over an occasional transfer. Propagated risk is
+//! never recorded as an account's own risk, so it does not echo back.
+
+use super::chain_analytics::LinkedAddress;
+use super::country_risk::risk_level;
+use super::monitoring::MonitoredTransaction;
+use crate::config::{CounterpartyRiskConfig, RiskThresholds};
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// An account's own risk, as of its latest compliance check
+#[derive(Debug, Clone)]
+struct NodeRisk {
+    level: AmlRiskLevel,
+    assessed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct Graph {
+    /// Volume exchanged with each neighbor, in both directions
+    edges: HashMap<String, HashMap<String, f64>>,
+    
+    /// Total volume of each node
+    volume: HashMap<String, f64>,
+    
+    /// Node each linked address was merged into
+    aliases: HashMap<String, String>,
+    
+    risk: HashMap<String, NodeRisk>,
+}
+
+impl Graph {
+    fn resolve(&self, node: String) -> String {
+        self.aliases.get(&node).cloned().unwrap_or(node)
+    }
+    
+    fn add_volume(&mut self, a: &str, b: &str, amount: f64) {
+        *self.edges.entry(a.to_string()).or_default().entry(b.to_string()).or_default() += amount;
+        *self.edges.entry(b.to_string()).or_default().entry(a.to_string()).or_default() += amount;
+        *self.volume.entry(a.to_string()).or_default() += amount;
+        *self.volume.entry(b.to_string()).or_default() += amount;
+    }
+    
+    /// Move every edge of `from` onto `into`
+    fn merge(&mut self, from: &str, into: &str) {
+        let Some(neighbors) = self.edges.remove(from) else {
+            return;
+        };
+        self.volume.remove(from);
+        for (neighbor, amount) in neighbors {
+            if let Some(edges) = self.edges.get_mut(&neighbor) {
+                edges.remove(from);
+            }
+            if let Some(volume) = self.volume.get_mut(&neighbor) {
+                *volume -= amount;
+            }
+            if neighbor != into {
+                self.add_volume(into, &neighbor, amount);
+            }
+        }
+    }
+}
+
+/// A node reached from an account, with the risk it passes on
+#[derive(Debug, Clone, Serialize)]
+pub struct NeighborRisk {
+    /// Counterparty account or address
+    pub node: String,
+    
+    pub hops: usize,
+    
+    /// Volume exchanged directly with the account, for first-hop neighbors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direct_volume: Option<f64>,
+    
+    /// Product of decay and volume shares along the strongest path
+    pub exposure: f64,
+    
+    /// The node's own risk level, when it is an assessed account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_level: Option<AmlRiskLevel>,
+    
+    /// Risk the node contributes to the account
+    pub contribution: f64,
+}
+
+/// An account's counterparty neighborhood and the risk propagated from it
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskNeighborhood {
+    pub account_id: AccountId,
+    
+    /// The account's own risk score, from its latest check
+    pub own_score: f64,
+    
+    /// When the account's own risk was recorded, if it has been checked
+    pub assessed_at: Option<DateTime<Utc>>,
+    
+    /// Largest contribution of any neighbor
+    pub propagated_score: f64,
+    
+    /// Higher of the own and propagated scores
+    pub score: f64,
+    pub risk_level: AmlRiskLevel,
+    
+    /// Neighbors within `max_hops`, largest contribution first
+    pub neighbors: Vec<NeighborRisk>,
+    
+    /// Whether the walk stopped at `max_neighborhood_nodes`
+    pub truncated: bool,
+}
+
+/// Path to a node on the frontier, ordered by exposure
+#[derive(Debug, PartialEq)]
+struct Frontier {
+    exposure: f64,
+    hops: usize,
+    node: String,
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.exposure.total_cmp(&other.exposure).then_with(|| other.hops.cmp(&self.hops))
+    }
+}
+
+/// Graph of accounts and counterparties propagating risk between them
+pub struct CounterpartyGraph {
+    /// Live configuration holding the propagation settings and risk thresholds
+    config: Arc<LiveConfig>,
+    
+    graph: RwLock<Graph>,
+}
+
+impl CounterpartyGraph {
+    /// Create an empty graph
+    pub fn new(config: Arc<LiveConfig>) -> Self {
+        Self {
+            config,
+            graph: RwLock::new(Graph::default()),
+        }
+    }
+    
+    /// Add the volume of monitored transactions to the edges they traverse
+    ///
+    /// Transactions without a counterparty, or with the account itself as
+    /// counterparty, are ignored.
+    pub async fn record_transactions(&self, transactions: &[MonitoredTransaction]) {
+        let mut graph = self.graph.write().await;
+        for transaction in transactions {
+            let Some(counterparty) = transaction.counterparty.as_deref() else {
+                continue;
+            };
+            let account = graph.resolve(transaction.account_id.to_string());
+            let counterparty = graph.resolve(node_key(counterparty));
+            if account != counterparty && transaction.amount > 0 {
+                graph.add_volume(&account, &counterparty, transaction.amount as f64);
+            }
+        }
+    }
+    
+    /// Merge an address linked to an account into the account's node
+    pub async fn link_address(&self, account_id: &AccountId, address: &LinkedAddress) {
+        let mut graph = self.graph.write().await;
+        let account = graph.resolve(account_id.to_string());
+        let address = node_key(&address.address);
+        if address == account || graph.aliases.contains_key(&address) {
+            return;
+        }
+        graph.merge(&address, &account);
+        graph.aliases.insert(address, account);
+    }
+    
+    /// Record an account's own risk level from a compliance check
+    pub async fn record_risk(&self, account_id: &AccountId, level: AmlRiskLevel, assessed_at: DateTime<Utc>) {
+        let mut graph = self.graph.write().await;
+        let node = graph.resolve(account_id.to_string());
+        graph.risk.insert(node, NodeRisk { level, assessed_at });
+    }
+    
+    /// An account's neighborhood, or `None` when propagation is disabled
+    pub async fn neighborhood(&self, account_id: &AccountId) -> Option<RiskNeighborhood> {
+        let compliance = self.config.compliance();
+        let config = &compliance.aml.counterparty_risk;
+        if !config.enabled {
+            return None;
+        }
+        let thresholds = &compliance.aml.risk_thresholds;
+        
+        let graph = self.graph.read().await;
+        let origin = graph.resolve(account_id.to_string());
+        let (mut neighbors, truncated) = walk(&graph, &origin, config);
+        for neighbor in &mut neighbors {
+            let risk = graph.risk.get(&neighbor.node);
+            neighbor.risk_level = risk.map(|risk| risk.level.clone());
+            neighbor.contribution = risk.map_or(0.0, |risk| level_score(&risk.level, thresholds) * neighbor.exposure);
+        }
+        neighbors.sort_by(|a, b| b.contribution.total_cmp(&a.contribution).then_with(|| a.node.cmp(&b.node)));
+        
+        let own = graph.risk.get(&origin);
+        let own_score = own.map_or(0.0, |risk| level_score(&risk.level, thresholds));
+        let propagated_score = neighbors.first().map_or(0.0, |neighbor| neighbor.contribution);
+        let score = own_score.max(propagated_score);
+        Some(RiskNeighborhood {
+            account_id: account_id.clone(),
+            own_score,
+            assessed_at: own.map(|risk| risk.assessed_at),
+            propagated_score,
+            score,
+            risk_level: risk_level(score, thresholds),
+            neighbors,
+            truncated,
+        })
+    }
+    
+    /// Raise an attestation's AML risk level to the risk propagated from counterparties
+    ///
+    /// Propagated risk never lowers the level of the attestation.
+    pub async fn apply(&self, attestation: &mut ComplianceAttestation) -> Option<RiskNeighborhood> {
+        let neighborhood = self.neighborhood(&attestation.account_id).await?;
+        let thresholds = &self.config.compliance().aml.risk_thresholds;
+        let propagated = risk_level(neighborhood.propagated_score, thresholds);
+        if propagated > attestation.aml_risk_level {
+            tracing::debug!(
+                account_id = %attestation.account_id,
+                score = neighborhood.propagated_score,
+                level = ?propagated,
+                "counterparty risk raised AML risk level"
+            );
+            attestation.aml_risk_level = propagated;
+        }
+        Some(neighborhood)
+    }
+}
+
+/// Strongest path to each node within `max_hops` of `origin`
+///
+/// Exposure only shrinks along a path, so nodes are settled in order of
+/// decreasing exposure, as in Dijkstra's algorithm.
+fn walk(graph: &Graph, origin: &str, config: &CounterpartyRiskConfig) -> (Vec<NeighborRisk>, bool) {
+    let mut settled: HashMap<String, NeighborRisk> = HashMap::new();
+    let mut frontier = BinaryHeap::from([Frontier {
+        exposure: 1.0,
+        hops: 0,
+        node: origin.to_string(),
+    }]);
+    let mut truncated = false;
+    
+    while let Some(Frontier { exposure, hops, node }) = frontier.pop() {
+        if node != origin {
+            if settled.contains_key(&node) {
+                continue;
+            }
+            if settled.len() >= config.max_neighborhood_nodes {
+                truncated = true;
+                break;
+            }
+            let direct_volume = (hops == 1).then(|| graph.edges[origin][&node]);
+            settled.insert(
+                node.clone(),
+                NeighborRisk {
+                    node: node.clone(),
+                    hops,
+                    direct_volume,
+                    exposure,
+                    risk_level: None,
+                    contribution: 0.0,
+                },
+            );
+        } else if hops > 0 {
+            continue;
+        }
+        if hops >= config.max_hops {
+            continue;
+        }
+        
+        let total = graph.volume.get(&node).copied().unwrap_or_default();
+        if total <= 0.0 {
+            continue;
+        }
+        for (neighbor, amount) in graph.edges.get(&node).into_iter().flatten() {
+            let share = amount / total;
+            if share < config.min_share || neighbor == origin || settled.contains_key(neighbor) {
+                continue;
+            }
+            frontier.push(Frontier {
+                exposure: exposure * config.decay * share,
+                hops: hops + 1,
+                node: neighbor.clone(),
+            });
+        }
+    }
+    (settled.into_values().collect(), truncated)
+}
+
+/// Representative score of a risk level, the lower bound of its band
+fn level_score(level: &AmlRiskLevel, thresholds: &RiskThresholds) -> f64 {
+    match level {
+        AmlRiskLevel::Low => 0.0,
+        AmlRiskLevel::Medium => thresholds.low,
+        AmlRiskLevel::High => thresholds.medium,
+        AmlRiskLevel::Critical => thresholds.high,
+    }
+}
+
+/// Node of an account id or address, with account ids normalized
+fn node_key(raw: &str) -> String {
+    AccountId::parse(raw).map_or_else(|_| raw.trim().to_string(), |account_id| account_id.to_string())
+}
@@ -0,0 +1,345 @@
+//! Country and geographic risk inputs for AML scoring
+//!
+//! Geographic risk combines the country of residence, the identity document
+//! issuing country, and counterparty geography. Each country's risk comes from
+//! a per-country override in `AmlConfig`, or else the higher of its FATF list
+//! weight and its normalized Basel AML Index score.
+
+use super::claims::JurisdictionClass;
+use crate::config::{CountryRiskConfig, RiskThresholds};
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Version of the built-in FATF lists
+pub const BUILTIN_DATASET_VERSION: &str = "fatf-2025-06";
+
+/// FATF high-risk jurisdictions subject to a call for action
+const FATF_BLACK_LIST: &[&str] = &["IR", "KP", "MM"];
+
+/// FATF jurisdictions under increased monitoring
+const FATF_GREY_LIST: &[&str] = &[
+    "AO", "BF", "BG", "BO", "CD", "CI", "CM", "DZ", "HT", "KE", "LA", "LB", "MC", "MZ", "NA", "NG", "NP", "SS",
+    "SY", "VE", "VG", "VN", "YE", "ZA",
+];
+
+/// Country-risk reference data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryRiskDataset {
+    /// Dataset version, recorded on every assessment
+    pub version: String,
+    
+    /// FATF call-for-action jurisdictions (ISO 3166-1 alpha-2)
+    pub fatf_black_list: BTreeSet<String>,
+    
+    /// FATF increased-monitoring jurisdictions (ISO 3166-1 alpha-2)
+    pub fatf_grey_list: BTreeSet<String>,
+    
+    /// Basel AML Index scores on the published 0-10 scale
+    #[serde(default)]
+    pub basel_scores: HashMap<String, f64>,
+}
+
+impl CountryRiskDataset {
+    /// The FATF lists compiled into the binary, without Basel scores
+    pub fn builtin() -> Self {
+        Self {
+            version: BUILTIN_DATASET_VERSION.to_string(),
+            fatf_black_list: FATF_BLACK_LIST.iter().map(|c| c.to_string()).collect(),
+            fatf_grey_list: FATF_GREY_LIST.iter().map(|c| c.to_string()).collect(),
+            basel_scores: HashMap::new(),
+        }
+    }
+    
+    /// Load a dataset from a JSON file
+    pub fn load(path: &str) -> Result<Self> {
+        let dataset: Self = serde_json::from_slice(&std::fs::read(path)?)?;
+        let codes = dataset
+            .fatf_black_list
+            .iter()
+            .chain(&dataset.fatf_grey_list)
+            .chain(dataset.basel_scores.keys());
+        for code in codes {
+            normalize_country("dataset", code)?;
+        }
+        if let Some((code, _)) = dataset.basel_scores.iter().find(|(_, score)| !(0.0..=10.0).contains(*score)) {
+            return Err(ComplianceError::validation(
+                "basel_scores",
+                format!("score for {} must be between 0 and 10", code),
+            ));
+        }
+        Ok(dataset)
+    }
+}
+
+/// Where a country enters an account's geographic risk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeographicFactorKind {
+    Residence,
+    DocumentIssuer,
+    Counterparty,
+}
+
+/// Countries associated with an account
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GeographicProfile {
+    /// Country of residence
+    pub residence: Option<String>,
+    
+    /// Country that issued the identity document
+    pub document_issuing_country: Option<String>,
+    
+    /// Countries of known counterparties
+    #[serde(default)]
+    pub counterparty_countries: Vec<String>,
+}
+
+impl GeographicProfile {
+    /// Validate and uppercase every country code
+    pub fn normalized(self) -> Result<Self> {
+        Ok(Self {
+            residence: self.residence.as_deref().map(|c| normalize_country("residence", c)).transpose()?,
+            document_issuing_country: self
+                .document_issuing_country
+                .as_deref()
+                .map(|c| normalize_country("document_issuing_country", c))
+                .transpose()?,
+            counterparty_countries: self
+                .counterparty_countries
+                .iter()
+                .map(|c| normalize_country("counterparty_countries", c))
+                .collect::<Result<BTreeSet<_>>>()?
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+/// Risk contributed by one country
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeographicFactor {
+    pub kind: GeographicFactorKind,
+    pub country: String,
+    
+    /// Country risk in `[0, 1]`
+    pub risk: f64,
+    
+    /// Reasons the country carries risk (e.g. "fatf_black_list", "basel_aml_index")
+    pub sources: Vec<String>,
+}
+
+/// Geographic risk of an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeographicRiskAssessment {
+    /// Combined risk in `[0, 1]`
+    pub score: f64,
+    pub risk_level: AmlRiskLevel,
+    pub factors: Vec<GeographicFactor>,
+    pub dataset_version: String,
+}
+
+/// Service scoring geographic risk from the country-risk dataset
+pub struct CountryRiskService {
+    /// Live configuration holding country weights and factor weights
+    config: Arc<LiveConfig>,
+    
+    dataset: CountryRiskDataset,
+    
+    /// Geographic profile of each account
+    profiles: RwLock<HashMap<AccountId, GeographicProfile>>,
+}
+
+impl CountryRiskService {
+    /// Create the service, loading the configured dataset
+    pub fn new(config: Arc<LiveConfig>) -> Result<Self> {
+        let dataset = match &config.compliance().aml.country_risk.dataset_path {
+            Some(path) => CountryRiskDataset::load(path)?,
+            None => CountryRiskDataset::builtin(),
+        };
+        tracing::info!(
+            version = %dataset.version,
+            black_list = dataset.fatf_black_list.len(),
+            grey_list = dataset.fatf_grey_list.len(),
+            basel_scores = dataset.basel_scores.len(),
+            "country risk dataset loaded"
+        );
+        
+        Ok(Self {
+            config,
+            dataset,
+            profiles: RwLock::new(HashMap::new()),
+        })
+    }
+    
+    /// Version of the loaded dataset
+    pub fn dataset_version(&self) -> &str {
+        &self.dataset.version
+    }
+    
+    /// Record the geographic profile of an account
+    pub async fn set_profile(&self, account_id: &AccountId, profile: GeographicProfile) -> Result<GeographicProfile> {
+        let profile = profile.normalized()?;
+        self.profiles.write().await.insert(account_id.clone(), profile.clone());
+        Ok(profile)
+    }
+    
+    /// Get the geographic profile of an account
+    pub async fn profile(&self, account_id: &AccountId) -> Option<GeographicProfile> {
+        self.profiles.read().await.get(account_id).cloned()
+    }
+    
+    /// Risk of a single country and the reasons for it
+    pub fn country_risk(&self, country: &str) -> (f64, Vec<String>) {
+        let compliance = self.config.compliance();
+        self.risk_of(&compliance.aml.country_risk, country)
+    }
+    
+    fn risk_of(&self, config: &CountryRiskConfig, country: &str) -> (f64, Vec<String>) {
+        if let Some(weight) = config.country_weights.get(country) {
+            return (*weight, vec!["configured_weight".to_string()]);
+        }
+        
+        let mut risk: f64 = 0.0;
+        let mut sources = Vec::new();
+        if self.dataset.fatf_black_list.contains(country) {
+            risk = risk.max(config.fatf_black_list_weight);
+            sources.push("fatf_black_list".to_string());
+        }
+        if self.dataset.fatf_grey_list.contains(country) {
+            risk = risk.max(config.fatf_grey_list_weight);
+            sources.push("fatf_grey_list".to_string());
+        }
+        if let Some(score) = self.dataset.basel_scores.get(country) {
+            risk = risk.max(score / 10.0);
+            sources.push("basel_aml_index".to_string());
+        }
+        (risk, sources)
+    }
+    
+    /// Score a geographic profile
+    ///
+    /// The score is the weighted mean of the factors present, so missing data
+    /// does not dilute known risk. Any factor in a FATF call-for-action country
+    /// raises the score to at least the high-risk threshold.
+    pub fn assess(&self, profile: &GeographicProfile) -> Option<GeographicRiskAssessment> {
+        let compliance = self.config.compliance();
+        let config = &compliance.aml.country_risk;
+        if !config.enabled {
+            return None;
+        }
+        
+        let factor = |kind, country: &str| {
+            let (risk, sources) = self.risk_of(config, country);
+            GeographicFactor {
+                kind,
+                country: country.to_string(),
+                risk,
+                sources,
+            }
+        };
+        
+        let mut factors = Vec::new();
+        let mut weighted = Vec::new();
+        if let Some(country) = &profile.residence {
+            let f = factor(GeographicFactorKind::Residence, country);
+            weighted.push((config.residence_weight, f.risk));
+            factors.push(f);
+        }
+        if let Some(country) = &profile.document_issuing_country {
+            let f = factor(GeographicFactorKind::DocumentIssuer, country);
+            weighted.push((config.document_issuer_weight, f.risk));
+            factors.push(f);
+        }
+        let counterparties: Vec<_> = profile
+            .counterparty_countries
+            .iter()
+            .map(|country| factor(GeographicFactorKind::Counterparty, country))
+            .collect();
+        if let Some(riskiest) = counterparties.iter().map(|f| f.risk).max_by(f64::total_cmp) {
+            weighted.push((config.counterparty_weight, riskiest));
+        }
+        factors.extend(counterparties);
+        
+        let total_weight: f64 = weighted.iter().map(|(weight, _)| weight).sum();
+        let mut score = if total_weight > 0.0 {
+            weighted.iter().map(|(weight, risk)| weight * risk).sum::<f64>() / total_weight
+        } else {
+            0.0
+        };
+        
+        let thresholds = &compliance.aml.risk_thresholds;
+        if factors.iter().any(|f| self.dataset.fatf_black_list.contains(&f.country)) {
+            score = score.max(thresholds.high);
+        }
+        
+        Some(GeographicRiskAssessment {
+            score,
+            risk_level: risk_level(score, thresholds),
+            factors,
+            dataset_version: self.dataset.version.clone(),
+        })
+    }
+    
+    /// FATF classification of an account's country of residence
+    pub async fn jurisdiction_class(&self, account_id: &AccountId) -> JurisdictionClass {
+        match self.profile(account_id).await.and_then(|profile| profile.residence) {
+            None => JurisdictionClass::Unassessed,
+            Some(country) if self.dataset.fatf_black_list.contains(&country) => JurisdictionClass::CallForAction,
+            Some(country) if self.dataset.fatf_grey_list.contains(&country) => JurisdictionClass::IncreasedMonitoring,
+            Some(_) => JurisdictionClass::Standard,
+        }
+    }
+    
+    /// Score the recorded geographic profile of an account
+    pub async fn assess_account(&self, account_id: &AccountId) -> Option<GeographicRiskAssessment> {
+        let profile = self.profile(account_id).await?;
+        self.assess(&profile)
+    }
+    
+    /// Raise an attestation's AML risk level to the account's geographic risk
+    ///
+    /// Geographic risk never lowers the level reported by the AML provider.
+    pub async fn apply(&self, attestation: &mut ComplianceAttestation) -> Option<GeographicRiskAssessment> {
+        let assessment = self.assess_account(&attestation.account_id).await?;
+        if assessment.risk_level > attestation.aml_risk_level {
+            tracing::debug!(
+                account_id = %attestation.account_id,
+                score = assessment.score,
+                level = ?assessment.risk_level,
+                "geographic risk raised AML risk level"
+            );
+            attestation.aml_risk_level = assessment.risk_level.clone();
+        }
+        Some(assessment)
+    }
+}
+
+/// Map a risk score to a level using the configured thresholds
+pub fn risk_level(score: f64, thresholds: &RiskThresholds) -> AmlRiskLevel {
+    if score >= thresholds.high {
+        AmlRiskLevel::Critical
+    } else if score >= thresholds.medium {
+        AmlRiskLevel::High
+    } else if score >= thresholds.low {
+        AmlRiskLevel::Medium
+    } else {
+        AmlRiskLevel::Low
+    }
+}
+
+/// Validate an ISO 3166-1 alpha-2 code and uppercase it
+pub(crate) fn normalize_country(field: &str, code: &str) -> Result<String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Err(ComplianceError::validation(
+            field,
+            format!("{:?} is not an ISO 3166-1 alpha-2 country code", code),
+        ));
+    }
+    Ok(code)
+}
@@ -0,0 +1,293 @@
+//! Signed chain-of-custody archives of an account for regulators
+//!
+//! An archive is a ZIP holding everything the deployment recorded about one
+//! account: its attestation lifecycle, every authorization decision with a
+//! hash of the inputs it was made from, every audited action by operators,
+//! clients and the system, every proof generated, and every webhook sent.
+//!
+//! `manifest.json` lists each other file with its SHA-256 hash and is
+//! serialized as canonical JSON (RFC 8785); `manifest.sig` holds the
+//! hex-encoded Ed25519 signature over those bytes. A regulator checks the
+//! signature against the deployment's published key, then each file against
+//! its hash, without trusting the party handing the archive over. Audit
+//! entries additionally carry the hash chain linking them into the full log,
+//! whose head at export time the manifest records.
+
+use super::attestation_events::RecordedEvent;
+use super::velocity::{AuthorizationDecision, DecisionInputs, VelocityService};
+use super::{ComplianceService, PROOF_ISSUED_ACTION};
+use crate::audit::{AuditEntry, AuditQuery, ChainVerification};
+use crate::crypto::signing::SIGNATURE_LENGTH;
+use crate::crypto::{canonical_json, AttestationSigner, TrustedKeys};
+use crate::storage::{OutboxMessage, OutboxRepo};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Current archive format version
+pub const CUSTODY_VERSION: u8 = 1;
+
+/// Name of the signed manifest in the archive
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Name of the manifest's detached signature in the archive
+pub const SIGNATURE_FILE: &str = "manifest.sig";
+
+/// Content type of an archive
+pub const CUSTODY_CONTENT_TYPE: &str = "application/zip";
+
+/// A file of the archive as listed in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub name: String,
+    /// Hex-encoded SHA-256 of the file's bytes
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Contents and provenance of an archive, covered by its signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustodyManifest {
+    pub version: u8,
+    pub export_id: Uuid,
+    pub account_id: AccountId,
+    /// Deployment the records were kept by
+    pub deployment_id: String,
+    pub exported_at: DateTime<Utc>,
+    /// Hash of the last audit log entry at export time
+    pub audit_head_hash: String,
+    /// Whether the whole audit log verified at export time
+    pub audit_chain_valid: bool,
+    pub files: Vec<ManifestFile>,
+    /// Identifier of the key that signed the manifest
+    pub key_id: String,
+    /// Hex-encoded public key of `key_id`, for out-of-band trust decisions
+    pub public_key: String,
+}
+
+/// An authorization decision with the hash of its input snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustodyDecision {
+    #[serde(flatten)]
+    pub decision: AuthorizationDecision,
+    /// File of the archive holding the inputs; absent for a decision whose
+    /// inputs were not kept
+    pub inputs_file: Option<String>,
+    /// Hex-encoded SHA-256 of the inputs file
+    pub inputs_sha256: Option<String>,
+}
+
+/// A built archive
+#[derive(Debug, Clone)]
+pub struct CustodyArchive {
+    pub manifest: CustodyManifest,
+    /// The ZIP file
+    pub bytes: Vec<u8>,
+}
+
+/// Where the records of an archive are read from
+pub struct CustodySources<'a> {
+    pub compliance: &'a ComplianceService,
+    pub velocity: &'a VelocityService,
+    pub outbox: &'a dyn OutboxRepo,
+}
+
+fn archive_error(e: zip::result::ZipError) -> ComplianceError {
+    ComplianceError::internal(format!("custody archive: {}", e))
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::crypto(format!("invalid custody archive: {}", reason.into()))
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Files of an archive other than the manifest and its signature, in order
+#[derive(Default)]
+struct Contents {
+    files: Vec<(String, Vec<u8>)>,
+    listed: Vec<ManifestFile>,
+}
+
+impl Contents {
+    fn add(&mut self, name: impl Into<String>, bytes: Vec<u8>) -> ManifestFile {
+        let file = ManifestFile {
+            name: name.into(),
+            sha256: sha256(&bytes),
+            size: bytes.len() as u64,
+        };
+        self.files.push((file.name.clone(), bytes));
+        self.listed.push(file.clone());
+        file
+    }
+    
+    fn add_json<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<()> {
+        self.add(name, serde_json::to_vec_pretty(value)?);
+        Ok(())
+    }
+}
+
+/// Everything recorded about an account, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct CustodyRecords {
+    pub events: Vec<RecordedEvent>,
+    /// Authorization decisions with their inputs, where kept
+    pub decisions: Vec<(AuthorizationDecision, Option<DecisionInputs>)>,
+    /// Audit entries concerning the account, proofs generated among them
+    pub audit: Vec<AuditEntry>,
+    pub webhooks: Vec<OutboxMessage>,
+}
+
+impl CustodyRecords {
+    /// Gather the records of an account
+    pub async fn gather(sources: CustodySources<'_>, account_id: &AccountId) -> Result<Self> {
+        let query = AuditQuery {
+            account_id: Some(account_id.clone()),
+            ..AuditQuery::default()
+        };
+        let mut decisions = Vec::new();
+        for decision in sources.velocity.decisions_for(account_id).await.into_iter().rev() {
+            let inputs = sources.velocity.decision_inputs(decision.id).await;
+            decisions.push((decision, inputs));
+        }
+        Ok(Self {
+            events: sources.compliance.events.events(account_id, None).await?,
+            decisions,
+            audit: sources.compliance.audit.export(&query).await?,
+            webhooks: sources.outbox.for_account(account_id).await?,
+        })
+    }
+}
+
+impl CustodyArchive {
+    /// Package and sign an account's records
+    ///
+    /// `audit_chain` is the verification of the whole audit log at export
+    /// time, whose head anchors the exported entries.
+    pub fn seal(
+        account_id: &AccountId,
+        deployment_id: &str,
+        records: CustodyRecords,
+        audit_chain: ChainVerification,
+        signer: &AttestationSigner,
+        exported_at: DateTime<Utc>,
+    ) -> Result<Self> {
+        let mut contents = Contents::default();
+        let mut decisions = Vec::with_capacity(records.decisions.len());
+        for (decision, inputs) in records.decisions {
+            let inputs = match inputs {
+                Some(inputs) => {
+                    let bytes = serde_json::to_vec_pretty(&inputs)?;
+                    Some(contents.add(format!("decisions/{}.json", decision.id), bytes))
+                }
+                None => None,
+            };
+            decisions.push(CustodyDecision {
+                decision,
+                inputs_sha256: inputs.as_ref().map(|file| file.sha256.clone()),
+                inputs_file: inputs.map(|file| file.name),
+            });
+        }
+        let proofs: Vec<&AuditEntry> =
+            records.audit.iter().filter(|entry| entry.action == PROOF_ISSUED_ACTION).collect();
+        contents.add_json("attestation_events.json", &records.events)?;
+        contents.add_json("decisions.json", &decisions)?;
+        contents.add_json("audit_entries.json", &records.audit)?;
+        contents.add_json("proofs.json", &proofs)?;
+        contents.add_json("webhooks.json", &records.webhooks)?;
+        
+        let manifest = CustodyManifest {
+            version: CUSTODY_VERSION,
+            export_id: Uuid::new_v4(),
+            account_id: account_id.clone(),
+            deployment_id: deployment_id.to_string(),
+            exported_at,
+            audit_head_hash: audit_chain.head_hash,
+            audit_chain_valid: audit_chain.valid,
+            files: contents.listed,
+            key_id: signer.key_id().to_string(),
+            public_key: hex::encode(signer.verifying_key().as_bytes()),
+        };
+        let manifest_bytes = canonical_json::to_vec(&manifest)?;
+        let signature = hex::encode(signer.sign(&manifest_bytes)?);
+        
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let files = [
+            (MANIFEST_FILE.to_string(), manifest_bytes),
+            (SIGNATURE_FILE.to_string(), signature.into_bytes()),
+        ];
+        for (name, bytes) in files.into_iter().chain(contents.files) {
+            zip.start_file(name, options).map_err(archive_error)?;
+            zip.write_all(&bytes)?;
+        }
+        let bytes = zip.finish().map_err(archive_error)?.into_inner();
+        Ok(Self { manifest, bytes })
+    }
+}
+
+/// Build and sign the archive of an account
+pub async fn export(
+    sources: CustodySources<'_>,
+    account_id: &AccountId,
+    deployment_id: &str,
+    signer: &AttestationSigner,
+) -> Result<CustodyArchive> {
+    let compliance = sources.compliance;
+    let records = CustodyRecords::gather(sources, account_id).await?;
+    // Verified after gathering, so the head follows every exported entry
+    let audit_chain = compliance.audit.verify_chain().await?;
+    CustodyArchive::seal(account_id, deployment_id, records, audit_chain, signer, compliance.clock.now())
+}
+
+/// Check an archive's signature and every file against the manifest
+///
+/// Fails if the manifest is not signed by a trusted key, a listed file is
+/// missing or altered, or the archive holds a file the manifest does not
+/// list.
+pub fn verify(archive: &[u8], trusted_keys: &TrustedKeys) -> Result<CustodyManifest> {
+    let mut zip = ZipArchive::new(Cursor::new(archive)).map_err(|e| invalid(e.to_string()))?;
+    let mut read = |name: &str| -> Result<Vec<u8>> {
+        let mut file = zip.by_name(name).map_err(|_| invalid(format!("{} is missing", name)))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    };
+    
+    let manifest_bytes = read(MANIFEST_FILE)?;
+    let signature = hex::decode(read(SIGNATURE_FILE)?).map_err(|_| invalid("signature is not hex"))?;
+    if signature.len() != SIGNATURE_LENGTH {
+        return Err(invalid("signature has wrong length"));
+    }
+    let manifest: CustodyManifest =
+        serde_json::from_slice(&manifest_bytes).map_err(|e| invalid(format!("malformed manifest: {}", e)))?;
+    trusted_keys
+        .verify(&manifest.key_id, &manifest_bytes, &signature)
+        .map_err(|e| invalid(e.to_string()))?;
+    if manifest.version != CUSTODY_VERSION {
+        return Err(invalid(format!("unsupported version {}", manifest.version)));
+    }
+    
+    for listed in &manifest.files {
+        let bytes = read(&listed.name)?;
+        if sha256(&bytes) != listed.sha256 || bytes.len() as u64 != listed.size {
+            return Err(invalid(format!("{} does not match the manifest", listed.name)));
+        }
+    }
+    let unlisted = zip.file_names().find(|name| {
+        *name != MANIFEST_FILE && *name != SIGNATURE_FILE && !manifest.files.iter().any(|file| file.name == *name)
+    });
+    if let Some(name) = unlisted {
+        return Err(invalid(format!("{} is not listed in the manifest", name)));
+    }
+    Ok(manifest)
+}
@@ -0,0 +1,312 @@
+//! Device and IP risk signals as inputs to AML scoring
+//!
+//! Clients that run device intelligence or IP reputation lookups can submit
+//! what they found at onboarding, and with each transaction they authorize:
+//! the device fingerprint, the IP address and the country it geolocates to,
+//! and whether the connection came through Tor, a VPN, a proxy, or a hosting
+//! provider. Each account keeps its most recent observations.
+//!
+//! Every signal seen in an account's observations becomes a factor with a
+//! configured risk, alongside the country risk of each IP country, a factor
+//! for IP countries other than the country of residence, and a factor for a
+//! device fingerprint seen on several accounts. The account's device risk is
+//! its riskiest factor, since one anonymized session is enough to matter, and
+//! raises the AML risk level of its next attestation the way geographic risk
+//! does. Ingestion is off unless [`DeviceRiskConfig::enabled`] is set.
+//!
+//! [`DeviceRiskConfig::enabled`]: crate::config::DeviceRiskConfig::enabled
+
+use super::country_risk::{normalize_country, risk_level, CountryRiskService};
+use crate::clock::{system_clock, SharedClock};
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Maximum length of a device fingerprint
+const MAX_FINGERPRINT_LEN: usize = 256;
+
+/// When signals were collected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalContext {
+    Onboarding,
+    Transaction,
+}
+
+/// Device and network signals from one session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceSignals {
+    /// Fingerprint from the client's device intelligence SDK
+    pub device_fingerprint: Option<String>,
+    
+    pub ip_address: Option<IpAddr>,
+    
+    /// Country the IP address geolocates to, as an ISO 3166-1 alpha-2 code
+    pub ip_country: Option<String>,
+    
+    pub tor: bool,
+    pub vpn: bool,
+    pub proxy: bool,
+    
+    /// The IP address belongs to a hosting provider or data center
+    pub hosting: bool,
+}
+
+impl DeviceSignals {
+    /// Validate the signals and uppercase the IP country
+    pub fn normalized(self) -> Result<Self> {
+        let device_fingerprint = match self.device_fingerprint.map(|f| f.trim().to_string()) {
+            Some(fingerprint) if fingerprint.is_empty() || fingerprint.len() > MAX_FINGERPRINT_LEN => {
+                return Err(ComplianceError::validation(
+                    "device_fingerprint",
+                    format!("must be 1 to {} characters", MAX_FINGERPRINT_LEN),
+                ));
+            }
+            fingerprint => fingerprint,
+        };
+        Ok(Self {
+            device_fingerprint,
+            ip_country: self.ip_country.as_deref().map(|c| normalize_country("ip_country", c)).transpose()?,
+            ..self
+        })
+    }
+}
+
+/// Signals recorded for an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceObservation {
+    pub context: SignalContext,
+    pub signals: DeviceSignals,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Signal contributing to an account's device risk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceFactorKind {
+    Tor,
+    Vpn,
+    Proxy,
+    Hosting,
+    /// Country risk of a country the account's IP addresses geolocate to
+    IpCountry,
+    /// An IP country differs from the country of residence
+    ResidenceMismatch,
+    /// A device fingerprint is shared with other accounts
+    SharedDevice,
+}
+
+/// Risk contributed by one signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceFactor {
+    pub kind: DeviceFactorKind,
+    
+    /// Risk in `[0, 1]`
+    pub risk: f64,
+    
+    /// Country or number of accounts behind the factor, where there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    
+    /// Observations the signal was seen in
+    pub observations: usize,
+}
+
+/// Device and IP risk of an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceRiskAssessment {
+    /// Risk of the riskiest factor, in `[0, 1]`
+    pub score: f64,
+    pub risk_level: AmlRiskLevel,
+    pub factors: Vec<DeviceFactor>,
+    pub observations: usize,
+    pub last_observed_at: DateTime<Utc>,
+}
+
+/// Service recording device and IP signals and scoring the risk they carry
+pub struct DeviceRiskService {
+    /// Live configuration holding the signal weights
+    config: Arc<LiveConfig>,
+    
+    /// Country risk of IP countries and accounts' countries of residence
+    country_risk: Arc<CountryRiskService>,
+    
+    clock: SharedClock,
+    
+    /// Most recent observations of each account
+    observations: RwLock<HashMap<AccountId, VecDeque<DeviceObservation>>>,
+    
+    /// Accounts each device fingerprint was seen on
+    devices: RwLock<HashMap<String, BTreeSet<AccountId>>>,
+}
+
+impl DeviceRiskService {
+    /// Create the service without observations
+    pub fn new(config: Arc<LiveConfig>, country_risk: Arc<CountryRiskService>) -> Self {
+        Self {
+            config,
+            country_risk,
+            clock: system_clock(),
+            observations: RwLock::new(HashMap::new()),
+            devices: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Whether signals are accepted
+    pub fn enabled(&self) -> bool {
+        self.config.compliance().aml.device_risk.enabled
+    }
+    
+    /// Record signals for an account, or `None` when ingestion is disabled
+    pub async fn record(
+        &self,
+        account_id: &AccountId,
+        context: SignalContext,
+        signals: DeviceSignals,
+    ) -> Result<Option<DeviceObservation>> {
+        let compliance = self.config.compliance();
+        let config = &compliance.aml.device_risk;
+        let signals = signals.normalized()?;
+        if !config.enabled {
+            return Ok(None);
+        }
+        
+        if let Some(fingerprint) = &signals.device_fingerprint {
+            self.devices
+                .write()
+                .await
+                .entry(fingerprint.clone())
+                .or_default()
+                .insert(account_id.clone());
+        }
+        let observation = DeviceObservation {
+            context,
+            signals,
+            observed_at: self.clock.now(),
+        };
+        let mut observations = self.observations.write().await;
+        let history = observations.entry(account_id.clone()).or_default();
+        history.push_back(observation.clone());
+        while history.len() > config.max_observations {
+            history.pop_front();
+        }
+        Ok(Some(observation))
+    }
+    
+    /// Recorded observations of an account, most recent first
+    pub async fn observations(&self, account_id: &AccountId) -> Vec<DeviceObservation> {
+        self.observations
+            .read()
+            .await
+            .get(account_id)
+            .map(|history| history.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+    
+    /// Score the recorded observations of an account, or `None` when device
+    /// risk is disabled or nothing was recorded
+    pub async fn assess_account(&self, account_id: &AccountId) -> Option<DeviceRiskAssessment> {
+        let compliance = self.config.compliance();
+        let config = &compliance.aml.device_risk;
+        if !config.enabled {
+            return None;
+        }
+        let observations = self.observations(account_id).await;
+        let last_observed_at = observations.first()?.observed_at;
+        let residence = self.country_risk.profile(account_id).await.and_then(|profile| profile.residence);
+        
+        let mut factors: Vec<DeviceFactor> = Vec::new();
+        // One factor per kind, and per country for IP countries
+        let mut add = |kind, risk: f64, detail: Option<String>| {
+            match factors.iter_mut().find(|f| f.kind == kind && f.detail == detail) {
+                Some(factor) => factor.observations += 1,
+                None => factors.push(DeviceFactor {
+                    kind,
+                    risk,
+                    detail,
+                    observations: 1,
+                }),
+            }
+        };
+        for observation in &observations {
+            let signals = &observation.signals;
+            for (seen, kind, weight) in [
+                (signals.tor, DeviceFactorKind::Tor, config.tor_weight),
+                (signals.vpn, DeviceFactorKind::Vpn, config.vpn_weight),
+                (signals.proxy, DeviceFactorKind::Proxy, config.proxy_weight),
+                (signals.hosting, DeviceFactorKind::Hosting, config.hosting_weight),
+            ] {
+                if seen {
+                    add(kind, weight, None);
+                }
+            }
+            if let Some(country) = &signals.ip_country {
+                let (risk, _) = self.country_risk.country_risk(country);
+                add(DeviceFactorKind::IpCountry, risk, Some(country.clone()));
+                if residence.as_ref().is_some_and(|residence| residence != country) {
+                    add(DeviceFactorKind::ResidenceMismatch, config.residence_mismatch_weight, None);
+                }
+            }
+        }
+        
+        let fingerprints: BTreeSet<&String> =
+            observations.iter().filter_map(|o| o.signals.device_fingerprint.as_ref()).collect();
+        let devices = self.devices.read().await;
+        let shared = fingerprints
+            .into_iter()
+            .filter_map(|fingerprint| Some((fingerprint, devices.get(fingerprint)?.len())))
+            .filter(|(_, accounts)| *accounts >= config.shared_device_accounts)
+            .max_by_key(|(_, accounts)| *accounts);
+        if let Some((fingerprint, accounts)) = shared {
+            factors.push(DeviceFactor {
+                kind: DeviceFactorKind::SharedDevice,
+                risk: config.shared_device_weight,
+                detail: Some(format!("{} accounts", accounts)),
+                observations: observations
+                    .iter()
+                    .filter(|o| o.signals.device_fingerprint.as_ref() == Some(fingerprint))
+                    .count(),
+            });
+        }
+        drop(devices);
+        
+        let score = factors.iter().map(|f| f.risk).fold(0.0, f64::max);
+        Some(DeviceRiskAssessment {
+            score,
+            risk_level: risk_level(score, &compliance.aml.risk_thresholds),
+            factors,
+            observations: observations.len(),
+            last_observed_at,
+        })
+    }
+    
+    /// Raise an attestation's AML risk level to the account's device risk
+    ///
+    /// Device risk never lowers the level reported by the AML provider.
+    pub async fn apply(&self, attestation: &mut ComplianceAttestation) -> Option<DeviceRiskAssessment> {
+        let assessment = self.assess_account(&attestation.account_id).await?;
+        if assessment.risk_level > attestation.aml_risk_level {
+            tracing::debug!(
+                account_id = %attestation.account_id,
+                score = assessment.score,
+                level = ?assessment.risk_level,
+                "device risk raised AML risk level"
+            );
+            attestation.aml_risk_level = assessment.risk_level.clone();
+        }
+        Some(assessment)
+    }
+}
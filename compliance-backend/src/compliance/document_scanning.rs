@@ -0,0 +1,151 @@
+//! Malware scanning of uploaded identity documents
+//!
+//! Documents are scanned before they reach the document vault. A document
+//! the scanner flags is quarantined instead: held apart from clean documents
+//! for review and never handed to KYC checks.
+
+use crate::config::DocumentScannerBackend;
+use crate::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Largest chunk streamed to clamd at a time
+const CLAMD_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Outcome of scanning a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum ScanVerdict {
+    Clean,
+    
+    /// Malware or otherwise unsafe content was found
+    Flagged { reason: String },
+}
+
+/// A scanner for malware in uploaded documents
+pub trait DocumentScanner: Send + Sync {
+    /// Scanner name used in logs and quarantine records
+    fn name(&self) -> &'static str;
+    
+    /// Scan a document
+    fn scan<'a>(&'a self, content_type: &'a str, bytes: &'a [u8]) -> BoxFuture<'a, Result<ScanVerdict>>;
+}
+
+/// Build a scanner from configuration
+pub fn from_config(backend: &DocumentScannerBackend) -> Arc<dyn DocumentScanner> {
+    match backend.clone() {
+        DocumentScannerBackend::Clamd { address } => Arc::new(ClamdScanner::new(address)),
+        DocumentScannerBackend::Http { url, api_key } => {
+            Arc::new(HttpScanner::new(reqwest::Client::new(), url, api_key))
+        }
+    }
+}
+
+/// Scans documents with a ClamAV daemon over its TCP socket
+pub struct ClamdScanner {
+    address: String,
+}
+
+impl ClamdScanner {
+    /// Create a scanner for the clamd listening on `address` (host:port)
+    pub fn new(address: String) -> Self {
+        Self { address }
+    }
+}
+
+impl DocumentScanner for ClamdScanner {
+    fn name(&self) -> &'static str {
+        "clamd"
+    }
+    
+    fn scan<'a>(&'a self, _content_type: &'a str, bytes: &'a [u8]) -> BoxFuture<'a, Result<ScanVerdict>> {
+        Box::pin(async move {
+            let mut stream = tokio::net::TcpStream::connect(&self.address).await?;
+            stream.write_all(b"zINSTREAM\0").await?;
+            for chunk in bytes.chunks(CLAMD_CHUNK_BYTES) {
+                stream.write_all(&(chunk.len() as u32).to_be_bytes()).await?;
+                stream.write_all(chunk).await?;
+            }
+            stream.write_all(&0u32.to_be_bytes()).await?;
+            
+            let mut reply = Vec::new();
+            stream.read_to_end(&mut reply).await?;
+            let reply = String::from_utf8_lossy(&reply);
+            let reply = reply.trim_end_matches(['\0', '\n']).trim_start_matches("stream:").trim();
+            if reply == "OK" {
+                Ok(ScanVerdict::Clean)
+            } else if let Some(signature) = reply.strip_suffix(" FOUND") {
+                Ok(ScanVerdict::Flagged {
+                    reason: signature.trim().to_string(),
+                })
+            } else {
+                Err(scanner_error(self.name(), reply))
+            }
+        })
+    }
+}
+
+/// Scans documents with an HTTP scanning service
+///
+/// The document is posted as the request body with its content type; the
+/// service answers `{"infected": bool, "signature": "..."}`.
+pub struct HttpScanner {
+    http: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpScanResult {
+    infected: bool,
+    #[serde(default)]
+    signature: Option<String>,
+}
+
+impl HttpScanner {
+    /// Create an HTTP scanner
+    pub fn new(http: reqwest::Client, url: String, api_key: Option<String>) -> Self {
+        Self { http, url, api_key }
+    }
+}
+
+impl DocumentScanner for HttpScanner {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+    
+    fn scan<'a>(&'a self, content_type: &'a str, bytes: &'a [u8]) -> BoxFuture<'a, Result<ScanVerdict>> {
+        Box::pin(async move {
+            let mut request = self
+                .http
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(bytes.to_vec());
+            if let Some(api_key) = &self.api_key {
+                request = request.bearer_auth(api_key);
+            }
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(scanner_error(self.name(), format!("scan returned {}", response.status())));
+            }
+            
+            let result: HttpScanResult = response.json().await?;
+            Ok(if result.infected {
+                ScanVerdict::Flagged {
+                    reason: result.signature.unwrap_or_else(|| "infected".to_string()),
+                }
+            } else {
+                ScanVerdict::Clean
+            })
+        })
+    }
+}
+
+pub(crate) fn scanner_error(scanner: &str, reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::ProviderUnavailable {
+        provider: "document_scanner".to_string(),
+        reason: format!("{}: {}", scanner, reason.into()),
+    }
+}
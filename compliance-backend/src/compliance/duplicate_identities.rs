@@ -0,0 +1,489 @@
+//! Detection of one person onboarding under several accounts
+//!
+//! Clients submit the identity attributes an account was verified with. They
+//! are normalized and reduced to keyed fingerprints, one per identifying
+//! signal: each identity document number, and the name together with the
+//! full date of birth. Only the fingerprints are kept. They are keyed from
+//! the deployment's JWT secret, so they cannot be reversed by hashing
+//! candidate names or document numbers without it.
+//!
+//! A fingerprint shared with another of the client's accounts is always a
+//! match. Accounts of another client only match when both clients are listed
+//! in [`DuplicateDetectionConfig::cross_client`] and both end users consented
+//! to cross-client matching; the submitting client then learns how many such
+//! matches there are, but not which accounts or clients they are.
+//!
+//! Matches open a fraud case, or extend an open case involving one of the
+//! accounts, and raise a [`AlertKind::DuplicateIdentity`] alert for
+//! operators. A dismissed case is not reopened by the same accounts matching
+//! again.
+//!
+//! [`AlertKind::DuplicateIdentity`]: crate::alerts::AlertKind::DuplicateIdentity
+
+use super::screening::attributes::document_number;
+use super::screening::normalize::NameForm;
+use crate::alerts::{Alert, AlertManager};
+use crate::clock::{system_clock, SharedClock};
+use crate::config::DuplicateDetectionConfig;
+use crate::reload::LiveConfig;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Context the fingerprint key is derived from the deployment secret in
+const FINGERPRINT_KEY_CONTEXT: &str = "compliance-backend 2024 identity fingerprints";
+
+/// Identity attributes an account was verified with
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdentitySubmission {
+    pub full_name: String,
+    
+    /// Date of birth as `YYYY-MM-DD`
+    pub date_of_birth: Option<String>,
+    
+    /// Passport, national ID and other identity document numbers
+    #[serde(default)]
+    pub document_numbers: Vec<String>,
+    
+    /// BCP 47 language hint for normalizing the name
+    pub language: Option<String>,
+    
+    /// Whether the end user consented to matching against other clients' accounts
+    #[serde(default)]
+    pub cross_client_consent: bool,
+}
+
+impl IdentitySubmission {
+    /// Reject submissions without a usable signal
+    pub fn validate(&self) -> Result<()> {
+        if self.full_name.trim().is_empty() {
+            return Err(ComplianceError::validation("full_name", "must not be empty"));
+        }
+        if self.date_of_birth.as_deref().is_some_and(|dob| NaiveDate::parse_from_str(dob, "%Y-%m-%d").is_err()) {
+            return Err(ComplianceError::validation("date_of_birth", "must be YYYY-MM-DD"));
+        }
+        if self.document_numbers.iter().any(|number| document_number(number).is_empty()) {
+            return Err(ComplianceError::validation("document_numbers", "must not be empty"));
+        }
+        if self.date_of_birth.is_none() && self.document_numbers.is_empty() {
+            return Err(ComplianceError::validation(
+                "date_of_birth",
+                "required when no document numbers are given",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Identifying signal a fingerprint was taken of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentitySignal {
+    DocumentNumber,
+    NameAndBirthDate,
+}
+
+impl IdentitySignal {
+    fn tag(self) -> &'static [u8] {
+        match self {
+            Self::DocumentNumber => b"document_number",
+            Self::NameAndBirthDate => b"name_and_birth_date",
+        }
+    }
+}
+
+type Fingerprint = [u8; 32];
+
+/// Fingerprints held for one account of one client
+#[derive(Debug, Clone)]
+struct Enrollment {
+    consent: bool,
+    fingerprints: Vec<(IdentitySignal, Fingerprint)>,
+}
+
+/// One client account in a fraud case
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaseSubject {
+    pub client_id: Uuid,
+    pub account_id: AccountId,
+}
+
+/// Status of a fraud case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudCaseStatus {
+    Open,
+    /// The accounts were confirmed to belong to one person
+    Confirmed,
+    /// The match was a false positive
+    Dismissed,
+}
+
+/// Operator decision closing a fraud case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FraudCaseResolution {
+    Confirm,
+    Dismiss,
+}
+
+/// Accounts that appear to belong to the same person
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FraudCase {
+    pub id: Uuid,
+    pub status: FraudCaseStatus,
+    pub subjects: Vec<CaseSubject>,
+    /// Signals the accounts were matched on
+    pub signals: Vec<IdentitySignal>,
+    pub opened_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub resolved_by: Option<String>,
+    pub resolution_notes: Option<String>,
+}
+
+impl FraudCase {
+    fn involves(&self, subject: &CaseSubject) -> bool {
+        self.subjects.contains(subject)
+    }
+}
+
+/// Another of the client's accounts that appears to be the same person
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateAccount {
+    pub account_id: AccountId,
+    pub signals: Vec<IdentitySignal>,
+}
+
+/// Outcome of submitting an account's identity attributes
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCheck {
+    pub account_id: AccountId,
+    
+    /// Other accounts of the same client matching the identity
+    pub duplicates: Vec<DuplicateAccount>,
+    
+    /// Accounts of other clients matching the identity, which are not disclosed
+    pub other_client_matches: usize,
+    
+    /// Fraud case the matches were recorded in
+    pub case_id: Option<Uuid>,
+}
+
+/// Keeps identity fingerprints and the fraud cases raised from them
+pub struct DuplicateIdentityService {
+    /// Live configuration holding the matching rules
+    config: Arc<LiveConfig>,
+    
+    /// Key fingerprints are computed with
+    fingerprint_key: [u8; 32],
+    
+    alerts: Arc<AlertManager>,
+    clock: SharedClock,
+    state: RwLock<State>,
+}
+
+#[derive(Default)]
+struct State {
+    enrollments: HashMap<(Uuid, AccountId), Enrollment>,
+    index: HashMap<Fingerprint, Vec<(Uuid, AccountId)>>,
+    cases: Vec<FraudCase>,
+}
+
+impl DuplicateIdentityService {
+    /// Create the service, deriving the fingerprint key from `secret`
+    pub fn new(config: Arc<LiveConfig>, secret: &[u8], alerts: Arc<AlertManager>) -> Self {
+        Self {
+            config,
+            fingerprint_key: blake3::derive_key(FINGERPRINT_KEY_CONTEXT, secret),
+            alerts,
+            clock: system_clock(),
+            state: RwLock::new(State::default()),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Fingerprint an account's identity, replacing any earlier submission,
+    /// and report the accounts it matches
+    pub async fn submit(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        submission: &IdentitySubmission,
+    ) -> Result<DuplicateCheck> {
+        submission.validate()?;
+        let config = self.config.compliance();
+        let settings = &config.duplicate_detection;
+        
+        let enrollment = Enrollment {
+            consent: submission.cross_client_consent,
+            fingerprints: self.fingerprints(submission),
+        };
+        let key = (client_id, account_id.clone());
+        
+        let mut state = self.state.write().await;
+        state.forget(&key);
+        for (_, fingerprint) in &enrollment.fingerprints {
+            state.index.entry(*fingerprint).or_default().push(key.clone());
+        }
+        state.enrollments.insert(key.clone(), enrollment.clone());
+        
+        let mut check = DuplicateCheck {
+            account_id: account_id.clone(),
+            duplicates: Vec::new(),
+            other_client_matches: 0,
+            case_id: None,
+        };
+        if !settings.enabled {
+            return Ok(check);
+        }
+        
+        let matches = state.matches(&key, &enrollment, settings);
+        if matches.is_empty() {
+            return Ok(check);
+        }
+        for ((other_client, other_account), signals) in &matches {
+            if *other_client == client_id {
+                check.duplicates.push(DuplicateAccount {
+                    account_id: other_account.clone(),
+                    signals: signals.clone(),
+                });
+            } else {
+                check.other_client_matches += 1;
+            }
+        }
+        
+        let now = self.clock.now();
+        let subjects: Vec<CaseSubject> = std::iter::once(&key)
+            .chain(matches.iter().map(|(subject, _)| subject))
+            .map(|(client_id, account_id)| CaseSubject {
+                client_id: *client_id,
+                account_id: account_id.clone(),
+            })
+            .collect();
+        let mut signals: Vec<IdentitySignal> =
+            matches.iter().flat_map(|(_, signals)| signals.iter().copied()).collect();
+        signals.sort();
+        signals.dedup();
+        
+        let raised = state.record_case(subjects, signals, now);
+        drop(state);
+        
+        if let Some((case_id, accounts, changed)) = raised {
+            check.case_id = Some(case_id);
+            if changed {
+                tracing::warn!(case_id = %case_id, accounts, "duplicate identity detected");
+                self.alerts.raise(Alert::duplicate_identity(case_id, accounts)).await;
+            }
+        }
+        Ok(check)
+    }
+    
+    /// Drop the fingerprints held for an account, as when its end user
+    /// withdraws consent
+    pub async fn forget(&self, client_id: Uuid, account_id: &AccountId) -> bool {
+        self.state.write().await.forget(&(client_id, account_id.clone()))
+    }
+    
+    /// Fraud cases, newest first, optionally with one status
+    pub async fn cases(&self, status: Option<FraudCaseStatus>) -> Vec<FraudCase> {
+        let state = self.state.read().await;
+        let mut cases: Vec<FraudCase> = state
+            .cases
+            .iter()
+            .filter(|case| status.is_none_or(|status| case.status == status))
+            .cloned()
+            .collect();
+        cases.sort_by(|a, b| b.opened_at.cmp(&a.opened_at));
+        cases
+    }
+    
+    /// Get a fraud case
+    pub async fn case(&self, case_id: Uuid) -> Result<FraudCase> {
+        let state = self.state.read().await;
+        state
+            .cases
+            .iter()
+            .find(|case| case.id == case_id)
+            .cloned()
+            .ok_or_else(|| case_not_found(case_id))
+    }
+    
+    /// Close an open fraud case
+    pub async fn resolve(
+        &self,
+        case_id: Uuid,
+        resolution: FraudCaseResolution,
+        operator: &str,
+        notes: Option<String>,
+    ) -> Result<FraudCase> {
+        let mut state = self.state.write().await;
+        let case = state
+            .cases
+            .iter_mut()
+            .find(|case| case.id == case_id)
+            .ok_or_else(|| case_not_found(case_id))?;
+        if case.status != FraudCaseStatus::Open {
+            return Err(ComplianceError::validation("case_id", "case is already resolved"));
+        }
+        
+        case.status = match resolution {
+            FraudCaseResolution::Confirm => FraudCaseStatus::Confirmed,
+            FraudCaseResolution::Dismiss => FraudCaseStatus::Dismissed,
+        };
+        case.resolved_by = Some(operator.to_string());
+        case.resolution_notes = notes;
+        case.updated_at = self.clock.now();
+        Ok(case.clone())
+    }
+    
+    fn fingerprints(&self, submission: &IdentitySubmission) -> Vec<(IdentitySignal, Fingerprint)> {
+        let mut fingerprints: Vec<_> = submission
+            .document_numbers
+            .iter()
+            .map(|number| self.fingerprint(IdentitySignal::DocumentNumber, &document_number(number)))
+            .collect();
+        if let Some(dob) = &submission.date_of_birth {
+            let name = NameForm::parse(&submission.full_name, submission.language.as_deref()).spelling();
+            let value = format!("{}|{}", name, dob);
+            fingerprints.push(self.fingerprint(IdentitySignal::NameAndBirthDate, &value));
+        }
+        fingerprints.sort();
+        fingerprints.dedup();
+        fingerprints
+    }
+    
+    fn fingerprint(&self, signal: IdentitySignal, value: &str) -> (IdentitySignal, Fingerprint) {
+        let mut hasher = blake3::Hasher::new_keyed(&self.fingerprint_key);
+        hasher.update(signal.tag());
+        hasher.update(&[0]);
+        hasher.update(value.as_bytes());
+        (signal, *hasher.finalize().as_bytes())
+    }
+}
+
+impl State {
+    /// Remove an enrollment and its fingerprints from the index
+    fn forget(&mut self, key: &(Uuid, AccountId)) -> bool {
+        let Some(enrollment) = self.enrollments.remove(key) else {
+            return false;
+        };
+        for (_, fingerprint) in &enrollment.fingerprints {
+            if let Some(holders) = self.index.get_mut(fingerprint) {
+                holders.retain(|holder| holder != key);
+                if holders.is_empty() {
+                    self.index.remove(fingerprint);
+                }
+            }
+        }
+        true
+    }
+    
+    /// Accounts sharing a fingerprint with `enrollment` that it may be matched
+    /// against, with the signals they share
+    fn matches(
+        &self,
+        key: &(Uuid, AccountId),
+        enrollment: &Enrollment,
+        settings: &DuplicateDetectionConfig,
+    ) -> Vec<((Uuid, AccountId), Vec<IdentitySignal>)> {
+        let (client_id, account_id) = key;
+        let cross_client = enrollment.consent && settings.cross_client.contains(client_id);
+        
+        let mut matches: Vec<((Uuid, AccountId), Vec<IdentitySignal>)> = Vec::new();
+        for (signal, fingerprint) in &enrollment.fingerprints {
+            for holder in self.index.get(fingerprint).into_iter().flatten() {
+                let (other_client, other_account) = holder;
+                // The same account at two clients is one person, not a duplicate
+                if other_account == account_id {
+                    continue;
+                }
+                if other_client != client_id {
+                    let consented = self.enrollments.get(holder).is_some_and(|other| other.consent);
+                    if !cross_client || !consented || !settings.cross_client.contains(other_client) {
+                        continue;
+                    }
+                }
+                match matches.iter_mut().find(|(subject, _)| subject == holder) {
+                    Some((_, signals)) if !signals.contains(signal) => signals.push(*signal),
+                    Some(_) => {}
+                    None => matches.push((holder.clone(), vec![*signal])),
+                }
+            }
+        }
+        matches
+    }
+    
+    /// Open or extend a case for `subjects`
+    ///
+    /// Returns the case id, the number of accounts in it, and whether the
+    /// case is new or gained accounts or signals; `None` when the accounts
+    /// are all in a case that was already dismissed.
+    fn record_case(
+        &mut self,
+        subjects: Vec<CaseSubject>,
+        signals: Vec<IdentitySignal>,
+        now: DateTime<Utc>,
+    ) -> Option<(Uuid, usize, bool)> {
+        let open = self
+            .cases
+            .iter_mut()
+            .find(|case| case.status == FraudCaseStatus::Open && subjects.iter().any(|s| case.involves(s)));
+        if let Some(case) = open {
+            let mut changed = false;
+            for subject in subjects {
+                if !case.involves(&subject) {
+                    case.subjects.push(subject);
+                    changed = true;
+                }
+            }
+            for signal in signals {
+                if !case.signals.contains(&signal) {
+                    case.signals.push(signal);
+                    changed = true;
+                }
+            }
+            if changed {
+                case.updated_at = now;
+            }
+            return Some((case.id, case.subjects.len(), changed));
+        }
+        
+        let dismissed = self
+            .cases
+            .iter()
+            .any(|case| case.status == FraudCaseStatus::Dismissed && subjects.iter().all(|s| case.involves(s)));
+        if dismissed {
+            return None;
+        }
+        
+        let case = FraudCase {
+            id: Uuid::new_v4(),
+            status: FraudCaseStatus::Open,
+            subjects,
+            signals,
+            opened_at: now,
+            updated_at: now,
+            resolved_by: None,
+            resolution_notes: None,
+        };
+        let raised = (case.id, case.subjects.len(), true);
+        self.cases.push(case);
+        Some(raised)
+    }
+}
+
+fn case_not_found(case_id: Uuid) -> ComplianceError {
+    ComplianceError::FraudCaseNotFound {
+        case_id: case_id.to_string(),
+    }
+}
@@ -0,0 +1,276 @@
+//! Epoch batching of attestation commitments
+//!
+//! Writing every attestation on-chain costs a transaction per issuance.
+//! Instead, issued attestations are queued and, once per epoch, their
+//! canonical commitments become the leaves of a Merkle tree whose root is
+//! anchored on the anchor account in a single transaction. Inclusion proofs
+//! for individual attestations are generated on demand from the epoch's
+//! leaves.
+
+use super::account_components::anchor_component;
+use super::account_components::foreign::miden_account_id;
+use super::ComplianceService;
+use crate::audit::AuditLog;
+use crate::crypto::attestation_commitment;
+use crate::reload::LiveConfig;
+use crate::scheduler::Job;
+use crate::types::{AccountId, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::crypto::merkle::{MerkleTree, NodeIndex};
+use miden_objects::{Digest, Word, EMPTY_WORD};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+/// Actor recorded for epochs closed by the background worker
+pub const EPOCH_ACTOR: &str = "system:epochs";
+
+/// Name of the job closing and anchoring epochs
+pub const ANCHOR_JOB: &str = "epoch_anchor";
+
+/// An attestation waiting for its epoch to close
+#[derive(Debug, Clone)]
+struct PendingAttestation {
+    attestation_id: Uuid,
+    account_id: AccountId,
+    commitment: Word,
+}
+
+/// An epoch whose root is anchored on-chain
+#[derive(Debug, Clone, Serialize)]
+pub struct AnchoredEpoch {
+    pub epoch: u64,
+    /// Hex-encoded Merkle root
+    pub root: String,
+    pub depth: u8,
+    pub attestations: usize,
+    pub closed_at: DateTime<Utc>,
+    /// Transaction that wrote the root to the anchor account
+    pub transaction_id: String,
+}
+
+/// Proof that an attestation is in an anchored epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochInclusionProof {
+    pub attestation_id: Uuid,
+    pub account_id: AccountId,
+    pub epoch: u64,
+    pub root: String,
+    /// Hex-encoded canonical commitment to the attestation
+    pub commitment: String,
+    /// Leaf position, as taken by `mtree_verify`
+    pub index: u64,
+    pub depth: u8,
+    /// Sibling nodes from the leaf up to the root
+    pub path: Vec<String>,
+    pub transaction_id: String,
+}
+
+/// An anchored epoch with the leaves its proofs are generated from
+struct Epoch {
+    anchored: AnchoredEpoch,
+    leaves: Vec<Word>,
+}
+
+/// Queue of issued attestations and the epochs they were anchored in
+#[derive(Default)]
+pub struct EpochBatcher {
+    pending: Mutex<Vec<PendingAttestation>>,
+    epochs: RwLock<Vec<Epoch>>,
+    /// Epoch and leaf index of every anchored attestation
+    anchored: RwLock<HashMap<Uuid, (u64, u64, AccountId)>>,
+    /// Serializes epoch closing so epochs are numbered in order
+    closing: Mutex<()>,
+}
+
+impl EpochBatcher {
+    /// Create a batcher with nothing queued
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Queue an issued attestation for the current epoch
+    pub async fn enqueue(&self, attestation: &ComplianceAttestation) {
+        self.pending.lock().await.push(PendingAttestation {
+            attestation_id: attestation.id,
+            account_id: attestation.account_id.clone(),
+            commitment: attestation_commitment(attestation).to_word(),
+        });
+    }
+    
+    /// Attestations waiting for the current epoch to close
+    pub async fn pending(&self) -> usize {
+        self.pending.lock().await.len()
+    }
+    
+    /// Anchored epochs, newest first
+    pub async fn list(&self) -> Vec<AnchoredEpoch> {
+        self.epochs.read().await.iter().rev().map(|epoch| epoch.anchored.clone()).collect()
+    }
+    
+    /// An anchored epoch
+    pub async fn get(&self, epoch: u64) -> Result<AnchoredEpoch> {
+        let epochs = self.epochs.read().await;
+        epoch
+            .checked_sub(1)
+            .and_then(|index| epochs.get(index as usize))
+            .map(|anchored| anchored.anchored.clone())
+            .ok_or(ComplianceError::EpochNotFound { epoch })
+    }
+    
+    /// Proof that an attestation is in the epoch it was anchored in
+    pub async fn inclusion_proof(&self, attestation_id: Uuid) -> Result<EpochInclusionProof> {
+        let (epoch, index, account_id) = self
+            .anchored
+            .read()
+            .await
+            .get(&attestation_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::AttestationNotAnchored {
+                attestation_id: attestation_id.to_string(),
+            })?;
+        let epochs = self.epochs.read().await;
+        let anchored = epochs
+            .get(epoch as usize - 1)
+            .ok_or(ComplianceError::EpochNotFound { epoch })?;
+        
+        let tree = MerkleTree::new(anchored.leaves.clone()).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let node = NodeIndex::new(tree.depth(), index).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let path = tree.get_path(node).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        
+        Ok(EpochInclusionProof {
+            attestation_id,
+            account_id,
+            epoch,
+            root: anchored.anchored.root.clone(),
+            commitment: Digest::from(anchored.leaves[index as usize]).to_hex(),
+            index,
+            depth: tree.depth(),
+            path: path.iter().map(|node| node.to_hex()).collect(),
+            transaction_id: anchored.anchored.transaction_id.clone(),
+        })
+    }
+    
+    /// Anchor the queued attestations as the next epoch on `anchor_account`
+    ///
+    /// Returns `None` when nothing is queued. If anchoring fails the
+    /// attestations stay queued for the next epoch.
+    pub async fn close_epoch(
+        &self,
+        compliance: &ComplianceService,
+        anchor_account: &AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<AnchoredEpoch>> {
+        let _closing = self.closing.lock().await;
+        let anchor_account = miden_account_id(anchor_account)?;
+        let batch = std::mem::take(&mut *self.pending.lock().await);
+        if batch.is_empty() {
+            return Ok(None);
+        }
+        
+        match self.anchor(compliance, anchor_account, &batch, now).await {
+            Ok(anchored) => Ok(Some(anchored)),
+            Err(e) => {
+                let mut pending = self.pending.lock().await;
+                let queued_since = std::mem::replace(&mut *pending, batch);
+                pending.extend(queued_since);
+                Err(e)
+            }
+        }
+    }
+    
+    async fn anchor(
+        &self,
+        compliance: &ComplianceService,
+        anchor_account: MidenAccountId,
+        batch: &[PendingAttestation],
+        now: DateTime<Utc>,
+    ) -> Result<AnchoredEpoch> {
+        let epoch = self.epochs.read().await.len() as u64 + 1;
+        let mut leaves: Vec<Word> = batch.iter().map(|pending| pending.commitment).collect();
+        leaves.resize(batch.len().next_power_of_two().max(2), EMPTY_WORD);
+        let tree = MerkleTree::new(leaves.clone()).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let root = tree.root();
+        
+        let script = anchor_component::anchor_script(root, epoch, batch.len() as u64)?;
+        let request = TransactionRequestBuilder::new()
+            .with_custom_script(script)
+            .build()
+            .map_err(|e| ComplianceError::TransactionExecutionFailed {
+                reason: format!("failed to build epoch anchor: {}", e),
+            })?;
+        let transaction_id = {
+            let mut client = compliance.miden_client.write().await;
+            let result = client.new_transaction(anchor_account, request).await?;
+            let transaction_id = result.executed_transaction().id().to_hex();
+            client.submit_transaction(result).await?;
+            transaction_id
+        };
+        
+        let anchored = AnchoredEpoch {
+            epoch,
+            root: root.to_hex(),
+            depth: tree.depth(),
+            attestations: batch.len(),
+            closed_at: now,
+            transaction_id,
+        };
+        {
+            let mut index = self.anchored.write().await;
+            for (leaf, pending) in batch.iter().enumerate() {
+                index.insert(pending.attestation_id, (epoch, leaf as u64, pending.account_id.clone()));
+            }
+        }
+        self.epochs.write().await.push(Epoch {
+            anchored: anchored.clone(),
+            leaves,
+        });
+        Ok(anchored)
+    }
+}
+
+/// Job closing an epoch on every run
+///
+/// The epoch length is the job's interval. Whether batching is enabled and
+/// the anchor account are read from the live configuration on every run.
+pub fn anchor_job(
+    compliance: Arc<ComplianceService>,
+    audit: Arc<AuditLog>,
+    config: Arc<LiveConfig>,
+    interval: std::time::Duration,
+) -> Job {
+    Job::every(ANCHOR_JOB, interval, move || {
+        let (compliance, audit, config) = (compliance.clone(), audit.clone(), config.clone());
+        async move {
+            let settings = config.compliance().attestation.epochs.clone();
+            let Some(account) = settings.anchor_account_id.filter(|_| settings.enabled) else {
+                return Ok(());
+            };
+            let account_id = AccountId::parse(&account)?;
+            let Some(epoch) = compliance.epochs.close_epoch(&compliance, &account_id, compliance.clock.now()).await?
+            else {
+                return Ok(());
+            };
+            audit
+                .record(
+                    EPOCH_ACTOR,
+                    "epoch.anchored",
+                    None,
+                    serde_json::json!({
+                        "epoch": epoch.epoch,
+                        "root": epoch.root,
+                        "attestations": epoch.attestations,
+                        "transaction_id": epoch.transaction_id,
+                    }),
+                )
+                .await;
+            tracing::info!(epoch = epoch.epoch, attestations = epoch.attestations, "attestation epoch anchored");
+            Ok(())
+        }
+    })
+}
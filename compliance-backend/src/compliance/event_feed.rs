@@ -0,0 +1,118 @@
+//! Ordered feed of attestation events for streaming subscribers
+
+use super::attestation_events::RecordedEvent;
+use crate::{ComplianceError, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tokio::sync::{broadcast, RwLock};
+
+/// Events kept for resumption by default
+pub const DEFAULT_RETENTION: usize = 10_000;
+
+/// Capacity of the live broadcast channel
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// An attestation event with its position in the deployment-wide feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedEvent {
+    /// Position in the feed, starting at 1
+    pub position: u64,
+    pub event: RecordedEvent,
+}
+
+impl FeedEvent {
+    /// Opaque token resuming the feed after this event
+    pub fn resume_token(&self) -> String {
+        encode_resume_token(self.position)
+    }
+}
+
+/// Encode a feed position as a resume token
+pub fn encode_resume_token(position: u64) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(position.to_be_bytes())
+}
+
+/// Decode a resume token into the position it resumes after
+pub fn decode_resume_token(token: &str) -> Result<u64> {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(token)
+        .ok()
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_be_bytes)
+        .ok_or_else(|| ComplianceError::validation("resume_token", "malformed resume token"))
+}
+
+/// Subscription to the feed: missed events followed by live ones
+pub struct FeedSubscription {
+    /// Retained events after the resume point, oldest first
+    pub backlog: Vec<FeedEvent>,
+    pub live: broadcast::Receiver<FeedEvent>,
+}
+
+/// Deployment-wide, ordered feed of attestation events
+///
+/// The most recent events are retained so that subscribers can resume after
+/// a disconnect without missing events.
+pub struct EventFeed {
+    retained: RwLock<VecDeque<FeedEvent>>,
+    sender: broadcast::Sender<FeedEvent>,
+    retention: usize,
+}
+
+impl EventFeed {
+    /// Create a feed retaining up to `retention` events
+    pub fn new(retention: usize) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            retained: RwLock::new(VecDeque::new()),
+            sender,
+            retention,
+        }
+    }
+    
+    /// Publish an event to the feed
+    pub async fn publish(&self, event: RecordedEvent) -> FeedEvent {
+        let mut retained = self.retained.write().await;
+        let position = retained.back().map_or(1, |last| last.position + 1);
+        let feed_event = FeedEvent { position, event };
+        
+        retained.push_back(feed_event.clone());
+        while retained.len() > self.retention {
+            retained.pop_front();
+        }
+        // Sent while holding the lock so subscribers see events in order
+        let _ = self.sender.send(feed_event.clone());
+        feed_event
+    }
+    
+    /// Subscribe to the feed, replaying retained events after `after` when given
+    ///
+    /// Fails if events after `after` are no longer retained.
+    pub async fn subscribe(&self, after: Option<u64>) -> Result<FeedSubscription> {
+        let retained = self.retained.read().await;
+        let live = self.sender.subscribe();
+        
+        let backlog = match after {
+            None => Vec::new(),
+            Some(after) => {
+                let oldest = retained.front().map_or(after + 1, |first| first.position);
+                if after + 1 < oldest {
+                    return Err(ComplianceError::validation(
+                        "resume_token",
+                        "events after this token are no longer retained; resubscribe without a token",
+                    ));
+                }
+                retained.iter().filter(|e| e.position > after).cloned().collect()
+            }
+        };
+        
+        Ok(FeedSubscription { backlog, live })
+    }
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETENTION)
+    }
+}
@@ -0,0 +1,60 @@
+//! Freshness a verifier can require of the attestation behind a proof
+//!
+//! A challenge may bound the age of the attestation a proof is generated
+//! from, and name the earliest version of each screening list the account
+//! must have been screened against. List versions are opaque, so a version
+//! satisfies the requirement when it was ingested no earlier than the
+//! required one.
+
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Freshness required of the attestation and screening behind a proof
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreshnessRequirement {
+    /// Oldest attestation, in seconds, the proof may be generated from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attestation_age_secs: Option<u64>,
+    
+    /// Earliest version of each screening list, by list name, the account
+    /// must have been screened against
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub min_list_versions: BTreeMap<String, String>,
+    
+    /// Re-screen the account when its screening predates a required list
+    /// version, instead of failing proof generation
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub rescreen: bool,
+}
+
+impl FreshnessRequirement {
+    /// Reject malformed requirements
+    pub fn validate(&self) -> Result<()> {
+        if self.max_attestation_age_secs == Some(0) {
+            return Err(ComplianceError::validation(
+                "freshness.max_attestation_age_secs",
+                "must be greater than 0",
+            ));
+        }
+        if self
+            .min_list_versions
+            .iter()
+            .any(|(list, version)| list.trim().is_empty() || version.trim().is_empty())
+        {
+            return Err(ComplianceError::validation(
+                "freshness.min_list_versions",
+                "list names and versions must not be empty",
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Whether an attestation created at `created_at` is recent enough at `now`
+    pub fn attestation_fresh(&self, created_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.max_attestation_age_secs
+            .is_none_or(|max_age| now - created_at <= Duration::seconds(max_age as i64))
+    }
+}
@@ -0,0 +1,240 @@
+//! Transaction scripts for compliance-gated transfers
+//!
+//! A wallet sends assets by running a transaction script against its own
+//! account that creates a P2ID note for the recipient and moves the assets
+//! into it. The scripts built here first check the sender's and/or the
+//! recipient's KYC component in the same transaction, so a transfer failing
+//! a check aborts on-chain instead of depending on an off-chain check by the
+//! backend.
+//!
+//! The sender's component is called directly, as the account executing the
+//! transaction. The recipient's is read through foreign procedure
+//! invocation, so a checked recipient must be a public account.
+
+use super::account_components::foreign::{
+    call_foreign, procedure_root, require_verified, KYC_STATUS_PROCEDURE,
+};
+use super::account_components::migration::ComponentKind;
+use super::account_components::template::{ComponentTemplates, MasmBuilder};
+use crate::types::ComplianceLevel;
+use crate::{ComplianceError, Result};
+use miden_client::rpc::domain::account::AccountStorageRequirements;
+use miden_client::transaction::{ForeignAccount, TransactionRequest, TransactionRequestBuilder};
+use miden_lib::note::utils::build_p2id_recipient;
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::asset::Asset;
+use miden_objects::note::{
+    Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteMetadata, NoteRecipient, NoteTag, NoteType,
+};
+use miden_objects::transaction::TransactionScript;
+use miden_objects::{Digest, Felt, TransactionKernel, Word};
+use std::fmt::Display;
+
+/// A party to a transfer whose compliance is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferParty {
+    Sender,
+    Recipient,
+}
+
+/// Builder of transfers that abort unless the parties' KYC checks pass
+#[derive(Debug, Clone)]
+pub struct GatedTransferBuilder {
+    sender: MidenAccountId,
+    recipient: MidenAccountId,
+    assets: Vec<Asset>,
+    note_type: NoteType,
+    checks: Vec<(TransferParty, ComplianceLevel)>,
+}
+
+/// A gated transfer ready to execute against the sender's account
+#[derive(Debug, Clone)]
+pub struct GatedTransfer {
+    /// Script run against the sender's account
+    pub script: TransactionScript,
+    
+    /// P2ID note the script creates for the recipient
+    pub note: Note,
+    
+    /// Recipient read through foreign procedure invocation, when its KYC is checked
+    pub foreign_recipient: Option<MidenAccountId>,
+}
+
+impl GatedTransfer {
+    /// Transaction request executing the transfer
+    ///
+    /// A checked recipient is fetched from the node during execution.
+    pub fn request(&self) -> Result<TransactionRequest> {
+        let mut builder = TransactionRequestBuilder::new()
+            .with_custom_script(self.script.clone())
+            .with_expected_output_notes(vec![self.note.clone()]);
+        if let Some(recipient) = self.foreign_recipient {
+            let foreign = ForeignAccount::public(recipient, AccountStorageRequirements::default())
+                .map_err(|e| transfer_error("invalid foreign recipient", e))?;
+            builder = builder.with_foreign_accounts([foreign]);
+        }
+        builder.build().map_err(|e| transfer_error("failed to build transfer request", e))
+    }
+}
+
+impl GatedTransferBuilder {
+    /// Transfer of `assets` from `sender` to `recipient`, with no checks yet
+    pub fn new(sender: MidenAccountId, recipient: MidenAccountId, assets: Vec<Asset>) -> Self {
+        Self {
+            sender,
+            recipient,
+            assets,
+            note_type: NoteType::Public,
+            checks: Vec::new(),
+        }
+    }
+    
+    /// Transfer that both parties must be at `level` or above for
+    pub fn between_verified(
+        sender: MidenAccountId,
+        recipient: MidenAccountId,
+        assets: Vec<Asset>,
+        level: ComplianceLevel,
+    ) -> Self {
+        Self::new(sender, recipient, assets)
+            .require_sender(level.clone())
+            .require_recipient(level)
+    }
+    
+    /// Require the sender's KYC to be verified, unexpired and at `level` or above
+    pub fn require_sender(mut self, level: ComplianceLevel) -> Self {
+        self.checks.push((TransferParty::Sender, level));
+        self
+    }
+    
+    /// Require the recipient's KYC to be verified, unexpired and at `level` or above
+    pub fn require_recipient(mut self, level: ComplianceLevel) -> Self {
+        self.checks.push((TransferParty::Recipient, level));
+        self
+    }
+    
+    /// Keep the note's details off-chain
+    pub fn private(mut self) -> Self {
+        self.note_type = NoteType::Private;
+        self
+    }
+    
+    /// Checks the transfer will run, in order
+    pub fn checks(&self) -> &[(TransferParty, ComplianceLevel)] {
+        &self.checks
+    }
+    
+    /// Generate the transaction script creating the note for `recipient`
+    ///
+    /// Both parties' KYC components must have been generated from `templates`.
+    pub fn script_source(&self, templates: &ComponentTemplates, recipient: &NoteRecipient) -> Result<String> {
+        self.validate()?;
+        let kyc_status_root = if self.checks.is_empty() {
+            None
+        } else {
+            Some(procedure_root(ComponentKind::Kyc, templates, KYC_STATUS_PROCEDURE)?)
+        };
+        let tag = self.tag()?;
+        
+        let mut masm = MasmBuilder::new();
+        masm.comment("Compliance-Gated Transfer")
+            .comment("Sends the assets to the recipient in a P2ID note once every check passes")
+            .blank()
+            .import("miden::tx")
+            .import("miden::contracts::wallets::basic->wallet")
+            .blank();
+        
+        masm.begin(|m| {
+            for (party, level) in &self.checks {
+                let root = kyc_status_root.expect("resolved for checks");
+                match party {
+                    TransferParty::Sender => {
+                        m.op_with_comment("padw padw padw padw", "Call inputs");
+                        m.op_with_comment(format_args!("call.{}", root.to_hex()), "Sender's KYC status");
+                    }
+                    TransferParty::Recipient => {
+                        m.comment("Recipient's KYC status");
+                        call_foreign(m, root, |m| {
+                            m.op(format_args!(
+                                "push.{} push.{}",
+                                self.recipient.suffix(),
+                                self.recipient.prefix().as_felt()
+                            ));
+                        });
+                    }
+                }
+                require_verified(m, level);
+            }
+            
+            m.op_with_comment(format_args!("push.{}", recipient.digest().to_hex()), "RECIPIENT");
+            m.op(format_args!(
+                "push.{} push.{} push.0 push.{}",
+                u64::from(NoteExecutionHint::always()),
+                Felt::from(self.note_type),
+                Felt::from(tag)
+            ));
+            m.comment("=> [tag, aux, note_type, execution_hint, RECIPIENT, pad(8)]");
+            m.op_with_comment("call.wallet::create_note", "=> [note_idx, pad(15)]");
+            for asset in &self.assets {
+                m.op(format_args!("push.{}", Digest::from(Word::from(*asset)).to_hex()));
+                m.op("call.wallet::move_asset_to_note dropw");
+            }
+            m.op("dropw dropw dropw drop");
+        });
+        
+        Ok(masm.finish())
+    }
+    
+    /// Build the transfer, with `serial_num` for the recipient's note
+    pub fn build(&self, templates: &ComponentTemplates, serial_num: Word) -> Result<GatedTransfer> {
+        let recipient = build_p2id_recipient(self.recipient, serial_num)
+            .map_err(|e| transfer_error("invalid P2ID recipient", e))?;
+        let script = TransactionScript::compile(
+            self.script_source(templates, &recipient)?,
+            [],
+            TransactionKernel::assembler(),
+        )
+        .map_err(|e| transfer_error("failed to compile transfer script", e))?;
+        
+        let metadata = NoteMetadata::new(
+            self.sender,
+            self.note_type,
+            self.tag()?,
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )
+        .map_err(|e| transfer_error("invalid note metadata", e))?;
+        let assets = NoteAssets::new(self.assets.clone()).map_err(|e| transfer_error("invalid note assets", e))?;
+        
+        Ok(GatedTransfer {
+            script,
+            note: Note::new(assets, metadata, recipient),
+            foreign_recipient: self
+                .checks
+                .iter()
+                .any(|(party, _)| *party == TransferParty::Recipient)
+                .then_some(self.recipient),
+        })
+    }
+    
+    fn tag(&self) -> Result<NoteTag> {
+        NoteTag::from_account_id(self.recipient, NoteExecutionMode::Local)
+            .map_err(|e| transfer_error("invalid note tag", e))
+    }
+    
+    fn validate(&self) -> Result<()> {
+        if self.assets.is_empty() {
+            return Err(ComplianceError::validation("assets", "a transfer must carry at least one asset"));
+        }
+        if self.sender == self.recipient {
+            return Err(ComplianceError::validation("recipient", "must differ from the sender"));
+        }
+        Ok(())
+    }
+}
+
+fn transfer_error(context: &str, e: impl Display) -> ComplianceError {
+    ComplianceError::TransactionExecutionFailed {
+        reason: format!("{}: {}", context, e),
+    }
+}
@@ -0,0 +1,190 @@
+//! Local quality checks on identity document images
+//!
+//! Every provider verification attempt is billed, and a blurry or cropped
+//! photo is rejected by the provider all the same. Images are checked here
+//! first, so the end user can retake them before an attempt is spent.
+//!
+//! Each check scores the image from 0 to 1, and an image fails a check
+//! scoring below `kyc.min_quality_score`:
+//!
+//! - resolution: the shorter side against `min_short_side_px`
+//! - sharpness: variance of the Laplacian of the grayscale image against
+//!   `sharp_laplacian_variance`; blur flattens the Laplacian
+//! - glare: share of blown-out pixels against `max_glare_share`
+//! - framing: share of the four border strips quieter than the interior; a
+//!   document running off the frame carries its edges and text into them
+//!
+//! Checks run on a copy scaled down to at most `ANALYSIS_MAX_SIDE`, so their
+//! cost does not grow with the camera's resolution.
+
+use crate::config::ImageQualityConfig;
+use image::GenericImageView;
+use serde::{Deserialize, Serialize};
+
+/// Longest side of the copy checks run on
+const ANALYSIS_MAX_SIDE: u32 = 1024;
+
+/// A problem with a document image the end user can fix by retaking it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityIssue {
+    /// The image could not be decoded
+    Unreadable,
+    LowResolution,
+    Blurry,
+    Glare,
+    /// The document is cut off by the edge of the frame
+    Cropped,
+}
+
+impl QualityIssue {
+    /// Feedback code returned to the client
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Unreadable => "unreadable",
+            Self::LowResolution => "low_resolution",
+            Self::Blurry => "blurry",
+            Self::Glare => "glare",
+            Self::Cropped => "cropped",
+        }
+    }
+}
+
+/// Outcome of checking a document image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    /// Lowest score of any check
+    pub score: f64,
+    
+    /// Checks scoring below the minimum, in check order
+    pub issues: Vec<QualityIssue>,
+    
+    pub width: u32,
+    pub height: u32,
+    
+    /// Variance of the Laplacian of the analysed copy
+    pub sharpness: f64,
+    
+    /// Share of blown-out pixels
+    pub glare_share: f64,
+    
+    /// Border strips quieter than the interior, out of four
+    pub quiet_borders: u8,
+}
+
+impl QualityReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+    
+    fn unreadable() -> Self {
+        Self {
+            score: 0.0,
+            issues: vec![QualityIssue::Unreadable],
+            width: 0,
+            height: 0,
+            sharpness: 0.0,
+            glare_share: 0.0,
+            quiet_borders: 0,
+        }
+    }
+}
+
+/// Check a document image, failing checks that score below `min_score`
+pub fn assess(bytes: &[u8], config: &ImageQualityConfig, min_score: f64) -> QualityReport {
+    let Ok(image) = image::load_from_memory(bytes) else {
+        return QualityReport::unreadable();
+    };
+    let (width, height) = image.dimensions();
+    if width < 3 || height < 3 {
+        return QualityReport {
+            issues: vec![QualityIssue::LowResolution],
+            width,
+            height,
+            ..QualityReport::unreadable()
+        };
+    }
+    let analysed = if width.max(height) > ANALYSIS_MAX_SIDE {
+        image.thumbnail(ANALYSIS_MAX_SIDE, ANALYSIS_MAX_SIDE)
+    } else {
+        image
+    };
+    let luma = analysed.to_luma8();
+    let (w, h) = (luma.width() as usize, luma.height() as usize);
+    let pixel = |x: usize, y: usize| luma.as_raw()[y * w + x] as f64;
+    
+    // Neighbors past the edge of the image are clamped to it
+    let mut laplacian = Vec::with_capacity((w - 2) * (h - 2));
+    let mut gradient = Vec::with_capacity(w * h);
+    for y in 0..h {
+        for x in 0..w {
+            let (up, down) = (pixel(x, y.saturating_sub(1)), pixel(x, (y + 1).min(h - 1)));
+            let (left, right) = (pixel(x.saturating_sub(1), y), pixel((x + 1).min(w - 1), y));
+            gradient.push((right - left).abs() + (down - up).abs());
+            if (1..h - 1).contains(&y) && (1..w - 1).contains(&x) {
+                laplacian.push(4.0 * pixel(x, y) - up - down - left - right);
+            }
+        }
+    }
+    let sharpness = variance(&laplacian);
+    let glare = luma.as_raw().iter().filter(|&&value| value >= config.glare_luma).count();
+    let glare_share = glare as f64 / (w * h) as f64;
+    let quiet_borders = quiet_borders(&gradient, w, h, config);
+    
+    let checks = [
+        (QualityIssue::LowResolution, width.min(height) as f64 / config.min_short_side_px as f64),
+        (QualityIssue::Blurry, sharpness / config.sharp_laplacian_variance),
+        (QualityIssue::Glare, 1.0 - glare_share / config.max_glare_share),
+        (QualityIssue::Cropped, quiet_borders as f64 / 4.0),
+    ];
+    let mut score: f64 = 1.0;
+    let mut issues = Vec::new();
+    for (issue, check) in checks {
+        let check = check.clamp(0.0, 1.0);
+        score = score.min(check);
+        if check < min_score {
+            issues.push(issue);
+        }
+    }
+    QualityReport {
+        score,
+        issues,
+        width,
+        height,
+        sharpness,
+        glare_share,
+        quiet_borders,
+    }
+}
+
+/// Border strips whose mean gradient is at most `max_border_edge_ratio` of the interior's
+fn quiet_borders(gradient: &[f64], w: usize, h: usize, config: &ImageQualityConfig) -> u8 {
+    let strip_x = ((w as f64 * config.border_share).round() as usize).clamp(1, w / 2);
+    let strip_y = ((h as f64 * config.border_share).round() as usize).clamp(1, h / 2);
+    let mean = |xs: std::ops::Range<usize>, ys: std::ops::Range<usize>| {
+        let count = xs.len() * ys.len();
+        let sum: f64 = ys.flat_map(|y| xs.clone().map(move |x| gradient[y * w + x])).sum();
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f64
+        }
+    };
+    let interior = mean(strip_x..w - strip_x, strip_y..h - strip_y);
+    let strips = [
+        mean(0..w, 0..strip_y),
+        mean(0..w, h - strip_y..h),
+        mean(0..strip_x, 0..h),
+        mean(w - strip_x..w, 0..h),
+    ];
+    strips.iter().filter(|&&strip| strip <= interior * config.max_border_edge_ratio).count() as u8
+}
+
+fn variance(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
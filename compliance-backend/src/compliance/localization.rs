@@ -0,0 +1,322 @@
+//! Localized display text for decisions shown to end users
+//!
+//! Decisions are described by stable message keys with parameters, such as
+//! `rejection.document_expired`. Responses and webhooks carry the keys
+//! together with display text rendered from a translation catalog, so client
+//! UIs can show decisions in the end user's language.
+//!
+//! The catalog is loaded at startup from one `{locale}.json` file per locale
+//! in `localization.catalog_dir`, each mapping message keys to templates with
+//! `{name}` placeholders. English text is built in. A message is rendered in
+//! the first locale of its fallback chain that translates it: each preferred
+//! locale in turn, followed by its language alone (`pt-br` falls back to
+//! `pt`), then the configured default locale, then built-in English.
+
+use super::rejection::{KycRejection, RejectionReason};
+use super::source_of_funds::SupportingDocumentType;
+use super::step_up::StepUpRequirement;
+use crate::config::LocalizationConfig;
+use crate::types::{ComplianceAttestation, KycStatus};
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Locale of the built-in text, the last resort of every fallback chain
+pub const BUILTIN_LOCALE: &str = "en";
+
+/// A message key with the parameters its templates are filled with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl Message {
+    /// A message without parameters
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            params: BTreeMap::new(),
+        }
+    }
+    
+    /// Add a parameter
+    pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+    
+    /// Explanation of a KYC rejection reason
+    pub fn rejection(reason: RejectionReason) -> Self {
+        Self::new(format!("rejection.{}", snake_name(&reason)))
+    }
+    
+    /// Name of evidence required by a step-up session
+    pub fn requirement(requirement: StepUpRequirement) -> Self {
+        Self::new(format!("requirement.{}", snake_name(&requirement)))
+    }
+    
+    /// Name of a supporting document
+    pub fn document(document_type: SupportingDocumentType) -> Self {
+        Self::new(format!("document.{}", snake_name(&document_type)))
+    }
+}
+
+/// A message rendered in one locale
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalizedMessage {
+    pub key: String,
+    
+    /// Locale the text is in, after fallback
+    pub locale: String,
+    
+    pub text: String,
+}
+
+/// Rendered messages, keyed by the locale they were requested in
+pub type Display = BTreeMap<String, Vec<LocalizedMessage>>;
+
+/// Messages describing why an attestation falls short
+///
+/// Covers KYC rejection reasons, or the KYC status when KYC is unverified
+/// without a recorded rejection, and uncleared sanctions screening.
+pub fn attestation_messages(attestation: &ComplianceAttestation, kyc_rejection: Option<&KycRejection>) -> Vec<Message> {
+    let mut messages = Vec::new();
+    match kyc_rejection {
+        Some(rejection) => messages.extend(rejection.reasons.iter().map(|reason| Message::rejection(*reason))),
+        None if attestation.kyc_status != KycStatus::Verified => {
+            messages.push(Message::new("policy.kyc_status").with("status", format!("{:?}", attestation.kyc_status)));
+        }
+        None => {}
+    }
+    if !attestation.sanctions_cleared {
+        messages.push(Message::new("policy.sanctions_not_cleared"));
+    }
+    messages
+}
+
+/// Translations by locale, with built-in English
+pub struct MessageCatalog {
+    translations: HashMap<String, HashMap<String, String>>,
+    default_locale: String,
+}
+
+impl MessageCatalog {
+    /// Catalog with only the built-in English text
+    pub fn builtin() -> Self {
+        let mut english: HashMap<String, String> = HashMap::new();
+        let reasons = [
+            RejectionReason::DocumentExpired,
+            RejectionReason::FaceMismatch,
+            RejectionReason::TamperedDocument,
+            RejectionReason::Underage,
+            RejectionReason::UnsupportedJurisdiction,
+            RejectionReason::Other,
+        ];
+        for reason in reasons {
+            english.insert(Message::rejection(reason).key, capitalize(reason.description()));
+        }
+        let requirements = [
+            (StepUpRequirement::AdditionalDocument, "An additional identity document"),
+            (StepUpRequirement::SourceOfFundsDeclaration, "A source of funds declaration"),
+            (StepUpRequirement::ReLiveness, "A new liveness check"),
+        ];
+        for (requirement, text) in requirements {
+            english.insert(Message::requirement(requirement).key, text.to_string());
+        }
+        let documents = [
+            (SupportingDocumentType::PaySlip, "Pay slip"),
+            (SupportingDocumentType::BankStatement, "Bank statement"),
+            (SupportingDocumentType::TaxReturn, "Tax return"),
+            (SupportingDocumentType::SaleContract, "Sale contract"),
+            (SupportingDocumentType::ProbateOrWill, "Probate or will"),
+            (SupportingDocumentType::GiftLetter, "Gift letter"),
+            (SupportingDocumentType::CompanyAccounts, "Company accounts"),
+            (SupportingDocumentType::LoanAgreement, "Loan agreement"),
+            (SupportingDocumentType::Other, "Other document"),
+        ];
+        for (document_type, text) in documents {
+            english.insert(Message::document(document_type).key, text.to_string());
+        }
+        for (key, text) in [
+            ("policy.kyc_status", "Identity verification is {status}"),
+            ("policy.sanctions_not_cleared", "Sanctions screening has not been cleared"),
+            ("policy.level_not_met", "{required} compliance is required"),
+        ] {
+            english.insert(key.to_string(), text.to_string());
+        }
+        
+        Self {
+            translations: HashMap::from([(BUILTIN_LOCALE.to_string(), english)]),
+            default_locale: BUILTIN_LOCALE.to_string(),
+        }
+    }
+    
+    /// Load the catalog configured for the deployment
+    pub fn from_config(config: &LocalizationConfig) -> Result<Self> {
+        let mut catalog = match &config.catalog_dir {
+            Some(dir) => Self::open(dir)?,
+            None => Self::builtin(),
+        };
+        catalog.default_locale = normalize_locale(&config.default_locale)?;
+        Ok(catalog)
+    }
+    
+    /// Load every `{locale}.json` translation file in `dir` over the built-in text
+    ///
+    /// Keys missing from a file fall back along the chain; a file for `en`
+    /// overrides the built-in text key by key.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut catalog = Self::builtin();
+        for entry in std::fs::read_dir(dir.as_ref())? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let locale = normalize_locale(stem)
+                .map_err(|_| ComplianceError::internal(format!("{} is not named after a locale", path.display())))?;
+            let messages: HashMap<String, String> = serde_json::from_slice(&std::fs::read(&path)?)
+                .map_err(|e| ComplianceError::internal(format!("invalid translation file {}: {}", path.display(), e)))?;
+            catalog.translations.entry(locale).or_default().extend(messages);
+        }
+        Ok(catalog)
+    }
+    
+    /// Locales with translations, sorted
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.translations.keys().cloned().collect();
+        locales.sort();
+        locales
+    }
+    
+    /// Locales tried, in order, for a reader preferring `preferred` locales
+    pub fn fallback_chain(&self, preferred: &[String]) -> Vec<String> {
+        let mut chain: Vec<String> = Vec::new();
+        let locales = preferred
+            .iter()
+            .filter_map(|locale| normalize_locale(locale).ok())
+            .chain([self.default_locale.clone()]);
+        for locale in locales {
+            let language = locale.split_once('-').map(|(language, _)| language.to_string());
+            for candidate in [Some(locale), language].into_iter().flatten() {
+                if !chain.contains(&candidate) {
+                    chain.push(candidate);
+                }
+            }
+        }
+        if !chain.iter().any(|locale| locale == BUILTIN_LOCALE) {
+            chain.push(BUILTIN_LOCALE.to_string());
+        }
+        chain
+    }
+    
+    /// Render a message in the first locale of the chain that translates it
+    ///
+    /// A key no locale translates is rendered as the key itself.
+    pub fn render(&self, message: &Message, preferred: &[String]) -> LocalizedMessage {
+        let found = self.fallback_chain(preferred).into_iter().find_map(|locale| {
+            let template = self.translations.get(&locale)?.get(&message.key)?;
+            Some((locale, template))
+        });
+        let (locale, text) = match found {
+            Some((locale, template)) => (locale, fill(template, &message.params)),
+            None => (BUILTIN_LOCALE.to_string(), message.key.clone()),
+        };
+        LocalizedMessage {
+            key: message.key.clone(),
+            locale,
+            text,
+        }
+    }
+    
+    /// Render messages for a reader preferring `preferred` locales, most preferred first
+    pub fn render_all(&self, messages: &[Message], preferred: &[String]) -> Vec<LocalizedMessage> {
+        messages.iter().map(|message| self.render(message, preferred)).collect()
+    }
+    
+    /// Render messages in each of `locales`, or in the default locale when none are given
+    pub fn display(&self, messages: &[Message], locales: &[String]) -> Display {
+        let default = [self.default_locale.clone()];
+        let locales = if locales.is_empty() { &default[..] } else { locales };
+        locales
+            .iter()
+            .map(|locale| {
+                let rendered = self.render_all(messages, std::slice::from_ref(locale));
+                (normalize_locale(locale).unwrap_or_else(|_| locale.clone()), rendered)
+            })
+            .collect()
+    }
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Normalize a locale tag such as `pt_BR` to `pt-br`
+///
+/// Accepts a two or three letter language followed by alphanumeric subtags
+/// of up to eight characters.
+pub fn normalize_locale(tag: &str) -> Result<String> {
+    let normalized = tag.trim().replace('_', "-").to_ascii_lowercase();
+    let mut subtags = normalized.split('-');
+    let language_ok = subtags
+        .next()
+        .is_some_and(|language| (2..=3).contains(&language.len()) && language.bytes().all(|b| b.is_ascii_alphabetic()));
+    let rest_ok =
+        subtags.all(|subtag| (1..=8).contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphanumeric()));
+    if !language_ok || !rest_ok {
+        return Err(ComplianceError::validation("locale", format!("{} is not a locale tag", tag)));
+    }
+    Ok(normalized)
+}
+
+/// Locales of an `Accept-Language` header, most preferred first
+///
+/// Unparseable entries, the `*` wildcard, and entries with `q=0` are left out.
+pub fn accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(f32, usize, String)> = header
+        .split(',')
+        .enumerate()
+        .filter_map(|(position, entry)| {
+            let mut parts = entry.split(';');
+            let locale = normalize_locale(parts.next()?).ok()?;
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (quality > 0.0).then_some((quality, position, locale))
+        })
+        .collect();
+    weighted.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    weighted.into_iter().map(|(_, _, locale)| locale).collect()
+}
+
+/// Fill `{name}` placeholders; unknown placeholders are left as they are
+fn fill(template: &str, params: &BTreeMap<String, String>) -> String {
+    params
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Serialized name of a unit enum variant
+fn snake_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
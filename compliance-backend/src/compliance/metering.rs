@@ -0,0 +1,399 @@
+//! Usage metering per business client
+//!
+//! Billable operations are counted per client per calendar month (UTC) as
+//! they are performed: KYC verifications and sanctions screenings for each
+//! provider call, proof generations for each run of the prover, and every
+//! authenticated API call. The counts are the usage data clients are billed
+//! on. Operations performed outside any client's request, such as renewals,
+//! are not billed.
+//!
+//! A client's plan may set a quota per operation. An operation over quota is
+//! rejected before it is performed. Usage webhooks are sent through the
+//! outbox as a client's usage first reaches each alert threshold of a quota
+//! in a period.
+//!
+//! Provider calls made for a client are recorded with their vendor and cost,
+//! for operators to see what serving each client costs. Costs are never shown
+//! to clients.
+
+use super::breaker::Provider;
+use super::provider_credentials::current_client;
+use crate::clock::SharedClock;
+use crate::config::MeteringConfig;
+use crate::reload::LiveConfig;
+use crate::storage::{OutboxMessage, UnitOfWork, UsageRepo, WriteBatch};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Webhook event sent when usage reaches an alert threshold of a quota
+pub const USAGE_THRESHOLD_EVENT: &str = "usage.threshold_reached";
+
+/// An operation clients are billed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BillableOperation {
+    KycVerification,
+    Screening,
+    ProofGeneration,
+    ApiCall,
+}
+
+impl BillableOperation {
+    /// Every billable operation
+    pub const ALL: [Self; 4] = [Self::KycVerification, Self::Screening, Self::ProofGeneration, Self::ApiCall];
+    
+    /// Name used in configuration and webhooks
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::KycVerification => "kyc_verification",
+            Self::Screening => "screening",
+            Self::ProofGeneration => "proof_generation",
+            Self::ApiCall => "api_call",
+        }
+    }
+}
+
+/// Start and end of the billing period containing `at`
+pub fn billing_period(at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = Utc
+        .with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0)
+        .single()
+        .expect("the first of a month is a valid UTC time");
+    (start, start + Months::new(1))
+}
+
+/// A client's usage in one billing period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub client_id: Uuid,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    
+    /// Operations performed, by operation
+    pub counts: HashMap<BillableOperation, u64>,
+    
+    /// Highest alert threshold notified, by operation, as a percentage of its quota
+    #[serde(default)]
+    pub notified: HashMap<BillableOperation, u8>,
+    
+    /// Provider calls made for the client, by provider and vendor
+    #[serde(default)]
+    pub provider_calls: Vec<ProviderCallUsage>,
+    
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UsageRecord {
+    fn empty(client_id: Uuid, now: DateTime<Utc>) -> Self {
+        let (period_start, period_end) = billing_period(now);
+        Self {
+            client_id,
+            period_start,
+            period_end,
+            counts: HashMap::new(),
+            notified: HashMap::new(),
+            provider_calls: Vec::new(),
+            updated_at: now,
+        }
+    }
+}
+
+/// Calls to one vendor of a provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderCallUsage {
+    pub provider: Provider,
+    pub vendor: String,
+    
+    /// Calls that succeeded
+    pub calls: u64,
+    
+    /// Calls that failed; these are not costed
+    pub failed_calls: u64,
+    
+    /// Cost of the calls that succeeded
+    pub cost: f64,
+}
+
+/// What a client's provider calls cost in a billing period
+#[derive(Debug, Clone, Serialize)]
+pub struct CostReport {
+    pub client_id: Uuid,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub currency: String,
+    pub total_cost: f64,
+    
+    /// Calls by provider and vendor
+    pub providers: Vec<ProviderCallUsage>,
+}
+
+impl CostReport {
+    fn new(currency: &str, client_id: Uuid, at: DateTime<Utc>, record: Option<&UsageRecord>) -> Self {
+        let (period_start, period_end) = billing_period(at);
+        let mut providers = record.map(|record| record.provider_calls.clone()).unwrap_or_default();
+        providers.sort_by(|a, b| (a.provider.name(), &a.vendor).cmp(&(b.provider.name(), &b.vendor)));
+        Self {
+            client_id,
+            period_start,
+            period_end,
+            currency: currency.to_string(),
+            total_cost: providers.iter().map(|usage| usage.cost).sum(),
+            providers,
+        }
+    }
+}
+
+/// Usage of one operation against its quota
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationUsage {
+    pub operation: BillableOperation,
+    pub used: u64,
+    
+    /// Operations allowed in the period; unlimited when absent
+    pub quota: Option<u64>,
+    pub remaining: Option<u64>,
+}
+
+/// A client's usage in a billing period, against its plan
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSummary {
+    pub client_id: Uuid,
+    
+    /// Plan the quotas come from
+    pub plan: Option<String>,
+    
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub operations: Vec<OperationUsage>,
+}
+
+impl UsageSummary {
+    fn new(metering: &MeteringConfig, client_id: Uuid, at: DateTime<Utc>, record: Option<&UsageRecord>) -> Self {
+        let (period_start, period_end) = billing_period(at);
+        let plan = metering.plan_for(client_id);
+        let operations = BillableOperation::ALL
+            .into_iter()
+            .map(|operation| {
+                let used = record.and_then(|record| record.counts.get(&operation).copied()).unwrap_or(0);
+                let quota = plan.and_then(|(_, plan)| plan.quotas.get(&operation).copied());
+                OperationUsage {
+                    operation,
+                    used,
+                    quota,
+                    remaining: quota.map(|quota| quota.saturating_sub(used)),
+                }
+            })
+            .collect();
+        
+        Self {
+            client_id,
+            plan: plan.map(|(name, _)| name.to_string()),
+            period_start,
+            period_end,
+            operations,
+        }
+    }
+}
+
+/// Counts billable operations and enforces plan quotas
+pub struct UsageMeter {
+    /// Live configuration holding plans and alert thresholds
+    config: Arc<LiveConfig>,
+    
+    repo: Arc<dyn UsageRepo>,
+    
+    /// Commits usage records together with the webhooks they trigger
+    storage: Arc<dyn UnitOfWork>,
+    
+    clock: SharedClock,
+    
+    /// Held across each read-then-write of a usage record
+    lock: Mutex<()>,
+}
+
+impl UsageMeter {
+    /// Create a meter
+    ///
+    /// `repo` must be the usage repository `storage` commits to.
+    pub fn new(
+        config: Arc<LiveConfig>,
+        repo: Arc<dyn UsageRepo>,
+        storage: Arc<dyn UnitOfWork>,
+        clock: SharedClock,
+    ) -> Self {
+        Self {
+            config,
+            repo,
+            storage,
+            clock,
+            lock: Mutex::new(()),
+        }
+    }
+    
+    /// Count an operation performed for a client
+    ///
+    /// Fails with `QuotaExceeded`, without counting the operation, when the
+    /// client's plan allows no more of it this period.
+    pub async fn charge(&self, client_id: Uuid, operation: BillableOperation) -> Result<()> {
+        let config = self.config.compliance();
+        let metering = &config.metering;
+        let quota = metering
+            .plan_for(client_id)
+            .and_then(|(_, plan)| plan.quotas.get(&operation).copied());
+        let now = self.clock.now();
+        let (period_start, period_end) = billing_period(now);
+        
+        let _guard = self.lock.lock().await;
+        let mut record = self
+            .repo
+            .get(client_id, period_start)
+            .await?
+            .unwrap_or_else(|| UsageRecord::empty(client_id, now));
+        let used = record.counts.get(&operation).copied().unwrap_or(0);
+        if let Some(quota) = quota.filter(|quota| used >= *quota) {
+            return Err(ComplianceError::QuotaExceeded {
+                operation: operation.name().to_string(),
+                quota,
+            });
+        }
+        let used = used + 1;
+        record.counts.insert(operation, used);
+        record.updated_at = now;
+        
+        let mut batch = WriteBatch::default();
+        if let Some(quota) = quota {
+            let notified = record.notified.get(&operation).copied().unwrap_or(0);
+            let reached = metering
+                .alert_thresholds
+                .iter()
+                .copied()
+                .filter(|threshold| *threshold > notified && used * 100 >= quota * u64::from(*threshold))
+                .max();
+            if let Some(threshold) = reached {
+                record.notified.insert(operation, threshold);
+                batch.outbox.push(OutboxMessage::new(
+                    client_id,
+                    USAGE_THRESHOLD_EVENT,
+                    serde_json::json!({
+                        "operation": operation,
+                        "threshold_percent": threshold,
+                        "used": used,
+                        "quota": quota,
+                        "period_start": period_start,
+                        "period_end": period_end,
+                    }),
+                    now,
+                ));
+            }
+        }
+        batch.usage.push(record);
+        self.storage.commit(&batch).await
+    }
+    
+    /// Count an operation performed on behalf of the current client, if any
+    pub async fn charge_current(&self, operation: BillableOperation) -> Result<()> {
+        match current_client() {
+            Some(client_id) => self.charge(client_id, operation).await,
+            None => Ok(()),
+        }
+    }
+    
+    /// Record a provider call made for a client, costing it when it succeeded
+    pub async fn record_provider_call(
+        &self,
+        client_id: Uuid,
+        provider: Provider,
+        vendor: &str,
+        cost: f64,
+        succeeded: bool,
+    ) -> Result<()> {
+        let now = self.clock.now();
+        let (period_start, _) = billing_period(now);
+        
+        let _guard = self.lock.lock().await;
+        let mut record = self
+            .repo
+            .get(client_id, period_start)
+            .await?
+            .unwrap_or_else(|| UsageRecord::empty(client_id, now));
+        let index = match record
+            .provider_calls
+            .iter()
+            .position(|usage| usage.provider == provider && usage.vendor == vendor)
+        {
+            Some(index) => index,
+            None => {
+                record.provider_calls.push(ProviderCallUsage {
+                    provider,
+                    vendor: vendor.to_string(),
+                    calls: 0,
+                    failed_calls: 0,
+                    cost: 0.0,
+                });
+                record.provider_calls.len() - 1
+            }
+        };
+        let usage = &mut record.provider_calls[index];
+        if succeeded {
+            usage.calls += 1;
+            usage.cost += cost;
+        } else {
+            usage.failed_calls += 1;
+        }
+        record.updated_at = now;
+        
+        let mut batch = WriteBatch::default();
+        batch.usage.push(record);
+        self.storage.commit(&batch).await
+    }
+    
+    /// What a client's provider calls cost in the billing period containing `at`
+    pub async fn cost_report(&self, client_id: Uuid, at: DateTime<Utc>) -> Result<CostReport> {
+        let (period_start, _) = billing_period(at);
+        let record = self.repo.get(client_id, period_start).await?;
+        let currency = &self.config.compliance().provider_routing.currency;
+        Ok(CostReport::new(currency, client_id, at, record.as_ref()))
+    }
+    
+    /// Cost reports of every client with usage in the billing period containing `at`
+    pub async fn period_costs(&self, at: DateTime<Utc>) -> Result<Vec<CostReport>> {
+        let (period_start, _) = billing_period(at);
+        let config = self.config.compliance();
+        let mut reports: Vec<CostReport> = self
+            .repo
+            .period(period_start)
+            .await?
+            .iter()
+            .map(|record| CostReport::new(&config.provider_routing.currency, record.client_id, at, Some(record)))
+            .collect();
+        reports.sort_by_key(|report| report.client_id);
+        Ok(reports)
+    }
+    
+    /// A client's usage in the billing period containing `at`
+    pub async fn usage(&self, client_id: Uuid, at: DateTime<Utc>) -> Result<UsageSummary> {
+        let (period_start, _) = billing_period(at);
+        let record = self.repo.get(client_id, period_start).await?;
+        Ok(UsageSummary::new(&self.config.compliance().metering, client_id, at, record.as_ref()))
+    }
+    
+    /// Usage of every client with usage in the billing period containing `at`
+    pub async fn period_usage(&self, at: DateTime<Utc>) -> Result<Vec<UsageSummary>> {
+        let (period_start, _) = billing_period(at);
+        let config = self.config.compliance();
+        let mut summaries: Vec<UsageSummary> = self
+            .repo
+            .period(period_start)
+            .await?
+            .iter()
+            .map(|record| UsageSummary::new(&config.metering, record.client_id, at, Some(record)))
+            .collect();
+        summaries.sort_by_key(|summary| summary.client_id);
+        Ok(summaries)
+    }
+}
@@ -1,18 +1,199 @@
 //! Core compliance modules for ZeroTrust Compliance Backend
 
+pub mod claims;
+pub mod freshness;
+pub mod presentation;
+pub mod proof_envelope;
+pub mod rejection;
+pub mod scope;
+
+#[cfg(feature = "server")]
 pub mod kyc;
+#[cfg(feature = "server")]
 pub mod aml;
+#[cfg(feature = "server")]
 pub mod sanctions;
+#[cfg(feature = "server")]
 pub mod attestation;
+#[cfg(feature = "server")]
 pub mod account_components;
+#[cfg(feature = "server")]
 pub mod note_scripts;
+#[cfg(feature = "server")]
+pub mod conditional_notes;
+#[cfg(feature = "server")]
+pub mod gated_transfers;
+#[cfg(feature = "server")]
+pub mod account_watch;
+#[cfg(feature = "server")]
+pub mod schema_versions;
+#[cfg(feature = "server")]
+pub mod velocity;
+#[cfg(feature = "server")]
+pub mod monitoring;
+#[cfg(feature = "server")]
+pub mod step_up;
+#[cfg(feature = "server")]
+pub mod screening;
+#[cfg(feature = "server")]
+pub mod clients;
+#[cfg(feature = "server")]
+pub mod watchlists;
+#[cfg(feature = "server")]
+pub mod attestation_events;
+#[cfg(feature = "server")]
+pub mod challenges;
+#[cfg(feature = "server")]
+pub mod approvals;
+#[cfg(feature = "server")]
+pub mod breaker;
+#[cfg(feature = "server")]
+pub mod proving;
+#[cfg(feature = "server")]
+pub mod portability;
+#[cfg(feature = "server")]
+pub mod event_feed;
+#[cfg(feature = "server")]
+pub mod country_risk;
+#[cfg(feature = "server")]
+pub mod device_risk;
+#[cfg(feature = "server")]
+pub mod risk_factors;
+#[cfg(feature = "server")]
+pub mod source_of_funds;
+#[cfg(feature = "server")]
+pub mod chain_analytics;
+#[cfg(feature = "server")]
+pub mod renewal;
+#[cfg(feature = "server")]
+pub mod provider_callbacks;
+#[cfg(feature = "server")]
+pub mod provider_credentials;
+#[cfg(feature = "server")]
+pub mod provider_routing;
+#[cfg(feature = "server")]
+pub mod oracle;
+#[cfg(feature = "server")]
+pub mod attestation_registry;
+#[cfg(feature = "server")]
+pub mod epochs;
+#[cfg(feature = "server")]
+pub mod notarization;
+#[cfg(feature = "server")]
+pub mod residency;
+#[cfg(feature = "server")]
+pub mod status_page;
+#[cfg(feature = "server")]
+pub mod outbox;
+#[cfg(feature = "server")]
+pub mod workflows;
+#[cfg(feature = "server")]
+pub mod metering;
+#[cfg(feature = "server")]
+pub mod verification_cache;
+#[cfg(feature = "server")]
+pub mod verifying;
+#[cfg(feature = "server")]
+pub mod localization;
+#[cfg(feature = "server")]
+pub mod anomaly;
+#[cfg(feature = "server")]
+pub mod counterparty_graph;
+#[cfg(feature = "server")]
+pub mod document_scanning;
+#[cfg(feature = "server")]
+pub mod image_quality;
+#[cfg(feature = "server")]
+pub mod verification_sessions;
+#[cfg(feature = "server")]
+pub mod upgrades;
+#[cfg(feature = "server")]
+pub mod offboarding;
+#[cfg(feature = "server")]
+pub mod duplicate_identities;
+#[cfg(feature = "server")]
+pub mod presentation_store;
+#[cfg(feature = "server")]
+pub mod threshold_signing;
+#[cfg(feature = "server")]
+pub mod tags;
+#[cfg(feature = "server")]
+pub mod custody;
 
+#[cfg(feature = "server")]
 use crate::{Result, types::*};
+#[cfg(feature = "server")]
+use attestation_events::{AttestationEvent, AttestationEventStore, AttestationState, AttestationStatus};
+#[cfg(feature = "server")]
+use anomaly::AnomalyDetector;
+#[cfg(feature = "server")]
+use breaker::{is_provider_failure, Provider, ProviderBreakers};
+#[cfg(feature = "server")]
+use chain_analytics::ChainAnalyticsService;
+#[cfg(feature = "server")]
+use counterparty_graph::CounterpartyGraph;
+#[cfg(feature = "server")]
+use country_risk::CountryRiskService;
+#[cfg(feature = "server")]
+use device_risk::DeviceRiskService;
+#[cfg(feature = "server")]
+use epochs::EpochBatcher;
+#[cfg(feature = "server")]
+use metering::{BillableOperation, UsageMeter};
+#[cfg(feature = "server")]
+use notarization::TimestampAuthority;
+#[cfg(feature = "server")]
+use provider_routing::ProviderRouter;
+#[cfg(feature = "server")]
+use risk_factors::RiskFactorService;
+#[cfg(feature = "server")]
+use source_of_funds::FundsDeclarationService;
+#[cfg(feature = "server")]
+use proving::{ProofPriority, ProvingQueue};
+#[cfg(feature = "server")]
+use verifying::VerificationPool;
+#[cfg(feature = "server")]
+use localization::attestation_messages;
+#[cfg(feature = "server")]
+use renewal::{PreparedRenewal, RenewalStore};
+#[cfg(feature = "server")]
+use crate::audit::AuditLog;
+#[cfg(feature = "server")]
+use crate::clock::{system_clock, SharedClock};
+#[cfg(feature = "server")]
+use crate::config::{CheckCriticality, FallbackPolicy};
+#[cfg(feature = "server")]
+use crate::storage::{OutboxMessage, UnitOfWork, WriteBatch};
+#[cfg(feature = "server")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
 use miden_client::Client;
+#[cfg(feature = "server")]
 use std::sync::Arc;
+#[cfg(feature = "server")]
 use tokio::sync::RwLock;
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// Audit actor for attestations issued outside any client's request
+#[cfg(feature = "server")]
+pub const CHECK_ACTOR: &str = "system:compliance-check";
+
+/// Audit action and webhook event type for attestations re-issued once their
+/// optional checks complete
+#[cfg(feature = "server")]
+pub const CHECKS_COMPLETED_EVENT: &str = "attestation.checks_completed";
+
+/// Audit actor for proofs generated outside any client's request
+#[cfg(feature = "server")]
+pub const HOLDER_ACTOR: &str = "holder";
+
+/// Audit action recorded for every proof envelope generated
+#[cfg(feature = "server")]
+pub const PROOF_ISSUED_ACTION: &str = "proof.issued";
 
 /// Main compliance service that coordinates all compliance operations
+#[cfg(feature = "server")]
 pub struct ComplianceService {
     /// KYC service
     pub kyc: Arc<kyc::KycService>,
@@ -28,16 +209,94 @@ pub struct ComplianceService {
     
     /// Miden client
     pub miden_client: Arc<RwLock<Client>>,
+    
+    /// Attestation lifecycle events
+    pub events: Arc<AttestationEventStore>,
+    
+    /// Circuit breakers around external providers
+    pub breakers: Arc<ProviderBreakers>,
+    
+    /// Cost-aware routing of provider calls between vendors
+    pub router: Arc<ProviderRouter>,
+    
+    /// Admission queue for proof generation
+    pub proving: Arc<ProvingQueue>,
+    
+    /// Worker pool proofs are verified on
+    pub verifying: Arc<VerificationPool>,
+    
+    /// Geographic risk scoring
+    pub country_risk: Arc<CountryRiskService>,
+    
+    /// Device and IP risk signals
+    pub device_risk: Arc<DeviceRiskService>,
+    
+    /// Risk factors defined in configuration
+    pub risk_factors: Arc<RiskFactorService>,
+    
+    /// Source-of-funds and source-of-wealth declarations
+    pub funds: Arc<FundsDeclarationService>,
+    
+    /// On-chain address risk scoring
+    pub chain_analytics: Arc<ChainAnalyticsService>,
+    
+    /// Risk propagated from counterparties
+    pub counterparties: Arc<CounterpartyGraph>,
+    
+    /// Renewals pre-issued ahead of expiry
+    pub renewals: Arc<RenewalStore>,
+    
+    /// Issued attestations batched for on-chain anchoring
+    pub epochs: Arc<EpochBatcher>,
+    
+    /// Audit log issuance is recorded in
+    pub audit: Arc<AuditLog>,
+    
+    /// Atomic writes across the event store, the audit log, and the webhook outbox
+    pub storage: Arc<dyn UnitOfWork>,
+    
+    /// Billable usage of business clients
+    pub meter: Arc<UsageMeter>,
+    
+    /// Baselines of clients' check outcomes, alerting on deviations
+    pub anomalies: Arc<AnomalyDetector>,
+    
+    /// Time source for expiry and validity checks
+    pub clock: SharedClock,
+    
+    /// Independent timestamping of issuance, when configured
+    pub notary: Option<Arc<dyn TimestampAuthority>>,
 }
 
+#[cfg(feature = "server")]
 impl ComplianceService {
     /// Create a new compliance service
+    ///
+    /// `events` and `audit` must persist to the repositories `storage`
+    /// commits to.
     pub fn new(
         kyc: Arc<kyc::KycService>,
         aml: Arc<aml::AmlService>,
         sanctions: Arc<sanctions::SanctionsService>,
         attestation: Arc<attestation::AttestationService>,
         miden_client: Arc<RwLock<Client>>,
+        events: Arc<AttestationEventStore>,
+        breakers: Arc<ProviderBreakers>,
+        router: Arc<ProviderRouter>,
+        proving: Arc<ProvingQueue>,
+        verifying: Arc<VerificationPool>,
+        country_risk: Arc<CountryRiskService>,
+        device_risk: Arc<DeviceRiskService>,
+        risk_factors: Arc<RiskFactorService>,
+        funds: Arc<FundsDeclarationService>,
+        chain_analytics: Arc<ChainAnalyticsService>,
+        counterparties: Arc<CounterpartyGraph>,
+        renewals: Arc<RenewalStore>,
+        epochs: Arc<EpochBatcher>,
+        audit: Arc<AuditLog>,
+        storage: Arc<dyn UnitOfWork>,
+        meter: Arc<UsageMeter>,
+        anomalies: Arc<AnomalyDetector>,
     ) -> Self {
         Self {
             kyc,
@@ -45,73 +304,772 @@ impl ComplianceService {
             sanctions,
             attestation,
             miden_client,
+            events,
+            breakers,
+            router,
+            proving,
+            verifying,
+            country_risk,
+            device_risk,
+            risk_factors,
+            funds,
+            chain_analytics,
+            counterparties,
+            renewals,
+            epochs,
+            audit,
+            storage,
+            meter,
+            anomalies,
+            clock: system_clock(),
+            notary: None,
         }
     }
     
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Notarize every issued attestation with `notary`
+    pub fn with_notary(mut self, notary: Arc<dyn TimestampAuthority>) -> Self {
+        self.notary = Some(notary);
+        self
+    }
+    
     /// Perform comprehensive compliance check
-    pub async fn comprehensive_check(&self, account_id: &str) -> Result<ComplianceAttestation> {
+    ///
+    /// The returned attestation is not stored. With `dry_run` set the check is
+    /// a preview only: callers must not persist the result, issue notes, or
+    /// emit events for it.
+    ///
+    /// Provider calls go through circuit breakers. When a provider is
+    /// unavailable its configured fallback policy decides the outcome.
+    ///
+    /// The account's geographic, device, custom and on-chain risk can raise,
+    /// but never lower, the AML risk level reported by the provider. The
+    /// resulting level is recorded as the account's own risk in the
+    /// counterparty graph, unless `dry_run` is set, before risk propagated
+    /// from its counterparties can raise it further.
+    ///
+    /// KYC verification and sanctions screening are billed to the current
+    /// client, dry run or not, since the providers are called either way.
+    /// Provider latency counts towards the client's outcome baselines.
+    ///
+    /// Provider calls are routed between vendors by the account's current
+    /// compliance level (see [`provider_routing`]).
+    ///
+    /// An optional check (see [`CheckCriticality`]) that fails or times out
+    /// does not fail the compliance check: the attestation lists it as
+    /// `partial` and, unless `dry_run` is set, it is queued for retry.
+    pub async fn comprehensive_check(&self, account_id: &AccountId, dry_run: bool) -> Result<ComplianceAttestation> {
         // Run all compliance checks in parallel
-        let (kyc_result, aml_result, sanctions_result) = tokio::try_join!(
-            self.kyc.verify_account(account_id),
-            self.aml.assess_risk(account_id),
-            self.sanctions.screen_account(account_id)
-        )?;
+        let checks = tokio::try_join!(
+            async {
+                self.meter.charge_current(BillableOperation::KycVerification).await?;
+                self.call_provider(Provider::Kyc, account_id, || self.kyc.verify_account(account_id)).await
+            },
+            self.call_provider(Provider::Aml, account_id, || self.aml.assess_risk(account_id)),
+            async {
+                self.meter.charge_current(BillableOperation::Screening).await?;
+                self.call_provider(Provider::Sanctions, account_id, || self.sanctions.screen_account(account_id)).await
+            },
+            self.optional_check(
+                Provider::ChainAnalytics,
+                account_id,
+                self.call_provider(
+                    Provider::ChainAnalytics,
+                    account_id,
+                    || self.chain_analytics.account_profile(account_id)
+                )
+            )
+        );
+        let (kyc_result, aml_result, sanctions_result, chain_check) = match checks {
+            Ok(results) => results,
+            Err(error) => return self.provider_fallback(account_id, error, dry_run).await,
+        };
+        let mut partial = Vec::new();
+        let chain_profile = chain_check.unwrap_or_else(|| {
+            partial.push(Provider::ChainAnalytics);
+            None
+        });
         
         // Generate compliance attestation
-        let attestation = self.attestation.generate_attestation(
+        let mut attestation = self.attestation.generate_attestation(
             account_id,
             kyc_result,
             aml_result,
             sanctions_result,
         ).await?;
+        attestation.partial = partial.iter().map(|provider| provider.name().to_string()).collect();
+        if !dry_run {
+            for provider in partial {
+                self.breakers.queue_retry(account_id, provider);
+            }
+        }
+        self.country_risk.apply(&mut attestation).await;
+        self.device_risk.apply(&mut attestation).await;
+        self.risk_factors.apply(&mut attestation).await;
+        if let Some(profile) = &chain_profile {
+            chain_analytics::apply_profile(&mut attestation, profile);
+        }
+        if !dry_run {
+            self.counterparties
+                .record_risk(account_id, attestation.aml_risk_level.clone(), self.clock.now())
+                .await;
+        }
+        self.counterparties.apply(&mut attestation).await;
+        
+        if dry_run {
+            tracing::debug!(account_id = %account_id, "dry-run compliance check completed");
+        }
         
         Ok(attestation)
     }
     
+    /// Call a provider through its breaker, timing calls made on behalf of a client
+    ///
+    /// Calls rejected by an open or probing breaker never reach the provider
+    /// and are not timed. `call` is made once per vendor the call is routed to.
+    async fn call_provider<T, Fut>(
+        &self,
+        provider: Provider,
+        account_id: &AccountId,
+        call: impl Fn() -> Fut,
+    ) -> Result<T>
+    where
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let level = if self.router.routes_provider(provider) {
+            match self.get_compliance_status(account_id).await? {
+                Some(attestation) => self.highest_compliance_level(&attestation).await,
+                None => None,
+            }
+        } else {
+            None
+        };
+        let breaker = self.breakers.get(provider);
+        let client_id = provider_credentials::current_client().filter(|_| breaker.is_closed());
+        let started = std::time::Instant::now();
+        let result = breaker.call(self.router.call(provider, level.as_ref(), call)).await;
+        if let Some(client_id) = client_id {
+            self.anomalies.record_latency(client_id, account_id, provider, started.elapsed()).await;
+        }
+        result
+    }
+    
+    /// Await a provider's check, tolerating its failure when it is optional
+    ///
+    /// Returns `None` when an optional check failed or timed out.
+    async fn optional_check<T>(
+        &self,
+        provider: Provider,
+        account_id: &AccountId,
+        check: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<Option<T>> {
+        match check.await {
+            Ok(result) => Ok(Some(result)),
+            Err(error)
+                if self.breakers.get(provider).criticality() == CheckCriticality::Optional
+                    && is_provider_failure(&error) =>
+            {
+                tracing::warn!(
+                    account_id = %account_id,
+                    provider = provider.name(),
+                    error = %error,
+                    "optional check failed, attestation will be partial"
+                );
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+    
+    /// Apply the fallback policy of an unavailable provider
+    async fn provider_fallback(
+        &self,
+        account_id: &AccountId,
+        error: crate::ComplianceError,
+        dry_run: bool,
+    ) -> Result<ComplianceAttestation> {
+        let Some(provider) = ProviderBreakers::provider_of(&error) else {
+            return Err(error);
+        };
+        
+        match self.breakers.get(provider).fallback() {
+            FallbackPolicy::FailClosed => Err(error),
+            FallbackPolicy::QueueForRetry => {
+                if !dry_run {
+                    self.breakers.queue_retry(account_id, provider);
+                }
+                Err(error)
+            }
+            FallbackPolicy::FailOpenWithFlag => {
+                // Without a valid attestation to keep in force there is nothing to fail open to
+                let now = self.clock.now();
+                let Some(current) = self
+                    .events
+                    .project(account_id, None)
+                    .await?
+                    .filter(|state| state.is_valid_at(now))
+                else {
+                    return Err(error);
+                };
+                
+                tracing::warn!(
+                    account_id = %account_id,
+                    provider = provider.name(),
+                    error = %error,
+                    "provider unavailable, keeping existing attestation"
+                );
+                if !dry_run {
+                    self.events
+                        .append(
+                            account_id,
+                            AttestationEvent::ProviderFallback {
+                                provider: provider.name().to_string(),
+                                reason: error.to_string(),
+                            },
+                        )
+                        .await?;
+                }
+                Ok(current.attestation)
+            }
+        }
+    }
+    
     /// Create a privacy-preserving compliance proof
-    pub async fn create_compliance_proof(&self, account_id: &str) -> Result<String> {
+    ///
+    /// Proof generation waits for a slot in the proving queue and fails with
+    /// `ProverSaturated` when the queue for `priority` is full.
+    pub async fn create_compliance_proof(&self, account_id: &AccountId, priority: ProofPriority) -> Result<String> {
+        if let Some(renewal) = self.prepared_renewal(account_id).await? {
+            return Ok(renewal.proof);
+        }
+        
         // Get the compliance attestation
-        let attestation = self.comprehensive_check(account_id).await?;
+        let attestation = self.comprehensive_check(account_id, false).await?;
         
         // Generate zero-knowledge proof using Miden
-        let proof = self.attestation.generate_zk_proof(&attestation).await?;
+        self.meter.charge_current(BillableOperation::ProofGeneration).await?;
+        let proof = self
+            .proving
+            .run(priority, self.attestation.generate_zk_proof(&attestation))
+            .await?;
         
         Ok(proof)
     }
     
     /// Verify a compliance proof
-    pub async fn verify_compliance_proof(&self, proof: &str, account_id: &str) -> Result<bool> {
-        self.attestation.verify_zk_proof(proof, account_id).await
+    ///
+    /// Runs on the verification pool, off the async runtime, and fails with
+    /// `ProofVerificationTimedOut` when not finished within `deadline`.
+    pub async fn verify_compliance_proof(
+        &self,
+        proof: &str,
+        account_id: &AccountId,
+        deadline: std::time::Duration,
+    ) -> Result<bool> {
+        let attestation = self.attestation.clone();
+        let (proof, account_id) = (proof.to_string(), account_id.clone());
+        self.verifying
+            .run(deadline, async move { attestation.verify_zk_proof(&proof, &account_id).await })
+            .await
+    }
+    
+    /// Create a signed proof envelope bound to a verifier's challenge
+    ///
+    /// A scoped proof is only issued when the account meets the scope's
+    /// minimum compliance level; the scope is signed into the envelope.
+    /// A renewal pre-issued ahead of expiry is used as is, without re-running
+    /// the checks or the prover.
+    ///
+    /// The envelope commits to every claim about the attestation and opens
+    /// those the options list, or else those the challenge asks for. PEP
+    /// status comes from screening, which the caller looks up.
+    ///
+    /// Fails with `ProofTooLarge` when the embedded proof, after compression,
+    /// exceeds `max_proof_size`, so no envelope is handed out that verifiers
+    /// would reject for its size.
+    pub async fn create_proof_envelope(
+        &self,
+        challenge: &challenges::ProofChallenge,
+        scope: Option<scope::ProofScope>,
+        pep_status: claims::PepStatus,
+        signer: &crate::crypto::AttestationSigner,
+        validity: chrono::Duration,
+        options: &proof_envelope::ProofOptions,
+        max_proof_size: usize,
+    ) -> Result<(proof_envelope::ProofEnvelope, proof_envelope::ProofReport)> {
+        let now = self.clock.now();
+        let freshness = challenge.freshness.clone().unwrap_or_default();
+        // A renewal prepared too long ago for the challenge gives way to a fresh check
+        let prepared = self
+            .prepared_renewal(&challenge.account_id)
+            .await?
+            .filter(|renewal| freshness.attestation_fresh(renewal.attestation.created_at, now));
+        let attestation = match &prepared {
+            Some(renewal) => renewal.attestation.clone(),
+            None => self.comprehensive_check(&challenge.account_id, false).await?,
+        };
+        if !freshness.attestation_fresh(attestation.created_at, self.clock.now()) {
+            return Err(crate::ComplianceError::AttestationNotFresh {
+                reason: "attestation is older than the challenge allows".to_string(),
+            });
+        }
+        if let Some(required) = scope.as_ref().and_then(|s| s.min_level.clone()) {
+            if !self.meets_compliance_level(&attestation, required.clone(), self.clock.now()).await {
+                return Err(crate::ComplianceError::CompliancePolicyViolation {
+                    policy: format!("proof scope requires {:?} compliance", required),
+                });
+            }
+        }
+        let started = std::time::Instant::now();
+        let proof = match &prepared {
+            Some(renewal) => renewal.proof.clone(),
+            None => {
+                self.meter.charge_current(BillableOperation::ProofGeneration).await?;
+                self.proving
+                    .run(ProofPriority::Interactive, self.attestation.generate_zk_proof(&attestation))
+                    .await?
+            }
+        };
+        let generation_ms = if prepared.is_some() { 0 } else { started.elapsed().as_millis() as u64 };
+        
+        let age = (self.clock.now() - attestation.created_at).num_seconds().max(0) as u64;
+        let claims = claims::ClaimSet::new(vec![
+            claims::Claim::KycTier(self.highest_compliance_level(&attestation).await),
+            claims::Claim::RiskBand(attestation.aml_risk_level.clone()),
+            claims::Claim::PepStatus(pep_status),
+            claims::Claim::JurisdictionClass(self.country_risk.jurisdiction_class(&challenge.account_id).await),
+            claims::Claim::AttestationAge(age),
+        ])?;
+        
+        let sealing = std::time::Instant::now();
+        let proof = proof.into_bytes();
+        let proof_size = proof.len();
+        let envelope = proof_envelope::ProofEnvelope::seal(
+            proof_envelope::EnvelopeParams {
+                attestation: &attestation,
+                claims: &claims,
+                disclose: options.claims.as_deref().unwrap_or(challenge.claims.as_slice()),
+                audience: &challenge.audience,
+                nonce: &challenge.nonce,
+                scope,
+                proof,
+                compression: options.compression,
+                validity,
+            },
+            signer,
+        )?;
+        if envelope.proof_bytes.len() > max_proof_size {
+            return Err(crate::ComplianceError::ProofTooLarge {
+                size: envelope.proof_bytes.len(),
+                limit: max_proof_size,
+            });
+        }
+        
+        let report = proof_envelope::ProofReport {
+            proof_size,
+            embedded_size: envelope.proof_bytes.len(),
+            compression: options.compression,
+            generation_ms,
+            sealing_ms: sealing.elapsed().as_millis() as u64,
+            prepared: prepared.is_some(),
+        };
+        
+        let actor = provider_credentials::current_client()
+            .map_or_else(|| HOLDER_ACTOR.to_string(), |id| id.to_string());
+        self.audit
+            .record(
+                &actor,
+                PROOF_ISSUED_ACTION,
+                Some(&challenge.account_id),
+                serde_json::json!({
+                    "attestation_id": attestation.id,
+                    "audience": challenge.audience,
+                    "nonce": challenge.nonce,
+                    "expires_at": envelope.expires_at(),
+                    "proof_hash": blake3::hash(&envelope.proof_bytes).to_hex().to_string(),
+                    "prepared": prepared.is_some(),
+                }),
+            )
+            .await;
+        Ok((envelope, report))
     }
     
     /// Update compliance status for an account
-    pub async fn update_compliance_status(&self, account_id: &str) -> Result<ComplianceAttestation> {
+    ///
+    /// A newly issued attestation is committed together with its audit entry
+    /// and, when running on behalf of a business client, the client's
+    /// webhook notification.
+    pub async fn update_compliance_status(&self, account_id: &AccountId) -> Result<ComplianceAttestation> {
         // Re-run compliance checks
-        let attestation = self.comprehensive_check(account_id).await?;
+        let attestation = self.comprehensive_check(account_id, false).await?;
+        
+        // A provider fallback keeps the attestation already in force
+        let current = self.events.project(account_id, None).await?;
+        if current.is_some_and(|state| state.attestation.id == attestation.id) {
+            return Ok(attestation);
+        }
+        
+        self.issue_checked(&attestation).await?;
+        Ok(attestation)
+    }
+    
+    /// Re-run the checks queued for an account once their provider recovers
+    ///
+    /// An account whose attestation is partial has only the missing checks
+    /// run; any other account is checked afresh.
+    pub async fn retry_checks(&self, account_id: &AccountId) -> Result<ComplianceAttestation> {
+        let current = self
+            .events
+            .project(account_id, None)
+            .await?
+            .filter(|state| !state.attestation.partial.is_empty() && state.is_valid_at(self.clock.now()));
+        match current {
+            Some(state) => self.complete_checks(state.attestation).await,
+            None => self.update_compliance_status(account_id).await,
+        }
+    }
+    
+    /// Run the optional checks a partial attestation is missing
+    ///
+    /// Completed checks re-issue the attestation under a new id, superseding
+    /// the partial one instead of changing what it committed to; their results
+    /// can raise the AML risk level. The re-issued attestation lists the
+    /// checks still missing. A check failing again is queued for another
+    /// retry.
+    async fn complete_checks(&self, partial: ComplianceAttestation) -> Result<ComplianceAttestation> {
+        let account_id = partial.account_id.clone();
+        let mut attestation = ComplianceAttestation {
+            id: Uuid::new_v4(),
+            created_at: self.clock.now(),
+            ..partial.clone()
+        };
+        let mut completed = Vec::new();
+        for check in partial.partial.clone() {
+            if check == Provider::ChainAnalytics.name() {
+                let profile = self
+                    .call_provider(Provider::ChainAnalytics, &account_id, || {
+                        self.chain_analytics.account_profile(&account_id)
+                    })
+                    .await
+                    .inspect_err(|_| self.breakers.queue_retry(&account_id, Provider::ChainAnalytics))?;
+                if let Some(profile) = &profile {
+                    chain_analytics::apply_profile(&mut attestation, profile);
+                }
+                completed.push(check);
+            }
+        }
+        if completed.is_empty() {
+            return Ok(partial);
+        }
+        attestation.partial.retain(|check| !completed.contains(check));
         
-        // Store updated attestation
-        self.attestation.store_attestation(&attestation).await?;
+        let actor = provider_credentials::current_client().map_or_else(|| CHECK_ACTOR.to_string(), |id| id.to_string());
+        let details = serde_json::json!({
+            "attestation_id": attestation.id,
+            "previous_attestation_id": partial.id,
+            "checks": completed,
+        });
+        self.issue_attestation(&attestation, &actor, CHECKS_COMPLETED_EVENT, details).await?;
         
+        tracing::info!(
+            account_id = %account_id,
+            attestation_id = %attestation.id,
+            previous_attestation_id = %partial.id,
+            "partial attestation superseded"
+        );
         Ok(attestation)
     }
     
+    /// Issue an attestation from fresh checks, on behalf of the client the task runs for
+    ///
+    /// The checks' outcomes count towards the client's outcome baselines.
+    async fn issue_checked(&self, attestation: &ComplianceAttestation) -> Result<u64> {
+        let actor = provider_credentials::current_client().map_or_else(|| CHECK_ACTOR.to_string(), |id| id.to_string());
+        let details = serde_json::json!({ "attestation_id": attestation.id });
+        let sequence = self.issue_attestation(attestation, &actor, "attestation.issued", details).await?;
+        
+        if let Some(client_id) = provider_credentials::current_client() {
+            let account_id = &attestation.account_id;
+            match attestation.kyc_status {
+                KycStatus::Verified => self.anomalies.record_kyc(client_id, account_id, false).await,
+                KycStatus::Rejected => self.anomalies.record_kyc(client_id, account_id, true).await,
+                _ => {}
+            }
+            self.anomalies
+                .record_screening(client_id, account_id, !attestation.sanctions_cleared)
+                .await;
+        }
+        Ok(sequence)
+    }
+    
+    /// Store an attestation and make it the account's current one
+    ///
+    /// The issuance event, the audit entry recording `action` by `actor`, and
+    /// a webhook notification for the client the task runs on behalf of are
+    /// committed in one batch, so none of them exists without the others. The
+    /// attestation itself is only stored once the batch has committed.
+    ///
+    /// Issuance is notarized when a notary is configured. Notarization is
+    /// best effort: an unreachable authority leaves the attestation without
+    /// evidence rather than failing issuance.
+    ///
+    /// Returns the sequence of the last event appended for the issuance.
+    async fn issue_attestation(
+        &self,
+        attestation: &ComplianceAttestation,
+        actor: &str,
+        action: &str,
+        details: serde_json::Value,
+    ) -> Result<u64> {
+        // Rejection reasons recorded before this issuance carry over to it
+        let kyc_rejection = match provider_credentials::current_client() {
+            Some(_) if attestation.kyc_status != KycStatus::Verified => self
+                .events
+                .project(&attestation.account_id, None)
+                .await?
+                .and_then(|state| state.kyc_rejection),
+            _ => None,
+        };
+        
+        let mut batch = WriteBatch::default();
+        let mut events = self.events.stage().await;
+        let mut audit = self.audit.stage().await;
+        let mut recorded = events
+            .push(
+                &mut batch,
+                &attestation.account_id,
+                AttestationEvent::AttestationIssued { attestation: attestation.clone() },
+            )
+            .await?;
+        audit.push(&mut batch, actor, action, Some(&attestation.account_id), details);
+        if let Some(client_id) = provider_credentials::current_client() {
+            // Completed checks get their own event, so clients can tell them
+            // from a fresh issuance
+            let event_type = match action {
+                CHECKS_COMPLETED_EVENT => CHECKS_COMPLETED_EVENT,
+                _ => "attestation.issued",
+            };
+            batch.outbox.push(OutboxMessage::new(
+                client_id,
+                event_type,
+                serde_json::json!({
+                    "account_id": attestation.account_id,
+                    "attestation_id": attestation.id,
+                    "expires_at": attestation.expires_at,
+                    "partial": attestation.partial,
+                    "messages": attestation_messages(attestation, kyc_rejection.as_ref()),
+                }),
+                self.clock.now(),
+            ));
+        }
+        self.storage.commit(&batch).await?;
+        events.committed(&batch).await;
+        audit.committed();
+        self.attestation.store_attestation(attestation).await?;
+        
+        self.epochs.enqueue(attestation).await;
+        
+        if let Some(notary) = &self.notary {
+            match notary.notarize(&crate::crypto::attestation_commitment(attestation)).await {
+                Ok(notarization) => {
+                    recorded = self
+                        .events
+                        .append(
+                            &attestation.account_id,
+                            AttestationEvent::Notarized {
+                                attestation_id: attestation.id,
+                                notarization,
+                            },
+                        )
+                        .await?;
+                }
+                Err(e) => tracing::warn!(
+                    attestation_id = %attestation.id,
+                    authority = notary.name(),
+                    error = %e,
+                    "attestation notarization failed"
+                ),
+            }
+        }
+        Ok(recorded.sequence)
+    }
+    
+    /// The account's pre-issued renewal, while no later event has changed its attestation
+    async fn prepared_renewal(&self, account_id: &AccountId) -> Result<Option<PreparedRenewal>> {
+        let state = self
+            .events
+            .project(account_id, None)
+            .await?
+            .filter(|state| state.is_valid_at(self.clock.now()));
+        match state {
+            Some(state) => Ok(self.renewals.get(account_id, state.version).await),
+            None => Ok(None),
+        }
+    }
+    
+    /// Revoke the current attestation for an account
+    pub async fn revoke_attestation(&self, account_id: &AccountId, reason: &str, revoked_by: Option<&str>) -> Result<()> {
+        if self.events.project(account_id, None).await?.is_none() {
+            return Err(crate::ComplianceError::AccountNotFound {
+                account_id: account_id.to_string(),
+            });
+        }
+        
+        self.events
+            .append(
+                account_id,
+                AttestationEvent::Revoked {
+                    reason: reason.to_string(),
+                    revoked_by: revoked_by.map(str::to_string),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+    
+    /// Record why the KYC provider rejected an account's verification
+    pub async fn record_kyc_rejection(
+        &self,
+        account_id: &AccountId,
+        rejection: rejection::KycRejection,
+    ) -> Result<AttestationState> {
+        if self.events.project(account_id, None).await?.is_none() {
+            return Err(crate::ComplianceError::AccountNotFound {
+                account_id: account_id.to_string(),
+            });
+        }
+        
+        self.events.append(account_id, AttestationEvent::KycRejected { rejection }).await?;
+        self.events
+            .project(account_id, None)
+            .await?
+            .ok_or_else(|| crate::ComplianceError::internal("attestation projection missing after KYC rejection"))
+    }
+    
+    /// Apply an approved manual override
+    pub async fn apply_override(&self, action: &approvals::OverrideAction, approved_by: Vec<String>) -> Result<AttestationState> {
+        let account_id = action.account_id();
+        let state = self.events.project(account_id, None).await?.ok_or_else(|| crate::ComplianceError::AccountNotFound {
+            account_id: account_id.to_string(),
+        })?;
+        
+        let event = match action {
+            approvals::OverrideAction::SanctionsClearance { .. } => AttestationEvent::SanctionsOverridden {
+                sanctions_cleared: true,
+                approved_by,
+            },
+            approvals::OverrideAction::RevocationReversal { .. } => {
+                if state.status != AttestationStatus::Revoked {
+                    return Err(crate::ComplianceError::validation("action", "attestation is not revoked"));
+                }
+                AttestationEvent::Reinstated { approved_by }
+            }
+            approvals::OverrideAction::RiskDowngrade { aml_risk_level, .. } => AttestationEvent::RiskUpdated {
+                aml_risk_level: aml_risk_level.clone(),
+            },
+        };
+        
+        self.events.append(account_id, event).await?;
+        self.events
+            .project(account_id, None)
+            .await?
+            .ok_or_else(|| crate::ComplianceError::internal("attestation projection missing after override"))
+    }
+    
+    /// Undo an expired time-bounded override
+    pub async fn revert_override(&self, request: &approvals::ApprovalRequest) -> Result<()> {
+        let event = match &request.action {
+            approvals::OverrideAction::SanctionsClearance { .. } => AttestationEvent::SanctionsOverridden {
+                sanctions_cleared: false,
+                approved_by: vec![approvals::EXPIRY_ACTOR.to_string()],
+            },
+            approvals::OverrideAction::RiskDowngrade { .. } => AttestationEvent::RiskUpdated {
+                aml_risk_level: request
+                    .prior_risk_level
+                    .clone()
+                    .ok_or_else(|| crate::ComplianceError::internal("risk downgrade without prior risk level"))?,
+            },
+            approvals::OverrideAction::RevocationReversal { .. } => return Ok(()),
+        };
+        
+        self.events.append(request.action.account_id(), event).await?;
+        Ok(())
+    }
+    
     /// Get compliance status for an account
-    pub async fn get_compliance_status(&self, account_id: &str) -> Result<Option<ComplianceAttestation>> {
-        self.attestation.get_attestation(account_id).await
+    ///
+    /// Revoked attestations are not returned. Accounts without lifecycle events
+    /// fall back to the attestation store.
+    pub async fn get_compliance_status(&self, account_id: &AccountId) -> Result<Option<ComplianceAttestation>> {
+        match self.events.project(account_id, None).await? {
+            Some(state) if state.status == AttestationStatus::Revoked => Ok(None),
+            Some(state) => Ok(Some(state.attestation)),
+            None => self.attestation.get_attestation(account_id).await,
+        }
+    }
+    
+    /// Get the attestation state of an account as it was at a point in time
+    pub async fn get_compliance_state_at(
+        &self,
+        account_id: &AccountId,
+        as_of: DateTime<Utc>,
+    ) -> Result<Option<AttestationState>> {
+        self.events.project(account_id, Some(as_of)).await
     }
     
     /// Check if account meets compliance level requirements
-    pub async fn check_compliance_level(&self, account_id: &str, required_level: ComplianceLevel) -> Result<bool> {
+    pub async fn check_compliance_level(&self, account_id: &AccountId, required_level: ComplianceLevel) -> Result<bool> {
         let attestation = self.get_compliance_status(account_id).await?;
         
         match attestation {
-            Some(att) => Ok(self.meets_compliance_level(&att, required_level)),
+            Some(att) => Ok(self.meets_compliance_level(&att, required_level, self.clock.now()).await),
             None => Ok(false),
         }
     }
     
+    /// Get the highest compliance level an attestation satisfies
+    pub async fn highest_compliance_level(&self, attestation: &ComplianceAttestation) -> Option<ComplianceLevel> {
+        self.highest_compliance_level_at(attestation, self.clock.now()).await
+    }
+    
+    /// Get the highest compliance level an attestation satisfied at a point in time
+    pub async fn highest_compliance_level_at(
+        &self,
+        attestation: &ComplianceAttestation,
+        at: DateTime<Utc>,
+    ) -> Option<ComplianceLevel> {
+        for level in [
+            ComplianceLevel::InstitutionalGrade,
+            ComplianceLevel::Enhanced,
+            ComplianceLevel::Standard,
+            ComplianceLevel::Basic,
+        ] {
+            if self.meets_compliance_level(attestation, level.clone(), at).await {
+                return Some(level);
+            }
+        }
+        None
+    }
+    
     /// Helper function to check if attestation meets compliance level
-    fn meets_compliance_level(&self, attestation: &ComplianceAttestation, required_level: ComplianceLevel) -> bool {
+    ///
+    /// Enhanced and institutional levels also require verified source-of-funds
+    /// and source-of-wealth declarations in force at `at`.
+    async fn meets_compliance_level(
+        &self,
+        attestation: &ComplianceAttestation,
+        required_level: ComplianceLevel,
+        at: DateTime<Utc>,
+    ) -> bool {
+        if !self.funds.satisfies(&attestation.account_id, &required_level, at).await {
+            return false;
+        }
+        
         match required_level {
             ComplianceLevel::Basic => {
                 attestation.kyc_status == KycStatus::Verified && 
@@ -131,7 +1089,7 @@ impl ComplianceService {
                 attestation.kyc_status == KycStatus::Verified &&
                 attestation.sanctions_cleared &&
                 attestation.aml_risk_level == AmlRiskLevel::Low &&
-                !attestation.expires_at.le(&chrono::Utc::now())
+                !attestation.expires_at.le(&at)
             },
         }
     }
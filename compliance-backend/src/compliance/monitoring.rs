@@ -0,0 +1,361 @@
+//! Ingestion of executed transactions reported for ongoing monitoring
+//!
+//! Exchange clients report thousands of executed transactions per second.
+//! Reported transactions are buffered and written in multi-row inserts when
+//! the buffer reaches the batch size or the flush interval elapses. Per-account
+//! aggregates are updated in memory as transactions arrive and checkpointed
+//! periodically, so neither path costs a database round trip per event.
+//!
+//! Transactions still buffered when the process dies are lost; the API only
+//! acknowledges them as accepted, and clients reconcile by `external_id`,
+//! which makes re-reporting harmless.
+
+use crate::config::MonitoringIngestionConfig;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify, RwLock};
+use uuid::Uuid;
+
+/// Columns bound per row of a transaction insert
+const TRANSACTION_COLUMNS: usize = 9;
+
+/// Bind parameters Postgres accepts in one statement
+const MAX_BIND_PARAMS: usize = 65_535;
+
+/// Days of per-day totals kept in each account aggregate
+const AGGREGATE_RETENTION_DAYS: i64 = 90;
+
+/// An executed transaction reported by a business client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitoredTransaction {
+    /// Client-assigned transaction id, unique per client
+    pub external_id: String,
+    
+    pub account_id: AccountId,
+    
+    /// Amount in the asset's base unit
+    pub amount: u64,
+    
+    pub asset: Option<String>,
+    
+    /// Counterparty account or address
+    pub counterparty: Option<String>,
+    
+    /// Transaction type (e.g. "transfer", "withdrawal")
+    pub transaction_type: String,
+    
+    /// When the transaction executed
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Transaction count and volume over a period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Totals {
+    pub count: u64,
+    pub volume: u64,
+}
+
+impl Totals {
+    fn add(&mut self, amount: u64) {
+        self.count += 1;
+        self.volume = self.volume.saturating_add(amount);
+    }
+}
+
+/// Running totals of an account's monitored transactions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountAggregate {
+    /// Totals since the account was first reported
+    pub lifetime: Totals,
+    
+    pub first_seen: Option<DateTime<Utc>>,
+    
+    pub last_seen: Option<DateTime<Utc>>,
+    
+    /// Per-day totals over the retention window, keyed by UTC date
+    pub daily: BTreeMap<NaiveDate, Totals>,
+}
+
+impl AccountAggregate {
+    /// Totals over the `days` UTC days ending with the day of `now`
+    pub fn window(&self, days: u32, now: DateTime<Utc>) -> Totals {
+        let today = now.date_naive();
+        let first = today - Duration::days(days.saturating_sub(1) as i64);
+        self.daily.range(first..=today).fold(Totals::default(), |mut totals, (_, day)| {
+            totals.count += day.count;
+            totals.volume = totals.volume.saturating_add(day.volume);
+            totals
+        })
+    }
+    
+    fn apply(&mut self, transaction: &MonitoredTransaction) {
+        self.lifetime.add(transaction.amount);
+        self.first_seen = Some(self.first_seen.map_or(transaction.occurred_at, |at| at.min(transaction.occurred_at)));
+        self.last_seen = Some(self.last_seen.map_or(transaction.occurred_at, |at| at.max(transaction.occurred_at)));
+        self.daily.entry(transaction.occurred_at.date_naive()).or_default().add(transaction.amount);
+        
+        let cutoff = Utc::now().date_naive() - Duration::days(AGGREGATE_RETENTION_DAYS);
+        self.daily = self.daily.split_off(&cutoff);
+    }
+}
+
+/// A transaction waiting to be written
+#[derive(Debug, Clone)]
+struct Buffered {
+    client_id: Uuid,
+    received_at: DateTime<Utc>,
+    transaction: MonitoredTransaction,
+}
+
+/// Buffers reported transactions and maintains per-account aggregates
+pub struct TransactionMonitor {
+    pool: PgPool,
+    config: MonitoringIngestionConfig,
+    
+    /// Accepted transactions not yet written, oldest first
+    buffer: Mutex<VecDeque<Buffered>>,
+    
+    /// Woken when the buffer reaches the batch size
+    flush_requested: Notify,
+    
+    /// Serializes flushes so batches are written in acceptance order
+    flushing: Mutex<()>,
+    
+    aggregates: RwLock<HashMap<AccountId, AccountAggregate>>,
+    
+    /// Accounts whose aggregate changed since the last checkpoint
+    dirty: Mutex<HashSet<AccountId>>,
+}
+
+impl TransactionMonitor {
+    /// Create a monitor writing to the given database
+    pub fn new(pool: PgPool, config: MonitoringIngestionConfig) -> Self {
+        Self {
+            pool,
+            config,
+            buffer: Mutex::new(VecDeque::new()),
+            flush_requested: Notify::new(),
+            flushing: Mutex::new(()),
+            aggregates: RwLock::new(HashMap::new()),
+            dirty: Mutex::new(HashSet::new()),
+        }
+    }
+    
+    /// Load checkpointed aggregates, then fold in transactions written after each checkpoint
+    pub async fn restore(&self) -> Result<usize> {
+        let mut aggregates = HashMap::new();
+        let rows = sqlx::query("SELECT account_id, aggregate FROM monitoring_account_aggregates")
+            .fetch_all(&self.pool)
+            .await?;
+        for row in rows {
+            let account_id = AccountId::parse(row.try_get::<&str, _>("account_id")?)?;
+            let Json(aggregate): Json<AccountAggregate> = row.try_get("aggregate")?;
+            aggregates.insert(account_id, aggregate);
+        }
+        
+        let rows = sqlx::query(
+            "SELECT t.account_id, t.external_id, t.amount, t.asset, t.counterparty, t.transaction_type, t.occurred_at \
+             FROM monitored_transactions t \
+             LEFT JOIN monitoring_account_aggregates a ON a.account_id = t.account_id \
+             WHERE a.checkpointed_at IS NULL OR t.received_at > a.checkpointed_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let replayed = rows.len();
+        let mut dirty = self.dirty.lock().await;
+        for row in rows {
+            let transaction = MonitoredTransaction {
+                external_id: row.try_get("external_id")?,
+                account_id: AccountId::parse(row.try_get::<&str, _>("account_id")?)?,
+                amount: row.try_get::<i64, _>("amount")? as u64,
+                asset: row.try_get("asset")?,
+                counterparty: row.try_get("counterparty")?,
+                transaction_type: row.try_get("transaction_type")?,
+                occurred_at: row.try_get("occurred_at")?,
+            };
+            aggregates.entry(transaction.account_id.clone()).or_default().apply(&transaction);
+            dirty.insert(transaction.account_id);
+        }
+        drop(dirty);
+        
+        tracing::info!(accounts = aggregates.len(), replayed, "monitoring aggregates restored");
+        *self.aggregates.write().await = aggregates;
+        Ok(replayed)
+    }
+    
+    /// Accept reported transactions for writing
+    ///
+    /// Aggregates reflect the transactions immediately. Fails without
+    /// accepting any of them when the buffer cannot take the whole batch.
+    pub async fn ingest(&self, client_id: Uuid, transactions: Vec<MonitoredTransaction>) -> Result<usize> {
+        let accepted = transactions.len();
+        let received_at = Utc::now();
+        
+        let buffered = {
+            let mut buffer = self.buffer.lock().await;
+            if buffer.len() + accepted > self.config.max_buffered_transactions {
+                return Err(ComplianceError::MonitoringBackpressure {
+                    retry_after_secs: self.config.flush_interval_ms.div_ceil(1000).max(1),
+                });
+            }
+            buffer.extend(transactions.iter().cloned().map(|transaction| Buffered {
+                client_id,
+                received_at,
+                transaction,
+            }));
+            buffer.len()
+        };
+        
+        {
+            let mut aggregates = self.aggregates.write().await;
+            let mut dirty = self.dirty.lock().await;
+            for transaction in &transactions {
+                aggregates.entry(transaction.account_id.clone()).or_default().apply(transaction);
+                dirty.insert(transaction.account_id.clone());
+            }
+        }
+        
+        if buffered >= self.config.flush_batch_size {
+            self.flush_requested.notify_one();
+        }
+        Ok(accepted)
+    }
+    
+    /// Current aggregate of an account
+    pub async fn aggregate(&self, account_id: &AccountId) -> Option<AccountAggregate> {
+        self.aggregates.read().await.get(account_id).cloned()
+    }
+    
+    /// Number of accepted transactions not yet written
+    pub async fn buffered(&self) -> usize {
+        self.buffer.lock().await.len()
+    }
+    
+    /// Write every buffered transaction in batches
+    ///
+    /// On failure the unwritten transactions are returned to the front of the
+    /// buffer, in order, to be retried by the next flush.
+    pub async fn flush(&self) -> Result<usize> {
+        let _flushing = self.flushing.lock().await;
+        let mut pending: VecDeque<Buffered> = std::mem::take(&mut *self.buffer.lock().await);
+        let batch_size = self
+            .config
+            .flush_batch_size
+            .clamp(1, MAX_BIND_PARAMS / TRANSACTION_COLUMNS);
+        
+        let mut written = 0;
+        while !pending.is_empty() {
+            let batch: Vec<Buffered> = pending.drain(..batch_size.min(pending.len())).collect();
+            if let Err(e) = self.insert(&batch).await {
+                let mut buffer = self.buffer.lock().await;
+                for item in batch.into_iter().chain(pending).rev() {
+                    buffer.push_front(item);
+                }
+                tracing::warn!(written, requeued = buffer.len(), error = %e, "monitored transaction flush failed");
+                return Err(e);
+            }
+            written += batch.len();
+        }
+        
+        if written > 0 {
+            tracing::debug!(written, "monitored transactions flushed");
+        }
+        Ok(written)
+    }
+    
+    /// Write the aggregates of every account changed since the last checkpoint
+    pub async fn checkpoint(&self) -> Result<usize> {
+        let accounts: Vec<AccountId> = self.dirty.lock().await.drain().collect();
+        if accounts.is_empty() {
+            return Ok(0);
+        }
+        let checkpointed_at = Utc::now();
+        let snapshot: Vec<(AccountId, AccountAggregate)> = {
+            let aggregates = self.aggregates.read().await;
+            accounts
+                .iter()
+                .filter_map(|account_id| aggregates.get(account_id).map(|a| (account_id.clone(), a.clone())))
+                .collect()
+        };
+        
+        for chunk in snapshot.chunks(MAX_BIND_PARAMS / 3) {
+            let mut query: QueryBuilder<Postgres> =
+                QueryBuilder::new("INSERT INTO monitoring_account_aggregates (account_id, aggregate, checkpointed_at) ");
+            query.push_values(chunk, |mut row, (account_id, aggregate)| {
+                row.push_bind(account_id.to_string())
+                    .push_bind(Json(aggregate))
+                    .push_bind(checkpointed_at);
+            });
+            query.push(
+                " ON CONFLICT (account_id) DO UPDATE \
+                 SET aggregate = EXCLUDED.aggregate, checkpointed_at = EXCLUDED.checkpointed_at",
+            );
+            if let Err(e) = query.build().execute(&self.pool).await {
+                self.dirty.lock().await.extend(accounts);
+                return Err(e.into());
+            }
+        }
+        
+        tracing::debug!(accounts = snapshot.len(), "monitoring aggregates checkpointed");
+        Ok(snapshot.len())
+    }
+    
+    /// Write everything outstanding, for graceful shutdown
+    pub async fn drain(&self) -> Result<()> {
+        self.flush().await?;
+        self.checkpoint().await?;
+        Ok(())
+    }
+    
+    async fn insert(&self, batch: &[Buffered]) -> Result<()> {
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO monitored_transactions \
+             (client_id, external_id, account_id, amount, asset, counterparty, transaction_type, occurred_at, received_at) ",
+        );
+        query.push_values(batch, |mut row, item| {
+            let transaction = &item.transaction;
+            row.push_bind(item.client_id)
+                .push_bind(&transaction.external_id)
+                .push_bind(transaction.account_id.to_string())
+                .push_bind(transaction.amount.min(i64::MAX as u64) as i64)
+                .push_bind(&transaction.asset)
+                .push_bind(&transaction.counterparty)
+                .push_bind(&transaction.transaction_type)
+                .push_bind(transaction.occurred_at)
+                .push_bind(item.received_at);
+        });
+        // A client re-reporting a transaction must not count it twice
+        query.push(" ON CONFLICT (client_id, external_id) DO NOTHING");
+        query.build().execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+/// Flush on the batch size or interval, and checkpoint aggregates, in the background
+pub fn spawn_writer(monitor: Arc<TransactionMonitor>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut flush = tokio::time::interval(std::time::Duration::from_millis(monitor.config.flush_interval_ms));
+        let mut checkpoint =
+            tokio::time::interval(std::time::Duration::from_secs(monitor.config.checkpoint_interval_secs));
+        loop {
+            tokio::select! {
+                _ = flush.tick() => {}
+                _ = monitor.flush_requested.notified() => {}
+                _ = checkpoint.tick() => {
+                    if let Err(e) = monitor.checkpoint().await {
+                        tracing::error!(error = %e, "monitoring aggregate checkpoint failed");
+                    }
+                    continue;
+                }
+            }
+            // Failures are logged by `flush` and retried on the next tick
+            let _ = monitor.flush().await;
+        }
+    })
+}
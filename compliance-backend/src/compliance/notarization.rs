@@ -0,0 +1,295 @@
+//! Independent evidence of attestation issuance times
+//!
+//! Issuance times recorded by the backend are self-asserted. When a
+//! notarization backend is configured, every issued attestation's canonical
+//! commitment is also submitted to an outside party and the evidence is
+//! stored with the attestation:
+//!
+//! - an RFC 3161 timestamp authority returns a signed token proving the
+//!   commitment existed no later than the token's time
+//! - an L1 block reference binds the commitment to the latest block hash of
+//!   an Ethereum-compatible chain, proving the attestation was not signed
+//!   before that block
+//!
+//! Tokens are stored as returned and verified with standard tooling such as
+//! `openssl ts -verify`; the message imprint is the SHA-256 digest of the
+//! commitment bytes.
+
+use crate::clock::SharedClock;
+use crate::config::NotarizationBackend;
+use crate::crypto::{AttestationSigner, Commitment};
+use crate::{ComplianceError, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Domain separator of block reference bindings
+const BLOCK_BINDING_DOMAIN: &[u8] = b"zerotrust-compliance-block-reference-v1";
+
+/// DER AlgorithmIdentifier of SHA-256 with NULL parameters
+const SHA256_ALGORITHM: [u8; 15] = [
+    0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00,
+];
+
+/// Evidence of when an attestation was issued
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Notarization {
+    /// RFC 3161 timestamp token
+    Rfc3161 {
+        /// Timestamp authority the token was requested from
+        authority: String,
+        /// Hex-encoded SHA-256 digest of the commitment, as timestamped
+        message_imprint: String,
+        /// Base64-encoded DER TimeStampToken
+        token: String,
+        requested_at: DateTime<Utc>,
+    },
+    /// Reference to an L1 block preceding issuance
+    BlockReference {
+        chain: String,
+        block_number: u64,
+        /// Hex-encoded block hash
+        block_hash: String,
+        block_timestamp: DateTime<Utc>,
+        /// Key that signed the binding
+        key_id: String,
+        /// Hex-encoded Ed25519 signature over the domain separator, commitment
+        /// bytes, block hash and block number
+        binding: String,
+    },
+}
+
+/// An outside party that can attest to when a commitment existed
+pub trait TimestampAuthority: Send + Sync {
+    /// Authority name used in logs
+    fn name(&self) -> &'static str;
+    
+    /// Obtain evidence for a commitment
+    fn notarize<'a>(&'a self, commitment: &'a Commitment) -> BoxFuture<'a, Result<Notarization>>;
+}
+
+/// Build an authority from configuration
+pub fn from_config(
+    backend: &NotarizationBackend,
+    signer: Arc<AttestationSigner>,
+    clock: SharedClock,
+) -> Arc<dyn TimestampAuthority> {
+    let http = reqwest::Client::new();
+    match backend.clone() {
+        NotarizationBackend::Rfc3161 { url } => Arc::new(Rfc3161Authority { http, url, clock }),
+        NotarizationBackend::BlockReference { chain, rpc_url } => Arc::new(BlockReferenceAuthority {
+            http,
+            chain,
+            rpc_url,
+            signer,
+        }),
+    }
+}
+
+/// RFC 3161 timestamp authority reached over HTTP
+pub struct Rfc3161Authority {
+    http: reqwest::Client,
+    url: String,
+    clock: SharedClock,
+}
+
+impl TimestampAuthority for Rfc3161Authority {
+    fn name(&self) -> &'static str {
+        "rfc3161"
+    }
+    
+    fn notarize<'a>(&'a self, commitment: &'a Commitment) -> BoxFuture<'a, Result<Notarization>> {
+        Box::pin(async move {
+            let imprint: [u8; 32] = Sha256::digest(commitment.to_bytes()).into();
+            let nonce = rand::random::<u64>() >> 2 | 1 << 62;
+            let requested_at = self.clock.now();
+            let response = self
+                .http
+                .post(&self.url)
+                .header("Content-Type", "application/timestamp-query")
+                .body(timestamp_request(&imprint, nonce))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(notarization_error(self.name(), format!("authority returned {}", response.status())));
+            }
+            let token = timestamp_token(&response.bytes().await?).map_err(|e| notarization_error(self.name(), e))?;
+            
+            Ok(Notarization::Rfc3161 {
+                authority: self.url.clone(),
+                message_imprint: hex::encode(imprint),
+                token: STANDARD.encode(token),
+                requested_at,
+            })
+        })
+    }
+}
+
+/// DER TimeStampReq for a SHA-256 imprint, requesting the TSA certificate
+pub fn timestamp_request(imprint: &[u8; 32], nonce: u64) -> Vec<u8> {
+    let mut message_imprint = SHA256_ALGORITHM.to_vec();
+    message_imprint.extend_from_slice(&[0x04, 0x20]);
+    message_imprint.extend_from_slice(imprint);
+    
+    let mut body = vec![0x02, 0x01, 0x01];
+    body.extend(der(0x30, &message_imprint));
+    body.extend(der(0x02, &nonce.to_be_bytes()));
+    body.extend_from_slice(&[0x01, 0x01, 0xff]);
+    der(0x30, &body)
+}
+
+/// TimeStampToken of a TimeStampResp, if the request was granted
+pub fn timestamp_token(response: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let (tag, body, _) = der_element(response)?;
+    if tag != 0x30 {
+        return Err("response is not a DER sequence".to_string());
+    }
+    let (tag, status_info, token) = der_element(body)?;
+    if tag != 0x30 {
+        return Err("response has no status".to_string());
+    }
+    let (tag, status, _) = der_element(status_info)?;
+    if tag != 0x02 || status.len() != 1 {
+        return Err("response status is malformed".to_string());
+    }
+    // granted (0) or grantedWithMods (1)
+    if status[0] > 1 {
+        return Err(format!("request rejected with status {}", status[0]));
+    }
+    if token.is_empty() {
+        return Err("response carries no token".to_string());
+    }
+    Ok(token.to_vec())
+}
+
+/// DER element with a definite length
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut element = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        element.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        element.push(0x80 | bytes.len() as u8);
+        element.extend(bytes);
+    }
+    element.extend_from_slice(content);
+    element
+}
+
+/// Split the first DER element into its tag, content and the bytes after it
+fn der_element(bytes: &[u8]) -> std::result::Result<(u8, &[u8], &[u8]), String> {
+    let truncated = || "truncated DER element".to_string();
+    let (&tag, rest) = bytes.split_first().ok_or_else(truncated)?;
+    let (&first, rest) = rest.split_first().ok_or_else(truncated)?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+            return Err("unsupported DER length".to_string());
+        }
+        let len = rest[..count].iter().fold(0usize, |len, b| len << 8 | *b as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        return Err(truncated());
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+/// Latest block of an Ethereum-compatible chain, read over JSON-RPC
+pub struct BlockReferenceAuthority {
+    http: reqwest::Client,
+    chain: String,
+    rpc_url: String,
+    signer: Arc<AttestationSigner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<RpcBlock>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcBlock {
+    number: String,
+    hash: String,
+    timestamp: String,
+}
+
+/// Message signed to bind a commitment to a block
+pub fn block_binding_message(commitment: &Commitment, block_hash: &[u8], block_number: u64) -> Vec<u8> {
+    let mut message = BLOCK_BINDING_DOMAIN.to_vec();
+    message.extend_from_slice(&commitment.to_bytes());
+    message.extend_from_slice(block_hash);
+    message.extend_from_slice(&block_number.to_be_bytes());
+    message
+}
+
+impl TimestampAuthority for BlockReferenceAuthority {
+    fn name(&self) -> &'static str {
+        "block_reference"
+    }
+    
+    fn notarize<'a>(&'a self, commitment: &'a Commitment) -> BoxFuture<'a, Result<Notarization>> {
+        Box::pin(async move {
+            let response = self
+                .http
+                .post(&self.rpc_url)
+                .json(&serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "eth_getBlockByNumber",
+                    "params": ["latest", false],
+                }))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(notarization_error(self.name(), format!("node returned {}", response.status())));
+            }
+            let response: RpcResponse = response.json().await?;
+            let block = match (response.result, response.error) {
+                (Some(block), _) => block,
+                (None, error) => {
+                    return Err(notarization_error(self.name(), format!("no block returned: {:?}", error)));
+                }
+            };
+            
+            let block_number = quantity(&block.number).map_err(|e| notarization_error(self.name(), e))?;
+            let timestamp = quantity(&block.timestamp).map_err(|e| notarization_error(self.name(), e))?;
+            let block_timestamp = DateTime::from_timestamp(timestamp as i64, 0)
+                .ok_or_else(|| notarization_error(self.name(), "block timestamp out of range"))?;
+            let block_hash = hex::decode(block.hash.trim_start_matches("0x"))
+                .map_err(|e| notarization_error(self.name(), format!("invalid block hash: {}", e)))?;
+            let binding = self.signer.sign(&block_binding_message(commitment, &block_hash, block_number))?;
+            
+            Ok(Notarization::BlockReference {
+                chain: self.chain.clone(),
+                block_number,
+                block_hash: hex::encode(block_hash),
+                block_timestamp,
+                key_id: self.signer.key_id().to_string(),
+                binding: hex::encode(binding),
+            })
+        })
+    }
+}
+
+/// Decode a JSON-RPC hex quantity
+fn quantity(value: &str) -> std::result::Result<u64, String> {
+    u64::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|e| format!("invalid quantity {}: {}", value, e))
+}
+
+fn notarization_error(authority: &str, reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::ProviderUnavailable {
+        provider: "notarization".to_string(),
+        reason: format!("{}: {}", authority, reason.into()),
+    }
+}
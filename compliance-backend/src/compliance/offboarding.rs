@@ -0,0 +1,603 @@
+//! Account offboarding
+//!
+//! Offboarding an account runs as a job of ordered steps: the account's
+//! attestation is revoked, outstanding compliance notes addressed to it are
+//! voided, erasure of its PII is scheduled for the end of the retention
+//! period, the client is notified with an `account.offboarded` webhook, and a
+//! tombstone is left recording that the account was offboarded. Each step is
+//! recorded on the job as it settles. A failed step stops the job, and a
+//! retry resumes from that step; steps are safe to run again.
+//!
+//! Notes are voided by consuming them with the configured void account, so
+//! only notes that account is able to consume, such as reclaimable notes it
+//! sent, can be voided. Notes it cannot consume are reported as outstanding.
+//!
+//! Once retention ends, the erasure sweeper deletes the documents uploaded
+//! in the account's verification sessions and announces it with an
+//! `account.pii_erased` webhook. The tombstone stays, so an offboarded
+//! account cannot be checked or offboarded again.
+
+use super::account_components::foreign::miden_account_id;
+use super::attestation_events::AttestationStatus;
+use super::verification_sessions::VerificationSessionService;
+use super::ComplianceService;
+use crate::reload::LiveConfig;
+use crate::scheduler::Job;
+use crate::storage::{OutboxMessage, WriteBatch};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_objects::note::NoteId;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Webhook event sent once an account is offboarded
+pub const OFFBOARDED_EVENT: &str = "account.offboarded";
+
+/// Webhook event sent once an offboarded account's PII is erased
+pub const PII_ERASED_EVENT: &str = "account.pii_erased";
+
+/// Audit actor for erasures carried out by the sweeper
+pub const ERASURE_ACTOR: &str = "system:erasure";
+
+/// Name of the job erasing PII whose retention has ended
+pub const ERASURE_JOB: &str = "pii_erasure";
+
+/// Step of an offboarding job, in the order steps run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffboardingStep {
+    RevokeAttestation,
+    VoidNotes,
+    ScheduleErasure,
+    NotifyClient,
+    RecordTombstone,
+}
+
+impl OffboardingStep {
+    /// Every step, in order
+    pub const ALL: [Self; 5] = [
+        Self::RevokeAttestation,
+        Self::VoidNotes,
+        Self::ScheduleErasure,
+        Self::NotifyClient,
+        Self::RecordTombstone,
+    ];
+    
+    /// Name of the step in logs
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RevokeAttestation => "revoke_attestation",
+            Self::VoidNotes => "void_notes",
+            Self::ScheduleErasure => "schedule_erasure",
+            Self::NotifyClient => "notify_client",
+            Self::RecordTombstone => "record_tombstone",
+        }
+    }
+}
+
+/// Status of an offboarding job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffboardingStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Status of a step of an offboarding job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// State of one step of an offboarding job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepState {
+    pub step: OffboardingStep,
+    pub status: StepStatus,
+    
+    /// What the step did, once completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<serde_json::Value>,
+    
+    /// Why the step last failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    
+    pub attempts: u32,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// An account's offboarding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffboardingJob {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub account_id: AccountId,
+    pub reason: String,
+    pub requested_by: String,
+    pub status: OffboardingStatus,
+    pub steps: Vec<StepState>,
+    
+    /// Notes voided by consuming them with the void account, as hex ids
+    pub voided_notes: Vec<String>,
+    
+    /// Notes addressed to the account that could not be voided, as hex ids
+    pub outstanding_notes: Vec<String>,
+    
+    /// When the account's PII is erased, once scheduled
+    pub erasure_due_at: Option<DateTime<Utc>>,
+    
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OffboardingJob {
+    /// First step not yet completed
+    pub fn next_step(&self) -> Option<OffboardingStep> {
+        self.steps
+            .iter()
+            .find(|state| state.status != StepStatus::Completed)
+            .map(|state| state.step)
+    }
+}
+
+/// Record that an account was offboarded, kept after its PII is erased
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub account_id: AccountId,
+    pub client_id: Uuid,
+    pub job_id: Uuid,
+    pub reason: String,
+    pub offboarded_at: DateTime<Utc>,
+    pub erasure_due_at: DateTime<Utc>,
+    pub erased_at: Option<DateTime<Utc>>,
+    
+    /// Documents deleted by the erasure
+    pub erased_documents: usize,
+}
+
+/// Service running offboarding jobs and erasing offboarded accounts' PII
+pub struct OffboardingService {
+    compliance: Arc<ComplianceService>,
+    sessions: Arc<VerificationSessionService>,
+    
+    /// Live configuration holding the retention period and the void account
+    config: Arc<LiveConfig>,
+    
+    jobs: RwLock<HashMap<Uuid, OffboardingJob>>,
+    tombstones: RwLock<HashMap<AccountId, Tombstone>>,
+}
+
+impl OffboardingService {
+    /// Create a service erasing PII through `sessions`
+    pub fn new(
+        compliance: Arc<ComplianceService>,
+        sessions: Arc<VerificationSessionService>,
+        config: Arc<LiveConfig>,
+    ) -> Self {
+        Self {
+            compliance,
+            sessions,
+            config,
+            jobs: RwLock::new(HashMap::new()),
+            tombstones: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Open an offboarding job for an account of `client_id`
+    ///
+    /// Fails when the account is already offboarded or being offboarded. The
+    /// job's steps run with [`OffboardingService::run`].
+    pub async fn start(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        reason: &str,
+        requested_by: &str,
+    ) -> Result<OffboardingJob> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(ComplianceError::validation("reason", "must not be empty"));
+        }
+        self.ensure_active(account_id).await?;
+        
+        let mut jobs = self.jobs.write().await;
+        if jobs
+            .values()
+            .any(|job| &job.account_id == account_id && job.status != OffboardingStatus::Completed)
+        {
+            return Err(ComplianceError::validation("account_id", "account is already being offboarded"));
+        }
+        let now = self.compliance.clock.now();
+        let job = OffboardingJob {
+            id: Uuid::new_v4(),
+            client_id,
+            account_id: account_id.clone(),
+            reason: reason.to_string(),
+            requested_by: requested_by.to_string(),
+            status: OffboardingStatus::Running,
+            steps: OffboardingStep::ALL
+                .into_iter()
+                .map(|step| StepState {
+                    step,
+                    status: StepStatus::Pending,
+                    outcome: None,
+                    error: None,
+                    attempts: 0,
+                    completed_at: None,
+                })
+                .collect(),
+            voided_notes: Vec::new(),
+            outstanding_notes: Vec::new(),
+            erasure_due_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        jobs.insert(job.id, job.clone());
+        Ok(job)
+    }
+    
+    /// Get one of a client's jobs
+    pub async fn get(&self, client_id: Uuid, job_id: Uuid) -> Result<OffboardingJob> {
+        self.jobs
+            .read()
+            .await
+            .get(&job_id)
+            .filter(|job| job.client_id == client_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::OffboardingJobNotFound {
+                job_id: job_id.to_string(),
+            })
+    }
+    
+    /// A client's jobs for an account, newest first
+    pub async fn for_account(&self, client_id: Uuid, account_id: &AccountId) -> Vec<OffboardingJob> {
+        let mut jobs: Vec<OffboardingJob> = self
+            .jobs
+            .read()
+            .await
+            .values()
+            .filter(|job| job.client_id == client_id && &job.account_id == account_id)
+            .cloned()
+            .collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        jobs
+    }
+    
+    /// Tombstone of an offboarded account
+    pub async fn tombstone(&self, account_id: &AccountId) -> Option<Tombstone> {
+        self.tombstones.read().await.get(account_id).cloned()
+    }
+    
+    /// Fail with `AccountOffboarded` if the account was offboarded
+    pub async fn ensure_active(&self, account_id: &AccountId) -> Result<()> {
+        if self.tombstones.read().await.contains_key(account_id) {
+            return Err(ComplianceError::AccountOffboarded {
+                account_id: account_id.to_string(),
+            });
+        }
+        Ok(())
+    }
+    
+    /// Resume a failed job of a client from the step that failed
+    pub async fn retry(&self, client_id: Uuid, job_id: Uuid) -> Result<OffboardingJob> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs
+            .get_mut(&job_id)
+            .filter(|job| job.client_id == client_id)
+            .ok_or_else(|| ComplianceError::OffboardingJobNotFound {
+                job_id: job_id.to_string(),
+            })?;
+        if job.status != OffboardingStatus::Failed {
+            return Err(ComplianceError::validation("job_id", "only failed jobs can be retried"));
+        }
+        job.status = OffboardingStatus::Running;
+        job.updated_at = self.compliance.clock.now();
+        Ok(job.clone())
+    }
+    
+    /// Run a job's remaining steps in order, stopping at the first that fails
+    pub async fn run(&self, job_id: Uuid) -> Result<OffboardingJob> {
+        loop {
+            let job = self.job(job_id).await?;
+            if job.status != OffboardingStatus::Running {
+                return Ok(job);
+            }
+            let Some(step) = job.next_step() else {
+                return self.finish(job_id, OffboardingStatus::Completed).await;
+            };
+            
+            let result = self.run_step(&job, step).await;
+            let now = self.compliance.clock.now();
+            let mut jobs = self.jobs.write().await;
+            let job = jobs.get_mut(&job_id).ok_or_else(|| ComplianceError::OffboardingJobNotFound {
+                job_id: job_id.to_string(),
+            })?;
+            let state = job
+                .steps
+                .iter_mut()
+                .find(|state| state.step == step)
+                .ok_or_else(|| ComplianceError::internal("offboarding step missing from job"))?;
+            state.attempts += 1;
+            job.updated_at = now;
+            match result {
+                Ok(outcome) => {
+                    state.status = StepStatus::Completed;
+                    state.outcome = Some(outcome);
+                    state.error = None;
+                    state.completed_at = Some(now);
+                }
+                Err(e) => {
+                    tracing::warn!(job_id = %job_id, step = step.name(), error = %e, "offboarding step failed");
+                    state.status = StepStatus::Failed;
+                    state.error = Some(e.to_string());
+                    job.status = OffboardingStatus::Failed;
+                }
+            }
+        }
+    }
+    
+    /// Run a job in the background
+    pub fn spawn(self: &Arc<Self>, job_id: Uuid) -> tokio::task::JoinHandle<()> {
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = service.run(job_id).await {
+                tracing::error!(job_id = %job_id, error = %e, "offboarding job aborted");
+            }
+        })
+    }
+    
+    /// Erase the PII of offboarded accounts whose retention has ended
+    ///
+    /// Returns the tombstones of the accounts erased. An account whose
+    /// erasure fails is retried on the next sweep.
+    pub async fn erase_due(&self) -> Result<Vec<Tombstone>> {
+        let now = self.compliance.clock.now();
+        let due: Vec<Tombstone> = self
+            .tombstones
+            .read()
+            .await
+            .values()
+            .filter(|tombstone| tombstone.erased_at.is_none() && tombstone.erasure_due_at <= now)
+            .cloned()
+            .collect();
+        
+        let mut erased = Vec::with_capacity(due.len());
+        for mut tombstone in due {
+            let documents = match self.sessions.erase_documents(&tombstone.account_id).await {
+                Ok(documents) => documents,
+                Err(e) => {
+                    tracing::warn!(account_id = %tombstone.account_id, error = %e, "PII erasure failed");
+                    continue;
+                }
+            };
+            tombstone.erased_at = Some(now);
+            tombstone.erased_documents = documents;
+            
+            let mut batch = WriteBatch::default();
+            batch.outbox.push(OutboxMessage::new(
+                tombstone.client_id,
+                PII_ERASED_EVENT,
+                json!({
+                    "account_id": tombstone.account_id,
+                    "job_id": tombstone.job_id,
+                    "erased_at": now,
+                    "erased_documents": documents,
+                }),
+                now,
+            ));
+            self.compliance.storage.commit(&batch).await?;
+            self.compliance
+                .audit
+                .record(
+                    ERASURE_ACTOR,
+                    "account.pii_erased",
+                    Some(&tombstone.account_id),
+                    json!({ "job_id": tombstone.job_id, "erased_documents": documents }),
+                )
+                .await;
+            self.tombstones
+                .write()
+                .await
+                .insert(tombstone.account_id.clone(), tombstone.clone());
+            erased.push(tombstone);
+        }
+        Ok(erased)
+    }
+    
+    async fn job(&self, job_id: Uuid) -> Result<OffboardingJob> {
+        self.jobs
+            .read()
+            .await
+            .get(&job_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::OffboardingJobNotFound {
+                job_id: job_id.to_string(),
+            })
+    }
+    
+    async fn finish(&self, job_id: Uuid, status: OffboardingStatus) -> Result<OffboardingJob> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs.get_mut(&job_id).ok_or_else(|| ComplianceError::OffboardingJobNotFound {
+            job_id: job_id.to_string(),
+        })?;
+        job.status = status;
+        job.updated_at = self.compliance.clock.now();
+        Ok(job.clone())
+    }
+    
+    /// Run one step, returning what it did
+    async fn run_step(&self, job: &OffboardingJob, step: OffboardingStep) -> Result<serde_json::Value> {
+        match step {
+            OffboardingStep::RevokeAttestation => self.revoke(job).await,
+            OffboardingStep::VoidNotes => self.void_notes(job).await,
+            OffboardingStep::ScheduleErasure => self.schedule_erasure(job).await,
+            OffboardingStep::NotifyClient => self.notify(job).await,
+            OffboardingStep::RecordTombstone => self.record_tombstone(job).await,
+        }
+    }
+    
+    /// Revoke the account's attestation, unless it has none in force
+    async fn revoke(&self, job: &OffboardingJob) -> Result<serde_json::Value> {
+        let revocable = self
+            .compliance
+            .events
+            .project(&job.account_id, None)
+            .await?
+            .is_some_and(|state| state.status != AttestationStatus::Revoked);
+        if revocable {
+            let reason = format!("offboarded: {}", job.reason);
+            self.compliance
+                .revoke_attestation(&job.account_id, &reason, Some(&job.requested_by))
+                .await?;
+        }
+        Ok(json!({ "revoked": revocable }))
+    }
+    
+    /// Consume notes addressed to the account that the void account can consume
+    async fn void_notes(&self, job: &OffboardingJob) -> Result<serde_json::Value> {
+        let account = miden_account_id(&job.account_id)?;
+        let void_account = match &self.config.compliance().offboarding.void_account_id {
+            Some(id) => Some(miden_account_id(&AccountId::parse(id)?)?),
+            None => None,
+        };
+        
+        let mut client = self.compliance.miden_client.write().await;
+        client.sync_state().await?;
+        let (mut voidable, mut outstanding): (Vec<NoteId>, Vec<NoteId>) = (Vec::new(), Vec::new());
+        for (record, consumability) in client.get_consumable_notes(None).await? {
+            if !consumability.iter().any(|(consumer, _)| *consumer == account) {
+                continue;
+            }
+            match void_account {
+                Some(void_account) if consumability.iter().any(|(consumer, _)| *consumer == void_account) => {
+                    voidable.push(record.id())
+                }
+                _ => outstanding.push(record.id()),
+            }
+        }
+        
+        let mut transaction_id = None;
+        if let (Some(void_account), false) = (void_account, voidable.is_empty()) {
+            let request = TransactionRequestBuilder::new()
+                .build_consume_notes(voidable.clone())
+                .map_err(|e| ComplianceError::TransactionExecutionFailed {
+                    reason: format!("failed to build note voiding: {}", e),
+                })?;
+            let result = client.new_transaction(void_account, request).await?;
+            transaction_id = Some(result.executed_transaction().id().to_hex());
+            client.submit_transaction(result).await?;
+        }
+        drop(client);
+        
+        let voided: Vec<String> = voidable.iter().map(NoteId::to_hex).collect();
+        let outstanding: Vec<String> = outstanding.iter().map(NoteId::to_hex).collect();
+        if let Some(job) = self.jobs.write().await.get_mut(&job.id) {
+            job.voided_notes = voided.clone();
+            job.outstanding_notes = outstanding.clone();
+        }
+        Ok(json!({
+            "voided": voided,
+            "outstanding": outstanding,
+            "transaction_id": transaction_id,
+        }))
+    }
+    
+    /// Set when the account's PII is erased, at the end of the retention period
+    async fn schedule_erasure(&self, job: &OffboardingJob) -> Result<serde_json::Value> {
+        let retention_days = self.config.compliance().offboarding.pii_retention_days;
+        let due_at = job
+            .erasure_due_at
+            .unwrap_or_else(|| self.compliance.clock.now() + Duration::days(i64::from(retention_days)));
+        if let Some(job) = self.jobs.write().await.get_mut(&job.id) {
+            job.erasure_due_at = Some(due_at);
+        }
+        Ok(json!({ "erasure_due_at": due_at, "retention_days": retention_days }))
+    }
+    
+    /// Send the client its `account.offboarded` webhook
+    async fn notify(&self, job: &OffboardingJob) -> Result<serde_json::Value> {
+        let now = self.compliance.clock.now();
+        let mut batch = WriteBatch::default();
+        batch.outbox.push(OutboxMessage::new(
+            job.client_id,
+            OFFBOARDED_EVENT,
+            json!({
+                "account_id": job.account_id,
+                "job_id": job.id,
+                "reason": job.reason,
+                "voided_notes": job.voided_notes,
+                "outstanding_notes": job.outstanding_notes,
+                "erasure_due_at": job.erasure_due_at,
+            }),
+            now,
+        ));
+        self.compliance.storage.commit(&batch).await?;
+        Ok(json!({ "event": OFFBOARDED_EVENT }))
+    }
+    
+    /// Leave the account's tombstone and audit the offboarding
+    async fn record_tombstone(&self, job: &OffboardingJob) -> Result<serde_json::Value> {
+        let erasure_due_at = job
+            .erasure_due_at
+            .ok_or_else(|| ComplianceError::internal("offboarding tombstone without an erasure date"))?;
+        let now = self.compliance.clock.now();
+        self.tombstones.write().await.insert(
+            job.account_id.clone(),
+            Tombstone {
+                account_id: job.account_id.clone(),
+                client_id: job.client_id,
+                job_id: job.id,
+                reason: job.reason.clone(),
+                offboarded_at: now,
+                erasure_due_at,
+                erased_at: None,
+                erased_documents: 0,
+            },
+        );
+        self.compliance
+            .audit
+            .record(
+                &job.requested_by,
+                "account.offboarded",
+                Some(&job.account_id),
+                json!({
+                    "job_id": job.id,
+                    "reason": job.reason,
+                    "voided_notes": job.voided_notes.len(),
+                    "outstanding_notes": job.outstanding_notes.len(),
+                    "erasure_due_at": erasure_due_at,
+                }),
+            )
+            .await;
+        Ok(json!({ "offboarded_at": now }))
+    }
+}
+
+/// Job periodically erasing the PII of offboarded accounts whose retention
+/// has ended
+///
+/// The interval is read from the live configuration when the job is built.
+pub fn erasure_job(service: Arc<OffboardingService>) -> Job {
+    let interval = service.config.compliance().offboarding.erasure_interval_secs;
+    Job::every(ERASURE_JOB, std::time::Duration::from_secs(interval), move || {
+        let service = service.clone();
+        async move {
+            let erased = service.erase_due().await?;
+            if !erased.is_empty() {
+                tracing::info!(accounts = erased.len(), "erased PII of offboarded accounts");
+            }
+            Ok(())
+        }
+    })
+}
@@ -0,0 +1,317 @@
+//! Compliance oracle
+//!
+//! Other protocols want to check compliance on-chain rather than call the
+//! API. The oracle periodically commits to the set of currently compliant
+//! Miden accounts as the root of a Merkle tree and writes it to the oracle
+//! account. Each leaf binds an account to its compliance level and
+//! attestation expiry; holders fetch an inclusion proof for their leaf and
+//! present it to a contract, which reads the root from the oracle account
+//! and checks the proof with `mtree_verify`. Every root is also signed so
+//! off-chain consumers can check it came from this backend.
+
+use super::account_components::foreign::miden_account_id;
+use super::account_components::oracle_component;
+use super::ComplianceService;
+use crate::audit::AuditLog;
+use crate::crypto::commitment::compliance_level_code;
+use crate::crypto::{AttestationSigner, Commitment, CommitmentDomain, FieldEncoder};
+use crate::reload::LiveConfig;
+use crate::scheduler::Job;
+use crate::types::{AccountId, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::crypto::merkle::{MerkleTree, NodeIndex};
+use miden_objects::{Digest, Word, EMPTY_WORD};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Actor recorded for roots published by the background worker
+pub const ORACLE_ACTOR: &str = "system:oracle";
+
+/// Name of the job publishing the compliant account set
+pub const PUBLISH_JOB: &str = "oracle_publish";
+
+/// Domain separator of signed oracle roots
+const SIGNATURE_DOMAIN: &[u8] = b"zerotrust-compliance-oracle-v1";
+
+/// Leaf committing to one account's compliance
+///
+/// The canonical commitment in the oracle leaf domain to
+/// `(account_id_prefix, account_id_suffix, compliance_level, expires_at)`.
+/// Contracts should also check the expiry against the block time, since a
+/// root stays in place until the next publication.
+pub fn oracle_leaf(account: MidenAccountId, level: &ComplianceLevel, expires_at: DateTime<Utc>) -> Commitment {
+    let mut encoder = FieldEncoder::new(CommitmentDomain::OracleLeaf);
+    encoder
+        .felt(account.prefix().as_felt())
+        .felt(account.suffix())
+        .value(compliance_level_code(level))
+        .timestamp(expires_at);
+    encoder.commit()
+}
+
+/// Message signed for a published root
+fn signed_message(epoch: u64, root: &Digest, accounts: usize, published_at: DateTime<Utc>) -> Vec<u8> {
+    let mut message = SIGNATURE_DOMAIN.to_vec();
+    message.extend_from_slice(&epoch.to_be_bytes());
+    message.extend_from_slice(&root.as_bytes());
+    message.extend_from_slice(&(accounts as u64).to_be_bytes());
+    message.extend_from_slice(&published_at.timestamp().to_be_bytes());
+    message
+}
+
+/// A compliant account committed to by a root
+#[derive(Debug, Clone, Serialize)]
+pub struct OracleEntry {
+    pub account_id: AccountId,
+    pub compliance_level: ComplianceLevel,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A published root of the compliant account set
+#[derive(Debug, Clone, Serialize)]
+pub struct OracleRoot {
+    pub epoch: u64,
+    /// Hex-encoded Merkle root
+    pub root: String,
+    pub depth: u8,
+    pub accounts: usize,
+    pub published_at: DateTime<Utc>,
+    /// Key that signed the root
+    pub key_id: String,
+    /// Hex-encoded Ed25519 signature over the domain separator, epoch, root,
+    /// account count and publication time
+    pub signature: String,
+    /// Transaction that wrote the root to the oracle account
+    pub transaction_id: String,
+}
+
+/// Proof that an account is in a published root
+#[derive(Debug, Clone, Serialize)]
+pub struct InclusionProof {
+    pub account_id: AccountId,
+    pub epoch: u64,
+    pub root: String,
+    pub compliance_level: ComplianceLevel,
+    pub expires_at: DateTime<Utc>,
+    /// Leaf value, recomputable from the account, level and expiry
+    pub leaf: String,
+    /// Leaf position, as taken by `mtree_verify`
+    pub index: u64,
+    pub depth: u8,
+    /// Sibling nodes from the leaf up to the root
+    pub path: Vec<String>,
+}
+
+/// The latest root with the tree proofs are generated from
+struct Snapshot {
+    root: OracleRoot,
+    tree: MerkleTree,
+    entries: HashMap<AccountId, (u64, OracleEntry)>,
+}
+
+/// Published roots of the compliant account set
+#[derive(Default)]
+pub struct ComplianceOracle {
+    latest: RwLock<Option<Snapshot>>,
+    history: RwLock<Vec<OracleRoot>>,
+    /// Serializes publications so epochs are assigned in order
+    publishing: Mutex<()>,
+}
+
+impl ComplianceOracle {
+    /// Create an oracle with nothing published
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Latest published root
+    pub async fn latest(&self) -> Result<OracleRoot> {
+        self.latest
+            .read()
+            .await
+            .as_ref()
+            .map(|snapshot| snapshot.root.clone())
+            .ok_or(ComplianceError::OracleNotPublished)
+    }
+    
+    /// Every root published since startup, newest first
+    pub async fn history(&self) -> Vec<OracleRoot> {
+        self.history.read().await.iter().rev().cloned().collect()
+    }
+    
+    /// Proof that an account is in the latest root
+    pub async fn inclusion_proof(&self, account_id: &AccountId) -> Result<InclusionProof> {
+        let latest = self.latest.read().await;
+        let snapshot = latest.as_ref().ok_or(ComplianceError::OracleNotPublished)?;
+        let (index, entry) = snapshot
+            .entries
+            .get(account_id)
+            .ok_or_else(|| ComplianceError::NotInComplianceSet {
+                account_id: account_id.to_string(),
+            })?;
+        
+        let depth = snapshot.tree.depth();
+        let node = NodeIndex::new(depth, *index).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let path = snapshot
+            .tree
+            .get_path(node)
+            .map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let leaf = snapshot
+            .tree
+            .get_node(node)
+            .map_err(|e| ComplianceError::internal(e.to_string()))?;
+        
+        Ok(InclusionProof {
+            account_id: account_id.clone(),
+            epoch: snapshot.root.epoch,
+            root: snapshot.root.root.clone(),
+            compliance_level: entry.compliance_level.clone(),
+            expires_at: entry.expires_at,
+            leaf: leaf.to_hex(),
+            index: *index,
+            depth,
+            path: path.iter().map(|node| node.to_hex()).collect(),
+        })
+    }
+    
+    /// Commit to the accounts compliant at `now` and write the root to `oracle_account`
+    pub async fn publish(
+        &self,
+        compliance: &ComplianceService,
+        signer: &AttestationSigner,
+        oracle_account: &AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<OracleRoot> {
+        let _publishing = self.publishing.lock().await;
+        let oracle_account = miden_account_id(oracle_account)?;
+        let epoch = self.latest.read().await.as_ref().map_or(1, |snapshot| snapshot.root.epoch + 1);
+        
+        let compliant = compliant_accounts(compliance, now).await?;
+        let mut leaves: Vec<Word> = compliant
+            .iter()
+            .map(|(miden_id, entry)| oracle_leaf(*miden_id, &entry.compliance_level, entry.expires_at).to_word())
+            .collect();
+        leaves.resize(compliant.len().next_power_of_two().max(2), EMPTY_WORD);
+        let tree = MerkleTree::new(leaves).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let root = tree.root();
+        
+        let script = oracle_component::publish_script(root, epoch, compliant.len() as u64)?;
+        let request = TransactionRequestBuilder::new()
+            .with_custom_script(script)
+            .build()
+            .map_err(|e| ComplianceError::TransactionExecutionFailed {
+                reason: format!("failed to build oracle publication: {}", e),
+            })?;
+        let transaction_id = {
+            let mut client = compliance.miden_client.write().await;
+            let result = client.new_transaction(oracle_account, request).await?;
+            let transaction_id = result.executed_transaction().id().to_hex();
+            client.submit_transaction(result).await?;
+            transaction_id
+        };
+        
+        let published_at = compliance.clock.now();
+        let signature = signer.sign(&signed_message(epoch, &root, compliant.len(), published_at))?;
+        let published = OracleRoot {
+            epoch,
+            root: root.to_hex(),
+            depth: tree.depth(),
+            accounts: compliant.len(),
+            published_at,
+            key_id: signer.key_id().to_string(),
+            signature: hex::encode(signature),
+            transaction_id,
+        };
+        let entries = compliant
+            .into_iter()
+            .enumerate()
+            .map(|(index, (_, entry))| (entry.account_id.clone(), (index as u64, entry)))
+            .collect();
+        
+        *self.latest.write().await = Some(Snapshot {
+            root: published.clone(),
+            tree,
+            entries,
+        });
+        self.history.write().await.push(published.clone());
+        Ok(published)
+    }
+}
+
+/// Miden accounts with an attestation valid at `now`, ordered by account id
+///
+/// Linked EVM addresses are left out: contracts have no Miden identity to
+/// check them against.
+async fn compliant_accounts(
+    compliance: &ComplianceService,
+    now: DateTime<Utc>,
+) -> Result<Vec<(MidenAccountId, OracleEntry)>> {
+    let mut compliant = Vec::new();
+    for (account_id, state) in compliance.events.current_states().await? {
+        if !state.is_valid_at(now) {
+            continue;
+        }
+        let Some(level) = compliance.highest_compliance_level_at(&state.attestation, now).await else {
+            continue;
+        };
+        let Ok(miden_id) = miden_account_id(&account_id) else {
+            continue;
+        };
+        compliant.push((
+            miden_id,
+            OracleEntry {
+                account_id,
+                compliance_level: level,
+                expires_at: state.attestation.expires_at,
+            },
+        ));
+    }
+    compliant.sort_by(|a, b| a.1.account_id.cmp(&b.1.account_id));
+    Ok(compliant)
+}
+
+/// Job periodically publishing the compliant account set
+///
+/// Whether the oracle is enabled and the oracle account are read from the
+/// live configuration on every run.
+pub fn publish_job(
+    oracle: Arc<ComplianceOracle>,
+    compliance: Arc<ComplianceService>,
+    signer: Arc<AttestationSigner>,
+    audit: Arc<AuditLog>,
+    config: Arc<LiveConfig>,
+    interval: std::time::Duration,
+) -> Job {
+    Job::every(PUBLISH_JOB, interval, move || {
+        let (oracle, compliance, signer) = (oracle.clone(), compliance.clone(), signer.clone());
+        let (audit, config) = (audit.clone(), config.clone());
+        async move {
+            let settings = config.compliance().attestation.oracle.clone();
+            let Some(account) = settings.account_id.filter(|_| settings.enabled) else {
+                return Ok(());
+            };
+            let account_id = AccountId::parse(&account)?;
+            let root = oracle.publish(&compliance, &signer, &account_id, compliance.clock.now()).await?;
+            audit
+                .record(
+                    ORACLE_ACTOR,
+                    "oracle.root_published",
+                    None,
+                    serde_json::json!({
+                        "epoch": root.epoch,
+                        "root": root.root,
+                        "accounts": root.accounts,
+                        "transaction_id": root.transaction_id,
+                    }),
+                )
+                .await;
+            tracing::info!(epoch = root.epoch, accounts = root.accounts, "compliance oracle root published");
+            Ok(())
+        }
+    })
+}
@@ -0,0 +1,173 @@
+//! Webhook delivery from the outbox
+//!
+//! Notifications are committed to the outbox in the same batch as the
+//! records they announce and delivered from there by a relay. Delivery is at
+//! least once: a message is marked delivered only after the client's
+//! endpoint acknowledges it, so receivers must tolerate duplicates, keyed by
+//! the message id sent with every delivery.
+//!
+//! Deliveries to an endpoint with a webhook encryption key are sent as a
+//! JWE, signed over the encrypted body.
+//!
+//! A message whose payload carries `messages` keys is delivered with their
+//! display text in each of the client's locales, rendered at delivery so
+//! locale changes apply to messages still pending.
+//!
+//! Deliveries follow the payload schema version the client's webhooks are
+//! pinned to at delivery time, which they carry along with the event.
+
+use super::clients::ClientRegistry;
+use super::localization::{Message, MessageCatalog};
+use super::schema_versions::{self, SCHEMA_VERSION_HEADER};
+use crate::clock::SharedClock;
+use crate::config::WebhookConfig;
+use crate::crypto::canonical_json;
+use crate::crypto::webhook_encryption::{self, JWE_CONTENT_TYPE};
+use crate::crypto::webhook_signature::{self, WEBHOOK_SIGNATURE_HEADER};
+use crate::leader::LeaderElection;
+use crate::storage::{OutboxMessage, OutboxRepo};
+use crate::types::ClientEnvironment;
+use crate::{ComplianceError, Result};
+use std::sync::Arc;
+
+/// Messages delivered per relay pass
+pub const RELAY_BATCH_SIZE: usize = 100;
+
+/// Leader lease guarding the production relay; the sandbox relay's lease
+/// carries a `.sandbox` suffix
+pub const RELAY_LOCK: &str = "outbox_relay";
+
+/// Header carrying the outbox message id, for receivers to drop duplicates
+pub const WEBHOOK_ID_HEADER: &str = "x-zerotrust-delivery-id";
+
+/// Deliver one outbox message to its client's webhook in `environment`
+///
+/// A client without a webhook URL in the environment has nowhere to deliver
+/// to; its messages count as delivered.
+pub async fn deliver(
+    http: &reqwest::Client,
+    clients: &ClientRegistry,
+    environment: ClientEnvironment,
+    config: &WebhookConfig,
+    message: &OutboxMessage,
+    catalog: &MessageCatalog,
+    clock: &SharedClock,
+) -> Result<()> {
+    let Some(client) = clients.get(message.client_id).await?.in_environment(environment) else {
+        return Ok(());
+    };
+    let Some(url) = client.webhook_url.clone() else {
+        return Ok(());
+    };
+    
+    let schema_version = client.schema.for_webhooks();
+    let mut body = serde_json::json!({
+        "id": message.id,
+        "type": message.event_type,
+        "schema_version": schema_version,
+        "created_at": message.created_at,
+        "data": message.payload,
+    });
+    if let Some(messages) = message.payload.get("messages") {
+        let messages: Vec<Message> = serde_json::from_value(messages.clone())?;
+        body["display"] = serde_json::to_value(catalog.display(&messages, &client.locales))?;
+    }
+    schema_versions::downgrade_webhook(&message.event_type, &mut body, schema_version);
+    let (body, content_type) = match &client.webhook_encryption_key {
+        Some(key) => {
+            let jwe = webhook_encryption::encrypt(key, &canonical_json::to_vec(&body)?)?;
+            (jwe.into_bytes(), JWE_CONTENT_TYPE)
+        }
+        None => (canonical_json::to_vec(&body)?, "application/json"),
+    };
+    let signature = webhook_signature::sign(config.secret.as_bytes(), &body, clock.now());
+    let response = http
+        .post(&url)
+        .timeout(std::time::Duration::from_secs(config.timeout))
+        .header("Content-Type", content_type)
+        .header(WEBHOOK_SIGNATURE_HEADER, signature)
+        .header(WEBHOOK_ID_HEADER, message.id.to_string())
+        .header(SCHEMA_VERSION_HEADER, schema_version.to_string())
+        .body(body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(ComplianceError::internal(format!("webhook returned {}", response.status())));
+    }
+    Ok(())
+}
+
+/// Deliver pending outbox messages in the background
+///
+/// Every interval of `retry_delay` seconds the relay delivers pending
+/// messages oldest first. A message that has failed `max_retries` times is
+/// abandoned. The sandbox runs a relay of its own over its outbox, delivering
+/// to clients' sandbox webhooks.
+///
+/// With a `leader` election, the relay delivers only while this instance
+/// holds the relay's lease, so replicas sharing an outbox do not each send
+/// every webhook.
+pub fn spawn_outbox_relay(
+    outbox: Arc<dyn OutboxRepo>,
+    clients: Arc<ClientRegistry>,
+    environment: ClientEnvironment,
+    config: WebhookConfig,
+    catalog: Arc<MessageCatalog>,
+    clock: SharedClock,
+    leader: Option<Arc<LeaderElection>>,
+) -> tokio::task::JoinHandle<()> {
+    let lock = match environment {
+        ClientEnvironment::Production => RELAY_LOCK.to_string(),
+        ClientEnvironment::Sandbox => format!("{}.sandbox", RELAY_LOCK),
+    };
+    tokio::spawn(async move {
+        let http = reqwest::Client::new();
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(config.retry_delay.max(1)));
+        loop {
+            ticker.tick().await;
+            if !config.enabled {
+                continue;
+            }
+            if let Some(leader) = &leader {
+                if !leader.acquire(&lock).await {
+                    continue;
+                }
+            }
+            let pending = match outbox.pending(RELAY_BATCH_SIZE).await {
+                Ok(pending) => pending,
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to load webhook outbox");
+                    continue;
+                }
+            };
+            
+            for (i, message) in pending.into_iter().enumerate() {
+                // Renew the lease as the pass goes, so a pass held up by slow
+                // endpoints stops before another instance takes over
+                if let Some(leader) = leader.as_ref().filter(|_| i > 0) {
+                    if !leader.acquire(&lock).await {
+                        break;
+                    }
+                }
+                let updated = match deliver(&http, &clients, environment, &config, &message, &catalog, &clock).await {
+                    Ok(()) => outbox.mark_delivered(message.id, clock.now()).await,
+                    Err(e) => {
+                        let abandon = message.attempts + 1 >= config.max_retries;
+                        tracing::warn!(
+                            message_id = %message.id,
+                            client_id = %message.client_id,
+                            attempts = message.attempts + 1,
+                            abandoned = abandon,
+                            error = %e,
+                            "webhook delivery failed"
+                        );
+                        outbox.record_failure(message.id, abandon.then(|| clock.now())).await
+                    }
+                };
+                if let Err(e) = updated {
+                    tracing::warn!(message_id = %message.id, error = %e, "failed to update webhook outbox");
+                }
+            }
+        }
+    })
+}
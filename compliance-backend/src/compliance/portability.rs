@@ -0,0 +1,270 @@
+//! Signed attestation export bundles for migration between deployments
+
+use super::attestation_events::{AttestationEvent, AttestationState, AttestationStatus, RecordedEvent};
+use super::notarization::Notarization;
+use super::ComplianceService;
+use crate::crypto::signing::SIGNATURE_LENGTH;
+use crate::crypto::{attestation_commitment, canonical_json, AttestationSigner, TrustedKeys};
+use crate::types::{AccountId, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Current bundle format version
+pub const BUNDLE_VERSION: u8 = 2;
+
+/// Bundle format version signed over serde's field order, still accepted on import
+pub const LEGACY_BUNDLE_VERSION: u8 = 1;
+
+/// Signed collection of attestations exported from a deployment
+///
+/// The signature covers the canonical JSON serialization (RFC 8785) of the
+/// bundle with `signature` empty, so it can be checked outside this crate.
+/// Importers must trust `key_id` to accept the bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportBundle {
+    pub version: u8,
+    pub bundle_id: Uuid,
+    /// Deployment the attestations were issued by
+    pub source_deployment: String,
+    pub exported_at: DateTime<Utc>,
+    pub records: Vec<ExportedAttestation>,
+    /// Identifier of the key that produced `signature`
+    pub key_id: String,
+    /// Hex-encoded public key of `key_id`, for out-of-band trust decisions
+    pub public_key: String,
+    /// Hex-encoded Ed25519 signature
+    pub signature: String,
+}
+
+/// One account's attestation with its lifecycle history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportedAttestation {
+    pub account_id: AccountId,
+    pub attestation: ComplianceAttestation,
+    pub status: AttestationStatus,
+    pub revocation_reason: Option<String>,
+    /// Hex-encoded commitment binding the attestation, as embedded in proof envelopes
+    pub attestation_commitment: String,
+    /// Independent evidence of the attestation's issuance time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notarization: Option<Notarization>,
+    /// Lifecycle events in the source deployment
+    pub history: Vec<RecordedEvent>,
+}
+
+/// Outcome of importing a single record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedRecord {
+    pub account_id: AccountId,
+    pub attestation_id: Uuid,
+    pub imported: bool,
+    /// Why the record was skipped
+    pub reason: Option<String>,
+}
+
+/// Outcome of importing a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub bundle_id: Uuid,
+    pub source_deployment: String,
+    pub records: Vec<ImportedRecord>,
+}
+
+impl ExportBundle {
+    /// Build and sign a bundle
+    pub fn seal(
+        source_deployment: &str,
+        records: Vec<ExportedAttestation>,
+        signer: &AttestationSigner,
+    ) -> Result<Self> {
+        let mut bundle = Self {
+            version: BUNDLE_VERSION,
+            bundle_id: Uuid::new_v4(),
+            source_deployment: source_deployment.to_string(),
+            exported_at: Utc::now(),
+            records,
+            key_id: signer.key_id().to_string(),
+            public_key: hex::encode(signer.verifying_key().as_bytes()),
+            signature: String::new(),
+        };
+        bundle.signature = hex::encode(signer.sign(&bundle.signing_payload()?)?);
+        Ok(bundle)
+    }
+    
+    /// Check the signature, provenance, and internal consistency of the bundle
+    pub fn verify(&self, trusted_keys: &TrustedKeys) -> Result<()> {
+        if self.version != BUNDLE_VERSION && self.version != LEGACY_BUNDLE_VERSION {
+            return Err(invalid(format!("unsupported bundle version {}", self.version)));
+        }
+        let signature = hex::decode(&self.signature).map_err(|_| invalid("signature is not hex"))?;
+        if signature.len() != SIGNATURE_LENGTH {
+            return Err(invalid("signature has wrong length"));
+        }
+        trusted_keys
+            .verify(&self.key_id, &self.signing_payload()?, &signature)
+            .map_err(|e| invalid(e.to_string()))?;
+        
+        for record in &self.records {
+            record.verify()?;
+        }
+        Ok(())
+    }
+    
+    /// Bytes covered by the signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>> {
+        let unsigned = Self {
+            signature: String::new(),
+            ..self.clone()
+        };
+        if self.version == LEGACY_BUNDLE_VERSION {
+            return Ok(serde_json::to_vec(&unsigned)?);
+        }
+        canonical_json::to_vec(&unsigned)
+    }
+}
+
+impl ExportedAttestation {
+    /// Export an account's current state and history
+    pub fn from_history(history: Vec<RecordedEvent>) -> Result<Option<Self>> {
+        let Some(state) = history.iter().fold(None, AttestationState::apply) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            account_id: state.attestation.account_id.clone(),
+            attestation_commitment: attestation_commitment(&state.attestation).to_hex(),
+            attestation: state.attestation,
+            status: state.status,
+            revocation_reason: state.revocation_reason,
+            notarization: state.notarization,
+            history,
+        }))
+    }
+    
+    /// Check the record against its own history and commitment
+    fn verify(&self) -> Result<()> {
+        if self.attestation.account_id != self.account_id {
+            return Err(invalid(format!("record for {} carries another account's attestation", self.account_id)));
+        }
+        if attestation_commitment(&self.attestation).to_hex() != self.attestation_commitment {
+            return Err(invalid(format!("commitment mismatch for {}", self.account_id)));
+        }
+        if self.history.iter().any(|e| e.account_id != self.account_id) {
+            return Err(invalid(format!("history for {} contains foreign events", self.account_id)));
+        }
+        
+        let replayed = self.history.iter().fold(None, AttestationState::apply);
+        let consistent = replayed.is_some_and(|state| {
+            state.attestation.id == self.attestation.id
+                && state.status == self.status
+                && state.revocation_reason == self.revocation_reason
+                && state.notarization == self.notarization
+        });
+        if !consistent {
+            return Err(invalid(format!("history for {} does not reproduce its attestation", self.account_id)));
+        }
+        Ok(())
+    }
+}
+
+impl ComplianceService {
+    /// Export attestations for the given accounts, or every account when none are given
+    pub async fn export_attestations(
+        &self,
+        account_ids: Option<&[AccountId]>,
+        source_deployment: &str,
+        signer: &AttestationSigner,
+    ) -> Result<ExportBundle> {
+        let account_ids = match account_ids {
+            Some(ids) => ids.to_vec(),
+            None => self.events.accounts().await?,
+        };
+        
+        let mut records = Vec::new();
+        for account_id in &account_ids {
+            if let Some(record) = ExportedAttestation::from_history(self.events.events(account_id, None).await?)? {
+                records.push(record);
+            }
+        }
+        
+        ExportBundle::seal(source_deployment, records, signer)
+    }
+    
+    /// Verify and import a bundle
+    ///
+    /// The whole bundle is rejected if its signature or any record fails
+    /// verification. Records whose account already holds an attestation at
+    /// least as recent are skipped.
+    pub async fn import_attestations(&self, bundle: &ExportBundle, trusted_keys: &TrustedKeys) -> Result<ImportReport> {
+        bundle.verify(trusted_keys)?;
+        
+        let mut records = Vec::with_capacity(bundle.records.len());
+        for record in &bundle.records {
+            let current = self.events.project(&record.account_id, None).await?;
+            if let Some(current) = current.filter(|c| c.attestation.created_at >= record.attestation.created_at) {
+                records.push(ImportedRecord {
+                    account_id: record.account_id.clone(),
+                    attestation_id: record.attestation.id,
+                    imported: false,
+                    reason: Some(format!("account already holds attestation {}", current.attestation.id)),
+                });
+                continue;
+            }
+            
+            self.attestation.store_attestation(&record.attestation).await?;
+            self.events
+                .append(
+                    &record.account_id,
+                    AttestationEvent::Imported {
+                        attestation: record.attestation.clone(),
+                        bundle_id: bundle.bundle_id,
+                        source_deployment: bundle.source_deployment.clone(),
+                    },
+                )
+                .await?;
+            if let Some(notarization) = &record.notarization {
+                self.events
+                    .append(
+                        &record.account_id,
+                        AttestationEvent::Notarized {
+                            attestation_id: record.attestation.id,
+                            notarization: notarization.clone(),
+                        },
+                    )
+                    .await?;
+            }
+            match record.status {
+                AttestationStatus::Active => {}
+                AttestationStatus::Revoked => {
+                    let reason = record.revocation_reason.clone().unwrap_or_default();
+                    self.events
+                        .append(&record.account_id, AttestationEvent::Revoked { reason, revoked_by: None })
+                        .await?;
+                }
+                AttestationStatus::Expired => {
+                    self.events.append(&record.account_id, AttestationEvent::Expired).await?;
+                }
+            }
+            
+            records.push(ImportedRecord {
+                account_id: record.account_id.clone(),
+                attestation_id: record.attestation.id,
+                imported: true,
+                reason: None,
+            });
+        }
+        
+        Ok(ImportReport {
+            bundle_id: bundle.bundle_id,
+            source_deployment: bundle.source_deployment.clone(),
+            records,
+        })
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::validation("bundle", reason)
+}
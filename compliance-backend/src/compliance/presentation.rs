@@ -0,0 +1,146 @@
+//! QR code and deep link presentation of proof envelopes
+//!
+//! A presentation is a URI the holder shows as a QR code or opens as a deep
+//! link, and a verifier scans or follows. It takes one of two forms:
+//!
+//! ```text
+//! zerotrust:present?v=1&e=<envelope>
+//! zerotrust:present?v=1&u=<retrieval URL>&t=<token>
+//! ```
+//!
+//! An inline presentation carries the base64url envelope itself, so it can
+//! be checked offline. Envelopes too large for a QR code are presented by
+//! reference: the URI carries the percent-encoded URL the envelope is
+//! retrieved from and a one-time token that retrieves it. Parameters may come
+//! in any order and unknown parameters are ignored, so later versions can add
+//! optional ones; `v` is always required.
+
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+
+/// URI scheme of presentations
+pub const PRESENTATION_SCHEME: &str = "zerotrust";
+
+/// Current presentation URI version
+pub const PRESENTATION_VERSION: u8 = 1;
+
+/// Bytes the largest QR code holds in byte mode (version 40, low error correction)
+pub const QR_BYTE_CAPACITY: usize = 2953;
+
+/// How a proof is presented
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresentationMode {
+    /// Inline when the envelope fits, by reference otherwise
+    #[default]
+    Auto,
+    Inline,
+    Reference,
+}
+
+/// A presentation as carried in its URI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Presentation {
+    /// The encoded envelope itself
+    Inline { envelope: String },
+    
+    /// Where to retrieve the envelope, and the one-time token retrieving it
+    Reference { url: String, token: String },
+}
+
+impl Presentation {
+    /// Encode the presentation as a URI
+    pub fn to_uri(&self) -> String {
+        let prefix = format!("{}:present?v={}", PRESENTATION_SCHEME, PRESENTATION_VERSION);
+        match self {
+            Self::Inline { envelope } => format!("{}&e={}", prefix, envelope),
+            Self::Reference { url, token } => {
+                format!("{}&u={}&t={}", prefix, percent_encode(url), percent_encode(token))
+            }
+        }
+    }
+    
+    /// Parse a scanned or followed presentation URI
+    pub fn parse(uri: &str) -> Result<Self> {
+        let query = uri
+            .trim()
+            .strip_prefix(PRESENTATION_SCHEME)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .and_then(|rest| rest.strip_prefix("present?"))
+            .ok_or_else(|| invalid(format!("not a {}:present URI", PRESENTATION_SCHEME)))?;
+        
+        let (mut version, mut envelope, mut url, mut token) = (None, None, None, None);
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let slot = match key {
+                "v" => &mut version,
+                "e" => &mut envelope,
+                "u" => &mut url,
+                "t" => &mut token,
+                _ => continue,
+            };
+            if slot.replace(percent_decode(value)?).is_some() {
+                return Err(invalid(format!("parameter {} is repeated", key)));
+            }
+        }
+        
+        match version.as_deref() {
+            Some(v) if v == PRESENTATION_VERSION.to_string() => {}
+            Some(v) => return Err(invalid(format!("unsupported presentation version {}", v))),
+            None => return Err(invalid("presentation version is missing")),
+        }
+        match (envelope, url, token) {
+            (Some(envelope), None, None) if !envelope.is_empty() => Ok(Self::Inline { envelope }),
+            (None, Some(url), Some(token)) if !url.is_empty() && !token.is_empty() => {
+                Ok(Self::Reference { url, token })
+            }
+            _ => Err(invalid("presentation must carry either an envelope or a retrieval URL and token")),
+        }
+    }
+    
+    /// Mode the presentation was made in
+    pub fn mode(&self) -> PresentationMode {
+        match self {
+            Self::Inline { .. } => PresentationMode::Inline,
+            Self::Reference { .. } => PresentationMode::Reference,
+        }
+    }
+}
+
+/// Percent-encode everything but unreserved characters and `:` and `/`
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~:/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid("malformed percent-encoding"))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid("presentation is not valid UTF-8"))
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::InvalidProof { reason: reason.into() }
+}
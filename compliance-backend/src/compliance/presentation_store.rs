@@ -0,0 +1,194 @@
+//! Presentations of proof envelopes, and the envelopes presented by reference
+//!
+//! An envelope whose presentation URI fits the configured inline limit is
+//! carried in the URI. A larger one is held here until it is retrieved with
+//! its one-time token or its retrieval window ends, whichever comes first,
+//! and never past the envelope's own expiry. Only a hash of each token is
+//! kept.
+
+use super::presentation::{Presentation, PresentationMode};
+use crate::clock::{system_clock, SharedClock};
+use crate::reload::LiveConfig;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Path reference presentations are retrieved from, below the public URL
+pub const RETRIEVAL_PATH: &str = "/v1/presentations/";
+
+/// A presentation made for a holder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuedPresentation {
+    /// URI to render as a QR code or open as a deep link
+    pub uri: String,
+    pub mode: PresentationMode,
+    
+    /// Reference presentations only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation_id: Option<Uuid>,
+    
+    /// When the presentation can no longer be redeemed
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An envelope held for retrieval by reference
+struct StoredPresentation {
+    account_id: AccountId,
+    envelope: String,
+    token_hash: [u8; 32],
+    expires_at: DateTime<Utc>,
+}
+
+/// Store of envelopes presented by reference
+pub struct PresentationStore {
+    /// Live configuration holding the public URL and inline limit
+    config: Arc<LiveConfig>,
+    
+    clock: SharedClock,
+    
+    presentations: RwLock<HashMap<Uuid, StoredPresentation>>,
+}
+
+impl PresentationStore {
+    /// Create an empty store
+    pub fn new(config: Arc<LiveConfig>) -> Self {
+        Self {
+            config,
+            clock: system_clock(),
+            presentations: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Present an encoded envelope
+    ///
+    /// In `Auto` mode the envelope is carried inline when the URI fits the
+    /// configured limit, and held for retrieval by reference otherwise.
+    pub async fn present(
+        &self,
+        account_id: &AccountId,
+        envelope: String,
+        envelope_expires_at: Option<DateTime<Utc>>,
+        mode: PresentationMode,
+    ) -> Result<IssuedPresentation> {
+        let compliance = self.config.compliance();
+        let config = &compliance.presentations;
+        
+        let uri = Presentation::Inline {
+            envelope: envelope.clone(),
+        }
+        .to_uri();
+        let fits = uri.len() <= config.max_inline_bytes;
+        match mode {
+            PresentationMode::Inline if !fits => {
+                return Err(ComplianceError::validation(
+                    "mode",
+                    format!(
+                        "a {} byte presentation exceeds the {} byte inline limit",
+                        uri.len(),
+                        config.max_inline_bytes
+                    ),
+                ));
+            }
+            PresentationMode::Inline | PresentationMode::Auto if fits => {
+                return Ok(IssuedPresentation {
+                    uri,
+                    mode: PresentationMode::Inline,
+                    presentation_id: None,
+                    expires_at: envelope_expires_at,
+                });
+            }
+            _ => {}
+        }
+        
+        let Some(public_url) = &config.public_url else {
+            return Err(ComplianceError::validation(
+                "mode",
+                "presentation by reference needs compliance.presentations.public_url",
+            ));
+        };
+        let now = self.clock.now();
+        let expires_at = envelope_expires_at
+            .into_iter()
+            .chain([now + Duration::seconds(config.reference_ttl_secs as i64)])
+            .min()
+            .unwrap_or(now);
+        let id = Uuid::new_v4();
+        let token = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+        
+        let mut presentations = self.presentations.write().await;
+        presentations.retain(|_, stored| stored.expires_at > now);
+        presentations.insert(
+            id,
+            StoredPresentation {
+                account_id: account_id.clone(),
+                envelope,
+                token_hash: *blake3::hash(token.as_bytes()).as_bytes(),
+                expires_at,
+            },
+        );
+        let presentation = Presentation::Reference {
+            url: format!("{}{}{}", public_url.trim_end_matches('/'), RETRIEVAL_PATH, id),
+            token,
+        };
+        Ok(IssuedPresentation {
+            uri: presentation.to_uri(),
+            mode: PresentationMode::Reference,
+            presentation_id: Some(id),
+            expires_at: Some(expires_at),
+        })
+    }
+    
+    /// Retrieve an envelope presented by reference, spending its token
+    ///
+    /// Unknown, expired, and already retrieved presentations, and wrong
+    /// tokens, are all reported as not found.
+    pub async fn retrieve(&self, presentation_id: Uuid, token: &str) -> Result<(AccountId, String)> {
+        let now = self.clock.now();
+        let mut presentations = self.presentations.write().await;
+        let token_hash = blake3::hash(token.as_bytes());
+        let matches = presentations.get(&presentation_id).is_some_and(|stored| {
+            stored.expires_at > now && bool::from(stored.token_hash[..].ct_eq(&token_hash.as_bytes()[..]))
+        });
+        match matches.then(|| presentations.remove(&presentation_id)).flatten() {
+            Some(stored) => Ok((stored.account_id, stored.envelope)),
+            None => Err(ComplianceError::PresentationNotFound {
+                presentation_id: presentation_id.to_string(),
+            }),
+        }
+    }
+    
+    /// Envelope a scanned presentation carries or refers to
+    ///
+    /// Reference presentations must point at this deployment's public URL.
+    pub async fn resolve(&self, presentation: Presentation) -> Result<String> {
+        match presentation {
+            Presentation::Inline { envelope } => Ok(envelope),
+            Presentation::Reference { url, token } => {
+                let compliance = self.config.compliance();
+                let presentation_id = compliance
+                    .presentations
+                    .public_url
+                    .as_deref()
+                    .and_then(|public_url| url.strip_prefix(public_url.trim_end_matches('/')))
+                    .and_then(|path| path.strip_prefix(RETRIEVAL_PATH))
+                    .and_then(|id| id.parse::<Uuid>().ok())
+                    .ok_or_else(|| ComplianceError::validation("uri", "presentation was not issued by this service"))?;
+                Ok(self.retrieve(presentation_id, &token).await?.1)
+            }
+        }
+    }
+}
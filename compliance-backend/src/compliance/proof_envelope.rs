@@ -0,0 +1,332 @@
+//! Structured, signed envelope carrying a compliance proof
+
+use super::claims::{ClaimKind, ClaimSet, DisclosedClaim};
+use super::scope::ProofScope;
+use crate::crypto::signing::SIGNATURE_LENGTH;
+use crate::crypto::{attestation_commitment, AttestationSigner, TrustedKeys};
+use crate::types::{AccountId, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Current envelope version
+pub const ENVELOPE_VERSION: u8 = 2;
+
+/// Allowed clock skew when checking `issued_at`, in seconds
+const MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+/// Largest proof a compressed embedded proof may inflate to
+pub const MAX_INFLATED_PROOF_SIZE: usize = 16 * 1024 * 1024;
+
+/// Proof system and encoding used for `proof_bytes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofFormat {
+    /// Miden VM STARK proof
+    MidenStark,
+    /// Miden VM STARK proof, DEFLATE-compressed
+    MidenStarkDeflate,
+}
+
+/// Compression of the proof embedded in an envelope
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofCompression {
+    #[default]
+    None,
+    Deflate,
+}
+
+impl ProofCompression {
+    /// Format of a proof embedded with this compression
+    pub fn format(self) -> ProofFormat {
+        match self {
+            Self::None => ProofFormat::MidenStark,
+            Self::Deflate => ProofFormat::MidenStarkDeflate,
+        }
+    }
+}
+
+/// Per-request options trading envelope size against generation time
+///
+/// Some verifiers accept only small payloads: compressing the proof and
+/// disclosing fewer claims both shrink the envelope, and a size budget makes
+/// generation fail rather than hand out a proof the verifier will reject.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProofOptions {
+    /// Compression of the embedded proof
+    #[serde(default)]
+    pub compression: ProofCompression,
+    
+    /// Claims to disclose, each adding its opening to the envelope; defaults
+    /// to, and must include, the claims the challenge requires
+    #[serde(default)]
+    pub claims: Option<Vec<ClaimKind>>,
+    
+    /// Largest embedded proof the verifier accepts, in bytes; never above
+    /// `attestation.max_proof_size`
+    #[serde(default)]
+    pub max_proof_size: Option<usize>,
+}
+
+/// Size and timing of a generated proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofReport {
+    /// Size of the proof as generated, in bytes
+    pub proof_size: usize,
+    
+    /// Size of the proof as embedded in the envelope, in bytes
+    pub embedded_size: usize,
+    
+    pub compression: ProofCompression,
+    
+    /// Time spent generating the proof, including any wait for a proving
+    /// slot, in milliseconds; zero when a prepared renewal's proof was used
+    pub generation_ms: u64,
+    
+    /// Time spent compressing the proof and sealing the envelope, in milliseconds
+    pub sealing_ms: u64,
+    
+    /// Whether the proof was prepared ahead of time with a renewal
+    pub prepared: bool,
+}
+
+/// A compliance proof with its binding metadata and issuer signature
+///
+/// Encoded canonically as base64url (no padding) of the CBOR serialization of
+/// this struct. The signature covers the encoding with `signature` empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProofEnvelope {
+    pub version: u8,
+    pub format: ProofFormat,
+    pub account_id: AccountId,
+    /// Canonical commitment to the attested compliance state
+    #[serde(with = "serde_bytes_array")]
+    pub attestation_commitment: [u8; 32],
+    /// Root of the salted claim tree, committing to every claim separately
+    #[serde(with = "serde_bytes_array")]
+    pub claims_root: [u8; 32],
+    /// Claims the verifier requested, opened against `claims_root`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claims: Vec<DisclosedClaim>,
+    /// Verifier the proof is intended for
+    pub audience: String,
+    /// Verifier-issued challenge nonce
+    pub nonce: String,
+    /// Where the proof may be relied on; omitted for unscoped proofs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<ProofScope>,
+    /// Unix timestamp (seconds)
+    pub issued_at: i64,
+    /// Unix timestamp (seconds)
+    pub expires_at: i64,
+    #[serde(with = "serde_bytes_vec")]
+    pub proof_bytes: Vec<u8>,
+    /// Identifier of the key that produced `signature`
+    pub key_id: String,
+    #[serde(with = "serde_bytes_vec")]
+    pub signature: Vec<u8>,
+}
+
+/// Parameters for building an envelope
+pub struct EnvelopeParams<'a> {
+    pub attestation: &'a ComplianceAttestation,
+    /// Claims about the attestation
+    pub claims: &'a ClaimSet,
+    /// Kinds of claim to open for the verifier
+    pub disclose: &'a [ClaimKind],
+    pub audience: &'a str,
+    pub nonce: &'a str,
+    pub scope: Option<ProofScope>,
+    pub proof: Vec<u8>,
+    /// Compression applied to `proof` before it is embedded
+    pub compression: ProofCompression,
+    /// Maximum envelope lifetime; the attestation expiry also caps it
+    pub validity: Duration,
+}
+
+impl ProofEnvelope {
+    /// Build and sign an envelope
+    pub fn seal(params: EnvelopeParams<'_>, signer: &AttestationSigner) -> Result<Self> {
+        let now = Utc::now();
+        let expires_at = params.attestation.expires_at.min(now + params.validity);
+        
+        let proof_bytes = match params.compression {
+            ProofCompression::None => params.proof,
+            ProofCompression::Deflate => miniz_oxide::deflate::compress_to_vec(&params.proof, 9),
+        };
+        
+        let mut envelope = Self {
+            version: ENVELOPE_VERSION,
+            format: params.compression.format(),
+            account_id: params.attestation.account_id.clone(),
+            attestation_commitment: attestation_commitment(params.attestation).to_bytes(),
+            claims_root: params.claims.root(),
+            claims: params.claims.disclose(params.disclose)?,
+            audience: params.audience.to_string(),
+            nonce: params.nonce.to_string(),
+            scope: params.scope,
+            issued_at: now.timestamp(),
+            expires_at: expires_at.timestamp(),
+            proof_bytes,
+            key_id: signer.key_id().to_string(),
+            signature: vec![],
+        };
+        envelope.signature = signer.sign(&envelope.signing_payload()?)?;
+        Ok(envelope)
+    }
+    
+    /// Canonical encoding: base64url (no padding) of CBOR
+    pub fn encode(&self) -> Result<String> {
+        Ok(URL_SAFE_NO_PAD.encode(self.to_cbor()?))
+    }
+    
+    /// Strictly decode an envelope
+    ///
+    /// Rejects invalid base64url, unknown fields, unsupported versions, and any
+    /// encoding that is not the canonical one.
+    pub fn decode(encoded: &str) -> Result<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded.trim())
+            .map_err(|e| invalid(format!("envelope is not base64url: {}", e)))?;
+        let envelope: Self = ciborium::de::from_reader(bytes.as_slice())
+            .map_err(|e| invalid(format!("envelope is not valid CBOR: {}", e)))?;
+        
+        if envelope.version != ENVELOPE_VERSION {
+            return Err(invalid(format!("unsupported envelope version {}", envelope.version)));
+        }
+        if envelope.to_cbor()? != bytes {
+            return Err(invalid("envelope is not canonically encoded"));
+        }
+        
+        Ok(envelope)
+    }
+    
+    /// Validate the envelope's signature, lifetime, audience, size, and disclosed claims
+    pub fn validate(
+        &self,
+        trusted_keys: &TrustedKeys,
+        expected_audience: &str,
+        max_proof_size: usize,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        if self.signature.len() != SIGNATURE_LENGTH {
+            return Err(invalid("signature has wrong length"));
+        }
+        if self.proof_bytes.is_empty() || self.proof_bytes.len() > max_proof_size {
+            return Err(invalid(format!("proof size {} is out of bounds", self.proof_bytes.len())));
+        }
+        if self.audience != expected_audience {
+            return Err(invalid(format!("envelope audience {} does not match verifier", self.audience)));
+        }
+        if self.issued_at > now.timestamp() + MAX_CLOCK_SKEW_SECS {
+            return Err(invalid("envelope issued in the future"));
+        }
+        if self.expires_at <= now.timestamp() {
+            return Err(invalid("envelope has expired"));
+        }
+        if self.expires_at <= self.issued_at {
+            return Err(invalid("envelope expires before it was issued"));
+        }
+        // Claims are opened in tree order, each kind at most once
+        if self.claims.windows(2).any(|pair| pair[0].claim.kind() >= pair[1].claim.kind()) {
+            return Err(invalid("disclosed claims are not in canonical order"));
+        }
+        for claim in &self.claims {
+            claim.verify(&self.claims_root)?;
+        }
+        
+        trusted_keys
+            .verify(&self.key_id, &self.signing_payload()?, &self.signature)
+            .map_err(|e| invalid(e.to_string()))
+    }
+    
+    /// Bytes covered by the signature
+    pub fn signing_payload(&self) -> Result<Vec<u8>> {
+        Self {
+            signature: vec![],
+            ..self.clone()
+        }
+        .to_cbor()
+    }
+    
+    /// The embedded proof as generated, inflated if it was compressed
+    pub fn proof(&self) -> Result<Vec<u8>> {
+        match self.format {
+            ProofFormat::MidenStark => Ok(self.proof_bytes.clone()),
+            ProofFormat::MidenStarkDeflate => {
+                miniz_oxide::inflate::decompress_to_vec_with_limit(&self.proof_bytes, MAX_INFLATED_PROOF_SIZE)
+                    .map_err(|e| invalid(format!("compressed proof does not inflate: {:?}", e.status)))
+            }
+        }
+    }
+    
+    /// Expiry as a timestamp
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.expires_at, 0)
+    }
+    
+    fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(self, &mut bytes)
+            .map_err(|e| ComplianceError::internal(format!("failed to encode envelope: {}", e)))?;
+        Ok(bytes)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::InvalidProof { reason: reason.into() }
+}
+
+/// Serialize byte vectors as CBOR byte strings rather than integer arrays
+pub(crate) mod serde_bytes_vec {
+    use serde::de::{Error, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+    
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+        
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+            
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a byte string")
+            }
+            
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+            
+            fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+        }
+        
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// Serialize fixed-size digests as CBOR byte strings
+pub(crate) mod serde_bytes_array {
+    use serde::de::Error;
+    use serde::{Deserializer, Serializer};
+    
+    pub fn serialize<S: Serializer>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+        let bytes = super::serde_bytes_vec::deserialize(deserializer)?;
+        bytes
+            .try_into()
+            .map_err(|_| D::Error::custom("expected a 32-byte digest"))
+    }
+}
@@ -0,0 +1,245 @@
+//! Inbound result callbacks from KYC, AML, and sanctions providers
+//!
+//! Providers finish document, liveness, and enhanced due diligence checks
+//! asynchronously and post the outcome back. Every callback is stored raw,
+//! before any processing, once its signature verifies. A callback that fails
+//! to process stays stored as `failed` so an operator can replay it after
+//! fixing the cause.
+
+use super::breaker::Provider;
+use super::rejection::{KycRejection, RejectionReason};
+use super::ComplianceService;
+use crate::config::CallbackVerificationConfig;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use subtle::ConstantTimeEq;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Processing state of a stored callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackStatus {
+    /// Stored, not yet processed
+    Received,
+    /// Mapped into the account's verification state
+    Processed,
+    /// Valid, but carried nothing to act on
+    Ignored,
+    /// Processing failed; see `last_error`
+    Failed,
+}
+
+/// A callback as received from a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderCallback {
+    pub id: Uuid,
+    pub provider: Provider,
+    pub received_at: DateTime<Utc>,
+    /// Raw request body, exactly as signed by the provider
+    pub body: String,
+    pub status: CallbackStatus,
+    /// Account named by the callback, once parsed
+    pub account_id: Option<AccountId>,
+    /// Processing attempts, including replays
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome a provider reports for a verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationOutcome {
+    Approved,
+    Declined,
+    /// Escalated to the provider's manual review
+    NeedsReview,
+    /// Still in progress at the provider
+    Pending,
+}
+
+/// Body of a provider callback
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallbackPayload {
+    /// Provider event name (e.g. "verification.completed")
+    pub event_type: String,
+    pub account_id: AccountId,
+    /// Provider's reference for the verification
+    pub reference: Option<String>,
+    pub outcome: VerificationOutcome,
+    /// Reasons for a declined KYC verification, as taxonomy codes
+    #[serde(default)]
+    pub rejection_reasons: Vec<RejectionReason>,
+}
+
+/// Result of processing a callback
+#[derive(Debug, Clone)]
+pub struct CallbackProcessing {
+    pub callback: ProviderCallback,
+    /// Attestation re-issued from the provider's final result
+    pub attestation: Option<ComplianceAttestation>,
+}
+
+/// Verify a callback signature
+///
+/// The signature header carries the hex HMAC-SHA256 of the raw body, with or
+/// without a `sha256=` prefix.
+pub fn verify_signature(config: &CallbackVerificationConfig, signature: Option<&str>, body: &[u8]) -> Result<()> {
+    let signature = signature
+        .map(|value| value.trim().trim_start_matches("sha256="))
+        .and_then(|value| hex::decode(value).ok())
+        .ok_or_else(|| ComplianceError::InvalidCallbackSignature {
+            reason: "signature is missing or malformed".to_string(),
+        })?;
+    
+    let mut mac = Hmac::<Sha256>::new_from_slice(config.secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    if bool::from(mac.finalize().into_bytes().as_slice().ct_eq(&signature)) {
+        Ok(())
+    } else {
+        Err(ComplianceError::InvalidCallbackSignature {
+            reason: "signature does not match".to_string(),
+        })
+    }
+}
+
+/// Stored provider callbacks
+#[derive(Default)]
+pub struct CallbackStore {
+    callbacks: RwLock<HashMap<Uuid, ProviderCallback>>,
+}
+
+impl CallbackStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Store a verified callback before processing
+    pub async fn record(&self, provider: Provider, body: String) -> ProviderCallback {
+        let callback = ProviderCallback {
+            id: Uuid::new_v4(),
+            provider,
+            received_at: Utc::now(),
+            body,
+            status: CallbackStatus::Received,
+            account_id: None,
+            attempts: 0,
+            last_error: None,
+            processed_at: None,
+        };
+        self.callbacks.write().await.insert(callback.id, callback.clone());
+        callback
+    }
+    
+    /// Get a stored callback
+    pub async fn get(&self, callback_id: Uuid) -> Result<ProviderCallback> {
+        self.callbacks
+            .read()
+            .await
+            .get(&callback_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::ProviderCallbackNotFound {
+                callback_id: callback_id.to_string(),
+            })
+    }
+    
+    /// List callbacks, most recent first, optionally only those in one status
+    pub async fn list(&self, status: Option<CallbackStatus>, limit: usize) -> Vec<ProviderCallback> {
+        let mut callbacks: Vec<ProviderCallback> = self
+            .callbacks
+            .read()
+            .await
+            .values()
+            .filter(|callback| status.map_or(true, |s| callback.status == s))
+            .cloned()
+            .collect();
+        callbacks.sort_by(|a, b| b.received_at.cmp(&a.received_at));
+        callbacks.truncate(limit);
+        callbacks
+    }
+    
+    /// Map a stored callback into the account's verification state
+    ///
+    /// Approved and declined outcomes re-run the account's checks, which now
+    /// pick up the provider's final result, and re-issue the attestation. A
+    /// declined KYC verification also records the provider's rejection
+    /// reasons. Pending and review outcomes are recorded without changing
+    /// anything.
+    pub async fn process(&self, callback_id: Uuid, compliance: &ComplianceService) -> Result<CallbackProcessing> {
+        let callback = self.get(callback_id).await?;
+        
+        let (account_id, result) = match serde_json::from_str::<CallbackPayload>(&callback.body) {
+            Ok(payload) => {
+                let account_id = payload.account_id.clone();
+                let result = match payload.outcome {
+                    VerificationOutcome::Approved => compliance.update_compliance_status(&account_id).await.map(Some),
+                    VerificationOutcome::Declined => apply_decline(compliance, callback.provider, &payload).await.map(Some),
+                    VerificationOutcome::NeedsReview | VerificationOutcome::Pending => Ok(None),
+                };
+                tracing::info!(
+                    callback_id = %callback_id,
+                    provider = callback.provider.name(),
+                    event_type = %payload.event_type,
+                    reference = ?payload.reference,
+                    outcome = ?payload.outcome,
+                    "provider callback processed"
+                );
+                (Some(account_id), result)
+            }
+            Err(e) => (None, Err(ComplianceError::validation("body", format!("unrecognized callback payload: {}", e)))),
+        };
+        
+        let mut callbacks = self.callbacks.write().await;
+        let stored = callbacks
+            .get_mut(&callback_id)
+            .ok_or_else(|| ComplianceError::internal("provider callback disappeared during processing"))?;
+        stored.attempts += 1;
+        if account_id.is_some() {
+            stored.account_id = account_id;
+        }
+        let attestation = match result {
+            Ok(attestation) => {
+                stored.status = if attestation.is_some() {
+                    CallbackStatus::Processed
+                } else {
+                    CallbackStatus::Ignored
+                };
+                stored.last_error = None;
+                stored.processed_at = Some(Utc::now());
+                attestation
+            }
+            Err(e) => {
+                tracing::warn!(callback_id = %callback_id, provider = stored.provider.name(), error = %e, "provider callback processing failed");
+                stored.status = CallbackStatus::Failed;
+                stored.last_error = Some(e.to_string());
+                None
+            }
+        };
+        
+        Ok(CallbackProcessing {
+            callback: stored.clone(),
+            attestation,
+        })
+    }
+}
+
+/// Re-issue the attestation for a declined verification, recording why KYC was rejected
+async fn apply_decline(
+    compliance: &ComplianceService,
+    provider: Provider,
+    payload: &CallbackPayload,
+) -> Result<ComplianceAttestation> {
+    let attestation = compliance.update_compliance_status(&payload.account_id).await?;
+    if provider == Provider::Kyc && attestation.kyc_status != KycStatus::Verified {
+        let rejection = KycRejection::new(payload.rejection_reasons.clone(), payload.reference.clone(), Utc::now());
+        compliance.record_kyc_rejection(&payload.account_id, rejection).await?;
+    }
+    Ok(attestation)
+}
@@ -0,0 +1,356 @@
+//! Business clients' own provider credentials
+//!
+//! Some clients are contractually required to run checks through their own
+//! vendor accounts. A client can register an endpoint and API key per
+//! provider; provider calls made while serving that client's requests use
+//! them in place of the globally configured credentials. Keys are sealed with
+//! XChaCha20-Poly1305 at rest, bound to the client and provider they were
+//! registered for, and never returned once stored.
+//!
+//! Clients tagged with a data residency region fall back to the region's
+//! endpoints rather than the global ones (see [`super::residency`]). Calls
+//! routed to another vendor use that vendor's credentials in place of the
+//! global ones (see [`super::provider_routing`]).
+
+use super::breaker::Provider;
+use super::provider_routing::current_vendor;
+use super::residency;
+use crate::config::{ChainAnalyticsBackend, ComplianceConfig, ProviderCredentialsConfig};
+use crate::types::DataRegion;
+use crate::{ComplianceError, Result};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+tokio::task_local! {
+    /// Business client on whose behalf the current request runs, with its data residency region
+    static REQUEST_CLIENT: (Uuid, Option<DataRegion>);
+}
+
+/// Run `f` on behalf of a business client, routing its provider calls to the
+/// client's own credentials where registered, or else to its region's endpoints
+pub async fn with_client<F: Future>(client_id: Uuid, region: Option<DataRegion>, f: F) -> F::Output {
+    REQUEST_CLIENT.scope((client_id, region), f).await
+}
+
+/// Business client the current task runs on behalf of, if any
+pub fn current_client() -> Option<Uuid> {
+    REQUEST_CLIENT.try_with(|(client_id, _)| *client_id).ok()
+}
+
+/// Data residency region of the client the current task runs on behalf of, if any
+pub fn current_region() -> Option<DataRegion> {
+    REQUEST_CLIENT.try_with(|(_, region)| *region).ok().flatten()
+}
+
+/// Endpoint and API key used to call a provider
+#[derive(Clone, Default)]
+pub struct ProviderCredentials {
+    pub endpoint: Option<String>,
+    pub api_key: Option<String>,
+}
+
+impl std::fmt::Debug for ProviderCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderCredentials")
+            .field("endpoint", &self.endpoint)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[redacted]"))
+            .finish()
+    }
+}
+
+/// Whose credentials a provider call uses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// Credentials from `ComplianceConfig`
+    Global,
+    /// A data residency region's endpoint from `ComplianceConfig`
+    Regional(DataRegion),
+    /// A business client's own credentials
+    Client(Uuid),
+    /// A vendor from `compliance.provider_routing` the call is routed to
+    Vendor(String),
+}
+
+/// Credentials selected for a provider call
+#[derive(Debug, Clone)]
+pub struct ResolvedCredentials {
+    pub source: CredentialSource,
+    pub credentials: ProviderCredentials,
+}
+
+/// A registered client credential, without its API key
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialSummary {
+    pub client_id: Uuid,
+    pub provider: Provider,
+    pub endpoint: Option<String>,
+    /// Last four characters of the API key; empty for short keys
+    pub api_key_hint: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Client credential as stored, with its API key sealed
+struct SealedCredential {
+    endpoint: Option<String>,
+    nonce: [u8; 24],
+    ciphertext: Vec<u8>,
+    api_key_hint: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Globally configured credentials for a provider
+pub fn global_credentials(provider: Provider, config: &ComplianceConfig) -> ProviderCredentials {
+    let (endpoint, api_key) = match provider {
+        Provider::Kyc => (&config.kyc.provider_endpoint, &config.kyc.provider_api_key),
+        Provider::Aml => (&config.aml.provider_endpoint, &config.aml.provider_api_key),
+        Provider::Sanctions => (&config.sanctions.provider_endpoint, &config.sanctions.provider_api_key),
+        Provider::AdverseMedia => return ProviderCredentials::default(),
+        Provider::ChainAnalytics => {
+            return match &config.aml.chain_analytics.backend {
+                Some(ChainAnalyticsBackend::Chainalysis { api_url, api_key } | ChainAnalyticsBackend::Trm { api_url, api_key }) => {
+                    ProviderCredentials {
+                        endpoint: Some(api_url.clone()),
+                        api_key: Some(api_key.clone()),
+                    }
+                }
+                None => ProviderCredentials::default(),
+            };
+        }
+    };
+    ProviderCredentials {
+        endpoint: endpoint.clone(),
+        api_key: api_key.clone(),
+    }
+}
+
+/// Client provider credentials, sealed at rest
+pub struct ProviderCredentialStore {
+    /// Absent when no encryption key is configured; clients cannot register credentials then
+    cipher: Option<XChaCha20Poly1305>,
+    credentials: RwLock<HashMap<(Uuid, Provider), SealedCredential>>,
+}
+
+impl ProviderCredentialStore {
+    /// Create an empty store sealing credentials with `key`
+    pub fn new(key: Option<[u8; 32]>) -> Self {
+        Self {
+            cipher: key.map(|key| XChaCha20Poly1305::new(&key.into())),
+            credentials: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Create an empty store from configuration
+    pub fn from_config(config: &ProviderCredentialsConfig) -> Result<Self> {
+        let key = match &config.encryption_key {
+            Some(key) => {
+                let bytes = hex::decode(key)
+                    .map_err(|_| ComplianceError::crypto("provider credential encryption key is not hex"))?;
+                let key: [u8; 32] = bytes
+                    .try_into()
+                    .map_err(|_| ComplianceError::crypto("provider credential encryption key must be 32 bytes"))?;
+                Some(key)
+            }
+            None => None,
+        };
+        Ok(Self::new(key))
+    }
+    
+    /// Register or replace a client's credentials for a provider
+    ///
+    /// Without an endpoint, the globally configured endpoint is used with the
+    /// client's API key.
+    pub async fn set(
+        &self,
+        client_id: Uuid,
+        provider: Provider,
+        endpoint: Option<String>,
+        api_key: &str,
+    ) -> Result<CredentialSummary> {
+        if api_key.trim().is_empty() {
+            return Err(ComplianceError::validation("api_key", "must not be empty"));
+        }
+        if let Some(endpoint) = &endpoint {
+            if !endpoint.starts_with("https://") {
+                return Err(ComplianceError::validation("endpoint", "must be an https URL"));
+            }
+        }
+        let cipher = self.cipher()?;
+        
+        let nonce: [u8; 24] = rand::random();
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: api_key.as_bytes(),
+                    aad: &associated_data(client_id, provider),
+                },
+            )
+            .map_err(|_| ComplianceError::crypto("failed to seal provider credential"))?;
+        let key_len = api_key.chars().count();
+        let api_key_hint = if key_len > 8 {
+            api_key.chars().skip(key_len - 4).collect()
+        } else {
+            String::new()
+        };
+        
+        let now = Utc::now();
+        let mut credentials = self.credentials.write().await;
+        let created_at = credentials
+            .get(&(client_id, provider))
+            .map_or(now, |existing| existing.created_at);
+        let sealed = SealedCredential {
+            endpoint,
+            nonce,
+            ciphertext,
+            api_key_hint,
+            created_at,
+            updated_at: now,
+        };
+        let summary = summarize(client_id, provider, &sealed);
+        credentials.insert((client_id, provider), sealed);
+        Ok(summary)
+    }
+    
+    /// Remove a client's credentials for a provider, reverting it to the global ones
+    pub async fn remove(&self, client_id: Uuid, provider: Provider) -> Result<()> {
+        self.credentials
+            .write()
+            .await
+            .remove(&(client_id, provider))
+            .map(|_| ())
+            .ok_or_else(|| ComplianceError::ProviderCredentialNotFound {
+                client_id: client_id.to_string(),
+                provider: provider.name().to_string(),
+            })
+    }
+    
+    /// Credentials registered by a client
+    pub async fn list(&self, client_id: Uuid) -> Vec<CredentialSummary> {
+        let mut summaries: Vec<_> = self
+            .credentials
+            .read()
+            .await
+            .iter()
+            .filter(|((owner, _), _)| *owner == client_id)
+            .map(|((_, provider), sealed)| summarize(client_id, *provider, sealed))
+            .collect();
+        summaries.sort_by_key(|summary| summary.provider.name());
+        summaries
+    }
+    
+    /// Credentials to call `provider` with on behalf of `client_id`, tagged with `region`
+    ///
+    /// Falls back to the region's endpoint when the client has none
+    /// registered, and to the global credentials when the region has none
+    /// either. A strict region never falls back to the global credentials.
+    pub async fn resolve(
+        &self,
+        client_id: Option<Uuid>,
+        region: Option<DataRegion>,
+        provider: Provider,
+        config: &ComplianceConfig,
+    ) -> Result<ResolvedCredentials> {
+        let fallback = || -> Result<ResolvedCredentials> {
+            let regional = match region {
+                Some(region) => residency::regional_credentials(region, provider, config)?.map(|c| (region, c)),
+                None => None,
+            };
+            Ok(match regional {
+                Some((region, credentials)) => ResolvedCredentials {
+                    source: CredentialSource::Regional(region),
+                    credentials,
+                },
+                None => ResolvedCredentials {
+                    source: CredentialSource::Global,
+                    credentials: global_credentials(provider, config),
+                },
+            })
+        };
+        let Some(client_id) = client_id else {
+            return fallback();
+        };
+        
+        let credentials = self.credentials.read().await;
+        let Some(sealed) = credentials.get(&(client_id, provider)) else {
+            return fallback();
+        };
+        let api_key = self
+            .cipher()?
+            .decrypt(
+                XNonce::from_slice(&sealed.nonce),
+                Payload {
+                    msg: &sealed.ciphertext,
+                    aad: &associated_data(client_id, provider),
+                },
+            )
+            .map_err(|_| ComplianceError::crypto("failed to open provider credential"))?;
+        let api_key = String::from_utf8(api_key).map_err(|_| ComplianceError::crypto("provider credential is not UTF-8"))?;
+        
+        let endpoint = match &sealed.endpoint {
+            Some(endpoint) => Some(endpoint.clone()),
+            None => fallback()?.credentials.endpoint,
+        };
+        
+        Ok(ResolvedCredentials {
+            source: CredentialSource::Client(client_id),
+            credentials: ProviderCredentials {
+                endpoint,
+                api_key: Some(api_key),
+            },
+        })
+    }
+    
+    /// Credentials to call `provider` with for the current request
+    ///
+    /// A call routed to a vendor uses the vendor's credentials where it
+    /// would otherwise use the global ones.
+    pub async fn resolve_current(&self, provider: Provider, config: &ComplianceConfig) -> Result<ResolvedCredentials> {
+        let resolved = self.resolve(current_client(), current_region(), provider, config).await?;
+        if resolved.source != CredentialSource::Global {
+            return Ok(resolved);
+        }
+        let routed = current_vendor().and_then(|name| {
+            let vendor = config.provider_routing.vendors.get(&name)?;
+            (vendor.provider == provider).then(|| ResolvedCredentials {
+                credentials: ProviderCredentials {
+                    endpoint: Some(vendor.endpoint.clone()),
+                    api_key: vendor.api_key.clone(),
+                },
+                source: CredentialSource::Vendor(name),
+            })
+        });
+        Ok(routed.unwrap_or(resolved))
+    }
+    
+    fn cipher(&self) -> Result<&XChaCha20Poly1305> {
+        self.cipher.as_ref().ok_or_else(|| {
+            ComplianceError::crypto("compliance.provider_credentials.encryption_key is not configured")
+        })
+    }
+}
+
+/// Bind a sealed key to the client and provider it was registered for
+fn associated_data(client_id: Uuid, provider: Provider) -> Vec<u8> {
+    let mut aad = client_id.as_bytes().to_vec();
+    aad.extend_from_slice(provider.name().as_bytes());
+    aad
+}
+
+fn summarize(client_id: Uuid, provider: Provider, sealed: &SealedCredential) -> CredentialSummary {
+    CredentialSummary {
+        client_id,
+        provider,
+        endpoint: sealed.endpoint.clone(),
+        api_key_hint: sealed.api_key_hint.clone(),
+        created_at: sealed.created_at,
+        updated_at: sealed.updated_at,
+    }
+}
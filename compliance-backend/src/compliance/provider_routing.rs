@@ -0,0 +1,166 @@
+//! Cost-aware routing of provider calls between vendors
+//!
+//! A provider can be served by more than one vendor: its globally configured
+//! one, named `default`, and any listed in `compliance.provider_routing`.
+//! Routing rules pick the vendors for a call by provider and by the account's
+//! current compliance level, so that basic verifications can go to a cheaper
+//! vendor with a premium one behind it. A call falls back along the rule's
+//! vendors while they are unavailable; the provider's circuit breaker only
+//! sees the call fail once every vendor has.
+//!
+//! Calls using a client's own credentials or a region's endpoints are not
+//! routed, as those fix the vendor. Calls made for a client are recorded with
+//! their vendor and cost in the client's usage (see [`super::metering`]).
+
+use super::breaker::{is_provider_failure, Provider};
+use super::metering::UsageMeter;
+use super::provider_credentials::{current_client, CredentialSource, ProviderCredentialStore};
+use crate::reload::LiveConfig;
+use crate::types::ComplianceLevel;
+use crate::Result;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Name of a provider's globally configured vendor
+pub const DEFAULT_VENDOR: &str = "default";
+
+tokio::task_local! {
+    /// Vendor the current provider call is routed to, other than the default
+    static ROUTED_VENDOR: String;
+}
+
+/// Vendor the current provider call is routed to, if other than the default
+pub fn current_vendor() -> Option<String> {
+    ROUTED_VENDOR.try_with(Clone::clone).ok()
+}
+
+/// A vendor a call can be routed to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub vendor: String,
+    
+    /// Cost of a call to the vendor
+    pub cost: f64,
+}
+
+/// Routes provider calls between vendors and records their cost
+pub struct ProviderRouter {
+    /// Live configuration holding vendors and routing rules
+    config: Arc<LiveConfig>,
+    
+    /// Client and regional credentials, which pin the vendor
+    credentials: Arc<ProviderCredentialStore>,
+    
+    meter: Arc<UsageMeter>,
+}
+
+impl ProviderRouter {
+    /// Create a router
+    pub fn new(config: Arc<LiveConfig>, credentials: Arc<ProviderCredentialStore>, meter: Arc<UsageMeter>) -> Self {
+        Self {
+            config,
+            credentials,
+            meter,
+        }
+    }
+    
+    /// Whether any routing rule is configured for `provider`
+    pub fn routes_provider(&self, provider: Provider) -> bool {
+        self.config.compliance().provider_routing.rules.iter().any(|rule| rule.provider == provider)
+    }
+    
+    /// Vendors to call for a call to `provider` in the current request, in order
+    ///
+    /// `level` is the account's current compliance level; accounts without
+    /// one count as basic.
+    pub async fn routes(&self, provider: Provider, level: Option<&ComplianceLevel>) -> Result<Vec<Route>> {
+        let config = self.config.compliance();
+        let routing = &config.provider_routing;
+        let default = Route {
+            vendor: DEFAULT_VENDOR.to_string(),
+            cost: routing.default_costs.get(&provider).copied().unwrap_or(0.0),
+        };
+        let resolved = self.credentials.resolve_current(provider, &config).await?;
+        if resolved.source != CredentialSource::Global {
+            return Ok(vec![default]);
+        }
+        
+        let level = level.cloned().unwrap_or(ComplianceLevel::Basic);
+        let rule = routing
+            .rules
+            .iter()
+            .find(|rule| rule.provider == provider && (rule.levels.is_empty() || rule.levels.contains(&level)));
+        let Some(rule) = rule else {
+            return Ok(vec![default]);
+        };
+        let mut routes: Vec<Route> = rule
+            .vendors
+            .iter()
+            .filter_map(|name| match routing.vendors.get(name) {
+                Some(vendor) => Some(Route {
+                    vendor: name.clone(),
+                    cost: vendor.cost_per_call,
+                }),
+                None => (name == DEFAULT_VENDOR).then(|| default.clone()),
+            })
+            .collect();
+        if rule.cheapest_first {
+            routes.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+        }
+        if !rule.fallback {
+            routes.truncate(1);
+        }
+        if routes.is_empty() {
+            routes.push(default);
+        }
+        Ok(routes)
+    }
+    
+    /// Make a call to `provider`, falling back to the next vendor while one is unavailable
+    ///
+    /// `call` is made once per vendor tried, with credentials resolving to
+    /// that vendor's. Errors that do not count as provider failures for its
+    /// circuit breaker are returned without trying another vendor.
+    pub async fn call<T, F, Fut>(&self, provider: Provider, level: Option<&ComplianceLevel>, call: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let routes = self.routes(provider, level).await?;
+        let client_id = current_client();
+        let mut routes = routes.into_iter().peekable();
+        while let Some(route) = routes.next() {
+            let result = if route.vendor == DEFAULT_VENDOR {
+                call().await
+            } else {
+                ROUTED_VENDOR.scope(route.vendor.clone(), call()).await
+            };
+            if let Some(client_id) = client_id {
+                let recorded = self
+                    .meter
+                    .record_provider_call(client_id, provider, &route.vendor, route.cost, result.is_ok())
+                    .await;
+                if let Err(error) = recorded {
+                    tracing::warn!(
+                        client_id = %client_id,
+                        vendor = %route.vendor,
+                        "failed to record provider call: {}",
+                        error
+                    );
+                }
+            }
+            match result {
+                Err(error) if routes.peek().is_some() && is_provider_failure(&error) => {
+                    tracing::warn!(
+                        provider = provider.name(),
+                        vendor = %route.vendor,
+                        "vendor unavailable, falling back: {}",
+                        error
+                    );
+                }
+                result => return result,
+            }
+        }
+        unreachable!("routes always include at least one vendor")
+    }
+}
@@ -0,0 +1,177 @@
+//! Bounded, prioritized admission in front of the Miden proving path
+
+use crate::config::ProvingQueueConfig;
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::oneshot;
+
+/// Priority class of a proof request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofPriority {
+    /// A verifier is waiting on the proof
+    Interactive,
+    /// Background re-screening and bulk regeneration
+    Batch,
+}
+
+/// Point-in-time view of the proving queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvingQueueStatus {
+    pub running: usize,
+    pub queued_interactive: usize,
+    pub queued_batch: usize,
+    pub max_concurrent: usize,
+    /// Moving average of proof generation time in milliseconds
+    pub avg_proof_ms: u64,
+    pub rejected_total: u64,
+}
+
+struct QueueInner {
+    running: usize,
+    interactive: VecDeque<oneshot::Sender<ProvingPermit>>,
+    batch: VecDeque<oneshot::Sender<ProvingPermit>>,
+    avg_proof_ms: f64,
+    rejected_total: u64,
+}
+
+/// Admission queue for proof generation
+///
+/// At most `max_concurrent` proofs run at once. Further requests wait in a
+/// bounded queue per priority class, interactive requests first. Requests
+/// that find their class queue full are rejected with a retry hint.
+pub struct ProvingQueue {
+    config: ProvingQueueConfig,
+    inner: Arc<Mutex<QueueInner>>,
+}
+
+/// Slot held while a proof is being generated; the next waiter is admitted on drop
+///
+/// A permit handed to a waiter that has since gone away is dropped with the
+/// channel, which passes the slot on again.
+pub struct ProvingPermit {
+    inner: Option<Arc<Mutex<QueueInner>>>,
+    started_at: Instant,
+}
+
+impl ProvingQueue {
+    /// Create an empty queue
+    pub fn new(config: ProvingQueueConfig) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(QueueInner {
+                running: 0,
+                interactive: VecDeque::new(),
+                batch: VecDeque::new(),
+                avg_proof_ms: config.initial_estimate_ms as f64,
+                rejected_total: 0,
+            })),
+            config,
+        }
+    }
+    
+    /// Run a proving future once admitted
+    pub async fn run<T>(&self, priority: ProofPriority, prove: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        let _permit = self.acquire(priority).await?;
+        prove.await
+    }
+    
+    /// Wait for a proving slot
+    pub async fn acquire(&self, priority: ProofPriority) -> Result<ProvingPermit> {
+        let waiter = {
+            let mut inner = lock(&self.inner);
+            let nobody_waiting = inner.interactive.is_empty() && inner.batch.is_empty();
+            if inner.running < self.config.max_concurrent && nobody_waiting {
+                inner.running += 1;
+                return Ok(self.permit());
+            }
+            
+            let (limit, queued) = match priority {
+                ProofPriority::Interactive => (self.config.max_queued_interactive, inner.interactive.len()),
+                ProofPriority::Batch => (self.config.max_queued_batch, inner.batch.len()),
+            };
+            if queued >= limit {
+                inner.rejected_total += 1;
+                return Err(ComplianceError::ProverSaturated {
+                    retry_after_secs: self.retry_after(&inner, priority),
+                });
+            }
+            
+            let (tx, rx) = oneshot::channel();
+            match priority {
+                ProofPriority::Interactive => inner.interactive.push_back(tx),
+                ProofPriority::Batch => inner.batch.push_back(tx),
+            }
+            rx
+        };
+        
+        // The slot is handed over by the permit being released
+        waiter
+            .await
+            .map_err(|_| ComplianceError::internal("proving queue closed"))
+    }
+    
+    /// Current queue status
+    pub fn status(&self) -> ProvingQueueStatus {
+        let inner = lock(&self.inner);
+        ProvingQueueStatus {
+            running: inner.running,
+            queued_interactive: inner.interactive.len(),
+            queued_batch: inner.batch.len(),
+            max_concurrent: self.config.max_concurrent,
+            avg_proof_ms: inner.avg_proof_ms as u64,
+            rejected_total: inner.rejected_total,
+        }
+    }
+    
+    fn permit(&self) -> ProvingPermit {
+        ProvingPermit::new(self.inner.clone())
+    }
+    
+    /// Estimate when a slot for this class is likely to free up
+    fn retry_after(&self, inner: &QueueInner, priority: ProofPriority) -> u64 {
+        let ahead = match priority {
+            ProofPriority::Interactive => inner.interactive.len(),
+            ProofPriority::Batch => inner.interactive.len() + inner.batch.len(),
+        };
+        let rounds = (ahead / self.config.max_concurrent.max(1)) as f64 + 1.0;
+        let secs = (rounds * inner.avg_proof_ms / 1000.0).ceil() as u64;
+        secs.max(1)
+    }
+}
+
+impl ProvingPermit {
+    fn new(inner: Arc<Mutex<QueueInner>>) -> Self {
+        Self {
+            inner: Some(inner),
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ProvingPermit {
+    fn drop(&mut self) {
+        let Some(shared) = self.inner.take() else {
+            return;
+        };
+        let mut inner = lock(&shared);
+        let elapsed_ms = self.started_at.elapsed().as_millis() as f64;
+        inner.avg_proof_ms = inner.avg_proof_ms * 0.8 + elapsed_ms * 0.2;
+        
+        // Hand the slot to the next live waiter, interactive first
+        while let Some(waiter) = inner.interactive.pop_front().or_else(|| inner.batch.pop_front()) {
+            match waiter.send(ProvingPermit::new(shared.clone())) {
+                Ok(()) => return,
+                // Disarm the undelivered permit so it does not release the slot again
+                Err(mut undelivered) => undelivered.inner = None,
+            }
+        }
+        inner.running -= 1;
+    }
+}
+
+fn lock(inner: &Mutex<QueueInner>) -> std::sync::MutexGuard<'_, QueueInner> {
+    inner.lock().expect("proving queue lock poisoned")
+}
@@ -0,0 +1,77 @@
+//! Structured KYC rejections
+//!
+//! A bare `Rejected` status tells an end user nothing about what to fix. A
+//! rejection carries the provider's reasons mapped onto a fixed taxonomy,
+//! each of which states whether the user may retry verification.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why a KYC verification was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// The identity document is past its expiry date
+    DocumentExpired,
+    /// The selfie or liveness capture does not match the document photo
+    FaceMismatch,
+    /// The document shows signs of forgery or digital alteration
+    TamperedDocument,
+    /// The user is below the minimum age
+    Underage,
+    /// The document's issuing country or the user's residence is not supported
+    UnsupportedJurisdiction,
+    /// A provider reason outside the taxonomy
+    #[serde(other)]
+    Other,
+}
+
+impl RejectionReason {
+    /// Whether the user may start a new verification after this rejection
+    ///
+    /// Expired documents and face mismatches can be fixed by the user;
+    /// anything else needs review before another attempt is accepted.
+    pub fn retry_eligible(self) -> bool {
+        matches!(self, Self::DocumentExpired | Self::FaceMismatch)
+    }
+    
+    /// Explanation suitable for showing to the end user
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::DocumentExpired => "the identity document has expired",
+            Self::FaceMismatch => "the photo does not match the identity document",
+            Self::TamperedDocument => "the identity document could not be verified as genuine",
+            Self::Underage => "the minimum age requirement is not met",
+            Self::UnsupportedJurisdiction => "the jurisdiction is not supported",
+            Self::Other => "the verification was rejected by the provider",
+        }
+    }
+}
+
+/// A rejected KYC verification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KycRejection {
+    pub reasons: Vec<RejectionReason>,
+    /// Whether every reason allows the user to retry
+    pub retry_eligible: bool,
+    /// Provider's reference for the rejected verification
+    pub provider_reference: Option<String>,
+    pub rejected_at: DateTime<Utc>,
+}
+
+impl KycRejection {
+    /// Build a rejection; a rejection without reasons is recorded as `Other`
+    pub fn new(mut reasons: Vec<RejectionReason>, provider_reference: Option<String>, rejected_at: DateTime<Utc>) -> Self {
+        if reasons.is_empty() {
+            reasons.push(RejectionReason::Other);
+        }
+        reasons.sort();
+        reasons.dedup();
+        Self {
+            retry_eligible: reasons.iter().all(|reason| reason.retry_eligible()),
+            reasons,
+            provider_reference,
+            rejected_at,
+        }
+    }
+}
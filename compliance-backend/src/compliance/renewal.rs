@@ -0,0 +1,225 @@
+//! Attestation pre-issuance ahead of expiry
+//!
+//! Renewing at expiry blocks users for as long as the checks and proof take.
+//! The renewal worker instead re-runs the checks for attestations within the
+//! configured lead time of expiry. When they still support the account's
+//! current compliance level, it issues the renewed attestation right away and
+//! keeps its proof, so proof requests around the old expiry need neither a
+//! fresh check nor a fresh proof.
+
+use super::attestation_events::AttestationStatus;
+use super::proving::ProofPriority;
+use super::ComplianceService;
+use crate::reload::LiveConfig;
+use crate::scheduler::Job;
+use crate::types::*;
+use crate::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Actor recorded for attestations issued by the renewal worker
+pub const RENEWAL_ACTOR: &str = "system:renewal";
+
+/// Name of the job pre-issuing renewals
+pub const RENEWAL_JOB: &str = "attestation_renewal";
+
+/// A renewed attestation and its proof, prepared ahead of the previous expiry
+#[derive(Debug, Clone)]
+pub struct PreparedRenewal {
+    pub attestation: ComplianceAttestation,
+    pub proof: String,
+    /// Event sequence that issued the attestation; any later event invalidates the proof
+    pub version: u64,
+    pub prepared_at: DateTime<Utc>,
+}
+
+/// Renewals prepared by the worker, by account
+#[derive(Default)]
+pub struct RenewalStore {
+    prepared: RwLock<HashMap<AccountId, PreparedRenewal>>,
+}
+
+impl RenewalStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record a prepared renewal, replacing any earlier one for the account
+    pub async fn insert(&self, renewal: PreparedRenewal) {
+        self.prepared
+            .write()
+            .await
+            .insert(renewal.attestation.account_id.clone(), renewal);
+    }
+    
+    /// Get an account's prepared renewal if its attestation state is still at `version`
+    pub async fn get(&self, account_id: &AccountId, version: u64) -> Option<PreparedRenewal> {
+        self.prepared
+            .read()
+            .await
+            .get(account_id)
+            .filter(|renewal| renewal.version == version)
+            .cloned()
+    }
+    
+    /// Drop renewals prepared before `cutoff`, after which proofs are generated afresh again
+    pub async fn prune(&self, cutoff: DateTime<Utc>) {
+        self.prepared.write().await.retain(|_, renewal| renewal.prepared_at > cutoff);
+    }
+}
+
+/// Outcome of one renewal pass
+#[derive(Debug, Default)]
+pub struct RenewalRun {
+    pub renewed: usize,
+    /// Accounts whose checks no longer support their current level
+    pub declined: usize,
+    pub failed: usize,
+}
+
+/// Pre-issue renewals for attestations expiring within `lead_time` of `now`
+///
+/// At most `max_renewals` accounts are renewed per pass, soonest expiry first.
+/// An account whose fresh checks would lower its compliance level is left to
+/// expire, so the downgrade surfaces through the normal check path.
+pub async fn renew_expiring(
+    compliance: &ComplianceService,
+    now: DateTime<Utc>,
+    lead_time: Duration,
+    max_renewals: usize,
+) -> RenewalRun {
+    let states = match compliance.events.current_states().await {
+        Ok(states) => states,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load attestations for pre-issuance");
+            return RenewalRun::default();
+        }
+    };
+    let mut due = Vec::new();
+    for (_, state) in states {
+        let expires_at = state.attestation.expires_at;
+        if state.status == AttestationStatus::Active && expires_at > now && expires_at - now <= lead_time {
+            due.push(state.attestation);
+        }
+    }
+    due.sort_by_key(|attestation| attestation.expires_at);
+    due.truncate(max_renewals);
+    
+    let mut run = RenewalRun::default();
+    for current in due {
+        match renew(compliance, &current, now).await {
+            Ok(Some(_)) => run.renewed += 1,
+            Ok(None) => run.declined += 1,
+            Err(e) => {
+                run.failed += 1;
+                tracing::warn!(account_id = %current.account_id, error = %e, "attestation pre-issuance failed");
+            }
+        }
+    }
+    
+    compliance.renewals.prune(now - lead_time).await;
+    run
+}
+
+/// Renew one attestation if its fresh checks still support its level
+async fn renew(
+    compliance: &ComplianceService,
+    current: &ComplianceAttestation,
+    now: DateTime<Utc>,
+) -> Result<Option<ComplianceAttestation>> {
+    let account_id = &current.account_id;
+    let renewed = compliance.comprehensive_check(account_id, false).await?;
+    
+    // A provider fallback hands back the attestation in force; nothing was renewed
+    if renewed.id == current.id {
+        return Ok(None);
+    }
+    
+    let current_level = compliance.highest_compliance_level_at(current, now).await;
+    let renewed_level = compliance.highest_compliance_level_at(&renewed, now).await;
+    if renewed_level < current_level {
+        tracing::info!(
+            account_id = %account_id,
+            current_level = ?current_level,
+            renewed_level = ?renewed_level,
+            "renewal checks no longer support the current level, leaving attestation to expire"
+        );
+        return Ok(None);
+    }
+    
+    let proof = compliance
+        .proving
+        .run(ProofPriority::Batch, compliance.attestation.generate_zk_proof(&renewed))
+        .await?;
+    
+    // Revocations and overrides recorded while the checks ran take precedence
+    let unchanged = compliance
+        .events
+        .project(account_id, None)
+        .await?
+        .is_some_and(|state| state.attestation.id == current.id && state.is_valid_at(compliance.clock.now()));
+    if !unchanged {
+        return Ok(None);
+    }
+    let details = serde_json::json!({
+        "previous_attestation_id": current.id,
+        "previous_expires_at": current.expires_at,
+        "attestation_id": renewed.id,
+        "expires_at": renewed.expires_at,
+    });
+    let version = compliance
+        .issue_attestation(&renewed, RENEWAL_ACTOR, "attestation.pre_issued", details)
+        .await?;
+    compliance
+        .renewals
+        .insert(PreparedRenewal {
+            attestation: renewed.clone(),
+            proof,
+            version,
+            prepared_at: compliance.clock.now(),
+        })
+        .await;
+    
+    tracing::info!(
+        account_id = %account_id,
+        previous_expires_at = %current.expires_at,
+        expires_at = %renewed.expires_at,
+        "attestation pre-issued"
+    );
+    Ok(Some(renewed))
+}
+
+/// Job periodically pre-issuing renewals
+///
+/// Lead time and batch size are read from the live configuration on every run.
+pub fn renewal_job(compliance: Arc<ComplianceService>, config: Arc<LiveConfig>, interval: std::time::Duration) -> Job {
+    Job::every(RENEWAL_JOB, interval, move || {
+        let (compliance, config) = (compliance.clone(), config.clone());
+        async move {
+            let renewal = config.compliance().attestation.renewal.clone();
+            if !renewal.enabled {
+                return Ok(());
+            }
+            
+            let run = renew_expiring(
+                &compliance,
+                compliance.clock.now(),
+                Duration::hours(renewal.lead_time_hours as i64),
+                renewal.max_renewals_per_run,
+            )
+            .await;
+            if run.renewed + run.declined + run.failed > 0 {
+                tracing::info!(
+                    renewed = run.renewed,
+                    declined = run.declined,
+                    failed = run.failed,
+                    "attestation renewal pass complete"
+                );
+            }
+            Ok(())
+        }
+    })
+}
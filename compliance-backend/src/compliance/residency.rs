@@ -0,0 +1,108 @@
+//! Data residency routing
+//!
+//! A business client can be tagged with the region its end users' PII must
+//! stay in. PII stored for a tagged client goes to the region's database or
+//! schema from `DatabaseConfig`, and provider calls made on its behalf go to
+//! the region's endpoints from `ComplianceConfig`. A strict region (the EU by
+//! default) never falls back to the global resources: anything the region
+//! does not configure fails rather than leaving it. Other regions use the
+//! global resources for whatever they do not configure.
+
+use super::breaker::Provider;
+use super::provider_credentials::{global_credentials, ProviderCredentials};
+use crate::config::{ComplianceConfig, DatabaseConfig, ResidencyConfig};
+use crate::types::DataRegion;
+use crate::{ComplianceError, Result};
+
+/// Check if PII of a region must stay on the region's own resources
+pub fn is_strict(region: DataRegion, config: &ResidencyConfig) -> bool {
+    config.strict_regions.contains(&region)
+}
+
+/// Credentials for calling a provider from a region
+///
+/// `None` when the region configures no endpoint for the provider and may
+/// use the global one.
+pub fn regional_credentials(
+    region: DataRegion,
+    provider: Provider,
+    config: &ComplianceConfig,
+) -> Result<Option<ProviderCredentials>> {
+    let regional = config.residency.regions.get(&region).and_then(|providers| providers.get(&provider));
+    match regional {
+        Some(regional) => Ok(Some(ProviderCredentials {
+            endpoint: Some(regional.endpoint.clone()),
+            api_key: regional
+                .api_key
+                .clone()
+                .or_else(|| global_credentials(provider, config).api_key),
+        })),
+        None if is_strict(region, &config.residency) => {
+            Err(unavailable(region, &format!("{} provider", provider.name())))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Database PII of a region is stored in
+#[derive(Clone)]
+pub struct StorageTarget {
+    /// Region the storage serves; `None` for the primary database
+    pub region: Option<DataRegion>,
+    
+    /// Connection URL with the password substituted in
+    pub url: String,
+    
+    /// Schema to store in; the default schema when unset
+    pub schema: Option<String>,
+}
+
+impl std::fmt::Debug for StorageTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageTarget")
+            .field("region", &self.region)
+            .field("url", &"[redacted]")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+/// Where to store the PII of a client tagged with `region`
+pub fn storage_target(
+    region: Option<DataRegion>,
+    database: &DatabaseConfig,
+    residency: &ResidencyConfig,
+) -> Result<StorageTarget> {
+    let primary = StorageTarget {
+        region: None,
+        url: database.connection_url(),
+        schema: None,
+    };
+    let Some(region) = region else {
+        return Ok(primary);
+    };
+    match database.regions.get(&region) {
+        Some(regional) => Ok(StorageTarget {
+            region: Some(region),
+            url: regional.connection_url(database),
+            schema: Some(regional.schema.clone()),
+        }),
+        None if is_strict(region, residency) => Err(unavailable(region, "database")),
+        None => Ok(primary),
+    }
+}
+
+/// Check that clients can be tagged with a region
+///
+/// A strict region needs its own storage before any client's PII can be
+/// accepted for it.
+pub fn ensure_supported(region: DataRegion, database: &DatabaseConfig, residency: &ResidencyConfig) -> Result<()> {
+    storage_target(Some(region), database, residency).map(|_| ())
+}
+
+fn unavailable(region: DataRegion, resource: &str) -> ComplianceError {
+    ComplianceError::ResidencyUnavailable {
+        region: region.name().to_string(),
+        resource: resource.to_string(),
+    }
+}
@@ -0,0 +1,325 @@
+//! Risk factors defined in configuration rather than code
+//!
+//! Each factor in `compliance.aml.custom_factors` reads one field from a data
+//! source about the account: attributes the client submitted for it, its
+//! geographic profile, or the attestation being scored. A transform maps the
+//! value to a risk in `[0, 1]`, and the account's custom risk is the weighted
+//! mean of the factors whose field is present, so missing data does not
+//! dilute known risk. Like geographic and device risk, custom risk raises the
+//! AML risk level of the next attestation and never lowers it.
+//!
+//! Factors are read from the live configuration, so a reload adds, changes or
+//! removes them without a release. Definitions are checked when the
+//! configuration is loaded (see [`RiskFactorDefinition::validate`]).
+
+use super::country_risk::{risk_level, CountryRiskService};
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation};
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Maximum number of attributes recorded per account
+pub const MAX_ATTRIBUTES: usize = 64;
+
+/// Maximum length of an attribute name
+const MAX_ATTRIBUTE_NAME_LEN: usize = 64;
+
+/// Where a factor reads its value from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFactorSource {
+    /// Attributes the client recorded for the account
+    Attributes,
+    /// The account's geographic profile
+    Geography,
+    /// The attestation being scored; absent outside a compliance check
+    Attestation,
+}
+
+/// How a factor's value maps to a risk in `[0, 1]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RiskFactorTransform {
+    /// Scale a number from `min` (no risk) to `max` (full risk), clamped
+    Linear { min: f64, max: f64 },
+    /// Full risk for a number at or above `at_least`, none below it
+    Threshold { at_least: f64 },
+    /// Full risk for `true`, none for `false`
+    Boolean,
+    /// Risk of each listed value, and `default` for any other
+    Lookup {
+        values: HashMap<String, f64>,
+        #[serde(default)]
+        default: f64,
+    },
+}
+
+impl RiskFactorTransform {
+    /// Risk of `value`, or `None` when the value has the wrong type
+    pub fn apply(&self, value: &Value) -> Option<f64> {
+        let risk = match (self, value) {
+            (_, Value::Null) => return None,
+            (Self::Linear { min, max }, value) => ((value.as_f64()? - min) / (max - min)).clamp(0.0, 1.0),
+            (Self::Threshold { at_least }, value) => {
+                if value.as_f64()? >= *at_least {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            (Self::Boolean, value) => {
+                if value.as_bool()? {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            (Self::Lookup { values, default }, Value::String(value)) => *values.get(value).unwrap_or(default),
+            (Self::Lookup { values, default }, Value::Bool(_) | Value::Number(_)) => {
+                *values.get(&value.to_string()).unwrap_or(default)
+            }
+            (Self::Lookup { .. }, _) => return None,
+        };
+        Some(risk)
+    }
+}
+
+/// A risk factor evaluated from account data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskFactorDefinition {
+    /// Name reported in the factor breakdown
+    pub name: String,
+    
+    pub source: RiskFactorSource,
+    
+    /// Dot-separated path to the value within the source (e.g.
+    /// `employment.status`); array elements are addressed by index
+    pub field: String,
+    
+    /// Weight of the factor in the combined score
+    pub weight: f64,
+    
+    pub transform: RiskFactorTransform,
+}
+
+impl RiskFactorDefinition {
+    /// Check the definition, describing the first problem found
+    ///
+    /// Names are lowercase identifiers, fields are non-empty dotted paths,
+    /// and every weight and risk lies in `[0, 1]`.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if !is_identifier(&self.name) {
+            return Err("name must be lowercase letters, digits and underscores".to_string());
+        }
+        if self.field.split('.').any(|segment| !is_path_segment(segment)) {
+            return Err(format!("field {:?} must be a dot-separated path of names", self.field));
+        }
+        if !(0.0..=1.0).contains(&self.weight) {
+            return Err("weight must be between 0 and 1".to_string());
+        }
+        match &self.transform {
+            RiskFactorTransform::Linear { min, max } => {
+                if !min.is_finite() || !max.is_finite() || min >= max {
+                    return Err("linear transform must have min below max".to_string());
+                }
+            }
+            RiskFactorTransform::Threshold { at_least } => {
+                if !at_least.is_finite() {
+                    return Err("threshold transform must have a finite at_least".to_string());
+                }
+            }
+            RiskFactorTransform::Boolean => {}
+            RiskFactorTransform::Lookup { values, default } => {
+                if values.is_empty() {
+                    return Err("lookup transform must list at least one value".to_string());
+                }
+                if values.values().chain([default]).any(|risk| !(0.0..=1.0).contains(risk)) {
+                    return Err("lookup transform risks must be between 0 and 1".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+}
+
+fn is_path_segment(segment: &str) -> bool {
+    !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Look up a dot-separated path in a JSON value
+fn lookup<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    field.split('.').try_fold(value, |value, segment| match value {
+        Value::Object(object) => object.get(segment),
+        Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Risk contributed by one configured factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomFactor {
+    pub name: String,
+    pub source: RiskFactorSource,
+    pub field: String,
+    pub weight: f64,
+    
+    /// Value the factor was evaluated on; `None` when the field is absent
+    pub value: Option<Value>,
+    
+    /// Risk in `[0, 1]`; `None` when the field is absent or has the wrong
+    /// type, in which case the factor does not count towards the score
+    pub risk: Option<f64>,
+}
+
+/// Custom risk of an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomRiskAssessment {
+    /// Weighted mean risk of the evaluated factors, in `[0, 1]`
+    pub score: f64,
+    pub risk_level: AmlRiskLevel,
+    
+    /// Every configured factor, in configuration order
+    pub factors: Vec<CustomFactor>,
+}
+
+/// Service recording account attributes and scoring the configured factors
+pub struct RiskFactorService {
+    /// Live configuration holding the factor definitions
+    config: Arc<LiveConfig>,
+    
+    /// Geographic profiles, for factors reading them
+    country_risk: Arc<CountryRiskService>,
+    
+    /// Attributes recorded for each account
+    attributes: RwLock<HashMap<AccountId, Map<String, Value>>>,
+}
+
+impl RiskFactorService {
+    /// Create the service without attributes
+    pub fn new(config: Arc<LiveConfig>, country_risk: Arc<CountryRiskService>) -> Self {
+        Self {
+            config,
+            country_risk,
+            attributes: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Whether any custom factor is configured
+    pub fn enabled(&self) -> bool {
+        !self.config.compliance().aml.custom_factors.is_empty()
+    }
+    
+    /// Replace the attributes recorded for an account
+    pub async fn set_attributes(&self, account_id: &AccountId, attributes: Map<String, Value>) -> Result<()> {
+        validate_attributes(&attributes)?;
+        self.attributes.write().await.insert(account_id.clone(), attributes);
+        Ok(())
+    }
+    
+    /// Attributes recorded for an account
+    pub async fn attributes(&self, account_id: &AccountId) -> Map<String, Value> {
+        self.attributes.read().await.get(account_id).cloned().unwrap_or_default()
+    }
+    
+    /// Score the configured factors for an account, or `None` when none is
+    /// configured
+    ///
+    /// Factors reading the attestation count only when one is given.
+    pub async fn assess(
+        &self,
+        account_id: &AccountId,
+        attestation: Option<&ComplianceAttestation>,
+    ) -> Option<CustomRiskAssessment> {
+        let compliance = self.config.compliance();
+        let definitions = &compliance.aml.custom_factors;
+        if definitions.is_empty() {
+            return None;
+        }
+        
+        let attributes = Value::Object(self.attributes(account_id).await);
+        let geography = match self.country_risk.profile(account_id).await {
+            Some(profile) => serde_json::to_value(profile).ok(),
+            None => None,
+        };
+        let attestation = attestation.and_then(|attestation| serde_json::to_value(attestation).ok());
+        
+        let factors: Vec<CustomFactor> = definitions
+            .iter()
+            .map(|definition| {
+                let source = match definition.source {
+                    RiskFactorSource::Attributes => Some(&attributes),
+                    RiskFactorSource::Geography => geography.as_ref(),
+                    RiskFactorSource::Attestation => attestation.as_ref(),
+                };
+                let value = source.and_then(|source| lookup(source, &definition.field)).cloned();
+                CustomFactor {
+                    name: definition.name.clone(),
+                    source: definition.source,
+                    field: definition.field.clone(),
+                    weight: definition.weight,
+                    risk: value.as_ref().and_then(|value| definition.transform.apply(value)),
+                    value,
+                }
+            })
+            .collect();
+        
+        let weighted: Vec<(f64, f64)> = factors.iter().filter_map(|f| Some((f.weight, f.risk?))).collect();
+        let total_weight: f64 = weighted.iter().map(|(weight, _)| weight).sum();
+        let score = if total_weight > 0.0 {
+            weighted.iter().map(|(weight, risk)| weight * risk).sum::<f64>() / total_weight
+        } else {
+            0.0
+        };
+        
+        Some(CustomRiskAssessment {
+            score,
+            risk_level: risk_level(score, &compliance.aml.risk_thresholds),
+            factors,
+        })
+    }
+    
+    /// Raise an attestation's AML risk level to the account's custom risk
+    ///
+    /// Custom risk never lowers the level reported by the AML provider.
+    pub async fn apply(&self, attestation: &mut ComplianceAttestation) -> Option<CustomRiskAssessment> {
+        let assessment = self.assess(&attestation.account_id, Some(attestation)).await?;
+        if assessment.risk_level > attestation.aml_risk_level {
+            tracing::debug!(
+                account_id = %attestation.account_id,
+                score = assessment.score,
+                level = ?assessment.risk_level,
+                "custom risk factors raised AML risk level"
+            );
+            attestation.aml_risk_level = assessment.risk_level.clone();
+        }
+        Some(assessment)
+    }
+}
+
+/// Check the number and names of an account's attributes
+pub fn validate_attributes(attributes: &Map<String, Value>) -> Result<()> {
+    if attributes.len() > MAX_ATTRIBUTES {
+        return Err(ComplianceError::validation(
+            "attributes",
+            format!("at most {} attributes may be recorded", MAX_ATTRIBUTES),
+        ));
+    }
+    if let Some(name) = attributes
+        .keys()
+        .find(|name| name.len() > MAX_ATTRIBUTE_NAME_LEN || !is_path_segment(name))
+    {
+        return Err(ComplianceError::validation(
+            "attributes",
+            format!("{:?} must be 1 to {} letters, digits, underscores or dashes", name, MAX_ATTRIBUTE_NAME_LEN),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Versioned payload schemas for webhooks and API responses
+//!
+//! Every webhook delivery and API response sent to a business client is
+//! tagged with the schema version it follows. Clients are pinned to the
+//! current version when registered, and can pin their webhooks or individual
+//! endpoints to other versions. Payloads are always built in the current
+//! shape and downgraded on the way out by the adapters of every change newer
+//! than the version served, newest first, so fields added by a new version
+//! never reach consumers pinned to an older one.
+//!
+//! To change a payload's shape, add a [`SchemaChange`] with the next version
+//! whose adapters undo what it introduces.
+
+use crate::types::SchemaPins;
+use crate::{ComplianceError, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Header carrying the schema version of a payload, and requesting one for an API response
+pub const SCHEMA_VERSION_HEADER: &str = "x-zerotrust-schema-version";
+
+/// A change to payload shapes introduced by a schema version
+pub struct SchemaChange {
+    pub version: u32,
+    pub description: &'static str,
+    
+    /// Reshape a webhook body of an event type to the previous version,
+    /// returning whether anything changed
+    pub downgrade_webhook: fn(&str, &mut Value) -> bool,
+    
+    /// Reshape a response body of an endpoint (`METHOD /route`) to the
+    /// previous version, returning whether anything changed
+    pub downgrade_response: fn(&str, &mut Value) -> bool,
+}
+
+/// Every change since the first version, oldest first
+pub static CHANGES: &[SchemaChange] = &[SchemaChange {
+    version: 2,
+    description: "Localized display text in webhook deliveries and in check and step-up responses",
+    downgrade_webhook: |_, body| remove_at(body, &["display"]),
+    downgrade_response: remove_display,
+}];
+
+/// A schema version as listed to clients
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaVersionInfo {
+    pub version: u32,
+    pub description: &'static str,
+}
+
+/// Version payloads are built in
+pub fn current_version() -> u32 {
+    CHANGES.last().map_or(SchemaPins::FIRST_VERSION, |change| change.version)
+}
+
+/// Every schema version, oldest first
+pub fn versions() -> Vec<SchemaVersionInfo> {
+    std::iter::once(SchemaVersionInfo {
+        version: SchemaPins::FIRST_VERSION,
+        description: "Initial payload schema",
+    })
+    .chain(CHANGES.iter().map(|change| SchemaVersionInfo {
+        version: change.version,
+        description: change.description,
+    }))
+    .collect()
+}
+
+/// Check that a version exists
+pub fn check_version(field: &str, version: u32) -> Result<u32> {
+    if (SchemaPins::FIRST_VERSION..=current_version()).contains(&version) {
+        Ok(version)
+    } else {
+        Err(ComplianceError::validation(
+            field,
+            format!(
+                "unknown schema version {}, versions {} to {} are available",
+                version,
+                SchemaPins::FIRST_VERSION,
+                current_version()
+            ),
+        ))
+    }
+}
+
+/// Check every version in a client's pins, and that endpoints are keyed as `METHOD /route`
+pub fn check_pins(pins: &SchemaPins) -> Result<()> {
+    check_version("default", pins.default)?;
+    if let Some(version) = pins.webhooks {
+        check_version("webhooks", version)?;
+    }
+    for (endpoint, version) in &pins.endpoints {
+        let well_formed = endpoint.split_once(' ').is_some_and(|(method, route)| {
+            ["GET", "POST", "PUT", "PATCH", "DELETE"].contains(&method) && route.starts_with("/v1/")
+        });
+        if !well_formed {
+            return Err(ComplianceError::validation(
+                "endpoints",
+                format!("{} is not a method and route, e.g. POST /v1/accounts/{{id}}/check", endpoint),
+            ));
+        }
+        check_version("endpoints", *version)?;
+    }
+    Ok(())
+}
+
+/// Version of an API response
+///
+/// A version requested with the request wins over the client's pins;
+/// requests from anyone but a client get the current version.
+pub fn negotiate(requested: Option<u32>, pins: Option<&SchemaPins>, endpoint: &str) -> u32 {
+    requested
+        .or_else(|| pins.map(|pins| pins.for_endpoint(endpoint)))
+        .unwrap_or_else(current_version)
+}
+
+/// Downgrade a webhook body to `version`
+pub fn downgrade_webhook(event_type: &str, body: &mut Value, version: u32) -> bool {
+    CHANGES
+        .iter()
+        .rev()
+        .take_while(|change| change.version > version)
+        .fold(false, |changed, change| (change.downgrade_webhook)(event_type, body) | changed)
+}
+
+/// Downgrade a response body of an endpoint to `version`
+pub fn downgrade_response(endpoint: &str, body: &mut Value, version: u32) -> bool {
+    CHANGES
+        .iter()
+        .rev()
+        .take_while(|change| change.version > version)
+        .fold(false, |changed, change| (change.downgrade_response)(endpoint, body) | changed)
+}
+
+/// Display text added to responses by version 2
+fn remove_display(endpoint: &str, body: &mut Value) -> bool {
+    match endpoint {
+        "POST /v1/accounts/{id}/check" => remove_at(body, &["policy", "display"]),
+        "POST /v1/accounts/{id}/upgrade-compliance-level" => remove_at(body, &["session", "display"]),
+        "POST /v1/accounts/{id}/step-up"
+        | "GET /v1/step-up/{session_id}"
+        | "POST /v1/step-up/{session_id}/evidence"
+        | "POST /v1/step-up/{session_id}/cancel" => remove_at(body, &["display"]),
+        _ => false,
+    }
+}
+
+/// Remove the member at `path` from nested objects
+fn remove_at(value: &mut Value, path: &[&str]) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut value = value;
+    for key in parents {
+        match value.get_mut(*key) {
+            Some(child) => value = child,
+            None => return false,
+        }
+    }
+    value.as_object_mut().is_some_and(|object| object.remove(*last).is_some())
+}
@@ -0,0 +1,131 @@
+//! Scope claims restricting where a compliance proof may be used
+//!
+//! A scoped proof attests compliance for one application, asset class, or
+//! transaction-size band rather than blanket compliance. The scope is signed
+//! into the proof envelope and checked against the verifier's intended use.
+
+use crate::types::ComplianceLevel;
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Maximum length of an application identifier
+const MAX_APPLICATION_LEN: usize = 128;
+
+/// Class of asset a proof may be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetClass {
+    Cryptocurrency,
+    Stablecoin,
+    TokenizedSecurity,
+    Nft,
+    Derivative,
+}
+
+/// Inclusive range of transaction amounts, in the asset's base unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmountBand {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl AmountBand {
+    /// Check if an amount falls inside the band
+    pub fn contains(&self, amount: u64) -> bool {
+        (self.min..=self.max).contains(&amount)
+    }
+}
+
+/// Restrictions embedded in a proof
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProofScope {
+    /// dApp or service the proof is valid for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_class: Option<AssetClass>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_band: Option<AmountBand>,
+    
+    /// Compliance level the account was proven to meet for this scope
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_level: Option<ComplianceLevel>,
+}
+
+/// How a verifier intends to rely on a proof
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScopeUsage {
+    pub application: Option<String>,
+    pub asset_class: Option<AssetClass>,
+    /// Transaction amount, required when the proof is scoped to an amount band
+    pub amount: Option<u64>,
+}
+
+impl ProofScope {
+    /// Validate a requested scope
+    pub fn validate(&self) -> Result<()> {
+        if let Some(application) = &self.application {
+            if application.trim().is_empty() || application.len() > MAX_APPLICATION_LEN {
+                return Err(ComplianceError::validation(
+                    "scope.application",
+                    format!("must be 1-{} characters", MAX_APPLICATION_LEN),
+                ));
+            }
+        }
+        if self.amount_band.is_some_and(|band| band.min > band.max) {
+            return Err(ComplianceError::validation("scope.amount_band", "min must not exceed max"));
+        }
+        Ok(())
+    }
+    
+    /// Compliance level an account must meet to be issued a proof for this scope
+    ///
+    /// The highest of the level requested explicitly, the level configured for
+    /// the asset class, and the level required for the top of the amount band.
+    pub fn required_level(
+        &self,
+        asset_class_levels: &HashMap<AssetClass, ComplianceLevel>,
+        level_for_amount: impl Fn(u64) -> ComplianceLevel,
+    ) -> ComplianceLevel {
+        [
+            self.min_level.clone(),
+            self.asset_class.and_then(|class| asset_class_levels.get(&class).cloned()),
+            self.amount_band.map(|band| level_for_amount(band.max)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(ComplianceLevel::Basic)
+    }
+    
+    /// Check that a verifier's intended use falls within the scope
+    pub fn permits(&self, usage: &ScopeUsage) -> std::result::Result<(), String> {
+        if let Some(application) = &self.application {
+            if usage.application.as_ref() != Some(application) {
+                return Err(format!("proof is scoped to application {}", application));
+            }
+        }
+        if let Some(class) = self.asset_class {
+            if usage.asset_class != Some(class) {
+                return Err(format!("proof is scoped to asset class {:?}", class));
+            }
+        }
+        if let Some(band) = self.amount_band {
+            match usage.amount {
+                Some(amount) if band.contains(amount) => {}
+                Some(amount) => {
+                    return Err(format!(
+                        "amount {} is outside the proof's band {}..={}",
+                        amount, band.min, band.max
+                    ))
+                }
+                None => return Err("proof is scoped to an amount band; an amount is required".to_string()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+//! Analyst adjudication of screening matches
+//!
+//! Analysts mark screening matches as true hits or false positives. A false
+//! positive suppresses the entity for that account on later screens, for as
+//! long as the list entry stays as it was when adjudicated, so list updates
+//! stop resurfacing matches that were already cleared. A changed entry is
+//! presented again.
+//!
+//! Adjudications are also aggregated per business client into threshold
+//! suggestions. A suggestion adopted by a compliance officer replaces the
+//! configured fuzzy match threshold for that client's accounts from their
+//! next screen on.
+
+use super::ScreenedEntity;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Adjudications a client needs before a threshold is suggested
+pub const MIN_ADJUDICATIONS_FOR_SUGGESTION: usize = 20;
+
+/// Distance kept between a suggested threshold and the lowest-scoring true hit
+const TRUE_HIT_MARGIN: f64 = 0.02;
+
+/// Highest threshold ever suggested
+const MAX_SUGGESTED_THRESHOLD: f64 = 0.99;
+
+/// Analyst verdict on a screening match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    TrueHit,
+    FalsePositive,
+}
+
+/// An analyst's verdict on one screening match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Adjudication {
+    pub id: Uuid,
+    pub account_id: AccountId,
+    
+    /// Business client the account was screened for
+    pub client_id: Option<Uuid>,
+    
+    pub list: String,
+    
+    /// List entry as it was when adjudicated
+    pub entity: ScreenedEntity,
+    
+    pub matched_name: String,
+    
+    /// Score of the match when adjudicated
+    pub score: f64,
+    
+    pub verdict: Verdict,
+    
+    /// Operator who adjudicated the match
+    pub analyst: String,
+    
+    pub reason: Option<String>,
+    
+    pub adjudicated_at: DateTime<Utc>,
+}
+
+/// Fields accepted when adjudicating a match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjudicationInput {
+    pub list: String,
+    pub entity_id: String,
+    pub verdict: Verdict,
+    pub reason: Option<String>,
+}
+
+/// Threshold change suggested by a client's adjudications
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdSuggestion {
+    pub client_id: Uuid,
+    
+    /// Threshold currently applied to the client's accounts
+    pub current_threshold: f64,
+    
+    pub suggested_threshold: f64,
+    
+    pub true_hits: usize,
+    
+    pub false_positives: usize,
+    
+    /// Adjudicated false positives the suggested threshold would not have reported
+    pub false_positives_avoided: usize,
+}
+
+/// Adjudications of screening matches and the thresholds adopted from them
+#[derive(Default)]
+pub struct AdjudicationStore {
+    /// Adjudications of every account, oldest first
+    adjudications: RwLock<HashMap<AccountId, Vec<Adjudication>>>,
+    
+    /// Fuzzy match thresholds adopted per business client
+    thresholds: RwLock<HashMap<Uuid, f64>>,
+}
+
+impl AdjudicationStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record an adjudication; the latest one for an entry supersedes earlier ones
+    pub async fn record(&self, adjudication: Adjudication) {
+        self.adjudications
+            .write()
+            .await
+            .entry(adjudication.account_id.clone())
+            .or_default()
+            .push(adjudication);
+    }
+    
+    /// Adjudications of an account, oldest first
+    pub async fn for_account(&self, account_id: &AccountId) -> Vec<Adjudication> {
+        self.adjudications.read().await.get(account_id).cloned().unwrap_or_default()
+    }
+    
+    /// Check if an entity is suppressed for an account
+    ///
+    /// It is when the latest adjudication of the entry is a false positive
+    /// and the entry has not changed since.
+    pub async fn is_suppressed(&self, account_id: &AccountId, list: &str, entity: &ScreenedEntity) -> bool {
+        self.adjudications
+            .read()
+            .await
+            .get(account_id)
+            .and_then(|adjudications| {
+                adjudications
+                    .iter()
+                    .rev()
+                    .find(|a| a.list == list && a.entity.id == entity.id)
+            })
+            .is_some_and(|latest| latest.verdict == Verdict::FalsePositive && latest.entity == *entity)
+    }
+    
+    /// Threshold adopted for a client, if any
+    pub async fn threshold(&self, client_id: Uuid) -> Option<f64> {
+        self.thresholds.read().await.get(&client_id).copied()
+    }
+    
+    /// Adopt a threshold for a client's accounts, or revert to the configured one with `None`
+    pub async fn set_threshold(&self, client_id: Uuid, threshold: Option<f64>) -> Result<()> {
+        let mut thresholds = self.thresholds.write().await;
+        match threshold {
+            Some(threshold) if !(0.0..=1.0).contains(&threshold) => {
+                return Err(ComplianceError::validation("threshold", "must be between 0 and 1"));
+            }
+            Some(threshold) => {
+                thresholds.insert(client_id, threshold);
+            }
+            None => {
+                thresholds.remove(&client_id);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Threshold suggestions for every client with enough adjudications
+    ///
+    /// A suggestion raises the client's threshold just above the false
+    /// positives scoring below every true hit, stopping short of the
+    /// lowest-scoring true hit. Clients whose adjudications do not support a
+    /// higher threshold get no suggestion.
+    pub async fn suggestions(&self, configured_threshold: f64) -> Vec<ThresholdSuggestion> {
+        let mut scores: HashMap<Uuid, (Vec<f64>, Vec<f64>)> = HashMap::new();
+        for adjudications in self.adjudications.read().await.values() {
+            for latest in latest_per_entry(adjudications) {
+                let Some(client_id) = latest.client_id else {
+                    continue;
+                };
+                let (true_hits, false_positives) = scores.entry(client_id).or_default();
+                match latest.verdict {
+                    Verdict::TrueHit => true_hits.push(latest.score),
+                    Verdict::FalsePositive => false_positives.push(latest.score),
+                }
+            }
+        }
+        
+        let thresholds = self.thresholds.read().await;
+        let mut suggestions: Vec<_> = scores
+            .into_iter()
+            .filter(|(_, (true_hits, false_positives))| {
+                true_hits.len() + false_positives.len() >= MIN_ADJUDICATIONS_FOR_SUGGESTION
+            })
+            .filter_map(|(client_id, (true_hits, false_positives))| {
+                let current = thresholds.get(&client_id).copied().unwrap_or(configured_threshold);
+                suggest(client_id, current, &true_hits, &false_positives)
+            })
+            .collect();
+        suggestions.sort_by_key(|suggestion| suggestion.client_id);
+        suggestions
+    }
+}
+
+/// Latest adjudication of each entry, from one account's history
+fn latest_per_entry(adjudications: &[Adjudication]) -> impl Iterator<Item = &Adjudication> {
+    let mut latest: HashMap<(&str, &str), &Adjudication> = HashMap::new();
+    for adjudication in adjudications {
+        latest.insert((adjudication.list.as_str(), adjudication.entity.id.as_str()), adjudication);
+    }
+    latest.into_values()
+}
+
+fn suggest(client_id: Uuid, current: f64, true_hits: &[f64], false_positives: &[f64]) -> Option<ThresholdSuggestion> {
+    let ceiling = true_hits
+        .iter()
+        .copied()
+        .fold(MAX_SUGGESTED_THRESHOLD + TRUE_HIT_MARGIN, f64::min)
+        - TRUE_HIT_MARGIN;
+    // Just above the highest false positive that could be cleared, in hundredths
+    let suggested = false_positives
+        .iter()
+        .copied()
+        .filter(|score| *score < ceiling)
+        .fold(current, f64::max);
+    let suggested = ((suggested * 100.0 + 1e-9).floor() + 1.0) / 100.0;
+    let suggested = suggested.min(ceiling);
+    
+    let avoided = false_positives.iter().filter(|score| **score >= current && **score < suggested).count();
+    (suggested > current && avoided > 0).then_some(ThresholdSuggestion {
+        client_id,
+        current_threshold: current,
+        suggested_threshold: suggested,
+        true_hits: true_hits.len(),
+        false_positives: false_positives.len(),
+        false_positives_avoided: avoided,
+    })
+}
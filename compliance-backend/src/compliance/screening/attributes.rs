@@ -0,0 +1,251 @@
+//! Secondary attributes weighed alongside names in screening
+//!
+//! A name match alone flags everyone sharing a common name. When the screened
+//! subject's date of birth, nationalities, document numbers or addresses are
+//! known, each one published for the list entry is compared and moves the
+//! name score up or down by its configured weight. Attributes missing on
+//! either side are not compared and contribute nothing.
+
+use super::matcher::jaro_winkler;
+use super::script;
+use super::ScreenedEntity;
+use crate::config::AttributeWeights;
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Similarity at which two addresses are taken to be the same
+const ADDRESS_MATCH_THRESHOLD: f64 = 0.9;
+
+/// Secondary attributes of a screened subject
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubjectAttributes {
+    /// Date of birth as `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    pub date_of_birth: Option<String>,
+    
+    /// Nationalities as ISO 3166-1 alpha-2 codes
+    pub nationalities: Vec<String>,
+    
+    /// Passport, national ID and other identity document numbers
+    pub document_numbers: Vec<String>,
+    
+    /// Known addresses
+    pub addresses: Vec<String>,
+}
+
+impl SubjectAttributes {
+    /// Check if no attribute is known
+    pub fn is_empty(&self) -> bool {
+        self.date_of_birth.is_none()
+            && self.nationalities.is_empty()
+            && self.document_numbers.is_empty()
+            && self.addresses.is_empty()
+    }
+    
+    /// Reject malformed attributes
+    pub fn validate(&self) -> Result<()> {
+        if self.date_of_birth.as_deref().is_some_and(|dob| PartialDate::parse(dob).is_none()) {
+            return Err(ComplianceError::validation(
+                "attributes.date_of_birth",
+                "must be YYYY, YYYY-MM or YYYY-MM-DD",
+            ));
+        }
+        if self.nationalities.iter().any(|code| code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic())) {
+            return Err(ComplianceError::validation(
+                "attributes.nationalities",
+                "must be ISO 3166-1 alpha-2 codes",
+            ));
+        }
+        if self.document_numbers.iter().any(|number| document_number(number).is_empty()) {
+            return Err(ComplianceError::validation("attributes.document_numbers", "must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+/// A secondary attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Attribute {
+    DateOfBirth,
+    Nationality,
+    DocumentNumber,
+    Address,
+}
+
+/// How a subject's attribute compared with the list entry's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeOutcome {
+    /// The values agree
+    Match,
+    /// The values agree to the precision the less precise one is published in
+    Partial,
+    /// The values differ, within the configured tolerance
+    WithinTolerance,
+    /// The values differ
+    Mismatch,
+}
+
+/// Effect of one attribute on a composite match score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeContribution {
+    pub attribute: Attribute,
+    pub outcome: AttributeOutcome,
+    /// Amount added to the name score; negative for evidence against a match
+    pub contribution: f64,
+}
+
+/// Compare every attribute known for both the subject and the entity
+pub fn compare(subject: &SubjectAttributes, entity: &ScreenedEntity, weights: &AttributeWeights) -> Vec<AttributeContribution> {
+    let mut contributions = Vec::new();
+    
+    let dates = subject
+        .date_of_birth
+        .as_deref()
+        .and_then(PartialDate::parse)
+        .zip(entity.date_of_birth.as_deref().and_then(PartialDate::parse));
+    if let Some((ours, theirs)) = dates {
+        let outcome = ours.compare(&theirs, weights.dob_tolerance_years);
+        let contribution = match outcome {
+            AttributeOutcome::Match => weights.dob_match_bonus,
+            AttributeOutcome::Partial => weights.dob_partial_bonus,
+            AttributeOutcome::WithinTolerance => 0.0,
+            AttributeOutcome::Mismatch => -weights.dob_mismatch_penalty,
+        };
+        contributions.push(AttributeContribution {
+            attribute: Attribute::DateOfBirth,
+            outcome,
+            contribution,
+        });
+    }
+    
+    if !subject.nationalities.is_empty() && !entity.nationalities.is_empty() {
+        let shared = subject
+            .nationalities
+            .iter()
+            .any(|ours| entity.nationalities.iter().any(|theirs| ours.eq_ignore_ascii_case(theirs)));
+        contributions.push(if shared {
+            AttributeContribution {
+                attribute: Attribute::Nationality,
+                outcome: AttributeOutcome::Match,
+                contribution: weights.nationality_match_bonus,
+            }
+        } else {
+            AttributeContribution {
+                attribute: Attribute::Nationality,
+                outcome: AttributeOutcome::Mismatch,
+                contribution: -weights.nationality_mismatch_penalty,
+            }
+        });
+    }
+    
+    if !subject.document_numbers.is_empty() && !entity.document_numbers.is_empty() {
+        let shared = subject
+            .document_numbers
+            .iter()
+            .map(String::as_str)
+            .map(document_number)
+            .any(|ours| entity.document_numbers.iter().any(|theirs| document_number(theirs) == ours));
+        contributions.push(if shared {
+            AttributeContribution {
+                attribute: Attribute::DocumentNumber,
+                outcome: AttributeOutcome::Match,
+                contribution: weights.document_match_bonus,
+            }
+        } else {
+            AttributeContribution {
+                attribute: Attribute::DocumentNumber,
+                outcome: AttributeOutcome::Mismatch,
+                contribution: -weights.document_mismatch_penalty,
+            }
+        });
+    }
+    
+    if !subject.addresses.is_empty() && !entity.addresses.is_empty() {
+        let best = subject
+            .addresses
+            .iter()
+            .flat_map(|ours| entity.addresses.iter().map(move |theirs| jaro_winkler(&address(ours), &address(theirs))))
+            .fold(0.0, f64::max);
+        // People move, so a different address is no evidence against a match
+        contributions.push(if best >= ADDRESS_MATCH_THRESHOLD {
+            AttributeContribution {
+                attribute: Attribute::Address,
+                outcome: AttributeOutcome::Match,
+                contribution: weights.address_match_bonus,
+            }
+        } else {
+            AttributeContribution {
+                attribute: Attribute::Address,
+                outcome: AttributeOutcome::Mismatch,
+                contribution: 0.0,
+            }
+        });
+    }
+    
+    contributions
+}
+
+/// A date of birth published to year, month or day precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PartialDate {
+    year: i32,
+    month: Option<u32>,
+    day: Option<u32>,
+}
+
+impl PartialDate {
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let year = parts.next().filter(|y| y.len() == 4)?.parse().ok()?;
+        let month = parts.next().map(str::parse::<u32>).transpose().ok()?.filter(|m| (1..=12).contains(m));
+        let day = parts.next().map(str::parse::<u32>).transpose().ok()?.filter(|d| (1..=31).contains(d));
+        // A day without a month, or trailing parts, is malformed
+        if parts.next().is_some() || (day.is_some() && month.is_none()) {
+            return None;
+        }
+        if value.matches('-').count() != usize::from(month.is_some()) + usize::from(day.is_some()) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+    
+    fn compare(&self, other: &Self, tolerance_years: u32) -> AttributeOutcome {
+        let same_month = match (self.month, other.month) {
+            (Some(ours), Some(theirs)) => Some(ours == theirs),
+            _ => None,
+        };
+        let same_day = match (self.day, other.day) {
+            (Some(ours), Some(theirs)) => Some(ours == theirs),
+            _ => None,
+        };
+        
+        if self.year == other.year && same_month != Some(false) && same_day != Some(false) {
+            if same_day == Some(true) {
+                AttributeOutcome::Match
+            } else {
+                AttributeOutcome::Partial
+            }
+        } else if self.year.abs_diff(other.year) <= tolerance_years {
+            AttributeOutcome::WithinTolerance
+        } else {
+            AttributeOutcome::Mismatch
+        }
+    }
+}
+
+/// Document number with separators removed, uppercased
+pub(crate) fn document_number(number: &str) -> String {
+    number.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/// Address romanized, lowercased, with punctuation collapsed
+fn address(address: &str) -> String {
+    script::romanize(address, None)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,138 @@
+//! Differences between consecutive versions of a screening list
+
+use super::matcher::metaphone;
+use super::normalize::NameForm;
+use super::ScreenedEntity;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// An entity whose published record changed between two list versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedEntity {
+    pub previous: ScreenedEntity,
+    pub current: ScreenedEntity,
+}
+
+/// Entries added, removed, and changed between two versions of a list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListDelta {
+    pub list: String,
+    
+    /// Version being replaced (`None` on first ingestion)
+    pub from_version: Option<String>,
+    
+    pub to_version: String,
+    
+    pub added: Vec<ScreenedEntity>,
+    
+    pub removed: Vec<ScreenedEntity>,
+    
+    pub changed: Vec<ChangedEntity>,
+}
+
+/// Counts of a delta's entries, for responses and logs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaSummary {
+    pub list: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+impl ListDelta {
+    /// Compute the delta between two versions of a list, keyed by entity id
+    pub fn compute(
+        list: &str,
+        from_version: Option<&str>,
+        previous: &[ScreenedEntity],
+        to_version: &str,
+        current: &[ScreenedEntity],
+    ) -> Self {
+        let previous_by_id: HashMap<&str, &ScreenedEntity> =
+            previous.iter().map(|entity| (entity.id.as_str(), entity)).collect();
+        let current_ids: BTreeSet<&str> = current.iter().map(|entity| entity.id.as_str()).collect();
+        
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for entity in current {
+            match previous_by_id.get(entity.id.as_str()) {
+                None => added.push(entity.clone()),
+                Some(old) if *old != entity => changed.push(ChangedEntity {
+                    previous: (*old).clone(),
+                    current: entity.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        let removed = previous
+            .iter()
+            .filter(|entity| !current_ids.contains(entity.id.as_str()))
+            .cloned()
+            .collect();
+        
+        Self {
+            list: list.to_string(),
+            from_version: from_version.map(str::to_string),
+            to_version: to_version.to_string(),
+            added,
+            removed,
+            changed,
+        }
+    }
+    
+    /// Check if the new version is identical to the old one
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+    
+    /// Ids of entities a prior match may no longer be valid for
+    pub fn touched_ids(&self) -> BTreeSet<&str> {
+        self.removed
+            .iter()
+            .map(|entity| entity.id.as_str())
+            .chain(self.changed.iter().map(|change| change.current.id.as_str()))
+            .collect()
+    }
+    
+    /// Name keys of every added entity and of both sides of every changed entity
+    pub fn name_keys(&self) -> BTreeSet<String> {
+        self.added
+            .iter()
+            .chain(self.changed.iter().flat_map(|change| [&change.previous, &change.current]))
+            .flat_map(ScreenedEntity::names)
+            .flat_map(|name| name_keys(name, None))
+            .collect()
+    }
+    
+    /// Summarize the delta without its entity records
+    pub fn summary(&self) -> DeltaSummary {
+        DeltaSummary {
+            list: self.list.clone(),
+            from_version: self.from_version.clone(),
+            to_version: self.to_version.clone(),
+            added: self.added.len(),
+            removed: self.removed.len(),
+            changed: self.changed.len(),
+        }
+    }
+}
+
+/// Blocking keys for a name: its normalized tokens and their Metaphone codes
+///
+/// Two names the matcher can score above threshold share at least one token or
+/// phonetic code in practice, so an account whose keys are disjoint from a
+/// delta's keys does not need re-screening against it. A language hint adds
+/// the tokens of the name's hinted romanization.
+pub fn name_keys(name: &str, language: Option<&str>) -> BTreeSet<String> {
+    NameForm::variants(name, language)
+        .into_iter()
+        .flat_map(|form| form.written)
+        .flat_map(|token| {
+            let phonetic = metaphone(&token);
+            let phonetic = (!phonetic.is_empty()).then(|| format!("~{}", phonetic));
+            std::iter::once(token).chain(phonetic)
+        })
+        .collect()
+}
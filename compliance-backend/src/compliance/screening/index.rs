@@ -0,0 +1,279 @@
+//! Candidate index over screening list names
+//!
+//! Scoring every entity of a 50k-entry list on each screening is linear in
+//! list size. The index maps character trigrams and phonetic keys of every
+//! name token to the entities carrying them, so a query only scores entities
+//! that share enough keys to possibly reach the match threshold.
+//!
+//! The index is built at ingestion in a flat little-endian layout that is
+//! queried in place, so a persisted index is memory-mapped on startup
+//! instead of being rebuilt or deserialized:
+//!
+//! ```text
+//! magic "ZTSI" | format u32 | version_len u32 | version bytes
+//! entity_count u32 | key_count u32
+//! key_count x (key_hash u64, postings_offset u32, postings_len u32), sorted by hash
+//! postings: u32 entity indices
+//! ```
+
+use super::matcher::metaphone;
+use super::normalize::NameForm;
+use super::ScreenedEntity;
+use crate::{ComplianceError, Result};
+use memmap2::Mmap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"ZTSI";
+const FORMAT_VERSION: u32 = 2;
+const KEY_ENTRY_SIZE: usize = 16;
+
+/// Upper bound on the share of the query's trigrams an entity must carry
+///
+/// Kept well below what a name scoring 0.8 shares, since a single typo
+/// removes up to three trigrams from a token.
+const MAX_TRIGRAM_SHARE: f64 = 0.5;
+
+/// Index keys of a name: token trigrams and phonetic codes
+#[derive(Debug, Clone, Default)]
+pub struct NameKeys {
+    trigrams: Vec<u64>,
+    phonetic: Vec<u64>,
+}
+
+impl NameKeys {
+    /// Compute the keys of a name
+    pub fn of(name: &str) -> Self {
+        Self::of_in(name, None)
+    }
+    
+    /// Compute the keys of every form of a name in a hinted language
+    pub fn of_in(name: &str, language: Option<&str>) -> Self {
+        let mut keys = Self::default();
+        let tokens = NameForm::variants(name, language).into_iter().flat_map(|form| form.written);
+        for token in tokens {
+            let padded: Vec<char> = format!("^{}$", token).chars().collect();
+            keys.trigrams.extend(padded.windows(3).map(|gram| hash(gram.iter().collect::<String>().as_bytes())));
+            let phonetic = metaphone(&token);
+            if !phonetic.is_empty() {
+                keys.phonetic.push(hash(format!("~{}", phonetic).as_bytes()));
+            }
+        }
+        keys.trigrams.sort_unstable();
+        keys.trigrams.dedup();
+        keys.phonetic.sort_unstable();
+        keys.phonetic.dedup();
+        keys
+    }
+    
+    fn all(&self) -> impl Iterator<Item = u64> + '_ {
+        self.trigrams.iter().chain(&self.phonetic).copied()
+    }
+}
+
+enum Bytes {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+    
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Owned(bytes) => bytes,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+/// Trigram and phonetic candidate index of one list version
+pub struct CandidateIndex {
+    bytes: Bytes,
+    version: std::ops::Range<usize>,
+    entity_count: usize,
+    keys_start: usize,
+    key_count: usize,
+    postings_start: usize,
+}
+
+impl CandidateIndex {
+    /// Build the index of a list version
+    pub fn build(version: &str, entities: &[ScreenedEntity]) -> Self {
+        let mut postings: BTreeMap<u64, Vec<u32>> = BTreeMap::new();
+        for (i, entity) in entities.iter().enumerate() {
+            for name in entity.names() {
+                for key in NameKeys::of(name).all() {
+                    let entries = postings.entry(key).or_default();
+                    if entries.last() != Some(&(i as u32)) {
+                        entries.push(i as u32);
+                    }
+                }
+            }
+        }
+        
+        let posting_count: usize = postings.values().map(Vec::len).sum();
+        let mut out = Vec::with_capacity(20 + version.len() + postings.len() * KEY_ENTRY_SIZE + posting_count * 4);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&(version.len() as u32).to_le_bytes());
+        out.extend_from_slice(version.as_bytes());
+        out.extend_from_slice(&(entities.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(postings.len() as u32).to_le_bytes());
+        
+        let mut offset = 0u32;
+        for (key, entries) in &postings {
+            out.extend_from_slice(&key.to_le_bytes());
+            out.extend_from_slice(&offset.to_le_bytes());
+            out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+            offset += entries.len() as u32;
+        }
+        for entity in postings.values().flatten() {
+            out.extend_from_slice(&entity.to_le_bytes());
+        }
+        
+        Self::parse(Bytes::Owned(out)).expect("a freshly built index is well formed")
+    }
+    
+    /// Memory-map a persisted index
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the list store only replaces index files by rename, never
+        // writes them in place, so the mapped file does not change underneath us.
+        let map = unsafe { Mmap::map(&file)? };
+        Self::parse(Bytes::Mapped(map))
+    }
+    
+    /// Serialized index, as persisted
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    
+    /// Version of the list the index was built from
+    pub fn list_version(&self) -> &str {
+        std::str::from_utf8(&self.bytes[self.version.clone()]).unwrap_or_default()
+    }
+    
+    /// Number of entities in the indexed list
+    pub fn entity_count(&self) -> usize {
+        self.entity_count
+    }
+    
+    /// Indices of the entities worth scoring against a query, in list order
+    ///
+    /// An entity is a candidate if it carries every phonetic key of the query,
+    /// or enough of its trigrams for the `threshold` to be reachable. Lower
+    /// thresholds admit entities sharing fewer trigrams.
+    pub fn candidates(&self, query: &NameKeys, threshold: f64) -> Vec<usize> {
+        let share = (threshold - 0.3).clamp(0.0, MAX_TRIGRAM_SHARE);
+        let required = ((query.trigrams.len() as f64 * share).ceil() as u32).max(1);
+        let mut shared: HashMap<u32, (u32, usize)> = HashMap::new();
+        
+        for key in &query.trigrams {
+            for entity in self.postings(*key) {
+                shared.entry(entity).or_default().0 += 1;
+            }
+        }
+        for key in &query.phonetic {
+            for entity in self.postings(*key) {
+                shared.entry(entity).or_default().1 += 1;
+            }
+        }
+        
+        let mut candidates: Vec<usize> = shared
+            .into_iter()
+            .filter(|(_, (trigrams, phonetic))| {
+                *trigrams >= required || (!query.phonetic.is_empty() && *phonetic == query.phonetic.len())
+            })
+            .map(|(entity, _)| entity as usize)
+            .filter(|entity| *entity < self.entity_count)
+            .collect();
+        candidates.sort_unstable();
+        candidates
+    }
+    
+    fn postings(&self, key: u64) -> impl Iterator<Item = u32> + '_ {
+        let range = self.find(key).map_or(0..0, |(offset, len)| {
+            let start = self.postings_start + offset * 4;
+            start..start + len * 4
+        });
+        self.bytes[range].chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+    
+    fn find(&self, key: u64) -> Option<(usize, usize)> {
+        let (mut lo, mut hi) = (0, self.key_count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let entry = self.keys_start + mid * KEY_ENTRY_SIZE;
+            match read_u64(&self.bytes, entry).cmp(&key) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => {
+                    return Some((
+                        read_u32(&self.bytes, entry + 8) as usize,
+                        read_u32(&self.bytes, entry + 12) as usize,
+                    ));
+                }
+            }
+        }
+        None
+    }
+    
+    fn parse(bytes: Bytes) -> Result<Self> {
+        let malformed = || ComplianceError::internal("screening index file is malformed");
+        if bytes.len() < 12 || &bytes[..4] != MAGIC {
+            return Err(malformed());
+        }
+        if read_u32(&bytes, 4) != FORMAT_VERSION {
+            return Err(ComplianceError::internal("screening index format version is not supported"));
+        }
+        
+        let version_len = read_u32(&bytes, 8) as usize;
+        let version = 12..12 + version_len;
+        let header_end = version.end + 8;
+        if bytes.len() < header_end || std::str::from_utf8(&bytes[version.clone()]).is_err() {
+            return Err(malformed());
+        }
+        let entity_count = read_u32(&bytes, version.end) as usize;
+        let key_count = read_u32(&bytes, version.end + 4) as usize;
+        
+        let postings_start = header_end + key_count * KEY_ENTRY_SIZE;
+        if bytes.len() < postings_start {
+            return Err(malformed());
+        }
+        let posting_count = (bytes.len() - postings_start) / 4;
+        for i in 0..key_count {
+            let entry = header_end + i * KEY_ENTRY_SIZE;
+            let end = read_u32(&bytes, entry + 8) as usize + read_u32(&bytes, entry + 12) as usize;
+            if end > posting_count {
+                return Err(malformed());
+            }
+        }
+        
+        Ok(Self {
+            bytes,
+            version,
+            entity_count,
+            keys_start: header_end,
+            key_count,
+            postings_start,
+        })
+    }
+}
+
+/// FNV-1a, stable across builds so persisted indexes stay valid
+fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().expect("slice is four bytes"))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().expect("slice is eight bytes"))
+}
@@ -0,0 +1,426 @@
+//! Name normalization and fuzzy/phonetic matching
+
+use super::attributes::{self, AttributeContribution, SubjectAttributes};
+use super::normalize::{consonant_skeleton, NameForm};
+use super::script;
+use super::ScreenedEntity;
+use crate::config::AttributeWeights;
+use serde::{Deserialize, Serialize};
+
+/// How a candidate name was matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMethod {
+    Exact,
+    Fuzzy,
+    Phonetic,
+}
+
+/// Score of a single name comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameMatch {
+    /// Similarity in `[0, 1]`
+    pub score: f64,
+    pub method: MatchMethod,
+}
+
+/// Score of a name match adjusted by secondary attributes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeMatch {
+    /// Name score plus attribute contributions, in `[0, 1]`
+    pub score: f64,
+    pub name_score: f64,
+    pub method: MatchMethod,
+    /// Attributes compared, with their effect on the score
+    pub attributes: Vec<AttributeContribution>,
+}
+
+/// Fuzzy and phonetic name matcher
+#[derive(Debug, Clone)]
+pub struct NameMatcher {
+    /// Scores below this threshold are not reported as matches
+    pub threshold: f64,
+    
+    /// Weights of secondary attributes in composite scores
+    pub attribute_weights: AttributeWeights,
+}
+
+impl NameMatcher {
+    /// Weight applied to phonetic agreement, which is weaker evidence than spelling
+    const PHONETIC_WEIGHT: f64 = 0.9;
+    
+    /// Create a matcher with the given threshold
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            attribute_weights: AttributeWeights::default(),
+        }
+    }
+    
+    /// Weigh secondary attributes with the given weights
+    pub fn with_attribute_weights(mut self, weights: AttributeWeights) -> Self {
+        self.attribute_weights = weights;
+        self
+    }
+    
+    /// Compare two names, returning a match if the score reaches the threshold
+    pub fn matches(&self, query: &str, candidate: &str) -> Option<NameMatch> {
+        self.matches_in(query, None, candidate)
+    }
+    
+    /// Compare a query name in a hinted language against a candidate name
+    pub fn matches_in(&self, query: &str, language: Option<&str>, candidate: &str) -> Option<NameMatch> {
+        let result = self.score_in(query, language, candidate);
+        (result.score >= self.threshold).then_some(result)
+    }
+    
+    /// Score the similarity of two names
+    pub fn score(&self, query: &str, candidate: &str) -> NameMatch {
+        self.score_in(query, None, candidate)
+    }
+    
+    /// Score the similarity of a query name in a hinted language and a candidate name
+    ///
+    /// `language` is a BCP 47 tag, typically carried from the account holder's
+    /// KYC data. It selects language-specific romanization and name order.
+    pub fn score_in(&self, query: &str, language: Option<&str>, candidate: &str) -> NameMatch {
+        let candidate = NameForm::parse(candidate, None);
+        NameForm::variants(query, language)
+            .iter()
+            .map(|query| self.compare(query, &candidate))
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+            .unwrap_or(NameMatch { score: 0.0, method: MatchMethod::Fuzzy })
+    }
+    
+    /// Adjust a name match by the secondary attributes known for both sides
+    ///
+    /// The result may fall below the threshold, e.g. when the dates of birth
+    /// are years apart; callers decide whether to suppress it.
+    pub fn composite(&self, name: NameMatch, subject: &SubjectAttributes, entity: &ScreenedEntity) -> CompositeMatch {
+        let attributes = attributes::compare(subject, entity, &self.attribute_weights);
+        let adjustment: f64 = attributes.iter().map(|a| a.contribution).sum();
+        CompositeMatch {
+            score: (name.score + adjustment).clamp(0.0, 1.0),
+            name_score: name.score,
+            method: name.method,
+            attributes,
+        }
+    }
+    
+    fn compare(&self, query: &NameForm, candidate: &NameForm) -> NameMatch {
+        let query_tokens = &query.written;
+        let candidate_tokens = &candidate.written;
+        
+        if query_tokens.is_empty() || candidate_tokens.is_empty() {
+            return NameMatch { score: 0.0, method: MatchMethod::Fuzzy };
+        }
+        
+        let mut sorted_query = query_tokens.clone();
+        let mut sorted_candidate = candidate_tokens.clone();
+        sorted_query.sort();
+        sorted_candidate.sort();
+        
+        if sorted_query == sorted_candidate {
+            return NameMatch { score: 1.0, method: MatchMethod::Exact };
+        }
+        
+        // Whole spellings in both orders catch given names split differently
+        let query_spellings = [query.spelling(), query.written_spelling()];
+        let candidate_spellings = [candidate.spelling(), candidate.written_spelling()];
+        let best_spelling = |f: &dyn Fn(&str) -> String| {
+            query_spellings
+                .iter()
+                .flat_map(|q| candidate_spellings.iter().map(move |c| (q, c)))
+                .map(|(q, c)| jaro_winkler(&f(q.as_str()), &f(c.as_str())))
+                .fold(0.0, f64::max)
+        };
+        
+        let fuzzy = jaro_winkler(&sorted_query.join(" "), &sorted_candidate.join(" "))
+            .max(token_set_similarity(query_tokens, candidate_tokens))
+            .max(best_spelling(&|spelling| spelling.to_string()));
+        let mut phonetic = phonetic_similarity(query_tokens, candidate_tokens);
+        // Abjad romanizations lack most vowels, so compare consonants only
+        if query.abjad || candidate.abjad {
+            phonetic = phonetic.max(best_spelling(&consonant_skeleton));
+        }
+        let phonetic = phonetic * Self::PHONETIC_WEIGHT;
+        
+        if phonetic > fuzzy {
+            NameMatch { score: phonetic, method: MatchMethod::Phonetic }
+        } else {
+            NameMatch { score: fuzzy, method: MatchMethod::Fuzzy }
+        }
+    }
+}
+
+/// Normalize a name: transliterate to Latin, lowercase, and drop honorifics, particles and punctuation
+pub fn normalize(name: &str) -> String {
+    tokens(name).join(" ")
+}
+
+/// Split a name into normalized tokens, in the order written
+pub fn tokens(name: &str) -> Vec<String> {
+    NameForm::parse(name, None).written
+}
+
+/// Transliterate Latin diacritics and Cyrillic, Arabic, Hangul, kana and Han script to ASCII
+pub fn transliterate(name: &str) -> String {
+    script::romanize(name, None)
+}
+
+/// American Soundex code of a single token
+pub fn soundex(token: &str) -> String {
+    fn code(c: char) -> Option<char> {
+        match c {
+            'b' | 'f' | 'p' | 'v' => Some('1'),
+            'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+            'd' | 't' => Some('3'),
+            'l' => Some('4'),
+            'm' | 'n' => Some('5'),
+            'r' => Some('6'),
+            _ => None,
+        }
+    }
+    
+    let letters: Vec<char> = token.to_ascii_lowercase().chars().filter(char::is_ascii_alphabetic).collect();
+    let Some(&first) = letters.first() else {
+        return String::new();
+    };
+    
+    let mut out = String::from(first.to_ascii_uppercase());
+    let mut last = code(first);
+    for &c in &letters[1..] {
+        let current = code(c);
+        if let Some(digit) = current.filter(|_| current != last) {
+            out.push(digit);
+            if out.len() == 4 {
+                break;
+            }
+        }
+        // 'h' and 'w' do not separate letters with the same code
+        if c != 'h' && c != 'w' {
+            last = current;
+        }
+    }
+    
+    while out.len() < 4 {
+        out.push('0');
+    }
+    out
+}
+
+/// Metaphone code of a single token
+pub fn metaphone(token: &str) -> String {
+    let word: Vec<char> = token.to_ascii_lowercase().chars().filter(char::is_ascii_alphabetic).collect();
+    if word.is_empty() {
+        return String::new();
+    }
+    
+    let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u');
+    let at = |i: isize| -> char {
+        if i < 0 || i as usize >= word.len() {
+            '\0'
+        } else {
+            word[i as usize]
+        }
+    };
+    
+    // Initial letter exceptions
+    let mut start = 0;
+    let mut out = String::new();
+    match (word[0], at(1)) {
+        ('a', 'e') | ('g', 'n') | ('k', 'n') | ('p', 'n') | ('w', 'r') => start = 1,
+        ('x', _) => {
+            out.push('S');
+            start = 1;
+        }
+        ('w', 'h') => {
+            out.push('W');
+            start = 2;
+        }
+        _ => {}
+    }
+    
+    let mut i = start as isize;
+    while (i as usize) < word.len() {
+        let c = at(i);
+        let prev = at(i - 1);
+        let next = at(i + 1);
+        
+        // Skip duplicate adjacent letters except 'c'
+        if c == prev && c != 'c' {
+            i += 1;
+            continue;
+        }
+        
+        match c {
+            'a' | 'e' | 'i' | 'o' | 'u' => {
+                if i == 0 {
+                    out.push(c.to_ascii_uppercase());
+                }
+            }
+            'b' => {
+                if !(prev == 'm' && i as usize == word.len() - 1) {
+                    out.push('B');
+                }
+            }
+            'c' => {
+                if (next == 'i' && at(i + 2) == 'a') || next == 'h' {
+                    out.push(if prev == 's' { 'K' } else { 'X' });
+                    if next == 'h' {
+                        i += 1;
+                    }
+                } else if matches!(next, 'i' | 'e' | 'y') {
+                    if prev != 's' {
+                        out.push('S');
+                    }
+                } else {
+                    out.push('K');
+                }
+            }
+            'd' => {
+                if next == 'g' && matches!(at(i + 2), 'e' | 'i' | 'y') {
+                    out.push('J');
+                    i += 1;
+                } else {
+                    out.push('T');
+                }
+            }
+            'g' => {
+                let silent = (next == 'h' && !is_vowel(at(i + 2)) && at(i + 2) != '\0')
+                    || (next == 'n' && (at(i + 2) == '\0' || (at(i + 2) == 'e' && at(i + 3) == 'd')));
+                if silent {
+                    // silent
+                } else if matches!(next, 'i' | 'e' | 'y') && prev != 'g' {
+                    out.push('J');
+                } else {
+                    out.push('K');
+                }
+            }
+            'h' => {
+                if is_vowel(next) && !matches!(prev, 'c' | 's' | 'p' | 't' | 'g') {
+                    out.push('H');
+                }
+            }
+            'k' => {
+                if prev != 'c' {
+                    out.push('K');
+                }
+            }
+            'p' => {
+                if next == 'h' {
+                    out.push('F');
+                    i += 1;
+                } else {
+                    out.push('P');
+                }
+            }
+            'q' => out.push('K'),
+            's' => {
+                if next == 'h' || (next == 'i' && matches!(at(i + 2), 'o' | 'a')) {
+                    out.push('X');
+                    if next == 'h' {
+                        i += 1;
+                    }
+                } else {
+                    out.push('S');
+                }
+            }
+            't' => {
+                if next == 'i' && matches!(at(i + 2), 'o' | 'a') {
+                    out.push('X');
+                } else if next == 'h' {
+                    out.push('0');
+                    i += 1;
+                } else if !(next == 'c' && at(i + 2) == 'h') {
+                    out.push('T');
+                }
+            }
+            'v' => out.push('F'),
+            'w' | 'y' => {
+                if is_vowel(next) {
+                    out.push(c.to_ascii_uppercase());
+                }
+            }
+            'x' => out.push_str("KS"),
+            'z' => out.push('S'),
+            'f' | 'j' | 'l' | 'm' | 'n' | 'r' => out.push(c.to_ascii_uppercase()),
+            _ => {}
+        }
+        i += 1;
+    }
+    
+    out
+}
+
+/// Jaro-Winkler similarity of two strings
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    
+    for i in 0..a.len() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && a[i] == b[j] {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    
+    if matches == 0 {
+        return 0.0;
+    }
+    
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+    
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count() as f64;
+    jaro + prefix * 0.1 * (1.0 - jaro)
+}
+
+/// Average best per-token Jaro-Winkler similarity, tolerant of missing middle names
+fn token_set_similarity(query: &[String], candidate: &[String]) -> f64 {
+    let (shorter, longer) = if query.len() <= candidate.len() { (query, candidate) } else { (candidate, query) };
+    let total: f64 = shorter
+        .iter()
+        .map(|token| longer.iter().map(|other| jaro_winkler(token, other)).fold(0.0, f64::max))
+        .sum();
+    
+    // Penalize unmatched extra tokens so "john" does not fully match "john smith"
+    let coverage = shorter.len() as f64 / longer.len() as f64;
+    (total / shorter.len() as f64) * (0.7 + 0.3 * coverage)
+}
+
+/// Fraction of query tokens with a phonetically equivalent candidate token
+fn phonetic_similarity(query: &[String], candidate: &[String]) -> f64 {
+    let candidate_codes: Vec<(String, String)> = candidate.iter().map(|t| (soundex(t), metaphone(t))).collect();
+    let matched = query
+        .iter()
+        .filter(|token| {
+            let (sx, mp) = (soundex(token), metaphone(token));
+            candidate_codes.iter().any(|(csx, cmp)| *cmp == mp || (*csx == sx && !sx.is_empty() && mp.chars().next() == cmp.chars().next()))
+        })
+        .count();
+    
+    let coverage = query.len().min(candidate.len()) as f64 / query.len().max(candidate.len()) as f64;
+    (matched as f64 / query.len() as f64) * (0.7 + 0.3 * coverage)
+}
@@ -0,0 +1,378 @@
+//! Screening list storage and name matching shared by automated and ad-hoc screening
+
+pub mod adjudications;
+pub mod attributes;
+pub mod delta;
+pub mod index;
+pub mod matcher;
+pub mod normalize;
+pub mod results;
+pub mod script;
+pub mod search;
+
+use delta::ListDelta;
+use index::{CandidateIndex, NameKeys};
+use crate::config::SanctionsConfig;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+
+/// Kind of screened entity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityType {
+    Individual,
+    Organization,
+    Vessel,
+    Aircraft,
+}
+
+/// An entry from an ingested sanctions or PEP list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenedEntity {
+    /// Identifier assigned by the list publisher
+    pub id: String,
+    
+    /// List the entity was ingested from (e.g. "ofac_sdn", "un_consolidated", "pep")
+    pub list: String,
+    
+    /// Primary name
+    pub name: String,
+    
+    /// Alternate names and spellings
+    pub aliases: Vec<String>,
+    
+    pub entity_type: EntityType,
+    
+    /// Sanctions programs or PEP positions
+    pub programs: Vec<String>,
+    
+    /// Date of birth as published (may be partial, e.g. "1970" or "1970-03")
+    pub date_of_birth: Option<String>,
+    
+    /// Nationalities as ISO 3166-1 alpha-2 codes
+    pub nationalities: Vec<String>,
+    
+    /// Passport, national ID and other identity document numbers
+    #[serde(default)]
+    pub document_numbers: Vec<String>,
+    
+    /// Known addresses
+    #[serde(default)]
+    pub addresses: Vec<String>,
+}
+
+impl ScreenedEntity {
+    /// Iterate over the primary name and all aliases
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+/// An ingested list version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningList {
+    pub name: String,
+    pub version: String,
+    pub ingested_at: DateTime<Utc>,
+    pub entities: Vec<ScreenedEntity>,
+}
+
+/// Record of a list version having been ingested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListVersionRecord {
+    pub name: String,
+    pub version: String,
+    pub ingested_at: DateTime<Utc>,
+}
+
+/// A list version with its candidate index
+struct IndexedList {
+    list: ScreeningList,
+    index: CandidateIndex,
+}
+
+/// Store of the latest version of each screening list
+///
+/// Lists are held in memory. When opened on a directory, every ingested list
+/// and its candidate index are also written there, and the indexes are
+/// memory-mapped rather than rebuilt when the store is reopened.
+#[derive(Default)]
+pub struct ScreeningListStore {
+    lists: RwLock<HashMap<String, IndexedList>>,
+    
+    /// Every ingested version, in ingestion order
+    history: RwLock<Vec<ListVersionRecord>>,
+    
+    /// Directory lists and indexes are persisted in
+    dir: Option<PathBuf>,
+}
+
+impl ScreeningListStore {
+    /// Create an empty in-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create the store configured for sanctions screening
+    pub fn from_config(config: &SanctionsConfig) -> Result<Self> {
+        match &config.list_store_dir {
+            Some(dir) => Self::open(dir),
+            None => Ok(Self::new()),
+        }
+    }
+    
+    /// Open a store persisted in a directory, creating the directory if needed
+    ///
+    /// Each list is loaded from `<name>.json` and its index memory-mapped from
+    /// `<name>.idx`. An index that is missing or was built from another list
+    /// version is rebuilt in memory and rewritten on the next ingestion.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        
+        let mut lists = HashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let list: ScreeningList = serde_json::from_slice(&std::fs::read(&path)?)?;
+            let index = match CandidateIndex::open(&path.with_extension("idx")) {
+                Ok(index) if index.list_version() == list.version && index.entity_count() == list.entities.len() => index,
+                result => {
+                    if let Err(e) = result {
+                        tracing::warn!(list = %list.name, error = %e, "screening index unreadable, rebuilding");
+                    } else {
+                        tracing::warn!(list = %list.name, "screening index is stale, rebuilding");
+                    }
+                    CandidateIndex::build(&list.version, &list.entities)
+                }
+            };
+            lists.insert(list.name.clone(), IndexedList { list, index });
+        }
+        
+        let mut history: Vec<ListVersionRecord> = lists
+            .values()
+            .map(|indexed| ListVersionRecord {
+                name: indexed.list.name.clone(),
+                version: indexed.list.version.clone(),
+                ingested_at: indexed.list.ingested_at,
+            })
+            .collect();
+        history.sort_by_key(|record| record.ingested_at);
+        
+        tracing::info!(dir = %dir.display(), lists = lists.len(), "screening lists loaded");
+        Ok(Self {
+            lists: RwLock::new(lists),
+            history: RwLock::new(history),
+            dir: Some(dir),
+        })
+    }
+    
+    /// Ingest a list version, replacing any previous version of the same list
+    ///
+    /// Returns the entries added, removed, and changed relative to the version
+    /// being replaced, so only accounts affected by the update are re-screened.
+    pub async fn ingest(&self, name: &str, version: &str, entities: Vec<ScreenedEntity>) -> Result<ListDelta> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(ComplianceError::validation(
+                "name",
+                "list names may only contain ASCII letters, digits, '_' and '-'",
+            ));
+        }
+        
+        let list = ScreeningList {
+            name: name.to_string(),
+            version: version.to_string(),
+            ingested_at: Utc::now(),
+            entities,
+        };
+        let index = CandidateIndex::build(version, &list.entities);
+        
+        let mut lists = self.lists.write().await;
+        if let Some(dir) = &self.dir {
+            persist(dir, &list, &index).await?;
+        }
+        let delta = match lists.get(name).map(|indexed| &indexed.list) {
+            Some(previous) => ListDelta::compute(
+                name,
+                Some(&previous.version),
+                &previous.entities,
+                version,
+                &list.entities,
+            ),
+            None => ListDelta::compute(name, None, &[], version, &list.entities),
+        };
+        
+        tracing::info!(
+            list = name,
+            version,
+            entities = list.entities.len(),
+            added = delta.added.len(),
+            removed = delta.removed.len(),
+            changed = delta.changed.len(),
+            "screening list ingested"
+        );
+        self.history.write().await.push(ListVersionRecord {
+            name: list.name.clone(),
+            version: list.version.clone(),
+            ingested_at: list.ingested_at,
+        });
+        lists.insert(name.to_string(), IndexedList { list, index });
+        Ok(delta)
+    }
+    
+    /// Get the current version of a list
+    pub async fn get(&self, name: &str) -> Option<ScreeningList> {
+        self.lists.read().await.get(name).map(|indexed| indexed.list.clone())
+    }
+    
+    /// Get an entity of the current version of a list
+    pub async fn entity(&self, list: &str, entity_id: &str) -> Option<ScreenedEntity> {
+        self.lists
+            .read()
+            .await
+            .get(list)
+            .and_then(|indexed| indexed.list.entities.iter().find(|entity| entity.id == entity_id).cloned())
+    }
+    
+    /// Get the current version identifier of every list
+    pub async fn versions(&self) -> HashMap<String, String> {
+        self.lists
+            .read()
+            .await
+            .values()
+            .map(|indexed| (indexed.list.name.clone(), indexed.list.version.clone()))
+            .collect()
+    }
+    
+    /// Get the current version of every list with its ingestion time, by name
+    pub async fn current_versions(&self) -> Vec<ListVersionRecord> {
+        let mut versions: Vec<_> = self
+            .lists
+            .read()
+            .await
+            .values()
+            .map(|indexed| ListVersionRecord {
+                name: indexed.list.name.clone(),
+                version: indexed.list.version.clone(),
+                ingested_at: indexed.list.ingested_at,
+            })
+            .collect();
+        versions.sort_by(|a, b| a.name.cmp(&b.name));
+        versions
+    }
+    
+    /// Get the version of every list that was in force at a point in time
+    pub async fn versions_at(&self, as_of: DateTime<Utc>) -> HashMap<String, String> {
+        self.history
+            .read()
+            .await
+            .iter()
+            .take_while(|record| record.ingested_at <= as_of)
+            .map(|record| (record.name.clone(), record.version.clone()))
+            .collect()
+    }
+    
+    /// Check whether a version of a list was ever ingested
+    pub async fn has_version(&self, name: &str, version: &str) -> bool {
+        self.history
+            .read()
+            .await
+            .iter()
+            .any(|record| record.name == name && record.version == version)
+    }
+    
+    /// Lists whose screened version is older than the required version
+    ///
+    /// A screened version satisfies a required one when it was ingested no
+    /// earlier. Lists missing from `screened`, and required versions that
+    /// were never ingested, are always stale.
+    pub async fn stale_lists(
+        &self,
+        screened: &HashMap<String, String>,
+        required: &BTreeMap<String, String>,
+    ) -> Vec<String> {
+        let history = self.history.read().await;
+        let position = |name: &str, version: &str| {
+            history.iter().position(|record| record.name == name && record.version == version)
+        };
+        required
+            .iter()
+            .filter(|(name, min_version)| {
+                let Some(min_position) = position(name, min_version) else {
+                    return true;
+                };
+                !screened
+                    .get(*name)
+                    .and_then(|version| position(name, version))
+                    .is_some_and(|screened_position| screened_position >= min_position)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+    
+    /// Run a closure over the entities of the selected lists (all lists when `None`)
+    pub async fn with_entities<T>(
+        &self,
+        lists: Option<&[String]>,
+        f: impl FnOnce(&mut dyn Iterator<Item = (&ScreeningList, &ScreenedEntity)>) -> T,
+    ) -> T {
+        let guard = self.lists.read().await;
+        let mut entities = guard
+            .values()
+            .map(|indexed| &indexed.list)
+            .filter(|list| lists.map_or(true, |names| names.contains(&list.name)))
+            .flat_map(|list| list.entities.iter().map(move |entity| (list, entity)));
+        f(&mut entities)
+    }
+    
+    /// Run a closure over the entities of the selected lists that could match a name
+    ///
+    /// Candidates come from each list's index, so the closure sees a small
+    /// fraction of the entities `with_entities` would. Entities that cannot
+    /// reach `threshold` against `name`, in the hinted `language`, are left out.
+    pub async fn with_candidates<T>(
+        &self,
+        lists: Option<&[String]>,
+        name: &str,
+        language: Option<&str>,
+        threshold: f64,
+        f: impl FnOnce(&mut dyn Iterator<Item = (&ScreeningList, &ScreenedEntity)>) -> T,
+    ) -> T {
+        let keys = NameKeys::of_in(name, language);
+        let guard = self.lists.read().await;
+        let mut entities = guard
+            .values()
+            .filter(|indexed| lists.map_or(true, |names| names.contains(&indexed.list.name)))
+            .flat_map(|indexed| {
+                let list = &indexed.list;
+                indexed
+                    .index
+                    .candidates(&keys, threshold)
+                    .into_iter()
+                    .filter_map(move |i| list.entities.get(i).map(|entity| (list, entity)))
+            });
+        f(&mut entities)
+    }
+}
+
+/// Write a list and its index, replacing any previous files by rename
+async fn persist(dir: &Path, list: &ScreeningList, index: &CandidateIndex) -> Result<()> {
+    // The index goes first: if the list write fails, the old list's index
+    // no longer matches its version and is rebuilt when the store is reopened.
+    let files = [
+        (format!("{}.idx", list.name), index.as_bytes().to_vec()),
+        (format!("{}.json", list.name), serde_json::to_vec(list)?),
+    ];
+    for (file, bytes) in files {
+        let tmp = dir.join(format!("{}.tmp", file));
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, dir.join(file)).await?;
+    }
+    Ok(())
+}
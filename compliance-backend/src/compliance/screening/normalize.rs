@@ -0,0 +1,146 @@
+//! Name normalization ahead of matching
+//!
+//! A name is romanized, lowercased and split into tokens. Honorifics,
+//! name particles and legal-form words are dropped, since they vary between
+//! sources without identifying anyone. Name parts are also put in a canonical
+//! order, given names first and family name last, so that differently split
+//! given names ("Jong Un", "Jong-un", "Jongun") can be compared as one
+//! spelling. Names written "FAMILY, Given", names in scripts that put the
+//! family name first, and names whose language hint says so are reordered.
+
+use super::script;
+
+/// Titles and honorifics
+const HONORIFICS: &[&str] = &[
+    "mr", "mrs", "ms", "miss", "mx", "dr", "prof", "sir", "dame", "lord", "lady", "rev", "hon", "sheikh", "shaikh",
+    "sheik", "shaykh", "haji", "hajji", "sayyid", "sayed", "syed", "mullah", "maulana", "emir", "gen", "general",
+    "col", "colonel", "maj", "major", "capt", "captain", "lt", "cmdr", "adm", "admiral", "sgt",
+];
+
+/// Name particles and legal-form words
+const STOP_WORDS: &[&str] = &[
+    "al", "el", "ul", "bin", "ibn", "bint", "bn", "bnt", "ben", "ould", "van", "von", "der", "den", "de", "del",
+    "della", "dos", "das", "du", "ltd", "limited", "llc", "inc", "incorporated", "corp", "corporation", "co",
+    "company", "plc", "gmbh", "ag", "srl", "bv", "nv", "ooo", "oao", "zao", "jsc", "pjsc",
+];
+
+/// Languages whose names are written family name first
+const FAMILY_NAME_FIRST: &[&str] = &["zh", "ja", "ko", "vi", "hu"];
+
+/// Languages whose hint changes how their script is romanized
+const ROMANIZATION_HINTS: &[&str] = &["uk", "bg"];
+
+/// Check that a language hint is a well-formed BCP 47 tag
+pub fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    (2..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// A name reduced to comparable tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameForm {
+    /// Tokens in the order written
+    pub written: Vec<String>,
+    /// Tokens with given names first and the family name last
+    pub ordered: Vec<String>,
+    /// Whether the name was written in an abjad, whose romanization omits most vowels
+    pub abjad: bool,
+}
+
+impl NameForm {
+    /// Normalize a name, with an optional BCP 47 language hint
+    pub fn parse(name: &str, language: Option<&str>) -> Self {
+        let family_first = script::is_family_name_first_script(name)
+            || language.is_some_and(|tag| FAMILY_NAME_FIRST.contains(&script::primary_language(tag).as_str()));
+        
+        let (written, ordered) = match name.split_once(',') {
+            // "FAMILY, Given" as published on many lists
+            Some((family, given)) if !given.contains(',') => {
+                let family = tokens(family, language);
+                let given = tokens(given, language);
+                let written = family.iter().chain(&given).cloned().collect();
+                let ordered = given.into_iter().chain(family).collect();
+                (written, ordered)
+            }
+            _ => {
+                let written = tokens(name, language);
+                let mut ordered = written.clone();
+                if family_first && ordered.len() > 1 {
+                    ordered.rotate_left(1);
+                }
+                (written, ordered)
+            }
+        };
+        
+        Self {
+            written,
+            ordered,
+            abjad: script::has_abjad(name),
+        }
+    }
+    
+    /// Forms a name is compared in: as hinted and, when the hint changes the
+    /// romanization, as if unhinted, since lists use either convention
+    pub fn variants(name: &str, language: Option<&str>) -> Vec<Self> {
+        let hinted = Self::parse(name, language);
+        let mut variants = vec![hinted];
+        if language.is_some_and(|tag| ROMANIZATION_HINTS.contains(&script::primary_language(tag).as_str())) {
+            let plain = Self::parse(name, None);
+            if plain.written != variants[0].written {
+                variants.push(plain);
+            }
+        }
+        variants
+    }
+    
+    /// The name as one spelling, in canonical order
+    pub fn spelling(&self) -> String {
+        self.ordered.concat()
+    }
+    
+    /// The name as one spelling, in written order
+    pub fn written_spelling(&self) -> String {
+        self.written.concat()
+    }
+}
+
+/// Consonant skeleton of a romanized spelling
+///
+/// Vowels and the semivowels `w` and `y`, which abjad romanizations supply
+/// inconsistently, are dropped, `q` is read as `k` and doubled letters are
+/// collapsed, so "Mohammed" and "Muhammad" both reduce to "mhmd".
+pub fn consonant_skeleton(spelling: &str) -> String {
+    let mut skeleton = String::with_capacity(spelling.len());
+    for c in spelling.chars().filter(|c| !matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'w' | 'y')) {
+        let c = if c == 'q' { 'k' } else { c };
+        if !skeleton.ends_with(c) {
+            skeleton.push(c);
+        }
+    }
+    skeleton
+}
+
+/// Romanized, lowercased tokens with honorifics and stop words removed
+///
+/// A name made only of such words keeps them, so it still matches itself.
+fn tokens(name: &str, language: Option<&str>) -> Vec<String> {
+    let all: Vec<String> = script::romanize(name, language)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect();
+    let significant: Vec<String> = all
+        .iter()
+        .filter(|token| !HONORIFICS.contains(&token.as_str()) && !STOP_WORDS.contains(&token.as_str()))
+        .cloned()
+        .collect();
+    if significant.is_empty() {
+        all
+    } else {
+        significant
+    }
+}
@@ -0,0 +1,364 @@
+//! Per-account name screening results with list-version provenance
+
+use super::adjudications::{Adjudication, AdjudicationInput, AdjudicationStore};
+use super::attributes::{AttributeContribution, SubjectAttributes};
+use super::delta::{name_keys, ListDelta};
+use super::matcher::NameMatcher;
+use super::search::{self as name_search, HitSource, SearchRequest};
+use super::ScreeningListStore;
+use crate::compliance::claims::PepStatus;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Maximum number of matches kept on a screening result
+const MAX_RESULT_MATCHES: usize = 100;
+
+/// A list entry an account's name matched
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreeningMatch {
+    pub list: String,
+    pub entity_id: String,
+    pub matched_name: String,
+    /// Name score adjusted by the compared attributes
+    pub score: f64,
+    pub name_score: f64,
+    #[serde(default)]
+    pub attributes: Vec<AttributeContribution>,
+}
+
+/// Outcome of screening an account's name against the ingested lists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningResult {
+    pub account_id: AccountId,
+    
+    /// Business client the account was screened for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<Uuid>,
+    
+    /// Name that was screened
+    pub screened_name: String,
+    
+    /// BCP 47 language hint the name was screened with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    
+    /// Secondary attributes the name was screened with
+    #[serde(default, skip_serializing_if = "SubjectAttributes::is_empty")]
+    pub attributes: SubjectAttributes,
+    
+    /// Blocking keys of the screened name, used to select accounts for re-screening
+    pub name_keys: BTreeSet<String>,
+    
+    pub matches: Vec<ScreeningMatch>,
+    
+    /// Matches not reported because an analyst adjudicated them false positives
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed: Vec<ScreeningMatch>,
+    
+    /// Version of every list the name was screened against
+    pub list_versions: HashMap<String, String>,
+    
+    pub screened_at: DateTime<Utc>,
+}
+
+impl ScreeningResult {
+    /// PEP status of the account: a match when any reported match is on a PEP list
+    pub fn pep_status(&self) -> PepStatus {
+        if self.matches.iter().any(|m| m.list.to_lowercase().contains("pep")) {
+            PepStatus::Match
+        } else {
+            PepStatus::Clear
+        }
+    }
+    
+    /// Check if two results matched the same list entries
+    fn same_matches(&self, other: &ScreeningResult) -> bool {
+        let key = |m: &ScreeningMatch| (m.list.clone(), m.entity_id.clone());
+        let ours: BTreeSet<_> = self.matches.iter().map(key).collect();
+        let theirs: BTreeSet<_> = other.matches.iter().map(key).collect();
+        ours == theirs
+    }
+}
+
+/// An account re-screened after a list update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rescreening {
+    pub account_id: AccountId,
+    pub previous: ScreeningResult,
+    pub current: ScreeningResult,
+    
+    /// Whether the set of matched entries differs from the previous result
+    pub outcome_changed: bool,
+}
+
+/// Latest screening result of every screened account
+#[derive(Default)]
+pub struct ScreeningResultStore {
+    results: RwLock<HashMap<AccountId, ScreeningResult>>,
+    
+    /// Analyst verdicts applied on every screen
+    adjudications: AdjudicationStore,
+}
+
+impl ScreeningResultStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Adjudications and adopted thresholds
+    pub fn adjudications(&self) -> &AdjudicationStore {
+        &self.adjudications
+    }
+    
+    /// Screen an account's name against every ingested list and record the result
+    ///
+    /// The threshold adopted for `client_id`, if any, replaces the matcher's,
+    /// and entities adjudicated false positives for the account are suppressed.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn screen(
+        &self,
+        lists: &ScreeningListStore,
+        matcher: &NameMatcher,
+        account_id: &AccountId,
+        client_id: Option<Uuid>,
+        name: &str,
+        language: Option<&str>,
+        attributes: &SubjectAttributes,
+    ) -> ScreeningResult {
+        let adopted = match client_id {
+            Some(client_id) => self.adjudications.threshold(client_id).await,
+            None => None,
+        };
+        let matcher = NameMatcher {
+            threshold: adopted.unwrap_or(matcher.threshold),
+            ..matcher.clone()
+        };
+        let mut result = screen_name(lists, &matcher, account_id, name, language, attributes).await;
+        result.client_id = client_id;
+        
+        let mut kept = Vec::with_capacity(result.matches.len());
+        for m in std::mem::take(&mut result.matches) {
+            let entity = lists.entity(&m.list, &m.entity_id).await;
+            let suppressed = match &entity {
+                Some(entity) => self.adjudications.is_suppressed(account_id, &m.list, entity).await,
+                None => false,
+            };
+            if suppressed {
+                result.suppressed.push(m);
+            } else {
+                kept.push(m);
+            }
+        }
+        result.matches = kept;
+        
+        self.results.write().await.insert(account_id.clone(), result.clone());
+        result
+    }
+    
+    /// Record an analyst's verdict on one of an account's current matches
+    ///
+    /// Suppressed matches may be adjudicated again, e.g. to reinstate one as a
+    /// true hit.
+    pub async fn adjudicate(
+        &self,
+        lists: &ScreeningListStore,
+        account_id: &AccountId,
+        analyst: &str,
+        input: AdjudicationInput,
+    ) -> Result<Adjudication> {
+        let (list, entity_id) = (input.list.as_str(), input.entity_id.as_str());
+        let result = self.get(account_id).await.ok_or_else(|| ComplianceError::ScreeningResultNotFound {
+            account_id: account_id.to_string(),
+        })?;
+        let not_found = || ComplianceError::ScreeningMatchNotFound {
+            account_id: account_id.to_string(),
+            list: list.to_string(),
+            entity_id: entity_id.to_string(),
+        };
+        let matched = result
+            .matches
+            .iter()
+            .chain(&result.suppressed)
+            .find(|m| m.list == list && m.entity_id == entity_id)
+            .ok_or_else(not_found)?;
+        // The entry may have been removed from the list since the screen
+        let entity = lists.entity(list, entity_id).await.ok_or_else(not_found)?;
+        
+        let adjudication = Adjudication {
+            id: Uuid::new_v4(),
+            account_id: account_id.clone(),
+            client_id: result.client_id,
+            list: input.list.clone(),
+            entity,
+            matched_name: matched.matched_name.clone(),
+            score: matched.score,
+            verdict: input.verdict,
+            analyst: analyst.to_string(),
+            reason: input.reason,
+            adjudicated_at: Utc::now(),
+        };
+        self.adjudications.record(adjudication.clone()).await;
+        Ok(adjudication)
+    }
+    
+    /// Get the latest screening result of an account
+    pub async fn get(&self, account_id: &AccountId) -> Option<ScreeningResult> {
+        self.results.read().await.get(account_id).cloned()
+    }
+    
+    /// Accounts whose prior matches or name keys intersect a list delta
+    ///
+    /// An account is affected when it previously matched an entry that was
+    /// removed or changed, including suppressed matches, or when its name shares a blocking key with an
+    /// added entry or either side of a changed one.
+    pub async fn affected_by(&self, delta: &ListDelta) -> Vec<AccountId> {
+        if delta.is_empty() {
+            return vec![];
+        }
+        
+        let touched_ids = delta.touched_ids();
+        let keys = delta.name_keys();
+        
+        self.results
+            .read()
+            .await
+            .values()
+            .filter(|result| {
+                result
+                    .matches
+                    .iter()
+                    .chain(&result.suppressed)
+                    .any(|m| m.list == delta.list && touched_ids.contains(m.entity_id.as_str()))
+                    || !result.name_keys.is_disjoint(&keys)
+            })
+            .map(|result| result.account_id.clone())
+            .collect()
+    }
+    
+    /// Re-screen an account against the current lists with the name and
+    /// attributes it was last screened with, or `None` if it never was
+    pub async fn rescreen_account(
+        &self,
+        lists: &ScreeningListStore,
+        matcher: &NameMatcher,
+        account_id: &AccountId,
+    ) -> Option<ScreeningResult> {
+        let previous = self.get(account_id).await?;
+        let result = self
+            .screen(
+                lists,
+                matcher,
+                account_id,
+                previous.client_id,
+                &previous.screened_name,
+                previous.language.as_deref(),
+                &previous.attributes,
+            )
+            .await;
+        Some(result)
+    }
+    
+    /// Re-screen only the accounts affected by a list delta
+    ///
+    /// Unaffected accounts keep their previous result and provenance, which
+    /// remains accurate because the delta cannot change their outcome.
+    pub async fn rescreen(
+        &self,
+        lists: &ScreeningListStore,
+        matcher: &NameMatcher,
+        delta: &ListDelta,
+    ) -> Vec<Rescreening> {
+        let affected = self.affected_by(delta).await;
+        let mut rescreenings = Vec::with_capacity(affected.len());
+        
+        for account_id in affected {
+            let Some(previous) = self.get(&account_id).await else {
+                continue;
+            };
+            let current = self
+                .screen(
+                    lists,
+                    matcher,
+                    &account_id,
+                    previous.client_id,
+                    &previous.screened_name,
+                    previous.language.as_deref(),
+                    &previous.attributes,
+                )
+                .await;
+            rescreenings.push(Rescreening {
+                outcome_changed: !previous.same_matches(&current),
+                account_id,
+                previous,
+                current,
+            });
+        }
+        
+        tracing::info!(
+            list = %delta.list,
+            version = %delta.to_version,
+            screened_accounts = self.results.read().await.len(),
+            rescreened = rescreenings.len(),
+            changed = rescreenings.iter().filter(|r| r.outcome_changed).count(),
+            "list delta re-screening complete"
+        );
+        rescreenings
+    }
+}
+
+/// Screen a name against every ingested list without recording the result
+async fn screen_name(
+    lists: &ScreeningListStore,
+    matcher: &NameMatcher,
+    account_id: &AccountId,
+    name: &str,
+    language: Option<&str>,
+    attributes: &SubjectAttributes,
+) -> ScreeningResult {
+    let list_versions = lists.versions().await;
+    let request = SearchRequest {
+        name: name.to_string(),
+        language: language.map(str::to_string),
+        attributes: attributes.clone(),
+        lists: None,
+        entity_type: None,
+        min_score: None,
+        limit: Some(MAX_RESULT_MATCHES),
+    };
+    let response = name_search::search(lists, matcher, &request, vec![]).await;
+    
+    let matches = response
+        .hits
+        .into_iter()
+        .filter_map(|hit| match hit.source {
+            HitSource::Official { list, .. } => Some(ScreeningMatch {
+                list,
+                entity_id: hit.entity.id,
+                matched_name: hit.matched_name,
+                score: hit.score,
+                name_score: hit.name_score,
+                attributes: hit.attributes,
+            }),
+            HitSource::ClientWatchlist { .. } => None,
+        })
+        .collect();
+    
+    ScreeningResult {
+        account_id: account_id.clone(),
+        client_id: None,
+        screened_name: name.to_string(),
+        language: language.map(str::to_string),
+        attributes: attributes.clone(),
+        name_keys: name_keys(name, language),
+        matches,
+        suppressed: vec![],
+        list_versions,
+        screened_at: Utc::now(),
+    }
+}
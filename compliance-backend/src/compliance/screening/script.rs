@@ -0,0 +1,607 @@
+//! Romanization of non-Latin scripts
+//!
+//! Names are compared in Latin transcription. Latin diacritics are folded,
+//! Cyrillic follows BGN/PCGN-style romanization with Ukrainian and Bulgarian
+//! conventions selected by a language hint, Arabic and Persian letters map to
+//! their usual consonants, Hangul follows the Revised Romanization with the
+//! customary spellings of common surnames, kana follow Hepburn, and common Han
+//! characters map to Hanyu Pinyin. Han characters outside the table become an
+//! opaque token per code point, so names written in the same characters still
+//! match each other. Kanji have no reading table; Japanese names match through
+//! their kana or Latin spellings.
+
+/// Romanize a name, leaving ASCII untouched
+///
+/// Han and Hangul names are written family name first without spaces; the
+/// first syllable of such a run is split off as the family name.
+pub fn romanize(name: &str, language: Option<&str>) -> String {
+    let language = language.map(primary_language);
+    let language = language.as_deref();
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len());
+    let mut i = 0;
+    
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii() {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        
+        let run_end = |is: fn(char) -> bool| chars[i..].iter().position(|c| !is(*c)).map_or(chars.len(), |n| i + n);
+        if is_hangul(c) {
+            let end = run_end(is_hangul);
+            out.push_str(&romanize_hangul(&chars[i..end]));
+            i = end;
+        } else if is_kana(c) {
+            let end = run_end(is_kana);
+            out.push_str(&romanize_kana(&chars[i..end]));
+            i = end;
+        } else if is_han(c) {
+            let end = run_end(is_han);
+            out.push_str(&romanize_han(&chars[i..end]));
+            i = end;
+        } else if is_arabic(c) {
+            let at_token_start = !out.chars().last().is_some_and(|c| c.is_ascii_alphanumeric());
+            // The definite article is written joined to the name it precedes
+            let article = c == 'ا' && chars.get(i + 1) == Some(&'ل') && chars.get(i + 2).is_some_and(|c| is_arabic(*c));
+            if at_token_start && article {
+                out.push_str("al ");
+                i += 2;
+                continue;
+            }
+            out.push_str(arabic(c));
+            i += 1;
+        } else {
+            out.push_str(letter(c, language));
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Primary subtag of a BCP 47 language tag, lowercased
+pub fn primary_language(tag: &str) -> String {
+    tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase()
+}
+
+/// Check if a name contains letters of an abjad, whose romanization omits most vowels
+pub fn has_abjad(name: &str) -> bool {
+    name.chars().any(is_arabic)
+}
+
+/// Check if a name is written in a script that puts the family name first
+pub fn is_family_name_first_script(name: &str) -> bool {
+    name.chars().any(|c| is_han(c) || is_hangul(c) || is_kana(c))
+}
+
+fn is_hangul(c: char) -> bool {
+    ('\u{AC00}'..='\u{D7A3}').contains(&c)
+}
+
+fn is_kana(c: char) -> bool {
+    ('\u{3041}'..='\u{3096}').contains(&c) || ('\u{30A1}'..='\u{30FA}').contains(&c) || c == 'ー'
+}
+
+fn is_han(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c)
+}
+
+fn is_arabic(c: char) -> bool {
+    ('\u{0600}'..='\u{06FF}').contains(&c) || ('\u{0750}'..='\u{077F}').contains(&c)
+}
+
+/// Latin and Cyrillic letters
+fn letter(c: char, language: Option<&str>) -> &'static str {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    match (language, lower) {
+        (Some("uk"), 'г') => return "h",
+        (Some("uk"), 'и') => return "y",
+        (Some("bg"), 'щ') => return "sht",
+        (Some("bg"), 'ъ') => return "a",
+        _ => {}
+    }
+    match lower {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        // Cyrillic (BGN/PCGN-style romanization)
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'д' => "d",
+        'е' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' => "i",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        'і' => "i",
+        'ї' => "yi",
+        'є' => "ye",
+        'ґ' => "g",
+        'ў' => "w",
+        'ђ' => "dj",
+        'ј' => "j",
+        'љ' => "lj",
+        'њ' => "nj",
+        'ћ' => "c",
+        'џ' => "dz",
+        'ѓ' => "gj",
+        'ќ' => "kj",
+        'ѕ' => "dz",
+        _ => " ",
+    }
+}
+
+/// Arabic, Persian and Urdu letters; short vowel marks are kept when written
+fn arabic(c: char) -> &'static str {
+    match c {
+        'ا' | 'أ' | 'إ' | 'آ' | 'ٱ' | 'ى' | 'ع' => "a",
+        'ب' => "b",
+        'ت' | 'ط' | 'ٹ' => "t",
+        'ث' => "th",
+        'ج' => "j",
+        'ح' | 'ه' | 'ہ' | 'ھ' => "h",
+        'خ' => "kh",
+        'د' | 'ض' | 'ڈ' => "d",
+        'ذ' => "dh",
+        'ر' | 'ڑ' => "r",
+        'ز' | 'ظ' => "z",
+        'س' | 'ص' => "s",
+        'ش' => "sh",
+        'غ' => "gh",
+        'ف' => "f",
+        'ق' => "q",
+        'ك' | 'ک' => "k",
+        'ل' => "l",
+        'م' => "m",
+        'ن' | 'ں' => "n",
+        'و' => "w",
+        'ي' | 'ی' | 'ے' => "y",
+        'ة' => "a",
+        'پ' => "p",
+        'چ' => "ch",
+        'ژ' => "zh",
+        'گ' => "g",
+        // Fatha, damma and kasra
+        '\u{064E}' => "a",
+        '\u{064F}' => "u",
+        '\u{0650}' => "i",
+        // Hamza carriers, tatweel, tanwin, shadda and sukun
+        'ء' | 'ؤ' | 'ئ' | '\u{0640}' | '\u{064B}'..='\u{064D}' | '\u{0651}' | '\u{0652}' => "",
+        _ => " ",
+    }
+}
+
+/// Revised Romanization of a run of Hangul syllables
+fn romanize_hangul(run: &[char]) -> String {
+    const INITIALS: [&str; 19] = [
+        "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p", "h",
+    ];
+    const MEDIALS: [&str; 21] = [
+        "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we", "wi", "yu", "eu",
+        "ui", "i",
+    ];
+    const FINALS: [&str; 28] = [
+        "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p", "t", "t", "ng",
+        "t", "t", "k", "t", "p", "t",
+    ];
+    
+    let syllable = |c: char| {
+        let index = c as usize - 0xAC00;
+        format!("{}{}{}", INITIALS[index / 588], MEDIALS[index % 588 / 28], FINALS[index % 28])
+    };
+    
+    match run {
+        [] => String::new(),
+        [family, given @ ..] if run.len() <= 4 => {
+            let mut out = korean_surname(*family).map_or_else(|| syllable(*family), str::to_string);
+            if !given.is_empty() {
+                out.push(' ');
+                out.extend(given.iter().map(|c| syllable(*c)));
+            }
+            out
+        }
+        _ => run.iter().map(|c| syllable(*c)).collect(),
+    }
+}
+
+/// Customary spellings of common Korean surnames
+fn korean_surname(c: char) -> Option<&'static str> {
+    Some(match c {
+        '김' => "kim",
+        '이' => "lee",
+        '박' => "park",
+        '최' => "choi",
+        '정' => "jung",
+        '강' => "kang",
+        '조' => "cho",
+        '윤' => "yoon",
+        '장' => "jang",
+        '임' => "lim",
+        '한' => "han",
+        '오' => "oh",
+        '서' => "seo",
+        '신' => "shin",
+        '권' => "kwon",
+        '황' => "hwang",
+        '안' => "ahn",
+        '송' => "song",
+        '류' => "ryu",
+        '유' => "yoo",
+        '전' => "jeon",
+        '홍' => "hong",
+        '고' => "ko",
+        '문' => "moon",
+        '양' => "yang",
+        '손' => "son",
+        '배' => "bae",
+        '백' => "baek",
+        '허' => "heo",
+        '노' => "roh",
+        '남' => "nam",
+        '심' => "shim",
+        '곽' => "kwak",
+        '성' => "sung",
+        '차' => "cha",
+        '주' => "joo",
+        '우' => "woo",
+        '구' => "koo",
+        '민' => "min",
+        '진' => "jin",
+        '현' => "hyun",
+        '변' => "byun",
+        '추' => "choo",
+        '명' => "myung",
+        _ => return None,
+    })
+}
+
+/// Hepburn romanization of a run of kana
+fn romanize_kana(run: &[char]) -> String {
+    // Katakana share their layout with hiragana, 0x60 code points higher
+    let fold = |c: char| match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    };
+    
+    let mut out = String::new();
+    let mut geminate = false;
+    let mut i = 0;
+    while i < run.len() {
+        let c = fold(run[i]);
+        i += 1;
+        if c == 'っ' {
+            geminate = true;
+            continue;
+        }
+        let Some(base) = kana(c) else {
+            continue;
+        };
+        let mut syllable = base.to_string();
+        
+        // Palatalized syllables: き + ゃ is "kya", し + ゃ is "sha"
+        let small = run.get(i).map(|c| match fold(*c) {
+            'ゃ' => Some('a'),
+            'ゅ' => Some('u'),
+            'ょ' => Some('o'),
+            _ => None,
+        });
+        if let Some(Some(vowel)) = small.filter(|_| syllable.len() > 1 && syllable.ends_with('i')) {
+            syllable.pop();
+            if !(syllable.ends_with("sh") || syllable.ends_with("ch") || syllable == "j") {
+                syllable.push('y');
+            }
+            syllable.push(vowel);
+            i += 1;
+        }
+        
+        if std::mem::take(&mut geminate) {
+            match syllable.chars().next() {
+                Some('c') => out.push('t'),
+                Some(first) if !matches!(first, 'a' | 'i' | 'u' | 'e' | 'o' | 'n') => out.push(first),
+                _ => {}
+            }
+        }
+        out.push_str(&syllable);
+    }
+    out
+}
+
+/// Hepburn romanization of a hiragana letter
+fn kana(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        // The long vowel mark is dropped, as in passport spellings
+        _ => return None,
+    })
+}
+
+/// Pinyin of a run of Han characters, family name split off
+fn romanize_han(run: &[char]) -> String {
+    let reading = |c: char| han(c).map_or_else(|| format!("u{:x}", c as u32), str::to_string);
+    match run {
+        [] => String::new(),
+        [family, given @ ..] if run.len() <= 4 => {
+            let mut out = reading(*family);
+            if !given.is_empty() {
+                out.push(' ');
+                out.extend(given.iter().map(|c| reading(*c)));
+            }
+            out
+        }
+        _ => run.iter().map(|c| reading(*c)).collect(),
+    }
+}
+
+/// Pinyin, without tones, of common surname and given-name characters
+fn han(c: char) -> Option<&'static str> {
+    Some(match c {
+        // Family names, simplified and traditional
+        '王' | '汪' => "wang",
+        '李' | '黎' | '礼' | '禮' | '丽' | '麗' | '立' | '利' => "li",
+        '张' | '張' => "zhang",
+        '刘' | '劉' => "liu",
+        '陈' | '陳' | '辰' | '晨' => "chen",
+        '杨' | '楊' | '阳' | '陽' => "yang",
+        '黄' | '黃' => "huang",
+        '赵' | '趙' => "zhao",
+        '吴' | '吳' | '武' => "wu",
+        '周' => "zhou",
+        '徐' | '许' | '許' => "xu",
+        '孙' | '孫' => "sun",
+        '马' | '馬' => "ma",
+        '朱' => "zhu",
+        '胡' | '虎' => "hu",
+        '郭' | '国' | '國' => "guo",
+        '何' | '贺' | '賀' | '鹤' | '鶴' => "he",
+        '林' | '琳' => "lin",
+        '高' => "gao",
+        '罗' | '羅' => "luo",
+        '郑' | '鄭' | '正' => "zheng",
+        '梁' | '良' | '亮' => "liang",
+        '谢' | '謝' => "xie",
+        '宋' => "song",
+        '唐' | '汤' | '湯' => "tang",
+        '韩' | '韓' => "han",
+        '冯' | '馮' | '峰' => "feng",
+        '邓' | '鄧' => "deng",
+        '曹' => "cao",
+        '彭' | '鹏' | '鵬' => "peng",
+        '曾' => "zeng",
+        '肖' | '蕭' | '小' | '晓' | '曉' => "xiao",
+        '田' | '天' => "tian",
+        '董' | '东' | '東' | '冬' => "dong",
+        '袁' | '远' | '遠' => "yuan",
+        '潘' => "pan",
+        '于' | '余' | '宇' | '玉' => "yu",
+        '蒋' | '蔣' | '姜' | '江' => "jiang",
+        '蔡' | '财' | '財' => "cai",
+        '杜' => "du",
+        '叶' | '葉' => "ye",
+        '程' | '成' | '承' => "cheng",
+        '苏' | '蘇' => "su",
+        '魏' | '韦' | '韋' | '伟' | '偉' | '维' | '維' => "wei",
+        '吕' | '呂' | '卢' | '盧' | '陆' | '陸' => "lu",
+        '丁' => "ding",
+        '任' | '仁' => "ren",
+        '沈' => "shen",
+        '姚' | '耀' => "yao",
+        '崔' => "cui",
+        '钟' | '鍾' | '忠' | '中' => "zhong",
+        '谭' | '譚' => "tan",
+        '范' => "fan",
+        '金' | '近' | '锦' | '錦' | '进' | '進' => "jin",
+        '石' | '史' | '世' => "shi",
+        '廖' => "liao",
+        '贾' | '賈' | '家' | '佳' => "jia",
+        '夏' | '霞' => "xia",
+        '付' | '福' | '富' => "fu",
+        '方' | '芳' => "fang",
+        '白' => "bai",
+        '邹' | '鄒' => "zou",
+        '孟' => "meng",
+        '熊' => "xiong",
+        '秦' | '覃' => "qin",
+        '邱' | '秋' => "qiu",
+        '尹' => "yin",
+        '薛' | '雪' | '学' | '學' => "xue",
+        '闫' | '严' | '嚴' | '燕' => "yan",
+        '段' => "duan",
+        '雷' | '磊' => "lei",
+        '侯' => "hou",
+        '龙' | '龍' => "long",
+        '陶' | '涛' | '濤' => "tao",
+        '顾' | '顧' => "gu",
+        '毛' => "mao",
+        '郝' | '浩' => "hao",
+        '龚' | '龔' => "gong",
+        '邵' => "shao",
+        '万' | '萬' => "wan",
+        '钱' | '錢' => "qian",
+        '戴' => "dai",
+        '莫' => "mo",
+        '孔' => "kong",
+        '向' | '祥' => "xiang",
+        '习' | '習' => "xi",
+        // Given names
+        '平' | '萍' => "ping",
+        '泽' | '澤' => "ze",
+        '明' | '鸣' | '鳴' => "ming",
+        '华' | '華' => "hua",
+        '建' | '健' => "jian",
+        '军' | '軍' | '俊' | '君' => "jun",
+        '强' | '強' => "qiang",
+        '文' => "wen",
+        '海' => "hai",
+        '红' | '紅' | '洪' | '宏' | '鸿' | '鴻' => "hong",
+        '春' => "chun",
+        '德' => "de",
+        '志' | '智' | '之' => "zhi",
+        '永' | '勇' => "yong",
+        '光' => "guang",
+        '新' | '信' | '鑫' | '欣' => "xin",
+        '兴' | '興' => "xing",
+        '庆' | '慶' | '清' => "qing",
+        '云' | '雲' => "yun",
+        '宁' | '寧' => "ning",
+        '安' => "an",
+        '民' | '敏' => "min",
+        '山' => "shan",
+        '生' | '胜' | '勝' | '盛' => "sheng",
+        '荣' | '榮' => "rong",
+        '义' | '義' | '怡' | '一' => "yi",
+        '杰' | '傑' => "jie",
+        '斌' => "bin",
+        '辉' | '輝' | '慧' => "hui",
+        '刚' | '剛' => "gang",
+        '波' | '博' => "bo",
+        '飞' | '飛' => "fei",
+        '超' | '朝' => "chao",
+        '静' | '靜' | '晶' => "jing",
+        '娟' => "juan",
+        '英' => "ying",
+        '秀' => "xiu",
+        '兰' | '蘭' => "lan",
+        '梅' => "mei",
+        '婷' => "ting",
+        '子' => "zi",
+        '恩' => "en",
+        '日' => "ri",
+        '大' => "da",
+        '贵' | '貴' => "gui",
+        '发' | '發' => "fa",
+        '宝' | '寶' => "bao",
+        '玲' => "ling",
+        '振' => "zhen",
+        '瑞' => "rui",
+        '凯' | '凱' => "kai",
+        '克' => "ke",
+        '长' | '長' | '昌' => "chang",
+        '来' | '來' => "lai",
+        '思' => "si",
+        '根' => "gen",
+        '友' => "you",
+        '如' => "ru",
+        '泰' => "tai",
+        '康' => "kang",
+        '然' => "ran",
+        '轩' | '軒' => "xuan",
+        '恒' => "heng",
+        '彪' => "biao",
+        '继' | '繼' => "ji",
+        _ => return None,
+    })
+}
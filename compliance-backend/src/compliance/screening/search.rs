@@ -0,0 +1,182 @@
+//! Ad-hoc name search over ingested screening lists
+
+use super::attributes::{AttributeContribution, SubjectAttributes};
+use super::matcher::{MatchMethod, NameMatcher};
+use super::{EntityType, ScreenedEntity, ScreeningListStore};
+use crate::compliance::watchlists::WatchlistHit;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Ad-hoc name search request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchRequest {
+    /// Name to search for, in any supported script
+    pub name: String,
+    
+    /// BCP 47 language of the name, e.g. from the holder's KYC documents;
+    /// selects language-specific romanization and name order
+    pub language: Option<String>,
+    
+    /// Date of birth, nationalities and other attributes weighed with the name
+    #[serde(default)]
+    pub attributes: SubjectAttributes,
+    
+    /// Restrict the search to these lists (all lists when omitted)
+    pub lists: Option<Vec<String>>,
+    
+    /// Restrict the search to one entity type
+    pub entity_type: Option<EntityType>,
+    
+    /// Minimum score to report (defaults to the configured fuzzy match threshold)
+    pub min_score: Option<f64>,
+    
+    /// Maximum number of results
+    pub limit: Option<usize>,
+}
+
+/// Where a search hit came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HitSource {
+    /// An official sanctions or PEP list
+    Official { list: String, version: String },
+    /// A business client's own watchlist
+    ClientWatchlist { client_id: Uuid, entry_id: Uuid },
+}
+
+/// A scored search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub entity: ScreenedEntity,
+    
+    /// Provenance of the matched entry
+    pub source: HitSource,
+    
+    /// The entity name or alias that produced the best score
+    pub matched_name: String,
+    
+    /// Name score adjusted by the compared attributes
+    pub score: f64,
+    
+    /// Score of the matched name alone
+    pub name_score: f64,
+    
+    pub method: MatchMethod,
+    
+    /// Attributes compared, with their effect on the score
+    pub attributes: Vec<AttributeContribution>,
+}
+
+/// Search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    /// Entities scored, after the list indexes ruled out those that cannot match
+    pub total_candidates: usize,
+    /// Name matches dropped because their attributes lowered the score below the threshold
+    pub suppressed: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+impl From<WatchlistHit> for SearchHit {
+    fn from(hit: WatchlistHit) -> Self {
+        Self {
+            entity: ScreenedEntity {
+                id: hit.entry.id.to_string(),
+                list: "client_watchlist".to_string(),
+                name: hit.entry.value.clone(),
+                aliases: vec![],
+                entity_type: EntityType::Individual,
+                programs: hit.entry.reason.clone().into_iter().collect(),
+                date_of_birth: None,
+                nationalities: vec![],
+                document_numbers: vec![],
+                addresses: vec![],
+            },
+            source: HitSource::ClientWatchlist {
+                client_id: hit.entry.client_id,
+                entry_id: hit.entry.id,
+            },
+            matched_name: hit.entry.value,
+            score: hit.score,
+            name_score: hit.score,
+            method: hit.method,
+            attributes: vec![],
+        }
+    }
+}
+
+/// Default number of results returned by a search
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Search the screening lists for names matching the request
+///
+/// Entities are ranked by their name score adjusted by the request's
+/// attributes. Client watchlist hits, when supplied by the caller, are merged
+/// into the ranked results with their own provenance.
+pub async fn search(
+    store: &ScreeningListStore,
+    matcher: &NameMatcher,
+    request: &SearchRequest,
+    watchlist_hits: Vec<WatchlistHit>,
+) -> SearchResponse {
+    let matcher = NameMatcher {
+        threshold: request.min_score.unwrap_or(matcher.threshold),
+        ..matcher.clone()
+    };
+    let limit = request.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let language = request.language.as_deref();
+    
+    let (total_candidates, suppressed, mut hits) = store
+        .with_candidates(request.lists.as_deref(), &request.name, language, matcher.threshold, |entities| {
+            let mut total = 0;
+            let mut suppressed = 0;
+            let mut hits = Vec::new();
+            
+            for (list, entity) in entities {
+                if request.entity_type.as_ref().is_some_and(|t| *t != entity.entity_type) {
+                    continue;
+                }
+                total += 1;
+                
+                let best = entity
+                    .names()
+                    .filter_map(|name| matcher.matches_in(&request.name, language, name).map(|m| (name, m)))
+                    .max_by(|(_, a), (_, b)| a.score.total_cmp(&b.score));
+                
+                if let Some((name, result)) = best {
+                    let result = matcher.composite(result, &request.attributes, entity);
+                    if result.score < matcher.threshold {
+                        suppressed += 1;
+                        continue;
+                    }
+                    hits.push(SearchHit {
+                        entity: entity.clone(),
+                        source: HitSource::Official {
+                            list: list.name.clone(),
+                            version: list.version.clone(),
+                        },
+                        matched_name: name.to_string(),
+                        score: result.score,
+                        name_score: result.name_score,
+                        method: result.method,
+                        attributes: result.attributes,
+                    });
+                }
+            }
+            
+            (total, suppressed, hits)
+        })
+        .await;
+    
+    hits.extend(watchlist_hits.into_iter().map(SearchHit::from));
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    
+    SearchResponse {
+        query: request.name.clone(),
+        total_candidates,
+        suppressed,
+        hits,
+    }
+}
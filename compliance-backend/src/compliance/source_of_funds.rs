@@ -0,0 +1,382 @@
+//! Source-of-funds and source-of-wealth declarations for enhanced due diligence
+
+use crate::config::FundsDeclarationConfig;
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Maximum length of a vault document reference
+const MAX_VAULT_REF_LEN: usize = 256;
+
+/// What a declaration accounts for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclarationKind {
+    /// Origin of the funds used with the account
+    SourceOfFunds,
+    /// Origin of the account holder's overall wealth
+    SourceOfWealth,
+}
+
+/// Declared origin of funds or wealth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundsOrigin {
+    Employment,
+    BusinessIncome,
+    Investments,
+    SaleOfProperty,
+    SaleOfBusiness,
+    Inheritance,
+    Gift,
+    Savings,
+    Pension,
+    Loan,
+    DigitalAssetTrading,
+    Other,
+}
+
+/// One declared source with its share of the total
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredSource {
+    pub origin: FundsOrigin,
+    
+    /// Free-text explanation (employer, business, asset sold)
+    pub description: String,
+    
+    /// Amount in the minor unit of the declaration currency
+    pub amount: Option<u64>,
+}
+
+/// Kind of document supporting a declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportingDocumentType {
+    PaySlip,
+    BankStatement,
+    TaxReturn,
+    SaleContract,
+    ProbateOrWill,
+    GiftLetter,
+    CompanyAccounts,
+    LoanAgreement,
+    Other,
+}
+
+/// Reference to a supporting document held in the encrypted document vault
+///
+/// Documents never pass through this service; only their vault references do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportingDocument {
+    pub document_type: SupportingDocumentType,
+    pub vault_ref: String,
+}
+
+/// Verification workflow state of a declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclarationStatus {
+    /// Waiting for review
+    Submitted,
+    /// Assigned to an analyst
+    UnderReview,
+    /// Analyst asked for more information; a new declaration is expected
+    InformationRequested,
+    Verified,
+    Rejected,
+    /// Replaced by a later declaration of the same kind before review finished
+    Superseded,
+}
+
+impl DeclarationStatus {
+    /// Check if the declaration is still awaiting a decision
+    pub fn is_open(self) -> bool {
+        matches!(self, Self::Submitted | Self::UnderReview | Self::InformationRequested)
+    }
+}
+
+/// Declaration submitted by a business client on behalf of an account holder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundsDeclaration {
+    pub id: Uuid,
+    pub account_id: AccountId,
+    pub client_id: Uuid,
+    pub kind: DeclarationKind,
+    pub sources: Vec<DeclaredSource>,
+    
+    /// Total declared amount in the minor unit of `currency`
+    pub total_amount: u64,
+    
+    /// ISO 4217 currency code
+    pub currency: String,
+    
+    pub supporting_documents: Vec<SupportingDocument>,
+    pub status: DeclarationStatus,
+    pub submitted_at: DateTime<Utc>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_notes: Option<String>,
+    
+    /// When a verified declaration stops counting towards compliance evaluation
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+impl FundsDeclaration {
+    /// Check if the declaration is verified and in force at a point in time
+    pub fn is_verified_at(&self, at: DateTime<Utc>) -> bool {
+        self.status == DeclarationStatus::Verified
+            && self.reviewed_at.is_some_and(|reviewed| reviewed <= at)
+            && self.valid_until.map_or(true, |until| at < until)
+    }
+}
+
+/// Declaration submission
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeclarationSubmission {
+    pub kind: DeclarationKind,
+    pub sources: Vec<DeclaredSource>,
+    pub total_amount: u64,
+    pub currency: String,
+    #[serde(default)]
+    pub supporting_documents: Vec<SupportingDocument>,
+}
+
+/// Analyst decision on a declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDecision {
+    StartReview,
+    RequestInformation,
+    Verify,
+    Reject,
+}
+
+/// Verified declarations of an account at a point in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FundsEvidence {
+    pub source_of_funds: Option<Uuid>,
+    pub source_of_wealth: Option<Uuid>,
+}
+
+/// Service tracking declarations through verification
+pub struct FundsDeclarationService {
+    config: Arc<LiveConfig>,
+    declarations: RwLock<HashMap<Uuid, FundsDeclaration>>,
+}
+
+impl FundsDeclarationService {
+    /// Create a new declaration service
+    pub fn new(config: Arc<LiveConfig>) -> Self {
+        Self {
+            config,
+            declarations: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Submit a declaration, superseding any open declaration of the same kind
+    pub async fn submit(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        submission: DeclarationSubmission,
+    ) -> Result<FundsDeclaration> {
+        let config = self.config.compliance().funds_declarations.clone();
+        validate_submission(&submission, &config)?;
+        
+        let declaration = FundsDeclaration {
+            id: Uuid::new_v4(),
+            account_id: account_id.clone(),
+            client_id,
+            kind: submission.kind,
+            sources: submission.sources,
+            total_amount: submission.total_amount,
+            currency: submission.currency.to_ascii_uppercase(),
+            supporting_documents: submission.supporting_documents,
+            status: DeclarationStatus::Submitted,
+            submitted_at: Utc::now(),
+            reviewed_by: None,
+            reviewed_at: None,
+            review_notes: None,
+            valid_until: None,
+        };
+        
+        let mut declarations = self.declarations.write().await;
+        for previous in declarations.values_mut() {
+            if previous.account_id == *account_id && previous.kind == declaration.kind && previous.status.is_open() {
+                previous.status = DeclarationStatus::Superseded;
+            }
+        }
+        declarations.insert(declaration.id, declaration.clone());
+        Ok(declaration)
+    }
+    
+    /// Get a declaration
+    pub async fn get(&self, declaration_id: Uuid) -> Result<FundsDeclaration> {
+        self.declarations
+            .read()
+            .await
+            .get(&declaration_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::FundsDeclarationNotFound {
+                declaration_id: declaration_id.to_string(),
+            })
+    }
+    
+    /// Get every declaration for an account, newest first
+    pub async fn for_account(&self, account_id: &AccountId) -> Vec<FundsDeclaration> {
+        let mut declarations: Vec<_> = self
+            .declarations
+            .read()
+            .await
+            .values()
+            .filter(|d| d.account_id == *account_id)
+            .cloned()
+            .collect();
+        declarations.sort_by(|a, b| b.submitted_at.cmp(&a.submitted_at));
+        declarations
+    }
+    
+    /// Get declarations in a given status, oldest first
+    pub async fn with_status(&self, status: DeclarationStatus) -> Vec<FundsDeclaration> {
+        let mut declarations: Vec<_> = self
+            .declarations
+            .read()
+            .await
+            .values()
+            .filter(|d| d.status == status)
+            .cloned()
+            .collect();
+        declarations.sort_by_key(|d| d.submitted_at);
+        declarations
+    }
+    
+    /// Apply an analyst decision
+    pub async fn review(
+        &self,
+        declaration_id: Uuid,
+        decision: ReviewDecision,
+        reviewer: &str,
+        notes: Option<String>,
+    ) -> Result<FundsDeclaration> {
+        let validity_days = self.config.compliance().funds_declarations.validity_days;
+        let now = Utc::now();
+        let mut declarations = self.declarations.write().await;
+        let declaration = declarations
+            .get_mut(&declaration_id)
+            .ok_or_else(|| ComplianceError::FundsDeclarationNotFound {
+                declaration_id: declaration_id.to_string(),
+            })?;
+        
+        let next = match (declaration.status, decision) {
+            (DeclarationStatus::Submitted, ReviewDecision::StartReview) => DeclarationStatus::UnderReview,
+            (DeclarationStatus::Submitted | DeclarationStatus::UnderReview, ReviewDecision::RequestInformation) => {
+                DeclarationStatus::InformationRequested
+            }
+            (DeclarationStatus::Submitted | DeclarationStatus::UnderReview, ReviewDecision::Verify) => {
+                DeclarationStatus::Verified
+            }
+            (
+                DeclarationStatus::Submitted | DeclarationStatus::UnderReview | DeclarationStatus::InformationRequested,
+                ReviewDecision::Reject,
+            ) => DeclarationStatus::Rejected,
+            (status, decision) => {
+                return Err(ComplianceError::validation(
+                    "decision",
+                    format!("cannot apply {:?} to a {:?} declaration", decision, status),
+                ));
+            }
+        };
+        
+        if next == DeclarationStatus::Verified && declaration.supporting_documents.is_empty() {
+            return Err(ComplianceError::validation(
+                "decision",
+                "a declaration without supporting documents cannot be verified",
+            ));
+        }
+        
+        declaration.status = next;
+        declaration.reviewed_by = Some(reviewer.to_string());
+        declaration.reviewed_at = Some(now);
+        declaration.review_notes = notes;
+        if next == DeclarationStatus::Verified {
+            declaration.valid_until = Some(now + Duration::days(validity_days as i64));
+        }
+        
+        Ok(declaration.clone())
+    }
+    
+    /// Verified declarations of an account in force at a point in time
+    pub async fn evidence_at(&self, account_id: &AccountId, at: DateTime<Utc>) -> FundsEvidence {
+        let declarations = self.declarations.read().await;
+        let latest = |kind| {
+            declarations
+                .values()
+                .filter(|d| d.account_id == *account_id && d.kind == kind && d.is_verified_at(at))
+                .max_by_key(|d| d.reviewed_at)
+                .map(|d| d.id)
+        };
+        
+        FundsEvidence {
+            source_of_funds: latest(DeclarationKind::SourceOfFunds),
+            source_of_wealth: latest(DeclarationKind::SourceOfWealth),
+        }
+    }
+    
+    /// Check if an account's declarations satisfy a compliance level at a point in time
+    ///
+    /// `Enhanced` and above need a verified source-of-funds declaration and
+    /// `InstitutionalGrade` also a verified source-of-wealth declaration,
+    /// unless the requirement is disabled in configuration.
+    pub async fn satisfies(&self, account_id: &AccountId, level: &ComplianceLevel, at: DateTime<Utc>) -> bool {
+        let config = self.config.compliance().funds_declarations.clone();
+        let needs_funds = config.require_funds_for_enhanced && *level >= ComplianceLevel::Enhanced;
+        let needs_wealth = config.require_wealth_for_institutional && *level >= ComplianceLevel::InstitutionalGrade;
+        if !needs_funds && !needs_wealth {
+            return true;
+        }
+        
+        let evidence = self.evidence_at(account_id, at).await;
+        (!needs_funds || evidence.source_of_funds.is_some()) && (!needs_wealth || evidence.source_of_wealth.is_some())
+    }
+}
+
+fn validate_submission(submission: &DeclarationSubmission, config: &FundsDeclarationConfig) -> Result<()> {
+    if submission.sources.is_empty() {
+        return Err(ComplianceError::validation("sources", "at least one source is required"));
+    }
+    if let Some(source) = submission.sources.iter().find(|s| s.description.trim().is_empty()) {
+        return Err(ComplianceError::validation(
+            "sources",
+            format!("{:?} source must have a description", source.origin),
+        ));
+    }
+    let itemized: u64 = submission.sources.iter().filter_map(|s| s.amount).sum();
+    if itemized > submission.total_amount {
+        return Err(ComplianceError::validation("sources", "itemized amounts exceed the declared total"));
+    }
+    if submission.currency.len() != 3 || !submission.currency.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return Err(ComplianceError::validation("currency", "must be an ISO 4217 currency code"));
+    }
+    if submission.supporting_documents.len() > config.max_supporting_documents {
+        return Err(ComplianceError::validation(
+            "supporting_documents",
+            format!("at most {} documents may be attached", config.max_supporting_documents),
+        ));
+    }
+    for document in &submission.supporting_documents {
+        let vault_ref = &document.vault_ref;
+        if vault_ref.is_empty() || vault_ref.len() > MAX_VAULT_REF_LEN || vault_ref.chars().any(char::is_whitespace) {
+            return Err(ComplianceError::validation(
+                "supporting_documents",
+                format!("vault_ref must be 1-{} characters without whitespace", MAX_VAULT_REF_LEN),
+            ));
+        }
+    }
+    Ok(())
+}
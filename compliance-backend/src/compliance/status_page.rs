@@ -0,0 +1,216 @@
+//! Aggregate service statistics for a public status page
+//!
+//! The status page is served without authentication, so it only carries
+//! figures that say nothing about any one client: the screening list
+//! versions in force and their freshness, which providers are degraded, and
+//! the average proof verification latency. Latency is only reported once the
+//! window holds enough verifications, and is rounded, so a single client's
+//! verifications cannot be picked out of it. A status is computed at most
+//! once per refresh period, so polling does not reveal when verifications
+//! happen, and callers are rate limited.
+
+use super::breaker::{BreakerState, Provider, ProviderBreakers};
+use super::screening::ScreeningListStore;
+use crate::clock::{system_clock, SharedClock};
+use crate::config::StatusPageConfig;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use tokio::sync::RwLock;
+
+/// Overall state of the service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    Operational,
+    /// Some providers are failing or some lists are stale
+    Degraded,
+    /// Every provider is failing
+    MajorOutage,
+}
+
+/// State of one external provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderState {
+    pub provider: Provider,
+    pub state: ServiceState,
+}
+
+/// Version and freshness of a screening list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListFreshness {
+    pub name: String,
+    pub version: String,
+    pub ingested_at: DateTime<Utc>,
+    
+    /// Whether the list is older than the configured freshness limit
+    pub stale: bool,
+}
+
+/// Average proof verification latency over a window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationLatency {
+    /// Rounded to the configured granularity
+    pub average_ms: u64,
+    pub window_secs: u64,
+}
+
+/// Status served to the public status page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub state: ServiceState,
+    pub providers: Vec<ProviderState>,
+    pub lists: Vec<ListFreshness>,
+    
+    /// Absent while too few verifications were made in the window
+    pub verification_latency: Option<VerificationLatency>,
+    
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Requests a caller made in the current minute
+struct CallerWindow {
+    started_at: DateTime<Utc>,
+    requests: u32,
+}
+
+/// Statistics behind the public status page
+pub struct StatusPage {
+    config: StatusPageConfig,
+    
+    /// Completion time and duration in milliseconds of recent verifications
+    latencies: RwLock<VecDeque<(DateTime<Utc>, u64)>>,
+    
+    /// Last computed status
+    cached: RwLock<Option<ServiceStatus>>,
+    
+    /// Rate limit windows keyed by caller address; callers without one share a window
+    callers: RwLock<HashMap<Option<IpAddr>, CallerWindow>>,
+    
+    clock: SharedClock,
+}
+
+impl StatusPage {
+    /// Create the status page with no recorded verifications
+    pub fn new(config: StatusPageConfig) -> Self {
+        Self {
+            config,
+            latencies: RwLock::new(VecDeque::new()),
+            cached: RwLock::new(None),
+            callers: RwLock::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Record how long a proof verification took
+    pub async fn record_verification(&self, latency: std::time::Duration) {
+        let now = self.clock.now();
+        let mut latencies = self.latencies.write().await;
+        latencies.push_back((now, latency.as_millis() as u64));
+        let cutoff = now - Duration::seconds(self.config.latency_window_secs as i64);
+        while latencies.front().is_some_and(|(at, _)| *at < cutoff) {
+            latencies.pop_front();
+        }
+    }
+    
+    /// Count a request against a caller's per-minute limit
+    pub async fn admit(&self, caller: Option<IpAddr>) -> Result<()> {
+        let now = self.clock.now();
+        let minute_ago = now - Duration::minutes(1);
+        let mut callers = self.callers.write().await;
+        callers.retain(|_, window| window.started_at > minute_ago);
+        
+        let window = callers.entry(caller).or_insert(CallerWindow {
+            started_at: now,
+            requests: 0,
+        });
+        if window.requests >= self.config.requests_per_minute {
+            return Err(ComplianceError::RateLimitExceeded);
+        }
+        window.requests += 1;
+        Ok(())
+    }
+    
+    /// Current status, recomputed once the last one is older than the refresh period
+    pub async fn status(&self, lists: &ScreeningListStore, breakers: &ProviderBreakers) -> ServiceStatus {
+        let now = self.clock.now();
+        let refresh = Duration::seconds(self.config.refresh_secs as i64);
+        if let Some(status) = self.cached.read().await.as_ref() {
+            if now - status.generated_at < refresh {
+                return status.clone();
+            }
+        }
+        
+        let status = self.compute(lists, breakers, now).await;
+        *self.cached.write().await = Some(status.clone());
+        status
+    }
+    
+    async fn compute(&self, lists: &ScreeningListStore, breakers: &ProviderBreakers, now: DateTime<Utc>) -> ServiceStatus {
+        let providers: Vec<_> = breakers
+            .statuses()
+            .into_iter()
+            .map(|status| ProviderState {
+                provider: status.provider,
+                state: match status.state {
+                    BreakerState::Closed => ServiceState::Operational,
+                    BreakerState::HalfOpen => ServiceState::Degraded,
+                    BreakerState::Open => ServiceState::MajorOutage,
+                },
+            })
+            .collect();
+        
+        let stale_after = Duration::seconds(self.config.list_stale_after_secs as i64);
+        let lists: Vec<_> = lists
+            .current_versions()
+            .await
+            .into_iter()
+            .map(|record| ListFreshness {
+                stale: now - record.ingested_at > stale_after,
+                name: record.name,
+                version: record.version,
+                ingested_at: record.ingested_at,
+            })
+            .collect();
+        
+        let state = if !providers.is_empty() && providers.iter().all(|p| p.state == ServiceState::MajorOutage) {
+            ServiceState::MajorOutage
+        } else if providers.iter().any(|p| p.state != ServiceState::Operational) || lists.iter().any(|l| l.stale) {
+            ServiceState::Degraded
+        } else {
+            ServiceState::Operational
+        };
+        
+        ServiceStatus {
+            state,
+            providers,
+            lists,
+            verification_latency: self.latency(now).await,
+            generated_at: now,
+        }
+    }
+    
+    async fn latency(&self, now: DateTime<Utc>) -> Option<VerificationLatency> {
+        let cutoff = now - Duration::seconds(self.config.latency_window_secs as i64);
+        let latencies = self.latencies.read().await;
+        let samples: Vec<u64> = latencies.iter().filter(|(at, _)| *at >= cutoff).map(|(_, ms)| *ms).collect();
+        if samples.is_empty() || samples.len() < self.config.min_latency_samples {
+            return None;
+        }
+        
+        let average = samples.iter().sum::<u64>() as f64 / samples.len() as f64;
+        let rounding = self.config.latency_rounding_ms.max(1);
+        Some(VerificationLatency {
+            average_ms: (average / rounding as f64).round() as u64 * rounding,
+            window_secs: self.config.latency_window_secs,
+        })
+    }
+}
@@ -0,0 +1,323 @@
+//! Step-up verification sessions for moving an account to a higher compliance tier
+
+use crate::reload::LiveConfig;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Additional evidence an account can be asked to provide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepUpRequirement {
+    AdditionalDocument,
+    SourceOfFundsDeclaration,
+    ReLiveness,
+}
+
+/// Step-up session status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepUpStatus {
+    /// Waiting for evidence
+    Open,
+    /// All evidence provided, attestation being re-issued
+    Completed,
+    /// Attestation re-issued at the target level
+    Upgraded,
+    /// Re-issued attestation did not reach the target level
+    Failed,
+    Expired,
+    Cancelled,
+}
+
+/// Progress on a single step-up requirement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementProgress {
+    pub requirement: StepUpRequirement,
+    /// Reference to the submitted evidence (document id, declaration id)
+    pub evidence_ref: Option<String>,
+    pub satisfied_at: Option<DateTime<Utc>>,
+}
+
+/// A step-up verification session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepUpSession {
+    pub id: Uuid,
+    /// Client that opened the session
+    pub client_id: Uuid,
+    pub account_id: AccountId,
+    pub current_level: Option<ComplianceLevel>,
+    pub target_level: ComplianceLevel,
+    pub requirements: Vec<RequirementProgress>,
+    pub status: StepUpStatus,
+    /// Reasons that triggered the step-up
+    pub reasons: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    
+    /// Opened through the upgrade path; completing it runs only the checks
+    /// the target level still needs (see [`super::upgrades`])
+    #[serde(default)]
+    pub upgrade: bool,
+}
+
+impl StepUpSession {
+    /// Check if every requirement has been satisfied
+    pub fn is_satisfied(&self) -> bool {
+        self.requirements.iter().all(|r| r.satisfied_at.is_some())
+    }
+}
+
+/// Service tracking step-up sessions through to completion
+pub struct StepUpService {
+    config: Arc<LiveConfig>,
+    sessions: RwLock<HashMap<Uuid, StepUpSession>>,
+}
+
+impl StepUpService {
+    /// Create a new step-up service
+    pub fn new(config: Arc<LiveConfig>) -> Self {
+        Self {
+            config,
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Get the evidence required to reach a compliance level
+    pub fn requirements_for(target_level: &ComplianceLevel) -> Vec<StepUpRequirement> {
+        match target_level {
+            ComplianceLevel::Basic | ComplianceLevel::Standard => vec![StepUpRequirement::AdditionalDocument],
+            ComplianceLevel::Enhanced => vec![
+                StepUpRequirement::AdditionalDocument,
+                StepUpRequirement::SourceOfFundsDeclaration,
+            ],
+            ComplianceLevel::InstitutionalGrade => vec![
+                StepUpRequirement::AdditionalDocument,
+                StepUpRequirement::SourceOfFundsDeclaration,
+                StepUpRequirement::ReLiveness,
+            ],
+        }
+    }
+    
+    /// Get the evidence a compliance level requires beyond what `current_level` already did
+    pub fn upgrade_requirements(
+        current_level: &ComplianceLevel,
+        target_level: &ComplianceLevel,
+    ) -> Vec<StepUpRequirement> {
+        let held = Self::requirements_for(current_level);
+        Self::requirements_for(target_level)
+            .into_iter()
+            .filter(|requirement| !held.contains(requirement))
+            .collect()
+    }
+    
+    /// Open a step-up session, reusing the client's open session for the same target level
+    pub async fn create_session(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        current_level: Option<ComplianceLevel>,
+        target_level: ComplianceLevel,
+        reasons: Vec<String>,
+    ) -> Result<StepUpSession> {
+        let requirements = Self::requirements_for(&target_level);
+        self.open(client_id, account_id, current_level, target_level, requirements, reasons, false).await
+    }
+    
+    /// Open a session collecting the evidence an upgrade path still needs
+    ///
+    /// The client's open session for the same target level is reused.
+    pub async fn create_upgrade_session(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        current_level: ComplianceLevel,
+        target_level: ComplianceLevel,
+        requirements: Vec<StepUpRequirement>,
+    ) -> Result<StepUpSession> {
+        let reasons = vec![format!("upgrade from {:?} to {:?}", current_level, target_level)];
+        self.open(client_id, account_id, Some(current_level), target_level, requirements, reasons, true).await
+    }
+    
+    #[allow(clippy::too_many_arguments)]
+    async fn open(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        current_level: Option<ComplianceLevel>,
+        target_level: ComplianceLevel,
+        requirements: Vec<StepUpRequirement>,
+        reasons: Vec<String>,
+        upgrade: bool,
+    ) -> Result<StepUpSession> {
+        let now = Utc::now();
+        let config = self.config.compliance().step_up.clone();
+        let mut sessions = self.sessions.write().await;
+        
+        let mut open_count = 0;
+        for session in sessions.values_mut() {
+            if session.client_id != client_id
+                || session.account_id != *account_id
+                || session.status != StepUpStatus::Open
+            {
+                continue;
+            }
+            if session.expires_at <= now {
+                session.status = StepUpStatus::Expired;
+                continue;
+            }
+            if session.target_level == target_level {
+                return Ok(session.clone());
+            }
+            open_count += 1;
+        }
+        
+        if open_count >= config.max_open_sessions {
+            return Err(ComplianceError::CompliancePolicyViolation {
+                policy: format!("account {} already has an open step-up session", account_id),
+            });
+        }
+        
+        let session = StepUpSession {
+            id: Uuid::new_v4(),
+            client_id,
+            account_id: account_id.clone(),
+            current_level,
+            requirements: requirements
+                .into_iter()
+                .map(|requirement| RequirementProgress {
+                    requirement,
+                    evidence_ref: None,
+                    satisfied_at: None,
+                })
+                .collect(),
+            target_level,
+            status: StepUpStatus::Open,
+            reasons,
+            created_at: now,
+            expires_at: now + Duration::hours(config.session_ttl_hours as i64),
+            completed_at: None,
+            upgrade,
+        };
+        
+        sessions.insert(session.id, session.clone());
+        Ok(session)
+    }
+    
+    /// Get a step-up session opened by a client
+    ///
+    /// Sessions opened by other clients are reported as not found.
+    pub async fn get_session(&self, client_id: Uuid, session_id: Uuid) -> Result<StepUpSession> {
+        self.sessions
+            .read()
+            .await
+            .get(&session_id)
+            .filter(|session| session.client_id == client_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::StepUpSessionNotFound {
+                session_id: session_id.to_string(),
+            })
+    }
+    
+    /// Get every session opened for an account
+    pub async fn sessions_for(&self, account_id: &AccountId) -> Vec<StepUpSession> {
+        self.sessions
+            .read()
+            .await
+            .values()
+            .filter(|s| s.account_id == *account_id)
+            .cloned()
+            .collect()
+    }
+    
+    /// Record evidence for a requirement, completing the session when all are satisfied
+    pub async fn submit_evidence(
+        &self,
+        client_id: Uuid,
+        session_id: Uuid,
+        requirement: StepUpRequirement,
+        evidence_ref: String,
+    ) -> Result<StepUpSession> {
+        let now = Utc::now();
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(&session_id)
+            .filter(|session| session.client_id == client_id)
+            .ok_or_else(|| ComplianceError::StepUpSessionNotFound {
+                session_id: session_id.to_string(),
+            })?;
+        
+        if session.status == StepUpStatus::Open && session.expires_at <= now {
+            session.status = StepUpStatus::Expired;
+        }
+        if session.status != StepUpStatus::Open {
+            return Err(ComplianceError::validation(
+                "session_id",
+                format!("step-up session is {:?}", session.status),
+            ));
+        }
+        
+        let progress = session
+            .requirements
+            .iter_mut()
+            .find(|r| r.requirement == requirement)
+            .ok_or_else(|| {
+                ComplianceError::validation("requirement", format!("{:?} is not required by this session", requirement))
+            })?;
+        progress.evidence_ref = Some(evidence_ref);
+        progress.satisfied_at = Some(now);
+        
+        if session.is_satisfied() {
+            session.status = StepUpStatus::Completed;
+            session.completed_at = Some(now);
+        }
+        
+        Ok(session.clone())
+    }
+    
+    /// Record the outcome of re-issuing the attestation for a completed session
+    pub async fn record_reissue(
+        &self,
+        session_id: Uuid,
+        achieved_level: Option<ComplianceLevel>,
+    ) -> Result<StepUpSession> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or_else(|| ComplianceError::StepUpSessionNotFound {
+                session_id: session_id.to_string(),
+            })?;
+        
+        session.status = if achieved_level.as_ref().is_some_and(|level| *level >= session.target_level) {
+            StepUpStatus::Upgraded
+        } else {
+            StepUpStatus::Failed
+        };
+        session.current_level = achieved_level;
+        
+        Ok(session.clone())
+    }
+    
+    /// Cancel an open session opened by a client
+    pub async fn cancel(&self, client_id: Uuid, session_id: Uuid) -> Result<StepUpSession> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(&session_id)
+            .filter(|session| session.client_id == client_id)
+            .ok_or_else(|| ComplianceError::StepUpSessionNotFound {
+                session_id: session_id.to_string(),
+            })?;
+        
+        if session.status == StepUpStatus::Open {
+            session.status = StepUpStatus::Cancelled;
+        }
+        
+        Ok(session.clone())
+    }
+}
@@ -0,0 +1,332 @@
+//! Client-scoped key-value tags on attestations, cases, and accounts
+//!
+//! Tags are a business client's own metadata, such as a product line or a
+//! campaign, and are seen only by that client: two clients tagging the same
+//! account keep separate tags. Every change is sent to the client's webhook
+//! as a `tags.changed` event, and list endpoints filter their results by a
+//! [`TagFilter`] over the client's tags.
+
+use crate::clock::{system_clock, SharedClock};
+use crate::storage::{OutboxMessage, UnitOfWork, WriteBatch};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Webhook event sent to the client when a record's tags change
+pub const TAGS_CHANGED_EVENT: &str = "tags.changed";
+
+/// Tags a single record may carry
+pub const MAX_TAGS_PER_RECORD: usize = 50;
+
+/// Longest tag key
+pub const MAX_KEY_LENGTH: usize = 64;
+
+/// Longest tag value
+pub const MAX_VALUE_LENGTH: usize = 256;
+
+/// A record's tags, by key
+pub type Tags = BTreeMap<String, String>;
+
+/// Kind of record a tag is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaggedKind {
+    Attestation,
+    /// Fraud case or override approval case
+    Case,
+    Account,
+}
+
+impl TaggedKind {
+    /// Canonical form of the id of a record of this kind
+    ///
+    /// Attestations and cases are identified by UUID, accounts by account id.
+    pub fn canonical_id(&self, id: &str) -> Result<String> {
+        match self {
+            Self::Attestation | Self::Case => Uuid::parse_str(id)
+                .map(|id| id.to_string())
+                .map_err(|_| ComplianceError::validation("id", "must be a UUID")),
+            Self::Account => AccountId::parse(id).map(|id| id.to_string()),
+        }
+    }
+}
+
+/// Check a tag key given in `field`: 1 to 64 lowercase letters, digits,
+/// `_`, `-` and `.`
+pub fn check_key(field: &str, key: &str) -> Result<()> {
+    let valid = (1..=MAX_KEY_LENGTH).contains(&key.len())
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-' | b'.'));
+    if !valid {
+        return Err(ComplianceError::validation(
+            field,
+            format!(
+                "invalid tag key {:?}: keys are 1 to {} lowercase letters, digits, '_', '-' and '.'",
+                key, MAX_KEY_LENGTH
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Changes to a record's tags
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagUpdate {
+    /// Tags to add or overwrite
+    #[serde(default)]
+    pub set: Tags,
+    /// Keys to remove; a key both set and removed is set
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl TagUpdate {
+    /// Check every key and value of the update
+    pub fn validate(&self) -> Result<()> {
+        for (key, value) in &self.set {
+            check_key("set", key)?;
+            if value.chars().count() > MAX_VALUE_LENGTH || value.chars().any(char::is_control) {
+                return Err(ComplianceError::validation(
+                    "set",
+                    format!("value of {:?} must be at most {} printable characters", key, MAX_VALUE_LENGTH),
+                ));
+            }
+        }
+        for (i, key) in self.remove.iter().enumerate() {
+            check_key(&format!("remove[{}]", i), key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Conjunction of tag conditions, each on a key alone or on a key and value
+///
+/// Parsed from `key:value` and `key` terms separated by commas, so that
+/// `product:wallet,campaign` matches records tagged `product` = `wallet`
+/// that also carry a `campaign` tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    terms: Vec<(String, Option<String>)>,
+}
+
+impl TagFilter {
+    /// Parse a filter, failing on malformed keys
+    pub fn parse(filter: &str) -> Result<Self> {
+        let terms = filter
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(|term| {
+                let (key, value) = match term.split_once(':') {
+                    Some((key, value)) => (key, Some(value.to_string())),
+                    None => (term, None),
+                };
+                check_key("tag", key)?;
+                Ok((key.to_string(), value))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { terms })
+    }
+    
+    /// Parse an optional filter from a query parameter
+    pub fn from_query(filter: Option<&str>) -> Result<Option<Self>> {
+        filter.map(Self::parse).transpose()
+    }
+    
+    /// Whether the filter has no condition and matches every record
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+    
+    /// Whether `tags` meet every condition
+    pub fn matches(&self, tags: &Tags) -> bool {
+        self.terms.iter().all(|(key, value)| match (tags.get(key), value) {
+            (Some(actual), Some(expected)) => actual == expected,
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+    }
+}
+
+/// A record's tags as seen by one client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaggedRecord {
+    pub kind: TaggedKind,
+    pub id: String,
+    pub tags: Tags,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How an update changed a record's tags
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagChanges {
+    /// Keys that were not set before, with their values
+    pub added: Tags,
+    /// Keys whose values changed, with their new values
+    pub changed: Tags,
+    /// Keys that were removed
+    pub removed: Vec<String>,
+}
+
+impl TagChanges {
+    fn between(before: &Tags, after: &Tags) -> Self {
+        let mut changes = Self::default();
+        for (key, value) in after {
+            match before.get(key) {
+                None => {
+                    changes.added.insert(key.clone(), value.clone());
+                }
+                Some(previous) if previous != value => {
+                    changes.changed.insert(key.clone(), value.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = before.keys().filter(|key| !after.contains_key(*key)).cloned().collect();
+        changes
+    }
+    
+    /// Whether the update changed nothing
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Store of every client's tags
+pub struct TagService {
+    /// Tags by client, kind and record id
+    tags: RwLock<HashMap<(Uuid, TaggedKind, String), TaggedRecord>>,
+    
+    /// Commits tag change webhooks to the outbox
+    storage: Arc<dyn UnitOfWork>,
+    
+    clock: SharedClock,
+}
+
+impl TagService {
+    /// Create an empty store, committing change webhooks to `storage`
+    pub fn new(storage: Arc<dyn UnitOfWork>) -> Self {
+        Self {
+            tags: RwLock::new(HashMap::new()),
+            storage,
+            clock: system_clock(),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// A record's tags for a client, empty when it has none
+    pub async fn get(&self, client_id: Uuid, kind: TaggedKind, id: &str) -> Result<Tags> {
+        let id = kind.canonical_id(id)?;
+        Ok(self
+            .tags
+            .read()
+            .await
+            .get(&(client_id, kind, id))
+            .map(|record| record.tags.clone())
+            .unwrap_or_default())
+    }
+    
+    /// Apply an update to a record's tags
+    ///
+    /// A change that leaves the record's tags as they were is not reported.
+    /// A record left without tags is forgotten.
+    pub async fn update(&self, client_id: Uuid, kind: TaggedKind, id: &str, update: TagUpdate) -> Result<TaggedRecord> {
+        let id = kind.canonical_id(id)?;
+        update.validate()?;
+        let key = (client_id, kind, id.clone());
+        let mut tags = self.tags.write().await;
+        let before = tags.get(&key).map(|record| record.tags.clone()).unwrap_or_default();
+        let mut after = before.clone();
+        for removed in &update.remove {
+            after.remove(removed);
+        }
+        after.extend(update.set);
+        if after.len() > MAX_TAGS_PER_RECORD {
+            return Err(ComplianceError::validation(
+                "set",
+                format!("a record may carry at most {} tags", MAX_TAGS_PER_RECORD),
+            ));
+        }
+        
+        let now = self.clock.now();
+        let changes = TagChanges::between(&before, &after);
+        let record = TaggedRecord {
+            kind,
+            id: id.clone(),
+            tags: after,
+            updated_at: now,
+        };
+        if changes.is_empty() {
+            return Ok(tags.get(&key).cloned().unwrap_or(record));
+        }
+        
+        let payload = serde_json::json!({
+            "kind": kind,
+            "id": id,
+            "tags": record.tags,
+            "added": changes.added,
+            "changed": changes.changed,
+            "removed": changes.removed,
+        });
+        let mut batch = WriteBatch::default();
+        batch.outbox.push(OutboxMessage::new(client_id, TAGS_CHANGED_EVENT, payload, now));
+        self.storage.commit(&batch).await?;
+        
+        if record.tags.is_empty() {
+            tags.remove(&key);
+        } else {
+            tags.insert(key, record.clone());
+        }
+        Ok(record)
+    }
+    
+    /// A client's tagged records of a kind matching `filter`, by id
+    pub async fn list(&self, client_id: Uuid, kind: TaggedKind, filter: &TagFilter) -> Vec<TaggedRecord> {
+        let mut records: Vec<TaggedRecord> = self
+            .tags
+            .read()
+            .await
+            .iter()
+            .filter(|((owner, record_kind, _), record)| {
+                *owner == client_id && *record_kind == kind && filter.matches(&record.tags)
+            })
+            .map(|(_, record)| record.clone())
+            .collect();
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        records
+    }
+    
+    /// Keep the items whose record matches `filter` under the client's tags
+    ///
+    /// `id` gives the canonical id of an item's record. Nothing is removed
+    /// without a filter, so list endpoints can pass their optional filter
+    /// straight through.
+    pub async fn retain<T>(
+        &self,
+        client_id: Uuid,
+        kind: TaggedKind,
+        filter: Option<&TagFilter>,
+        items: &mut Vec<T>,
+        id: impl Fn(&T) -> String,
+    ) {
+        let Some(filter) = filter.filter(|filter| !filter.is_empty()) else {
+            return;
+        };
+        let tags = self.tags.read().await;
+        items.retain(|item| {
+            tags.get(&(client_id, kind, id(item)))
+                .is_some_and(|record| filter.matches(&record.tags))
+        });
+    }
+}
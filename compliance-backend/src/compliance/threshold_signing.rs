@@ -0,0 +1,290 @@
+//! Collection of t-of-n signatures over attestations
+//!
+//! Attestations reaching a level with a configured threshold policy, such as
+//! `InstitutionalGrade`, are not trusted on the automated signer's key alone.
+//! A signing request is opened for the attestation and the automated signer
+//! contributes its partial signature straight away when the policy lists it.
+//! The remaining signers, such as a compliance officer's HSM key, sign the
+//! request's message out of band and submit their partial signatures. Once
+//! the threshold is met the partials are combined; a request still short of
+//! it when its timeout passes is closed as timed out and must be reopened.
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::LevelSigningPolicy;
+use crate::crypto::commitment::compliance_level_code;
+use crate::crypto::{
+    attestation_commitment, AttestationSigner, PartialSignature, ThresholdPolicy, ThresholdSignature, TrustedKeys,
+};
+use crate::reload::LiveConfig;
+use crate::types::{AccountId, ComplianceAttestation, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Domain separator of attestation cosignatures
+const SIGNATURE_DOMAIN: &[u8] = b"zerotrust-compliance-attestation-cosign-v1";
+
+/// Message every signer of an attestation signs
+///
+/// The domain separator, the attestation commitment and the level the
+/// signers vouch for.
+pub fn signing_message(attestation: &ComplianceAttestation, level: &ComplianceLevel) -> Vec<u8> {
+    let mut message = SIGNATURE_DOMAIN.to_vec();
+    message.extend_from_slice(&attestation_commitment(attestation).to_bytes());
+    message.extend_from_slice(&compliance_level_code(level).to_be_bytes());
+    message
+}
+
+/// Policy applying to an attestation at `level`
+///
+/// The policy with the highest minimum level the attestation reaches; `None`
+/// when single-key issuance suffices.
+pub fn policy_for<'a>(policies: &'a [LevelSigningPolicy], level: &ComplianceLevel) -> Option<&'a LevelSigningPolicy> {
+    policies
+        .iter()
+        .filter(|policy| policy.min_level <= *level)
+        .max_by(|a, b| a.min_level.cmp(&b.min_level))
+}
+
+/// Where a signing request stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningRequestStatus {
+    /// Waiting for partial signatures
+    Pending,
+    /// The threshold was met and the signature combined
+    Complete,
+    /// The timeout passed before the threshold was met
+    TimedOut,
+}
+
+impl SigningRequestStatus {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Complete => "complete",
+            Self::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Collection of partial signatures over one attestation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningRequest {
+    pub id: Uuid,
+    pub account_id: AccountId,
+    pub attestation_id: Uuid,
+    /// Level the signers vouch for
+    pub level: ComplianceLevel,
+    pub policy: ThresholdPolicy,
+    /// Hex-encoded message each signer signs
+    pub message: String,
+    pub partials: Vec<PartialSignature>,
+    pub status: SigningRequestStatus,
+    /// Combined signature, once the threshold is met
+    pub signature: Option<ThresholdSignature>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SigningRequest {
+    /// Signers of the policy that have not signed yet
+    pub fn outstanding(&self) -> Vec<&str> {
+        self.policy
+            .signers
+            .iter()
+            .filter(|signer| !self.partials.iter().any(|partial| &partial.key_id == *signer))
+            .map(String::as_str)
+            .collect()
+    }
+    
+    /// Close the request as timed out if it is pending past its timeout
+    fn expire(&mut self, now: DateTime<Utc>) -> bool {
+        let due = self.status == SigningRequestStatus::Pending && self.expires_at <= now;
+        if due {
+            self.status = SigningRequestStatus::TimedOut;
+        }
+        due
+    }
+}
+
+/// Signing requests of attestations needing several signers
+pub struct ThresholdSigningService {
+    /// Live configuration holding the policies and timeout
+    config: Arc<LiveConfig>,
+    
+    /// Keys partial signatures are verified against
+    trusted_keys: Arc<TrustedKeys>,
+    
+    clock: SharedClock,
+    
+    requests: RwLock<HashMap<Uuid, SigningRequest>>,
+}
+
+impl ThresholdSigningService {
+    /// Create a service with no open requests
+    pub fn new(config: Arc<LiveConfig>, trusted_keys: Arc<TrustedKeys>) -> Self {
+        Self {
+            config,
+            trusted_keys,
+            clock: system_clock(),
+            requests: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Threshold policy an attestation at `level` is signed under, if any
+    pub fn policy(&self, level: &ComplianceLevel) -> Option<ThresholdPolicy> {
+        let compliance = self.config.compliance();
+        policy_for(&compliance.attestation.threshold_signing.policies, level).map(|policy| policy.policy.clone())
+    }
+    
+    /// Open a signing request for an attestation at `level`
+    ///
+    /// `signer` contributes its partial signature at once when the policy
+    /// lists its key. Fails when no policy applies to the level.
+    pub async fn open(
+        &self,
+        attestation: &ComplianceAttestation,
+        level: ComplianceLevel,
+        signer: &AttestationSigner,
+    ) -> Result<SigningRequest> {
+        let policy = self.policy(&level).ok_or_else(|| {
+            ComplianceError::validation("level", format!("no threshold signing policy applies to {:?}", level))
+        })?;
+        let timeout = self.config.compliance().attestation.threshold_signing.timeout_secs;
+        let now = self.clock.now();
+        let message = signing_message(attestation, &level);
+        
+        let mut request = SigningRequest {
+            id: Uuid::new_v4(),
+            account_id: attestation.account_id.clone(),
+            attestation_id: attestation.id,
+            level,
+            policy,
+            message: hex::encode(&message),
+            partials: Vec::new(),
+            status: SigningRequestStatus::Pending,
+            signature: None,
+            created_at: now,
+            expires_at: now + Duration::seconds(timeout as i64),
+        };
+        if request.policy.allows(signer.key_id()) {
+            request.partials.push(PartialSignature {
+                key_id: signer.key_id().to_string(),
+                signature: hex::encode(signer.sign(&message)?),
+            });
+            complete_if_met(&mut request)?;
+        }
+        self.requests.write().await.insert(request.id, request.clone());
+        Ok(request)
+    }
+    
+    /// Add a signer's partial signature to a pending request
+    ///
+    /// The partial must come from a signer of the request's policy that has
+    /// not signed yet, and verify over the request's message. The request
+    /// completes when the threshold is met.
+    pub async fn submit(&self, request_id: Uuid, partial: PartialSignature) -> Result<SigningRequest> {
+        let now = self.clock.now();
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(&request_id).ok_or_else(|| not_found(request_id))?;
+        request.expire(now);
+        if request.status != SigningRequestStatus::Pending {
+            return Err(ComplianceError::SigningRequestClosed {
+                request_id: request_id.to_string(),
+                status: request.status.name().to_string(),
+            });
+        }
+        if !request.policy.allows(&partial.key_id) {
+            return Err(ComplianceError::validation("key_id", "is not a signer of the request's policy"));
+        }
+        if request.partials.iter().any(|signed| signed.key_id == partial.key_id) {
+            return Err(ComplianceError::validation("key_id", "has already signed the request"));
+        }
+        let message = hex::decode(&request.message).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        partial
+            .verify(&self.trusted_keys, &message)
+            .map_err(|e| ComplianceError::validation("signature", e.to_string()))?;
+        
+        request.partials.push(partial);
+        complete_if_met(request)?;
+        Ok(request.clone())
+    }
+    
+    /// A signing request
+    pub async fn get(&self, request_id: Uuid) -> Result<SigningRequest> {
+        let now = self.clock.now();
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(&request_id).ok_or_else(|| not_found(request_id))?;
+        request.expire(now);
+        Ok(request.clone())
+    }
+    
+    /// Signing requests, newest first, optionally in one status
+    pub async fn list(&self, status: Option<SigningRequestStatus>) -> Vec<SigningRequest> {
+        let now = self.clock.now();
+        let mut requests = self.requests.write().await;
+        let mut listed: Vec<SigningRequest> = requests
+            .values_mut()
+            .filter_map(|request| {
+                request.expire(now);
+                status.map_or(true, |status| request.status == status).then(|| request.clone())
+            })
+            .collect();
+        listed.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        listed
+    }
+    
+    /// Close pending requests whose timeout has passed
+    ///
+    /// Returns the requests that timed out.
+    pub async fn expire_due(&self) -> Vec<SigningRequest> {
+        let now = self.clock.now();
+        let mut requests = self.requests.write().await;
+        requests
+            .values_mut()
+            .filter_map(|request| request.expire(now).then(|| request.clone()))
+            .collect()
+    }
+    
+    /// Verify the combined signature of an attestation at `level`
+    ///
+    /// Checked against the policy currently configured for the level, so
+    /// raising a threshold also applies to signatures combined before.
+    pub fn verify(
+        &self,
+        attestation: &ComplianceAttestation,
+        level: &ComplianceLevel,
+        signature: &ThresholdSignature,
+    ) -> Result<()> {
+        let policy = self.policy(level).ok_or_else(|| {
+            ComplianceError::validation("level", format!("no threshold signing policy applies to {:?}", level))
+        })?;
+        signature.verify(&policy, &self.trusted_keys, &signing_message(attestation, level))
+    }
+}
+
+/// Combine the partials once the threshold is met
+fn complete_if_met(request: &mut SigningRequest) -> Result<()> {
+    if request.partials.len() >= request.policy.threshold {
+        request.signature = Some(ThresholdSignature::combine(&request.policy, &request.partials)?);
+        request.status = SigningRequestStatus::Complete;
+    }
+    Ok(())
+}
+
+fn not_found(request_id: Uuid) -> ComplianceError {
+    ComplianceError::SigningRequestNotFound {
+        request_id: request_id.to_string(),
+    }
+}
@@ -0,0 +1,125 @@
+//! Upgrade path from an account's current compliance level to a higher one
+//!
+//! Moving up a level only takes what the account's attestation in force and
+//! its verified declarations do not already cover: the step-up evidence the
+//! target level adds over the current one, and an AML reassessment when the
+//! account's risk is too high for the target. Outstanding evidence is
+//! collected through a step-up session. The level an attestation holds
+//! follows from its check outcomes and the account's verified declarations,
+//! so only a reassessment re-issues it; otherwise the account reaches the
+//! target level once its declarations are verified, without redoing
+//! onboarding.
+
+use super::attestation_events::AttestationState;
+use super::step_up::{StepUpRequirement, StepUpService};
+use super::ComplianceService;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use serde::Serialize;
+
+/// What moving an account to a higher compliance level takes
+#[derive(Debug, Clone, Serialize)]
+pub struct UpgradePlan {
+    pub account_id: AccountId,
+    pub current_level: ComplianceLevel,
+    pub target_level: ComplianceLevel,
+    
+    /// Evidence the account still has to provide
+    pub requirements: Vec<StepUpRequirement>,
+    
+    /// Whether the account's AML risk is too high for the target level and has to be reassessed
+    pub aml_reassessment: bool,
+}
+
+/// Highest AML risk a compliance level admits
+fn max_aml_risk(level: &ComplianceLevel) -> AmlRiskLevel {
+    match level {
+        ComplianceLevel::Basic => AmlRiskLevel::Critical,
+        ComplianceLevel::Standard => AmlRiskLevel::Medium,
+        ComplianceLevel::Enhanced | ComplianceLevel::InstitutionalGrade => AmlRiskLevel::Low,
+    }
+}
+
+impl ComplianceService {
+    /// Work out what moving an account to `target_level` takes
+    ///
+    /// The account needs an attestation in force at some compliance level;
+    /// accounts without one go through onboarding instead. A plan for a
+    /// level the account already holds needs nothing.
+    pub async fn upgrade_plan(&self, account_id: &AccountId, target_level: ComplianceLevel) -> Result<UpgradePlan> {
+        let (state, current_level) = self.upgrade_basis(account_id).await?;
+        if current_level >= target_level {
+            return Ok(UpgradePlan {
+                account_id: account_id.clone(),
+                current_level,
+                target_level,
+                requirements: Vec::new(),
+                aml_reassessment: false,
+            });
+        }
+        
+        let mut requirements = StepUpService::upgrade_requirements(&current_level, &target_level);
+        if self.funds.satisfies(account_id, &target_level, self.clock.now()).await {
+            requirements.retain(|requirement| *requirement != StepUpRequirement::SourceOfFundsDeclaration);
+        }
+        Ok(UpgradePlan {
+            account_id: account_id.clone(),
+            aml_reassessment: state.attestation.aml_risk_level > max_aml_risk(&target_level),
+            current_level,
+            target_level,
+            requirements,
+        })
+    }
+    
+    /// Move an account towards `target_level`, running only the checks its plan needs
+    ///
+    /// Evidence collected outside the service is taken as provided, but
+    /// declarations must be verified. When the account's AML risk is too
+    /// high for the target and its declarations are verified, its checks are
+    /// re-run, since AML risk is assessed together with KYC and sanctions.
+    /// Otherwise nothing is issued and the attestation in force is returned
+    /// as is: re-issuing it unchanged would not change the level it holds.
+    pub async fn upgrade_attestation(
+        &self,
+        account_id: &AccountId,
+        target_level: ComplianceLevel,
+    ) -> Result<ComplianceAttestation> {
+        let plan = self.upgrade_plan(account_id, target_level).await?;
+        let (state, _) = self.upgrade_basis(account_id).await?;
+        if plan.current_level >= plan.target_level
+            || !plan.aml_reassessment
+            || !self.funds.satisfies(account_id, &plan.target_level, self.clock.now()).await
+        {
+            return Ok(state.attestation);
+        }
+        self.update_compliance_status(account_id).await
+    }
+    
+    /// What an account is missing for `plan`'s target level besides the evidence in the plan
+    ///
+    /// Only verified declarations can be missing: with them in force, a plan
+    /// without an AML reassessment means the account already holds the level.
+    pub async fn upgrade_shortfall(&self, plan: &UpgradePlan) -> Option<String> {
+        if !self.funds.satisfies(&plan.account_id, &plan.target_level, self.clock.now()).await {
+            return Some(format!(
+                "{:?} is missing verified source-of-funds or source-of-wealth declarations",
+                plan.target_level
+            ));
+        }
+        None
+    }
+    
+    /// An account's attestation in force and the compliance level it holds
+    async fn upgrade_basis(&self, account_id: &AccountId) -> Result<(AttestationState, ComplianceLevel)> {
+        let no_level =
+            || ComplianceError::validation("account_id", "account holds no compliance level to upgrade from");
+        let state = self
+            .events
+            .project(account_id, None)
+            .await?
+            .filter(|state| state.is_valid_at(self.clock.now()))
+            .ok_or_else(no_level)?;
+        let level = self.highest_compliance_level(&state.attestation).await.ok_or_else(no_level)?;
+        Ok((state, level))
+    }
+}
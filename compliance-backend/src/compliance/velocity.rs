@@ -0,0 +1,499 @@
+//! Pre-transaction authorization against compliance level, risk, and velocity limits
+
+use crate::compliance::country_risk::{risk_level, CountryRiskService};
+use crate::clock::{system_clock, SharedClock};
+use crate::compliance::watchlists::{WatchlistHit, WatchlistService};
+use crate::config::{RiskThresholds, TransactionMonitoringConfig};
+use crate::reload::LiveConfig;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// A transaction proposed by a business client before execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionRequest {
+    /// Transaction amount in the asset's base unit
+    pub amount: u64,
+    
+    /// Asset identifier (faucet id or ticker)
+    pub asset: Option<String>,
+    
+    /// Counterparty account or address
+    pub counterparty: Option<String>,
+    
+    /// Counterparty country as an ISO 3166-1 alpha-2 code, when known
+    #[serde(default)]
+    pub counterparty_country: Option<String>,
+    
+    /// Transaction type (e.g. "transfer", "withdrawal")
+    pub transaction_type: String,
+}
+
+/// Outcome of a transaction authorization
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorizationOutcome {
+    Allow,
+    Deny,
+    StepUp,
+}
+
+/// Authorization decision returned to the client and recorded for monitoring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationDecision {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub account_id: AccountId,
+    pub outcome: AuthorizationOutcome,
+    pub reasons: Vec<String>,
+    pub amount: u64,
+    pub transaction_type: String,
+    /// Compliance level the account currently satisfies
+    pub compliance_level: Option<ComplianceLevel>,
+    /// Compliance level the transaction requires
+    pub required_level: ComplianceLevel,
+    /// Client watchlist entries that influenced the decision
+    pub watchlist_hits: Vec<WatchlistHit>,
+    /// Step-up session opened when the outcome is `StepUp`
+    pub step_up_session: Option<Uuid>,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Version of the authorization rules applied by [`decide`]
+///
+/// Bump whenever a change to the rules can change the outcome for the same
+/// inputs, so earlier decisions are not replayed against different rules.
+pub const AUTHORIZATION_RULES_VERSION: u32 = 1;
+
+/// Risk of the counterparty's country as assessed when deciding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryRiskInput {
+    pub score: f64,
+    pub sources: Vec<String>,
+    pub dataset_version: String,
+}
+
+/// Previously allowed transactions of the account as of the decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VelocityWindow {
+    /// Transactions in the preceding hour
+    pub hourly_count: u32,
+    /// Transactions in the preceding day
+    pub daily_count: u32,
+    /// Volume of the transactions in the preceding day
+    pub daily_volume: u64,
+}
+
+/// Everything an authorization decision was made from
+///
+/// [`decide`] depends on nothing else, so replaying a decision against its
+/// inputs reproduces it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionInputs {
+    pub rules_version: u32,
+    pub account_id: AccountId,
+    pub request: TransactionRequest,
+    /// Attestation in force, carrying the providers' KYC, AML and sanctions outcomes
+    pub attestation: Option<ComplianceAttestation>,
+    pub compliance_level: Option<ComplianceLevel>,
+    /// Client blocklist entry matching the counterparty
+    pub blocked_counterparty: Option<WatchlistHit>,
+    /// Client allowlist entry matching the counterparty, when not blocked
+    pub allowlisted_counterparty: Option<WatchlistHit>,
+    pub counterparty_country_risk: Option<CountryRiskInput>,
+    pub risk_thresholds: RiskThresholds,
+    /// Amount and velocity limits
+    pub limits: TransactionMonitoringConfig,
+    pub velocity: VelocityWindow,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// What the authorization rules concluded from a decision's inputs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionOutcome {
+    pub outcome: AuthorizationOutcome,
+    pub reasons: Vec<String>,
+    pub required_level: ComplianceLevel,
+}
+
+/// A recorded decision re-run against its stored inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionReplay {
+    pub decision_id: Uuid,
+    /// Whether the replay reached the recorded outcome, for the same reasons
+    pub deterministic: bool,
+    pub recorded: DecisionOutcome,
+    pub replayed: DecisionOutcome,
+    pub inputs: DecisionInputs,
+    pub replayed_at: DateTime<Utc>,
+}
+
+/// Allowed transactions per account, with their decision times and amounts
+type Ledger = HashMap<AccountId, Vec<(DateTime<Utc>, u64)>>;
+
+/// Service enforcing amount and velocity limits before transactions execute
+pub struct VelocityService {
+    /// Live configuration holding amount and velocity limits
+    config: Arc<LiveConfig>,
+    
+    /// Client watchlists consulted for the counterparty
+    watchlists: Arc<WatchlistService>,
+    
+    /// Country risk consulted for the counterparty's geography
+    country_risk: Arc<CountryRiskService>,
+    
+    /// Allowed transactions per account, used for velocity windows
+    ledger: RwLock<Ledger>,
+    
+    /// Recorded authorization decisions
+    decisions: RwLock<Vec<AuthorizationDecision>>,
+    
+    /// Inputs of each recorded decision
+    inputs: RwLock<HashMap<Uuid, DecisionInputs>>,
+    
+    /// Time source for velocity windows
+    clock: SharedClock,
+}
+
+impl VelocityService {
+    /// Create a new velocity service
+    pub fn new(config: Arc<LiveConfig>, watchlists: Arc<WatchlistService>, country_risk: Arc<CountryRiskService>) -> Self {
+        Self {
+            config,
+            watchlists,
+            country_risk,
+            ledger: RwLock::new(HashMap::new()),
+            decisions: RwLock::new(Vec::new()),
+            inputs: RwLock::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Get the compliance level required for a transaction amount
+    pub fn required_level(&self, amount: u64) -> ComplianceLevel {
+        required_level(amount, &self.config.compliance().aml.transaction_monitoring)
+    }
+    
+    /// Authorize a proposed transaction and record the decision
+    ///
+    /// Everything the decision is made from is recorded with it, so it can be
+    /// replayed with [`Self::replay_decision`]. The velocity ledger stays
+    /// locked from the limit check until the decision is recorded, so
+    /// concurrent transactions cannot both fit under a limit only one fits.
+    /// The counterparty's watchlist entries are looked up before the ledger
+    /// is locked, so authorizations never wait on each other's lookups.
+    pub async fn authorize(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        attestation: Option<&ComplianceAttestation>,
+        compliance_level: Option<ComplianceLevel>,
+        request: &TransactionRequest,
+    ) -> AuthorizationDecision {
+        let counterparty = self.counterparty_hits(client_id, request).await;
+        let mut ledger = self.ledger.write().await;
+        let history = ledger.get(account_id).map(Vec::as_slice).unwrap_or_default();
+        let inputs = self.snapshot(account_id, attestation, compliance_level, request, counterparty, history);
+        let DecisionOutcome { outcome, reasons, required_level } = decide(&inputs);
+        
+        let decision = AuthorizationDecision {
+            id: Uuid::new_v4(),
+            client_id,
+            account_id: account_id.clone(),
+            outcome,
+            reasons,
+            amount: request.amount,
+            transaction_type: request.transaction_type.clone(),
+            compliance_level: inputs.compliance_level.clone(),
+            required_level,
+            watchlist_hits: inputs
+                .blocked_counterparty
+                .iter()
+                .chain(&inputs.allowlisted_counterparty)
+                .cloned()
+                .collect(),
+            step_up_session: None,
+            decided_at: inputs.decided_at,
+        };
+        
+        self.record(&mut ledger, &decision, inputs).await;
+        decision
+    }
+    
+    /// Re-run a recorded decision against the inputs it was made from
+    ///
+    /// Decisions made under an earlier version of the rules cannot be
+    /// replayed, since only the current rules are available.
+    pub async fn replay_decision(&self, decision_id: Uuid) -> Result<DecisionReplay> {
+        let not_found = || ComplianceError::DecisionNotFound {
+            decision_id: decision_id.to_string(),
+        };
+        let decision = self
+            .decisions
+            .read()
+            .await
+            .iter()
+            .rev()
+            .find(|d| d.id == decision_id)
+            .cloned()
+            .ok_or_else(not_found)?;
+        let inputs = self.inputs.read().await.get(&decision_id).cloned().ok_or_else(not_found)?;
+        if inputs.rules_version != AUTHORIZATION_RULES_VERSION {
+            return Err(ComplianceError::validation(
+                "decision_id",
+                format!(
+                    "decision was made under authorization rules version {}, this deployment runs version {}",
+                    inputs.rules_version, AUTHORIZATION_RULES_VERSION
+                ),
+            ));
+        }
+        
+        let recorded = DecisionOutcome {
+            outcome: decision.outcome,
+            reasons: decision.reasons,
+            required_level: decision.required_level,
+        };
+        let replayed = decide(&inputs);
+        Ok(DecisionReplay {
+            decision_id,
+            deterministic: replayed == recorded,
+            recorded,
+            replayed,
+            inputs,
+            replayed_at: self.clock.now(),
+        })
+    }
+    
+    /// Client blocklist and, when not blocked, allowlist entries matching the counterparty
+    async fn counterparty_hits(
+        &self,
+        client_id: Uuid,
+        request: &TransactionRequest,
+    ) -> (Option<WatchlistHit>, Option<WatchlistHit>) {
+        let Some(counterparty) = &request.counterparty else {
+            return (None, None);
+        };
+        match self.watchlists.check_address(client_id, counterparty).await {
+            Some(blocked) => (Some(blocked), None),
+            None => (None, self.watchlists.allowlisted(client_id, counterparty).await),
+        }
+    }
+    
+    /// Gather everything a decision depends on at the current time
+    ///
+    /// `counterparty` holds the counterparty's blocklist and allowlist hits,
+    /// and `history` is the account's velocity ledger.
+    fn snapshot(
+        &self,
+        account_id: &AccountId,
+        attestation: Option<&ComplianceAttestation>,
+        compliance_level: Option<ComplianceLevel>,
+        request: &TransactionRequest,
+        counterparty: (Option<WatchlistHit>, Option<WatchlistHit>),
+        history: &[(DateTime<Utc>, u64)],
+    ) -> DecisionInputs {
+        let now = self.clock.now();
+        let compliance = self.config.compliance();
+        let (blocked_counterparty, allowlisted_counterparty) = counterparty;
+        
+        let counterparty_country_risk = request.counterparty_country.as_ref().map(|country| {
+            let (score, sources) = self.country_risk.country_risk(&country.to_ascii_uppercase());
+            CountryRiskInput {
+                score,
+                sources,
+                dataset_version: self.country_risk.dataset_version().to_string(),
+            }
+        });
+        
+        // Velocity windows only count previously allowed transactions
+        let hour_ago = now - Duration::hours(1);
+        let day_ago = now - Duration::days(1);
+        let daily: Vec<u64> = history.iter().filter(|(at, _)| *at > day_ago).map(|(_, amount)| *amount).collect();
+        let velocity = VelocityWindow {
+            hourly_count: history.iter().filter(|(at, _)| *at > hour_ago).count() as u32,
+            daily_count: daily.len() as u32,
+            daily_volume: daily.iter().sum(),
+        };
+        
+        DecisionInputs {
+            rules_version: AUTHORIZATION_RULES_VERSION,
+            account_id: account_id.clone(),
+            request: request.clone(),
+            attestation: attestation.cloned(),
+            compliance_level,
+            blocked_counterparty,
+            allowlisted_counterparty,
+            counterparty_country_risk,
+            risk_thresholds: compliance.aml.risk_thresholds.clone(),
+            limits: compliance.aml.transaction_monitoring.clone(),
+            velocity,
+            decided_at: now,
+        }
+    }
+    
+    /// Link a recorded decision to the step-up session opened for it
+    pub async fn attach_step_up_session(&self, decision_id: Uuid, session_id: Uuid) {
+        if let Some(decision) = self.decisions.write().await.iter_mut().rev().find(|d| d.id == decision_id) {
+            decision.step_up_session = Some(session_id);
+        }
+    }
+    
+    /// Get recorded decisions for an account, most recent first
+    pub async fn decisions_for(&self, account_id: &AccountId) -> Vec<AuthorizationDecision> {
+        self.decisions
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|decision| decision.account_id == *account_id)
+            .cloned()
+            .collect()
+    }
+    
+    /// Inputs a recorded decision was made from
+    pub async fn decision_inputs(&self, decision_id: Uuid) -> Option<DecisionInputs> {
+        self.inputs.read().await.get(&decision_id).cloned()
+    }
+    
+    /// Get a client's decisions made within `[from, to)`, oldest first
+    pub async fn decisions_by_client(
+        &self,
+        client_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<AuthorizationDecision> {
+        self.decisions
+            .read()
+            .await
+            .iter()
+            .filter(|d| d.client_id == client_id && d.decided_at >= from && d.decided_at < to)
+            .cloned()
+            .collect()
+    }
+    
+    /// Record a decision with its inputs and, when allowed, count it towards velocity limits
+    async fn record(&self, ledger: &mut Ledger, decision: &AuthorizationDecision, inputs: DecisionInputs) {
+        tracing::info!(
+            decision_id = %decision.id,
+            account_id = %decision.account_id,
+            outcome = ?decision.outcome,
+            amount = decision.amount,
+            "transaction authorization decision"
+        );
+        
+        if decision.outcome == AuthorizationOutcome::Allow {
+            let cutoff = decision.decided_at - Duration::days(1);
+            let history = ledger.entry(decision.account_id.clone()).or_default();
+            history.retain(|(at, _)| *at > cutoff);
+            history.push((decision.decided_at, decision.amount));
+        }
+        
+        self.inputs.write().await.insert(decision.id, inputs);
+        self.decisions.write().await.push(decision.clone());
+    }
+}
+
+/// Compliance level required for a transaction amount under `limits`
+fn required_level(amount: u64, limits: &TransactionMonitoringConfig) -> ComplianceLevel {
+    if amount > limits.max_amount_medium_risk {
+        ComplianceLevel::Enhanced
+    } else if amount > limits.max_amount_low_risk {
+        ComplianceLevel::Standard
+    } else {
+        ComplianceLevel::Basic
+    }
+}
+
+/// Apply the authorization rules to a decision's inputs
+pub fn decide(inputs: &DecisionInputs) -> DecisionOutcome {
+    let request = &inputs.request;
+    let limits = &inputs.limits;
+    let required_level = required_level(request.amount, limits);
+    let mut deny = Vec::new();
+    let mut step_up = Vec::new();
+    
+    if let Some(hit) = &inputs.blocked_counterparty {
+        deny.push(format!("counterparty is on the client blocklist (entry {})", hit.entry.id));
+    }
+    
+    if let (Some(country), Some(risk)) = (&request.counterparty_country, &inputs.counterparty_country_risk) {
+        match risk_level(risk.score, &inputs.risk_thresholds) {
+            AmlRiskLevel::Critical => deny.push(format!(
+                "counterparty country {} is high risk ({})",
+                country,
+                risk.sources.join(", ")
+            )),
+            AmlRiskLevel::High => step_up.push(format!(
+                "counterparty country {} is elevated risk ({})",
+                country,
+                risk.sources.join(", ")
+            )),
+            _ => {}
+        }
+    }
+    
+    match &inputs.attestation {
+        None => deny.push("no compliance attestation on record".to_string()),
+        Some(att) => {
+            if att.kyc_status != KycStatus::Verified {
+                deny.push(format!("KYC status is {:?}", att.kyc_status));
+            }
+            if !att.sanctions_cleared {
+                deny.push("sanctions screening not cleared".to_string());
+            }
+            match att.aml_risk_level {
+                AmlRiskLevel::Critical => deny.push("AML risk level is critical".to_string()),
+                AmlRiskLevel::High => step_up.push("AML risk level is high".to_string()),
+                _ => {}
+            }
+            if att.expires_at <= inputs.decided_at {
+                step_up.push("compliance attestation has expired".to_string());
+            }
+        }
+    }
+    
+    if inputs.compliance_level.as_ref().map_or(true, |level| *level < required_level) {
+        step_up.push(format!(
+            "amount {} requires {:?} compliance, account has {:?}",
+            request.amount, required_level, inputs.compliance_level
+        ));
+    }
+    
+    // Allowlisted counterparties are exempt from velocity limits only
+    if inputs.allowlisted_counterparty.is_none() {
+        let velocity = &inputs.velocity;
+        if velocity.hourly_count >= limits.max_hourly_transactions {
+            deny.push(format!("hourly transaction limit of {} reached", limits.max_hourly_transactions));
+        }
+        if velocity.daily_count >= limits.max_daily_transactions {
+            deny.push(format!("daily transaction limit of {} reached", limits.max_daily_transactions));
+        }
+        if velocity.daily_volume.saturating_add(request.amount) > limits.max_daily_volume {
+            deny.push(format!("daily volume limit of {} would be exceeded", limits.max_daily_volume));
+        }
+    }
+    
+    let (outcome, reasons) = if !deny.is_empty() {
+        (AuthorizationOutcome::Deny, deny.into_iter().chain(step_up).collect())
+    } else if !step_up.is_empty() {
+        (AuthorizationOutcome::StepUp, step_up)
+    } else {
+        (AuthorizationOutcome::Allow, Vec::new())
+    };
+    DecisionOutcome {
+        outcome,
+        reasons,
+        required_level,
+    }
+}
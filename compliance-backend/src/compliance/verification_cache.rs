@@ -0,0 +1,230 @@
+//! Cache of proof verification outcomes
+//!
+//! Running the Miden verifier over an envelope's STARK proof is the costly
+//! part of serving verifiers, so its outcome is cached by envelope hash,
+//! failures included. A successful outcome is held until the envelope or the
+//! attestation it was issued from expires, whichever comes first; a failure
+//! for `negative_ttl_secs`. Outcomes for an account are dropped as soon as
+//! its attestation is revoked or expires.
+//!
+//! Checks of the envelope itself (signature, lifetime, audience, scope) and
+//! consumption of its challenge are cheap and run on every verification.
+
+use super::attestation_events::{AttestationEvent, AttestationEventStore, AttestationStatus};
+use crate::clock::SharedClock;
+use crate::reload::LiveConfig;
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+
+/// BLAKE3 digest of an encoded envelope
+pub type EnvelopeHash = [u8; 32];
+
+/// Hash an encoded envelope
+///
+/// Decoding only accepts the canonical encoding, so every envelope has
+/// exactly one hash.
+pub fn envelope_hash(encoded: &str) -> EnvelopeHash {
+    *blake3::hash(encoded.trim().as_bytes()).as_bytes()
+}
+
+/// Outcome of verifying an envelope's proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    /// The verifier accepted or rejected the proof
+    Verified(bool),
+    
+    /// The proof could not be verified, with the reason given
+    Invalid(String),
+}
+
+#[derive(Debug, Clone)]
+struct CachedOutcome {
+    account_id: AccountId,
+    outcome: Outcome,
+    expires_at: DateTime<Utc>,
+}
+
+/// Hits and misses since the cache was created
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// Verification outcomes by envelope hash
+pub struct VerificationCache {
+    /// Live configuration holding the cache limits
+    config: Arc<LiveConfig>,
+    
+    /// Attestation states outcomes are tied to
+    events: Arc<AttestationEventStore>,
+    
+    clock: SharedClock,
+    
+    outcomes: RwLock<HashMap<EnvelopeHash, CachedOutcome>>,
+    stats: RwLock<CacheStats>,
+}
+
+impl VerificationCache {
+    /// Create an empty cache
+    pub fn new(config: Arc<LiveConfig>, events: Arc<AttestationEventStore>, clock: SharedClock) -> Self {
+        Self {
+            config,
+            events,
+            clock,
+            outcomes: RwLock::new(HashMap::new()),
+            stats: RwLock::new(CacheStats::default()),
+        }
+    }
+    
+    /// Verify an envelope's proof, reusing a cached outcome while it is fresh
+    ///
+    /// `verify` runs only on a miss. Its `InvalidProof` errors are cached and
+    /// returned again on later hits; any other error is returned without
+    /// being cached, so transient failures are retried.
+    pub async fn verify<F>(
+        &self,
+        hash: EnvelopeHash,
+        account_id: &AccountId,
+        envelope_expires_at: Option<DateTime<Utc>>,
+        verify: F,
+    ) -> Result<bool>
+    where
+        F: Future<Output = Result<bool>>,
+    {
+        let config = self.config.compliance();
+        let settings = &config.attestation.verification_cache;
+        if !settings.enabled {
+            return verify.await;
+        }
+        
+        let now = self.clock.now();
+        let cached = self.outcomes.read().await.get(&hash).cloned();
+        match cached {
+            Some(cached) if cached.expires_at > now => {
+                self.stats.write().await.hits += 1;
+                return match cached.outcome {
+                    Outcome::Verified(valid) => Ok(valid),
+                    Outcome::Invalid(reason) => Err(ComplianceError::InvalidProof { reason }),
+                };
+            }
+            _ => self.stats.write().await.misses += 1,
+        }
+        
+        let outcome = match verify.await {
+            Ok(valid) => Outcome::Verified(valid),
+            Err(ComplianceError::InvalidProof { reason }) => Outcome::Invalid(reason),
+            Err(e) => return Err(e),
+        };
+        // Held while the attestation is read, so a revocation recorded meanwhile
+        // either shows in the attestation or drops the outcome after it is cached
+        let mut outcomes = self.outcomes.write().await;
+        let expires_at = match outcome {
+            Outcome::Verified(true) => self.attestation_expiry(account_id).await?.map(|attestation_expiry| {
+                envelope_expires_at.map_or(attestation_expiry, |envelope| envelope.min(attestation_expiry))
+            }),
+            _ => Some(now + Duration::seconds(settings.negative_ttl_secs as i64)),
+        };
+        
+        // Successful outcomes are not cached for accounts without an active attestation
+        if let Some(expires_at) = expires_at.filter(|expires_at| *expires_at > now) {
+            if outcomes.len() >= settings.max_entries {
+                outcomes.retain(|_, cached| cached.expires_at > now);
+            }
+            while outcomes.len() >= settings.max_entries {
+                let soonest = outcomes
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.expires_at)
+                    .map(|(hash, _)| *hash);
+                match soonest {
+                    Some(hash) => outcomes.remove(&hash),
+                    None => break,
+                };
+            }
+            outcomes.insert(
+                hash,
+                CachedOutcome {
+                    account_id: account_id.clone(),
+                    outcome: outcome.clone(),
+                    expires_at,
+                },
+            );
+        }
+        drop(outcomes);
+        
+        match outcome {
+            Outcome::Verified(valid) => Ok(valid),
+            Outcome::Invalid(reason) => Err(ComplianceError::InvalidProof { reason }),
+        }
+    }
+    
+    /// Expiry of the account's attestation, if it is active
+    async fn attestation_expiry(&self, account_id: &AccountId) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .events
+            .project(account_id, None)
+            .await?
+            .filter(|state| state.status == AttestationStatus::Active)
+            .map(|state| state.attestation.expires_at))
+    }
+    
+    /// Drop every cached outcome for an account
+    pub async fn invalidate(&self, account_id: &AccountId) {
+        self.outcomes.write().await.retain(|_, cached| cached.account_id != *account_id);
+    }
+    
+    /// Drop every cached outcome
+    pub async fn clear(&self) {
+        self.outcomes.write().await.clear();
+    }
+    
+    /// Hits, misses, and entries held
+    pub async fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.outcomes.read().await.len(),
+            ..*self.stats.read().await
+        }
+    }
+}
+
+/// Drop cached outcomes of accounts whose attestation is revoked or expires
+///
+/// Follows the live event feed. If it falls behind the feed, every outcome
+/// is dropped, since revocations may have been missed.
+pub fn spawn_invalidation(cache: Arc<VerificationCache>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let mut subscription = match cache.events.feed().subscribe(None).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    tracing::error!(error = %e, "failed to follow attestation events, retrying");
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    continue;
+                }
+            };
+            loop {
+                match subscription.live.recv().await {
+                    Ok(event) => {
+                        if matches!(event.event.event, AttestationEvent::Revoked { .. } | AttestationEvent::Expired) {
+                            cache.invalidate(&event.event.account_id).await;
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => {
+                        tracing::warn!("verification cache fell behind attestation events, clearing it");
+                        cache.clear().await;
+                        break;
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,853 @@
+//! Sessions in which end users complete KYC directly against the API
+//!
+//! A business client opens a session for one of its accounts and hands the
+//! returned token, or the hosted page link carrying it, to its end user. The
+//! end user uploads identity documents and polls the outcome with the token
+//! alone, so the client never handles the documents.
+//!
+//! Tokens are `vs_<session id>.<expiry>.<mac>`, an HMAC-SHA256 over the
+//! session id and expiry keyed from the deployment's JWT secret. They cannot
+//! be extended or moved to another session, and stop working at expiry.
+//! Uploaded documents go through the malware scanner, when one is
+//! configured, and then straight to the document vault; the session keeps
+//! only their vault references and digests. A flagged document goes to the
+//! quarantine vault instead and is recorded on the session for review.
+//!
+//! Each lifecycle transition is announced to the client by webhook:
+//! `verification_session.started` on the first upload, then `.submitted`,
+//! and `.completed` or `.failed` with the outcome, or `.expired` or
+//! `.cancelled` for sessions never submitted. Quarantined documents are
+//! announced with `verification_session.document_quarantined`.
+
+use super::document_scanning::{scanner_error, DocumentScanner, ScanVerdict};
+use super::image_quality;
+use super::localization::{attestation_messages, Message};
+use crate::clock::{system_clock, SharedClock};
+use crate::reload::LiveConfig;
+use crate::scheduler::Job;
+use crate::storage::{OutboxMessage, UnitOfWork, WriteBatch};
+use crate::types::{AccountId, ComplianceAttestation, KycStatus};
+use crate::{ComplianceError, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Prefix of session tokens
+const TOKEN_PREFIX: &str = "vs_";
+
+/// Context the token key is derived from the JWT secret under
+const TOKEN_KEY_CONTEXT: &str = "zerotrust-compliance verification session token v1";
+
+/// Maximum length of a document type
+const MAX_DOCUMENT_TYPE_LEN: usize = 64;
+
+/// Name of the job expiring sessions never submitted
+pub const EXPIRY_JOB: &str = "verification_session_expiry";
+
+/// Stage of a verification session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    /// Opened, no document uploaded yet
+    Created,
+    
+    /// Documents are being uploaded
+    InProgress,
+    
+    /// Submitted and being checked
+    Submitted,
+    
+    /// Checked, with KYC verified
+    Completed,
+    
+    /// Checked, with KYC not verified
+    Failed,
+    
+    /// Not submitted before expiry
+    Expired,
+    
+    /// Cancelled by the client
+    Cancelled,
+}
+
+impl SessionStatus {
+    /// Whether the end user can still upload and submit
+    pub fn is_open(self) -> bool {
+        matches!(self, Self::Created | Self::InProgress)
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::InProgress => "in_progress",
+            Self::Submitted => "submitted",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Expired => "expired",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A document uploaded in a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDocument {
+    pub id: Uuid,
+    
+    /// One of `kyc.supported_documents` (e.g. "passport")
+    pub document_type: String,
+    
+    pub content_type: String,
+    pub size: usize,
+    
+    /// Hex SHA-256 of the uploaded bytes
+    pub sha256: String,
+    
+    pub vault_ref: String,
+    
+    /// Whether the malware scanner cleared the document; false when no
+    /// scanner is configured or it failed with `fail_open` set
+    pub scanned: bool,
+    
+    /// Lowest image quality check score, for images that were checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<f64>,
+    
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// A document the malware scanner flagged, held in the quarantine vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedDocument {
+    pub id: Uuid,
+    pub document_type: String,
+    pub content_type: String,
+    pub size: usize,
+    
+    /// Hex SHA-256 of the uploaded bytes
+    pub sha256: String,
+    
+    /// Scanner that flagged the document
+    pub scanner: String,
+    
+    /// Signature or reason given by the scanner
+    pub reason: String,
+    
+    /// Reference in the quarantine vault
+    pub vault_ref: String,
+    
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// A session opened for an end user to complete KYC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationSession {
+    pub id: Uuid,
+    
+    /// Client that opened the session and receives its webhooks
+    pub client_id: Uuid,
+    
+    pub account_id: AccountId,
+    pub status: SessionStatus,
+    pub documents: Vec<SessionDocument>,
+    
+    /// Uploads flagged by the malware scanner, never passed to KYC checks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quarantined: Vec<QuarantinedDocument>,
+    
+    /// Where the hosted page sends the end user once the session is checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+    
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    
+    /// When the session reached its final status
+    pub closed_at: Option<DateTime<Utc>>,
+    
+    /// KYC status of the attestation issued from the session
+    pub kyc_status: Option<KycStatus>,
+    
+    /// Why the attestation falls short, once checked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<Message>,
+}
+
+/// Request to open a session
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CreateSessionRequest {
+    /// Session lifetime in seconds (defaults to `default_ttl_secs`)
+    pub ttl_secs: Option<u64>,
+    
+    /// Where the hosted page sends the end user once the session is checked
+    pub redirect_url: Option<String>,
+}
+
+/// A newly opened session with the credentials handed to the end user
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedSession {
+    pub session: VerificationSession,
+    
+    /// Bearer token for the end user's requests
+    pub token: String,
+    
+    /// Hosted page link, with the token in the fragment so it is never sent to a server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// What the end user sees of a session
+#[derive(Debug, Clone, Serialize)]
+pub struct EndUserSessionView {
+    pub status: SessionStatus,
+    pub expires_at: DateTime<Utc>,
+    
+    /// Document types that may be uploaded
+    pub accepted_document_types: Vec<String>,
+    
+    /// Types of the documents uploaded so far
+    pub uploaded: Vec<String>,
+    
+    pub kyc_status: Option<KycStatus>,
+    
+    /// Set once the session is checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+}
+
+/// Storage for uploaded identity documents
+pub trait DocumentVault: Send + Sync {
+    /// Store a document, returning its vault reference
+    fn store<'a>(
+        &'a self,
+        account_id: &'a AccountId,
+        content_type: &'a str,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>>;
+    
+    /// Delete a stored document; deleting a reference not stored succeeds
+    fn delete<'a>(&'a self, vault_ref: &'a str) -> BoxFuture<'a, Result<()>>;
+}
+
+/// In-memory vault for development and tests
+#[derive(Default)]
+pub struct MemoryDocumentVault {
+    documents: RwLock<HashMap<String, (String, Vec<u8>)>>,
+}
+
+impl MemoryDocumentVault {
+    /// Create an empty vault
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Content type and bytes of a stored document
+    pub async fn get(&self, vault_ref: &str) -> Option<(String, Vec<u8>)> {
+        self.documents.read().await.get(vault_ref).cloned()
+    }
+}
+
+impl DocumentVault for MemoryDocumentVault {
+    fn store<'a>(
+        &'a self,
+        _account_id: &'a AccountId,
+        content_type: &'a str,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let vault_ref = format!("memory:{}", Uuid::new_v4());
+            self.documents.write().await.insert(vault_ref.clone(), (content_type.to_string(), bytes));
+            Ok(vault_ref)
+        })
+    }
+    
+    fn delete<'a>(&'a self, vault_ref: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.documents.write().await.remove(vault_ref);
+            Ok(())
+        })
+    }
+}
+
+/// Service opening sessions and tracking them through their lifecycle
+pub struct VerificationSessionService {
+    /// Live configuration holding session limits and accepted documents
+    config: Arc<LiveConfig>,
+    
+    /// Key session tokens are signed with
+    token_key: [u8; 32],
+    
+    vault: Arc<dyn DocumentVault>,
+    
+    /// Malware scanner and the vault flagged documents are held in
+    scanning: Option<Scanning>,
+    
+    /// Commits lifecycle webhooks to the outbox
+    storage: Arc<dyn UnitOfWork>,
+    
+    clock: SharedClock,
+    sessions: RwLock<HashMap<Uuid, VerificationSession>>,
+}
+
+impl VerificationSessionService {
+    /// Create the service, deriving the token key from `secret`
+    pub fn new(
+        config: Arc<LiveConfig>,
+        secret: &[u8],
+        vault: Arc<dyn DocumentVault>,
+        storage: Arc<dyn UnitOfWork>,
+    ) -> Self {
+        Self {
+            config,
+            token_key: blake3::derive_key(TOKEN_KEY_CONTEXT, secret),
+            vault,
+            scanning: None,
+            storage,
+            clock: system_clock(),
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Scan uploads with `scanner`, holding flagged documents in `quarantine`
+    pub fn with_scanner(mut self, scanner: Arc<dyn DocumentScanner>, quarantine: Arc<dyn DocumentVault>) -> Self {
+        self.scanning = Some(Scanning { scanner, quarantine });
+        self
+    }
+    
+    /// Open a session for an account of `client_id`
+    pub async fn create(
+        &self,
+        client_id: Uuid,
+        account_id: &AccountId,
+        request: CreateSessionRequest,
+    ) -> Result<IssuedSession> {
+        let compliance = self.config.compliance();
+        let config = &compliance.verification_sessions;
+        if !config.enabled {
+            return Err(ComplianceError::validation("verification_sessions", "verification sessions are disabled"));
+        }
+        let ttl_secs = request.ttl_secs.unwrap_or(config.default_ttl_secs);
+        if ttl_secs == 0 || ttl_secs > config.max_ttl_secs {
+            return Err(ComplianceError::validation(
+                "ttl_secs",
+                format!("must be between 1 and {}", config.max_ttl_secs),
+            ));
+        }
+        if let Some(url) = &request.redirect_url {
+            if !url.starts_with("https://") {
+                return Err(ComplianceError::validation("redirect_url", "must be an https URL"));
+            }
+        }
+        
+        let now = self.clock.now();
+        let session = VerificationSession {
+            id: Uuid::new_v4(),
+            client_id,
+            account_id: account_id.clone(),
+            status: SessionStatus::Created,
+            documents: Vec::new(),
+            quarantined: Vec::new(),
+            redirect_url: request.redirect_url,
+            created_at: now,
+            // Whole seconds, as the token carries them
+            expires_at: DateTime::from_timestamp(now.timestamp() + ttl_secs as i64, 0).unwrap_or(now),
+            submitted_at: None,
+            closed_at: None,
+            kyc_status: None,
+            messages: Vec::new(),
+        };
+        let token = self.sign(session.id, session.expires_at);
+        let url = config.hosted_url.as_ref().map(|base| format!("{}#token={}", base, token));
+        self.sessions.write().await.insert(session.id, session.clone());
+        
+        Ok(IssuedSession { session, token, url })
+    }
+    
+    /// A session opened by `client_id`
+    ///
+    /// Sessions of other clients are reported as not found.
+    pub async fn get(&self, client_id: Uuid, session_id: Uuid) -> Result<VerificationSession> {
+        self.expire_if_due(session_id).await?;
+        self.sessions
+            .read()
+            .await
+            .get(&session_id)
+            .filter(|session| session.client_id == client_id)
+            .cloned()
+            .ok_or_else(|| not_found(session_id))
+    }
+    
+    /// Sessions opened for an account by `client_id`, newest first
+    pub async fn for_account(&self, client_id: Uuid, account_id: &AccountId) -> Vec<VerificationSession> {
+        let mut sessions: Vec<_> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .filter(|session| session.client_id == client_id && &session.account_id == account_id)
+            .cloned()
+            .collect();
+        sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        sessions
+    }
+    
+    /// Resolve an end user's token to its session
+    ///
+    /// Fails on a malformed, forged, or expired token. A session past its
+    /// expiry is moved to `expired` on the way.
+    pub async fn authenticate(&self, token: &str) -> Result<VerificationSession> {
+        let invalid = |reason: &str| ComplianceError::InvalidSessionToken {
+            reason: reason.to_string(),
+        };
+        let (session_id, expires_at) = self.verify(token).ok_or_else(|| invalid("malformed or forged"))?;
+        if self.clock.now() >= expires_at {
+            // The sweeper retries an expiry whose webhook fails to commit
+            self.expire_if_due(session_id).await.ok();
+            return Err(invalid("expired"));
+        }
+        self.sessions
+            .read()
+            .await
+            .get(&session_id)
+            .cloned()
+            .ok_or_else(|| invalid("unknown session"))
+    }
+    
+    /// What the end user sees of a session
+    pub fn view(&self, session: &VerificationSession) -> EndUserSessionView {
+        let checked = matches!(session.status, SessionStatus::Completed | SessionStatus::Failed);
+        EndUserSessionView {
+            status: session.status,
+            expires_at: session.expires_at,
+            accepted_document_types: self.config.compliance().kyc.supported_documents.clone(),
+            uploaded: session.documents.iter().map(|document| document.document_type.clone()).collect(),
+            kyc_status: session.kyc_status.clone(),
+            redirect_url: session.redirect_url.clone().filter(|_| checked),
+        }
+    }
+    
+    /// Scan and quality-check a document uploaded by the end user and store it
+    ///
+    /// A document the scanner flags is quarantined and the upload fails with
+    /// [`ComplianceError::DocumentQuarantined`]. An image failing the quality
+    /// checks is discarded and the upload fails with
+    /// [`ComplianceError::DocumentQualityInsufficient`], carrying feedback
+    /// codes for the end user to act on before a provider attempt is spent.
+    pub async fn upload(
+        &self,
+        session_id: Uuid,
+        document_type: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<VerificationSession> {
+        let compliance = self.config.compliance();
+        let config = &compliance.kyc.documents;
+        let document_type = document_type.trim().to_ascii_lowercase();
+        if document_type.len() > MAX_DOCUMENT_TYPE_LEN || !compliance.kyc.supported_documents.contains(&document_type) {
+            return Err(ComplianceError::validation(
+                "document_type",
+                format!("must be one of {}", compliance.kyc.supported_documents.join(", ")),
+            ));
+        }
+        let content_type = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        if !config.allowed_content_types.contains(&content_type) {
+            return Err(ComplianceError::validation(
+                "content_type",
+                format!("must be one of {}", config.allowed_content_types.join(", ")),
+            ));
+        }
+        if bytes.is_empty() || bytes.len() > config.max_document_bytes {
+            return Err(ComplianceError::validation(
+                "document",
+                format!("must be between 1 and {} bytes", config.max_document_bytes),
+            ));
+        }
+        
+        let account_id = {
+            let session = self.open_session(session_id).await?;
+            let max_documents = compliance.verification_sessions.max_documents;
+            if session.documents.len() >= max_documents {
+                return Err(ComplianceError::validation(
+                    "document",
+                    format!("at most {} documents may be uploaded in a session", max_documents),
+                ));
+            }
+            session.account_id
+        };
+        let size = bytes.len();
+        let sha256 = hex::encode(Sha256::digest(&bytes));
+        let scanned = match self.scan(&content_type, &bytes).await? {
+            Some(ScanVerdict::Flagged { reason }) => {
+                return self
+                    .quarantine(session_id, &account_id, document_type, content_type, bytes, sha256, reason)
+                    .await;
+            }
+            Some(ScanVerdict::Clean) => true,
+            None => false,
+        };
+        let (bytes, quality_score) = self.check_quality(&document_type, &content_type, bytes).await?;
+        let vault_ref = self.vault.store(&account_id, &content_type, bytes).await?;
+        let document = SessionDocument {
+            id: Uuid::new_v4(),
+            document_type,
+            content_type,
+            size,
+            sha256,
+            vault_ref,
+            scanned,
+            quality_score,
+            uploaded_at: self.clock.now(),
+        };
+        
+        // The session may have closed while the document was being stored
+        self.transition(session_id, |session| {
+            if !session.status.is_open() {
+                return Err(closed(session));
+            }
+            session.documents.push(document);
+            if session.status == SessionStatus::Created {
+                session.status = SessionStatus::InProgress;
+                return Ok(Some("verification_session.started"));
+            }
+            Ok(None)
+        })
+        .await
+    }
+    
+    /// Submit the uploaded documents for checking
+    pub async fn submit(&self, session_id: Uuid) -> Result<VerificationSession> {
+        self.open_session(session_id).await?;
+        let now = self.clock.now();
+        self.transition(session_id, |session| {
+            if !session.status.is_open() {
+                return Err(closed(session));
+            }
+            if session.documents.is_empty() {
+                return Err(ComplianceError::validation("documents", "at least one document must be uploaded"));
+            }
+            session.status = SessionStatus::Submitted;
+            session.submitted_at = Some(now);
+            Ok(Some("verification_session.submitted"))
+        })
+        .await
+    }
+    
+    /// Return a submitted session to the end user after its check could not run
+    pub async fn reopen(&self, session_id: Uuid) -> Result<VerificationSession> {
+        self.transition(session_id, |session| {
+            if session.status == SessionStatus::Submitted {
+                session.status = SessionStatus::InProgress;
+                session.submitted_at = None;
+            }
+            Ok(None)
+        })
+        .await
+    }
+    
+    /// Close a submitted session with the attestation issued from its check
+    pub async fn complete(&self, session_id: Uuid, attestation: &ComplianceAttestation) -> Result<VerificationSession> {
+        let now = self.clock.now();
+        self.transition(session_id, |session| {
+            if session.status != SessionStatus::Submitted {
+                return Err(closed(session));
+            }
+            let verified = attestation.kyc_status == KycStatus::Verified;
+            session.status = if verified { SessionStatus::Completed } else { SessionStatus::Failed };
+            session.kyc_status = Some(attestation.kyc_status.clone());
+            session.messages = attestation_messages(attestation, None);
+            session.closed_at = Some(now);
+            Ok(Some(if verified { "verification_session.completed" } else { "verification_session.failed" }))
+        })
+        .await
+    }
+    
+    /// Cancel an open session of `client_id`
+    pub async fn cancel(&self, client_id: Uuid, session_id: Uuid) -> Result<VerificationSession> {
+        self.get(client_id, session_id).await?;
+        let now = self.clock.now();
+        self.transition(session_id, |session| {
+            if !session.status.is_open() {
+                return Err(closed(session));
+            }
+            session.status = SessionStatus::Cancelled;
+            session.closed_at = Some(now);
+            Ok(Some("verification_session.cancelled"))
+        })
+        .await
+    }
+    
+    /// Delete every document uploaded for an account, returning how many were deleted
+    ///
+    /// Sessions are kept without their documents, quarantined uploads, or
+    /// redirect URL, and any still open are cancelled without a webhook. A
+    /// failed deletion leaves the remaining documents recorded, so erasure
+    /// can be retried.
+    pub async fn erase_documents(&self, account_id: &AccountId) -> Result<usize> {
+        let now = self.clock.now();
+        let mut sessions = self.sessions.write().await;
+        let mut erased = 0;
+        for session in sessions.values_mut().filter(|session| &session.account_id == account_id) {
+            for document in &session.documents {
+                self.vault.delete(&document.vault_ref).await?;
+            }
+            if let Some(Scanning { quarantine, .. }) = &self.scanning {
+                for document in &session.quarantined {
+                    quarantine.delete(&document.vault_ref).await?;
+                }
+            }
+            erased += session.documents.len() + session.quarantined.len();
+            session.documents.clear();
+            session.quarantined.clear();
+            session.redirect_url = None;
+            if session.status.is_open() {
+                session.status = SessionStatus::Cancelled;
+                session.closed_at = Some(now);
+            }
+        }
+        Ok(erased)
+    }
+    
+    /// Expire every open session past its expiry, returning how many expired
+    pub async fn expire_due(&self) -> Result<usize> {
+        let now = self.clock.now();
+        let due: Vec<Uuid> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .filter(|session| session.status.is_open() && session.expires_at <= now)
+            .map(|session| session.id)
+            .collect();
+        for session_id in &due {
+            self.expire_if_due(*session_id).await?;
+        }
+        Ok(due.len())
+    }
+    
+    /// Scanner verdict on a document, or `None` when it was not scanned
+    async fn scan(&self, content_type: &str, bytes: &[u8]) -> Result<Option<ScanVerdict>> {
+        let Some(Scanning { scanner, .. }) = &self.scanning else {
+            return Ok(None);
+        };
+        let config = self.config.compliance().kyc.documents.clone();
+        let timeout = std::time::Duration::from_secs(config.scan_timeout_secs);
+        let result = match tokio::time::timeout(timeout, scanner.scan(content_type, bytes)).await {
+            Ok(result) => result,
+            Err(_) => Err(scanner_error(scanner.name(), "scan timed out")),
+        };
+        match result {
+            Ok(verdict) => Ok(Some(verdict)),
+            Err(e) if config.fail_open => {
+                tracing::warn!(error = %e, "document scan failed; accepting the document unscanned");
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Run the image quality checks on an image, returning its bytes and score
+    ///
+    /// Documents other than images, such as PDFs, are not checked.
+    async fn check_quality(
+        &self,
+        document_type: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<(Vec<u8>, Option<f64>)> {
+        let compliance = self.config.compliance();
+        let config = compliance.kyc.documents.image_quality.clone();
+        if !config.enabled || !content_type.starts_with("image/") {
+            return Ok((bytes, None));
+        }
+        let min_score = compliance.kyc.min_quality_score;
+        // Decoding and filtering a camera image takes long enough to stall a runtime worker
+        let (bytes, report) = tokio::task::spawn_blocking(move || {
+            let report = image_quality::assess(&bytes, &config, min_score);
+            (bytes, report)
+        })
+        .await
+        .map_err(|e| ComplianceError::internal(format!("image quality worker failed: {}", e)))?;
+        if !report.passed() {
+            tracing::debug!(document_type, score = report.score, issues = ?report.issues, "document image rejected");
+            return Err(ComplianceError::DocumentQualityInsufficient {
+                document_type: document_type.to_string(),
+                score: report.score,
+                feedback: report.issues.iter().map(|issue| issue.code().to_string()).collect(),
+            });
+        }
+        Ok((bytes, Some(report.score)))
+    }
+    
+    /// Hold a flagged document in the quarantine vault and record it on its session
+    #[allow(clippy::too_many_arguments)]
+    async fn quarantine(
+        &self,
+        session_id: Uuid,
+        account_id: &AccountId,
+        document_type: String,
+        content_type: String,
+        bytes: Vec<u8>,
+        sha256: String,
+        reason: String,
+    ) -> Result<VerificationSession> {
+        let Some(Scanning { scanner, quarantine }) = &self.scanning else {
+            return Err(ComplianceError::internal("quarantine requested without a scanner"));
+        };
+        let size = bytes.len();
+        let vault_ref = quarantine.store(account_id, &content_type, bytes).await?;
+        tracing::warn!(
+            %session_id,
+            %account_id,
+            scanner = scanner.name(),
+            reason = %reason,
+            "uploaded document quarantined"
+        );
+        let document = QuarantinedDocument {
+            id: Uuid::new_v4(),
+            document_type: document_type.clone(),
+            content_type,
+            size,
+            sha256,
+            scanner: scanner.name().to_string(),
+            reason,
+            vault_ref,
+            quarantined_at: self.clock.now(),
+        };
+        self.transition(session_id, |session| {
+            session.quarantined.push(document);
+            Ok(Some("verification_session.document_quarantined"))
+        })
+        .await?;
+        Err(ComplianceError::DocumentQuarantined { document_type })
+    }
+    
+    /// An open, unexpired session
+    async fn open_session(&self, session_id: Uuid) -> Result<VerificationSession> {
+        let session = self.expire_if_due(session_id).await?;
+        if !session.status.is_open() {
+            return Err(closed(&session));
+        }
+        Ok(session)
+    }
+    
+    /// Move an open session past its expiry to `expired`
+    async fn expire_if_due(&self, session_id: Uuid) -> Result<VerificationSession> {
+        let now = self.clock.now();
+        self.transition(session_id, |session| {
+            if !session.status.is_open() || session.expires_at > now {
+                return Ok(None);
+            }
+            session.status = SessionStatus::Expired;
+            session.closed_at = Some(now);
+            Ok(Some("verification_session.expired"))
+        })
+        .await
+    }
+    
+    /// Apply a change to a session, committing the webhook it names before the change is kept
+    async fn transition(
+        &self,
+        session_id: Uuid,
+        change: impl FnOnce(&mut VerificationSession) -> Result<Option<&'static str>>,
+    ) -> Result<VerificationSession> {
+        let mut sessions = self.sessions.write().await;
+        let current = sessions.get(&session_id).ok_or_else(|| not_found(session_id))?;
+        let mut session = current.clone();
+        let Some(event_type) = change(&mut session)? else {
+            sessions.insert(session_id, session.clone());
+            return Ok(session);
+        };
+        
+        let mut payload = serde_json::json!({
+            "session_id": session.id,
+            "account_id": session.account_id,
+            "status": session.status,
+            "expires_at": session.expires_at,
+        });
+        if let Some(kyc_status) = &session.kyc_status {
+            payload["kyc_status"] = serde_json::to_value(kyc_status)?;
+            payload["messages"] = serde_json::to_value(&session.messages)?;
+        }
+        if event_type == "verification_session.document_quarantined" {
+            payload["document"] = serde_json::to_value(session.quarantined.last())?;
+        }
+        let mut batch = WriteBatch::default();
+        batch.outbox.push(OutboxMessage::new(session.client_id, event_type, payload, self.clock.now()));
+        self.storage.commit(&batch).await?;
+        
+        tracing::debug!(session_id = %session.id, status = session.status.name(), "verification session updated");
+        sessions.insert(session_id, session.clone());
+        Ok(session)
+    }
+    
+    fn sign(&self, session_id: Uuid, expires_at: DateTime<Utc>) -> String {
+        let claims = format!("{}.{}", session_id.simple(), expires_at.timestamp());
+        let tag = self.mac(&claims).finalize().into_bytes();
+        format!("{}{}.{}", TOKEN_PREFIX, claims, URL_SAFE_NO_PAD.encode(tag))
+    }
+    
+    /// Session id and expiry of a token with a valid MAC
+    fn verify(&self, token: &str) -> Option<(Uuid, DateTime<Utc>)> {
+        let (claims, tag) = token.trim().strip_prefix(TOKEN_PREFIX)?.rsplit_once('.')?;
+        self.mac(claims).verify_slice(&URL_SAFE_NO_PAD.decode(tag).ok()?).ok()?;
+        let (session_id, expires_at) = claims.split_once('.')?;
+        Some((
+            Uuid::parse_str(session_id).ok()?,
+            DateTime::from_timestamp(expires_at.parse().ok()?, 0)?,
+        ))
+    }
+    
+    fn mac(&self, claims: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.token_key).expect("HMAC accepts any key length");
+        mac.update(claims.as_bytes());
+        mac
+    }
+}
+
+/// Malware scanner and quarantine vault for uploads
+struct Scanning {
+    scanner: Arc<dyn DocumentScanner>,
+    quarantine: Arc<dyn DocumentVault>,
+}
+
+/// Job periodically expiring sessions never submitted
+pub fn expiry_job(sessions: Arc<VerificationSessionService>, interval: std::time::Duration) -> Job {
+    Job::every(EXPIRY_JOB, interval, move || {
+        let sessions = sessions.clone();
+        async move {
+            let expired = sessions.expire_due().await?;
+            if expired > 0 {
+                tracing::info!(expired, "verification sessions expired");
+            }
+            Ok(())
+        }
+    })
+}
+
+fn not_found(session_id: Uuid) -> ComplianceError {
+    ComplianceError::VerificationSessionNotFound {
+        session_id: session_id.to_string(),
+    }
+}
+
+fn closed(session: &VerificationSession) -> ComplianceError {
+    ComplianceError::VerificationSessionClosed {
+        session_id: session.id.to_string(),
+        status: session.status.name().to_string(),
+    }
+}
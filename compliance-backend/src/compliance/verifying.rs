@@ -0,0 +1,172 @@
+//! Bounded worker pool proof verification runs on
+//!
+//! STARK verification is CPU-bound for hundreds of milliseconds. Run on the
+//! async runtime it holds tokio workers and starves unrelated requests, so
+//! each verification runs on a blocking thread instead, with at most
+//! `max_concurrent` at once. Further verifications wait in a bounded queue;
+//! a verification that finds the queue full is rejected with a retry hint,
+//! and one that has not finished by its deadline fails without waiting on.
+//! A verification past its deadline keeps its worker until it finishes, so
+//! the pool never runs more than `max_concurrent` verifications.
+
+use crate::config::VerificationPoolConfig;
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Point-in-time view of the verification pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationPoolStatus {
+    pub running: usize,
+    pub queued: usize,
+    pub max_concurrent: usize,
+    pub max_queued: usize,
+    /// Moving average of verification time in milliseconds
+    pub avg_verify_ms: u64,
+    pub rejected_total: u64,
+    pub timed_out_total: u64,
+}
+
+struct PoolInner {
+    running: usize,
+    queued: usize,
+    avg_verify_ms: f64,
+    rejected_total: u64,
+    timed_out_total: u64,
+}
+
+/// Worker pool for proof verification
+pub struct VerificationPool {
+    config: VerificationPoolConfig,
+    workers: Arc<Semaphore>,
+    inner: Arc<Mutex<PoolInner>>,
+}
+
+/// Worker held while a proof is verified, released on drop even if verification panics
+struct Working {
+    inner: Arc<Mutex<PoolInner>>,
+    started_at: Instant,
+    _worker: OwnedSemaphorePermit,
+}
+
+impl Drop for Working {
+    fn drop(&mut self) {
+        let mut inner = lock(&self.inner);
+        inner.running -= 1;
+        let elapsed_ms = self.started_at.elapsed().as_millis() as f64;
+        inner.avg_verify_ms = inner.avg_verify_ms * 0.8 + elapsed_ms * 0.2;
+    }
+}
+
+/// Counts a verification as queued until dropped, including when its caller gives up
+struct Queued<'a>(&'a Mutex<PoolInner>);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        lock(self.0).queued -= 1;
+    }
+}
+
+impl VerificationPool {
+    /// Create an idle pool
+    pub fn new(config: VerificationPoolConfig) -> Self {
+        Self {
+            workers: Arc::new(Semaphore::new(config.max_concurrent)),
+            inner: Arc::new(Mutex::new(PoolInner {
+                running: 0,
+                queued: 0,
+                avg_verify_ms: config.initial_estimate_ms as f64,
+                rejected_total: 0,
+                timed_out_total: 0,
+            })),
+            config,
+        }
+    }
+    
+    /// Drive a verification future to completion on a worker
+    ///
+    /// Fails with `VerifierSaturated` when the queue is full, and with
+    /// `ProofVerificationTimedOut` when the verification has not finished
+    /// within `deadline` of the call, time spent queued included.
+    pub async fn run<T, F>(&self, deadline: Duration, verify: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let timed_out = || {
+            lock(&self.inner).timed_out_total += 1;
+            ComplianceError::ProofVerificationTimedOut {
+                timeout_secs: deadline.as_secs(),
+            }
+        };
+        let started = Instant::now();
+        let worker = match self.workers.clone().try_acquire_owned() {
+            Ok(worker) => worker,
+            Err(_) => {
+                {
+                    let mut inner = lock(&self.inner);
+                    if inner.queued >= self.config.max_queued {
+                        inner.rejected_total += 1;
+                        return Err(ComplianceError::VerifierSaturated {
+                            retry_after_secs: self.retry_after(&inner),
+                        });
+                    }
+                    inner.queued += 1;
+                }
+                let _queued = Queued(&self.inner);
+                match tokio::time::timeout(deadline, self.workers.clone().acquire_owned()).await {
+                    Ok(worker) => worker.map_err(|_| ComplianceError::internal("verification pool closed"))?,
+                    Err(_) => return Err(timed_out()),
+                }
+            }
+        };
+        
+        lock(&self.inner).running += 1;
+        let working = Working {
+            inner: self.inner.clone(),
+            started_at: Instant::now(),
+            _worker: worker,
+        };
+        let handle = Handle::current();
+        let task = tokio::task::spawn_blocking(move || {
+            let _working = working;
+            handle.block_on(verify)
+        });
+        
+        let remaining = deadline.saturating_sub(started.elapsed());
+        match tokio::time::timeout(remaining, task).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(e)) => Err(ComplianceError::internal(format!("proof verification worker failed: {}", e))),
+            Err(_) => Err(timed_out()),
+        }
+    }
+    
+    /// Current pool status
+    pub fn status(&self) -> VerificationPoolStatus {
+        let inner = lock(&self.inner);
+        VerificationPoolStatus {
+            running: inner.running,
+            queued: inner.queued,
+            max_concurrent: self.config.max_concurrent,
+            max_queued: self.config.max_queued,
+            avg_verify_ms: inner.avg_verify_ms as u64,
+            rejected_total: inner.rejected_total,
+            timed_out_total: inner.timed_out_total,
+        }
+    }
+    
+    /// Estimate when a worker is likely to free up for a new verification
+    fn retry_after(&self, inner: &PoolInner) -> u64 {
+        let rounds = (inner.queued / self.config.max_concurrent.max(1)) as f64 + 1.0;
+        let secs = (rounds * inner.avg_verify_ms / 1000.0).ceil() as u64;
+        secs.max(1)
+    }
+}
+
+fn lock(inner: &Mutex<PoolInner>) -> std::sync::MutexGuard<'_, PoolInner> {
+    inner.lock().expect("verification pool lock poisoned")
+}
@@ -0,0 +1,220 @@
+//! Client-maintained watchlists consulted alongside official screening lists
+
+use crate::compliance::screening::matcher::{MatchMethod, NameMatcher};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Kind of watchlist entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchlistKind {
+    /// Identity (person or organization name) the client refuses to onboard
+    BlockedIdentity,
+    /// Blockchain address or account id the client refuses to transact with
+    BlockedAddress,
+    /// Counterparty the client has explicitly approved
+    AllowlistedCounterparty,
+}
+
+/// A client watchlist entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistEntry {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub kind: WatchlistKind,
+    /// Name for identities, address or account id otherwise
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl WatchlistEntry {
+    /// Check if the entry is in force at the given time
+    pub fn is_active(&self, at: DateTime<Utc>) -> bool {
+        self.expires_at.map_or(true, |expires_at| expires_at > at)
+    }
+}
+
+/// Fields accepted when creating or updating a watchlist entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistEntryInput {
+    pub kind: WatchlistKind,
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A watchlist match, carrying the entry as provenance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistHit {
+    pub entry: WatchlistEntry,
+    pub score: f64,
+    pub method: MatchMethod,
+}
+
+/// Service managing per-client watchlists
+#[derive(Default)]
+pub struct WatchlistService {
+    entries: RwLock<HashMap<Uuid, WatchlistEntry>>,
+}
+
+impl WatchlistService {
+    /// Create an empty watchlist service
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add an entry to a client's watchlist
+    pub async fn create(&self, client_id: Uuid, input: WatchlistEntryInput) -> Result<WatchlistEntry> {
+        let value = Self::validate_value(input.kind, &input.value)?;
+        let now = Utc::now();
+        let entry = WatchlistEntry {
+            id: Uuid::new_v4(),
+            client_id,
+            kind: input.kind,
+            value,
+            reason: input.reason,
+            created_by: input.created_by,
+            created_at: now,
+            updated_at: now,
+            expires_at: input.expires_at,
+        };
+        
+        self.entries.write().await.insert(entry.id, entry.clone());
+        Ok(entry)
+    }
+    
+    /// Get an entry owned by a client
+    pub async fn get(&self, client_id: Uuid, entry_id: Uuid) -> Result<WatchlistEntry> {
+        self.entries
+            .read()
+            .await
+            .get(&entry_id)
+            .filter(|entry| entry.client_id == client_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::WatchlistEntryNotFound {
+                entry_id: entry_id.to_string(),
+            })
+    }
+    
+    /// List a client's entries, optionally filtered by kind
+    pub async fn list(&self, client_id: Uuid, kind: Option<WatchlistKind>) -> Vec<WatchlistEntry> {
+        let mut entries: Vec<WatchlistEntry> = self
+            .entries
+            .read()
+            .await
+            .values()
+            .filter(|entry| entry.client_id == client_id && kind.map_or(true, |k| entry.kind == k))
+            .cloned()
+            .collect();
+        entries.sort_by_key(|entry| entry.created_at);
+        entries
+    }
+    
+    /// Replace the contents of an entry
+    pub async fn update(&self, client_id: Uuid, entry_id: Uuid, input: WatchlistEntryInput) -> Result<WatchlistEntry> {
+        let value = Self::validate_value(input.kind, &input.value)?;
+        let mut entries = self.entries.write().await;
+        let entry = entries
+            .get_mut(&entry_id)
+            .filter(|entry| entry.client_id == client_id)
+            .ok_or_else(|| ComplianceError::WatchlistEntryNotFound {
+                entry_id: entry_id.to_string(),
+            })?;
+        
+        entry.kind = input.kind;
+        entry.value = value;
+        entry.reason = input.reason;
+        entry.expires_at = input.expires_at;
+        entry.updated_at = Utc::now();
+        
+        Ok(entry.clone())
+    }
+    
+    /// Remove an entry
+    pub async fn delete(&self, client_id: Uuid, entry_id: Uuid) -> Result<WatchlistEntry> {
+        let mut entries = self.entries.write().await;
+        match entries.get(&entry_id) {
+            Some(entry) if entry.client_id == client_id => Ok(entries.remove(&entry_id).expect("entry exists")),
+            _ => Err(ComplianceError::WatchlistEntryNotFound {
+                entry_id: entry_id.to_string(),
+            }),
+        }
+    }
+    
+    /// Match a name against a client's blocked identities
+    pub async fn check_identity(
+        &self,
+        client_id: Uuid,
+        name: &str,
+        language: Option<&str>,
+        matcher: &NameMatcher,
+    ) -> Vec<WatchlistHit> {
+        let now = Utc::now();
+        let mut hits: Vec<WatchlistHit> = self
+            .entries
+            .read()
+            .await
+            .values()
+            .filter(|entry| entry.client_id == client_id && entry.kind == WatchlistKind::BlockedIdentity && entry.is_active(now))
+            .filter_map(|entry| {
+                matcher.matches_in(name, language, &entry.value).map(|m| WatchlistHit {
+                    entry: entry.clone(),
+                    score: m.score,
+                    method: m.method,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits
+    }
+    
+    /// Find the blocked-address entry matching an address, if any
+    pub async fn check_address(&self, client_id: Uuid, address: &str) -> Option<WatchlistHit> {
+        self.find_address(client_id, WatchlistKind::BlockedAddress, address).await
+    }
+    
+    /// Find the allowlist entry matching a counterparty, if any
+    pub async fn allowlisted(&self, client_id: Uuid, counterparty: &str) -> Option<WatchlistHit> {
+        self.find_address(client_id, WatchlistKind::AllowlistedCounterparty, counterparty).await
+    }
+    
+    async fn find_address(&self, client_id: Uuid, kind: WatchlistKind, address: &str) -> Option<WatchlistHit> {
+        let now = Utc::now();
+        let address = Self::normalize_address(address);
+        self.entries
+            .read()
+            .await
+            .values()
+            .find(|entry| entry.client_id == client_id && entry.kind == kind && entry.is_active(now) && entry.value == address)
+            .map(|entry| WatchlistHit {
+                entry: entry.clone(),
+                score: 1.0,
+                method: MatchMethod::Exact,
+            })
+    }
+    
+    fn validate_value(kind: WatchlistKind, value: &str) -> Result<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(ComplianceError::validation("value", "must not be empty"));
+        }
+        Ok(match kind {
+            WatchlistKind::BlockedIdentity => value.to_string(),
+            WatchlistKind::BlockedAddress | WatchlistKind::AllowlistedCounterparty => Self::normalize_address(value),
+        })
+    }
+    
+    /// Addresses and account ids are compared case-insensitively
+    fn normalize_address(address: &str) -> String {
+        address.trim().to_ascii_lowercase()
+    }
+}
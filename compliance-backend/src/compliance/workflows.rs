@@ -0,0 +1,802 @@
+//! Declarative compliance workflows
+//!
+//! A workflow lists the stages an account goes through before it is
+//! attested. Stages run in order and the steps of a stage run in parallel.
+//! Each step has its own retry budget and timeout, and says where the
+//! workflow goes when it fails: stop, carry on, or jump ahead to a later
+//! stage. Workflows are defined in configuration and assigned per business
+//! client; clients without one get the built-in comprehensive check.
+//!
+//! The KYC provider verifies the identity document and liveness in a single
+//! verification, so `kyc_document` and `liveness` steps share its result.
+//! Provider calls still go through the circuit breakers, and each attempt is
+//! billed to the client, but a failed step follows the workflow's branching
+//! rather than the provider's fallback policy. `policy` and `attestation`
+//! steps run once, on the results of the checks before them.
+//!
+//! Every run is recorded with the state of each step, queryable per account.
+//! Runs are checkpointed as each stage settles, with the results of the
+//! checks that passed, and runs interrupted by a restart resume from the
+//! stage they had reached. Only the checks of that stage are repeated, and
+//! an attestation issued just before the interruption is not issued again.
+
+use super::chain_analytics::{self, ChainRiskProfile};
+use super::clients::ClientRegistry;
+use super::metering::BillableOperation;
+use super::provider_credentials::{self, current_client};
+use super::ComplianceService;
+use crate::config::WorkflowConfig;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Runs kept per account, newest first
+pub const MAX_RUNS_PER_ACCOUNT: usize = 20;
+
+/// A check or decision a workflow step performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKind {
+    KycDocument,
+    Liveness,
+    Sanctions,
+    Aml,
+    ChainAnalytics,
+    /// Check that the checked account meets the required compliance level
+    Policy,
+    /// Issue the attestation
+    Attestation,
+}
+
+impl StepKind {
+    /// Name used in configuration
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::KycDocument => "kyc_document",
+            Self::Liveness => "liveness",
+            Self::Sanctions => "sanctions",
+            Self::Aml => "aml",
+            Self::ChainAnalytics => "chain_analytics",
+            Self::Policy => "policy",
+            Self::Attestation => "attestation",
+        }
+    }
+    
+    /// Whether the step decides on earlier results rather than calling a provider
+    fn is_decision(self) -> bool {
+        matches!(self, Self::Policy | Self::Attestation)
+    }
+}
+
+/// Where a workflow goes when a step fails
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// Stop the workflow
+    #[default]
+    Fail,
+    /// Carry on with the next stage
+    Continue,
+    /// Jump ahead to the stage with this id
+    Goto(String),
+}
+
+/// A step of a workflow stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepDefinition {
+    pub kind: StepKind,
+    
+    /// Attempts after a failed one; provider checks only
+    #[serde(default)]
+    pub retries: u32,
+    
+    /// Milliseconds between attempts
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    
+    /// Seconds each attempt may take
+    #[serde(default = "default_step_timeout_secs")]
+    pub timeout_secs: u64,
+    
+    #[serde(default)]
+    pub on_failure: OnFailure,
+}
+
+fn default_retry_delay_ms() -> u64 {
+    500
+}
+
+fn default_step_timeout_secs() -> u64 {
+    60
+}
+
+/// Steps run in parallel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageDefinition {
+    pub id: String,
+    pub steps: Vec<StepDefinition>,
+}
+
+impl StageDefinition {
+    fn step(&self, kind: StepKind) -> Option<&StepDefinition> {
+        self.steps.iter().find(|step| step.kind == kind)
+    }
+}
+
+/// An onboarding flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowDefinition {
+    /// Stages run in order
+    pub stages: Vec<StageDefinition>,
+    
+    /// Level the `policy` step requires; the client's own level when unset
+    #[serde(default)]
+    pub required_level: Option<ComplianceLevel>,
+}
+
+impl WorkflowDefinition {
+    /// Check that the workflow can run
+    ///
+    /// Every kind of step appears at most once. `policy` and `attestation`
+    /// steps each have a stage of their own, after the KYC, AML, and
+    /// sanctions checks they decide on, and the workflow issues an
+    /// attestation. A failed step can only jump ahead, so every workflow ends.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let mut stage_ids = HashSet::new();
+        let mut seen = HashSet::new();
+        for (index, stage) in self.stages.iter().enumerate() {
+            if stage.id.is_empty() {
+                return Err("stage ids must not be empty".to_string());
+            }
+            if !stage_ids.insert(stage.id.as_str()) {
+                return Err(format!("stage id {} is used more than once", stage.id));
+            }
+            if stage.steps.is_empty() {
+                return Err(format!("stage {} has no steps", stage.id));
+            }
+            
+            for step in &stage.steps {
+                let kind = step.kind.name();
+                if !seen.insert(step.kind) {
+                    return Err(format!("step {} appears more than once", kind));
+                }
+                if step.timeout_secs == 0 {
+                    return Err(format!("step {} must have a timeout greater than 0", kind));
+                }
+                if step.kind.is_decision() {
+                    if stage.steps.len() > 1 {
+                        return Err(format!("step {} must have a stage of its own", kind));
+                    }
+                    let checked = seen.contains(&StepKind::KycDocument) || seen.contains(&StepKind::Liveness);
+                    if !checked || !seen.contains(&StepKind::Aml) || !seen.contains(&StepKind::Sanctions) {
+                        return Err(format!("step {} must follow kyc_document or liveness, aml, and sanctions", kind));
+                    }
+                }
+                if let OnFailure::Goto(target) = &step.on_failure {
+                    if !self.stages[index + 1..].iter().any(|later| later.id == *target) {
+                        return Err(format!("step {} can only go to a later stage, not {}", kind, target));
+                    }
+                }
+            }
+        }
+        
+        if !seen.contains(&StepKind::Attestation) {
+            return Err("workflow must issue an attestation".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Lifecycle of a workflow run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Lifecycle of a step in a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// Branched past, or left when the workflow stopped
+    Skipped,
+}
+
+/// State of one step in a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepState {
+    pub stage: String,
+    pub kind: StepKind,
+    pub status: StepStatus,
+    pub attempts: u32,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    
+    /// Error of the last attempt, when it failed
+    pub error: Option<String>,
+}
+
+/// One run of a workflow for an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: Uuid,
+    pub account_id: AccountId,
+    
+    /// Client the workflow ran on behalf of
+    pub client_id: Option<Uuid>,
+    
+    /// Name of the workflow definition
+    pub workflow: String,
+    
+    pub status: WorkflowStatus,
+    pub steps: Vec<StepState>,
+    
+    /// Attestation issued by a completed run
+    pub attestation_id: Option<Uuid>,
+    
+    /// Why a failed run stopped
+    pub error: Option<String>,
+    
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl WorkflowRun {
+    fn start(workflow: &str, definition: &WorkflowDefinition, account_id: &AccountId, now: DateTime<Utc>) -> Self {
+        let steps = definition
+            .stages
+            .iter()
+            .flat_map(|stage| {
+                stage.steps.iter().map(|step| StepState {
+                    stage: stage.id.clone(),
+                    kind: step.kind,
+                    status: StepStatus::Pending,
+                    attempts: 0,
+                    started_at: None,
+                    finished_at: None,
+                    error: None,
+                })
+            })
+            .collect();
+        
+        Self {
+            id: Uuid::new_v4(),
+            account_id: account_id.clone(),
+            client_id: current_client(),
+            workflow: workflow.to_string(),
+            status: WorkflowStatus::Running,
+            steps,
+            attestation_id: None,
+            error: None,
+            started_at: now,
+            finished_at: None,
+        }
+    }
+    
+    fn steps_of<'a>(&'a mut self, stage: &'a str) -> impl Iterator<Item = &'a mut StepState> {
+        self.steps.iter_mut().filter(move |step| step.stage == stage)
+    }
+    
+    fn begin_stage(&mut self, stage: &str, now: DateTime<Utc>) {
+        for step in self.steps_of(stage) {
+            step.status = StepStatus::Running;
+            step.started_at = Some(now);
+        }
+    }
+    
+    fn settle_step(
+        &mut self,
+        stage: &str,
+        kind: StepKind,
+        attempts: u32,
+        error: Option<&ComplianceError>,
+        now: DateTime<Utc>,
+    ) {
+        if let Some(step) = self.steps_of(stage).find(|step| step.kind == kind) {
+            step.status = if error.is_some() { StepStatus::Failed } else { StepStatus::Succeeded };
+            step.attempts = attempts;
+            step.error = error.map(ToString::to_string);
+            step.finished_at = Some(now);
+        }
+    }
+    
+    fn finish(&mut self, error: Option<String>, now: DateTime<Utc>) {
+        for step in &mut self.steps {
+            if matches!(step.status, StepStatus::Pending | StepStatus::Running) {
+                step.status = StepStatus::Skipped;
+            }
+        }
+        self.status = if error.is_some() { WorkflowStatus::Failed } else { WorkflowStatus::Completed };
+        self.error = error;
+        self.finished_at = Some(now);
+    }
+}
+
+/// A finished run, with the attestation a completed run issued
+#[derive(Debug, Clone)]
+pub struct WorkflowOutcome {
+    pub run: WorkflowRun,
+    pub attestation: Option<ComplianceAttestation>,
+}
+
+impl WorkflowOutcome {
+    /// The issued attestation, or the reason the run failed
+    pub fn into_attestation(self) -> Result<ComplianceAttestation> {
+        self.attestation.ok_or_else(|| ComplianceError::WorkflowFailed {
+            workflow: self.run.workflow,
+            run_id: self.run.id.to_string(),
+            reason: self.run.error.unwrap_or_default(),
+        })
+    }
+}
+
+/// Progress of a run, enough to resume it after a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowCheckpoint {
+    /// Definition the run started with; configuration changes apply to later runs
+    pub definition: WorkflowDefinition,
+    pub required_level: ComplianceLevel,
+    
+    /// Stage the run continues from
+    pub stage: usize,
+    
+    /// Results of the provider checks that passed, by step, until a decision step uses them
+    pub results: HashMap<StepKind, serde_json::Value>,
+    
+    /// Attestation generated from the checks
+    pub attestation: Option<ComplianceAttestation>,
+    pub issued: bool,
+}
+
+impl WorkflowCheckpoint {
+    /// Progress of a run that has not started
+    pub fn new(definition: &WorkflowDefinition, required_level: ComplianceLevel) -> Self {
+        Self {
+            definition: definition.clone(),
+            required_level,
+            stage: 0,
+            results: HashMap::new(),
+            attestation: None,
+            issued: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredRun {
+    run: WorkflowRun,
+    checkpoint: WorkflowCheckpoint,
+}
+
+/// Workflow runs per account, with the progress of each
+///
+/// Runs are kept in memory, and written through to a directory when one is
+/// configured so that running ones can be resumed after a restart.
+#[derive(Default)]
+pub struct WorkflowStore {
+    runs: RwLock<HashMap<AccountId, Vec<StoredRun>>>,
+    dir: Option<PathBuf>,
+}
+
+impl WorkflowStore {
+    /// Create an empty in-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create the store configured for workflow runs
+    pub fn from_config(config: &WorkflowConfig) -> Result<Self> {
+        match &config.state_dir {
+            Some(dir) => Self::open(dir),
+            None => Ok(Self::new()),
+        }
+    }
+    
+    /// Open a store persisted in a directory, creating the directory if needed
+    ///
+    /// Each run is kept in `<run id>.json`.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        
+        let mut runs: HashMap<AccountId, Vec<StoredRun>> = HashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let stored: StoredRun = serde_json::from_slice(&std::fs::read(&path)?)?;
+            runs.entry(stored.run.account_id.clone()).or_default().push(stored);
+        }
+        for account_runs in runs.values_mut() {
+            account_runs.sort_by(|a, b| b.run.started_at.cmp(&a.run.started_at));
+        }
+        
+        Ok(Self {
+            runs: RwLock::new(runs),
+            dir: Some(dir),
+        })
+    }
+    
+    /// Insert a run with its progress, or replace the one with the same id
+    ///
+    /// An account keeps its newest runs, and every run still running.
+    pub async fn save(&self, run: &WorkflowRun, checkpoint: &WorkflowCheckpoint) -> Result<()> {
+        let stored = StoredRun {
+            run: run.clone(),
+            checkpoint: checkpoint.clone(),
+        };
+        let bytes = serde_json::to_vec(&stored)?;
+        
+        let mut runs = self.runs.write().await;
+        let account_runs = runs.entry(run.account_id.clone()).or_default();
+        let mut dropped = Vec::new();
+        match account_runs.iter_mut().find(|existing| existing.run.id == run.id) {
+            Some(existing) => *existing = stored,
+            None => {
+                account_runs.insert(0, stored);
+                let mut kept = 0;
+                account_runs.retain(|existing| {
+                    kept += 1;
+                    let keep = kept <= MAX_RUNS_PER_ACCOUNT || existing.run.status == WorkflowStatus::Running;
+                    if !keep {
+                        dropped.push(existing.run.id);
+                    }
+                    keep
+                });
+            }
+        }
+        
+        // Written under the lock, so saves of the same run land in order
+        if let Some(dir) = &self.dir {
+            let tmp = dir.join(format!("{}.json.tmp", run.id));
+            tokio::fs::write(&tmp, bytes).await?;
+            tokio::fs::rename(&tmp, dir.join(format!("{}.json", run.id))).await?;
+            for run_id in dropped {
+                tokio::fs::remove_file(dir.join(format!("{}.json", run_id))).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// An account's runs, newest first
+    pub async fn runs(&self, account_id: &AccountId) -> Vec<WorkflowRun> {
+        self.runs
+            .read()
+            .await
+            .get(account_id)
+            .map(|runs| runs.iter().map(|stored| stored.run.clone()).collect())
+            .unwrap_or_default()
+    }
+    
+    /// Get one of an account's runs
+    pub async fn get(&self, account_id: &AccountId, run_id: Uuid) -> Option<WorkflowRun> {
+        self.runs
+            .read()
+            .await
+            .get(account_id)
+            .and_then(|runs| runs.iter().find(|stored| stored.run.id == run_id))
+            .map(|stored| stored.run.clone())
+    }
+    
+    /// Runs still running, with their progress
+    pub async fn running(&self) -> Vec<(WorkflowRun, WorkflowCheckpoint)> {
+        self.runs
+            .read()
+            .await
+            .values()
+            .flatten()
+            .filter(|stored| stored.run.status == WorkflowStatus::Running)
+            .map(|stored| (stored.run.clone(), stored.checkpoint.clone()))
+            .collect()
+    }
+}
+
+/// Run a step's attempts, each bounded by the step's timeout
+///
+/// Returns the number of attempts made and the last result, or `None` when
+/// the stage has no such step.
+async fn attempt<T, F, Fut>(step: Option<&StepDefinition>, mut call: F) -> Option<(u32, Result<T>)>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let step = step?;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let result = tokio::time::timeout(Duration::from_secs(step.timeout_secs), call())
+            .await
+            .unwrap_or_else(|_| Err(timed_out(step)));
+        if result.is_ok() || attempts > step.retries {
+            return Some((attempts, result));
+        }
+        tokio::time::sleep(Duration::from_millis(step.retry_delay_ms)).await;
+    }
+}
+
+/// Record the result of a step that succeeded, returning its attempts and error
+fn settle<T: Serialize>(
+    outcome: Option<(u32, Result<T>)>,
+    kind: StepKind,
+    results: &mut HashMap<StepKind, serde_json::Value>,
+) -> Option<(u32, Option<ComplianceError>)> {
+    let (attempts, result) = outcome?;
+    match result.and_then(|result| Ok(serde_json::to_value(result)?)) {
+        Ok(value) => {
+            results.insert(kind, value);
+            Some((attempts, None))
+        }
+        Err(e) => Some((attempts, Some(e))),
+    }
+}
+
+/// Take a step's recorded result
+fn take_result<T: DeserializeOwned>(
+    results: &mut HashMap<StepKind, serde_json::Value>,
+    kind: StepKind,
+) -> Result<Option<T>> {
+    Ok(results.remove(&kind).map(serde_json::from_value).transpose()?)
+}
+
+/// Save a run's progress; a run that cannot be saved carries on, but cannot be resumed
+async fn save_progress(runs: &WorkflowStore, run: &WorkflowRun, checkpoint: &WorkflowCheckpoint) {
+    if let Err(e) = runs.save(run, checkpoint).await {
+        tracing::warn!(run_id = %run.id, error = %e, "failed to save workflow progress");
+    }
+}
+
+fn timed_out(step: &StepDefinition) -> ComplianceError {
+    ComplianceError::WorkflowStepTimedOut {
+        step: step.kind.name().to_string(),
+        timeout_secs: step.timeout_secs,
+    }
+}
+
+impl ComplianceService {
+    /// Run a workflow for an account, issuing its attestation when every stage passes
+    ///
+    /// The run is saved to `runs` as each stage settles. A run that fails is
+    /// returned without an attestation; errors are only returned when the run
+    /// could not be carried out at all.
+    pub async fn run_workflow(
+        &self,
+        name: &str,
+        definition: &WorkflowDefinition,
+        account_id: &AccountId,
+        required_level: ComplianceLevel,
+        runs: &WorkflowStore,
+    ) -> Result<WorkflowOutcome> {
+        let run = WorkflowRun::start(name, definition, account_id, self.clock.now());
+        self.drive(run, WorkflowCheckpoint::new(definition, required_level), runs).await
+    }
+    
+    /// Carry on with an interrupted run from the stage it had reached
+    ///
+    /// Must be called on behalf of the run's client, for the client's
+    /// provider credentials to be used.
+    pub async fn resume_workflow(
+        &self,
+        run: WorkflowRun,
+        checkpoint: WorkflowCheckpoint,
+        runs: &WorkflowStore,
+    ) -> Result<WorkflowOutcome> {
+        tracing::info!(
+            run_id = %run.id,
+            account_id = %run.account_id,
+            workflow = %run.workflow,
+            stage = checkpoint.stage,
+            "resuming workflow run"
+        );
+        self.drive(run, checkpoint, runs).await
+    }
+    
+    async fn drive(
+        &self,
+        mut run: WorkflowRun,
+        mut checkpoint: WorkflowCheckpoint,
+        runs: &WorkflowStore,
+    ) -> Result<WorkflowOutcome> {
+        let definition = checkpoint.definition.clone();
+        let required_level = checkpoint.required_level.clone();
+        let account_id = run.account_id.clone();
+        save_progress(runs, &run, &checkpoint).await;
+        
+        while let Some(stage) = definition.stages.get(checkpoint.stage) {
+            run.begin_stage(&stage.id, self.clock.now());
+            save_progress(runs, &run, &checkpoint).await;
+            
+            let mut failed = Vec::new();
+            if let Some(step) = stage.steps.iter().find(|step| step.kind.is_decision()) {
+                let decided = tokio::time::timeout(Duration::from_secs(step.timeout_secs), async {
+                    if checkpoint.attestation.is_none() {
+                        let results = &mut checkpoint.results;
+                        let (Some(kyc), Some(aml), Some(sanctions)) = (
+                            take_result(results, StepKind::KycDocument)?,
+                            take_result(results, StepKind::Aml)?,
+                            take_result(results, StepKind::Sanctions)?,
+                        ) else {
+                            return Err(ComplianceError::ComplianceAttestation {
+                                reason: "KYC, AML, or sanctions check did not pass".to_string(),
+                            });
+                        };
+                        let chain_profile: Option<Option<ChainRiskProfile>> =
+                            take_result(results, StepKind::ChainAnalytics)?;
+                        let mut checked = self
+                            .attestation
+                            .generate_attestation(&account_id, kyc, aml, sanctions)
+                            .await?;
+                        self.country_risk.apply(&mut checked).await;
+                        self.device_risk.apply(&mut checked).await;
+                        if let Some(profile) = chain_profile.flatten() {
+                            chain_analytics::apply_profile(&mut checked, &profile);
+                        }
+                        checkpoint.attestation = Some(checked);
+                    }
+                    let checked = checkpoint.attestation.as_ref().expect("attestation generated above");
+                    
+                    if step.kind == StepKind::Policy {
+                        if !self.meets_compliance_level(checked, required_level.clone(), self.clock.now()).await {
+                            return Err(ComplianceError::CompliancePolicyViolation {
+                                policy: format!("workflow requires {:?} compliance", required_level),
+                            });
+                        }
+                    } else {
+                        // A run interrupted after issuing finds its attestation already recorded
+                        let recorded = self
+                            .events
+                            .project(&account_id, None)
+                            .await?
+                            .is_some_and(|state| state.attestation.id == checked.id);
+                        if !recorded {
+                            self.issue_checked(checked).await?;
+                        }
+                        checkpoint.issued = true;
+                    }
+                    Ok(())
+                })
+                .await
+                .unwrap_or_else(|_| Err(timed_out(step)));
+                
+                let error = decided.err();
+                run.settle_step(&stage.id, step.kind, 1, error.as_ref(), self.clock.now());
+                failed.extend(error.map(|e| (step.kind, e.to_string())));
+            } else {
+                // A verification from an earlier stage also stands for this one
+                let had_kyc = checkpoint.results.contains_key(&StepKind::KycDocument);
+                let kyc_step = stage
+                    .step(StepKind::KycDocument)
+                    .or_else(|| stage.step(StepKind::Liveness))
+                    .filter(|_| !had_kyc);
+                let (kyc_outcome, aml_outcome, sanctions_outcome, chain_outcome) = tokio::join!(
+                    attempt(kyc_step, || async {
+                        self.meter.charge_current(BillableOperation::KycVerification).await?;
+                        self.breakers.kyc.call(self.kyc.verify_account(&account_id)).await
+                    }),
+                    attempt(stage.step(StepKind::Aml), || self.breakers.aml.call(self.aml.assess_risk(&account_id))),
+                    attempt(stage.step(StepKind::Sanctions), || async {
+                        self.meter.charge_current(BillableOperation::Screening).await?;
+                        self.breakers.sanctions.call(self.sanctions.screen_account(&account_id)).await
+                    }),
+                    attempt(stage.step(StepKind::ChainAnalytics), || {
+                        self.breakers.chain_analytics.call(self.chain_analytics.account_profile(&account_id))
+                    }),
+                );
+                
+                let results = &mut checkpoint.results;
+                let kyc_settled = match settle(kyc_outcome, StepKind::KycDocument, results) {
+                    Some(settled) => Some(settled),
+                    None if had_kyc => Some((0, None)),
+                    None => None,
+                };
+                let settled = [
+                    (StepKind::KycDocument, kyc_settled),
+                    (StepKind::Aml, settle(aml_outcome, StepKind::Aml, results)),
+                    (StepKind::Sanctions, settle(sanctions_outcome, StepKind::Sanctions, results)),
+                    (StepKind::ChainAnalytics, settle(chain_outcome, StepKind::ChainAnalytics, results)),
+                ];
+                let now = self.clock.now();
+                for (kind, outcome) in settled {
+                    let Some((attempts, error)) = outcome else {
+                        continue;
+                    };
+                    let kinds: &[StepKind] = match kind {
+                        StepKind::KycDocument => &[StepKind::KycDocument, StepKind::Liveness],
+                        _ => &[kind],
+                    };
+                    for kind in kinds {
+                        run.settle_step(&stage.id, *kind, attempts, error.as_ref(), now);
+                    }
+                    if let Some(e) = error {
+                        failed.extend(kinds.iter().map(|kind| (*kind, e.to_string())));
+                    }
+                }
+            }
+            
+            // The first failed step, in definition order, decides where the workflow goes
+            let failure = stage
+                .steps
+                .iter()
+                .find_map(|step| failed.iter().find(|(kind, _)| *kind == step.kind).map(|(_, e)| (step, e)));
+            checkpoint.stage = match failure {
+                None => checkpoint.stage + 1,
+                Some((step, error)) => match &step.on_failure {
+                    OnFailure::Fail => {
+                        run.finish(Some(format!("{}: {}", step.kind.name(), error)), self.clock.now());
+                        checkpoint.results.clear();
+                        save_progress(runs, &run, &checkpoint).await;
+                        return Ok(WorkflowOutcome { run, attestation: None });
+                    }
+                    OnFailure::Continue => checkpoint.stage + 1,
+                    OnFailure::Goto(target) => definition
+                        .stages
+                        .iter()
+                        .position(|stage| stage.id == *target)
+                        .ok_or_else(|| ComplianceError::internal(format!("workflow stage {} not found", target)))?,
+                },
+            };
+            save_progress(runs, &run, &checkpoint).await;
+        }
+        
+        let attestation = checkpoint.attestation.clone().filter(|_| checkpoint.issued);
+        let error = attestation.is_none().then(|| "no attestation was issued".to_string());
+        run.attestation_id = attestation.as_ref().map(|attestation| attestation.id);
+        run.finish(error, self.clock.now());
+        checkpoint.results.clear();
+        save_progress(runs, &run, &checkpoint).await;
+        Ok(WorkflowOutcome { run, attestation })
+    }
+}
+
+/// Resume the runs a previous process left running
+///
+/// Runs are resumed one after another in the background, each on behalf of
+/// its client with the client's provider credentials and data region.
+pub fn resume_interrupted(
+    compliance: Arc<ComplianceService>,
+    runs: Arc<WorkflowStore>,
+    clients: Arc<ClientRegistry>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        for (run, checkpoint) in runs.running().await {
+            let run_id = run.id;
+            let resumed = match run.client_id {
+                Some(client_id) => {
+                    let region = match clients.get(client_id).await {
+                        Ok(client) => client.region,
+                        Err(e) => {
+                            tracing::warn!(
+                                run_id = %run_id,
+                                client_id = %client_id,
+                                error = %e,
+                                "workflow run's client not found"
+                            );
+                            continue;
+                        }
+                    };
+                    let resumed = compliance.resume_workflow(run, checkpoint, &runs);
+                    provider_credentials::with_client(client_id, region, resumed).await
+                }
+                None => compliance.resume_workflow(run, checkpoint, &runs).await,
+            };
+            if let Err(e) = resumed {
+                tracing::warn!(run_id = %run_id, error = %e, "failed to resume workflow run");
+            }
+        }
+    })
+}
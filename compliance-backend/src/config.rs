@@ -1,11 +1,34 @@
 //! Configuration management for the ZeroTrust Compliance Backend
 
+use crate::alerts::AlertSeverity;
+use crate::compliance::breaker::Provider;
+use crate::compliance::metering::BillableOperation;
+use crate::compliance::presentation::QR_BYTE_CAPACITY;
+use crate::compliance::provider_routing::DEFAULT_VENDOR;
+use crate::compliance::risk_factors::RiskFactorDefinition;
+use crate::compliance::scope::AssetClass;
+use crate::compliance::workflows::WorkflowDefinition;
+use crate::crypto::ThresholdPolicy;
+use crate::secrets::SecretResolver;
+use crate::types::{AccountId, AccountIdKind, ComplianceLevel, DataRegion};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Default webhook secret, rejected outside development
+const DEFAULT_WEBHOOK_SECRET: &str = "default_webhook_secret";
+
+/// Default JWT secret, rejected outside development
+const DEFAULT_JWT_SECRET: &str = "default_jwt_secret";
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Deployment environment
+    pub environment: Environment,
+    
     /// Server configuration
     pub server: ServerConfig,
     
@@ -26,6 +49,48 @@ pub struct Config {
     
     /// Logging configuration
     pub logging: LoggingConfig,
+    
+    /// Alerting configuration
+    pub alerting: AlertingConfig,
+    
+    /// External secret sources
+    pub secrets: SecretsConfig,
+    
+    /// Management information reporting
+    pub reporting: ReportingConfig,
+    
+    /// Event bus publishing
+    pub event_bus: EventBusConfig,
+    
+    /// Public status page data
+    #[serde(default)]
+    pub status_page: StatusPageConfig,
+    
+    /// Sandbox environment for business clients' test traffic
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    
+    /// Translations of display text shown to end users
+    #[serde(default)]
+    pub localization: LocalizationConfig,
+    
+    /// Schedules of background jobs
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    
+    /// Running scheduled jobs and webhook delivery on one instance of a
+    /// multi-instance deployment
+    #[serde(default)]
+    pub leader_election: LeaderElectionConfig,
+}
+
+/// Deployment environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Development,
+    Staging,
+    Production,
 }
 
 /// Server configuration
@@ -37,16 +102,107 @@ pub struct ServerConfig {
     /// Server port
     pub port: u16,
     
-    /// Maximum request body size in bytes
-    pub max_body_size: usize,
+    /// Request body size and content type accepted by each route
+    #[serde(default)]
+    pub body_limits: BodyLimitsConfig,
     
     /// Request timeout in seconds
     pub request_timeout: u64,
     
+    /// How long responses to requests carrying an `Idempotency-Key` are replayed, in seconds
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+    
+    /// Longest a long-polling request may wait for a change, in seconds
+    #[serde(default = "default_max_long_poll_secs")]
+    pub max_long_poll_secs: u64,
+    
+    /// Compression and streaming of responses
+    #[serde(default)]
+    pub responses: ResponseConfig,
+    
     /// CORS configuration
     pub cors: CorsConfig,
 }
 
+/// Compression and streaming of responses
+///
+/// Responses are compressed in an offered encoding the client accepts in its
+/// `Accept-Encoding` header. Large responses, such as proof envelopes and
+/// exports, are sent in chunks as they are written, so a client starts
+/// receiving before the whole body is out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResponseConfig {
+    /// Whether responses are compressed
+    pub compression: bool,
+    
+    /// Whether gzip is offered
+    pub gzip: bool,
+    
+    /// Whether Brotli is offered
+    pub br: bool,
+    
+    /// Smallest response compressed, in bytes
+    pub min_compress_bytes: u16,
+    
+    /// Responses larger than this are streamed in chunks, in bytes
+    pub stream_threshold_bytes: usize,
+    
+    /// Size of each chunk of a streamed response, in bytes
+    pub stream_chunk_bytes: usize,
+}
+
+/// Request body limits
+///
+/// Routes are keyed by method and matched path, e.g.
+/// `POST /v1/end-user/session/documents`. A route without an entry accepts
+/// `query_max_bytes` for GET, HEAD, DELETE and OPTIONS requests and
+/// `default_max_bytes` of `default_content_types` for the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BodyLimitsConfig {
+    /// Largest body accepted by a write without its own limit, in bytes
+    pub default_max_bytes: usize,
+    
+    /// Largest body accepted by a read or delete without its own limit, in
+    /// bytes; 0 rejects any body
+    pub query_max_bytes: usize,
+    
+    /// Media types accepted by a route without its own list; an
+    /// `application/json` entry also accepts `application/*+json`
+    pub default_content_types: Vec<String>,
+    
+    /// Limits of individual routes, by `METHOD /path`
+    pub routes: HashMap<String, RouteBodyLimit>,
+}
+
+/// Body limit of one route
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteBodyLimit {
+    /// Largest body accepted, in bytes
+    pub max_bytes: usize,
+    
+    /// Media types accepted; empty for the default content types
+    #[serde(default)]
+    pub content_types: Vec<String>,
+}
+
+/// Methods whose bodies fall under `query_max_bytes` by default
+pub const QUERY_METHODS: &[&str] = &["GET", "HEAD", "DELETE", "OPTIONS"];
+
+impl BodyLimitsConfig {
+    /// Largest body and accepted media types of a route
+    pub fn resolve(&self, method: &str, path: &str) -> (usize, &[String]) {
+        match self.routes.get(&format!("{} {}", method, path)) {
+            Some(route) if route.content_types.is_empty() => (route.max_bytes, &self.default_content_types),
+            Some(route) => (route.max_bytes, &route.content_types),
+            None if QUERY_METHODS.contains(&method) => (self.query_max_bytes, &self.default_content_types),
+            None => (self.default_max_bytes, &self.default_content_types),
+        }
+    }
+}
+
 /// CORS configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CorsConfig {
@@ -66,9 +222,12 @@ pub struct CorsConfig {
 /// Database configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
-    /// Database URL
+    /// Database URL, with an optional `{password}` placeholder
     pub url: String,
     
+    /// Database password substituted into the URL (usually a secret reference)
+    pub password: Option<String>,
+    
     /// Maximum number of connections
     pub max_connections: u32,
     
@@ -80,6 +239,25 @@ pub struct DatabaseConfig {
     
     /// Run migrations on startup
     pub run_migrations: bool,
+    
+    /// Databases or schemas holding the PII of clients tagged with each region
+    #[serde(default)]
+    pub regions: HashMap<DataRegion, RegionalDatabaseConfig>,
+}
+
+/// Where a region's PII is stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionalDatabaseConfig {
+    /// Database URL, with an optional `{password}` placeholder; the primary database when unset
+    #[serde(default)]
+    pub url: Option<String>,
+    
+    /// Database password substituted into the URL (usually a secret reference)
+    #[serde(default)]
+    pub password: Option<String>,
+    
+    /// Schema the region's tables live in
+    pub schema: String,
 }
 
 /// Miden client configuration
@@ -111,6 +289,72 @@ pub struct MidenConfig {
     
     /// Enable delegated proving
     pub enable_delegated_proving: bool,
+    
+    /// Admission queue in front of proof generation
+    #[serde(default)]
+    pub proving_queue: ProvingQueueConfig,
+    
+    /// Worker pool proofs are verified on
+    #[serde(default)]
+    pub verification_pool: VerificationPoolConfig,
+    
+    /// Parameters baked into generated account components
+    #[serde(default)]
+    pub components: ComponentTemplateConfig,
+}
+
+/// Proof generation queue configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvingQueueConfig {
+    /// Proofs generated concurrently
+    pub max_concurrent: usize,
+    
+    /// Interactive requests allowed to wait for a slot
+    pub max_queued_interactive: usize,
+    
+    /// Batch requests allowed to wait for a slot
+    pub max_queued_batch: usize,
+    
+    /// Proof generation time assumed before any proofs have completed, in milliseconds
+    pub initial_estimate_ms: u64,
+}
+
+/// Proof verification pool configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationPoolConfig {
+    /// Proofs verified concurrently, each on its own blocking thread
+    pub max_concurrent: usize,
+    
+    /// Verifications allowed to wait for a worker
+    pub max_queued: usize,
+    
+    /// Verification time assumed before any proofs have been verified, in milliseconds
+    pub initial_estimate_ms: u64,
+}
+
+/// Account component generation parameters
+///
+/// Changing any of these changes the generated component code, so accounts
+/// deployed with earlier parameters are reported as unrecognized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentTemplateConfig {
+    /// Seconds a KYC verification recorded on-chain stays valid
+    pub kyc_validity_secs: u64,
+    
+    /// Verifier IDs allowed to verify KYC data; any verifier when empty
+    pub authorized_verifiers: Vec<u64>,
+    
+    /// Transaction amount from which the AML component adds large-transaction risk
+    pub large_transaction_threshold: u64,
+    
+    /// Risk score from which the AML component reports medium risk
+    pub medium_risk_score: u64,
+    
+    /// Risk score from which the AML component reports high risk
+    pub high_risk_score: u64,
+    
+    /// Authorization keys accepted for manual sanctions overrides; any non-zero key when empty
+    pub sanctions_override_keys: Vec<u64>,
 }
 
 /// Compliance configuration
@@ -127,6 +371,319 @@ pub struct ComplianceConfig {
     
     /// Attestation configuration
     pub attestation: AttestationConfig,
+    
+    /// Step-up verification configuration
+    pub step_up: StepUpConfig,
+    
+    /// Manual override configuration
+    pub overrides: OverrideConfig,
+    
+    /// Circuit breaker settings for external providers
+    #[serde(default)]
+    pub providers: ProviderResilienceConfig,
+    
+    /// Source-of-funds and source-of-wealth declaration requirements
+    #[serde(default)]
+    pub funds_declarations: FundsDeclarationConfig,
+    
+    /// Inbound result callbacks from providers
+    #[serde(default)]
+    pub callbacks: ProviderCallbackConfig,
+    
+    /// Per-client provider credential overrides
+    #[serde(default)]
+    pub provider_credentials: ProviderCredentialsConfig,
+    
+    /// Region-specific provider endpoints for clients with data residency requirements
+    #[serde(default)]
+    pub residency: ResidencyConfig,
+    
+    /// Onboarding workflows per business client
+    #[serde(default)]
+    pub workflows: WorkflowConfig,
+    
+    /// Usage metering and plan quotas per business client
+    #[serde(default)]
+    pub metering: MeteringConfig,
+    
+    /// Alerts on clients' outcomes deviating from their baseline
+    #[serde(default)]
+    pub anomaly_detection: AnomalyDetectionConfig,
+    
+    /// Sessions in which end users complete KYC directly
+    #[serde(default)]
+    pub verification_sessions: VerificationSessionConfig,
+    
+    /// Provider vendors' costs and cost-aware routing between them
+    #[serde(default)]
+    pub provider_routing: ProviderRoutingConfig,
+    
+    /// Offboarding of accounts and erasure of their PII
+    #[serde(default)]
+    pub offboarding: OffboardingConfig,
+    
+    /// Detection of one person onboarding under several accounts
+    #[serde(default)]
+    pub duplicate_detection: DuplicateDetectionConfig,
+    
+    /// QR code and deep link presentation of proofs
+    #[serde(default)]
+    pub presentations: PresentationConfig,
+}
+
+/// End-user verification sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerificationSessionConfig {
+    pub enabled: bool,
+    
+    /// Lifetime of a session when the client does not ask for one, in seconds
+    pub default_ttl_secs: u64,
+    
+    /// Longest lifetime a client may ask for, in seconds
+    pub max_ttl_secs: u64,
+    
+    /// Documents an end user may upload in one session
+    pub max_documents: usize,
+    
+    /// Hosted verification page; sessions get a link to it carrying their token
+    pub hosted_url: Option<String>,
+}
+
+/// Account offboarding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OffboardingConfig {
+    /// Days an offboarded account's PII is retained before it is erased
+    pub pii_retention_days: u32,
+    
+    /// Miden account that consumes outstanding compliance notes addressed to
+    /// offboarded accounts; notes are only reported when unset
+    pub void_account_id: Option<String>,
+    
+    /// Seconds between sweeps erasing PII whose retention has ended
+    pub erasure_interval_secs: u64,
+}
+
+/// Detection of one person onboarding under several accounts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuplicateDetectionConfig {
+    pub enabled: bool,
+    
+    /// Clients whose accounts are also matched against each other's, for end
+    /// users who consented to it; a client's own accounts are always matched
+    pub cross_client: Vec<Uuid>,
+}
+
+/// QR code and deep link presentation of proofs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresentationConfig {
+    /// Base URL of this API that reference presentations are retrieved from;
+    /// proofs can only be presented inline when unset
+    pub public_url: Option<String>,
+    
+    /// Longest presentation URI, in bytes, that carries its envelope inline
+    pub max_inline_bytes: usize,
+    
+    /// How long a reference presentation can be retrieved, in seconds
+    pub reference_ttl_secs: u64,
+}
+
+/// Detection of anomalous screening and verification outcomes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyDetectionConfig {
+    pub enabled: bool,
+    
+    /// Length of the windows outcomes are counted in, in seconds
+    pub window_secs: u64,
+    
+    /// Span in windows of the exponentially weighted baseline
+    pub baseline_windows: u32,
+    
+    /// Windows a baseline is built from before deviations are flagged
+    pub min_baseline_windows: u32,
+    
+    /// Outcomes a window must hold before it is tested
+    pub min_samples: u64,
+    
+    /// Standard errors above the baseline at which a window is flagged
+    pub z_threshold: f64,
+    
+    /// Accounts listed in an alert
+    pub max_affected_accounts: usize,
+}
+
+/// Declarative onboarding workflows
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    /// Workflow definitions by name
+    pub definitions: HashMap<String, WorkflowDefinition>,
+    
+    /// Workflow for clients without one of their own; the built-in check when unset
+    pub default: Option<String>,
+    
+    /// Workflow of each business client, by client id
+    pub clients: HashMap<Uuid, String>,
+    
+    /// Directory workflow runs are persisted to, so that runs interrupted by a
+    /// restart resume where they stopped. Runs are kept in memory only, and
+    /// interrupted ones are lost, when unset.
+    pub state_dir: Option<String>,
+}
+
+impl WorkflowConfig {
+    /// Name and definition of the workflow a client's checks run through, if any
+    pub fn for_client(&self, client_id: Uuid) -> Option<(&str, &WorkflowDefinition)> {
+        let name = self.clients.get(&client_id).or(self.default.as_ref())?;
+        self.definitions.get_key_value(name).map(|(name, definition)| (name.as_str(), definition))
+    }
+}
+
+/// Usage metering and plan quotas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeteringConfig {
+    /// Plans by name
+    pub plans: HashMap<String, PlanConfig>,
+    
+    /// Plan of clients without one of their own; usage is unlimited when unset
+    pub default_plan: Option<String>,
+    
+    /// Plan of each business client, by client id
+    pub clients: HashMap<Uuid, String>,
+    
+    /// Percentages of a quota at which a usage webhook is sent, once per period
+    pub alert_thresholds: Vec<u8>,
+}
+
+/// A billing plan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanConfig {
+    /// Operations allowed per billing period; operations not listed are unlimited
+    #[serde(default)]
+    pub quotas: HashMap<BillableOperation, u64>,
+}
+
+impl MeteringConfig {
+    /// Name and definition of a client's plan, if it has one
+    pub fn plan_for(&self, client_id: Uuid) -> Option<(&str, &PlanConfig)> {
+        let name = self.clients.get(&client_id).or(self.default_plan.as_ref())?;
+        self.plans.get_key_value(name).map(|(name, plan)| (name.as_str(), plan))
+    }
+}
+
+/// Provider vendors and cost-aware routing of provider calls between them
+///
+/// Each provider's globally configured vendor is named `default`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderRoutingConfig {
+    /// Currency vendor costs are stated in
+    pub currency: String,
+    
+    /// Cost of a call to each provider's default vendor; free when not listed
+    pub default_costs: HashMap<Provider, f64>,
+    
+    /// Vendors other than the default ones, by name
+    pub vendors: HashMap<String, VendorConfig>,
+    
+    /// Routing rules; the first rule matching a call picks its vendors, and
+    /// calls no rule matches go to the default vendor
+    pub rules: Vec<RoutingRule>,
+}
+
+/// A vendor provider calls can be routed to
+///
+/// The vendor must serve the same API as the provider's default vendor; only
+/// the endpoint and API key differ.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorConfig {
+    /// Provider the vendor serves
+    pub provider: Provider,
+    
+    pub endpoint: String,
+    
+    #[serde(default)]
+    pub api_key: Option<String>,
+    
+    /// Cost of a call
+    pub cost_per_call: f64,
+}
+
+/// Vendors serving a provider's calls for accounts at some compliance levels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingRule {
+    pub provider: Provider,
+    
+    /// Compliance levels of the accounts the rule applies to, by their current
+    /// attestation; accounts without one count as `basic`. Every level when empty.
+    #[serde(default)]
+    pub levels: Vec<ComplianceLevel>,
+    
+    /// Vendors to call, in order
+    pub vendors: Vec<String>,
+    
+    /// Call the vendors cheapest first instead of in the order listed
+    #[serde(default)]
+    pub cheapest_first: bool,
+    
+    /// Call the next vendor when one is unavailable; only the first is called otherwise
+    #[serde(default = "default_routing_fallback")]
+    pub fallback: bool,
+}
+
+/// Data residency routing of provider calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResidencyConfig {
+    /// Provider endpoints serving each region
+    pub regions: HashMap<DataRegion, HashMap<Provider, RegionalProviderConfig>>,
+    
+    /// Regions whose PII may only reach resources configured for the region;
+    /// other regions fall back to the global resources
+    pub strict_regions: Vec<DataRegion>,
+}
+
+/// A provider endpoint serving one region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionalProviderConfig {
+    pub endpoint: String,
+    
+    /// API key for the regional endpoint; the globally configured key when unset
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// Business clients' own provider credentials
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderCredentialsConfig {
+    /// Hex-encoded 32-byte key sealing stored client credentials; clients
+    /// cannot register credentials while unset
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+}
+
+/// Inbound provider callback configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderCallbackConfig {
+    /// Signature verification per provider; callbacks from providers not listed are rejected
+    #[serde(default)]
+    pub providers: HashMap<Provider, CallbackVerificationConfig>,
+}
+
+/// How a provider signs its callbacks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallbackVerificationConfig {
+    /// Shared secret the provider signs callback bodies with
+    pub secret: String,
+    
+    /// Header carrying the hex HMAC-SHA256 of the body
+    #[serde(default = "default_callback_signature_header")]
+    pub signature_header: String,
 }
 
 /// KYC configuration
@@ -152,6 +709,70 @@ pub struct KycConfig {
     
     /// Verification expiry in days
     pub verification_expiry_days: u32,
+    
+    /// Limits and malware scanning for uploaded identity documents
+    #[serde(default)]
+    pub documents: DocumentIntakeConfig,
+}
+
+/// Intake of uploaded identity documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentIntakeConfig {
+    /// Largest document accepted, in bytes
+    pub max_document_bytes: usize,
+    
+    /// Media types documents may be uploaded as
+    pub allowed_content_types: Vec<String>,
+    
+    /// Malware scanner documents pass through before reaching the vault
+    pub scanner: Option<DocumentScannerBackend>,
+    
+    /// Seconds a scan may take
+    pub scan_timeout_secs: u64,
+    
+    /// Accept documents unscanned when the scanner fails, instead of rejecting them
+    pub fail_open: bool,
+    
+    /// Checks on images before they are accepted
+    pub image_quality: ImageQualityConfig,
+}
+
+/// Local image quality checks, scored against `kyc.min_quality_score`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageQualityConfig {
+    pub enabled: bool,
+    
+    /// Shorter side, in pixels, at which resolution scores full marks
+    pub min_short_side_px: u32,
+    
+    /// Variance of the Laplacian at which sharpness scores full marks
+    pub sharp_laplacian_variance: f64,
+    
+    /// Luma at or above which a pixel counts as glare
+    pub glare_luma: u8,
+    
+    /// Share of glare pixels at which the glare check scores zero
+    pub max_glare_share: f64,
+    
+    /// Width of the border strips checked for cropping, as a share of each side
+    pub border_share: f64,
+    
+    /// Highest ratio of a border strip's edge density to the interior's for the
+    /// strip to count as background
+    pub max_border_edge_ratio: f64,
+}
+
+/// Malware scanner for uploaded documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DocumentScannerBackend {
+    /// ClamAV daemon, streamed to with `INSTREAM`
+    Clamd { address: String },
+    
+    /// Scanning service taking the document as the request body
+    Http { url: String, api_key: Option<String> },
 }
 
 /// AML configuration
@@ -174,6 +795,138 @@ pub struct AmlConfig {
     
     /// Transaction monitoring settings
     pub transaction_monitoring: TransactionMonitoringConfig,
+    
+    /// Geographic risk scoring
+    #[serde(default)]
+    pub country_risk: CountryRiskConfig,
+    
+    /// Blockchain address risk scoring
+    #[serde(default)]
+    pub chain_analytics: ChainAnalyticsConfig,
+    
+    /// Risk propagated from counterparties
+    #[serde(default)]
+    pub counterparty_risk: CounterpartyRiskConfig,
+    
+    /// Device and IP risk signals
+    #[serde(default)]
+    pub device_risk: DeviceRiskConfig,
+    
+    /// Additional risk factors evaluated from account data
+    #[serde(default)]
+    pub custom_factors: Vec<RiskFactorDefinition>,
+}
+
+/// On-chain analytics provider configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainAnalyticsConfig {
+    /// Include address risk in AML scoring
+    pub enabled: bool,
+    
+    /// Analytics provider
+    pub backend: Option<ChainAnalyticsBackend>,
+    
+    /// Seconds an address score is reused before the provider is asked again
+    pub cache_ttl_secs: u64,
+    
+    /// Share of an address's volume directly exposed to mixers, darknet markets,
+    /// or hacks above which the address is treated as high risk
+    pub max_direct_exposure_share: f64,
+    
+    /// Maximum addresses linked to a single account
+    pub max_addresses_per_account: usize,
+}
+
+/// On-chain analytics provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainAnalyticsBackend {
+    /// Chainalysis address screening API
+    Chainalysis { api_url: String, api_key: String },
+    
+    /// TRM Labs address screening API
+    Trm { api_url: String, api_key: String },
+}
+
+/// Propagation of risk across the counterparty graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CounterpartyRiskConfig {
+    /// Raise AML risk levels with risk propagated from counterparties
+    pub enabled: bool,
+    
+    /// Edges risk propagates across
+    pub max_hops: usize,
+    
+    /// Factor risk is multiplied by per hop
+    pub decay: f64,
+    
+    /// Share of a node's volume below which an edge does not propagate risk
+    pub min_share: f64,
+    
+    /// Nodes visited when computing an account's neighborhood
+    pub max_neighborhood_nodes: usize,
+}
+
+/// Risk of the devices and networks an account is used from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceRiskConfig {
+    /// Accept device and IP signals and include them in AML scoring
+    pub enabled: bool,
+    
+    /// Risk of a connection through Tor
+    pub tor_weight: f64,
+    
+    /// Risk of a connection through a VPN
+    pub vpn_weight: f64,
+    
+    /// Risk of a connection through an open or anonymizing proxy
+    pub proxy_weight: f64,
+    
+    /// Risk of a connection from a hosting provider or data center
+    pub hosting_weight: f64,
+    
+    /// Risk of an IP address geolocated outside the country of residence
+    pub residence_mismatch_weight: f64,
+    
+    /// Risk of a device fingerprint shared with other accounts
+    pub shared_device_weight: f64,
+    
+    /// Accounts a device fingerprint must be seen on to count as shared
+    pub shared_device_accounts: usize,
+    
+    /// Observations retained per account, oldest dropped first
+    pub max_observations: usize,
+}
+
+/// Country risk weights used for geographic AML risk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryRiskConfig {
+    /// Include geographic risk in AML scoring
+    pub enabled: bool,
+    
+    /// JSON country-risk dataset (FATF lists and Basel AML Index scores);
+    /// the built-in FATF lists are used when unset
+    pub dataset_path: Option<String>,
+    
+    /// Risk of a country on the FATF call-for-action (black) list
+    pub fatf_black_list_weight: f64,
+    
+    /// Risk of a country under FATF increased monitoring (grey list)
+    pub fatf_grey_list_weight: f64,
+    
+    /// Per-country risk overrides keyed by ISO 3166-1 alpha-2 code
+    pub country_weights: HashMap<String, f64>,
+    
+    /// Weight of the country of residence in the combined score
+    pub residence_weight: f64,
+    
+    /// Weight of the identity document issuing country in the combined score
+    pub document_issuer_weight: f64,
+    
+    /// Weight of the riskiest counterparty country in the combined score
+    pub counterparty_weight: f64,
 }
 
 /// Risk thresholds for AML
@@ -201,8 +954,34 @@ pub struct TransactionMonitoringConfig {
     /// Maximum daily transaction count
     pub max_daily_transactions: u32,
     
+    /// Maximum hourly transaction count
+    pub max_hourly_transactions: u32,
+    
+    /// Maximum total transaction volume per day
+    pub max_daily_volume: u64,
+    
     /// Suspicious pattern detection
     pub enable_pattern_detection: bool,
+    
+    /// Buffering of reported transactions before they are written
+    #[serde(default)]
+    pub ingestion: MonitoringIngestionConfig,
+}
+
+/// Batched writes of reported transactions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitoringIngestionConfig {
+    /// Milliseconds between flushes of the transaction buffer
+    pub flush_interval_ms: u64,
+    
+    /// Buffered transactions that trigger an early flush, and the rows per insert
+    pub flush_batch_size: usize,
+    
+    /// Buffered transactions beyond which reports are rejected until a flush catches up
+    pub max_buffered_transactions: usize,
+    
+    /// Seconds between checkpoints of per-account aggregates
+    pub checkpoint_interval_secs: u64,
 }
 
 /// Sanctions screening configuration
@@ -225,22 +1004,301 @@ pub struct SanctionsConfig {
     
     /// Fuzzy matching threshold
     pub fuzzy_match_threshold: f64,
+    
+    /// Directory ingested lists and their candidate indexes are persisted in
+    ///
+    /// Lists are kept in memory only, and must be re-ingested after a
+    /// restart, when unset.
+    #[serde(default)]
+    pub list_store_dir: Option<String>,
+    
+    /// Weights of secondary attributes in composite match scores
+    #[serde(default)]
+    pub attribute_weights: AttributeWeights,
 }
 
-/// Attestation configuration
+/// Contribution of secondary attributes to a screening match score
+///
+/// Bonuses are added to the name score when an attribute agrees with the
+/// list entry; penalties are subtracted when it disagrees.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AttestationConfig {
-    /// Attestation validity period in days
-    pub validity_period_days: u32,
+pub struct AttributeWeights {
+    /// Years by which dates of birth may differ before they count against a match
+    pub dob_tolerance_years: u32,
     
-    /// Enable proof verification
-    pub enable_proof_verification: bool,
+    pub dob_match_bonus: f64,
     
-    /// Proof verification timeout in seconds
-    pub proof_verification_timeout: u64,
+    /// Bonus for dates of birth agreeing to the precision both are known in
+    pub dob_partial_bonus: f64,
+    
+    pub dob_mismatch_penalty: f64,
+    
+    pub nationality_match_bonus: f64,
+    
+    pub nationality_mismatch_penalty: f64,
+    
+    pub document_match_bonus: f64,
+    
+    pub document_mismatch_penalty: f64,
+    
+    pub address_match_bonus: f64,
+}
+
+/// Attestation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationConfig {
+    /// Attestation validity period in days
+    pub validity_period_days: u32,
+    
+    /// Enable proof verification
+    pub enable_proof_verification: bool,
+    
+    /// Proof verification timeout in seconds
+    pub proof_verification_timeout: u64,
     
     /// Maximum proof size in bytes
     pub max_proof_size: usize,
+    
+    /// Proof challenge lifetime in seconds
+    pub challenge_ttl_secs: u64,
+    
+    /// Maximum proof envelope lifetime in seconds
+    pub proof_validity_secs: u64,
+    
+    /// Minimum compliance level for proofs scoped to each asset class
+    #[serde(default = "default_asset_class_levels")]
+    pub asset_class_levels: HashMap<AssetClass, ComplianceLevel>,
+    
+    /// Pre-issuance of renewals ahead of expiry
+    #[serde(default)]
+    pub renewal: RenewalConfig,
+    
+    /// On-chain publication of the compliant account set
+    #[serde(default)]
+    pub oracle: OracleConfig,
+    
+    /// Signed registry of valid attestations with non-membership proofs
+    #[serde(default)]
+    pub registry: RegistryConfig,
+    
+    /// Batched on-chain anchoring of issued attestations
+    #[serde(default)]
+    pub epochs: EpochConfig,
+    
+    /// Independent timestamping of attestation issuance
+    #[serde(default)]
+    pub notarization: NotarizationConfig,
+    
+    /// Caching of proof verification outcomes
+    #[serde(default)]
+    pub verification_cache: VerificationCacheConfig,
+    
+    /// Attestations that need several signers
+    #[serde(default)]
+    pub threshold_signing: ThresholdSigningConfig,
+}
+
+/// Attestation pre-issuance configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewalConfig {
+    /// Pre-issue renewals in the background
+    pub enabled: bool,
+    
+    /// Hours before expiry at which an attestation is renewed
+    pub lead_time_hours: u32,
+    
+    /// Seconds between renewal passes
+    pub check_interval_secs: u64,
+    
+    /// Maximum accounts renewed per pass, soonest expiry first
+    pub max_renewals_per_run: usize,
+}
+
+/// Compliance oracle configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleConfig {
+    /// Publish the compliant account set in the background
+    pub enabled: bool,
+    
+    /// Miden account carrying the oracle component the root is written to
+    pub account_id: Option<String>,
+    
+    /// Seconds between publications
+    pub publish_interval_secs: u64,
+}
+
+/// Attestation registry configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    /// Publish the registry root in the background
+    pub enabled: bool,
+    
+    /// Seconds between publications
+    pub publish_interval_secs: u64,
+}
+
+/// Attestation epoch batching configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochConfig {
+    /// Anchor a root of the attestations issued in each epoch
+    pub enabled: bool,
+    
+    /// Miden account carrying the anchor component roots are written to
+    pub anchor_account_id: Option<String>,
+    
+    /// Epoch length in seconds
+    pub epoch_secs: u64,
+}
+
+/// Attestation notarization configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotarizationConfig {
+    /// Where issuance is timestamped; attestations are not notarized when unset
+    pub backend: Option<NotarizationBackend>,
+}
+
+/// Proof verification cache configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationCacheConfig {
+    /// Reuse verification outcomes of envelopes seen before
+    pub enabled: bool,
+    
+    /// Maximum outcomes held
+    pub max_entries: usize,
+    
+    /// Seconds a failed verification is remembered
+    pub negative_ttl_secs: u64,
+}
+
+/// Threshold signing configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThresholdSigningConfig {
+    /// Policies by minimum compliance level; attestations below every
+    /// policy's level are signed by the automated signer alone
+    pub policies: Vec<LevelSigningPolicy>,
+    
+    /// Seconds a signing request collects partial signatures before timing out
+    pub timeout_secs: u64,
+}
+
+/// Threshold policy for attestations at or above a compliance level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelSigningPolicy {
+    pub min_level: ComplianceLevel,
+    
+    #[serde(flatten)]
+    pub policy: ThresholdPolicy,
+}
+
+/// Source of independent issuance timestamps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotarizationBackend {
+    /// RFC 3161 timestamp authority
+    Rfc3161 { url: String },
+    
+    /// Latest block of an Ethereum-compatible chain, read over JSON-RPC
+    BlockReference { chain: String, rpc_url: String },
+}
+
+/// Step-up verification configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepUpConfig {
+    /// Step-up session lifetime in hours
+    pub session_ttl_hours: u32,
+    
+    /// Maximum number of open sessions each client can hold per account
+    pub max_open_sessions: u32,
+}
+
+/// Manual override configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverrideConfig {
+    /// Longest lifetime an override may be granted, in days
+    pub max_duration_days: u32,
+    
+    /// Interval in seconds between checks for expired overrides
+    pub expiry_check_interval_secs: u64,
+}
+
+/// Source-of-funds and source-of-wealth declaration configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundsDeclarationConfig {
+    /// Require a verified source-of-funds declaration for `Enhanced` and above
+    pub require_funds_for_enhanced: bool,
+    
+    /// Require a verified source-of-wealth declaration for `InstitutionalGrade`
+    pub require_wealth_for_institutional: bool,
+    
+    /// Days a verified declaration counts towards compliance evaluation
+    pub validity_days: u32,
+    
+    /// Maximum supporting documents per declaration
+    pub max_supporting_documents: usize,
+}
+
+/// Circuit breaker settings for each external provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderResilienceConfig {
+    pub kyc: BreakerConfig,
+    pub aml: BreakerConfig,
+    pub sanctions: BreakerConfig,
+    pub adverse_media: BreakerConfig,
+    
+    #[serde(default = "default_chain_analytics_breaker")]
+    pub chain_analytics: BreakerConfig,
+    
+    /// Interval in seconds between attempts to drain the retry queue
+    pub retry_interval_secs: u64,
+}
+
+/// Circuit breaker settings for a single provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerConfig {
+    /// Consecutive failures before the breaker opens
+    pub failure_threshold: u32,
+    
+    /// Seconds the breaker stays open before probing the provider again
+    pub open_duration_secs: u64,
+    
+    /// Concurrent probe calls allowed while half-open
+    pub half_open_max_probes: u32,
+    
+    /// Timeout in seconds for a single provider call
+    pub call_timeout_secs: u64,
+    
+    /// Behavior when the provider is unavailable
+    pub fallback: FallbackPolicy,
+    
+    /// Whether the provider's check must succeed for a compliance check to
+    /// pass; only chain analytics checks can be optional
+    #[serde(default)]
+    pub criticality: CheckCriticality,
+}
+
+/// Whether a check must succeed for a compliance check to pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckCriticality {
+    /// A failure fails the compliance check, subject to the fallback policy
+    #[default]
+    Critical,
+    /// A failure or timeout is recorded as a partial attestation and the
+    /// check retried later
+    Optional,
+}
+
+/// Behavior when a provider is unavailable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// Reject the check
+    FailClosed,
+    /// Keep the last valid attestation and raise an alert
+    FailOpenWithFlag,
+    /// Reject the check and re-run it once the provider recovers
+    QueueForRetry,
 }
 
 /// Webhook configuration
@@ -260,6 +1318,22 @@ pub struct WebhookConfig {
     
     /// Webhook secret for signature verification
     pub secret: String,
+    
+    /// Outbound network settings for webhook delivery
+    pub egress: WebhookEgressConfig,
+}
+
+/// Outbound network settings for webhook delivery
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebhookEgressConfig {
+    /// Local address deliveries are sent from (optional)
+    pub local_address: Option<std::net::IpAddr>,
+    
+    /// Egress proxy URL (optional)
+    pub proxy: Option<String>,
+    
+    /// Source IPs published to receivers for their allowlists
+    pub source_ips: Vec<String>,
 }
 
 /// Security configuration
@@ -279,6 +1353,60 @@ pub struct SecurityConfig {
     
     /// Enable API key authentication
     pub enable_api_key_auth: bool,
+    
+    /// Operator session lifetime in seconds
+    pub operator_session_ttl_secs: u64,
+    
+    /// Identifier of this deployment, recorded as provenance in export bundles
+    pub deployment_id: String,
+    
+    /// Identifier of the attestation signing key
+    pub signing_key_id: String,
+    
+    /// Hex-encoded Ed25519 seed of the attestation signing key (ephemeral when unset)
+    pub signing_key_seed: Option<String>,
+    
+    /// Where the attestation and JWT signing key is held
+    #[serde(default)]
+    pub signing_backend: SigningBackendConfig,
+}
+
+/// Holder of the signing key
+///
+/// Every backend but `software` keeps the private key out of process memory;
+/// the key must be an Ed25519 key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SigningBackendConfig {
+    /// In process memory, from `signing_key_seed`
+    #[default]
+    Software,
+    
+    /// HSM or other token reached through its vendor's PKCS#11 module
+    Pkcs11 {
+        module_path: PathBuf,
+        token_label: String,
+        /// Label of the private key and of its public key object
+        key_label: String,
+        /// User PIN
+        pin: String,
+    },
+    
+    /// AWS KMS key with the `ECC_NIST_EDWARDS25519` key spec
+    AwsKms {
+        /// Key id, ARN or alias
+        key_id: String,
+        region: String,
+    },
+    
+    /// Google Cloud KMS key version with the `EC_SIGN_ED25519` algorithm
+    GcpKms {
+        /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+        key_version: String,
+        /// Access token; the instance's service account is used when unset
+        #[serde(default)]
+        access_token: Option<String>,
+    },
 }
 
 /// Rate limiting configuration
@@ -314,11 +1442,268 @@ pub struct LoggingConfig {
     
     /// Log file path (optional)
     pub log_file: Option<PathBuf>,
+    
+    /// Additional field names redacted from logged bodies
+    pub redact_fields: Vec<String>,
+}
+
+/// Alerting configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertingConfig {
+    /// Enable alerting
+    pub enabled: bool,
+    
+    /// Window in seconds during which duplicate alerts are suppressed
+    pub dedup_window_secs: u64,
+    
+    /// Maximum number of alerts retained in memory
+    pub max_retained: usize,
+    
+    /// Alert sinks
+    pub sinks: Vec<AlertSinkConfig>,
+}
+
+/// Event bus publishing configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventBusConfig {
+    /// Mirror compliance events onto the event bus
+    pub enabled: bool,
+    
+    /// Broker to publish to
+    pub backend: Option<EventBusBackend>,
+    
+    /// Prefix of the topics (Kafka) or subjects (NATS) events are published to
+    pub topic_prefix: String,
+    
+    /// Initial delay in milliseconds before retrying a failed publish
+    pub retry_backoff_ms: u64,
+    
+    /// Upper bound in milliseconds on the retry delay
+    pub max_retry_backoff_ms: u64,
+}
+
+/// Sandbox environment configuration
+///
+/// Requests made with a client's sandbox API key are served by services
+/// built from [`Config::sandbox`], apart from production's data, Miden
+/// network, and providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SandboxConfig {
+    /// Issue sandbox API keys and serve their requests
+    pub enabled: bool,
+    
+    /// Database holding sandbox data, with an optional `{password}`
+    /// placeholder filled from `database.password`
+    pub database_url: Option<String>,
+    
+    /// Miden node of the test network sandbox attestations are anchored on
+    pub miden_rpc_endpoint: String,
+    
+    /// Base URL of the mock KYC, AML, and sanctions providers, served under
+    /// `/kyc`, `/aml`, and `/sanctions`
+    pub mock_provider_endpoint: Option<String>,
+    
+    /// Directory the sandbox Miden store, keystore, and local state are kept in
+    pub data_dir: PathBuf,
+}
+
+/// Display text localization configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalizationConfig {
+    /// Directory of `{locale}.json` translation files loaded at startup;
+    /// only the built-in English text is used when unset
+    pub catalog_dir: Option<PathBuf>,
+    
+    /// Locale tried after the reader's own, before built-in English
+    pub default_locale: String,
+}
+
+/// Background job scheduler configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Seconds each run may be delayed by at random, unless a job sets its own
+    pub jitter_secs: u64,
+    
+    /// Overrides of jobs' built-in schedules, by job name
+    pub jobs: HashMap<String, JobScheduleConfig>,
+}
+
+/// Override of a job's built-in schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobScheduleConfig {
+    /// Run the job at all
+    pub enabled: bool,
+    
+    /// Five-field cron expression, evaluated in UTC
+    pub cron: Option<String>,
+    
+    /// Seconds between runs, in place of the built-in interval
+    pub interval_secs: Option<u64>,
+    
+    /// Seconds each run may be delayed by at random
+    pub jitter_secs: Option<u64>,
+}
+
+/// Leader election among instances sharing the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeaderElectionConfig {
+    /// Take a lease in the database before each job run and relay pass;
+    /// every instance does all the work when disabled
+    pub enabled: bool,
+    
+    /// Name this instance holds leases under; the host name when unset
+    pub instance_id: Option<String>,
+    
+    /// Seconds a lease is held after it is taken or renewed, and so how long
+    /// a crashed holder's work waits before another instance takes it over
+    pub lease_secs: u64,
+}
+
+/// Public status page configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusPageConfig {
+    /// Serve `GET /v1/status`
+    pub enabled: bool,
+    
+    /// Requests each caller may make per minute
+    pub requests_per_minute: u32,
+    
+    /// Seconds a computed status is served before it is recomputed
+    pub refresh_secs: u64,
+    
+    /// Seconds of proof verifications averaged into the reported latency
+    pub latency_window_secs: u64,
+    
+    /// Verifications the window must hold before any latency is reported
+    pub min_latency_samples: usize,
+    
+    /// Granularity in milliseconds the reported latency is rounded to
+    pub latency_rounding_ms: u64,
+    
+    /// Age in seconds after which a screening list is reported stale
+    pub list_stale_after_secs: u64,
+}
+
+/// Event bus broker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventBusBackend {
+    /// Apache Kafka (requires the `kafka` feature)
+    Kafka {
+        brokers: Vec<String>,
+        sasl_username: Option<String>,
+        sasl_password: Option<String>,
+    },
+    
+    /// NATS JetStream (requires the `nats` feature)
+    Nats {
+        url: String,
+        /// Path to a NATS credentials file
+        credentials_file: Option<PathBuf>,
+    },
+}
+
+/// Management information reporting configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportingConfig {
+    /// Enable scheduled report generation
+    pub enabled: bool,
+    
+    /// Maximum number of reports retained
+    pub max_retained: usize,
+    
+    /// Report schedules
+    pub schedules: Vec<ReportScheduleConfig>,
+}
+
+/// A scheduled report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportScheduleConfig {
+    /// Schedule name, recorded on generated reports
+    pub name: String,
+    
+    /// Five-field cron expression, evaluated in UTC
+    pub cron: String,
+    
+    /// Number of days covered by each report, ending at generation time
+    pub period_days: u32,
+    
+    /// Clients to report on (all clients when empty)
+    #[serde(default)]
+    pub clients: Vec<uuid::Uuid>,
+}
+
+/// External secret source configuration
+///
+/// Secret fields elsewhere in the configuration may hold references such as
+/// `env:NAME`, `file:/path`, `vault:mount/path#field`, or `aws-sm:secret-id#field`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// HashiCorp Vault source (optional)
+    pub vault: Option<VaultConfig>,
+    
+    /// AWS Secrets Manager source (optional)
+    pub aws: Option<AwsSecretsConfig>,
+    
+    /// Interval in seconds between secret refreshes (0 disables refresh)
+    pub refresh_interval_secs: u64,
+}
+
+/// HashiCorp Vault configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// Vault server address
+    pub address: String,
+    
+    /// Vault token, given as an `env:` or `file:` reference
+    pub token: String,
+    
+    /// Vault Enterprise namespace (optional)
+    pub namespace: Option<String>,
+}
+
+/// AWS Secrets Manager configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AwsSecretsConfig {
+    /// AWS region
+    pub region: String,
+}
+
+/// Alert sink configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertSinkConfig {
+    /// Email delivered through an HTTP mail relay
+    Email {
+        relay_endpoint: String,
+        api_key: Option<String>,
+        recipients: Vec<String>,
+        min_severity: AlertSeverity,
+    },
+    
+    /// Slack incoming webhook
+    Slack {
+        webhook_url: String,
+        min_severity: AlertSeverity,
+    },
+    
+    /// PagerDuty Events API v2
+    PagerDuty {
+        routing_key: String,
+        min_severity: AlertSeverity,
+    },
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            environment: Environment::Development,
             server: ServerConfig::default(),
             database: DatabaseConfig::default(),
             miden: MidenConfig::default(),
@@ -326,6 +1711,15 @@ impl Default for Config {
             webhooks: WebhookConfig::default(),
             security: SecurityConfig::default(),
             logging: LoggingConfig::default(),
+            alerting: AlertingConfig::default(),
+            secrets: SecretsConfig::default(),
+            reporting: ReportingConfig::default(),
+            event_bus: EventBusConfig::default(),
+            status_page: StatusPageConfig::default(),
+            sandbox: SandboxConfig::default(),
+            localization: LocalizationConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            leader_election: LeaderElectionConfig::default(),
         }
     }
 }
@@ -335,13 +1729,56 @@ impl Default for ServerConfig {
         Self {
             host: "0.0.0.0".to_string(),
             port: 8080,
-            max_body_size: 10 * 1024 * 1024, // 10MB
+            body_limits: BodyLimitsConfig::default(),
             request_timeout: 30,
+            idempotency_ttl_secs: default_idempotency_ttl_secs(),
+            max_long_poll_secs: default_max_long_poll_secs(),
+            responses: ResponseConfig::default(),
             cors: CorsConfig::default(),
         }
     }
 }
 
+impl Default for ResponseConfig {
+    fn default() -> Self {
+        Self {
+            compression: true,
+            gzip: true,
+            br: true,
+            min_compress_bytes: 1024,
+            stream_threshold_bytes: 256 * 1024,
+            stream_chunk_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl Default for BodyLimitsConfig {
+    fn default() -> Self {
+        let json = |max_bytes| RouteBodyLimit {
+            max_bytes,
+            content_types: Vec::new(),
+        };
+        let routes = HashMap::from([
+            (
+                "POST /v1/end-user/session/documents".to_string(),
+                RouteBodyLimit {
+                    max_bytes: 12 * 1024 * 1024, // 12MB, a 10MB document and its multipart framing
+                    content_types: vec!["multipart/form-data".to_string()],
+                },
+            ),
+            ("PUT /v1/admin/screening/lists/{name}".to_string(), json(32 * 1024 * 1024)),
+            ("POST /v1/admin/attestations/import".to_string(), json(32 * 1024 * 1024)),
+            ("POST /v1/providers/{provider}/callback".to_string(), json(1024 * 1024)),
+        ]);
+        Self {
+            default_max_bytes: 64 * 1024,
+            query_max_bytes: 0,
+            default_content_types: vec!["application/json".to_string()],
+            routes,
+        }
+    }
+}
+
 impl Default for CorsConfig {
     fn default() -> Self {
         Self {
@@ -357,10 +1794,21 @@ impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
             url: "postgresql://localhost/compliance".to_string(),
+            password: None,
             max_connections: 20,
             connection_timeout: 30,
             idle_timeout: 600,
             run_migrations: true,
+            regions: HashMap::new(),
+        }
+    }
+}
+
+impl Default for ResidencyConfig {
+    fn default() -> Self {
+        Self {
+            regions: HashMap::new(),
+            strict_regions: vec![DataRegion::Eu],
         }
     }
 }
@@ -377,78 +1825,335 @@ impl Default for MidenConfig {
             sync_interval: 30,
             transaction_timeout: 60,
             enable_delegated_proving: false,
+            proving_queue: ProvingQueueConfig::default(),
+            verification_pool: VerificationPoolConfig::default(),
+            components: ComponentTemplateConfig::default(),
         }
     }
 }
 
-impl Default for ComplianceConfig {
+impl Default for ProvingQueueConfig {
     fn default() -> Self {
         Self {
-            kyc: KycConfig::default(),
-            aml: AmlConfig::default(),
-            sanctions: SanctionsConfig::default(),
-            attestation: AttestationConfig::default(),
+            max_concurrent: 2,
+            max_queued_interactive: 32,
+            max_queued_batch: 256,
+            initial_estimate_ms: 2000,
         }
     }
 }
 
-impl Default for KycConfig {
+impl Default for VerificationPoolConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            provider_endpoint: None,
-            provider_api_key: None,
-            verification_timeout: 300,
-            min_quality_score: 0.85,
-            supported_documents: vec!["passport".to_string(), "driver_license".to_string(), "national_id".to_string()],
-            verification_expiry_days: 365,
+            max_concurrent: std::thread::available_parallelism().map_or(4, |n| n.get()),
+            max_queued: 256,
+            initial_estimate_ms: 200,
         }
     }
 }
 
-impl Default for AmlConfig {
+impl Default for ComponentTemplateConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            provider_endpoint: None,
-            provider_api_key: None,
-            assessment_timeout: 60,
-            risk_thresholds: RiskThresholds::default(),
-            transaction_monitoring: TransactionMonitoringConfig::default(),
+            kyc_validity_secs: 31_536_000,
+            authorized_verifiers: Vec::new(),
+            large_transaction_threshold: 10_000,
+            medium_risk_score: 150,
+            high_risk_score: 300,
+            sanctions_override_keys: Vec::new(),
         }
     }
 }
 
-impl Default for RiskThresholds {
+impl Default for ComplianceConfig {
     fn default() -> Self {
         Self {
-            low: 0.3,
-            medium: 0.7,
-            high: 0.9,
+            kyc: KycConfig::default(),
+            aml: AmlConfig::default(),
+            sanctions: SanctionsConfig::default(),
+            attestation: AttestationConfig::default(),
+            step_up: StepUpConfig::default(),
+            overrides: OverrideConfig::default(),
+            providers: ProviderResilienceConfig::default(),
+            funds_declarations: FundsDeclarationConfig::default(),
+            callbacks: ProviderCallbackConfig::default(),
+            provider_credentials: ProviderCredentialsConfig::default(),
+            residency: ResidencyConfig::default(),
+            workflows: WorkflowConfig::default(),
+            metering: MeteringConfig::default(),
+            anomaly_detection: AnomalyDetectionConfig::default(),
+            verification_sessions: VerificationSessionConfig::default(),
+            provider_routing: ProviderRoutingConfig::default(),
+            offboarding: OffboardingConfig::default(),
+            duplicate_detection: DuplicateDetectionConfig::default(),
+            presentations: PresentationConfig::default(),
         }
     }
 }
 
-impl Default for TransactionMonitoringConfig {
+impl Default for MeteringConfig {
     fn default() -> Self {
         Self {
-            max_amount_low_risk: 1000,
-            max_amount_medium_risk: 10000,
-            max_daily_transactions: 100,
-            enable_pattern_detection: true,
+            plans: HashMap::new(),
+            default_plan: None,
+            clients: HashMap::new(),
+            alert_thresholds: vec![80, 100],
         }
     }
 }
 
-impl Default for SanctionsConfig {
+impl Default for ProviderRoutingConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            provider_endpoint: None,
+            currency: "USD".to_string(),
+            default_costs: HashMap::new(),
+            vendors: HashMap::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+fn default_routing_fallback() -> bool {
+    true
+}
+
+impl Default for AnomalyDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_secs: 900,
+            baseline_windows: 96,
+            min_baseline_windows: 8,
+            min_samples: 20,
+            z_threshold: 4.0,
+            max_affected_accounts: 50,
+        }
+    }
+}
+
+impl Default for VerificationSessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_ttl_secs: 3600,
+            max_ttl_secs: 86400,
+            max_documents: 10,
+            hosted_url: None,
+        }
+    }
+}
+
+impl Default for OffboardingConfig {
+    fn default() -> Self {
+        Self {
+            pii_retention_days: 5 * 365,
+            void_account_id: None,
+            erasure_interval_secs: 3600,
+        }
+    }
+}
+
+impl Default for DuplicateDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cross_client: Vec::new(),
+        }
+    }
+}
+
+impl Default for PresentationConfig {
+    fn default() -> Self {
+        Self {
+            public_url: None,
+            max_inline_bytes: 2048,
+            reference_ttl_secs: 300,
+        }
+    }
+}
+
+impl Default for DocumentIntakeConfig {
+    fn default() -> Self {
+        Self {
+            max_document_bytes: 10 * 1024 * 1024,
+            allowed_content_types: vec![
+                "image/jpeg".to_string(),
+                "image/png".to_string(),
+                "application/pdf".to_string(),
+            ],
+            scanner: None,
+            scan_timeout_secs: 30,
+            fail_open: false,
+            image_quality: ImageQualityConfig::default(),
+        }
+    }
+}
+
+impl Default for ImageQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_short_side_px: 600,
+            sharp_laplacian_variance: 100.0,
+            glare_luma: 250,
+            max_glare_share: 0.2,
+            border_share: 0.02,
+            max_border_edge_ratio: 1.0,
+        }
+    }
+}
+
+impl Default for KycConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider_endpoint: None,
+            provider_api_key: None,
+            verification_timeout: 300,
+            min_quality_score: 0.85,
+            supported_documents: vec!["passport".to_string(), "driver_license".to_string(), "national_id".to_string()],
+            verification_expiry_days: 365,
+            documents: DocumentIntakeConfig::default(),
+        }
+    }
+}
+
+impl Default for AmlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider_endpoint: None,
+            provider_api_key: None,
+            assessment_timeout: 60,
+            risk_thresholds: RiskThresholds::default(),
+            transaction_monitoring: TransactionMonitoringConfig::default(),
+            country_risk: CountryRiskConfig::default(),
+            chain_analytics: ChainAnalyticsConfig::default(),
+            counterparty_risk: CounterpartyRiskConfig::default(),
+            device_risk: DeviceRiskConfig::default(),
+            custom_factors: Vec::new(),
+        }
+    }
+}
+
+impl Default for ChainAnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: None,
+            cache_ttl_secs: 3600,
+            max_direct_exposure_share: 0.01,
+            max_addresses_per_account: 50,
+        }
+    }
+}
+
+impl Default for CounterpartyRiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_hops: 2,
+            decay: 0.5,
+            min_share: 0.05,
+            max_neighborhood_nodes: 500,
+        }
+    }
+}
+
+impl Default for DeviceRiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tor_weight: 0.8,
+            vpn_weight: 0.3,
+            proxy_weight: 0.4,
+            hosting_weight: 0.3,
+            residence_mismatch_weight: 0.3,
+            shared_device_weight: 0.6,
+            shared_device_accounts: 3,
+            max_observations: 50,
+        }
+    }
+}
+
+impl Default for CountryRiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dataset_path: None,
+            fatf_black_list_weight: 1.0,
+            fatf_grey_list_weight: 0.6,
+            country_weights: HashMap::new(),
+            residence_weight: 0.5,
+            document_issuer_weight: 0.2,
+            counterparty_weight: 0.3,
+        }
+    }
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            low: 0.3,
+            medium: 0.7,
+            high: 0.9,
+        }
+    }
+}
+
+impl Default for TransactionMonitoringConfig {
+    fn default() -> Self {
+        Self {
+            max_amount_low_risk: 1000,
+            max_amount_medium_risk: 10000,
+            max_daily_transactions: 100,
+            max_hourly_transactions: 20,
+            max_daily_volume: 50000,
+            enable_pattern_detection: true,
+            ingestion: MonitoringIngestionConfig::default(),
+        }
+    }
+}
+
+impl Default for MonitoringIngestionConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: 250,
+            flush_batch_size: 1000,
+            max_buffered_transactions: 100_000,
+            checkpoint_interval_secs: 60,
+        }
+    }
+}
+
+impl Default for SanctionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider_endpoint: None,
             provider_api_key: None,
             screening_timeout: 30,
             update_interval_hours: 24,
             fuzzy_match_threshold: 0.8,
+            list_store_dir: None,
+            attribute_weights: AttributeWeights::default(),
+        }
+    }
+}
+
+impl Default for AttributeWeights {
+    fn default() -> Self {
+        Self {
+            dob_tolerance_years: 1,
+            dob_match_bonus: 0.1,
+            dob_partial_bonus: 0.05,
+            dob_mismatch_penalty: 0.25,
+            nationality_match_bonus: 0.05,
+            nationality_mismatch_penalty: 0.1,
+            document_match_bonus: 0.3,
+            document_mismatch_penalty: 0.05,
+            address_match_bonus: 0.05,
         }
     }
 }
@@ -460,10 +2165,157 @@ impl Default for AttestationConfig {
             enable_proof_verification: true,
             proof_verification_timeout: 120,
             max_proof_size: 1024 * 1024, // 1MB
+            challenge_ttl_secs: 300,
+            proof_validity_secs: 3600,
+            asset_class_levels: default_asset_class_levels(),
+            renewal: RenewalConfig::default(),
+            oracle: OracleConfig::default(),
+            registry: RegistryConfig::default(),
+            epochs: EpochConfig::default(),
+            notarization: NotarizationConfig::default(),
+            verification_cache: VerificationCacheConfig::default(),
+            threshold_signing: ThresholdSigningConfig::default(),
+        }
+    }
+}
+
+impl Default for ThresholdSigningConfig {
+    fn default() -> Self {
+        Self {
+            policies: Vec::new(),
+            timeout_secs: 24 * 3600,
+        }
+    }
+}
+
+impl Default for VerificationCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 100_000,
+            negative_ttl_secs: 300,
+        }
+    }
+}
+
+impl Default for RenewalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lead_time_hours: 72,
+            check_interval_secs: 300,
+            max_renewals_per_run: 100,
+        }
+    }
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            account_id: None,
+            publish_interval_secs: 600,
+        }
+    }
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            publish_interval_secs: 300,
+        }
+    }
+}
+
+impl Default for EpochConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            anchor_account_id: None,
+            epoch_secs: 600,
+        }
+    }
+}
+
+impl Default for StepUpConfig {
+    fn default() -> Self {
+        Self {
+            session_ttl_hours: 72,
+            max_open_sessions: 1,
+        }
+    }
+}
+
+impl Default for OverrideConfig {
+    fn default() -> Self {
+        Self {
+            max_duration_days: 90,
+            expiry_check_interval_secs: 60,
+        }
+    }
+}
+
+impl Default for FundsDeclarationConfig {
+    fn default() -> Self {
+        Self {
+            require_funds_for_enhanced: true,
+            require_wealth_for_institutional: true,
+            validity_days: 365,
+            max_supporting_documents: 20,
+        }
+    }
+}
+
+impl Default for ProviderResilienceConfig {
+    fn default() -> Self {
+        Self {
+            kyc: BreakerConfig::with_fallback(FallbackPolicy::QueueForRetry),
+            aml: BreakerConfig::with_fallback(FallbackPolicy::FailOpenWithFlag),
+            sanctions: BreakerConfig::with_fallback(FallbackPolicy::FailClosed),
+            adverse_media: BreakerConfig::with_fallback(FallbackPolicy::FailOpenWithFlag),
+            chain_analytics: default_chain_analytics_breaker(),
+            retry_interval_secs: 30,
+        }
+    }
+}
+
+impl BreakerConfig {
+    fn with_fallback(fallback: FallbackPolicy) -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration_secs: 30,
+            half_open_max_probes: 1,
+            call_timeout_secs: 10,
+            fallback,
+            criticality: CheckCriticality::Critical,
         }
     }
 }
 
+fn default_idempotency_ttl_secs() -> u64 {
+    24 * 3600
+}
+
+fn default_max_long_poll_secs() -> u64 {
+    25
+}
+
+fn default_callback_signature_header() -> String {
+    "x-signature".to_string()
+}
+
+fn default_chain_analytics_breaker() -> BreakerConfig {
+    BreakerConfig::with_fallback(FallbackPolicy::FailOpenWithFlag)
+}
+
+fn default_asset_class_levels() -> HashMap<AssetClass, ComplianceLevel> {
+    HashMap::from([
+        (AssetClass::TokenizedSecurity, ComplianceLevel::Enhanced),
+        (AssetClass::Derivative, ComplianceLevel::Enhanced),
+    ])
+}
+
 impl Default for WebhookConfig {
     fn default() -> Self {
         Self {
@@ -471,7 +2323,8 @@ impl Default for WebhookConfig {
             timeout: 30,
             max_retries: 3,
             retry_delay: 5,
-            secret: "default_webhook_secret".to_string(),
+            secret: DEFAULT_WEBHOOK_SECRET.to_string(),
+            egress: WebhookEgressConfig::default(),
         }
     }
 }
@@ -480,10 +2333,15 @@ impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
             api_key_length: 32,
-            jwt_secret: "default_jwt_secret".to_string(),
+            jwt_secret: DEFAULT_JWT_SECRET.to_string(),
             jwt_expiry: 3600,
             rate_limiting: RateLimitConfig::default(),
             enable_api_key_auth: true,
+            operator_session_ttl_secs: 28800,
+            deployment_id: "default".to_string(),
+            signing_key_id: "attestation-signer-1".to_string(),
+            signing_key_seed: None,
+            signing_backend: SigningBackendConfig::default(),
         }
     }
 }
@@ -507,27 +2365,1295 @@ impl Default for LoggingConfig {
             log_requests: true,
             log_responses: false,
             log_file: None,
+            redact_fields: vec![],
         }
     }
 }
 
-impl Config {
-    /// Load configuration from file
-    pub fn from_file(path: &str) -> Result<Self, config::ConfigError> {
-        let settings = config::Config::builder()
-            .add_source(config::File::with_name(path))
-            .add_source(config::Environment::with_prefix("COMPLIANCE"))
-            .build()?;
-        
-        settings.try_deserialize()
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dedup_window_secs: 900,
+            max_retained: 10000,
+            sinks: vec![],
+        }
     }
-    
-    /// Load configuration from environment variables
-    pub fn from_env() -> Result<Self, config::ConfigError> {
-        let settings = config::Config::builder()
-            .add_source(config::Environment::with_prefix("COMPLIANCE"))
-            .build()?;
-        
-        settings.try_deserialize()
+}
+
+impl Default for ReportingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_retained: 1000,
+            schedules: vec![],
+        }
+    }
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: None,
+            topic_prefix: "zerotrust.compliance".to_string(),
+            retry_backoff_ms: 500,
+            max_retry_backoff_ms: 30_000,
+        }
     }
-} 
\ No newline at end of file
+}
+
+impl Default for StatusPageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            requests_per_minute: 30,
+            refresh_secs: 60,
+            latency_window_secs: 3600,
+            min_latency_samples: 100,
+            latency_rounding_ms: 50,
+            list_stale_after_secs: 2 * 86_400,
+        }
+    }
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            database_url: None,
+            miden_rpc_endpoint: "https://testnet-rpc.miden.io".to_string(),
+            mock_provider_endpoint: None,
+            data_dir: PathBuf::from("./sandbox"),
+        }
+    }
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            jitter_secs: 0,
+            jobs: HashMap::new(),
+        }
+    }
+}
+
+impl Default for JobScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cron: None,
+            interval_secs: None,
+            jitter_secs: None,
+        }
+    }
+}
+
+impl Default for LeaderElectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            instance_id: None,
+            lease_secs: 300,
+        }
+    }
+}
+
+impl Default for LocalizationConfig {
+    fn default() -> Self {
+        Self {
+            catalog_dir: None,
+            default_locale: crate::compliance::localization::BUILTIN_LOCALE.to_string(),
+        }
+    }
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            vault: None,
+            aws: None,
+            refresh_interval_secs: 300,
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// Database URL with the password substituted in
+    pub fn connection_url(&self) -> String {
+        match &self.password {
+            Some(password) => self.url.replace("{password}", password),
+            None => self.url.clone(),
+        }
+    }
+}
+
+impl RegionalDatabaseConfig {
+    /// Database URL with the password substituted in, falling back to the primary database
+    pub fn connection_url(&self, primary: &DatabaseConfig) -> String {
+        match &self.url {
+            Some(url) => match &self.password {
+                Some(password) => url.replace("{password}", password),
+                None => url.clone(),
+            },
+            None => primary.connection_url(),
+        }
+    }
+}
+
+impl Config {
+    /// Configuration the sandbox services are built from, when the sandbox is enabled
+    ///
+    /// The sandbox keeps production's settings except where test traffic must
+    /// stay apart from live traffic: its own database, the test Miden network,
+    /// the mock providers, and local state under the sandbox data directory.
+    /// Regional databases are not used, so sandbox data stays in the sandbox
+    /// database whatever the client's region.
+    pub fn sandbox(&self) -> Option<Config> {
+        let sandbox = &self.sandbox;
+        if !sandbox.enabled {
+            return None;
+        }
+        let mock = sandbox.mock_provider_endpoint.as_deref()?.trim_end_matches('/');
+        
+        let mut config = self.clone();
+        config.sandbox.enabled = false;
+        config.database.url = sandbox.database_url.clone()?;
+        config.database.regions.clear();
+        config.miden.rpc_endpoint = sandbox.miden_rpc_endpoint.clone();
+        config.miden.store_path = sandbox.data_dir.join("miden-store.sqlite3");
+        config.miden.keystore_path = sandbox.data_dir.join("keystore");
+        
+        let compliance = &mut config.compliance;
+        for (endpoint, api_key, provider) in [
+            (&mut compliance.kyc.provider_endpoint, &mut compliance.kyc.provider_api_key, "kyc"),
+            (&mut compliance.aml.provider_endpoint, &mut compliance.aml.provider_api_key, "aml"),
+            (&mut compliance.sanctions.provider_endpoint, &mut compliance.sanctions.provider_api_key, "sanctions"),
+        ] {
+            *endpoint = Some(format!("{}/{}", mock, provider));
+            *api_key = Some("sandbox".to_string());
+        }
+        compliance.residency = ResidencyConfig::default();
+        let state_dir = |name: &str| sandbox.data_dir.join(name).to_string_lossy().into_owned();
+        if compliance.sanctions.list_store_dir.is_some() {
+            compliance.sanctions.list_store_dir = Some(state_dir("screening-lists"));
+        }
+        if compliance.workflows.state_dir.is_some() {
+            compliance.workflows.state_dir = Some(state_dir("workflow-runs"));
+        }
+        Some(config)
+    }
+    
+    /// Load configuration from file
+    pub fn from_file(path: &str) -> Result<Self, config::ConfigError> {
+        let settings = config::Config::builder()
+            .add_source(config::File::with_name(path))
+            .add_source(config::Environment::with_prefix("COMPLIANCE"))
+            .build()?;
+        
+        settings.try_deserialize()
+    }
+    
+    /// Load configuration from environment variables
+    pub fn from_env() -> Result<Self, config::ConfigError> {
+        let settings = config::Config::builder()
+            .add_source(config::Environment::with_prefix("COMPLIANCE"))
+            .build()?;
+        
+        settings.try_deserialize()
+    }
+    
+    /// Load configuration from file and validate it
+    pub fn load(path: &str) -> crate::Result<Self> {
+        let config = Self::from_file(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+    
+    /// Load configuration from file, resolve secret references, and validate it
+    ///
+    /// Outside development every secret must be given as a reference rather
+    /// than plaintext. The returned resolver keeps the secrets it fetched cached
+    /// for refresh.
+    pub async fn load_with_secrets(path: &str) -> crate::Result<(Self, SecretResolver)> {
+        let mut config = Self::from_file(path)?;
+        let resolver = SecretResolver::from_config(&config.secrets);
+        
+        if config.environment != Environment::Development {
+            let mut v = ConfigViolations::default();
+            for (field, value) in config.secret_fields_mut() {
+                if !resolver.is_reference(value) {
+                    v.push(field, "plaintext secrets are not allowed outside development; use a secret reference");
+                }
+            }
+            v.into_result()?;
+        }
+        
+        config.resolve_secrets(&resolver).await?;
+        config.validate()?;
+        Ok((config, resolver))
+    }
+    
+    /// Replace every secret reference with its resolved value
+    pub async fn resolve_secrets(&mut self, resolver: &SecretResolver) -> crate::Result<()> {
+        for (field, value) in self.secret_fields_mut() {
+            resolver.resolve_in_place(value).await.map_err(|e| {
+                let reason = match e {
+                    crate::ComplianceError::SecretResolution { message } => message,
+                    other => other.to_string(),
+                };
+                crate::ComplianceError::SecretResolution {
+                    message: format!("{}: {}", field, reason),
+                }
+            })?;
+        }
+        Ok(())
+    }
+    
+    /// Every configured secret value with its dotted field path
+    fn secret_fields_mut(&mut self) -> Vec<(String, &mut String)> {
+        let mut fields = Vec::new();
+        let compliance = &mut self.compliance;
+        for (field, secret) in [
+            ("compliance.kyc.provider_api_key", &mut compliance.kyc.provider_api_key),
+            ("compliance.aml.provider_api_key", &mut compliance.aml.provider_api_key),
+            ("compliance.sanctions.provider_api_key", &mut compliance.sanctions.provider_api_key),
+            (
+                "compliance.provider_credentials.encryption_key",
+                &mut compliance.provider_credentials.encryption_key,
+            ),
+            ("database.password", &mut self.database.password),
+            ("security.signing_key_seed", &mut self.security.signing_key_seed),
+        ] {
+            if let Some(value) = secret {
+                fields.push((field.to_string(), value));
+            }
+        }
+        fields.push(("security.jwt_secret".to_string(), &mut self.security.jwt_secret));
+        match &mut self.security.signing_backend {
+            SigningBackendConfig::Pkcs11 { pin, .. } => {
+                fields.push(("security.signing_backend.pin".to_string(), pin));
+            }
+            SigningBackendConfig::GcpKms {
+                access_token: Some(access_token),
+                ..
+            } => {
+                fields.push(("security.signing_backend.access_token".to_string(), access_token));
+            }
+            _ => {}
+        }
+        fields.push(("webhooks.secret".to_string(), &mut self.webhooks.secret));
+        
+        for (i, sink) in self.alerting.sinks.iter_mut().enumerate() {
+            match sink {
+                AlertSinkConfig::Email { api_key: Some(api_key), .. } => {
+                    fields.push((format!("alerting.sinks[{}].api_key", i), api_key));
+                }
+                AlertSinkConfig::Slack { webhook_url, .. } => {
+                    fields.push((format!("alerting.sinks[{}].webhook_url", i), webhook_url));
+                }
+                AlertSinkConfig::PagerDuty { routing_key, .. } => {
+                    fields.push((format!("alerting.sinks[{}].routing_key", i), routing_key));
+                }
+                AlertSinkConfig::Email { api_key: None, .. } => {}
+            }
+        }
+        if let Some(EventBusBackend::Kafka { sasl_password: Some(password), .. }) = &mut self.event_bus.backend {
+            fields.push(("event_bus.backend.sasl_password".to_string(), password));
+        }
+        for (provider, callback) in compliance.callbacks.providers.iter_mut() {
+            fields.push((
+                format!("compliance.callbacks.providers.{}.secret", provider.name()),
+                &mut callback.secret,
+            ));
+        }
+        let scanner = &mut compliance.kyc.documents.scanner;
+        if let Some(DocumentScannerBackend::Http { api_key: Some(api_key), .. }) = scanner {
+            fields.push(("compliance.kyc.documents.scanner.api_key".to_string(), api_key));
+        }
+        match &mut compliance.aml.chain_analytics.backend {
+            Some(ChainAnalyticsBackend::Chainalysis { api_key, .. } | ChainAnalyticsBackend::Trm { api_key, .. }) => {
+                fields.push(("compliance.aml.chain_analytics.backend.api_key".to_string(), api_key));
+            }
+            None => {}
+        }
+        for (region, providers) in compliance.residency.regions.iter_mut() {
+            for (provider, regional) in providers.iter_mut() {
+                if let Some(api_key) = &mut regional.api_key {
+                    fields.push((
+                        format!("compliance.residency.regions.{}.{}.api_key", region.name(), provider.name()),
+                        api_key,
+                    ));
+                }
+            }
+        }
+        for (name, vendor) in compliance.provider_routing.vendors.iter_mut() {
+            if let Some(api_key) = &mut vendor.api_key {
+                fields.push((format!("compliance.provider_routing.vendors.{}.api_key", name), api_key));
+            }
+        }
+        for (region, database) in self.database.regions.iter_mut() {
+            if let Some(password) = &mut database.password {
+                fields.push((format!("database.regions.{}.password", region.name()), password));
+            }
+        }
+        fields
+    }
+    
+    /// Check cross-field invariants, reporting every violation found
+    pub fn validate(&self) -> Result<(), ConfigViolations> {
+        let mut v = ConfigViolations::default();
+        let production_like = self.environment != Environment::Development;
+        
+        // Server
+        if self.server.port == 0 {
+            v.push("server.port", "must not be 0");
+        }
+        let body_limits = &self.server.body_limits;
+        if body_limits.default_max_bytes == 0 {
+            v.push("server.body_limits.default_max_bytes", "must be greater than 0");
+        }
+        if body_limits.default_content_types.is_empty() {
+            v.push("server.body_limits.default_content_types", "must not be empty");
+        } else if body_limits.default_content_types.iter().any(|media_type| !is_media_type(media_type)) {
+            v.push(
+                "server.body_limits.default_content_types",
+                "must be media types such as `application/json`",
+            );
+        }
+        for (key, route) in &body_limits.routes {
+            let field = format!("server.body_limits.routes.{}", key);
+            let well_formed = key.split_once(' ').is_some_and(|(method, path)| {
+                !method.is_empty() && method.bytes().all(|b| b.is_ascii_uppercase()) && path.starts_with('/')
+            });
+            if !well_formed {
+                v.push(&field, "must be a method and a path, e.g. `POST /v1/accounts`");
+            }
+            if route.content_types.iter().any(|media_type| !is_media_type(media_type)) {
+                v.push(format!("{}.content_types", field), "must be media types such as `application/json`");
+            }
+        }
+        if self.server.request_timeout == 0 {
+            v.push("server.request_timeout", "must be greater than 0");
+        }
+        if self.server.max_long_poll_secs >= self.server.request_timeout {
+            v.push("server.max_long_poll_secs", "must be less than server.request_timeout");
+        }
+        let responses = &self.server.responses;
+        if responses.compression && !responses.gzip && !responses.br {
+            v.push("server.responses", "at least one of gzip and br must be offered when compression is enabled");
+        }
+        if responses.stream_chunk_bytes == 0 {
+            v.push("server.responses.stream_chunk_bytes", "must be greater than 0");
+        }
+        if responses.stream_threshold_bytes < responses.stream_chunk_bytes {
+            v.push("server.responses.stream_threshold_bytes", "must be at least server.responses.stream_chunk_bytes");
+        }
+        if production_like && self.server.cors.allowed_origins.iter().any(|o| o == "*") {
+            v.push("server.cors.allowed_origins", "wildcard origin is not allowed outside development");
+        }
+        
+        // Database
+        if self.database.url.trim().is_empty() {
+            v.push("database.url", "must not be empty");
+        }
+        if self.database.max_connections == 0 {
+            v.push("database.max_connections", "must be greater than 0");
+        }
+        for (region, database) in &self.database.regions {
+            let field = format!("database.regions.{}", region.name());
+            if database.schema.is_empty()
+                || !database.schema.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                v.push(format!("{}.schema", field), "must be a non-empty identifier of ASCII letters, digits and '_'");
+            }
+            if database.url.as_ref().is_some_and(|url| url.trim().is_empty()) {
+                v.push(format!("{}.url", field), "must not be empty");
+            }
+        }
+        
+        // Miden
+        if self.miden.enable_delegated_proving && self.miden.remote_prover_endpoint.is_none() {
+            v.push(
+                "miden.remote_prover_endpoint",
+                "must be set when miden.enable_delegated_proving is true",
+            );
+        }
+        if self.miden.sync_interval == 0 {
+            v.push("miden.sync_interval", "must be greater than 0");
+        }
+        if self.miden.proving_queue.max_concurrent == 0 {
+            v.push("miden.proving_queue.max_concurrent", "must be greater than 0");
+        }
+        if self.miden.verification_pool.max_concurrent == 0 {
+            v.push("miden.verification_pool.max_concurrent", "must be greater than 0");
+        }
+        let components = &self.miden.components;
+        if components.kyc_validity_secs == 0 {
+            v.push("miden.components.kyc_validity_secs", "must be greater than 0");
+        }
+        if components.medium_risk_score >= components.high_risk_score {
+            v.push("miden.components.medium_risk_score", "must be below high_risk_score");
+        }
+        if components.authorized_verifiers.contains(&0) {
+            v.push("miden.components.authorized_verifiers", "must not contain 0");
+        }
+        if components.sanctions_override_keys.contains(&0) {
+            v.push("miden.components.sanctions_override_keys", "must not contain 0");
+        }
+        
+        // Compliance
+        let compliance = &self.compliance;
+        check_provider(&mut v, "compliance.kyc", compliance.kyc.enabled, &compliance.kyc.provider_endpoint, &compliance.kyc.provider_api_key);
+        check_provider(&mut v, "compliance.aml", compliance.aml.enabled, &compliance.aml.provider_endpoint, &compliance.aml.provider_api_key);
+        check_provider(
+            &mut v,
+            "compliance.sanctions",
+            compliance.sanctions.enabled,
+            &compliance.sanctions.provider_endpoint,
+            &compliance.sanctions.provider_api_key,
+        );
+        check_unit_interval(&mut v, "compliance.kyc.min_quality_score", compliance.kyc.min_quality_score);
+        check_unit_interval(&mut v, "compliance.sanctions.fuzzy_match_threshold", compliance.sanctions.fuzzy_match_threshold);
+        let weights = &compliance.sanctions.attribute_weights;
+        for (field, value) in [
+            ("dob_match_bonus", weights.dob_match_bonus),
+            ("dob_partial_bonus", weights.dob_partial_bonus),
+            ("dob_mismatch_penalty", weights.dob_mismatch_penalty),
+            ("nationality_match_bonus", weights.nationality_match_bonus),
+            ("nationality_mismatch_penalty", weights.nationality_mismatch_penalty),
+            ("document_match_bonus", weights.document_match_bonus),
+            ("document_mismatch_penalty", weights.document_mismatch_penalty),
+            ("address_match_bonus", weights.address_match_bonus),
+        ] {
+            check_unit_interval(&mut v, &format!("compliance.sanctions.attribute_weights.{}", field), value);
+        }
+        if compliance.kyc.enabled && compliance.kyc.supported_documents.is_empty() {
+            v.push("compliance.kyc.supported_documents", "must not be empty when KYC is enabled");
+        }
+        let documents = &compliance.kyc.documents;
+        let (upload_limit, _) = self.server.body_limits.resolve("POST", "/v1/end-user/session/documents");
+        if documents.max_document_bytes == 0 || documents.max_document_bytes > upload_limit {
+            v.push(
+                "compliance.kyc.documents.max_document_bytes",
+                "must be greater than 0 and at most the body limit of the document upload route",
+            );
+        }
+        if documents.allowed_content_types.is_empty() {
+            v.push("compliance.kyc.documents.allowed_content_types", "must not be empty");
+        }
+        if documents.scan_timeout_secs == 0 {
+            v.push("compliance.kyc.documents.scan_timeout_secs", "must be greater than 0");
+        }
+        let quality = &documents.image_quality;
+        if quality.min_short_side_px == 0 {
+            v.push("compliance.kyc.documents.image_quality.min_short_side_px", "must be greater than 0");
+        }
+        if !(quality.sharp_laplacian_variance > 0.0) {
+            v.push("compliance.kyc.documents.image_quality.sharp_laplacian_variance", "must be greater than 0");
+        }
+        if !(quality.max_glare_share > 0.0 && quality.max_glare_share <= 1.0) {
+            v.push("compliance.kyc.documents.image_quality.max_glare_share", "must be in (0, 1]");
+        }
+        if !(quality.border_share > 0.0 && quality.border_share < 0.5) {
+            v.push("compliance.kyc.documents.image_quality.border_share", "must be in (0, 0.5)");
+        }
+        if !(quality.max_border_edge_ratio > 0.0) {
+            v.push("compliance.kyc.documents.image_quality.max_border_edge_ratio", "must be greater than 0");
+        }
+        match &documents.scanner {
+            Some(DocumentScannerBackend::Clamd { address }) => {
+                let host_port = address.rsplit_once(':');
+                if !host_port.is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok()) {
+                    v.push("compliance.kyc.documents.scanner.address", "must be a host:port address");
+                }
+            }
+            Some(DocumentScannerBackend::Http { url, .. }) => {
+                check_url(&mut v, "compliance.kyc.documents.scanner.url", url);
+            }
+            None => {}
+        }
+        
+        let thresholds = &compliance.aml.risk_thresholds;
+        for (field, value) in [("low", thresholds.low), ("medium", thresholds.medium), ("high", thresholds.high)] {
+            check_unit_interval(&mut v, &format!("compliance.aml.risk_thresholds.{}", field), value);
+        }
+        if !(thresholds.low < thresholds.medium && thresholds.medium < thresholds.high) {
+            v.push("compliance.aml.risk_thresholds", "must satisfy low < medium < high");
+        }
+        
+        let monitoring = &compliance.aml.transaction_monitoring;
+        if monitoring.max_amount_low_risk > monitoring.max_amount_medium_risk {
+            v.push(
+                "compliance.aml.transaction_monitoring.max_amount_low_risk",
+                "must not exceed max_amount_medium_risk",
+            );
+        }
+        if monitoring.max_hourly_transactions > monitoring.max_daily_transactions {
+            v.push(
+                "compliance.aml.transaction_monitoring.max_hourly_transactions",
+                "must not exceed max_daily_transactions",
+            );
+        }
+        if monitoring.max_amount_medium_risk > monitoring.max_daily_volume {
+            v.push(
+                "compliance.aml.transaction_monitoring.max_daily_volume",
+                "must be at least max_amount_medium_risk",
+            );
+        }
+        let ingestion = &monitoring.ingestion;
+        if ingestion.flush_interval_ms == 0 {
+            v.push("compliance.aml.transaction_monitoring.ingestion.flush_interval_ms", "must be greater than 0");
+        }
+        if ingestion.flush_batch_size == 0 {
+            v.push("compliance.aml.transaction_monitoring.ingestion.flush_batch_size", "must be greater than 0");
+        }
+        if ingestion.max_buffered_transactions < ingestion.flush_batch_size {
+            v.push(
+                "compliance.aml.transaction_monitoring.ingestion.max_buffered_transactions",
+                "must be at least flush_batch_size",
+            );
+        }
+        if ingestion.checkpoint_interval_secs == 0 {
+            v.push(
+                "compliance.aml.transaction_monitoring.ingestion.checkpoint_interval_secs",
+                "must be greater than 0",
+            );
+        }
+        
+        let country_risk = &compliance.aml.country_risk;
+        for (field, value) in [
+            ("fatf_black_list_weight", country_risk.fatf_black_list_weight),
+            ("fatf_grey_list_weight", country_risk.fatf_grey_list_weight),
+            ("residence_weight", country_risk.residence_weight),
+            ("document_issuer_weight", country_risk.document_issuer_weight),
+            ("counterparty_weight", country_risk.counterparty_weight),
+        ] {
+            check_unit_interval(&mut v, &format!("compliance.aml.country_risk.{}", field), value);
+        }
+        for (country, weight) in &country_risk.country_weights {
+            let field = format!("compliance.aml.country_risk.country_weights.{}", country);
+            if country.len() != 2 || !country.bytes().all(|b| b.is_ascii_uppercase()) {
+                v.push(field.clone(), "must be keyed by an ISO 3166-1 alpha-2 code");
+            }
+            check_unit_interval(&mut v, &field, *weight);
+        }
+        if country_risk.residence_weight + country_risk.document_issuer_weight + country_risk.counterparty_weight <= 0.0 {
+            v.push("compliance.aml.country_risk", "at least one factor weight must be greater than 0");
+        }
+        
+        let chain_analytics = &compliance.aml.chain_analytics;
+        check_unit_interval(
+            &mut v,
+            "compliance.aml.chain_analytics.max_direct_exposure_share",
+            chain_analytics.max_direct_exposure_share,
+        );
+        if chain_analytics.cache_ttl_secs == 0 {
+            v.push("compliance.aml.chain_analytics.cache_ttl_secs", "must be greater than 0");
+        }
+        if chain_analytics.max_addresses_per_account == 0 {
+            v.push("compliance.aml.chain_analytics.max_addresses_per_account", "must be greater than 0");
+        }
+        if chain_analytics.enabled {
+            match &chain_analytics.backend {
+                None => v.push(
+                    "compliance.aml.chain_analytics.backend",
+                    "must be set when chain analytics is enabled",
+                ),
+                Some(ChainAnalyticsBackend::Chainalysis { api_url, api_key } | ChainAnalyticsBackend::Trm { api_url, api_key }) => {
+                    check_url(&mut v, "compliance.aml.chain_analytics.backend.api_url", api_url);
+                    if api_key.is_empty() {
+                        v.push("compliance.aml.chain_analytics.backend.api_key", "must not be empty");
+                    }
+                }
+            }
+        }
+        
+        let counterparty_risk = &compliance.aml.counterparty_risk;
+        check_unit_interval(&mut v, "compliance.aml.counterparty_risk.decay", counterparty_risk.decay);
+        check_unit_interval(&mut v, "compliance.aml.counterparty_risk.min_share", counterparty_risk.min_share);
+        if counterparty_risk.max_hops == 0 {
+            v.push("compliance.aml.counterparty_risk.max_hops", "must be greater than 0");
+        }
+        if counterparty_risk.max_neighborhood_nodes == 0 {
+            v.push("compliance.aml.counterparty_risk.max_neighborhood_nodes", "must be greater than 0");
+        }
+        
+        let device_risk = &compliance.aml.device_risk;
+        for (field, value) in [
+            ("tor_weight", device_risk.tor_weight),
+            ("vpn_weight", device_risk.vpn_weight),
+            ("proxy_weight", device_risk.proxy_weight),
+            ("hosting_weight", device_risk.hosting_weight),
+            ("residence_mismatch_weight", device_risk.residence_mismatch_weight),
+            ("shared_device_weight", device_risk.shared_device_weight),
+        ] {
+            check_unit_interval(&mut v, &format!("compliance.aml.device_risk.{}", field), value);
+        }
+        if device_risk.shared_device_accounts < 2 {
+            v.push("compliance.aml.device_risk.shared_device_accounts", "must be at least 2");
+        }
+        if device_risk.max_observations == 0 {
+            v.push("compliance.aml.device_risk.max_observations", "must be greater than 0");
+        }
+        
+        let mut factor_names = HashSet::new();
+        for (i, factor) in compliance.aml.custom_factors.iter().enumerate() {
+            let field = format!("compliance.aml.custom_factors[{}]", i);
+            if let Err(message) = factor.validate() {
+                v.push(field.clone(), message);
+            }
+            if !factor_names.insert(factor.name.as_str()) {
+                v.push(field, format!("factor {} is defined more than once", factor.name));
+            }
+        }
+        
+        for (provider, callback) in &compliance.callbacks.providers {
+            let field = format!("compliance.callbacks.providers.{}", provider.name());
+            if callback.secret.is_empty() {
+                v.push(format!("{}.secret", field), "must not be empty");
+            }
+            if axum::http::HeaderName::from_bytes(callback.signature_header.as_bytes()).is_err() {
+                v.push(format!("{}.signature_header", field), "must be a valid header name");
+            }
+        }
+        
+        for (region, providers) in &compliance.residency.regions {
+            for (provider, regional) in providers {
+                let field = format!("compliance.residency.regions.{}.{}.endpoint", region.name(), provider.name());
+                check_url(&mut v, &field, &regional.endpoint);
+            }
+        }
+        
+        let workflows = &compliance.workflows;
+        for (name, definition) in &workflows.definitions {
+            if let Err(message) = definition.validate() {
+                v.push(format!("compliance.workflows.definitions.{}", name), message);
+            }
+        }
+        if let Some(name) = &workflows.default {
+            if !workflows.definitions.contains_key(name) {
+                v.push("compliance.workflows.default", format!("no workflow named {}", name));
+            }
+        }
+        for (client_id, name) in &workflows.clients {
+            if !workflows.definitions.contains_key(name) {
+                v.push(format!("compliance.workflows.clients.{}", client_id), format!("no workflow named {}", name));
+            }
+        }
+        
+        let metering = &compliance.metering;
+        for (name, plan) in &metering.plans {
+            for (operation, quota) in &plan.quotas {
+                if *quota == 0 {
+                    v.push(
+                        format!("compliance.metering.plans.{}.quotas.{}", name, operation.name()),
+                        "must be greater than 0",
+                    );
+                }
+            }
+        }
+        if let Some(name) = &metering.default_plan {
+            if !metering.plans.contains_key(name) {
+                v.push("compliance.metering.default_plan", format!("no plan named {}", name));
+            }
+        }
+        for (client_id, name) in &metering.clients {
+            if !metering.plans.contains_key(name) {
+                v.push(format!("compliance.metering.clients.{}", client_id), format!("no plan named {}", name));
+            }
+        }
+        if metering.alert_thresholds.iter().any(|threshold| !(1..=100).contains(threshold)) {
+            v.push("compliance.metering.alert_thresholds", "must be between 1 and 100");
+        }
+        
+        let routing = &compliance.provider_routing;
+        if routing.currency.trim().is_empty() {
+            v.push("compliance.provider_routing.currency", "must not be empty");
+        }
+        for (provider, cost) in &routing.default_costs {
+            if !(cost.is_finite() && *cost >= 0.0) {
+                v.push(
+                    format!("compliance.provider_routing.default_costs.{}", provider.name()),
+                    "must be a non-negative number",
+                );
+            }
+        }
+        for (name, vendor) in &routing.vendors {
+            let field = format!("compliance.provider_routing.vendors.{}", name);
+            if name == DEFAULT_VENDOR {
+                v.push(&field, "is reserved for the globally configured vendor");
+            }
+            check_url(&mut v, &format!("{}.endpoint", field), &vendor.endpoint);
+            if !(vendor.cost_per_call.is_finite() && vendor.cost_per_call >= 0.0) {
+                v.push(format!("{}.cost_per_call", field), "must be a non-negative number");
+            }
+        }
+        for (index, rule) in routing.rules.iter().enumerate() {
+            let field = format!("compliance.provider_routing.rules[{}].vendors", index);
+            if rule.vendors.is_empty() {
+                v.push(&field, "must not be empty");
+            }
+            for name in &rule.vendors {
+                if name == DEFAULT_VENDOR {
+                    continue;
+                }
+                match routing.vendors.get(name) {
+                    Some(vendor) if vendor.provider != rule.provider => v.push(
+                        &field,
+                        format!("vendor {} serves {}, not {}", name, vendor.provider.name(), rule.provider.name()),
+                    ),
+                    Some(_) => {}
+                    None => v.push(&field, format!("no vendor named {}", name)),
+                }
+            }
+        }
+        
+        let anomaly = &compliance.anomaly_detection;
+        if anomaly.window_secs == 0 {
+            v.push("compliance.anomaly_detection.window_secs", "must be greater than 0");
+        }
+        if anomaly.baseline_windows == 0 {
+            v.push("compliance.anomaly_detection.baseline_windows", "must be greater than 0");
+        }
+        if anomaly.min_samples == 0 {
+            v.push("compliance.anomaly_detection.min_samples", "must be greater than 0");
+        }
+        if !(anomaly.z_threshold > 0.0) {
+            v.push("compliance.anomaly_detection.z_threshold", "must be greater than 0");
+        }
+        
+        let sessions = &compliance.verification_sessions;
+        if sessions.default_ttl_secs == 0 {
+            v.push("compliance.verification_sessions.default_ttl_secs", "must be greater than 0");
+        }
+        if sessions.max_ttl_secs < sessions.default_ttl_secs {
+            v.push("compliance.verification_sessions.max_ttl_secs", "must be at least default_ttl_secs");
+        }
+        if sessions.max_documents == 0 {
+            v.push("compliance.verification_sessions.max_documents", "must be greater than 0");
+        }
+        if let Some(url) = &sessions.hosted_url {
+            check_url(&mut v, "compliance.verification_sessions.hosted_url", url);
+        }
+        
+        let presentations = &compliance.presentations;
+        if let Some(url) = &presentations.public_url {
+            check_url(&mut v, "compliance.presentations.public_url", url);
+            if url.contains(['?', '#']) {
+                v.push("compliance.presentations.public_url", "must not have a query or fragment");
+            }
+        }
+        if presentations.max_inline_bytes == 0 || presentations.max_inline_bytes > QR_BYTE_CAPACITY {
+            v.push(
+                "compliance.presentations.max_inline_bytes",
+                format!("must be between 1 and {}", QR_BYTE_CAPACITY),
+            );
+        }
+        if presentations.reference_ttl_secs == 0 {
+            v.push("compliance.presentations.reference_ttl_secs", "must be greater than 0");
+        }
+        
+        let offboarding = &compliance.offboarding;
+        match offboarding.void_account_id.as_deref().map(AccountId::parse) {
+            None => {}
+            Some(Ok(account_id)) if account_id.kind() == AccountIdKind::Miden => {}
+            Some(_) => v.push("compliance.offboarding.void_account_id", "must be a Miden account id"),
+        }
+        if offboarding.erasure_interval_secs == 0 {
+            v.push("compliance.offboarding.erasure_interval_secs", "must be greater than 0");
+        }
+        
+        if let Some(key) = &compliance.provider_credentials.encryption_key {
+            if !matches!(hex::decode(key), Ok(bytes) if bytes.len() == 32) {
+                v.push("compliance.provider_credentials.encryption_key", "must be 32 bytes, hex-encoded");
+            }
+        }
+        
+        let attestation = &compliance.attestation;
+        if attestation.validity_period_days == 0 {
+            v.push("compliance.attestation.validity_period_days", "must be greater than 0");
+        }
+        if attestation.proof_verification_timeout == 0 {
+            v.push("compliance.attestation.proof_verification_timeout", "must be greater than 0");
+        }
+        if attestation.max_proof_size == 0 {
+            v.push("compliance.attestation.max_proof_size", "must be greater than 0");
+        }
+        if attestation.challenge_ttl_secs == 0 {
+            v.push("compliance.attestation.challenge_ttl_secs", "must be greater than 0");
+        }
+        if attestation.proof_validity_secs == 0 {
+            v.push("compliance.attestation.proof_validity_secs", "must be greater than 0");
+        }
+        let renewal = &attestation.renewal;
+        if renewal.enabled {
+            if renewal.lead_time_hours == 0 || renewal.lead_time_hours >= attestation.validity_period_days * 24 {
+                v.push(
+                    "compliance.attestation.renewal.lead_time_hours",
+                    "must be greater than 0 and shorter than validity_period_days",
+                );
+            }
+            if renewal.check_interval_secs == 0 {
+                v.push("compliance.attestation.renewal.check_interval_secs", "must be greater than 0");
+            }
+            if renewal.max_renewals_per_run == 0 {
+                v.push("compliance.attestation.renewal.max_renewals_per_run", "must be greater than 0");
+            }
+        }
+        let oracle = &attestation.oracle;
+        if oracle.enabled {
+            match oracle.account_id.as_deref().map(AccountId::parse) {
+                None => v.push("compliance.attestation.oracle.account_id", "must be set when the oracle is enabled"),
+                Some(Ok(account_id)) if account_id.kind() == AccountIdKind::Miden => {}
+                Some(_) => v.push("compliance.attestation.oracle.account_id", "must be a Miden account id"),
+            }
+            if oracle.publish_interval_secs == 0 {
+                v.push("compliance.attestation.oracle.publish_interval_secs", "must be greater than 0");
+            }
+        }
+        if attestation.registry.enabled && attestation.registry.publish_interval_secs == 0 {
+            v.push("compliance.attestation.registry.publish_interval_secs", "must be greater than 0");
+        }
+        let epochs = &attestation.epochs;
+        if epochs.enabled {
+            match epochs.anchor_account_id.as_deref().map(AccountId::parse) {
+                None => v.push(
+                    "compliance.attestation.epochs.anchor_account_id",
+                    "must be set when epoch batching is enabled",
+                ),
+                Some(Ok(account_id)) if account_id.kind() == AccountIdKind::Miden => {}
+                Some(_) => v.push("compliance.attestation.epochs.anchor_account_id", "must be a Miden account id"),
+            }
+            if epochs.epoch_secs == 0 {
+                v.push("compliance.attestation.epochs.epoch_secs", "must be greater than 0");
+            }
+        }
+        match &attestation.notarization.backend {
+            None => {}
+            Some(NotarizationBackend::Rfc3161 { url }) => {
+                check_url(&mut v, "compliance.attestation.notarization.backend.url", url);
+            }
+            Some(NotarizationBackend::BlockReference { chain, rpc_url }) => {
+                if chain.is_empty() {
+                    v.push("compliance.attestation.notarization.backend.chain", "must not be empty");
+                }
+                check_url(&mut v, "compliance.attestation.notarization.backend.rpc_url", rpc_url);
+            }
+        }
+        let cache = &attestation.verification_cache;
+        if cache.enabled {
+            if cache.max_entries == 0 {
+                v.push("compliance.attestation.verification_cache.max_entries", "must be greater than 0");
+            }
+            if cache.negative_ttl_secs == 0 {
+                v.push("compliance.attestation.verification_cache.negative_ttl_secs", "must be greater than 0");
+            }
+        }
+        let threshold_signing = &attestation.threshold_signing;
+        for (i, level_policy) in threshold_signing.policies.iter().enumerate() {
+            let field = format!("compliance.attestation.threshold_signing.policies[{}]", i);
+            match level_policy.policy.validate() {
+                Ok(()) => {}
+                Err(crate::ComplianceError::Validation { field: inner, message }) => {
+                    v.push(format!("{}.{}", field, inner), message)
+                }
+                Err(e) => v.push(field.clone(), e.to_string()),
+            }
+            if threshold_signing.policies[..i].iter().any(|other| other.min_level == level_policy.min_level) {
+                v.push(format!("{}.min_level", field), "has more than one policy");
+            }
+        }
+        if !threshold_signing.policies.is_empty() && threshold_signing.timeout_secs == 0 {
+            v.push("compliance.attestation.threshold_signing.timeout_secs", "must be greater than 0");
+        }
+        if compliance.step_up.session_ttl_hours == 0 {
+            v.push("compliance.step_up.session_ttl_hours", "must be greater than 0");
+        }
+        if compliance.overrides.max_duration_days == 0 {
+            v.push("compliance.overrides.max_duration_days", "must be greater than 0");
+        }
+        if compliance.overrides.expiry_check_interval_secs == 0 {
+            v.push("compliance.overrides.expiry_check_interval_secs", "must be greater than 0");
+        }
+        if compliance.funds_declarations.validity_days == 0 {
+            v.push("compliance.funds_declarations.validity_days", "must be greater than 0");
+        }
+        if compliance.funds_declarations.max_supporting_documents == 0 {
+            v.push("compliance.funds_declarations.max_supporting_documents", "must be greater than 0");
+        }
+        let providers = &compliance.providers;
+        for (name, breaker) in [
+            ("kyc", &providers.kyc),
+            ("aml", &providers.aml),
+            ("sanctions", &providers.sanctions),
+            ("adverse_media", &providers.adverse_media),
+            ("chain_analytics", &providers.chain_analytics),
+        ] {
+            let field = format!("compliance.providers.{}", name);
+            if breaker.failure_threshold == 0 {
+                v.push(format!("{}.failure_threshold", field), "must be greater than 0");
+            }
+            if breaker.half_open_max_probes == 0 {
+                v.push(format!("{}.half_open_max_probes", field), "must be greater than 0");
+            }
+            if breaker.call_timeout_secs == 0 {
+                v.push(format!("{}.call_timeout_secs", field), "must be greater than 0");
+            }
+        }
+        for (name, breaker) in [("kyc", &providers.kyc), ("aml", &providers.aml), ("sanctions", &providers.sanctions)] {
+            if breaker.criticality == CheckCriticality::Optional {
+                v.push(
+                    format!("compliance.providers.{}.criticality", name),
+                    "must be critical, attestations are issued from its result",
+                );
+            }
+        }
+        if providers.adverse_media.criticality == CheckCriticality::Optional {
+            v.push(
+                "compliance.providers.adverse_media.criticality",
+                "must be critical, adverse media is not checked during compliance checks",
+            );
+        }
+        if providers.retry_interval_secs == 0 {
+            v.push("compliance.providers.retry_interval_secs", "must be greater than 0");
+        }
+        if production_like && providers.sanctions.fallback == FallbackPolicy::FailOpenWithFlag {
+            v.push(
+                "compliance.providers.sanctions.fallback",
+                "fail-open is not allowed for sanctions outside development",
+            );
+        }
+        
+        // Webhooks
+        if self.webhooks.enabled {
+            if self.webhooks.secret.is_empty() {
+                v.push("webhooks.secret", "must not be empty when webhooks are enabled");
+            } else if production_like && self.webhooks.secret == DEFAULT_WEBHOOK_SECRET {
+                v.push("webhooks.secret", "still set to the default value");
+            }
+            if self.webhooks.timeout == 0 {
+                v.push("webhooks.timeout", "must be greater than 0");
+            }
+            if let Some(proxy) = &self.webhooks.egress.proxy {
+                check_url(&mut v, "webhooks.egress.proxy", proxy);
+            }
+            for (i, ip) in self.webhooks.egress.source_ips.iter().enumerate() {
+                if ip.parse::<std::net::IpAddr>().is_err() {
+                    v.push(format!("webhooks.egress.source_ips[{}]", i), "must be an IP address");
+                }
+            }
+        }
+        
+        // Security
+        let security = &self.security;
+        if production_like && security.jwt_secret == DEFAULT_JWT_SECRET {
+            v.push("security.jwt_secret", "still set to the default value");
+        } else if production_like && security.jwt_secret.len() < 32 {
+            v.push("security.jwt_secret", "must be at least 32 bytes");
+        }
+        let backend_holds_key = !matches!(security.signing_backend, SigningBackendConfig::Software);
+        if production_like && !backend_holds_key && security.signing_key_seed.is_none() {
+            v.push("security.signing_key_seed", "must be set; an ephemeral key would invalidate proofs on restart");
+        }
+        if backend_holds_key && security.signing_key_seed.is_some() {
+            v.push("security.signing_key_seed", "must not be set when a signing backend holds the key");
+        }
+        match &security.signing_backend {
+            SigningBackendConfig::Software => {}
+            SigningBackendConfig::Pkcs11 {
+                module_path,
+                token_label,
+                key_label,
+                pin,
+            } => {
+                if module_path.as_os_str().is_empty() {
+                    v.push("security.signing_backend.module_path", "must not be empty");
+                }
+                for (field, value) in [("token_label", token_label), ("key_label", key_label), ("pin", pin)] {
+                    if value.is_empty() {
+                        v.push(format!("security.signing_backend.{}", field), "must not be empty");
+                    }
+                }
+            }
+            SigningBackendConfig::AwsKms { key_id, region } => {
+                if key_id.trim().is_empty() {
+                    v.push("security.signing_backend.key_id", "must not be empty");
+                }
+                if region.trim().is_empty() {
+                    v.push("security.signing_backend.region", "must not be empty");
+                }
+            }
+            SigningBackendConfig::GcpKms { key_version, .. } => {
+                let segments: Vec<&str> = key_version.split('/').collect();
+                let well_formed = segments.len() == 10
+                    && segments
+                        .iter()
+                        .step_by(2)
+                        .copied()
+                        .eq(["projects", "locations", "keyRings", "cryptoKeys", "cryptoKeyVersions"])
+                    && segments.iter().skip(1).step_by(2).all(|segment| !segment.is_empty());
+                if !well_formed {
+                    v.push(
+                        "security.signing_backend.key_version",
+                        "must be a projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/* name",
+                    );
+                }
+            }
+        }
+        if security.deployment_id.trim().is_empty() {
+            v.push("security.deployment_id", "must not be empty");
+        }
+        if security.operator_session_ttl_secs == 0 {
+            v.push("security.operator_session_ttl_secs", "must be greater than 0");
+        }
+        if security.api_key_length < 16 {
+            v.push("security.api_key_length", "must be at least 16");
+        }
+        
+        let limits = &security.rate_limiting;
+        if limits.requests_per_minute == 0 || limits.requests_per_hour == 0 || limits.requests_per_day == 0 {
+            v.push("security.rate_limiting", "request limits must be greater than 0");
+        }
+        if limits.requests_per_minute > limits.requests_per_hour {
+            v.push("security.rate_limiting.requests_per_minute", "must not exceed requests_per_hour");
+        }
+        if limits.requests_per_hour > limits.requests_per_day {
+            v.push("security.rate_limiting.requests_per_hour", "must not exceed requests_per_day");
+        }
+        if limits.burst_size > limits.requests_per_minute {
+            v.push("security.rate_limiting.burst_size", "must not exceed requests_per_minute");
+        }
+        
+        // Status page
+        let status_page = &self.status_page;
+        if status_page.requests_per_minute == 0 {
+            v.push("status_page.requests_per_minute", "must be greater than 0");
+        }
+        if status_page.latency_window_secs < status_page.refresh_secs {
+            v.push("status_page.latency_window_secs", "must not be shorter than refresh_secs");
+        }
+        // Fewer samples would let an observer infer individual clients' verifications
+        if status_page.min_latency_samples < 10 {
+            v.push("status_page.min_latency_samples", "must be at least 10");
+        }
+        if status_page.latency_rounding_ms == 0 {
+            v.push("status_page.latency_rounding_ms", "must be greater than 0");
+        }
+        if status_page.list_stale_after_secs == 0 {
+            v.push("status_page.list_stale_after_secs", "must be greater than 0");
+        }
+        
+        // Sandbox
+        let sandbox = &self.sandbox;
+        if sandbox.enabled {
+            match &sandbox.database_url {
+                None => v.push("sandbox.database_url", "must be set when the sandbox is enabled"),
+                Some(url) if url.trim().is_empty() || *url == self.database.url => {
+                    v.push("sandbox.database_url", "must not be empty or the production database");
+                }
+                Some(_) => {}
+            }
+            match &sandbox.mock_provider_endpoint {
+                None => v.push("sandbox.mock_provider_endpoint", "must be set when the sandbox is enabled"),
+                Some(endpoint) => check_url(&mut v, "sandbox.mock_provider_endpoint", endpoint),
+            }
+            check_url(&mut v, "sandbox.miden_rpc_endpoint", &sandbox.miden_rpc_endpoint);
+            if self.environment == Environment::Production && sandbox.miden_rpc_endpoint == self.miden.rpc_endpoint {
+                v.push("sandbox.miden_rpc_endpoint", "must not be the production Miden node");
+            }
+        }
+        
+        // Localization
+        if crate::compliance::localization::normalize_locale(&self.localization.default_locale).is_err() {
+            v.push("localization.default_locale", "must be a locale tag such as \"en\" or \"pt-BR\"");
+        }
+        if self.localization.catalog_dir.as_ref().is_some_and(|dir| !dir.is_dir()) {
+            v.push("localization.catalog_dir", "must be an existing directory");
+        }
+        
+        // Scheduler
+        for (name, job) in &self.scheduler.jobs {
+            let field = format!("scheduler.jobs.{}", name);
+            if job.cron.is_some() && job.interval_secs.is_some() {
+                v.push(&field, "must set at most one of cron and interval_secs");
+            }
+            if let Some(Err(e)) = job.cron.as_deref().map(crate::reporting::schedule::CronSchedule::parse) {
+                v.push(format!("{}.cron", field), e);
+            }
+            if job.interval_secs == Some(0) {
+                v.push(format!("{}.interval_secs", field), "must be greater than 0");
+            }
+        }
+        
+        // Leader election
+        let leader_election = &self.leader_election;
+        if leader_election.enabled {
+            if leader_election.lease_secs <= self.webhooks.retry_delay {
+                v.push(
+                    "leader_election.lease_secs",
+                    "must be longer than webhooks.retry_delay, so the outbox relay renews its lease in time",
+                );
+            }
+            if leader_election.instance_id.as_ref().is_some_and(|id| id.trim().is_empty()) {
+                v.push("leader_election.instance_id", "must not be empty");
+            }
+        }
+        
+        // Logging
+        if !matches!(self.logging.format.as_str(), "json" | "text") {
+            v.push("logging.format", "must be \"json\" or \"text\"");
+        }
+        if !matches!(self.logging.level.as_str(), "trace" | "debug" | "info" | "warn" | "error") {
+            v.push("logging.level", "must be one of trace, debug, info, warn, error");
+        }
+        
+        // Secrets
+        if let Some(vault) = &self.secrets.vault {
+            check_url(&mut v, "secrets.vault.address", &vault.address);
+            if !(vault.token.starts_with("env:") || vault.token.starts_with("file:")) {
+                v.push("secrets.vault.token", "must be an env: or file: reference");
+            }
+        }
+        if self.secrets.aws.as_ref().is_some_and(|aws| aws.region.is_empty()) {
+            v.push("secrets.aws.region", "must not be empty");
+        }
+        
+        // Reporting
+        for (i, schedule) in self.reporting.schedules.iter().enumerate() {
+            let field = format!("reporting.schedules[{}]", i);
+            if let Err(e) = crate::reporting::schedule::CronSchedule::parse(&schedule.cron) {
+                v.push(format!("{}.cron", field), e);
+            }
+            if schedule.period_days == 0 {
+                v.push(format!("{}.period_days", field), "must be greater than 0");
+            }
+        }
+        
+        // Event bus
+        if self.event_bus.enabled {
+            match &self.event_bus.backend {
+                None => v.push("event_bus.backend", "must be set when the event bus is enabled"),
+                Some(EventBusBackend::Kafka { brokers, sasl_username, sasl_password }) => {
+                    if !cfg!(feature = "kafka") {
+                        v.push("event_bus.backend", "Kafka support requires the `kafka` feature");
+                    }
+                    if brokers.is_empty() {
+                        v.push("event_bus.backend.brokers", "must not be empty");
+                    }
+                    if sasl_username.is_some() != sasl_password.is_some() {
+                        v.push("event_bus.backend.sasl_password", "SASL username and password must be set together");
+                    }
+                }
+                Some(EventBusBackend::Nats { url, .. }) => {
+                    if !cfg!(feature = "nats") {
+                        v.push("event_bus.backend", "NATS support requires the `nats` feature");
+                    }
+                    if url.is_empty() {
+                        v.push("event_bus.backend.url", "must not be empty");
+                    }
+                }
+            }
+            if self.event_bus.topic_prefix.is_empty() {
+                v.push("event_bus.topic_prefix", "must not be empty");
+            }
+            if self.event_bus.retry_backoff_ms == 0 || self.event_bus.retry_backoff_ms > self.event_bus.max_retry_backoff_ms {
+                v.push("event_bus.retry_backoff_ms", "must be greater than 0 and at most max_retry_backoff_ms");
+            }
+        }
+        
+        // Alerting
+        for (i, sink) in self.alerting.sinks.iter().enumerate() {
+            let field = format!("alerting.sinks[{}]", i);
+            match sink {
+                AlertSinkConfig::Email { relay_endpoint, recipients, .. } => {
+                    check_url(&mut v, &field, relay_endpoint);
+                    if recipients.is_empty() {
+                        v.push(field, "email sink must have at least one recipient");
+                    }
+                }
+                AlertSinkConfig::Slack { webhook_url, .. } => check_url(&mut v, &field, webhook_url),
+                AlertSinkConfig::PagerDuty { routing_key, .. } => {
+                    if routing_key.is_empty() {
+                        v.push(field, "PagerDuty routing key must not be empty");
+                    }
+                }
+            }
+        }
+        
+        v.into_result()
+    }
+}
+
+/// A single configuration invariant violation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigViolation {
+    /// Dotted path of the offending field
+    pub field: String,
+    pub message: String,
+}
+
+/// All violations found by [`Config::validate`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigViolations(pub Vec<ConfigViolation>);
+
+impl ConfigViolations {
+    fn push(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigViolation {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+    
+    fn into_result(self) -> Result<(), Self> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ConfigViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}: {}", violation.field, violation.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigViolations {}
+
+/// Whether `value` is a `type/subtype` media type without parameters
+fn is_media_type(value: &str) -> bool {
+    let token = |part: &str| {
+        !part.is_empty()
+            && part.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"!#$&-^_.+*".contains(&b))
+    };
+    value.split_once('/').is_some_and(|(kind, subtype)| token(kind) && token(subtype))
+}
+
+fn check_unit_interval(v: &mut ConfigViolations, field: &str, value: f64) {
+    if !(0.0..=1.0).contains(&value) {
+        v.push(field, "must be between 0 and 1");
+    }
+}
+
+fn check_provider(v: &mut ConfigViolations, section: &str, enabled: bool, endpoint: &Option<String>, api_key: &Option<String>) {
+    if let Some(endpoint) = endpoint {
+        check_url(v, &format!("{}.provider_endpoint", section), endpoint);
+        if enabled && api_key.is_none() {
+            v.push(format!("{}.provider_api_key", section), "must be set when a provider endpoint is configured");
+        }
+    }
+}
+
+fn check_url(v: &mut ConfigViolations, field: &str, url: &str) {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        v.push(field, "must be an http(s) URL");
+    }
+}
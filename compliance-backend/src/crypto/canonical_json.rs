@@ -0,0 +1,139 @@
+//! JSON Canonicalization Scheme (RFC 8785)
+//!
+//! Signatures over JSON only verify if signer and verifier hash the same
+//! bytes, and serde's output depends on field declaration order and map
+//! types that other languages do not reproduce. Signed JSON payloads are
+//! therefore serialized canonically: no whitespace, object members sorted by
+//! the UTF-16 code units of their names, strings with the minimal escaping of
+//! RFC 8785, and numbers formatted as ECMAScript formats doubles. A consumer
+//! in any language re-serializes a parsed payload with its own JCS library
+//! and gets the signed bytes back.
+//!
+//! Numbers must be representable as IEEE 754 doubles, so integers beyond
+//! ±2^53 are rejected rather than silently rounded. serde_json already
+//! serializes non-finite floats as `null`.
+
+use crate::{ComplianceError, Result};
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Largest integer every double can represent exactly
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serialize a value as canonical JSON
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    write_value(&serde_json::to_value(value)?, &mut out)?;
+    Ok(out)
+}
+
+/// Serialize a value as a canonical JSON string
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    let bytes = to_vec(value)?;
+    Ok(String::from_utf8(bytes).expect("canonical JSON is UTF-8"))
+}
+
+/// Canonicalize JSON text, e.g. a received payload before checking its signature
+pub fn canonicalize(json: &[u8]) -> Result<Vec<u8>> {
+    to_vec(&serde_json::from_slice::<Value>(json)?)
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(true) => out.extend_from_slice(b"true"),
+        Value::Bool(false) => out.extend_from_slice(b"false"),
+        Value::Number(number) => out.extend_from_slice(format_number(number)?.as_bytes()),
+        Value::String(string) => serde_json::to_writer(&mut *out, string)?,
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push(b'{');
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                serde_json::to_writer(&mut *out, name)?;
+                out.push(b':');
+                write_value(value, out)?;
+            }
+            out.push(b'}');
+        }
+    }
+    Ok(())
+}
+
+fn format_number(number: &Number) -> Result<String> {
+    if let Some(n) = number.as_u64() {
+        return if n <= MAX_SAFE_INTEGER {
+            Ok(n.to_string())
+        } else {
+            Err(unrepresentable(number))
+        };
+    }
+    if let Some(n) = number.as_i64() {
+        return if n.unsigned_abs() <= MAX_SAFE_INTEGER {
+            Ok(n.to_string())
+        } else {
+            Err(unrepresentable(number))
+        };
+    }
+    let n = number.as_f64().ok_or_else(|| unrepresentable(number))?;
+    Ok(format_double(n))
+}
+
+/// Format a finite double as ECMAScript's `Number.prototype.toString` does
+fn format_double(n: f64) -> String {
+    if n == 0.0 {
+        return "0".to_string();
+    }
+    let mut out = String::new();
+    if n < 0.0 {
+        out.push('-');
+    }
+    
+    // Shortest round-tripping digits, with the value being 0.<digits> × 10^point
+    let scientific = format!("{:e}", n.abs());
+    let (mantissa, exponent) = scientific.split_once('e').expect("exponent notation has an exponent");
+    let digits = mantissa.replace('.', "");
+    let point = exponent.parse::<i32>().expect("exponent is an integer") + 1;
+    let len = digits.len() as i32;
+    
+    if len <= point && point <= 21 {
+        out.push_str(&digits);
+        out.push_str(&"0".repeat((point - len) as usize));
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        out.push_str(integer);
+        out.push('.');
+        out.push_str(fraction);
+    } else if -6 < point && point <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat(-point as usize));
+        out.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            out.push('.');
+            out.push_str(rest);
+        }
+        let exponent = point - 1;
+        out.push_str(&format!("e{}{}", if exponent < 0 { '-' } else { '+' }, exponent.abs()));
+    }
+    out
+}
+
+fn unrepresentable(number: &Number) -> ComplianceError {
+    ComplianceError::internal(format!("{} cannot be represented exactly in canonical JSON", number))
+}
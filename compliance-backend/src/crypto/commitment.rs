@@ -0,0 +1,220 @@
+//! Canonical commitments to compliance data
+//!
+//! Every commitment the backend publishes, whether stored in an account
+//! slot, embedded in a proof envelope or used as a Merkle leaf, is an RPO
+//! hash over a fixed field-element encoding:
+//!
+//! ```text
+//! RPO(version, domain, field_1, field_2, ...)
+//! ```
+//!
+//! Fields are encoded as follows, so any implementation with an RPO hash can
+//! reproduce a commitment, including a Miden program:
+//!
+//! - integers and enum codes: one element holding the value
+//! - flags: one element, `1` or `0`
+//! - timestamps: one element holding Unix seconds, clamped at zero
+//! - byte strings: one element holding the length, then the bytes in
+//!   little-endian 4-byte limbs, the last one zero-padded
+//! - text: the UTF-8 bytes as a byte string
+//!
+//! The version changes whenever an encoding changes, so commitments from
+//! different versions never collide.
+
+use crate::types::{AmlRiskLevel, ComplianceAttestation, ComplianceLevel, KycStatus};
+use chrono::{DateTime, Utc};
+use miden_crypto::hash::rpo::{Rpo256, RpoDigest};
+use miden_crypto::{Felt, Word};
+use std::fmt;
+
+/// Version of the field encoding
+pub const COMMITMENT_VERSION: u64 = 2;
+
+/// What a commitment is to
+///
+/// The domain is hashed after the version, so equal fields committed for
+/// different purposes give different commitments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitmentDomain {
+    /// A compliance attestation, as bound by proof envelopes
+    Attestation,
+    /// A compliant account in the oracle's Merkle tree
+    OracleLeaf,
+    /// An account's key in the attestation registry
+    RegistryKey,
+    /// A salted claim in a proof envelope's claim tree
+    Claim,
+}
+
+impl CommitmentDomain {
+    /// Element identifying the domain
+    pub fn code(self) -> u64 {
+        match self {
+            Self::Attestation => 1,
+            Self::OracleLeaf => 2,
+            Self::RegistryKey => 3,
+            Self::Claim => 4,
+        }
+    }
+}
+
+/// Value encoding a KYC status
+pub fn kyc_status_code(status: &KycStatus) -> u64 {
+    match status {
+        KycStatus::Pending => 0,
+        KycStatus::Verified => 1,
+        KycStatus::Rejected => 2,
+        KycStatus::Expired => 3,
+    }
+}
+
+/// Value encoding a compliance level
+pub fn compliance_level_code(level: &ComplianceLevel) -> u64 {
+    match level {
+        ComplianceLevel::Basic => 0,
+        ComplianceLevel::Standard => 1,
+        ComplianceLevel::Enhanced => 2,
+        ComplianceLevel::InstitutionalGrade => 3,
+    }
+}
+
+/// Value encoding an AML risk level
+pub fn aml_risk_code(level: &AmlRiskLevel) -> u64 {
+    match level {
+        AmlRiskLevel::Low => 0,
+        AmlRiskLevel::Medium => 1,
+        AmlRiskLevel::High => 2,
+        AmlRiskLevel::Critical => 3,
+    }
+}
+
+/// Field elements of a commitment, written field by field
+#[derive(Debug, Clone)]
+pub struct FieldEncoder {
+    elements: Vec<Felt>,
+}
+
+impl FieldEncoder {
+    /// Start an encoding in `domain` at the current version
+    pub fn new(domain: CommitmentDomain) -> Self {
+        let mut encoder = Self { elements: Vec::new() };
+        encoder.value(COMMITMENT_VERSION).value(domain.code());
+        encoder
+    }
+    
+    /// Element that is already a field element, such as a Miden account id half
+    pub fn felt(&mut self, element: Felt) -> &mut Self {
+        self.elements.push(element);
+        self
+    }
+    
+    /// Integer or enum code
+    ///
+    /// Values are reduced modulo the field; callers only pass codes and
+    /// counts well below it.
+    pub fn value(&mut self, value: u64) -> &mut Self {
+        self.felt(Felt::new(value))
+    }
+    
+    /// Flag
+    pub fn flag(&mut self, flag: bool) -> &mut Self {
+        self.value(flag as u64)
+    }
+    
+    /// Timestamp in Unix seconds, clamped at zero
+    pub fn timestamp(&mut self, at: DateTime<Utc>) -> &mut Self {
+        self.value(at.timestamp().max(0) as u64)
+    }
+    
+    /// Length-prefixed byte string
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.value(bytes.len() as u64);
+        for chunk in bytes.chunks(4) {
+            let mut limb = [0u8; 4];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            self.value(u32::from_le_bytes(limb) as u64);
+        }
+        self
+    }
+    
+    /// UTF-8 text
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.bytes(text.as_bytes())
+    }
+    
+    /// Elements written so far, including the version and domain
+    pub fn elements(&self) -> &[Felt] {
+        &self.elements
+    }
+    
+    /// Hash the elements
+    pub fn commit(&self) -> Commitment {
+        Commitment(Rpo256::hash_elements(&self.elements))
+    }
+}
+
+/// Canonical commitment to compliance data
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Commitment(RpoDigest);
+
+impl Commitment {
+    /// Digest as bytes, as embedded in proof envelopes
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.as_bytes()
+    }
+    
+    /// Hex encoding of the digest bytes
+    pub fn to_hex(&self) -> String {
+        self.0.to_hex()
+    }
+    
+    /// Digest as a storage word or Merkle leaf
+    pub fn to_word(&self) -> Word {
+        self.0.into()
+    }
+    
+    /// Underlying RPO digest
+    pub fn digest(&self) -> RpoDigest {
+        self.0
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Commitment({})", self.to_hex())
+    }
+}
+
+/// Field encoding of an attestation
+///
+/// Fields in order: id (16 bytes), account id (text), KYC status, AML risk
+/// level, sanctions clearance, creation time, expiry, proof hash (32 bytes),
+/// then the number of partial checks followed by each check's name (text).
+pub fn encode_attestation(attestation: &ComplianceAttestation) -> FieldEncoder {
+    let mut encoder = FieldEncoder::new(CommitmentDomain::Attestation);
+    encoder
+        .bytes(attestation.id.as_bytes())
+        .text(attestation.account_id.as_str())
+        .value(kyc_status_code(&attestation.kyc_status))
+        .value(aml_risk_code(&attestation.aml_risk_level))
+        .flag(attestation.sanctions_cleared)
+        .timestamp(attestation.created_at)
+        .timestamp(attestation.expires_at)
+        .bytes(attestation.proof_hash.as_bytes())
+        .value(attestation.partial.len() as u64);
+    for check in &attestation.partial {
+        encoder.text(check);
+    }
+    encoder
+}
+
+/// Commitment to an attestation
+pub fn attestation_commitment(attestation: &ComplianceAttestation) -> Commitment {
+    encode_attestation(attestation).commit()
+}
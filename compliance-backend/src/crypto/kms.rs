@@ -0,0 +1,226 @@
+//! Cloud KMS signing backends
+//!
+//! The private key stays in the provider's KMS; each signature is a request
+//! to it. Both backends expect an Ed25519 key: `ECC_NIST_EDWARDS25519` on
+//! AWS, `EC_SIGN_ED25519` on Google Cloud.
+//!
+//! [`SigningBackend`] is synchronous, so requests are driven to completion
+//! on the calling thread. Inside a Tokio runtime that must be the
+//! multi-threaded one, which lets the worker block while the KMS answers.
+
+use super::signing::SigningBackend;
+use crate::secrets::aws::sigv4_authorization;
+use crate::{ComplianceError, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::VerifyingKey;
+use std::future::Future;
+use std::sync::Mutex;
+
+/// DER prefix of an Ed25519 SubjectPublicKeyInfo, followed by the 32-byte key
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+const AWS_CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Metadata server handing out the attached service account's tokens
+const GCP_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// Ed25519 public key of a DER SubjectPublicKeyInfo
+pub fn ed25519_from_spki(der: &[u8]) -> Result<VerifyingKey> {
+    let key: [u8; 32] = der
+        .strip_prefix(&ED25519_SPKI_PREFIX[..])
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(|| kms_error("public key is not an Ed25519 key"))?;
+    VerifyingKey::from_bytes(&key).map_err(|e| kms_error(format!("invalid public key: {}", e)))
+}
+
+/// Key in AWS KMS, reached with SigV4-signed requests
+///
+/// Credentials are taken from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+/// and optionally `AWS_SESSION_TOKEN`, as for AWS Secrets Manager.
+pub struct AwsKmsBackend {
+    http: reqwest::Client,
+    key_id: String,
+    region: String,
+}
+
+impl AwsKmsBackend {
+    /// Create a backend for a key id, ARN or alias
+    pub fn new(key_id: impl Into<String>, region: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            key_id: key_id.into(),
+            region: region.into(),
+        }
+    }
+    
+    async fn call(&self, target: &str, body: serde_json::Value) -> Result<serde_json::Value> {
+        let access_key = std::env::var("AWS_ACCESS_KEY_ID").map_err(|_| kms_error("AWS_ACCESS_KEY_ID is not set"))?;
+        let secret_key =
+            std::env::var("AWS_SECRET_ACCESS_KEY").map_err(|_| kms_error("AWS_SECRET_ACCESS_KEY is not set"))?;
+        let session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+        
+        let host = format!("kms.{}.amazonaws.com", self.region);
+        let body = body.to_string();
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("content-type", AWS_CONTENT_TYPE.to_string()),
+            ("host", host.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.push(("x-amz-target", format!("TrentService.{}", target)));
+        let authorization =
+            sigv4_authorization(&access_key, &secret_key, &self.region, "kms", &amz_date, &headers, body.as_bytes());
+        
+        let mut request = self.http.post(format!("https://{}/", host)).body(body);
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(*name, value);
+        }
+        let response = request.header("authorization", authorization).send().await?;
+        if !response.status().is_success() {
+            return Err(kms_error(format!("AWS KMS {} returned {}", target, response.status())));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+impl SigningBackend for AwsKmsBackend {
+    fn verifying_key(&self) -> Result<VerifyingKey> {
+        let response = block_on(self.call("GetPublicKey", serde_json::json!({ "KeyId": self.key_id })))?;
+        let der = response["PublicKey"]
+            .as_str()
+            .and_then(|key| STANDARD.decode(key).ok())
+            .ok_or_else(|| kms_error("AWS KMS returned no public key"))?;
+        ed25519_from_spki(&der)
+    }
+    
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let response = block_on(self.call(
+            "Sign",
+            serde_json::json!({
+                "KeyId": self.key_id,
+                "Message": STANDARD.encode(message),
+                "MessageType": "RAW",
+                "SigningAlgorithm": "ED25519_SHA_512",
+            }),
+        ))?;
+        response["Signature"]
+            .as_str()
+            .and_then(|signature| STANDARD.decode(signature).ok())
+            .ok_or_else(|| kms_error("AWS KMS returned no signature"))
+    }
+}
+
+/// Key version in Google Cloud KMS
+///
+/// Requests are authorized with the configured access token, or else with
+/// tokens of the service account attached to the instance, fetched from the
+/// metadata server and reused until shortly before they expire.
+pub struct GcpKmsBackend {
+    http: reqwest::Client,
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    key_version: String,
+    access_token: Option<String>,
+    cached_token: Mutex<Option<(String, DateTime<Utc>)>>,
+}
+
+impl GcpKmsBackend {
+    /// Create a backend for a key version resource name
+    pub fn new(key_version: impl Into<String>, access_token: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            key_version: key_version.into(),
+            access_token,
+            cached_token: Mutex::new(None),
+        }
+    }
+    
+    async fn token(&self) -> Result<String> {
+        if let Some(token) = &self.access_token {
+            return Ok(token.clone());
+        }
+        let now = Utc::now();
+        if let Some((token, expires_at)) = self.cached_token.lock().expect("token cache poisoned").as_ref() {
+            if *expires_at > now {
+                return Ok(token.clone());
+            }
+        }
+        
+        let response = self.http.get(GCP_TOKEN_URL).header("Metadata-Flavor", "Google").send().await?;
+        if !response.status().is_success() {
+            return Err(kms_error(format!("metadata server returned {}", response.status())));
+        }
+        let payload: serde_json::Value = response.json().await?;
+        let token = payload["access_token"]
+            .as_str()
+            .ok_or_else(|| kms_error("metadata server returned no access token"))?
+            .to_string();
+        let lifetime = payload["expires_in"].as_i64().unwrap_or(0);
+        *self.cached_token.lock().expect("token cache poisoned") =
+            Some((token.clone(), now + Duration::seconds(lifetime - 60)));
+        Ok(token)
+    }
+    
+    async fn get_public_key(&self) -> Result<VerifyingKey> {
+        let url = format!("https://cloudkms.googleapis.com/v1/{}/publicKey", self.key_version);
+        let response = self.http.get(url).bearer_auth(self.token().await?).send().await?;
+        if !response.status().is_success() {
+            return Err(kms_error(format!("Cloud KMS getPublicKey returned {}", response.status())));
+        }
+        let payload: serde_json::Value = response.json().await?;
+        let pem = payload["pem"].as_str().ok_or_else(|| kms_error("Cloud KMS returned no public key"))?;
+        let body: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+        let der = STANDARD.decode(body).map_err(|e| kms_error(format!("invalid public key PEM: {}", e)))?;
+        ed25519_from_spki(&der)
+    }
+    
+    async fn asymmetric_sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let url = format!("https://cloudkms.googleapis.com/v1/{}:asymmetricSign", self.key_version);
+        let response = self
+            .http
+            .post(url)
+            .bearer_auth(self.token().await?)
+            .json(&serde_json::json!({ "data": STANDARD.encode(message) }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(kms_error(format!("Cloud KMS asymmetricSign returned {}", response.status())));
+        }
+        let payload: serde_json::Value = response.json().await?;
+        payload["signature"]
+            .as_str()
+            .and_then(|signature| STANDARD.decode(signature).ok())
+            .ok_or_else(|| kms_error("Cloud KMS returned no signature"))
+    }
+}
+
+impl SigningBackend for GcpKmsBackend {
+    fn verifying_key(&self) -> Result<VerifyingKey> {
+        block_on(self.get_public_key())
+    }
+    
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        block_on(self.asymmetric_sign(message))
+    }
+}
+
+/// Drive a KMS request to completion on the calling thread
+fn block_on<F: Future>(future: F) -> F::Output {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => tokio::task::block_in_place(|| handle.block_on(future)),
+        Err(_) => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build a runtime for a KMS request")
+            .block_on(future),
+    }
+}
+
+fn kms_error(message: impl Into<String>) -> ComplianceError {
+    ComplianceError::crypto(message)
+}
@@ -0,0 +1,22 @@
+//! Cryptographic primitives for attestation signing and commitments
+
+pub mod canonical_json;
+pub mod commitment;
+pub mod proof_hash;
+pub mod signing;
+pub mod threshold;
+#[cfg(any(feature = "server", feature = "client"))]
+pub mod webhook_signature;
+#[cfg(any(feature = "server", feature = "client"))]
+pub mod webhook_encryption;
+#[cfg(feature = "server")]
+pub mod tls;
+#[cfg(feature = "server")]
+pub mod pkcs11;
+#[cfg(feature = "server")]
+pub mod kms;
+
+pub use commitment::{attestation_commitment, Commitment, CommitmentDomain, FieldEncoder};
+pub use proof_hash::ProofHash;
+pub use signing::{AttestationSigner, SigningBackend, TrustedKeys};
+pub use threshold::{PartialSignature, ThresholdPolicy, ThresholdSignature};
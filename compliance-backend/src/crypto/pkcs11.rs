@@ -0,0 +1,142 @@
+//! PKCS#11 signing backend for hardware security modules
+//!
+//! The vendor's PKCS#11 module is loaded at startup and a session is opened
+//! on the token with the configured label, logged in as the user. Keys are
+//! found by label: the Ed25519 private key never leaves the token, and the
+//! public key object of the same label provides the verifying key. A session
+//! the token drops, for instance when the HSM restarts, is reopened once
+//! before a signature fails.
+
+use super::signing::SigningBackend;
+use crate::{ComplianceError, Result};
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+use ed25519_dalek::VerifyingKey;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Ed25519 key held in a PKCS#11 token
+pub struct Pkcs11Backend {
+    context: Pkcs11,
+    slot: Slot,
+    pin: String,
+    key_label: String,
+    /// Logged-in session and the private key handle found in it
+    session: Mutex<Option<(Session, ObjectHandle)>>,
+}
+
+impl Pkcs11Backend {
+    /// Load the module and log in to the token with `token_label`
+    pub fn open(module_path: &Path, token_label: &str, key_label: &str, pin: &str) -> Result<Self> {
+        let context = Pkcs11::new(module_path)
+            .map_err(|e| pkcs11_error(format!("failed to load {}: {}", module_path.display(), e)))?;
+        context
+            .initialize(CInitializeArgs::OsThreads)
+            .map_err(|e| pkcs11_error(format!("failed to initialize the module: {}", e)))?;
+        let slot = context
+            .get_slots_with_token()
+            .map_err(|e| pkcs11_error(e.to_string()))?
+            .into_iter()
+            .find(|slot| {
+                context
+                    .get_token_info(*slot)
+                    .is_ok_and(|info| info.label().trim_end() == token_label)
+            })
+            .ok_or_else(|| pkcs11_error(format!("no token labelled {}", token_label)))?;
+        
+        let backend = Self {
+            context,
+            slot,
+            pin: pin.to_string(),
+            key_label: key_label.to_string(),
+            session: Mutex::new(None),
+        };
+        // Log in now so a wrong PIN or missing key fails at startup
+        *backend.session.lock().expect("PKCS#11 session lock poisoned") = Some(backend.login()?);
+        Ok(backend)
+    }
+    
+    /// Open a session, log in and find the private key
+    fn login(&self) -> Result<(Session, ObjectHandle)> {
+        let session = self
+            .context
+            .open_ro_session(self.slot)
+            .map_err(|e| pkcs11_error(format!("failed to open a session: {}", e)))?;
+        session
+            .login(UserType::User, Some(&AuthPin::new(self.pin.clone())))
+            .map_err(|e| pkcs11_error(format!("failed to log in: {}", e)))?;
+        let key = self.find(&session, ObjectClass::PRIVATE_KEY)?;
+        Ok((session, key))
+    }
+    
+    /// The Ed25519 key object of the configured label and class
+    fn find(&self, session: &Session, class: ObjectClass) -> Result<ObjectHandle> {
+        let template = [
+            Attribute::Class(class),
+            Attribute::KeyType(KeyType::EC_EDWARDS),
+            Attribute::Label(self.key_label.as_bytes().to_vec()),
+        ];
+        let mut objects = session.find_objects(&template).map_err(|e| pkcs11_error(e.to_string()))?;
+        match objects.len() {
+            1 => Ok(objects.remove(0)),
+            0 => Err(pkcs11_error(format!("no Ed25519 {} labelled {}", class, self.key_label))),
+            n => Err(pkcs11_error(format!("{} Ed25519 {} objects labelled {}", n, class, self.key_label))),
+        }
+    }
+    
+    /// Run `f` on the logged-in session, reopening it once if `f` fails
+    fn with_session<T>(&self, f: impl Fn(&Session, ObjectHandle) -> Result<T>) -> Result<T> {
+        let mut guard = self.session.lock().expect("PKCS#11 session lock poisoned");
+        if let Some((session, key)) = guard.as_ref() {
+            match f(session, *key) {
+                Ok(value) => return Ok(value),
+                Err(e) => tracing::warn!(error = %e, "PKCS#11 operation failed, reopening the session"),
+            }
+        }
+        *guard = None;
+        let (session, key) = self.login()?;
+        let value = f(&session, key)?;
+        *guard = Some((session, key));
+        Ok(value)
+    }
+}
+
+impl SigningBackend for Pkcs11Backend {
+    fn verifying_key(&self) -> Result<VerifyingKey> {
+        self.with_session(|session, _| {
+            let public = self.find(session, ObjectClass::PUBLIC_KEY)?;
+            let attributes = session
+                .get_attributes(public, &[AttributeType::EcPoint])
+                .map_err(|e| pkcs11_error(e.to_string()))?;
+            let point = attributes
+                .into_iter()
+                .find_map(|attribute| match attribute {
+                    Attribute::EcPoint(point) => Some(point),
+                    _ => None,
+                })
+                .ok_or_else(|| pkcs11_error("public key has no EC point"))?;
+            // Tokens return the point either DER-wrapped in an OCTET STRING or raw
+            let key: [u8; 32] = match point.as_slice() {
+                [0x04, 0x20, key @ ..] if key.len() == 32 => key.try_into().expect("length checked"),
+                key => key.try_into().map_err(|_| pkcs11_error("public key is not an Ed25519 point"))?,
+            };
+            VerifyingKey::from_bytes(&key).map_err(|e| pkcs11_error(format!("invalid public key: {}", e)))
+        })
+    }
+    
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        self.with_session(|session, key| {
+            session
+                .sign(&Mechanism::Eddsa, key, message)
+                .map_err(|e| pkcs11_error(format!("signing failed: {}", e)))
+        })
+    }
+}
+
+fn pkcs11_error(message: impl Into<String>) -> ComplianceError {
+    ComplianceError::crypto(format!("PKCS#11: {}", message.into()))
+}
@@ -0,0 +1,113 @@
+//! Fixed-size digest identifying a compliance proof
+
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use subtle::ConstantTimeEq;
+
+/// Length of a proof hash in bytes
+pub const PROOF_HASH_LENGTH: usize = 32;
+
+/// BLAKE3 digest of a proof
+///
+/// Serialized as lowercase hex. Parsing accepts hex or base58 and rejects
+/// wrong lengths, invalid characters, and the all-zero placeholder.
+/// Equality is constant-time.
+#[derive(Clone, Copy)]
+pub struct ProofHash([u8; PROOF_HASH_LENGTH]);
+
+impl ProofHash {
+    /// Hash proof bytes
+    pub fn of(proof: &[u8]) -> Self {
+        Self(*blake3::hash(proof).as_bytes())
+    }
+    
+    /// Wrap raw digest bytes
+    pub fn from_bytes(bytes: [u8; PROOF_HASH_LENGTH]) -> Result<Self> {
+        if bytes == [0; PROOF_HASH_LENGTH] {
+            return Err(invalid("must not be all zeros"));
+        }
+        Ok(Self(bytes))
+    }
+    
+    /// Parse a hex (64 characters) or base58 encoded digest
+    pub fn parse(encoded: &str) -> Result<Self> {
+        let bytes = if encoded.len() == PROOF_HASH_LENGTH * 2 {
+            hex::decode(encoded).map_err(|e| invalid(format!("invalid hex: {}", e)))?
+        } else {
+            bs58::decode(encoded)
+                .into_vec()
+                .map_err(|e| invalid(format!("invalid base58: {}", e)))?
+        };
+        let bytes: [u8; PROOF_HASH_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| invalid(format!("must be {} bytes", PROOF_HASH_LENGTH)))?;
+        Self::from_bytes(bytes)
+    }
+    
+    /// Raw digest bytes
+    pub fn as_bytes(&self) -> &[u8; PROOF_HASH_LENGTH] {
+        &self.0
+    }
+    
+    /// Lowercase hex encoding
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+    
+    /// Base58 encoding
+    pub fn to_base58(&self) -> String {
+        bs58::encode(self.0).into_string()
+    }
+    
+    /// Check that proof bytes hash to this digest
+    pub fn matches(&self, proof: &[u8]) -> bool {
+        *self == Self::of(proof)
+    }
+}
+
+impl PartialEq for ProofHash {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for ProofHash {}
+
+impl fmt::Display for ProofHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for ProofHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ProofHash({})", self.to_hex())
+    }
+}
+
+impl FromStr for ProofHash {
+    type Err = ComplianceError;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for ProofHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProofHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::parse(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::validation("proof_hash", reason)
+}
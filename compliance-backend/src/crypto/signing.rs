@@ -0,0 +1,221 @@
+//! Ed25519 signing of attestation artifacts
+//!
+//! The private key is held by a [`SigningBackend`]: in process memory for
+//! development, or in a PKCS#11 token or cloud KMS so that it never enters
+//! the process at all.
+
+use crate::{ComplianceError, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Length of an Ed25519 signature in bytes
+pub const SIGNATURE_LENGTH: usize = ed25519_dalek::SIGNATURE_LENGTH;
+
+/// Holder of an Ed25519 private key that signs on request
+///
+/// Backends may block while a token or remote service answers.
+pub trait SigningBackend: Send + Sync {
+    /// Public key of the held private key
+    fn verifying_key(&self) -> Result<VerifyingKey>;
+    
+    /// Sign a message with the held private key
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Private key held in process memory
+pub struct SoftwareKey(SigningKey);
+
+impl SigningBackend for SoftwareKey {
+    fn verifying_key(&self) -> Result<VerifyingKey> {
+        Ok(self.0.verifying_key())
+    }
+    
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.0.sign(message).to_bytes().to_vec())
+    }
+}
+
+/// Signer for attestation artifacts such as proof envelopes
+pub struct AttestationSigner {
+    key_id: String,
+    /// Public key read from the backend when the signer was created
+    verifying_key: VerifyingKey,
+    backend: Box<dyn SigningBackend>,
+}
+
+impl AttestationSigner {
+    /// Create a signer over a backend holding the private key
+    pub fn from_backend(key_id: impl Into<String>, backend: Box<dyn SigningBackend>) -> Result<Self> {
+        Ok(Self {
+            key_id: key_id.into(),
+            verifying_key: backend.verifying_key()?,
+            backend,
+        })
+    }
+    
+    /// Create a signer from a hex-encoded 32-byte seed
+    pub fn from_hex_seed(key_id: impl Into<String>, seed: &str) -> Result<Self> {
+        let bytes: [u8; 32] = hex::decode(seed)
+            .map_err(|e| ComplianceError::crypto(format!("invalid signing key seed: {}", e)))?
+            .try_into()
+            .map_err(|_| ComplianceError::crypto("signing key seed must be 32 bytes"))?;
+        
+        Self::from_backend(key_id, Box::new(SoftwareKey(SigningKey::from_bytes(&bytes))))
+    }
+    
+    /// Generate a random signer
+    pub fn generate(key_id: impl Into<String>) -> Self {
+        let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+        Self {
+            key_id: key_id.into(),
+            verifying_key: signing_key.verifying_key(),
+            backend: Box::new(SoftwareKey(signing_key)),
+        }
+    }
+    
+    /// Identifier of the signing key
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+    
+    /// Public key used to verify this signer's signatures
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.verifying_key
+    }
+    
+    /// Sign a message
+    ///
+    /// The signature is checked against the signer's public key before it
+    /// is handed out, so a backend signing with the wrong key, or returning
+    /// garbage, fails here rather than at every verifier.
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let signature = self.backend.sign(message)?;
+        let parsed = Signature::from_slice(&signature)
+            .map_err(|e| ComplianceError::crypto(format!("signing backend returned a malformed signature: {}", e)))?;
+        self.verifying_key
+            .verify_strict(message, &parsed)
+            .map_err(|_| ComplianceError::crypto("signing backend returned a signature that does not verify"))?;
+        Ok(signature)
+    }
+    
+    /// Sign claims as a compact EdDSA JWT with the key id in its header
+    pub fn sign_jwt<T: Serialize>(&self, claims: &T) -> Result<String> {
+        let header = serde_json::json!({ "alg": "EdDSA", "typ": "JWT", "kid": self.key_id });
+        let claims = serde_json::to_vec(claims).map_err(|e| ComplianceError::internal(e.to_string()))?;
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims)
+        );
+        let signature = self.sign(signing_input.as_bytes())?;
+        Ok(format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature)))
+    }
+}
+
+#[cfg(feature = "server")]
+impl AttestationSigner {
+    /// Create the signer the security configuration selects
+    ///
+    /// With the software backend and no seed configured the key is ephemeral.
+    pub fn from_config(security: &crate::config::SecurityConfig) -> Result<Self> {
+        use crate::config::SigningBackendConfig;
+        
+        let key_id = security.signing_key_id.clone();
+        match &security.signing_backend {
+            SigningBackendConfig::Software => match &security.signing_key_seed {
+                Some(seed) => Self::from_hex_seed(key_id, seed),
+                None => Ok(Self::generate(key_id)),
+            },
+            SigningBackendConfig::Pkcs11 {
+                module_path,
+                token_label,
+                key_label,
+                pin,
+            } => {
+                let backend = super::pkcs11::Pkcs11Backend::open(module_path, token_label, key_label, pin)?;
+                Self::from_backend(key_id, Box::new(backend))
+            }
+            SigningBackendConfig::AwsKms { key_id: kms_key_id, region } => {
+                Self::from_backend(key_id, Box::new(super::kms::AwsKmsBackend::new(kms_key_id, region)))
+            }
+            SigningBackendConfig::GcpKms {
+                key_version,
+                access_token,
+            } => Self::from_backend(
+                key_id,
+                Box::new(super::kms::GcpKmsBackend::new(key_version, access_token.clone())),
+            ),
+        }
+    }
+}
+
+/// Set of public keys trusted to sign attestation artifacts, indexed by key id
+#[derive(Debug, Clone, Default)]
+pub struct TrustedKeys {
+    keys: HashMap<String, VerifyingKey>,
+}
+
+impl TrustedKeys {
+    /// Create an empty key set
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Trust a public key
+    pub fn insert(&mut self, key_id: impl Into<String>, key: VerifyingKey) {
+        self.keys.insert(key_id.into(), key);
+    }
+    
+    /// Trust a hex-encoded public key
+    pub fn insert_hex(&mut self, key_id: impl Into<String>, key: &str) -> Result<()> {
+        let bytes: [u8; 32] = hex::decode(key)
+            .map_err(|e| ComplianceError::crypto(format!("invalid public key: {}", e)))?
+            .try_into()
+            .map_err(|_| ComplianceError::crypto("public key must be 32 bytes"))?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|e| ComplianceError::crypto(format!("invalid public key: {}", e)))?;
+        self.insert(key_id, key);
+        Ok(())
+    }
+    
+    /// Verify a signature made by a trusted key
+    pub fn verify(&self, key_id: &str, message: &[u8], signature: &[u8]) -> Result<()> {
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| ComplianceError::crypto(format!("untrusted signing key: {}", key_id)))?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|e| ComplianceError::crypto(format!("malformed signature: {}", e)))?;
+        
+        key.verify_strict(message, &signature)
+            .map_err(|_| ComplianceError::crypto("signature verification failed"))
+    }
+    
+    /// Verify a compact EdDSA JWT signed by a trusted key and decode its claims
+    ///
+    /// Only the signature is checked; expiry and audience are up to the caller.
+    pub fn verify_jwt<T: DeserializeOwned>(&self, token: &str) -> Result<T> {
+        let malformed = |reason: &str| ComplianceError::crypto(format!("malformed JWT: {}", reason));
+        let (signing_input, signature) = token.trim().rsplit_once('.').ok_or_else(|| malformed("no signature"))?;
+        let (header, claims) = signing_input.split_once('.').ok_or_else(|| malformed("no claims"))?;
+        
+        let header: serde_json::Value = URL_SAFE_NO_PAD
+            .decode(header)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| malformed("invalid header"))?;
+        if header["alg"] != "EdDSA" {
+            return Err(malformed("algorithm is not EdDSA"));
+        }
+        let key_id = header["kid"].as_str().ok_or_else(|| malformed("no key id"))?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| malformed("invalid signature encoding"))?;
+        self.verify(key_id, signing_input.as_bytes(), &signature)?;
+        
+        let claims = URL_SAFE_NO_PAD.decode(claims).map_err(|_| malformed("invalid claims encoding"))?;
+        serde_json::from_slice(&claims).map_err(|e| malformed(&e.to_string()))
+    }
+}
@@ -0,0 +1,127 @@
+//! t-of-n multi-signatures over attestation artifacts
+//!
+//! A threshold policy names the keys allowed to sign and how many of them
+//! must. Each signer contributes an ordinary Ed25519 signature over the same
+//! message; the combined signature is the set of partial signatures from
+//! distinct policy signers, and verifies when at least the threshold of them
+//! verify against trusted keys.
+
+use super::signing::TrustedKeys;
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Keys allowed to sign, and how many of them must
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdPolicy {
+    /// Signatures required
+    pub threshold: usize,
+    /// Key ids of the signers
+    pub signers: Vec<String>,
+}
+
+impl ThresholdPolicy {
+    /// Check that the policy can be satisfied
+    pub fn validate(&self) -> Result<()> {
+        if self.threshold == 0 || self.threshold > self.signers.len() {
+            return Err(ComplianceError::validation(
+                "threshold",
+                format!("must be between 1 and the {} signers", self.signers.len()),
+            ));
+        }
+        let mut seen = HashSet::new();
+        for (i, signer) in self.signers.iter().enumerate() {
+            if signer.trim().is_empty() {
+                return Err(ComplianceError::validation(format!("signers[{}]", i), "must not be empty"));
+            }
+            if !seen.insert(signer) {
+                return Err(ComplianceError::validation(format!("signers[{}]", i), "is listed twice"));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Whether a key may sign under the policy
+    pub fn allows(&self, key_id: &str) -> bool {
+        self.signers.iter().any(|signer| signer == key_id)
+    }
+}
+
+/// One signer's contribution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialSignature {
+    pub key_id: String,
+    /// Hex-encoded Ed25519 signature over the message
+    pub signature: String,
+}
+
+impl PartialSignature {
+    /// Check the signature against a trusted key
+    pub fn verify(&self, trusted_keys: &TrustedKeys, message: &[u8]) -> Result<()> {
+        let signature = hex::decode(&self.signature)
+            .map_err(|e| ComplianceError::crypto(format!("malformed signature: {}", e)))?;
+        trusted_keys.verify(&self.key_id, message, &signature)
+    }
+}
+
+/// Partial signatures combined to meet a threshold
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdSignature {
+    /// Signatures the policy required when combined
+    pub threshold: usize,
+    /// Partial signatures of distinct signers, ordered by key id
+    pub signatures: Vec<PartialSignature>,
+}
+
+impl ThresholdSignature {
+    /// Combine the first partial signatures of distinct policy signers
+    ///
+    /// The partials are expected to have been verified as they were
+    /// collected; fails when they fall short of the threshold.
+    pub fn combine(policy: &ThresholdPolicy, partials: &[PartialSignature]) -> Result<Self> {
+        let mut seen = HashSet::new();
+        let mut signatures: Vec<PartialSignature> = partials
+            .iter()
+            .filter(|partial| policy.allows(&partial.key_id) && seen.insert(partial.key_id.as_str()))
+            .take(policy.threshold)
+            .cloned()
+            .collect();
+        if signatures.len() < policy.threshold {
+            return Err(ComplianceError::crypto(format!(
+                "{} of {} required signatures collected",
+                signatures.len(),
+                policy.threshold
+            )));
+        }
+        signatures.sort_by(|a, b| a.key_id.cmp(&b.key_id));
+        Ok(Self {
+            threshold: policy.threshold,
+            signatures,
+        })
+    }
+    
+    /// Verify that enough distinct policy signers signed the message
+    ///
+    /// The threshold is taken from the verifier's policy, never from the
+    /// signature itself.
+    pub fn verify(&self, policy: &ThresholdPolicy, trusted_keys: &TrustedKeys, message: &[u8]) -> Result<()> {
+        let mut signers = HashSet::new();
+        for partial in &self.signatures {
+            if !policy.allows(&partial.key_id) {
+                return Err(ComplianceError::crypto(format!("{} is not a signer of the policy", partial.key_id)));
+            }
+            if !signers.insert(partial.key_id.as_str()) {
+                return Err(ComplianceError::crypto(format!("{} signed more than once", partial.key_id)));
+            }
+            partial.verify(trusted_keys, message)?;
+        }
+        if signers.len() < policy.threshold {
+            return Err(ComplianceError::crypto(format!(
+                "{} of {} required signatures",
+                signers.len(),
+                policy.threshold
+            )));
+        }
+        Ok(())
+    }
+}
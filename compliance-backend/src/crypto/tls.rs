@@ -0,0 +1,198 @@
+//! Mutual TLS client identities for outbound webhook delivery
+
+use crate::config::WebhookEgressConfig;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Minimum TLS protocol version accepted by a webhook endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MinTlsVersion {
+    #[serde(rename = "1.2")]
+    Tls12,
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+/// A client certificate and key presented to a webhook endpoint
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ClientIdentity {
+    /// PEM-encoded certificate chain, leaf first
+    pub certificate_pem: String,
+    
+    /// PEM-encoded private key
+    #[serde(skip_serializing)]
+    pub private_key_pem: String,
+    
+    /// Time from which this identity should be presented
+    pub not_before: DateTime<Utc>,
+}
+
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientIdentity")
+            .field("not_before", &self.not_before)
+            .field("private_key_pem", &"[redacted]")
+            .finish_non_exhaustive()
+    }
+}
+
+/// TLS settings attached to a webhook endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointTlsConfig {
+    /// Client identity currently presented
+    pub identity: Option<ClientIdentity>,
+    
+    /// Identity staged to replace the current one once its `not_before` passes
+    pub next_identity: Option<ClientIdentity>,
+    
+    /// PEM-encoded CA certificates trusted for this endpoint, in addition to system roots
+    pub ca_bundle_pem: Option<String>,
+    
+    pub min_tls_version: MinTlsVersion,
+    
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EndpointTlsConfig {
+    /// Promote the staged identity if it has become active, returning whether it was promoted
+    fn promote_due(&mut self, now: DateTime<Utc>) -> bool {
+        if self.next_identity.as_ref().is_some_and(|next| next.not_before <= now) {
+            self.identity = self.next_identity.take();
+            self.updated_at = now;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Per-endpoint TLS configuration and the HTTP clients built from it
+pub struct WebhookTlsStore {
+    egress: WebhookEgressConfig,
+    timeout: Duration,
+    configs: RwLock<HashMap<Uuid, EndpointTlsConfig>>,
+    clients: RwLock<HashMap<Uuid, reqwest::Client>>,
+}
+
+impl WebhookTlsStore {
+    /// Create a store applying the given egress settings to every client
+    pub fn new(egress: WebhookEgressConfig, timeout: Duration) -> Self {
+        Self {
+            egress,
+            timeout,
+            configs: RwLock::new(HashMap::new()),
+            clients: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Set the TLS configuration of an endpoint
+    ///
+    /// The configuration is validated by building a client from it before
+    /// it is stored.
+    pub async fn set(&self, endpoint_id: Uuid, config: EndpointTlsConfig) -> Result<()> {
+        let client = build_client(&config, &self.egress, self.timeout)?;
+        self.configs.write().await.insert(endpoint_id, config);
+        self.clients.write().await.insert(endpoint_id, client);
+        Ok(())
+    }
+    
+    /// Get the TLS configuration of an endpoint
+    pub async fn get(&self, endpoint_id: Uuid) -> Option<EndpointTlsConfig> {
+        self.configs.read().await.get(&endpoint_id).cloned()
+    }
+    
+    /// Remove an endpoint's TLS configuration
+    pub async fn remove(&self, endpoint_id: Uuid) {
+        self.configs.write().await.remove(&endpoint_id);
+        self.clients.write().await.remove(&endpoint_id);
+    }
+    
+    /// Stage a new client identity for rotation
+    ///
+    /// The current identity keeps being presented until `not_before`, so the
+    /// receiving bank can trust the new certificate ahead of the switch.
+    pub async fn stage_rotation(&self, endpoint_id: Uuid, identity: ClientIdentity) -> Result<EndpointTlsConfig> {
+        let mut configs = self.configs.write().await;
+        let config = configs
+            .get_mut(&endpoint_id)
+            .ok_or_else(|| ComplianceError::validation("endpoint_id", "endpoint has no TLS configuration"))?;
+        
+        let mut candidate = config.clone();
+        candidate.identity = Some(identity.clone());
+        build_client(&candidate, &self.egress, self.timeout)?;
+        
+        config.next_identity = Some(identity);
+        config.updated_at = Utc::now();
+        Ok(config.clone())
+    }
+    
+    /// Get the HTTP client for delivering to an endpoint
+    ///
+    /// Endpoints without TLS configuration get a client with egress settings only.
+    pub async fn client_for(&self, endpoint_id: Uuid) -> Result<reqwest::Client> {
+        self.promote_due(Utc::now()).await;
+        if let Some(client) = self.clients.read().await.get(&endpoint_id) {
+            return Ok(client.clone());
+        }
+        egress_builder(&self.egress, self.timeout)
+            .build()
+            .map_err(|e| ComplianceError::internal(format!("failed to build webhook client: {}", e)))
+    }
+    
+    /// Switch endpoints to their staged identities once due
+    pub async fn promote_due(&self, now: DateTime<Utc>) {
+        let mut configs = self.configs.write().await;
+        for (endpoint_id, config) in configs.iter_mut() {
+            if !config.promote_due(now) {
+                continue;
+            }
+            match build_client(config, &self.egress, self.timeout) {
+                Ok(client) => {
+                    self.clients.write().await.insert(*endpoint_id, client);
+                    tracing::info!(endpoint_id = %endpoint_id, "webhook client certificate rotated");
+                }
+                Err(e) => tracing::error!(endpoint_id = %endpoint_id, error = %e, "failed to rotate webhook client certificate"),
+            }
+        }
+    }
+}
+
+fn egress_builder(egress: &WebhookEgressConfig, timeout: Duration) -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder().use_rustls_tls().timeout(timeout).local_address(egress.local_address);
+    if let Some(proxy) = egress.proxy.as_ref().and_then(|url| reqwest::Proxy::all(url).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    builder
+}
+
+/// Build an HTTP client presenting an endpoint's client identity
+pub fn build_client(config: &EndpointTlsConfig, egress: &WebhookEgressConfig, timeout: Duration) -> Result<reqwest::Client> {
+    let mut builder = egress_builder(egress, timeout).min_tls_version(match config.min_tls_version {
+        MinTlsVersion::Tls12 => reqwest::tls::Version::TLS_1_2,
+        MinTlsVersion::Tls13 => reqwest::tls::Version::TLS_1_3,
+    });
+    
+    if let Some(identity) = &config.identity {
+        let pem = format!("{}\n{}", identity.certificate_pem.trim(), identity.private_key_pem.trim());
+        let identity = reqwest::Identity::from_pem(pem.as_bytes())
+            .map_err(|e| ComplianceError::validation("identity", format!("invalid client certificate or key: {}", e)))?;
+        builder = builder.identity(identity);
+    }
+    
+    if let Some(bundle) = &config.ca_bundle_pem {
+        let certificates = reqwest::Certificate::from_pem_bundle(bundle.as_bytes())
+            .map_err(|e| ComplianceError::validation("ca_bundle_pem", format!("invalid CA bundle: {}", e)))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    
+    builder
+        .build()
+        .map_err(|e| ComplianceError::internal(format!("failed to build webhook client: {}", e)))
+}
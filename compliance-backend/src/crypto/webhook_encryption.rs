@@ -0,0 +1,161 @@
+//! JWE encryption of outbound webhook deliveries
+//!
+//! An endpoint with a registered X25519 public key receives each delivery as
+//! a JWE in compact serialization (RFC 7516) rather than plain JSON, so PII
+//! in the payload is unreadable to logging and proxies on the way to the
+//! receiver. Keys are agreed with `ECDH-ES` over a fresh ephemeral key per
+//! delivery (RFC 8037) and the content is encrypted with `A256GCM`.
+//!
+//! The HMAC signature is computed over the JWE itself, so receivers check it
+//! before decrypting anything.
+
+use crate::types::WebhookEncryptionKey;
+use crate::{ComplianceError, Result};
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce, Tag};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+/// Content type of encrypted deliveries
+pub const JWE_CONTENT_TYPE: &str = "application/jose";
+
+/// Key agreement algorithm
+const ALG: &str = "ECDH-ES";
+
+/// Content encryption algorithm
+const ENC: &str = "A256GCM";
+
+/// Ephemeral public key in a JWE header
+#[derive(Debug, Serialize, Deserialize)]
+struct EphemeralJwk {
+    kty: String,
+    crv: String,
+    x: String,
+}
+
+/// Protected header of an encrypted delivery
+#[derive(Debug, Serialize, Deserialize)]
+struct JweHeader {
+    alg: String,
+    enc: String,
+    kid: String,
+    cty: String,
+    epk: EphemeralJwk,
+}
+
+/// Check that a key is usable for encrypting deliveries
+///
+/// Rejects keys that are not 32 bytes and low-order points, which would
+/// make the agreed key independent of the ephemeral secret.
+pub fn check_key(key: &WebhookEncryptionKey) -> Result<()> {
+    if key.kid.trim().is_empty() {
+        return Err(ComplianceError::validation("kid", "must not be empty"));
+    }
+    let public = public_key(key).map_err(|_| ComplianceError::validation("x", "must be a base64url X25519 key"))?;
+    let probe = EphemeralSecret::random_from_rng(rand::rngs::OsRng);
+    if !probe.diffie_hellman(&public).was_contributory() {
+        return Err(ComplianceError::validation("x", "must not be a low-order point"));
+    }
+    Ok(())
+}
+
+/// Encrypt a delivery body to a key, returning the compact JWE
+pub fn encrypt(key: &WebhookEncryptionKey, plaintext: &[u8]) -> Result<String> {
+    let recipient = public_key(key)?;
+    let ephemeral = EphemeralSecret::random_from_rng(rand::rngs::OsRng);
+    let epk = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&recipient);
+    if !shared.was_contributory() {
+        return Err(ComplianceError::crypto("webhook encryption key is a low-order point"));
+    }
+    
+    let header = JweHeader {
+        alg: ALG.to_string(),
+        enc: ENC.to_string(),
+        kid: key.kid.clone(),
+        cty: "json".to_string(),
+        epk: EphemeralJwk {
+            kty: "OKP".to_string(),
+            crv: "X25519".to_string(),
+            x: URL_SAFE_NO_PAD.encode(epk.as_bytes()),
+        },
+    };
+    let protected = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?);
+    let iv: [u8; 12] = rand::random();
+    let mut ciphertext = plaintext.to_vec();
+    let tag = Aes256Gcm::new(&content_key(shared.as_bytes()).into())
+        .encrypt_in_place_detached(Nonce::from_slice(&iv), protected.as_bytes(), &mut ciphertext)
+        .map_err(|_| ComplianceError::crypto("failed to encrypt webhook delivery"))?;
+    
+    // Direct key agreement leaves the encrypted key empty
+    Ok(format!(
+        "{}..{}.{}.{}",
+        protected,
+        URL_SAFE_NO_PAD.encode(iv),
+        URL_SAFE_NO_PAD.encode(&ciphertext),
+        URL_SAFE_NO_PAD.encode(tag)
+    ))
+}
+
+/// Decrypt a delivery with the receiver's X25519 private key
+pub fn decrypt(private_key: &[u8; 32], jwe: &str) -> Result<Vec<u8>> {
+    let malformed = || ComplianceError::crypto("malformed JWE");
+    let parts: Vec<&str> = jwe.trim().split('.').collect();
+    let [protected, encrypted_key, iv, ciphertext, tag] = parts[..] else {
+        return Err(malformed());
+    };
+    if !encrypted_key.is_empty() {
+        return Err(malformed());
+    }
+    let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|_| malformed());
+    let header: JweHeader = serde_json::from_slice(&decode(protected)?).map_err(|_| malformed())?;
+    if header.alg != ALG || header.enc != ENC || header.epk.kty != "OKP" || header.epk.crv != "X25519" {
+        return Err(ComplianceError::crypto(format!("unsupported JWE algorithms {}/{}", header.alg, header.enc)));
+    }
+    
+    let epk: [u8; 32] = decode(&header.epk.x)?.try_into().map_err(|_| malformed())?;
+    let shared = StaticSecret::from(*private_key).diffie_hellman(&PublicKey::from(epk));
+    if !shared.was_contributory() {
+        return Err(malformed());
+    }
+    let iv = decode(iv)?;
+    let tag = decode(tag)?;
+    if iv.len() != 12 || tag.len() != 16 {
+        return Err(malformed());
+    }
+    let mut plaintext = decode(ciphertext)?;
+    Aes256Gcm::new(&content_key(shared.as_bytes()).into())
+        .decrypt_in_place_detached(
+            Nonce::from_slice(&iv),
+            protected.as_bytes(),
+            &mut plaintext,
+            Tag::from_slice(&tag),
+        )
+        .map_err(|_| ComplianceError::crypto("webhook delivery failed to decrypt"))?;
+    Ok(plaintext)
+}
+
+fn public_key(key: &WebhookEncryptionKey) -> Result<PublicKey> {
+    let bytes: [u8; 32] = URL_SAFE_NO_PAD
+        .decode(key.x.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ComplianceError::crypto("webhook encryption key is not a 32-byte X25519 key"))?;
+    Ok(PublicKey::from(bytes))
+}
+
+/// Concat KDF of RFC 7518 §4.6 for direct `ECDH-ES` with `A256GCM`, without party info
+fn content_key(shared_secret: &[u8]) -> [u8; 32] {
+    let mut kdf = Sha256::new();
+    kdf.update(1u32.to_be_bytes());
+    kdf.update(shared_secret);
+    kdf.update((ENC.len() as u32).to_be_bytes());
+    kdf.update(ENC.as_bytes());
+    kdf.update(0u32.to_be_bytes());
+    kdf.update(0u32.to_be_bytes());
+    kdf.update(256u32.to_be_bytes());
+    kdf.finalize().into()
+}
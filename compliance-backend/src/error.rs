@@ -1,31 +1,44 @@
 //! Error handling for the ZeroTrust Compliance Backend
 
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use thiserror::Error;
 
 /// Main error type for the compliance backend
 #[derive(Error, Debug)]
 pub enum ComplianceError {
+    #[cfg(feature = "server")]
     #[error("Miden client error: {0}")]
     MidenClient(#[from] miden_client::ClientError),
     
+    #[cfg(feature = "server")]
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
     
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
     
+    #[cfg(feature = "server")]
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
     
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     
+    #[cfg(feature = "server")]
     #[error("Configuration error: {0}")]
     Config(#[from] config::ConfigError),
     
+    #[cfg(feature = "server")]
+    #[error("Invalid configuration: {0}")]
+    InvalidConfiguration(#[from] crate::config::ConfigViolations),
+    
     #[error("Cryptographic error: {message}")]
     Crypto { message: String },
     
+    #[error("Secret resolution failed: {message}")]
+    SecretResolution { message: String },
+    
     #[error("KYC verification failed: {reason}")]
     KycVerificationFailed { reason: String },
     
@@ -74,6 +87,9 @@ pub enum ComplianceError {
     #[error("Validation error: {field}: {message}")]
     Validation { field: String, message: String },
     
+    #[error("Invalid request: {0}")]
+    InvalidRequest(FieldViolations),
+    
     #[error("Business client not found: {client_id}")]
     BusinessClientNotFound { client_id: String },
     
@@ -85,11 +101,223 @@ pub enum ComplianceError {
     
     #[error("Delegated proving failed: {reason}")]
     DelegatedProvingFailed { reason: String },
+    
+    #[error("Step-up session not found: {session_id}")]
+    StepUpSessionNotFound { session_id: String },
+    
+    #[error("Alert not found: {alert_id}")]
+    AlertNotFound { alert_id: String },
+    
+    #[error("Alert delivery failed: {sink}: {reason}")]
+    AlertDeliveryFailed { sink: String, reason: String },
+    
+    #[error("Watchlist entry not found: {entry_id}")]
+    WatchlistEntryNotFound { entry_id: String },
+    
+    #[error("Proof challenge rejected: {reason}")]
+    ChallengeRejected { reason: String },
+    
+    #[error("Invalid operator credentials")]
+    InvalidCredentials,
+    
+    #[error("Permission denied: {permission}")]
+    PermissionDenied { permission: String },
+    
+    #[error("Operator not found: {operator_id}")]
+    OperatorNotFound { operator_id: String },
+    
+    #[error("Approval request not found: {approval_id}")]
+    ApprovalNotFound { approval_id: String },
+    
+    #[error("Report not found: {report_id}")]
+    ReportNotFound { report_id: String },
+    
+    #[error("Provider unavailable: {provider}: {reason}")]
+    ProviderUnavailable { provider: String, reason: String },
+    
+    #[error("Proof generation queue is full, retry after {retry_after_secs}s")]
+    ProverSaturated { retry_after_secs: u64 },
+    
+    #[error("Funds declaration not found: {declaration_id}")]
+    FundsDeclarationNotFound { declaration_id: String },
+    
+    #[error("Transaction monitoring buffer is full, retry after {retry_after_secs}s")]
+    MonitoringBackpressure { retry_after_secs: u64 },
+    
+    #[error("Provider callback not found: {callback_id}")]
+    ProviderCallbackNotFound { callback_id: String },
+    
+    #[error("Invalid provider callback signature: {reason}")]
+    InvalidCallbackSignature { reason: String },
+    
+    #[error("Provider credential not found: {client_id}: {provider}")]
+    ProviderCredentialNotFound { client_id: String, provider: String },
+    
+    #[error("Compliance oracle has not published a root yet")]
+    OracleNotPublished,
+    
+    #[error("Account {account_id} is not in the published compliance set")]
+    NotInComplianceSet { account_id: String },
+    
+    #[error("Attestation registry has not published a root yet")]
+    RegistryNotPublished,
+    
+    #[error("Attestation epoch not found: {epoch}")]
+    EpochNotFound { epoch: u64 },
+    
+    #[error("Attestation {attestation_id} has not been anchored in an epoch")]
+    AttestationNotAnchored { attestation_id: String },
+    
+    #[error("Account {account_id} has not been screened")]
+    ScreeningResultNotFound { account_id: String },
+    
+    #[error("Account {account_id} has no screening match with {list} entity {entity_id}")]
+    ScreeningMatchNotFound { account_id: String, list: String, entity_id: String },
+    
+    #[error("No {resource} is configured in data residency region {region}")]
+    ResidencyUnavailable { region: String, resource: String },
+    
+    #[error("Workflow step {step} timed out after {timeout_secs}s")]
+    WorkflowStepTimedOut { step: String, timeout_secs: u64 },
+    
+    #[error("Workflow {workflow} run {run_id} failed: {reason}")]
+    WorkflowFailed { workflow: String, run_id: String, reason: String },
+    
+    #[error("Workflow run not found: {run_id}")]
+    WorkflowRunNotFound { run_id: String },
+    
+    #[error("Usage quota exceeded: {quota} {operation} operations per billing period")]
+    QuotaExceeded { operation: String, quota: u64 },
+    
+    #[error("Business client {client_id} has no sandbox")]
+    ClientSandboxNotFound { client_id: String },
+    
+    #[error("Proof verification pool is full, retry after {retry_after_secs}s")]
+    VerifierSaturated { retry_after_secs: u64 },
+    
+    #[error("Proof verification timed out after {timeout_secs}s")]
+    ProofVerificationTimedOut { timeout_secs: u64 },
+    
+    #[error("Verification session not found: {session_id}")]
+    VerificationSessionNotFound { session_id: String },
+    
+    #[error("Invalid verification session token: {reason}")]
+    InvalidSessionToken { reason: String },
+    
+    #[error("Verification session {session_id} is {status}")]
+    VerificationSessionClosed { session_id: String, status: String },
+    
+    #[error("Document quarantined: {document_type} was flagged by the malware scanner")]
+    DocumentQuarantined { document_type: String },
+    
+    #[error("Image quality of {document_type} too low ({score:.2}): {}", .feedback.join(", "))]
+    DocumentQualityInsufficient {
+        document_type: String,
+        score: f64,
+        feedback: Vec<String>,
+    },
+    
+    #[error("Account watch not found: {watch_id}")]
+    AccountWatchNotFound { watch_id: String },
+    
+    #[error("Authorization decision not found: {decision_id}")]
+    DecisionNotFound { decision_id: String },
+    
+    #[error("Offboarding job not found: {job_id}")]
+    OffboardingJobNotFound { job_id: String },
+    
+    #[error("Account offboarded: {account_id}")]
+    AccountOffboarded { account_id: String },
+    
+    #[error("Proof of {size} bytes exceeds the {limit} byte limit")]
+    ProofTooLarge { size: usize, limit: usize },
+    
+    #[error("Fraud case not found: {case_id}")]
+    FraudCaseNotFound { case_id: String },
+    
+    #[error("Attestation does not meet the required freshness: {reason}")]
+    AttestationNotFresh { reason: String },
+    
+    #[error("Presentation not found: {presentation_id}")]
+    PresentationNotFound { presentation_id: String },
+    
+    #[error("Signing request not found: {request_id}")]
+    SigningRequestNotFound { request_id: String },
+    
+    #[error("Signing request {request_id} is {status}")]
+    SigningRequestClosed { request_id: String, status: String },
+    
+    #[error("Scheduled job not found: {name}")]
+    JobNotFound { name: String },
+    
+    #[error("Request body to {endpoint} exceeds the {limit} byte limit")]
+    PayloadTooLarge { endpoint: String, limit: usize },
+    
+    #[error("{endpoint} does not accept {content_type} bodies")]
+    UnsupportedMediaType {
+        endpoint: String,
+        content_type: String,
+        accepted: Vec<String>,
+    },
 }
 
 /// Result type for the compliance backend
 pub type Result<T> = std::result::Result<T, ComplianceError>;
 
+/// A problem with one field of a request body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldViolation {
+    /// JSON pointer (RFC 6901) to the offending field, e.g. `/transactions/3/amount`
+    pub pointer: String,
+    pub message: String,
+}
+
+impl FieldViolation {
+    /// Create a violation of a field named with dots and indexes, e.g. `transactions[3].amount`
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            pointer: json_pointer(field),
+            message: message.into(),
+        }
+    }
+}
+
+/// Every problem found with a request body
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldViolations(pub Vec<FieldViolation>);
+
+impl fmt::Display for FieldViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}: {}", violation.pointer, violation.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Convert a field name with dots and indexes to a JSON pointer
+///
+/// Names that already are pointers are returned unchanged, and the empty
+/// name points at the whole document.
+pub fn json_pointer(field: &str) -> String {
+    if field.is_empty() || field.starts_with('/') {
+        return field.to_string();
+    }
+    field
+        .split('.')
+        .flat_map(|part| part.split('['))
+        .map(|token| token.strip_suffix(']').unwrap_or(token))
+        .filter(|token| !token.is_empty())
+        .fold(String::new(), |mut pointer, token| {
+            pointer.push('/');
+            pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+            pointer
+        })
+}
+
 impl ComplianceError {
     /// Create a new crypto error
     pub fn crypto(message: impl Into<String>) -> Self {
@@ -113,6 +341,15 @@ impl ComplianceError {
         }
     }
     
+    /// Field-level problems behind a validation error
+    pub fn violations(&self) -> Vec<FieldViolation> {
+        match self {
+            Self::Validation { field, message } => vec![FieldViolation::new(field, message.clone())],
+            Self::InvalidRequest(violations) => violations.0.clone(),
+            _ => vec![],
+        }
+    }
+    
     /// Check if the error is a client error (4xx)
     pub fn is_client_error(&self) -> bool {
         matches!(
@@ -123,8 +360,52 @@ impl ComplianceError {
                 | Self::RateLimitExceeded
                 | Self::InvalidApiKey
                 | Self::Validation { .. }
+                | Self::InvalidRequest(_)
                 | Self::BusinessClientNotFound { .. }
                 | Self::CompliancePolicyViolation { .. }
+                | Self::StepUpSessionNotFound { .. }
+                | Self::AlertNotFound { .. }
+                | Self::WatchlistEntryNotFound { .. }
+                | Self::ChallengeRejected { .. }
+                | Self::InvalidCredentials
+                | Self::PermissionDenied { .. }
+                | Self::OperatorNotFound { .. }
+                | Self::ApprovalNotFound { .. }
+                | Self::ReportNotFound { .. }
+                | Self::ProverSaturated { .. }
+                | Self::FundsDeclarationNotFound { .. }
+                | Self::MonitoringBackpressure { .. }
+                | Self::ProviderCallbackNotFound { .. }
+                | Self::InvalidCallbackSignature { .. }
+                | Self::ProviderCredentialNotFound { .. }
+                | Self::NotInComplianceSet { .. }
+                | Self::EpochNotFound { .. }
+                | Self::AttestationNotAnchored { .. }
+                | Self::ScreeningResultNotFound { .. }
+                | Self::ScreeningMatchNotFound { .. }
+                | Self::WorkflowFailed { .. }
+                | Self::WorkflowRunNotFound { .. }
+                | Self::QuotaExceeded { .. }
+                | Self::ClientSandboxNotFound { .. }
+                | Self::VerifierSaturated { .. }
+                | Self::VerificationSessionNotFound { .. }
+                | Self::InvalidSessionToken { .. }
+                | Self::VerificationSessionClosed { .. }
+                | Self::DocumentQuarantined { .. }
+                | Self::DocumentQualityInsufficient { .. }
+                | Self::AccountWatchNotFound { .. }
+                | Self::DecisionNotFound { .. }
+                | Self::OffboardingJobNotFound { .. }
+                | Self::AccountOffboarded { .. }
+                | Self::ProofTooLarge { .. }
+                | Self::FraudCaseNotFound { .. }
+                | Self::AttestationNotFresh { .. }
+                | Self::PresentationNotFound { .. }
+                | Self::SigningRequestNotFound { .. }
+                | Self::SigningRequestClosed { .. }
+                | Self::JobNotFound { .. }
+                | Self::PayloadTooLarge { .. }
+                | Self::UnsupportedMediaType { .. }
         )
     }
     
@@ -133,15 +414,130 @@ impl ComplianceError {
         !self.is_client_error()
     }
     
+    /// Stable machine-readable code returned with API errors
+    ///
+    /// Server errors other than provider outages share `internal_error` so
+    /// clients never depend on internal failure details.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AccountNotFound { .. } => "account_not_found",
+            Self::InsufficientPrivileges { .. } => "insufficient_privileges",
+            Self::InvalidProof { .. } => "invalid_proof",
+            Self::RateLimitExceeded => "rate_limit_exceeded",
+            Self::InvalidApiKey => "invalid_api_key",
+            Self::Validation { .. } | Self::InvalidRequest(_) => "validation_error",
+            Self::BusinessClientNotFound { .. } => "business_client_not_found",
+            Self::CompliancePolicyViolation { .. } => "compliance_policy_violation",
+            Self::StepUpSessionNotFound { .. } => "step_up_session_not_found",
+            Self::AlertNotFound { .. } => "alert_not_found",
+            Self::WatchlistEntryNotFound { .. } => "watchlist_entry_not_found",
+            Self::ChallengeRejected { .. } => "challenge_rejected",
+            Self::InvalidCredentials => "invalid_credentials",
+            Self::PermissionDenied { .. } => "permission_denied",
+            Self::OperatorNotFound { .. } => "operator_not_found",
+            Self::ApprovalNotFound { .. } => "approval_not_found",
+            Self::ReportNotFound { .. } => "report_not_found",
+            Self::ProverSaturated { .. } => "prover_saturated",
+            Self::FundsDeclarationNotFound { .. } => "funds_declaration_not_found",
+            Self::ProviderUnavailable { .. } => "provider_unavailable",
+            Self::MonitoringBackpressure { .. } => "monitoring_backpressure",
+            Self::ProviderCallbackNotFound { .. } => "provider_callback_not_found",
+            Self::InvalidCallbackSignature { .. } => "invalid_callback_signature",
+            Self::ProviderCredentialNotFound { .. } => "provider_credential_not_found",
+            Self::OracleNotPublished => "oracle_not_published",
+            Self::NotInComplianceSet { .. } => "not_in_compliance_set",
+            Self::RegistryNotPublished => "registry_not_published",
+            Self::EpochNotFound { .. } => "epoch_not_found",
+            Self::AttestationNotAnchored { .. } => "attestation_not_anchored",
+            Self::ScreeningResultNotFound { .. } => "screening_result_not_found",
+            Self::ScreeningMatchNotFound { .. } => "screening_match_not_found",
+            Self::ResidencyUnavailable { .. } => "residency_unavailable",
+            Self::WorkflowStepTimedOut { .. } => "workflow_step_timed_out",
+            Self::WorkflowFailed { .. } => "workflow_failed",
+            Self::WorkflowRunNotFound { .. } => "workflow_run_not_found",
+            Self::QuotaExceeded { .. } => "quota_exceeded",
+            Self::ClientSandboxNotFound { .. } => "client_sandbox_not_found",
+            Self::VerifierSaturated { .. } => "verifier_saturated",
+            Self::ProofVerificationTimedOut { .. } => "proof_verification_timed_out",
+            Self::VerificationSessionNotFound { .. } => "verification_session_not_found",
+            Self::InvalidSessionToken { .. } => "invalid_session_token",
+            Self::VerificationSessionClosed { .. } => "verification_session_closed",
+            Self::DocumentQuarantined { .. } => "document_quarantined",
+            Self::DocumentQualityInsufficient { .. } => "document_quality_insufficient",
+            Self::AccountWatchNotFound { .. } => "account_watch_not_found",
+            Self::DecisionNotFound { .. } => "decision_not_found",
+            Self::OffboardingJobNotFound { .. } => "offboarding_job_not_found",
+            Self::AccountOffboarded { .. } => "account_offboarded",
+            Self::ProofTooLarge { .. } => "proof_too_large",
+            Self::FraudCaseNotFound { .. } => "fraud_case_not_found",
+            Self::AttestationNotFresh { .. } => "attestation_not_fresh",
+            Self::PresentationNotFound { .. } => "presentation_not_found",
+            Self::SigningRequestNotFound { .. } => "signing_request_not_found",
+            Self::SigningRequestClosed { .. } => "signing_request_closed",
+            Self::JobNotFound { .. } => "job_not_found",
+            Self::PayloadTooLarge { .. } => "payload_too_large",
+            Self::UnsupportedMediaType { .. } => "unsupported_media_type",
+            _ => "internal_error",
+        }
+    }
+    
     /// Get the HTTP status code for this error
     pub fn status_code(&self) -> u16 {
         match self {
-            Self::AccountNotFound { .. } | Self::BusinessClientNotFound { .. } => 404,
-            Self::InsufficientPrivileges { .. } | Self::InvalidApiKey => 401,
-            Self::CompliancePolicyViolation { .. } => 403,
-            Self::RateLimitExceeded => 429,
-            Self::Validation { .. } => 400,
-            Self::InvalidProof { .. } => 400,
+            Self::AccountNotFound { .. }
+            | Self::BusinessClientNotFound { .. }
+            | Self::StepUpSessionNotFound { .. }
+            | Self::AlertNotFound { .. }
+            | Self::WatchlistEntryNotFound { .. }
+            | Self::OperatorNotFound { .. }
+            | Self::ApprovalNotFound { .. }
+            | Self::ReportNotFound { .. }
+            | Self::FundsDeclarationNotFound { .. }
+            | Self::ProviderCallbackNotFound { .. }
+            | Self::ProviderCredentialNotFound { .. }
+            | Self::NotInComplianceSet { .. }
+            | Self::EpochNotFound { .. }
+            | Self::AttestationNotAnchored { .. }
+            | Self::ScreeningResultNotFound { .. }
+            | Self::ScreeningMatchNotFound { .. }
+            | Self::WorkflowRunNotFound { .. }
+            | Self::ClientSandboxNotFound { .. }
+            | Self::VerificationSessionNotFound { .. }
+            | Self::AccountWatchNotFound { .. }
+            | Self::DecisionNotFound { .. }
+            | Self::OffboardingJobNotFound { .. }
+            | Self::FraudCaseNotFound { .. }
+            | Self::PresentationNotFound { .. }
+            | Self::SigningRequestNotFound { .. }
+            | Self::JobNotFound { .. } => 404,
+            Self::InsufficientPrivileges { .. }
+            | Self::InvalidApiKey
+            | Self::InvalidCredentials
+            | Self::InvalidCallbackSignature { .. }
+            | Self::InvalidSessionToken { .. } => 401,
+            Self::CompliancePolicyViolation { .. } | Self::PermissionDenied { .. } => 403,
+            Self::RateLimitExceeded
+            | Self::ProverSaturated { .. }
+            | Self::MonitoringBackpressure { .. }
+            | Self::QuotaExceeded { .. }
+            | Self::VerifierSaturated { .. } => 429,
+            Self::ProviderUnavailable { .. }
+            | Self::OracleNotPublished
+            | Self::RegistryNotPublished
+            | Self::ResidencyUnavailable { .. } => 503,
+            Self::Validation { .. } | Self::InvalidRequest(_) => 400,
+            Self::InvalidProof { .. } | Self::ChallengeRejected { .. } => 400,
+            Self::WorkflowStepTimedOut { .. } | Self::ProofVerificationTimedOut { .. } => 504,
+            Self::WorkflowFailed { .. }
+            | Self::DocumentQuarantined { .. }
+            | Self::DocumentQualityInsufficient { .. }
+            | Self::ProofTooLarge { .. } => 422,
+            Self::VerificationSessionClosed { .. }
+            | Self::AccountOffboarded { .. }
+            | Self::AttestationNotFresh { .. }
+            | Self::SigningRequestClosed { .. } => 409,
+            Self::PayloadTooLarge { .. } => 413,
+            Self::UnsupportedMediaType { .. } => 415,
             _ => 500,
         }
     }
@@ -0,0 +1,55 @@
+//! Kafka event publisher
+
+use super::EventPublisher;
+use crate::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::time::Duration;
+
+/// Time a publish may wait for space in the producer queue
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Publishes to Kafka with idempotent, fully acknowledged writes
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    /// Create a producer for the given brokers, with optional SASL credentials
+    pub fn new(brokers: &[String], sasl: Option<(&str, &str)>) -> Result<Self> {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers.join(","))
+            .set("acks", "all")
+            .set("enable.idempotence", "true");
+        if let Some((username, password)) = sasl {
+            config
+                .set("security.protocol", "SASL_SSL")
+                .set("sasl.mechanisms", "SCRAM-SHA-512")
+                .set("sasl.username", username)
+                .set("sasl.password", password);
+        }
+        
+        let producer = config
+            .create()
+            .map_err(|e| ComplianceError::internal(format!("failed to create Kafka producer: {}", e)))?;
+        Ok(Self { producer })
+    }
+}
+
+impl EventPublisher for KafkaPublisher {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+    
+    fn publish<'a>(&'a self, topic: &'a str, key: &'a str, payload: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.producer
+                .send(FutureRecord::to(topic).key(key).payload(payload), QUEUE_TIMEOUT)
+                .await
+                .map(|_| ())
+                .map_err(|(e, _)| ComplianceError::internal(format!("Kafka publish failed: {}", e)))
+        })
+    }
+}
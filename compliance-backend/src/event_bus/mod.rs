@@ -0,0 +1,157 @@
+//! Mirrors compliance events onto Kafka or NATS
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+use crate::compliance::attestation_events::AttestationEventStore;
+use crate::compliance::event_feed::FeedEvent;
+use crate::config::{EventBusBackend, EventBusConfig};
+use crate::types::AccountId;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Schema identifier of published attestation events
+pub const ATTESTATION_EVENT_SCHEMA: &str = "zerotrust.compliance.attestation_event";
+
+/// Current payload schema version
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Payload published for each event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusMessage {
+    pub schema: String,
+    pub schema_version: u32,
+    /// Stable identifier for de-duplication by consumers
+    pub message_id: String,
+    /// Position in the deployment's event feed
+    pub position: u64,
+    pub account_id: AccountId,
+    pub recorded_at: DateTime<Utc>,
+    pub event: serde_json::Value,
+}
+
+impl BusMessage {
+    /// Wrap a feed event in the versioned envelope
+    pub fn from_feed(deployment_id: &str, event: &FeedEvent) -> Result<Self> {
+        Ok(Self {
+            schema: ATTESTATION_EVENT_SCHEMA.to_string(),
+            schema_version: SCHEMA_VERSION,
+            message_id: format!("{}:{}:{}", deployment_id, event.event.account_id, event.event.sequence),
+            position: event.position,
+            account_id: event.event.account_id.clone(),
+            recorded_at: event.event.recorded_at,
+            event: serde_json::to_value(&event.event.event)?,
+        })
+    }
+}
+
+/// Broker events are published to
+pub trait EventPublisher: Send + Sync {
+    /// Publisher name used in logs
+    fn name(&self) -> &'static str;
+    
+    /// Publish a payload, resolving once the broker has acknowledged it
+    fn publish<'a>(&'a self, topic: &'a str, key: &'a str, payload: &'a [u8]) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Build a publisher from configuration
+pub async fn from_config(backend: &EventBusBackend) -> Result<Arc<dyn EventPublisher>> {
+    match backend {
+        #[cfg(feature = "kafka")]
+        EventBusBackend::Kafka { brokers, sasl_username, sasl_password } => Ok(Arc::new(kafka::KafkaPublisher::new(
+            brokers,
+            sasl_username.as_deref().zip(sasl_password.as_deref()),
+        )?)),
+        #[cfg(feature = "nats")]
+        EventBusBackend::Nats { url, credentials_file } => {
+            Ok(Arc::new(nats::NatsPublisher::connect(url, credentials_file.as_deref()).await?))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(ComplianceError::internal("event bus backend is not compiled in")),
+    }
+}
+
+/// Topic or subject an event is published to
+fn topic_for(prefix: &str, message: &BusMessage) -> String {
+    let event_type = message.event.get("type").and_then(|t| t.as_str()).unwrap_or("unknown");
+    format!("{}.attestation.{}", prefix, event_type)
+}
+
+/// Publish every feed event to the bus with at-least-once delivery
+///
+/// Events are published in feed order and retried with backoff until the
+/// broker acknowledges them. If the publisher falls behind the live feed it
+/// resumes from the last acknowledged position.
+pub fn spawn_publisher(
+    config: EventBusConfig,
+    deployment_id: String,
+    events: Arc<AttestationEventStore>,
+    publisher: Arc<dyn EventPublisher>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut acknowledged: Option<u64> = None;
+        loop {
+            let mut subscription = match events.feed().subscribe(acknowledged).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    tracing::error!(publisher = publisher.name(), error = %e, "events lost before publishing, resuming from live feed");
+                    acknowledged = None;
+                    continue;
+                }
+            };
+            
+            let mut backlog = std::mem::take(&mut subscription.backlog).into_iter();
+            loop {
+                let event = match backlog.next() {
+                    Some(event) => event,
+                    None => match subscription.live.recv().await {
+                        Ok(event) => event,
+                        Err(RecvError::Lagged(_)) => break,
+                        Err(RecvError::Closed) => return,
+                    },
+                };
+                publish_with_retry(&config, &deployment_id, publisher.as_ref(), &event).await;
+                acknowledged = Some(event.position);
+            }
+        }
+    })
+}
+
+async fn publish_with_retry(config: &EventBusConfig, deployment_id: &str, publisher: &dyn EventPublisher, event: &FeedEvent) {
+    let message = match BusMessage::from_feed(deployment_id, event) {
+        Ok(message) => message,
+        Err(e) => {
+            tracing::error!(position = event.position, error = %e, "failed to encode event, skipping");
+            return;
+        }
+    };
+    let topic = topic_for(&config.topic_prefix, &message);
+    let payload = match serde_json::to_vec(&message) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::error!(position = event.position, error = %e, "failed to encode event, skipping");
+            return;
+        }
+    };
+    
+    let mut backoff = Duration::from_millis(config.retry_backoff_ms);
+    let max_backoff = Duration::from_millis(config.max_retry_backoff_ms);
+    while let Err(e) = publisher.publish(&topic, &message.account_id, &payload).await {
+        tracing::warn!(
+            publisher = publisher.name(),
+            position = event.position,
+            error = %e,
+            retry_in_ms = backoff.as_millis() as u64,
+            "event publish failed"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
@@ -0,0 +1,54 @@
+//! NATS JetStream event publisher
+
+use super::EventPublisher;
+use crate::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use std::path::Path;
+
+/// Publishes to NATS JetStream, waiting for the stream's acknowledgement
+pub struct NatsPublisher {
+    jetstream: async_nats::jetstream::Context,
+}
+
+impl NatsPublisher {
+    /// Connect to a NATS server, with an optional credentials file
+    pub async fn connect(url: &str, credentials_file: Option<&Path>) -> Result<Self> {
+        let mut options = async_nats::ConnectOptions::new();
+        if let Some(path) = credentials_file {
+            options = options
+                .credentials_file(path)
+                .await
+                .map_err(|e| ComplianceError::internal(format!("failed to load NATS credentials: {}", e)))?;
+        }
+        let client = options
+            .connect(url)
+            .await
+            .map_err(|e| ComplianceError::internal(format!("failed to connect to NATS: {}", e)))?;
+        
+        Ok(Self {
+            jetstream: async_nats::jetstream::new(client),
+        })
+    }
+}
+
+impl EventPublisher for NatsPublisher {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+    
+    fn publish<'a>(&'a self, subject: &'a str, key: &'a str, payload: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut headers = async_nats::HeaderMap::new();
+            headers.insert("Nats-Msg-Id", format!("{}:{}", key, blake3::hash(payload).to_hex()).as_str());
+            
+            let ack = self
+                .jetstream
+                .publish_with_headers(subject.to_string(), headers, payload.to_vec().into())
+                .await
+                .map_err(|e| ComplianceError::internal(format!("NATS publish failed: {}", e)))?;
+            ack.await
+                .map(|_| ())
+                .map_err(|e| ComplianceError::internal(format!("NATS publish not acknowledged: {}", e)))
+        })
+    }
+}
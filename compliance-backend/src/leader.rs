@@ -0,0 +1,229 @@
+//! Leader election among the instances of a deployment
+//!
+//! Work that must happen once per deployment rather than once per instance,
+//! such as scheduled jobs and webhook delivery from the outbox, takes a
+//! named lease before each pass. Only the instance holding the lease does
+//! the work; the others skip the pass. The holder renews the lease on every
+//! pass, and when it stops, by crashing or losing the database, the lease
+//! lapses after its term and the next instance to try takes the work over.
+//!
+//! Leases live in a [`LeaseStore`] shared by the instances. [`PgLeaseStore`]
+//! keeps them in the deployment's database as rows with an expiry rather
+//! than as session advisory locks, so a lease is not tied to one pooled
+//! connection and failover does not wait on the database noticing a dead
+//! session. A store that cannot be reached counts as the lease being held
+//! elsewhere: a pass is skipped rather than risk running twice.
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::LeaderElectionConfig;
+use crate::Result;
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Leases deciding which instance does each piece of work
+pub trait LeaseStore: Send + Sync {
+    /// Take or renew the lease on `lock` for `holder` until `until`
+    ///
+    /// Succeeds when the lease is free, has lapsed by `now`, or is already
+    /// held by `holder`; returns whether `holder` holds it afterwards.
+    fn try_acquire<'a>(
+        &'a self,
+        lock: &'a str,
+        holder: &'a str,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<bool>>;
+}
+
+/// Leases held in process, for single-instance deployments and tests
+#[derive(Default)]
+pub struct MemoryLeaseStore {
+    leases: Mutex<HashMap<String, (String, DateTime<Utc>)>>,
+}
+
+impl LeaseStore for MemoryLeaseStore {
+    fn try_acquire<'a>(
+        &'a self,
+        lock: &'a str,
+        holder: &'a str,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let mut leases = self.leases.lock().expect("lease lock poisoned");
+            match leases.get(lock) {
+                Some((current, expires_at)) if current != holder && *expires_at > now => Ok(false),
+                _ => {
+                    leases.insert(lock.to_string(), (holder.to_string(), until));
+                    Ok(true)
+                }
+            }
+        })
+    }
+}
+
+/// Leases kept in the `leader_leases` table
+pub struct PgLeaseStore {
+    pool: PgPool,
+}
+
+impl PgLeaseStore {
+    /// Create a store over the given database
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl LeaseStore for PgLeaseStore {
+    fn try_acquire<'a>(
+        &'a self,
+        lock: &'a str,
+        holder: &'a str,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            // The upsert only overwrites a row this holder owns or that has
+            // lapsed, so of two instances racing for a lease one gets no row back
+            let acquired = sqlx::query(
+                "INSERT INTO leader_leases (lock_name, holder, expires_at) VALUES ($1, $2, $4) \
+                 ON CONFLICT (lock_name) DO UPDATE SET holder = EXCLUDED.holder, expires_at = EXCLUDED.expires_at \
+                 WHERE leader_leases.holder = EXCLUDED.holder OR leader_leases.expires_at <= $3 \
+                 RETURNING holder",
+            )
+            .bind(lock)
+            .bind(holder)
+            .bind(now)
+            .bind(until)
+            .fetch_optional(&self.pool)
+            .await?;
+            Ok(acquired.is_some())
+        })
+    }
+}
+
+/// This instance's view of one lease, for health checks and metrics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LockHealth {
+    pub name: String,
+    
+    /// Whether this instance held the lease at its last attempt
+    pub held: bool,
+    
+    /// Times this instance took the lease while not holding it
+    pub acquisitions: u64,
+    
+    /// Times this instance renewed a lease it held
+    pub renewals: u64,
+    
+    /// Times this instance found a lease it held taken over
+    pub losses: u64,
+    
+    /// Attempts that failed because the store could not be reached
+    pub errors: u64,
+    
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Leases this instance takes, and their health
+pub struct LeaderElection {
+    store: Arc<dyn LeaseStore>,
+    instance_id: String,
+    lease: Duration,
+    locks: Mutex<HashMap<String, LockHealth>>,
+    clock: SharedClock,
+}
+
+impl LeaderElection {
+    /// Create an election taking leases of `lease` in `store` as `instance_id`
+    pub fn new(instance_id: impl Into<String>, lease: std::time::Duration, store: Arc<dyn LeaseStore>) -> Self {
+        Self {
+            store,
+            instance_id: instance_id.into(),
+            lease: Duration::seconds(lease.as_secs() as i64),
+            locks: Mutex::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+    
+    /// Create the configured election, or `None` when it is disabled
+    ///
+    /// The instance id falls back to the host name, then to a random id.
+    pub fn from_config(config: &LeaderElectionConfig, store: Arc<dyn LeaseStore>) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let instance_id = config.instance_id.clone().unwrap_or_else(|| {
+            std::env::var("HOSTNAME").unwrap_or_else(|_| uuid::Uuid::new_v4().to_string())
+        });
+        let lease = std::time::Duration::from_secs(config.lease_secs);
+        Some(Self::new(instance_id, lease, store))
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Name this instance holds leases under
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+    
+    /// Take or renew the lease on `lock`, returning whether this instance
+    /// may do the work it guards
+    pub async fn acquire(&self, lock: &str) -> bool {
+        let now = self.clock.now();
+        let result = self.store.try_acquire(lock, &self.instance_id, now, now + self.lease).await;
+        
+        let mut locks = self.locks.lock().expect("leader lock poisoned");
+        let health = locks.entry(lock.to_string()).or_insert_with(|| LockHealth {
+            name: lock.to_string(),
+            ..Default::default()
+        });
+        health.last_attempt_at = Some(now);
+        let held = match result {
+            Ok(true) if health.held => {
+                health.renewals += 1;
+                true
+            }
+            Ok(true) => {
+                health.acquisitions += 1;
+                tracing::info!(lock, instance_id = %self.instance_id, "took leader lease");
+                true
+            }
+            Ok(false) => {
+                if health.held {
+                    health.losses += 1;
+                    tracing::warn!(
+                        lock,
+                        instance_id = %self.instance_id,
+                        "leader lease taken over by another instance"
+                    );
+                }
+                false
+            }
+            Err(e) => {
+                health.errors += 1;
+                health.last_error = Some(e.to_string());
+                tracing::warn!(lock, error = %e, "leader lease store unavailable, skipping");
+                false
+            }
+        };
+        health.held = held;
+        held
+    }
+    
+    /// Health of every lease this instance has tried to take, by name
+    pub fn health(&self) -> Vec<LockHealth> {
+        let mut locks: Vec<LockHealth> = self.locks.lock().expect("leader lock poisoned").values().cloned().collect();
+        locks.sort_by(|a, b| a.name.cmp(&b.name));
+        locks
+    }
+}
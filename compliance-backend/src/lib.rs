@@ -5,25 +5,64 @@
 //! while maintaining user privacy through zero-knowledge proofs.
 
 pub mod error;
+pub mod compliance;
+pub mod crypto;
+pub mod clock;
+pub mod verifier;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "server")]
 pub mod config;
+#[cfg(feature = "server")]
 pub mod miden_client;
-pub mod compliance;
+#[cfg(feature = "server")]
 pub mod api;
+#[cfg(feature = "server")]
 pub mod database;
-pub mod crypto;
+#[cfg(feature = "server")]
+pub mod storage;
+#[cfg(feature = "server")]
 pub mod webhooks;
+#[cfg(feature = "server")]
+pub mod alerts;
+#[cfg(feature = "server")]
+pub mod secrets;
+#[cfg(feature = "server")]
+pub mod audit;
+#[cfg(feature = "server")]
+pub mod reload;
+#[cfg(feature = "server")]
+pub mod logging;
+#[cfg(feature = "server")]
+pub mod reporting;
+#[cfg(feature = "server")]
+pub mod event_bus;
+#[cfg(feature = "server")]
+pub mod scheduler;
+#[cfg(feature = "server")]
+pub mod leader;
+#[cfg(feature = "bench")]
+pub mod simulation;
 
 pub use error::{ComplianceError, Result};
+#[cfg(feature = "server")]
 pub use config::Config;
 
 /// Core compliance types and utilities
 pub mod types {
+    mod account_id;
+    
     use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
     use uuid::Uuid;
     use chrono::{DateTime, Utc};
     
+    pub use account_id::{AccountId, AccountIdKind};
+    
     /// Represents a KYC verification status
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
     pub enum KycStatus {
         Pending,
         Verified,
@@ -32,7 +71,7 @@ pub mod types {
     }
     
     /// Represents an AML risk level
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     pub enum AmlRiskLevel {
         Low,
         Medium,
@@ -44,13 +83,17 @@ pub mod types {
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct ComplianceAttestation {
         pub id: Uuid,
-        pub account_id: String,
+        pub account_id: AccountId,
         pub kyc_status: KycStatus,
         pub aml_risk_level: AmlRiskLevel,
         pub sanctions_cleared: bool,
         pub created_at: DateTime<Utc>,
         pub expires_at: DateTime<Utc>,
-        pub proof_hash: String,
+        pub proof_hash: crate::crypto::ProofHash,
+        /// Optional checks that failed or timed out when the attestation was
+        /// issued, retried until they complete
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub partial: Vec<String>,
     }
     
     /// Business client configuration
@@ -62,14 +105,130 @@ pub mod types {
         pub webhook_url: Option<String>,
         pub compliance_level: ComplianceLevel,
         pub created_at: DateTime<Utc>,
+        
+        /// Key webhook deliveries are encrypted to; deliveries are plain JSON without one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub webhook_encryption_key: Option<WebhookEncryptionKey>,
+        
+        /// Region the client's PII must be stored and processed in
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub region: Option<DataRegion>,
+        
+        /// Settings of the client's sandbox traffic, when it has a sandbox
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub sandbox: Option<SandboxProfile>,
+        
+        /// Locales the client's webhooks carry display text in, the deployment default when empty
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub locales: Vec<String>,
+        
+        /// Payload schema versions the client's integrations are pinned to
+        #[serde(default)]
+        pub schema: SchemaPins,
+    }
+    
+    /// Payload schema versions a business client receives
+    ///
+    /// Clients registered before payloads were versioned get the first version.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct SchemaPins {
+        /// Version of webhooks and API responses not pinned otherwise
+        pub default: u32,
+        
+        /// Version of webhook deliveries
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub webhooks: Option<u32>,
+        
+        /// Versions of individual API endpoints, keyed by method and route
+        /// (e.g. `POST /v1/accounts/{id}/check`)
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        pub endpoints: BTreeMap<String, u32>,
+    }
+    
+    impl SchemaPins {
+        /// First payload schema version
+        pub const FIRST_VERSION: u32 = 1;
+        
+        /// Pins to a single version for everything
+        pub fn pinned(version: u32) -> Self {
+            Self {
+                default: version,
+                webhooks: None,
+                endpoints: BTreeMap::new(),
+            }
+        }
+        
+        /// Version of webhook deliveries
+        pub fn for_webhooks(&self) -> u32 {
+            self.webhooks.unwrap_or(self.default)
+        }
+        
+        /// Version of responses from an endpoint
+        pub fn for_endpoint(&self, endpoint: &str) -> u32 {
+            self.endpoints.get(endpoint).copied().unwrap_or(self.default)
+        }
+    }
+    
+    impl Default for SchemaPins {
+        fn default() -> Self {
+            Self::pinned(Self::FIRST_VERSION)
+        }
     }
     
-    /// Compliance level requirements
+    /// A business client's settings for test traffic in the sandbox
     #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SandboxProfile {
+        pub api_key: String,
+        pub webhook_url: Option<String>,
+        pub compliance_level: ComplianceLevel,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub webhook_encryption_key: Option<WebhookEncryptionKey>,
+    }
+    
+    /// X25519 public key a webhook endpoint receives encrypted deliveries for
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct WebhookEncryptionKey {
+        /// Key id carried in each delivery's JWE header, to pick the private key
+        pub kid: String,
+        
+        /// Base64url-encoded public key, as the `x` member of an `OKP` JWK
+        pub x: String,
+    }
+    
+    /// Environment a business client's request is served in, chosen by its API key
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ClientEnvironment {
+        Sandbox,
+        Production,
+    }
+    
+    /// Compliance level requirements, ordered from least to most stringent
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     pub enum ComplianceLevel {
         Basic,
         Standard,
         Enhanced,
         InstitutionalGrade,
     }
+    
+    /// Data residency region
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum DataRegion {
+        Eu,
+        Us,
+        Apac,
+    }
+    
+    impl DataRegion {
+        /// Region name used in configuration and errors
+        pub fn name(self) -> &'static str {
+            match self {
+                Self::Eu => "eu",
+                Self::Us => "us",
+                Self::Apac => "apac",
+            }
+        }
+    }
 } 
\ No newline at end of file
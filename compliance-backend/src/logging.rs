@@ -0,0 +1,142 @@
+//! PII redaction for anything written to the log sink
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// How a redacted field is rendered in logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redaction {
+    /// Replace the value with a fixed marker
+    Mask,
+    
+    /// Replace the value with a short hash so equal values can be correlated
+    Hash,
+}
+
+/// Fields redacted by default: personal data from KYC payloads and credentials
+const DEFAULT_FIELDS: &[(&str, Redaction)] = &[
+    ("name", Redaction::Mask),
+    ("first_name", Redaction::Mask),
+    ("middle_name", Redaction::Mask),
+    ("last_name", Redaction::Mask),
+    ("full_name", Redaction::Mask),
+    ("given_name", Redaction::Mask),
+    ("family_name", Redaction::Mask),
+    ("aliases", Redaction::Mask),
+    ("date_of_birth", Redaction::Mask),
+    ("dob", Redaction::Mask),
+    ("place_of_birth", Redaction::Mask),
+    ("nationality", Redaction::Mask),
+    ("document_number", Redaction::Hash),
+    ("passport_number", Redaction::Hash),
+    ("national_id", Redaction::Hash),
+    ("tax_id", Redaction::Hash),
+    ("ssn", Redaction::Hash),
+    ("address", Redaction::Mask),
+    ("street", Redaction::Mask),
+    ("city", Redaction::Mask),
+    ("postal_code", Redaction::Mask),
+    ("email", Redaction::Hash),
+    ("phone", Redaction::Hash),
+    ("api_key", Redaction::Mask),
+    ("password", Redaction::Mask),
+    ("secret", Redaction::Mask),
+    ("token", Redaction::Mask),
+];
+
+/// Registry of field names whose values must never be logged
+///
+/// Field names are matched case-insensitively, ignoring `_` and `-`, at any
+/// depth of a JSON document.
+#[derive(Debug, Clone)]
+pub struct RedactionRegistry {
+    fields: HashMap<String, Redaction>,
+}
+
+impl RedactionRegistry {
+    /// Create a registry with the default PII and credential fields
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        for (field, redaction) in DEFAULT_FIELDS {
+            registry.register(field, *redaction);
+        }
+        registry
+    }
+    
+    /// Create a registry with no fields
+    pub fn empty() -> Self {
+        Self { fields: HashMap::new() }
+    }
+    
+    /// Create the default registry extended with configured fields
+    pub fn with_fields(extra: &[String]) -> Self {
+        let mut registry = Self::new();
+        for field in extra {
+            registry.register(field, Redaction::Mask);
+        }
+        registry
+    }
+    
+    /// Register a field for redaction
+    pub fn register(&mut self, field: &str, redaction: Redaction) {
+        self.fields.insert(normalize_field(field), redaction);
+    }
+    
+    /// Get the redaction applied to a field, if any
+    pub fn redaction_for(&self, field: &str) -> Option<Redaction> {
+        self.fields.get(&normalize_field(field)).copied()
+    }
+    
+    /// Redact registered fields throughout a JSON document
+    pub fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    match self.redaction_for(key) {
+                        Some(redaction) if !child.is_null() => *child = apply(redaction, child),
+                        _ => self.redact(child),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact(item)),
+            _ => {}
+        }
+    }
+    
+    /// Render a request or response body for logging
+    ///
+    /// JSON bodies are redacted; anything else is summarized by size only,
+    /// since unstructured payloads cannot be redacted field by field.
+    pub fn render_body(&self, body: &[u8]) -> String {
+        if body.is_empty() {
+            return String::new();
+        }
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut value) => {
+                self.redact(&mut value);
+                value.to_string()
+            }
+            Err(_) => format!("<{} bytes, not JSON>", body.len()),
+        }
+    }
+}
+
+impl Default for RedactionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalize_field(field: &str) -> String {
+    field.chars().filter(|c| *c != '_' && *c != '-').flat_map(char::to_lowercase).collect()
+}
+
+fn apply(redaction: Redaction, value: &Value) -> Value {
+    match redaction {
+        Redaction::Mask => Value::String("[redacted]".to_string()),
+        Redaction::Hash => {
+            let hash = blake3::hash(value.to_string().as_bytes()).to_hex();
+            Value::String(format!("[hash:{}]", &hash[..12]))
+        }
+    }
+}
@@ -1,3 +1,21 @@
-fn main() {
-    println!("Hello, world!");
+use compliance_backend::{ComplianceError, Config};
+
+#[tokio::main]
+async fn main() {
+    let path = std::env::var("COMPLIANCE_CONFIG_PATH").unwrap_or_else(|_| "config".to_string());
+    
+    match Config::load_with_secrets(&path).await {
+        Ok((config, _secrets)) => println!("Configuration valid ({:?})", config.environment),
+        Err(ComplianceError::InvalidConfiguration(violations)) => {
+            eprintln!("Invalid configuration in {}:", path);
+            for violation in &violations.0 {
+                eprintln!("  - {}: {}", violation.field, violation.message);
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to load configuration from {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
 }
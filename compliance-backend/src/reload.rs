@@ -0,0 +1,215 @@
+//! Hot reloading of the compliance configuration section
+//!
+//! Risk thresholds, matching thresholds, monitoring limits, and policy
+//! defaults are read by running services through [`LiveConfig`], so a reload
+//! takes effect on the next request. Other sections still require a restart.
+
+use crate::audit::AuditLog;
+use crate::config::{ComplianceConfig, Config};
+use crate::secrets::SecretResolver;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Extensions the `config` crate probes for when given a path without one
+const CONFIG_EXTENSIONS: [&str; 5] = ["toml", "yaml", "yml", "json", "ini"];
+
+/// Compliance configuration shared with running services
+pub struct LiveConfig {
+    current: RwLock<(u64, Arc<ComplianceConfig>)>,
+}
+
+impl LiveConfig {
+    /// Create live configuration at version 1
+    pub fn new(config: ComplianceConfig) -> Self {
+        Self {
+            current: RwLock::new((1, Arc::new(config))),
+        }
+    }
+    
+    /// Get the current compliance configuration
+    pub fn compliance(&self) -> Arc<ComplianceConfig> {
+        self.current.read().expect("live config lock poisoned").1.clone()
+    }
+    
+    /// Get the current configuration version
+    pub fn version(&self) -> u64 {
+        self.current.read().expect("live config lock poisoned").0
+    }
+    
+    /// Install new configuration, returning the new version
+    fn replace(&self, config: ComplianceConfig) -> u64 {
+        let mut current = self.current.write().expect("live config lock poisoned");
+        current.0 += 1;
+        current.1 = Arc::new(config);
+        current.0
+    }
+}
+
+/// A single changed configuration value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Dotted path of the changed field
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+/// Result of a reload attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadOutcome {
+    /// Configuration version in effect after the reload
+    pub version: u64,
+    
+    /// Changes applied, empty when the file was unchanged
+    pub changes: Vec<ConfigChange>,
+}
+
+/// Reloads the compliance section from the configuration file
+pub struct ConfigReloader {
+    path: String,
+    live: Arc<LiveConfig>,
+    secrets: Arc<SecretResolver>,
+    audit: Arc<AuditLog>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader for the configuration at `path`
+    pub fn new(path: impl Into<String>, live: Arc<LiveConfig>, secrets: Arc<SecretResolver>, audit: Arc<AuditLog>) -> Self {
+        Self {
+            path: path.into(),
+            live,
+            secrets,
+            audit,
+        }
+    }
+    
+    /// Re-read the configuration file and apply changes to the compliance section
+    ///
+    /// The whole file is validated and rejected if invalid, leaving the
+    /// running configuration untouched. Each applied change is recorded in
+    /// the audit log with its new version.
+    pub async fn reload(&self, actor: &str) -> Result<ReloadOutcome> {
+        let mut config = Config::from_file(&self.path)?;
+        config.resolve_secrets(&self.secrets).await?;
+        config.validate()?;
+        
+        let current = self.live.compliance();
+        let changes = diff(&serde_json::to_value(&*current)?, &serde_json::to_value(&config.compliance)?);
+        if changes.is_empty() {
+            return Ok(ReloadOutcome {
+                version: self.live.version(),
+                changes,
+            });
+        }
+        
+        let version = self.live.replace(config.compliance);
+        self.audit
+            .record(
+                actor,
+                "config.reloaded",
+                None,
+                serde_json::json!({
+                    "version": version,
+                    "path": self.path,
+                    "changes": changes,
+                }),
+            )
+            .await;
+        tracing::info!(version, changes = changes.len(), "compliance configuration reloaded");
+        
+        Ok(ReloadOutcome { version, changes })
+    }
+    
+    /// Reload on every SIGHUP
+    #[cfg(unix)]
+    pub fn spawn_sighup(self: Arc<Self>) -> std::io::Result<tokio::task::JoinHandle<()>> {
+        let mut hangups = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        Ok(tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                self.reload_logged("sighup").await;
+            }
+        }))
+    }
+    
+    /// Poll the configuration file and reload whenever it is modified
+    pub fn spawn_watch(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut last_modified = self.modified_at();
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let modified = self.modified_at();
+                if modified != last_modified {
+                    last_modified = modified;
+                    self.reload_logged("file-watch").await;
+                }
+            }
+        })
+    }
+    
+    async fn reload_logged(&self, actor: &str) {
+        if let Err(e) = self.reload(actor).await {
+            tracing::error!(error = %e, path = %self.path, "configuration reload rejected");
+        }
+    }
+    
+    /// Modification time of the file the `config` crate would load
+    fn modified_at(&self) -> Option<SystemTime> {
+        let path = Path::new(&self.path);
+        std::iter::once(path.to_path_buf())
+            .chain(CONFIG_EXTENSIONS.iter().map(|ext| path.with_extension(ext)))
+            .find(|candidate: &PathBuf| candidate.is_file())
+            .and_then(|file| std::fs::metadata(file).ok())
+            .and_then(|metadata| metadata.modified().ok())
+    }
+}
+
+/// Field-level differences between two JSON documents
+fn diff(old: &serde_json::Value, new: &serde_json::Value) -> Vec<ConfigChange> {
+    let (mut before, mut after) = (BTreeMap::new(), BTreeMap::new());
+    flatten("compliance", old, &mut before);
+    flatten("compliance", new, &mut after);
+    
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or(serde_json::Value::Null);
+            let new = after.get(field).cloned().unwrap_or(serde_json::Value::Null);
+            (old != new).then(|| ConfigChange {
+                field: field.clone(),
+                // Never write resolved secrets to the audit log
+                old: redact(field, old),
+                new: redact(field, new),
+            })
+        })
+        .collect()
+}
+
+fn flatten(prefix: &str, value: &serde_json::Value, out: &mut BTreeMap<String, serde_json::Value>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                flatten(&format!("{}.{}", prefix, key), child, out);
+            }
+        }
+        other => {
+            out.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+fn redact(field: &str, value: serde_json::Value) -> serde_json::Value {
+    if field.ends_with("api_key") && !value.is_null() {
+        serde_json::Value::String("[redacted]".to_string())
+    } else {
+        value
+    }
+}
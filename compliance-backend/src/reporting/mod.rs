@@ -0,0 +1,305 @@
+//! Management information reports per business client
+
+pub mod render;
+pub mod schedule;
+
+use crate::audit::AuditLog;
+use crate::compliance::clients::ClientRegistry;
+use crate::compliance::step_up::{StepUpService, StepUpStatus};
+use crate::compliance::velocity::{AuthorizationOutcome, VelocityService};
+use crate::compliance::ComplianceService;
+use crate::config::ReportScheduleConfig;
+use crate::types::*;
+use crate::{ComplianceError, Result};
+use crate::scheduler::Job;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Rendered report format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Csv,
+    Pdf,
+}
+
+/// How far accounts progressed through onboarding
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OnboardingFunnel {
+    pub accounts_checked: u64,
+    pub attestations_issued: u64,
+    pub kyc_verified: u64,
+    pub sanctions_cleared: u64,
+    pub reached_standard: u64,
+    pub reached_enhanced: u64,
+}
+
+/// Sanctions screening hit rate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScreeningMetrics {
+    pub accounts_screened: u64,
+    pub sanctions_hits: u64,
+    pub hit_rate: f64,
+}
+
+/// Service-level metrics for authorization and step-up flows
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlaMetrics {
+    pub authorizations: u64,
+    pub authorization_outcomes: BTreeMap<String, u64>,
+    pub step_up_sessions: u64,
+    pub step_up_upgraded: u64,
+    pub step_up_expired: u64,
+    /// Median time from opening to completing a step-up session
+    pub median_step_up_hours: Option<f64>,
+}
+
+/// Metrics computed for a report period
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportMetrics {
+    pub funnel: OnboardingFunnel,
+    pub rejection_reasons: BTreeMap<String, u64>,
+    pub risk_distribution: BTreeMap<String, u64>,
+    pub screening: ScreeningMetrics,
+    pub sla: SlaMetrics,
+}
+
+/// A generated management information report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    /// Schedule that produced the report, absent for on-demand reports
+    pub schedule: Option<String>,
+    pub period_from: DateTime<Utc>,
+    pub period_to: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub metrics: ReportMetrics,
+}
+
+/// Service generating and storing MI reports
+pub struct ReportService {
+    compliance: Arc<ComplianceService>,
+    velocity: Arc<VelocityService>,
+    step_up: Arc<StepUpService>,
+    audit: Arc<AuditLog>,
+    max_retained: usize,
+    reports: RwLock<VecDeque<Report>>,
+}
+
+impl ReportService {
+    /// Create a report service
+    pub fn new(
+        compliance: Arc<ComplianceService>,
+        velocity: Arc<VelocityService>,
+        step_up: Arc<StepUpService>,
+        audit: Arc<AuditLog>,
+        max_retained: usize,
+    ) -> Self {
+        Self {
+            compliance,
+            velocity,
+            step_up,
+            audit,
+            max_retained,
+            reports: RwLock::new(VecDeque::new()),
+        }
+    }
+    
+    /// Generate and store a report for a client covering `[from, to)`
+    pub async fn generate(
+        &self,
+        client_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        schedule: Option<String>,
+    ) -> Result<Report> {
+        if from >= to {
+            return Err(ComplianceError::validation("from", "must be before to"));
+        }
+        
+        let decisions = self.velocity.decisions_by_client(client_id, from, to).await;
+        let mut accounts = self.audit.accounts_touched_by(&client_id.to_string(), from, to).await?;
+        accounts.extend(decisions.iter().map(|d| d.account_id.clone()));
+        accounts.sort();
+        accounts.dedup();
+        
+        let mut metrics = ReportMetrics::default();
+        let mut step_up_hours = Vec::new();
+        
+        for account_id in &accounts {
+            metrics.funnel.accounts_checked += 1;
+            
+            if let Some(state) = self.compliance.get_compliance_state_at(account_id, to).await? {
+                let attestation = &state.attestation;
+                metrics.funnel.attestations_issued += 1;
+                metrics.screening.accounts_screened += 1;
+                
+                if attestation.kyc_status == KycStatus::Verified {
+                    metrics.funnel.kyc_verified += 1;
+                } else {
+                    *metrics
+                        .rejection_reasons
+                        .entry(format!("KYC status is {:?}", attestation.kyc_status))
+                        .or_default() += 1;
+                }
+                if attestation.sanctions_cleared {
+                    metrics.funnel.sanctions_cleared += 1;
+                } else {
+                    metrics.screening.sanctions_hits += 1;
+                }
+                
+                let level = self.compliance.highest_compliance_level(attestation).await;
+                if level.as_ref().is_some_and(|l| *l >= ComplianceLevel::Standard) {
+                    metrics.funnel.reached_standard += 1;
+                }
+                if level.as_ref().is_some_and(|l| *l >= ComplianceLevel::Enhanced) {
+                    metrics.funnel.reached_enhanced += 1;
+                }
+                *metrics
+                    .risk_distribution
+                    .entry(format!("{:?}", attestation.aml_risk_level))
+                    .or_default() += 1;
+            }
+            
+            for session in self.step_up.sessions_for(account_id).await {
+                if session.created_at < from || session.created_at >= to {
+                    continue;
+                }
+                metrics.sla.step_up_sessions += 1;
+                match session.status {
+                    StepUpStatus::Upgraded => metrics.sla.step_up_upgraded += 1,
+                    StepUpStatus::Expired => metrics.sla.step_up_expired += 1,
+                    _ => {}
+                }
+                if let Some(completed_at) = session.completed_at {
+                    step_up_hours.push((completed_at - session.created_at).num_seconds() as f64 / 3600.0);
+                }
+            }
+        }
+        
+        for decision in &decisions {
+            metrics.sla.authorizations += 1;
+            *metrics
+                .sla
+                .authorization_outcomes
+                .entry(format!("{:?}", decision.outcome).to_lowercase())
+                .or_default() += 1;
+            if decision.outcome == AuthorizationOutcome::Deny {
+                for reason in &decision.reasons {
+                    *metrics.rejection_reasons.entry(reason.clone()).or_default() += 1;
+                }
+            }
+        }
+        
+        if metrics.screening.accounts_screened > 0 {
+            metrics.screening.hit_rate =
+                metrics.screening.sanctions_hits as f64 / metrics.screening.accounts_screened as f64;
+        }
+        metrics.sla.median_step_up_hours = median(&mut step_up_hours);
+        
+        let report = Report {
+            id: Uuid::new_v4(),
+            client_id,
+            schedule,
+            period_from: from,
+            period_to: to,
+            generated_at: Utc::now(),
+            metrics,
+        };
+        
+        let mut reports = self.reports.write().await;
+        reports.push_back(report.clone());
+        while reports.len() > self.max_retained {
+            reports.pop_front();
+        }
+        
+        Ok(report)
+    }
+    
+    /// Get a report
+    pub async fn get(&self, report_id: Uuid) -> Result<Report> {
+        self.reports
+            .read()
+            .await
+            .iter()
+            .find(|r| r.id == report_id)
+            .cloned()
+            .ok_or_else(|| ComplianceError::ReportNotFound {
+                report_id: report_id.to_string(),
+            })
+    }
+    
+    /// List a client's reports, most recent first
+    pub async fn list(&self, client_id: Uuid) -> Vec<Report> {
+        self.reports
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|r| r.client_id == client_id)
+            .cloned()
+            .collect()
+    }
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// One job per configured report schedule, named `report.<schedule>`
+///
+/// Schedules with an invalid cron expression are skipped; configuration
+/// validation rejects them at startup.
+pub fn report_jobs(
+    reports: Arc<ReportService>,
+    clients: Arc<ClientRegistry>,
+    schedules: Vec<ReportScheduleConfig>,
+) -> Vec<Job> {
+    schedules
+        .into_iter()
+        .filter_map(|config| {
+            let (reports, clients) = (reports.clone(), clients.clone());
+            let cron = config.cron.clone();
+            Job::cron(format!("report.{}", config.name), &cron, move || {
+                let (reports, clients, config) = (reports.clone(), clients.clone(), config.clone());
+                async move {
+                    let now = Utc::now();
+                    let from = now - Duration::days(config.period_days as i64);
+                    let targets: Vec<Uuid> = if config.clients.is_empty() {
+                        clients.list().await?.into_iter().map(|c| c.id).collect()
+                    } else {
+                        config.clients.clone()
+                    };
+                    
+                    for client_id in targets {
+                        if let Err(e) = reports.generate(client_id, from, now, Some(config.name.clone())).await {
+                            tracing::error!(
+                                schedule = %config.name,
+                                client_id = %client_id,
+                                error = %e,
+                                "scheduled report failed"
+                            );
+                        }
+                    }
+                    tracing::info!(schedule = %config.name, "scheduled reports generated");
+                    Ok(())
+                }
+            })
+            .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+//! CSV and PDF rendering of reports
+
+use super::Report;
+
+/// PDF text lines per page
+const LINES_PER_PAGE: usize = 50;
+
+/// Render a report as `section,metric,value` CSV rows
+pub fn to_csv(report: &Report) -> String {
+    let mut csv = String::from("section,metric,value\n");
+    for (section, metric, value) in rows(report) {
+        csv.push_str(&format!("{},{},{}\n", escape_csv(&section), escape_csv(&metric), escape_csv(&value)));
+    }
+    csv
+}
+
+/// Render a report as a plain single-font PDF document
+pub fn to_pdf(report: &Report) -> Vec<u8> {
+    let mut lines = vec![
+        "Management Information Report".to_string(),
+        format!("Client: {}", report.client_id),
+        format!("Period: {} to {}", report.period_from.to_rfc3339(), report.period_to.to_rfc3339()),
+        format!("Generated: {}", report.generated_at.to_rfc3339()),
+        String::new(),
+    ];
+    let mut current_section = String::new();
+    for (section, metric, value) in rows(report) {
+        if section != current_section {
+            lines.push(String::new());
+            lines.push(section.replace('_', " ").to_uppercase());
+            current_section = section;
+        }
+        lines.push(format!("  {}: {}", metric, value));
+    }
+    
+    write_pdf(&lines.chunks(LINES_PER_PAGE).collect::<Vec<_>>())
+}
+
+fn rows(report: &Report) -> Vec<(String, String, String)> {
+    let m = &report.metrics;
+    let mut rows = Vec::new();
+    let mut push = |section: &str, metric: &str, value: String| rows.push((section.to_string(), metric.to_string(), value));
+    
+    push("onboarding_funnel", "accounts_checked", m.funnel.accounts_checked.to_string());
+    push("onboarding_funnel", "attestations_issued", m.funnel.attestations_issued.to_string());
+    push("onboarding_funnel", "kyc_verified", m.funnel.kyc_verified.to_string());
+    push("onboarding_funnel", "sanctions_cleared", m.funnel.sanctions_cleared.to_string());
+    push("onboarding_funnel", "reached_standard", m.funnel.reached_standard.to_string());
+    push("onboarding_funnel", "reached_enhanced", m.funnel.reached_enhanced.to_string());
+    for (reason, count) in &m.rejection_reasons {
+        push("rejection_reasons", reason, count.to_string());
+    }
+    for (level, count) in &m.risk_distribution {
+        push("risk_distribution", level, count.to_string());
+    }
+    push("screening", "accounts_screened", m.screening.accounts_screened.to_string());
+    push("screening", "sanctions_hits", m.screening.sanctions_hits.to_string());
+    push("screening", "hit_rate", format!("{:.4}", m.screening.hit_rate));
+    push("sla", "authorizations", m.sla.authorizations.to_string());
+    for (outcome, count) in &m.sla.authorization_outcomes {
+        push("sla", &format!("authorizations_{}", outcome), count.to_string());
+    }
+    push("sla", "step_up_sessions", m.sla.step_up_sessions.to_string());
+    push("sla", "step_up_upgraded", m.sla.step_up_upgraded.to_string());
+    push("sla", "step_up_expired", m.sla.step_up_expired.to_string());
+    push(
+        "sla",
+        "median_step_up_hours",
+        m.sla.median_step_up_hours.map(|h| format!("{:.2}", h)).unwrap_or_default(),
+    );
+    rows
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_pdf_text(line: &str) -> String {
+    line.chars()
+        .map(|c| match c {
+            '\\' | '(' | ')' => format!("\\{}", c),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Write a minimal PDF 1.4 document with one Helvetica text page per chunk
+fn write_pdf(pages: &[&[String]]) -> Vec<u8> {
+    // Objects 1-3 are the catalog, page tree, and font; each page then takes two
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len()).map(|i| format!("{} 0 R", 4 + 2 * i)).collect::<Vec<_>>().join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    for (i, lines) in pages.iter().enumerate() {
+        let mut content = String::from("BT /F1 10 Tf 14 TL 50 750 Td\n");
+        for line in lines.iter() {
+            content.push_str(&format!("({}) Tj T*\n", escape_pdf_text(line)));
+        }
+        content.push_str("ET");
+        
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            5 + 2 * i
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content));
+    }
+    
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+    
+    let xref_offset = pdf.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        xref.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    xref.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+    pdf.extend_from_slice(xref.as_bytes());
+    pdf
+}
@@ -0,0 +1,127 @@
+//! Cron-like report and job schedules
+//!
+//! Five fields: minute, hour, day of month, month, day of week (0 or 7 is
+//! Sunday). Each field accepts `*`, values, ranges `a-b`, steps `*/n` or
+//! `a-b/n`, and comma-separated lists. Times are evaluated in UTC.
+
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+
+/// How far ahead [`CronSchedule::next_after`] looks for a matching minute
+const MAX_LOOKAHEAD_DAYS: i64 = 4 * 366;
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether day of month and day of week were both restricted
+    day_fields_restricted: (bool, bool),
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let &[minute, hour, dom, month, dow] = fields.as_slice() else {
+            return Err(format!("expected 5 fields, found {}", fields.len()));
+        };
+        
+        let mut days_of_week = parse_field(dow, 0, 7)?;
+        // Both 0 and 7 mean Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(dom, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            day_fields_restricted: (dom != "*", dow != "*"),
+        })
+    }
+    
+    /// Check if the schedule fires in the minute containing `at`
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        self.day_matches(at) && bit(self.hours, at.hour()) && bit(self.minutes, at.minute())
+    }
+    
+    /// First minute strictly after the one containing `after` in which the
+    /// schedule fires
+    ///
+    /// `None` when the schedule never fires, such as on February 30th.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let end = at + Duration::days(MAX_LOOKAHEAD_DAYS);
+        while at < end {
+            if !self.day_matches(at) {
+                at = at.duration_trunc(Duration::days(1)).ok()? + Duration::days(1);
+            } else if !bit(self.hours, at.hour()) {
+                at = at.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+            } else if !bit(self.minutes, at.minute()) {
+                at += Duration::minutes(1);
+            } else {
+                return Some(at);
+            }
+        }
+        None
+    }
+    
+    fn day_matches(&self, at: DateTime<Utc>) -> bool {
+        let dom = bit(self.days_of_month, at.day());
+        let dow = bit(self.days_of_week, at.weekday().num_days_from_sunday());
+        // As in cron, when both day fields are restricted either may match
+        let day = match self.day_fields_restricted {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        };
+        day && bit(self.months, at.month())
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn parse_field(spec: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| format!("invalid step in {}", part))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(format!("step must be positive in {}", part));
+        }
+        
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, max)?, parse_value(b, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `a/n` runs from a to the end of the range
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start > end {
+            return Err(format!("invalid range {}", range));
+        }
+        
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| format!("{} is outside {}-{}", value, min, max))
+}
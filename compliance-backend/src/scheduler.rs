@@ -0,0 +1,366 @@
+//! Background job scheduler
+//!
+//! Periodic work — expiry scans, retention purges, scheduled reports,
+//! publications — is registered as a [`Job`] running on a fixed interval or
+//! a cron expression, and driven by one [`Scheduler`] loop. Each job's
+//! built-in schedule can be overridden or the job disabled by name in
+//! [`SchedulerConfig::jobs`].
+//!
+//! Jitter delays every run by a random amount up to the job's jitter, so
+//! that instances and jobs sharing a schedule do not all fire at once. A job
+//! never overlaps itself: a run that falls due while the previous one is
+//! still going is skipped.
+//!
+//! With [leader election](crate::leader), a run first takes the job's lease.
+//! Only the instance holding the lease runs the job; the others record the
+//! run as skipped.
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::SchedulerConfig;
+use crate::reporting::schedule::CronSchedule;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Duration, Utc};
+use crate::leader::LeaderElection;
+use futures::future::BoxFuture;
+use rand::Rng;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// When a job runs
+#[derive(Debug, Clone)]
+pub enum JobSchedule {
+    /// A fixed interval after the previous run started
+    Every(std::time::Duration),
+    Cron(CronSchedule),
+}
+
+impl JobSchedule {
+    /// When the job is next due after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => Some(after + Duration::from_std(*interval).ok()?),
+            Self::Cron(cron) => cron.next_after(after),
+        }
+    }
+}
+
+/// Work a job does on each run
+pub type JobTask = Arc<dyn Fn() -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// A named piece of periodic work
+pub struct Job {
+    name: String,
+    schedule: JobSchedule,
+    /// Written out for the status API
+    schedule_text: String,
+    jitter: Option<std::time::Duration>,
+    task: JobTask,
+}
+
+impl Job {
+    /// Create a job running `task` on `schedule`
+    pub fn new<F, Fut>(name: impl Into<String>, schedule: JobSchedule, task: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let schedule_text = describe(&schedule, None);
+        Self {
+            name: name.into(),
+            schedule,
+            schedule_text,
+            jitter: None,
+            task: Arc::new(move || Box::pin(task())),
+        }
+    }
+    
+    /// Create a job running `task` every `interval`
+    pub fn every<F, Fut>(name: impl Into<String>, interval: std::time::Duration, task: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        Self::new(name, JobSchedule::Every(interval), task)
+    }
+    
+    /// Create a job running `task` on a cron `expression`
+    pub fn cron<F, Fut>(name: impl Into<String>, expression: &str, task: F) -> Result<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let cron = CronSchedule::parse(expression).map_err(|e| ComplianceError::validation("cron", e))?;
+        let mut job = Self::new(name, JobSchedule::Cron(cron), task);
+        job.schedule_text = describe(&job.schedule, Some(expression));
+        Ok(job)
+    }
+    
+    /// Delay each run by up to `jitter`, in place of the scheduler's default
+    pub fn with_jitter(mut self, jitter: std::time::Duration) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+    
+    /// Name the job is configured and reported under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn describe(schedule: &JobSchedule, cron: Option<&str>) -> String {
+    match (schedule, cron) {
+        (_, Some(cron)) => format!("cron {}", cron),
+        (JobSchedule::Every(interval), None) => format!("every {}s", interval.as_secs()),
+        (JobSchedule::Cron(_), None) => "cron".to_string(),
+    }
+}
+
+/// Result of a job's last run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    Succeeded,
+    Failed,
+    /// Another instance holds the job's lease
+    NotLeader,
+}
+
+/// A job as reported by the status API
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: String,
+    pub schedule: String,
+    pub enabled: bool,
+    pub running: bool,
+    /// When the next run starts, jitter included
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_outcome: Option<JobOutcome>,
+    pub last_error: Option<String>,
+    /// Runs this instance has made, and how many of them failed
+    pub runs: u64,
+    pub failures: u64,
+}
+
+struct Entry {
+    job: Job,
+    jitter: std::time::Duration,
+    status: JobStatus,
+}
+
+/// Runs registered jobs when they fall due
+pub struct Scheduler {
+    config: SchedulerConfig,
+    entries: Mutex<Vec<Entry>>,
+    leader: Option<Arc<LeaderElection>>,
+    clock: SharedClock,
+}
+
+impl Scheduler {
+    /// Create a scheduler with no jobs
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(Vec::new()),
+            leader: None,
+            clock: system_clock(),
+        }
+    }
+    
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Run jobs only while holding their lease in `leader`
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+    
+    /// Register a job, applying its configured override
+    ///
+    /// The first run is due one period from now.
+    pub fn register(&self, mut job: Job) -> Result<()> {
+        let mut entries = self.entries.lock().expect("scheduler lock poisoned");
+        if entries.iter().any(|entry| entry.job.name == job.name) {
+            return Err(ComplianceError::internal(format!("job {} is registered twice", job.name)));
+        }
+        
+        let mut jitter = job.jitter.unwrap_or(std::time::Duration::from_secs(self.config.jitter_secs));
+        let mut enabled = true;
+        if let Some(config) = self.config.jobs.get(&job.name) {
+            enabled = config.enabled;
+            if let Some(cron) = &config.cron {
+                job.schedule =
+                    JobSchedule::Cron(CronSchedule::parse(cron).map_err(|e| ComplianceError::validation("cron", e))?);
+                job.schedule_text = describe(&job.schedule, Some(cron));
+            } else if let Some(secs) = config.interval_secs {
+                job.schedule = JobSchedule::Every(std::time::Duration::from_secs(secs));
+                job.schedule_text = describe(&job.schedule, None);
+            }
+            if let Some(secs) = config.jitter_secs {
+                jitter = std::time::Duration::from_secs(secs);
+            }
+        }
+        
+        let next_run_at = if enabled {
+            job.schedule.next_after(self.clock.now()).map(|due| due + random_jitter(jitter))
+        } else {
+            None
+        };
+        let status = JobStatus {
+            name: job.name.clone(),
+            schedule: job.schedule_text.clone(),
+            enabled,
+            running: false,
+            next_run_at,
+            last_started_at: None,
+            last_finished_at: None,
+            last_outcome: None,
+            last_error: None,
+            runs: 0,
+            failures: 0,
+        };
+        entries.push(Entry { job, jitter, status });
+        Ok(())
+    }
+    
+    /// Status of every registered job, by name
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        let entries = self.entries.lock().expect("scheduler lock poisoned");
+        let mut statuses: Vec<JobStatus> = entries.iter().map(|entry| entry.status.clone()).collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+    
+    /// Status of one job
+    pub fn status(&self, name: &str) -> Result<JobStatus> {
+        self.entries
+            .lock()
+            .expect("scheduler lock poisoned")
+            .iter()
+            .find(|entry| entry.job.name == name)
+            .map(|entry| entry.status.clone())
+            .ok_or_else(|| ComplianceError::JobNotFound { name: name.to_string() })
+    }
+    
+    /// Make an enabled job due now, ahead of its schedule
+    pub fn trigger(&self, name: &str) -> Result<JobStatus> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().expect("scheduler lock poisoned");
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.job.name == name)
+            .ok_or_else(|| ComplianceError::JobNotFound { name: name.to_string() })?;
+        if !entry.status.enabled {
+            return Err(ComplianceError::validation("name", format!("job {} is disabled", name)));
+        }
+        entry.status.next_run_at = Some(now);
+        Ok(entry.status.clone())
+    }
+    
+    /// Run every job that is due, and wait for the runs to finish
+    ///
+    /// Returns the names of the jobs run, including those skipped for
+    /// want of their lease.
+    pub async fn run_due(&self) -> Vec<String> {
+        let due = self.take_due();
+        let names = due.iter().map(|(name, _)| name.clone()).collect();
+        futures::future::join_all(due.into_iter().map(|(name, task)| self.execute(name, task))).await;
+        names
+    }
+    
+    /// Run due jobs in the background, checking every second
+    ///
+    /// Jobs run concurrently, so a slow job does not hold up the others.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let registered: Vec<String> = self.statuses().into_iter().map(|status| status.name).collect();
+        for name in self.config.jobs.keys().filter(|name| !registered.contains(name)) {
+            tracing::warn!(job = %name, "configured job schedule matches no registered job");
+        }
+        
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(1));
+            loop {
+                ticker.tick().await;
+                for (name, task) in self.take_due() {
+                    let scheduler = self.clone();
+                    tokio::spawn(async move { scheduler.execute(name, task).await });
+                }
+            }
+        })
+    }
+    
+    /// Mark due jobs that are not already running as started, and schedule
+    /// their next runs
+    fn take_due(&self) -> Vec<(String, JobTask)> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().expect("scheduler lock poisoned");
+        let mut due = Vec::new();
+        for entry in entries.iter_mut() {
+            if !entry.status.next_run_at.is_some_and(|at| at <= now) {
+                continue;
+            }
+            entry.status.next_run_at =
+                entry.job.schedule.next_after(now).map(|next| next + random_jitter(entry.jitter));
+            if entry.status.running {
+                tracing::warn!(job = %entry.job.name, "job still running, skipping this run");
+                continue;
+            }
+            entry.status.running = true;
+            entry.status.last_started_at = Some(now);
+            due.push((entry.job.name.clone(), entry.job.task.clone()));
+        }
+        due
+    }
+    
+    async fn execute(&self, name: String, task: JobTask) {
+        let leading = match &self.leader {
+            Some(leader) => leader.acquire(&name).await,
+            None => true,
+        };
+        let outcome = if leading {
+            task().await.map(|()| JobOutcome::Succeeded)
+        } else {
+            Ok(JobOutcome::NotLeader)
+        };
+        if let Err(e) = &outcome {
+            tracing::warn!(job = %name, error = %e, "scheduled job failed");
+        }
+        
+        let finished_at = self.clock.now();
+        let mut entries = self.entries.lock().expect("scheduler lock poisoned");
+        let Some(entry) = entries.iter_mut().find(|entry| entry.job.name == name) else {
+            return;
+        };
+        let status = &mut entry.status;
+        status.running = false;
+        status.last_finished_at = Some(finished_at);
+        match outcome {
+            Ok(JobOutcome::NotLeader) => status.last_outcome = Some(JobOutcome::NotLeader),
+            Ok(outcome) => {
+                status.runs += 1;
+                status.last_outcome = Some(outcome);
+                status.last_error = None;
+            }
+            Err(e) => {
+                status.runs += 1;
+                status.failures += 1;
+                status.last_outcome = Some(JobOutcome::Failed);
+                status.last_error = Some(e.to_string());
+            }
+        }
+    }
+    }
+
+fn random_jitter(jitter: std::time::Duration) -> Duration {
+    let millis = jitter.as_millis() as i64;
+    if millis == 0 {
+        return Duration::zero();
+    }
+    Duration::milliseconds(rand::thread_rng().gen_range(0..=millis))
+}
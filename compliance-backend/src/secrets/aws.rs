@@ -0,0 +1,153 @@
+//! AWS Secrets Manager secret source
+
+use super::{secret_error, split_field, SecretSource};
+use crate::config::AwsSecretsConfig;
+use crate::Result;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+const TARGET: &str = "secretsmanager.GetSecretValue";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Reads secrets from AWS Secrets Manager using SigV4-signed requests
+///
+/// Credentials are taken from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+/// and optionally `AWS_SESSION_TOKEN`.
+pub struct AwsSecretsManagerSource {
+    http: reqwest::Client,
+    config: AwsSecretsConfig,
+}
+
+impl AwsSecretsManagerSource {
+    /// Create an AWS Secrets Manager source
+    pub fn new(config: AwsSecretsConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+        }
+    }
+    
+    fn host(&self) -> String {
+        format!("secretsmanager.{}.amazonaws.com", self.config.region)
+    }
+}
+
+impl SecretSource for AwsSecretsManagerSource {
+    fn scheme(&self) -> &'static str {
+        "aws-sm"
+    }
+    
+    /// Locator format: `secret-id` or `secret-id#json_field`
+    fn fetch<'a>(&'a self, locator: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let (secret_id, field) = split_field(locator);
+            let access_key = std::env::var("AWS_ACCESS_KEY_ID").map_err(|_| secret_error("AWS_ACCESS_KEY_ID is not set"))?;
+            let secret_key =
+                std::env::var("AWS_SECRET_ACCESS_KEY").map_err(|_| secret_error("AWS_SECRET_ACCESS_KEY is not set"))?;
+            let session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+            
+            let host = self.host();
+            let body = serde_json::json!({ "SecretId": secret_id }).to_string();
+            let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+            
+            let mut headers = vec![
+                ("content-type", CONTENT_TYPE.to_string()),
+                ("host", host.clone()),
+                ("x-amz-date", amz_date.clone()),
+            ];
+            if let Some(token) = &session_token {
+                headers.push(("x-amz-security-token", token.clone()));
+            }
+            headers.push(("x-amz-target", TARGET.to_string()));
+            
+            let authorization = sigv4_authorization(
+                &access_key,
+                &secret_key,
+                &self.config.region,
+                "secretsmanager",
+                &amz_date,
+                &headers,
+                body.as_bytes(),
+            );
+            
+            let mut request = self.http.post(format!("https://{}/", host)).body(body);
+            for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+                request = request.header(*name, value);
+            }
+            let response = request.header("authorization", authorization).send().await?;
+            if !response.status().is_success() {
+                return Err(secret_error(format!(
+                    "secrets manager returned {} for {}",
+                    response.status(),
+                    secret_id
+                )));
+            }
+            
+            let payload: serde_json::Value = response.json().await?;
+            let secret = payload["SecretString"]
+                .as_str()
+                .ok_or_else(|| secret_error(format!("secret {} has no SecretString", secret_id)))?;
+            
+            match field {
+                None => Ok(secret.to_string()),
+                Some(field) => {
+                    let fields: serde_json::Value = serde_json::from_str(secret)?;
+                    fields[field]
+                        .as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| secret_error(format!("secret {} has no string field {}", secret_id, field)))
+                }
+            }
+        })
+    }
+}
+
+/// Build a SigV4 `Authorization` header for a POST to `/`
+///
+/// `headers` must be sorted by lowercase name and include `host` and `x-amz-date`.
+pub(crate) fn sigv4_authorization(
+    access_key: &str,
+    secret_key: &str,
+    region: &str,
+    service: &str,
+    amz_date: &str,
+    headers: &[(&str, String)],
+    body: &[u8],
+) -> String {
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "POST\n/\n\n{}\n{}\n{}",
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(body))
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    
+    let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    let k_signing = hmac_sha256(&k_service, b"aws4_request");
+    let signature = hex::encode(hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+    
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, scope, signed_headers, signature
+    )
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,208 @@
+//! Resolution of secret references from external secret stores
+//!
+//! Sensitive configuration values may be given as references instead of
+//! plaintext:
+//!
+//! - `env:NAME` reads an environment variable
+//! - `file:/path/to/secret` reads a file (trailing newline trimmed)
+//! - `vault:mount/path#field` reads a HashiCorp Vault KV v2 secret field
+//! - `aws-sm:secret-id#field` reads an AWS Secrets Manager secret, optionally a JSON field
+//!
+//! Any other value is treated as a literal.
+
+pub mod aws;
+pub mod vault;
+
+use crate::config::SecretsConfig;
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// A source secrets can be fetched from
+pub trait SecretSource: Send + Sync {
+    /// Reference scheme handled by this source (e.g. "vault")
+    fn scheme(&self) -> &'static str;
+    
+    /// Fetch the secret identified by the part of the reference after the scheme
+    fn fetch<'a>(&'a self, locator: &'a str) -> BoxFuture<'a, Result<String>>;
+}
+
+/// Environment variable indirection
+pub struct EnvSource;
+
+impl SecretSource for EnvSource {
+    fn scheme(&self) -> &'static str {
+        "env"
+    }
+    
+    fn fetch<'a>(&'a self, locator: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            std::env::var(locator).map_err(|_| secret_error(format!("environment variable {} is not set", locator)))
+        })
+    }
+}
+
+/// Secrets mounted as files
+pub struct FileSource;
+
+impl SecretSource for FileSource {
+    fn scheme(&self) -> &'static str {
+        "file"
+    }
+    
+    fn fetch<'a>(&'a self, locator: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let contents = tokio::fs::read_to_string(locator).await?;
+            Ok(contents.trim_end_matches(['\n', '\r']).to_string())
+        })
+    }
+}
+
+/// A resolved secret and when it was fetched
+#[derive(Clone)]
+struct CachedSecret {
+    value: String,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Resolves secret references through registered sources, caching results
+pub struct SecretResolver {
+    sources: HashMap<&'static str, Arc<dyn SecretSource>>,
+    cache: RwLock<HashMap<String, CachedSecret>>,
+}
+
+impl SecretResolver {
+    /// Create a resolver with the environment and file sources
+    pub fn new() -> Self {
+        let mut resolver = Self {
+            sources: HashMap::new(),
+            cache: RwLock::new(HashMap::new()),
+        };
+        resolver.register(Arc::new(EnvSource));
+        resolver.register(Arc::new(FileSource));
+        resolver
+    }
+    
+    /// Create a resolver with every source enabled in configuration
+    pub fn from_config(config: &SecretsConfig) -> Self {
+        let mut resolver = Self::new();
+        if let Some(vault) = &config.vault {
+            resolver.register(Arc::new(vault::VaultSource::new(vault.clone())));
+        }
+        if let Some(aws) = &config.aws {
+            resolver.register(Arc::new(aws::AwsSecretsManagerSource::new(aws.clone())));
+        }
+        resolver
+    }
+    
+    /// Register a source, replacing any source with the same scheme
+    pub fn register(&mut self, source: Arc<dyn SecretSource>) {
+        self.sources.insert(source.scheme(), source);
+    }
+    
+    /// Check if a value is a reference to a registered source
+    pub fn is_reference(&self, value: &str) -> bool {
+        value
+            .split_once(':')
+            .is_some_and(|(scheme, _)| self.sources.contains_key(scheme))
+    }
+    
+    /// Resolve a value, returning literals unchanged
+    pub async fn resolve(&self, value: &str) -> Result<String> {
+        let Some((scheme, locator)) = value.split_once(':').filter(|(scheme, _)| self.sources.contains_key(scheme)) else {
+            return Ok(value.to_string());
+        };
+        
+        if let Some(cached) = self.cache.read().await.get(value) {
+            return Ok(cached.value.clone());
+        }
+        
+        let secret = self.sources[scheme].fetch(locator).await?;
+        self.cache.write().await.insert(
+            value.to_string(),
+            CachedSecret {
+                value: secret.clone(),
+                fetched_at: Utc::now(),
+            },
+        );
+        Ok(secret)
+    }
+    
+    /// Resolve a value in place
+    pub async fn resolve_in_place(&self, value: &mut String) -> Result<()> {
+        *value = self.resolve(value).await?;
+        Ok(())
+    }
+    
+    /// Re-fetch every cached secret, returning the references whose value changed
+    pub async fn refresh(&self) -> Vec<String> {
+        let references: Vec<String> = self.cache.read().await.keys().cloned().collect();
+        let mut rotated = Vec::new();
+        
+        for reference in references {
+            let Some((scheme, locator)) = reference.split_once(':') else { continue };
+            match self.sources[scheme].fetch(locator).await {
+                Ok(secret) => {
+                    let mut cache = self.cache.write().await;
+                    let changed = cache.get(&reference).map_or(true, |cached| cached.value != secret);
+                    cache.insert(
+                        reference.clone(),
+                        CachedSecret {
+                            value: secret,
+                            fetched_at: Utc::now(),
+                        },
+                    );
+                    if changed {
+                        rotated.push(reference);
+                    }
+                }
+                // Keep serving the last known value when a refresh fails
+                Err(e) => tracing::warn!(scheme, error = %e, "secret refresh failed"),
+            }
+        }
+        
+        rotated
+    }
+    
+    /// Time the oldest cached secret was fetched
+    pub async fn oldest_fetch(&self) -> Option<DateTime<Utc>> {
+        self.cache.read().await.values().map(|c| c.fetched_at).min()
+    }
+    
+    /// Periodically refresh cached secrets in the background
+    pub fn spawn_refresh(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let rotated = self.refresh().await;
+                if !rotated.is_empty() {
+                    tracing::info!(count = rotated.len(), "secrets rotated");
+                }
+            }
+        })
+    }
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn secret_error(message: impl Into<String>) -> ComplianceError {
+    ComplianceError::SecretResolution { message: message.into() }
+}
+
+/// Split a `path#field` locator
+pub(crate) fn split_field(locator: &str) -> (&str, Option<&str>) {
+    match locator.split_once('#') {
+        Some((path, field)) => (path, Some(field)),
+        None => (locator, None),
+    }
+}
@@ -0,0 +1,66 @@
+//! HashiCorp Vault KV v2 secret source
+
+use super::{secret_error, split_field, SecretResolver, SecretSource};
+use crate::config::VaultConfig;
+use crate::Result;
+use futures::future::BoxFuture;
+
+/// Reads secrets from a Vault KV v2 engine
+pub struct VaultSource {
+    http: reqwest::Client,
+    config: VaultConfig,
+}
+
+impl VaultSource {
+    /// Create a Vault source
+    pub fn new(config: VaultConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+        }
+    }
+    
+    async fn token(&self) -> Result<String> {
+        // The token itself may come from the environment or a file
+        SecretResolver::new().resolve(&self.config.token).await
+    }
+}
+
+impl SecretSource for VaultSource {
+    fn scheme(&self) -> &'static str {
+        "vault"
+    }
+    
+    /// Locator format: `mount/path#field`
+    fn fetch<'a>(&'a self, locator: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let (path, field) = split_field(locator);
+            let field = field.ok_or_else(|| secret_error(format!("vault reference {} has no #field", locator)))?;
+            let (mount, secret_path) = path
+                .split_once('/')
+                .ok_or_else(|| secret_error(format!("vault reference {} has no mount", locator)))?;
+            
+            let url = format!(
+                "{}/v1/{}/data/{}",
+                self.config.address.trim_end_matches('/'),
+                mount,
+                secret_path
+            );
+            let mut request = self.http.get(&url).header("X-Vault-Token", self.token().await?);
+            if let Some(namespace) = &self.config.namespace {
+                request = request.header("X-Vault-Namespace", namespace);
+            }
+            
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(secret_error(format!("vault returned {} for {}", response.status(), path)));
+            }
+            
+            let body: serde_json::Value = response.json().await?;
+            body["data"]["data"][field]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| secret_error(format!("vault secret {} has no string field {}", path, field)))
+        })
+    }
+}
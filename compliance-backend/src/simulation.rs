@@ -0,0 +1,195 @@
+//! Synthetic workload generators for benchmarks and load tests
+//!
+//! Enabled with the `bench` feature. Every generator is deterministic for a
+//! given seed, so benchmark runs are comparable across commits.
+
+use crate::compliance::country_risk::GeographicProfile;
+use crate::compliance::screening::{EntityType, ScreenedEntity};
+use crate::compliance::velocity::TransactionRequest;
+use crate::crypto::ProofHash;
+use crate::types::{AccountId, AmlRiskLevel, ComplianceAttestation, KycStatus};
+use chrono::{Duration, Utc};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use uuid::Uuid;
+
+const GIVEN_NAMES: &[&str] = &[
+    "Ahmed", "Alexei", "Ana", "Chen", "Dmitri", "Fatima", "Hassan", "Ivan", "Jose", "Kim", "Li", "Maria",
+    "Mohammed", "Nadia", "Olga", "Omar", "Pavel", "Sergei", "Viktor", "Wei", "Yusuf", "Zhang", "John", "Anna",
+];
+
+const SYLLABLES: &[&str] = &[
+    "al", "an", "ba", "chen", "dar", "ev", "go", "ha", "ib", "ko", "lo", "mar", "nov", "ov", "pet", "ra", "sh",
+    "sk", "ta", "ul", "vi", "wa", "ya", "zh", "in", "ez", "os", "ski", "enko", "ani",
+];
+
+const COUNTRIES: &[&str] = &[
+    "US", "GB", "DE", "FR", "CH", "SG", "AE", "NG", "ZA", "VN", "RU", "CN", "IR", "KP", "MM", "SY", "VE", "BR",
+];
+
+const TRANSACTION_TYPES: &[&str] = &["transfer", "withdrawal", "deposit", "swap"];
+
+/// A synthetic account holder
+#[derive(Debug, Clone)]
+pub struct SyntheticIdentity {
+    pub account_id: AccountId,
+    pub name: String,
+    pub residence: String,
+    pub document_issuing_country: String,
+    pub counterparty_countries: Vec<String>,
+}
+
+impl SyntheticIdentity {
+    /// Geographic profile for country-risk scoring
+    pub fn geographic_profile(&self) -> GeographicProfile {
+        GeographicProfile {
+            residence: Some(self.residence.clone()),
+            document_issuing_country: Some(self.document_issuing_country.clone()),
+            counterparty_countries: self.counterparty_countries.clone(),
+        }
+    }
+}
+
+/// Generate a sanctions list of `size` entities
+///
+/// Roughly half the entities carry aliases with spelling variations, as real
+/// consolidated lists do.
+pub fn sanctions_list(list: &str, size: usize, seed: u64) -> Vec<ScreenedEntity> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..size)
+        .map(|i| {
+            let name = person_name(&mut rng);
+            let aliases = if rng.gen_bool(0.5) {
+                (0..rng.gen_range(1..=3)).map(|_| misspell(&mut rng, &name)).collect()
+            } else {
+                vec![]
+            };
+            ScreenedEntity {
+                id: format!("{}-{}", list, i),
+                list: list.to_string(),
+                name,
+                aliases,
+                entity_type: if rng.gen_bool(0.85) {
+                    EntityType::Individual
+                } else {
+                    EntityType::Organization
+                },
+                programs: vec!["SIM".to_string()],
+                date_of_birth: Some(format!("19{:02}", rng.gen_range(40..99))),
+                nationalities: vec![pick(&mut rng, COUNTRIES).to_string()],
+                document_numbers: vec![],
+                addresses: vec![],
+            }
+        })
+        .collect()
+}
+
+/// Generate `count` identities
+///
+/// A `hit_rate` share of identities are given a misspelled name taken from
+/// `list`, so screening exercises both matching and non-matching paths.
+pub fn identities(count: usize, list: &[ScreenedEntity], hit_rate: f64, seed: u64) -> Vec<SyntheticIdentity> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| {
+            let name = match list.choose(&mut rng) {
+                Some(entity) if rng.gen_bool(hit_rate.clamp(0.0, 1.0)) => misspell(&mut rng, &entity.name),
+                _ => person_name(&mut rng),
+            };
+            let residence = pick(&mut rng, COUNTRIES).to_string();
+            SyntheticIdentity {
+                account_id: account_id(&mut rng),
+                name,
+                document_issuing_country: if rng.gen_bool(0.9) {
+                    residence.clone()
+                } else {
+                    pick(&mut rng, COUNTRIES).to_string()
+                },
+                residence,
+                counterparty_countries: (0..rng.gen_range(0..4))
+                    .map(|_| pick(&mut rng, COUNTRIES).to_string())
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Generate a stream of `count` transactions across the given identities
+///
+/// Amounts are log-uniform between 1 and 10^9 base units.
+pub fn transactions(
+    identities: &[SyntheticIdentity],
+    count: usize,
+    seed: u64,
+) -> Vec<(AccountId, TransactionRequest)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    if identities.is_empty() {
+        return vec![];
+    }
+    (0..count)
+        .map(|_| {
+            let identity = &identities[rng.gen_range(0..identities.len())];
+            let request = TransactionRequest {
+                amount: 10f64.powf(rng.gen_range(0.0..9.0)) as u64,
+                asset: Some("USDC".to_string()),
+                counterparty: Some(account_id(&mut rng).to_string()),
+                counterparty_country: identity.counterparty_countries.first().cloned(),
+                transaction_type: pick(&mut rng, TRANSACTION_TYPES).to_string(),
+            };
+            (identity.account_id.clone(), request)
+        })
+        .collect()
+}
+
+/// A verified attestation for an identity, as produced by a passing check
+pub fn attestation(identity: &SyntheticIdentity) -> ComplianceAttestation {
+    let now = Utc::now();
+    ComplianceAttestation {
+        id: Uuid::new_v4(),
+        account_id: identity.account_id.clone(),
+        kyc_status: KycStatus::Verified,
+        aml_risk_level: AmlRiskLevel::Low,
+        sanctions_cleared: true,
+        created_at: now,
+        expires_at: now + Duration::days(90),
+        proof_hash: ProofHash::of(identity.name.as_bytes()),
+        partial: Vec::new(),
+    }
+}
+
+fn person_name(rng: &mut StdRng) -> String {
+    let surname: String = (0..rng.gen_range(2..=3)).map(|_| pick(rng, SYLLABLES)).collect();
+    let mut chars = surname.chars();
+    let surname = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => surname,
+    };
+    format!("{} {}", pick(rng, GIVEN_NAMES), surname)
+}
+
+/// Apply one typo: drop, duplicate, or swap adjacent characters
+fn misspell(rng: &mut StdRng, name: &str) -> String {
+    let mut chars: Vec<char> = name.chars().collect();
+    if chars.len() < 3 {
+        return name.to_string();
+    }
+    let i = rng.gen_range(1..chars.len() - 1);
+    match rng.gen_range(0..3) {
+        0 => {
+            chars.remove(i);
+        }
+        1 => chars.insert(i, chars[i]),
+        _ => chars.swap(i, i + 1),
+    }
+    chars.into_iter().collect()
+}
+
+fn account_id(rng: &mut StdRng) -> AccountId {
+    let digits: [u8; 15] = rng.gen();
+    AccountId::parse(&format!("0x{}", hex::encode(digits))).expect("30 hex digits form a Miden account id")
+}
+
+fn pick<'a>(rng: &mut StdRng, items: &[&'a str]) -> &'a str {
+    items[rng.gen_range(0..items.len())]
+}
@@ -0,0 +1,301 @@
+//! In-process repositories
+//!
+//! Records live for the lifetime of the process. Used by default and in
+//! tests.
+
+use super::{
+    sequence_conflict, AttestationRepo, AuditRepo, CaseRepo, ClientRepo, OutboxMessage, OutboxRepo, UnitOfWork,
+    UsageRepo, WriteBatch,
+};
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::compliance::approvals::ApprovalRequest;
+use crate::compliance::attestation_events::RecordedEvent;
+use crate::compliance::metering::UsageRecord;
+use crate::types::{AccountId, BusinessClient};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Business clients held in memory
+#[derive(Default)]
+pub struct MemoryClientRepo {
+    clients: RwLock<HashMap<Uuid, BusinessClient>>,
+}
+
+impl ClientRepo for MemoryClientRepo {
+    fn get(&self, client_id: Uuid) -> BoxFuture<'_, Result<Option<BusinessClient>>> {
+        Box::pin(async move { Ok(self.clients.read().await.get(&client_id).cloned()) })
+    }
+    
+    fn find_by_api_key<'a>(&'a self, api_key: &'a str) -> BoxFuture<'a, Result<Option<BusinessClient>>> {
+        Box::pin(async move {
+            Ok(self
+                .clients
+                .read()
+                .await
+                .values()
+                .find(|client| {
+                    client.api_key == api_key
+                        || client.sandbox.as_ref().is_some_and(|sandbox| sandbox.api_key == api_key)
+                })
+                .cloned())
+        })
+    }
+    
+    fn list(&self) -> BoxFuture<'_, Result<Vec<BusinessClient>>> {
+        Box::pin(async move { Ok(self.clients.read().await.values().cloned().collect()) })
+    }
+    
+    fn save<'a>(&'a self, client: &'a BusinessClient) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.clients.write().await.insert(client.id, client.clone());
+            Ok(())
+        })
+    }
+}
+
+/// Audit chain held in memory
+#[derive(Default)]
+pub struct MemoryAuditRepo {
+    entries: RwLock<Vec<AuditEntry>>,
+}
+
+impl AuditRepo for MemoryAuditRepo {
+    fn head(&self) -> BoxFuture<'_, Result<Option<AuditEntry>>> {
+        Box::pin(async move { Ok(self.entries.read().await.last().cloned()) })
+    }
+    
+    fn append<'a>(&'a self, entry: &'a AuditEntry) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut entries = self.entries.write().await;
+            let expected = entries.len() as u64 + 1;
+            if entry.sequence != expected {
+                return Err(sequence_conflict("audit entry", expected, entry.sequence));
+            }
+            entries.push(entry.clone());
+            Ok(())
+        })
+    }
+    
+    fn find<'a>(
+        &'a self,
+        query: &'a AuditQuery,
+        after: u64,
+        limit: Option<usize>,
+    ) -> BoxFuture<'a, Result<Vec<AuditEntry>>> {
+        Box::pin(async move {
+            // Sequences start at 1 and are contiguous, so `after` is an index
+            Ok(self
+                .entries
+                .read()
+                .await
+                .iter()
+                .skip(after as usize)
+                .filter(|entry| query.matches(entry))
+                .take(limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect())
+        })
+    }
+}
+
+/// Attestation event streams held in memory
+#[derive(Default)]
+pub struct MemoryAttestationRepo {
+    streams: RwLock<HashMap<AccountId, Vec<RecordedEvent>>>,
+}
+
+impl AttestationRepo for MemoryAttestationRepo {
+    fn last_sequence<'a>(&'a self, account_id: &'a AccountId) -> BoxFuture<'a, Result<u64>> {
+        Box::pin(async move { Ok(self.streams.read().await.get(account_id).map_or(0, |s| s.len() as u64)) })
+    }
+    
+    fn append<'a>(&'a self, event: &'a RecordedEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut streams = self.streams.write().await;
+            let stream = streams.entry(event.account_id.clone()).or_default();
+            let expected = stream.len() as u64 + 1;
+            if event.sequence != expected {
+                return Err(sequence_conflict("attestation event", expected, event.sequence));
+            }
+            stream.push(event.clone());
+            Ok(())
+        })
+    }
+    
+    fn stream<'a>(&'a self, account_id: &'a AccountId) -> BoxFuture<'a, Result<Vec<RecordedEvent>>> {
+        Box::pin(async move { Ok(self.streams.read().await.get(account_id).cloned().unwrap_or_default()) })
+    }
+    
+    fn accounts(&self) -> BoxFuture<'_, Result<Vec<AccountId>>> {
+        Box::pin(async move { Ok(self.streams.read().await.keys().cloned().collect()) })
+    }
+}
+
+/// Approval cases held in memory
+#[derive(Default)]
+pub struct MemoryCaseRepo {
+    requests: RwLock<HashMap<Uuid, ApprovalRequest>>,
+}
+
+impl CaseRepo for MemoryCaseRepo {
+    fn get(&self, approval_id: Uuid) -> BoxFuture<'_, Result<Option<ApprovalRequest>>> {
+        Box::pin(async move { Ok(self.requests.read().await.get(&approval_id).cloned()) })
+    }
+    
+    fn list(&self) -> BoxFuture<'_, Result<Vec<ApprovalRequest>>> {
+        Box::pin(async move { Ok(self.requests.read().await.values().cloned().collect()) })
+    }
+    
+    fn save<'a>(&'a self, request: &'a ApprovalRequest) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.requests.write().await.insert(request.id, request.clone());
+            Ok(())
+        })
+    }
+}
+
+/// Usage counters held in memory
+#[derive(Default)]
+pub struct MemoryUsageRepo {
+    records: RwLock<HashMap<(Uuid, DateTime<Utc>), UsageRecord>>,
+}
+
+impl UsageRepo for MemoryUsageRepo {
+    fn get(&self, client_id: Uuid, period_start: DateTime<Utc>) -> BoxFuture<'_, Result<Option<UsageRecord>>> {
+        Box::pin(async move { Ok(self.records.read().await.get(&(client_id, period_start)).cloned()) })
+    }
+    
+    fn period(&self, period_start: DateTime<Utc>) -> BoxFuture<'_, Result<Vec<UsageRecord>>> {
+        Box::pin(async move {
+            Ok(self
+                .records
+                .read()
+                .await
+                .values()
+                .filter(|record| record.period_start == period_start)
+                .cloned()
+                .collect())
+        })
+    }
+}
+
+/// Webhook outbox held in memory
+#[derive(Default)]
+pub struct MemoryOutboxRepo {
+    messages: RwLock<Vec<OutboxMessage>>,
+}
+
+impl MemoryOutboxRepo {
+    async fn update(&self, message_id: Uuid, f: impl FnOnce(&mut OutboxMessage)) -> Result<()> {
+        let mut messages = self.messages.write().await;
+        let message = messages
+            .iter_mut()
+            .find(|message| message.id == message_id)
+            .ok_or_else(|| ComplianceError::internal(format!("outbox message {} not found", message_id)))?;
+        f(message);
+        Ok(())
+    }
+}
+
+impl OutboxRepo for MemoryOutboxRepo {
+    fn pending(&self, limit: usize) -> BoxFuture<'_, Result<Vec<OutboxMessage>>> {
+        Box::pin(async move {
+            Ok(self
+                .messages
+                .read()
+                .await
+                .iter()
+                .filter(|message| message.delivered_at.is_none() && message.abandoned_at.is_none())
+                .take(limit)
+                .cloned()
+                .collect())
+        })
+    }
+    
+    fn mark_delivered(&self, message_id: Uuid, at: DateTime<Utc>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.update(message_id, move |message| message.delivered_at = Some(at)))
+    }
+    
+    fn record_failure(&self, message_id: Uuid, abandoned_at: Option<DateTime<Utc>>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.update(message_id, move |message| {
+            message.attempts += 1;
+            message.abandoned_at = abandoned_at;
+        }))
+    }
+    
+    fn for_account<'a>(&'a self, account_id: &'a AccountId) -> BoxFuture<'a, Result<Vec<OutboxMessage>>> {
+        Box::pin(async move {
+            let account_id = serde_json::to_value(account_id)?;
+            Ok(self
+                .messages
+                .read()
+                .await
+                .iter()
+                .filter(|message| message.payload.get("account_id") == Some(&account_id))
+                .cloned()
+                .collect())
+        })
+    }
+}
+
+/// Every repository held in memory, with batches committed across them atomically
+///
+/// Services must be built on these repositories for their batches to land
+/// alongside their individual writes.
+#[derive(Default)]
+pub struct MemoryStore {
+    pub clients: Arc<MemoryClientRepo>,
+    pub audit: Arc<MemoryAuditRepo>,
+    pub attestations: Arc<MemoryAttestationRepo>,
+    pub cases: Arc<MemoryCaseRepo>,
+    pub outbox: Arc<MemoryOutboxRepo>,
+    pub usage: Arc<MemoryUsageRepo>,
+}
+
+impl UnitOfWork for MemoryStore {
+    fn commit<'a>(&'a self, batch: &'a WriteBatch) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            // Every lock is held and every sequence checked before anything is written
+            let mut streams = self.attestations.streams.write().await;
+            let mut entries = self.audit.entries.write().await;
+            let mut requests = self.cases.requests.write().await;
+            let mut messages = self.outbox.messages.write().await;
+            let mut usage = self.usage.records.write().await;
+            
+            let mut next: HashMap<&AccountId, u64> = HashMap::new();
+            for event in &batch.events {
+                let expected = next
+                    .entry(&event.account_id)
+                    .or_insert_with(|| streams.get(&event.account_id).map_or(0, |s| s.len() as u64) + 1);
+                if event.sequence != *expected {
+                    return Err(sequence_conflict("attestation event", *expected, event.sequence));
+                }
+                *expected += 1;
+            }
+            for (i, entry) in batch.audit.iter().enumerate() {
+                let expected = (entries.len() + i) as u64 + 1;
+                if entry.sequence != expected {
+                    return Err(sequence_conflict("audit entry", expected, entry.sequence));
+                }
+            }
+            
+            for event in &batch.events {
+                streams.entry(event.account_id.clone()).or_default().push(event.clone());
+            }
+            entries.extend(batch.audit.iter().cloned());
+            for request in &batch.cases {
+                requests.insert(request.id, request.clone());
+            }
+            messages.extend(batch.outbox.iter().cloned());
+            for record in &batch.usage {
+                usage.insert((record.client_id, record.period_start), record.clone());
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,186 @@
+//! Persistence behind the compliance services
+//!
+//! Services keep their rules (hash chaining, stream sequencing, the
+//! four-eyes check) and hand finished records to a repository, so storage
+//! backends can be swapped without touching them. A backend implements the
+//! repository traits; [`memory`] keeps records in process, for tests and
+//! single-node deployments.
+//!
+//! Repositories are not expected to serialize writers: each service holds a
+//! lock around its read-then-write sequences. A backend shared by several
+//! processes must instead reject an append whose sequence is already taken.
+//!
+//! Records that must not exist without each other, such as an issued
+//! attestation and its audit entry, are written together through a
+//! [`UnitOfWork`]. Webhook notifications go through the same batch into an
+//! outbox and are delivered from there, so a notification is never sent for
+//! records that were rolled back, nor lost for records that were committed.
+
+pub mod memory;
+
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::compliance::approvals::ApprovalRequest;
+use crate::compliance::attestation_events::RecordedEvent;
+use crate::compliance::metering::UsageRecord;
+use crate::types::{AccountId, BusinessClient};
+use crate::Result;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Storage of business clients
+pub trait ClientRepo: Send + Sync {
+    /// Get a client by id
+    fn get(&self, client_id: Uuid) -> BoxFuture<'_, Result<Option<BusinessClient>>>;
+    
+    /// Find the client owning an API key, production or sandbox
+    fn find_by_api_key<'a>(&'a self, api_key: &'a str) -> BoxFuture<'a, Result<Option<BusinessClient>>>;
+    
+    /// Every client
+    fn list(&self) -> BoxFuture<'_, Result<Vec<BusinessClient>>>;
+    
+    /// Insert a client or replace the one with the same id
+    fn save<'a>(&'a self, client: &'a BusinessClient) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Storage of the hash-chained audit log
+pub trait AuditRepo: Send + Sync {
+    /// Last entry of the chain
+    fn head(&self) -> BoxFuture<'_, Result<Option<AuditEntry>>>;
+    
+    /// Append an entry; fails if its sequence does not follow the head's
+    fn append<'a>(&'a self, entry: &'a AuditEntry) -> BoxFuture<'a, Result<()>>;
+    
+    /// Entries matching `query` with a sequence above `after`, oldest first,
+    /// at most `limit` of them when given
+    fn find<'a>(
+        &'a self,
+        query: &'a AuditQuery,
+        after: u64,
+        limit: Option<usize>,
+    ) -> BoxFuture<'a, Result<Vec<AuditEntry>>>;
+}
+
+/// Storage of attestation lifecycle events, one stream per account
+pub trait AttestationRepo: Send + Sync {
+    /// Sequence of the last event in an account's stream; 0 for an empty stream
+    fn last_sequence<'a>(&'a self, account_id: &'a AccountId) -> BoxFuture<'a, Result<u64>>;
+    
+    /// Append an event; fails if its sequence does not follow the stream's last
+    fn append<'a>(&'a self, event: &'a RecordedEvent) -> BoxFuture<'a, Result<()>>;
+    
+    /// An account's events, oldest first
+    fn stream<'a>(&'a self, account_id: &'a AccountId) -> BoxFuture<'a, Result<Vec<RecordedEvent>>>;
+    
+    /// Accounts with at least one event
+    fn accounts(&self) -> BoxFuture<'_, Result<Vec<AccountId>>>;
+}
+
+/// Storage of override approval cases
+pub trait CaseRepo: Send + Sync {
+    /// Get a case by id
+    fn get(&self, approval_id: Uuid) -> BoxFuture<'_, Result<Option<ApprovalRequest>>>;
+    
+    /// Every case
+    fn list(&self) -> BoxFuture<'_, Result<Vec<ApprovalRequest>>>;
+    
+    /// Insert a case or replace the one with the same id
+    fn save<'a>(&'a self, request: &'a ApprovalRequest) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Storage of per-client usage counters, one record per client and billing period
+pub trait UsageRepo: Send + Sync {
+    /// A client's usage in the period starting at `period_start`
+    fn get(&self, client_id: Uuid, period_start: DateTime<Utc>) -> BoxFuture<'_, Result<Option<UsageRecord>>>;
+    
+    /// Every client's usage in the period starting at `period_start`
+    fn period(&self, period_start: DateTime<Utc>) -> BoxFuture<'_, Result<Vec<UsageRecord>>>;
+}
+
+/// Webhook notification awaiting delivery to a business client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+    
+    /// Client whose webhook receives the notification
+    pub client_id: Uuid,
+    
+    /// Dotted event name (e.g. "attestation.issued")
+    pub event_type: String,
+    
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+    
+    pub delivered_at: Option<DateTime<Utc>>,
+    
+    /// Set once delivery is given up on
+    pub abandoned_at: Option<DateTime<Utc>>,
+}
+
+impl OutboxMessage {
+    /// Create an undelivered message
+    pub fn new(client_id: Uuid, event_type: &str, payload: serde_json::Value, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            client_id,
+            event_type: event_type.to_string(),
+            payload,
+            created_at,
+            attempts: 0,
+            delivered_at: None,
+            abandoned_at: None,
+        }
+    }
+}
+
+/// Storage of webhook notifications written alongside the records they announce
+pub trait OutboxRepo: Send + Sync {
+    /// Messages neither delivered nor abandoned, oldest first, at most `limit` of them
+    fn pending(&self, limit: usize) -> BoxFuture<'_, Result<Vec<OutboxMessage>>>;
+    
+    /// Mark a message delivered
+    fn mark_delivered(&self, message_id: Uuid, at: DateTime<Utc>) -> BoxFuture<'_, Result<()>>;
+    
+    /// Count a failed delivery attempt, giving up on the message when `abandoned_at` is set
+    fn record_failure(&self, message_id: Uuid, abandoned_at: Option<DateTime<Utc>>) -> BoxFuture<'_, Result<()>>;
+    
+    /// Every message whose payload names the account, delivered or not, oldest first
+    fn for_account<'a>(&'a self, account_id: &'a AccountId) -> BoxFuture<'a, Result<Vec<OutboxMessage>>>;
+}
+
+/// Records written together or not at all
+#[derive(Debug, Clone, Default)]
+pub struct WriteBatch {
+    /// Attestation events, in stream order per account
+    pub events: Vec<RecordedEvent>,
+    
+    /// Audit entries, in chain order
+    pub audit: Vec<AuditEntry>,
+    
+    pub outbox: Vec<OutboxMessage>,
+    
+    /// Cases to insert or replace
+    pub cases: Vec<ApprovalRequest>,
+    
+    /// Usage records to insert or replace
+    pub usage: Vec<UsageRecord>,
+}
+
+/// Atomic writes across repositories
+///
+/// A database backend commits a batch in one transaction. Appends in the
+/// batch are sequence-checked as they are when made one at a time, and a
+/// conflict anywhere rejects the whole batch.
+pub trait UnitOfWork: Send + Sync {
+    /// Write every record in `batch`, or none of them
+    fn commit<'a>(&'a self, batch: &'a WriteBatch) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Error for an append whose sequence is already taken or skips ahead
+pub(crate) fn sequence_conflict(what: &str, expected: u64, got: u64) -> crate::ComplianceError {
+    crate::ComplianceError::internal(format!("{} append out of sequence: expected {}, got {}", what, expected, got))
+}
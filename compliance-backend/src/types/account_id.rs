@@ -0,0 +1,181 @@
+//! Validated account identifiers
+
+use crate::{ComplianceError, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha3::{Digest, Keccak256};
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// Human-readable prefixes of bech32 Miden account ids (mainnet, testnet, devnet)
+const MIDEN_BECH32_HRPS: [&str; 3] = ["mm", "mtst", "mdev"];
+
+/// Hex digits in a Miden account id (120 bits)
+const MIDEN_HEX_DIGITS: usize = 30;
+
+/// Address type byte leading the bech32 payload of a Miden account id
+const MIDEN_BECH32_ACCOUNT_ID_TYPE: u8 = 0;
+
+/// Bytes in the bech32 payload of a Miden account id: address type and id
+const MIDEN_BECH32_PAYLOAD_BYTES: usize = 1 + MIDEN_HEX_DIGITS / 2;
+
+/// Hex digits in an EVM address (160 bits)
+const EVM_HEX_DIGITS: usize = 40;
+
+/// Kind of account an identifier refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountIdKind {
+    /// Miden account, in hex or bech32 form
+    Miden,
+    /// EVM address of a linked account
+    Evm,
+}
+
+/// Validated and normalized account identifier
+///
+/// Accepts Miden account ids as `0x`-prefixed hex or bech32, and EVM addresses
+/// for linked accounts. Identifiers are normalized to lowercase `0x` hex, so
+/// the bech32 and hex forms of an account share one id; mixed-case EVM
+/// addresses must carry a valid EIP-55 checksum.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
+pub struct AccountId(String);
+
+impl AccountId {
+    /// Validate and normalize an account identifier
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        if let Some(digits) = raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+            if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid("hex account id contains non-hex characters"));
+            }
+            return match digits.len() {
+                MIDEN_HEX_DIGITS => Ok(Self(format!("0x{}", digits.to_ascii_lowercase()))),
+                EVM_HEX_DIGITS => {
+                    check_eip55(digits)?;
+                    Ok(Self(format!("0x{}", digits.to_ascii_lowercase())))
+                }
+                len => Err(invalid(format!(
+                    "hex account id must have {} (Miden) or {} (EVM) digits, got {}",
+                    MIDEN_HEX_DIGITS, EVM_HEX_DIGITS, len
+                ))),
+            };
+        }
+        
+        let (hrp, data) = bech32::decode(raw).map_err(|e| invalid(format!("not a hex or bech32 account id: {}", e)))?;
+        if !MIDEN_BECH32_HRPS.contains(&hrp.as_str()) {
+            return Err(invalid(format!("unknown network prefix {}", hrp)));
+        }
+        match data.split_first() {
+            Some((&MIDEN_BECH32_ACCOUNT_ID_TYPE, id)) if data.len() == MIDEN_BECH32_PAYLOAD_BYTES => {
+                Ok(Self(format!("0x{}", hex::encode(id))))
+            }
+            Some((&MIDEN_BECH32_ACCOUNT_ID_TYPE, _)) => Err(invalid(format!(
+                "bech32 account id payload must have {} bytes, got {}",
+                MIDEN_BECH32_PAYLOAD_BYTES,
+                data.len()
+            ))),
+            Some((address_type, _)) => Err(invalid(format!(
+                "bech32 address type {} is not an account id",
+                address_type
+            ))),
+            None => Err(invalid("bech32 account id has no payload")),
+        }
+    }
+    
+    /// Normalized identifier
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    
+    /// Kind of account the identifier refers to
+    pub fn kind(&self) -> AccountIdKind {
+        if self.0.len() == EVM_HEX_DIGITS + 2 && self.0.starts_with("0x") {
+            AccountIdKind::Evm
+        } else {
+            AccountIdKind::Miden
+        }
+    }
+}
+
+/// Verify the EIP-55 checksum of a mixed-case EVM address
+fn check_eip55(digits: &str) -> Result<()> {
+    let lower = digits.to_ascii_lowercase();
+    if digits == lower || digits == digits.to_ascii_uppercase() {
+        return Ok(());
+    }
+    
+    let hash = Keccak256::digest(lower.as_bytes());
+    for (i, c) in digits.chars().enumerate() {
+        let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+        if c.is_ascii_alphabetic() && c.is_ascii_uppercase() != (nibble >= 8) {
+            return Err(invalid("EVM address checksum mismatch"));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(reason: impl Into<String>) -> ComplianceError {
+    ComplianceError::validation("account_id", reason)
+}
+
+impl Deref for AccountId {
+    type Target = str;
+    
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for AccountId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for AccountId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for AccountId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for AccountId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for AccountId {
+    type Err = ComplianceError;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::parse(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<AccountId> for String {
+    fn from(id: AccountId) -> Self {
+        id.0
+    }
+}
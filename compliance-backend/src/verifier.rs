@@ -0,0 +1,132 @@
+//! Offline verification of proof envelopes
+//!
+//! Available with only the `verifier` feature, so third parties can check
+//! envelopes inside their own services without a database, HTTP server, or
+//! Miden client store. Verification covers the canonical encoding, issuer
+//! signature, lifetime, audience, scope, and disclosed claims; single-use
+//! enforcement of the challenge nonce is left to the verifier that issued it.
+
+use crate::compliance::claims::{Claim, ClaimKind};
+use crate::compliance::proof_envelope::ProofEnvelope;
+use crate::compliance::scope::{ProofScope, ScopeUsage};
+use crate::crypto::TrustedKeys;
+use crate::types::{AccountId, ComplianceLevel};
+use crate::{ComplianceError, Result};
+use chrono::{DateTime, Utc};
+
+/// Default maximum size of the embedded proof, matching the backend default
+pub const DEFAULT_MAX_PROOF_SIZE: usize = 1024 * 1024;
+
+/// What a verifier requires of a proof envelope
+#[derive(Debug, Clone)]
+pub struct VerificationPolicy {
+    /// Audience of the verifier; envelopes issued for anyone else are rejected
+    pub audience: String,
+    
+    /// How the verifier intends to rely on the proof, checked against its scope
+    pub usage: ScopeUsage,
+    
+    /// Compliance level the proof's scope must attest; unscoped proofs carry no level
+    pub required_level: Option<ComplianceLevel>,
+    
+    /// Claims the envelope must disclose
+    pub required_claims: Vec<ClaimKind>,
+    
+    pub max_proof_size: usize,
+    
+    /// Time to check the envelope lifetime against (defaults to now)
+    pub at: Option<DateTime<Utc>>,
+}
+
+impl VerificationPolicy {
+    /// Policy for an audience with default limits and no scope requirements
+    pub fn new(audience: impl Into<String>) -> Self {
+        Self {
+            audience: audience.into(),
+            usage: ScopeUsage::default(),
+            required_level: None,
+            required_claims: vec![],
+            max_proof_size: DEFAULT_MAX_PROOF_SIZE,
+            at: None,
+        }
+    }
+}
+
+/// Claims of an envelope that passed verification
+#[derive(Debug, Clone)]
+pub struct VerifiedProof {
+    pub account_id: AccountId,
+    pub audience: String,
+    pub nonce: String,
+    
+    /// Identifier of the trusted key that signed the envelope
+    pub key_id: String,
+    
+    pub attestation_commitment: [u8; 32],
+    pub claims_root: [u8; 32],
+    
+    /// Disclosed claims, each checked against `claims_root`
+    pub claims: Vec<Claim>,
+    
+    pub scope: Option<ProofScope>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    
+    /// Embedded Miden STARK proof, inflated if it was compressed, for callers
+    /// that also run the Miden verifier
+    pub proof_bytes: Vec<u8>,
+}
+
+/// Decode and verify an encoded proof envelope against a policy
+pub fn verify_proof_envelope(
+    envelope: &str,
+    trusted_keys: &TrustedKeys,
+    policy: &VerificationPolicy,
+) -> Result<VerifiedProof> {
+    let envelope = ProofEnvelope::decode(envelope)?;
+    envelope.validate(
+        trusted_keys,
+        &policy.audience,
+        policy.max_proof_size,
+        policy.at.unwrap_or_else(Utc::now),
+    )?;
+    
+    if let Some(scope) = &envelope.scope {
+        scope
+            .permits(&policy.usage)
+            .map_err(|reason| ComplianceError::InvalidProof { reason })?;
+    }
+    if let Some(required) = &policy.required_level {
+        let attested = envelope.scope.as_ref().and_then(|scope| scope.min_level.as_ref());
+        if attested.is_none_or(|level| level < required) {
+            return Err(ComplianceError::InvalidProof {
+                reason: format!("proof does not attest {:?} compliance", required),
+            });
+        }
+    }
+    
+    if let Some(missing) = policy
+        .required_claims
+        .iter()
+        .find(|kind| !envelope.claims.iter().any(|disclosed| disclosed.claim.kind() == **kind))
+    {
+        return Err(ComplianceError::InvalidProof {
+            reason: format!("proof does not disclose the {:?} claim", missing),
+        });
+    }
+    
+    let proof_bytes = envelope.proof()?;
+    Ok(VerifiedProof {
+        issued_at: DateTime::from_timestamp(envelope.issued_at, 0),
+        expires_at: envelope.expires_at(),
+        account_id: envelope.account_id,
+        audience: envelope.audience,
+        nonce: envelope.nonce,
+        key_id: envelope.key_id,
+        attestation_commitment: envelope.attestation_commitment,
+        claims_root: envelope.claims_root,
+        claims: envelope.claims.into_iter().map(|disclosed| disclosed.claim).collect(),
+        scope: envelope.scope,
+        proof_bytes,
+    })
+}
@@ -0,0 +1,102 @@
+//! WebAssembly bindings for client-side proof verification
+//!
+//! Build with `wasm-pack build --no-default-features --features wasm` to get a
+//! module browsers and Node SDKs can use to verify proof envelopes and
+//! attestation signatures without a server round trip.
+
+use crate::compliance::claims::{Claim, ClaimKind};
+use crate::compliance::scope::ScopeUsage;
+use crate::crypto::TrustedKeys;
+use crate::types::ComplianceLevel;
+use crate::verifier::{self, VerificationPolicy, DEFAULT_MAX_PROOF_SIZE};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Verification policy as passed from JavaScript
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsPolicy {
+    audience: String,
+    #[serde(default)]
+    usage: ScopeUsage,
+    required_level: Option<ComplianceLevel>,
+    #[serde(default)]
+    required_claims: Vec<ClaimKind>,
+    max_proof_size: Option<usize>,
+    /// Unix timestamp (seconds) to check the envelope lifetime against
+    at: Option<i64>,
+}
+
+/// Verified envelope claims as returned to JavaScript
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsVerifiedProof {
+    account_id: String,
+    audience: String,
+    nonce: String,
+    key_id: String,
+    /// Hex-encoded
+    attestation_commitment: String,
+    /// Hex-encoded
+    claims_root: String,
+    claims: Vec<Claim>,
+    scope: Option<crate::compliance::scope::ProofScope>,
+    issued_at: Option<i64>,
+    expires_at: Option<i64>,
+}
+
+/// Verify an encoded proof envelope
+///
+/// `trustedKeys` maps key ids to hex-encoded Ed25519 public keys. Throws with
+/// the rejection reason if the envelope does not satisfy the policy.
+#[wasm_bindgen(js_name = verifyProofEnvelope)]
+pub fn verify_proof_envelope(envelope: &str, trusted_keys: JsValue, policy: JsValue) -> Result<JsValue, JsError> {
+    let trusted = trusted_keys_from_js(trusted_keys)?;
+    let policy: JsPolicy = serde_wasm_bindgen::from_value(policy)?;
+    let policy = VerificationPolicy {
+        audience: policy.audience,
+        usage: policy.usage,
+        required_level: policy.required_level,
+        required_claims: policy.required_claims,
+        max_proof_size: policy.max_proof_size.unwrap_or(DEFAULT_MAX_PROOF_SIZE),
+        at: policy.at.and_then(|at| DateTime::from_timestamp(at, 0)),
+    };
+    
+    let verified = verifier::verify_proof_envelope(envelope, &trusted, &policy)?;
+    let result = JsVerifiedProof {
+        account_id: verified.account_id.to_string(),
+        audience: verified.audience,
+        nonce: verified.nonce,
+        key_id: verified.key_id,
+        attestation_commitment: hex::encode(verified.attestation_commitment),
+        claims_root: hex::encode(verified.claims_root),
+        claims: verified.claims,
+        scope: verified.scope,
+        issued_at: verified.issued_at.map(|t| t.timestamp()),
+        expires_at: verified.expires_at.map(|t| t.timestamp()),
+    };
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// Verify an Ed25519 signature over an attestation artifact made by a trusted key
+#[wasm_bindgen(js_name = verifyAttestationSignature)]
+pub fn verify_attestation_signature(
+    trusted_keys: JsValue,
+    key_id: &str,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), JsError> {
+    let trusted = trusted_keys_from_js(trusted_keys)?;
+    Ok(trusted.verify(key_id, message, signature)?)
+}
+
+fn trusted_keys_from_js(value: JsValue) -> Result<TrustedKeys, JsError> {
+    let keys: HashMap<String, String> = serde_wasm_bindgen::from_value(value)?;
+    let mut trusted = TrustedKeys::new();
+    for (key_id, key) in keys {
+        trusted.insert_hex(key_id, &key)?;
+    }
+    Ok(trusted)
+}
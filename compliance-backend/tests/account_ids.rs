@@ -0,0 +1,102 @@
+//! Account id parsing and normalization
+
+use bech32::{Bech32m, Hrp};
+use compliance_backend::types::{AccountId, AccountIdKind};
+use compliance_backend::ComplianceError;
+
+const MIDEN_ID: &str = "0x0123456789abcdef0123456789abcd";
+
+/// EIP-55 checksummed address from the EIP's test vectors
+const CHECKSUMMED: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+fn bech32(hrp: &str, payload: &[u8]) -> String {
+    bech32::encode::<Bech32m>(Hrp::parse(hrp).unwrap(), payload).unwrap()
+}
+
+fn miden_payload() -> Vec<u8> {
+    let mut payload = vec![0u8];
+    payload.extend(hex::decode(&MIDEN_ID[2..]).unwrap());
+    payload
+}
+
+fn rejected(raw: &str) -> String {
+    match AccountId::parse(raw) {
+        Err(ComplianceError::Validation { field, message }) => {
+            assert_eq!(field, "account_id");
+            message
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn miden_hex_ids_are_lowercased() {
+    let id = AccountId::parse(&format!("  0X{}  ", MIDEN_ID[2..].to_uppercase())).unwrap();
+    assert_eq!(id, MIDEN_ID);
+    assert_eq!(id.kind(), AccountIdKind::Miden);
+    assert_eq!(MIDEN_ID.parse::<AccountId>().unwrap(), id);
+}
+
+#[test]
+fn bech32_ids_share_the_hex_id() {
+    for hrp in ["mm", "mtst", "mdev"] {
+        let id = AccountId::parse(&bech32(hrp, &miden_payload())).unwrap();
+        assert_eq!(id, MIDEN_ID);
+        assert_eq!(id.kind(), AccountIdKind::Miden);
+    }
+}
+
+#[test]
+fn bech32_payloads_must_be_account_ids() {
+    let mut short = miden_payload();
+    short.pop();
+    assert!(rejected(&bech32("mtst", &short)).contains("16 bytes, got 15"));
+    
+    let mut long = miden_payload();
+    long.push(0);
+    assert!(rejected(&bech32("mtst", &long)).contains("16 bytes, got 17"));
+    
+    let mut note = miden_payload();
+    note[0] = 1;
+    assert!(rejected(&bech32("mtst", &note)).contains("address type 1"));
+    
+    assert!(rejected(&bech32("mtst", &[])).contains("no payload"));
+    assert!(rejected(&bech32("bc", &miden_payload())).contains("unknown network prefix bc"));
+}
+
+#[test]
+fn evm_addresses_are_checked_and_lowercased() {
+    let id = AccountId::parse(CHECKSUMMED).unwrap();
+    assert_eq!(id, CHECKSUMMED.to_lowercase().as_str());
+    assert_eq!(id.kind(), AccountIdKind::Evm);
+    
+    assert_eq!(AccountId::parse(&CHECKSUMMED.to_lowercase()).unwrap(), id);
+    assert_eq!(AccountId::parse(&format!("0x{}", CHECKSUMMED[2..].to_uppercase())).unwrap(), id);
+    
+    let miscased = CHECKSUMMED.replacen("aA", "aa", 1);
+    assert!(rejected(&miscased).contains("checksum"));
+}
+
+#[test]
+fn malformed_ids_are_rejected() {
+    assert!(rejected("0x0123").contains("got 4"));
+    assert!(rejected(&format!("{}g", &MIDEN_ID[..31])).contains("non-hex"));
+    assert!(rejected("").contains("not a hex or bech32"));
+    assert!(rejected("account-1").contains("not a hex or bech32"));
+    
+    let mut corrupted = bech32("mtst", &miden_payload());
+    let last = if corrupted.ends_with('q') { 'p' } else { 'q' };
+    corrupted.pop();
+    corrupted.push(last);
+    assert!(rejected(&corrupted).contains("not a hex or bech32"));
+}
+
+#[test]
+fn deserialization_validates_and_normalizes() {
+    let id: AccountId = serde_json::from_str(&format!("\"{}\"", CHECKSUMMED)).unwrap();
+    assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{}\"", CHECKSUMMED.to_lowercase()));
+    
+    let encoded = format!("\"{}\"", bech32("mtst", &miden_payload()));
+    assert_eq!(serde_json::from_str::<AccountId>(&encoded).unwrap(), MIDEN_ID);
+    assert!(serde_json::from_str::<AccountId>("\"0x0123\"").is_err());
+}
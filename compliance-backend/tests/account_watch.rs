@@ -0,0 +1,52 @@
+//! Client watches on Miden accounts
+
+use compliance_backend::compliance::account_watch::{slot_changes, AccountWatchInput, AccountWatchService};
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use miden_objects::{Felt, Word, EMPTY_WORD};
+use uuid::Uuid;
+
+fn word(value: u64) -> Word {
+    [Felt::new(value), Felt::new(0), Felt::new(0), Felt::new(0)]
+}
+
+#[test]
+fn only_changed_slots_are_reported() {
+    let previous = [word(1), word(2), word(3)];
+    let current = [word(1), word(5), word(3)];
+    let changes = slot_changes(&previous, &current);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].slot, 1);
+    assert_ne!(changes[0].previous, changes[0].current);
+    
+    assert!(slot_changes(&previous, &previous).is_empty());
+}
+
+#[test]
+fn added_and_removed_slots_compare_against_the_empty_word() {
+    let changes = slot_changes(&[word(1)], &[word(1), word(4)]);
+    assert_eq!(changes.iter().map(|change| change.slot).collect::<Vec<_>>(), [1]);
+    
+    let changes = slot_changes(&[word(1), word(4)], &[word(1)]);
+    assert_eq!(changes.iter().map(|change| change.slot).collect::<Vec<_>>(), [1]);
+    
+    assert!(slot_changes(&[word(1), EMPTY_WORD], &[word(1)]).is_empty());
+}
+
+#[tokio::test]
+async fn only_miden_accounts_can_be_watched() {
+    let service = AccountWatchService::new(10);
+    let evm = AccountId::parse("0x52908400098527886e0f7030069857d2e4169ee7").unwrap();
+    let result = service
+        .watch(Uuid::new_v4(), AccountWatchInput { account_id: evm, label: None })
+        .await;
+    assert!(matches!(result, Err(ComplianceError::Validation { .. })));
+    assert!(service.watched_accounts().await.is_empty());
+}
+
+#[tokio::test]
+async fn unknown_watches_are_not_found() {
+    let service = AccountWatchService::new(10);
+    let result = service.unwatch(Uuid::new_v4(), Uuid::new_v4()).await;
+    assert!(matches!(result, Err(ComplianceError::AccountWatchNotFound { .. })));
+}
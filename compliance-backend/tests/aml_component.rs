@@ -0,0 +1,89 @@
+//! AML component procedures executed in the Miden VM
+
+mod masm_harness;
+
+use compliance_backend::compliance::account_components::template::AmlTemplate;
+use masm_harness::{ComponentHarness, DEFAULT_NOW};
+
+#[test]
+fn assess_aml_risk_scores_large_transactions_and_counterparty_risk() {
+    let outcome = ComponentHarness::aml().call("assess_aml_risk", &[20_000, 1, 5]);
+    
+    // 100 for a large transaction plus 10 per counterparty risk point
+    assert_eq!(&outcome.stack()[..2], &[1, 150]);
+    assert_eq!(outcome.slot(0), 1);
+    assert_eq!(outcome.slot(1), 150);
+    assert_eq!(outcome.slot(2), DEFAULT_NOW);
+}
+
+#[test]
+fn assess_aml_risk_keeps_small_transactions_low() {
+    let outcome = ComponentHarness::aml().call("assess_aml_risk", &[500, 1, 2]);
+    
+    assert_eq!(&outcome.stack()[..2], &[0, 20]);
+    assert_eq!(outcome.slot(0), 0);
+}
+
+#[test]
+fn assess_aml_risk_accumulates_into_high_risk() {
+    let outcome = ComponentHarness::aml()
+        .with_slot(1, 250)
+        .call("assess_aml_risk", &[20_000, 1, 0]);
+    
+    assert_eq!(&outcome.stack()[..2], &[2, 350]);
+    assert_eq!(outcome.slot(0), 2);
+}
+
+#[test]
+fn assess_aml_risk_uses_configured_thresholds() {
+    let template = AmlTemplate {
+        large_transaction_threshold: 1_000,
+        medium_risk_score: 100,
+        ..AmlTemplate::default()
+    };
+    let outcome = ComponentHarness::aml_with(&template).call("assess_aml_risk", &[1_000, 1, 0]);
+    
+    assert_eq!(&outcome.stack()[..2], &[1, 100]);
+}
+
+#[test]
+fn update_risk_score_stores_score_and_level() {
+    let outcome = ComponentHarness::aml().call("update_risk_score", &[400, 2]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(0), 2);
+    assert_eq!(outcome.slot(1), 400);
+    assert_eq!(outcome.slot(2), DEFAULT_NOW);
+}
+
+#[test]
+fn record_transaction_updates_totals_and_flags_round_amounts() {
+    let outcome = ComponentHarness::aml()
+        .with_slot(3, 4)
+        .with_slot(4, 1_000)
+        .call("record_transaction", &[20_000, 1, 7]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(3), 5);
+    assert_eq!(outcome.slot(4), 21_000);
+    assert_eq!(outcome.slot(5), 1);
+}
+
+#[test]
+fn record_transaction_does_not_flag_other_amounts() {
+    let outcome = ComponentHarness::aml().call("record_transaction", &[12_345, 1, 7]);
+    
+    assert_eq!(outcome.slot(3), 1);
+    assert_eq!(outcome.slot(4), 12_345);
+    assert_eq!(outcome.slot(5), 0);
+}
+
+#[test]
+fn get_transaction_stats_reads_stored_slots() {
+    let outcome = ComponentHarness::aml()
+        .with_slot(3, 4)
+        .with_slot(4, 1_000)
+        .call("get_transaction_stats", &[]);
+    
+    assert_eq!(&outcome.stack()[..2], &[1_000, 4]);
+}
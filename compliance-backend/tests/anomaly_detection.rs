@@ -0,0 +1,161 @@
+//! Outcome baselines per client and alerts on statistically significant deviations
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::alerts::{AlertKind, AlertManager, AlertSeverity};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::anomaly::AnomalyDetector;
+use compliance_backend::compliance::breaker::Provider;
+use compliance_backend::config::{AlertingConfig, ComplianceConfig};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn account(n: usize) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+struct Detector {
+    detector: AnomalyDetector,
+    alerts: Arc<AlertManager>,
+    clock: Arc<MockClock>,
+}
+
+impl Detector {
+    /// Record `total` KYC outcomes for a client in the current window,
+    /// `rejected` of them rejections spread evenly among the rest
+    async fn kyc_window(&self, client_id: Uuid, total: usize, rejected: usize) {
+        for n in 0..total {
+            let is_rejected = (n + 1) * rejected / total > n * rejected / total;
+            self.detector.record_kyc(client_id, &account(n), is_rejected).await;
+        }
+    }
+    
+    fn next_window(&self) {
+        self.clock.advance(Duration::seconds(900));
+    }
+}
+
+fn detector() -> Detector {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    let alerts = Arc::new(AlertManager::with_sinks(AlertingConfig::default(), vec![]));
+    let config = Arc::new(LiveConfig::new(ComplianceConfig::default()));
+    Detector {
+        detector: AnomalyDetector::new(config, alerts.clone(), clock.clone()),
+        alerts,
+        clock,
+    }
+}
+
+#[tokio::test]
+async fn rejection_waves_raise_an_alert_with_the_window_and_accounts() {
+    let d = detector();
+    let client = Uuid::new_v4();
+    for _ in 0..10 {
+        d.kyc_window(client, 40, 2).await;
+        d.next_window();
+    }
+    d.kyc_window(client, 40, 2).await;
+    assert!(d.alerts.list(false).await.is_empty());
+    
+    d.next_window();
+    d.kyc_window(client, 20, 10).await;
+    let alerts = d.alerts.list(false).await;
+    assert_eq!(alerts.len(), 1);
+    let alert = &alerts[0];
+    assert_eq!(alert.kind, AlertKind::OutcomeAnomaly);
+    assert_eq!(alert.severity, AlertSeverity::Critical);
+    assert_eq!(alert.subject, format!("{}/kyc_rejection_rate", client));
+    assert_eq!(alert.details["samples"], 20);
+    assert_eq!(alert.details["window_start"], serde_json::json!(d.clock.now()));
+    let affected = alert.details["affected_accounts"].as_array().unwrap();
+    assert_eq!(affected.len(), 10);
+    assert!(affected.contains(&serde_json::json!(account(19))));
+    assert!(!affected.contains(&serde_json::json!(account(18))));
+}
+
+#[tokio::test]
+async fn deviations_are_not_flagged_without_an_established_baseline() {
+    let d = detector();
+    let client = Uuid::new_v4();
+    for _ in 0..3 {
+        d.kyc_window(client, 40, 2).await;
+        d.next_window();
+    }
+    d.kyc_window(client, 40, 30).await;
+    assert!(d.alerts.list(false).await.is_empty());
+}
+
+#[tokio::test]
+async fn flagged_windows_stay_out_of_the_baseline() {
+    let d = detector();
+    let client = Uuid::new_v4();
+    for _ in 0..10 {
+        d.kyc_window(client, 40, 2).await;
+        d.next_window();
+    }
+    for _ in 0..3 {
+        d.kyc_window(client, 40, 30).await;
+        d.next_window();
+    }
+    d.kyc_window(client, 1, 0).await;
+    
+    let baselines = d.detector.baselines();
+    let kyc = baselines.iter().find(|b| b.metric == "kyc_rejection_rate").unwrap();
+    assert_eq!(kyc.baseline_windows, 10);
+    assert!((kyc.baseline - 0.05).abs() < 1e-9);
+    assert_eq!(d.alerts.list(false).await.len(), 1);
+    assert_eq!(d.alerts.list(false).await[0].suppressed_count, 2);
+}
+
+#[tokio::test]
+async fn baselines_are_kept_per_client() {
+    let d = detector();
+    let steady = Uuid::new_v4();
+    let noisy = Uuid::new_v4();
+    for _ in 0..10 {
+        d.kyc_window(steady, 40, 2).await;
+        d.kyc_window(noisy, 40, 24).await;
+        d.next_window();
+    }
+    d.kyc_window(noisy, 40, 26).await;
+    assert!(d.alerts.list(false).await.is_empty());
+    
+    d.kyc_window(steady, 40, 26).await;
+    let alerts = d.alerts.list(false).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].details["client_id"], serde_json::json!(steady));
+}
+
+#[tokio::test]
+async fn provider_slowdowns_are_flagged() {
+    let d = detector();
+    let client = Uuid::new_v4();
+    let latency = |millis: u64| std::time::Duration::from_millis(millis);
+    for _ in 0..10 {
+        for n in 0..30 {
+            let millis = 280 + (n as u64 % 5) * 10;
+            d.detector.record_latency(client, &account(n), Provider::Kyc, latency(millis)).await;
+        }
+        d.next_window();
+    }
+    for n in 0..25 {
+        d.detector.record_latency(client, &account(n), Provider::Kyc, latency(900)).await;
+        d.detector.record_screening(client, &account(n), false).await;
+    }
+    
+    let alerts = d.alerts.list(false).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].subject, format!("{}/kyc_latency", client));
+    assert!(alerts[0].details["observed"].as_f64().unwrap() > 899.0);
+}
+
+#[tokio::test]
+async fn disabled_detection_records_nothing() {
+    let mut config = ComplianceConfig::default();
+    config.anomaly_detection.enabled = false;
+    let d = detector();
+    let detector = AnomalyDetector::new(Arc::new(LiveConfig::new(config)), d.alerts.clone(), d.clock.clone());
+    detector.record_kyc(Uuid::new_v4(), &account(1), true).await;
+    assert!(detector.baselines().is_empty());
+}
@@ -0,0 +1,199 @@
+//! Typed API client retries, idempotency keys, errors, and webhook signatures
+
+use chrono::{Duration, Utc};
+use compliance_backend::client::webhook_signature;
+use compliance_backend::client::{ClientError, ComplianceClient, ErrorCode, RetryPolicy};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Requests received by the fake API, lowercased head and raw body
+type Received = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+/// Serve one canned HTTP response per connection, in order
+async fn fake_api(responses: Vec<&'static str>) -> (String, Received) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Received::default();
+    let log = received.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = Vec::new();
+            let head_end = loop {
+                let mut chunk = [0u8; 4096];
+                let n = socket.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..n]);
+                if let Some(i) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break i + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&buffer[..head_end]).to_ascii_lowercase();
+            let length = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map_or(0, |value| value.trim().parse::<usize>().unwrap());
+            while buffer.len() < head_end + length {
+                let mut chunk = [0u8; 4096];
+                let n = socket.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..n]);
+            }
+            log.lock().unwrap().push((head, buffer[head_end..].to_vec()));
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+    (base_url, received)
+}
+
+fn response(status: &str, headers: &str, body: &str) -> &'static str {
+    let text = format!(
+        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+        status,
+        body.len(),
+        headers,
+        body
+    );
+    Box::leak(text.into_boxed_str())
+}
+
+fn client(base_url: &str) -> ComplianceClient {
+    ComplianceClient::new(base_url, "ztc_key")
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: std::time::Duration::from_millis(1),
+            max_delay: std::time::Duration::from_millis(20),
+        })
+}
+
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried_with_the_same_idempotency_key() {
+    let (base_url, received) = fake_api(vec![
+        response("503 Service Unavailable", "", r#"{"error":"busy"}"#),
+        response("429 Too Many Requests", "retry-after: 60\r\n", r#"{"error":"slow down"}"#),
+        response("200 OK", "", r#"{"accepted":true}"#),
+    ])
+    .await;
+    
+    let reply: Value = client(&base_url).post("/v1/things", &json!({"n": 1}), None).await.unwrap();
+    assert_eq!(reply, json!({"accepted": true}));
+    
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    let keys: Vec<_> = received.iter().map(|(head, _)| header(head, "idempotency-key").unwrap()).collect();
+    assert!(keys.iter().all(|key| *key == keys[0]));
+    assert!(received.iter().all(|(head, _)| header(head, "x-api-key") == Some("ztc_key")));
+    assert!(received.iter().all(|(_, body)| body.as_slice() == br#"{"n":1}"#));
+}
+
+#[tokio::test]
+async fn given_idempotency_keys_are_sent_and_gets_carry_none() {
+    let (base_url, received) = fake_api(vec![
+        response("200 OK", "", "{}"),
+        response("200 OK", "", "{}"),
+    ])
+    .await;
+    let client = client(&base_url);
+    
+    let _: Value = client.post("/v1/things", &json!({}), Some("txn-42")).await.unwrap();
+    let _: Value = client.get("/v1/things").await.unwrap();
+    
+    let received = received.lock().unwrap();
+    assert_eq!(header(&received[0].0, "idempotency-key"), Some("txn-42"));
+    assert_eq!(header(&received[1].0, "idempotency-key"), None);
+}
+
+#[tokio::test]
+async fn retries_stop_after_the_policy_limit() {
+    let unavailable = response("503 Service Unavailable", "", r#"{"error":"down","code":"provider_unavailable"}"#);
+    let (base_url, received) = fake_api(vec![unavailable; 3]).await;
+    
+    let error = client(&base_url).get::<Value>("/v1/things").await.unwrap_err();
+    assert_eq!(error.code(), Some(&ErrorCode::ProviderUnavailable));
+    assert!(error.is_retryable());
+    assert_eq!(received.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn api_errors_are_decoded_and_not_retried() {
+    let body = json!({
+        "error": "invalid body",
+        "code": "validation_error",
+        "errors": [{"pointer": "/amount", "message": "must be positive"}],
+    });
+    let (base_url, received) = fake_api(vec![response("422 Unprocessable Entity", "", &body.to_string())]).await;
+    
+    let error = client(&base_url).post::<_, Value>("/v1/things", &json!({}), None).await.unwrap_err();
+    let ClientError::Api { status, message, .. } = &error else {
+        panic!("expected an API error, got {:?}", error);
+    };
+    assert_eq!(status.as_u16(), 422);
+    assert_eq!(message, "invalid body");
+    assert_eq!(error.code(), Some(&ErrorCode::ValidationError));
+    assert_eq!(error.violations()[0].pointer, "/amount");
+    assert!(!error.is_retryable());
+    assert_eq!(received.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn unstructured_error_bodies_become_internal_errors() {
+    let (base_url, _) = fake_api(vec![response("500 Internal Server Error", "", "upstream exploded")]).await;
+    
+    let error = client(&base_url).get::<Value>("/v1/things").await.unwrap_err();
+    assert_eq!(error.code(), Some(&ErrorCode::InternalError));
+    assert!(error.to_string().contains("upstream exploded"));
+}
+
+#[test]
+fn error_codes_parse_with_a_fallback_for_new_codes() {
+    assert_eq!(ErrorCode::parse("epoch_not_found"), ErrorCode::EpochNotFound);
+    assert_eq!(ErrorCode::parse("quota_exceeded"), ErrorCode::QuotaExceeded);
+    assert_eq!(ErrorCode::parse("brand_new"), ErrorCode::Unknown("brand_new".to_string()));
+}
+
+#[test]
+fn api_keys_must_be_valid_header_values() {
+    assert!(matches!(ComplianceClient::new("http://localhost", ""), Err(ClientError::Configuration(_))));
+    assert!(matches!(
+        ComplianceClient::new("http://localhost", "key\nwith newline"),
+        Err(ClientError::Configuration(_))
+    ));
+}
+
+#[test]
+fn webhook_signatures_bind_the_body_and_timestamp() {
+    let now = Utc::now();
+    let tolerance = Duration::seconds(webhook_signature::DEFAULT_TOLERANCE_SECS);
+    let signature = webhook_signature::sign(b"secret", b"{\"a\":1}", now);
+    assert!(signature.starts_with(&format!("t={},v1=", now.timestamp())));
+    
+    webhook_signature::verify(b"secret", &signature, b"{\"a\":1}", tolerance, now).unwrap();
+    assert!(webhook_signature::verify(b"secret", &signature, b"{\"a\":2}", tolerance, now).is_err());
+    assert!(webhook_signature::verify(b"other", &signature, b"{\"a\":1}", tolerance, now).is_err());
+    let replayed = now + Duration::seconds(301);
+    assert!(webhook_signature::verify(b"secret", &signature, b"{\"a\":1}", tolerance, replayed).is_err());
+    assert!(webhook_signature::verify(b"secret", "v1=00", b"{\"a\":1}", tolerance, now).is_err());
+    
+    let old = webhook_signature::sign(b"old", b"{\"a\":1}", now);
+    let rotating = format!("{},{}", signature, old.split_once(',').unwrap().1);
+    webhook_signature::verify(b"old", &rotating, b"{\"a\":1}", tolerance, now).unwrap();
+}
+
+#[test]
+fn json_deliveries_verify_after_reserialization() {
+    let now = Utc::now();
+    let tolerance = Duration::seconds(webhook_signature::DEFAULT_TOLERANCE_SECS);
+    let (body, signature) = webhook_signature::sign_json(b"secret", &json!({"b": 1, "a": [true]}), now).unwrap();
+    assert_eq!(body, br#"{"a":[true],"b":1}"#);
+    
+    let reformatted = b"{ \"b\": 1,\n  \"a\": [ true ] }";
+    webhook_signature::verify_json(b"secret", &signature, reformatted, tolerance, now).unwrap();
+    assert!(webhook_signature::verify(b"secret", &signature, reformatted, tolerance, now).is_err());
+}
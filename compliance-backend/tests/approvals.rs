@@ -0,0 +1,137 @@
+//! Four-eyes approval of manual overrides
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::approvals::{ApprovalService, ApprovalStatus, OverrideAction};
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use compliance_backend::ComplianceError;
+use uuid::Uuid;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn clearance(n: u32) -> OverrideAction {
+    OverrideAction::SanctionsClearance { account_id: account(n) }
+}
+
+fn in_days(days: i64) -> Option<chrono::DateTime<Utc>> {
+    Some(Utc::now() + Duration::days(days))
+}
+
+#[tokio::test]
+async fn overrides_need_a_second_operator() {
+    let approvals = ApprovalService::new();
+    let request = approvals
+        .propose(clearance(1), "false positive", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    assert_eq!(request.status, ApprovalStatus::Pending);
+    
+    assert!(matches!(
+        approvals.approve(request.id, "alice", None).await,
+        Err(ComplianceError::CompliancePolicyViolation { .. })
+    ));
+    assert!(matches!(
+        approvals.reject(request.id, "alice", None).await,
+        Err(ComplianceError::CompliancePolicyViolation { .. })
+    ));
+    assert_eq!(approvals.get(request.id).await.unwrap().status, ApprovalStatus::Pending);
+    
+    let approved = approvals
+        .approve(request.id, "bob", Some("checked the match".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(approved.status, ApprovalStatus::Approved);
+    assert_eq!(approved.decided_by.as_deref(), Some("bob"));
+    assert_eq!(approved.decision_note.as_deref(), Some("checked the match"));
+    assert!(approved.decided_at.is_some());
+}
+
+#[tokio::test]
+async fn requests_are_decided_once() {
+    let approvals = ApprovalService::new();
+    let request = approvals
+        .propose(clearance(1), "false positive", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    
+    approvals.reject(request.id, "bob", None).await.unwrap();
+    assert!(approvals.approve(request.id, "carol", None).await.is_err());
+    assert_eq!(approvals.get(request.id).await.unwrap().status, ApprovalStatus::Rejected);
+    
+    assert!(matches!(
+        approvals.approve(Uuid::new_v4(), "bob", None).await,
+        Err(ComplianceError::ApprovalNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn only_one_override_of_a_type_is_pending_per_account() {
+    let approvals = ApprovalService::new();
+    let first = approvals
+        .propose(clearance(1), "false positive", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    assert!(approvals
+        .propose(clearance(1), "again", "carol", in_days(7), None, Duration::days(30))
+        .await
+        .is_err());
+    
+    let downgrade = OverrideAction::RiskDowngrade {
+        account_id: account(1),
+        aml_risk_level: AmlRiskLevel::Low,
+    };
+    approvals
+        .propose(downgrade, "reviewed", "alice", in_days(7), Some(AmlRiskLevel::High), Duration::days(30))
+        .await
+        .unwrap();
+    approvals
+        .propose(clearance(2), "false positive", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    
+    approvals.approve(first.id, "bob", None).await.unwrap();
+    approvals
+        .propose(clearance(1), "new hit", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    assert_eq!(approvals.list(Some(ApprovalStatus::Pending)).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn proposals_are_validated() {
+    let approvals = ApprovalService::new();
+    let max = Duration::days(30);
+    
+    assert!(approvals.propose(clearance(1), " ", "alice", in_days(7), None, max).await.is_err());
+    assert!(approvals.propose(clearance(1), "reason", "alice", None, None, max).await.is_err());
+    assert!(approvals.propose(clearance(1), "reason", "alice", in_days(-1), None, max).await.is_err());
+    assert!(approvals.propose(clearance(1), "reason", "alice", in_days(31), None, max).await.is_err());
+    
+    let reversal = || OverrideAction::RevocationReversal { account_id: account(1) };
+    assert!(approvals.propose(reversal(), "reason", "alice", in_days(7), None, max).await.is_err());
+    approvals.propose(reversal(), "reason", "alice", None, None, max).await.unwrap();
+}
+
+#[tokio::test]
+async fn approved_overrides_are_reverted_after_they_expire() {
+    let approvals = ApprovalService::new();
+    let request = approvals
+        .propose(clearance(1), "false positive", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    approvals
+        .propose(clearance(2), "false positive", "alice", in_days(7), None, Duration::days(30))
+        .await
+        .unwrap();
+    
+    assert!(approvals.due_for_revert(Utc::now() + Duration::days(8)).await.unwrap().is_empty());
+    approvals.approve(request.id, "bob", None).await.unwrap();
+    
+    assert!(approvals.due_for_revert(Utc::now()).await.unwrap().is_empty());
+    let due = approvals.due_for_revert(Utc::now() + Duration::days(8)).await.unwrap();
+    assert_eq!(due.iter().map(|r| r.id).collect::<Vec<_>>(), vec![request.id]);
+    
+    approvals.mark_reverted(request.id).await.unwrap();
+    assert!(approvals.due_for_revert(Utc::now() + Duration::days(8)).await.unwrap().is_empty());
+}
@@ -0,0 +1,75 @@
+//! Epoch batching of attestation commitments
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::account_components::anchor_component::{
+    anchor_script, compile_anchor_component, render, ANCHOR_PROCEDURE, GET_EPOCH_ROOT_PROCEDURE,
+};
+use compliance_backend::compliance::epochs::EpochBatcher;
+use compliance_backend::config::EpochConfig;
+use compliance_backend::crypto::attestation_commitment;
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use uuid::Uuid;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+#[tokio::test]
+async fn issued_attestations_wait_for_their_epoch() {
+    let batcher = EpochBatcher::new();
+    let created_at = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    assert_eq!(batcher.pending().await, 0);
+    
+    for n in 1..=3 {
+        batcher.enqueue(&common::attestation(&account(n), created_at, Duration::days(365))).await;
+    }
+    assert_eq!(batcher.pending().await, 3);
+    assert!(batcher.list().await.is_empty(), "nothing is anchored until the epoch closes");
+}
+
+#[tokio::test]
+async fn unknown_epochs_and_unanchored_attestations_are_reported() {
+    let batcher = EpochBatcher::new();
+    let created_at = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let attestation = common::attestation(&account(1), created_at, Duration::days(365));
+    batcher.enqueue(&attestation).await;
+    
+    assert!(matches!(batcher.get(0).await, Err(ComplianceError::EpochNotFound { epoch: 0 })));
+    assert!(matches!(batcher.get(1).await, Err(ComplianceError::EpochNotFound { epoch: 1 })));
+    
+    let queued = batcher.inclusion_proof(attestation.id).await;
+    assert!(matches!(queued, Err(ComplianceError::AttestationNotAnchored { .. })));
+    let unknown = batcher.inclusion_proof(Uuid::new_v4()).await;
+    assert!(matches!(unknown, Err(ComplianceError::AttestationNotAnchored { .. })));
+}
+
+#[test]
+fn the_component_exposes_anchor_and_read_procedures() {
+    let code = render();
+    assert!(code.contains(&format!("export.{}", ANCHOR_PROCEDURE)));
+    assert!(code.contains(&format!("export.{}", GET_EPOCH_ROOT_PROCEDURE)));
+    assert!(code.contains("export.get_latest_epoch"));
+    
+    compile_anchor_component().unwrap();
+}
+
+#[test]
+fn anchor_scripts_compile_for_any_epoch() {
+    let created_at = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let attestation = common::attestation(&account(1), created_at, Duration::days(365));
+    let root = attestation_commitment(&attestation).digest();
+    
+    anchor_script(root, 1, 1).unwrap();
+    anchor_script(root, 1_000_000, 512).unwrap();
+}
+
+#[test]
+fn batching_is_off_by_default() {
+    let config = EpochConfig::default();
+    assert!(!config.enabled);
+    assert!(config.anchor_account_id.is_none());
+    assert_eq!(config.epoch_secs, 600);
+}
@@ -0,0 +1,143 @@
+//! Attestation lifecycle events and the state projected from them
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::{Clock, MockClock};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore, AttestationStatus};
+use compliance_backend::crypto::ProofHash;
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use std::sync::Arc;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn store() -> (AttestationEventStore, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    (AttestationEventStore::with_clock(clock.clone()), clock)
+}
+
+fn issued(account_id: &AccountId, clock: &MockClock) -> AttestationEvent {
+    AttestationEvent::AttestationIssued {
+        attestation: common::attestation(account_id, clock.now(), Duration::days(30)),
+    }
+}
+
+#[tokio::test]
+async fn streams_are_sequenced_per_account() {
+    let (store, clock) = store();
+    let (first, second) = (account(1), account(2));
+    
+    assert_eq!(store.append(&first, issued(&first, &clock)).await.unwrap().sequence, 1);
+    assert_eq!(store.append(&second, issued(&second, &clock)).await.unwrap().sequence, 1);
+    let recorded = store
+        .append(&first, AttestationEvent::RiskUpdated { aml_risk_level: AmlRiskLevel::High })
+        .await
+        .unwrap();
+    assert_eq!(recorded.sequence, 2);
+    assert_eq!(recorded.account_id, first);
+    
+    let sequences: Vec<_> = store.events(&first, None).await.unwrap().iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, [1, 2]);
+    let mut accounts = store.accounts().await.unwrap();
+    accounts.sort();
+    assert_eq!(accounts, [first, second]);
+}
+
+#[tokio::test]
+async fn events_before_issuance_are_ignored() {
+    let (store, clock) = store();
+    let account_id = account(1);
+    store.append(&account_id, AttestationEvent::Expired).await.unwrap();
+    assert!(store.project(&account_id, None).await.unwrap().is_none());
+    
+    store.append(&account_id, issued(&account_id, &clock)).await.unwrap();
+    let state = store.project(&account_id, None).await.unwrap().unwrap();
+    assert_eq!(state.status, AttestationStatus::Active);
+    assert_eq!(state.version, 2);
+}
+
+#[tokio::test]
+async fn the_projection_follows_the_lifecycle() {
+    let (store, clock) = store();
+    let account_id = account(1);
+    store.append(&account_id, issued(&account_id, &clock)).await.unwrap();
+    
+    let events = [
+        AttestationEvent::RiskUpdated { aml_risk_level: AmlRiskLevel::Medium },
+        AttestationEvent::SanctionsOverridden {
+            sanctions_cleared: false,
+            approved_by: vec!["alice".to_string(), "bob".to_string()],
+        },
+        AttestationEvent::Revoked {
+            reason: "fraud".to_string(),
+            revoked_by: Some("alice".to_string()),
+        },
+    ];
+    for event in events {
+        store.append(&account_id, event).await.unwrap();
+    }
+    
+    let state = store.project(&account_id, None).await.unwrap().unwrap();
+    assert_eq!(state.attestation.aml_risk_level, AmlRiskLevel::Medium);
+    assert!(!state.attestation.sanctions_cleared);
+    assert_eq!(state.status, AttestationStatus::Revoked);
+    assert_eq!(state.revocation_reason.as_deref(), Some("fraud"));
+    assert!(!state.is_valid_at(Utc::now()));
+    
+    store
+        .append(&account_id, AttestationEvent::Reinstated { approved_by: vec!["carol".to_string()] })
+        .await
+        .unwrap();
+    let state = store.project(&account_id, None).await.unwrap().unwrap();
+    assert_eq!(state.status, AttestationStatus::Active);
+    assert!(state.revocation_reason.is_none());
+    assert_eq!(state.version, 5);
+}
+
+#[tokio::test]
+async fn due_attestations_expire_and_renewal_reactivates_them() {
+    let (store, clock) = store();
+    let (expiring, fresh) = (account(1), account(2));
+    store.append(&expiring, issued(&expiring, &clock)).await.unwrap();
+    clock.advance(Duration::days(20));
+    store.append(&fresh, issued(&fresh, &clock)).await.unwrap();
+    
+    clock.advance(Duration::days(15));
+    assert_eq!(store.expire_due(clock.now()).await.unwrap(), [expiring.clone()]);
+    assert!(store.expire_due(clock.now()).await.unwrap().is_empty());
+    let state = store.project(&expiring, None).await.unwrap().unwrap();
+    assert_eq!(state.status, AttestationStatus::Expired);
+    
+    let renewed = AttestationEvent::Renewed {
+        expires_at: clock.now() + Duration::days(30),
+        proof_hash: ProofHash::of(b"renewed proof"),
+    };
+    store.append(&expiring, renewed).await.unwrap();
+    let state = store.project(&expiring, None).await.unwrap().unwrap();
+    assert_eq!(state.status, AttestationStatus::Active);
+    assert_eq!(state.attestation.proof_hash, ProofHash::of(b"renewed proof"));
+    assert!(state.is_valid_at(clock.now()));
+}
+
+#[tokio::test]
+async fn past_states_are_projected_as_of_a_point_in_time() {
+    let (store, clock) = store();
+    let account_id = account(1);
+    store.append(&account_id, issued(&account_id, &clock)).await.unwrap();
+    let before_revocation = clock.now();
+    
+    clock.advance(Duration::hours(1));
+    let revoked = AttestationEvent::Revoked {
+        reason: "fraud".to_string(),
+        revoked_by: None,
+    };
+    store.append(&account_id, revoked).await.unwrap();
+    
+    let past = store.project(&account_id, Some(before_revocation)).await.unwrap().unwrap();
+    assert_eq!(past.status, AttestationStatus::Active);
+    assert_eq!(past.version, 1);
+    assert!(store.project(&account_id, Some(before_revocation - Duration::seconds(1))).await.unwrap().is_none());
+    assert_eq!(store.project(&account_id, None).await.unwrap().unwrap().status, AttestationStatus::Revoked);
+}
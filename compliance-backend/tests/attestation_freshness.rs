@@ -0,0 +1,79 @@
+//! Attestation age and screening list versions required by verifier challenges
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::challenges::ChallengeService;
+use compliance_backend::compliance::freshness::FreshnessRequirement;
+use compliance_backend::compliance::screening::ScreeningListStore;
+use compliance_backend::types::AccountId;
+use std::collections::{BTreeMap, HashMap};
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+fn versions(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(list, version)| (list.to_string(), version.to_string())).collect()
+}
+
+fn required(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs.iter().map(|(list, version)| (list.to_string(), version.to_string())).collect()
+}
+
+#[test]
+fn attestations_older_than_the_maximum_age_are_not_fresh() {
+    let created = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let freshness = FreshnessRequirement {
+        max_attestation_age_secs: Some(3_600),
+        ..FreshnessRequirement::default()
+    };
+    
+    assert!(freshness.attestation_fresh(created, created + Duration::minutes(60)));
+    assert!(!freshness.attestation_fresh(created, created + Duration::minutes(61)));
+    assert!(FreshnessRequirement::default().attestation_fresh(created, created + Duration::days(365)));
+}
+
+#[tokio::test]
+async fn list_versions_are_ordered_by_ingestion() {
+    let lists = ScreeningListStore::new();
+    for version in ["2025-03", "2025-01", "2025-02"] {
+        lists.ingest("ofac", version, vec![]).await.unwrap();
+    }
+    let min = required(&[("ofac", "2025-01")]);
+    
+    // Versions are opaque: "2025-03" was ingested before "2025-01"
+    assert_eq!(lists.stale_lists(&versions(&[("ofac", "2025-03")]), &min).await, ["ofac"]);
+    assert!(lists.stale_lists(&versions(&[("ofac", "2025-02")]), &min).await.is_empty());
+    assert!(lists.stale_lists(&versions(&[("ofac", "2025-01")]), &min).await.is_empty());
+}
+
+#[tokio::test]
+async fn unscreened_lists_and_unknown_versions_are_stale() {
+    let lists = ScreeningListStore::new();
+    lists.ingest("ofac", "v1", vec![]).await.unwrap();
+    assert!(lists.has_version("ofac", "v1").await);
+    assert!(!lists.has_version("ofac", "v2").await);
+    
+    assert_eq!(lists.stale_lists(&HashMap::new(), &required(&[("ofac", "v1")])).await, ["ofac"]);
+    assert_eq!(lists.stale_lists(&versions(&[("ofac", "v1")]), &required(&[("ofac", "v2")])).await, ["ofac"]);
+}
+
+#[tokio::test]
+async fn challenges_carry_validated_freshness() {
+    let challenges = ChallengeService::new(300);
+    let zero_age = FreshnessRequirement {
+        max_attestation_age_secs: Some(0),
+        ..FreshnessRequirement::default()
+    };
+    assert!(challenges.issue("verifier.example", &account(), None, vec![], Some(zero_age)).await.is_err());
+    
+    let freshness = FreshnessRequirement {
+        max_attestation_age_secs: Some(86_400),
+        min_list_versions: required(&[("ofac", "v1")]),
+        rescreen: true,
+    };
+    let challenge = challenges
+        .issue("verifier.example", &account(), None, vec![], Some(freshness.clone()))
+        .await
+        .unwrap();
+    assert_eq!(challenge.freshness, Some(freshness));
+}
@@ -0,0 +1,128 @@
+//! Sparse Merkle registry openings of valid attestations
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::attestation_registry::{
+    registry_key, AttestationRegistry, Membership, RegistryProof,
+};
+use compliance_backend::crypto::attestation_commitment;
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use miden_objects::crypto::merkle::Smt;
+use miden_objects::utils::Serializable;
+use miden_objects::{Digest, Word, EMPTY_WORD};
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+/// A registry holding an attestation for account 1, with its commitment
+fn registry_tree() -> (Smt, Digest) {
+    let created_at = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let attestation = common::attestation(&account(1), created_at, Duration::days(365));
+    let commitment = attestation_commitment(&attestation);
+    let tree = Smt::with_entries([(registry_key(&account(1)), commitment.to_word())]).unwrap();
+    (tree, commitment.digest())
+}
+
+fn opening(tree: &Smt, account_id: &AccountId, membership: Membership, commitment: Option<Digest>) -> RegistryProof {
+    let key = registry_key(account_id);
+    RegistryProof {
+        account_id: account_id.clone(),
+        epoch: 1,
+        root: tree.root().to_hex(),
+        membership,
+        key: key.to_hex(),
+        attestation_commitment: commitment.map(|commitment| commitment.to_hex()),
+        attestation_status: None,
+        proof: hex::encode(tree.open(&key).to_bytes()),
+    }
+}
+
+#[test]
+fn keys_are_distinct_per_account() {
+    assert_eq!(registry_key(&account(1)), registry_key(&account(1)));
+    assert_ne!(registry_key(&account(1)), registry_key(&account(2)));
+}
+
+#[test]
+fn attested_openings_verify_against_their_root() {
+    let (tree, commitment) = registry_tree();
+    assert_eq!(tree.get_value(&registry_key(&account(1))), Word::from(commitment));
+    
+    opening(&tree, &account(1), Membership::Attested, Some(commitment)).verify().unwrap();
+}
+
+#[test]
+fn non_membership_is_provable_for_unknown_accounts() {
+    let (tree, _) = registry_tree();
+    assert_eq!(tree.get_value(&registry_key(&account(2))), EMPTY_WORD);
+    
+    opening(&tree, &account(2), Membership::NotAttested, None).verify().unwrap();
+}
+
+#[test]
+fn claims_that_contradict_the_tree_are_rejected() {
+    let (tree, commitment) = registry_tree();
+    
+    let denied = opening(&tree, &account(1), Membership::NotAttested, None);
+    assert!(matches!(denied.verify(), Err(ComplianceError::Validation { .. })));
+    
+    let forged = opening(&tree, &account(2), Membership::Attested, Some(commitment));
+    assert!(forged.verify().is_err());
+    
+    let missing = opening(&tree, &account(1), Membership::Attested, None);
+    assert!(missing.verify().is_err());
+    
+    let mut other_account = opening(&tree, &account(1), Membership::Attested, Some(commitment));
+    other_account.account_id = account(2);
+    assert!(other_account.verify().is_err());
+    
+    let mut stale = opening(&tree, &account(1), Membership::Attested, Some(commitment));
+    stale.root = Smt::new().root().to_hex();
+    assert!(stale.verify().is_err());
+}
+
+#[test]
+fn malformed_proofs_are_rejected() {
+    let (tree, commitment) = registry_tree();
+    
+    let mut bad_root = opening(&tree, &account(1), Membership::Attested, Some(commitment));
+    bad_root.root = "0xnot-a-root".to_string();
+    assert!(matches!(bad_root.verify(), Err(ComplianceError::Validation { .. })));
+    
+    let mut bad_proof = opening(&tree, &account(1), Membership::Attested, Some(commitment));
+    bad_proof.proof = "zz".to_string();
+    assert!(bad_proof.verify().is_err());
+    
+    let mut truncated = opening(&tree, &account(1), Membership::Attested, Some(commitment));
+    truncated.proof.truncate(16);
+    assert!(truncated.verify().is_err());
+}
+
+#[test]
+fn proofs_round_trip_through_json() {
+    let (tree, commitment) = registry_tree();
+    let proof = opening(&tree, &account(1), Membership::Attested, Some(commitment));
+    
+    let json = serde_json::to_value(&proof).unwrap();
+    assert_eq!(json["membership"], "attested");
+    assert!(json.get("attestation_status").is_none());
+    
+    let decoded: RegistryProof = serde_json::from_value(json).unwrap();
+    decoded.verify().unwrap();
+    
+    let absent = opening(&tree, &account(2), Membership::NotAttested, None);
+    let json = serde_json::to_value(&absent).unwrap();
+    assert_eq!(json["membership"], "not_attested");
+    assert!(json.get("attestation_commitment").is_none());
+}
+
+#[tokio::test]
+async fn nothing_is_served_before_the_first_publication() {
+    let registry = AttestationRegistry::new();
+    
+    assert!(matches!(registry.latest().await, Err(ComplianceError::RegistryNotPublished)));
+    assert!(registry.history().await.is_empty());
+}
@@ -0,0 +1,139 @@
+//! Audit log pagination, filtering, and hash-chain verification
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::audit::{decode_cursor, encode_cursor, verify_entries, AuditLog, AuditQuery, GENESIS_HASH};
+use compliance_backend::clock::MockClock;
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+
+fn account(n: u64) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+#[tokio::test]
+async fn entries_form_a_verifiable_chain() {
+    let log = AuditLog::new();
+    for n in 0..5 {
+        log.record("operator", "kyc.approved", Some(&account(n)), serde_json::json!({ "n": n })).await;
+    }
+    
+    let entries = log.entries().await.unwrap();
+    assert_eq!(entries.iter().map(|e| e.sequence).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+    assert!(entries.windows(2).all(|pair| pair[1].prev_hash == pair[0].hash));
+    
+    let verification = log.verify_chain().await.unwrap();
+    assert!(verification.valid);
+    assert_eq!(verification.entries_checked, 5);
+    assert_eq!(verification.first_invalid_sequence, None);
+    assert_eq!(verification.head_hash, entries[4].hash);
+}
+
+#[tokio::test]
+async fn tampered_entry_breaks_the_chain() {
+    let log = AuditLog::new();
+    for n in 0..4 {
+        log.record("operator", "kyc.approved", Some(&account(n)), serde_json::json!({})).await;
+    }
+    
+    let mut entries = log.entries().await.unwrap();
+    entries[2].actor = "someone-else".to_string();
+    let verification = verify_entries(&entries, GENESIS_HASH);
+    assert!(!verification.valid);
+    assert_eq!(verification.entries_checked, 2);
+    assert_eq!(verification.first_invalid_sequence, Some(3));
+    assert_eq!(verification.head_hash, entries[1].hash);
+    
+    // Dropping an entry is caught by the broken link
+    let mut entries = log.entries().await.unwrap();
+    entries.remove(1);
+    assert_eq!(verify_entries(&entries, GENESIS_HASH).first_invalid_sequence, Some(3));
+}
+
+#[tokio::test]
+async fn pages_follow_cursors_to_the_end() {
+    let log = AuditLog::new();
+    for n in 0..7 {
+        log.record("operator", "kyc.approved", Some(&account(n)), serde_json::json!({})).await;
+    }
+    
+    let query = AuditQuery::default();
+    let mut sequences = Vec::new();
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let page = log.query(&query, cursor, 3).await.unwrap();
+        pages += 1;
+        sequences.extend(page.entries.iter().map(|e| e.sequence));
+        match page.next_cursor {
+            Some(next) => cursor = Some(decode_cursor(&next).unwrap()),
+            None => break,
+        }
+    }
+    
+    assert_eq!(pages, 3);
+    assert_eq!(sequences, (1..=7).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn exact_page_has_no_next_cursor() {
+    let log = AuditLog::new();
+    for n in 0..3 {
+        log.record("operator", "kyc.approved", Some(&account(n)), serde_json::json!({})).await;
+    }
+    
+    let page = log.query(&AuditQuery::default(), None, 3).await.unwrap();
+    assert_eq!(page.entries.len(), 3);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn cursors_round_trip_and_reject_garbage() {
+    assert_eq!(decode_cursor(&encode_cursor(42)).unwrap(), 42);
+    assert!(decode_cursor("not a cursor!").is_err());
+    assert!(decode_cursor("AAAA").is_err());
+}
+
+#[tokio::test]
+async fn queries_filter_by_actor_action_account_and_time() {
+    let start = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+    let clock = Arc::new(MockClock::new(start));
+    let log = AuditLog::with_clock(clock.clone());
+    
+    log.record("alice", "kyc.approved", Some(&account(1)), serde_json::json!({})).await;
+    clock.advance(Duration::hours(1));
+    log.record("bob", "kyc.rejected", Some(&account(2)), serde_json::json!({})).await;
+    clock.advance(Duration::hours(1));
+    log.record("alice", "override.granted", Some(&account(2)), serde_json::json!({})).await;
+    
+    let by_actor = AuditQuery {
+        actor: Some("alice".to_string()),
+        ..AuditQuery::default()
+    };
+    assert_eq!(log.export(&by_actor).await.unwrap().len(), 2);
+    
+    let by_action = AuditQuery {
+        action: Some("kyc.rejected".to_string()),
+        ..AuditQuery::default()
+    };
+    assert_eq!(log.export(&by_action).await.unwrap()[0].actor, "bob");
+    
+    let by_account = AuditQuery {
+        account_id: Some(account(2)),
+        ..AuditQuery::default()
+    };
+    assert_eq!(log.export(&by_account).await.unwrap().len(), 2);
+    
+    // `from` is inclusive, `to` exclusive
+    let window = AuditQuery {
+        from: Some(start + Duration::hours(1)),
+        to: Some(start + Duration::hours(2)),
+        ..AuditQuery::default()
+    };
+    let entries = log.export(&window).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].sequence, 2);
+    
+    let touched = log.accounts_touched_by("alice", start, start + Duration::hours(3)).await.unwrap();
+    assert_eq!(touched, vec![account(1), account(2)]);
+}
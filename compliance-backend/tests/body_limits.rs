@@ -0,0 +1,122 @@
+//! Per-route request body limits and their configuration
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, HeaderValue};
+use compliance_backend::api::body_limits::{media_type, RouteLimit};
+use compliance_backend::config::{BodyLimitsConfig, Config, RouteBodyLimit};
+use compliance_backend::ComplianceError;
+
+const UPLOAD: &str = "/v1/end-user/session/documents";
+
+#[test]
+fn reads_take_no_body_and_writes_a_small_one_by_default() {
+    let limits = BodyLimitsConfig::default();
+    let (status_limit, _) = limits.resolve("GET", "/v1/accounts/{id}/status");
+    let (write_limit, types) = limits.resolve("POST", "/v1/accounts");
+    assert_eq!(status_limit, 0);
+    assert_eq!(write_limit, 64 * 1024);
+    assert_eq!(types, ["application/json".to_string()]);
+}
+
+#[test]
+fn document_upload_takes_large_multipart_bodies() {
+    let limits = BodyLimitsConfig::default();
+    let (upload_limit, types) = limits.resolve("POST", UPLOAD);
+    assert!(upload_limit > Config::default().compliance.kyc.documents.max_document_bytes);
+    assert_eq!(types, ["multipart/form-data".to_string()]);
+    
+    // Only the method the route is configured for gets its limit
+    let (read_limit, _) = limits.resolve("GET", UPLOAD);
+    assert_eq!(read_limit, 0);
+}
+
+#[test]
+fn route_without_content_types_accepts_the_defaults() {
+    let mut limits = BodyLimitsConfig::default();
+    limits.routes.insert(
+        "PUT /v1/accounts/{id}/identity".to_string(),
+        RouteBodyLimit {
+            max_bytes: 8 * 1024,
+            content_types: Vec::new(),
+        },
+    );
+    let (max_bytes, types) = limits.resolve("PUT", "/v1/accounts/{id}/identity");
+    assert_eq!(max_bytes, 8 * 1024);
+    assert_eq!(types, limits.default_content_types.as_slice());
+}
+
+#[test]
+fn route_limit_is_looked_up_by_method_and_path() {
+    let limits = BodyLimitsConfig::default();
+    let limit = RouteLimit::new(&limits, "POST", "/v1/providers/{provider}/callback");
+    assert_eq!(limit.endpoint, "POST /v1/providers/{provider}/callback");
+    assert_eq!(limit.max_bytes, 1024 * 1024);
+    
+    // A request matching no route is looked up by its path, and gets the
+    // defaults for its method
+    let request = Request::builder().method("POST").uri("/v1/nowhere").body(Body::empty()).unwrap();
+    let unmatched = RouteLimit::of(&limits, &request);
+    assert_eq!(unmatched.endpoint, "POST /v1/nowhere");
+    assert_eq!(unmatched.max_bytes, limits.default_max_bytes);
+}
+
+#[test]
+fn json_routes_accept_structured_json_suffixes() {
+    let limit = RouteLimit::new(&BodyLimitsConfig::default(), "POST", "/v1/accounts");
+    assert!(limit.accepts("application/json"));
+    assert!(limit.accepts("application/merge-patch+json"));
+    assert!(!limit.accepts("text/plain"));
+    assert!(!limit.accepts("multipart/form-data"));
+}
+
+#[test]
+fn media_type_drops_parameters_and_case() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(media_type(&headers), None);
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("Application/JSON; charset=utf-8"));
+    assert_eq!(media_type(&headers).as_deref(), Some("application/json"));
+}
+
+#[test]
+fn rejections_are_413_and_415_with_their_limits() {
+    let limit = RouteLimit::new(&BodyLimitsConfig::default(), "POST", "/v1/accounts");
+    let too_large = limit.too_large();
+    assert_eq!(too_large.status_code(), 413);
+    assert_eq!(too_large.code(), "payload_too_large");
+    assert!(too_large.to_string().contains("65536"));
+    
+    let unsupported = ComplianceError::UnsupportedMediaType {
+        endpoint: limit.endpoint.clone(),
+        content_type: "text/plain".to_string(),
+        accepted: limit.content_types.clone(),
+    };
+    assert_eq!(unsupported.status_code(), 415);
+    assert_eq!(unsupported.code(), "unsupported_media_type");
+    assert!(unsupported.is_client_error());
+}
+
+#[test]
+fn malformed_limits_are_rejected() {
+    let mut config = Config::default();
+    config.server.body_limits.default_max_bytes = 0;
+    config.server.body_limits.routes.insert(
+        "/v1/accounts".to_string(),
+        RouteBodyLimit {
+            max_bytes: 1024,
+            content_types: vec!["json".to_string()],
+        },
+    );
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("server.body_limits.default_max_bytes"));
+    assert!(violations.contains("server.body_limits.routes./v1/accounts"));
+    assert!(violations.contains("server.body_limits.routes./v1/accounts.content_types"));
+}
+
+#[test]
+fn documents_must_fit_the_upload_route() {
+    let mut config = Config::default();
+    config.server.body_limits.routes.get_mut(&format!("POST {}", UPLOAD)).unwrap().max_bytes = 1024 * 1024;
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("compliance.kyc.documents.max_document_bytes"));
+}
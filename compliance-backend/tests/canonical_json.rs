@@ -0,0 +1,80 @@
+//! RFC 8785 canonical JSON, checked against the RFC's cross-language vectors
+
+use chrono::{Duration, Utc};
+use compliance_backend::crypto::canonical_json;
+use compliance_backend::crypto::webhook_signature;
+use serde_json::json;
+
+#[test]
+fn rfc_sample_is_canonicalized() {
+    let input = br#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#;
+    let expected = "{\"literals\":[null,true,false],\"numbers\":[333333333.3333333,1e+30,4.5,0.002,1e-27],\
+                    \"string\":\"\u{20ac}$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"}";
+    
+    assert_eq!(String::from_utf8(canonical_json::canonicalize(input).unwrap()).unwrap(), expected);
+}
+
+#[test]
+fn members_are_sorted_by_utf16_code_units() {
+    let input = json!({
+        "\u{20ac}": "Euro Sign",
+        "\r": "Carriage Return",
+        "\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "\u{1f600}": "Emoji: Grinning Face",
+        "\u{80}": "Control",
+        "\u{f6}": "Latin Small Letter O With Diaeresis",
+    });
+    let expected = "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",\
+                    \"\u{f6}\":\"Latin Small Letter O With Diaeresis\",\"\u{20ac}\":\"Euro Sign\",\
+                    \"\u{1f600}\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}";
+    
+    assert_eq!(canonical_json::to_string(&input).unwrap(), expected);
+}
+
+#[test]
+fn numbers_are_formatted_as_ecmascript_doubles() {
+    let vectors = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+    for (bits, expected) in vectors {
+        assert_eq!(canonical_json::to_string(&f64::from_bits(bits)).unwrap(), expected, "{:#018x}", bits);
+    }
+}
+
+#[test]
+fn integers_beyond_double_precision_are_rejected() {
+    assert_eq!(canonical_json::to_string(&((1u64 << 53) - 1)).unwrap(), "9007199254740991");
+    assert!(canonical_json::to_vec(&u64::MAX).is_err());
+    assert!(canonical_json::to_vec(&i64::MIN).is_err());
+}
+
+#[test]
+fn reserialized_webhook_bodies_still_verify() {
+    let secret = b"whsec";
+    let now = Utc::now();
+    let payload = json!({ "event": "attestation.issued", "data": { "risk": 0.25, "account_id": "0x01" } });
+    let (body, header) = webhook_signature::sign_json(secret, &payload, now).unwrap();
+    
+    let reformatted = serde_json::to_vec_pretty(&serde_json::from_slice::<serde_json::Value>(&body).unwrap()).unwrap();
+    assert!(webhook_signature::verify(secret, &header, &reformatted, Duration::minutes(5), now).is_err());
+    webhook_signature::verify_json(secret, &header, &reformatted, Duration::minutes(5), now).unwrap();
+}
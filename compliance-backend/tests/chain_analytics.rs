@@ -0,0 +1,222 @@
+//! On-chain exposure scoring of addresses linked to accounts
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::chain_analytics::{
+    apply_profile, AddressRisk, CategoryExposure, ChainAnalyticsProvider, ChainAnalyticsService, ChainRiskProfile,
+    ExposureCategory, LinkedAddress,
+};
+use compliance_backend::compliance::provider_credentials::ProviderCredentialStore;
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use compliance_backend::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn exposure(category: ExposureCategory, direct: bool, share: f64) -> CategoryExposure {
+    CategoryExposure {
+        category,
+        direct,
+        share,
+        volume_usd: None,
+    }
+}
+
+/// Provider answering from a fixed table of address scores
+#[derive(Default)]
+struct FakeProvider {
+    risks: HashMap<String, (f64, Vec<CategoryExposure>)>,
+    calls: AtomicUsize,
+}
+
+impl ChainAnalyticsProvider for FakeProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+    
+    fn score_address<'a>(&'a self, chain: &'a str, address: &'a str) -> BoxFuture<'a, Result<AddressRisk>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let risk = self.risks.get(address).cloned().map(|(score, exposures)| AddressRisk {
+            chain: chain.to_string(),
+            address: address.to_string(),
+            provider: "fake".to_string(),
+            score,
+            exposures,
+            assessed_at: Utc::now(),
+        });
+        Box::pin(async move { risk.ok_or_else(|| ComplianceError::internal("unknown address")) })
+    }
+}
+
+fn scoring(
+    provider: FakeProvider,
+    configure: impl FnOnce(&mut ComplianceConfig),
+) -> (ChainAnalyticsService, Arc<FakeProvider>) {
+    let mut compliance = ComplianceConfig::default();
+    compliance.aml.chain_analytics.enabled = true;
+    configure(&mut compliance);
+    let provider = Arc::new(provider);
+    let service = ChainAnalyticsService::with_provider(
+        Arc::new(LiveConfig::new(compliance)),
+        Arc::new(ProviderCredentialStore::new(None)),
+        Some(provider.clone()),
+    );
+    (service, provider)
+}
+
+fn scored(entries: &[(&str, f64, Vec<CategoryExposure>)]) -> FakeProvider {
+    FakeProvider {
+        risks: entries
+            .iter()
+            .map(|(address, score, exposures)| (address.to_string(), (*score, exposures.clone())))
+            .collect(),
+        calls: AtomicUsize::new(0),
+    }
+}
+
+fn linked(address: &str) -> LinkedAddress {
+    LinkedAddress {
+        chain: "ethereum".to_string(),
+        address: address.to_string(),
+    }
+}
+
+#[test]
+fn provider_labels_map_to_categories() {
+    assert_eq!(ExposureCategory::from_label("Sanctioned Entity"), ExposureCategory::Sanctions);
+    assert_eq!(ExposureCategory::from_label("coin tumbler"), ExposureCategory::Mixer);
+    assert_eq!(ExposureCategory::from_label("Exchange Hack"), ExposureCategory::StolenFunds);
+    assert_eq!(ExposureCategory::from_label("exchange"), ExposureCategory::Other);
+    assert!(ExposureCategory::ChildAbuse.is_prohibited());
+    assert!(ExposureCategory::Ransomware.is_severe());
+    assert!(!ExposureCategory::Gambling.is_severe());
+}
+
+#[tokio::test]
+async fn addresses_are_normalized_deduplicated_and_capped() {
+    let (service, _) = scoring(FakeProvider::default(), |c| c.aml.chain_analytics.max_addresses_per_account = 2);
+    let spaced = LinkedAddress {
+        chain: " Ethereum ".to_string(),
+        address: " 0xabc ".to_string(),
+    };
+    
+    assert_eq!(service.link_address(&account(1), spaced).await.unwrap(), [linked("0xabc")]);
+    assert_eq!(service.link_address(&account(1), linked("0xabc")).await.unwrap().len(), 1);
+    service.link_address(&account(1), linked("0xdef")).await.unwrap();
+    assert!(service.link_address(&account(1), linked("0x123")).await.is_err());
+    
+    assert!(service.link_address(&account(2), linked("0x ab")).await.is_err());
+    let bad_chain = LinkedAddress {
+        chain: "eth/main".to_string(),
+        address: "0xabc".to_string(),
+    };
+    assert!(service.link_address(&account(2), bad_chain).await.is_err());
+    assert!(service.addresses(&account(2)).await.is_empty());
+}
+
+#[tokio::test]
+async fn scores_are_cached_within_their_ttl() {
+    let (service, provider) = scoring(scored(&[("0xabc", 0.2, vec![])]), |_| {});
+    
+    service.address_risk("ethereum", "0xabc").await.unwrap();
+    service.address_risk("ethereum", "0xabc").await.unwrap();
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    
+    let (service, provider) = scoring(scored(&[("0xabc", 0.2, vec![])]), |c| {
+        c.aml.chain_analytics.cache_ttl_secs = 0
+    });
+    service.address_risk("ethereum", "0xabc").await.unwrap();
+    service.address_risk("ethereum", "0xabc").await.unwrap();
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn the_worst_address_sets_the_profile() {
+    let (service, _) = scoring(
+        scored(&[
+            ("0xabc", 0.2, vec![exposure(ExposureCategory::Gambling, false, 0.3)]),
+            ("0xdef", 0.5, vec![exposure(ExposureCategory::Gambling, false, 0.1)]),
+        ]),
+        |_| {},
+    );
+    assert!(service.account_profile(&account(1)).await.unwrap().is_none());
+    
+    service.link_address(&account(1), linked("0xabc")).await.unwrap();
+    service.link_address(&account(1), linked("0xdef")).await.unwrap();
+    let profile = service.account_profile(&account(1)).await.unwrap().unwrap();
+    
+    assert_eq!(profile.score, 0.5);
+    assert_eq!(profile.risk_level, AmlRiskLevel::Medium);
+    assert_eq!(profile.addresses.len(), 2);
+    assert_eq!(profile.exposures.len(), 1);
+    assert_eq!(profile.exposures[0].share, 0.3);
+}
+
+#[tokio::test]
+async fn direct_exposure_floors_the_risk_level() {
+    let (service, _) = scoring(
+        scored(&[
+            ("0xmixer", 0.1, vec![exposure(ExposureCategory::Mixer, true, 0.05)]),
+            ("0xdust", 0.1, vec![exposure(ExposureCategory::Mixer, true, 0.001)]),
+            ("0xindirect", 0.1, vec![exposure(ExposureCategory::Sanctions, false, 0.5)]),
+            ("0xsanctioned", 0.1, vec![exposure(ExposureCategory::Sanctions, true, 0.001)]),
+        ]),
+        |_| {},
+    );
+    let level = |n: u32, address: &'static str| {
+        let service = &service;
+        async move {
+            service.link_address(&account(n), linked(address)).await.unwrap();
+            service.account_profile(&account(n)).await.unwrap().unwrap().risk_level
+        }
+    };
+    
+    assert_eq!(level(1, "0xmixer").await, AmlRiskLevel::High);
+    assert_eq!(level(2, "0xdust").await, AmlRiskLevel::Low);
+    assert_eq!(level(3, "0xindirect").await, AmlRiskLevel::Low);
+    assert_eq!(level(4, "0xsanctioned").await, AmlRiskLevel::Critical);
+}
+
+#[tokio::test]
+async fn disabled_scoring_and_missing_providers() {
+    let (service, provider) = scoring(scored(&[("0xabc", 0.9, vec![])]), |c| {
+        c.aml.chain_analytics.enabled = false
+    });
+    service.link_address(&account(1), linked("0xabc")).await.unwrap();
+    assert!(service.account_profile(&account(1)).await.unwrap().is_none());
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+    
+    let unconfigured = ChainAnalyticsService::with_provider(
+        Arc::new(LiveConfig::new(ComplianceConfig::default())),
+        Arc::new(ProviderCredentialStore::new(None)),
+        None,
+    );
+    assert!(matches!(
+        unconfigured.address_risk("ethereum", "0xabc").await,
+        Err(ComplianceError::ProviderUnavailable { .. })
+    ));
+}
+
+#[test]
+fn on_chain_risk_raises_but_never_lowers_attestations() {
+    let profile = |risk_level| ChainRiskProfile {
+        score: 0.0,
+        risk_level,
+        exposures: vec![],
+        addresses: vec![],
+    };
+    let mut attestation = common::attestation(&account(1), Utc::now(), Duration::days(30));
+    
+    apply_profile(&mut attestation, &profile(AmlRiskLevel::High));
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::High);
+    apply_profile(&mut attestation, &profile(AmlRiskLevel::Low));
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::High);
+}
@@ -0,0 +1,84 @@
+//! Verifier-issued challenges binding proofs to a nonce and audience
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::challenges::ChallengeService;
+use compliance_backend::compliance::claims::ClaimKind;
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use std::sync::Arc;
+
+const AUDIENCE: &str = "exchange-a.example";
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn service() -> (ChallengeService, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    (ChallengeService::new(300).with_clock(clock.clone()), clock)
+}
+
+fn rejection<T: std::fmt::Debug>(result: compliance_backend::Result<T>) -> String {
+    match result {
+        Err(ComplianceError::ChallengeRejected { reason }) => reason,
+        other => panic!("expected a rejected challenge, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn challenges_carry_a_fresh_nonce_and_their_lifetime() {
+    let (service, _) = service();
+    let first = service.issue(AUDIENCE, &account(1), None, vec![], None).await.unwrap();
+    let second = service.issue(AUDIENCE, &account(1), None, vec![], None).await.unwrap();
+    
+    assert_eq!(first.nonce.len(), 64);
+    assert!(hex::decode(&first.nonce).is_ok());
+    assert_ne!(first.nonce, second.nonce);
+    assert_eq!(first.expires_at - first.issued_at, Duration::seconds(300));
+    assert!(first.consumed_at.is_none());
+}
+
+#[tokio::test]
+async fn requested_claims_are_deduplicated_and_audiences_required() {
+    let (service, _) = service();
+    let claims = vec![ClaimKind::RiskBand, ClaimKind::KycTier, ClaimKind::RiskBand];
+    let challenge = service.issue(AUDIENCE, &account(1), None, claims, None).await.unwrap();
+    assert_eq!(challenge.claims, [ClaimKind::KycTier, ClaimKind::RiskBand]);
+    
+    assert!(matches!(
+        service.issue("  ", &account(1), None, vec![], None).await,
+        Err(ComplianceError::Validation { .. })
+    ));
+}
+
+#[tokio::test]
+async fn a_nonce_is_consumed_once_by_its_audience_for_its_account() {
+    let (service, _) = service();
+    let challenge = service.issue(AUDIENCE, &account(1), None, vec![], None).await.unwrap();
+    
+    assert_eq!(service.get_open(&challenge.nonce, &account(1)).await.unwrap().nonce, challenge.nonce);
+    assert!(rejection(service.consume(&challenge.nonce, "exchange-b.example", &account(1)).await)
+        .contains("different audience"));
+    assert!(rejection(service.consume(&challenge.nonce, AUDIENCE, &account(2)).await).contains("different account"));
+    
+    let consumed = service.consume(&challenge.nonce, AUDIENCE, &account(1)).await.unwrap();
+    assert!(consumed.consumed_at.is_some());
+    assert!(rejection(service.consume(&challenge.nonce, AUDIENCE, &account(1)).await).contains("already been used"));
+    assert!(rejection(service.get_open(&challenge.nonce, &account(1)).await).contains("already been used"));
+}
+
+#[tokio::test]
+async fn unknown_and_expired_nonces_are_rejected() {
+    let (service, clock) = service();
+    assert!(rejection(service.consume(&"00".repeat(32), AUDIENCE, &account(1)).await).contains("unknown nonce"));
+    
+    let challenge = service.issue(AUDIENCE, &account(1), None, vec![], None).await.unwrap();
+    clock.advance(Duration::seconds(300));
+    assert!(rejection(service.get_open(&challenge.nonce, &account(1)).await).contains("expired"));
+    assert!(rejection(service.consume(&challenge.nonce, AUDIENCE, &account(1)).await).contains("expired"));
+    
+    // Issuing prunes expired challenges
+    service.issue(AUDIENCE, &account(1), None, vec![], None).await.unwrap();
+    assert!(rejection(service.get_open(&challenge.nonce, &account(1)).await).contains("unknown nonce"));
+}
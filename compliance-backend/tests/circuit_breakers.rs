@@ -0,0 +1,96 @@
+//! Provider circuit breakers
+
+use compliance_backend::compliance::breaker::{BreakerState, CircuitBreaker, Provider, ProviderBreakers};
+use compliance_backend::config::{BreakerConfig, CheckCriticality, FallbackPolicy, ProviderResilienceConfig};
+use compliance_backend::types::AccountId;
+use compliance_backend::{ComplianceError, Result};
+use std::time::Duration;
+
+fn breaker(open_duration_secs: u64) -> CircuitBreaker {
+    CircuitBreaker::new(
+        Provider::Sanctions,
+        BreakerConfig {
+            failure_threshold: 2,
+            open_duration_secs,
+            half_open_max_probes: 1,
+            call_timeout_secs: 30,
+            fallback: FallbackPolicy::FailClosed,
+            criticality: CheckCriticality::Critical,
+        },
+    )
+}
+
+fn outage() -> ComplianceError {
+    std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "provider down").into()
+}
+
+async fn fail(breaker: &CircuitBreaker) {
+    assert!(breaker.call(async { Err::<(), _>(outage()) }).await.is_err());
+}
+
+#[tokio::test]
+async fn consecutive_failures_open_the_breaker() {
+    let breaker = breaker(3_600);
+    fail(&breaker).await;
+    assert!(breaker.is_closed());
+    fail(&breaker).await;
+    assert_eq!(breaker.status().state, BreakerState::Open);
+    
+    assert!(matches!(
+        breaker.call(async { Ok(()) }).await,
+        Err(ComplianceError::ProviderUnavailable { .. })
+    ));
+    assert_eq!(breaker.status().rejected_calls, 1);
+}
+
+#[tokio::test]
+async fn provider_answers_do_not_count_as_failures() {
+    let breaker = breaker(3_600);
+    for _ in 0..3 {
+        let answer = breaker.call(async { Err::<(), _>(ComplianceError::validation("name", "empty")) }).await;
+        assert!(answer.is_err());
+    }
+    assert!(breaker.is_closed());
+    assert_eq!(breaker.status().total_failures, 0);
+}
+
+#[tokio::test]
+async fn a_successful_probe_closes_the_breaker() {
+    let breaker = breaker(0);
+    fail(&breaker).await;
+    fail(&breaker).await;
+    
+    breaker.call(async { Ok(()) }).await.unwrap();
+    assert!(breaker.is_closed());
+    assert_eq!(breaker.status().consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn a_cancelled_probe_releases_its_slot() {
+    let breaker = breaker(0);
+    fail(&breaker).await;
+    fail(&breaker).await;
+    
+    let probe = breaker.call(std::future::pending::<Result<()>>());
+    assert!(tokio::time::timeout(Duration::from_millis(10), probe).await.is_err());
+    assert_eq!(breaker.status().state, BreakerState::HalfOpen);
+    
+    breaker.call(async { Ok(()) }).await.unwrap();
+    assert!(breaker.is_closed());
+}
+
+#[tokio::test]
+async fn retries_are_queued_per_account_and_provider() {
+    let breakers = ProviderBreakers::new(&ProviderResilienceConfig::default());
+    let account_id = AccountId::parse(&format!("0x{:030x}", 1)).unwrap();
+    breakers.queue_retry(&account_id, Provider::Kyc);
+    breakers.queue_retry(&account_id, Provider::ChainAnalytics);
+    breakers.queue_retry(&account_id, Provider::Kyc);
+    assert_eq!(breakers.retry_queue_depth(), 2);
+    
+    let ready = breakers.take_ready_retries();
+    let providers: Vec<_> = ready.iter().map(|retry| retry.provider).collect();
+    assert_eq!(providers, [Provider::Kyc, Provider::ChainAnalytics]);
+    assert!(ready.iter().all(|retry| retry.account_id == account_id && retry.client.is_none()));
+    assert_eq!(breakers.retry_queue_depth(), 0);
+}
@@ -0,0 +1,102 @@
+//! Operator command-line tool and the client administration behind it
+
+use compliance_backend::compliance::clients::ClientRegistry;
+use compliance_backend::crypto::AttestationSigner;
+use compliance_backend::types::{ClientEnvironment, ComplianceLevel};
+use compliance_backend::ComplianceError;
+use serde_json::Value;
+use std::process::{Command, Output};
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_compliance-cli"))
+        .args(args)
+        .env_remove("COMPLIANCE_OPERATOR_TOKEN")
+        .env_remove("COMPLIANCE_API_KEY")
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn generated_signing_keys_are_usable_by_the_backend() {
+    let output = cli(&["keys", "generate-signing", "--key-id", "2025-06"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    
+    let printed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(printed["key_id"], "2025-06");
+    let seed = printed["seed"].as_str().unwrap();
+    assert_eq!(seed.len(), 64);
+    
+    let signer = AttestationSigner::from_hex_seed("2025-06", seed).unwrap();
+    assert_eq!(printed["public_key"], hex::encode(signer.verifying_key().to_bytes()));
+    
+    let again = cli(&["keys", "generate-signing", "--key-id", "2025-06"]);
+    let again: Value = serde_json::from_slice(&again.stdout).unwrap();
+    assert_ne!(again["seed"], printed["seed"]);
+}
+
+#[test]
+fn admin_commands_require_an_operator_token() {
+    for args in [
+        &["clients", "create", "--name", "Acme"][..],
+        &["keys", "rotate-client", "00000000-0000-0000-0000-000000000001"],
+        &["--api-url", "http://127.0.0.1:9", "clients", "create", "--name", "Acme"],
+    ] {
+        let output = cli(args);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stderr(&output).contains("operator token is required"), "{}", stderr(&output));
+    }
+}
+
+#[test]
+fn malformed_arguments_are_rejected_before_running() {
+    let bad_level = cli(&["clients", "create", "--name", "Acme", "--compliance-level", "platinum"]);
+    assert_eq!(bad_level.status.code(), Some(2));
+    assert!(stderr(&bad_level).contains("unknown compliance level"));
+    
+    let bad_class = cli(&["proofs", "verify", "envelope", "--audience", "a", "--asset-class", "bond"]);
+    assert_eq!(bad_class.status.code(), Some(2));
+    assert!(stderr(&bad_class).contains("unknown asset class"));
+    
+    assert_eq!(cli(&["keys", "rotate-client", "not-a-uuid"]).status.code(), Some(2));
+    assert_eq!(cli(&[]).status.code(), Some(2));
+}
+
+#[tokio::test]
+async fn created_clients_get_a_fresh_api_key() {
+    let registry = ClientRegistry::new();
+    let first = registry.create(" Acme ", None, ComplianceLevel::Enhanced, None, false).await.unwrap();
+    let second = registry.create("Globex", None, ComplianceLevel::Basic, None, false).await.unwrap();
+    
+    assert_eq!(first.name, "Acme");
+    assert_eq!(first.compliance_level, ComplianceLevel::Enhanced);
+    assert!(first.api_key.starts_with("ztc_"));
+    assert_ne!(first.api_key, second.api_key);
+    assert_eq!(registry.find_by_api_key(&first.api_key).await.unwrap().unwrap().id, first.id);
+    
+    assert!(matches!(
+        registry.create("  ", None, ComplianceLevel::Basic, None, false).await,
+        Err(ComplianceError::Validation { .. })
+    ));
+    let plaintext = Some("http://acme.example/hooks".to_string());
+    assert!(registry.create("Acme", plaintext, ComplianceLevel::Basic, None, false).await.is_err());
+}
+
+#[tokio::test]
+async fn rotating_an_api_key_revokes_the_previous_one() {
+    let registry = ClientRegistry::new();
+    let client = registry.create("Acme", None, ComplianceLevel::Basic, None, false).await.unwrap();
+    
+    let rotated = registry.rotate_api_key(client.id, ClientEnvironment::Production).await.unwrap();
+    assert_ne!(rotated.api_key, client.api_key);
+    assert!(registry.find_by_api_key(&client.api_key).await.unwrap().is_none());
+    assert_eq!(registry.find_by_api_key(&rotated.api_key).await.unwrap().unwrap().id, client.id);
+    
+    assert!(matches!(
+        registry.rotate_api_key(uuid::Uuid::new_v4(), ClientEnvironment::Production).await,
+        Err(ComplianceError::BusinessClientNotFound { .. })
+    ));
+}
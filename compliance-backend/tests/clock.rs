@@ -0,0 +1,63 @@
+//! Time-dependent behavior driven by a mock clock
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::audit::AuditLog;
+use compliance_backend::clock::{Clock, MockClock};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore, AttestationStatus};
+use compliance_backend::compliance::challenges::ChallengeService;
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+
+fn start() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()
+}
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+#[tokio::test]
+async fn challenges_expire_on_the_injected_clock() {
+    let clock = Arc::new(MockClock::new(start()));
+    let challenges = ChallengeService::new(300).with_clock(clock.clone());
+    let challenge = challenges.issue("verifier.example", &account(), None, vec![], None).await.unwrap();
+    assert_eq!(challenge.expires_at, start() + Duration::seconds(300));
+    
+    clock.advance(Duration::seconds(299));
+    assert!(challenges.get_open(&challenge.nonce, &account()).await.is_ok());
+    
+    clock.advance(Duration::seconds(1));
+    assert!(challenges.get_open(&challenge.nonce, &account()).await.is_err());
+}
+
+#[tokio::test]
+async fn audit_entries_are_timestamped_by_the_clock() {
+    let clock = Arc::new(MockClock::new(start()));
+    let audit = AuditLog::with_clock(clock.clone());
+    
+    let first = audit.record("tester", "test.first", None, serde_json::json!({})).await;
+    clock.advance(Duration::minutes(5));
+    let second = audit.record("tester", "test.second", None, serde_json::json!({})).await;
+    
+    assert_eq!(first.recorded_at, start());
+    assert_eq!(second.recorded_at, start() + Duration::minutes(5));
+}
+
+#[tokio::test]
+async fn attestations_expire_when_the_clock_passes_expiry() {
+    let clock = Arc::new(MockClock::new(start()));
+    let events = AttestationEventStore::with_clock(clock.clone());
+    let attestation = common::attestation(&account(), start(), Duration::days(90));
+    events.append(&account(), AttestationEvent::AttestationIssued { attestation }).await.unwrap();
+    
+    clock.advance(Duration::days(89));
+    assert!(events.expire_due(clock.now()).await.unwrap().is_empty());
+    
+    clock.advance(Duration::days(1));
+    assert_eq!(events.expire_due(clock.now()).await.unwrap(), vec![account()]);
+    let state = events.project(&account(), None).await.unwrap().unwrap();
+    assert_eq!(state.status, AttestationStatus::Expired);
+    assert_eq!(state.updated_at, start() + Duration::days(90));
+}
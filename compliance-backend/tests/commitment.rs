@@ -0,0 +1,128 @@
+//! Test vectors for the canonical commitment encoding
+//!
+//! Implementations in other languages should reproduce these element
+//! sequences; the commitment is the RPO hash of the sequence.
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::crypto::commitment::{encode_attestation, COMMITMENT_VERSION};
+use compliance_backend::crypto::{attestation_commitment, CommitmentDomain, FieldEncoder, ProofHash};
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceAttestation, KycStatus};
+use miden_crypto::hash::rpo::Rpo256;
+use miden_crypto::Felt;
+use uuid::Uuid;
+
+/// Changes to an attestation, each expected to change its commitment
+type Mutations = Vec<Box<dyn Fn(&mut ComplianceAttestation)>>;
+
+fn felts(values: &[u64]) -> Vec<Felt> {
+    values.iter().map(|value| Felt::new(*value)).collect()
+}
+
+fn attestation() -> ComplianceAttestation {
+    let account_id = AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap();
+    let created_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    ComplianceAttestation {
+        id: Uuid::parse_str("00112233-4455-6677-8899-aabbccddeeff").unwrap(),
+        aml_risk_level: AmlRiskLevel::Medium,
+        proof_hash: ProofHash::from_bytes([0x11; 32]).unwrap(),
+        ..common::attestation(&account_id, created_at, Duration::days(366))
+    }
+}
+
+#[test]
+fn encoding_starts_with_version_and_domain() {
+    assert_eq!(COMMITMENT_VERSION, 2);
+    assert_eq!(FieldEncoder::new(CommitmentDomain::Attestation).elements(), felts(&[2, 1]));
+    assert_eq!(FieldEncoder::new(CommitmentDomain::OracleLeaf).elements(), felts(&[2, 2]));
+}
+
+#[test]
+fn byte_strings_are_length_prefixed_little_endian_limbs() {
+    let mut encoder = FieldEncoder::new(CommitmentDomain::Attestation);
+    encoder.bytes(&[]).text("abc").bytes(&[1, 2, 3, 4, 5]);
+    
+    assert_eq!(
+        &encoder.elements()[2..],
+        felts(&[0, 3, 0x0063_6261, 5, 0x0403_0201, 0x0000_0005])
+    );
+}
+
+#[test]
+fn timestamps_and_flags_are_single_elements() {
+    let mut encoder = FieldEncoder::new(CommitmentDomain::Attestation);
+    encoder
+        .timestamp(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+        .timestamp(Utc.with_ymd_and_hms(1969, 12, 31, 0, 0, 0).unwrap())
+        .flag(true)
+        .flag(false);
+    
+    assert_eq!(&encoder.elements()[2..], felts(&[1_704_067_200, 0, 1, 0]));
+}
+
+#[test]
+fn attestation_encoding_vector() {
+    #[rustfmt::skip]
+    let expected = felts(&[
+        // version, domain
+        2, 1,
+        // id
+        16, 0x3322_1100, 0x7766_5544, 0xbbaa_9988, 0xffee_ddcc,
+        // account id "0x0123456789abcdef0123456789abcd"
+        32, 0x3130_7830, 0x3534_3332, 0x3938_3736, 0x6463_6261,
+        0x3130_6665, 0x3534_3332, 0x3938_3736, 0x6463_6261,
+        // KYC verified, AML medium, sanctions cleared
+        1, 1, 1,
+        // created at, expires at
+        1_704_067_200, 1_735_689_600,
+        // proof hash
+        32, 0x1111_1111, 0x1111_1111, 0x1111_1111, 0x1111_1111,
+        0x1111_1111, 0x1111_1111, 0x1111_1111, 0x1111_1111,
+        // no partial checks
+        0,
+    ]);
+    
+    assert_eq!(encode_attestation(&attestation()).elements(), expected);
+    assert_eq!(attestation_commitment(&attestation()).digest(), Rpo256::hash_elements(&expected));
+}
+
+#[test]
+fn commitment_bytes_match_digest() {
+    let commitment = attestation_commitment(&attestation());
+    
+    assert_eq!(commitment.to_bytes(), commitment.digest().as_bytes());
+    assert_eq!(commitment.to_hex(), commitment.digest().to_hex());
+}
+
+#[test]
+fn every_field_changes_the_commitment() {
+    let base = attestation_commitment(&attestation());
+    let variants: Mutations = vec![
+        Box::new(|a| a.id = Uuid::nil()),
+        Box::new(|a| a.account_id = AccountId::parse("0x0123456789abcdef0123456789abce").unwrap()),
+        Box::new(|a| a.kyc_status = KycStatus::Expired),
+        Box::new(|a| a.aml_risk_level = AmlRiskLevel::Low),
+        Box::new(|a| a.sanctions_cleared = false),
+        Box::new(|a| a.created_at += Duration::seconds(1)),
+        Box::new(|a| a.expires_at += Duration::seconds(1)),
+        Box::new(|a| a.proof_hash = ProofHash::from_bytes([0x12; 32]).unwrap()),
+        Box::new(|a| a.partial = vec!["chain_analytics".to_string()]),
+    ];
+    
+    for change in variants {
+        let mut changed = attestation();
+        change(&mut changed);
+        assert_ne!(attestation_commitment(&changed), base);
+    }
+}
+
+#[test]
+fn domains_separate_equal_fields() {
+    let mut attestation = FieldEncoder::new(CommitmentDomain::Attestation);
+    let mut leaf = FieldEncoder::new(CommitmentDomain::OracleLeaf);
+    attestation.value(7);
+    leaf.value(7);
+    
+    assert_ne!(attestation.commit(), leaf.commit());
+}
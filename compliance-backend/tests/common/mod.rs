@@ -0,0 +1,28 @@
+//! Fixtures shared by the integration tests
+//!
+//! Tests change the fields they exercise with struct update syntax, e.g.
+//! `ComplianceAttestation { kyc_status: KycStatus::Rejected, ..attestation(..) }`.
+
+#![allow(dead_code)]
+
+use chrono::{DateTime, Duration, Utc};
+use compliance_backend::crypto::ProofHash;
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceAttestation, KycStatus};
+use uuid::Uuid;
+
+/// Complete attestation of a verified, low-risk, sanctions-cleared account
+///
+/// Issued at `created_at` and valid for `validity`.
+pub fn attestation(account_id: &AccountId, created_at: DateTime<Utc>, validity: Duration) -> ComplianceAttestation {
+    ComplianceAttestation {
+        id: Uuid::new_v4(),
+        account_id: account_id.clone(),
+        kyc_status: KycStatus::Verified,
+        aml_risk_level: AmlRiskLevel::Low,
+        sanctions_cleared: true,
+        created_at,
+        expires_at: created_at + validity,
+        proof_hash: ProofHash::of(b"proof"),
+        partial: Vec::new(),
+    }
+}
@@ -0,0 +1,78 @@
+//! Compliance oracle leaves, proofs and publication scripts
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::account_components::foreign::miden_account_id;
+use compliance_backend::compliance::account_components::oracle_component::{
+    compile_oracle_component, publish_script, render, GET_ROOT_PROCEDURE, PUBLISH_PROCEDURE,
+};
+use compliance_backend::compliance::oracle::{oracle_leaf, ComplianceOracle};
+use compliance_backend::crypto::{CommitmentDomain, FieldEncoder};
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use compliance_backend::ComplianceError;
+
+/// A public regular account with updatable code
+const MEMBER: &str = "0xac0000000000dd100000ee000000fc";
+
+#[test]
+fn leaves_bind_the_account_level_and_expiry() {
+    let account = miden_account_id(&AccountId::parse(MEMBER).unwrap()).unwrap();
+    let expires_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    
+    let leaf = oracle_leaf(account, &ComplianceLevel::Enhanced, expires_at);
+    assert_eq!(leaf, oracle_leaf(account, &ComplianceLevel::Enhanced, expires_at));
+    assert_ne!(leaf, oracle_leaf(account, &ComplianceLevel::Basic, expires_at));
+    assert_ne!(leaf, oracle_leaf(account, &ComplianceLevel::Enhanced, expires_at + Duration::seconds(1)));
+}
+
+#[test]
+fn leaves_are_separated_from_other_commitment_domains() {
+    let account = miden_account_id(&AccountId::parse(MEMBER).unwrap()).unwrap();
+    let expires_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    
+    let mut encoder = FieldEncoder::new(CommitmentDomain::OracleLeaf);
+    encoder
+        .felt(account.prefix().as_felt())
+        .felt(account.suffix())
+        .value(2)
+        .timestamp(expires_at);
+    let leaf = oracle_leaf(account, &ComplianceLevel::Enhanced, expires_at);
+    assert_eq!(leaf, encoder.commit(), "Enhanced is encoded as level 2");
+    
+    let mut other = FieldEncoder::new(CommitmentDomain::Attestation);
+    other
+        .felt(account.prefix().as_felt())
+        .felt(account.suffix())
+        .value(2)
+        .timestamp(expires_at);
+    assert_ne!(leaf, other.commit());
+}
+
+#[tokio::test]
+async fn nothing_is_served_before_the_first_publication() {
+    let oracle = ComplianceOracle::new();
+    
+    assert!(matches!(oracle.latest().await, Err(ComplianceError::OracleNotPublished)));
+    assert!(oracle.history().await.is_empty());
+    
+    let proof = oracle.inclusion_proof(&AccountId::parse(MEMBER).unwrap()).await;
+    assert!(matches!(proof, Err(ComplianceError::OracleNotPublished)));
+}
+
+#[test]
+fn the_component_exposes_publish_and_read_procedures() {
+    let code = render();
+    assert!(code.contains(&format!("export.{}", PUBLISH_PROCEDURE)));
+    assert!(code.contains(&format!("export.{}", GET_ROOT_PROCEDURE)));
+    
+    compile_oracle_component().unwrap();
+}
+
+#[test]
+fn publication_scripts_compile_for_any_root() {
+    let account = miden_account_id(&AccountId::parse(MEMBER).unwrap()).unwrap();
+    let expires_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let root = oracle_leaf(account, &ComplianceLevel::Basic, expires_at).digest();
+    
+    publish_script(root, 1, 1).unwrap();
+    publish_script(root, u64::from(u32::MAX), 0).unwrap();
+}
@@ -0,0 +1,102 @@
+//! Entity tags and long-poll parameters of the account compliance snapshot
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::api::accounts::{etag_matches, snapshot_etag, ComplianceQuery, ComplianceSnapshot};
+use compliance_backend::compliance::attestation_events::{AttestationState, AttestationStatus};
+use compliance_backend::config::ServerConfig;
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use std::collections::HashMap;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn snapshot() -> ComplianceSnapshot {
+    let issued_at = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let attestation = common::attestation(&account(1), issued_at, Duration::days(365));
+    ComplianceSnapshot {
+        account_id: account(1),
+        as_of: issued_at + Duration::days(1),
+        state: Some(AttestationState {
+            attestation,
+            status: AttestationStatus::Active,
+            version: 1,
+            updated_at: issued_at,
+            revocation_reason: None,
+            kyc_rejection: None,
+            notarization: None,
+        }),
+        compliance_level: Some(ComplianceLevel::Standard),
+        sanctions_list_versions: HashMap::from([("ofac".to_string(), "2025-06-01".to_string())]),
+    }
+}
+
+#[test]
+fn etags_are_weak_and_ignore_the_snapshot_time() {
+    let first = snapshot();
+    let etag = snapshot_etag(&first).unwrap();
+    assert!(etag.starts_with("W/\"") && etag.ends_with('"'));
+    assert_eq!(etag.len(), 36);
+    
+    let mut later = snapshot();
+    later.as_of = first.as_of + Duration::hours(1);
+    later.state = first.state.clone();
+    assert_eq!(snapshot_etag(&later).unwrap(), etag);
+}
+
+#[test]
+fn etags_change_with_the_snapshot_content() {
+    let base = snapshot();
+    let etag = snapshot_etag(&base).unwrap();
+    
+    let mut revoked = snapshot();
+    revoked.state = base.state.clone();
+    let state = revoked.state.as_mut().unwrap();
+    state.status = AttestationStatus::Revoked;
+    state.version = 2;
+    assert_ne!(snapshot_etag(&revoked).unwrap(), etag);
+    
+    let mut downgraded = snapshot();
+    downgraded.state = base.state.clone();
+    downgraded.compliance_level = None;
+    assert_ne!(snapshot_etag(&downgraded).unwrap(), etag);
+    
+    let mut relisted = snapshot();
+    relisted.state = base.state.clone();
+    relisted.sanctions_list_versions.insert("ofac".to_string(), "2025-06-02".to_string());
+    assert_ne!(snapshot_etag(&relisted).unwrap(), etag);
+    
+    let mut other = snapshot();
+    other.state = base.state.clone();
+    other.account_id = account(2);
+    assert_ne!(snapshot_etag(&other).unwrap(), etag);
+}
+
+#[test]
+fn if_none_match_is_compared_weakly() {
+    let etag = "W/\"abc\"";
+    assert!(etag_matches("W/\"abc\"", etag));
+    assert!(etag_matches("\"abc\"", etag));
+    assert!(etag_matches("\"xyz\", W/\"abc\"", etag));
+    assert!(etag_matches(" * ", etag));
+    
+    assert!(!etag_matches("W/\"abd\"", etag));
+    assert!(!etag_matches("\"xyz\", \"abcd\"", etag));
+    assert!(!etag_matches("", etag));
+}
+
+#[test]
+fn long_polls_are_requested_in_seconds() {
+    let query: ComplianceQuery = serde_json::from_value(serde_json::json!({ "wait": 30 })).unwrap();
+    assert_eq!(query.wait, Some(30));
+    assert!(query.as_of.is_none());
+    
+    let query: ComplianceQuery = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert!(query.wait.is_none());
+    
+    let server = ServerConfig::default();
+    assert_eq!(server.max_long_poll_secs, 25);
+    assert!(server.max_long_poll_secs < server.request_timeout);
+}
@@ -0,0 +1,90 @@
+//! Evidence needed to upgrade an account's compliance level
+
+use compliance_backend::compliance::step_up::{StepUpRequirement, StepUpService, StepUpStatus};
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[test]
+fn upgrades_need_only_the_evidence_the_target_level_adds() {
+    use ComplianceLevel::*;
+    use StepUpRequirement::*;
+    
+    assert!(StepUpService::upgrade_requirements(&Basic, &Standard).is_empty());
+    assert_eq!(
+        StepUpService::upgrade_requirements(&Standard, &Enhanced),
+        [SourceOfFundsDeclaration]
+    );
+    assert_eq!(
+        StepUpService::upgrade_requirements(&Standard, &InstitutionalGrade),
+        [SourceOfFundsDeclaration, ReLiveness]
+    );
+    assert_eq!(StepUpService::upgrade_requirements(&Enhanced, &InstitutionalGrade), [ReLiveness]);
+}
+
+#[tokio::test]
+async fn upgrade_sessions_collect_only_the_outstanding_evidence() {
+    let service = StepUpService::new(Arc::new(LiveConfig::new(ComplianceConfig::default())));
+    let account_id = AccountId::parse(&format!("0x{:030x}", 7)).unwrap();
+    let client_id = Uuid::new_v4();
+    let session = service
+        .create_upgrade_session(
+            client_id,
+            &account_id,
+            ComplianceLevel::Standard,
+            ComplianceLevel::Enhanced,
+            vec![StepUpRequirement::SourceOfFundsDeclaration],
+        )
+        .await
+        .unwrap();
+    assert!(session.upgrade);
+    assert_eq!(session.current_level, Some(ComplianceLevel::Standard));
+    assert_eq!(session.requirements.len(), 1);
+    
+    let session = service
+        .submit_evidence(client_id, session.id, StepUpRequirement::SourceOfFundsDeclaration, "declaration".to_string())
+        .await
+        .unwrap();
+    assert_eq!(session.status, StepUpStatus::Completed);
+    
+    let regular = service
+        .create_session(
+            client_id,
+            &account_id,
+            Some(ComplianceLevel::Standard),
+            ComplianceLevel::InstitutionalGrade,
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+    assert!(!regular.upgrade);
+    assert_eq!(regular.requirements.len(), 3);
+}
+
+#[tokio::test]
+async fn sessions_are_scoped_to_the_client_that_opened_them() {
+    let service = StepUpService::new(Arc::new(LiveConfig::new(ComplianceConfig::default())));
+    let account_id = AccountId::parse(&format!("0x{:030x}", 8)).unwrap();
+    let owner = Uuid::new_v4();
+    let other = Uuid::new_v4();
+    let session = service
+        .create_session(owner, &account_id, None, ComplianceLevel::Standard, Vec::new())
+        .await
+        .unwrap();
+    
+    assert!(service.get_session(other, session.id).await.is_err());
+    assert!(service
+        .submit_evidence(other, session.id, StepUpRequirement::AdditionalDocument, Uuid::new_v4().to_string())
+        .await
+        .is_err());
+    assert!(service.cancel(other, session.id).await.is_err());
+    assert_eq!(service.get_session(owner, session.id).await.unwrap().status, StepUpStatus::Open);
+    
+    let theirs = service
+        .create_session(other, &account_id, None, ComplianceLevel::Standard, Vec::new())
+        .await
+        .unwrap();
+    assert_ne!(theirs.id, session.id);
+}
@@ -0,0 +1,140 @@
+//! Release tracking and storage migration of deployed compliance components
+
+use compliance_backend::compliance::account_components::migration::{
+    migration_script, migration_steps, ComponentKind, MigrationStatus, MigrationStep, MigrationTracker, RELEASES,
+};
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+#[test]
+fn every_component_has_a_current_release_with_a_layout() {
+    for kind in ComponentKind::ALL {
+        let current = kind.current();
+        assert_eq!(current.kind, kind);
+        assert!(RELEASES.iter().filter(|r| r.kind == kind).all(|r| r.version <= current.version));
+        
+        let slots = (current.slots)();
+        assert!(!slots.is_empty(), "{} has no slots", kind.name());
+        let mut indices: Vec<u8> = slots.iter().map(|(_, slot)| *slot).collect();
+        indices.sort();
+        indices.dedup();
+        assert_eq!(indices.len(), slots.len(), "{} reuses a slot", kind.name());
+    }
+}
+
+#[test]
+fn current_components_need_no_steps_and_unknown_releases_are_rejected() {
+    for kind in ComponentKind::ALL {
+        let current = kind.current().version;
+        assert!(migration_steps(kind, current).unwrap().is_empty());
+        assert!(matches!(
+            migration_steps(kind, current + 1),
+            Err(ComplianceError::Validation { .. })
+        ));
+    }
+}
+
+#[test]
+fn migration_scripts_apply_each_step_in_order() {
+    let script = migration_script(&[
+        MigrationStep::MoveSlot { from: 2, to: 5 },
+        MigrationStep::InitSlot { slot: 6, value: [1, 2, 3, 4] },
+        MigrationStep::ClearSlot { slot: 7 },
+    ]);
+    
+    assert!(script.starts_with("use.miden::account\n\nbegin\n"));
+    assert!(script.ends_with("end\n"));
+    let moved = script.find("push.2 exec.account::get_item").unwrap();
+    let stored = script.find("push.5 exec.account::set_item").unwrap();
+    let initialized = script.find("push.4.3.2.1 push.6 exec.account::set_item").unwrap();
+    let cleared = script.find("padw push.7 exec.account::set_item").unwrap();
+    assert!(moved < stored && stored < initialized && initialized < cleared);
+    assert!(script.contains("padw push.2 exec.account::set_item"), "the vacated slot is zeroed");
+}
+
+#[test]
+fn migration_steps_serialize_with_their_type() {
+    let json = serde_json::to_value(MigrationStep::MoveSlot { from: 1, to: 3 }).unwrap();
+    assert_eq!(json, serde_json::json!({"type": "move_slot", "from": 1, "to": 3}));
+}
+
+#[tokio::test]
+async fn detected_releases_set_the_migration_status() {
+    let tracker = MigrationTracker::default();
+    let current = ComponentKind::Kyc.current().version;
+    
+    let state = tracker.record(&account(1), ComponentKind::Kyc, Some(current)).await;
+    assert_eq!(state.status, MigrationStatus::Current);
+    assert_eq!(state.target_version, current);
+    
+    let outdated = tracker.record(&account(2), ComponentKind::Kyc, Some(current - 1)).await;
+    assert_eq!(outdated.status, MigrationStatus::Outdated);
+    
+    let unrecognized = tracker.record(&account(3), ComponentKind::Kyc, None).await;
+    assert_eq!(unrecognized.status, MigrationStatus::Unrecognized);
+}
+
+#[tokio::test]
+async fn failures_stand_until_the_component_is_current() {
+    let tracker = MigrationTracker::default();
+    let account_id = account(1);
+    let current = ComponentKind::Aml.current().version;
+    tracker.record(&account_id, ComponentKind::Aml, Some(current - 1)).await;
+    
+    tracker.mark_failed(&account_id, ComponentKind::Aml, "out of gas").await.unwrap();
+    let state = tracker.record(&account_id, ComponentKind::Aml, Some(current - 1)).await;
+    assert_eq!(state.status, MigrationStatus::Failed);
+    assert_eq!(state.last_error.as_deref(), Some("out of gas"));
+    
+    let state = tracker.record(&account_id, ComponentKind::Aml, Some(current)).await;
+    assert_eq!(state.status, MigrationStatus::Current);
+    assert!(state.last_error.is_none());
+    
+    assert!(matches!(
+        tracker.mark_failed(&account(9), ComponentKind::Aml, "unknown").await,
+        Err(ComplianceError::AccountNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn only_outdated_known_releases_can_be_planned() {
+    let tracker = MigrationTracker::default();
+    let current = ComponentKind::Sanctions.current().version;
+    tracker.record(&account(1), ComponentKind::Sanctions, Some(current)).await;
+    tracker.record(&account(2), ComponentKind::Sanctions, None).await;
+    
+    let already = tracker.plan(&account(1), ComponentKind::Sanctions).await;
+    assert!(matches!(already, Err(ComplianceError::Validation { .. })));
+    let unrecognized = tracker.plan(&account(2), ComponentKind::Sanctions).await;
+    assert!(matches!(unrecognized, Err(ComplianceError::Validation { .. })));
+    let unseen = tracker.plan(&account(3), ComponentKind::Sanctions).await;
+    assert!(matches!(unseen, Err(ComplianceError::AccountNotFound { .. })));
+}
+
+#[tokio::test]
+async fn the_dashboard_lists_and_summarizes_states() {
+    let tracker = MigrationTracker::default();
+    let current = ComponentKind::Kyc.current().version;
+    tracker.record(&account(2), ComponentKind::Kyc, Some(current)).await;
+    tracker.record(&account(1), ComponentKind::Kyc, Some(current)).await;
+    tracker.record(&account(3), ComponentKind::Kyc, None).await;
+    tracker.record(&account(1), ComponentKind::Aml, Some(current - 1)).await;
+    
+    let kyc = tracker.list(Some(ComponentKind::Kyc), None).await;
+    let accounts: Vec<_> = kyc.iter().map(|state| state.account_id.clone()).collect();
+    assert_eq!(accounts, [account(1), account(2), account(3)]);
+    assert_eq!(tracker.list(None, Some(MigrationStatus::Outdated)).await.len(), 1);
+    assert_eq!(tracker.list(None, None).await.len(), 4);
+    
+    let summary = tracker.summary().await;
+    assert_eq!(summary.len(), ComponentKind::ALL.len());
+    let kyc = summary.iter().find(|s| s.kind == ComponentKind::Kyc).unwrap();
+    assert_eq!(kyc.accounts_by_version.get(&current), Some(&2));
+    assert_eq!(kyc.accounts_by_status.get(&MigrationStatus::Unrecognized), Some(&1));
+    let sanctions = summary.iter().find(|s| s.kind == ComponentKind::Sanctions).unwrap();
+    assert!(sanctions.accounts_by_status.is_empty());
+}
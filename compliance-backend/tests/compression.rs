@@ -0,0 +1,139 @@
+//! Response compression, streaming, and size metrics
+
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::Request;
+use axum::http::header;
+use axum::response::Response;
+use compliance_backend::api::compression::{compression_layer, sized, Streamed};
+use compliance_backend::api::response_sizes::{ResponseSizes, RouteSizeStats};
+use compliance_backend::config::{Config, ResponseConfig};
+use http_body_util::BodyExt;
+use std::convert::Infallible;
+use tower::{service_fn, Layer, ServiceExt};
+
+const PROOFS: &str = "POST /v1/accounts/{id}/proofs";
+
+/// Encoding the compression layer picks for a body of `size` bytes of
+/// `content_type`, when the client accepts `accept`
+async fn negotiated(config: &ResponseConfig, content_type: &'static str, size: usize, accept: &str) -> Option<String> {
+    let service = compression_layer(config).layer(service_fn(move |_: Request| async move {
+        let mut response = Response::new(Body::from(vec![b'a'; size]));
+        response.headers_mut().insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        Ok::<_, Infallible>(response)
+    }));
+    let request = Request::builder().header(header::ACCEPT_ENCODING, accept).body(Body::empty()).unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn responses_are_compressed_in_the_accepted_encoding() {
+    let config = ResponseConfig::default();
+    assert_eq!(negotiated(&config, "application/json", 4096, "gzip").await.as_deref(), Some("gzip"));
+    assert_eq!(negotiated(&config, "application/json", 4096, "br").await.as_deref(), Some("br"));
+    assert_eq!(negotiated(&config, "application/json", 4096, "identity").await, None);
+}
+
+#[tokio::test]
+async fn small_and_precompressed_responses_are_sent_as_is() {
+    let config = ResponseConfig::default();
+    assert_eq!(negotiated(&config, "application/json", 100, "gzip").await, None);
+    assert_eq!(negotiated(&config, "application/zip", 4096, "gzip").await, None);
+    assert_eq!(negotiated(&config, "text/event-stream", 4096, "gzip").await, None);
+}
+
+#[tokio::test]
+async fn disabled_compression_offers_no_encoding() {
+    let config = ResponseConfig {
+        compression: false,
+        ..ResponseConfig::default()
+    };
+    assert_eq!(negotiated(&config, "application/json", 4096, "gzip, br").await, None);
+    
+    let gzip_only = ResponseConfig {
+        br: false,
+        ..ResponseConfig::default()
+    };
+    assert_eq!(negotiated(&gzip_only, "application/json", 4096, "br, gzip").await.as_deref(), Some("gzip"));
+}
+
+#[tokio::test]
+async fn streamed_bodies_arrive_in_chunks() {
+    let bytes = Bytes::from((0..10u8).collect::<Vec<_>>());
+    let mut body = Streamed { chunk_bytes: 4 }.body(bytes.clone());
+    let mut chunks = Vec::new();
+    while let Some(frame) = body.frame().await {
+        chunks.push(frame.unwrap().into_data().unwrap());
+    }
+    assert_eq!(chunks.iter().map(Bytes::len).collect::<Vec<_>>(), [4, 4, 2]);
+    assert_eq!(chunks.concat(), bytes);
+}
+
+#[tokio::test]
+async fn only_large_responses_are_streamed() {
+    let config = ResponseConfig {
+        stream_threshold_bytes: 16,
+        stream_chunk_bytes: 8,
+        ..ResponseConfig::default()
+    };
+    let small = sized(&config, "application/json", vec![b'1'; 16]);
+    assert!(small.extensions().get::<Streamed>().is_none());
+    assert_eq!(small.headers()[header::CONTENT_TYPE], "application/json");
+    
+    let large = sized(&config, "application/json", vec![b'1'; 17]);
+    assert_eq!(large.extensions().get::<Streamed>(), Some(&Streamed { chunk_bytes: 8 }));
+    assert_eq!(large.headers()[header::CONTENT_TYPE], "application/json");
+    assert_eq!(to_bytes(large.into_body(), usize::MAX).await.unwrap().len(), 17);
+}
+
+#[tokio::test]
+async fn response_sizes_count_bytes_sent_per_route_and_encoding() {
+    let sizes = ResponseSizes::new();
+    let plain = sizes.track(PROOFS, Response::new(Body::from(vec![0u8; 300])));
+    to_bytes(plain.into_body(), usize::MAX).await.unwrap();
+    
+    let mut compressed = Response::new(Body::from(vec![0u8; 40]));
+    compressed.headers_mut().insert(header::CONTENT_ENCODING, "br".parse().unwrap());
+    let compressed = sizes.track(PROOFS, compressed);
+    to_bytes(compressed.into_body(), usize::MAX).await.unwrap();
+    
+    // A body never sent counts the response but none of its bytes
+    drop(sizes.track(PROOFS, Response::new(Body::from(vec![0u8; 500]))));
+    
+    assert_eq!(
+        sizes.stats(),
+        vec![
+            RouteSizeStats {
+                route: PROOFS.to_string(),
+                encoding: "br".to_string(),
+                responses: 1,
+                bytes: 40,
+            },
+            RouteSizeStats {
+                route: PROOFS.to_string(),
+                encoding: "identity".to_string(),
+                responses: 2,
+                bytes: 300,
+            },
+        ]
+    );
+}
+
+#[test]
+fn streaming_needs_chunks_no_larger_than_its_threshold() {
+    let mut config = Config::default();
+    config.server.responses.stream_chunk_bytes = 0;
+    config.server.responses.gzip = false;
+    config.server.responses.br = false;
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("server.responses.stream_chunk_bytes"));
+    assert!(violations.contains("server.responses: at least one of gzip and br"));
+    
+    let mut config = Config::default();
+    config.server.responses.stream_threshold_bytes = config.server.responses.stream_chunk_bytes - 1;
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("server.responses.stream_threshold_bytes"));
+}
@@ -0,0 +1,108 @@
+//! Notes with expiry, compliance-level and timelock spend conditions
+
+mod common;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use compliance_backend::compliance::account_components::foreign::{
+    miden_account_id, procedure_root, KYC_STATUS_PROCEDURE,
+};
+use compliance_backend::compliance::account_components::migration::ComponentKind;
+use compliance_backend::compliance::account_components::template::ComponentTemplates;
+use compliance_backend::compliance::conditional_notes::{ConditionalNoteBuilder, NoteCondition};
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use compliance_backend::ComplianceError;
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::note::NoteType;
+use miden_objects::{Felt, Word};
+
+/// A public regular account with updatable code
+const SENDER: &str = "0xac0000000000dd100000ee000000fc";
+
+fn sender() -> MidenAccountId {
+    miden_account_id(&AccountId::parse(SENDER).unwrap()).unwrap()
+}
+
+fn at(secs: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(secs, 0).unwrap()
+}
+
+fn serial_num() -> Word {
+    [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]
+}
+
+#[test]
+fn shortcuts_record_their_condition() {
+    let account_id = AccountId::parse(SENDER).unwrap();
+    let created_at = at(1_700_000_000);
+    let attestation = common::attestation(&account_id, created_at, Duration::days(30));
+    
+    let expiring = ConditionalNoteBuilder::before_attestation_expiry(sender(), Vec::new(), &attestation);
+    assert_eq!(expiring.conditions(), [NoteCondition::ConsumeBefore(attestation.expires_at)]);
+    
+    let gated = ConditionalNoteBuilder::for_level(sender(), Vec::new(), ComplianceLevel::Enhanced);
+    assert_eq!(gated.conditions(), [NoteCondition::MinimumLevel(ComplianceLevel::Enhanced)]);
+    
+    let locked = ConditionalNoteBuilder::timelocked(sender(), Vec::new(), created_at);
+    assert_eq!(locked.conditions(), [NoteCondition::ConsumeAfter(created_at)]);
+}
+
+#[test]
+fn time_conditions_compare_against_the_block_timestamp() {
+    let source = ConditionalNoteBuilder::new(sender(), Vec::new())
+        .consume_after(at(1_000))
+        .consume_before(at(2_000))
+        .script_source(&ComponentTemplates::default())
+        .unwrap();
+    
+    assert!(source.contains("exec.tx::get_block_timestamp push.1000 gte assert"));
+    assert!(source.contains("exec.tx::get_block_timestamp push.2000 lt assert"));
+    assert!(source.contains("exec.add_note_assets_to_account"));
+    assert!(!source.contains("call.0x"), "no KYC call without a level condition");
+}
+
+#[test]
+fn level_conditions_call_the_consumers_kyc_component() {
+    let templates = ComponentTemplates::default();
+    let root = procedure_root(ComponentKind::Kyc, &templates, KYC_STATUS_PROCEDURE).unwrap();
+    
+    let source = ConditionalNoteBuilder::for_level(sender(), Vec::new(), ComplianceLevel::Enhanced)
+        .script_source(&templates)
+        .unwrap();
+    assert!(source.contains(&format!("call.{}", root.to_hex())));
+    assert!(source.contains("push.2 gte assert"), "the minimum level is Enhanced");
+}
+
+#[test]
+fn timelocks_must_end_before_the_deadline() {
+    let templates = ComponentTemplates::default();
+    
+    let overlapping = ConditionalNoteBuilder::new(sender(), Vec::new())
+        .consume_before(at(1_000))
+        .consume_after(at(1_000));
+    assert!(matches!(overlapping.script_source(&templates), Err(ComplianceError::Validation { .. })));
+    assert!(matches!(overlapping.build(&templates, serial_num()), Err(ComplianceError::Validation { .. })));
+    
+    let tightest = ConditionalNoteBuilder::new(sender(), Vec::new())
+        .consume_before(at(5_000))
+        .consume_before(at(1_500))
+        .consume_after(at(2_000));
+    assert!(tightest.script_source(&templates).is_err(), "the earliest deadline applies");
+}
+
+#[test]
+fn built_notes_carry_the_sender_and_note_type() {
+    let templates = ComponentTemplates::default();
+    let builder = ConditionalNoteBuilder::new(sender(), Vec::new())
+        .consume_after(at(1_000))
+        .consume_before(at(2_000))
+        .min_level(ComplianceLevel::Basic);
+    
+    let public = builder.build(&templates, serial_num()).unwrap();
+    assert_eq!(public.metadata().sender(), sender());
+    assert_eq!(public.metadata().note_type(), NoteType::Public);
+    assert_eq!(public.assets().num_assets(), 0);
+    
+    let private = builder.clone().private().build(&templates, serial_num()).unwrap();
+    assert_eq!(private.metadata().note_type(), NoteType::Private);
+    assert_eq!(private.recipient().digest(), public.recipient().digest());
+}
@@ -0,0 +1,100 @@
+//! Hot reloading of the compliance configuration section
+
+use compliance_backend::audit::AuditLog;
+use compliance_backend::config::Config;
+use compliance_backend::reload::{ConfigReloader, LiveConfig};
+use compliance_backend::secrets::SecretResolver;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn write_config(path: &PathBuf, config: &Config) {
+    std::fs::write(path, serde_json::to_string_pretty(config).unwrap()).unwrap();
+}
+
+fn reloader(config: &Config) -> (ConfigReloader, Arc<LiveConfig>, Arc<AuditLog>, PathBuf) {
+    let path = std::env::temp_dir().join(format!("compliance-reload-{}.json", Uuid::new_v4()));
+    write_config(&path, config);
+    let live = Arc::new(LiveConfig::new(config.compliance.clone()));
+    let audit = Arc::new(AuditLog::new());
+    let reloader = ConfigReloader::new(
+        path.to_string_lossy().to_string(),
+        live.clone(),
+        Arc::new(SecretResolver::new()),
+        audit.clone(),
+    );
+    (reloader, live, audit, path)
+}
+
+#[tokio::test]
+async fn unchanged_file_keeps_version() {
+    let (reloader, live, audit, path) = reloader(&Config::default());
+    
+    let outcome = reloader.reload("operator").await.unwrap();
+    assert_eq!(outcome.version, 1);
+    assert!(outcome.changes.is_empty());
+    assert_eq!(live.version(), 1);
+    assert!(audit.entries().await.unwrap().iter().all(|entry| entry.action != "config.reloaded"));
+    
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn changed_threshold_is_applied_and_audited() {
+    let mut config = Config::default();
+    let (reloader, live, audit, path) = reloader(&config);
+    
+    config.compliance.sanctions.fuzzy_match_threshold = 0.9;
+    write_config(&path, &config);
+    
+    let outcome = reloader.reload("operator").await.unwrap();
+    assert_eq!(outcome.version, 2);
+    assert_eq!(outcome.changes.len(), 1);
+    assert_eq!(outcome.changes[0].field, "compliance.sanctions.fuzzy_match_threshold");
+    assert_eq!(outcome.changes[0].old, serde_json::json!(0.8));
+    assert_eq!(outcome.changes[0].new, serde_json::json!(0.9));
+    assert_eq!(live.version(), 2);
+    assert_eq!(live.compliance().sanctions.fuzzy_match_threshold, 0.9);
+    
+    let entries = audit.entries().await.unwrap();
+    let entry = entries.iter().find(|entry| entry.action == "config.reloaded").unwrap();
+    assert_eq!(entry.actor, "operator");
+    assert_eq!(entry.details["version"], 2);
+    
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn invalid_file_leaves_configuration_untouched() {
+    let mut config = Config::default();
+    let (reloader, live, _audit, path) = reloader(&config);
+    
+    config.compliance.sanctions.fuzzy_match_threshold = 1.5;
+    write_config(&path, &config);
+    
+    assert!(reloader.reload("operator").await.is_err());
+    assert_eq!(live.version(), 1);
+    assert_eq!(live.compliance().sanctions.fuzzy_match_threshold, 0.8);
+    
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn api_keys_are_redacted_in_changes() {
+    let mut config = Config::default();
+    let (reloader, _live, _audit, path) = reloader(&config);
+    
+    config.compliance.sanctions.provider_api_key = Some("sk-live-secret".to_string());
+    write_config(&path, &config);
+    
+    let outcome = reloader.reload("operator").await.unwrap();
+    let change = outcome
+        .changes
+        .iter()
+        .find(|change| change.field == "compliance.sanctions.provider_api_key")
+        .unwrap();
+    assert_eq!(change.old, serde_json::Value::Null);
+    assert_eq!(change.new, serde_json::json!("[redacted]"));
+    
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,89 @@
+//! Startup validation of configuration invariants
+
+use compliance_backend::config::{Config, ConfigViolations, Environment};
+use compliance_backend::ComplianceError;
+
+fn violations(config: &Config) -> Vec<(String, String)> {
+    match config.validate() {
+        Ok(()) => vec![],
+        Err(ConfigViolations(violations)) => violations.into_iter().map(|v| (v.field, v.message)).collect(),
+    }
+}
+
+fn fields(config: &Config) -> Vec<String> {
+    violations(config).into_iter().map(|(field, _)| field).collect()
+}
+
+#[test]
+fn the_development_defaults_are_valid() {
+    assert_eq!(violations(&Config::default()), vec![]);
+}
+
+#[test]
+fn every_violation_is_reported_at_once() {
+    let mut config = Config::default();
+    config.server.port = 0;
+    config.database.url = " ".to_string();
+    config.miden.sync_interval = 0;
+    config.compliance.sanctions.fuzzy_match_threshold = 1.5;
+    
+    let found = violations(&config);
+    assert_eq!(
+        found,
+        [
+            ("server.port".to_string(), "must not be 0".to_string()),
+            ("database.url".to_string(), "must not be empty".to_string()),
+            ("miden.sync_interval".to_string(), "must be greater than 0".to_string()),
+            (
+                "compliance.sanctions.fuzzy_match_threshold".to_string(),
+                "must be between 0 and 1".to_string()
+            ),
+        ]
+    );
+    
+    let error = ComplianceError::from(config.validate().unwrap_err());
+    assert!(matches!(error, ComplianceError::InvalidConfiguration(_)));
+    assert_eq!(
+        error.to_string(),
+        "Invalid configuration: server.port: must not be 0; database.url: must not be empty; \
+         miden.sync_interval: must be greater than 0; \
+         compliance.sanctions.fuzzy_match_threshold: must be between 0 and 1"
+    );
+}
+
+#[test]
+fn production_rejects_development_defaults() {
+    let mut config = Config::default();
+    config.environment = Environment::Production;
+    
+    let found = violations(&config);
+    assert!(found.contains(&("security.jwt_secret".to_string(), "still set to the default value".to_string())));
+    assert!(found.iter().any(|(field, _)| field == "security.signing_key_seed"));
+    assert!(found.contains(&(
+        "server.cors.allowed_origins".to_string(),
+        "wildcard origin is not allowed outside development".to_string()
+    )));
+    
+    config.security.jwt_secret = "too short".to_string();
+    config.server.cors.allowed_origins = vec!["https://app.example".to_string()];
+    let found = violations(&config);
+    assert!(found.contains(&("security.jwt_secret".to_string(), "must be at least 32 bytes".to_string())));
+    assert!(!fields(&config).contains(&"server.cors.allowed_origins".to_string()));
+}
+
+#[test]
+fn dependent_settings_are_checked_together() {
+    let mut config = Config::default();
+    config.miden.enable_delegated_proving = true;
+    config.miden.remote_prover_endpoint = None;
+    config.miden.components.medium_risk_score = config.miden.components.high_risk_score;
+    config.server.max_long_poll_secs = config.server.request_timeout;
+    
+    let found = fields(&config);
+    assert!(found.contains(&"miden.remote_prover_endpoint".to_string()));
+    assert!(found.contains(&"miden.components.medium_risk_score".to_string()));
+    assert!(found.contains(&"server.max_long_poll_secs".to_string()));
+    
+    config.miden.remote_prover_endpoint = Some("https://prover.example".to_string());
+    assert!(!fields(&config).contains(&"miden.remote_prover_endpoint".to_string()));
+}
@@ -0,0 +1,158 @@
+//! Risk propagated across the counterparty graph
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::chain_analytics::LinkedAddress;
+use compliance_backend::compliance::counterparty_graph::CounterpartyGraph;
+use compliance_backend::compliance::monitoring::MonitoredTransaction;
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn account(n: usize) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn transfer(from: &AccountId, to: &str, amount: u64) -> MonitoredTransaction {
+    MonitoredTransaction {
+        external_id: Uuid::new_v4().to_string(),
+        account_id: from.clone(),
+        amount,
+        asset: None,
+        counterparty: Some(to.to_string()),
+        transaction_type: "transfer".to_string(),
+        occurred_at: Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap(),
+    }
+}
+
+fn graph(configure: impl FnOnce(&mut ComplianceConfig)) -> CounterpartyGraph {
+    let mut config = ComplianceConfig::default();
+    configure(&mut config);
+    CounterpartyGraph::new(Arc::new(LiveConfig::new(config)))
+}
+
+async fn assess(graph: &CounterpartyGraph, account_id: &AccountId, level: AmlRiskLevel) {
+    graph.record_risk(account_id, level, Utc::now()).await;
+}
+
+#[tokio::test]
+async fn heavy_exposure_to_a_high_risk_counterparty_raises_the_level() {
+    let graph = graph(|_| {});
+    let (customer, risky, benign) = (account(1), account(2), account(3));
+    assess(&graph, &risky, AmlRiskLevel::Critical).await;
+    assess(&graph, &benign, AmlRiskLevel::Low).await;
+    graph
+        .record_transactions(&[
+            transfer(&customer, &risky.to_string(), 900),
+            transfer(&customer, &benign.to_string(), 100),
+        ])
+        .await;
+    
+    let mut checked = common::attestation(&customer, Utc::now(), Duration::days(365));
+    let neighborhood = graph.apply(&mut checked).await.unwrap();
+    assert_eq!(checked.aml_risk_level, AmlRiskLevel::Medium);
+    assert_eq!(neighborhood.neighbors[0].node, risky.to_string());
+    assert_eq!(neighborhood.neighbors[0].hops, 1);
+    assert_eq!(neighborhood.neighbors[0].direct_volume, Some(900.0));
+    assert!((neighborhood.propagated_score - 0.9 * 0.5 * 0.9).abs() < 1e-9);
+    assert_eq!(neighborhood.own_score, 0.0);
+    assert_eq!(neighborhood.assessed_at, None);
+}
+
+#[tokio::test]
+async fn occasional_transfers_and_low_risk_levels_are_not_raised() {
+    let graph = graph(|_| {});
+    let (customer, risky, benign) = (account(1), account(2), account(3));
+    assess(&graph, &risky, AmlRiskLevel::Critical).await;
+    graph
+        .record_transactions(&[
+            transfer(&customer, &risky.to_string(), 40),
+            transfer(&customer, &benign.to_string(), 960),
+        ])
+        .await;
+    
+    let mut checked = common::attestation(&customer, Utc::now(), Duration::days(365));
+    let neighborhood = graph.apply(&mut checked).await.unwrap();
+    assert_eq!(checked.aml_risk_level, AmlRiskLevel::Low);
+    
+    // Edges below `min_share` of the account's volume do not propagate at all
+    assert!(neighborhood.neighbors.iter().all(|neighbor| neighbor.node != risky.to_string()));
+    
+    // Propagation only raises the level
+    checked.aml_risk_level = AmlRiskLevel::High;
+    graph.apply(&mut checked).await.unwrap();
+    assert_eq!(checked.aml_risk_level, AmlRiskLevel::High);
+}
+
+#[tokio::test]
+async fn risk_decays_over_hops_up_to_max_hops() {
+    let far = |max_hops| {
+        graph(move |config| {
+            config.aml.counterparty_risk.decay = 0.9;
+            config.aml.counterparty_risk.max_hops = max_hops;
+        })
+    };
+    let (customer, intermediary, risky) = (account(1), account(2), account(3));
+    for max_hops in [1, 2] {
+        let graph = far(max_hops);
+        assess(&graph, &risky, AmlRiskLevel::Critical).await;
+        graph
+            .record_transactions(&[
+                transfer(&customer, &intermediary.to_string(), 500),
+                transfer(&intermediary, &risky.to_string(), 500),
+            ])
+            .await;
+        
+        let neighborhood = graph.neighborhood(&customer).await.unwrap();
+        let reached = neighborhood.neighbors.iter().find(|neighbor| neighbor.node == risky.to_string());
+        if max_hops == 1 {
+            assert!(reached.is_none());
+            assert_eq!(neighborhood.risk_level, AmlRiskLevel::Low);
+        } else {
+            // Full share into the intermediary, which sends half its volume on
+            let reached = reached.unwrap();
+            assert_eq!(reached.hops, 2);
+            assert_eq!(reached.direct_volume, None);
+            assert!((reached.exposure - 0.9 * 0.9 * 0.5).abs() < 1e-9);
+            assert_eq!(neighborhood.risk_level, AmlRiskLevel::Medium);
+        }
+    }
+}
+
+#[tokio::test]
+async fn linked_addresses_merge_into_their_account() {
+    let graph = graph(|_| {});
+    let (customer, risky) = (account(1), account(2));
+    assess(&graph, &risky, AmlRiskLevel::Critical).await;
+    graph.record_transactions(&[transfer(&customer, "bc1qriskyaddress", 1_000)]).await;
+    assert!(graph.neighborhood(&customer).await.unwrap().neighbors[0].risk_level.is_none());
+    
+    // Transfers made before and after the address was linked both count
+    let address = LinkedAddress {
+        chain: "bitcoin".to_string(),
+        address: "bc1qriskyaddress".to_string(),
+    };
+    graph.link_address(&risky, &address).await;
+    graph.record_transactions(&[transfer(&customer, "bc1qriskyaddress", 1_000)]).await;
+    
+    let neighborhood = graph.neighborhood(&customer).await.unwrap();
+    assert_eq!(neighborhood.neighbors.len(), 1);
+    assert_eq!(neighborhood.neighbors[0].node, risky.to_string());
+    assert_eq!(neighborhood.neighbors[0].direct_volume, Some(2_000.0));
+    assert_eq!(neighborhood.risk_level, AmlRiskLevel::Medium);
+}
+
+#[tokio::test]
+async fn disabled_propagation_leaves_attestations_alone() {
+    let graph = graph(|config| config.aml.counterparty_risk.enabled = false);
+    let (customer, risky) = (account(1), account(2));
+    assess(&graph, &risky, AmlRiskLevel::Critical).await;
+    graph.record_transactions(&[transfer(&customer, &risky.to_string(), 1_000)]).await;
+    
+    let mut checked = common::attestation(&customer, Utc::now(), Duration::days(365));
+    assert!(graph.apply(&mut checked).await.is_none());
+    assert_eq!(checked.aml_risk_level, AmlRiskLevel::Low);
+}
@@ -0,0 +1,177 @@
+//! Geographic risk from FATF lists, Basel AML Index scores, and overrides
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::claims::JurisdictionClass;
+use compliance_backend::compliance::country_risk::{
+    risk_level, CountryRiskDataset, CountryRiskService, GeographicFactorKind, GeographicProfile,
+    BUILTIN_DATASET_VERSION,
+};
+use compliance_backend::config::{ComplianceConfig, RiskThresholds};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn service(configure: impl FnOnce(&mut ComplianceConfig)) -> CountryRiskService {
+    let mut compliance = ComplianceConfig::default();
+    configure(&mut compliance);
+    CountryRiskService::new(Arc::new(LiveConfig::new(compliance))).unwrap()
+}
+
+fn profile(residence: Option<&str>, document: Option<&str>, counterparties: &[&str]) -> GeographicProfile {
+    GeographicProfile {
+        residence: residence.map(str::to_string),
+        document_issuing_country: document.map(str::to_string),
+        counterparty_countries: counterparties.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+fn write_dataset(json: serde_json::Value) -> String {
+    let path = std::env::temp_dir().join(format!("country-risk-{}.json", Uuid::new_v4()));
+    std::fs::write(&path, json.to_string()).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn countries_carry_fatf_and_configured_risk() {
+    let service = service(|c| {
+        c.aml.country_risk.country_weights.insert("CH".to_string(), 0.4);
+    });
+    
+    assert_eq!(service.dataset_version(), BUILTIN_DATASET_VERSION);
+    assert_eq!(service.country_risk("IR"), (1.0, vec!["fatf_black_list".to_string()]));
+    assert_eq!(service.country_risk("NG"), (0.6, vec!["fatf_grey_list".to_string()]));
+    assert_eq!(service.country_risk("DE"), (0.0, vec![]));
+    assert_eq!(service.country_risk("CH"), (0.4, vec!["configured_weight".to_string()]));
+}
+
+#[test]
+fn scores_are_weighted_over_the_factors_present() {
+    let service = service(|_| {});
+    
+    let residence_only = service.assess(&profile(Some("NG"), None, &[])).unwrap();
+    assert!((residence_only.score - 0.6).abs() < 1e-9);
+    assert_eq!(residence_only.risk_level, AmlRiskLevel::Medium);
+    
+    // Only the riskiest counterparty counts
+    let mixed = service.assess(&profile(Some("DE"), Some("DE"), &["FR", "VN"])).unwrap();
+    assert!((mixed.score - 0.18).abs() < 1e-9);
+    assert_eq!(mixed.risk_level, AmlRiskLevel::Low);
+    assert_eq!(mixed.factors.len(), 4);
+    assert_eq!(mixed.factors[0].kind, GeographicFactorKind::Residence);
+    assert_eq!(mixed.factors[1].kind, GeographicFactorKind::DocumentIssuer);
+    
+    let empty = service.assess(&GeographicProfile::default()).unwrap();
+    assert_eq!(empty.score, 0.0);
+    assert!(empty.factors.is_empty());
+}
+
+#[test]
+fn any_call_for_action_country_is_high_risk() {
+    let service = service(|_| {});
+    
+    let assessment = service.assess(&profile(Some("DE"), None, &["KP"])).unwrap();
+    assert!(assessment.score >= 0.9);
+    assert_eq!(assessment.risk_level, AmlRiskLevel::Critical);
+}
+
+#[test]
+fn disabled_scoring_assesses_nothing() {
+    let service = service(|c| c.aml.country_risk.enabled = false);
+    assert!(service.assess(&profile(Some("IR"), None, &[])).is_none());
+}
+
+#[test]
+fn scores_map_to_levels_by_threshold() {
+    let thresholds = RiskThresholds::default();
+    assert_eq!(risk_level(0.29, &thresholds), AmlRiskLevel::Low);
+    assert_eq!(risk_level(0.3, &thresholds), AmlRiskLevel::Medium);
+    assert_eq!(risk_level(0.7, &thresholds), AmlRiskLevel::High);
+    assert_eq!(risk_level(0.9, &thresholds), AmlRiskLevel::Critical);
+}
+
+#[tokio::test]
+async fn profiles_are_normalized_and_classified() {
+    let service = service(|_| {});
+    assert_eq!(service.jurisdiction_class(&account(1)).await, JurisdictionClass::Unassessed);
+    
+    let stored = service
+        .set_profile(&account(1), profile(Some(" ng "), Some("de"), &["vn", "VN", "fr"]))
+        .await
+        .unwrap();
+    assert_eq!(stored.residence.as_deref(), Some("NG"));
+    assert_eq!(stored.document_issuing_country.as_deref(), Some("DE"));
+    assert_eq!(stored.counterparty_countries, ["FR", "VN"]);
+    assert_eq!(service.jurisdiction_class(&account(1)).await, JurisdictionClass::IncreasedMonitoring);
+    
+    assert!(service.set_profile(&account(2), profile(Some("DEU"), None, &[])).await.is_err());
+    assert!(service.set_profile(&account(2), profile(None, None, &["1A"])).await.is_err());
+    
+    service.set_profile(&account(3), profile(Some("IR"), None, &[])).await.unwrap();
+    assert_eq!(service.jurisdiction_class(&account(3)).await, JurisdictionClass::CallForAction);
+    service.set_profile(&account(4), profile(Some("DE"), None, &[])).await.unwrap();
+    assert_eq!(service.jurisdiction_class(&account(4)).await, JurisdictionClass::Standard);
+}
+
+#[tokio::test]
+async fn geographic_risk_raises_but_never_lowers_attestations() {
+    let service = service(|_| {});
+    service.set_profile(&account(1), profile(Some("NG"), None, &[])).await.unwrap();
+    
+    let mut attestation = common::attestation(&account(1), Utc::now(), Duration::days(30));
+    service.apply(&mut attestation).await.unwrap();
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::Medium);
+    
+    attestation.aml_risk_level = AmlRiskLevel::High;
+    service.apply(&mut attestation).await.unwrap();
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::High);
+    
+    let mut unprofiled = common::attestation(&account(2), Utc::now(), Duration::days(30));
+    assert!(service.apply(&mut unprofiled).await.is_none());
+    assert_eq!(unprofiled.aml_risk_level, AmlRiskLevel::Low);
+}
+
+#[test]
+fn datasets_load_with_basel_scores() {
+    let path = write_dataset(serde_json::json!({
+        "version": "basel-2025",
+        "fatf_black_list": ["KP"],
+        "fatf_grey_list": ["NG"],
+        "basel_scores": { "NG": 7.5, "DE": 3.0 },
+    }));
+    let service = service(|c| c.aml.country_risk.dataset_path = Some(path.clone()));
+    
+    assert_eq!(service.dataset_version(), "basel-2025");
+    let (risk, sources) = service.country_risk("NG");
+    assert!((risk - 0.75).abs() < 1e-9);
+    assert_eq!(sources, ["fatf_grey_list", "basel_aml_index"]);
+    assert!((service.country_risk("DE").0 - 0.3).abs() < 1e-9);
+    
+    // Black-listed countries no longer in the dataset carry no list risk
+    assert_eq!(service.country_risk("IR").0, 0.0);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn invalid_datasets_are_rejected() {
+    for json in [
+        serde_json::json!({ "version": "x", "fatf_black_list": ["KPR"], "fatf_grey_list": [] }),
+        serde_json::json!({
+            "version": "x",
+            "fatf_black_list": [],
+            "fatf_grey_list": [],
+            "basel_scores": { "NG": 11.0 },
+        }),
+    ] {
+        let path = write_dataset(json);
+        assert!(CountryRiskDataset::load(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+    assert!(CountryRiskDataset::load("/nonexistent/country-risk.json").is_err());
+}
@@ -0,0 +1,131 @@
+//! Signed chain-of-custody archives
+
+use chrono::{TimeZone, Utc};
+use compliance_backend::audit::AuditLog;
+use compliance_backend::compliance::custody::{self, CustodyArchive, CustodyRecords, MANIFEST_FILE};
+use compliance_backend::compliance::PROOF_ISSUED_ACTION;
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::storage::{OutboxMessage, OutboxRepo, UnitOfWork, WriteBatch};
+use compliance_backend::types::AccountId;
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn trusting(signer: &AttestationSigner) -> TrustedKeys {
+    let mut keys = TrustedKeys::new();
+    keys.insert(signer.key_id(), signer.verifying_key());
+    keys
+}
+
+async fn sealed(signer: &AttestationSigner) -> CustodyArchive {
+    let account_id = account(1);
+    let audit = AuditLog::new();
+    audit
+        .record("alice", "attestation.revoked", Some(&account_id), serde_json::json!({ "reason": "fraud" }))
+        .await;
+    audit
+        .record("holder", PROOF_ISSUED_ACTION, Some(&account_id), serde_json::json!({ "audience": "dex" }))
+        .await;
+    let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+    let records = CustodyRecords {
+        audit: audit.entries().await.unwrap(),
+        webhooks: vec![OutboxMessage::new(
+            Uuid::new_v4(),
+            "attestation.revoked",
+            serde_json::json!({ "account_id": account_id }),
+            now,
+        )],
+        ..Default::default()
+    };
+    let chain = audit.verify_chain().await.unwrap();
+    CustodyArchive::seal(&account_id, "eu-1", records, chain, signer, now).unwrap()
+}
+
+fn read(archive: &[u8], name: &str) -> Vec<u8> {
+    let mut zip = ZipArchive::new(Cursor::new(archive)).unwrap();
+    let mut bytes = Vec::new();
+    zip.by_name(name).unwrap().read_to_end(&mut bytes).unwrap();
+    bytes
+}
+
+/// Copy an archive, replacing or adding `file`
+fn rewritten(archive: &[u8], file: (&str, &[u8])) -> Vec<u8> {
+    let mut source = ZipArchive::new(Cursor::new(archive)).unwrap();
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let names: Vec<String> = source.file_names().map(str::to_string).collect();
+    for name in names.iter().filter(|name| name.as_str() != file.0) {
+        let mut bytes = Vec::new();
+        source.by_name(name).unwrap().read_to_end(&mut bytes).unwrap();
+        zip.start_file(name.as_str(), SimpleFileOptions::default()).unwrap();
+        zip.write_all(&bytes).unwrap();
+    }
+    zip.start_file(file.0, SimpleFileOptions::default()).unwrap();
+    zip.write_all(file.1).unwrap();
+    zip.finish().unwrap().into_inner()
+}
+
+#[tokio::test]
+async fn archives_verify_against_the_signing_key() {
+    let signer = AttestationSigner::generate("custody-key");
+    let archive = sealed(&signer).await;
+    
+    let manifest = custody::verify(&archive.bytes, &trusting(&signer)).unwrap();
+    assert_eq!(manifest.export_id, archive.manifest.export_id);
+    assert_eq!(manifest.account_id, account(1));
+    assert!(manifest.audit_chain_valid);
+    let names: Vec<&str> = manifest.files.iter().map(|file| file.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["attestation_events.json", "decisions.json", "audit_entries.json", "proofs.json", "webhooks.json"]
+    );
+    
+    let audit: serde_json::Value = serde_json::from_slice(&read(&archive.bytes, "audit_entries.json")).unwrap();
+    assert_eq!(audit.as_array().unwrap().len(), 2);
+    let proofs: serde_json::Value = serde_json::from_slice(&read(&archive.bytes, "proofs.json")).unwrap();
+    assert_eq!(proofs[0]["action"], PROOF_ISSUED_ACTION);
+    assert_eq!(proofs[0]["hash"], audit[1]["hash"]);
+    
+    let other = AttestationSigner::generate("custody-key");
+    assert!(custody::verify(&archive.bytes, &trusting(&other)).is_err());
+}
+
+#[tokio::test]
+async fn tampering_is_detected() {
+    let signer = AttestationSigner::generate("custody-key");
+    let keys = trusting(&signer);
+    let archive = sealed(&signer).await;
+    
+    let altered = rewritten(&archive.bytes, ("audit_entries.json", b"[]"));
+    assert!(custody::verify(&altered, &keys).is_err());
+    
+    let extra = rewritten(&archive.bytes, ("notes.txt", b"added later"));
+    assert!(custody::verify(&extra, &keys).is_err());
+    
+    let mut manifest: serde_json::Value = serde_json::from_slice(&read(&archive.bytes, MANIFEST_FILE)).unwrap();
+    manifest["deployment_id"] = "us-1".into();
+    let forged = rewritten(&archive.bytes, (MANIFEST_FILE, &serde_json::to_vec(&manifest).unwrap()));
+    assert!(custody::verify(&forged, &keys).is_err());
+}
+
+#[tokio::test]
+async fn webhooks_are_found_by_account() {
+    let store = MemoryStore::default();
+    let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+    let mut batch = WriteBatch::default();
+    for n in [1, 2, 1] {
+        let payload = serde_json::json!({ "account_id": account(n) });
+        batch.outbox.push(OutboxMessage::new(Uuid::new_v4(), "attestation.issued", payload, now));
+    }
+    batch.outbox.push(OutboxMessage::new(Uuid::new_v4(), "usage.threshold", serde_json::json!({}), now));
+    store.commit(&batch).await.unwrap();
+    
+    assert_eq!(store.outbox.for_account(&account(1)).await.unwrap().len(), 2);
+    assert_eq!(store.outbox.for_account(&account(2)).await.unwrap().len(), 1);
+    assert!(store.outbox.for_account(&account(3)).await.unwrap().is_empty());
+}
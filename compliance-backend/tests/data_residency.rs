@@ -0,0 +1,102 @@
+//! Data residency routing of PII storage and provider calls
+
+use compliance_backend::compliance::breaker::Provider;
+use compliance_backend::compliance::provider_credentials::{CredentialSource, ProviderCredentialStore};
+use compliance_backend::compliance::residency;
+use compliance_backend::config::{
+    ComplianceConfig, DatabaseConfig, RegionalDatabaseConfig, RegionalProviderConfig, ResidencyConfig,
+};
+use compliance_backend::types::DataRegion;
+use compliance_backend::ComplianceError;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const GLOBAL_KYC: &str = "https://kyc.example";
+const EU_KYC: &str = "https://eu.kyc.example";
+
+fn config() -> ComplianceConfig {
+    let mut config = ComplianceConfig::default();
+    config.kyc.provider_endpoint = Some(GLOBAL_KYC.to_string());
+    config.kyc.provider_api_key = Some("global-key".to_string());
+    config
+}
+
+fn with_eu_kyc(mut config: ComplianceConfig) -> ComplianceConfig {
+    let providers = HashMap::from([(
+        Provider::Kyc,
+        RegionalProviderConfig {
+            endpoint: EU_KYC.to_string(),
+            api_key: None,
+        },
+    )]);
+    config.residency.regions.insert(DataRegion::Eu, providers);
+    config
+}
+
+#[tokio::test]
+async fn strict_region_without_an_endpoint_is_unavailable() {
+    let store = ProviderCredentialStore::new(None);
+    let result = store.resolve(None, Some(DataRegion::Eu), Provider::Kyc, &config()).await;
+    assert!(matches!(result, Err(ComplianceError::ResidencyUnavailable { .. })));
+}
+
+#[tokio::test]
+async fn regional_endpoint_is_used_with_the_global_key() {
+    let store = ProviderCredentialStore::new(None);
+    let resolved = store
+        .resolve(None, Some(DataRegion::Eu), Provider::Kyc, &with_eu_kyc(config()))
+        .await
+        .unwrap();
+    assert_eq!(resolved.source, CredentialSource::Regional(DataRegion::Eu));
+    assert_eq!(resolved.credentials.endpoint.as_deref(), Some(EU_KYC));
+    assert_eq!(resolved.credentials.api_key.as_deref(), Some("global-key"));
+}
+
+#[tokio::test]
+async fn lenient_region_falls_back_to_global_credentials() {
+    let store = ProviderCredentialStore::new(None);
+    let resolved = store.resolve(None, Some(DataRegion::Us), Provider::Kyc, &config()).await.unwrap();
+    assert_eq!(resolved.source, CredentialSource::Global);
+    assert_eq!(resolved.credentials.endpoint.as_deref(), Some(GLOBAL_KYC));
+}
+
+#[tokio::test]
+async fn client_key_without_an_endpoint_uses_the_regional_endpoint() {
+    let store = ProviderCredentialStore::new(Some([7; 32]));
+    let client_id = Uuid::new_v4();
+    store.set(client_id, Provider::Kyc, None, "client-key").await.unwrap();
+    
+    let resolved = store
+        .resolve(Some(client_id), Some(DataRegion::Eu), Provider::Kyc, &with_eu_kyc(config()))
+        .await
+        .unwrap();
+    assert_eq!(resolved.source, CredentialSource::Client(client_id));
+    assert_eq!(resolved.credentials.endpoint.as_deref(), Some(EU_KYC));
+    assert_eq!(resolved.credentials.api_key.as_deref(), Some("client-key"));
+    
+    let result = store.resolve(Some(client_id), Some(DataRegion::Eu), Provider::Kyc, &config()).await;
+    assert!(matches!(result, Err(ComplianceError::ResidencyUnavailable { .. })));
+}
+
+#[test]
+fn pii_is_stored_in_the_region_schema() {
+    let residency = ResidencyConfig::default();
+    let mut database = DatabaseConfig::default();
+    assert!(residency::ensure_supported(DataRegion::Eu, &database, &residency).is_err());
+    
+    database.regions.insert(
+        DataRegion::Eu,
+        RegionalDatabaseConfig {
+            url: None,
+            password: None,
+            schema: "pii_eu".to_string(),
+        },
+    );
+    let target = residency::storage_target(Some(DataRegion::Eu), &database, &residency).unwrap();
+    assert_eq!(target.region, Some(DataRegion::Eu));
+    assert_eq!(target.schema.as_deref(), Some("pii_eu"));
+    assert_eq!(target.url, database.connection_url());
+    
+    let target = residency::storage_target(Some(DataRegion::Apac), &database, &residency).unwrap();
+    assert_eq!(target.region, None);
+}
@@ -0,0 +1,66 @@
+//! Replays of transaction authorization decisions against their recorded inputs
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::country_risk::CountryRiskService;
+use compliance_backend::compliance::velocity::{AuthorizationOutcome, TransactionRequest, VelocityService};
+use compliance_backend::compliance::watchlists::WatchlistService;
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use compliance_backend::ComplianceError;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn service() -> VelocityService {
+    let config = Arc::new(LiveConfig::new(ComplianceConfig::default()));
+    let country_risk = Arc::new(CountryRiskService::new(config.clone()).unwrap());
+    VelocityService::new(config, Arc::new(WatchlistService::new()), country_risk)
+}
+
+fn transfer(amount: u64) -> TransactionRequest {
+    TransactionRequest {
+        amount,
+        asset: None,
+        counterparty: None,
+        counterparty_country: None,
+        transaction_type: "transfer".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn replays_reproduce_decisions_from_their_own_inputs() {
+    let service = service();
+    let account_id = AccountId::parse(&format!("0x{:030x}", 1)).unwrap();
+    let attestation = common::attestation(&account_id, Utc::now(), Duration::days(90));
+    let client_id = Uuid::new_v4();
+    
+    let allowed = service
+        .authorize(client_id, &account_id, Some(&attestation), Some(ComplianceLevel::Enhanced), &transfer(30_000))
+        .await;
+    assert_eq!(allowed.outcome, AuthorizationOutcome::Allow);
+    
+    // The first transfer now counts towards the daily volume limit
+    let denied = service
+        .authorize(client_id, &account_id, Some(&attestation), Some(ComplianceLevel::Enhanced), &transfer(30_000))
+        .await;
+    assert_eq!(denied.outcome, AuthorizationOutcome::Deny);
+    
+    let replay = service.replay_decision(allowed.id).await.unwrap();
+    assert!(replay.deterministic);
+    assert_eq!(replay.replayed.outcome, AuthorizationOutcome::Allow);
+    assert_eq!(replay.inputs.velocity.daily_volume, 0);
+    
+    let replay = service.replay_decision(denied.id).await.unwrap();
+    assert!(replay.deterministic);
+    assert_eq!(replay.replayed.outcome, AuthorizationOutcome::Deny);
+    assert_eq!(replay.replayed.reasons, denied.reasons);
+    assert_eq!(replay.inputs.velocity.daily_volume, 30_000);
+}
+
+#[tokio::test]
+async fn unknown_decisions_cannot_be_replayed() {
+    let result = service().replay_decision(Uuid::new_v4()).await;
+    assert!(matches!(result, Err(ComplianceError::DecisionNotFound { .. })));
+}
@@ -0,0 +1,99 @@
+//! Device and IP signals scored as AML risk factors
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::country_risk::{CountryRiskService, GeographicProfile};
+use compliance_backend::compliance::device_risk::{DeviceFactorKind, DeviceRiskService, DeviceSignals, SignalContext};
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use std::sync::Arc;
+
+fn account(n: usize) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn service(enabled: bool) -> (DeviceRiskService, Arc<CountryRiskService>) {
+    let mut config = ComplianceConfig::default();
+    config.aml.device_risk.enabled = enabled;
+    let config = Arc::new(LiveConfig::new(config));
+    let country_risk = Arc::new(CountryRiskService::new(config.clone()).unwrap());
+    (DeviceRiskService::new(config, country_risk.clone()), country_risk)
+}
+
+#[tokio::test]
+async fn signals_are_ignored_unless_enabled() {
+    let (service, _) = service(false);
+    let signals = DeviceSignals {
+        tor: true,
+        ..DeviceSignals::default()
+    };
+    
+    assert!(service.record(&account(1), SignalContext::Onboarding, signals).await.unwrap().is_none());
+    assert!(service.assess_account(&account(1)).await.is_none());
+}
+
+#[tokio::test]
+async fn tor_raises_the_attestation_risk_level() {
+    let (service, _) = service(true);
+    let signals = DeviceSignals {
+        tor: true,
+        ..DeviceSignals::default()
+    };
+    service.record(&account(1), SignalContext::Transaction, signals).await.unwrap();
+    
+    let mut attestation = common::attestation(&account(1), Utc::now(), Duration::days(30));
+    let assessment = service.apply(&mut attestation).await.unwrap();
+    assert_eq!(assessment.factors[0].kind, DeviceFactorKind::Tor);
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::High);
+}
+
+#[tokio::test]
+async fn ip_countries_carry_country_risk_and_residence_mismatch() {
+    let (service, country_risk) = service(true);
+    let profile = GeographicProfile {
+        residence: Some("DE".to_string()),
+        ..GeographicProfile::default()
+    };
+    country_risk.set_profile(&account(1), profile).await.unwrap();
+    let signals = DeviceSignals {
+        ip_country: Some("ir".to_string()),
+        ..DeviceSignals::default()
+    };
+    service.record(&account(1), SignalContext::Onboarding, signals).await.unwrap();
+    
+    let assessment = service.assess_account(&account(1)).await.unwrap();
+    let kinds: Vec<_> = assessment.factors.iter().map(|f| f.kind).collect();
+    assert_eq!(kinds, [DeviceFactorKind::IpCountry, DeviceFactorKind::ResidenceMismatch]);
+    assert_eq!(assessment.factors[0].detail.as_deref(), Some("IR"));
+    assert_eq!(assessment.risk_level, AmlRiskLevel::Critical);
+}
+
+#[tokio::test]
+async fn a_device_seen_on_several_accounts_is_shared() {
+    let (service, _) = service(true);
+    let signals = DeviceSignals {
+        device_fingerprint: Some("fp-1".to_string()),
+        ..DeviceSignals::default()
+    };
+    for n in 1..=2 {
+        service.record(&account(n), SignalContext::Onboarding, signals.clone()).await.unwrap();
+    }
+    assert!(service.assess_account(&account(1)).await.unwrap().factors.is_empty());
+    
+    service.record(&account(3), SignalContext::Onboarding, signals).await.unwrap();
+    let assessment = service.assess_account(&account(1)).await.unwrap();
+    assert_eq!(assessment.factors[0].kind, DeviceFactorKind::SharedDevice);
+    assert_eq!(assessment.factors[0].detail.as_deref(), Some("3 accounts"));
+}
+
+#[tokio::test]
+async fn malformed_signals_are_rejected() {
+    let (service, _) = service(true);
+    let signals = DeviceSignals {
+        ip_country: Some("Germany".to_string()),
+        ..DeviceSignals::default()
+    };
+    assert!(service.record(&account(1), SignalContext::Onboarding, signals).await.is_err());
+}
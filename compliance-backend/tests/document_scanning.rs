@@ -0,0 +1,165 @@
+//! Malware scanning and quarantine of uploaded documents
+
+use compliance_backend::compliance::document_scanning::{ClamdScanner, DocumentScanner, ScanVerdict};
+use compliance_backend::compliance::verification_sessions::{
+    CreateSessionRequest, MemoryDocumentVault, VerificationSessionService,
+};
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::storage::OutboxRepo;
+use compliance_backend::types::AccountId;
+use compliance_backend::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+/// Flags documents containing the EICAR marker; fails when `down`
+struct StubScanner {
+    down: bool,
+}
+
+impl DocumentScanner for StubScanner {
+    fn name(&self) -> &'static str {
+        "stub"
+    }
+    
+    fn scan<'a>(&'a self, _content_type: &'a str, bytes: &'a [u8]) -> BoxFuture<'a, Result<ScanVerdict>> {
+        Box::pin(async move {
+            if self.down {
+                return Err(ComplianceError::internal("scanner down"));
+            }
+            Ok(if bytes.windows(5).any(|window| window == b"EICAR") {
+                ScanVerdict::Flagged {
+                    reason: "Eicar-Test-Signature".to_string(),
+                }
+            } else {
+                ScanVerdict::Clean
+            })
+        })
+    }
+}
+
+struct Intake {
+    service: VerificationSessionService,
+    vault: Arc<MemoryDocumentVault>,
+    quarantine: Arc<MemoryDocumentVault>,
+    store: Arc<MemoryStore>,
+}
+
+fn intake(down: bool, fail_open: bool) -> Intake {
+    let mut config = ComplianceConfig::default();
+    config.kyc.documents.fail_open = fail_open;
+    config.kyc.documents.image_quality.enabled = false;
+    let vault = Arc::new(MemoryDocumentVault::new());
+    let quarantine = Arc::new(MemoryDocumentVault::new());
+    let store = Arc::new(MemoryStore::default());
+    let service =
+        VerificationSessionService::new(Arc::new(LiveConfig::new(config)), b"test-secret", vault.clone(), store.clone())
+            .with_scanner(Arc::new(StubScanner { down }), quarantine.clone());
+    Intake {
+        service,
+        vault,
+        quarantine,
+        store,
+    }
+}
+
+fn account() -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", 7)).unwrap()
+}
+
+#[tokio::test]
+async fn flagged_documents_are_quarantined_instead_of_stored() {
+    let intake = intake(false, false);
+    let issued = intake.service.create(Uuid::new_v4(), &account(), CreateSessionRequest::default()).await.unwrap();
+    let id = issued.session.id;
+    
+    let flagged = intake.service.upload(id, "passport", "application/pdf", b"%PDF EICAR".to_vec()).await;
+    assert!(matches!(
+        flagged,
+        Err(ComplianceError::DocumentQuarantined { document_type }) if document_type == "passport"
+    ));
+    let session = intake.service.get(issued.session.client_id, id).await.unwrap();
+    assert!(session.documents.is_empty());
+    let quarantined = &session.quarantined[0];
+    assert_eq!(quarantined.scanner, "stub");
+    assert_eq!(quarantined.reason, "Eicar-Test-Signature");
+    assert_eq!(intake.quarantine.get(&quarantined.vault_ref).await.unwrap().1, b"%PDF EICAR");
+    assert!(intake.vault.get(&quarantined.vault_ref).await.is_none());
+    
+    // The session stays open for a clean document
+    let session = intake.service.upload(id, "passport", "application/pdf", b"%PDF clean".to_vec()).await.unwrap();
+    assert!(session.documents[0].scanned);
+    
+    let pending = intake.store.outbox.pending(100).await.unwrap();
+    let events: Vec<_> = pending.iter().map(|message| message.event_type.as_str()).collect();
+    assert_eq!(
+        events,
+        ["verification_session.document_quarantined", "verification_session.started"]
+    );
+    assert_eq!(pending[0].payload["document"]["reason"], "Eicar-Test-Signature");
+}
+
+#[tokio::test]
+async fn scanner_failures_reject_uploads_unless_failing_open() {
+    let closed = intake(true, false);
+    let issued = closed.service.create(Uuid::new_v4(), &account(), CreateSessionRequest::default()).await.unwrap();
+    let rejected = closed.service.upload(issued.session.id, "passport", "image/png", vec![1]).await;
+    assert!(rejected.is_err());
+    
+    let open = intake(true, true);
+    let issued = open.service.create(Uuid::new_v4(), &account(), CreateSessionRequest::default()).await.unwrap();
+    let session = open.service.upload(issued.session.id, "passport", "image/png", vec![1]).await.unwrap();
+    assert!(!session.documents[0].scanned);
+}
+
+/// Serve one clamd `INSTREAM` request, replying `reply` and returning the bytes streamed
+async fn fake_clamd(reply: &'static str) -> (String, tokio::task::JoinHandle<Vec<u8>>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut command = [0u8; 10];
+        socket.read_exact(&mut command).await.unwrap();
+        assert_eq!(&command, b"zINSTREAM\0");
+        let mut streamed = Vec::new();
+        loop {
+            let mut len = [0u8; 4];
+            socket.read_exact(&mut len).await.unwrap();
+            let len = u32::from_be_bytes(len) as usize;
+            if len == 0 {
+                break;
+            }
+            let mut chunk = vec![0u8; len];
+            socket.read_exact(&mut chunk).await.unwrap();
+            streamed.extend(chunk);
+        }
+        socket.write_all(reply.as_bytes()).await.unwrap();
+        streamed
+    });
+    (address, server)
+}
+
+#[tokio::test]
+async fn clamd_verdicts_are_parsed_from_instream_replies() {
+    let document = vec![7u8; 200 * 1024];
+    let (address, server) = fake_clamd("stream: OK\0").await;
+    let verdict = ClamdScanner::new(address).scan("image/png", &document).await.unwrap();
+    assert_eq!(verdict, ScanVerdict::Clean);
+    assert_eq!(server.await.unwrap(), document);
+    
+    let (address, _) = fake_clamd("stream: Eicar-Test-Signature FOUND\0").await;
+    let verdict = ClamdScanner::new(address).scan("image/png", b"EICAR").await.unwrap();
+    assert_eq!(
+        verdict,
+        ScanVerdict::Flagged {
+            reason: "Eicar-Test-Signature".to_string()
+        }
+    );
+    
+    let (address, _) = fake_clamd("INSTREAM size limit exceeded. ERROR\0").await;
+    let failed = ClamdScanner::new(address).scan("image/png", b"large").await;
+    assert!(matches!(failed, Err(ComplianceError::ProviderUnavailable { .. })));
+}
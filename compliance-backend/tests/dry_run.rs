@@ -0,0 +1,56 @@
+//! Dry-run compliance checks through the check endpoint
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::api::accounts::{CheckRequest, CheckResponse, PolicyResult};
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use uuid::Uuid;
+
+fn response(dry_run: bool, workflow_run_id: Option<Uuid>) -> CheckResponse {
+    let account_id = AccountId::parse(&format!("0x{:030x}", 1)).unwrap();
+    CheckResponse {
+        dry_run,
+        attestation: common::attestation(&account_id, Utc::now(), Duration::days(365)),
+        policy: PolicyResult {
+            required_level: ComplianceLevel::Basic,
+            meets_required_level: true,
+            compliance_level: Some(ComplianceLevel::Standard),
+            reasons: Vec::new(),
+            kyc_rejection: None,
+            display: Vec::new(),
+        },
+        workflow_run_id,
+    }
+}
+
+#[test]
+fn checks_are_real_unless_a_dry_run_is_requested() {
+    let request: CheckRequest = serde_json::from_str("{}").unwrap();
+    assert!(!request.dry_run);
+    assert_eq!(request.required_level, None);
+    
+    let request: CheckRequest = serde_json::from_str(r#"{"dry_run":true,"required_level":"Enhanced"}"#).unwrap();
+    assert!(request.dry_run);
+    assert_eq!(request.required_level, Some(ComplianceLevel::Enhanced));
+}
+
+#[test]
+fn responses_say_whether_they_were_a_dry_run() {
+    let json = serde_json::to_value(response(true, None)).unwrap();
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["policy"]["meets_required_level"], true);
+    assert_eq!(json["policy"]["compliance_level"], "Standard");
+    
+    // Dry runs never go through a workflow, so no run is reported
+    assert!(json.get("workflow_run_id").is_none());
+}
+
+#[test]
+fn workflow_runs_are_reported_for_real_checks() {
+    let run_id = Uuid::new_v4();
+    let json = serde_json::to_value(response(false, Some(run_id))).unwrap();
+    
+    assert_eq!(json["dry_run"], false);
+    assert_eq!(json["workflow_run_id"], run_id.to_string());
+}
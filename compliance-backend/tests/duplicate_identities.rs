@@ -0,0 +1,140 @@
+//! Keyed identity fingerprints, consent-gated cross-client matching and fraud cases
+
+use compliance_backend::alerts::{AlertKind, AlertManager};
+use compliance_backend::compliance::duplicate_identities::{
+    DuplicateIdentityService, FraudCaseResolution, FraudCaseStatus, IdentitySignal, IdentitySubmission,
+};
+use compliance_backend::config::{AlertingConfig, ComplianceConfig};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn account(n: usize) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn identity(name: &str, passport: &str, consent: bool) -> IdentitySubmission {
+    IdentitySubmission {
+        full_name: name.to_string(),
+        date_of_birth: Some("1985-04-12".to_string()),
+        document_numbers: vec![passport.to_string()],
+        language: None,
+        cross_client_consent: consent,
+    }
+}
+
+fn service(cross_client: Vec<Uuid>) -> (DuplicateIdentityService, Arc<AlertManager>) {
+    let mut config = ComplianceConfig::default();
+    config.duplicate_detection.cross_client = cross_client;
+    let alerts = Arc::new(AlertManager::with_sinks(AlertingConfig::default(), vec![]));
+    let service = DuplicateIdentityService::new(Arc::new(LiveConfig::new(config)), b"secret", alerts.clone());
+    (service, alerts)
+}
+
+#[tokio::test]
+async fn normalized_attributes_match_another_account_of_the_client() {
+    let (service, alerts) = service(vec![]);
+    let client = Uuid::new_v4();
+    
+    let first = service.submit(client, &account(1), &identity("Ana Pérez", "X12-345", false)).await.unwrap();
+    assert!(first.duplicates.is_empty());
+    assert!(first.case_id.is_none());
+    
+    let second = service.submit(client, &account(2), &identity("ANA PEREZ", "x12345", false)).await.unwrap();
+    assert_eq!(second.duplicates.len(), 1);
+    assert_eq!(second.duplicates[0].account_id, account(1));
+    assert_eq!(
+        second.duplicates[0].signals,
+        [IdentitySignal::DocumentNumber, IdentitySignal::NameAndBirthDate]
+    );
+    
+    let case = service.case(second.case_id.unwrap()).await.unwrap();
+    assert_eq!(case.status, FraudCaseStatus::Open);
+    assert_eq!(case.subjects.len(), 2);
+    assert_eq!(alerts.list(false).await[0].kind, AlertKind::DuplicateIdentity);
+}
+
+#[tokio::test]
+async fn further_matches_extend_the_open_case() {
+    let (service, _) = service(vec![]);
+    let client = Uuid::new_v4();
+    
+    service.submit(client, &account(1), &identity("Ana Perez", "X1", false)).await.unwrap();
+    let second = service.submit(client, &account(2), &identity("Ana Perez", "X1", false)).await.unwrap();
+    let third = service.submit(client, &account(3), &identity("Ana Perez", "X1", false)).await.unwrap();
+    
+    assert_eq!(third.duplicates.len(), 2);
+    assert_eq!(third.case_id, second.case_id);
+    assert_eq!(service.case(third.case_id.unwrap()).await.unwrap().subjects.len(), 3);
+}
+
+#[tokio::test]
+async fn other_clients_match_only_when_both_opted_in_and_consented() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let (service, _) = service(vec![a, b]);
+    
+    service.submit(a, &account(1), &identity("Ana Perez", "X1", true)).await.unwrap();
+    
+    let outside = service.submit(c, &account(2), &identity("Ana Perez", "X1", true)).await.unwrap();
+    assert_eq!(outside.other_client_matches, 0);
+    
+    let no_consent = service.submit(b, &account(3), &identity("Ana Perez", "X1", false)).await.unwrap();
+    assert_eq!(no_consent.other_client_matches, 0);
+    
+    let consented = service.submit(b, &account(3), &identity("Ana Perez", "X1", true)).await.unwrap();
+    assert_eq!(consented.other_client_matches, 1);
+    assert!(consented.duplicates.is_empty());
+    assert!(consented.case_id.is_some());
+}
+
+#[tokio::test]
+async fn the_same_account_at_two_clients_is_not_a_duplicate() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let (service, _) = service(vec![a, b]);
+    
+    service.submit(a, &account(1), &identity("Ana Perez", "X1", true)).await.unwrap();
+    let check = service.submit(b, &account(1), &identity("Ana Perez", "X1", true)).await.unwrap();
+    assert_eq!(check.other_client_matches, 0);
+    assert!(check.case_id.is_none());
+}
+
+#[tokio::test]
+async fn dismissed_cases_are_not_reopened_by_the_same_accounts() {
+    let (service, _) = service(vec![]);
+    let client = Uuid::new_v4();
+    
+    service.submit(client, &account(1), &identity("Ana Perez", "X1", false)).await.unwrap();
+    let check = service.submit(client, &account(2), &identity("Ana Perez", "X1", false)).await.unwrap();
+    let case_id = check.case_id.unwrap();
+    service.resolve(case_id, FraudCaseResolution::Dismiss, "ops", None).await.unwrap();
+    assert!(service.resolve(case_id, FraudCaseResolution::Confirm, "ops", None).await.is_err());
+    
+    let again = service.submit(client, &account(2), &identity("Ana Perez", "X1", false)).await.unwrap();
+    assert_eq!(again.duplicates.len(), 1);
+    assert!(again.case_id.is_none());
+    assert!(service.cases(Some(FraudCaseStatus::Open)).await.is_empty());
+}
+
+#[tokio::test]
+async fn forgotten_accounts_no_longer_match() {
+    let (service, _) = service(vec![]);
+    let client = Uuid::new_v4();
+    
+    service.submit(client, &account(1), &identity("Ana Perez", "X1", false)).await.unwrap();
+    assert!(service.forget(client, &account(1)).await);
+    
+    let check = service.submit(client, &account(2), &identity("Ana Perez", "X1", false)).await.unwrap();
+    assert!(check.duplicates.is_empty());
+}
+
+#[tokio::test]
+async fn submissions_need_a_birth_date_or_document() {
+    let (service, _) = service(vec![]);
+    let submission = IdentitySubmission {
+        date_of_birth: None,
+        document_numbers: vec![],
+        ..identity("Ana Perez", "X1", false)
+    };
+    assert!(service.submit(Uuid::new_v4(), &account(1), &submission).await.is_err());
+}
@@ -0,0 +1,124 @@
+//! Mirroring compliance events onto a message broker
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::config::EventBusConfig;
+use compliance_backend::event_bus::{
+    spawn_publisher, BusMessage, EventPublisher, ATTESTATION_EVENT_SCHEMA, SCHEMA_VERSION,
+};
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+use compliance_backend::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+/// Broker that fails its first `failures` publishes and forwards the rest
+struct FakeBroker {
+    failures: AtomicUsize,
+    attempts: AtomicUsize,
+    published: mpsc::UnboundedSender<(String, String, BusMessage)>,
+}
+
+impl EventPublisher for FakeBroker {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+    
+    fn publish<'a>(&'a self, topic: &'a str, key: &'a str, payload: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        let result = if self.failures.load(Ordering::SeqCst) > 0 {
+            self.failures.fetch_sub(1, Ordering::SeqCst);
+            Err(ComplianceError::internal("broker unavailable"))
+        } else {
+            let message = serde_json::from_slice(payload).unwrap();
+            let _ = self.published.send((topic.to_string(), key.to_string(), message));
+            Ok(())
+        };
+        Box::pin(async move { result })
+    }
+}
+
+fn config() -> EventBusConfig {
+    EventBusConfig {
+        enabled: true,
+        retry_backoff_ms: 5,
+        max_retry_backoff_ms: 20,
+        ..EventBusConfig::default()
+    }
+}
+
+type Published = mpsc::UnboundedReceiver<(String, String, BusMessage)>;
+
+async fn start(failures: usize) -> (Arc<AttestationEventStore>, Arc<FakeBroker>, Published) {
+    let (published, received) = mpsc::unbounded_channel();
+    let broker = Arc::new(FakeBroker {
+        failures: AtomicUsize::new(failures),
+        attempts: AtomicUsize::new(0),
+        published,
+    });
+    let events = Arc::new(AttestationEventStore::new());
+    spawn_publisher(config(), "eu-1".to_string(), events.clone(), broker.clone());
+    // Let the publisher subscribe before events are recorded
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    (events, broker, received)
+}
+
+async fn issue(events: &AttestationEventStore, n: u32) {
+    let attestation = common::attestation(&account(n), Utc::now(), Duration::days(30));
+    events.append(&account(n), AttestationEvent::AttestationIssued { attestation }).await.unwrap();
+}
+
+#[tokio::test]
+async fn events_are_published_in_order_with_versioned_envelopes() {
+    let (events, _broker, mut received) = start(0).await;
+    issue(&events, 1).await;
+    events
+        .append(&account(1), AttestationEvent::RiskUpdated { aml_risk_level: AmlRiskLevel::High })
+        .await
+        .unwrap();
+    
+    let (topic, key, message) = received.recv().await.unwrap();
+    assert_eq!(topic, "zerotrust.compliance.attestation.attestation_issued");
+    assert_eq!(key, account(1).to_string());
+    assert_eq!(message.schema, ATTESTATION_EVENT_SCHEMA);
+    assert_eq!(message.schema_version, SCHEMA_VERSION);
+    assert_eq!(message.position, 1);
+    assert_eq!(message.message_id, format!("eu-1:{}:1", account(1)));
+    
+    let (topic, _, message) = received.recv().await.unwrap();
+    assert_eq!(topic, "zerotrust.compliance.attestation.risk_updated");
+    assert_eq!(message.position, 2);
+    assert_eq!(message.event["aml_risk_level"], "High");
+}
+
+#[tokio::test]
+async fn failed_publishes_are_retried_until_acknowledged() {
+    let (events, broker, mut received) = start(3).await;
+    issue(&events, 1).await;
+    issue(&events, 2).await;
+    
+    let (_, first, _) = received.recv().await.unwrap();
+    let (_, second, _) = received.recv().await.unwrap();
+    assert_eq!((first, second), (account(1).to_string(), account(2).to_string()));
+    assert_eq!(broker.attempts.load(Ordering::SeqCst), 5);
+}
+
+#[cfg(not(feature = "nats"))]
+#[tokio::test]
+async fn backends_must_be_compiled_in() {
+    use compliance_backend::config::EventBusBackend;
+    use compliance_backend::event_bus::from_config;
+    
+    let backend = EventBusBackend::Nats {
+        url: "nats://localhost:4222".to_string(),
+        credentials_file: None,
+    };
+    assert!(from_config(&backend).await.is_err());
+}
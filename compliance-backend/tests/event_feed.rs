@@ -0,0 +1,92 @@
+//! Resumable feed of attestation events for streaming subscribers
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore, RecordedEvent};
+use compliance_backend::compliance::event_feed::{decode_resume_token, encode_resume_token, EventFeed};
+use compliance_backend::types::AccountId;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+async fn recorded(store: &AttestationEventStore, n: u32) -> RecordedEvent {
+    let attestation = common::attestation(&account(n), Utc::now(), Duration::days(30));
+    store.append(&account(n), AttestationEvent::AttestationIssued { attestation }).await.unwrap()
+}
+
+#[tokio::test]
+async fn events_are_positioned_in_publish_order() {
+    let (store, feed) = (AttestationEventStore::new(), EventFeed::new(10));
+    
+    for n in 1..=3 {
+        let published = feed.publish(recorded(&store, n).await).await;
+        assert_eq!(published.position, u64::from(n));
+        assert_eq!(published.event.account_id, account(n));
+        assert_eq!(decode_resume_token(&published.resume_token()).unwrap(), u64::from(n));
+    }
+}
+
+#[tokio::test]
+async fn new_subscribers_only_see_live_events() {
+    let (store, feed) = (AttestationEventStore::new(), EventFeed::new(10));
+    feed.publish(recorded(&store, 1).await).await;
+    
+    let mut subscription = feed.subscribe(None).await.unwrap();
+    assert!(subscription.backlog.is_empty());
+    
+    feed.publish(recorded(&store, 2).await).await;
+    let live = subscription.live.recv().await.unwrap();
+    assert_eq!(live.position, 2);
+    assert_eq!(live.event.account_id, account(2));
+}
+
+#[tokio::test]
+async fn resuming_replays_missed_events_before_live_ones() {
+    let (store, feed) = (AttestationEventStore::new(), EventFeed::new(10));
+    let mut last_seen = None;
+    for n in 1..=4 {
+        let published = feed.publish(recorded(&store, n).await).await;
+        if n == 2 {
+            last_seen = Some(published.resume_token());
+        }
+    }
+    
+    let after = decode_resume_token(&last_seen.unwrap()).unwrap();
+    let mut subscription = feed.subscribe(Some(after)).await.unwrap();
+    let replayed: Vec<u64> = subscription.backlog.iter().map(|e| e.position).collect();
+    assert_eq!(replayed, vec![3, 4]);
+    
+    feed.publish(recorded(&store, 5).await).await;
+    assert_eq!(subscription.live.recv().await.unwrap().position, 5);
+}
+
+#[tokio::test]
+async fn resuming_at_the_head_replays_nothing() {
+    let (store, feed) = (AttestationEventStore::new(), EventFeed::new(10));
+    let head = feed.publish(recorded(&store, 1).await).await;
+    
+    assert!(feed.subscribe(Some(head.position)).await.unwrap().backlog.is_empty());
+    assert_eq!(feed.subscribe(Some(0)).await.unwrap().backlog.len(), 1);
+}
+
+#[tokio::test]
+async fn tokens_past_retention_are_rejected() {
+    let (store, feed) = (AttestationEventStore::new(), EventFeed::new(2));
+    for n in 1..=5 {
+        feed.publish(recorded(&store, n).await).await;
+    }
+    
+    // Positions 4 and 5 are retained, so resuming after 3 is still complete
+    assert_eq!(feed.subscribe(Some(3)).await.unwrap().backlog.len(), 2);
+    assert!(feed.subscribe(Some(2)).await.is_err());
+    assert!(feed.subscribe(None).await.is_ok());
+}
+
+#[test]
+fn resume_tokens_are_opaque_and_checked() {
+    assert_eq!(decode_resume_token(&encode_resume_token(7)).unwrap(), 7);
+    assert!(decode_resume_token("not-a-token!").is_err());
+    assert!(decode_resume_token("AAAA").is_err());
+}
@@ -0,0 +1,140 @@
+//! Signed attestation export bundles and their verification on import
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::{Clock, MockClock};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore, AttestationStatus};
+use compliance_backend::compliance::portability::{ExportBundle, ExportedAttestation, LEGACY_BUNDLE_VERSION};
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn trusting(signer: &AttestationSigner) -> TrustedKeys {
+    let mut trusted = TrustedKeys::new();
+    trusted.insert(signer.key_id(), signer.verifying_key());
+    trusted
+}
+
+/// Records for an active account and a revoked one
+async fn exported() -> Vec<ExportedAttestation> {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    let store = AttestationEventStore::with_clock(clock.clone());
+    for n in [1, 2] {
+        let attestation = common::attestation(&account(n), clock.now(), Duration::days(30));
+        store.append(&account(n), AttestationEvent::AttestationIssued { attestation }).await.unwrap();
+    }
+    let revoked = AttestationEvent::Revoked {
+        reason: "fraud".to_string(),
+        revoked_by: None,
+    };
+    store.append(&account(2), revoked).await.unwrap();
+    
+    let mut records = Vec::new();
+    for n in [1, 2] {
+        let history = store.events(&account(n), None).await.unwrap();
+        records.push(ExportedAttestation::from_history(history).unwrap().unwrap());
+    }
+    records
+}
+
+#[tokio::test]
+async fn records_carry_their_state_and_history() {
+    let records = exported().await;
+    
+    assert_eq!(records[0].status, AttestationStatus::Active);
+    assert_eq!(records[0].history.len(), 1);
+    assert_eq!(records[1].status, AttestationStatus::Revoked);
+    assert_eq!(records[1].revocation_reason.as_deref(), Some("fraud"));
+    assert_eq!(records[1].history.len(), 2);
+    assert!(ExportedAttestation::from_history(Vec::new()).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn sealed_bundles_verify_against_trusted_keys() {
+    let signer = AttestationSigner::generate("export");
+    let bundle = ExportBundle::seal("eu-1", exported().await, &signer).unwrap();
+    bundle.verify(&trusting(&signer)).unwrap();
+    
+    // Bundles survive transport as JSON
+    let parsed: ExportBundle = serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+    parsed.verify(&trusting(&signer)).unwrap();
+    
+    let stranger = AttestationSigner::generate("export");
+    assert!(bundle.verify(&trusting(&stranger)).is_err());
+    assert!(bundle.verify(&TrustedKeys::new()).is_err());
+}
+
+#[tokio::test]
+async fn tampering_breaks_the_signature() {
+    let signer = AttestationSigner::generate("export");
+    let trusted = trusting(&signer);
+    let bundle = ExportBundle::seal("eu-1", exported().await, &signer).unwrap();
+    
+    let mut tampered = bundle.clone();
+    tampered.records[1].status = AttestationStatus::Active;
+    assert!(tampered.verify(&trusted).is_err());
+    
+    let mut tampered = bundle.clone();
+    tampered.source_deployment = "us-1".to_string();
+    assert!(tampered.verify(&trusted).is_err());
+    
+    let mut tampered = bundle.clone();
+    tampered.signature = "zz".to_string();
+    assert!(tampered.verify(&trusted).is_err());
+    
+    let mut tampered = bundle;
+    tampered.signature.truncate(64);
+    assert!(tampered.verify(&trusted).is_err());
+}
+
+#[tokio::test]
+async fn signed_records_must_match_their_history() {
+    let signer = AttestationSigner::generate("export");
+    let trusted = trusting(&signer);
+    let reseal = |records| ExportBundle::seal("eu-1", records, &signer).unwrap();
+    
+    let mut records = exported().await;
+    records[1].status = AttestationStatus::Active;
+    records[1].revocation_reason = None;
+    assert!(reseal(records).verify(&trusted).is_err());
+    
+    let mut records = exported().await;
+    records[0].attestation.sanctions_cleared = false;
+    assert!(reseal(records).verify(&trusted).is_err());
+    
+    let mut records = exported().await;
+    records[0].account_id = account(2);
+    assert!(reseal(records).verify(&trusted).is_err());
+    
+    let mut records = exported().await;
+    let foreign = records[1].history[0].clone();
+    records[0].history.push(foreign);
+    assert!(reseal(records).verify(&trusted).is_err());
+}
+
+#[tokio::test]
+async fn legacy_bundles_are_still_accepted() {
+    let signer = AttestationSigner::generate("export");
+    let mut bundle = ExportBundle::seal("eu-1", exported().await, &signer).unwrap();
+    bundle.version = LEGACY_BUNDLE_VERSION;
+    bundle.signature = hex::encode(signer.sign(&bundle.signing_payload().unwrap()).unwrap());
+    bundle.verify(&trusting(&signer)).unwrap();
+    
+    bundle.version = 3;
+    assert!(bundle.verify(&trusting(&signer)).is_err());
+}
+
+#[tokio::test]
+async fn unknown_fields_are_rejected() {
+    let signer = AttestationSigner::generate("export");
+    let bundle = ExportBundle::seal("eu-1", exported().await, &signer).unwrap();
+    let mut json = serde_json::to_value(&bundle).unwrap();
+    json["extra"] = serde_json::json!(true);
+    
+    assert!(serde_json::from_value::<ExportBundle>(json).is_err());
+}
@@ -0,0 +1,169 @@
+//! Source-of-funds and source-of-wealth declarations through verification
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::source_of_funds::{
+    DeclarationKind, DeclarationStatus, DeclarationSubmission, DeclaredSource, FundsDeclarationService, FundsOrigin,
+    ReviewDecision, SupportingDocument, SupportingDocumentType,
+};
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn service(configure: impl FnOnce(&mut ComplianceConfig)) -> FundsDeclarationService {
+    let mut compliance = ComplianceConfig::default();
+    configure(&mut compliance);
+    FundsDeclarationService::new(Arc::new(LiveConfig::new(compliance)))
+}
+
+fn submission(kind: DeclarationKind) -> DeclarationSubmission {
+    DeclarationSubmission {
+        kind,
+        sources: vec![DeclaredSource {
+            origin: FundsOrigin::Employment,
+            description: "Salary from Acme Ltd".to_string(),
+            amount: Some(8_000_000),
+        }],
+        total_amount: 10_000_000,
+        currency: "eur".to_string(),
+        supporting_documents: vec![SupportingDocument {
+            document_type: SupportingDocumentType::PaySlip,
+            vault_ref: "vault://docs/123".to_string(),
+        }],
+    }
+}
+
+async fn verified(service: &FundsDeclarationService, n: u32, kind: DeclarationKind) -> Uuid {
+    let declaration = service.submit(Uuid::new_v4(), &account(n), submission(kind)).await.unwrap();
+    service.review(declaration.id, ReviewDecision::Verify, "analyst", None).await.unwrap();
+    declaration.id
+}
+
+#[tokio::test]
+async fn submissions_are_validated() {
+    let service = service(|c| c.funds_declarations.max_supporting_documents = 1);
+    let account_id = account(1);
+    let submit = |s| service.submit(Uuid::new_v4(), &account_id, s);
+    
+    let mut s = submission(DeclarationKind::SourceOfFunds);
+    s.sources.clear();
+    assert!(submit(s).await.is_err());
+    
+    let mut s = submission(DeclarationKind::SourceOfFunds);
+    s.sources[0].description = " ".to_string();
+    assert!(submit(s).await.is_err());
+    
+    let mut s = submission(DeclarationKind::SourceOfFunds);
+    s.sources[0].amount = Some(20_000_000);
+    assert!(submit(s).await.is_err());
+    
+    let mut s = submission(DeclarationKind::SourceOfFunds);
+    s.currency = "EURO".to_string();
+    assert!(submit(s).await.is_err());
+    
+    let mut s = submission(DeclarationKind::SourceOfFunds);
+    s.supporting_documents.push(s.supporting_documents[0].clone());
+    assert!(submit(s).await.is_err());
+    
+    let mut s = submission(DeclarationKind::SourceOfFunds);
+    s.supporting_documents[0].vault_ref = "vault ref".to_string();
+    assert!(submit(s).await.is_err());
+    
+    let declaration = submit(submission(DeclarationKind::SourceOfFunds)).await.unwrap();
+    assert_eq!(declaration.currency, "EUR");
+    assert_eq!(declaration.status, DeclarationStatus::Submitted);
+}
+
+#[tokio::test]
+async fn new_submissions_supersede_open_ones_of_the_same_kind() {
+    let service = service(|_| {});
+    let client_id = Uuid::new_v4();
+    let first = service.submit(client_id, &account(1), submission(DeclarationKind::SourceOfFunds)).await.unwrap();
+    let wealth = service.submit(client_id, &account(1), submission(DeclarationKind::SourceOfWealth)).await.unwrap();
+    let second = service.submit(client_id, &account(1), submission(DeclarationKind::SourceOfFunds)).await.unwrap();
+    
+    assert_eq!(service.get(first.id).await.unwrap().status, DeclarationStatus::Superseded);
+    assert_eq!(service.get(wealth.id).await.unwrap().status, DeclarationStatus::Submitted);
+    assert_eq!(service.get(second.id).await.unwrap().status, DeclarationStatus::Submitted);
+    assert_eq!(service.for_account(&account(1)).await.len(), 3);
+    assert_eq!(service.with_status(DeclarationStatus::Submitted).await.len(), 2);
+    assert!(service.get(Uuid::new_v4()).await.is_err());
+}
+
+#[tokio::test]
+async fn reviews_follow_the_workflow() {
+    let service = service(|_| {});
+    let declaration = service
+        .submit(Uuid::new_v4(), &account(1), submission(DeclarationKind::SourceOfFunds))
+        .await
+        .unwrap();
+    
+    let review = |decision| service.review(declaration.id, decision, "analyst", None);
+    assert_eq!(review(ReviewDecision::StartReview).await.unwrap().status, DeclarationStatus::UnderReview);
+    assert!(review(ReviewDecision::StartReview).await.is_err());
+    assert_eq!(
+        review(ReviewDecision::RequestInformation).await.unwrap().status,
+        DeclarationStatus::InformationRequested
+    );
+    assert!(review(ReviewDecision::Verify).await.is_err());
+    
+    let rejected = review(ReviewDecision::Reject).await.unwrap();
+    assert_eq!(rejected.status, DeclarationStatus::Rejected);
+    assert_eq!(rejected.reviewed_by.as_deref(), Some("analyst"));
+    assert!(rejected.valid_until.is_none());
+    assert!(review(ReviewDecision::Verify).await.is_err());
+}
+
+#[tokio::test]
+async fn declarations_need_documents_to_be_verified() {
+    let service = service(|c| c.funds_declarations.validity_days = 30);
+    let mut undocumented = submission(DeclarationKind::SourceOfFunds);
+    undocumented.supporting_documents.clear();
+    let declaration = service.submit(Uuid::new_v4(), &account(1), undocumented).await.unwrap();
+    assert!(service.review(declaration.id, ReviewDecision::Verify, "analyst", None).await.is_err());
+    
+    let id = verified(&service, 2, DeclarationKind::SourceOfFunds).await;
+    let declaration = service.get(id).await.unwrap();
+    let (reviewed_at, valid_until) = (declaration.reviewed_at.unwrap(), declaration.valid_until.unwrap());
+    assert_eq!(valid_until - reviewed_at, Duration::days(30));
+    assert!(declaration.is_verified_at(reviewed_at));
+    assert!(!declaration.is_verified_at(reviewed_at - Duration::seconds(1)));
+    assert!(!declaration.is_verified_at(valid_until));
+}
+
+#[tokio::test]
+async fn levels_require_the_declarations_in_force() {
+    let service = service(|_| {});
+    let now = Utc::now();
+    assert!(service.satisfies(&account(1), &ComplianceLevel::Standard, now).await);
+    assert!(!service.satisfies(&account(1), &ComplianceLevel::Enhanced, now).await);
+    
+    let funds = verified(&service, 1, DeclarationKind::SourceOfFunds).await;
+    let now = Utc::now();
+    assert!(service.satisfies(&account(1), &ComplianceLevel::Enhanced, now).await);
+    assert!(!service.satisfies(&account(1), &ComplianceLevel::InstitutionalGrade, now).await);
+    
+    let wealth = verified(&service, 1, DeclarationKind::SourceOfWealth).await;
+    let now = Utc::now();
+    assert!(service.satisfies(&account(1), &ComplianceLevel::InstitutionalGrade, now).await);
+    let evidence = service.evidence_at(&account(1), now).await;
+    assert_eq!((evidence.source_of_funds, evidence.source_of_wealth), (Some(funds), Some(wealth)));
+    
+    // Expired declarations no longer count
+    let later = now + Duration::days(366);
+    assert!(!service.satisfies(&account(1), &ComplianceLevel::Enhanced, later).await);
+}
+
+#[tokio::test]
+async fn requirements_can_be_disabled() {
+    let service = service(|c| {
+        c.funds_declarations.require_funds_for_enhanced = false;
+        c.funds_declarations.require_wealth_for_institutional = false;
+    });
+    assert!(service.satisfies(&account(1), &ComplianceLevel::InstitutionalGrade, Utc::now()).await);
+}
@@ -0,0 +1,125 @@
+//! Transaction scripts for compliance-gated transfers
+
+use compliance_backend::compliance::account_components::foreign::{
+    miden_account_id, procedure_root, KYC_STATUS_PROCEDURE,
+};
+use compliance_backend::compliance::account_components::migration::ComponentKind;
+use compliance_backend::compliance::account_components::template::ComponentTemplates;
+use compliance_backend::compliance::gated_transfers::{GatedTransferBuilder, TransferParty};
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use compliance_backend::ComplianceError;
+use miden_lib::note::utils::build_p2id_recipient;
+use miden_objects::account::AccountId as MidenAccountId;
+use miden_objects::asset::{Asset, FungibleAsset};
+use miden_objects::note::NoteType;
+use miden_objects::{Felt, Word};
+
+/// Public regular accounts with updatable code
+const SENDER: &str = "0xac0000000000dd100000ee000000fc";
+const RECIPIENT: &str = "0xee0000000000ff100000cc000000dd";
+
+/// A public fungible faucet
+const FAUCET: &str = "0xaa0000000000bc200000bc000000de";
+
+fn miden(id: &str) -> MidenAccountId {
+    miden_account_id(&AccountId::parse(id).unwrap()).unwrap()
+}
+
+fn assets() -> Vec<Asset> {
+    vec![FungibleAsset::new(miden(FAUCET), 100).unwrap().into()]
+}
+
+fn serial_num() -> Word {
+    [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]
+}
+
+#[test]
+fn both_parties_are_checked_when_transferring_between_verified_accounts() {
+    let transfer =
+        GatedTransferBuilder::between_verified(miden(SENDER), miden(RECIPIENT), assets(), ComplianceLevel::Standard);
+    assert_eq!(
+        transfer.checks(),
+        [
+            (TransferParty::Sender, ComplianceLevel::Standard),
+            (TransferParty::Recipient, ComplianceLevel::Standard),
+        ]
+    );
+}
+
+#[test]
+fn scripts_check_kyc_before_creating_the_note() {
+    let templates = ComponentTemplates::default();
+    let root = procedure_root(ComponentKind::Kyc, &templates, KYC_STATUS_PROCEDURE).unwrap();
+    let recipient = build_p2id_recipient(miden(RECIPIENT), serial_num()).unwrap();
+    
+    let source = GatedTransferBuilder::new(miden(SENDER), miden(RECIPIENT), assets())
+        .require_sender(ComplianceLevel::Basic)
+        .require_recipient(ComplianceLevel::Enhanced)
+        .script_source(&templates, &recipient)
+        .unwrap();
+    
+    let sender_check = source.find(&format!("call.{}", root.to_hex())).unwrap();
+    let recipient_check = source.find("exec.tx::execute_foreign_procedure").unwrap();
+    let create_note = source.find("call.wallet::create_note").unwrap();
+    assert!(sender_check < recipient_check && recipient_check < create_note);
+    assert!(source.contains("push.0 gte assert"), "the sender must be at least Basic");
+    assert!(source.contains("push.2 gte assert"), "the recipient must be at least Enhanced");
+    assert!(source.contains(&format!("push.{}", recipient.digest().to_hex())));
+    assert_eq!(source.matches("call.wallet::move_asset_to_note").count(), 1);
+}
+
+#[test]
+fn unchecked_transfers_make_no_kyc_calls() {
+    let recipient = build_p2id_recipient(miden(RECIPIENT), serial_num()).unwrap();
+    let source = GatedTransferBuilder::new(miden(SENDER), miden(RECIPIENT), assets())
+        .script_source(&ComponentTemplates::default(), &recipient)
+        .unwrap();
+    
+    assert!(!source.contains("gte assert"));
+    assert!(!source.contains("execute_foreign_procedure"));
+    assert!(source.contains("call.wallet::create_note"));
+}
+
+#[test]
+fn transfers_need_assets_and_distinct_parties() {
+    let templates = ComponentTemplates::default();
+    
+    let empty = GatedTransferBuilder::new(miden(SENDER), miden(RECIPIENT), Vec::new());
+    assert!(matches!(empty.build(&templates, serial_num()), Err(ComplianceError::Validation { .. })));
+    
+    let to_self = GatedTransferBuilder::new(miden(SENDER), miden(SENDER), assets());
+    assert!(matches!(to_self.build(&templates, serial_num()), Err(ComplianceError::Validation { .. })));
+}
+
+#[test]
+fn only_checked_recipients_are_read_as_foreign_accounts() {
+    let templates = ComponentTemplates::default();
+    
+    let sender_only = GatedTransferBuilder::new(miden(SENDER), miden(RECIPIENT), assets())
+        .require_sender(ComplianceLevel::Basic)
+        .build(&templates, serial_num())
+        .unwrap();
+    assert_eq!(sender_only.foreign_recipient, None);
+    sender_only.request().unwrap();
+    
+    let both = GatedTransferBuilder::between_verified(miden(SENDER), miden(RECIPIENT), assets(), ComplianceLevel::Basic)
+        .build(&templates, serial_num())
+        .unwrap();
+    assert_eq!(both.foreign_recipient, Some(miden(RECIPIENT)));
+    both.request().unwrap();
+}
+
+#[test]
+fn built_notes_pay_the_recipient() {
+    let templates = ComponentTemplates::default();
+    let transfer = GatedTransferBuilder::new(miden(SENDER), miden(RECIPIENT), assets())
+        .private()
+        .build(&templates, serial_num())
+        .unwrap();
+    
+    let recipient = build_p2id_recipient(miden(RECIPIENT), serial_num()).unwrap();
+    assert_eq!(transfer.note.recipient().digest(), recipient.digest());
+    assert_eq!(transfer.note.metadata().sender(), miden(SENDER));
+    assert_eq!(transfer.note.metadata().note_type(), NoteType::Private);
+    assert_eq!(transfer.note.assets().num_assets(), 1);
+}
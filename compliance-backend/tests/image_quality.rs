@@ -0,0 +1,106 @@
+//! Local quality checks on document images
+
+use compliance_backend::compliance::image_quality::{assess, QualityIssue};
+use compliance_backend::compliance::verification_sessions::{
+    CreateSessionRequest, MemoryDocumentVault, VerificationSessionService,
+};
+use compliance_backend::config::{ComplianceConfig, ImageQualityConfig};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use image::{DynamicImage, GrayImage, ImageFormat, Luma};
+use std::io::Cursor;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const MIN_SCORE: f64 = 0.85;
+const BACKGROUND: u8 = 90;
+
+/// A patterned document on a plain background, covering `document` as (x0, y0, x1, y1)
+fn photo(width: u32, height: u32, document: (u32, u32, u32, u32)) -> GrayImage {
+    let (x0, y0, x1, y1) = document;
+    GrayImage::from_fn(width, height, |x, y| {
+        if (x0..x1).contains(&x) && (y0..y1).contains(&y) {
+            Luma([if (x / 8 + y / 8) % 2 == 0 { 40 } else { 200 }])
+        } else {
+            Luma([BACKGROUND])
+        }
+    })
+}
+
+/// A well-framed document photo
+fn framed(width: u32, height: u32) -> GrayImage {
+    photo(width, height, (width / 10, height / 10, width * 9 / 10, height * 9 / 10))
+}
+
+fn png(image: GrayImage) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    DynamicImage::ImageLuma8(image).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).unwrap();
+    bytes
+}
+
+fn issues(image: GrayImage) -> Vec<QualityIssue> {
+    assess(&png(image), &ImageQualityConfig::default(), MIN_SCORE).issues
+}
+
+#[test]
+fn a_sharp_well_framed_photo_passes() {
+    let report = assess(&png(framed(800, 600)), &ImageQualityConfig::default(), MIN_SCORE);
+    assert!(report.passed(), "{:?}", report);
+    assert_eq!(report.score, 1.0);
+    assert_eq!((report.width, report.height), (800, 600));
+    assert_eq!(report.quiet_borders, 4);
+}
+
+#[test]
+fn each_defect_is_reported_on_its_own() {
+    assert_eq!(issues(image::imageops::blur(&framed(800, 600), 8.0)), [QualityIssue::Blurry]);
+    assert_eq!(issues(framed(400, 300)), [QualityIssue::LowResolution]);
+    
+    let mut glare = framed(800, 600);
+    for (x, y, pixel) in glare.enumerate_pixels_mut() {
+        if (100..700).contains(&x) && (100..400).contains(&y) {
+            *pixel = Luma([255]);
+        }
+    }
+    assert_eq!(issues(glare), [QualityIssue::Glare]);
+    
+    // Running off the left edge of the frame
+    assert_eq!(issues(photo(800, 600, (0, 60, 400, 540))), [QualityIssue::Cropped]);
+    
+    assert_eq!(
+        assess(b"not an image", &ImageQualityConfig::default(), MIN_SCORE).issues,
+        [QualityIssue::Unreadable]
+    );
+}
+
+#[tokio::test]
+async fn uploads_of_poor_images_fail_with_feedback_before_reaching_the_vault() {
+    let vault = Arc::new(MemoryDocumentVault::new());
+    let service = VerificationSessionService::new(
+        Arc::new(LiveConfig::new(ComplianceConfig::default())),
+        b"test-secret",
+        vault,
+        Arc::new(MemoryStore::default()),
+    );
+    let account_id = AccountId::parse(&format!("0x{:030x}", 7)).unwrap();
+    let issued = service.create(Uuid::new_v4(), &account_id, CreateSessionRequest::default()).await.unwrap();
+    let id = issued.session.id;
+    
+    let blurry = png(image::imageops::blur(&framed(400, 300), 8.0));
+    match service.upload(id, "passport", "image/png", blurry).await {
+        Err(ComplianceError::DocumentQualityInsufficient { feedback, score, .. }) => {
+            assert_eq!(feedback, ["low_resolution", "blurry"]);
+            assert!(score < MIN_SCORE);
+        }
+        other => panic!("expected a quality rejection, got {:?}", other),
+    }
+    let session = service.upload(id, "passport", "image/png", png(framed(800, 600))).await.unwrap();
+    assert_eq!(session.documents.len(), 1);
+    assert_eq!(session.documents[0].quality_score, Some(1.0));
+    
+    // PDFs are not checked
+    let session = service.upload(id, "national_id", "application/pdf", b"%PDF".to_vec()).await.unwrap();
+    assert_eq!(session.documents[1].quality_score, None);
+}
@@ -0,0 +1,128 @@
+//! KYC component procedures executed in the Miden VM
+
+mod masm_harness;
+
+use compliance_backend::compliance::account_components::template::KycTemplate;
+use masm_harness::{ComponentHarness, DEFAULT_NOW, KYC_VALIDITY_SECS};
+
+const KYC_HASH: u64 = 0xabc;
+const VERIFIER: u64 = 77;
+
+#[test]
+fn get_kyc_status_reads_stored_slots() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(0, 1)
+        .with_slot(2, 100)
+        .with_slot(3, 200)
+        .with_slot(5, 2)
+        .call("get_kyc_status", &[]);
+    
+    assert_eq!(&outcome.stack()[..4], &[2, 200, 100, 1]);
+}
+
+#[test]
+fn verify_kyc_data_with_matching_hash_verifies() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(1, KYC_HASH)
+        .call("verify_kyc_data", &[KYC_HASH, VERIFIER, 2, 9]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(0), 1);
+    assert_eq!(outcome.slot(2), DEFAULT_NOW);
+    assert_eq!(outcome.slot(3), DEFAULT_NOW + KYC_VALIDITY_SECS);
+    assert_eq!(outcome.slot(4), VERIFIER);
+    assert_eq!(outcome.slot(5), 2);
+}
+
+#[test]
+fn verify_kyc_data_with_mismatched_hash_fails() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(1, KYC_HASH)
+        .call("verify_kyc_data", &[0xdef, VERIFIER, 2, 9]);
+    
+    assert_eq!(outcome.top(), 0);
+    assert_eq!(outcome.slot(0), 0);
+    assert_eq!(outcome.slot(4), 0);
+}
+
+#[test]
+fn verify_kyc_data_uses_configured_validity() {
+    let template = KycTemplate {
+        validity_secs: 86_400,
+        ..KycTemplate::default()
+    };
+    let outcome = ComponentHarness::kyc_with(&template)
+        .with_slot(1, KYC_HASH)
+        .call("verify_kyc_data", &[KYC_HASH, VERIFIER, 2, 9]);
+    
+    assert_eq!(outcome.slot(3), DEFAULT_NOW + 86_400);
+}
+
+#[test]
+fn verify_kyc_data_accepts_only_authorized_verifiers() {
+    let template = KycTemplate {
+        authorized_verifiers: vec![VERIFIER, 78],
+        ..KycTemplate::default()
+    };
+    let harness = ComponentHarness::kyc_with(&template).with_slot(1, KYC_HASH);
+    
+    let authorized = harness.call("verify_kyc_data", &[KYC_HASH, 78, 2, 9]);
+    assert_eq!(authorized.top(), 1);
+    assert_eq!(authorized.slot(4), 78);
+    
+    let unauthorized = harness.call("verify_kyc_data", &[KYC_HASH, 79, 2, 9]);
+    assert_eq!(unauthorized.top(), 0);
+    assert_eq!(unauthorized.slot(0), 0);
+}
+
+#[test]
+fn verify_kyc_data_when_already_verified_keeps_state() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(0, 1)
+        .with_slot(4, VERIFIER)
+        .call("verify_kyc_data", &[0xdef, 78, 3, 9]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(4), VERIFIER);
+    assert_eq!(outcome.slot(5), 0);
+}
+
+#[test]
+fn update_kyc_status_by_verifier_succeeds() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(4, VERIFIER)
+        .call("update_kyc_status", &[2, VERIFIER]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(0), 2);
+}
+
+#[test]
+fn update_kyc_status_by_other_caller_is_refused() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(0, 1)
+        .with_slot(4, VERIFIER)
+        .call("update_kyc_status", &[2, VERIFIER + 1]);
+    
+    assert_eq!(outcome.top(), 0);
+    assert_eq!(outcome.slot(0), 1);
+}
+
+#[test]
+fn update_compliance_level_by_verifier_succeeds() {
+    let outcome = ComponentHarness::kyc()
+        .with_slot(4, VERIFIER)
+        .with_slot(5, 1)
+        .call("update_compliance_level", &[3, VERIFIER]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(5), 3);
+}
+
+#[test]
+fn verify_kyc_proof_checks_commitment() {
+    let harness = ComponentHarness::kyc().with_slot(1, KYC_HASH);
+    
+    assert_eq!(harness.call("verify_kyc_proof", &[KYC_HASH, 5]).top(), 1);
+    assert_eq!(harness.call("verify_kyc_proof", &[0xdef, 5]).top(), 0);
+}
@@ -0,0 +1,83 @@
+//! On-chain counterparty KYC checks through foreign procedure invocation
+
+use compliance_backend::compliance::account_components::foreign::{
+    foreign_read_request, kyc_check_script, miden_account_id, procedure_root, KycGate, GATE_PROCEDURE,
+    KYC_STATUS_PROCEDURE,
+};
+use compliance_backend::compliance::account_components::migration::ComponentKind;
+use compliance_backend::compliance::account_components::template::{ComponentTemplates, KycTemplate};
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use compliance_backend::ComplianceError;
+
+/// A public regular account with updatable code
+const COUNTERPARTY: &str = "0xac0000000000dd100000ee000000fc";
+
+fn templates(validity_secs: u64) -> ComponentTemplates {
+    ComponentTemplates {
+        kyc: KycTemplate {
+            validity_secs,
+            ..KycTemplate::default()
+        },
+        ..ComponentTemplates::default()
+    }
+}
+
+#[test]
+fn procedure_roots_depend_on_the_deployment_parameters() {
+    let root = procedure_root(ComponentKind::Kyc, &ComponentTemplates::default(), KYC_STATUS_PROCEDURE).unwrap();
+    let again = procedure_root(ComponentKind::Kyc, &ComponentTemplates::default(), KYC_STATUS_PROCEDURE).unwrap();
+    assert_eq!(root, again);
+    
+    let verify = procedure_root(ComponentKind::Kyc, &ComponentTemplates::default(), "verify_kyc_data").unwrap();
+    assert_ne!(root, verify);
+    
+    let default_verify = procedure_root(ComponentKind::Kyc, &templates(31_536_000), "verify_kyc_data").unwrap();
+    let short_verify = procedure_root(ComponentKind::Kyc, &templates(86_400), "verify_kyc_data").unwrap();
+    assert_eq!(default_verify, verify);
+    assert_ne!(short_verify, verify);
+}
+
+#[test]
+fn unknown_procedures_are_rejected() {
+    let missing = procedure_root(ComponentKind::Kyc, &ComponentTemplates::default(), "steal_funds");
+    assert!(matches!(missing, Err(ComplianceError::Validation { .. })));
+}
+
+#[test]
+fn the_gate_calls_the_kyc_status_procedure_by_root() {
+    let templates = ComponentTemplates::default();
+    let root = procedure_root(ComponentKind::Kyc, &templates, KYC_STATUS_PROCEDURE).unwrap();
+    let gate = KycGate {
+        min_level: ComplianceLevel::Enhanced,
+    };
+    
+    let code = gate.render(root);
+    assert!(code.contains(&format!("export.{}", GATE_PROCEDURE)));
+    assert!(code.contains(&format!("push.{}", root.to_hex())));
+    assert!(code.contains("exec.tx::execute_foreign_procedure"));
+    assert!(code.contains("push.2 gte assert"), "the minimum level is Enhanced");
+    assert!(code.contains("push.1 eq assert"), "KYC must be verified");
+    
+    gate.component(&templates).unwrap();
+}
+
+#[test]
+fn check_scripts_compile_for_miden_counterparties() {
+    let counterparty = miden_account_id(&AccountId::parse(COUNTERPARTY).unwrap()).unwrap();
+    let templates = ComponentTemplates::default();
+    
+    let script = kyc_check_script(counterparty, &ComplianceLevel::Basic, &templates).unwrap();
+    foreign_read_request(counterparty, script).unwrap();
+}
+
+#[test]
+fn only_valid_miden_accounts_can_be_read() {
+    let evm = AccountId::parse("0x52908400098527886e0f7030069857d2e4169ee7").unwrap();
+    assert!(matches!(miden_account_id(&evm), Err(ComplianceError::Validation { .. })));
+    
+    let malformed = AccountId::parse(&format!("0x{:030x}", u64::MAX)).unwrap();
+    assert!(miden_account_id(&malformed).is_err());
+    
+    let valid = miden_account_id(&AccountId::parse(COUNTERPARTY).unwrap()).unwrap();
+    assert_eq!(valid.to_hex(), COUNTERPARTY);
+}
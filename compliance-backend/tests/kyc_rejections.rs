@@ -0,0 +1,101 @@
+//! Structured KYC rejection reasons and their retry eligibility
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::{Clock, MockClock};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::compliance::rejection::{KycRejection, RejectionReason};
+use compliance_backend::types::{AccountId, ComplianceAttestation, KycStatus};
+use std::sync::Arc;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn rejection(reasons: Vec<RejectionReason>) -> KycRejection {
+    KycRejection::new(reasons, Some("ref-1".to_string()), Utc::now())
+}
+
+fn issued(attestation: ComplianceAttestation) -> AttestationEvent {
+    AttestationEvent::AttestationIssued { attestation }
+}
+
+#[test]
+fn only_user_fixable_reasons_allow_a_retry() {
+    assert!(RejectionReason::DocumentExpired.retry_eligible());
+    assert!(RejectionReason::FaceMismatch.retry_eligible());
+    for reason in [
+        RejectionReason::TamperedDocument,
+        RejectionReason::Underage,
+        RejectionReason::UnsupportedJurisdiction,
+        RejectionReason::Other,
+    ] {
+        assert!(!reason.retry_eligible(), "{:?}", reason);
+        assert!(!reason.description().is_empty());
+    }
+}
+
+#[test]
+fn a_rejection_is_retryable_only_if_every_reason_is() {
+    let fixable = rejection(vec![RejectionReason::FaceMismatch, RejectionReason::DocumentExpired]);
+    assert!(fixable.retry_eligible);
+    
+    let mixed = rejection(vec![RejectionReason::FaceMismatch, RejectionReason::TamperedDocument]);
+    assert!(!mixed.retry_eligible);
+}
+
+#[test]
+fn reasons_are_normalized() {
+    let unexplained = rejection(vec![]);
+    assert_eq!(unexplained.reasons, [RejectionReason::Other]);
+    assert!(!unexplained.retry_eligible);
+    
+    let repeated = rejection(vec![
+        RejectionReason::Underage,
+        RejectionReason::DocumentExpired,
+        RejectionReason::Underage,
+    ]);
+    assert_eq!(repeated.reasons, [RejectionReason::DocumentExpired, RejectionReason::Underage]);
+}
+
+#[test]
+fn reasons_use_taxonomy_codes_on_the_wire() {
+    let unsupported = rejection(vec![RejectionReason::UnsupportedJurisdiction]);
+    let json = serde_json::to_value(&unsupported).unwrap();
+    assert_eq!(json["reasons"], serde_json::json!(["unsupported_jurisdiction"]));
+    assert_eq!(json["retry_eligible"], false);
+    assert_eq!(json["provider_reference"], "ref-1");
+    assert_eq!(serde_json::from_value::<KycRejection>(json).unwrap(), unsupported);
+    
+    let unknown: RejectionReason = serde_json::from_str("\"selfie_blurry\"").unwrap();
+    assert_eq!(unknown, RejectionReason::Other);
+}
+
+#[tokio::test]
+async fn the_rejection_stands_until_kyc_is_verified() {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    let store = AttestationEventStore::with_clock(clock.clone());
+    let account_id = account(1);
+    let verified = common::attestation(&account_id, clock.now(), Duration::days(30));
+    let rejected = ComplianceAttestation {
+        kyc_status: KycStatus::Rejected,
+        ..common::attestation(&account_id, clock.now(), Duration::days(30))
+    };
+    let kyc_rejection = rejection(vec![RejectionReason::FaceMismatch]);
+    
+    store.append(&account_id, issued(rejected.clone())).await.unwrap();
+    store
+        .append(&account_id, AttestationEvent::KycRejected { rejection: kyc_rejection.clone() })
+        .await
+        .unwrap();
+    let state = store.project(&account_id, None).await.unwrap().unwrap();
+    assert_eq!(state.kyc_rejection, Some(kyc_rejection.clone()));
+    
+    store.append(&account_id, issued(rejected)).await.unwrap();
+    let state = store.project(&account_id, None).await.unwrap().unwrap();
+    assert_eq!(state.kyc_rejection, Some(kyc_rejection));
+    
+    store.append(&account_id, issued(verified)).await.unwrap();
+    assert!(store.project(&account_id, None).await.unwrap().unwrap().kyc_rejection.is_none());
+}
@@ -0,0 +1,104 @@
+//! Leader leases shared by the instances of a deployment
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::config::LeaderElectionConfig;
+use compliance_backend::leader::{LeaderElection, LeaseStore, MemoryLeaseStore};
+use compliance_backend::{ComplianceError, Result};
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()
+}
+
+fn instance(id: &str, store: Arc<dyn LeaseStore>, clock: &Arc<MockClock>) -> LeaderElection {
+    LeaderElection::new(id, std::time::Duration::from_secs(60), store).with_clock(clock.clone())
+}
+
+/// Lease store whose database can be taken down
+#[derive(Default)]
+struct FlakyStore {
+    inner: MemoryLeaseStore,
+    down: AtomicBool,
+}
+
+impl LeaseStore for FlakyStore {
+    fn try_acquire<'a>(
+        &'a self,
+        lock: &'a str,
+        holder: &'a str,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> BoxFuture<'a, Result<bool>> {
+        if self.down.load(Ordering::SeqCst) {
+            return Box::pin(async { Err(ComplianceError::internal("database unreachable")) });
+        }
+        self.inner.try_acquire(lock, holder, now, until)
+    }
+}
+
+#[tokio::test]
+async fn one_instance_holds_a_lease_until_it_lapses() {
+    let clock = Arc::new(MockClock::new(start()));
+    let store: Arc<dyn LeaseStore> = Arc::new(MemoryLeaseStore::default());
+    let (a, b) = (instance("a", store.clone(), &clock), instance("b", store, &clock));
+    
+    assert!(a.acquire("outbox_relay").await);
+    assert!(!b.acquire("outbox_relay").await);
+    assert!(b.acquire("epoch_anchor").await);
+    
+    clock.advance(Duration::seconds(30));
+    assert!(a.acquire("outbox_relay").await);
+    
+    // A holder that stops renewing loses the lease once it lapses
+    clock.advance(Duration::seconds(61));
+    assert!(b.acquire("outbox_relay").await);
+    assert!(!a.acquire("outbox_relay").await);
+    
+    let health = a.health();
+    assert_eq!(health.len(), 1);
+    let relay = &health[0];
+    assert_eq!(relay.name, "outbox_relay");
+    assert!(!relay.held);
+    assert_eq!((relay.acquisitions, relay.renewals, relay.losses), (1, 1, 1));
+    
+    let health = b.health();
+    assert_eq!(health.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["epoch_anchor", "outbox_relay"]);
+    assert!(health.iter().all(|l| l.held && l.acquisitions == 1));
+}
+
+#[tokio::test]
+async fn an_unreachable_store_skips_the_work() {
+    let clock = Arc::new(MockClock::new(start()));
+    let store = Arc::new(FlakyStore::default());
+    let leader = instance("a", store.clone(), &clock);
+    
+    assert!(leader.acquire("outbox_relay").await);
+    store.down.store(true, Ordering::SeqCst);
+    assert!(!leader.acquire("outbox_relay").await);
+    
+    let relay = &leader.health()[0];
+    assert!(!relay.held);
+    assert_eq!(relay.errors, 1);
+    assert!(relay.last_error.as_deref().unwrap().contains("database unreachable"));
+    
+    store.down.store(false, Ordering::SeqCst);
+    assert!(leader.acquire("outbox_relay").await);
+    assert_eq!(leader.health()[0].acquisitions, 2);
+}
+
+#[test]
+fn election_follows_configuration() {
+    let store: Arc<dyn LeaseStore> = Arc::new(MemoryLeaseStore::default());
+    assert!(LeaderElection::from_config(&LeaderElectionConfig::default(), store.clone()).is_none());
+    
+    let config = LeaderElectionConfig {
+        enabled: true,
+        instance_id: Some("replica-1".to_string()),
+        ..Default::default()
+    };
+    let leader = LeaderElection::from_config(&config, store).unwrap();
+    assert_eq!(leader.instance_id(), "replica-1");
+}
@@ -0,0 +1,153 @@
+//! List version deltas and re-screening of only the affected accounts
+
+use compliance_backend::compliance::screening::attributes::SubjectAttributes;
+use compliance_backend::compliance::screening::delta::{name_keys, ListDelta};
+use compliance_backend::compliance::screening::matcher::NameMatcher;
+use compliance_backend::compliance::screening::results::ScreeningResultStore;
+use compliance_backend::compliance::screening::{EntityType, ScreenedEntity, ScreeningListStore};
+use compliance_backend::types::AccountId;
+
+fn entity(id: &str, name: &str, programs: &[&str]) -> ScreenedEntity {
+    ScreenedEntity {
+        id: id.to_string(),
+        list: "ofac_sdn".to_string(),
+        name: name.to_string(),
+        aliases: vec![],
+        entity_type: EntityType::Individual,
+        programs: programs.iter().map(|p| p.to_string()).collect(),
+        date_of_birth: None,
+        nationalities: vec![],
+        document_numbers: vec![],
+        addresses: vec![],
+    }
+}
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+async fn screen(results: &ScreeningResultStore, lists: &ScreeningListStore, n: u32, name: &str) -> Vec<String> {
+    let matcher = NameMatcher::new(0.85);
+    let result = results
+        .screen(lists, &matcher, &account(n), None, name, None, &SubjectAttributes::default())
+        .await;
+    result.matches.into_iter().map(|m| m.entity_id).collect()
+}
+
+#[test]
+fn deltas_classify_entries_by_id() {
+    let previous = vec![
+        entity("1", "Ali Rezaei", &["SDGT"]),
+        entity("2", "Ivan Petrov", &["UKRAINE"]),
+        entity("3", "Kim Jong", &["DPRK"]),
+    ];
+    let current = vec![
+        entity("1", "Ali Rezaei", &["SDGT"]),
+        entity("2", "Ivan Petrov", &["UKRAINE", "RUSSIA"]),
+        entity("4", "Maria Lopez", &["SDNTK"]),
+    ];
+    
+    let delta = ListDelta::compute("ofac_sdn", Some("v1"), &previous, "v2", &current);
+    assert_eq!(delta.added.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), ["4"]);
+    assert_eq!(delta.removed.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), ["3"]);
+    assert_eq!(delta.changed.len(), 1);
+    assert_eq!(delta.changed[0].previous.programs, ["UKRAINE"]);
+    assert_eq!(delta.touched_ids().into_iter().collect::<Vec<_>>(), ["2", "3"]);
+    
+    let summary = delta.summary();
+    assert_eq!((summary.added, summary.removed, summary.changed), (1, 1, 1));
+    assert_eq!(summary.from_version.as_deref(), Some("v1"));
+    assert_eq!(summary.to_version, "v2");
+    
+    assert!(ListDelta::compute("ofac_sdn", Some("v2"), &current, "v3", &current).is_empty());
+}
+
+#[test]
+fn name_keys_include_tokens_and_phonetic_codes() {
+    let keys = name_keys("Ali Rezaei", None);
+    assert!(keys.contains("ali"));
+    assert!(keys.contains("rezaei"));
+    assert!(keys.iter().any(|key| key.starts_with('~')));
+    
+    let delta = ListDelta::compute("ofac_sdn", None, &[], "v1", &[entity("1", "Ali Rezaei", &[])]);
+    assert_eq!(delta.name_keys(), keys);
+}
+
+#[tokio::test]
+async fn ingesting_reports_the_delta_against_the_previous_version() {
+    let lists = ScreeningListStore::new();
+    let first = lists.ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaei", &[])]).await.unwrap();
+    assert_eq!(first.from_version, None);
+    assert_eq!(first.added.len(), 1);
+    
+    let second = lists.ingest("ofac_sdn", "v2", vec![entity("2", "Ivan Petrov", &[])]).await.unwrap();
+    assert_eq!(second.from_version.as_deref(), Some("v1"));
+    assert_eq!((second.added.len(), second.removed.len()), (1, 1));
+    
+    assert!(lists.ingest("ofac sdn", "v1", vec![]).await.is_err());
+}
+
+#[tokio::test]
+async fn only_accounts_sharing_keys_or_matches_are_affected() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    lists.ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaei", &["SDGT"])]).await.unwrap();
+    assert_eq!(screen(&results, &lists, 1, "Ali Rezaei").await, ["1"]);
+    assert!(screen(&results, &lists, 2, "Ivan Petrov").await.is_empty());
+    assert!(screen(&results, &lists, 3, "Maria Lopez").await.is_empty());
+    
+    // Removing the matched entry affects its match; adding Petrov affects the namesake
+    let delta = lists.ingest("ofac_sdn", "v2", vec![entity("2", "Ivan Petrov", &[])]).await.unwrap();
+    let mut affected = results.affected_by(&delta).await;
+    affected.sort();
+    assert_eq!(affected, [account(1), account(2)]);
+    
+    let unchanged = lists.ingest("ofac_sdn", "v3", vec![entity("2", "Ivan Petrov", &[])]).await.unwrap();
+    assert!(results.affected_by(&unchanged).await.is_empty());
+}
+
+#[tokio::test]
+async fn rescreening_reports_changed_outcomes_and_leaves_others_alone() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    lists.ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaei", &["SDGT"])]).await.unwrap();
+    screen(&results, &lists, 1, "Ali Rezaei").await;
+    screen(&results, &lists, 2, "Ivan Petrov").await;
+    screen(&results, &lists, 3, "Maria Lopez").await;
+    let untouched = results.get(&account(3)).await.unwrap();
+    
+    let delta = lists
+        .ingest(
+            "ofac_sdn",
+            "v2",
+            vec![entity("1", "Ali Rezaei", &["SDGT", "IRGC"]), entity("2", "Ivan Petrov", &[])],
+        )
+        .await
+        .unwrap();
+    let mut rescreenings = results.rescreen(&lists, &NameMatcher::new(0.85), &delta).await;
+    rescreenings.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+    
+    assert_eq!(rescreenings.len(), 2);
+    assert_eq!(rescreenings[0].account_id, account(1));
+    assert!(!rescreenings[0].outcome_changed);
+    assert_eq!(rescreenings[1].account_id, account(2));
+    assert!(rescreenings[1].outcome_changed);
+    assert_eq!(rescreenings[1].current.matches[0].entity_id, "2");
+    assert_eq!(rescreenings[1].current.list_versions.get("ofac_sdn").map(String::as_str), Some("v2"));
+    
+    assert_eq!(results.get(&account(3)).await.unwrap().screened_at, untouched.screened_at);
+}
+
+#[tokio::test]
+async fn accounts_are_rescreened_with_their_recorded_name() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    let matcher = NameMatcher::new(0.85);
+    assert!(results.rescreen_account(&lists, &matcher, &account(1)).await.is_none());
+    
+    screen(&results, &lists, 1, "Ivan Petrov").await;
+    lists.ingest("ofac_sdn", "v1", vec![entity("2", "Ivan Petrov", &[])]).await.unwrap();
+    let result = results.rescreen_account(&lists, &matcher, &account(1)).await.unwrap();
+    assert_eq!(result.screened_name, "Ivan Petrov");
+    assert_eq!(result.matches.len(), 1);
+}
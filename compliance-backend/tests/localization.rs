@@ -0,0 +1,139 @@
+//! Display text rendering, catalog loading, and locale fallback
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::localization::{
+    accept_language, attestation_messages, normalize_locale, Message, MessageCatalog,
+};
+use compliance_backend::compliance::rejection::{KycRejection, RejectionReason};
+use compliance_backend::compliance::step_up::StepUpRequirement;
+use compliance_backend::config::LocalizationConfig;
+use compliance_backend::types::{AccountId, ComplianceAttestation, KycStatus};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// A catalog directory holding the given `(locale, json)` translation files
+fn catalog_dir(files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ztc-catalog-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (locale, json) in files {
+        std::fs::write(dir.join(format!("{}.json", locale)), json).unwrap();
+    }
+    dir
+}
+
+fn catalog() -> MessageCatalog {
+    let dir = catalog_dir(&[
+        ("pt", r#"{"rejection.document_expired": "O documento de identidade expirou"}"#),
+        ("pt-BR", r#"{"policy.level_not_met": "É necessária conformidade {required}"}"#),
+        ("fr", r#"{"requirement.re_liveness": "Une nouvelle vérification du vivant"}"#),
+    ]);
+    MessageCatalog::open(&dir).unwrap()
+}
+
+fn locales(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn built_in_english_is_rendered_without_a_catalog() {
+    let catalog = MessageCatalog::builtin();
+    let rendered = catalog.render(&Message::rejection(RejectionReason::DocumentExpired), &[]);
+    assert_eq!(rendered.key, "rejection.document_expired");
+    assert_eq!(rendered.locale, "en");
+    assert_eq!(rendered.text, "The identity document has expired");
+    
+    let rendered = catalog.render(&Message::requirement(StepUpRequirement::ReLiveness), &locales(&["de"]));
+    assert_eq!((rendered.locale.as_str(), rendered.text.as_str()), ("en", "A new liveness check"));
+}
+
+#[test]
+fn regions_fall_back_to_their_language_then_english() {
+    let catalog = catalog();
+    let preferred = locales(&["pt-BR"]);
+    
+    let level = Message::new("policy.level_not_met").with("required", "Enhanced");
+    let rendered = catalog.render(&level, &preferred);
+    assert_eq!((rendered.locale.as_str(), rendered.text.as_str()), ("pt-br", "É necessária conformidade Enhanced"));
+    
+    let expired = catalog.render(&Message::rejection(RejectionReason::DocumentExpired), &preferred);
+    assert_eq!(expired.locale, "pt");
+    
+    let underage = catalog.render(&Message::rejection(RejectionReason::Underage), &preferred);
+    assert_eq!(underage.locale, "en");
+    
+    assert_eq!(catalog.fallback_chain(&preferred), locales(&["pt-br", "pt", "en"]));
+}
+
+#[test]
+fn the_default_locale_comes_before_english() {
+    let dir = catalog_dir(&[("fr", r#"{"requirement.re_liveness": "Une nouvelle vérification du vivant"}"#)]);
+    let catalog = MessageCatalog::from_config(&LocalizationConfig {
+        catalog_dir: Some(dir),
+        default_locale: "fr".to_string(),
+    })
+    .unwrap();
+    
+    let rendered = catalog.render(&Message::requirement(StepUpRequirement::ReLiveness), &locales(&["de"]));
+    assert_eq!(rendered.locale, "fr");
+    assert_eq!(catalog.fallback_chain(&locales(&["de-AT"])), locales(&["de-at", "de", "fr", "en"]));
+}
+
+#[test]
+fn display_renders_each_requested_locale() {
+    let catalog = catalog();
+    let messages = [Message::rejection(RejectionReason::DocumentExpired)];
+    let display = catalog.display(&messages, &locales(&["pt_BR", "en"]));
+    assert_eq!(display["pt-br"][0].text, "O documento de identidade expirou");
+    assert_eq!(display["en"][0].text, "The identity document has expired");
+    
+    let display = catalog.display(&messages, &[]);
+    assert_eq!(display.keys().collect::<Vec<_>>(), ["en"]);
+}
+
+#[test]
+fn unknown_keys_render_as_the_key() {
+    let rendered = catalog().render(&Message::new("policy.unheard_of"), &locales(&["pt"]));
+    assert_eq!(rendered.text, "policy.unheard_of");
+}
+
+#[test]
+fn translation_files_must_be_named_after_a_locale() {
+    let dir = catalog_dir(&[("not a locale", "{}")]);
+    assert!(MessageCatalog::open(&dir).is_err());
+    let dir = catalog_dir(&[("de", "not json")]);
+    assert!(MessageCatalog::open(&dir).is_err());
+}
+
+#[test]
+fn accept_language_orders_by_quality() {
+    assert_eq!(
+        accept_language("fr;q=0.5, pt-BR, *;q=0.1, de;q=0, en;q=0.8"),
+        locales(&["pt-br", "en", "fr"])
+    );
+    assert!(accept_language("").is_empty());
+    assert_eq!(normalize_locale("zh_Hant_TW").unwrap(), "zh-hant-tw");
+    assert!(normalize_locale("english").is_err());
+}
+
+#[test]
+fn attestation_messages_explain_what_is_missing() {
+    let account_id = AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap();
+    let attestation = ComplianceAttestation {
+        kyc_status: KycStatus::Rejected,
+        sanctions_cleared: false,
+        ..common::attestation(&account_id, Utc::now(), Duration::zero())
+    };
+    let keys = |messages: Vec<Message>| messages.into_iter().map(|m| m.key).collect::<Vec<_>>();
+    
+    assert_eq!(
+        keys(attestation_messages(&attestation, None)),
+        ["policy.kyc_status", "policy.sanctions_not_cleared"]
+    );
+    let rejection = KycRejection::new(vec![RejectionReason::FaceMismatch], None, Utc::now());
+    assert_eq!(
+        keys(attestation_messages(&attestation, Some(&rejection))),
+        ["rejection.face_mismatch", "policy.sanctions_not_cleared"]
+    );
+}
@@ -0,0 +1,93 @@
+//! PII redaction of request and response bodies written to logs
+
+use compliance_backend::logging::{Redaction, RedactionRegistry};
+use serde_json::json;
+
+#[test]
+fn default_fields_are_redacted_at_any_depth() {
+    let registry = RedactionRegistry::new();
+    let mut body = json!({
+        "account_id": "0xabc",
+        "applicant": {
+            "first_name": "Jane",
+            "date_of_birth": "1990-01-01",
+            "documents": [{ "type": "passport", "passport_number": "X1234567" }],
+        },
+        "api_key": "sk-live",
+    });
+    
+    registry.redact(&mut body);
+    
+    assert_eq!(body["account_id"], "0xabc");
+    assert_eq!(body["applicant"]["first_name"], "[redacted]");
+    assert_eq!(body["applicant"]["date_of_birth"], "[redacted]");
+    assert_eq!(body["applicant"]["documents"][0]["type"], "passport");
+    assert!(body["applicant"]["documents"][0]["passport_number"].as_str().unwrap().starts_with("[hash:"));
+    assert_eq!(body["api_key"], "[redacted]");
+}
+
+#[test]
+fn hashed_values_correlate_without_revealing() {
+    let registry = RedactionRegistry::new();
+    let mut first = json!({ "email": "jane@example.com" });
+    let mut second = json!({ "email": "jane@example.com" });
+    let mut other = json!({ "email": "john@example.com" });
+    
+    registry.redact(&mut first);
+    registry.redact(&mut second);
+    registry.redact(&mut other);
+    
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+    assert!(!first.to_string().contains("jane"));
+}
+
+#[test]
+fn field_names_match_ignoring_case_and_separators() {
+    let registry = RedactionRegistry::new();
+    
+    assert_eq!(registry.redaction_for("FirstName"), Some(Redaction::Mask));
+    assert_eq!(registry.redaction_for("first-name"), Some(Redaction::Mask));
+    assert_eq!(registry.redaction_for("Tax_ID"), Some(Redaction::Hash));
+    assert_eq!(registry.redaction_for("amount"), None);
+}
+
+#[test]
+fn configured_fields_extend_the_defaults() {
+    let registry = RedactionRegistry::with_fields(&["wallet_label".to_string()]);
+    let mut body = json!({ "wallet_label": "savings", "name": "Jane", "amount": 10 });
+    
+    registry.redact(&mut body);
+    
+    assert_eq!(body, json!({ "wallet_label": "[redacted]", "name": "[redacted]", "amount": 10 }));
+}
+
+#[test]
+fn null_values_are_left_alone() {
+    let registry = RedactionRegistry::new();
+    let mut body = json!({ "middle_name": null });
+    
+    registry.redact(&mut body);
+    
+    assert_eq!(body, json!({ "middle_name": null }));
+}
+
+#[test]
+fn empty_registry_redacts_nothing() {
+    let registry = RedactionRegistry::empty();
+    let mut body = json!({ "name": "Jane", "password": "hunter2" });
+    
+    registry.redact(&mut body);
+    
+    assert_eq!(body, json!({ "name": "Jane", "password": "hunter2" }));
+}
+
+#[test]
+fn rendered_bodies_are_redacted_or_summarized() {
+    let registry = RedactionRegistry::new();
+    
+    assert_eq!(registry.render_body(b""), "");
+    assert_eq!(registry.render_body(br#"{"ssn":null,"amount":5}"#), r#"{"amount":5,"ssn":null}"#);
+    assert!(!registry.render_body(br#"{"password":"hunter2"}"#).contains("hunter2"));
+    assert_eq!(registry.render_body(b"name=Jane"), "<9 bytes, not JSON>");
+}
@@ -0,0 +1,177 @@
+//! In-process execution of compliance component procedures
+//!
+//! A procedure runs inside a generated program: storage fixtures are written
+//! to the component's slots, the scripted inputs are pushed, the procedure is
+//! executed, and every slot is read back. `sys.time_now` resolves to a fixed
+//! clock so timestamps written by a procedure are predictable.
+
+#![allow(dead_code)]
+
+use compliance_backend::compliance::account_components::template::{AmlTemplate, KycTemplate, SanctionsTemplate};
+use miden_stdlib::StdLibrary;
+use miden_vm::assembly::ast::{Module, ModuleKind};
+use miden_vm::assembly::{DefaultSourceManager, LibraryPath};
+use miden_vm::{Assembler, DefaultHost, ExecutionOptions, StackInputs};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Storage slots of every component, read back after each run
+pub const SLOT_COUNT: usize = 6;
+
+/// Clock reading returned by `sys.time_now` unless overridden
+pub const DEFAULT_NOW: u64 = 1_700_000_000;
+
+/// Seconds a KYC verification stays valid
+pub const KYC_VALIDITY_SECS: u64 = 31_536_000;
+
+/// A component loaded for procedure runs
+pub struct ComponentHarness {
+    name: &'static str,
+    source: String,
+    slots: Vec<(usize, u64)>,
+    now: u64,
+}
+
+impl ComponentHarness {
+    /// Load a component's source under `name`
+    pub fn new(name: &'static str, source: String) -> Self {
+        Self {
+            name,
+            source,
+            slots: Vec::new(),
+            now: DEFAULT_NOW,
+        }
+    }
+    
+    /// The KYC component with default parameters
+    pub fn kyc() -> Self {
+        Self::kyc_with(&KycTemplate::default())
+    }
+    
+    /// The KYC component generated from `template`
+    pub fn kyc_with(template: &KycTemplate) -> Self {
+        Self::new("kyc", template.render())
+    }
+    
+    /// The AML component with default parameters
+    pub fn aml() -> Self {
+        Self::aml_with(&AmlTemplate::default())
+    }
+    
+    /// The AML component generated from `template`
+    pub fn aml_with(template: &AmlTemplate) -> Self {
+        Self::new("aml", template.render())
+    }
+    
+    /// The sanctions screening component with default parameters
+    pub fn sanctions() -> Self {
+        Self::sanctions_with(&SanctionsTemplate::default())
+    }
+    
+    /// The sanctions screening component generated from `template`
+    pub fn sanctions_with(template: &SanctionsTemplate) -> Self {
+        Self::new("sanctions", template.render())
+    }
+    
+    /// Set a storage slot before the procedure runs
+    pub fn with_slot(mut self, slot: usize, value: u64) -> Self {
+        assert!(slot < SLOT_COUNT, "components have {} slots", SLOT_COUNT);
+        self.slots.push((slot, value));
+        self
+    }
+    
+    /// Fix the clock read by `sys.time_now`
+    pub fn at(mut self, now: u64) -> Self {
+        self.now = now;
+        self
+    }
+    
+    /// Run a procedure with `inputs`, first input on top of the stack
+    ///
+    /// Panics if the component fails to assemble or the procedure fails to execute.
+    pub fn call(&self, procedure: &str, inputs: &[u64]) -> Outcome {
+        self.try_call(procedure, inputs)
+            .unwrap_or_else(|e| panic!("{}::{} failed: {}", self.name, procedure, e))
+    }
+    
+    /// Run a procedure, returning assembly and execution errors
+    pub fn try_call(&self, procedure: &str, inputs: &[u64]) -> Result<Outcome, String> {
+        let source_manager = Arc::new(DefaultSourceManager::default());
+        let parse = |path: &str, source: String| {
+            let path = LibraryPath::new(path).map_err(|e| e.to_string())?;
+            Module::parser(ModuleKind::Library)
+                .parse_str(path, source, &source_manager)
+                .map_err(|e| e.to_string())
+        };
+        let clock = parse("harness::clock", format!("export.time_now\n    push.{}\nend\n", self.now))?;
+        let component = parse(
+            &format!("harness::{}", self.name),
+            self.source.replace("use.std::sys", "use.harness::clock->sys"),
+        )?;
+        
+        let program = Assembler::new(source_manager.clone())
+            .with_library(StdLibrary::default())
+            .and_then(|assembler| assembler.with_module(clock))
+            .and_then(|assembler| assembler.with_module(component))
+            .and_then(|assembler| assembler.assemble_program(self.program(procedure, inputs)))
+            .map_err(|e| e.to_string())?;
+        
+        let mut host = DefaultHost::default();
+        host.load_mast_forest(StdLibrary::default().mast_forest().clone())
+            .map_err(|e| e.to_string())?;
+        let trace = miden_vm::execute(&program, StackInputs::default(), &mut host, ExecutionOptions::default())
+            .map_err(|e| e.to_string())?;
+        
+        let stack: Vec<u64> = trace.stack_outputs().stack().iter().map(|felt| felt.as_int()).collect();
+        let mut slots = [0; SLOT_COUNT];
+        slots.copy_from_slice(&stack[..SLOT_COUNT]);
+        Ok(Outcome {
+            slots,
+            stack: stack[SLOT_COUNT..].to_vec(),
+        })
+    }
+    
+    /// Program writing the fixtures, running the procedure, and reading the slots back
+    fn program(&self, procedure: &str, inputs: &[u64]) -> String {
+        let mut program = format!("use.std::sys\nuse.harness::{}\n\nbegin\n", self.name);
+        for (slot, value) in &self.slots {
+            let _ = writeln!(program, "    push.{} push.{} mem_store", value, slot);
+        }
+        for input in inputs.iter().rev() {
+            let _ = writeln!(program, "    push.{}", input);
+        }
+        let _ = writeln!(program, "    exec.{}::{}", self.name, procedure);
+        for slot in (0..SLOT_COUNT).rev() {
+            let _ = writeln!(program, "    push.{} mem_load", slot);
+        }
+        program.push_str("    exec.sys::truncate_stack\nend\n");
+        program
+    }
+}
+
+/// State after a procedure run
+#[derive(Debug)]
+pub struct Outcome {
+    slots: [u64; SLOT_COUNT],
+    stack: Vec<u64>,
+}
+
+impl Outcome {
+    /// Value of a storage slot after the run
+    pub fn slot(&self, slot: usize) -> u64 {
+        self.slots[slot]
+    }
+    
+    /// Stack left by the procedure, top first
+    ///
+    /// Holds the procedure's outputs followed by any inputs it left in place,
+    /// truncated to the ten elements below the read-back slots.
+    pub fn stack(&self) -> &[u64] {
+        &self.stack
+    }
+    
+    /// Top of the stack left by the procedure
+    pub fn top(&self) -> u64 {
+        self.stack[0]
+    }
+}
@@ -0,0 +1,110 @@
+//! Usage counting, plan quotas, and usage webhooks
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::metering::{billing_period, BillableOperation, UsageMeter, USAGE_THRESHOLD_EVENT};
+use compliance_backend::config::{ComplianceConfig, PlanConfig};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::storage::OutboxRepo;
+use compliance_backend::ComplianceError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn start() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 30, 23, 0, 0).unwrap()
+}
+
+struct Metering {
+    meter: UsageMeter,
+    store: Arc<MemoryStore>,
+    clock: Arc<MockClock>,
+}
+
+/// A meter whose `limited` client may verify 10 accounts per period
+fn metering(limited: Uuid) -> Metering {
+    let mut config = ComplianceConfig::default();
+    config.metering.plans.insert(
+        "starter".to_string(),
+        PlanConfig {
+            quotas: HashMap::from([(BillableOperation::KycVerification, 10)]),
+        },
+    );
+    config.metering.clients.insert(limited, "starter".to_string());
+    
+    let store = Arc::new(MemoryStore::default());
+    let clock = Arc::new(MockClock::new(start()));
+    Metering {
+        meter: UsageMeter::new(Arc::new(LiveConfig::new(config)), store.usage.clone(), store.clone(), clock.clone()),
+        store,
+        clock,
+    }
+}
+
+#[test]
+fn billing_periods_are_calendar_months() {
+    let (start, end) = billing_period(Utc.with_ymd_and_hms(2025, 12, 15, 8, 30, 0).unwrap());
+    assert_eq!(start, Utc.with_ymd_and_hms(2025, 12, 1, 0, 0, 0).unwrap());
+    assert_eq!(end, Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn usage_is_counted_per_client_and_operation() {
+    let client = Uuid::new_v4();
+    let metering = metering(Uuid::new_v4());
+    for _ in 0..3 {
+        metering.meter.charge(client, BillableOperation::ApiCall).await.unwrap();
+    }
+    metering.meter.charge(client, BillableOperation::Screening).await.unwrap();
+    
+    let usage = metering.meter.usage(client, start()).await.unwrap();
+    assert!(usage.plan.is_none());
+    let used = |operation| usage.operations.iter().find(|u| u.operation == operation).unwrap().used;
+    assert_eq!(used(BillableOperation::ApiCall), 3);
+    assert_eq!(used(BillableOperation::Screening), 1);
+    assert_eq!(used(BillableOperation::KycVerification), 0);
+    
+    let all = metering.meter.period_usage(start()).await.unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].client_id, client);
+}
+
+#[tokio::test]
+async fn operations_over_quota_are_rejected_until_the_next_period() {
+    let client = Uuid::new_v4();
+    let metering = metering(client);
+    for _ in 0..10 {
+        metering.meter.charge(client, BillableOperation::KycVerification).await.unwrap();
+    }
+    let rejected = metering.meter.charge(client, BillableOperation::KycVerification).await;
+    assert!(matches!(rejected, Err(ComplianceError::QuotaExceeded { quota: 10, .. })));
+    
+    let usage = metering.meter.usage(client, start()).await.unwrap();
+    let kyc = usage.operations.iter().find(|u| u.operation == BillableOperation::KycVerification).unwrap();
+    assert_eq!((kyc.used, kyc.quota, kyc.remaining), (10, Some(10), Some(0)));
+    
+    // Operations without a quota are unaffected
+    metering.meter.charge(client, BillableOperation::ApiCall).await.unwrap();
+    
+    metering.clock.advance(Duration::hours(1));
+    metering.meter.charge(client, BillableOperation::KycVerification).await.unwrap();
+}
+
+#[tokio::test]
+async fn each_threshold_is_notified_once_per_period() {
+    let client = Uuid::new_v4();
+    let metering = metering(client);
+    for _ in 0..10 {
+        metering.meter.charge(client, BillableOperation::KycVerification).await.unwrap();
+    }
+    let _ = metering.meter.charge(client, BillableOperation::KycVerification).await;
+    
+    let pending = metering.store.outbox.pending(10).await.unwrap();
+    let thresholds: Vec<_> = pending
+        .iter()
+        .filter(|message| message.event_type == USAGE_THRESHOLD_EVENT)
+        .map(|message| message.payload["threshold_percent"].as_u64().unwrap())
+        .collect();
+    assert_eq!(thresholds, vec![80, 100]);
+}
@@ -0,0 +1,134 @@
+//! Management information report schedules and rendering
+
+use chrono::{TimeZone, Utc};
+use compliance_backend::reporting::render::{to_csv, to_pdf};
+use compliance_backend::reporting::schedule::CronSchedule;
+use compliance_backend::reporting::{Report, ReportFormat, ReportMetrics};
+use uuid::Uuid;
+
+fn report(metrics: ReportMetrics) -> Report {
+    Report {
+        id: Uuid::new_v4(),
+        client_id: Uuid::new_v4(),
+        schedule: Some("monthly".to_string()),
+        period_from: Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap(),
+        period_to: Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap(),
+        generated_at: Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap(),
+        metrics,
+    }
+}
+
+fn metrics() -> ReportMetrics {
+    let mut metrics = ReportMetrics::default();
+    metrics.funnel.accounts_checked = 120;
+    metrics.funnel.attestations_issued = 100;
+    metrics.rejection_reasons.insert("document_expired".to_string(), 7);
+    metrics.rejection_reasons.insert("name mismatch, \"John\"".to_string(), 2);
+    metrics.risk_distribution.insert("Low".to_string(), 80);
+    metrics.screening.accounts_screened = 100;
+    metrics.screening.sanctions_hits = 3;
+    metrics.screening.hit_rate = 0.03;
+    metrics.sla.authorization_outcomes.insert("approved".to_string(), 40);
+    metrics.sla.median_step_up_hours = Some(1.5);
+    metrics
+}
+
+#[test]
+fn cron_expressions_are_validated() {
+    assert!(CronSchedule::parse("0 6 * * *").is_ok());
+    assert!(CronSchedule::parse("0 6 * *").is_err());
+    assert!(CronSchedule::parse("60 * * * *").is_err());
+    assert!(CronSchedule::parse("0 0 0 * *").is_err());
+    assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    assert!(CronSchedule::parse("30-10 * * * *").is_err());
+    assert!(CronSchedule::parse("a * * * *").is_err());
+}
+
+#[test]
+fn cron_fields_accept_lists_ranges_and_steps() {
+    let at = |day, hour, minute| Utc.with_ymd_and_hms(2025, 6, day, hour, minute, 0).unwrap();
+    let schedule = CronSchedule::parse("0,30 9-17/4 * * 1-5").unwrap();
+    
+    // 2 June 2025 is a Monday
+    assert!(schedule.matches(at(2, 9, 0)));
+    assert!(schedule.matches(at(2, 13, 30)));
+    assert!(schedule.matches(at(2, 17, 0)));
+    assert!(!schedule.matches(at(2, 10, 0)));
+    assert!(!schedule.matches(at(2, 9, 15)));
+    assert!(!schedule.matches(at(1, 9, 0)));
+}
+
+#[test]
+fn sunday_is_zero_or_seven() {
+    // 1 June 2025 is a Sunday
+    let sunday = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    assert!(CronSchedule::parse("0 0 * * 0").unwrap().matches(sunday));
+    assert!(CronSchedule::parse("0 0 * * 7").unwrap().matches(sunday));
+}
+
+#[test]
+fn restricted_day_fields_match_either() {
+    let schedule = CronSchedule::parse("0 0 15 * 1").unwrap();
+    
+    // The 15th (a Sunday) and any Monday both fire
+    assert!(schedule.matches(Utc.with_ymd_and_hms(2025, 6, 15, 0, 0, 0).unwrap()));
+    assert!(schedule.matches(Utc.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap()));
+    assert!(!schedule.matches(Utc.with_ymd_and_hms(2025, 6, 3, 0, 0, 0).unwrap()));
+}
+
+#[test]
+fn csv_lists_every_metric_by_section() {
+    let csv = to_csv(&report(metrics()));
+    let lines: Vec<&str> = csv.lines().collect();
+    
+    assert_eq!(lines[0], "section,metric,value");
+    assert!(lines.contains(&"onboarding_funnel,accounts_checked,120"));
+    assert!(lines.contains(&"rejection_reasons,document_expired,7"));
+    assert!(lines.contains(&"rejection_reasons,\"name mismatch, \"\"John\"\"\",2"));
+    assert!(lines.contains(&"risk_distribution,Low,80"));
+    assert!(lines.contains(&"screening,hit_rate,0.0300"));
+    assert!(lines.contains(&"sla,authorizations_approved,40"));
+    assert!(lines.contains(&"sla,median_step_up_hours,1.50"));
+}
+
+#[test]
+fn missing_medians_render_empty() {
+    let csv = to_csv(&report(ReportMetrics::default()));
+    assert!(csv.lines().any(|line| line == "sla,median_step_up_hours,"));
+}
+
+#[test]
+fn pdf_is_a_complete_document() {
+    let pdf = to_pdf(&report(metrics()));
+    let text = String::from_utf8_lossy(&pdf);
+    
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(text.trim_end().ends_with("%%EOF"));
+    assert!(text.contains("(Management Information Report) Tj"));
+    assert!(text.contains("(ONBOARDING FUNNEL) Tj"));
+    assert_eq!(text.matches("/Type /Page /Parent").count(), 1);
+    
+    // The cross-reference table points at each object
+    let xref = text.rfind("startxref\n").unwrap();
+    let offset: usize = text[xref + 10..].lines().next().unwrap().parse().unwrap();
+    assert!(text[offset..].starts_with("xref\n"));
+}
+
+#[test]
+fn long_reports_span_pages_and_escape_text() {
+    let mut metrics = metrics();
+    for n in 0..60 {
+        metrics.rejection_reasons.insert(format!("reason ({})", n), 1);
+    }
+    let text = String::from_utf8_lossy(&to_pdf(&report(metrics))).to_string();
+    
+    assert_eq!(text.matches("/Type /Page /Parent").count(), 2);
+    assert!(text.contains("/Count 2"));
+    assert!(text.contains("reason \\(0\\): 1"));
+}
+
+#[test]
+fn formats_use_lowercase_names() {
+    assert_eq!(serde_json::to_value(ReportFormat::Csv).unwrap(), "csv");
+    assert_eq!(serde_json::from_str::<ReportFormat>("\"pdf\"").unwrap(), ReportFormat::Pdf);
+}
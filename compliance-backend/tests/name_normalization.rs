@@ -0,0 +1,75 @@
+//! Script normalization, honorific stripping and name-part ordering in screening
+
+use compliance_backend::compliance::screening::matcher::{transliterate, MatchMethod, NameMatcher};
+use compliance_backend::compliance::screening::normalize::{consonant_skeleton, is_language_tag, NameForm};
+
+fn matcher() -> NameMatcher {
+    NameMatcher::new(0.85)
+}
+
+#[test]
+fn honorifics_and_particles_are_dropped() {
+    assert_eq!(NameForm::parse("Dr. Ahmed Al-Rashid", None).written, ["ahmed", "rashid"]);
+    assert_eq!(NameForm::parse("Sheikh", None).written, ["sheikh"]);
+}
+
+#[test]
+fn comma_inverted_names_are_reordered() {
+    let form = NameForm::parse("KIM, Jong Un", None);
+    assert_eq!(form.written, ["kim", "jong", "un"]);
+    assert_eq!(form.ordered, ["jong", "un", "kim"]);
+}
+
+#[test]
+fn differently_split_given_names_match() {
+    let result = matcher().score_in("Kim Jongun", Some("ko"), "KIM, Jong Un");
+    assert!((result.score - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn hangul_names_use_customary_surnames() {
+    let form = NameForm::parse("김정은", None);
+    assert_eq!(form.written, ["kim", "jeongeun"]);
+    assert_eq!(form.ordered, ["jeongeun", "kim"]);
+}
+
+#[test]
+fn han_names_match_their_pinyin() {
+    assert_eq!(transliterate("习近平"), "xi jinping");
+    assert_eq!(matcher().score("习近平", "XI Jinping").method, MatchMethod::Exact);
+}
+
+#[test]
+fn kana_follow_hepburn() {
+    assert_eq!(transliterate("ニッポン"), "nippon");
+    assert_eq!(transliterate("きょうこ"), "kyouko");
+    assert_eq!(transliterate("しゃ ちゃ じょ"), "sha cha jo");
+}
+
+#[test]
+fn arabic_names_match_on_consonants() {
+    assert_eq!(transliterate("محمد"), "mhmd");
+    assert_eq!(consonant_skeleton("mohammed"), "mhmd");
+    
+    let result = matcher().score("محمد", "Mohammed");
+    assert_eq!(result.method, MatchMethod::Phonetic);
+    assert!(result.score >= 0.9 - 1e-9);
+    
+    assert!(matcher().matches("أسامة بن لادن", "Usama bin Laden").is_some());
+}
+
+#[test]
+fn language_hints_select_romanization() {
+    let variants = NameForm::variants("Григорій", Some("uk"));
+    assert_eq!(variants[0].written, ["hryhoriy"]);
+    assert_eq!(variants[1].written, ["grigoriy"]);
+    assert_eq!(NameForm::variants("Григорий", None).len(), 1);
+}
+
+#[test]
+fn language_tags_are_validated() {
+    assert!(is_language_tag("uk"));
+    assert!(is_language_tag("zh-Hant-TW"));
+    assert!(!is_language_tag("x"));
+    assert!(!is_language_tag("en_US"));
+}
@@ -0,0 +1,89 @@
+//! RFC 3161 message encoding and notarization evidence in projections
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::compliance::notarization::{timestamp_request, timestamp_token, Notarization};
+use compliance_backend::types::AccountId;
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+fn block_reference() -> Notarization {
+    Notarization::BlockReference {
+        chain: "ethereum".to_string(),
+        block_number: 20_000_000,
+        block_hash: "ab".repeat(32),
+        block_timestamp: Utc.with_ymd_and_hms(2025, 6, 1, 11, 59, 48).unwrap(),
+        key_id: "test".to_string(),
+        binding: "cd".repeat(64),
+    }
+}
+
+#[test]
+fn timestamp_request_is_der_encoded() {
+    let imprint = [0x11; 32];
+    let request = timestamp_request(&imprint, 0x4000_0000_0000_0001);
+    
+    let mut expected = vec![0x30, 0x43, 0x02, 0x01, 0x01, 0x30, 0x31];
+    expected.extend([0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00]);
+    expected.extend([0x04, 0x20]);
+    expected.extend(imprint);
+    expected.extend([0x02, 0x08, 0x40, 0, 0, 0, 0, 0, 0, 0x01]);
+    expected.extend([0x01, 0x01, 0xff]);
+    assert_eq!(request, expected);
+}
+
+#[test]
+fn granted_response_yields_the_token() {
+    let token = [0x30, 0x03, 0x02, 0x01, 0x07];
+    let mut response = vec![0x30, 0x0a, 0x30, 0x03, 0x02, 0x01, 0x00];
+    response.extend(token);
+    assert_eq!(timestamp_token(&response).unwrap(), token);
+}
+
+#[test]
+fn rejected_response_is_an_error() {
+    let response = [0x30, 0x05, 0x30, 0x03, 0x02, 0x01, 0x02];
+    assert!(timestamp_token(&response).is_err());
+}
+
+#[test]
+fn truncated_response_is_an_error() {
+    let response = [0x30, 0x0a, 0x30, 0x03, 0x02];
+    assert!(timestamp_token(&response).is_err());
+}
+
+#[tokio::test]
+async fn notarization_attaches_to_the_current_attestation_only() {
+    let events = AttestationEventStore::new();
+    let first = common::attestation(&account(), Utc::now(), Duration::days(90));
+    events.append(&account(), AttestationEvent::AttestationIssued { attestation: first.clone() }).await.unwrap();
+    events
+        .append(
+            &account(),
+            AttestationEvent::Notarized {
+                attestation_id: first.id,
+                notarization: block_reference(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events.project(&account(), None).await.unwrap().unwrap().notarization, Some(block_reference()));
+    
+    let second = common::attestation(&account(), Utc::now(), Duration::days(90));
+    events.append(&account(), AttestationEvent::AttestationIssued { attestation: second }).await.unwrap();
+    events
+        .append(
+            &account(),
+            AttestationEvent::Notarized {
+                attestation_id: first.id,
+                notarization: block_reference(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(events.project(&account(), None).await.unwrap().unwrap().notarization, None);
+}
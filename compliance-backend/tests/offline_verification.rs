@@ -0,0 +1,161 @@
+//! Offline verification of proof envelopes by third-party verifiers
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::claims::{Claim, ClaimSet, JurisdictionClass, PepStatus};
+use compliance_backend::compliance::proof_envelope::{EnvelopeParams, ProofCompression, ProofEnvelope};
+use compliance_backend::compliance::scope::{AmountBand, AssetClass, ProofScope, ScopeUsage};
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceLevel};
+use compliance_backend::verifier::{verify_proof_envelope, VerificationPolicy, DEFAULT_MAX_PROOF_SIZE};
+use compliance_backend::ComplianceError;
+
+const AUDIENCE: &str = "exchange.example";
+
+fn account_id() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+/// Encoded envelope signed by a fresh key, and the keys trusting it
+fn issue(scope: Option<ProofScope>) -> (String, TrustedKeys) {
+    let claims = ClaimSet::new(vec![
+        Claim::AttestationAge(3_600),
+        Claim::KycTier(Some(ComplianceLevel::Standard)),
+        Claim::RiskBand(AmlRiskLevel::Low),
+        Claim::PepStatus(PepStatus::Clear),
+        Claim::JurisdictionClass(JurisdictionClass::Standard),
+    ])
+    .unwrap();
+    let signer = AttestationSigner::generate("issuer-1");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert(signer.key_id(), signer.verifying_key());
+    let envelope = ProofEnvelope::seal(
+        EnvelopeParams {
+            attestation: &common::attestation(&account_id(), Utc::now(), Duration::days(30)),
+            claims: &claims,
+            disclose: &[],
+            audience: AUDIENCE,
+            nonce: "ab",
+            scope,
+            proof: vec![7u8; 64],
+            compression: ProofCompression::None,
+            validity: Duration::hours(1),
+        },
+        &signer,
+    )
+    .unwrap();
+    (envelope.encode().unwrap(), trusted)
+}
+
+fn invalid_reason(result: compliance_backend::Result<impl std::fmt::Debug>) -> String {
+    match result {
+        Err(ComplianceError::InvalidProof { reason }) => reason,
+        other => panic!("expected an invalid proof, got {:?}", other),
+    }
+}
+
+fn stablecoin_scope() -> ProofScope {
+    ProofScope {
+        application: Some("dex".to_string()),
+        asset_class: Some(AssetClass::Stablecoin),
+        amount_band: Some(AmountBand { min: 0, max: 10_000 }),
+        min_level: Some(ComplianceLevel::Standard),
+    }
+}
+
+#[test]
+fn a_valid_envelope_yields_its_verified_contents() {
+    let (encoded, trusted) = issue(None);
+    let verified = verify_proof_envelope(&encoded, &trusted, &VerificationPolicy::new(AUDIENCE)).unwrap();
+    
+    assert_eq!(verified.account_id, account_id());
+    assert_eq!(verified.audience, AUDIENCE);
+    assert_eq!(verified.nonce, "ab");
+    assert_eq!(verified.key_id, "issuer-1");
+    assert_eq!(verified.proof_bytes, vec![7u8; 64]);
+    assert!(verified.claims.is_empty());
+    assert!(verified.scope.is_none());
+    
+    let (issued_at, expires_at) = (verified.issued_at.unwrap(), verified.expires_at.unwrap());
+    assert_eq!(expires_at - issued_at, Duration::hours(1));
+}
+
+#[test]
+fn policy_defaults_match_the_backend() {
+    let policy = VerificationPolicy::new(AUDIENCE);
+    assert_eq!(policy.max_proof_size, DEFAULT_MAX_PROOF_SIZE);
+    assert!(policy.required_level.is_none());
+    assert!(policy.required_claims.is_empty());
+    assert!(policy.at.is_none());
+}
+
+#[test]
+fn issuer_audience_size_and_lifetime_are_enforced() {
+    let (encoded, trusted) = issue(None);
+    
+    let untrusted = verify_proof_envelope(&encoded, &TrustedKeys::new(), &VerificationPolicy::new(AUDIENCE));
+    assert!(matches!(untrusted, Err(ComplianceError::InvalidProof { .. })));
+    
+    let elsewhere = verify_proof_envelope(&encoded, &trusted, &VerificationPolicy::new("other.example"));
+    assert!(matches!(elsewhere, Err(ComplianceError::InvalidProof { .. })));
+    
+    let small = VerificationPolicy {
+        max_proof_size: 63,
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    assert!(verify_proof_envelope(&encoded, &trusted, &small).is_err());
+    
+    let later = VerificationPolicy {
+        at: Some(Utc::now() + Duration::hours(2)),
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    assert!(verify_proof_envelope(&encoded, &trusted, &later).is_err());
+    
+    assert!(verify_proof_envelope("garbage", &trusted, &VerificationPolicy::new(AUDIENCE)).is_err());
+}
+
+#[test]
+fn the_intended_use_must_fall_within_the_scope() {
+    let (encoded, trusted) = issue(Some(stablecoin_scope()));
+    let policy = |application: &str, amount: u64| VerificationPolicy {
+        usage: ScopeUsage {
+            application: Some(application.to_string()),
+            asset_class: Some(AssetClass::Stablecoin),
+            amount: Some(amount),
+        },
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    
+    let verified = verify_proof_envelope(&encoded, &trusted, &policy("dex", 10_000)).unwrap();
+    assert_eq!(verified.scope, Some(stablecoin_scope()));
+    
+    assert!(invalid_reason(verify_proof_envelope(&encoded, &trusted, &policy("lending", 5))).contains("application"));
+    assert!(invalid_reason(verify_proof_envelope(&encoded, &trusted, &policy("dex", 10_001))).contains("outside"));
+    
+    let unstated = VerificationPolicy::new(AUDIENCE);
+    assert!(verify_proof_envelope(&encoded, &trusted, &unstated).is_err());
+}
+
+#[test]
+fn a_required_level_must_be_attested_by_the_scope() {
+    let usage = ScopeUsage {
+        application: Some("dex".to_string()),
+        asset_class: Some(AssetClass::Stablecoin),
+        amount: Some(1),
+    };
+    let requiring = |level| VerificationPolicy {
+        usage: usage.clone(),
+        required_level: Some(level),
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    let (scoped, trusted) = issue(Some(stablecoin_scope()));
+    
+    assert!(verify_proof_envelope(&scoped, &trusted, &requiring(ComplianceLevel::Basic)).is_ok());
+    assert!(verify_proof_envelope(&scoped, &trusted, &requiring(ComplianceLevel::Standard)).is_ok());
+    let enhanced = verify_proof_envelope(&scoped, &trusted, &requiring(ComplianceLevel::Enhanced));
+    assert!(invalid_reason(enhanced).contains("Enhanced"));
+    
+    let (unscoped, trusted) = issue(None);
+    assert!(verify_proof_envelope(&unscoped, &trusted, &requiring(ComplianceLevel::Basic)).is_err());
+}
@@ -0,0 +1,106 @@
+//! Mandatory expiry of time-bounded manual overrides
+
+use chrono::{DateTime, Duration, Utc};
+use compliance_backend::compliance::approvals::{ApprovalService, ApprovalStatus, OverrideAction};
+use compliance_backend::types::{AccountId, AmlRiskLevel};
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn clearance(n: u32) -> OverrideAction {
+    OverrideAction::SanctionsClearance { account_id: account(n) }
+}
+
+fn max() -> Duration {
+    Duration::days(30)
+}
+
+#[test]
+fn only_revocation_reversals_are_open_ended() {
+    assert!(clearance(1).is_time_bounded());
+    assert!(OverrideAction::RiskDowngrade {
+        account_id: account(1),
+        aml_risk_level: AmlRiskLevel::Low,
+    }
+    .is_time_bounded());
+    assert!(!OverrideAction::RevocationReversal { account_id: account(1) }.is_time_bounded());
+}
+
+#[tokio::test]
+async fn expiry_and_prior_risk_level_are_kept_on_the_request() {
+    let approvals = ApprovalService::new();
+    let expires_at = Utc::now() + Duration::days(7);
+    let downgrade = OverrideAction::RiskDowngrade {
+        account_id: account(1),
+        aml_risk_level: AmlRiskLevel::Low,
+    };
+    
+    let request = approvals
+        .propose(downgrade, "reviewed", "alice", Some(expires_at), Some(AmlRiskLevel::High), max())
+        .await
+        .unwrap();
+    let approved = approvals.approve(request.id, "bob", None).await.unwrap();
+    
+    assert_eq!(approved.expires_at, Some(expires_at));
+    assert_eq!(approved.prior_risk_level, Some(AmlRiskLevel::High));
+    assert_eq!(approved.reverted_at, None);
+}
+
+#[tokio::test]
+async fn expiry_just_inside_the_maximum_duration_is_accepted() {
+    let approvals = ApprovalService::new();
+    let at_limit = Utc::now() + max() - Duration::seconds(1);
+    
+    approvals.propose(clearance(1), "reason", "alice", Some(at_limit), None, max()).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_approved_overrides_are_due() {
+    let approvals = ApprovalService::new();
+    let expires_at = Some(Utc::now() + Duration::days(1));
+    let later: DateTime<Utc> = Utc::now() + Duration::days(2);
+    
+    let pending = approvals.propose(clearance(1), "reason", "alice", expires_at, None, max()).await.unwrap();
+    let rejected = approvals.propose(clearance(2), "reason", "alice", expires_at, None, max()).await.unwrap();
+    let failed = approvals.propose(clearance(3), "reason", "alice", expires_at, None, max()).await.unwrap();
+    let approved = approvals.propose(clearance(4), "reason", "alice", expires_at, None, max()).await.unwrap();
+    
+    approvals.reject(rejected.id, "bob", None).await.unwrap();
+    approvals.approve(failed.id, "bob", None).await.unwrap();
+    approvals.mark_failed(failed.id, "provider unavailable").await.unwrap();
+    approvals.approve(approved.id, "bob", None).await.unwrap();
+    
+    let due = approvals.due_for_revert(later).await.unwrap();
+    assert_eq!(due.iter().map(|r| r.id).collect::<Vec<_>>(), vec![approved.id]);
+    assert_eq!(approvals.get(pending.id).await.unwrap().status, ApprovalStatus::Pending);
+}
+
+#[tokio::test]
+async fn revocation_reversals_are_never_due() {
+    let approvals = ApprovalService::new();
+    let reversal = OverrideAction::RevocationReversal { account_id: account(1) };
+    let request = approvals.propose(reversal, "reason", "alice", None, None, max()).await.unwrap();
+    approvals.approve(request.id, "bob", None).await.unwrap();
+    
+    assert!(approvals.due_for_revert(Utc::now() + Duration::days(365)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reverted_overrides_record_when_and_are_not_due_again() {
+    let approvals = ApprovalService::new();
+    let expires_at = Some(Utc::now() + Duration::days(1));
+    let later = Utc::now() + Duration::days(2);
+    let request = approvals.propose(clearance(1), "reason", "alice", expires_at, None, max()).await.unwrap();
+    approvals.approve(request.id, "bob", None).await.unwrap();
+    
+    approvals.mark_reverted(request.id).await.unwrap();
+    
+    let reverted = approvals.get(request.id).await.unwrap();
+    assert!(reverted.reverted_at.is_some());
+    assert_eq!(reverted.status, ApprovalStatus::Approved);
+    assert!(approvals.due_for_revert(later).await.unwrap().is_empty());
+    
+    // A new clearance for the same account can be proposed once the old one lapsed
+    approvals.propose(clearance(1), "new review", "alice", expires_at, None, max()).await.unwrap();
+}
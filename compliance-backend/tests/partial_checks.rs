@@ -0,0 +1,104 @@
+//! Optional checks that fail without failing the compliance check
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::compliance::breaker::{Provider, ProviderBreakers, QueuedRetry};
+use compliance_backend::compliance::provider_credentials::with_client;
+use compliance_backend::config::{CheckCriticality, Config, ProviderResilienceConfig};
+use compliance_backend::crypto::attestation_commitment;
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceAttestation, DataRegion};
+use uuid::Uuid;
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+fn attestation(partial: &[&str]) -> ComplianceAttestation {
+    ComplianceAttestation {
+        partial: partial.iter().map(|check| check.to_string()).collect(),
+        ..common::attestation(&account(), Utc::now(), Duration::days(90))
+    }
+}
+
+#[test]
+fn complete_attestations_serialize_without_the_flag() {
+    let complete = serde_json::to_value(attestation(&[])).unwrap();
+    assert!(complete.get("partial").is_none());
+    let parsed: ComplianceAttestation = serde_json::from_value(complete).unwrap();
+    assert!(parsed.partial.is_empty());
+    
+    let partial = serde_json::to_value(attestation(&["chain_analytics"])).unwrap();
+    assert_eq!(partial["partial"], serde_json::json!(["chain_analytics"]));
+}
+
+#[test]
+fn partial_checks_are_committed_to() {
+    let partial = attestation(&["chain_analytics"]);
+    let complete = ComplianceAttestation {
+        partial: Vec::new(),
+        ..partial.clone()
+    };
+    assert_ne!(attestation_commitment(&partial), attestation_commitment(&complete));
+}
+
+#[tokio::test]
+async fn completed_checks_supersede_the_partial_attestation() {
+    let events = AttestationEventStore::new();
+    let partial = attestation(&["chain_analytics"]);
+    let completed = ComplianceAttestation {
+        id: Uuid::new_v4(),
+        aml_risk_level: AmlRiskLevel::High,
+        partial: Vec::new(),
+        ..partial.clone()
+    };
+    events.append(&account(), AttestationEvent::AttestationIssued { attestation: partial.clone() }).await.unwrap();
+    events.append(&account(), AttestationEvent::AttestationIssued { attestation: completed.clone() }).await.unwrap();
+    
+    let state = events.project(&account(), None).await.unwrap().unwrap();
+    assert_eq!(state.attestation.id, completed.id);
+    assert!(state.attestation.partial.is_empty());
+    assert_eq!(state.attestation.aml_risk_level, AmlRiskLevel::High);
+}
+
+#[tokio::test]
+async fn retries_run_on_behalf_of_the_queuing_client() {
+    let breakers = ProviderBreakers::new(&ProviderResilienceConfig::default());
+    let client_id = Uuid::new_v4();
+    with_client(client_id, Some(DataRegion::Eu), async {
+        breakers.queue_retry(&account(), Provider::ChainAnalytics);
+        breakers.queue_retry(&account(), Provider::Kyc);
+    })
+    .await;
+    
+    assert_eq!(breakers.retry_queue_depth(), 1);
+    assert_eq!(
+        breakers.take_ready_retries(),
+        vec![QueuedRetry {
+            account_id: account(),
+            provider: Provider::ChainAnalytics,
+            client: Some((client_id, Some(DataRegion::Eu))),
+        }]
+    );
+    assert_eq!(breakers.retry_queue_depth(), 0);
+    
+    breakers.queue_retry(&account(), Provider::Kyc);
+    assert_eq!(breakers.take_ready_retries()[0].client, None);
+}
+
+#[test]
+fn checks_attestations_are_issued_from_must_be_critical() {
+    let defaults = ProviderResilienceConfig::default();
+    assert_eq!(defaults.kyc.criticality, CheckCriticality::Critical);
+    assert_eq!(defaults.adverse_media.criticality, CheckCriticality::Critical);
+    
+    let mut config = Config::default();
+    config.compliance.providers.chain_analytics.criticality = CheckCriticality::Optional;
+    config.compliance.providers.aml.criticality = CheckCriticality::Optional;
+    config.compliance.providers.adverse_media.criticality = CheckCriticality::Optional;
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("compliance.providers.aml.criticality"));
+    assert!(violations.contains("compliance.providers.adverse_media.criticality"));
+    assert!(!violations.contains("compliance.providers.chain_analytics.criticality"));
+}
@@ -0,0 +1,153 @@
+//! QR code and deep link presentations of proof envelopes
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::{Clock, MockClock};
+use compliance_backend::compliance::presentation::{Presentation, PresentationMode};
+use compliance_backend::compliance::presentation_store::PresentationStore;
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+
+const PUBLIC_URL: &str = "https://compliance.example.com";
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+fn presentation_store(public_url: Option<&str>, max_inline_bytes: usize) -> (PresentationStore, Arc<MockClock>) {
+    let mut config = ComplianceConfig::default();
+    config.presentations.public_url = public_url.map(str::to_string);
+    config.presentations.max_inline_bytes = max_inline_bytes;
+    config.presentations.reference_ttl_secs = 300;
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    let store = PresentationStore::new(Arc::new(LiveConfig::new(config))).with_clock(clock.clone());
+    (store, clock)
+}
+
+fn token_of(uri: &str) -> String {
+    match Presentation::parse(uri).unwrap() {
+        Presentation::Reference { token, .. } => token,
+        other => panic!("expected a reference presentation, got {:?}", other),
+    }
+}
+
+#[test]
+fn presentations_round_trip_through_their_uri() {
+    let inline = Presentation::Inline {
+        envelope: "eyJ2IjoxfQ".to_string(),
+    };
+    assert_eq!(inline.to_uri(), "zerotrust:present?v=1&e=eyJ2IjoxfQ");
+    assert_eq!(Presentation::parse(&inline.to_uri()).unwrap(), inline);
+    
+    let reference = Presentation::Reference {
+        url: "https://example.com/v1/presentations/abc?x=1&y=2".to_string(),
+        token: "t0k+en/=".to_string(),
+    };
+    let uri = reference.to_uri();
+    assert!(uri.contains("u=https://example.com/v1/presentations/abc%3Fx%3D1%26y%3D2"), "{}", uri);
+    assert_eq!(Presentation::parse(&uri).unwrap(), reference);
+    assert_eq!(Presentation::parse(&uri).unwrap().mode(), PresentationMode::Reference);
+}
+
+#[test]
+fn malformed_uris_are_rejected() {
+    for uri in [
+        "https://example.com/?v=1&e=abc",
+        "zerotrust:present?v=2&e=abc",
+        "zerotrust:present?e=abc",
+        "zerotrust:present?v=1",
+        "zerotrust:present?v=1&e=abc&u=https://example.com&t=x",
+        "zerotrust:present?v=1&u=https://example.com",
+        "zerotrust:present?v=1&e=abc&e=def",
+        "zerotrust:present?v=1&e=%zz",
+    ] {
+        let error = Presentation::parse(uri).unwrap_err();
+        assert_eq!(error.code(), "invalid_proof", "{}", uri);
+    }
+}
+
+#[test]
+fn unknown_parameters_are_ignored() {
+    let presentation = Presentation::parse("zerotrust:present?label=Acme&v=1&e=abc").unwrap();
+    assert_eq!(
+        presentation,
+        Presentation::Inline {
+            envelope: "abc".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn small_envelopes_are_carried_inline() {
+    let (store, _) = presentation_store(Some(PUBLIC_URL), 2048);
+    let issued = store.present(&account(), "abc".to_string(), None, PresentationMode::Auto).await.unwrap();
+    
+    assert_eq!(issued.mode, PresentationMode::Inline);
+    assert_eq!(issued.presentation_id, None);
+    assert_eq!(store.resolve(Presentation::parse(&issued.uri).unwrap()).await.unwrap(), "abc");
+}
+
+#[tokio::test]
+async fn large_envelopes_are_presented_by_reference() {
+    let (store, clock) = presentation_store(Some(PUBLIC_URL), 64);
+    let envelope = "e".repeat(512);
+    let issued = store.present(&account(), envelope.clone(), None, PresentationMode::Auto).await.unwrap();
+    
+    assert_eq!(issued.mode, PresentationMode::Reference);
+    assert!(issued.uri.len() <= 2048);
+    assert_eq!(issued.expires_at, Some(clock.now() + Duration::seconds(300)));
+    assert_eq!(store.resolve(Presentation::parse(&issued.uri).unwrap()).await.unwrap(), envelope);
+}
+
+#[tokio::test]
+async fn references_need_a_public_url() {
+    let (store, _) = presentation_store(None, 64);
+    let error = store.present(&account(), "e".repeat(512), None, PresentationMode::Auto).await.unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+    
+    let (store, _) = presentation_store(Some(PUBLIC_URL), 64);
+    let error = store.present(&account(), "e".repeat(512), None, PresentationMode::Inline).await.unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+}
+
+#[tokio::test]
+async fn tokens_retrieve_once() {
+    let (store, _) = presentation_store(Some(PUBLIC_URL), 2048);
+    let issued = store.present(&account(), "abc".to_string(), None, PresentationMode::Reference).await.unwrap();
+    let id = issued.presentation_id.unwrap();
+    let token = token_of(&issued.uri);
+    
+    let error = store.retrieve(id, "wrong").await.unwrap_err();
+    assert_eq!(error.code(), "presentation_not_found");
+    
+    let (account_id, envelope) = store.retrieve(id, &token).await.unwrap();
+    assert_eq!(account_id, account());
+    assert_eq!(envelope, "abc");
+    assert_eq!(store.retrieve(id, &token).await.unwrap_err().code(), "presentation_not_found");
+}
+
+#[tokio::test]
+async fn references_expire_with_the_envelope_or_the_window() {
+    let (store, clock) = presentation_store(Some(PUBLIC_URL), 2048);
+    let envelope_expiry = clock.now() + Duration::seconds(60);
+    let issued = store
+        .present(&account(), "abc".to_string(), Some(envelope_expiry), PresentationMode::Reference)
+        .await
+        .unwrap();
+    assert_eq!(issued.expires_at, Some(envelope_expiry));
+    
+    clock.advance(Duration::seconds(61));
+    let error = store.retrieve(issued.presentation_id.unwrap(), &token_of(&issued.uri)).await.unwrap_err();
+    assert_eq!(error.code(), "presentation_not_found");
+}
+
+#[tokio::test]
+async fn foreign_references_are_not_followed() {
+    let (store, _) = presentation_store(Some(PUBLIC_URL), 2048);
+    let foreign = Presentation::Reference {
+        url: format!("https://attacker.example/v1/presentations/{}", uuid::Uuid::new_v4()),
+        token: "token".to_string(),
+    };
+    assert_eq!(store.resolve(foreign).await.unwrap_err().code(), "validation_error");
+}
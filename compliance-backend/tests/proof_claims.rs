@@ -0,0 +1,152 @@
+//! Claim-level commitments and proof compression in proof envelopes
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::claims::{Claim, ClaimKind, ClaimSet, JurisdictionClass, PepStatus};
+use compliance_backend::compliance::proof_envelope::{EnvelopeParams, ProofCompression, ProofEnvelope, ProofFormat};
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceLevel};
+use compliance_backend::verifier::{verify_proof_envelope, VerificationPolicy};
+use compliance_backend::ComplianceError;
+
+const AUDIENCE: &str = "verifier.example";
+
+fn claims() -> ClaimSet {
+    ClaimSet::new(vec![
+        Claim::AttestationAge(3_600),
+        Claim::KycTier(Some(ComplianceLevel::Enhanced)),
+        Claim::RiskBand(AmlRiskLevel::Low),
+        Claim::PepStatus(PepStatus::Clear),
+        Claim::JurisdictionClass(JurisdictionClass::Standard),
+    ])
+    .unwrap()
+}
+
+fn seal(claims: &ClaimSet, disclose: &[ClaimKind]) -> (ProofEnvelope, TrustedKeys) {
+    seal_proof(claims, disclose, vec![0u8; 64], ProofCompression::None)
+}
+
+fn seal_proof(
+    claims: &ClaimSet,
+    disclose: &[ClaimKind],
+    proof: Vec<u8>,
+    compression: ProofCompression,
+) -> (ProofEnvelope, TrustedKeys) {
+    let signer = AttestationSigner::generate("test");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert(signer.key_id(), signer.verifying_key());
+    let account_id = AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap();
+    let envelope = ProofEnvelope::seal(
+        EnvelopeParams {
+            attestation: &common::attestation(&account_id, Utc::now(), Duration::days(30)),
+            claims,
+            disclose,
+            audience: AUDIENCE,
+            nonce: "00",
+            scope: None,
+            proof,
+            compression,
+            validity: Duration::hours(1),
+        },
+        &signer,
+    )
+    .unwrap();
+    (envelope, trusted)
+}
+
+#[test]
+fn claim_set_needs_one_claim_of_every_kind() {
+    assert!(ClaimSet::new(vec![Claim::RiskBand(AmlRiskLevel::Low)]).is_err());
+    
+    let set = claims();
+    assert_eq!(set.get(ClaimKind::PepStatus), &Claim::PepStatus(PepStatus::Clear));
+}
+
+#[test]
+fn disclosed_claims_verify_against_the_root_in_tree_order() {
+    let set = claims();
+    let disclosed = set
+        .disclose(&[ClaimKind::AttestationAge, ClaimKind::RiskBand, ClaimKind::RiskBand])
+        .unwrap();
+    
+    let kinds: Vec<_> = disclosed.iter().map(|d| d.claim.kind()).collect();
+    assert_eq!(kinds, [ClaimKind::RiskBand, ClaimKind::AttestationAge]);
+    for claim in &disclosed {
+        claim.verify(&set.root()).unwrap();
+    }
+}
+
+#[test]
+fn altered_claims_do_not_verify() {
+    let set = claims();
+    let mut disclosed = set.disclose(&[ClaimKind::RiskBand]).unwrap().remove(0);
+    disclosed.claim = Claim::RiskBand(AmlRiskLevel::Medium);
+    
+    assert!(matches!(disclosed.verify(&set.root()), Err(ComplianceError::InvalidProof { .. })));
+}
+
+#[test]
+fn salts_hide_equal_claims_across_envelopes() {
+    assert_ne!(claims().root(), claims().root());
+}
+
+#[test]
+fn envelope_discloses_only_the_requested_claims() {
+    let (envelope, trusted) = seal(&claims(), &[ClaimKind::KycTier, ClaimKind::PepStatus]);
+    let policy = VerificationPolicy {
+        required_claims: vec![ClaimKind::PepStatus],
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    
+    let verified = verify_proof_envelope(&envelope.encode().unwrap(), &trusted, &policy).unwrap();
+    assert_eq!(
+        verified.claims,
+        [Claim::KycTier(Some(ComplianceLevel::Enhanced)), Claim::PepStatus(PepStatus::Clear)]
+    );
+}
+
+#[test]
+fn verifier_rejects_envelopes_missing_required_claims() {
+    let (envelope, trusted) = seal(&claims(), &[ClaimKind::KycTier]);
+    let policy = VerificationPolicy {
+        required_claims: vec![ClaimKind::JurisdictionClass],
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    
+    let result = verify_proof_envelope(&envelope.encode().unwrap(), &trusted, &policy);
+    assert!(matches!(result, Err(ComplianceError::InvalidProof { .. })));
+}
+
+#[test]
+fn envelope_with_a_claim_from_another_tree_is_rejected() {
+    let (mut envelope, trusted) = seal(&claims(), &[ClaimKind::RiskBand]);
+    envelope.claims = claims().disclose(&[ClaimKind::RiskBand]).unwrap();
+    
+    let result = envelope.validate(&trusted, AUDIENCE, usize::MAX, Utc::now());
+    assert!(matches!(result, Err(ComplianceError::InvalidProof { .. })));
+}
+
+#[test]
+fn compressed_proofs_are_smaller_and_inflate_for_the_verifier() {
+    let proof = b"0a1b2c3d".repeat(4096);
+    let (envelope, trusted) = seal_proof(&claims(), &[], proof.clone(), ProofCompression::Deflate);
+    assert_eq!(envelope.format, ProofFormat::MidenStarkDeflate);
+    assert!(envelope.proof_bytes.len() < proof.len());
+    
+    // The size limit applies to the proof as embedded
+    let policy = VerificationPolicy {
+        max_proof_size: envelope.proof_bytes.len(),
+        ..VerificationPolicy::new(AUDIENCE)
+    };
+    let verified = verify_proof_envelope(&envelope.encode().unwrap(), &trusted, &policy).unwrap();
+    assert_eq!(verified.proof_bytes, proof);
+}
+
+#[test]
+fn compressed_proofs_that_do_not_inflate_are_rejected() {
+    let (mut envelope, _) = seal_proof(&claims(), &[], vec![7u8; 256], ProofCompression::Deflate);
+    envelope.proof_bytes = vec![0xff; 16];
+    
+    assert!(matches!(envelope.proof(), Err(ComplianceError::InvalidProof { .. })));
+}
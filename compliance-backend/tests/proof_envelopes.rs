@@ -0,0 +1,150 @@
+//! Canonical encoding and strict parsing of proof envelopes
+
+mod common;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use ciborium::Value;
+use compliance_backend::compliance::claims::{Claim, ClaimSet, JurisdictionClass, PepStatus};
+use compliance_backend::compliance::proof_envelope::{EnvelopeParams, ProofCompression, ProofEnvelope};
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceLevel};
+use compliance_backend::ComplianceError;
+
+const AUDIENCE: &str = "verifier.example";
+
+fn seal() -> (ProofEnvelope, TrustedKeys) {
+    let claims = ClaimSet::new(vec![
+        Claim::AttestationAge(3_600),
+        Claim::KycTier(Some(ComplianceLevel::Standard)),
+        Claim::RiskBand(AmlRiskLevel::Low),
+        Claim::PepStatus(PepStatus::Clear),
+        Claim::JurisdictionClass(JurisdictionClass::Standard),
+    ])
+    .unwrap();
+    let signer = AttestationSigner::generate("test");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert(signer.key_id(), signer.verifying_key());
+    let account_id = AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap();
+    let envelope = ProofEnvelope::seal(
+        EnvelopeParams {
+            attestation: &common::attestation(&account_id, Utc::now(), Duration::days(30)),
+            claims: &claims,
+            disclose: &[],
+            audience: AUDIENCE,
+            nonce: "00",
+            scope: None,
+            proof: vec![0u8; 64],
+            compression: ProofCompression::None,
+            validity: Duration::hours(1),
+        },
+        &signer,
+    )
+    .unwrap();
+    (envelope, trusted)
+}
+
+/// Re-encode an envelope after editing the entries of its CBOR map
+fn reencode(envelope: &ProofEnvelope, edit: impl FnOnce(&mut Vec<(Value, Value)>)) -> String {
+    let bytes = URL_SAFE_NO_PAD.decode(envelope.encode().unwrap()).unwrap();
+    let mut value: Value = ciborium::de::from_reader(bytes.as_slice()).unwrap();
+    let Value::Map(entries) = &mut value else {
+        panic!("envelope is not encoded as a map");
+    };
+    edit(entries);
+    
+    let mut edited = Vec::new();
+    ciborium::ser::into_writer(&value, &mut edited).unwrap();
+    URL_SAFE_NO_PAD.encode(edited)
+}
+
+fn rejected(encoded: &str) -> String {
+    match ProofEnvelope::decode(encoded) {
+        Err(ComplianceError::InvalidProof { reason }) => reason,
+        other => panic!("expected an invalid proof, got {:?}", other),
+    }
+}
+
+#[test]
+fn envelopes_round_trip_through_the_canonical_encoding() {
+    let (envelope, trusted) = seal();
+    let encoded = envelope.encode().unwrap();
+    
+    let decoded = ProofEnvelope::decode(&encoded).unwrap();
+    assert_eq!(decoded, envelope);
+    assert_eq!(decoded.encode().unwrap(), encoded);
+    decoded.validate(&trusted, AUDIENCE, usize::MAX, Utc::now()).unwrap();
+}
+
+#[test]
+fn only_unpadded_base64url_is_accepted() {
+    let (envelope, _) = seal();
+    let encoded = envelope.encode().unwrap();
+    let standard = base64::engine::general_purpose::STANDARD.encode(URL_SAFE_NO_PAD.decode(&encoded).unwrap());
+    
+    assert!(rejected(&format!("{}=", encoded)).contains("base64url"));
+    if standard != encoded {
+        assert!(rejected(&standard).contains("base64url"));
+    }
+    assert!(rejected("not an envelope!").contains("base64url"));
+}
+
+#[test]
+fn bytes_that_are_not_an_envelope_are_rejected() {
+    let garbage = URL_SAFE_NO_PAD.encode([0xff, 0x00, 0x13]);
+    assert!(rejected(&garbage).contains("CBOR"));
+    
+    let (envelope, _) = seal();
+    let truncated = URL_SAFE_NO_PAD.encode(&URL_SAFE_NO_PAD.decode(envelope.encode().unwrap()).unwrap()[..40]);
+    assert!(rejected(&truncated).contains("CBOR"));
+}
+
+#[test]
+fn unknown_and_missing_fields_are_rejected() {
+    let (envelope, _) = seal();
+    
+    let extended = reencode(&envelope, |entries| {
+        entries.push((Value::Text("debug".to_string()), Value::Bool(true)));
+    });
+    assert!(rejected(&extended).contains("CBOR"));
+    
+    let stripped = reencode(&envelope, |entries| {
+        entries.retain(|(key, _)| key.as_text() != Some("audience"));
+    });
+    assert!(rejected(&stripped).contains("CBOR"));
+}
+
+#[test]
+fn other_envelope_versions_are_rejected() {
+    let (mut envelope, _) = seal();
+    envelope.version -= 1;
+    
+    assert!(rejected(&envelope.encode().unwrap()).contains("version"));
+}
+
+#[test]
+fn non_canonical_encodings_are_rejected() {
+    let (envelope, _) = seal();
+    let reordered = reencode(&envelope, |entries| entries.reverse());
+    
+    assert!(rejected(&reordered).contains("canonical"));
+}
+
+#[test]
+fn validation_binds_the_audience_lifetime_and_signature() {
+    let (envelope, trusted) = seal();
+    let now = Utc::now();
+    assert!(envelope.validate(&trusted, "other.example", usize::MAX, now).is_err());
+    assert!(envelope.validate(&trusted, AUDIENCE, usize::MAX, now + Duration::hours(2)).is_err());
+    assert!(envelope.validate(&trusted, AUDIENCE, 63, now).is_err());
+    assert!(envelope.validate(&TrustedKeys::new(), AUDIENCE, usize::MAX, now).is_err());
+    
+    let mut tampered = envelope.clone();
+    tampered.nonce = "01".to_string();
+    assert!(tampered.validate(&trusted, AUDIENCE, usize::MAX, now).is_err());
+    
+    let mut truncated = envelope;
+    truncated.signature.pop();
+    assert!(truncated.validate(&trusted, AUDIENCE, usize::MAX, now).is_err());
+}
@@ -0,0 +1,82 @@
+//! Proof hash encodings, validation, and comparison
+
+use compliance_backend::crypto::proof_hash::PROOF_HASH_LENGTH;
+use compliance_backend::crypto::ProofHash;
+use compliance_backend::ComplianceError;
+
+fn rejected(encoded: &str) -> String {
+    match ProofHash::parse(encoded) {
+        Err(ComplianceError::Validation { field, message }) => {
+            assert_eq!(field, "proof_hash");
+            message
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn hashes_compare_by_every_byte() {
+    let hash = ProofHash::of(b"proof");
+    assert_eq!(hash, ProofHash::of(b"proof"));
+    assert_ne!(hash, ProofHash::of(b"other proof"));
+    
+    for index in [0, PROOF_HASH_LENGTH / 2, PROOF_HASH_LENGTH - 1] {
+        let mut bytes = *hash.as_bytes();
+        bytes[index] ^= 1;
+        assert_ne!(hash, ProofHash::from_bytes(bytes).unwrap(), "byte {} ignored", index);
+    }
+    
+    assert!(hash.matches(b"proof"));
+    assert!(!hash.matches(b"proof "));
+}
+
+#[test]
+fn hex_and_base58_encodings_round_trip() {
+    let hash = ProofHash::of(b"proof");
+    
+    let hex = hash.to_hex();
+    assert_eq!(hex.len(), PROOF_HASH_LENGTH * 2);
+    assert_eq!(hex, hex.to_lowercase());
+    assert_eq!(ProofHash::parse(&hex).unwrap(), hash);
+    assert_eq!(ProofHash::parse(&hex.to_uppercase()).unwrap(), hash);
+    assert_eq!(hex.parse::<ProofHash>().unwrap(), hash);
+    
+    assert_eq!(ProofHash::parse(&hash.to_base58()).unwrap(), hash);
+    assert_eq!(hash.to_string(), hex);
+    assert_eq!(format!("{:?}", hash), format!("ProofHash({})", hex));
+}
+
+#[test]
+fn malformed_encodings_are_rejected() {
+    let hex = ProofHash::of(b"proof").to_hex();
+    
+    assert!(rejected(&"0".repeat(62)).contains("base58"));
+    assert!(rejected(&format!("{}zz", &hex[..62])).contains("hex"));
+    assert!(rejected("0OIl").contains("base58"));
+    assert!(rejected(&bs58::encode([7u8; 31]).into_string()).contains("32 bytes"));
+    assert!(rejected(&bs58::encode([7u8; 33]).into_string()).contains("32 bytes"));
+    assert!(rejected("").contains("32 bytes"));
+}
+
+#[test]
+fn the_all_zero_placeholder_is_rejected() {
+    assert!(rejected(&"0".repeat(PROOF_HASH_LENGTH * 2)).contains("all zeros"));
+    assert!(rejected(&bs58::encode([0u8; PROOF_HASH_LENGTH]).into_string()).contains("all zeros"));
+    assert!(ProofHash::from_bytes([0; PROOF_HASH_LENGTH]).is_err());
+}
+
+#[test]
+fn serde_uses_lowercase_hex_and_validates() {
+    let hash = ProofHash::of(b"proof");
+    let json = serde_json::to_string(&hash).unwrap();
+    assert_eq!(json, format!("\"{}\"", hash.to_hex()));
+    assert_eq!(serde_json::from_str::<ProofHash>(&json).unwrap(), hash);
+    
+    let base58 = format!("\"{}\"", hash.to_base58());
+    assert_eq!(serde_json::from_str::<ProofHash>(&base58).unwrap(), hash);
+    
+    let zeros = format!("\"{}\"", "0".repeat(PROOF_HASH_LENGTH * 2));
+    assert!(serde_json::from_str::<ProofHash>(&zeros).is_err());
+    assert!(serde_json::from_str::<ProofHash>("\"abc\"").is_err());
+    assert!(serde_json::from_str::<ProofHash>("42").is_err());
+}
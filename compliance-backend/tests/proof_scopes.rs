@@ -0,0 +1,201 @@
+//! Proofs scoped to an application, asset class, or amount band
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::challenges::ChallengeService;
+use compliance_backend::compliance::claims::{Claim, ClaimSet, JurisdictionClass, PepStatus};
+use compliance_backend::compliance::proof_envelope::{EnvelopeParams, ProofCompression, ProofEnvelope};
+use compliance_backend::compliance::scope::{AmountBand, AssetClass, ProofScope, ScopeUsage};
+use compliance_backend::config::AttestationConfig;
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceLevel};
+use compliance_backend::ComplianceError;
+
+const AUDIENCE: &str = "dex.example";
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn band(min: u64, max: u64) -> Option<AmountBand> {
+    Some(AmountBand { min, max })
+}
+
+fn usage(application: Option<&str>, asset_class: Option<AssetClass>, amount: Option<u64>) -> ScopeUsage {
+    ScopeUsage {
+        application: application.map(str::to_string),
+        asset_class,
+        amount,
+    }
+}
+
+/// Level required by a test schedule: Enhanced above 10k, Standard above 1k
+fn level_for_amount(amount: u64) -> ComplianceLevel {
+    match amount {
+        0..=1_000 => ComplianceLevel::Basic,
+        1_001..=10_000 => ComplianceLevel::Standard,
+        _ => ComplianceLevel::Enhanced,
+    }
+}
+
+#[test]
+fn scopes_are_validated() {
+    assert!(ProofScope::default().validate().is_ok());
+    
+    for application in ["  ", &"a".repeat(129)] {
+        let scope = ProofScope {
+            application: Some(application.to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(scope.validate(), Err(ComplianceError::Validation { .. })));
+    }
+    
+    let inverted = ProofScope {
+        amount_band: band(500, 100),
+        ..Default::default()
+    };
+    assert!(inverted.validate().is_err());
+    let single = ProofScope {
+        amount_band: band(100, 100),
+        ..Default::default()
+    };
+    assert!(single.validate().is_ok());
+}
+
+#[test]
+fn the_strictest_requirement_sets_the_required_level() {
+    let classes = AttestationConfig::default().asset_class_levels;
+    assert_eq!(
+        ProofScope::default().required_level(&classes, level_for_amount),
+        ComplianceLevel::Basic
+    );
+    
+    let security = ProofScope {
+        asset_class: Some(AssetClass::TokenizedSecurity),
+        amount_band: band(0, 5_000),
+        ..Default::default()
+    };
+    assert_eq!(security.required_level(&classes, level_for_amount), ComplianceLevel::Enhanced);
+    
+    let stablecoin = ProofScope {
+        asset_class: Some(AssetClass::Stablecoin),
+        amount_band: band(0, 5_000),
+        ..Default::default()
+    };
+    assert_eq!(stablecoin.required_level(&classes, level_for_amount), ComplianceLevel::Standard);
+    
+    let explicit = ProofScope {
+        min_level: Some(ComplianceLevel::InstitutionalGrade),
+        amount_band: band(0, 50_000),
+        ..Default::default()
+    };
+    assert_eq!(
+        explicit.required_level(&classes, level_for_amount),
+        ComplianceLevel::InstitutionalGrade
+    );
+}
+
+#[test]
+fn usage_must_fall_within_every_restriction() {
+    let scope = ProofScope {
+        application: Some("dex".to_string()),
+        asset_class: Some(AssetClass::Stablecoin),
+        amount_band: band(100, 1_000),
+        min_level: None,
+    };
+    
+    assert!(scope.permits(&usage(Some("dex"), Some(AssetClass::Stablecoin), Some(100))).is_ok());
+    assert!(scope.permits(&usage(Some("dex"), Some(AssetClass::Stablecoin), Some(1_000))).is_ok());
+    
+    let wrong_app = scope.permits(&usage(Some("lending"), Some(AssetClass::Stablecoin), Some(500)));
+    assert!(wrong_app.unwrap_err().contains("application dex"));
+    let wrong_class = scope.permits(&usage(Some("dex"), Some(AssetClass::Nft), Some(500)));
+    assert!(wrong_class.unwrap_err().contains("asset class"));
+    let outside = scope.permits(&usage(Some("dex"), Some(AssetClass::Stablecoin), Some(1_001)));
+    assert!(outside.unwrap_err().contains("outside"));
+    let missing = scope.permits(&usage(Some("dex"), Some(AssetClass::Stablecoin), None));
+    assert!(missing.unwrap_err().contains("amount is required"));
+    
+    assert!(ProofScope::default().permits(&usage(None, None, None)).is_ok());
+}
+
+#[test]
+fn scopes_serialize_only_their_restrictions() {
+    let scope = ProofScope {
+        asset_class: Some(AssetClass::TokenizedSecurity),
+        amount_band: band(1, 2),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(&scope).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"asset_class": "tokenized_security", "amount_band": {"min": 1, "max": 2}})
+    );
+    assert_eq!(serde_json::from_value::<ProofScope>(json).unwrap(), scope);
+    
+    let unknown = serde_json::json!({"application": "dex", "region": "eu"});
+    assert!(serde_json::from_value::<ProofScope>(unknown).is_err());
+}
+
+#[tokio::test]
+async fn challenges_carry_a_validated_scope() {
+    let service = ChallengeService::new(300);
+    let scope = ProofScope {
+        application: Some("dex".to_string()),
+        ..Default::default()
+    };
+    
+    let challenge = service.issue(AUDIENCE, &account(1), Some(scope.clone()), vec![], None).await.unwrap();
+    assert_eq!(challenge.scope, Some(scope));
+    
+    let invalid = ProofScope {
+        amount_band: band(2, 1),
+        ..Default::default()
+    };
+    assert!(service.issue(AUDIENCE, &account(1), Some(invalid), vec![], None).await.is_err());
+}
+
+#[test]
+fn the_scope_is_signed_into_the_envelope() {
+    let signer = AttestationSigner::generate("test");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert(signer.key_id(), signer.verifying_key());
+    let account_id = account(1);
+    let attestation = common::attestation(&account_id, Utc::now(), Duration::days(30));
+    let claims = ClaimSet::new(vec![
+        Claim::AttestationAge(60),
+        Claim::KycTier(Some(ComplianceLevel::Standard)),
+        Claim::RiskBand(AmlRiskLevel::Low),
+        Claim::PepStatus(PepStatus::Clear),
+        Claim::JurisdictionClass(JurisdictionClass::Standard),
+    ])
+    .unwrap();
+    let scope = ProofScope {
+        application: Some("dex".to_string()),
+        ..Default::default()
+    };
+    
+    let envelope = ProofEnvelope::seal(
+        EnvelopeParams {
+            attestation: &attestation,
+            claims: &claims,
+            disclose: &[],
+            audience: AUDIENCE,
+            nonce: "00",
+            scope: Some(scope.clone()),
+            proof: vec![0u8; 64],
+            compression: ProofCompression::None,
+            validity: Duration::hours(1),
+        },
+        &signer,
+    )
+    .unwrap();
+    let decoded = ProofEnvelope::decode(&envelope.encode().unwrap()).unwrap();
+    assert_eq!(decoded.scope, Some(scope));
+    decoded.validate(&trusted, AUDIENCE, usize::MAX, Utc::now()).unwrap();
+    
+    let mut widened = decoded;
+    widened.scope = None;
+    assert!(widened.validate(&trusted, AUDIENCE, usize::MAX, Utc::now()).is_err());
+}
@@ -0,0 +1,107 @@
+//! Provider callback signatures, storage, and payloads
+
+use compliance_backend::compliance::breaker::Provider;
+use compliance_backend::compliance::provider_callbacks::{
+    verify_signature, CallbackPayload, CallbackStatus, CallbackStore, VerificationOutcome,
+};
+use compliance_backend::compliance::rejection::RejectionReason;
+use compliance_backend::config::CallbackVerificationConfig;
+use compliance_backend::ComplianceError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+const BODY: &[u8] = concat!(
+    r#"{"event_type":"verification.completed","#,
+    r#""account_id":"0x0123456789abcdef0123456789abcd","outcome":"approved"}"#
+)
+.as_bytes();
+
+fn config() -> CallbackVerificationConfig {
+    CallbackVerificationConfig {
+        secret: "callback secret".to_string(),
+        signature_header: "x-signature".to_string(),
+    }
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn rejected(signature: Option<&str>, body: &[u8]) -> String {
+    match verify_signature(&config(), signature, body) {
+        Err(ComplianceError::InvalidCallbackSignature { reason }) => reason,
+        other => panic!("expected an invalid signature, got {:?}", other),
+    }
+}
+
+#[test]
+fn signatures_over_the_raw_body_verify() {
+    let signature = sign("callback secret", BODY);
+    verify_signature(&config(), Some(&signature), BODY).unwrap();
+    verify_signature(&config(), Some(&format!("sha256={}", signature)), BODY).unwrap();
+    verify_signature(&config(), Some(&format!(" {} ", signature.to_uppercase())), BODY).unwrap();
+}
+
+#[test]
+fn signatures_from_another_secret_or_body_are_rejected() {
+    let signature = sign("callback secret", BODY);
+    
+    assert_eq!(rejected(Some(&sign("other secret", BODY)), BODY), "signature does not match");
+    assert_eq!(rejected(Some(&signature), &BODY[1..]), "signature does not match");
+    assert_eq!(rejected(Some(&signature[..32]), BODY), "signature does not match");
+    assert_eq!(rejected(Some(""), BODY), "signature does not match");
+}
+
+#[test]
+fn missing_or_malformed_signatures_are_rejected() {
+    let signature = sign("callback secret", BODY);
+    
+    assert_eq!(rejected(None, BODY), "signature is missing or malformed");
+    assert_eq!(rejected(Some("not hex"), BODY), "signature is missing or malformed");
+    assert_eq!(rejected(Some(&signature[1..]), BODY), "signature is missing or malformed");
+}
+
+#[test]
+fn payloads_parse_with_optional_rejection_reasons() {
+    let payload: CallbackPayload = serde_json::from_slice(BODY).unwrap();
+    assert_eq!(payload.outcome, VerificationOutcome::Approved);
+    assert_eq!(payload.account_id, "0x0123456789abcdef0123456789abcd");
+    assert!(payload.reference.is_none());
+    assert!(payload.rejection_reasons.is_empty());
+    
+    let declined: CallbackPayload = serde_json::from_str(
+        r#"{"event_type":"verification.completed","account_id":"0x0123456789abcdef0123456789abcd",
+            "reference":"ref-1","outcome":"declined","rejection_reasons":["face_mismatch","blurry_scan"]}"#,
+    )
+    .unwrap();
+    assert_eq!(declined.rejection_reasons, vec![RejectionReason::FaceMismatch, RejectionReason::Other]);
+    
+    let invalid = r#"{"event_type":"verification.completed","account_id":"0x0123","outcome":"approved"}"#;
+    assert!(serde_json::from_str::<CallbackPayload>(invalid).is_err());
+}
+
+#[tokio::test]
+async fn callbacks_are_stored_raw_before_processing() {
+    let store = CallbackStore::new();
+    let body = String::from_utf8(BODY.to_vec()).unwrap();
+    let callback = store.record(Provider::Kyc, body.clone()).await;
+    
+    let stored = store.get(callback.id).await.unwrap();
+    assert_eq!(stored.body, body);
+    assert_eq!(stored.status, CallbackStatus::Received);
+    assert_eq!(stored.attempts, 0);
+    assert!(stored.account_id.is_none());
+    
+    store.record(Provider::Sanctions, "{}".to_string()).await;
+    assert_eq!(store.list(Some(CallbackStatus::Received), 10).await.len(), 2);
+    assert_eq!(store.list(None, 1).await.len(), 1);
+    assert!(store.list(Some(CallbackStatus::Failed), 10).await.is_empty());
+    
+    assert!(matches!(
+        store.get(Uuid::new_v4()).await,
+        Err(ComplianceError::ProviderCallbackNotFound { .. })
+    ));
+}
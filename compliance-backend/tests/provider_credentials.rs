@@ -0,0 +1,130 @@
+//! Business clients calling providers with their own sealed credentials
+
+use compliance_backend::compliance::breaker::Provider;
+use compliance_backend::compliance::provider_credentials::{
+    current_client, with_client, CredentialSource, ProviderCredentialStore, ProviderCredentials,
+};
+use compliance_backend::config::{ComplianceConfig, ProviderCredentialsConfig};
+use compliance_backend::ComplianceError;
+use uuid::Uuid;
+
+fn store() -> ProviderCredentialStore {
+    ProviderCredentialStore::new(Some([7u8; 32]))
+}
+
+fn config() -> ComplianceConfig {
+    let mut config = ComplianceConfig::default();
+    config.kyc.provider_endpoint = Some("https://kyc.global.example".to_string());
+    config.kyc.provider_api_key = Some("global-kyc-key".to_string());
+    config
+}
+
+#[tokio::test]
+async fn client_requests_use_the_clients_own_credentials() {
+    let store = store();
+    let (client_id, other) = (Uuid::new_v4(), Uuid::new_v4());
+    let endpoint = Some("https://kyc.acme.example".to_string());
+    store.set(client_id, Provider::Kyc, endpoint.clone(), "acme-secret-key").await.unwrap();
+    let config = config();
+    
+    let resolved = with_client(client_id, None, store.resolve_current(Provider::Kyc, &config)).await.unwrap();
+    assert_eq!(resolved.source, CredentialSource::Client(client_id));
+    assert_eq!(resolved.credentials.endpoint, endpoint);
+    assert_eq!(resolved.credentials.api_key.as_deref(), Some("acme-secret-key"));
+    
+    let unregistered = with_client(other, None, store.resolve_current(Provider::Kyc, &config)).await.unwrap();
+    assert_eq!(unregistered.source, CredentialSource::Global);
+    assert_eq!(unregistered.credentials.api_key.as_deref(), Some("global-kyc-key"));
+    
+    let elsewhere = with_client(client_id, None, store.resolve_current(Provider::Aml, &config)).await.unwrap();
+    assert_eq!(elsewhere.source, CredentialSource::Global);
+    
+    let outside = store.resolve_current(Provider::Kyc, &config).await.unwrap();
+    assert_eq!(outside.source, CredentialSource::Global);
+}
+
+#[tokio::test]
+async fn the_request_client_is_scoped_to_its_task() {
+    let client_id = Uuid::new_v4();
+    assert_eq!(current_client(), None);
+    assert_eq!(with_client(client_id, None, async { current_client() }).await, Some(client_id));
+    assert_eq!(current_client(), None);
+}
+
+#[tokio::test]
+async fn credentials_without_an_endpoint_use_the_global_endpoint() {
+    let store = store();
+    let client_id = Uuid::new_v4();
+    store.set(client_id, Provider::Kyc, None, "acme-secret-key").await.unwrap();
+    
+    let resolved = store.resolve(Some(client_id), None, Provider::Kyc, &config()).await.unwrap();
+    assert_eq!(resolved.credentials.endpoint.as_deref(), Some("https://kyc.global.example"));
+    assert_eq!(resolved.credentials.api_key.as_deref(), Some("acme-secret-key"));
+}
+
+#[tokio::test]
+async fn summaries_never_reveal_the_key() {
+    let store = store();
+    let client_id = Uuid::new_v4();
+    let first = store.set(client_id, Provider::Sanctions, None, "sanctions-key-1234").await.unwrap();
+    assert_eq!(first.api_key_hint, "1234");
+    
+    let replaced = store.set(client_id, Provider::Sanctions, None, "short").await.unwrap();
+    assert_eq!(replaced.api_key_hint, "");
+    assert_eq!(replaced.created_at, first.created_at);
+    assert!(replaced.updated_at >= first.updated_at);
+    
+    store.set(client_id, Provider::Aml, None, "aml-key-abcdefgh").await.unwrap();
+    store.set(Uuid::new_v4(), Provider::Kyc, None, "someone-else").await.unwrap();
+    let listed = store.list(client_id).await;
+    assert_eq!(listed.len(), 2);
+    assert!(listed.iter().all(|summary| summary.client_id == client_id));
+    
+    let json = serde_json::to_string(&listed).unwrap();
+    assert!(!json.contains("aml-key") && !json.contains("short"));
+    
+    let credentials = ProviderCredentials {
+        endpoint: None,
+        api_key: Some("aml-key-abcdefgh".to_string()),
+    };
+    assert!(!format!("{:?}", credentials).contains("aml-key"));
+}
+
+#[tokio::test]
+async fn removing_credentials_reverts_to_the_global_ones() {
+    let store = store();
+    let client_id = Uuid::new_v4();
+    store.set(client_id, Provider::Kyc, None, "acme-secret-key").await.unwrap();
+    
+    store.remove(client_id, Provider::Kyc).await.unwrap();
+    let resolved = store.resolve(Some(client_id), None, Provider::Kyc, &config()).await.unwrap();
+    assert_eq!(resolved.source, CredentialSource::Global);
+    assert!(matches!(
+        store.remove(client_id, Provider::Kyc).await,
+        Err(ComplianceError::ProviderCredentialNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn registrations_are_validated() {
+    let store = store();
+    let client_id = Uuid::new_v4();
+    assert!(store.set(client_id, Provider::Kyc, None, "  ").await.is_err());
+    let plaintext = Some("http://kyc.acme.example".to_string());
+    assert!(store.set(client_id, Provider::Kyc, plaintext, "acme-secret-key").await.is_err());
+    
+    let unkeyed = ProviderCredentialStore::new(None);
+    assert!(matches!(
+        unkeyed.set(client_id, Provider::Kyc, None, "acme-secret-key").await,
+        Err(ComplianceError::Crypto { .. })
+    ));
+}
+
+#[test]
+fn the_encryption_key_must_be_32_hex_bytes() {
+    let config = |key: Option<String>| ProviderCredentialsConfig { encryption_key: key };
+    assert!(ProviderCredentialStore::from_config(&config(None)).is_ok());
+    assert!(ProviderCredentialStore::from_config(&config(Some("ab".repeat(32)))).is_ok());
+    assert!(ProviderCredentialStore::from_config(&config(Some("ab".repeat(16)))).is_err());
+    assert!(ProviderCredentialStore::from_config(&config(Some("zz".repeat(32)))).is_err());
+}
@@ -0,0 +1,197 @@
+//! Cost-aware routing of provider calls and per-client cost reports
+
+use chrono::{TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::breaker::Provider;
+use compliance_backend::compliance::metering::UsageMeter;
+use compliance_backend::compliance::provider_credentials::{with_client, CredentialSource, ProviderCredentialStore};
+use compliance_backend::compliance::provider_routing::{ProviderRouter, Route, DEFAULT_VENDOR};
+use compliance_backend::config::{ComplianceConfig, RoutingRule, VendorConfig};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::types::ComplianceLevel;
+use compliance_backend::{ComplianceError, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+struct Routing {
+    router: ProviderRouter,
+    meter: Arc<UsageMeter>,
+    credentials: Arc<ProviderCredentialStore>,
+    config: Arc<LiveConfig>,
+}
+
+/// Basic KYC goes to a budget vendor backed by the default one; other levels go to the default only
+fn routing(fallback: bool) -> Routing {
+    let mut config = ComplianceConfig::default();
+    config.kyc.provider_endpoint = Some("https://kyc.example".to_string());
+    config.kyc.provider_api_key = Some("premium-key".to_string());
+    let routing = &mut config.provider_routing;
+    routing.default_costs.insert(Provider::Kyc, 2.5);
+    routing.vendors.insert(
+        "budget".to_string(),
+        VendorConfig {
+            provider: Provider::Kyc,
+            endpoint: "https://budget-kyc.example".to_string(),
+            api_key: Some("budget-key".to_string()),
+            cost_per_call: 0.4,
+        },
+    );
+    routing.rules.push(RoutingRule {
+        provider: Provider::Kyc,
+        levels: vec![ComplianceLevel::Basic],
+        vendors: vec![DEFAULT_VENDOR.to_string(), "budget".to_string()],
+        cheapest_first: true,
+        fallback,
+    });
+    
+    let config = Arc::new(LiveConfig::new(config));
+    let store = Arc::new(MemoryStore::default());
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 10, 12, 0, 0).unwrap()));
+    let meter = Arc::new(UsageMeter::new(config.clone(), store.usage.clone(), store.clone(), clock));
+    let credentials = Arc::new(ProviderCredentialStore::new(Some([7; 32])));
+    Routing {
+        router: ProviderRouter::new(config.clone(), credentials.clone(), meter.clone()),
+        meter,
+        credentials,
+        config,
+    }
+}
+
+/// Calls a KYC provider, recording the credentials each attempt resolved to
+///
+/// Vendors listed in `down` are unavailable.
+async fn verify(routing: &Routing, down: &[&str], attempts: &Mutex<Vec<String>>) -> Result<String> {
+    let attempt = || async move {
+        let resolved = routing.credentials.resolve_current(Provider::Kyc, &routing.config.compliance()).await?;
+        let vendor = match resolved.source {
+            CredentialSource::Vendor(name) => name,
+            CredentialSource::Client(_) => "client".to_string(),
+            _ => DEFAULT_VENDOR.to_string(),
+        };
+        attempts.lock().unwrap().push(vendor.clone());
+        if down.contains(&vendor.as_str()) {
+            return Err(ComplianceError::ProviderUnavailable {
+                provider: "kyc".to_string(),
+                reason: format!("{}: down", vendor),
+            });
+        }
+        Ok(resolved.credentials.api_key.unwrap_or_default())
+    };
+    routing.router.call(Provider::Kyc, None, attempt).await
+}
+
+#[tokio::test]
+async fn basic_accounts_go_to_the_cheaper_vendor_first() {
+    let routing = routing(true);
+    let routes = routing.router.routes(Provider::Kyc, None).await.unwrap();
+    assert_eq!(
+        routes,
+        [
+            Route {
+                vendor: "budget".to_string(),
+                cost: 0.4
+            },
+            Route {
+                vendor: DEFAULT_VENDOR.to_string(),
+                cost: 2.5
+            },
+        ]
+    );
+    
+    let enhanced = routing.router.routes(Provider::Kyc, Some(&ComplianceLevel::Enhanced)).await.unwrap();
+    assert_eq!(enhanced.len(), 1);
+    assert_eq!(enhanced[0].vendor, DEFAULT_VENDOR);
+    
+    let attempts = Mutex::new(Vec::new());
+    assert_eq!(verify(&routing, &[], &attempts).await.unwrap(), "budget-key");
+    assert_eq!(*attempts.lock().unwrap(), ["budget"]);
+}
+
+#[tokio::test]
+async fn unavailable_vendors_fall_back_to_the_next_one() {
+    let routing = routing(true);
+    let attempts = Mutex::new(Vec::new());
+    assert_eq!(verify(&routing, &["budget"], &attempts).await.unwrap(), "premium-key");
+    assert_eq!(*attempts.lock().unwrap(), ["budget", DEFAULT_VENDOR]);
+    
+    let attempts = Mutex::new(Vec::new());
+    let failed = verify(&routing, &["budget", DEFAULT_VENDOR], &attempts).await;
+    assert!(matches!(failed, Err(ComplianceError::ProviderUnavailable { .. })));
+    assert_eq!(attempts.lock().unwrap().len(), 2);
+    
+    let routing = self::routing(false);
+    let attempts = Mutex::new(Vec::new());
+    assert!(verify(&routing, &["budget"], &attempts).await.is_err());
+    assert_eq!(*attempts.lock().unwrap(), ["budget"]);
+}
+
+#[tokio::test]
+async fn client_credentials_are_not_routed() {
+    let routing = routing(true);
+    let client_id = Uuid::new_v4();
+    routing.credentials.set(client_id, Provider::Kyc, None, "client-key").await.unwrap();
+    
+    let attempts = Mutex::new(Vec::new());
+    let key = with_client(client_id, None, verify(&routing, &[], &attempts)).await.unwrap();
+    assert_eq!(key, "client-key");
+    assert_eq!(*attempts.lock().unwrap(), ["client"]);
+}
+
+#[tokio::test]
+async fn costs_are_reported_per_client_and_vendor() {
+    let routing = routing(true);
+    let (client, other) = (Uuid::new_v4(), Uuid::new_v4());
+    let attempts = Mutex::new(Vec::new());
+    with_client(client, None, verify(&routing, &[], &attempts)).await.unwrap();
+    with_client(client, None, verify(&routing, &["budget"], &attempts)).await.unwrap();
+    with_client(other, None, verify(&routing, &[], &attempts)).await.unwrap();
+    // Calls outside any client's request are not attributed
+    verify(&routing, &[], &attempts).await.unwrap();
+    
+    let now = Utc.with_ymd_and_hms(2025, 6, 20, 0, 0, 0).unwrap();
+    let report = routing.meter.cost_report(client, now).await.unwrap();
+    assert_eq!(report.currency, "USD");
+    assert!((report.total_cost - 2.9).abs() < 1e-9);
+    let calls: HashMap<_, _> = report
+        .providers
+        .iter()
+        .map(|usage| (usage.vendor.as_str(), (usage.calls, usage.failed_calls)))
+        .collect();
+    assert_eq!(calls, HashMap::from([("budget", (1, 1)), (DEFAULT_VENDOR, (1, 0))]));
+    
+    let reports = routing.meter.period_costs(now).await.unwrap();
+    assert_eq!(reports.len(), 2);
+    let other_report = reports.iter().find(|report| report.client_id == other).unwrap();
+    assert!((other_report.total_cost - 0.4).abs() < 1e-9);
+    
+    // Recording costs leaves billable usage untouched
+    let usage = routing.meter.usage(client, now).await.unwrap();
+    assert!(usage.operations.iter().all(|operation| operation.used == 0));
+}
+
+#[test]
+fn rules_must_name_vendors_of_their_provider() {
+    let mut config = compliance_backend::config::Config::default();
+    config.compliance.provider_routing.vendors.insert(
+        "budget".to_string(),
+        VendorConfig {
+            provider: Provider::Aml,
+            endpoint: "https://budget-aml.example".to_string(),
+            api_key: None,
+            cost_per_call: -1.0,
+        },
+    );
+    config.compliance.provider_routing.rules.push(RoutingRule {
+        provider: Provider::Kyc,
+        levels: Vec::new(),
+        vendors: vec!["budget".to_string(), "missing".to_string()],
+        cheapest_first: false,
+        fallback: true,
+    });
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("compliance.provider_routing.vendors.budget.cost_per_call"));
+    assert!(violations.contains("vendor budget serves aml, not kyc"));
+    assert!(violations.contains("no vendor named missing"));
+}
@@ -0,0 +1,132 @@
+//! Bounded, prioritized admission to proof generation
+
+use compliance_backend::compliance::proving::{ProofPriority, ProvingQueue};
+use compliance_backend::config::ProvingQueueConfig;
+use compliance_backend::ComplianceError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn queue(max_concurrent: usize, max_queued_interactive: usize, max_queued_batch: usize) -> Arc<ProvingQueue> {
+    Arc::new(ProvingQueue::new(ProvingQueueConfig {
+        max_concurrent,
+        max_queued_interactive,
+        max_queued_batch,
+        initial_estimate_ms: 2_000,
+    }))
+}
+
+/// Let spawned waiters reach the queue
+async fn settle() {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+}
+
+#[tokio::test]
+async fn slots_are_handed_to_waiters_on_release() {
+    let queue = queue(2, 4, 4);
+    let first = queue.acquire(ProofPriority::Interactive).await.unwrap();
+    let _second = queue.acquire(ProofPriority::Batch).await.unwrap();
+    assert_eq!(queue.status().running, 2);
+    
+    let waiter = tokio::spawn({
+        let queue = queue.clone();
+        async move { queue.acquire(ProofPriority::Batch).await.map(|_permit| ()) }
+    });
+    settle().await;
+    assert_eq!(queue.status().queued_batch, 1);
+    
+    drop(first);
+    waiter.await.unwrap().unwrap();
+    let status = queue.status();
+    assert_eq!(status.queued_batch, 0);
+    assert_eq!(status.running, 1);
+}
+
+#[tokio::test]
+async fn interactive_requests_go_first() {
+    let queue = queue(1, 4, 4);
+    let permit = queue.acquire(ProofPriority::Batch).await.unwrap();
+    let admitted = Arc::new(Mutex::new(Vec::new()));
+    
+    let mut waiters = Vec::new();
+    for (name, priority) in [("batch", ProofPriority::Batch), ("interactive", ProofPriority::Interactive)] {
+        let (queue, admitted) = (queue.clone(), admitted.clone());
+        waiters.push(tokio::spawn(async move {
+            let _permit = queue.acquire(priority).await.unwrap();
+            admitted.lock().unwrap().push(name);
+        }));
+        settle().await;
+    }
+    
+    drop(permit);
+    for waiter in waiters {
+        waiter.await.unwrap();
+    }
+    assert_eq!(*admitted.lock().unwrap(), vec!["interactive", "batch"]);
+}
+
+#[tokio::test]
+async fn full_class_queue_rejects_with_a_retry_hint() {
+    let queue = queue(1, 1, 1);
+    let _permit = queue.acquire(ProofPriority::Interactive).await.unwrap();
+    let _waiter = tokio::spawn({
+        let queue = queue.clone();
+        async move { queue.acquire(ProofPriority::Batch).await.map(|_permit| ()) }
+    });
+    settle().await;
+    
+    match queue.acquire(ProofPriority::Batch).await {
+        Err(ComplianceError::ProverSaturated { retry_after_secs }) => assert!(retry_after_secs >= 2),
+        other => panic!("expected saturation, got {:?}", other.map(|_| ())),
+    }
+    assert_eq!(queue.status().rejected_total, 1);
+    
+    // The interactive class still has room
+    let interactive = tokio::spawn({
+        let queue = queue.clone();
+        async move { queue.acquire(ProofPriority::Interactive).await.map(|_permit| ()) }
+    });
+    settle().await;
+    assert_eq!(queue.status().queued_interactive, 1);
+    interactive.abort();
+}
+
+#[tokio::test]
+async fn abandoned_waiters_do_not_hold_slots() {
+    let queue = queue(1, 4, 4);
+    let permit = queue.acquire(ProofPriority::Interactive).await.unwrap();
+    let waiter = tokio::spawn({
+        let queue = queue.clone();
+        async move { queue.acquire(ProofPriority::Interactive).await.map(|_permit| ()) }
+    });
+    settle().await;
+    waiter.abort();
+    let _ = waiter.await;
+    
+    drop(permit);
+    let status = queue.status();
+    assert_eq!(status.running, 0);
+    assert_eq!(status.queued_interactive, 0);
+    
+    let _next = queue.acquire(ProofPriority::Batch).await.unwrap();
+    assert_eq!(queue.status().running, 1);
+}
+
+#[tokio::test]
+async fn run_releases_its_slot_and_updates_the_estimate() {
+    let queue = queue(1, 1, 1);
+    assert_eq!(queue.status().avg_proof_ms, 2_000);
+    
+    let proof = queue.run(ProofPriority::Interactive, async { Ok("proof") }).await.unwrap();
+    assert_eq!(proof, "proof");
+    
+    let failed = queue
+        .run(ProofPriority::Batch, async { Err::<(), _>(ComplianceError::internal("prover crashed")) })
+        .await;
+    assert!(failed.is_err());
+    
+    let status = queue.status();
+    assert_eq!(status.running, 0);
+    assert_eq!(status.max_concurrent, 1);
+    // Fast proofs pull the estimate down from its initial value
+    assert!(status.avg_proof_ms < 2_000);
+}
@@ -0,0 +1,129 @@
+//! Operator roles, permission checks, and session lifecycle
+
+use compliance_backend::api::auth::rbac::{OperatorAuth, Permission, RbacService, Role};
+use compliance_backend::ComplianceError;
+
+const PASSWORD: &str = "correct horse battery";
+
+async fn operator_auth(service: &RbacService, username: &str, role: Role) -> OperatorAuth {
+    service.create_operator(username, username, role, PASSWORD).await.unwrap();
+    login(service, username).await
+}
+
+async fn login(service: &RbacService, username: &str) -> OperatorAuth {
+    let (token, _) = service.login(username, PASSWORD).await.unwrap();
+    OperatorAuth {
+        operator: service.authenticate(&token).await.unwrap(),
+        token,
+    }
+}
+
+#[test]
+fn roles_are_ordered_by_privilege() {
+    assert!(Role::Viewer < Role::Analyst);
+    assert!(Role::Analyst < Role::ComplianceOfficer);
+    assert!(Role::ComplianceOfficer < Role::Admin);
+}
+
+#[test]
+fn each_role_grants_everything_a_less_privileged_role_does() {
+    let roles = [Role::Viewer, Role::Analyst, Role::ComplianceOfficer, Role::Admin];
+    for pair in roles.windows(2) {
+        for permission in pair[0].permissions() {
+            assert!(pair[1].grants(*permission), "{:?} lacks {:?}", pair[1], permission);
+        }
+    }
+}
+
+#[test]
+fn sensitive_permissions_are_reserved_for_privileged_roles() {
+    assert!(!Role::Viewer.grants(Permission::AcknowledgeAlerts));
+    assert!(!Role::Analyst.grants(Permission::ManualOverride));
+    assert!(!Role::Analyst.grants(Permission::RevokeAttestation));
+    assert!(Role::ComplianceOfficer.grants(Permission::RevokeAttestation));
+    assert!(Role::ComplianceOfficer.grants(Permission::CosignAttestations));
+    
+    for permission in [
+        Permission::ManageOperators,
+        Permission::ManageClients,
+        Permission::ReloadConfig,
+        Permission::ManageJobs,
+    ] {
+        assert!(!Role::ComplianceOfficer.grants(permission));
+        assert!(Role::Admin.grants(permission));
+    }
+}
+
+#[tokio::test]
+async fn require_rejects_permissions_the_role_lacks() {
+    let service = RbacService::new(3_600);
+    let viewer = operator_auth(&service, "viewer", Role::Viewer).await;
+    
+    viewer.require(Permission::ViewAudit).unwrap();
+    match viewer.require(Permission::ManualOverride) {
+        Err(ComplianceError::PermissionDenied { permission }) => assert_eq!(permission, "ManualOverride"),
+        other => panic!("expected permission denied, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn logins_require_the_right_password_and_an_active_account() {
+    let service = RbacService::new(3_600);
+    let analyst = operator_auth(&service, "analyst", Role::Analyst).await;
+    
+    assert!(matches!(
+        service.login("analyst", "wrong password!").await,
+        Err(ComplianceError::InvalidCredentials)
+    ));
+    assert!(matches!(
+        service.login("nobody", PASSWORD).await,
+        Err(ComplianceError::InvalidCredentials)
+    ));
+    
+    service.update_operator(analyst.operator.id, None, Some(false)).await.unwrap();
+    assert!(matches!(
+        service.login("analyst", PASSWORD).await,
+        Err(ComplianceError::InvalidCredentials)
+    ));
+}
+
+#[tokio::test]
+async fn role_changes_end_existing_sessions() {
+    let service = RbacService::new(3_600);
+    let analyst = operator_auth(&service, "analyst", Role::Analyst).await;
+    
+    service
+        .update_operator(analyst.operator.id, Some(Role::ComplianceOfficer), None)
+        .await
+        .unwrap();
+    assert!(service.authenticate(&analyst.token).await.is_err());
+    
+    let promoted = login(&service, "analyst").await;
+    promoted.require(Permission::RevokeAttestation).unwrap();
+}
+
+#[tokio::test]
+async fn logout_and_expiry_invalidate_tokens() {
+    let service = RbacService::new(3_600);
+    let admin = operator_auth(&service, "admin", Role::Admin).await;
+    service.logout(&admin.token).await;
+    assert!(service.authenticate(&admin.token).await.is_err());
+    
+    let expiring = RbacService::new(0);
+    expiring.create_operator("admin", "Admin", Role::Admin, PASSWORD).await.unwrap();
+    let (token, _) = expiring.login("admin", PASSWORD).await.unwrap();
+    assert!(matches!(
+        expiring.authenticate(&token).await,
+        Err(ComplianceError::InvalidCredentials)
+    ));
+}
+
+#[tokio::test]
+async fn operator_accounts_are_validated() {
+    let service = RbacService::new(3_600);
+    assert!(service.create_operator(" ", "Blank", Role::Viewer, PASSWORD).await.is_err());
+    assert!(service.create_operator("short", "Short", Role::Viewer, "too short").await.is_err());
+    
+    service.create_operator("taken", "Taken", Role::Viewer, PASSWORD).await.unwrap();
+    assert!(service.create_operator("taken", "Again", Role::Admin, PASSWORD).await.is_err());
+}
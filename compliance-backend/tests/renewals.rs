@@ -0,0 +1,86 @@
+//! Attestations renewed and proven ahead of their expiry
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::renewal::{PreparedRenewal, RenewalStore};
+use compliance_backend::config::{Config, ConfigViolations};
+use compliance_backend::types::AccountId;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn prepared(account_id: &AccountId, version: u64, minutes_ago: i64) -> PreparedRenewal {
+    PreparedRenewal {
+        attestation: common::attestation(account_id, Utc::now(), Duration::days(90)),
+        proof: format!("proof-{}", version),
+        version,
+        prepared_at: Utc::now() - Duration::minutes(minutes_ago),
+    }
+}
+
+fn renewal_violations(config: &Config) -> Vec<String> {
+    match config.validate() {
+        Ok(()) => vec![],
+        Err(ConfigViolations(violations)) => violations
+            .into_iter()
+            .map(|v| v.field)
+            .filter(|field| field.starts_with("compliance.attestation.renewal."))
+            .collect(),
+    }
+}
+
+#[tokio::test]
+async fn prepared_renewals_are_served_only_at_their_version() {
+    let store = RenewalStore::new();
+    let account_id = account(1);
+    store.insert(prepared(&account_id, 7, 0)).await;
+    
+    assert_eq!(store.get(&account_id, 7).await.unwrap().proof, "proof-7");
+    assert!(store.get(&account_id, 8).await.is_none(), "a later event invalidates the proof");
+    assert!(store.get(&account(2), 7).await.is_none());
+}
+
+#[tokio::test]
+async fn a_newer_renewal_replaces_the_previous_one() {
+    let store = RenewalStore::new();
+    let account_id = account(1);
+    store.insert(prepared(&account_id, 7, 0)).await;
+    store.insert(prepared(&account_id, 9, 0)).await;
+    
+    assert!(store.get(&account_id, 7).await.is_none());
+    assert_eq!(store.get(&account_id, 9).await.unwrap().version, 9);
+}
+
+#[tokio::test]
+async fn renewals_prepared_before_the_cutoff_are_pruned() {
+    let store = RenewalStore::new();
+    store.insert(prepared(&account(1), 1, 120)).await;
+    store.insert(prepared(&account(2), 1, 5)).await;
+    
+    store.prune(Utc::now() - Duration::minutes(60)).await;
+    assert!(store.get(&account(1), 1).await.is_none());
+    assert!(store.get(&account(2), 1).await.is_some());
+}
+
+#[test]
+fn renewal_defaults_are_valid_and_bounded_by_the_validity_period() {
+    let mut config = Config::default();
+    let renewal = &config.compliance.attestation.renewal;
+    assert!(renewal.enabled);
+    assert_eq!(renewal.lead_time_hours, 72);
+    assert_eq!(renewal_violations(&config), Vec::<String>::new());
+    
+    config.compliance.attestation.validity_period_days = 3;
+    assert_eq!(renewal_violations(&config), ["compliance.attestation.renewal.lead_time_hours"]);
+    
+    let renewal = &mut config.compliance.attestation.renewal;
+    renewal.lead_time_hours = 0;
+    renewal.check_interval_secs = 0;
+    renewal.max_renewals_per_run = 0;
+    assert_eq!(renewal_violations(&config).len(), 3);
+    
+    config.compliance.attestation.renewal.enabled = false;
+    assert_eq!(renewal_violations(&config), Vec::<String>::new());
+}
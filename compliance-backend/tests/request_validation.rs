@@ -0,0 +1,97 @@
+//! Up-front validation of API request bodies with field-level errors
+
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use compliance_backend::api::screening::SetThresholdRequest;
+use compliance_backend::api::validation::{from_slice, Violations};
+use compliance_backend::api::webhook_tls::SetTlsRequest;
+use compliance_backend::compliance::screening::search::SearchRequest;
+use compliance_backend::error::{json_pointer, FieldViolation};
+use compliance_backend::ComplianceError;
+
+fn pointers(error: &ComplianceError) -> Vec<String> {
+    error.violations().into_iter().map(|v| v.pointer).collect()
+}
+
+#[test]
+fn field_names_become_json_pointers() {
+    assert_eq!(json_pointer("name"), "/name");
+    assert_eq!(json_pointer("transactions[3].amount"), "/transactions/3/amount");
+    assert_eq!(json_pointer("freshness.min_list_versions"), "/freshness/min_list_versions");
+    assert_eq!(json_pointer("/already/a/pointer"), "/already/a/pointer");
+    assert_eq!(json_pointer(""), "");
+    
+    let violation = FieldViolation::new("lists[0].a~b", "bad");
+    assert_eq!(violation.pointer, "/lists/0/a~0b");
+}
+
+#[test]
+fn every_bad_field_is_reported_at_once() {
+    let body = br#"{"name": " ", "language": "not a tag!", "min_score": 2.0, "limit": 0}"#;
+    let error = from_slice::<SearchRequest>(body).unwrap_err();
+    
+    assert_eq!(error.code(), "validation_error");
+    assert_eq!(error.status_code(), 400);
+    assert_eq!(pointers(&error), ["/name", "/language", "/min_score", "/limit"]);
+}
+
+#[test]
+fn valid_bodies_pass() {
+    let request = from_slice::<SetThresholdRequest>(br#"{"threshold": 0.9}"#).unwrap();
+    assert_eq!(request.threshold, Some(0.9));
+    
+    let error = from_slice::<SetThresholdRequest>(br#"{"threshold": 1.5}"#).unwrap_err();
+    assert_eq!(pointers(&error), ["/threshold"]);
+}
+
+#[test]
+fn deserialization_errors_point_at_the_field() {
+    let error = from_slice::<SetTlsRequest>(br#"{"identity": {"certificate_pem": 1, "private_key_pem": "k"}}"#)
+        .unwrap_err();
+    assert_eq!(pointers(&error), ["/identity/certificate_pem"]);
+    
+    let error = from_slice::<SetThresholdRequest>(br#"{"threshold": 0.5} trailing"#).unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+}
+
+#[test]
+fn nested_values_are_validated_under_their_pointer() {
+    let body = br#"{"identity": {"certificate_pem": "", "private_key_pem": " "}}"#;
+    let error = from_slice::<SetTlsRequest>(body).unwrap_err();
+    
+    assert_eq!(pointers(&error), ["/identity/certificate_pem", "/identity/private_key_pem"]);
+}
+
+#[test]
+fn domain_errors_are_absorbed_relative_to_the_value() {
+    let mut violations = Violations::new();
+    violations.absorb(Err(ComplianceError::validation("attributes.date_of_birth", "must be YYYY")));
+    violations.at("subjects[2]", |v| {
+        v.absorb(Err(ComplianceError::validation("name", "must not be empty")));
+        v.absorb(Err(ComplianceError::internal("unexpected")));
+        v.ensure(true, "language", "never recorded");
+    });
+    violations.absorb(Ok(()));
+    
+    let error = violations.into_result().unwrap_err();
+    assert_eq!(
+        pointers(&error),
+        ["/attributes/date_of_birth", "/subjects/2/name", "/subjects/2"]
+    );
+    assert!(Violations::new().into_result().is_ok());
+}
+
+#[tokio::test]
+async fn responses_list_every_violation() {
+    let error = from_slice::<SearchRequest>(br#"{"name": "", "limit": 0}"#).unwrap_err();
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["code"], "validation_error");
+    assert_eq!(body["errors"][0]["pointer"], "/name");
+    assert_eq!(body["errors"][0]["message"], "must not be empty");
+    assert_eq!(body["errors"][1]["pointer"], "/limit");
+}
@@ -0,0 +1,211 @@
+//! Risk factors defined in configuration
+
+mod common;
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::country_risk::{CountryRiskService, GeographicProfile};
+use compliance_backend::compliance::risk_factors::{
+    RiskFactorDefinition, RiskFactorService, RiskFactorSource, RiskFactorTransform,
+};
+use compliance_backend::config::{ComplianceConfig, Config};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, AmlRiskLevel, ComplianceAttestation};
+use serde_json::json;
+use std::sync::Arc;
+
+fn account(n: usize) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+fn factor(name: &str, source: RiskFactorSource, field: &str, transform: RiskFactorTransform) -> RiskFactorDefinition {
+    RiskFactorDefinition {
+        name: name.to_string(),
+        source,
+        field: field.to_string(),
+        weight: 0.5,
+        transform,
+    }
+}
+
+fn occupation() -> RiskFactorDefinition {
+    let values = [("dealer".to_string(), 1.0), ("teacher".to_string(), 0.1)].into();
+    factor(
+        "occupation",
+        RiskFactorSource::Attributes,
+        "occupation",
+        RiskFactorTransform::Lookup { values, default: 0.5 },
+    )
+}
+
+fn service(factors: Vec<RiskFactorDefinition>) -> (RiskFactorService, Arc<CountryRiskService>) {
+    let mut config = ComplianceConfig::default();
+    config.aml.custom_factors = factors;
+    let config = Arc::new(LiveConfig::new(config));
+    let country_risk = Arc::new(CountryRiskService::new(config.clone()).unwrap());
+    (RiskFactorService::new(config, country_risk.clone()), country_risk)
+}
+
+fn attributes(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[tokio::test]
+async fn nothing_is_scored_without_factors() {
+    let (service, _) = service(Vec::new());
+    service.set_attributes(&account(1), attributes(json!({ "occupation": "dealer" }))).await.unwrap();
+    
+    assert!(!service.enabled());
+    assert!(service.assess(&account(1), None).await.is_none());
+}
+
+#[tokio::test]
+async fn score_is_the_weighted_mean_of_present_factors() {
+    let cash_share = factor(
+        "cash_share",
+        RiskFactorSource::Attributes,
+        "business.cash_share",
+        RiskFactorTransform::Linear { min: 0.0, max: 100.0 },
+    );
+    let residence = factor(
+        "residence",
+        RiskFactorSource::Geography,
+        "residence",
+        RiskFactorTransform::Threshold { at_least: 0.0 },
+    );
+    let (service, _) = service(vec![occupation(), cash_share, residence]);
+    let recorded = attributes(json!({ "occupation": "dealer", "business": { "cash_share": 50 } }));
+    service.set_attributes(&account(1), recorded).await.unwrap();
+    
+    let assessment = service.assess(&account(1), None).await.unwrap();
+    assert!((assessment.score - 0.75).abs() < 1e-9);
+    assert_eq!(assessment.risk_level, AmlRiskLevel::High);
+    let names: Vec<&str> = assessment.factors.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["occupation", "cash_share", "residence"]);
+    assert_eq!(assessment.factors[0].value, Some(json!("dealer")));
+    assert_eq!(assessment.factors[1].risk, Some(0.5));
+    assert!(assessment.factors[2].value.is_none());
+    assert!(assessment.factors[2].risk.is_none());
+}
+
+#[tokio::test]
+async fn values_of_the_wrong_type_do_not_count() {
+    let cash_share = factor(
+        "cash_share",
+        RiskFactorSource::Attributes,
+        "cash_share",
+        RiskFactorTransform::Linear { min: 0.0, max: 100.0 },
+    );
+    let (service, _) = service(vec![occupation(), cash_share]);
+    let recorded = attributes(json!({ "occupation": "teacher", "cash_share": "high" }));
+    service.set_attributes(&account(1), recorded).await.unwrap();
+    
+    let assessment = service.assess(&account(1), None).await.unwrap();
+    assert!((assessment.score - 0.1).abs() < 1e-9);
+    assert_eq!(assessment.factors[1].value, Some(json!("high")));
+    assert!(assessment.factors[1].risk.is_none());
+}
+
+#[tokio::test]
+async fn factors_read_the_geography_and_the_attestation() {
+    let mut values = std::collections::HashMap::new();
+    values.insert("PA".to_string(), 0.8);
+    let residence = factor(
+        "residence",
+        RiskFactorSource::Geography,
+        "residence",
+        RiskFactorTransform::Lookup { values, default: 0.0 },
+    );
+    let uncleared = factor(
+        "uncleared",
+        RiskFactorSource::Attestation,
+        "sanctions_cleared",
+        RiskFactorTransform::Boolean,
+    );
+    let (service, country_risk) = service(vec![residence, uncleared]);
+    let profile = GeographicProfile {
+        residence: Some("pa".to_string()),
+        ..GeographicProfile::default()
+    };
+    country_risk.set_profile(&account(1), profile).await.unwrap();
+    
+    let mut attestation = ComplianceAttestation {
+        sanctions_cleared: false,
+        ..common::attestation(&account(1), Utc::now(), Duration::days(30))
+    };
+    let assessment = service.apply(&mut attestation).await.unwrap();
+    assert!((assessment.score - 0.4).abs() < 1e-9);
+    assert_eq!(assessment.factors[0].risk, Some(0.8));
+    assert_eq!(assessment.factors[1].risk, Some(0.0));
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::Medium);
+    
+    // Outside a compliance check only the geography counts
+    let assessment = service.assess(&account(1), None).await.unwrap();
+    assert!(assessment.factors[1].risk.is_none());
+    assert!((assessment.score - 0.8).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn custom_risk_never_lowers_the_attestation_risk_level() {
+    let (service, _) = service(vec![occupation()]);
+    service.set_attributes(&account(1), attributes(json!({ "occupation": "teacher" }))).await.unwrap();
+    
+    let mut attestation = common::attestation(&account(1), Utc::now(), Duration::days(30));
+    attestation.aml_risk_level = AmlRiskLevel::High;
+    service.apply(&mut attestation).await.unwrap();
+    assert_eq!(attestation.aml_risk_level, AmlRiskLevel::High);
+}
+
+#[tokio::test]
+async fn malformed_attributes_are_rejected() {
+    let (service, _) = service(vec![occupation()]);
+    
+    assert!(service.set_attributes(&account(1), attributes(json!({ "occu pation": 1 }))).await.is_err());
+    let many: serde_json::Map<_, _> = (0..65).map(|i| (format!("a{}", i), json!(i))).collect();
+    assert!(service.set_attributes(&account(1), many).await.is_err());
+}
+
+#[test]
+fn definitions_are_validated_at_load() {
+    let mut config = Config::default();
+    config.compliance.aml.custom_factors = vec![occupation(), occupation()];
+    let violations = config.validate().unwrap_err().to_string();
+    assert!(violations.contains("factor occupation is defined more than once"));
+    
+    let inverted = factor(
+        "cash_share",
+        RiskFactorSource::Attributes,
+        "cash_share",
+        RiskFactorTransform::Linear { min: 100.0, max: 0.0 },
+    );
+    assert!(inverted.validate().is_err());
+    let mut heavy = occupation();
+    heavy.weight = 2.0;
+    assert!(heavy.validate().is_err());
+    let mut unnamed = occupation();
+    unnamed.name = "Occupation Risk".to_string();
+    assert!(unnamed.validate().is_err());
+    let empty_segment = factor("x", RiskFactorSource::Attributes, "a..b", RiskFactorTransform::Boolean);
+    assert!(empty_segment.validate().is_err());
+    let empty_lookup = factor(
+        "x",
+        RiskFactorSource::Attributes,
+        "x",
+        RiskFactorTransform::Lookup { values: Default::default(), default: 0.0 },
+    );
+    assert!(empty_lookup.validate().is_err());
+    assert!(occupation().validate().is_ok());
+}
+
+#[test]
+fn definitions_deserialize_from_configuration() {
+    let definition: RiskFactorDefinition = serde_json::from_value(json!({
+        "name": "pep_relative",
+        "source": "attributes",
+        "field": "pep.relative",
+        "weight": 1.0,
+        "transform": { "type": "boolean" },
+    }))
+    .unwrap();
+    assert_eq!(definition.transform, RiskFactorTransform::Boolean);
+    assert!(definition.validate().is_ok());
+}
@@ -0,0 +1,82 @@
+//! Sanctions screening component procedures executed in the Miden VM
+
+mod masm_harness;
+
+use compliance_backend::compliance::account_components::template::SanctionsTemplate;
+use masm_harness::{ComponentHarness, DEFAULT_NOW};
+
+const SCREENING_HASH: u64 = 0xabc;
+
+#[test]
+fn get_sanctions_status_reads_stored_slots() {
+    let outcome = ComponentHarness::sanctions()
+        .with_slot(0, 1)
+        .with_slot(1, 100)
+        .with_slot(2, SCREENING_HASH)
+        .call("get_sanctions_status", &[]);
+    
+    assert_eq!(&outcome.stack()[..3], &[SCREENING_HASH, 100, 1]);
+}
+
+#[test]
+fn update_sanctions_status_sets_status_and_override_flag() {
+    let outcome = ComponentHarness::sanctions()
+        .at(1_800_000_000)
+        .call("update_sanctions_status", &[2, 9]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(0), 2);
+    assert_eq!(outcome.slot(1), 1_800_000_000);
+    assert_eq!(outcome.slot(5), 1);
+}
+
+#[test]
+fn update_sanctions_status_uses_default_clock() {
+    let outcome = ComponentHarness::sanctions().call("update_sanctions_status", &[1, 9]);
+    
+    assert_eq!(outcome.slot(1), DEFAULT_NOW);
+}
+
+#[test]
+fn verify_screening_proof_checks_stored_hash() {
+    let harness = ComponentHarness::sanctions().with_slot(2, SCREENING_HASH);
+    
+    assert_eq!(harness.call("verify_screening_proof", &[SCREENING_HASH]).top(), 1);
+    assert_eq!(harness.call("verify_screening_proof", &[0xdef]).top(), 0);
+}
+
+#[test]
+fn manual_override_with_authorization_applies() {
+    let outcome = ComponentHarness::sanctions()
+        .with_slot(0, 2)
+        .call("manual_override", &[0, 42]);
+    
+    assert_eq!(outcome.top(), 1);
+    assert_eq!(outcome.slot(0), 0);
+    assert_eq!(outcome.slot(5), 1);
+}
+
+#[test]
+fn manual_override_without_authorization_is_refused() {
+    let outcome = ComponentHarness::sanctions()
+        .with_slot(0, 2)
+        .call("manual_override", &[0, 0]);
+    
+    assert_eq!(outcome.top(), 0);
+    assert_eq!(outcome.slot(0), 2);
+    assert_eq!(outcome.slot(5), 0);
+}
+
+#[test]
+fn manual_override_accepts_only_configured_keys() {
+    let template = SanctionsTemplate { override_keys: vec![42] };
+    let harness = ComponentHarness::sanctions_with(&template).with_slot(0, 2);
+    
+    let authorized = harness.call("manual_override", &[0, 42]);
+    assert_eq!(authorized.top(), 1);
+    assert_eq!(authorized.slot(0), 0);
+    
+    let unauthorized = harness.call("manual_override", &[0, 43]);
+    assert_eq!(unauthorized.top(), 0);
+    assert_eq!(unauthorized.slot(0), 2);
+}
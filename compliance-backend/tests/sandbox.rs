@@ -0,0 +1,109 @@
+//! Separate sandbox keys, settings, and configuration for client test traffic
+
+use compliance_backend::compliance::clients::{ClientRegistry, SANDBOX_API_KEY_PREFIX};
+use compliance_backend::config::Config;
+use compliance_backend::types::{ClientEnvironment, ComplianceLevel};
+use compliance_backend::ComplianceError;
+
+async fn client_with_sandbox(registry: &ClientRegistry) -> compliance_backend::types::BusinessClient {
+    registry
+        .create("Acme", Some("https://acme.example/hooks".to_string()), ComplianceLevel::Standard, None, true)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sandbox_keys_resolve_to_the_clients_sandbox_settings() {
+    let registry = ClientRegistry::new();
+    let client = client_with_sandbox(&registry).await;
+    registry
+        .update_sandbox(client.id, Some("https://acme.example/test-hooks".to_string()), ComplianceLevel::Basic)
+        .await
+        .unwrap();
+    let sandbox_key = client.sandbox.as_ref().unwrap().api_key.clone();
+    assert!(sandbox_key.starts_with(SANDBOX_API_KEY_PREFIX));
+    assert!(!client.api_key.starts_with(SANDBOX_API_KEY_PREFIX));
+    
+    let production = registry.find_by_api_key(&client.api_key).await.unwrap().unwrap();
+    assert_eq!(production.webhook_url.as_deref(), Some("https://acme.example/hooks"));
+    assert_eq!(production.compliance_level, ComplianceLevel::Standard);
+    
+    let sandbox = registry.find_by_api_key(&sandbox_key).await.unwrap().unwrap();
+    assert_eq!(sandbox.id, client.id);
+    assert_eq!(sandbox.api_key, sandbox_key);
+    assert_eq!(sandbox.webhook_url.as_deref(), Some("https://acme.example/test-hooks"));
+    assert_eq!(sandbox.compliance_level, ComplianceLevel::Basic);
+}
+
+#[tokio::test]
+async fn rotating_one_environments_key_keeps_the_other() {
+    let registry = ClientRegistry::new();
+    let client = client_with_sandbox(&registry).await;
+    let sandbox_key = client.sandbox.as_ref().unwrap().api_key.clone();
+    
+    let rotated = registry.rotate_api_key(client.id, ClientEnvironment::Sandbox).await.unwrap();
+    assert_eq!(rotated.api_key, client.api_key);
+    assert!(registry.find_by_api_key(&sandbox_key).await.unwrap().is_none());
+    assert!(registry.find_by_api_key(&client.api_key).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn promotion_copies_sandbox_settings_but_not_keys() {
+    let registry = ClientRegistry::new();
+    let client = client_with_sandbox(&registry).await;
+    registry
+        .update_sandbox(client.id, Some("https://acme.example/v2/hooks".to_string()), ComplianceLevel::Enhanced)
+        .await
+        .unwrap();
+    
+    let promoted = registry.promote_sandbox(client.id).await.unwrap();
+    assert_eq!(promoted.webhook_url.as_deref(), Some("https://acme.example/v2/hooks"));
+    assert_eq!(promoted.compliance_level, ComplianceLevel::Enhanced);
+    assert_eq!(promoted.api_key, client.api_key);
+    assert_ne!(promoted.api_key, promoted.sandbox.unwrap().api_key);
+}
+
+#[tokio::test]
+async fn clients_without_a_sandbox_cannot_use_one() {
+    let registry = ClientRegistry::new();
+    let client = registry.create("Acme", None, ComplianceLevel::Basic, None, false).await.unwrap();
+    assert!(client.sandbox.is_none());
+    assert!(client.in_environment(ClientEnvironment::Sandbox).is_none());
+    
+    let promoted = registry.promote_sandbox(client.id).await;
+    assert!(matches!(promoted, Err(ComplianceError::ClientSandboxNotFound { .. })));
+}
+
+fn sandboxed() -> Config {
+    let mut config = Config::default();
+    config.database.url = "postgres://db.example/compliance".to_string();
+    config.sandbox.enabled = true;
+    config.sandbox.database_url = Some("postgres://db.example/compliance_sandbox".to_string());
+    config.sandbox.mock_provider_endpoint = Some("http://mock-providers.internal/".to_string());
+    config.compliance.kyc.provider_endpoint = Some("https://kyc.example".to_string());
+    config.compliance.kyc.provider_api_key = Some("live-key".to_string());
+    config
+}
+
+#[test]
+fn sandbox_configuration_keeps_test_traffic_apart() {
+    let config = sandboxed();
+    let sandbox = config.sandbox().unwrap();
+    
+    assert_eq!(sandbox.database.url, "postgres://db.example/compliance_sandbox");
+    assert_eq!(sandbox.miden.rpc_endpoint, config.sandbox.miden_rpc_endpoint);
+    assert!(sandbox.miden.store_path.starts_with(&config.sandbox.data_dir));
+    assert_eq!(sandbox.compliance.kyc.provider_endpoint.as_deref(), Some("http://mock-providers.internal/kyc"));
+    assert_ne!(sandbox.compliance.kyc.provider_api_key.as_deref(), Some("live-key"));
+    assert!(sandbox.sandbox().is_none());
+    
+    assert!(Config::default().sandbox().is_none());
+}
+
+#[test]
+fn enabled_sandbox_needs_its_own_database() {
+    let mut config = sandboxed();
+    config.sandbox.database_url = Some(config.database.url.clone());
+    let violations = config.validate().unwrap_err();
+    assert!(violations.0.iter().any(|violation| violation.field == "sandbox.database_url"));
+}
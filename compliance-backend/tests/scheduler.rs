@@ -0,0 +1,178 @@
+//! Background job scheduling, overrides, and leader election
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::config::{JobScheduleConfig, SchedulerConfig};
+use compliance_backend::reporting::schedule::CronSchedule;
+use compliance_backend::leader::{LeaderElection, MemoryLeaseStore};
+use compliance_backend::scheduler::{Job, JobOutcome, Scheduler};
+use compliance_backend::ComplianceError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A Sunday
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()
+}
+
+fn counting_job(name: &str, runs: Arc<AtomicUsize>) -> Job {
+    Job::every(name, std::time::Duration::from_secs(60), move || {
+        let runs = runs.clone();
+        async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })
+}
+
+fn scheduler(config: SchedulerConfig) -> (Scheduler, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(start()));
+    (Scheduler::new(config).with_clock(clock.clone()), clock)
+}
+
+#[tokio::test]
+async fn interval_jobs_run_when_due() {
+    let (scheduler, clock) = scheduler(SchedulerConfig::default());
+    let runs = Arc::new(AtomicUsize::new(0));
+    scheduler.register(counting_job("tick", runs.clone())).unwrap();
+    assert_eq!(scheduler.status("tick").unwrap().next_run_at, Some(start() + Duration::seconds(60)));
+    
+    clock.advance(Duration::seconds(59));
+    assert!(scheduler.run_due().await.is_empty());
+    clock.advance(Duration::seconds(1));
+    assert_eq!(scheduler.run_due().await, vec!["tick".to_string()]);
+    assert!(scheduler.run_due().await.is_empty());
+    
+    let status = scheduler.status("tick").unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(status.runs, 1);
+    assert_eq!(status.last_outcome, Some(JobOutcome::Succeeded));
+    assert_eq!(status.next_run_at, Some(start() + Duration::seconds(120)));
+    assert!(!status.running);
+}
+
+#[test]
+fn cron_schedules_find_their_next_run() {
+    let at = |month, day, hour, minute| Utc.with_ymd_and_hms(2025, month, day, hour, minute, 0).unwrap();
+    let next = |expression: &str, after| CronSchedule::parse(expression).unwrap().next_after(after);
+    
+    assert_eq!(next("* * * * *", at(6, 1, 12, 0)), Some(at(6, 1, 12, 1)));
+    assert_eq!(next("*/15 * * * *", at(6, 1, 12, 7) + Duration::seconds(30)), Some(at(6, 1, 12, 15)));
+    assert_eq!(next("0 9 * * 1", at(6, 1, 12, 0)), Some(at(6, 2, 9, 0)));
+    assert_eq!(next("30 23 31 12 *", at(6, 1, 12, 0)), Some(at(12, 31, 23, 30)));
+    assert_eq!(next("0 0 30 2 *", at(6, 1, 12, 0)), None);
+}
+
+#[tokio::test]
+async fn cron_jobs_keep_their_expression() {
+    let (scheduler, _) = scheduler(SchedulerConfig::default());
+    let job = Job::cron("report.daily", "0 6 * * *", || async { Ok(()) }).unwrap();
+    scheduler.register(job).unwrap();
+    
+    let status = scheduler.status("report.daily").unwrap();
+    assert_eq!(status.schedule, "cron 0 6 * * *");
+    assert_eq!(status.next_run_at, Some(Utc.with_ymd_and_hms(2025, 6, 2, 6, 0, 0).unwrap()));
+    assert!(Job::cron("report.broken", "0 25 * * *", || async { Ok(()) }).is_err());
+}
+
+#[tokio::test]
+async fn configuration_overrides_and_disables_jobs() {
+    let mut config = SchedulerConfig::default();
+    config.jobs.insert(
+        "fast".to_string(),
+        JobScheduleConfig {
+            interval_secs: Some(10),
+            ..Default::default()
+        },
+    );
+    config.jobs.insert(
+        "off".to_string(),
+        JobScheduleConfig {
+            enabled: false,
+            ..Default::default()
+        },
+    );
+    let (scheduler, clock) = scheduler(config);
+    let (fast, off) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    scheduler.register(counting_job("fast", fast.clone())).unwrap();
+    scheduler.register(counting_job("off", off.clone())).unwrap();
+    
+    let status = scheduler.status("fast").unwrap();
+    assert_eq!(status.schedule, "every 10s");
+    assert_eq!(status.next_run_at, Some(start() + Duration::seconds(10)));
+    assert!(!scheduler.status("off").unwrap().enabled);
+    assert!(scheduler.status("off").unwrap().next_run_at.is_none());
+    
+    clock.advance(Duration::minutes(5));
+    assert_eq!(scheduler.run_due().await, vec!["fast".to_string()]);
+    assert_eq!(off.load(Ordering::SeqCst), 0);
+    assert!(scheduler.trigger("off").is_err());
+}
+
+#[tokio::test]
+async fn failures_are_recorded() {
+    let (scheduler, _) = scheduler(SchedulerConfig::default());
+    let job = Job::every("flaky", std::time::Duration::from_secs(60), || async {
+        Err(ComplianceError::internal("provider unreachable"))
+    });
+    scheduler.register(job).unwrap();
+    scheduler.trigger("flaky").unwrap();
+    scheduler.run_due().await;
+    
+    let status = scheduler.status("flaky").unwrap();
+    assert_eq!(status.last_outcome, Some(JobOutcome::Failed));
+    assert_eq!((status.runs, status.failures), (1, 1));
+    assert!(status.last_error.unwrap().contains("provider unreachable"));
+}
+
+#[tokio::test]
+async fn jobs_can_be_run_ahead_of_schedule() {
+    let (scheduler, _) = scheduler(SchedulerConfig::default());
+    let runs = Arc::new(AtomicUsize::new(0));
+    scheduler.register(counting_job("tick", runs.clone())).unwrap();
+    
+    assert_eq!(scheduler.trigger("tick").unwrap().next_run_at, Some(start()));
+    scheduler.run_due().await;
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert!(matches!(scheduler.trigger("missing"), Err(ComplianceError::JobNotFound { .. })));
+    assert!(matches!(scheduler.status("missing"), Err(ComplianceError::JobNotFound { .. })));
+}
+
+#[tokio::test]
+async fn jobs_are_registered_once() {
+    let (scheduler, _) = scheduler(SchedulerConfig::default());
+    let runs = Arc::new(AtomicUsize::new(0));
+    scheduler.register(counting_job("tick", runs.clone())).unwrap();
+    assert!(scheduler.register(counting_job("tick", runs)).is_err());
+    assert_eq!(scheduler.statuses().len(), 1);
+}
+
+#[tokio::test]
+async fn one_instance_holds_each_job() {
+    let clock = Arc::new(MockClock::new(start()));
+    let leases = Arc::new(MemoryLeaseStore::default());
+    let instance = |id: &str| {
+        let leader = LeaderElection::new(id, std::time::Duration::from_secs(300), leases.clone());
+        let leader = leader.with_clock(clock.clone());
+        Scheduler::new(SchedulerConfig::default())
+            .with_clock(clock.clone())
+            .with_leader(Arc::new(leader))
+    };
+    let (a, b) = (instance("a"), instance("b"));
+    let (a_runs, b_runs) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    a.register(counting_job("tick", a_runs.clone())).unwrap();
+    b.register(counting_job("tick", b_runs.clone())).unwrap();
+    
+    clock.advance(Duration::seconds(60));
+    a.run_due().await;
+    b.run_due().await;
+    assert_eq!(a.status("tick").unwrap().last_outcome, Some(JobOutcome::Succeeded));
+    assert_eq!(b.status("tick").unwrap().last_outcome, Some(JobOutcome::NotLeader));
+    assert_eq!(b.status("tick").unwrap().runs, 0);
+    
+    // Once the holder stops renewing, the lease lapses and moves over
+    clock.advance(Duration::seconds(301));
+    b.run_due().await;
+    assert_eq!(b.status("tick").unwrap().last_outcome, Some(JobOutcome::Succeeded));
+    assert_eq!((a_runs.load(Ordering::SeqCst), b_runs.load(Ordering::SeqCst)), (1, 1));
+}
@@ -0,0 +1,81 @@
+//! Payload schema versions, client pins, and downgrades for older consumers
+
+use compliance_backend::compliance::clients::ClientRegistry;
+use compliance_backend::compliance::schema_versions::{
+    check_pins, current_version, downgrade_response, downgrade_webhook, negotiate,
+};
+use compliance_backend::types::{BusinessClient, ComplianceLevel, SchemaPins};
+use compliance_backend::ComplianceError;
+use serde_json::json;
+
+const CHECK: &str = "POST /v1/accounts/{id}/check";
+
+#[test]
+fn webhooks_pinned_to_the_first_version_lose_display_text() {
+    let body = json!({ "type": "attestation.issued", "data": {}, "display": { "en": ["Verified"] } });
+    
+    let mut current = body.clone();
+    assert!(!downgrade_webhook("attestation.issued", &mut current, current_version()));
+    assert_eq!(current, body);
+    
+    let mut first = body.clone();
+    assert!(downgrade_webhook("attestation.issued", &mut first, SchemaPins::FIRST_VERSION));
+    assert!(first.get("display").is_none());
+    assert_eq!(first["type"], "attestation.issued");
+}
+
+#[test]
+fn responses_are_only_downgraded_on_endpoints_the_change_touched() {
+    let body = json!({ "policy": { "reasons": [], "display": [] }, "display": [] });
+    
+    let mut check = body.clone();
+    assert!(downgrade_response(CHECK, &mut check, SchemaPins::FIRST_VERSION));
+    assert!(check["policy"].get("display").is_none());
+    assert!(check.get("display").is_some());
+    
+    let mut other = body.clone();
+    assert!(!downgrade_response("GET /v1/usage", &mut other, SchemaPins::FIRST_VERSION));
+    assert_eq!(other, body);
+}
+
+#[test]
+fn requested_versions_win_over_endpoint_and_default_pins() {
+    let mut pins = SchemaPins::pinned(current_version());
+    pins.endpoints.insert(CHECK.to_string(), SchemaPins::FIRST_VERSION);
+    
+    assert_eq!(negotiate(None, Some(&pins), CHECK), SchemaPins::FIRST_VERSION);
+    assert_eq!(negotiate(None, Some(&pins), "GET /v1/usage"), current_version());
+    assert_eq!(negotiate(Some(current_version()), Some(&pins), CHECK), current_version());
+    assert_eq!(negotiate(None, None, CHECK), current_version());
+}
+
+#[test]
+fn pins_must_name_known_versions_and_routes() {
+    assert!(check_pins(&SchemaPins::pinned(current_version())).is_ok());
+    assert!(matches!(
+        check_pins(&SchemaPins::pinned(current_version() + 1)),
+        Err(ComplianceError::Validation { .. })
+    ));
+    
+    let mut pins = SchemaPins::default();
+    pins.endpoints.insert("/v1/accounts/{id}/check".to_string(), SchemaPins::FIRST_VERSION);
+    assert!(matches!(check_pins(&pins), Err(ComplianceError::Validation { .. })));
+}
+
+#[tokio::test]
+async fn new_clients_are_pinned_to_the_current_version_and_existing_ones_to_the_first() {
+    let registry = ClientRegistry::new();
+    let client = registry.create("Acme", None, ComplianceLevel::Standard, None, false).await.unwrap();
+    assert_eq!(client.schema.default, current_version());
+    
+    let mut stored = serde_json::to_value(&client).unwrap();
+    stored.as_object_mut().unwrap().remove("schema");
+    let existing: BusinessClient = serde_json::from_value(stored).unwrap();
+    assert_eq!(existing.schema.for_webhooks(), SchemaPins::FIRST_VERSION);
+    
+    let mut pins = SchemaPins::pinned(current_version());
+    pins.webhooks = Some(SchemaPins::FIRST_VERSION);
+    let client = registry.set_schema_pins(client.id, pins).await.unwrap();
+    assert_eq!(client.schema.for_webhooks(), SchemaPins::FIRST_VERSION);
+    assert_eq!(client.schema.for_endpoint(CHECK), current_version());
+}
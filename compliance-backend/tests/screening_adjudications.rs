@@ -0,0 +1,209 @@
+//! False-positive suppression and threshold suggestions from match adjudications
+
+use chrono::Utc;
+use compliance_backend::compliance::screening::adjudications::{
+    Adjudication, AdjudicationInput, AdjudicationStore, Verdict,
+};
+use compliance_backend::compliance::screening::attributes::SubjectAttributes;
+use compliance_backend::compliance::screening::matcher::NameMatcher;
+use compliance_backend::compliance::screening::results::ScreeningResultStore;
+use compliance_backend::compliance::screening::{EntityType, ScreenedEntity, ScreeningListStore};
+use compliance_backend::types::AccountId;
+use uuid::Uuid;
+
+fn entity(id: &str, name: &str, programs: &[&str]) -> ScreenedEntity {
+    ScreenedEntity {
+        id: id.to_string(),
+        list: "ofac_sdn".to_string(),
+        name: name.to_string(),
+        aliases: vec![],
+        entity_type: EntityType::Individual,
+        programs: programs.iter().map(|p| p.to_string()).collect(),
+        date_of_birth: None,
+        nationalities: vec![],
+        document_numbers: vec![],
+        addresses: vec![],
+    }
+}
+
+fn account(n: u8) -> AccountId {
+    AccountId::parse(&format!("0x{:040x}", n)).unwrap()
+}
+
+async fn screen(results: &ScreeningResultStore, lists: &ScreeningListStore, client_id: Uuid) -> Vec<String> {
+    results
+        .screen(
+            lists,
+            &NameMatcher::new(0.85),
+            &account(1),
+            Some(client_id),
+            "Ali Rezaei",
+            None,
+            &SubjectAttributes::default(),
+        )
+        .await
+        .matches
+        .into_iter()
+        .map(|m| m.entity_id)
+        .collect()
+}
+
+fn false_positive(entity_id: &str) -> AdjudicationInput {
+    AdjudicationInput {
+        list: "ofac_sdn".to_string(),
+        entity_id: entity_id.to_string(),
+        verdict: Verdict::FalsePositive,
+        reason: Some("different person".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn false_positives_stay_suppressed_until_the_entry_changes() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    let client_id = Uuid::new_v4();
+    lists
+        .ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaei", &["SDGT"])])
+        .await
+        .unwrap();
+    assert_eq!(screen(&results, &lists, client_id).await, ["1"]);
+    
+    let adjudication = results
+        .adjudicate(&lists, &account(1), "analyst", false_positive("1"))
+        .await
+        .unwrap();
+    assert_eq!(adjudication.client_id, Some(client_id));
+    assert!(screen(&results, &lists, client_id).await.is_empty());
+    assert_eq!(results.get(&account(1)).await.unwrap().suppressed.len(), 1);
+    
+    // An unrelated entry being added does not resurface the cleared match
+    lists
+        .ingest(
+            "ofac_sdn",
+            "v2",
+            vec![entity("1", "Ali Rezaei", &["SDGT"]), entity("2", "Maria Lopez", &["SDNTK"])],
+        )
+        .await
+        .unwrap();
+    assert!(screen(&results, &lists, client_id).await.is_empty());
+    
+    lists
+        .ingest("ofac_sdn", "v3", vec![entity("1", "Ali Rezaei", &["SDGT", "IRGC"])])
+        .await
+        .unwrap();
+    assert_eq!(screen(&results, &lists, client_id).await, ["1"]);
+}
+
+#[tokio::test]
+async fn a_later_true_hit_reinstates_a_suppressed_match() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    let client_id = Uuid::new_v4();
+    lists
+        .ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaei", &["SDGT"])])
+        .await
+        .unwrap();
+    screen(&results, &lists, client_id).await;
+    results
+        .adjudicate(&lists, &account(1), "analyst", false_positive("1"))
+        .await
+        .unwrap();
+    assert!(screen(&results, &lists, client_id).await.is_empty());
+    
+    let true_hit = AdjudicationInput {
+        verdict: Verdict::TrueHit,
+        ..false_positive("1")
+    };
+    results.adjudicate(&lists, &account(1), "officer", true_hit).await.unwrap();
+    assert_eq!(screen(&results, &lists, client_id).await, ["1"]);
+}
+
+#[tokio::test]
+async fn only_current_matches_can_be_adjudicated() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    lists
+        .ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaei", &["SDGT"])])
+        .await
+        .unwrap();
+    
+    let unscreened = results.adjudicate(&lists, &account(1), "analyst", false_positive("1")).await;
+    assert!(unscreened.is_err());
+    
+    screen(&results, &lists, Uuid::new_v4()).await;
+    let unmatched = results.adjudicate(&lists, &account(1), "analyst", false_positive("2")).await;
+    assert!(unmatched.is_err());
+}
+
+#[tokio::test]
+async fn adopted_thresholds_apply_to_the_clients_accounts() {
+    let lists = ScreeningListStore::new();
+    let results = ScreeningResultStore::new();
+    let client_id = Uuid::new_v4();
+    lists
+        .ingest("ofac_sdn", "v1", vec![entity("1", "Ali Rezaie", &["SDGT"])])
+        .await
+        .unwrap();
+    assert_eq!(screen(&results, &lists, client_id).await, ["1"]);
+    
+    results.adjudications().set_threshold(client_id, Some(1.0)).await.unwrap();
+    assert!(screen(&results, &lists, client_id).await.is_empty());
+    assert!(results.adjudications().set_threshold(client_id, Some(1.5)).await.is_err());
+}
+
+fn adjudication(client_id: Uuid, n: u8, score: f64, verdict: Verdict) -> Adjudication {
+    Adjudication {
+        id: Uuid::new_v4(),
+        account_id: account(n),
+        client_id: Some(client_id),
+        list: "ofac_sdn".to_string(),
+        entity: entity(&n.to_string(), "Ali Rezaei", &["SDGT"]),
+        matched_name: "Ali Rezaei".to_string(),
+        score,
+        verdict,
+        analyst: "analyst".to_string(),
+        reason: None,
+        adjudicated_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn thresholds_are_suggested_above_cleared_false_positives() {
+    let store = AdjudicationStore::new();
+    let client_id = Uuid::new_v4();
+    for n in 0..18 {
+        let score = 0.86 + f64::from(n % 4) / 100.0;
+        store.record(adjudication(client_id, n, score, Verdict::FalsePositive)).await;
+    }
+    store.record(adjudication(client_id, 18, 0.97, Verdict::TrueHit)).await;
+    store.record(adjudication(client_id, 19, 0.99, Verdict::TrueHit)).await;
+    
+    let suggestions = store.suggestions(0.85).await;
+    assert_eq!(suggestions.len(), 1);
+    let suggestion = &suggestions[0];
+    assert_eq!(suggestion.client_id, client_id);
+    assert!((suggestion.suggested_threshold - 0.9).abs() < 1e-9);
+    assert_eq!(suggestion.true_hits, 2);
+    assert_eq!(suggestion.false_positives_avoided, 18);
+}
+
+#[tokio::test]
+async fn suggestions_never_cut_into_true_hits() {
+    let store = AdjudicationStore::new();
+    let client_id = Uuid::new_v4();
+    for n in 0..19 {
+        store.record(adjudication(client_id, n, 0.95, Verdict::FalsePositive)).await;
+    }
+    store.record(adjudication(client_id, 19, 0.88, Verdict::TrueHit)).await;
+    
+    assert!(store.suggestions(0.85).await.is_empty());
+}
+
+#[tokio::test]
+async fn clients_with_few_adjudications_get_no_suggestion() {
+    let store = AdjudicationStore::new();
+    let client_id = Uuid::new_v4();
+    store.record(adjudication(client_id, 0, 0.87, Verdict::FalsePositive)).await;
+    
+    assert!(store.suggestions(0.85).await.is_empty());
+}
@@ -0,0 +1,137 @@
+//! Date-of-birth and secondary attribute weighting in screening
+
+use compliance_backend::compliance::screening::attributes::{Attribute, AttributeOutcome, SubjectAttributes};
+use compliance_backend::compliance::screening::matcher::NameMatcher;
+use compliance_backend::compliance::screening::search::{self, SearchRequest};
+use compliance_backend::compliance::screening::{EntityType, ScreenedEntity, ScreeningListStore};
+
+fn entity(id: &str, name: &str, date_of_birth: &str) -> ScreenedEntity {
+    ScreenedEntity {
+        id: id.to_string(),
+        list: "ofac_sdn".to_string(),
+        name: name.to_string(),
+        aliases: vec![],
+        entity_type: EntityType::Individual,
+        programs: vec!["SDGT".to_string()],
+        date_of_birth: Some(date_of_birth.to_string()),
+        nationalities: vec!["IR".to_string()],
+        document_numbers: vec!["A1234567".to_string()],
+        addresses: vec![],
+    }
+}
+
+fn attributes(date_of_birth: &str) -> SubjectAttributes {
+    SubjectAttributes {
+        date_of_birth: Some(date_of_birth.to_string()),
+        ..Default::default()
+    }
+}
+
+fn request(name: &str, attributes: SubjectAttributes) -> SearchRequest {
+    SearchRequest {
+        name: name.to_string(),
+        language: None,
+        attributes,
+        lists: None,
+        entity_type: None,
+        min_score: None,
+        limit: None,
+    }
+}
+
+#[test]
+fn matching_date_of_birth_raises_the_score() {
+    let matcher = NameMatcher::new(0.85);
+    let name = matcher.score("Ali Rezaei", "Ali Rezaie");
+    let result = matcher.composite(name.clone(), &attributes("1970-03-15"), &entity("1", "Ali Rezaie", "1970-03-15"));
+    
+    assert!(result.score > name.score);
+    assert_eq!(result.attributes.len(), 1);
+    assert_eq!(result.attributes[0].attribute, Attribute::DateOfBirth);
+    assert_eq!(result.attributes[0].outcome, AttributeOutcome::Match);
+}
+
+#[test]
+fn partial_dates_compare_at_the_shared_precision() {
+    let matcher = NameMatcher::new(0.85);
+    let name = matcher.score("Ali Rezaei", "Ali Rezaei");
+    let result = matcher.composite(name, &attributes("1970-03-15"), &entity("1", "Ali Rezaei", "1970"));
+    assert_eq!(result.attributes[0].outcome, AttributeOutcome::Partial);
+    
+    let name = matcher.score("Ali Rezaei", "Ali Rezaei");
+    let result = matcher.composite(name, &attributes("1971"), &entity("1", "Ali Rezaei", "1970-03"));
+    assert_eq!(result.attributes[0].outcome, AttributeOutcome::WithinTolerance);
+    assert_eq!(result.attributes[0].contribution, 0.0);
+}
+
+#[test]
+fn distant_date_of_birth_lowers_the_score() {
+    let matcher = NameMatcher::new(0.85);
+    let name = matcher.score("Ali Rezaei", "Ali Rezaei");
+    let result = matcher.composite(name, &attributes("1990-01-01"), &entity("1", "Ali Rezaei", "1955-06-02"));
+    
+    assert!(result.score < 1.0);
+    assert!((result.name_score - 1.0).abs() < 1e-9);
+    assert_eq!(result.attributes[0].outcome, AttributeOutcome::Mismatch);
+    assert!(result.attributes[0].contribution < 0.0);
+}
+
+#[test]
+fn document_numbers_ignore_separators() {
+    let matcher = NameMatcher::new(0.85);
+    let subject = SubjectAttributes {
+        document_numbers: vec!["a 123-4567".to_string()],
+        ..Default::default()
+    };
+    let name = matcher.score("Ali Rezaei", "Ali Rezaie");
+    let result = matcher.composite(name, &subject, &entity("1", "Ali Rezaie", "1970"));
+    
+    assert_eq!(result.attributes[0].attribute, Attribute::DocumentNumber);
+    assert_eq!(result.attributes[0].outcome, AttributeOutcome::Match);
+}
+
+#[test]
+fn attributes_missing_on_either_side_are_not_compared() {
+    let matcher = NameMatcher::new(0.85);
+    let name = matcher.score("Ali Rezaei", "Ali Rezaei");
+    let result = matcher.composite(name, &SubjectAttributes::default(), &entity("1", "Ali Rezaei", "1970"));
+    
+    assert!(result.attributes.is_empty());
+    assert!((result.score - result.name_score).abs() < 1e-9);
+}
+
+#[test]
+fn malformed_attributes_are_rejected() {
+    assert!(attributes("1970-03-15").validate().is_ok());
+    assert!(attributes("1970").validate().is_ok());
+    assert!(attributes("15/03/1970").validate().is_err());
+    assert!(attributes("1970-13").validate().is_err());
+    assert!(attributes("1970--15").validate().is_err());
+    
+    let nationalities = SubjectAttributes {
+        nationalities: vec!["IRN".to_string()],
+        ..Default::default()
+    };
+    assert!(nationalities.validate().is_err());
+}
+
+#[tokio::test]
+async fn search_suppresses_hits_ruled_out_by_attributes() {
+    let store = ScreeningListStore::new();
+    store
+        .ingest(
+            "ofac_sdn",
+            "2024-01",
+            vec![entity("1", "Ali Rezaei", "1955-06-02"), entity("2", "Ali Rezaei", "1990")],
+        )
+        .await
+        .unwrap();
+    let matcher = NameMatcher::new(0.9);
+    
+    let response = search::search(&store, &matcher, &request("Ali Rezaei", attributes("1990-01-01")), vec![]).await;
+    
+    assert_eq!(response.suppressed, 1);
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.hits[0].entity.id, "2");
+    assert_eq!(response.hits[0].attributes[0].outcome, AttributeOutcome::Partial);
+}
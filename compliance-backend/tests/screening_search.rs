@@ -0,0 +1,199 @@
+//! Fuzzy and phonetic name search over screening lists
+
+use chrono::Utc;
+use compliance_backend::compliance::screening::matcher::{
+    jaro_winkler, metaphone, normalize, soundex, MatchMethod, NameMatcher,
+};
+use compliance_backend::compliance::screening::search::{self, HitSource, SearchRequest};
+use compliance_backend::compliance::screening::{EntityType, ScreenedEntity, ScreeningListStore};
+use compliance_backend::compliance::watchlists::{WatchlistEntry, WatchlistHit, WatchlistKind};
+use uuid::Uuid;
+
+fn entity(id: &str, list: &str, name: &str, entity_type: EntityType) -> ScreenedEntity {
+    ScreenedEntity {
+        id: id.to_string(),
+        list: list.to_string(),
+        name: name.to_string(),
+        aliases: vec![],
+        entity_type,
+        programs: vec!["SDGT".to_string()],
+        date_of_birth: None,
+        nationalities: vec![],
+        document_numbers: vec![],
+        addresses: vec![],
+    }
+}
+
+fn request(name: &str) -> SearchRequest {
+    SearchRequest {
+        name: name.to_string(),
+        language: None,
+        attributes: Default::default(),
+        lists: None,
+        entity_type: None,
+        min_score: None,
+        limit: None,
+    }
+}
+
+async fn store() -> ScreeningListStore {
+    let store = ScreeningListStore::new();
+    store
+        .ingest(
+            "ofac_sdn",
+            "2025-06-01",
+            vec![
+                entity("1", "ofac_sdn", "John Smith", EntityType::Individual),
+                entity("2", "ofac_sdn", "Maria Garcia", EntityType::Individual),
+                entity("3", "ofac_sdn", "Smith Shipping", EntityType::Organization),
+            ],
+        )
+        .await
+        .unwrap();
+    store
+        .ingest(
+            "un_consolidated",
+            "2025-06-01",
+            vec![entity("UN-1", "un_consolidated", "Jon Smyth", EntityType::Individual)],
+        )
+        .await
+        .unwrap();
+    store
+}
+
+#[test]
+fn soundex_follows_the_american_rules() {
+    assert_eq!(soundex("Robert"), "R163");
+    assert_eq!(soundex("Rupert"), "R163");
+    assert_eq!(soundex("Tymczak"), "T522");
+    assert_eq!(soundex("Pfister"), "P236");
+    
+    // 'h' does not separate letters with the same code
+    assert_eq!(soundex("Ashcraft"), "A261");
+    assert_eq!(soundex("Lee"), "L000");
+    assert_eq!(soundex(""), "");
+}
+
+#[test]
+fn metaphone_equates_spelling_variants() {
+    assert_eq!(metaphone("Smith"), "SM0");
+    assert_eq!(metaphone("Smith"), metaphone("Smyth"));
+    assert_eq!(metaphone("Philip"), metaphone("Filip"));
+    assert_eq!(metaphone("Knight"), "NT");
+    assert_eq!(metaphone(""), "");
+}
+
+#[test]
+fn jaro_winkler_rewards_shared_prefixes() {
+    assert!((jaro_winkler("martha", "marhta") - 0.9611).abs() < 1e-4);
+    assert!(jaro_winkler("martha", "marhta") > jaro_winkler("amrtha", "marhta"));
+    assert_eq!(jaro_winkler("smith", "smith"), 1.0);
+    assert_eq!(jaro_winkler("", ""), 1.0);
+    assert_eq!(jaro_winkler("smith", ""), 0.0);
+    assert_eq!(jaro_winkler("abc", "xyz"), 0.0);
+}
+
+#[test]
+fn names_match_regardless_of_order_case_and_punctuation() {
+    let matcher = NameMatcher::new(0.85);
+    assert_eq!(normalize("  SMITH,  John "), "smith john");
+    assert_eq!(normalize("Dr. John Smith"), "john smith");
+    
+    let result = matcher.matches("Smith John", "JOHN SMITH").unwrap();
+    assert_eq!(result.score, 1.0);
+    assert_eq!(result.method, MatchMethod::Exact);
+}
+
+#[test]
+fn misspelled_names_match_and_unrelated_names_do_not() {
+    let matcher = NameMatcher::new(0.85);
+    let result = matcher.matches("Jon Smyth", "John Smith").unwrap();
+    assert!(result.score < 1.0);
+    assert_ne!(result.method, MatchMethod::Exact);
+    
+    assert!(matcher.matches("Maria Garcia", "John Smith").is_none());
+    assert!(matcher.score("John", "John Smith").score < 1.0);
+    assert_eq!(matcher.score("", "John Smith").score, 0.0);
+}
+
+#[tokio::test]
+async fn search_ranks_hits_across_lists() {
+    let store = store().await;
+    let response = search::search(&store, &NameMatcher::new(0.85), &request("John Smith"), vec![]).await;
+    
+    assert_eq!(response.query, "John Smith");
+    let ids: Vec<_> = response.hits.iter().map(|hit| hit.entity.id.as_str()).collect();
+    assert_eq!(ids[0], "1");
+    assert!(ids.contains(&"UN-1"));
+    assert!(!ids.contains(&"2"));
+    assert!(response.hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    
+    let HitSource::Official { list, version } = &response.hits[0].source else {
+        panic!("expected an official list hit");
+    };
+    assert_eq!((list.as_str(), version.as_str()), ("ofac_sdn", "2025-06-01"));
+}
+
+#[tokio::test]
+async fn search_filters_by_list_entity_type_and_limit() {
+    let store = store().await;
+    let matcher = NameMatcher::new(0.85);
+    
+    let only_un = SearchRequest {
+        lists: Some(vec!["un_consolidated".to_string()]),
+        ..request("John Smith")
+    };
+    let response = search::search(&store, &matcher, &only_un, vec![]).await;
+    assert!(response.hits.iter().all(|hit| hit.entity.list == "un_consolidated"));
+    assert_eq!(response.hits.len(), 1);
+    
+    let organizations = SearchRequest {
+        entity_type: Some(EntityType::Organization),
+        min_score: Some(0.0),
+        ..request("Smith Shipping")
+    };
+    let response = search::search(&store, &matcher, &organizations, vec![]).await;
+    assert!(response.hits.iter().all(|hit| hit.entity.entity_type == EntityType::Organization));
+    assert_eq!(response.hits[0].entity.id, "3");
+    
+    let limited = SearchRequest {
+        limit: Some(1),
+        ..request("John Smith")
+    };
+    assert_eq!(search::search(&store, &matcher, &limited, vec![]).await.hits.len(), 1);
+}
+
+#[tokio::test]
+async fn watchlist_hits_are_merged_with_their_provenance() {
+    let store = store().await;
+    let client_id = Uuid::new_v4();
+    let entry = WatchlistEntry {
+        id: Uuid::new_v4(),
+        client_id,
+        kind: WatchlistKind::BlockedIdentity,
+        value: "John Smith".to_string(),
+        reason: Some("chargeback fraud".to_string()),
+        created_by: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        expires_at: None,
+    };
+    let hit = WatchlistHit {
+        entry: entry.clone(),
+        score: 1.0,
+        method: MatchMethod::Exact,
+    };
+    
+    let response = search::search(&store, &NameMatcher::new(0.85), &request("John Smith"), vec![hit]).await;
+    let merged = response
+        .hits
+        .iter()
+        .find(|hit| matches!(hit.source, HitSource::ClientWatchlist { .. }))
+        .unwrap();
+    assert_eq!(merged.entity.id, entry.id.to_string());
+    assert_eq!(merged.entity.programs, ["chargeback fraud"]);
+    assert!(matches!(
+        merged.source,
+        HitSource::ClientWatchlist { client_id: c, entry_id } if c == client_id && entry_id == entry.id
+    ));
+}
@@ -0,0 +1,138 @@
+//! Signing through backends that hold the private key, and EdDSA JWTs
+
+use compliance_backend::config::SigningBackendConfig;
+use compliance_backend::crypto::kms::ed25519_from_spki;
+use compliance_backend::crypto::{AttestationSigner, SigningBackend, TrustedKeys};
+use compliance_backend::{Config, Result};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// Backend standing in for an HSM, optionally signing with the wrong key
+struct FakeHsm {
+    key: SigningKey,
+    signs_with: SigningKey,
+}
+
+impl SigningBackend for FakeHsm {
+    fn verifying_key(&self) -> Result<VerifyingKey> {
+        Ok(self.key.verifying_key())
+    }
+    
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.signs_with.sign(message).to_bytes().to_vec())
+    }
+}
+
+fn key(byte: u8) -> SigningKey {
+    SigningKey::from_bytes(&[byte; 32])
+}
+
+fn violated_fields(config: &Config) -> Vec<String> {
+    match config.validate() {
+        Ok(()) => vec![],
+        Err(violations) => violations.0.into_iter().map(|v| v.field).collect(),
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: i64,
+}
+
+#[test]
+fn backend_signatures_verify_under_the_backend_key() {
+    let backend = FakeHsm {
+        key: key(1),
+        signs_with: key(1),
+    };
+    let signer = AttestationSigner::from_backend("hsm-1", Box::new(backend)).unwrap();
+    assert_eq!(signer.verifying_key(), key(1).verifying_key());
+    
+    let mut trusted = TrustedKeys::new();
+    trusted.insert("hsm-1", signer.verifying_key());
+    let signature = signer.sign(b"attestation").unwrap();
+    assert!(trusted.verify("hsm-1", b"attestation", &signature).is_ok());
+}
+
+#[test]
+fn signatures_under_another_key_are_not_handed_out() {
+    let backend = FakeHsm {
+        key: key(1),
+        signs_with: key(2),
+    };
+    let signer = AttestationSigner::from_backend("hsm-1", Box::new(backend)).unwrap();
+    assert!(signer.sign(b"attestation").is_err());
+}
+
+#[test]
+fn jwts_round_trip_through_trusted_keys() {
+    let signer = AttestationSigner::generate("jwt-1");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert("jwt-1", signer.verifying_key());
+    let claims = Claims {
+        sub: "operator".to_string(),
+        exp: 1_900_000_000,
+    };
+    
+    let token = signer.sign_jwt(&claims).unwrap();
+    assert_eq!(token.split('.').count(), 3);
+    assert_eq!(trusted.verify_jwt::<Claims>(&token).unwrap(), claims);
+    
+    let (header, rest) = token.split_once('.').unwrap();
+    let (_, signature) = rest.split_once('.').unwrap();
+    let forged_claims = base64_url(br#"{"sub":"admin","exp":1900000000}"#);
+    let forged = format!("{}.{}.{}", header, forged_claims, signature);
+    assert!(trusted.verify_jwt::<Claims>(&forged).is_err());
+    
+    let unsigned = format!("{}.{}.", base64_url(br#"{"alg":"none","kid":"jwt-1"}"#), forged_claims);
+    assert!(trusted.verify_jwt::<Claims>(&unsigned).is_err());
+    assert!(TrustedKeys::new().verify_jwt::<Claims>(&token).is_err());
+}
+
+#[test]
+fn kms_public_keys_are_read_from_spki() {
+    let public = key(3).verifying_key();
+    let mut der = hex::decode("302a300506032b6570032100").unwrap();
+    der.extend_from_slice(public.as_bytes());
+    assert_eq!(ed25519_from_spki(&der).unwrap(), public);
+    
+    assert!(ed25519_from_spki(&der[1..]).is_err());
+    der[7] = 0x71;
+    assert!(ed25519_from_spki(&der).is_err());
+}
+
+#[test]
+fn backends_are_validated() {
+    let mut config = Config::default();
+    config.security.signing_backend = SigningBackendConfig::GcpKms {
+        key_version: "projects/p/locations/global/keyRings/r/cryptoKeys/k".to_string(),
+        access_token: None,
+    };
+    config.security.signing_key_seed = Some("00".repeat(32));
+    let fields = violated_fields(&config);
+    assert!(fields.contains(&"security.signing_backend.key_version".to_string()));
+    assert!(fields.contains(&"security.signing_key_seed".to_string()));
+    
+    config.security.signing_backend = SigningBackendConfig::GcpKms {
+        key_version: "projects/p/locations/global/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1".to_string(),
+        access_token: None,
+    };
+    config.security.signing_key_seed = None;
+    let fields = violated_fields(&config);
+    assert!(!fields.iter().any(|field| field.starts_with("security.signing")));
+    
+    config.security.signing_backend = SigningBackendConfig::Pkcs11 {
+        module_path: "/usr/lib/softhsm/libsofthsm2.so".into(),
+        token_label: "attestation".to_string(),
+        key_label: "signer-1".to_string(),
+        pin: String::new(),
+    };
+    assert!(violated_fields(&config).contains(&"security.signing_backend.pin".to_string()));
+}
+
+fn base64_url(bytes: &[u8]) -> String {
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    URL_SAFE_NO_PAD.encode(bytes)
+}
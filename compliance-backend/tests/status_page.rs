@@ -0,0 +1,97 @@
+//! Public status page aggregation, caching and rate limiting
+
+use chrono::{Duration, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::breaker::ProviderBreakers;
+use compliance_backend::compliance::screening::ScreeningListStore;
+use compliance_backend::compliance::status_page::{ServiceState, StatusPage};
+use compliance_backend::config::{ProviderResilienceConfig, StatusPageConfig};
+use compliance_backend::ComplianceError;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+fn config() -> StatusPageConfig {
+    StatusPageConfig {
+        requests_per_minute: 2,
+        refresh_secs: 60,
+        latency_window_secs: 3600,
+        min_latency_samples: 10,
+        latency_rounding_ms: 50,
+        list_stale_after_secs: 86_400,
+        ..StatusPageConfig::default()
+    }
+}
+
+fn breakers() -> ProviderBreakers {
+    ProviderBreakers::new(&ProviderResilienceConfig::default())
+}
+
+#[tokio::test]
+async fn latency_is_withheld_until_enough_verifications() {
+    let clock = Arc::new(MockClock::new(Utc::now()));
+    let page = StatusPage::new(config()).with_clock(clock.clone());
+    for _ in 0..9 {
+        page.record_verification(std::time::Duration::from_millis(120)).await;
+    }
+    let status = page.status(&ScreeningListStore::new(), &breakers()).await;
+    assert!(status.verification_latency.is_none());
+    assert_eq!(status.state, ServiceState::Operational);
+    
+    page.record_verification(std::time::Duration::from_millis(120)).await;
+    clock.advance(Duration::seconds(61));
+    let latency = page
+        .status(&ScreeningListStore::new(), &breakers())
+        .await
+        .verification_latency
+        .unwrap();
+    assert_eq!(latency.average_ms, 100);
+    assert_eq!(latency.window_secs, 3600);
+}
+
+#[tokio::test]
+async fn status_is_served_from_cache_within_the_refresh_period() {
+    let clock = Arc::new(MockClock::new(Utc::now()));
+    let page = StatusPage::new(config()).with_clock(clock.clone());
+    let lists = ScreeningListStore::new();
+    let first = page.status(&lists, &breakers()).await;
+    
+    lists.ingest("ofac_sdn", "2025-06-01", vec![]).await.unwrap();
+    clock.advance(Duration::seconds(30));
+    let cached = page.status(&lists, &breakers()).await;
+    assert_eq!(cached.generated_at, first.generated_at);
+    assert!(cached.lists.is_empty());
+    
+    clock.advance(Duration::seconds(31));
+    let refreshed = page.status(&lists, &breakers()).await;
+    assert_eq!(refreshed.lists.len(), 1);
+    assert_eq!(refreshed.lists[0].version, "2025-06-01");
+    assert!(!refreshed.lists[0].stale);
+}
+
+#[tokio::test]
+async fn stale_lists_degrade_the_service() {
+    let clock = Arc::new(MockClock::new(Utc::now()));
+    let page = StatusPage::new(config()).with_clock(clock.clone());
+    let lists = ScreeningListStore::new();
+    lists.ingest("un_consolidated", "v1", vec![]).await.unwrap();
+    
+    clock.advance(Duration::days(2));
+    let status = page.status(&lists, &breakers()).await;
+    assert!(status.lists[0].stale);
+    assert_eq!(status.state, ServiceState::Degraded);
+}
+
+#[tokio::test]
+async fn callers_are_limited_per_minute() {
+    let clock = Arc::new(MockClock::new(Utc::now()));
+    let page = StatusPage::new(config()).with_clock(clock.clone());
+    let caller: IpAddr = "203.0.113.7".parse().unwrap();
+    
+    page.admit(Some(caller)).await.unwrap();
+    page.admit(Some(caller)).await.unwrap();
+    assert!(matches!(page.admit(Some(caller)).await, Err(ComplianceError::RateLimitExceeded)));
+    page.admit(Some("203.0.113.8".parse().unwrap())).await.unwrap();
+    
+    clock.advance(Duration::seconds(61));
+    page.admit(Some(caller)).await.unwrap();
+}
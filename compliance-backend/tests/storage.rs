@@ -0,0 +1,76 @@
+//! Services persisting through swappable repositories
+
+use chrono::{Duration, Utc};
+use compliance_backend::audit::{AuditLog, GENESIS_HASH};
+use compliance_backend::clock::system_clock;
+use compliance_backend::compliance::approvals::{ApprovalService, ApprovalStatus, OverrideAction};
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::storage::memory::{MemoryAttestationRepo, MemoryAuditRepo, MemoryCaseRepo};
+use compliance_backend::storage::{AttestationRepo, AuditRepo};
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+#[tokio::test]
+async fn reopened_audit_log_continues_the_chain() {
+    let repo = Arc::new(MemoryAuditRepo::default());
+    let first = AuditLog::open(repo.clone(), system_clock()).await.unwrap();
+    let entry = first.record("tester", "test.first", None, serde_json::json!({})).await;
+    assert_eq!(entry.prev_hash, GENESIS_HASH);
+    
+    let reopened = AuditLog::open(repo.clone(), system_clock()).await.unwrap();
+    let next = reopened.record("tester", "test.second", None, serde_json::json!({})).await;
+    assert_eq!(next.sequence, 2);
+    assert_eq!(next.prev_hash, entry.hash);
+    assert!(reopened.verify_chain().await.unwrap().valid);
+}
+
+#[tokio::test]
+async fn audit_repo_rejects_entries_out_of_sequence() {
+    let repo = MemoryAuditRepo::default();
+    let log = AuditLog::new();
+    let entry = log.record("tester", "test.first", None, serde_json::json!({})).await;
+    
+    repo.append(&entry).await.unwrap();
+    assert!(repo.append(&entry).await.is_err());
+}
+
+#[tokio::test]
+async fn event_stores_sharing_a_repo_see_each_others_events() {
+    let repo = Arc::new(MemoryAttestationRepo::default());
+    let writer = AttestationEventStore::with_repo(repo.clone(), system_clock());
+    let reader = AttestationEventStore::with_repo(repo.clone(), system_clock());
+    
+    let recorded = writer.append(&account(), AttestationEvent::Expired).await.unwrap();
+    assert_eq!(recorded.sequence, 1);
+    assert_eq!(reader.events(&account(), None).await.unwrap().len(), 1);
+    assert_eq!(repo.last_sequence(&account()).await.unwrap(), 1);
+    
+    assert!(repo.append(&recorded).await.is_err());
+}
+
+#[tokio::test]
+async fn approval_cases_persist_through_the_repo() {
+    let repo = Arc::new(MemoryCaseRepo::default());
+    let approvals = ApprovalService::with_repo(repo.clone());
+    let proposed = approvals
+        .propose(
+            OverrideAction::SanctionsClearance { account_id: account() },
+            "false positive",
+            "alice",
+            Some(Utc::now() + Duration::days(7)),
+            None,
+            Duration::days(30),
+        )
+        .await
+        .unwrap();
+    
+    let restarted = ApprovalService::with_repo(repo);
+    assert!(restarted.approve(proposed.id, "alice", None).await.is_err());
+    let approved = restarted.approve(proposed.id, "bob", None).await.unwrap();
+    assert_eq!(approved.status, ApprovalStatus::Approved);
+    assert!(restarted.list(Some(ApprovalStatus::Pending)).await.unwrap().is_empty());
+}
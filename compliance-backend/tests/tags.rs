@@ -0,0 +1,141 @@
+//! Client-scoped tags on attestations, cases, and accounts
+
+use chrono::{TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::tags::{TagFilter, TagService, TagUpdate, TaggedKind, Tags, TAGS_CHANGED_EVENT};
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::storage::OutboxRepo;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn tag_service() -> (TagService, Arc<MemoryStore>) {
+    let store = Arc::new(MemoryStore::default());
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()));
+    (TagService::new(store.clone()).with_clock(clock), store)
+}
+
+fn tags(pairs: &[(&str, &str)]) -> Tags {
+    pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+fn set(pairs: &[(&str, &str)]) -> TagUpdate {
+    TagUpdate {
+        set: tags(pairs),
+        remove: vec![],
+    }
+}
+
+fn account(n: u32) -> String {
+    format!("0x{:030x}", n)
+}
+
+#[tokio::test]
+async fn changes_are_sent_to_the_client_webhook() {
+    let (service, store) = tag_service();
+    let client = Uuid::new_v4();
+    let id = account(1);
+    
+    service
+        .update(client, TaggedKind::Account, &id, set(&[("product", "wallet"), ("campaign", "spring")]))
+        .await
+        .unwrap();
+    let update = TagUpdate {
+        set: tags(&[("product", "card")]),
+        remove: vec!["campaign".to_string()],
+    };
+    let record = service.update(client, TaggedKind::Account, &id, update).await.unwrap();
+    assert_eq!(record.tags, tags(&[("product", "card")]));
+    
+    // Setting a tag to the value it has is not a change
+    service.update(client, TaggedKind::Account, &id, set(&[("product", "card")])).await.unwrap();
+    
+    let messages = store.outbox.pending(10).await.unwrap();
+    assert_eq!(messages.len(), 2);
+    assert!(messages.iter().all(|m| m.event_type == TAGS_CHANGED_EVENT && m.client_id == client));
+    assert_eq!(messages[1].payload["changed"]["product"], "card");
+    assert_eq!(messages[1].payload["removed"][0], "campaign");
+}
+
+#[tokio::test]
+async fn tags_are_scoped_to_the_client() {
+    let (service, _) = tag_service();
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let id = account(2);
+    
+    service.update(a, TaggedKind::Account, &id, set(&[("segment", "retail")])).await.unwrap();
+    service.update(b, TaggedKind::Account, &id, set(&[("segment", "vip")])).await.unwrap();
+    
+    assert_eq!(service.get(a, TaggedKind::Account, &id).await.unwrap(), tags(&[("segment", "retail")]));
+    assert_eq!(service.get(b, TaggedKind::Account, &id).await.unwrap(), tags(&[("segment", "vip")]));
+    assert!(service.get(a, TaggedKind::Attestation, &Uuid::new_v4().to_string()).await.unwrap().is_empty());
+    
+    let filter = TagFilter::parse("segment:vip").unwrap();
+    assert!(service.list(a, TaggedKind::Account, &filter).await.is_empty());
+    assert_eq!(service.list(b, TaggedKind::Account, &filter).await.len(), 1);
+}
+
+#[tokio::test]
+async fn account_ids_are_canonical() {
+    let (service, _) = tag_service();
+    let client = Uuid::new_v4();
+    let id = format!("0X{:030X}", 0xabc);
+    
+    let record = service.update(client, TaggedKind::Account, &id, set(&[("tier", "1")])).await.unwrap();
+    assert_eq!(record.id, format!("0x{:030x}", 0xabc));
+    assert_eq!(service.get(client, TaggedKind::Account, &record.id).await.unwrap(), tags(&[("tier", "1")]));
+}
+
+#[tokio::test]
+async fn list_endpoints_keep_matching_records() {
+    let (service, _) = tag_service();
+    let client = Uuid::new_v4();
+    let cases: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    service
+        .update(client, TaggedKind::Case, &cases[0].to_string(), set(&[("line", "payments"), ("q", "3")]))
+        .await
+        .unwrap();
+    service
+        .update(client, TaggedKind::Case, &cases[1].to_string(), set(&[("line", "lending")]))
+        .await
+        .unwrap();
+    
+    let filter = TagFilter::parse("line:payments,q").unwrap();
+    let mut kept = cases.clone();
+    service
+        .retain(client, TaggedKind::Case, Some(&filter), &mut kept, |case| case.to_string())
+        .await;
+    assert_eq!(kept, vec![cases[0]]);
+    
+    let filter = TagFilter::parse("line").unwrap();
+    let mut kept = cases.clone();
+    service
+        .retain(client, TaggedKind::Case, Some(&filter), &mut kept, |case| case.to_string())
+        .await;
+    assert_eq!(kept, cases[..2].to_vec());
+    
+    let mut kept = cases.clone();
+    service.retain(client, TaggedKind::Case, None, &mut kept, |case| case.to_string()).await;
+    assert_eq!(kept, cases);
+}
+
+#[tokio::test]
+async fn malformed_tags_are_rejected() {
+    let (service, store) = tag_service();
+    let client = Uuid::new_v4();
+    let id = account(3);
+    
+    assert!(service.update(client, TaggedKind::Account, &id, set(&[("Product", "x")])).await.is_err());
+    assert!(service.update(client, TaggedKind::Account, &id, set(&[("a:b", "x")])).await.is_err());
+    assert!(service.update(client, TaggedKind::Account, &id, set(&[("note", "a\nb")])).await.is_err());
+    assert!(service.update(client, TaggedKind::Attestation, "not-a-uuid", set(&[("a", "b")])).await.is_err());
+    assert!(service.update(client, TaggedKind::Account, "0x12", set(&[("a", "b")])).await.is_err());
+    
+    let many: Vec<(String, String)> = (0..51).map(|i| (format!("k{}", i), "v".to_string())).collect();
+    let update = TagUpdate {
+        set: many.into_iter().collect(),
+        remove: vec![],
+    };
+    assert!(service.update(client, TaggedKind::Account, &id, update).await.is_err());
+    assert!(TagFilter::parse("Bad Key:1").is_err());
+    assert!(store.outbox.pending(10).await.unwrap().is_empty());
+}
@@ -0,0 +1,188 @@
+//! t-of-n signing of attestations with partial signature collection
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::threshold_signing::{
+    signing_message, SigningRequestStatus, ThresholdSigningService,
+};
+use compliance_backend::config::{ComplianceConfig, LevelSigningPolicy};
+use compliance_backend::crypto::{
+    AttestationSigner, PartialSignature, ThresholdPolicy, ThresholdSignature, TrustedKeys,
+};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, ComplianceAttestation, ComplianceLevel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn new_attestation() -> ComplianceAttestation {
+    let account_id = AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap();
+    common::attestation(&account_id, Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap(), Duration::days(92))
+}
+
+fn policy(threshold: usize, signers: &[&str]) -> ThresholdPolicy {
+    ThresholdPolicy {
+        threshold,
+        signers: signers.iter().map(|signer| signer.to_string()).collect(),
+    }
+}
+
+fn partial(signer: &AttestationSigner, message: &[u8]) -> PartialSignature {
+    PartialSignature {
+        key_id: signer.key_id().to_string(),
+        signature: hex::encode(signer.sign(message).unwrap()),
+    }
+}
+
+struct Fixture {
+    service: ThresholdSigningService,
+    clock: Arc<MockClock>,
+    automated: AttestationSigner,
+    officer: AttestationSigner,
+}
+
+fn fixture() -> Fixture {
+    let automated = AttestationSigner::generate("automated");
+    let officer = AttestationSigner::generate("officer-hsm");
+    let mut trusted = TrustedKeys::new();
+    trusted.insert("automated", automated.verifying_key());
+    trusted.insert("officer-hsm", officer.verifying_key());
+    
+    let mut config = ComplianceConfig::default();
+    config.attestation.threshold_signing.policies = vec![LevelSigningPolicy {
+        min_level: ComplianceLevel::InstitutionalGrade,
+        policy: policy(2, &["automated", "officer-hsm"]),
+    }];
+    config.attestation.threshold_signing.timeout_secs = 3600;
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()));
+    let service =
+        ThresholdSigningService::new(Arc::new(LiveConfig::new(config)), Arc::new(trusted)).with_clock(clock.clone());
+    Fixture {
+        service,
+        clock,
+        automated,
+        officer,
+    }
+}
+
+#[test]
+fn policies_must_be_satisfiable() {
+    assert!(policy(2, &["a", "b"]).validate().is_ok());
+    assert!(policy(0, &["a"]).validate().is_err());
+    assert!(policy(3, &["a", "b"]).validate().is_err());
+    assert!(policy(1, &["a", "a"]).validate().is_err());
+    assert!(policy(1, &[" "]).validate().is_err());
+}
+
+#[test]
+fn combined_signatures_need_the_threshold_of_distinct_signers() {
+    let a = AttestationSigner::generate("a");
+    let b = AttestationSigner::generate("b");
+    let outsider = AttestationSigner::generate("outsider");
+    let mut trusted = TrustedKeys::new();
+    for signer in [&a, &b, &outsider] {
+        trusted.insert(signer.key_id(), signer.verifying_key());
+    }
+    let policy = policy(2, &["a", "b"]);
+    let message = b"attestation";
+    
+    let combined = ThresholdSignature::combine(&policy, &[partial(&b, message), partial(&a, message)]).unwrap();
+    assert_eq!(combined.signatures[0].key_id, "a");
+    assert!(combined.verify(&policy, &trusted, message).is_ok());
+    assert!(combined.verify(&policy, &trusted, b"another").is_err());
+    
+    assert!(ThresholdSignature::combine(&policy, &[partial(&a, message), partial(&outsider, message)]).is_err());
+    let repeated = ThresholdSignature {
+        threshold: 2,
+        signatures: vec![partial(&a, message), partial(&a, message)],
+    };
+    assert!(repeated.verify(&policy, &trusted, message).is_err());
+    let understated = ThresholdSignature {
+        threshold: 1,
+        signatures: vec![partial(&a, message)],
+    };
+    assert!(understated.verify(&policy, &trusted, message).is_err());
+}
+
+#[tokio::test]
+async fn officer_signature_completes_the_request() {
+    let f = fixture();
+    let attestation = new_attestation();
+    let request = f
+        .service
+        .open(&attestation, ComplianceLevel::InstitutionalGrade, &f.automated)
+        .await
+        .unwrap();
+    assert_eq!(request.status, SigningRequestStatus::Pending);
+    assert_eq!(request.outstanding(), ["officer-hsm"]);
+    
+    let message = signing_message(&attestation, &ComplianceLevel::InstitutionalGrade);
+    assert_eq!(request.message, hex::encode(&message));
+    let request = f.service.submit(request.id, partial(&f.officer, &message)).await.unwrap();
+    assert_eq!(request.status, SigningRequestStatus::Complete);
+    
+    let signature = request.signature.unwrap();
+    assert!(f.service.verify(&attestation, &ComplianceLevel::InstitutionalGrade, &signature).is_ok());
+    assert!(f.service.verify(&new_attestation(), &ComplianceLevel::InstitutionalGrade, &signature).is_err());
+}
+
+#[tokio::test]
+async fn bad_partials_are_rejected() {
+    let f = fixture();
+    let attestation = new_attestation();
+    let request = f
+        .service
+        .open(&attestation, ComplianceLevel::InstitutionalGrade, &f.automated)
+        .await
+        .unwrap();
+    
+    let error = f.service.submit(request.id, partial(&f.officer, b"wrong message")).await.unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+    let message = signing_message(&attestation, &ComplianceLevel::InstitutionalGrade);
+    let error = f.service.submit(request.id, partial(&f.automated, &message)).await.unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+    let stranger = AttestationSigner::generate("stranger");
+    let error = f.service.submit(request.id, partial(&stranger, &message)).await.unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+    
+    assert_eq!(f.service.get(request.id).await.unwrap().status, SigningRequestStatus::Pending);
+    let error = f.service.submit(Uuid::new_v4(), partial(&f.officer, &message)).await.unwrap_err();
+    assert_eq!(error.code(), "signing_request_not_found");
+}
+
+#[tokio::test]
+async fn requests_time_out() {
+    let f = fixture();
+    let attestation = new_attestation();
+    let request = f
+        .service
+        .open(&attestation, ComplianceLevel::InstitutionalGrade, &f.automated)
+        .await
+        .unwrap();
+    
+    f.clock.advance(Duration::seconds(3600));
+    let timed_out = f.service.expire_due().await;
+    assert_eq!(timed_out.len(), 1);
+    assert_eq!(timed_out[0].status, SigningRequestStatus::TimedOut);
+    assert!(f.service.list(Some(SigningRequestStatus::Pending)).await.is_empty());
+    
+    let message = signing_message(&attestation, &ComplianceLevel::InstitutionalGrade);
+    let error = f.service.submit(request.id, partial(&f.officer, &message)).await.unwrap_err();
+    assert_eq!(error.code(), "signing_request_closed");
+    assert_eq!(error.status_code(), 409);
+}
+
+#[tokio::test]
+async fn levels_below_every_policy_need_no_cosigners() {
+    let f = fixture();
+    assert!(f.service.policy(&ComplianceLevel::Enhanced).is_none());
+    assert!(f.service.policy(&ComplianceLevel::InstitutionalGrade).is_some());
+    
+    let error = f
+        .service
+        .open(&new_attestation(), ComplianceLevel::Enhanced, &f.automated)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "validation_error");
+}
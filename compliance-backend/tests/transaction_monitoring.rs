@@ -0,0 +1,134 @@
+//! Buffered ingestion of monitored transactions and per-account aggregates
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::compliance::monitoring::{AccountAggregate, MonitoredTransaction, Totals, TransactionMonitor};
+use compliance_backend::config::MonitoringIngestionConfig;
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+
+fn account(n: u32) -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", n)).unwrap()
+}
+
+/// Monitor whose database refuses connections, so writes always fail
+fn monitor(configure: impl FnOnce(&mut MonitoringIngestionConfig)) -> TransactionMonitor {
+    let pool = PgPoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_millis(500))
+        .connect_lazy("postgres://compliance@127.0.0.1:1/compliance")
+        .unwrap();
+    let mut config = MonitoringIngestionConfig::default();
+    configure(&mut config);
+    TransactionMonitor::new(pool, config)
+}
+
+fn transaction(external_id: &str, account_id: &AccountId, amount: u64, days_ago: i64) -> MonitoredTransaction {
+    MonitoredTransaction {
+        external_id: external_id.to_string(),
+        account_id: account_id.clone(),
+        amount,
+        asset: Some("USDC".to_string()),
+        counterparty: None,
+        transaction_type: "transfer".to_string(),
+        occurred_at: Utc::now() - Duration::days(days_ago),
+    }
+}
+
+#[tokio::test]
+async fn aggregates_reflect_transactions_as_soon_as_they_are_accepted() {
+    let monitor = monitor(|_| {});
+    let account_id = account(1);
+    let batch = vec![
+        transaction("t1", &account_id, 100, 0),
+        transaction("t2", &account_id, 250, 2),
+        transaction("t3", &account_id, 50, 10),
+        transaction("t4", &account(2), 7, 0),
+    ];
+    
+    assert_eq!(monitor.ingest(Uuid::new_v4(), batch).await.unwrap(), 4);
+    assert_eq!(monitor.buffered().await, 4);
+    
+    let aggregate = monitor.aggregate(&account_id).await.unwrap();
+    assert_eq!(aggregate.lifetime, Totals { count: 3, volume: 400 });
+    assert_eq!(aggregate.window(1, Utc::now()), Totals { count: 1, volume: 100 });
+    assert_eq!(aggregate.window(7, Utc::now()), Totals { count: 2, volume: 350 });
+    assert!(aggregate.first_seen.unwrap() < aggregate.last_seen.unwrap());
+    assert!(monitor.aggregate(&account(3)).await.is_none());
+}
+
+#[tokio::test]
+async fn daily_totals_outside_the_retention_window_are_dropped() {
+    let monitor = monitor(|_| {});
+    let account_id = account(1);
+    let batch = vec![
+        transaction("old", &account_id, 1_000, 120),
+        transaction("new", &account_id, 10, 0),
+    ];
+    monitor.ingest(Uuid::new_v4(), batch).await.unwrap();
+    
+    let aggregate = monitor.aggregate(&account_id).await.unwrap();
+    assert_eq!(aggregate.daily.len(), 1);
+    assert_eq!(aggregate.lifetime, Totals { count: 2, volume: 1_010 });
+    assert_eq!(aggregate.window(365, Utc::now()), Totals { count: 1, volume: 10 });
+}
+
+#[tokio::test]
+async fn a_full_buffer_rejects_whole_batches() {
+    let monitor = monitor(|c| {
+        c.max_buffered_transactions = 3;
+        c.flush_interval_ms = 1_500;
+    });
+    let account_id = account(1);
+    monitor
+        .ingest(Uuid::new_v4(), vec![transaction("t1", &account_id, 1, 0), transaction("t2", &account_id, 1, 0)])
+        .await
+        .unwrap();
+    
+    let rejected = monitor
+        .ingest(Uuid::new_v4(), vec![transaction("t3", &account_id, 1, 0), transaction("t4", &account_id, 1, 0)])
+        .await;
+    assert!(matches!(rejected, Err(ComplianceError::MonitoringBackpressure { retry_after_secs: 2 })));
+    assert_eq!(monitor.buffered().await, 2);
+    assert_eq!(monitor.aggregate(&account_id).await.unwrap().lifetime.count, 2);
+    
+    monitor.ingest(Uuid::new_v4(), vec![transaction("t3", &account_id, 1, 0)]).await.unwrap();
+    assert_eq!(monitor.buffered().await, 3);
+}
+
+#[tokio::test]
+async fn failed_writes_keep_everything_for_the_next_attempt() {
+    let monitor = monitor(|c| c.flush_batch_size = 2);
+    let account_id = account(1);
+    let batch = (0..5).map(|i| transaction(&format!("t{}", i), &account_id, 1, 0)).collect();
+    monitor.ingest(Uuid::new_v4(), batch).await.unwrap();
+    
+    assert!(monitor.flush().await.is_err());
+    assert_eq!(monitor.buffered().await, 5);
+    
+    assert!(monitor.checkpoint().await.is_err());
+    assert!(monitor.checkpoint().await.is_err(), "accounts stay dirty after a failed checkpoint");
+    assert!(monitor.drain().await.is_err());
+}
+
+#[tokio::test]
+async fn nothing_to_write_needs_no_database() {
+    let monitor = monitor(|_| {});
+    assert_eq!(monitor.flush().await.unwrap(), 0);
+    assert_eq!(monitor.checkpoint().await.unwrap(), 0);
+    monitor.drain().await.unwrap();
+}
+
+#[test]
+fn windows_end_on_the_given_day() {
+    let now = Utc.with_ymd_and_hms(2025, 6, 10, 12, 0, 0).unwrap();
+    let mut aggregate = AccountAggregate::default();
+    for (day, volume) in [(8, 5), (9, 7), (10, 11), (11, 13)] {
+        let date = Utc.with_ymd_and_hms(2025, 6, day, 0, 0, 0).unwrap().date_naive();
+        aggregate.daily.insert(date, Totals { count: 1, volume });
+    }
+    
+    assert_eq!(aggregate.window(1, now), Totals { count: 1, volume: 11 });
+    assert_eq!(aggregate.window(3, now), Totals { count: 3, volume: 23 });
+    assert_eq!(aggregate.window(0, now), Totals { count: 1, volume: 11 });
+}
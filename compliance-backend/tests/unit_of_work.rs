@@ -0,0 +1,117 @@
+//! Records committed together through a unit of work
+
+use chrono::Utc;
+use compliance_backend::audit::AuditLog;
+use compliance_backend::clock::system_clock;
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::storage::{AttestationRepo, OutboxMessage, OutboxRepo, UnitOfWork, WriteBatch};
+use compliance_backend::types::AccountId;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+struct Services {
+    store: Arc<MemoryStore>,
+    events: AttestationEventStore,
+    audit: AuditLog,
+}
+
+async fn services() -> Services {
+    let store = Arc::new(MemoryStore::default());
+    Services {
+        events: AttestationEventStore::with_repo(store.attestations.clone(), system_clock()),
+        audit: AuditLog::open(store.audit.clone(), system_clock()).await.unwrap(),
+        store,
+    }
+}
+
+#[tokio::test]
+async fn batch_commits_event_audit_entry_and_outbox_message_together() {
+    let services = services().await;
+    let mut batch = WriteBatch::default();
+    let mut events = services.events.stage().await;
+    let mut audit = services.audit.stage().await;
+    events
+        .push(&mut batch, &account(), AttestationEvent::Expired)
+        .await
+        .unwrap();
+    audit.push(&mut batch, "tester", "test.batched", Some(&account()), serde_json::json!({}));
+    batch
+        .outbox
+        .push(OutboxMessage::new(Uuid::new_v4(), "test.batched", serde_json::json!({}), Utc::now()));
+    
+    services.store.commit(&batch).await.unwrap();
+    events.committed(&batch).await;
+    audit.committed();
+    
+    assert_eq!(services.store.attestations.last_sequence(&account()).await.unwrap(), 1);
+    assert_eq!(services.audit.entries().await.unwrap().len(), 1);
+    assert_eq!(services.store.outbox.pending(10).await.unwrap().len(), 1);
+    
+    // Individual writes continue after the committed records
+    let next = services.audit.record("tester", "test.after", None, serde_json::json!({})).await;
+    assert_eq!(next.sequence, 2);
+    assert!(services.audit.verify_chain().await.unwrap().valid);
+    let appended = services.events.append(&account(), AttestationEvent::Expired).await.unwrap();
+    assert_eq!(appended.sequence, 2);
+}
+
+#[tokio::test]
+async fn conflicting_batch_writes_nothing() {
+    let services = services().await;
+    let mut batch = WriteBatch::default();
+    {
+        let mut events = services.events.stage().await;
+        let mut audit = services.audit.stage().await;
+        events
+            .push(&mut batch, &account(), AttestationEvent::Expired)
+            .await
+            .unwrap();
+        audit.push(&mut batch, "tester", "test.batched", Some(&account()), serde_json::json!({}));
+        batch
+            .outbox
+            .push(OutboxMessage::new(Uuid::new_v4(), "test.batched", serde_json::json!({}), Utc::now()));
+    }
+    
+    // Another writer takes the event's sequence before the batch commits
+    services.events.append(&account(), AttestationEvent::Expired).await.unwrap();
+    assert!(services.store.commit(&batch).await.is_err());
+    
+    assert_eq!(services.store.attestations.last_sequence(&account()).await.unwrap(), 1);
+    assert!(services.audit.entries().await.unwrap().is_empty());
+    assert!(services.store.outbox.pending(10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn dropped_staging_leaves_the_audit_chain_in_place() {
+    let services = services().await;
+    {
+        let mut batch = WriteBatch::default();
+        let mut audit = services.audit.stage().await;
+        audit.push(&mut batch, "tester", "test.abandoned", None, serde_json::json!({}));
+    }
+    
+    let entry = services.audit.record("tester", "test.recorded", None, serde_json::json!({})).await;
+    assert_eq!(entry.sequence, 1);
+    assert!(services.audit.verify_chain().await.unwrap().valid);
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_abandoned_and_no_longer_pending() {
+    let services = services().await;
+    let message = OutboxMessage::new(Uuid::new_v4(), "test.sent", serde_json::json!({}), Utc::now());
+    let mut batch = WriteBatch::default();
+    batch.outbox.push(message.clone());
+    services.store.commit(&batch).await.unwrap();
+    
+    services.store.outbox.record_failure(message.id, None).await.unwrap();
+    let pending = services.store.outbox.pending(10).await.unwrap();
+    assert_eq!(pending[0].attempts, 1);
+    
+    services.store.outbox.record_failure(message.id, Some(Utc::now())).await.unwrap();
+    assert!(services.store.outbox.pending(10).await.unwrap().is_empty());
+}
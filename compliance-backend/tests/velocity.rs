@@ -0,0 +1,173 @@
+//! Velocity limits on transaction authorization
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::country_risk::CountryRiskService;
+use compliance_backend::compliance::velocity::{AuthorizationOutcome, TransactionRequest, VelocityService};
+use compliance_backend::compliance::watchlists::{WatchlistEntryInput, WatchlistKind, WatchlistService};
+use compliance_backend::config::{ComplianceConfig, TransactionMonitoringConfig};
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::{AccountId, ComplianceAttestation, ComplianceLevel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const COUNTERPARTY: &str = "0x00000000000000000000000000000f";
+
+struct Fixture {
+    service: Arc<VelocityService>,
+    watchlists: Arc<WatchlistService>,
+    clock: Arc<MockClock>,
+    client_id: Uuid,
+    account_id: AccountId,
+    attestation: ComplianceAttestation,
+}
+
+fn fixture(limits: TransactionMonitoringConfig) -> Fixture {
+    let mut compliance = ComplianceConfig::default();
+    compliance.aml.transaction_monitoring = limits;
+    let config = Arc::new(LiveConfig::new(compliance));
+    let country_risk = Arc::new(CountryRiskService::new(config.clone()).unwrap());
+    let watchlists = Arc::new(WatchlistService::new());
+    let start = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+    let clock = Arc::new(MockClock::new(start));
+    let account_id = AccountId::parse(&format!("0x{:030x}", 1)).unwrap();
+    Fixture {
+        service: Arc::new(VelocityService::new(config, watchlists.clone(), country_risk).with_clock(clock.clone())),
+        watchlists,
+        clock,
+        client_id: Uuid::new_v4(),
+        attestation: common::attestation(&account_id, start, Duration::days(90)),
+        account_id,
+    }
+}
+
+fn limits(hourly: u32, daily: u32, daily_volume: u64) -> TransactionMonitoringConfig {
+    TransactionMonitoringConfig {
+        max_hourly_transactions: hourly,
+        max_daily_transactions: daily,
+        max_daily_volume: daily_volume,
+        ..TransactionMonitoringConfig::default()
+    }
+}
+
+fn transfer(amount: u64, counterparty: Option<&str>) -> TransactionRequest {
+    TransactionRequest {
+        amount,
+        asset: None,
+        counterparty: counterparty.map(str::to_string),
+        counterparty_country: None,
+        transaction_type: "transfer".to_string(),
+    }
+}
+
+impl Fixture {
+    async fn authorize(&self, request: &TransactionRequest) -> AuthorizationOutcome {
+        self.service
+            .authorize(
+                self.client_id,
+                &self.account_id,
+                Some(&self.attestation),
+                Some(ComplianceLevel::Enhanced),
+                request,
+            )
+            .await
+            .outcome
+    }
+    
+    async fn watch(&self, kind: WatchlistKind) {
+        let input = WatchlistEntryInput {
+            kind,
+            value: COUNTERPARTY.to_string(),
+            reason: None,
+            created_by: None,
+            expires_at: None,
+        };
+        self.watchlists.create(self.client_id, input).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn the_hourly_limit_frees_up_as_the_window_moves() {
+    let fixture = fixture(limits(2, 100, 1_000_000));
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Allow);
+    fixture.clock.advance(Duration::minutes(30));
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Deny);
+    
+    // Only the first transfer has left the window
+    fixture.clock.advance(Duration::minutes(31));
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Deny);
+}
+
+#[tokio::test]
+async fn daily_limits_count_only_allowed_transactions_of_the_last_day() {
+    let fixture = fixture(limits(100, 100, 1_000));
+    assert_eq!(fixture.authorize(&transfer(600, None)).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(600, None)).await, AuthorizationOutcome::Deny);
+    
+    // The denied transfer did not count towards the volume
+    assert_eq!(fixture.authorize(&transfer(400, None)).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(1, None)).await, AuthorizationOutcome::Deny);
+    
+    fixture.clock.advance(Duration::days(1));
+    assert_eq!(fixture.authorize(&transfer(1_000, None)).await, AuthorizationOutcome::Allow);
+    
+    let fixture = self::fixture(limits(100, 1, 1_000_000));
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(100, None)).await, AuthorizationOutcome::Deny);
+}
+
+#[tokio::test]
+async fn allowlisted_counterparties_are_exempt_from_velocity_limits() {
+    let fixture = fixture(limits(1, 1, 1_000));
+    assert_eq!(fixture.authorize(&transfer(1_000, None)).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(1_000, Some(COUNTERPARTY))).await, AuthorizationOutcome::Deny);
+    
+    fixture.watch(WatchlistKind::AllowlistedCounterparty).await;
+    assert_eq!(fixture.authorize(&transfer(1_000, Some(COUNTERPARTY))).await, AuthorizationOutcome::Allow);
+    assert_eq!(fixture.authorize(&transfer(1_000, None)).await, AuthorizationOutcome::Deny);
+}
+
+#[tokio::test]
+async fn blocked_counterparties_are_denied_even_when_allowlisted() {
+    let fixture = fixture(limits(100, 100, 1_000_000));
+    fixture.watch(WatchlistKind::AllowlistedCounterparty).await;
+    fixture.watch(WatchlistKind::BlockedAddress).await;
+    
+    let decision = fixture
+        .service
+        .authorize(
+            fixture.client_id,
+            &fixture.account_id,
+            Some(&fixture.attestation),
+            Some(ComplianceLevel::Enhanced),
+            &transfer(100, Some(COUNTERPARTY)),
+        )
+        .await;
+    assert_eq!(decision.outcome, AuthorizationOutcome::Deny);
+    assert_eq!(decision.watchlist_hits.len(), 1);
+    assert_eq!(decision.watchlist_hits[0].entry.kind, WatchlistKind::BlockedAddress);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_transactions_cannot_overrun_a_limit() {
+    let fixture = Arc::new(fixture(limits(100, 5, 1_000_000)));
+    let tasks: Vec<_> = (0..20)
+        .map(|_| {
+            let fixture = fixture.clone();
+            tokio::spawn(async move { fixture.authorize(&transfer(100, None)).await })
+        })
+        .collect();
+    
+    let mut allowed = 0;
+    for task in tasks {
+        if task.await.unwrap() == AuthorizationOutcome::Allow {
+            allowed += 1;
+        }
+    }
+    assert_eq!(allowed, 5);
+    assert_eq!(fixture.service.decisions_for(&fixture.account_id).await.len(), 20);
+}
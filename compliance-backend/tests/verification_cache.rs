@@ -0,0 +1,149 @@
+//! Cached proof verification outcomes, their lifetimes, and invalidation
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::MockClock;
+use compliance_backend::compliance::attestation_events::{AttestationEvent, AttestationEventStore};
+use compliance_backend::compliance::verification_cache::{envelope_hash, spawn_invalidation, VerificationCache};
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::types::AccountId;
+use compliance_backend::ComplianceError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn start() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()
+}
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+struct Cache {
+    cache: Arc<VerificationCache>,
+    events: Arc<AttestationEventStore>,
+    clock: Arc<MockClock>,
+    runs: AtomicUsize,
+}
+
+impl Cache {
+    /// Verify through the cache, counting runs of the verifier
+    async fn verify(&self, envelope: &str, outcome: Result<bool, ComplianceError>) -> Result<bool, ComplianceError> {
+        let expires_at = Some(start() + Duration::hours(1));
+        self.cache
+            .verify(envelope_hash(envelope), &account(), expires_at, async {
+                self.runs.fetch_add(1, Ordering::SeqCst);
+                outcome
+            })
+            .await
+    }
+}
+
+/// A cache over an account whose attestation expires `validity` from the start
+async fn cache(validity: Duration) -> Cache {
+    let clock = Arc::new(MockClock::new(start()));
+    let events = Arc::new(AttestationEventStore::with_clock(clock.clone()));
+    let attestation = common::attestation(&account(), start(), validity);
+    events.append(&account(), AttestationEvent::AttestationIssued { attestation }).await.unwrap();
+    
+    let config = Arc::new(LiveConfig::new(ComplianceConfig::default()));
+    Cache {
+        cache: Arc::new(VerificationCache::new(config, events.clone(), clock.clone())),
+        events,
+        clock,
+        runs: AtomicUsize::new(0),
+    }
+}
+
+#[tokio::test]
+async fn successful_outcomes_are_reused_until_the_envelope_expires() {
+    let cache = cache(Duration::days(90)).await;
+    assert!(cache.verify("envelope", Ok(true)).await.unwrap());
+    assert!(cache.verify(" envelope\n", Ok(false)).await.unwrap());
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 1);
+    
+    cache.clock.advance(Duration::hours(1));
+    assert!(!cache.verify("envelope", Ok(false)).await.unwrap());
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 2);
+    
+    let stats = cache.cache.stats().await;
+    assert_eq!((stats.hits, stats.misses), (1, 2));
+}
+
+#[tokio::test]
+async fn successful_outcomes_expire_with_the_attestation() {
+    let cache = cache(Duration::minutes(10)).await;
+    cache.verify("envelope", Ok(true)).await.unwrap();
+    
+    cache.clock.advance(Duration::minutes(10));
+    cache.verify("envelope", Ok(true)).await.unwrap();
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn failures_are_cached_for_the_negative_ttl() {
+    let cache = cache(Duration::days(90)).await;
+    assert!(!cache.verify("forged", Ok(false)).await.unwrap());
+    let rejected = || ComplianceError::InvalidProof { reason: "malformed".to_string() };
+    assert!(cache.verify("malformed", Err(rejected())).await.is_err());
+    
+    assert!(!cache.verify("forged", Ok(true)).await.unwrap());
+    assert!(matches!(cache.verify("malformed", Ok(true)).await, Err(ComplianceError::InvalidProof { .. })));
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 2);
+    
+    cache.clock.advance(Duration::seconds(300));
+    assert!(cache.verify("forged", Ok(true)).await.unwrap());
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn other_errors_are_not_cached() {
+    let cache = cache(Duration::days(90)).await;
+    assert!(cache.verify("envelope", Err(ComplianceError::internal("timeout"))).await.is_err());
+    assert!(cache.verify("envelope", Ok(true)).await.unwrap());
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn revocation_drops_cached_outcomes() {
+    let cache = cache(Duration::days(90)).await;
+    let invalidation = spawn_invalidation(cache.cache.clone());
+    tokio::task::yield_now().await;
+    cache.verify("envelope", Ok(true)).await.unwrap();
+    assert_eq!(cache.cache.stats().await.entries, 1);
+    
+    let revoked = AttestationEvent::Revoked {
+        reason: "fraud".to_string(),
+        revoked_by: None,
+    };
+    cache.events.append(&account(), revoked).await.unwrap();
+    for _ in 0..10 {
+        if cache.cache.stats().await.entries == 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(cache.cache.stats().await.entries, 0);
+    
+    // Not cached again while the attestation is revoked
+    cache.verify("envelope", Ok(true)).await.unwrap();
+    assert_eq!(cache.cache.stats().await.entries, 0);
+    invalidation.abort();
+}
+
+#[tokio::test]
+async fn disabled_cache_always_verifies() {
+    let mut cache = cache(Duration::days(90)).await;
+    let mut config = ComplianceConfig::default();
+    config.attestation.verification_cache.enabled = false;
+    cache.cache = Arc::new(VerificationCache::new(
+        Arc::new(LiveConfig::new(config)),
+        cache.events.clone(),
+        cache.clock.clone(),
+    ));
+    cache.verify("envelope", Ok(true)).await.unwrap();
+    cache.verify("envelope", Ok(true)).await.unwrap();
+    assert_eq!(cache.runs.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,83 @@
+//! Bounded, deadline-limited proof verification off the async runtime
+
+use compliance_backend::compliance::verifying::VerificationPool;
+use compliance_backend::config::VerificationPoolConfig;
+use compliance_backend::ComplianceError;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn pool(max_concurrent: usize, max_queued: usize) -> Arc<VerificationPool> {
+    Arc::new(VerificationPool::new(VerificationPoolConfig {
+        max_concurrent,
+        max_queued,
+        initial_estimate_ms: 200,
+    }))
+}
+
+/// A verification that blocks its thread for `millis`, as STARK verification does
+async fn verify(millis: u64) -> compliance_backend::Result<bool> {
+    std::thread::sleep(Duration::from_millis(millis));
+    Ok(true)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn verification_does_not_block_the_runtime() {
+    let pool = pool(2, 8);
+    let verifications: Vec<_> = (0..2)
+        .map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.run(Duration::from_secs(5), verify(300)).await })
+        })
+        .collect();
+    
+    // Both runtime workers stay free while the proofs are verified
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let responsive = tokio::time::timeout(Duration::from_millis(100), tokio::spawn(async { 1 })).await;
+    assert!(responsive.is_ok());
+    assert_eq!(pool.status().running, 2);
+    
+    for verification in verifications {
+        assert!(verification.await.unwrap().unwrap());
+    }
+    assert_eq!(pool.status().running, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn full_queue_rejects_with_a_retry_hint() {
+    let pool = pool(1, 1);
+    let running = tokio::spawn({
+        let pool = pool.clone();
+        async move { pool.run(Duration::from_secs(5), verify(300)).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let queued = tokio::spawn({
+        let pool = pool.clone();
+        async move { pool.run(Duration::from_secs(5), verify(10)).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(pool.status().queued, 1);
+    
+    let rejected = pool.run(Duration::from_secs(5), verify(10)).await;
+    assert!(matches!(rejected, Err(ComplianceError::VerifierSaturated { retry_after_secs }) if retry_after_secs >= 1));
+    assert_eq!(pool.status().rejected_total, 1);
+    
+    assert!(running.await.unwrap().is_ok());
+    assert!(queued.await.unwrap().is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn verifications_past_their_deadline_fail_but_keep_their_worker() {
+    let pool = pool(1, 4);
+    let slow = pool.run(Duration::from_millis(50), verify(300)).await;
+    assert!(matches!(slow, Err(ComplianceError::ProofVerificationTimedOut { .. })));
+    assert_eq!(pool.status().running, 1);
+    
+    // Waiting for the worker counts against the deadline too
+    let queued = pool.run(Duration::from_millis(50), verify(10)).await;
+    assert!(matches!(queued, Err(ComplianceError::ProofVerificationTimedOut { .. })));
+    assert_eq!(pool.status().timed_out_total, 2);
+    assert_eq!(pool.status().queued, 0);
+    
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(pool.run(Duration::from_secs(5), verify(10)).await.unwrap());
+}
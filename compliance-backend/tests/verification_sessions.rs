@@ -0,0 +1,210 @@
+//! End-user verification sessions: tokens, uploads, and lifecycle webhooks
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use compliance_backend::clock::{Clock, MockClock};
+use compliance_backend::compliance::verification_sessions::{
+    CreateSessionRequest, MemoryDocumentVault, SessionStatus, VerificationSessionService,
+};
+use compliance_backend::config::ComplianceConfig;
+use compliance_backend::reload::LiveConfig;
+use compliance_backend::storage::memory::MemoryStore;
+use compliance_backend::storage::OutboxRepo;
+use compliance_backend::types::{AccountId, ComplianceAttestation, KycStatus};
+use compliance_backend::ComplianceError;
+use std::sync::Arc;
+use uuid::Uuid;
+
+struct Sessions {
+    service: VerificationSessionService,
+    vault: Arc<MemoryDocumentVault>,
+    store: Arc<MemoryStore>,
+    clock: Arc<MockClock>,
+}
+
+impl Sessions {
+    /// Event types of the webhooks committed so far
+    async fn webhooks(&self) -> Vec<String> {
+        let pending = self.store.outbox.pending(100).await.unwrap();
+        pending.into_iter().map(|message| message.event_type).collect()
+    }
+}
+
+fn sessions() -> Sessions {
+    let mut config = ComplianceConfig::default();
+    config.verification_sessions.hosted_url = Some("https://verify.example.com/start".to_string());
+    config.kyc.documents.image_quality.enabled = false;
+    let vault = Arc::new(MemoryDocumentVault::new());
+    let store = Arc::new(MemoryStore::default());
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()));
+    let service =
+        VerificationSessionService::new(Arc::new(LiveConfig::new(config)), b"test-secret", vault.clone(), store.clone())
+            .with_clock(clock.clone());
+    Sessions {
+        service,
+        vault,
+        store,
+        clock,
+    }
+}
+
+fn account() -> AccountId {
+    AccountId::parse(&format!("0x{:030x}", 7)).unwrap()
+}
+
+#[tokio::test]
+async fn end_users_upload_and_submit_with_the_token_alone() {
+    let s = sessions();
+    let client = Uuid::new_v4();
+    let issued = s.service.create(client, &account(), CreateSessionRequest::default()).await.unwrap();
+    assert_eq!(issued.session.status, SessionStatus::Created);
+    assert_eq!(issued.session.expires_at, s.clock.now() + Duration::hours(1));
+    assert_eq!(issued.url, Some(format!("https://verify.example.com/start#token={}", issued.token)));
+    
+    let session = s.service.authenticate(&issued.token).await.unwrap();
+    let uploaded = s
+        .service
+        .upload(session.id, "Passport", "image/jpeg; charset=binary", b"jpeg bytes".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(uploaded.status, SessionStatus::InProgress);
+    let document = &uploaded.documents[0];
+    assert_eq!(document.document_type, "passport");
+    assert_eq!(document.content_type, "image/jpeg");
+    assert_eq!(s.vault.get(&document.vault_ref).await.unwrap().1, b"jpeg bytes");
+    
+    s.service.submit(session.id).await.unwrap();
+    let attestation = common::attestation(&account(), Utc::now(), Duration::days(365));
+    let completed = s.service.complete(session.id, &attestation).await.unwrap();
+    assert_eq!(completed.status, SessionStatus::Completed);
+    assert_eq!(s.service.view(&completed).kyc_status, Some(KycStatus::Verified));
+    assert_eq!(
+        s.webhooks().await,
+        [
+            "verification_session.started",
+            "verification_session.submitted",
+            "verification_session.completed",
+        ]
+    );
+    
+    // Other clients cannot see the session
+    assert!(matches!(
+        s.service.get(Uuid::new_v4(), session.id).await,
+        Err(ComplianceError::VerificationSessionNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn forged_and_expired_tokens_are_rejected() {
+    let s = sessions();
+    let request = CreateSessionRequest {
+        ttl_secs: Some(600),
+        redirect_url: None,
+    };
+    let issued = s.service.create(Uuid::new_v4(), &account(), request).await.unwrap();
+    
+    // Extending the expiry invalidates the MAC
+    let (claims, tag) = issued.token.rsplit_once('.').unwrap();
+    let (id, expiry) = claims.rsplit_once('.').unwrap();
+    let extended = format!("{}.{}.{}", id, expiry.parse::<i64>().unwrap() + 86_400, tag);
+    for token in [extended.as_str(), "vs_garbage", ""] {
+        assert!(matches!(
+            s.service.authenticate(token).await,
+            Err(ComplianceError::InvalidSessionToken { .. })
+        ));
+    }
+    
+    s.clock.advance(Duration::seconds(600));
+    assert!(matches!(
+        s.service.authenticate(&issued.token).await,
+        Err(ComplianceError::InvalidSessionToken { .. })
+    ));
+    let expired = s.service.get(issued.session.client_id, issued.session.id).await.unwrap();
+    assert_eq!(expired.status, SessionStatus::Expired);
+    assert_eq!(s.webhooks().await, ["verification_session.expired"]);
+}
+
+#[tokio::test]
+async fn uploads_are_limited_to_supported_documents() {
+    let s = sessions();
+    let issued = s.service.create(Uuid::new_v4(), &account(), CreateSessionRequest::default()).await.unwrap();
+    let id = issued.session.id;
+    
+    let wrong_type = s.service.upload(id, "library_card", "image/png", vec![1]).await;
+    assert!(matches!(wrong_type, Err(ComplianceError::Validation { field, .. }) if field == "document_type"));
+    let wrong_media = s.service.upload(id, "passport", "text/html", vec![1]).await;
+    assert!(matches!(wrong_media, Err(ComplianceError::Validation { field, .. }) if field == "content_type"));
+    let too_large = s.service.upload(id, "passport", "image/png", vec![0; 10 * 1024 * 1024 + 1]).await;
+    assert!(matches!(too_large, Err(ComplianceError::Validation { field, .. }) if field == "document"));
+    
+    // Nothing to submit yet
+    assert!(matches!(s.service.submit(id).await, Err(ComplianceError::Validation { .. })));
+}
+
+#[tokio::test]
+async fn failed_checks_and_cancellation_close_the_session() {
+    let s = sessions();
+    let client = Uuid::new_v4();
+    let first = s.service.create(client, &account(), CreateSessionRequest::default()).await.unwrap();
+    let id = first.session.id;
+    s.service.upload(id, "national_id", "application/pdf", vec![1, 2, 3]).await.unwrap();
+    s.service.submit(id).await.unwrap();
+    
+    // A check that could not run hands the session back without a webhook
+    let reopened = s.service.reopen(id).await.unwrap();
+    assert_eq!(reopened.status, SessionStatus::InProgress);
+    s.service.submit(id).await.unwrap();
+    let rejected = ComplianceAttestation {
+        kyc_status: KycStatus::Rejected,
+        ..common::attestation(&account(), Utc::now(), Duration::days(365))
+    };
+    let failed = s.service.complete(id, &rejected).await.unwrap();
+    assert_eq!(failed.status, SessionStatus::Failed);
+    assert!(matches!(
+        s.service.upload(id, "passport", "image/png", vec![1]).await,
+        Err(ComplianceError::VerificationSessionClosed { .. })
+    ));
+    
+    let second = s.service.create(client, &account(), CreateSessionRequest::default()).await.unwrap();
+    let cancelled = s.service.cancel(client, second.session.id).await.unwrap();
+    assert_eq!(cancelled.status, SessionStatus::Cancelled);
+    assert!(matches!(
+        s.service.submit(second.session.id).await,
+        Err(ComplianceError::VerificationSessionClosed { .. })
+    ));
+    
+    assert_eq!(
+        s.webhooks().await,
+        [
+            "verification_session.started",
+            "verification_session.submitted",
+            "verification_session.submitted",
+            "verification_session.failed",
+            "verification_session.cancelled",
+        ]
+    );
+    assert_eq!(s.service.for_account(client, &account()).await.len(), 2);
+}
+
+#[tokio::test]
+async fn erasure_deletes_an_accounts_documents_and_closes_open_sessions() {
+    let s = sessions();
+    let client = Uuid::new_v4();
+    let issued = s.service.create(client, &account(), CreateSessionRequest::default()).await.unwrap();
+    let uploaded = s
+        .service
+        .upload(issued.session.id, "passport", "image/png", b"png bytes".to_vec())
+        .await
+        .unwrap();
+    let vault_ref = uploaded.documents[0].vault_ref.clone();
+    
+    assert_eq!(s.service.erase_documents(&account()).await.unwrap(), 1);
+    assert!(s.vault.get(&vault_ref).await.is_none());
+    let erased = s.service.get(client, issued.session.id).await.unwrap();
+    assert!(erased.documents.is_empty());
+    assert_eq!(erased.status, SessionStatus::Cancelled);
+    
+    // Erasing again finds nothing left
+    assert_eq!(s.service.erase_documents(&account()).await.unwrap(), 0);
+}
@@ -0,0 +1,63 @@
+//! Verification core shared with the WebAssembly bindings
+//!
+//! The bindings themselves only run on wasm32; these tests cover the key
+//! handling and policy encoding they are built on.
+
+use compliance_backend::compliance::claims::ClaimKind;
+use compliance_backend::compliance::scope::{AssetClass, ScopeUsage};
+use compliance_backend::crypto::{AttestationSigner, TrustedKeys};
+use compliance_backend::types::ComplianceLevel;
+use compliance_backend::ComplianceError;
+
+fn trusting(signer: &AttestationSigner) -> TrustedKeys {
+    let mut trusted = TrustedKeys::new();
+    trusted
+        .insert_hex(signer.key_id(), &hex::encode(signer.verifying_key().to_bytes()))
+        .unwrap();
+    trusted
+}
+
+#[test]
+fn hex_public_keys_verify_signatures_of_their_key() {
+    let signer = AttestationSigner::generate("issuer-1");
+    let trusted = trusting(&signer);
+    let signature = signer.sign(b"attestation").unwrap();
+    
+    trusted.verify("issuer-1", b"attestation", &signature).unwrap();
+    assert!(trusted.verify("issuer-1", b"attestation!", &signature).is_err());
+    assert!(trusted.verify("issuer-2", b"attestation", &signature).is_err());
+    assert!(trusted.verify("issuer-1", b"attestation", &signature[..63]).is_err());
+    
+    let other = AttestationSigner::generate("issuer-1");
+    let forged = other.sign(b"attestation").unwrap();
+    assert!(trusted.verify("issuer-1", b"attestation", &forged).is_err());
+}
+
+#[test]
+fn malformed_hex_public_keys_are_rejected() {
+    let mut trusted = TrustedKeys::new();
+    for key in ["zz", &"00".repeat(31), &"00".repeat(33)] {
+        assert!(matches!(trusted.insert_hex("k", key), Err(ComplianceError::Crypto { .. })));
+    }
+}
+
+#[test]
+fn policy_inputs_use_their_javascript_encodings() {
+    let usage: ScopeUsage = serde_json::from_value(serde_json::json!({
+        "application": "dex",
+        "asset_class": "tokenized_security",
+        "amount": 250
+    }))
+    .unwrap();
+    assert_eq!(usage.application.as_deref(), Some("dex"));
+    assert_eq!(usage.asset_class, Some(AssetClass::TokenizedSecurity));
+    assert_eq!(usage.amount, Some(250));
+    
+    let empty: ScopeUsage = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert!(empty.application.is_none() && empty.asset_class.is_none() && empty.amount.is_none());
+    
+    let claims: Vec<ClaimKind> = serde_json::from_value(serde_json::json!(["kyc_tier", "jurisdiction_class"])).unwrap();
+    assert_eq!(claims, [ClaimKind::KycTier, ClaimKind::JurisdictionClass]);
+    let level: ComplianceLevel = serde_json::from_value(serde_json::json!("InstitutionalGrade")).unwrap();
+    assert_eq!(level, ComplianceLevel::InstitutionalGrade);
+}
@@ -0,0 +1,105 @@
+//! JWE encryption of webhook deliveries to client-registered keys
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use compliance_backend::compliance::clients::ClientRegistry;
+use compliance_backend::crypto::webhook_encryption::{check_key, decrypt, encrypt};
+use compliance_backend::types::{ClientEnvironment, ComplianceLevel, WebhookEncryptionKey};
+use compliance_backend::ComplianceError;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// A receiver's private key and the public key it registers
+fn keypair(kid: &str) -> ([u8; 32], WebhookEncryptionKey) {
+    let secret = StaticSecret::random_from_rng(rand::rngs::OsRng);
+    let key = WebhookEncryptionKey {
+        kid: kid.to_string(),
+        x: URL_SAFE_NO_PAD.encode(PublicKey::from(&secret).as_bytes()),
+    };
+    (secret.to_bytes(), key)
+}
+
+#[test]
+fn deliveries_decrypt_with_the_receivers_key() {
+    let (private_key, key) = keypair("2025-06");
+    let body = br#"{"data":{"account_id":"0x0123"},"type":"attestation.issued"}"#;
+    let jwe = encrypt(&key, body).unwrap();
+    
+    let parts: Vec<&str> = jwe.split('.').collect();
+    assert_eq!(parts.len(), 5);
+    assert!(parts[1].is_empty());
+    let header: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[0]).unwrap()).unwrap();
+    assert_eq!(header["alg"], "ECDH-ES");
+    assert_eq!(header["enc"], "A256GCM");
+    assert_eq!(header["kid"], "2025-06");
+    assert_eq!(header["epk"]["crv"], "X25519");
+    
+    assert_eq!(decrypt(&private_key, &jwe).unwrap(), body);
+}
+
+#[test]
+fn each_delivery_uses_a_fresh_ephemeral_key() {
+    let (_, key) = keypair("k1");
+    assert_ne!(encrypt(&key, b"{}").unwrap(), encrypt(&key, b"{}").unwrap());
+}
+
+#[test]
+fn tampered_or_misdirected_deliveries_do_not_decrypt() {
+    let (private_key, key) = keypair("k1");
+    let jwe = encrypt(&key, b"{\"pii\":true}").unwrap();
+    
+    let mut parts: Vec<String> = jwe.split('.').map(str::to_string).collect();
+    let mut ciphertext = URL_SAFE_NO_PAD.decode(&parts[3]).unwrap();
+    ciphertext[0] ^= 1;
+    parts[3] = URL_SAFE_NO_PAD.encode(ciphertext);
+    assert!(matches!(decrypt(&private_key, &parts.join(".")), Err(ComplianceError::Crypto { .. })));
+    
+    let (other_key, _) = keypair("k2");
+    assert!(decrypt(&other_key, &jwe).is_err());
+    assert!(decrypt(&private_key, "not.a.jwe").is_err());
+}
+
+#[test]
+fn unusable_keys_are_rejected() {
+    let (_, key) = keypair("k1");
+    assert!(check_key(&key).is_ok());
+    
+    let low_order = WebhookEncryptionKey {
+        kid: "k1".to_string(),
+        x: URL_SAFE_NO_PAD.encode([0u8; 32]),
+    };
+    assert!(matches!(check_key(&low_order), Err(ComplianceError::Validation { .. })));
+    let short = WebhookEncryptionKey {
+        kid: "k1".to_string(),
+        x: URL_SAFE_NO_PAD.encode([7u8; 16]),
+    };
+    assert!(check_key(&short).is_err());
+    assert!(check_key(&WebhookEncryptionKey { kid: " ".to_string(), ..key }).is_err());
+}
+
+#[tokio::test]
+async fn encryption_is_configured_per_environment() {
+    let registry = ClientRegistry::new();
+    let client = registry
+        .create("Acme", Some("https://acme.example/hooks".to_string()), ComplianceLevel::Standard, None, true)
+        .await
+        .unwrap();
+    let (_, key) = keypair("sandbox-1");
+    registry
+        .set_webhook_encryption(client.id, ClientEnvironment::Sandbox, Some(key.clone()))
+        .await
+        .unwrap();
+    
+    let stored = registry.get(client.id).await.unwrap();
+    assert!(stored.webhook_encryption_key.is_none());
+    let sandbox = stored.in_environment(ClientEnvironment::Sandbox).unwrap();
+    assert_eq!(sandbox.webhook_encryption_key, Some(key.clone()));
+    
+    let promoted = registry.promote_sandbox(client.id).await.unwrap();
+    assert_eq!(promoted.webhook_encryption_key, Some(key));
+    
+    let cleared = registry
+        .set_webhook_encryption(client.id, ClientEnvironment::Production, None)
+        .await
+        .unwrap();
+    assert!(cleared.webhook_encryption_key.is_none());
+}
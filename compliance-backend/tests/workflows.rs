@@ -0,0 +1,174 @@
+//! Declarative workflow definitions, client assignment, and persisted runs
+
+use chrono::{Duration, Utc};
+use compliance_backend::compliance::workflows::{
+    OnFailure, StepKind, WorkflowCheckpoint, WorkflowDefinition, WorkflowRun, WorkflowStatus, WorkflowStore,
+    MAX_RUNS_PER_ACCOUNT,
+};
+use compliance_backend::config::WorkflowConfig;
+use compliance_backend::types::{AccountId, ComplianceLevel};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn onboarding() -> serde_json::Value {
+    serde_json::json!({
+        "stages": [
+            { "id": "identity", "steps": [{ "kind": "kyc_document", "retries": 2 }, { "kind": "liveness" }] },
+            {
+                "id": "screening",
+                "steps": [
+                    { "kind": "sanctions", "timeout_secs": 30 },
+                    { "kind": "aml" },
+                    { "kind": "chain_analytics", "on_failure": "continue" },
+                ],
+            },
+            { "id": "policy", "steps": [{ "kind": "policy", "on_failure": { "goto": "attestation" } }] },
+            { "id": "attestation", "steps": [{ "kind": "attestation" }] },
+        ],
+    })
+}
+
+fn definition(value: serde_json::Value) -> WorkflowDefinition {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn parses_and_accepts_an_onboarding_flow() {
+    let workflow = definition(onboarding());
+    assert!(workflow.validate().is_ok());
+    
+    let identity = &workflow.stages[0].steps[0];
+    assert_eq!(identity.kind, StepKind::KycDocument);
+    assert_eq!(identity.retries, 2);
+    assert_eq!(identity.on_failure, OnFailure::Fail);
+    assert_eq!(workflow.stages[1].steps[2].on_failure, OnFailure::Continue);
+    assert_eq!(workflow.stages[2].steps[0].on_failure, OnFailure::Goto("attestation".to_string()));
+}
+
+#[test]
+fn rejects_decisions_before_the_checks_they_decide_on() {
+    let mut value = onboarding();
+    value["stages"].as_array_mut().unwrap().swap(1, 2);
+    assert!(definition(value).validate().is_err());
+}
+
+#[test]
+fn rejects_jumps_backwards() {
+    let mut value = onboarding();
+    value["stages"][1]["steps"][0]["on_failure"] = serde_json::json!({ "goto": "identity" });
+    assert!(definition(value).validate().is_err());
+}
+
+#[test]
+fn rejects_workflows_without_attestation() {
+    let mut value = onboarding();
+    value["stages"].as_array_mut().unwrap().pop();
+    assert!(definition(value).validate().is_err());
+}
+
+#[test]
+fn rejects_repeated_steps_and_shared_decision_stages() {
+    let mut repeated = onboarding();
+    repeated["stages"][1]["steps"][1] = serde_json::json!({ "kind": "sanctions" });
+    assert!(definition(repeated).validate().is_err());
+    
+    let mut shared = onboarding();
+    shared["stages"][2]["steps"].as_array_mut().unwrap().push(serde_json::json!({ "kind": "attestation" }));
+    shared["stages"].as_array_mut().unwrap().pop();
+    assert!(definition(shared).validate().is_err());
+}
+
+#[test]
+fn clients_use_their_own_workflow_or_the_default() {
+    let assigned = Uuid::new_v4();
+    let config = WorkflowConfig {
+        definitions: HashMap::from([
+            ("standard".to_string(), definition(onboarding())),
+            ("strict".to_string(), definition(onboarding())),
+        ]),
+        default: Some("standard".to_string()),
+        clients: HashMap::from([(assigned, "strict".to_string())]),
+        state_dir: None,
+    };
+    
+    assert_eq!(config.for_client(assigned).unwrap().0, "strict");
+    assert_eq!(config.for_client(Uuid::new_v4()).unwrap().0, "standard");
+    
+    let unassigned = WorkflowConfig {
+        default: None,
+        ..config
+    };
+    assert!(unassigned.for_client(Uuid::new_v4()).is_none());
+}
+
+fn account() -> AccountId {
+    AccountId::parse("0x0123456789abcdef0123456789abcd").unwrap()
+}
+
+fn run(status: WorkflowStatus, minutes_ago: i64) -> WorkflowRun {
+    WorkflowRun {
+        id: Uuid::new_v4(),
+        account_id: account(),
+        client_id: Some(Uuid::new_v4()),
+        workflow: "standard".to_string(),
+        status,
+        steps: Vec::new(),
+        attestation_id: None,
+        error: None,
+        started_at: Utc::now() - Duration::minutes(minutes_ago),
+        finished_at: None,
+    }
+}
+
+fn state_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("workflow-runs-{}", Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn running_runs_resume_from_their_checkpoint_after_reopening() {
+    let dir = state_dir();
+    let running = run(WorkflowStatus::Running, 1);
+    let mut checkpoint = WorkflowCheckpoint::new(&definition(onboarding()), ComplianceLevel::Basic);
+    checkpoint.stage = 1;
+    checkpoint.results.insert(StepKind::KycDocument, serde_json::json!({ "verified": true }));
+    {
+        let store = WorkflowStore::open(&dir).unwrap();
+        let finished = WorkflowCheckpoint::new(&checkpoint.definition, ComplianceLevel::Basic);
+        store.save(&running, &checkpoint).await.unwrap();
+        store.save(&run(WorkflowStatus::Completed, 5), &finished).await.unwrap();
+    }
+    
+    let store = WorkflowStore::open(&dir).unwrap();
+    let runs = store.runs(&account()).await;
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].id, running.id);
+    
+    let resumable = store.running().await;
+    assert_eq!(resumable.len(), 1);
+    let (resumed, progress) = &resumable[0];
+    assert_eq!(resumed.id, running.id);
+    assert_eq!(progress.stage, 1);
+    assert!(progress.results.contains_key(&StepKind::KycDocument));
+    
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn only_the_newest_finished_runs_are_kept() {
+    let dir = state_dir();
+    let store = WorkflowStore::open(&dir).unwrap();
+    let checkpoint = WorkflowCheckpoint::new(&definition(onboarding()), ComplianceLevel::Basic);
+    let running = run(WorkflowStatus::Running, 1000);
+    store.save(&running, &checkpoint).await.unwrap();
+    for minutes_ago in (0..MAX_RUNS_PER_ACCOUNT as i64 + 5).rev() {
+        store.save(&run(WorkflowStatus::Completed, minutes_ago), &checkpoint).await.unwrap();
+    }
+    
+    let runs = store.runs(&account()).await;
+    assert_eq!(runs.len(), MAX_RUNS_PER_ACCOUNT + 1);
+    assert!(runs.iter().any(|kept| kept.id == running.id));
+    
+    let files = std::fs::read_dir(&dir).unwrap().count();
+    assert_eq!(files, MAX_RUNS_PER_ACCOUNT + 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}